@@ -16,8 +16,10 @@ use std::env;
 use std::time::Duration;
 
 // Import the services
-use api_gateway::config::SolanaProgramsConfig;
+use api_gateway::config::{Config, SolanaProgramsConfig};
 use api_gateway::services::blockchain::BlockchainService;
+use api_gateway::services::PiiCipher;
+use api_gateway::startup::initialize_pii_kms;
 use api_gateway::services::wallet::{WalletInitializationService, WalletStatus};
 
 #[derive(Parser, Debug)]
@@ -110,9 +112,18 @@ async fn main() -> Result<()> {
         SolanaProgramsConfig::default(),
     )?;
 
+    // Unwrap the PII data keys so emails can be read and looked up
+    let config = Config::from_env()?;
+    let pii = PiiCipher::load(db.clone(), initialize_pii_kms(&config)?).await?;
+
     // Create wallet initialization service
-    let service =
-        WalletInitializationService::new(db, encryption_secret, blockchain_service, solana_rpc_url);
+    let service = WalletInitializationService::new(
+        db,
+        encryption_secret,
+        blockchain_service,
+        solana_rpc_url,
+        pii,
+    );
 
     if args.diagnose {
         // Diagnose only
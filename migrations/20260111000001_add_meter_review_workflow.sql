@@ -0,0 +1,26 @@
+-- Admin meter review workflow: reviewer notes, suspension and verification history
+-- Migration: 20260111000001_add_meter_review_workflow
+
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS reviewer_notes TEXT;
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS suspended_at TIMESTAMPTZ;
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS suspension_reason TEXT;
+
+-- Every status transition performed by an admin (or by the system) is recorded here
+CREATE TABLE IF NOT EXISTS meter_verification_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    meter_id UUID NOT NULL REFERENCES meter_registry(id) ON DELETE CASCADE,
+    action VARCHAR(20) NOT NULL,
+    previous_status VARCHAR(20),
+    new_status VARCHAR(20) NOT NULL,
+    reviewer_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    notes TEXT,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_meter_verification_history_meter ON meter_verification_history (meter_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_meter_registry_serial_lower ON meter_registry (LOWER(meter_serial));
+
+COMMENT ON TABLE meter_verification_history IS 'Audit trail of meter approval, rejection and suspension decisions';
+
+COMMENT ON COLUMN meter_registry.reviewer_notes IS 'Notes left by the admin who last reviewed the meter';
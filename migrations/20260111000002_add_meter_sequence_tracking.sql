@@ -0,0 +1,10 @@
+-- Replay protection for signed meter readings
+-- Migration: 20260111000002_add_meter_sequence_tracking
+
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS last_sequence BIGINT;
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS last_sequence_at TIMESTAMPTZ;
+
+ALTER TABLE meter_readings ADD COLUMN IF NOT EXISTS message_version SMALLINT;
+ALTER TABLE meter_readings ADD COLUMN IF NOT EXISTS sequence BIGINT;
+
+COMMENT ON COLUMN meter_registry.last_sequence IS 'Highest accepted signed-message sequence number; readings must strictly exceed it';
@@ -0,0 +1,19 @@
+-- Device clock drift tracking for meter readings
+-- Migration: 20260111000003_add_clock_drift_tracking
+
+-- Per-reading: original device timestamp, observed drift and whether the stored timestamp was corrected
+ALTER TABLE meter_readings ADD COLUMN IF NOT EXISTS device_timestamp TIMESTAMPTZ;
+ALTER TABLE meter_readings ADD COLUMN IF NOT EXISTS clock_drift_secs INTEGER;
+ALTER TABLE meter_readings ADD COLUMN IF NOT EXISTS timestamp_corrected BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Per-meter: rolling drift statistics for the admin drift report
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS last_clock_drift_secs INTEGER;
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS max_abs_clock_drift_secs INTEGER;
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS drift_sample_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS last_drift_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_meter_registry_clock_drift ON meter_registry (max_abs_clock_drift_secs DESC) WHERE drift_sample_count > 0;
+
+COMMENT ON COLUMN meter_readings.device_timestamp IS 'Timestamp reported by the meter before any skew correction';
+COMMENT ON COLUMN meter_readings.clock_drift_secs IS 'Device minus server receipt time in seconds (positive = meter clock ahead)';
+COMMENT ON COLUMN meter_registry.max_abs_clock_drift_secs IS 'Largest absolute clock drift observed for this meter';
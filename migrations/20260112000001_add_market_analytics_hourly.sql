@@ -0,0 +1,30 @@
+-- Hourly market analytics aggregates maintained by the background aggregator
+-- Migration: 20260112000001_add_market_analytics_hourly
+
+CREATE TABLE IF NOT EXISTS market_analytics_hourly (
+    bucket_start TIMESTAMPTZ PRIMARY KEY,
+    -- Trades (order_matches)
+    volume_kwh NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    notional NUMERIC(28, 8) NOT NULL DEFAULT 0,
+    trade_count BIGINT NOT NULL DEFAULT 0,
+    low_price NUMERIC(20, 8),
+    high_price NUMERIC(20, 8),
+    -- Bid-ask spread samples taken from the live orderbook
+    spread_sum DOUBLE PRECISION NOT NULL DEFAULT 0,
+    spread_samples BIGINT NOT NULL DEFAULT 0,
+    -- Orders created in the bucket
+    requested_kwh NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    filled_kwh NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    orders_created BIGINT NOT NULL DEFAULT 0,
+    -- Orders filled in the bucket
+    orders_filled BIGINT NOT NULL DEFAULT 0,
+    ttf_sum_secs DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ttf_histogram BIGINT[] NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_matches_match_time ON order_matches (match_time);
+CREATE INDEX IF NOT EXISTS idx_trading_orders_filled_at ON trading_orders (filled_at) WHERE filled_at IS NOT NULL;
+
+COMMENT ON TABLE market_analytics_hourly IS 'Composable hourly trading aggregates (VWAP = notional / volume_kwh)';
+COMMENT ON COLUMN market_analytics_hourly.ttf_histogram IS 'Time-to-fill counts: <1m, 1-5m, 5-15m, 15-60m, 1-6h, >6h';
@@ -0,0 +1,29 @@
+-- Opt-in community leaderboards computed daily
+-- Migration: 20260112000002_add_leaderboards
+
+-- Privacy: users appear on leaderboards only after opting in, and only by display name
+ALTER TABLE users ADD COLUMN IF NOT EXISTS leaderboard_opt_in BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS display_name VARCHAR(50);
+
+CREATE TABLE IF NOT EXISTS leaderboard_snapshots (
+    snapshot_date DATE NOT NULL,
+    category VARCHAR(20) NOT NULL,
+    rank INTEGER NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    display_name VARCHAR(50) NOT NULL,
+    value DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (snapshot_date, category, rank)
+);
+
+-- Platform-wide totals (all users, not only opted-in ones; no personal data)
+CREATE TABLE IF NOT EXISTS community_stats_daily (
+    snapshot_date DATE PRIMARY KEY,
+    participants BIGINT NOT NULL DEFAULT 0,
+    total_generated_kwh DOUBLE PRECISION NOT NULL DEFAULT 0,
+    total_co2_saved_kg DOUBLE PRECISION NOT NULL DEFAULT 0,
+    total_traded_kwh DOUBLE PRECISION NOT NULL DEFAULT 0,
+    computed_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+COMMENT ON COLUMN users.leaderboard_opt_in IS 'User consented to appear on public leaderboards';
+COMMENT ON COLUMN users.display_name IS 'Public name shown on leaderboards instead of username';
@@ -0,0 +1,29 @@
+-- Monthly invoice / fee statements
+-- Migration: 20260112000003_add_invoices
+
+CREATE TABLE IF NOT EXISTS invoices (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    invoice_number VARCHAR(32) NOT NULL UNIQUE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    trade_count BIGINT NOT NULL DEFAULT 0,
+    energy_sold_kwh NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    energy_bought_kwh NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    sales_amount NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    purchases_amount NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    fees_amount NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    minted_kwh NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    grid_purchase_kwh NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    grid_purchase_amount NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    net_amount NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    storage_key TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'issued',
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    CONSTRAINT uq_invoices_user_period UNIQUE (user_id, period_start),
+    CONSTRAINT chk_invoice_status CHECK (status IN ('issued', 'void'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoices_user ON invoices (user_id, period_start DESC);
+
+COMMENT ON TABLE invoices IS 'Monthly statements of trades, fees, minting and grid purchases; PDFs live in object storage under storage_key';
@@ -0,0 +1,68 @@
+-- Prepaid fiat credit: top-ups via payment provider, ledger, refunds
+-- Migration: 20260113000001_add_prepaid_credit
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS prepaid_balance NUMERIC(20, 8) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS prepaid_topups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider VARCHAR(20) NOT NULL,
+    provider_payment_id VARCHAR(128) UNIQUE,
+    amount NUMERIC(20, 8) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    refunded_amount NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    failure_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    CONSTRAINT chk_prepaid_topup_amount CHECK (amount > 0),
+    CONSTRAINT chk_prepaid_topup_status CHECK (status IN ('pending', 'succeeded', 'failed', 'refunded'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_prepaid_topups_user ON prepaid_topups (user_id, created_at DESC);
+
+-- Every change to users.prepaid_balance is recorded here (signed amounts)
+CREATE TABLE IF NOT EXISTS prepaid_ledger (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    entry_type VARCHAR(20) NOT NULL,
+    amount NUMERIC(20, 8) NOT NULL,
+    balance_after NUMERIC(20, 8) NOT NULL,
+    topup_id UUID REFERENCES prepaid_topups(id),
+    settlement_id UUID,
+    refund_id UUID,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_prepaid_ledger_type CHECK (entry_type IN ('topup', 'settlement', 'refund', 'refund_reversal'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_prepaid_ledger_user ON prepaid_ledger (user_id, created_at DESC);
+CREATE UNIQUE INDEX IF NOT EXISTS uq_prepaid_ledger_settlement
+    ON prepaid_ledger (settlement_id) WHERE entry_type = 'settlement';
+
+CREATE TABLE IF NOT EXISTS prepaid_refunds (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    topup_id UUID NOT NULL REFERENCES prepaid_topups(id),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount NUMERIC(20, 8) NOT NULL,
+    provider_refund_id VARCHAR(128) UNIQUE,
+    -- 'user' for refunds requested through the API, 'provider' for dashboard refunds / chargebacks
+    initiated_by VARCHAR(20) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_prepaid_refund_amount CHECK (amount > 0),
+    CONSTRAINT chk_prepaid_refund_status CHECK (status IN ('pending', 'succeeded', 'failed'))
+);
+
+-- Processed provider webhook events (idempotency)
+CREATE TABLE IF NOT EXISTS payment_webhook_events (
+    provider VARCHAR(20) NOT NULL,
+    event_id VARCHAR(128) NOT NULL,
+    event_type VARCHAR(64) NOT NULL,
+    payload JSONB NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (provider, event_id)
+);
+
+COMMENT ON TABLE prepaid_ledger IS 'Audit trail of prepaid fiat credit: top-ups, settlement applications and refunds';
@@ -0,0 +1,75 @@
+-- Settlement dispute workflow
+-- Migration: 20260113000002_add_settlement_disputes
+
+CREATE TABLE IF NOT EXISTS settlement_disputes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    settlement_id UUID NOT NULL REFERENCES settlements(id) ON DELETE CASCADE,
+    raised_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    counterparty_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason VARCHAR(30) NOT NULL,
+    description TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'open',
+    -- Set when REC issuance was skipped because the dispute was active
+    rec_deferred BOOLEAN NOT NULL DEFAULT FALSE,
+    assigned_admin_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    resolution_notes TEXT,
+    refund_amount NUMERIC(20, 8),
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    resolved_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_dispute_reason CHECK (
+        reason IN ('not_delivered', 'quantity_mismatch', 'price_mismatch', 'meter_error', 'other')
+    ),
+    CONSTRAINT chk_dispute_status CHECK (
+        status IN ('open', 'under_review', 'upheld', 'rejected', 'withdrawn')
+    )
+);
+
+-- At most one active dispute per settlement
+CREATE UNIQUE INDEX IF NOT EXISTS uq_settlement_disputes_active
+    ON settlement_disputes (settlement_id) WHERE status IN ('open', 'under_review');
+CREATE INDEX IF NOT EXISTS idx_settlement_disputes_status ON settlement_disputes (status, created_at);
+CREATE INDEX IF NOT EXISTS idx_settlement_disputes_raised_by ON settlement_disputes (raised_by);
+CREATE INDEX IF NOT EXISTS idx_settlement_disputes_counterparty ON settlement_disputes (counterparty_id);
+
+CREATE TABLE IF NOT EXISTS dispute_evidence (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    dispute_id UUID NOT NULL REFERENCES settlement_disputes(id) ON DELETE CASCADE,
+    submitted_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_dispute_evidence_kind CHECK (kind IN ('note', 'url', 'meter_reading'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_dispute_evidence_dispute ON dispute_evidence (dispute_id, created_at);
+
+-- Compensating balance/energy adjustments (signed; user_id NULL = platform account)
+CREATE TABLE IF NOT EXISTS token_ledger_adjustments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    dispute_id UUID REFERENCES settlement_disputes(id) ON DELETE SET NULL,
+    settlement_id UUID REFERENCES settlements(id) ON DELETE SET NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    asset VARCHAR(10) NOT NULL,
+    amount NUMERIC(20, 8) NOT NULL,
+    reason TEXT NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_token_ledger_asset CHECK (asset IN ('currency', 'energy'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_ledger_adjustments_user ON token_ledger_adjustments (user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_token_ledger_adjustments_dispute ON token_ledger_adjustments (dispute_id);
+
+-- Certificates backed by disputed energy are held until resolution
+ALTER TABLE erc_certificates
+    ADD COLUMN IF NOT EXISTS dispute_id UUID REFERENCES settlement_disputes(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS dispute_hold VARCHAR(10);
+
+ALTER TABLE erc_certificates
+    DROP CONSTRAINT IF EXISTS chk_erc_dispute_hold;
+ALTER TABLE erc_certificates
+    ADD CONSTRAINT chk_erc_dispute_hold CHECK (dispute_hold IS NULL OR dispute_hold IN ('frozen', 'revoked'));
+
+COMMENT ON COLUMN erc_certificates.dispute_hold IS 'frozen while a settlement dispute is active, revoked when the dispute was upheld';
@@ -0,0 +1,32 @@
+-- Meter submission rate limit overrides
+-- Migration: 20260113000003_add_rate_limit_overrides
+
+CREATE TABLE IF NOT EXISTS rate_limit_overrides (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    principal_type VARCHAR(20) NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    -- SHA-256 hex of the API key; the key itself is never stored
+    api_key_hash VARCHAR(64),
+    api_key_hint VARCHAR(8),
+    ip_range CIDR,
+    -- NULL exempts the principal from the limit entirely
+    max_requests INTEGER,
+    reason TEXT NOT NULL,
+    expires_at TIMESTAMPTZ,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ,
+    revoked_by UUID REFERENCES users(id) ON DELETE SET NULL,
+
+    CONSTRAINT chk_rate_limit_principal_type CHECK (principal_type IN ('user', 'api_key', 'ip_range')),
+    CONSTRAINT chk_rate_limit_principal CHECK (
+        (principal_type = 'user' AND user_id IS NOT NULL) OR
+        (principal_type = 'api_key' AND api_key_hash IS NOT NULL) OR
+        (principal_type = 'ip_range' AND ip_range IS NOT NULL)
+    ),
+    CONSTRAINT chk_rate_limit_max_requests CHECK (max_requests IS NULL OR max_requests > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_overrides_active
+    ON rate_limit_overrides(created_at DESC)
+    WHERE revoked_at IS NULL;
@@ -0,0 +1,17 @@
+-- Network access control (CIDR allow/deny lists per route group)
+-- Migration: 20260114000001_add_network_access_rules
+
+CREATE TABLE IF NOT EXISTS network_access_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    route_group VARCHAR(20) NOT NULL,
+    action VARCHAR(10) NOT NULL,
+    cidr CIDR NOT NULL,
+    description TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_network_rule_group CHECK (route_group IN ('admin', 'ami')),
+    CONSTRAINT chk_network_rule_action CHECK (action IN ('allow', 'deny'))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_network_access_rules
+    ON network_access_rules (route_group, action, cidr);
@@ -0,0 +1,25 @@
+-- AMI gateway identities authenticated by mTLS client certificate
+-- Migration: 20260114000002_add_meter_gateways
+
+CREATE TABLE IF NOT EXISTS meter_gateways (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    operator VARCHAR(100),
+    -- SHA-256 of the DER-encoded client certificate, lowercase hex
+    cert_fingerprint CHAR(64) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'active',
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ,
+    last_seen_at TIMESTAMPTZ,
+    CONSTRAINT chk_meter_gateway_status CHECK (status IN ('active', 'revoked'))
+);
+
+-- A certificate maps to at most one active gateway
+CREATE UNIQUE INDEX IF NOT EXISTS uq_meter_gateways_active_fingerprint
+    ON meter_gateways (cert_fingerprint) WHERE status = 'active';
+
+-- Meters a gateway may submit readings for
+ALTER TABLE meter_registry
+    ADD COLUMN IF NOT EXISTS gateway_id UUID REFERENCES meter_gateways(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_meter_registry_gateway ON meter_registry (gateway_id);
@@ -0,0 +1,11 @@
+-- Migration: Store grid energy totals as NUMERIC
+-- Created: 2026-01-15
+--
+-- Energy totals accumulate many small readings; FLOAT8 drifts. Zone snapshots
+-- in zones_data now hold decimal strings (older rows keep JSON numbers, both
+-- are accepted when reading history).
+
+ALTER TABLE grid_status_history
+    ALTER COLUMN total_generation TYPE NUMERIC(20, 8) USING total_generation::NUMERIC(20, 8),
+    ALTER COLUMN total_consumption TYPE NUMERIC(20, 8) USING total_consumption::NUMERIC(20, 8),
+    ALTER COLUMN net_balance TYPE NUMERIC(20, 8) USING net_balance::NUMERIC(20, 8);
@@ -0,0 +1,31 @@
+-- Clearing price index series and daily reference index
+-- Migration: 20260116000001_add_clearing_price_index
+
+-- One entry per cleared epoch
+CREATE TABLE IF NOT EXISTS clearing_price_index (
+    epoch_id UUID PRIMARY KEY REFERENCES market_epochs(id) ON DELETE CASCADE,
+    epoch_number BIGINT NOT NULL,
+    epoch_start TIMESTAMPTZ NOT NULL,
+    clearing_price NUMERIC(20, 8) NOT NULL,
+    volume_kwh NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_clearing_price_index_start ON clearing_price_index (epoch_start);
+
+-- Backfill from epochs cleared before the series existed
+INSERT INTO clearing_price_index (epoch_id, epoch_number, epoch_start, clearing_price, volume_kwh)
+SELECT id, epoch_number, start_time, clearing_price, COALESCE(total_volume, 0)
+FROM market_epochs
+WHERE clearing_price IS NOT NULL
+ON CONFLICT (epoch_id) DO NOTHING;
+
+-- Daily volume-weighted reference price (UTC day), used as the futures
+-- settlement index. Rows are immutable once published.
+CREATE TABLE IF NOT EXISTS reference_price_index (
+    index_date DATE PRIMARY KEY,
+    value NUMERIC(20, 8) NOT NULL,
+    volume_kwh NUMERIC(20, 8) NOT NULL,
+    epoch_count INTEGER NOT NULL,
+    published_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,11 @@
+-- Per-epoch accounting of orders expired by the sweeper
+-- Migration: 20260116000002_add_order_expiry_stats
+
+ALTER TABLE market_epochs
+    ADD COLUMN IF NOT EXISTS expired_orders INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS expired_volume NUMERIC(20, 8) NOT NULL DEFAULT 0;
+
+-- Sweeper lookup of open orders past their expiry
+CREATE INDEX IF NOT EXISTS idx_trading_orders_open_expiry
+    ON trading_orders (expires_at)
+    WHERE status IN ('pending', 'active', 'partially_filled');
@@ -0,0 +1,25 @@
+-- Order lifecycle events
+-- Migration: 20260117000001_add_order_events
+
+-- Append-only history of every order state change. order_id is not a foreign
+-- key: rejected orders never reach trading_orders but keep their event.
+CREATE TABLE IF NOT EXISTS order_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    order_id UUID NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR(20) NOT NULL,
+    -- Quantity filled by this event (fills only)
+    fill_quantity NUMERIC(20, 8),
+    -- Total filled after this event
+    cumulative_filled NUMERIC(20, 8),
+    remaining NUMERIC(20, 8),
+    price NUMERIC(20, 8),
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_order_event_type CHECK (
+        event_type IN ('accepted', 'partially_filled', 'filled', 'cancelled', 'expired', 'rejected')
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_events_order ON order_events (order_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_order_events_user ON order_events (user_id, created_at DESC);
@@ -0,0 +1,70 @@
+-- Admin trade busts and manual trade entry under multi-signature approval
+-- Migration: 20260118000001_add_trade_approvals
+
+CREATE TABLE IF NOT EXISTS trade_approval_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    action_type VARCHAR(20) NOT NULL,
+    -- Action parameters (settlement to bust, or the manual trade terms)
+    payload JSONB NOT NULL,
+    reason TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    required_approvals INTEGER NOT NULL,
+    approvals INTEGER NOT NULL DEFAULT 0,
+    proposed_by UUID NOT NULL REFERENCES users(id) ON DELETE RESTRICT,
+    -- Busted settlement, or the settlement created by a manual trade
+    settlement_id UUID REFERENCES settlements(id) ON DELETE SET NULL,
+    failure_reason TEXT,
+    expires_at TIMESTAMPTZ NOT NULL,
+    executed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_trade_approval_action CHECK (action_type IN ('trade_bust', 'manual_trade')),
+    CONSTRAINT chk_trade_approval_status CHECK (
+        status IN ('pending', 'executed', 'rejected', 'expired', 'failed')
+    ),
+    CONSTRAINT chk_trade_approval_required CHECK (required_approvals >= 1)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_approval_requests_status ON trade_approval_requests (status, created_at);
+
+-- At most one pending bust per settlement
+CREATE UNIQUE INDEX IF NOT EXISTS uq_trade_approval_pending_bust
+    ON trade_approval_requests ((payload->>'settlement_id'))
+    WHERE action_type = 'trade_bust' AND status = 'pending';
+
+-- One signature per admin per request
+CREATE TABLE IF NOT EXISTS trade_approval_votes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    request_id UUID NOT NULL REFERENCES trade_approval_requests(id) ON DELETE CASCADE,
+    admin_id UUID NOT NULL REFERENCES users(id) ON DELETE RESTRICT,
+    decision VARCHAR(10) NOT NULL,
+    comment TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_trade_approval_decision CHECK (decision IN ('approve', 'reject')),
+    CONSTRAINT uq_trade_approval_votes_admin UNIQUE (request_id, admin_id)
+);
+
+-- Settlements entered by operators and busted settlements
+ALTER TABLE settlements
+    ADD COLUMN IF NOT EXISTS origin VARCHAR(10) NOT NULL DEFAULT 'market',
+    ADD COLUMN IF NOT EXISTS busted_at TIMESTAMPTZ;
+
+ALTER TABLE settlements DROP CONSTRAINT IF EXISTS chk_settlement_status;
+ALTER TABLE settlements
+    ADD CONSTRAINT chk_settlement_status CHECK (status IN ('pending', 'processing', 'completed', 'failed', 'busted'));
+
+ALTER TABLE settlements DROP CONSTRAINT IF EXISTS chk_settlement_origin;
+ALTER TABLE settlements
+    ADD CONSTRAINT chk_settlement_origin CHECK (origin IN ('market', 'manual'));
+
+ALTER TABLE order_matches DROP CONSTRAINT IF EXISTS chk_match_status;
+ALTER TABLE order_matches
+    ADD CONSTRAINT chk_match_status CHECK (status IN ('pending', 'settled', 'failed', 'busted'));
+
+-- Compensating entries posted by a trade bust
+ALTER TABLE token_ledger_adjustments
+    ADD COLUMN IF NOT EXISTS approval_request_id UUID REFERENCES trade_approval_requests(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_token_ledger_adjustments_approval ON token_ledger_adjustments (approval_request_id);
+
+COMMENT ON COLUMN settlements.origin IS 'market for matched orders, manual for off-market trades entered by operators';
@@ -0,0 +1,37 @@
+-- Energy token grant programs with cliff + linear vesting
+-- Migration: 20260118000002_add_vesting
+
+CREATE TABLE IF NOT EXISTS vesting_programs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL UNIQUE,
+    description TEXT,
+    -- Energy tokens (kWh) granted to each participant
+    amount_per_user NUMERIC(20, 8) NOT NULL,
+    cliff_days INTEGER NOT NULL DEFAULT 0,
+    vesting_days INTEGER NOT NULL,
+    vesting_start TIMESTAMPTZ NOT NULL,
+    -- Early adopter cutoff: users registered before this time are enrolled
+    eligible_registered_before TIMESTAMPTZ,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_vesting_amount CHECK (amount_per_user > 0),
+    CONSTRAINT chk_vesting_days CHECK (vesting_days > 0 AND cliff_days >= 0 AND cliff_days <= vesting_days)
+);
+
+CREATE TABLE IF NOT EXISTS vesting_grants (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    program_id UUID NOT NULL REFERENCES vesting_programs(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount NUMERIC(20, 8) NOT NULL,
+    start_at TIMESTAMPTZ NOT NULL,
+    cliff_at TIMESTAMPTZ NOT NULL,
+    end_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_vesting_grant_amount CHECK (amount > 0),
+    CONSTRAINT chk_vesting_grant_schedule CHECK (start_at <= cliff_at AND cliff_at <= end_at AND start_at < end_at),
+    CONSTRAINT uq_vesting_grants_program_user UNIQUE (program_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_vesting_grants_user ON vesting_grants (user_id);
+
+COMMENT ON TABLE vesting_grants IS 'Granted energy tokens count towards holdings; the unvested part cannot be sold or transferred';
@@ -0,0 +1,55 @@
+-- Referral and rewards program
+-- Migration: 20260118000003_add_referrals
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS referral_code VARCHAR(16) UNIQUE,
+    -- Captured at registration for referral fraud heuristics
+    ADD COLUMN IF NOT EXISTS signup_ip VARCHAR(45),
+    ADD COLUMN IF NOT EXISTS signup_device VARCHAR(128);
+
+CREATE TABLE IF NOT EXISTS referrals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    referrer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    referee_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    code VARCHAR(16) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    -- Qualifying activity of the referee
+    first_reading_at TIMESTAMPTZ,
+    first_trade_at TIMESTAMPTZ,
+    fraud_flags TEXT[] NOT NULL DEFAULT '{}',
+    qualified_at TIMESTAMPTZ,
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    review_note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_referral_status CHECK (status IN ('pending', 'qualified', 'flagged', 'rejected')),
+    CONSTRAINT chk_referral_not_self CHECK (referrer_id <> referee_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_referrals_referrer ON referrals (referrer_id);
+CREATE INDEX IF NOT EXISTS idx_referrals_status ON referrals (status);
+
+CREATE TABLE IF NOT EXISTS referral_rewards (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    referral_id UUID NOT NULL REFERENCES referrals(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(10) NOT NULL,
+    -- Energy tokens (kWh)
+    amount NUMERIC(20, 8) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    mint_tx_signature VARCHAR(128),
+    last_error TEXT,
+    minted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_referral_reward_role CHECK (role IN ('referrer', 'referee')),
+    CONSTRAINT chk_referral_reward_status CHECK (status IN ('pending', 'minted', 'failed')),
+    CONSTRAINT chk_referral_reward_amount CHECK (amount > 0),
+    CONSTRAINT uq_referral_rewards_role UNIQUE (referral_id, role)
+);
+
+CREATE INDEX IF NOT EXISTS idx_referral_rewards_user ON referral_rewards (user_id);
+CREATE INDEX IF NOT EXISTS idx_referral_rewards_pending ON referral_rewards (created_at) WHERE status = 'pending';
+
+COMMENT ON TABLE referral_rewards IS 'Token rewards for qualified referrals, minted on-chain by the referral job';
@@ -0,0 +1,49 @@
+-- Per-user API usage analytics and access anomalies
+-- Migration: 20260118000004_add_api_usage
+
+-- user_id is the authenticated subject; service credentials may act as
+-- users that have no row in users, so it is not a foreign key.
+
+-- Hourly buckets, kept for anomaly baselines and pruned after a week
+CREATE TABLE IF NOT EXISTS api_usage_hourly (
+    hour_start TIMESTAMPTZ NOT NULL,
+    user_id UUID NOT NULL,
+    -- jwt, engineering_key or signed:<key id>
+    credential VARCHAR(80) NOT NULL,
+    method VARCHAR(10) NOT NULL,
+    -- Request path with identifiers replaced by {id}
+    endpoint VARCHAR(255) NOT NULL,
+    request_count BIGINT NOT NULL DEFAULT 0,
+    error_count BIGINT NOT NULL DEFAULT 0,
+    last_used_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (hour_start, user_id, credential, method, endpoint)
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_usage_hourly_user ON api_usage_hourly (user_id, hour_start);
+
+-- Daily rollup, kept long term
+CREATE TABLE IF NOT EXISTS api_usage_daily (
+    day DATE NOT NULL,
+    user_id UUID NOT NULL,
+    credential VARCHAR(80) NOT NULL,
+    method VARCHAR(10) NOT NULL,
+    endpoint VARCHAR(255) NOT NULL,
+    request_count BIGINT NOT NULL DEFAULT 0,
+    error_count BIGINT NOT NULL DEFAULT 0,
+    last_used_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (day, user_id, credential, method, endpoint)
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_usage_daily_user ON api_usage_daily (user_id, day);
+
+CREATE TABLE IF NOT EXISTS security_anomalies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    kind VARCHAR(40) NOT NULL,
+    details TEXT NOT NULL,
+    window_start TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_security_anomalies_window UNIQUE (user_id, kind, window_start)
+);
+
+CREATE INDEX IF NOT EXISTS idx_security_anomalies_created ON security_anomalies (created_at DESC);
@@ -0,0 +1,32 @@
+-- Uploaded attachments
+-- Migration: 20260118000005_add_attachments
+
+-- Files referenced from KYC submissions, settlement disputes and certificate
+-- validation data. Objects live in object storage; rows keep the reference.
+
+CREATE TABLE IF NOT EXISTS attachments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    uploaded_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- What the file belongs to: kyc (a user), dispute or certificate
+    owner_type VARCHAR(20) NOT NULL,
+    owner_id UUID NOT NULL,
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    sha256 CHAR(64) NOT NULL,
+    storage_key TEXT NOT NULL UNIQUE,
+    -- clean when the malware scanner passed the file, unscanned without a scanner
+    scan_status VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_attachments_owner_type CHECK (owner_type IN ('kyc', 'dispute', 'certificate')),
+    CONSTRAINT chk_attachments_scan_status CHECK (scan_status IN ('clean', 'unscanned')),
+    CONSTRAINT chk_attachments_size CHECK (size_bytes > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_owner ON attachments (owner_type, owner_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_attachments_uploaded_by ON attachments (uploaded_by, created_at);
+
+-- Disputes reference uploaded files as evidence
+ALTER TABLE dispute_evidence DROP CONSTRAINT IF EXISTS chk_dispute_evidence_kind;
+ALTER TABLE dispute_evidence ADD CONSTRAINT chk_dispute_evidence_kind
+    CHECK (kind IN ('note', 'url', 'meter_reading', 'attachment'));
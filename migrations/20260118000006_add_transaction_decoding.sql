@@ -0,0 +1,16 @@
+-- Decoded transaction history
+-- Migration: 20260118000006_add_transaction_decoding
+
+-- Fee payer, block position and decoded instruction summaries of submitted
+-- transactions. Filled in once the transaction is found on-chain.
+
+ALTER TABLE blockchain_transactions
+    ADD COLUMN IF NOT EXISTS signer VARCHAR(44),
+    ADD COLUMN IF NOT EXISTS slot BIGINT,
+    ADD COLUMN IF NOT EXISTS block_time TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS instructions JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_blockchain_transactions_user_submitted
+    ON blockchain_transactions(user_id, submitted_at DESC);
+CREATE INDEX IF NOT EXISTS idx_blockchain_transactions_program ON blockchain_transactions(program_id);
+CREATE INDEX IF NOT EXISTS idx_blockchain_transactions_signer ON blockchain_transactions(signer);
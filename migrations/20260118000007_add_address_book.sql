@@ -0,0 +1,25 @@
+-- Address book of saved beneficiaries
+-- Migration: 20260118000007_add_address_book
+
+-- Labelled wallet addresses a user transfers to. Ownership of an address is
+-- proven by signing a short challenge with its key; the first transfer to
+-- an address must be confirmed explicitly.
+
+CREATE TABLE IF NOT EXISTS address_book_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    label VARCHAR(50) NOT NULL,
+    wallet_address VARCHAR(44) NOT NULL,
+    -- Pending ownership challenge, cleared once verified
+    verification_message TEXT,
+    verification_expires_at TIMESTAMPTZ,
+    verified_at TIMESTAMPTZ,
+    -- Set when the user confirms the first transfer to this address
+    first_used_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_address_book_user_address UNIQUE (user_id, wallet_address)
+);
+
+CREATE INDEX IF NOT EXISTS idx_address_book_user ON address_book_entries(user_id, label);
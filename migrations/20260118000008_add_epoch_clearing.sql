@@ -0,0 +1,18 @@
+-- Automatic epoch clearing
+-- Migration: 20260118000008_add_epoch_clearing
+
+-- The clearing scheduler claims a closed epoch by setting
+-- clearing_started_at and marks it done with cleared_at. Epoch status alone
+-- is not enough: it flips to 'cleared' as soon as an epoch is looked up
+-- after its end time.
+
+ALTER TABLE market_epochs
+    ADD COLUMN IF NOT EXISTS clearing_started_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS cleared_at TIMESTAMPTZ;
+
+-- Epochs that closed before the scheduler existed are not re-cleared
+UPDATE market_epochs SET cleared_at = NOW() WHERE end_time <= NOW() AND cleared_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_market_epochs_uncleared
+    ON market_epochs(end_time)
+    WHERE cleared_at IS NULL;
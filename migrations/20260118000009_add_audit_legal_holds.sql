@@ -0,0 +1,26 @@
+-- Legal holds on audit data
+-- Migration: 20260118000009_add_audit_legal_holds
+
+-- A hold exempts audit records from the retention purge while it is active.
+-- It covers one user's records, a time range, or both; a hold with neither
+-- covers everything. Holds are released, never deleted, so the table is
+-- their own audit trail.
+
+CREATE TABLE IF NOT EXISTS audit_legal_holds (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- No foreign key: a hold must outlive the user it covers
+    user_id UUID,
+    starts_at TIMESTAMPTZ,
+    ends_at TIMESTAMPTZ,
+    reason TEXT NOT NULL,
+    placed_by UUID NOT NULL,
+    placed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    released_by UUID,
+    released_at TIMESTAMPTZ,
+    release_reason TEXT,
+    CONSTRAINT chk_audit_legal_hold_range CHECK (starts_at IS NULL OR ends_at IS NULL OR starts_at <= ends_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_legal_holds_active
+    ON audit_legal_holds(user_id)
+    WHERE released_at IS NULL;
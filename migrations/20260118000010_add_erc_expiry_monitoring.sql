@@ -0,0 +1,15 @@
+-- ERC certificate expiry monitoring
+-- Migration: 20260118000010_add_erc_expiry_monitoring
+
+-- Owners are notified once when a certificate nears expiry; expired
+-- certificates move to status 'Expired' and are no longer retired,
+-- transferred or counted as active energy.
+
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'certificate_expiring';
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'certificate_expired';
+
+ALTER TABLE erc_certificates ADD COLUMN IF NOT EXISTS expiry_notified_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_erc_certificates_active_expiry
+    ON erc_certificates(expiry_date)
+    WHERE status = 'Active' AND expiry_date IS NOT NULL;
@@ -0,0 +1,33 @@
+-- ERC issuer registry and issuance policy
+-- Migration: 20260118000011_add_erc_issuers
+
+-- Authorized certificate issuers. Each issuer signs with its own key and
+-- carries its issuance policy: the renewable sources it may certify and
+-- the vintage window (days of metered production a certificate may cover).
+-- The default issuer signs certificates issued automatically at settlement.
+
+CREATE TABLE IF NOT EXISTS erc_issuers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    -- Base58 public key of the issuer's signing key
+    signing_key VARCHAR(44) NOT NULL UNIQUE,
+    allowed_sources TEXT[] NOT NULL DEFAULT '{}',
+    vintage_days INTEGER NOT NULL DEFAULT 365 CHECK (vintage_days > 0),
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_erc_issuers_default
+    ON erc_issuers(is_default)
+    WHERE is_default;
+
+-- Device a certificate was issued for, for double-counting checks
+ALTER TABLE erc_certificates ADD COLUMN IF NOT EXISTS meter_serial VARCHAR(100);
+
+CREATE INDEX IF NOT EXISTS idx_erc_certificates_meter_issue
+    ON erc_certificates(meter_serial, issue_date)
+    WHERE meter_serial IS NOT NULL;
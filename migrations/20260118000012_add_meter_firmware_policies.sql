@@ -0,0 +1,28 @@
+-- Meter firmware version tracking and compatibility policies
+-- Migration: 20260118000012_add_meter_firmware_policies
+
+-- Firmware version last reported by each meter
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS firmware_version VARCHAR(50);
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS firmware_updated_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_meter_registry_firmware
+    ON meter_registry (manufacturer, firmware_version);
+
+-- Minimum supported firmware per manufacturer. Readings from meters below
+-- the minimum are accepted with a warning or rejected, per `action`.
+CREATE TABLE IF NOT EXISTS meter_firmware_policies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    manufacturer VARCHAR(255) NOT NULL,
+    min_version VARCHAR(50) NOT NULL,
+    action VARCHAR(10) NOT NULL DEFAULT 'warn' CHECK (action IN ('warn', 'reject')),
+    note TEXT,
+    updated_by UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_meter_firmware_policies_manufacturer
+    ON meter_firmware_policies (LOWER(manufacturer));
+
+COMMENT ON COLUMN meter_registry.firmware_version IS 'Firmware version last reported with a reading';
+COMMENT ON COLUMN meter_firmware_policies.action IS 'warn: accept readings and flag; reject: refuse readings below min_version';
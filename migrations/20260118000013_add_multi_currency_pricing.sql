@@ -0,0 +1,34 @@
+-- Multi-currency pricing: FX reference rates and fiat values per trade
+-- Migration: 20260118000013_add_multi_currency_pricing
+
+-- Oracle FX rates: fiat (quote_currency) per one unit of the settlement mint
+CREATE TABLE IF NOT EXISTS fx_rates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    base_mint VARCHAR(44) NOT NULL,
+    quote_currency VARCHAR(3) NOT NULL,
+    rate NUMERIC(24, 10) NOT NULL CHECK (rate > 0),
+    source VARCHAR(50) NOT NULL,
+    observed_at TIMESTAMPTZ NOT NULL,
+    published_by UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_fx_rates_pair_observed
+    ON fx_rates (base_mint, quote_currency, observed_at DESC);
+
+-- Settlement asset and fiat reference value recorded at clearing time
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS settlement_mint VARCHAR(44);
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS fiat_currency VARCHAR(3);
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS fx_rate NUMERIC(24, 10);
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS fx_rate_source VARCHAR(50);
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS fx_observed_at TIMESTAMPTZ;
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS fiat_total_value NUMERIC(20, 4);
+
+-- Fiat reference totals on monthly statements
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS fiat_currency VARCHAR(3);
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS sales_fiat_amount NUMERIC(20, 4);
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS purchases_fiat_amount NUMERIC(20, 4);
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS fees_fiat_amount NUMERIC(20, 4);
+
+COMMENT ON COLUMN settlements.fx_rate IS 'Fiat per settlement token at clearing time; NULL when no rate was available';
+COMMENT ON COLUMN settlements.fiat_total_value IS 'total_amount converted at fx_rate, for display and reporting only';
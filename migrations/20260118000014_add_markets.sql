@@ -0,0 +1,42 @@
+-- Markets: independent orderbooks per product (zonal energy spot, ERC, futures)
+-- Migration: 20260118000014_add_markets
+
+CREATE TABLE IF NOT EXISTS markets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    code VARCHAR(32) NOT NULL UNIQUE,
+    name VARCHAR(100) NOT NULL,
+    market_type VARCHAR(20) NOT NULL CHECK (market_type IN ('energy_spot', 'erc', 'futures')),
+    -- Spot markets restricted to one grid zone; NULL accepts orders from any zone
+    zone_id INTEGER,
+    status VARCHAR(10) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'halted', 'closed')),
+    min_order_kwh NUMERIC(20, 9) NOT NULL DEFAULT 0.1 CHECK (min_order_kwh > 0),
+    tick_size NUMERIC(20, 9) CHECK (tick_size > 0),
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    created_by UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_markets_single_default ON markets (is_default) WHERE is_default;
+
+-- The original single energy market; orders without an explicit market land here
+INSERT INTO markets (id, code, name, market_type, is_default)
+VALUES ('00000000-0000-0000-0000-000000000001', 'ENERGY-SPOT', 'Energy Spot', 'energy_spot', TRUE)
+ON CONFLICT (id) DO NOTHING;
+
+ALTER TABLE trading_orders ADD COLUMN IF NOT EXISTS market_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES markets(id);
+
+CREATE INDEX IF NOT EXISTS idx_trading_orders_market_book
+    ON trading_orders (market_id, side, status);
+
+ALTER TABLE order_matches ADD COLUMN IF NOT EXISTS market_id UUID REFERENCES markets(id);
+
+UPDATE order_matches m SET market_id = o.market_id
+FROM trading_orders o
+WHERE o.id = m.buy_order_id AND m.market_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_order_matches_market_time ON order_matches (market_id, match_time DESC);
+
+COMMENT ON COLUMN markets.min_order_kwh IS 'Smallest order and match size; remainders below it are cancelled as dust';
+COMMENT ON COLUMN markets.tick_size IS 'Limit prices must be a multiple of this increment; NULL disables the check';
@@ -0,0 +1,40 @@
+-- Smart order routing: split market orders between the orderbook and AMM pools
+-- Migration: 20260118000015_add_order_routing
+
+-- AMM pool serving a market; the router only uses energy/currency pools
+ALTER TABLE liquidity_pools ADD COLUMN IF NOT EXISTS market_id UUID REFERENCES markets(id);
+
+CREATE INDEX IF NOT EXISTS idx_liquidity_pools_market ON liquidity_pools (market_id);
+
+-- Swap legs count towards the energy ledger (see ledger_energy_balance)
+CREATE INDEX IF NOT EXISTS idx_swap_user_status ON swap_transactions (user_id, status);
+
+-- Execution report of a routed market order and how it was split
+CREATE TABLE IF NOT EXISTS order_routing_reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id),
+    market_id UUID NOT NULL REFERENCES markets(id),
+    side VARCHAR(4) NOT NULL CHECK (side IN ('buy', 'sell')),
+    requested_kwh NUMERIC(20, 9) NOT NULL,
+    -- Best opposite orderbook price, or the pool spot price when the book is empty
+    reference_price NUMERIC(20, 9),
+    tolerance_bps INTEGER NOT NULL,
+    -- Orderbook leg: marketable limit order sized to the liquidity on the book
+    book_order_id UUID REFERENCES trading_orders(id),
+    book_kwh NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    book_limit_price NUMERIC(20, 9),
+    book_avg_price NUMERIC(20, 9),
+    -- AMM leg
+    pool_id UUID REFERENCES liquidity_pools(id),
+    swap_id UUID REFERENCES swap_transactions(id),
+    amm_kwh NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    amm_currency_amount NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    amm_fee_amount NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    amm_effective_price NUMERIC(20, 9),
+    -- Why the shortfall was not routed to the pool, if it was not
+    amm_skip_reason TEXT,
+    unfilled_kwh NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_routing_reports_user ON order_routing_reports (user_id, created_at DESC);
@@ -0,0 +1,35 @@
+-- FIX 4.4 gateway sessions, persisted sequence numbers and ClOrdID mapping
+-- Migration: 20260118000016_add_fix_gateway
+
+-- One row per counterparty session; orders entered on it belong to user_id.
+-- Drop-copy sessions receive execution reports for all of the user's orders
+-- and cannot enter orders.
+CREATE TABLE IF NOT EXISTS fix_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Counterparty SenderCompID (our TargetCompID)
+    sender_comp_id VARCHAR(64) NOT NULL UNIQUE,
+    user_id UUID NOT NULL REFERENCES users(id),
+    session_type VARCHAR(20) NOT NULL DEFAULT 'order_entry'
+        CHECK (session_type IN ('order_entry', 'drop_copy')),
+    -- bcrypt hash of the Logon Password (554)
+    password_hash TEXT NOT NULL,
+    next_in_seq BIGINT NOT NULL DEFAULT 1 CHECK (next_in_seq > 0),
+    next_out_seq BIGINT NOT NULL DEFAULT 1 CHECK (next_out_seq > 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    last_logon_at TIMESTAMPTZ,
+    last_logout_at TIMESTAMPTZ,
+    created_by UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Orders entered over FIX, keyed by the client's ClOrdID
+CREATE TABLE IF NOT EXISTS fix_orders (
+    session_id UUID NOT NULL REFERENCES fix_sessions(id) ON DELETE CASCADE,
+    cl_ord_id VARCHAR(64) NOT NULL,
+    order_id UUID NOT NULL REFERENCES trading_orders(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (session_id, cl_ord_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_fix_orders_order ON fix_orders (order_id);
@@ -0,0 +1,56 @@
+-- Market maker incentives: quote sampling, daily scores and rebates
+-- Migration: 20260118000017_add_maker_incentives
+
+-- One row per market and UTC day; samples is how often the book was sampled,
+-- the denominator of each maker's time at best quote
+CREATE TABLE IF NOT EXISTS maker_incentive_days (
+    market_id UUID NOT NULL REFERENCES markets(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    samples INTEGER NOT NULL DEFAULT 0,
+    -- Set when the day's rebates are allocated
+    pool_amount NUMERIC(20, 8),
+    settled_at TIMESTAMPTZ,
+    PRIMARY KEY (market_id, day)
+);
+
+CREATE INDEX IF NOT EXISTS idx_maker_incentive_days_unsettled ON maker_incentive_days (day) WHERE settled_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS maker_quote_stats (
+    market_id UUID NOT NULL REFERENCES markets(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    -- Samples with an order at the best bid or best ask
+    at_best_samples INTEGER NOT NULL DEFAULT 0,
+    -- Samples quoting both sides, and the sum of their own spread in bps
+    two_sided_samples INTEGER NOT NULL DEFAULT 0,
+    spread_bps_sum NUMERIC(20, 4) NOT NULL DEFAULT 0,
+    score NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (market_id, day, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_maker_quote_stats_user ON maker_quote_stats (user_id, day);
+
+CREATE TABLE IF NOT EXISTS maker_rebates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    market_id UUID NOT NULL REFERENCES markets(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    score NUMERIC(20, 8) NOT NULL,
+    -- Energy tokens (kWh)
+    amount NUMERIC(20, 8) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    mint_tx_signature VARCHAR(128),
+    last_error TEXT,
+    minted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_maker_rebate_status CHECK (status IN ('pending', 'minted', 'failed')),
+    CONSTRAINT chk_maker_rebate_amount CHECK (amount > 0),
+    CONSTRAINT uq_maker_rebates_day UNIQUE (market_id, day, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_maker_rebates_user ON maker_rebates (user_id);
+CREATE INDEX IF NOT EXISTS idx_maker_rebates_pending ON maker_rebates (created_at) WHERE status = 'pending';
+
+COMMENT ON TABLE maker_rebates IS 'Daily liquidity rebates for market makers, minted on-chain by the maker incentive job';
@@ -0,0 +1,50 @@
+-- Wallet signature challenge audit: issued nonces, failed attempts and blocks
+-- Migration: 20260118000018_add_wallet_challenge_audit
+
+-- Every challenge handed out for a wallet signature. A challenge is consumed
+-- at most once; issuing a new one supersedes the open one for the same
+-- user, wallet and purpose.
+CREATE TABLE IF NOT EXISTS wallet_challenges (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    wallet_address VARCHAR(64) NOT NULL,
+    purpose VARCHAR(40) NOT NULL,
+    nonce VARCHAR(64) NOT NULL UNIQUE,
+    message TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'issued',
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    consumed_at TIMESTAMPTZ,
+    CONSTRAINT chk_wallet_challenge_status CHECK (status IN ('issued', 'consumed', 'superseded'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_challenges_wallet ON wallet_challenges (wallet_address, issued_at DESC);
+CREATE INDEX IF NOT EXISTS idx_wallet_challenges_open ON wallet_challenges (user_id, wallet_address, purpose)
+    WHERE status = 'issued';
+
+CREATE TABLE IF NOT EXISTS wallet_auth_failures (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    wallet_address VARCHAR(64) NOT NULL,
+    user_id UUID NOT NULL,
+    challenge_id UUID REFERENCES wallet_challenges(id) ON DELETE SET NULL,
+    -- bad_signature, malformed_signature, expired or replay
+    reason VARCHAR(30) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_auth_failures_wallet ON wallet_auth_failures (wallet_address, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_wallet_auth_failures_created ON wallet_auth_failures (created_at DESC);
+
+-- Wallets refused for signature challenges after too many failures
+CREATE TABLE IF NOT EXISTS wallet_auth_blocks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    wallet_address VARCHAR(64) NOT NULL,
+    failures INTEGER NOT NULL,
+    blocked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    blocked_until TIMESTAMPTZ NOT NULL,
+    released_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    released_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_auth_blocks_active ON wallet_auth_blocks (wallet_address, blocked_until)
+    WHERE released_at IS NULL;
@@ -0,0 +1,11 @@
+-- Notification inbox: read timestamps and email-path notification types
+-- Migration: 20260118000019_add_notification_inbox
+
+-- Events that were previously only emailed are now kept in the inbox too
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'settlement_complete';
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'rec_issued';
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'order_cancelled';
+
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS read_at TIMESTAMPTZ;
+
+UPDATE notifications SET read_at = created_at WHERE read = true AND read_at IS NULL;
@@ -0,0 +1,16 @@
+-- Developer sandbox: faucet airdrop ledger for per-wallet rate limits
+-- Migration: 20260118000020_add_sandbox_airdrops
+
+CREATE TABLE IF NOT EXISTS sandbox_airdrops (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    wallet_address VARCHAR(64) NOT NULL,
+    amount_sol NUMERIC(20, 9) NOT NULL,
+    -- devnet or localnet
+    cluster VARCHAR(20) NOT NULL,
+    tx_signature VARCHAR(128),
+    -- Failed airdrops still count against the limit
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_sandbox_airdrops_wallet ON sandbox_airdrops (wallet_address, created_at DESC);
@@ -0,0 +1,11 @@
+-- Achieved commitment of on-chain operations
+-- Migration: 20260118000021_add_transaction_commitment
+
+-- processed, confirmed or finalized: the level the transaction was seen at
+-- when the operation was recorded, per CONFIRMATION_* settings.
+
+ALTER TABLE settlements
+    ADD COLUMN IF NOT EXISTS commitment VARCHAR(16);
+
+ALTER TABLE meter_readings
+    ADD COLUMN IF NOT EXISTS mint_commitment VARCHAR(16);
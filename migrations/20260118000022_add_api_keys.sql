@@ -0,0 +1,17 @@
+-- Service API keys
+-- Migration: 20260118000022_add_api_keys
+
+-- Keys are stored as salted SHA-256 hashes; the plain key is shown once
+-- when issued. Rotating a key deactivates the active keys of that name.
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    permissions JSONB NOT NULL DEFAULT '[]',
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_active_name ON api_keys (name) WHERE is_active;
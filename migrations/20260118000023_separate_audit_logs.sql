@@ -0,0 +1,135 @@
+-- Dedicated audit log with typed columns
+-- Migration: 20260118000023_separate_audit_logs
+
+-- Audit events were written to user_activities with everything but the
+-- event type in JSON metadata. They now go to audit_logs, which gains
+-- typed columns for filtering:
+--   severity       info, warning or critical
+--   actor_id       who performed the action; NULL for anonymous or system
+--   resource_type  what the event is about (order, dispute, legal_hold, ...)
+--   resource_id    its identifier
+--   outcome        success, failure or denied
+-- user_id stays the user the record belongs to (legal holds and per-user
+-- queries match on it).
+
+DO $$ BEGIN
+    CREATE TYPE audit_severity AS ENUM ('info', 'warning', 'critical');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+    CREATE TYPE audit_outcome AS ENUM ('success', 'failure', 'denied');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+ALTER TABLE audit_logs
+    ADD COLUMN IF NOT EXISTS severity audit_severity NOT NULL DEFAULT 'info',
+    ADD COLUMN IF NOT EXISTS actor_id UUID,
+    ADD COLUMN IF NOT EXISTS resource_type VARCHAR(50),
+    ADD COLUMN IF NOT EXISTS resource_id VARCHAR(128),
+    ADD COLUMN IF NOT EXISTS outcome audit_outcome NOT NULL DEFAULT 'success';
+
+UPDATE audit_logs SET event_data = '{}'::jsonb WHERE event_data IS NULL;
+UPDATE audit_logs SET created_at = NOW() WHERE created_at IS NULL;
+ALTER TABLE audit_logs
+    ALTER COLUMN event_data SET NOT NULL,
+    ALTER COLUMN created_at SET NOT NULL;
+
+-- Move audit events out of user_activities, deriving the typed columns
+-- from the event JSON the same way AuditEvent does for new events
+WITH audit_types(event_type) AS (
+    VALUES
+        ('user_login'), ('user_logout'), ('login_failed'), ('password_changed'),
+        ('email_verified'), ('api_key_generated'), ('blockchain_registration'),
+        ('order_created'), ('order_cancelled'), ('order_matched'),
+        ('unauthorized_access'), ('rate_limit_exceeded'), ('data_access'),
+        ('admin_action'), ('prepaid_topup_initiated'), ('prepaid_ledger_entry'),
+        ('payment_webhook_rejected'), ('dispute_opened'), ('dispute_closed'),
+        ('address_book_changed'), ('wallet_auth_blocked'), ('audit_legal_hold_changed')
+)
+INSERT INTO audit_logs (
+    id, event_type, user_id, ip_address, event_data, created_at,
+    severity, actor_id, resource_type, resource_id, outcome
+)
+SELECT
+    a.id,
+    a.activity_type,
+    a.user_id,
+    a.ip_address,
+    COALESCE(a.metadata, '{}'::jsonb),
+    COALESCE(a.created_at, NOW()),
+    (CASE
+        WHEN a.activity_type IN ('unauthorized_access', 'wallet_auth_blocked') THEN 'critical'
+        WHEN a.activity_type IN ('login_failed', 'rate_limit_exceeded', 'payment_webhook_rejected') THEN 'warning'
+        ELSE 'info'
+    END)::audit_severity,
+    CASE
+        WHEN a.activity_type IN ('admin_action', 'audit_legal_hold_changed') THEN (a.metadata->>'admin_id')::uuid
+        WHEN a.activity_type IN ('login_failed', 'unauthorized_access', 'rate_limit_exceeded',
+                                 'payment_webhook_rejected', 'wallet_auth_blocked', 'order_matched') THEN NULL
+        ELSE a.user_id
+    END,
+    CASE a.activity_type
+        WHEN 'api_key_generated' THEN 'api_key'
+        WHEN 'blockchain_registration' THEN 'wallet'
+        WHEN 'wallet_auth_blocked' THEN 'wallet'
+        WHEN 'order_created' THEN 'order'
+        WHEN 'order_cancelled' THEN 'order'
+        WHEN 'order_matched' THEN 'order'
+        WHEN 'unauthorized_access' THEN 'endpoint'
+        WHEN 'rate_limit_exceeded' THEN 'endpoint'
+        WHEN 'data_access' THEN a.metadata->>'resource_type'
+        WHEN 'admin_action' THEN CASE WHEN a.metadata->>'target_user_id' IS NOT NULL THEN 'user' END
+        WHEN 'prepaid_topup_initiated' THEN 'prepaid_topup'
+        WHEN 'prepaid_ledger_entry' THEN 'prepaid_ledger'
+        WHEN 'dispute_opened' THEN 'dispute'
+        WHEN 'dispute_closed' THEN 'dispute'
+        WHEN 'payment_webhook_rejected' THEN 'payment_provider'
+        WHEN 'address_book_changed' THEN 'address_book_entry'
+        WHEN 'audit_legal_hold_changed' THEN 'legal_hold'
+    END,
+    CASE a.activity_type
+        WHEN 'api_key_generated' THEN a.metadata->>'key_id'
+        WHEN 'blockchain_registration' THEN a.metadata->>'wallet_address'
+        WHEN 'wallet_auth_blocked' THEN a.metadata->>'wallet_address'
+        WHEN 'order_created' THEN a.metadata->>'order_id'
+        WHEN 'order_cancelled' THEN a.metadata->>'order_id'
+        WHEN 'order_matched' THEN a.metadata->>'order_id'
+        WHEN 'unauthorized_access' THEN a.metadata->>'endpoint'
+        WHEN 'rate_limit_exceeded' THEN a.metadata->>'endpoint'
+        WHEN 'data_access' THEN a.metadata->>'resource_id'
+        WHEN 'admin_action' THEN a.metadata->>'target_user_id'
+        WHEN 'prepaid_topup_initiated' THEN a.metadata->>'topup_id'
+        WHEN 'prepaid_ledger_entry' THEN a.metadata->>'reference'
+        WHEN 'dispute_opened' THEN a.metadata->>'dispute_id'
+        WHEN 'dispute_closed' THEN a.metadata->>'dispute_id'
+        WHEN 'payment_webhook_rejected' THEN a.metadata->>'provider'
+        WHEN 'address_book_changed' THEN a.metadata->>'entry_id'
+        WHEN 'audit_legal_hold_changed' THEN a.metadata->>'hold_id'
+    END,
+    (CASE
+        WHEN a.activity_type IN ('login_failed', 'payment_webhook_rejected') THEN 'failure'
+        WHEN a.activity_type IN ('unauthorized_access', 'rate_limit_exceeded', 'wallet_auth_blocked') THEN 'denied'
+        ELSE 'success'
+    END)::audit_outcome
+FROM user_activities a
+WHERE a.activity_type IN (SELECT event_type FROM audit_types)
+ON CONFLICT (id) DO NOTHING;
+
+DELETE FROM user_activities
+WHERE activity_type IN (
+    'user_login', 'user_logout', 'login_failed', 'password_changed',
+    'email_verified', 'api_key_generated', 'blockchain_registration',
+    'order_created', 'order_cancelled', 'order_matched',
+    'unauthorized_access', 'rate_limit_exceeded', 'data_access',
+    'admin_action', 'prepaid_topup_initiated', 'prepaid_ledger_entry',
+    'payment_webhook_rejected', 'dispute_opened', 'dispute_closed',
+    'address_book_changed', 'wallet_auth_blocked', 'audit_legal_hold_changed'
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_actor ON audit_logs (actor_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_logs_resource ON audit_logs (resource_type, resource_id);
+CREATE INDEX IF NOT EXISTS idx_audit_logs_severity ON audit_logs (severity, created_at DESC)
+    WHERE severity <> 'info';
@@ -0,0 +1,19 @@
+-- GeoIP filters on the audit log
+-- Migration: 20260118000024_add_audit_geoip_indexes
+
+-- Events with a client IP carry event_data.geo (country, city, latitude,
+-- longitude, asn, as_org) when GeoIP databases are configured. The admin
+-- audit search filters on country and ASN; impossible travel detection
+-- scans recent located logins.
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_geo_country
+    ON audit_logs ((event_data->'geo'->>'country'), created_at DESC)
+    WHERE event_data ? 'geo';
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_geo_asn
+    ON audit_logs (((event_data->'geo'->>'asn')::bigint), created_at DESC)
+    WHERE event_data ? 'geo';
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_located_logins
+    ON audit_logs (created_at, user_id)
+    WHERE event_type = 'user_login' AND event_data ? 'geo';
@@ -0,0 +1,10 @@
+-- API key owners
+-- Migration: 20260118000025_add_api_key_owner
+
+-- Keys issued to a user (rather than to a service) record who owns them so
+-- the user can see them in their security overview. Service keys keep a
+-- NULL owner.
+ALTER TABLE api_keys
+    ADD COLUMN IF NOT EXISTS owner_id UUID REFERENCES users(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_owner ON api_keys (owner_id) WHERE owner_id IS NOT NULL AND is_active;
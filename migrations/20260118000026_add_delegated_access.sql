@@ -0,0 +1,52 @@
+-- Scoped delegation tokens for third-party apps
+-- Migration: 20260118000026_add_delegated_access
+
+-- Third-party apps registered by an admin. Users grant an app a subset of
+-- its scopes through the consent flow; the app redeems the authorization
+-- code with its client secret for a delegated token.
+CREATE TABLE IF NOT EXISTS delegated_apps (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(200) NOT NULL,
+    client_id VARCHAR(64) NOT NULL UNIQUE,
+    client_secret_hash VARCHAR(64) NOT NULL,
+    redirect_uris TEXT[] NOT NULL,
+    scopes TEXT[] NOT NULL,
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    disabled_at TIMESTAMPTZ
+);
+
+-- A user's consent to an app; revoking it invalidates its tokens
+CREATE TABLE IF NOT EXISTS delegation_grants (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    app_id UUID NOT NULL REFERENCES delegated_apps(id) ON DELETE CASCADE,
+    scopes TEXT[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+-- Consenting again updates the user's active grant instead of adding one
+CREATE UNIQUE INDEX IF NOT EXISTS uq_delegation_grants_active
+    ON delegation_grants (user_id, app_id) WHERE revoked_at IS NULL;
+
+-- Single-use authorization codes, stored hashed
+CREATE TABLE IF NOT EXISTS delegation_codes (
+    code_hash VARCHAR(64) PRIMARY KEY,
+    grant_id UUID NOT NULL REFERENCES delegation_grants(id) ON DELETE CASCADE,
+    redirect_uri TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ
+);
+
+-- Delegated bearer tokens, stored hashed
+CREATE TABLE IF NOT EXISTS delegation_tokens (
+    token_hash VARCHAR(64) PRIMARY KEY,
+    grant_id UUID NOT NULL REFERENCES delegation_grants(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_delegation_tokens_grant ON delegation_tokens (grant_id);
@@ -0,0 +1,8 @@
+-- User locale preference
+-- Migration: 20260118000027_add_user_locale
+
+-- Language for notifications and emails sent outside a request. Set from
+-- Accept-Language at registration and changeable from account settings.
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS preferred_locale VARCHAR(5) NOT NULL DEFAULT 'en'
+        CHECK (preferred_locale IN ('en', 'th'));
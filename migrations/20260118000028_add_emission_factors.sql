@@ -0,0 +1,32 @@
+-- Grid emission factors
+-- Migration: 20260118000028_add_emission_factors
+
+-- Grid emission factors (kg CO2 per kWh of grid electricity displaced) by
+-- region and year, with the grid mix source each figure was taken from.
+CREATE TABLE IF NOT EXISTS emission_factors (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    region VARCHAR(32) NOT NULL,
+    year INTEGER NOT NULL CHECK (year BETWEEN 1990 AND 2100),
+    kg_co2_per_kwh DOUBLE PRECISION NOT NULL CHECK (kg_co2_per_kwh >= 0),
+    grid_mix_source TEXT NOT NULL,
+    notes TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (region, year)
+);
+
+-- The factor previously hardcoded for CO2 savings
+INSERT INTO emission_factors (region, year, kg_co2_per_kwh, grid_mix_source, notes)
+VALUES ('TH', 2024, 0.431, 'Thailand national grid mix', 'Factor used before emission factors became configurable')
+ON CONFLICT (region, year) DO NOTHING;
+
+-- Factor each CO2 figure was computed with
+ALTER TABLE grid_status_history
+    ADD COLUMN IF NOT EXISTS co2_factor JSONB;
+
+ALTER TABLE community_stats_daily
+    ADD COLUMN IF NOT EXISTS co2_factor JSONB;
+
+COMMENT ON COLUMN grid_status_history.co2_factor IS 'Emission factor used for co2_saved_kg {factor_id, region, year, kg_co2_per_kwh, grid_mix_source}';
+COMMENT ON COLUMN community_stats_daily.co2_factor IS 'Emission factor used for total_co2_saved_kg {factor_id, region, year, kg_co2_per_kwh, grid_mix_source}';
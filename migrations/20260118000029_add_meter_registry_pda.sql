@@ -0,0 +1,15 @@
+-- Meter on-chain registry accounts
+-- Migration: 20260118000029_add_meter_registry_pda
+
+-- Registry program account (PDA seeded by ["meter", serial]) of each
+-- verified meter, the transaction that created it and the last sync.
+ALTER TABLE meter_registry
+    ADD COLUMN IF NOT EXISTS registry_pda VARCHAR(44),
+    ADD COLUMN IF NOT EXISTS registry_tx_signature VARCHAR(88),
+    ADD COLUMN IF NOT EXISTS registry_synced_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS registry_sync_error TEXT;
+
+-- Verified meters still waiting for their registry account
+CREATE INDEX IF NOT EXISTS idx_meter_registry_unsynced
+    ON meter_registry(created_at)
+    WHERE verification_status = 'verified' AND registry_synced_at IS NULL;
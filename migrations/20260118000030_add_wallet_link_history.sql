@@ -0,0 +1,27 @@
+-- Wallet link history
+-- Migration: 20260118000030_add_wallet_link_history
+
+-- Every wallet an account has held. The account wallet is changed only
+-- with proof of control (challenge signature or memo transaction); the
+-- row without `unlinked_at` is the current wallet.
+CREATE TABLE IF NOT EXISTS wallet_link_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    wallet_address VARCHAR(88) NOT NULL,
+    method VARCHAR(20) NOT NULL,
+    proof VARCHAR(128),
+    linked_at TIMESTAMPTZ,
+    unlinked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_wallet_link_method
+        CHECK (method IN ('signature', 'memo_transaction', 'custodial', 'unverified'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_link_history_user ON wallet_link_history (user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_wallet_link_history_wallet ON wallet_link_history (wallet_address);
+CREATE UNIQUE INDEX IF NOT EXISTS uq_wallet_link_history_current ON wallet_link_history (user_id)
+    WHERE unlinked_at IS NULL;
+
+-- Wallets set before proof was required
+INSERT INTO wallet_link_history (user_id, wallet_address, method)
+SELECT id, wallet_address, 'unverified' FROM users WHERE wallet_address IS NOT NULL;
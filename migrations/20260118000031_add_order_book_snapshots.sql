@@ -0,0 +1,23 @@
+-- Order book snapshots
+-- Migration: 20260118000031_add_order_book_snapshots
+
+-- Full L2 books of every open market, captured periodically and at each
+-- epoch clearing, so the book can be reconstructed "as of" any time for
+-- dispute resolution and surveillance. The book is stored as JSONB, which
+-- Postgres compresses out of line (TOAST) once it outgrows a page.
+CREATE TABLE IF NOT EXISTS order_book_snapshots (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    market_id UUID NOT NULL REFERENCES markets(id) ON DELETE CASCADE,
+    reason VARCHAR(16) NOT NULL,
+    epoch_id UUID REFERENCES market_epochs(id) ON DELETE SET NULL,
+    bid_levels INTEGER NOT NULL,
+    ask_levels INTEGER NOT NULL,
+    book JSONB NOT NULL,
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_order_book_snapshot_reason CHECK (reason IN ('periodic', 'clearing'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_book_snapshots_market_time
+    ON order_book_snapshots (market_id, captured_at DESC);
+CREATE INDEX IF NOT EXISTS idx_order_book_snapshots_epoch
+    ON order_book_snapshots (epoch_id) WHERE epoch_id IS NOT NULL;
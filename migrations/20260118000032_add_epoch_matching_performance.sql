@@ -0,0 +1,21 @@
+-- Epoch matching performance
+-- Migration: 20260118000032_add_epoch_matching_performance
+
+-- Time spent in each stage of an epoch's matching run (milliseconds), so
+-- regressions in the clearing hot path show up per epoch in production.
+CREATE TABLE IF NOT EXISTS epoch_matching_performance (
+    epoch_id UUID PRIMARY KEY REFERENCES market_epochs(id) ON DELETE CASCADE,
+    markets INTEGER NOT NULL,
+    orders INTEGER NOT NULL,
+    matches INTEGER NOT NULL,
+    fetch_ms DOUBLE PRECISION NOT NULL,
+    matching_ms DOUBLE PRECISION NOT NULL,
+    db_write_ms DOUBLE PRECISION NOT NULL,
+    notify_ms DOUBLE PRECISION NOT NULL,
+    settlement_ms DOUBLE PRECISION NOT NULL,
+    total_ms DOUBLE PRECISION NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_epoch_matching_performance_recorded
+    ON epoch_matching_performance (recorded_at DESC);
@@ -0,0 +1,16 @@
+-- Resident book snapshots
+-- Migration: 20260118000033_add_resident_book_snapshots
+
+-- Latest in-memory book of each market kept by the resident matcher
+-- (MATCHING_MODE=resident). On restart a book is restored from here and
+-- the order events recorded after journal_position are replayed onto it.
+CREATE TABLE IF NOT EXISTS resident_book_snapshots (
+    market_id UUID PRIMARY KEY REFERENCES markets(id) ON DELETE CASCADE,
+    journal_position TIMESTAMPTZ,
+    order_count INTEGER NOT NULL,
+    book JSONB NOT NULL,
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Journal replay reads events by time across orders
+CREATE INDEX IF NOT EXISTS idx_order_events_created_at ON order_events (created_at);
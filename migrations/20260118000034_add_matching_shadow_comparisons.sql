@@ -0,0 +1,19 @@
+-- Matching shadow comparisons
+-- Migration: 20260118000034_add_matching_shadow_comparisons
+
+-- Live epoch matching of each market compared with an alternative matcher
+-- run in shadow mode (MATCHING_SHADOW_ENABLED). Shadow results are never
+-- settled; orders filled differently are kept in divergences.
+CREATE TABLE IF NOT EXISTS matching_shadow_comparisons (
+    epoch_id UUID NOT NULL REFERENCES market_epochs(id) ON DELETE CASCADE,
+    market_id UUID NOT NULL REFERENCES markets(id) ON DELETE CASCADE,
+    algorithm VARCHAR(32) NOT NULL,
+    live_volume NUMERIC(20, 8) NOT NULL,
+    shadow_volume NUMERIC(20, 8) NOT NULL,
+    live_price NUMERIC(20, 8),
+    shadow_price NUMERIC(20, 8),
+    diverged_orders INTEGER NOT NULL,
+    divergences JSONB NOT NULL DEFAULT '[]',
+    compared_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (epoch_id, market_id, algorithm)
+);
@@ -0,0 +1,13 @@
+-- Settlement failure quarantine
+-- Migration: 20260118000035_add_settlement_quarantine
+
+-- Failed settlements whose transfer fails again when simulated on its own
+-- are quarantined with the diagnosis and skipped by automatic retries until
+-- an admin releases them.
+ALTER TABLE settlements
+    ADD COLUMN IF NOT EXISTS quarantined_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS diagnosis TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_settlements_quarantined
+    ON settlements(quarantined_at DESC)
+    WHERE quarantined_at IS NOT NULL;
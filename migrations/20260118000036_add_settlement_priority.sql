@@ -0,0 +1,22 @@
+-- Settlement priority
+-- Migration: 20260118000036_add_settlement_priority
+
+-- Per-market policy for raising the execution priority of new settlements.
+-- A NULL threshold disables its rule.
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS settlement_high_notional NUMERIC(20, 8)
+    CHECK (settlement_high_notional > 0);
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS settlement_urgent_notional NUMERIC(20, 8)
+    CHECK (settlement_urgent_notional > 0);
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS settlement_corporate_high BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS settlement_deadline_secs INTEGER
+    CHECK (settlement_deadline_secs > 0);
+
+COMMENT ON COLUMN markets.settlement_high_notional IS 'Settlements of at least this value run at high priority';
+COMMENT ON COLUMN markets.settlement_urgent_notional IS 'Settlements of at least this value run at urgent priority';
+COMMENT ON COLUMN markets.settlement_corporate_high IS 'Settlements with a corporate counterparty run at least at high priority';
+COMMENT ON COLUMN markets.settlement_deadline_secs IS 'Settlements created within this many seconds of their epoch end are raised one level';
+
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS priority VARCHAR(10) NOT NULL DEFAULT 'normal'
+    CHECK (priority IN ('normal', 'high', 'urgent'));
+
+COMMENT ON COLUMN settlements.priority IS 'Execution priority from the market policy at creation: normal, high or urgent';
@@ -0,0 +1,10 @@
+-- Settlement fee payers
+-- Migration: 20260118000037_add_settlement_fee_payers
+
+-- Key that paid each settlement transfer's fee (a pool key from
+-- SETTLEMENT_FEE_PAYER_PATHS, or the seller) and the fee it was charged
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS fee_payer VARCHAR(44);
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS fee_lamports BIGINT CHECK (fee_lamports >= 0);
+
+CREATE INDEX IF NOT EXISTS idx_settlements_fee_payer ON settlements (fee_payer, processed_at)
+    WHERE fee_payer IS NOT NULL;
@@ -0,0 +1,26 @@
+-- Delivery verifications
+-- Migration: 20260118000038_add_delivery_verifications
+
+-- Physical delivery check of each completed settlement against the meter
+-- readings of its epoch, and the remedy applied to a seller shortfall
+CREATE TABLE IF NOT EXISTS delivery_verifications (
+    settlement_id UUID PRIMARY KEY REFERENCES settlements(id) ON DELETE CASCADE,
+    epoch_id UUID NOT NULL REFERENCES market_epochs(id) ON DELETE CASCADE,
+    buyer_id UUID NOT NULL,
+    seller_id UUID NOT NULL,
+    matched_kwh NUMERIC(20, 8) NOT NULL,
+    delivered_kwh NUMERIC(20, 8) NOT NULL,
+    shortfall_kwh NUMERIC(20, 8) NOT NULL,
+    buyer_shortfall_kwh NUMERIC(20, 8) NOT NULL,
+    seller_generated_kwh NUMERIC(20, 8) NOT NULL,
+    buyer_consumed_kwh NUMERIC(20, 8) NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    remedy VARCHAR(10),
+    refund_amount NUMERIC(20, 8),
+    verified_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_delivery_verification_status CHECK (status IN ('verified', 'shortfall', 'under_consumed')),
+    CONSTRAINT chk_delivery_verification_remedy CHECK (remedy IS NULL OR remedy IN ('flag', 'refund'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_delivery_verifications_epoch ON delivery_verifications (epoch_id);
+CREATE INDEX IF NOT EXISTS idx_delivery_verifications_status ON delivery_verifications (status, verified_at DESC);
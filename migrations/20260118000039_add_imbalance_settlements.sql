@@ -0,0 +1,25 @@
+-- Imbalance settlements
+-- Migration: 20260118000039_add_imbalance_settlements
+
+-- Gap between each participant's traded and metered net export in an
+-- epoch, priced at the imbalance rate. Pending rows are posted to balances
+-- by the settlement worker; balanced rows record a participant with no gap.
+CREATE TABLE IF NOT EXISTS imbalance_settlements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    epoch_id UUID NOT NULL REFERENCES market_epochs(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    contracted_kwh NUMERIC(20, 8) NOT NULL,
+    metered_kwh NUMERIC(20, 8) NOT NULL,
+    imbalance_kwh NUMERIC(20, 8) NOT NULL,
+    price_per_kwh NUMERIC(20, 8) NOT NULL,
+    amount NUMERIC(20, 8) NOT NULL,
+    status VARCHAR(10) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    settled_at TIMESTAMPTZ,
+    CONSTRAINT uq_imbalance_settlements_epoch_user UNIQUE (epoch_id, user_id),
+    CONSTRAINT chk_imbalance_settlement_status CHECK (status IN ('balanced', 'pending', 'settled'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_imbalance_settlements_user ON imbalance_settlements (user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_imbalance_settlements_pending ON imbalance_settlements (created_at)
+    WHERE status = 'pending';
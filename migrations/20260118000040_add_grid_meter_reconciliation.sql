@@ -0,0 +1,41 @@
+-- Grid meter data reconciliation
+-- Migration: 20260118000040_add_grid_meter_reconciliation
+
+-- Official interval data per meter uploaded by the DSO
+CREATE TABLE IF NOT EXISTS grid_meter_intervals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    meter_serial VARCHAR(100) NOT NULL,
+    interval_start TIMESTAMPTZ NOT NULL,
+    interval_end TIMESTAMPTZ NOT NULL,
+    energy_generated NUMERIC(20, 8) NOT NULL,
+    energy_consumed NUMERIC(20, 8) NOT NULL,
+    source VARCHAR(100) NOT NULL,
+    imported_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    imported_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_grid_meter_intervals UNIQUE (meter_serial, interval_start),
+    CONSTRAINT chk_grid_meter_interval_range CHECK (interval_end > interval_start),
+    CONSTRAINT chk_grid_meter_interval_energy CHECK (energy_generated >= 0 AND energy_consumed >= 0)
+);
+
+-- Comparison of an official interval with the readings users submitted for it
+CREATE TABLE IF NOT EXISTS meter_reconciliations (
+    interval_id UUID PRIMARY KEY REFERENCES grid_meter_intervals(id) ON DELETE CASCADE,
+    meter_serial VARCHAR(100) NOT NULL,
+    interval_start TIMESTAMPTZ NOT NULL,
+    official_generated NUMERIC(20, 8) NOT NULL,
+    official_consumed NUMERIC(20, 8) NOT NULL,
+    reported_generated NUMERIC(20, 8) NOT NULL,
+    reported_consumed NUMERIC(20, 8) NOT NULL,
+    -- Fraction of the official volume
+    deviation NUMERIC(12, 6) NOT NULL,
+    divergent BOOLEAN NOT NULL,
+    reconciled_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_meter_reconciliations_meter ON meter_reconciliations (meter_serial, interval_start DESC);
+CREATE INDEX IF NOT EXISTS idx_meter_reconciliations_start ON meter_reconciliations (interval_start);
+
+-- Minting freeze for meters that diverge systematically from official data
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS mint_frozen_at TIMESTAMPTZ;
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS mint_freeze_reason TEXT;
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS mint_unfrozen_at TIMESTAMPTZ;
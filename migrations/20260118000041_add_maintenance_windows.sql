@@ -0,0 +1,20 @@
+-- Maintenance windows
+-- Migration: 20260118000041_add_maintenance_windows
+
+-- Planned maintenance published on /api/v1/status. Windows are cancelled
+-- rather than deleted so partners can see what was announced.
+CREATE TABLE IF NOT EXISTS maintenance_windows (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    title VARCHAR(200) NOT NULL,
+    description TEXT,
+    components TEXT[] NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    cancelled_at TIMESTAMPTZ,
+    CONSTRAINT chk_maintenance_window_order CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_maintenance_windows_upcoming ON maintenance_windows (starts_at)
+    WHERE cancelled_at IS NULL;
@@ -0,0 +1,21 @@
+-- Trading halts
+-- Migration: 20260118000042_add_trading_halts
+
+-- Admin halts of one market, one zone, or the whole platform when both are
+-- empty. A halt is in force until lifted or until its scheduled resumption.
+CREATE TABLE IF NOT EXISTS trading_halts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    market_id UUID REFERENCES markets(id) ON DELETE CASCADE,
+    zone_id INTEGER,
+    mode VARCHAR(20) NOT NULL,
+    reason TEXT NOT NULL,
+    resume_at TIMESTAMPTZ,
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    lifted_at TIMESTAMPTZ,
+    lifted_by UUID REFERENCES users(id),
+    CONSTRAINT chk_trading_halt_mode CHECK (mode IN ('full', 'cancel_only'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_trading_halts_open ON trading_halts (created_at DESC)
+    WHERE lifted_at IS NULL;
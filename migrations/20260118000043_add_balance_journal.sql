@@ -0,0 +1,55 @@
+-- Balance journal and certificate retirement times
+-- Migration: 20260118000043_add_balance_journal
+
+-- Append-only journal of account balances, written by trigger so every code
+-- path that moves a balance is captured. Each row holds the balances after
+-- the change; the state of an account at any time is its latest row at or
+-- before that time. The baseline rows record balances when the journal
+-- started, so earlier points in time cannot be answered.
+CREATE TABLE IF NOT EXISTS balance_journal (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    entry_type VARCHAR(10) NOT NULL,
+    balance NUMERIC(20, 8) NOT NULL,
+    locked_amount NUMERIC(20, 8) NOT NULL,
+    locked_energy NUMERIC(20, 8) NOT NULL,
+    balance_delta NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp(),
+    CONSTRAINT chk_balance_journal_entry CHECK (entry_type IN ('baseline', 'opened', 'changed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_balance_journal_user ON balance_journal (user_id, recorded_at DESC, id DESC);
+
+CREATE OR REPLACE FUNCTION record_balance_journal() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO balance_journal (user_id, entry_type, balance, locked_amount, locked_energy, balance_delta)
+        VALUES (
+            NEW.id, 'opened', COALESCE(NEW.balance, 0), COALESCE(NEW.locked_amount, 0),
+            COALESCE(NEW.locked_energy, 0), COALESCE(NEW.balance, 0)
+        );
+    ELSIF (NEW.balance, NEW.locked_amount, NEW.locked_energy)
+        IS DISTINCT FROM (OLD.balance, OLD.locked_amount, OLD.locked_energy) THEN
+        INSERT INTO balance_journal (user_id, entry_type, balance, locked_amount, locked_energy, balance_delta)
+        VALUES (
+            NEW.id, 'changed', COALESCE(NEW.balance, 0), COALESCE(NEW.locked_amount, 0),
+            COALESCE(NEW.locked_energy, 0), COALESCE(NEW.balance, 0) - COALESCE(OLD.balance, 0)
+        );
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS users_balance_journal ON users;
+CREATE TRIGGER users_balance_journal
+    AFTER INSERT OR UPDATE OF balance, locked_amount, locked_energy ON users
+    FOR EACH ROW EXECUTE FUNCTION record_balance_journal();
+
+INSERT INTO balance_journal (user_id, entry_type, balance, locked_amount, locked_energy)
+SELECT id, 'baseline', COALESCE(balance, 0), COALESCE(locked_amount, 0), COALESCE(locked_energy, 0)
+FROM users;
+
+-- Certificate retirement time, for ownership as of a point in time. Earlier
+-- retirements only have their last update time to go by.
+ALTER TABLE erc_certificates ADD COLUMN IF NOT EXISTS retired_at TIMESTAMPTZ;
+UPDATE erc_certificates SET retired_at = updated_at WHERE status = 'Retired' AND retired_at IS NULL;
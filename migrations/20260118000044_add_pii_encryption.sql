@@ -0,0 +1,38 @@
+-- Encrypted personal data
+-- Migration: 20260118000044_add_pii_encryption
+
+-- Data keys for application-layer encryption of personal data, stored
+-- wrapped by the KMS master key. `data` keys seal values, the newest
+-- unretired one being active; the single `digest` key computes the HMAC
+-- digests used for lookups and is never rotated.
+CREATE TABLE IF NOT EXISTS pii_data_keys (
+    id SERIAL PRIMARY KEY,
+    purpose VARCHAR(10) NOT NULL,
+    kms_provider VARCHAR(20) NOT NULL,
+    wrapped_key BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set by the rotation job once no row is sealed under the key
+    retired_at TIMESTAMPTZ,
+    CONSTRAINT chk_pii_key_purpose CHECK (purpose IN ('data', 'digest'))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_pii_data_keys_digest ON pii_data_keys (purpose)
+    WHERE purpose = 'digest';
+
+-- Sealed user columns. The plaintext columns are cleared by the rotation
+-- job as it encrypts existing rows and are no longer written.
+ALTER TABLE users ALTER COLUMN email DROP NOT NULL;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_encrypted BYTEA;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_digest BYTEA;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS first_name_encrypted BYTEA;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS last_name_encrypted BYTEA;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS pii_key_id INTEGER REFERENCES pii_data_keys(id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_users_email_digest ON users (email_digest);
+CREATE INDEX IF NOT EXISTS idx_users_pii_key ON users (pii_key_id);
+
+-- Sealed meter installation address
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS location_address_encrypted BYTEA;
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS pii_key_id INTEGER REFERENCES pii_data_keys(id);
+
+CREATE INDEX IF NOT EXISTS idx_meter_registry_pii_key ON meter_registry (pii_key_id);
@@ -0,0 +1,22 @@
+-- Login step-up challenges
+-- Migration: 20260118000045_add_login_step_up
+
+-- One-time codes sent to users whose password login was scored risky. The
+-- session token is issued once a challenge is verified.
+CREATE TABLE IF NOT EXISTS login_step_up_challenges (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    method VARCHAR(20) NOT NULL DEFAULT 'email_otp',
+    -- SHA-256 of the challenge id and code
+    code_hash VARCHAR(64) NOT NULL,
+    signals TEXT[] NOT NULL,
+    ip_address VARCHAR(64) NOT NULL,
+    user_agent TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    verified_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_step_up_method CHECK (method IN ('email_otp'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_step_up_user ON login_step_up_challenges (user_id, created_at DESC);
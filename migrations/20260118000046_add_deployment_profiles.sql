@@ -0,0 +1,24 @@
+-- Deployment profiles
+-- Migration: 20260118000046_add_deployment_profiles
+
+-- The tenant a database belongs to. The first gateway started against an
+-- empty table records its profile; later starts must use the same profile,
+-- token mint and program IDs. Branding columns left NULL fall back to the
+-- gateway's BRAND_* settings.
+CREATE TABLE IF NOT EXISTS deployment_profiles (
+    profile_key VARCHAR(64) PRIMARY KEY,
+    brand_name VARCHAR(100),
+    support_email VARCHAR(255),
+    logo_url TEXT,
+    primary_color VARCHAR(16),
+    -- Enabled modules; NULL keeps the gateway's DEPLOYMENT_FEATURES
+    features TEXT[],
+    energy_token_mint VARCHAR(64) NOT NULL,
+    registry_program_id VARCHAR(64) NOT NULL,
+    oracle_program_id VARCHAR(64) NOT NULL,
+    governance_program_id VARCHAR(64) NOT NULL,
+    energy_token_program_id VARCHAR(64) NOT NULL,
+    trading_program_id VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
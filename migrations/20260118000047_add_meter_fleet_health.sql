@@ -0,0 +1,21 @@
+-- Meter fleet health counters
+-- Migration: 20260118000047_add_meter_fleet_health
+
+-- Time of the last accepted reading, for online/stale/offline classification
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS last_reading_at TIMESTAMPTZ;
+
+-- Per-meter, per-hour counts behind the fleet health report. Rows older than
+-- METER_FLEET_RETENTION_DAYS are pruned by the fleet health job.
+CREATE TABLE IF NOT EXISTS meter_health_hourly (
+    meter_serial VARCHAR(50) NOT NULL,
+    hour TIMESTAMPTZ NOT NULL,
+    readings INTEGER NOT NULL DEFAULT 0,
+    signature_checks INTEGER NOT NULL DEFAULT 0,
+    signature_failures INTEGER NOT NULL DEFAULT 0,
+    anomalies INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (meter_serial, hour)
+);
+
+CREATE INDEX IF NOT EXISTS idx_meter_health_hourly_hour ON meter_health_hourly (hour);
+
+COMMENT ON COLUMN meter_health_hourly.anomalies IS 'Alerts raised by meter_analyzer for readings in this hour';
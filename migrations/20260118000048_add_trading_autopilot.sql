@@ -0,0 +1,43 @@
+-- Trading autopilot
+-- Migration: 20260118000048_add_trading_autopilot
+
+-- Prosumers opted into autopilot and how their surplus is priced
+CREATE TABLE IF NOT EXISTS autopilot_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    strategy VARCHAR(20) NOT NULL DEFAULT 'index_linked',
+    -- Premium over the clearing price index, in basis points (index_linked)
+    index_offset_bps INTEGER NOT NULL DEFAULT 0,
+    -- Lowest price accepted; the limit price of limit_floor
+    floor_price NUMERIC(20, 8),
+    -- Share of the forecast surplus offered
+    sell_fraction NUMERIC(5, 4) NOT NULL DEFAULT 0.8,
+    max_kwh_per_epoch NUMERIC(20, 8),
+    session_token TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_autopilot_strategy CHECK (strategy IN ('index_linked', 'limit_floor')),
+    CONSTRAINT chk_autopilot_sell_fraction CHECK (sell_fraction > 0 AND sell_fraction <= 1)
+);
+
+-- What autopilot did for a prosumer in each epoch. The row is claimed before
+-- the order is placed so two instances never place the same epoch twice.
+CREATE TABLE IF NOT EXISTS autopilot_orders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    epoch_id UUID NOT NULL REFERENCES market_epochs(id) ON DELETE CASCADE,
+    order_id UUID REFERENCES trading_orders(id) ON DELETE SET NULL,
+    forecast_kwh NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    energy_amount NUMERIC(20, 8),
+    price_per_kwh NUMERIC(20, 8),
+    status VARCHAR(20) NOT NULL DEFAULT 'planning',
+    detail TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    closed_at TIMESTAMPTZ,
+    CONSTRAINT uq_autopilot_orders_epoch UNIQUE (user_id, epoch_id),
+    CONSTRAINT chk_autopilot_order_status CHECK (status IN ('planning', 'placed', 'skipped', 'failed', 'closed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_autopilot_orders_user ON autopilot_orders (user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_autopilot_orders_placed ON autopilot_orders (epoch_id) WHERE status = 'placed';
+CREATE INDEX IF NOT EXISTS idx_autopilot_orders_order ON autopilot_orders (order_id);
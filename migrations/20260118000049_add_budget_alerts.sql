@@ -0,0 +1,27 @@
+-- Household budget alerts
+-- Migration: 20260118000049_add_budget_alerts
+
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'budget_alert';
+
+-- User thresholds on monthly spend, daily consumption and the clearing
+-- price, checked by the budget alert job
+CREATE TABLE IF NOT EXISTS budget_alerts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(30) NOT NULL,
+    threshold NUMERIC(20, 8) NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    note TEXT,
+    -- Month or day the alert last fired for ('above' for a price alert);
+    -- cleared to re-arm once the value is back under the threshold
+    triggered_period VARCHAR(20),
+    last_triggered_at TIMESTAMPTZ,
+    last_value NUMERIC(20, 8),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_budget_alert_kind CHECK (kind IN ('monthly_spend', 'daily_consumption', 'clearing_price')),
+    CONSTRAINT chk_budget_alert_threshold CHECK (threshold > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_budget_alerts_user ON budget_alerts (user_id);
+CREATE INDEX IF NOT EXISTS idx_budget_alerts_enabled ON budget_alerts (kind) WHERE enabled;
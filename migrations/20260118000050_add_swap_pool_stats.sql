@@ -0,0 +1,20 @@
+-- Swap pool statistics
+-- Migration: 20260118000050_add_swap_pool_stats
+
+-- Hourly rollup of completed swaps per pool, rebuilt by the pool stats
+-- aggregator. Currency amounts value energy-side fees at the swap's price.
+-- Reserves are sampled when the aggregator runs during the hour and stay
+-- NULL for hours backfilled from older swaps.
+CREATE TABLE IF NOT EXISTS swap_pool_hourly (
+    pool_id UUID NOT NULL REFERENCES liquidity_pools(id) ON DELETE CASCADE,
+    hour TIMESTAMPTZ NOT NULL,
+    swaps INTEGER NOT NULL DEFAULT 0,
+    volume_kwh NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    volume_currency NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    fees_currency NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    reserve_energy NUMERIC(20, 9),
+    reserve_currency NUMERIC(20, 9),
+    PRIMARY KEY (pool_id, hour)
+);
+
+CREATE INDEX IF NOT EXISTS idx_swap_pool_time ON swap_transactions (pool_id, created_at);
@@ -0,0 +1,38 @@
+-- Idempotent epoch settlements
+-- Migration: 20260118000051_add_settlement_idempotency
+
+-- Clearing used to leave the order pair off its settlements; recover it
+-- from the match that links to each one
+UPDATE settlements s
+SET buy_order_id = m.buy_order_id,
+    sell_order_id = m.sell_order_id
+FROM order_matches m
+WHERE m.settlement_id = s.id
+  AND s.buy_order_id IS NULL;
+
+-- Settlements duplicated by overlapping clearing runs keep their rows for
+-- reconciliation but drop out of the key; matches point at the original
+WITH ranked AS (
+    SELECT id,
+           FIRST_VALUE(id) OVER w AS original_id,
+           ROW_NUMBER() OVER w AS rn
+    FROM settlements
+    WHERE epoch_id IS NOT NULL AND buy_order_id IS NOT NULL AND sell_order_id IS NOT NULL
+    WINDOW w AS (PARTITION BY epoch_id, buy_order_id, sell_order_id ORDER BY created_at, id)
+),
+repointed AS (
+    UPDATE order_matches m
+    SET settlement_id = r.original_id
+    FROM ranked r
+    WHERE m.settlement_id = r.id AND r.rn > 1
+)
+UPDATE settlements s
+SET buy_order_id = NULL,
+    sell_order_id = NULL
+FROM ranked r
+WHERE s.id = r.id AND r.rn > 1;
+
+-- One settlement per matched order pair in an epoch; clearing inserts
+-- against this key and reuses the row a concurrent run already wrote
+CREATE UNIQUE INDEX IF NOT EXISTS uq_settlements_epoch_orders
+    ON settlements(epoch_id, buy_order_id, sell_order_id);
@@ -0,0 +1,19 @@
+-- Order size limits and dust handling
+-- Migration: 20260118000052_add_order_size_limits_and_dust
+
+-- Largest order a market accepts; NULL for no limit
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS max_order_kwh NUMERIC(20, 9) CHECK (max_order_kwh > 0);
+-- Remainders below this are cancelled by the matchers; NULL uses min_order_kwh
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS dust_threshold_kwh NUMERIC(20, 9) CHECK (dust_threshold_kwh > 0);
+
+-- One row per order whose remainder was cancelled as dust
+CREATE TABLE IF NOT EXISTS order_dust (
+    order_id UUID PRIMARY KEY REFERENCES trading_orders(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    market_id UUID REFERENCES markets(id),
+    side VARCHAR(4) NOT NULL CHECK (side IN ('buy', 'sell')),
+    amount_kwh NUMERIC(20, 9) NOT NULL CHECK (amount_kwh > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_dust_user ON order_dust(user_id, created_at DESC);
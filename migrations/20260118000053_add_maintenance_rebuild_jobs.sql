@@ -0,0 +1,29 @@
+-- Maintenance rebuild jobs
+-- Migration: 20260118000053_add_maintenance_rebuild_jobs
+
+-- Admin-requested rebuilds of caches and aggregates, run in the background
+CREATE TABLE IF NOT EXISTS maintenance_rebuild_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    target VARCHAR(32) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'queued',
+    steps_done INTEGER NOT NULL DEFAULT 0,
+    steps_total INTEGER NOT NULL DEFAULT 0,
+    requested_by UUID NOT NULL REFERENCES users(id),
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ,
+    -- Touched with every progress update; a running job that stops
+    -- touching it was interrupted
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_rebuild_job_target CHECK (
+        target IN ('orderbook_cache', 'candle_aggregates', 'dashboard_grid_status', 'analytics_rollups')
+    ),
+    CONSTRAINT chk_rebuild_job_status CHECK (status IN ('queued', 'running', 'completed', 'failed'))
+);
+
+-- At most one unfinished rebuild per target
+CREATE UNIQUE INDEX IF NOT EXISTS uq_rebuild_jobs_active_target
+    ON maintenance_rebuild_jobs(target) WHERE status IN ('queued', 'running');
+
+CREATE INDEX IF NOT EXISTS idx_rebuild_jobs_created ON maintenance_rebuild_jobs(created_at DESC);
@@ -0,0 +1,66 @@
+-- AMI bulk backfill
+-- Migration: 20260118000054_add_ami_backfill_batches
+
+-- A gateway's declared backfill of missed readings over a past time window.
+-- Readings are staged until an admin approves the batch; only then are they
+-- imported into meter_readings and minted.
+CREATE TABLE IF NOT EXISTS ami_backfill_batches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    gateway_id UUID NOT NULL REFERENCES meter_gateways(id),
+    window_start TIMESTAMPTZ NOT NULL,
+    window_end TIMESTAMPTZ NOT NULL,
+    reason TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'open',
+    reviewed_by UUID REFERENCES users(id),
+    reviewed_at TIMESTAMPTZ,
+    review_note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_backfill_window CHECK (window_start < window_end),
+    CONSTRAINT chk_backfill_status CHECK (status IN ('open', 'approved', 'rejected'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_backfill_batches_status ON ami_backfill_batches(status, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_backfill_batches_gateway ON ami_backfill_batches(gateway_id, created_at DESC);
+
+-- Signed readings submitted for a batch. A reading is identified by meter and
+-- timestamp, so re-submitting it updates the staged row instead of adding one.
+-- The staged id becomes the meter_readings id on import.
+CREATE TABLE IF NOT EXISTS ami_backfill_readings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    batch_id UUID NOT NULL REFERENCES ami_backfill_batches(id) ON DELETE CASCADE,
+    meter_serial VARCHAR(50) NOT NULL,
+    meter_id UUID NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id),
+    wallet_address VARCHAR(88) NOT NULL,
+    reading_timestamp TIMESTAMPTZ NOT NULL,
+    kwh_amount NUMERIC(20, 8) NOT NULL,
+    energy_generated DOUBLE PRECISION,
+    energy_consumed DOUBLE PRECISION,
+    voltage DOUBLE PRECISION,
+    current_amps DOUBLE PRECISION,
+    power_factor DOUBLE PRECISION,
+    frequency DOUBLE PRECISION,
+    meter_signature TEXT NOT NULL,
+    message_version SMALLINT NOT NULL,
+    sequence BIGINT NOT NULL,
+    -- staged, imported, duplicate (a live reading already existed), minting,
+    -- minted, mint_failed or rejected. A row left in minting was interrupted
+    -- while minting and is not retried automatically.
+    status VARCHAR(16) NOT NULL DEFAULT 'staged',
+    mint_tx_signature VARCHAR(88),
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_backfill_reading UNIQUE (meter_serial, reading_timestamp),
+    CONSTRAINT chk_backfill_reading_status CHECK (
+        status IN ('staged', 'imported', 'duplicate', 'minting', 'minted', 'mint_failed', 'rejected')
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_backfill_readings_batch ON ami_backfill_readings(batch_id, status);
+
+-- Imported readings keep a link to their batch
+ALTER TABLE meter_readings ADD COLUMN IF NOT EXISTS backfill_batch_id UUID;
+
+COMMENT ON COLUMN meter_readings.backfill_batch_id IS 'AMI backfill batch the reading was imported from; NULL for live readings';
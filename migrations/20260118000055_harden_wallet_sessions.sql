@@ -0,0 +1,14 @@
+-- Wallet session hardening
+-- Migration: 20260118000055_harden_wallet_sessions
+
+-- Sessions are bound to the device that unlocked the wallet: the client's
+-- fingerprint and a SHA-256 hash of its User-Agent must match on every use.
+ALTER TABLE wallet_sessions ADD COLUMN IF NOT EXISTS user_agent_hash VARCHAR(64);
+
+-- expires_at slides forward with use; absolute_expires_at caps it and never moves
+ALTER TABLE wallet_sessions ADD COLUMN IF NOT EXISTS absolute_expires_at TIMESTAMPTZ;
+UPDATE wallet_sessions SET absolute_expires_at = expires_at WHERE absolute_expires_at IS NULL;
+ALTER TABLE wallet_sessions ALTER COLUMN absolute_expires_at SET NOT NULL;
+
+COMMENT ON COLUMN wallet_sessions.user_agent_hash IS 'SHA-256 hex of the User-Agent the session was created from; NULL binds the fingerprint only';
+COMMENT ON COLUMN wallet_sessions.absolute_expires_at IS 'Hard end of the session; expires_at never slides past it';
@@ -0,0 +1,58 @@
+-- Fee sponsorship of user token transfers
+-- Migration: 20260118000056_add_fee_sponsorship
+
+-- Corporate-sponsored programs: the platform payer covers transfer fees of
+-- member users, charged against the program's budget
+CREATE TABLE IF NOT EXISTS fee_sponsorship_programs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    sponsor VARCHAR(100) NOT NULL,
+    budget_lamports BIGINT NOT NULL CHECK (budget_lamports > 0),
+    -- Cap per member per calendar month; NULL leaves only the program budget
+    member_monthly_lamports BIGINT CHECK (member_monthly_lamports > 0),
+    starts_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ends_at TIMESTAMPTZ,
+    active BOOLEAN NOT NULL DEFAULT true,
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS fee_sponsorship_members (
+    program_id UUID NOT NULL REFERENCES fee_sponsorship_programs(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    added_by UUID REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (program_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_fee_sponsorship_members_user ON fee_sponsorship_members(user_id);
+
+-- One row per sponsored transfer. The estimated fee is reserved before the
+-- transfer is sent and replaced by the fee actually charged once confirmed;
+-- a failed transfer releases its reservation.
+CREATE TABLE IF NOT EXISTS fee_sponsorships (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id),
+    -- free_tier or program
+    rule VARCHAR(16) NOT NULL,
+    program_id UUID REFERENCES fee_sponsorship_programs(id),
+    recipient VARCHAR(44) NOT NULL,
+    amount_kwh NUMERIC(20, 9) NOT NULL,
+    fee_payer VARCHAR(44) NOT NULL,
+    reserved_lamports BIGINT NOT NULL CHECK (reserved_lamports >= 0),
+    fee_lamports BIGINT CHECK (fee_lamports >= 0),
+    tx_signature VARCHAR(88),
+    -- reserved, charged or released
+    status VARCHAR(16) NOT NULL DEFAULT 'reserved',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    settled_at TIMESTAMPTZ,
+    CONSTRAINT chk_fee_sponsorship_rule CHECK (rule IN ('free_tier', 'program')),
+    CONSTRAINT chk_fee_sponsorship_status CHECK (status IN ('reserved', 'charged', 'released')),
+    CONSTRAINT chk_fee_sponsorship_program CHECK ((rule = 'program') = (program_id IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_fee_sponsorships_user ON fee_sponsorships(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_fee_sponsorships_program ON fee_sponsorships(program_id, created_at DESC)
+    WHERE program_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_fee_sponsorships_created ON fee_sponsorships(created_at DESC);
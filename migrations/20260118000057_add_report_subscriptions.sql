@@ -0,0 +1,56 @@
+-- Scheduled analytics report subscriptions
+-- Migration: 20260118000057_add_report_subscriptions
+
+-- A user's (or, for admins, the platform's) recurring report. Each run
+-- covers the last completed day, week or month and is delivered by email
+-- with the report attached, or posted to the subscriber's webhook.
+CREATE TABLE IF NOT EXISTS report_subscriptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- daily_trading_summary, weekly_generation or monthly_carbon
+    report VARCHAR(32) NOT NULL,
+    -- user (the subscriber's own activity) or platform (admins only)
+    scope VARCHAR(16) NOT NULL DEFAULT 'user',
+    -- email or webhook
+    channel VARCHAR(16) NOT NULL,
+    -- Attachment format of emailed reports: csv or pdf
+    format VARCHAR(8) NOT NULL DEFAULT 'csv',
+    webhook_url TEXT,
+    -- HMAC key signing webhook deliveries, shown once at creation
+    webhook_secret VARCHAR(64),
+    active BOOLEAN NOT NULL DEFAULT true,
+    next_run_at TIMESTAMPTZ NOT NULL,
+    -- Start of the last period delivered, so a period is sent once
+    last_period_start TIMESTAMPTZ,
+    last_delivered_at TIMESTAMPTZ,
+    -- Consecutive failed deliveries; reset by a successful one
+    failure_count INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_report_subscription_report
+        CHECK (report IN ('daily_trading_summary', 'weekly_generation', 'monthly_carbon')),
+    CONSTRAINT chk_report_subscription_scope CHECK (scope IN ('user', 'platform')),
+    CONSTRAINT chk_report_subscription_channel CHECK (channel IN ('email', 'webhook')),
+    CONSTRAINT chk_report_subscription_format CHECK (format IN ('csv', 'pdf')),
+    CONSTRAINT chk_report_subscription_webhook
+        CHECK (channel <> 'webhook' OR (webhook_url IS NOT NULL AND webhook_secret IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_report_subscriptions_user ON report_subscriptions(user_id);
+CREATE INDEX IF NOT EXISTS idx_report_subscriptions_due ON report_subscriptions(next_run_at) WHERE active;
+
+-- Every delivery attempt, for the subscriber's delivery history
+CREATE TABLE IF NOT EXISTS report_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subscription_id UUID NOT NULL REFERENCES report_subscriptions(id) ON DELETE CASCADE,
+    period_start TIMESTAMPTZ NOT NULL,
+    period_end TIMESTAMPTZ NOT NULL,
+    -- delivered or failed
+    status VARCHAR(16) NOT NULL,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_report_deliveries_subscription
+    ON report_deliveries(subscription_id, created_at DESC);
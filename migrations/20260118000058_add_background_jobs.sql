@@ -0,0 +1,45 @@
+-- Background job tracking
+-- Migration: 20260118000058_add_background_jobs
+
+-- One row per run of a background job (report delivery, referral reward
+-- minting, monthly statements, audit retention, ...). Scheduled runs start
+-- as running; admin retries are queued until the job's loop claims them.
+CREATE TABLE IF NOT EXISTS background_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(48) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'queued',
+    -- 1 for a scheduled run, one more for each retry of it
+    attempt INTEGER NOT NULL DEFAULT 1,
+    retry_of UUID REFERENCES background_jobs(id) ON DELETE SET NULL,
+    -- Admin who queued a retry; NULL for scheduled runs
+    requested_by UUID REFERENCES users(id),
+    -- Set by an admin; a running job stops at its next checkpoint
+    cancel_requested BOOLEAN NOT NULL DEFAULT false,
+    cancelled_by UUID REFERENCES users(id),
+    summary TEXT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ,
+    -- Touched with every log line; a running job that stops touching it
+    -- was interrupted
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_background_job_status
+        CHECK (status IN ('queued', 'running', 'completed', 'failed', 'cancelled'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_background_jobs_created ON background_jobs(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_background_jobs_kind_status ON background_jobs(kind, status);
+CREATE INDEX IF NOT EXISTS idx_background_jobs_unfinished
+    ON background_jobs(status) WHERE status IN ('queued', 'running');
+
+CREATE TABLE IF NOT EXISTS background_job_logs (
+    id BIGSERIAL PRIMARY KEY,
+    job_id UUID NOT NULL REFERENCES background_jobs(id) ON DELETE CASCADE,
+    -- info, warn or error
+    level VARCHAR(8) NOT NULL,
+    message TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_background_job_logs_job ON background_job_logs(job_id, id);
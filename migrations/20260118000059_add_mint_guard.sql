@@ -0,0 +1,42 @@
+-- Minting guard
+-- Migration: 20260118000059_add_mint_guard
+
+-- Rated output of a meter's installation; NULL falls back to the configured default
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS capacity_kw NUMERIC(12, 3);
+ALTER TABLE meter_registry DROP CONSTRAINT IF EXISTS chk_meter_capacity_positive;
+ALTER TABLE meter_registry ADD CONSTRAINT chk_meter_capacity_positive CHECK (capacity_kw IS NULL OR capacity_kw > 0);
+
+-- Readings recorded without minting because they would have taken their
+-- wallet over its hourly or daily cap, held until an admin releases
+-- (mints) or rejects them
+CREATE TABLE IF NOT EXISTS mint_quarantine (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    reading_id UUID NOT NULL UNIQUE,
+    meter_serial VARCHAR(255) NOT NULL,
+    wallet_address VARCHAR(88) NOT NULL,
+    kwh_amount NUMERIC(20, 8) NOT NULL,
+    reading_timestamp TIMESTAMPTZ NOT NULL,
+    -- Cap that was exceeded: hour or day
+    breached_window VARCHAR(8) NOT NULL,
+    limit_kwh NUMERIC(20, 8) NOT NULL,
+    -- Already minted to the wallet within the window
+    minted_kwh NUMERIC(20, 8) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    reviewed_by UUID REFERENCES users(id),
+    reviewed_at TIMESTAMPTZ,
+    review_note TEXT,
+    mint_tx_signature VARCHAR(128),
+    -- Last failed release
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_mint_quarantine_window CHECK (breached_window IN ('hour', 'day')),
+    CONSTRAINT chk_mint_quarantine_status CHECK (status IN ('pending', 'releasing', 'released', 'rejected'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_mint_quarantine_status ON mint_quarantine(status, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_mint_quarantine_wallet ON mint_quarantine(wallet_address, created_at DESC);
+
+-- Minted energy per wallet over a window
+CREATE INDEX IF NOT EXISTS idx_meter_readings_wallet_minted
+    ON meter_readings(wallet_address, reading_timestamp)
+    WHERE minted = true;
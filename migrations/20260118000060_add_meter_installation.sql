@@ -0,0 +1,20 @@
+-- Meter installation metadata
+-- Migration: 20260118000060_add_meter_installation
+
+-- Orientation of the panels: azimuth clockwise from north, tilt from horizontal
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS panel_azimuth_deg NUMERIC(4, 1);
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS panel_tilt_deg NUMERIC(3, 1);
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS inverter_manufacturer VARCHAR(100);
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS inverter_model VARCHAR(100);
+-- AC rating of the inverter; output above it is clipped
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS inverter_capacity_kw NUMERIC(12, 3);
+
+ALTER TABLE meter_registry DROP CONSTRAINT IF EXISTS chk_meter_panel_azimuth;
+ALTER TABLE meter_registry ADD CONSTRAINT chk_meter_panel_azimuth
+    CHECK (panel_azimuth_deg IS NULL OR (panel_azimuth_deg >= 0 AND panel_azimuth_deg <= 360));
+ALTER TABLE meter_registry DROP CONSTRAINT IF EXISTS chk_meter_panel_tilt;
+ALTER TABLE meter_registry ADD CONSTRAINT chk_meter_panel_tilt
+    CHECK (panel_tilt_deg IS NULL OR (panel_tilt_deg >= 0 AND panel_tilt_deg <= 90));
+ALTER TABLE meter_registry DROP CONSTRAINT IF EXISTS chk_meter_inverter_capacity_positive;
+ALTER TABLE meter_registry ADD CONSTRAINT chk_meter_inverter_capacity_positive
+    CHECK (inverter_capacity_kw IS NULL OR inverter_capacity_kw > 0);
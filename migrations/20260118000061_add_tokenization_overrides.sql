@@ -0,0 +1,12 @@
+-- Runtime tokenization overrides
+-- Migration: 20260118000061_add_tokenization_overrides
+
+-- Tokenization parameters changed at runtime on top of the environment
+-- configuration; a single row, picked up by every instance on reload
+CREATE TABLE IF NOT EXISTS tokenization_overrides (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE,
+    overrides JSONB NOT NULL DEFAULT '{}'::jsonb,
+    updated_by UUID REFERENCES users(id),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_tokenization_overrides_single_row CHECK (id)
+);
@@ -0,0 +1,34 @@
+-- Public market statistics archive
+-- Migration: 20260118000062_add_market_daily_rollups
+
+-- One row per completed UTC day, written once by the nightly rollup job and
+-- served publicly instead of querying trades and readings directly
+CREATE TABLE IF NOT EXISTS market_daily_rollups (
+    rollup_date DATE PRIMARY KEY,
+    volume_kwh NUMERIC(20, 8) NOT NULL,
+    trade_count BIGINT NOT NULL,
+    -- Epochs cleared during the day; prices are NULL when none cleared
+    epoch_count INTEGER NOT NULL,
+    high_clearing_price NUMERIC(20, 8),
+    low_clearing_price NUMERIC(20, 8),
+    close_clearing_price NUMERIC(20, 8),
+    vwap_clearing_price NUMERIC(20, 8),
+    buyer_count INTEGER NOT NULL,
+    seller_count INTEGER NOT NULL,
+    -- Distinct users on either side of a trade
+    participant_count INTEGER NOT NULL,
+    minted_kwh NUMERIC(20, 8) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Published rollups are immutable
+CREATE OR REPLACE FUNCTION prevent_market_daily_rollup_change() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'market_daily_rollups rows are immutable';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_market_daily_rollups_immutable ON market_daily_rollups;
+CREATE TRIGGER trg_market_daily_rollups_immutable
+    BEFORE UPDATE OR DELETE ON market_daily_rollups
+    FOR EACH ROW EXECUTE FUNCTION prevent_market_daily_rollup_change();
@@ -0,0 +1,36 @@
+-- Asynchronous user data exports
+-- Migration: 20260118000063_add_data_exports
+
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'data_export_ready';
+
+-- Export requests of a user's readings, trades or audit trail. The export
+-- job builds the file into object storage; the row keeps the history and
+-- backs the per-user quota.
+CREATE TABLE IF NOT EXISTS data_exports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    dataset VARCHAR(20) NOT NULL,
+    format VARCHAR(10) NOT NULL,
+    -- Inclusive date range of the exported records
+    range_from DATE NOT NULL,
+    range_to DATE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'queued',
+    row_count BIGINT,
+    size_bytes BIGINT,
+    sha256 VARCHAR(64),
+    storage_key TEXT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    -- When the file is deleted; set once the export is ready
+    expires_at TIMESTAMPTZ,
+    CONSTRAINT chk_data_export_dataset CHECK (dataset IN ('readings', 'trades', 'audit')),
+    CONSTRAINT chk_data_export_format CHECK (format IN ('csv', 'zip')),
+    CONSTRAINT chk_data_export_status CHECK (status IN ('queued', 'running', 'ready', 'failed', 'expired')),
+    CONSTRAINT chk_data_export_range CHECK (range_from <= range_to)
+);
+
+CREATE INDEX IF NOT EXISTS idx_data_exports_user ON data_exports (user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_data_exports_queued ON data_exports (created_at) WHERE status = 'queued';
+CREATE INDEX IF NOT EXISTS idx_data_exports_expiry ON data_exports (expires_at) WHERE status = 'ready';
@@ -0,0 +1,28 @@
+-- Persistent settlement scheduler state
+-- Migration: 20260118000064_add_settlement_scheduler_state
+
+-- Settlements enqueued for the next batch, processed ahead of the backlog.
+-- An entry is removed once its settlement has been attempted or is no
+-- longer pending, so a restart keeps the queue and its order.
+CREATE TABLE IF NOT EXISTS settlement_queue (
+    settlement_id UUID PRIMARY KEY REFERENCES settlements(id) ON DELETE CASCADE,
+    seq BIGSERIAL NOT NULL,
+    enqueued_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_settlement_queue_seq ON settlement_queue(seq);
+
+-- Checkpoint of the settlement batch being executed: its settlements in
+-- execution order and how many have been attempted. A batch without
+-- finished_at was interrupted; the next run resumes it at batch_position.
+CREATE TABLE IF NOT EXISTS settlement_scheduler_state (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE,
+    batch_id UUID NOT NULL,
+    batch_settlement_ids UUID[] NOT NULL DEFAULT '{}',
+    batch_position INTEGER NOT NULL DEFAULT 0,
+    last_settlement_id UUID,
+    batch_started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    batch_finished_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_settlement_scheduler_state_single_row CHECK (id)
+);
@@ -0,0 +1,11 @@
+-- Dashboard metrics read model
+-- Migration: 20260118000065_add_dashboard_metrics_read_model
+
+-- Last assembled dashboard metrics; a single row rewritten after each event
+-- processor cycle and read when the Redis copy is missing
+CREATE TABLE IF NOT EXISTS dashboard_metrics_read_model (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE,
+    metrics JSONB NOT NULL,
+    refreshed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_dashboard_metrics_read_model_single_row CHECK (id)
+);
@@ -0,0 +1,12 @@
+-- Runtime WebSocket broadcast coalescing windows
+-- Migration: 20260118000066_add_broadcast_throttle
+
+-- Coalescing windows set by an admin, replacing the environment
+-- configuration; a single row, picked up by every instance on reload
+CREATE TABLE IF NOT EXISTS broadcast_throttle_settings (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE,
+    windows JSONB NOT NULL,
+    updated_by UUID REFERENCES users(id),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_broadcast_throttle_settings_single_row CHECK (id)
+);
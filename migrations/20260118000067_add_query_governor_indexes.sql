@@ -0,0 +1,8 @@
+-- Index backing the predicate the query governor requires of zone insights
+-- Migration: 20260118000067_add_query_governor_indexes
+
+-- Zone economic insights scan completed settlements by processing time
+CREATE INDEX IF NOT EXISTS idx_settlements_completed_processed_at
+    ON settlements (processed_at)
+    WHERE status = 'completed';
+
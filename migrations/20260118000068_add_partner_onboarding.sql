@@ -0,0 +1,41 @@
+-- B2B partner onboarding
+-- Migration: 20260118000068_add_partner_onboarding
+
+-- Partner organizations provisioned by an admin, with their rate-limit tier
+CREATE TABLE IF NOT EXISTS partner_organizations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(200) NOT NULL,
+    slug VARCHAR(40) NOT NULL UNIQUE,
+    rate_limit_tier VARCHAR(20) NOT NULL DEFAULT 'standard',
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_partner_organizations_tier
+        CHECK (rate_limit_tier IN ('standard', 'professional', 'enterprise'))
+);
+
+-- Keys issued to a partner; service and user keys keep a NULL partner
+ALTER TABLE api_keys
+    ADD COLUMN IF NOT EXISTS partner_id UUID REFERENCES partner_organizations(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_partner ON api_keys (partner_id) WHERE partner_id IS NOT NULL AND is_active;
+
+-- Endpoints receiving the partner's platform events, signed with the secret
+CREATE TABLE IF NOT EXISTS partner_webhook_endpoints (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    partner_id UUID NOT NULL REFERENCES partner_organizations(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret VARCHAR(64) NOT NULL,
+    event_types TEXT[] NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_partner_webhook_endpoints_partner ON partner_webhook_endpoints (partner_id) WHERE is_active;
+
+-- Sandbox users provisioned for a partner's integration team
+CREATE TABLE IF NOT EXISTS partner_sandbox_users (
+    partner_id UUID NOT NULL REFERENCES partner_organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (partner_id, user_id)
+);
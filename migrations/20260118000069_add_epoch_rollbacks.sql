@@ -0,0 +1,48 @@
+-- Epoch rollback under multi-signature approval
+-- Migration: 20260118000069_add_epoch_rollbacks
+
+-- Rolling back a cleared epoch cancels its settlements before they are
+-- submitted, restores the fill state of its orders from the order event
+-- journal and reopens them in the next epoch
+ALTER TABLE trade_approval_requests DROP CONSTRAINT IF EXISTS chk_trade_approval_action;
+ALTER TABLE trade_approval_requests
+    ADD CONSTRAINT chk_trade_approval_action
+    CHECK (action_type IN ('trade_bust', 'manual_trade', 'epoch_rollback'));
+
+-- At most one pending rollback per epoch
+CREATE UNIQUE INDEX IF NOT EXISTS uq_trade_approval_pending_rollback
+    ON trade_approval_requests ((payload->>'epoch_id'))
+    WHERE action_type = 'epoch_rollback' AND status = 'pending';
+
+ALTER TABLE settlements DROP CONSTRAINT IF EXISTS chk_settlement_status;
+ALTER TABLE settlements
+    ADD CONSTRAINT chk_settlement_status
+    CHECK (status IN ('pending', 'processing', 'completed', 'failed', 'busted', 'rolled_back'));
+
+ALTER TABLE order_matches DROP CONSTRAINT IF EXISTS chk_match_status;
+ALTER TABLE order_matches
+    ADD CONSTRAINT chk_match_status CHECK (status IN ('pending', 'settled', 'failed', 'busted', 'rolled_back'));
+
+-- An order put back on the book by a rollback
+ALTER TABLE order_events DROP CONSTRAINT IF EXISTS chk_order_event_type;
+ALTER TABLE order_events
+    ADD CONSTRAINT chk_order_event_type CHECK (
+        event_type IN ('accepted', 'partially_filled', 'filled', 'cancelled', 'expired', 'rejected', 'reopened')
+    );
+
+-- A rolled-back epoch keeps cleared_at so the scheduler never clears it again
+ALTER TABLE market_epochs ADD COLUMN IF NOT EXISTS rolled_back_at TIMESTAMPTZ;
+
+-- What each rollback did, step by step, for the audit trail
+CREATE TABLE IF NOT EXISTS epoch_rollbacks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    epoch_id UUID NOT NULL UNIQUE REFERENCES market_epochs(id) ON DELETE CASCADE,
+    approval_request_id UUID REFERENCES trade_approval_requests(id) ON DELETE SET NULL,
+    reopened_in_epoch_id UUID REFERENCES market_epochs(id) ON DELETE SET NULL,
+    cancelled_settlement_ids UUID[] NOT NULL DEFAULT '{}',
+    reopened_order_ids UUID[] NOT NULL DEFAULT '{}',
+    narrative JSONB NOT NULL DEFAULT '[]',
+    reason TEXT NOT NULL,
+    executed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,12 @@
+-- Mint status of meter readings
+-- Migration: 20260118000070_add_reading_mint_status
+
+-- Readings are stored, with their sequence claim, before their mint or burn
+-- is sent to the chain. 'pending' until the result is recorded, then
+-- 'completed' or 'failed'; NULL when nothing was sent to the chain.
+ALTER TABLE meter_readings ADD COLUMN IF NOT EXISTS mint_status VARCHAR(16);
+
+-- Readings whose chain result was never recorded, for reconciliation
+CREATE INDEX IF NOT EXISTS idx_meter_readings_mint_pending
+    ON meter_readings(created_at)
+    WHERE mint_status = 'pending';
@@ -0,0 +1,15 @@
+-- Quarantine readings whose minting guard check failed
+-- Migration: 20260118000071_add_mint_quarantine_unchecked
+
+-- The guard fails closed: a reading it could not check against the wallet's
+-- cap is held as 'unchecked' rather than minted
+ALTER TABLE mint_quarantine ALTER COLUMN breached_window TYPE VARCHAR(16);
+
+ALTER TABLE mint_quarantine DROP CONSTRAINT IF EXISTS chk_mint_quarantine_window;
+ALTER TABLE mint_quarantine ADD CONSTRAINT chk_mint_quarantine_window
+    CHECK (breached_window IN ('hour', 'day', 'unchecked'));
+
+-- Pending mints count against the wallet's cap
+CREATE INDEX IF NOT EXISTS idx_meter_readings_wallet_mint_pending
+    ON meter_readings(wallet_address, reading_timestamp)
+    WHERE mint_status = 'pending';
@@ -0,0 +1,10 @@
+-- On-chain escrow releases of market settlements
+-- Migration: 20260118000072_add_settlement_escrow_releases
+
+-- Set before a settlement's escrow is released on-chain (currency to the
+-- seller, energy to the buyer). A cleared epoch with any such settlement
+-- cannot be rolled back: reopening its orders would not return the tokens.
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS escrow_release_started_at TIMESTAMPTZ;
+
+-- Signatures of the releases that were confirmed
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS escrow_release_signatures TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,8 @@
+-- Grid loss transfers of settlements
+-- Migration: 20260118000073_add_settlement_loss_transfer
+
+-- The gross energy less the effective energy delivered to the buyer is
+-- swept from the seller to the grid loss sink after the settlement transfer.
+-- A failed sweep leaves the loss with the seller and records why.
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS loss_transfer_signature VARCHAR(128);
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS loss_transfer_error TEXT;
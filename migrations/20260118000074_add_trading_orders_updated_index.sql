@@ -0,0 +1,6 @@
+-- Orders changed since a market analytics pass
+-- Migration: 20260118000074_add_trading_orders_updated_index
+
+-- Each pass recomputes the hourly buckets of orders filled or cancelled
+-- since the previous one
+CREATE INDEX IF NOT EXISTS idx_trading_orders_updated ON trading_orders(updated_at);
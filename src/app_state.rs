@@ -33,6 +33,10 @@ pub struct AppState {
     pub websocket_service: services::WebSocketService,
     /// Redis cache service
     pub cache_service: services::CacheService,
+    /// Meter submission limits with admin overrides
+    pub rate_limiter: services::EnhancedRateLimiter,
+    /// Third-party app consent flow and scoped delegation tokens
+    pub delegation: services::DelegationService,
     /// Health check service
     pub health_checker: services::HealthChecker,
 
@@ -46,13 +50,102 @@ pub struct AppState {
     pub event_processor: services::EventProcessorService,
     pub price_monitor: services::PriceMonitor,
     pub recurring_scheduler: services::RecurringScheduler,
+    pub market_analytics: services::MarketAnalyticsAggregator,
+    pub leaderboard_service: services::LeaderboardService,
+    pub price_index_service: services::PriceIndexService,
+    pub market_session: services::MarketSessionService,
+    pub invoice_service: services::InvoiceService,
+    pub prepaid_service: services::PrepaidService,
+    pub dispute_service: services::DisputeService,
+    pub trade_admin_service: services::TradeAdminService,
+    pub vesting_service: services::VestingService,
+    pub data_fixes: services::DataFixService,
+    pub referral_service: services::ReferralService,
+    pub admin_overview: services::AdminOverviewService,
+    pub api_usage: services::ApiUsageService,
+    pub attachment_service: services::AttachmentService,
+    pub network_acl: services::NetworkAclService,
+    pub meter_gateway_service: services::MeterGatewayService,
+    pub meter_firmware: services::MeterFirmwareService,
+    pub meter_fleet_health: services::MeterFleetHealthService,
+    pub meter_registry_sync: services::MeterRegistrySyncService,
+    pub address_book: services::AddressBookService,
+    pub wallet_challenges: services::WalletChallengeService,
+    pub wallet_links: services::WalletLinkService,
+    pub epoch_clearing: services::EpochClearingService,
+    pub audit_retention: services::AuditRetentionService,
+    pub fx_rates: services::FxRateService,
+    pub emission_factors: services::EmissionFactorService,
+    pub markets: services::MarketService,
+    pub trading_halts: services::TradingHaltService,
+    pub book_snapshots: services::OrderBookSnapshotService,
+    pub swap_pool_stats: services::SwapPoolStatsService,
+    pub fix_sessions: services::FixSessionService,
+    pub maker_incentives: services::MakerIncentiveService,
+    pub sandbox: services::SandboxService,
+    pub delivery_verification: services::DeliveryVerificationService,
+    pub imbalance: services::ImbalanceService,
+    pub autopilot: services::AutopilotService,
+    pub grid_meter_data: services::GridMeterDataService,
+    /// Per-wallet minting caps from rated meter capacity, with quarantine
+    pub mint_guard: services::MintGuardService,
+    /// Capacity, orientation and inverter of registered meters
+    pub meter_installations: services::MeterInstallationService,
+    /// Gateway backfills of missed readings, minted after admin approval
+    pub ami_backfill: services::AmiBackfillService,
+    pub maintenance: services::MaintenanceService,
+    /// Background rebuilds of caches and aggregates
+    pub rebuilds: services::RebuildService,
+    pub ledger_history: services::LedgerHistoryService,
+    /// Encryption of personal data at rest
+    pub pii: services::PiiCipher,
+    pub pii_rotation: services::PiiRotationJob,
+    /// One-time code verification of risky logins
+    pub login_step_up: services::LoginStepUpService,
+    /// Device-bound wallet sessions with sliding expiry
+    pub wallet_sessions: services::WalletSessionService,
+    /// When the platform pays the fees of user transfers
+    pub fee_sponsorship: services::FeeSponsorshipService,
+    pub token_transfers: services::TokenTransferService,
+    /// Scheduled analytics reports delivered by email or webhook
+    pub report_subscriptions: services::ReportSubscriptionService,
+    /// Tracked runs of background jobs, with admin cancel and retry
+    pub jobs: services::JobQueue,
+    /// Tenant branding, chain addresses and enabled modules
+    pub deployment: services::DeploymentProfileService,
+    /// Rotating public IDs standing in for users in shared market data
+    pub participant_ids: services::ParticipantIds,
     pub webhook_service: services::WebhookService,
     pub erc_service: services::ErcService,
+    pub erc_expiry: services::erc::ErcExpiryMonitor,
+    pub notification_dispatcher: services::NotificationDispatcher,
+    pub budget_alerts: services::BudgetAlertService,
     
     /// Prometheus metrics handle
     pub metrics_handle: metrics_exporter_prometheus::PrometheusHandle,
     /// HTTP Client for external requests (Simulator, etc.)
     pub http_client: reqwest::Client,
+    /// How each dependency came up, served at `/readyz`
+    pub startup_report: std::sync::Arc<crate::startup::report::StartupReport>,
+    /// Cold-start warmup progress; `/readyz` waits for it
+    pub warmup: std::sync::Arc<crate::startup::warmup::Warmup>,
+    /// Runtime state of optional integrations (email, cache, metrics)
+    pub degradation: services::DegradationManager,
+    /// Tokenization parameters with runtime overrides applied
+    pub tokenization: services::TokenizationSettings,
+    /// Immutable daily market rollups served by the public archive
+    pub market_archive: services::MarketArchiveService,
+    /// Asynchronous user data exports and their signed downloads
+    pub data_exports: services::DataExportService,
+    /// WebSocket broadcast coalescing windows with runtime changes
+    pub broadcast_throttle: services::BroadcastThrottleSettings,
+    /// Statement timeouts, row limits and required predicates of heavy
+    /// analytics, audit and export queries
+    pub query_governor: crate::database::QueryGovernor,
+    /// B2B partner onboarding
+    pub partners: services::PartnerService,
+    /// Per-caller in-flight caps on expensive endpoints
+    pub concurrency_limiter: services::ConcurrencyLimiter,
 }
 
 
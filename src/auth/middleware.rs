@@ -2,24 +2,59 @@ use axum::extract::FromRequestParts;
 use axum::http::request::Parts;
 use axum::{
     body::Body,
-    extract::State,
+    extract::{OriginalUri, State},
     http::{Request, StatusCode, header::AUTHORIZATION},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
-use tracing::info;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 use crate::AppState;
+use crate::auth::request_signing::{self, SignatureError, SignedHeaders};
 use crate::auth::{Claims, Role};
 use crate::error::{ApiError, Result};
+use crate::services::api_usage::UsageIdentity;
+use crate::services::delegation::{self, delegated_scope, DelegatedAccess};
+
+/// Run the request as the authenticated caller and tag the response with
+/// the caller's identity for usage tracking
+async fn run_authenticated(
+    mut request: Request<Body>,
+    next: Next,
+    claims: Claims,
+    credential: &str,
+) -> Response {
+    let identity = UsageIdentity {
+        user_id: claims.sub,
+        credential: credential.to_string(),
+    };
+    request.extensions_mut().insert(claims);
+    let mut response = next.run(request).await;
+    response.extensions_mut().insert(identity);
+    response
+}
 
 /// JWT Authentication middleware
 pub async fn auth_middleware(
     State(state): State<AppState>,
-    mut request: Request<Body>,
+    request: Request<Body>,
     next: Next,
 ) -> Response {
+    // HMAC-signed server-to-server request
+    if request.headers().contains_key(request_signing::SIGNATURE_HEADER) {
+        return match authenticate_signed_request(&state, request).await {
+            Ok(request) => match request.extensions().get::<Claims>().cloned() {
+                Some(claims) => {
+                    let credential = format!("signed:{}", claims.username);
+                    run_authenticated(request, next, claims, &credential).await
+                }
+                None => next.run(request).await,
+            },
+            Err(response) => response,
+        };
+    }
+
     let auth_header = request
         .headers()
         .get(AUTHORIZATION)
@@ -76,8 +111,7 @@ pub async fn auth_middleware(
                         "simulator".to_string(),
                         "ami".to_string(), // Use AMI role
                     );
-                    request.extensions_mut().insert(claims);
-                    return next.run(request).await;
+                    return run_authenticated(request, next, claims, "engineering_key").await;
                 }
             }
 
@@ -135,9 +169,13 @@ pub async fn auth_middleware(
             "simulator".to_string(),
             "ami".to_string(), // Use AMI role
         );
-        request.extensions_mut().insert(claims);
-        return next.run(request).await;
+        return run_authenticated(request, next, claims, "engineering_key").await;
     }
+    // Delegated token of a third-party app
+    if token.starts_with(delegation::TOKEN_PREFIX) {
+        return authenticate_delegated(&state, request, next, token.to_string()).await;
+    }
+
     // Try JWT decoding if API key didn't match
 
 
@@ -145,8 +183,7 @@ pub async fn auth_middleware(
         Ok(claims) => {
             info!("🔓 JWT authenticated: {} (user_id: {})", claims.username, claims.sub);
             // Add claims to request extensions for use in handlers
-            request.extensions_mut().insert(claims);
-            next.run(request).await
+            run_authenticated(request, next, claims, "jwt").await
         }
         Err(_) => Response::builder()
             .status(StatusCode::UNAUTHORIZED)
@@ -155,6 +192,135 @@ pub async fn auth_middleware(
     }
 }
 
+/// Authenticate a third-party app's delegated token as the granting user,
+/// admitting it only on routes covered by the granted scopes
+async fn authenticate_delegated(state: &AppState, mut request: Request<Body>, next: Next, token: String) -> Response {
+    let caller = match state.delegation.authenticate(&token).await {
+        Ok(Some(caller)) => caller,
+        Ok(None) => {
+            return Response::builder()
+                .status(StatusCode::UNAUTHORIZED)
+                .body(Body::from("Invalid, expired or revoked delegated token"))
+                .unwrap_or_else(|_| Response::new(Body::from("Unauthorized")));
+        }
+        Err(e) => return e.into_response(),
+    };
+
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.0.path().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let access = DelegatedAccess {
+        grant_id: caller.grant_id,
+        app_id: caller.app_id,
+        client_id: caller.client_id,
+        scopes: caller.scopes,
+    };
+    let required = delegated_scope(request.method().as_str(), &path);
+    if !required.is_some_and(|scope| access.allows(scope)) {
+        warn!(
+            "🔑 Delegated token of {} refused on {} {} (needs {})",
+            access.client_id,
+            request.method(),
+            path,
+            required.unwrap_or("a non-delegable endpoint")
+        );
+        return ApiError::Forbidden(match required {
+            Some(scope) => format!("Delegated token lacks the {} scope", scope),
+            None => "Endpoint is not available to delegated tokens".to_string(),
+        })
+        .into_response();
+    }
+
+    let credential = format!("delegated:{}", access.client_id);
+    let claims = Claims::new(caller.user_id, caller.username, caller.role);
+    request.extensions_mut().insert(access);
+    run_authenticated(request, next, claims, &credential).await
+}
+
+/// Verify an HMAC-signed request, buffering the body for the digest check,
+/// and attach synthetic claims for the signing client
+async fn authenticate_signed_request(
+    state: &AppState,
+    request: Request<Body>,
+) -> std::result::Result<Request<Body>, Response> {
+    let signing = &state.config.request_signing;
+    let (mut parts, body) = request.into_parts();
+
+    let body = axum::body::to_bytes(body, signing.max_body_bytes)
+        .await
+        .map_err(|_| {
+            Response::builder()
+                .status(StatusCode::PAYLOAD_TOO_LARGE)
+                .body(Body::from("Signed request body too large"))
+                .unwrap_or_else(|_| Response::new(Body::from("Payload too large")))
+        })?;
+
+    let header = |name: &str| parts.headers.get(name).and_then(|h| h.to_str().ok());
+    let headers = SignedHeaders {
+        key_id: header(request_signing::KEY_ID_HEADER),
+        timestamp: header(request_signing::TIMESTAMP_HEADER),
+        content_sha256: header(request_signing::CONTENT_DIGEST_HEADER),
+        signature: header(request_signing::SIGNATURE_HEADER),
+        impersonate: header(request_signing::IMPERSONATE_HEADER),
+    };
+    let path_and_query = parts
+        .uri
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or("/");
+
+    let unauthorized = |e: SignatureError| {
+        warn!("🔏 Rejected signed request to {}: {}", path_and_query, e);
+        Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .body(Body::from(e.to_string()))
+            .unwrap_or_else(|_| Response::new(Body::from("Unauthorized")))
+    };
+
+    let client = request_signing::verify(
+        signing,
+        parts.method.as_str(),
+        path_and_query,
+        &headers,
+        &body,
+        chrono::Utc::now().timestamp(),
+    )
+    .map_err(unauthorized)?;
+
+    // Each signature is accepted once within the replay window
+    let replay_key = request_signing::replay_key(&client.key_id, headers.signature.unwrap_or_default());
+    match state
+        .cache_service
+        .set_if_absent(&replay_key, (signing.tolerance_secs.max(1) * 2) as u64)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => return Err(unauthorized(SignatureError::Replayed)),
+        Err(e) => {
+            tracing::error!("Replay check unavailable for signed request: {}", e);
+            return Err(Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .body(Body::from("Request signing temporarily unavailable"))
+                .unwrap_or_else(|_| Response::new(Body::from("Service unavailable"))));
+        }
+    }
+
+    let service_uuid = Uuid::parse_str(&state.config.simulator_user_id).unwrap_or_else(|_| Uuid::nil());
+    // Impersonation is limited to AMI clients, matching the engineering key
+    let user_id = match headers.impersonate {
+        Some(id) if client.role == "ami" => Uuid::parse_str(id).unwrap_or(service_uuid),
+        _ => service_uuid,
+    };
+
+    info!("🔏 Signed request authenticated: {} ({})", client.key_id, client.role);
+    let claims = Claims::new(user_id, client.key_id.clone(), client.role.clone());
+    parts.extensions.insert(claims);
+
+    Ok(Request::from_parts(parts, Body::from(body)))
+}
+
 /// Role-based authorization middleware for admin access
 pub async fn require_admin_role(
     user: AuthenticatedUser,
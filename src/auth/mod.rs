@@ -6,6 +6,7 @@ use utoipa::ToSchema;
 pub mod jwt;
 pub mod middleware;
 pub mod password;
+pub mod request_signing;
 pub mod roles;
 
 // Re-export Permission from the new roles module
@@ -0,0 +1,254 @@
+//! HMAC request signing for server-to-server callers
+//!
+//! An alternative to JWTs for non-interactive clients (simulator, settlement
+//! workers). The client sends:
+//!
+//! - `X-Signature-Key-Id`: configured client key id
+//! - `X-Signature-Timestamp`: unix seconds
+//! - `X-Content-SHA256`: hex SHA-256 of the raw body
+//! - `X-Signature`: hex HMAC-SHA256 of the canonical request
+//!
+//! The canonical request is the newline-joined method, path and query,
+//! timestamp, body digest and `X-Impersonate-User` value (empty if absent).
+//! Requests outside the timestamp window or whose signature was already seen
+//! within it are rejected.
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::config::{RequestSigningConfig, SigningClient};
+
+pub const KEY_ID_HEADER: &str = "x-signature-key-id";
+pub const TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+pub const CONTENT_DIGEST_HEADER: &str = "x-content-sha256";
+pub const SIGNATURE_HEADER: &str = "x-signature";
+pub const IMPERSONATE_HEADER: &str = "x-impersonate-user";
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum SignatureError {
+    #[error("Missing signature header: {0}")]
+    MissingHeader(&'static str),
+    #[error("Unknown signing key")]
+    UnknownKey,
+    #[error("Signature timestamp outside the allowed window")]
+    Expired,
+    #[error("Body digest does not match X-Content-SHA256")]
+    DigestMismatch,
+    #[error("Signature mismatch")]
+    InvalidSignature,
+    #[error("Request signature already used")]
+    Replayed,
+}
+
+/// Signature headers as sent by the client
+#[derive(Debug, Clone, Copy)]
+pub struct SignedHeaders<'a> {
+    pub key_id: Option<&'a str>,
+    pub timestamp: Option<&'a str>,
+    pub content_sha256: Option<&'a str>,
+    pub signature: Option<&'a str>,
+    pub impersonate: Option<&'a str>,
+}
+
+pub fn body_digest(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
+pub fn canonical_request(
+    method: &str,
+    path_and_query: &str,
+    timestamp: &str,
+    content_sha256: &str,
+    impersonate: Option<&str>,
+) -> String {
+    format!(
+        "{}\n{}\n{}\n{}\n{}",
+        method.to_uppercase(),
+        path_and_query,
+        timestamp,
+        content_sha256,
+        impersonate.unwrap_or("")
+    )
+}
+
+/// Hex HMAC-SHA256 of a canonical request (used by clients and tests)
+pub fn sign(secret: &str, canonical: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(canonical.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Verify a signed request and return the authenticated client.
+/// Replay detection is left to the caller (see [`replay_key`]).
+pub fn verify<'c>(
+    config: &'c RequestSigningConfig,
+    method: &str,
+    path_and_query: &str,
+    headers: &SignedHeaders<'_>,
+    body: &[u8],
+    now: i64,
+) -> Result<&'c SigningClient, SignatureError> {
+    let key_id = headers.key_id.ok_or(SignatureError::MissingHeader(KEY_ID_HEADER))?;
+    let timestamp = headers.timestamp.ok_or(SignatureError::MissingHeader(TIMESTAMP_HEADER))?;
+    let content_sha256 = headers
+        .content_sha256
+        .ok_or(SignatureError::MissingHeader(CONTENT_DIGEST_HEADER))?;
+    let signature = headers.signature.ok_or(SignatureError::MissingHeader(SIGNATURE_HEADER))?;
+
+    let client = config
+        .clients
+        .iter()
+        .find(|c| c.key_id == key_id)
+        .ok_or(SignatureError::UnknownKey)?;
+
+    let signed_at: i64 = timestamp.parse().map_err(|_| SignatureError::Expired)?;
+    if (now - signed_at).abs() > config.tolerance_secs {
+        return Err(SignatureError::Expired);
+    }
+
+    if !body_digest(body).eq_ignore_ascii_case(content_sha256) {
+        return Err(SignatureError::DigestMismatch);
+    }
+
+    let expected = hex::decode(signature).map_err(|_| SignatureError::InvalidSignature)?;
+    let canonical = canonical_request(
+        method,
+        path_and_query,
+        timestamp,
+        &content_sha256.to_lowercase(),
+        headers.impersonate,
+    );
+    let mut mac = HmacSha256::new_from_slice(client.secret.as_bytes())
+        .map_err(|_| SignatureError::InvalidSignature)?;
+    mac.update(canonical.as_bytes());
+    mac.verify_slice(&expected)
+        .map_err(|_| SignatureError::InvalidSignature)?;
+
+    Ok(client)
+}
+
+/// Cache key marking a signature as used for the rest of the replay window
+pub fn replay_key(key_id: &str, signature: &str) -> String {
+    format!("request_signing:seen:{}:{}", key_id, signature.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+    fn config() -> RequestSigningConfig {
+        RequestSigningConfig {
+            clients: vec![SigningClient {
+                key_id: "simulator".to_string(),
+                role: "ami".to_string(),
+                secret: SECRET.to_string(),
+            }],
+            tolerance_secs: 300,
+            max_body_bytes: 1024,
+        }
+    }
+
+    fn signed(body: &[u8], timestamp: &str) -> (String, String) {
+        let digest = body_digest(body);
+        let canonical = canonical_request("POST", "/api/meters/submit-reading", timestamp, &digest, None);
+        (digest, sign(SECRET, &canonical))
+    }
+
+    fn headers<'a>(digest: &'a str, signature: &'a str, timestamp: &'a str) -> SignedHeaders<'a> {
+        SignedHeaders {
+            key_id: Some("simulator"),
+            timestamp: Some(timestamp),
+            content_sha256: Some(digest),
+            signature: Some(signature),
+            impersonate: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_signature() {
+        let config = config();
+        let body = br#"{"kwh":1.5}"#;
+        let (digest, signature) = signed(body, "1700000000");
+
+        let client = verify(
+            &config,
+            "POST",
+            "/api/meters/submit-reading",
+            &headers(&digest, &signature, "1700000000"),
+            body,
+            1_700_000_100,
+        )
+        .unwrap();
+        assert_eq!(client.role, "ami");
+    }
+
+    #[test]
+    fn test_expired_timestamp() {
+        let config = config();
+        let body = b"{}";
+        let (digest, signature) = signed(body, "1700000000");
+
+        let result = verify(
+            &config,
+            "POST",
+            "/api/meters/submit-reading",
+            &headers(&digest, &signature, "1700000000"),
+            body,
+            1_700_000_301,
+        );
+        assert_eq!(result.unwrap_err(), SignatureError::Expired);
+    }
+
+    #[test]
+    fn test_tampered_body_and_path() {
+        let config = config();
+        let (digest, signature) = signed(b"{}", "1700000000");
+
+        let tampered_body = verify(
+            &config,
+            "POST",
+            "/api/meters/submit-reading",
+            &headers(&digest, &signature, "1700000000"),
+            br#"{"kwh":99}"#,
+            1_700_000_000,
+        );
+        assert_eq!(tampered_body.unwrap_err(), SignatureError::DigestMismatch);
+
+        let other_path = verify(
+            &config,
+            "POST",
+            "/api/v1/admin/disputes",
+            &headers(&digest, &signature, "1700000000"),
+            b"{}",
+            1_700_000_000,
+        );
+        assert_eq!(other_path.unwrap_err(), SignatureError::InvalidSignature);
+    }
+
+    #[test]
+    fn test_impersonation_is_signed() {
+        let config = config();
+        let (digest, signature) = signed(b"{}", "1700000000");
+        let mut h = headers(&digest, &signature, "1700000000");
+        h.impersonate = Some("63c1d015-6765-4843-9ca3-5ba21ee54d7e");
+
+        let result = verify(&config, "POST", "/api/meters/submit-reading", &h, b"{}", 1_700_000_000);
+        assert_eq!(result.unwrap_err(), SignatureError::InvalidSignature);
+    }
+
+    #[test]
+    fn test_unknown_key() {
+        let config = config();
+        let (digest, signature) = signed(b"{}", "1700000000");
+        let mut h = headers(&digest, &signature, "1700000000");
+        h.key_id = Some("unknown");
+
+        let result = verify(&config, "POST", "/api/meters/submit-reading", &h, b"{}", 1_700_000_000);
+        assert_eq!(result.unwrap_err(), SignatureError::UnknownKey);
+    }
+}
@@ -0,0 +1,336 @@
+//! gridtokenx-admin - operational tasks from the command line
+//!
+//! Builds the same services as the API gateway and runs one task against
+//! them, for when the HTTP admin surface is unreachable.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand};
+use sqlx::Row;
+use uuid::Uuid;
+
+use api_gateway::{config::Config, startup, AppState};
+
+#[derive(Parser)]
+#[command(name = "gridtokenx-admin", about = "GridTokenX operational tasks")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Trigger epoch clearing
+    #[command(subcommand)]
+    Clearing(ClearingCommand),
+    /// Failed mints and referral rewards
+    #[command(subcommand)]
+    Dlq(DlqCommand),
+    /// Service API keys
+    #[command(subcommand)]
+    ApiKeys(ApiKeyCommand),
+    /// Settlement progress of cleared epochs
+    #[command(subcommand)]
+    Batches(BatchCommand),
+}
+
+#[derive(Subcommand)]
+enum ClearingCommand {
+    /// Clear every closed epoch that is due, as the scheduler would
+    Run,
+    /// Clear one epoch now, even if it has not closed
+    Epoch { epoch_id: Uuid },
+}
+
+#[derive(Subcommand)]
+enum DlqCommand {
+    List,
+    /// Put dead-lettered items back in their queues
+    Requeue(RequeueArgs),
+}
+
+#[derive(Args)]
+struct RequeueArgs {
+    /// Admin user recorded in the audit log
+    #[arg(long)]
+    admin: Uuid,
+    /// Items to requeue; every dead-lettered item when omitted
+    ids: Vec<Uuid>,
+}
+
+#[derive(Subcommand)]
+enum ApiKeyCommand {
+    List,
+    /// Issue a new key under a name and deactivate the old ones
+    Rotate {
+        name: String,
+        /// Comma-separated permissions for the new key
+        #[arg(long, value_delimiter = ',')]
+        permissions: Vec<String>,
+        /// User the key is issued to; omit for service keys
+        #[arg(long)]
+        owner: Option<Uuid>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BatchCommand {
+    /// Cleared epochs and their settlement counts
+    Status {
+        /// Include epochs whose settlements are all done
+        #[arg(long)]
+        all: bool,
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "warn".into()),
+        )
+        .init();
+
+    let cli = Cli::parse();
+    let config = Config::from_env()?;
+    let state = startup::initialize_app(&config).await?;
+
+    match cli.command {
+        Command::Clearing(command) => clearing(&state, command).await,
+        Command::Dlq(command) => dlq(&state, command).await,
+        Command::ApiKeys(command) => api_keys(&state, command).await,
+        Command::Batches(command) => batches(&state, command).await,
+    }
+}
+
+async fn clearing(state: &AppState, command: ClearingCommand) -> Result<()> {
+    match command {
+        ClearingCommand::Run => {
+            let cleared = state.epoch_clearing.run_cycle().await?;
+            println!("Cleared {} epoch(s)", cleared);
+        }
+        ClearingCommand::Epoch { epoch_id } => {
+            if state.epoch_clearing.clear_now(epoch_id).await? {
+                println!("Cleared epoch {}", epoch_id);
+            } else {
+                println!("Epoch {} is already cleared or being cleared", epoch_id);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Dead-lettered items: mints out of retries and failed referral rewards
+const DLQ_QUERY: &str = r#"
+    SELECT 'mint' AS kind, id, error_message AS error, attempts, created_at
+    FROM minting_retry_queue WHERE attempts >= $1
+    UNION ALL
+    SELECT 'referral_reward', id, last_error, attempts, created_at
+    FROM referral_rewards WHERE status = 'failed'
+    ORDER BY created_at
+"#;
+
+async fn dlq(state: &AppState, command: DlqCommand) -> Result<()> {
+    let max_attempts = state.config.tokenization.max_retry_attempts as i32;
+    let items = sqlx::query(DLQ_QUERY).bind(max_attempts).fetch_all(&state.db).await?;
+
+    match command {
+        DlqCommand::List => {
+            println!("{:<16} {:<36} {:>8}  {:<25} ERROR", "KIND", "ID", "ATTEMPTS", "CREATED");
+            for item in &items {
+                let created: Option<DateTime<Utc>> = item.get("created_at");
+                println!(
+                    "{:<16} {:<36} {:>8}  {:<25} {}",
+                    item.get::<String, _>("kind"),
+                    item.get::<Uuid, _>("id"),
+                    item.get::<i32, _>("attempts"),
+                    created.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                    item.get::<Option<String>, _>("error").unwrap_or_default()
+                );
+            }
+            println!("{} item(s)", items.len());
+        }
+        DlqCommand::Requeue(args) => {
+            let mut requeued = 0;
+            for item in &items {
+                let id: Uuid = item.get("id");
+                if !is_selected(&args.ids, id) {
+                    continue;
+                }
+                match item.get::<String, _>("kind").as_str() {
+                    "mint" => {
+                        sqlx::query(
+                            "UPDATE minting_retry_queue SET attempts = 0, next_retry_at = NOW(), updated_at = NOW() WHERE id = $1",
+                        )
+                        .bind(id)
+                        .execute(&state.db)
+                        .await?;
+                    }
+                    _ => {
+                        state.referral_service.retry_reward(args.admin, id).await?;
+                    }
+                }
+                println!("Requeued {}", id);
+                requeued += 1;
+            }
+            if requeued == 0 && !args.ids.is_empty() {
+                return Err(anyhow!("None of the given IDs are dead-lettered"));
+            }
+            println!("{} item(s) requeued", requeued);
+        }
+    }
+    Ok(())
+}
+
+/// Whether a dead-lettered item is among the requested ones; every item is
+/// when none are given
+fn is_selected(requested: &[Uuid], id: Uuid) -> bool {
+    requested.is_empty() || requested.contains(&id)
+}
+
+async fn api_keys(state: &AppState, command: ApiKeyCommand) -> Result<()> {
+    match command {
+        ApiKeyCommand::List => {
+            let keys = sqlx::query(
+                "SELECT id, name, permissions, created_at, last_used_at FROM api_keys WHERE is_active ORDER BY name, created_at",
+            )
+            .fetch_all(&state.db)
+            .await?;
+            println!("{:<36} {:<24} {:<25} {:<25} PERMISSIONS", "ID", "NAME", "CREATED", "LAST USED");
+            for key in &keys {
+                let created: DateTime<Utc> = key.get("created_at");
+                let last_used: Option<DateTime<Utc>> = key.get("last_used_at");
+                println!(
+                    "{:<36} {:<24} {:<25} {:<25} {}",
+                    key.get::<Uuid, _>("id"),
+                    key.get::<String, _>("name"),
+                    created.to_rfc3339(),
+                    last_used.map(|t| t.to_rfc3339()).unwrap_or_else(|| "never".to_string()),
+                    key.get::<serde_json::Value, _>("permissions")
+                );
+            }
+        }
+        ApiKeyCommand::Rotate { name, permissions, owner } => {
+            let (key, key_hash) = state.api_key_service.generate_key(&name, permissions.clone())?;
+
+            let mut tx = state.db.begin().await?;
+            let revoked = sqlx::query(
+                "UPDATE api_keys SET is_active = false, revoked_at = NOW() WHERE name = $1 AND is_active",
+            )
+            .bind(&name)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+            let id: Uuid = sqlx::query_scalar(
+                "INSERT INTO api_keys (name, key_hash, permissions, owner_id) VALUES ($1, $2, $3, $4) RETURNING id",
+            )
+            .bind(&name)
+            .bind(&key_hash)
+            .bind(serde_json::json!(permissions))
+            .bind(owner)
+            .fetch_one(&mut *tx)
+            .await?;
+            tx.commit().await?;
+
+            println!("Issued key {} for '{}' ({} old key(s) deactivated)", id, name, revoked);
+            println!("{}", key);
+            println!("Store it now; it is not shown again.");
+        }
+    }
+    Ok(())
+}
+
+async fn batches(state: &AppState, command: BatchCommand) -> Result<()> {
+    let BatchCommand::Status { all, limit } = command;
+    let rows = sqlx::query(
+        r#"
+        SELECT e.id, e.epoch_number, e.status::text AS status, e.cleared_at,
+               COUNT(s.id) FILTER (WHERE s.status = 'pending') AS pending,
+               COUNT(s.id) FILTER (WHERE s.status = 'processing') AS processing,
+               COUNT(s.id) FILTER (WHERE s.status = 'completed') AS completed,
+               COUNT(s.id) FILTER (WHERE s.status = 'failed') AS failed
+        FROM market_epochs e
+        LEFT JOIN settlements s ON s.epoch_id = e.id
+        WHERE e.cleared_at IS NOT NULL AND ($1 OR e.status::text = 'cleared')
+        GROUP BY e.id
+        ORDER BY e.epoch_number DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(all)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await?;
+
+    println!(
+        "{:>8} {:<36} {:<8} {:<25} {:>7} {:>10} {:>9} {:>6}",
+        "EPOCH", "ID", "STATUS", "CLEARED", "PENDING", "PROCESSING", "COMPLETED", "FAILED"
+    );
+    for row in &rows {
+        let cleared: Option<DateTime<Utc>> = row.get("cleared_at");
+        println!(
+            "{:>8} {:<36} {:<8} {:<25} {:>7} {:>10} {:>9} {:>6}",
+            row.get::<i64, _>("epoch_number"),
+            row.get::<Uuid, _>("id"),
+            row.get::<String, _>("status"),
+            cleared.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            row.get::<i64, _>("pending"),
+            row.get::<i64, _>("processing"),
+            row.get::<i64, _>("completed"),
+            row.get::<i64, _>("failed")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parses_commands() {
+        let owner = Uuid::new_v4();
+        let cli = Cli::try_parse_from([
+            "gridtokenx-admin",
+            "api-keys",
+            "rotate",
+            "simulator",
+            "--permissions",
+            "meters:write,readings:write",
+            "--owner",
+            &owner.to_string(),
+        ])
+        .unwrap();
+        match cli.command {
+            Command::ApiKeys(ApiKeyCommand::Rotate { name, permissions, owner: parsed }) => {
+                assert_eq!(name, "simulator");
+                assert_eq!(permissions, vec!["meters:write", "readings:write"]);
+                assert_eq!(parsed, Some(owner));
+            }
+            _ => panic!("expected api-keys rotate"),
+        }
+
+        let cli = Cli::try_parse_from(["gridtokenx-admin", "batches", "status"]).unwrap();
+        assert!(matches!(cli.command, Command::Batches(BatchCommand::Status { all: false, limit: 20 })));
+
+        // Requeueing is audited, so the admin is required
+        assert!(Cli::try_parse_from(["gridtokenx-admin", "dlq", "requeue"]).is_err());
+    }
+
+    #[test]
+    fn test_requeue_selection() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert!(is_selected(&[], a));
+        assert!(is_selected(&[a], a));
+        assert!(!is_selected(&[a], b));
+    }
+}
@@ -1,9 +1,14 @@
 use anyhow::Result;
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use solana_sdk::commitment_config::CommitmentLevel;
+use std::collections::HashMap;
 use std::env;
+use utoipa::ToSchema;
 
 pub mod tokenization;
-pub use tokenization::{TokenizationConfig, ValidationError};
+pub use tokenization::{TimestampAssessment, TokenizationConfig, TokenizationOverrides, ValidationError};
 // Removed unused imports: ConfigError
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +28,9 @@ pub struct Config {
     pub redis_pool_size: u32,
     pub request_timeout: u64,
     pub rate_limit_window: u64,
+    /// Meter submissions allowed per principal per rate limit window; 0 disables the limit
+    pub meter_submission_limit: u32,
+    pub delegation: DelegationConfig,
     pub log_level: String,
     pub audit_log_enabled: bool,
     pub test_mode: bool,
@@ -30,6 +38,56 @@ pub struct Config {
     pub tokenization: TokenizationConfig,
     pub event_processor: EventProcessorConfig,
     pub solana_programs: SolanaProgramsConfig,
+    pub grid_tariff: GridTariffConfig,
+    pub invoicing: InvoicingConfig,
+    pub payments: PaymentsConfig,
+    pub network_acl: NetworkAclConfig,
+    pub ami_gateway: AmiGatewayConfig,
+    pub request_signing: RequestSigningConfig,
+    pub order_balance: OrderBalanceConfig,
+    pub market_session: MarketSessionConfig,
+    pub trade_approval: TradeApprovalConfig,
+    pub order_router: OrderRouterConfig,
+    pub fix_gateway: FixGatewayConfig,
+    pub maker_incentives: MakerIncentiveConfig,
+    pub matching_shadow: MatchingShadowConfig,
+    pub delivery_verification: DeliveryVerificationConfig,
+    pub imbalance: ImbalanceConfig,
+    pub autopilot: AutopilotConfig,
+    pub grid_reconciliation: GridReconciliationConfig,
+    pub sandbox: SandboxConfig,
+    pub referral: ReferralConfig,
+    pub attachments: AttachmentsConfig,
+    /// TTL of cached wallet balances, in seconds
+    pub balance_cache_ttl_secs: u64,
+    pub rpc_cache: RpcCacheConfig,
+    pub confirmation: ConfirmationConfig,
+    pub submission_queue: SubmissionQueueConfig,
+    /// Days before expiry at which certificate owners are notified
+    pub erc_expiry_warning_days: i64,
+    pub startup: StartupConfig,
+    pub docs: DocsConfig,
+    pub audit_retention: AuditRetentionConfig,
+    pub audit_buffer: AuditBufferConfig,
+    pub geoip: GeoIpConfig,
+    pub public_api: PublicApiConfig,
+    pub emissions: EmissionsConfig,
+    pub currency: CurrencyConfig,
+    pub pii: PiiConfig,
+    pub step_up: StepUpConfig,
+    pub deployment: DeploymentConfig,
+    pub meter_fleet: MeterFleetConfig,
+    pub wallet_session: WalletSessionConfig,
+    pub fee_sponsorship: FeeSponsorshipConfig,
+    pub report_subscriptions: ReportSubscriptionConfig,
+    pub jobs: JobsConfig,
+    pub mint_guard: MintGuardConfig,
+    pub degradation: DegradationConfig,
+    pub data_exports: DataExportConfig,
+    pub broadcast_throttle: BroadcastThrottleConfig,
+    pub query_governor: QueryGovernorConfig,
+    pub warmup: WarmupConfig,
+    pub concurrency_limits: ConcurrencyLimitConfig,
     /// Default simulator user UUID for engineering/test mode
     pub simulator_user_id: String,
     pub encryption_secret: String,
@@ -58,6 +116,1099 @@ impl Default for SolanaProgramsConfig {
     }
 }
 
+/// Utility grid tariffs used as the baseline for P2P savings calculations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridTariffConfig {
+    /// Price paid when buying energy from the grid (per kWh)
+    pub retail_per_kwh: f64,
+    /// Price received when exporting energy to the grid (per kWh)
+    pub feed_in_per_kwh: f64,
+}
+
+impl Default for GridTariffConfig {
+    fn default() -> Self {
+        Self {
+            retail_per_kwh: 4.18,
+            feed_in_per_kwh: 2.20,
+        }
+    }
+}
+
+/// Signed download links served by this API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedUrlConfig {
+    /// Secret used to sign download URLs
+    pub url_signing_secret: String,
+    /// Lifetime of a signed download URL (seconds)
+    pub url_ttl_secs: i64,
+    /// Public base URL used to build absolute download links
+    pub public_base_url: String,
+}
+
+/// Invoice statement storage and download link settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoicingConfig {
+    /// Root directory of the local object store for rendered statements
+    pub storage_dir: String,
+    pub signed_urls: SignedUrlConfig,
+}
+
+/// Payment provider settings for prepaid fiat top-ups
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentsConfig {
+    /// Provider name: "stripe" or "omise"
+    pub provider: String,
+    /// Provider secret API key (top-ups are disabled when unset)
+    pub api_key: Option<String>,
+    /// Secret used to verify webhook signatures
+    pub webhook_secret: Option<String>,
+    /// ISO currency code, lowercase (e.g. "thb")
+    pub currency: String,
+    /// Smallest accepted top-up amount
+    pub min_topup: f64,
+    /// Largest accepted top-up amount
+    pub max_topup: f64,
+    /// Maximum age of a signed webhook (seconds)
+    pub webhook_tolerance_secs: i64,
+}
+
+/// Bootstrap CIDR lists for network access control; runtime rules are managed via the admin API
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkAclConfig {
+    /// Networks always allowed to reach admin routes (e.g. office/VPN ranges)
+    pub admin_allow: Vec<String>,
+    /// Networks always allowed to reach AMI ingestion routes (utility head-end ranges)
+    pub ami_allow: Vec<String>,
+    /// Reverse proxies whose X-Forwarded-For header is trusted
+    pub trusted_proxies: Vec<String>,
+}
+
+/// Client-certificate authentication of AMI gateways. TLS is terminated by a
+/// trusted reverse proxy which forwards the verification result and fingerprint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmiGatewayConfig {
+    /// Reject batch ingestion without a verified gateway certificate
+    pub require_client_cert: bool,
+    /// Header carrying the SHA-256 fingerprint of the client certificate
+    pub fingerprint_header: String,
+    /// Header carrying the proxy's verification result ("SUCCESS" when verified)
+    pub verify_header: String,
+    /// Oldest reading a backfill window may reach back to, in days
+    pub backfill_max_age_days: i64,
+}
+
+impl Default for AmiGatewayConfig {
+    fn default() -> Self {
+        Self {
+            require_client_cert: false,
+            fingerprint_header: "x-client-cert-fingerprint".to_string(),
+            verify_header: "x-client-cert-verify".to_string(),
+            backfill_max_age_days: 90,
+        }
+    }
+}
+
+/// Server-to-server client allowed to authenticate with HMAC-signed requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningClient {
+    pub key_id: String,
+    /// Role granted to the client ("ami", "admin", ...)
+    pub role: String,
+    pub secret: String,
+}
+
+/// HMAC request signing for non-interactive callers (simulator, settlement workers)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestSigningConfig {
+    pub clients: Vec<SigningClient>,
+    /// Maximum clock skew between the signed timestamp and the server (seconds)
+    pub tolerance_secs: i64,
+    /// Largest body that will be buffered for digest verification
+    pub max_body_bytes: usize,
+}
+
+/// Balance checks applied when an order is placed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrderBalanceConfig {
+    /// Sell orders of at least this size (kWh) are also confirmed against the
+    /// on-chain token balance when on-chain escrow checks are enabled
+    pub onchain_confirmation_kwh: Decimal,
+    /// Percentage by which a role may sell beyond its uncommitted energy
+    /// (e.g. prosumers forward-selling expected generation). Roles not listed get 0.
+    pub oversubscription_pct: HashMap<String, Decimal>,
+}
+
+impl OrderBalanceConfig {
+    pub fn oversubscription_for(&self, role: &str) -> Decimal {
+        self.oversubscription_pct
+            .get(&role.to_lowercase())
+            .copied()
+            .unwrap_or(Decimal::ZERO)
+    }
+}
+
+/// Multi-signature approval of admin trade busts and manual trades
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeApprovalConfig {
+    /// Distinct admins that must approve, the proposer included
+    pub required_approvals: u32,
+    /// Pending requests expire after this many hours
+    pub ttl_hours: i64,
+}
+
+impl Default for TradeApprovalConfig {
+    fn default() -> Self {
+        Self {
+            required_approvals: 2,
+            ttl_hours: 48,
+        }
+    }
+}
+
+/// Smart order routing of market orders between the orderbook and AMM pools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderRouterConfig {
+    /// Route orderbook shortfalls to the market's AMM pool
+    pub amm_enabled: bool,
+    /// Default and maximum deviation of the AMM effective price from the
+    /// reference price, in basis points
+    pub max_slippage_bps: u32,
+}
+
+impl Default for OrderRouterConfig {
+    fn default() -> Self {
+        Self {
+            amm_enabled: false,
+            max_slippage_bps: 200,
+        }
+    }
+}
+
+/// FIX 4.4 acceptor for institutional order flow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixGatewayConfig {
+    pub enabled: bool,
+    /// Listen address of the acceptor, separate from the HTTP port
+    pub bind_addr: String,
+    /// Our CompID: SenderCompID of outgoing messages
+    pub comp_id: String,
+    /// Connections that have not logged on by then are dropped
+    pub logon_timeout_secs: u64,
+}
+
+impl Default for FixGatewayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_addr: "0.0.0.0:9878".to_string(),
+            comp_id: "GRIDTOKENX".to_string(),
+            logon_timeout_secs: 10,
+        }
+    }
+}
+
+/// Liquidity incentives for market makers quoting at the top of the book
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MakerIncentiveConfig {
+    pub enabled: bool,
+    /// How often each market's best quotes are sampled, in seconds
+    pub sample_interval_secs: u64,
+    /// Energy tokens (kWh) shared among a market's eligible makers per day
+    pub daily_pool_kwh: Decimal,
+    /// Own spread (bps of mid) at or above which quoting earns no tightness bonus
+    pub max_spread_bps: Decimal,
+    /// Share of the day's samples a maker must spend at the best bid or ask
+    /// to earn a rebate, in percent
+    pub min_time_at_best_pct: Decimal,
+}
+
+impl Default for MakerIncentiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_interval_secs: 60,
+            daily_pool_kwh: Decimal::from(100),
+            max_spread_bps: Decimal::from(200),
+            min_time_at_best_pct: Decimal::from(10),
+        }
+    }
+}
+
+/// Pricing policy of a matcher run in shadow mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShadowAlgorithm {
+    /// Every match of a market at one price, the midpoint of the marginal pair
+    UniformPrice,
+    /// Each match at the seller's price (pay-as-offer)
+    SellerPrice,
+}
+
+impl ShadowAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UniformPrice => "uniform_price",
+            Self::SellerPrice => "seller_price",
+        }
+    }
+}
+
+impl std::str::FromStr for ShadowAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "uniform_price" | "uniform" => Ok(Self::UniformPrice),
+            "seller_price" | "pay_as_offer" => Ok(Self::SellerPrice),
+            other => Err(anyhow::anyhow!("expected uniform_price or seller_price, got {}", other)),
+        }
+    }
+}
+
+/// Alternative matcher run alongside epoch clearing. Its results are only
+/// compared with the live matcher's, never persisted as fills.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchingShadowConfig {
+    pub enabled: bool,
+    pub algorithm: ShadowAlgorithm,
+}
+
+impl Default for MatchingShadowConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            algorithm: ShadowAlgorithm::UniformPrice,
+        }
+    }
+}
+
+/// What happens to a settlement whose energy was not fully delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryRemedy {
+    /// Record and report the shortfall only
+    Flag,
+    /// Refund the buyer the undelivered share of the settlement value
+    Refund,
+}
+
+impl DeliveryRemedy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Flag => "flag",
+            Self::Refund => "refund",
+        }
+    }
+}
+
+impl std::str::FromStr for DeliveryRemedy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "flag" => Ok(Self::Flag),
+            "refund" => Ok(Self::Refund),
+            other => Err(anyhow::anyhow!("expected flag or refund, got {}", other)),
+        }
+    }
+}
+
+/// Check completed settlements against meter readings of their epoch once
+/// the readings have had `grace_minutes` to arrive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryVerificationConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    pub grace_minutes: i64,
+    /// Shortfall below this fraction of the matched volume is accepted
+    pub tolerance: Decimal,
+    pub remedy: DeliveryRemedy,
+}
+
+impl Default for DeliveryVerificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 300,
+            grace_minutes: 60,
+            tolerance: Decimal::new(5, 2),
+            remedy: DeliveryRemedy::Flag,
+        }
+    }
+}
+
+/// Reference price imbalances are charged at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImbalancePriceSource {
+    /// `base_price` per kWh
+    Fixed,
+    /// The epoch's clearing price, `base_price` when it did not clear
+    Clearing,
+}
+
+impl ImbalancePriceSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fixed => "fixed",
+            Self::Clearing => "clearing",
+        }
+    }
+}
+
+impl std::str::FromStr for ImbalancePriceSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "fixed" => Ok(Self::Fixed),
+            "clearing" => Ok(Self::Clearing),
+            other => Err(anyhow::anyhow!("expected fixed or clearing, got {}", other)),
+        }
+    }
+}
+
+/// Price the gap between each participant's traded and metered net export
+/// per epoch. Shortfalls pay the reference price times `shortfall_multiplier`,
+/// surpluses are paid the reference price times `surplus_multiplier`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImbalanceConfig {
+    pub enabled: bool,
+    pub price_source: ImbalancePriceSource,
+    pub base_price: Decimal,
+    pub shortfall_multiplier: Decimal,
+    pub surplus_multiplier: Decimal,
+}
+
+impl Default for ImbalanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            price_source: ImbalancePriceSource::Clearing,
+            base_price: Decimal::new(4, 0),
+            shortfall_multiplier: Decimal::new(15, 1),
+            surplus_multiplier: Decimal::new(5, 1),
+        }
+    }
+}
+
+/// Sell orders placed each epoch for prosumers' forecast surplus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutopilotConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    /// Past days whose surplus in the same time slot forms the forecast
+    pub forecast_days: i64,
+    /// Smallest order placed; smaller forecasts skip the epoch
+    pub min_order_kwh: Decimal,
+}
+
+impl Default for AutopilotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 60,
+            forecast_days: 7,
+            min_order_kwh: Decimal::new(1, 1),
+        }
+    }
+}
+
+/// Comparison of official DSO interval data with user-submitted readings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridReconciliationConfig {
+    pub interval_secs: u64,
+    /// Deviation above this fraction of the official volume marks an interval divergent
+    pub tolerance: Decimal,
+    /// Recent reconciled intervals of a meter considered for freezing
+    pub window: i64,
+    /// Divergent intervals within the window that freeze minting; 0 disables freezing
+    pub freeze_after: i64,
+}
+
+impl Default for GridReconciliationConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 300,
+            tolerance: Decimal::new(5, 2),
+            window: 10,
+            freeze_after: 3,
+        }
+    }
+}
+
+/// Short-lived caching of hot RPC reads in the blockchain service; a TTL of
+/// 0 disables caching for that kind of read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcCacheConfig {
+    pub blockhash_ttl_ms: u64,
+    pub account_ttl_ms: u64,
+    pub token_balance_ttl_ms: u64,
+}
+
+impl Default for RpcCacheConfig {
+    fn default() -> Self {
+        Self {
+            blockhash_ttl_ms: 2000,
+            account_ttl_ms: 1000,
+            token_balance_ttl_ms: 1000,
+        }
+    }
+}
+
+/// Commitment each kind of on-chain operation waits for before it counts
+/// as done
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationConfig {
+    pub mint: CommitmentLevel,
+    pub settlement: CommitmentLevel,
+    pub transfer: CommitmentLevel,
+    /// Everything else: registrations, escrow, governance and oracle calls
+    pub other: CommitmentLevel,
+    /// How long to wait for the commitment before giving up
+    pub timeout_secs: u64,
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            mint: CommitmentLevel::Confirmed,
+            settlement: CommitmentLevel::Finalized,
+            transfer: CommitmentLevel::Confirmed,
+            other: CommitmentLevel::Confirmed,
+            timeout_secs: 60,
+        }
+    }
+}
+
+/// Prioritized transaction submission: settlements first, then mints,
+/// transfers, everything else and sandbox transactions last. Each class is
+/// capped separately under the overall in-flight limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionQueueConfig {
+    pub max_in_flight: usize,
+    pub settlement_limit: usize,
+    pub mint_limit: usize,
+    pub transfer_limit: usize,
+    pub other_limit: usize,
+    pub test_limit: usize,
+}
+
+impl Default for SubmissionQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 8,
+            settlement_limit: 4,
+            mint_limit: 3,
+            transfer_limit: 2,
+            other_limit: 2,
+            test_limit: 1,
+        }
+    }
+}
+
+/// Developer sandbox: faucet airdrops, funded test users and scenarios.
+/// Only served when the RPC endpoint is devnet or a local validator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxConfig {
+    pub enabled: bool,
+    /// Largest SOL airdrop per request
+    pub max_airdrop_sol: f64,
+    /// Airdrops per wallet per rolling hour
+    pub airdrops_per_hour: i64,
+    /// Steps accepted in one scenario run
+    pub max_scenario_steps: usize,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_airdrop_sol: 2.0,
+            airdrops_per_hour: 5,
+            max_scenario_steps: 50,
+        }
+    }
+}
+
+/// Referral rewards and the fraud heuristics that hold them for review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferralConfig {
+    /// Energy tokens (kWh) minted to the referrer per qualified referral
+    pub referrer_reward_kwh: Decimal,
+    /// Energy tokens (kWh) minted to the new user
+    pub referee_reward_kwh: Decimal,
+    /// Sign-ups referred by one user from a single IP within 24h before
+    /// further ones are flagged
+    pub max_signups_per_ip: i64,
+    /// Qualified referrals rewarded per referrer; later ones are flagged
+    pub max_rewards_per_referrer: i64,
+}
+
+impl Default for ReferralConfig {
+    fn default() -> Self {
+        Self {
+            referrer_reward_kwh: Decimal::from(10),
+            referee_reward_kwh: Decimal::from(5),
+            max_signups_per_ip: 3,
+            max_rewards_per_referrer: 50,
+        }
+    }
+}
+
+/// Third-party app delegation through the consent flow
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DelegationConfig {
+    /// How long an authorization code can be redeemed (seconds)
+    pub code_ttl_secs: i64,
+    /// Lifetime of a delegated token (days); revoking the grant ends it sooner
+    pub token_ttl_days: i64,
+}
+
+/// Uploaded documents (KYC, dispute evidence, certificate validation data)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentsConfig {
+    /// Largest accepted upload (bytes)
+    pub max_bytes: usize,
+    /// Storage backend: "local" or "s3"
+    pub backend: String,
+    /// Root directory of the local backend
+    pub storage_dir: String,
+    /// S3-compatible endpoint, e.g. https://s3.ap-southeast-1.amazonaws.com or a MinIO URL
+    pub s3_endpoint: String,
+    pub s3_bucket: String,
+    pub s3_region: String,
+    pub s3_access_key: String,
+    pub s3_secret_key: String,
+    /// Address the bucket in the path (MinIO) rather than the host name
+    pub s3_path_style: bool,
+    /// Local download links; S3 presigned URLs share their lifetime
+    pub signed_urls: SignedUrlConfig,
+    /// Malware scanner endpoint; uploads are stored unscanned when unset
+    pub scan_url: Option<String>,
+    pub scan_timeout_secs: u64,
+}
+
+/// What to do when a dependency is unavailable at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupPolicy {
+    /// Abort startup
+    FailFast,
+    /// Start without the dependency and report the gateway as degraded
+    Degraded,
+}
+
+impl std::str::FromStr for StartupPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "fail_fast" | "required" => Ok(Self::FailFast),
+            "degraded" | "optional" => Ok(Self::Degraded),
+            other => Err(anyhow::anyhow!("expected fail_fast or degraded, got {}", other)),
+        }
+    }
+}
+
+/// Startup retries and per-dependency failure policies. PostgreSQL and
+/// Redis are always required.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupConfig {
+    /// Attempts for dependencies with transient failures (database, Redis, RPC)
+    pub retry_attempts: u32,
+    /// Delay before the first retry, doubled after each attempt
+    pub retry_base_delay_ms: u64,
+    pub solana_rpc: StartupPolicy,
+    pub email: StartupPolicy,
+    pub authority_wallet: StartupPolicy,
+    /// Tables or columns the gateway queries are missing after migrations
+    pub schema_check: StartupPolicy,
+}
+
+/// Who can reach the Swagger UI and OpenAPI spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocsExposure {
+    Public,
+    /// Admin role and admin network only
+    Admin,
+    Disabled,
+}
+
+impl std::str::FromStr for DocsExposure {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "public" => Ok(Self::Public),
+            "admin" => Ok(Self::Admin),
+            "disabled" | "off" => Ok(Self::Disabled),
+            other => Err(anyhow::anyhow!("expected public, admin or disabled, got {}", other)),
+        }
+    }
+}
+
+/// API documentation exposure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocsConfig {
+    /// Defaults to disabled in production, public elsewhere
+    pub exposure: DocsExposure,
+}
+
+/// Redis buffer for audit events that could not be written to PostgreSQL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditBufferConfig {
+    /// Oldest events are dropped beyond this; 0 disables buffering
+    pub max_events: usize,
+    pub flush_interval_secs: u64,
+    /// Events replayed per flush
+    pub flush_batch: usize,
+}
+
+/// MaxMind databases for enriching audit events with client location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoIpConfig {
+    /// GeoLite2/GeoIP2 City database (.mmdb); no location when unset
+    pub city_db_path: Option<String>,
+    /// GeoLite2/GeoIP2 ASN database (.mmdb); no network when unset
+    pub asn_db_path: Option<String>,
+    /// Consecutive logins implying a faster speed are flagged as impossible travel
+    pub max_travel_kmh: f64,
+}
+
+/// Anonymous public market data API (`/api/v1/public/market`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicApiConfig {
+    /// Requests per minute allowed from one client IP
+    pub rate_limit_per_minute: u32,
+    /// Cache lifetime of the orderbook snapshot and last trades
+    pub live_cache_secs: u64,
+    /// Cache lifetime of clearing prices and candles
+    pub history_cache_secs: u64,
+    /// Hours a participant's public ID in market feeds stays the same
+    pub participant_id_rotation_hours: i64,
+    /// Key of public participant IDs; derived from `ENCRYPTION_SECRET` when unset
+    pub participant_id_secret: Option<String>,
+}
+
+/// Application-layer encryption of personal data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PiiConfig {
+    /// KMS wrapping the data keys: "vault" (transit engine) or "local"
+    pub kms_provider: String,
+    /// Base64 32-byte master key of the local provider; derived from
+    /// `ENCRYPTION_SECRET` when unset
+    pub master_key: Option<String>,
+    pub vault_addr: String,
+    pub vault_token: Option<String>,
+    /// Transit key wrapping the data keys
+    pub vault_key_name: String,
+    pub rotation_interval_secs: u64,
+    /// Rows re-encrypted per table and pass
+    pub rotation_batch_size: i64,
+    /// Age at which a new data key is made active; 0 rotates only on request
+    pub key_max_age_days: i64,
+}
+
+/// Step-up verification of risky logins
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepUpConfig {
+    /// Withhold the session token of risky logins until a one-time code is entered
+    pub enabled: bool,
+    pub code_ttl_secs: i64,
+    /// Wrong codes after which a challenge is locked
+    pub max_attempts: i32,
+    /// Login history a country or device must appear in to count as known
+    pub history_days: i64,
+}
+
+/// Optional platform modules a deployment can switch off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// P2P orders, markets and trading history
+    Trading,
+    /// Anonymous market data under `/api/v1/public/market`
+    PublicMarket,
+    Prepaid,
+    Invoicing,
+    Referrals,
+    Vesting,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 6] = [
+        Feature::Trading,
+        Feature::PublicMarket,
+        Feature::Prepaid,
+        Feature::Invoicing,
+        Feature::Referrals,
+        Feature::Vesting,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Feature::Trading => "trading",
+            Feature::PublicMarket => "public_market",
+            Feature::Prepaid => "prepaid",
+            Feature::Invoicing => "invoicing",
+            Feature::Referrals => "referrals",
+            Feature::Vesting => "vesting",
+        }
+    }
+}
+
+impl std::str::FromStr for Feature {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase();
+        Feature::ALL
+            .into_iter()
+            .find(|feature| feature.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("unknown feature {}", s))
+    }
+}
+
+/// Tenant this instance is deployed for. Branding set here is the default;
+/// the profile's row in `deployment_profiles` overrides it, and pins the
+/// token mint and program IDs the database belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentConfig {
+    /// Profile key, e.g. the utility's short name
+    pub profile: String,
+    pub brand_name: String,
+    pub support_email: String,
+    pub logo_url: Option<String>,
+    /// Hex color used by clients for theming
+    pub primary_color: String,
+    /// Enabled modules; all when `DEPLOYMENT_FEATURES` is unset
+    pub features: Vec<Feature>,
+    /// IANA name of the market timezone, e.g. Asia/Bangkok
+    pub timezone: String,
+    /// Offset of the market timezone; days, billing months and report
+    /// periods are counted in it while timestamps are stored in UTC
+    pub utc_offset_minutes: i32,
+}
+
+/// Thresholds of the meter fleet health report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeterFleetConfig {
+    /// Minutes without a reading after which a meter is stale
+    pub stale_after_mins: i64,
+    /// Hours without a reading after which a meter is offline
+    pub offline_after_hours: i64,
+    /// Reading interval meters are expected to keep, in minutes
+    pub expected_interval_mins: i64,
+    /// Days of hourly health counters kept
+    pub retention_days: i64,
+}
+
+/// Lifetime and limits of wallet sessions (unlocked trading keys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletSessionConfig {
+    /// Hours without use after which a session expires; each use slides it forward
+    pub idle_timeout_hours: i64,
+    /// Days after creation at which a session ends regardless of use
+    pub max_lifetime_days: i64,
+    /// Active sessions per user; creating one more revokes the oldest
+    pub max_concurrent: i64,
+}
+
+/// When the platform pays the fees of user token transfers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeSponsorshipConfig {
+    pub enabled: bool,
+    /// Transfers per user sponsored by the platform before fees fall to the user
+    pub free_transfers: i64,
+    /// Platform-sponsored fees per user per calendar month
+    pub user_monthly_lamports: i64,
+    /// Sponsored transfers per user per day, across all rules
+    pub daily_transfer_limit: i64,
+    /// Platform-wide sponsored fees per day (0 for no cap)
+    pub daily_budget_lamports: i64,
+    /// Smaller transfers are never sponsored
+    pub min_transfer_kwh: Decimal,
+    /// Fee reserved per transfer until the charged fee is known
+    pub fee_estimate_lamports: i64,
+}
+
+/// Delivery of scheduled analytics reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSubscriptionConfig {
+    pub enabled: bool,
+    /// Seconds between checks for due subscriptions
+    pub interval_secs: u64,
+    /// Subscriptions a user may keep
+    pub max_per_user: i64,
+    /// Consecutive failed deliveries after which a subscription is paused
+    pub max_failures: i32,
+}
+
+/// Tracking of background job runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobsConfig {
+    /// Days finished job runs and their logs are kept
+    pub retention_days: i64,
+    /// Minutes without a log line after which a running job counts as interrupted
+    pub stale_after_mins: i64,
+}
+
+/// What happens to a reading that would mint more than its wallet's meters can produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MintGuardMode {
+    /// Refuse the reading
+    Reject,
+    /// Record the reading without minting and hold it for admin review
+    Quarantine,
+}
+
+impl std::str::FromStr for MintGuardMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "reject" => Ok(Self::Reject),
+            "quarantine" => Ok(Self::Quarantine),
+            other => Err(anyhow::anyhow!("expected reject or quarantine, got {}", other)),
+        }
+    }
+}
+
+/// Caps on energy minted per wallet, derived from the rated capacity of
+/// the wallet owner's meters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MintGuardConfig {
+    pub enabled: bool,
+    pub mode: MintGuardMode,
+    /// Capacity assumed for meters without a rating, kW
+    pub default_capacity_kw: Decimal,
+    /// Hourly cap as a multiple of the capacity, allowing for metering tolerance
+    pub hourly_headroom: Decimal,
+    /// Hours at full capacity a wallet may mint per day
+    pub daily_full_load_hours: Decimal,
+}
+
+/// Runtime handling of optional integrations (email, cache, metrics) that fail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DegradationConfig {
+    /// Consecutive failures after which a subsystem is marked down
+    pub failure_threshold: u32,
+    /// Seconds between recovery probes of subsystems that are down
+    pub probe_interval_secs: u64,
+    /// Emails held while email is down; the oldest are dropped beyond this
+    pub email_queue_capacity: usize,
+}
+
+/// Asynchronous exports of a user's readings, trades and audit trail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataExportConfig {
+    /// Root directory of the local object store for export files
+    pub storage_dir: String,
+    pub signed_urls: SignedUrlConfig,
+    /// Exports a user may request per rolling 24 hours
+    pub daily_quota: i64,
+    /// Exports a user may have queued or running at once
+    pub max_pending: i64,
+    /// Rows written to one export; larger exports fail
+    pub max_rows: i64,
+    /// Widest date range of one export (days)
+    pub max_range_days: i64,
+    /// Days a finished export file is kept before it expires
+    pub retention_days: i64,
+    /// Seconds between checks for queued exports
+    pub interval_secs: u64,
+}
+
+/// Coalescing windows of high-frequency WebSocket broadcasts. Within a
+/// window only the latest event of a kind is sent; zero sends every event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BroadcastThrottleConfig {
+    /// Aggregate grid status (milliseconds)
+    pub grid_status_ms: u64,
+    /// Order book snapshots, side updates and depth (milliseconds)
+    pub order_book_ms: u64,
+    /// Market statistics (milliseconds)
+    pub market_stats_ms: u64,
+}
+
+impl BroadcastThrottleConfig {
+    /// Longest window, so clients are never starved of updates
+    pub const MAX_WINDOW_MS: u64 = 10_000;
+
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        for (name, window) in [
+            ("grid_status_ms", self.grid_status_ms),
+            ("order_book_ms", self.order_book_ms),
+            ("market_stats_ms", self.market_stats_ms),
+        ] {
+            if window > Self::MAX_WINDOW_MS {
+                return Err(format!("{} must be at most {}", name, Self::MAX_WINDOW_MS));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Guardrails for heavy analytics, audit and export queries
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct QueryGovernorConfig {
+    /// Statement timeout of analytics queries (milliseconds)
+    pub analytics_timeout_ms: u64,
+    /// Statement timeout of audit log searches (milliseconds)
+    pub audit_timeout_ms: u64,
+    /// Statement timeout of each page of a streamed export (milliseconds)
+    pub export_timeout_ms: u64,
+    /// Rows one analytics query may return
+    pub analytics_max_rows: i64,
+    /// Rows one audit log search may return
+    pub audit_max_rows: i64,
+    /// Governed queries slower than this are logged (milliseconds)
+    pub slow_query_ms: u64,
+}
+
+/// Cold-start warmup run before `/readyz` reports ready
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WarmupConfig {
+    pub enabled: bool,
+    /// Longest warmup may hold readiness back (seconds)
+    pub timeout_secs: u64,
+    /// Database connections opened and checked before ready
+    pub db_connections: u32,
+}
+
+/// Requests one caller may have in flight on expensive endpoints (0 disables)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ConcurrencyLimitConfig {
+    pub export_max_in_flight: usize,
+    pub analytics_max_in_flight: usize,
+    /// Order placement, import, routing and amendment
+    pub orders_max_in_flight: usize,
+    /// How long a request over the cap waits for a slot before 429 (0 rejects at once)
+    pub queue_timeout_ms: u64,
+}
+
+/// CO2 savings estimates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmissionsConfig {
+    /// Grid region whose emission factors apply (e.g. TH)
+    pub region: String,
+    /// kg CO2 per kWh used when no factor is configured for the region
+    pub fallback_kg_per_kwh: f64,
+}
+
+/// Retention of audit records per event class, in days (0 keeps forever).
+/// Records covered by an active legal hold are never purged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRetentionConfig {
+    pub enabled: bool,
+    /// Failed logins, unauthorized access, rate limiting
+    pub security_days: i64,
+    /// Logins, password and email changes, API keys
+    pub account_days: i64,
+    pub trading_days: i64,
+    /// Payments, prepaid ledger, disputes
+    pub financial_days: i64,
+    pub admin_days: i64,
+    /// Wallet audit log (key export, signing)
+    pub wallet_days: i64,
+    /// Event types not assigned to a class
+    pub other_days: i64,
+}
+
+/// Token orders and escrow are settled in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SettlementAsset {
+    /// Platform currency token (`CURRENCY_TOKEN_MINT`)
+    Token,
+    /// Configured stablecoin mint (`STABLECOIN_MINT`)
+    Stablecoin,
+}
+
+impl std::str::FromStr for SettlementAsset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "token" => Ok(Self::Token),
+            "stablecoin" => Ok(Self::Stablecoin),
+            other => Err(anyhow::anyhow!("expected token or stablecoin, got {}", other)),
+        }
+    }
+}
+
+/// Settlement currency and fiat reference pricing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrencyConfig {
+    /// ISO code of the fiat reference currency, uppercase (e.g. "THB")
+    pub display_currency: String,
+    pub settlement_asset: SettlementAsset,
+    pub currency_token_mint: String,
+    pub stablecoin_mint: Option<String>,
+    pub stablecoin_decimals: u8,
+    /// Oracle rates older than this are not used for new trades
+    pub fx_max_age_secs: i64,
+    /// Rate used when no fresh oracle rate is available (fiat per settlement token)
+    pub fx_fallback_rate: Option<Decimal>,
+}
+
+impl CurrencyConfig {
+    /// Mint that payments and currency escrow use
+    pub fn settlement_mint(&self) -> &str {
+        match (self.settlement_asset, &self.stablecoin_mint) {
+            (SettlementAsset::Stablecoin, Some(mint)) => mint,
+            _ => &self.currency_token_mint,
+        }
+    }
+
+    pub fn settlement_decimals(&self) -> u8 {
+        match self.settlement_asset {
+            SettlementAsset::Stablecoin => self.stablecoin_decimals,
+            SettlementAsset::Token => 6,
+        }
+    }
+}
+
+/// Trading calendar. Times are market-local, at a fixed UTC offset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketSessionConfig {
+    /// When disabled the market is open around the clock
+    pub enabled: bool,
+    pub utc_offset_minutes: i32,
+    pub open_time: NaiveTime,
+    pub close_time: NaiveTime,
+    /// Order collection window before the open; orders are accepted but not cleared
+    pub pre_open_minutes: i64,
+    pub trading_days: Vec<Weekday>,
+    /// Market-local dates on which the market stays closed
+    pub holidays: Vec<NaiveDate>,
+}
+
+impl Default for MarketSessionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            utc_offset_minutes: 420,
+            open_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap_or_default(),
+            close_time: NaiveTime::from_hms_opt(22, 0, 0).unwrap_or_default(),
+            pre_open_minutes: 30,
+            trading_days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ],
+            holidays: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventProcessorConfig {
     pub enabled: bool,
@@ -66,6 +1217,10 @@ pub struct EventProcessorConfig {
     pub max_retries: u32,
     pub webhook_url: Option<String>,
     pub webhook_secret: Option<String>,
+    /// Lag behind the chain head, in slots, above which the processor is unhealthy
+    pub max_lag_slots: u64,
+    /// Age of the oldest unconfirmed mint above which the processor is unhealthy
+    pub max_pending_age_secs: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -135,6 +1290,20 @@ impl Config {
             rate_limit_window: env::var("RATE_LIMIT_WINDOW")
                 .map_err(|_| anyhow::anyhow!("RATE_LIMIT_WINDOW environment variable is required"))?
                 .parse()?,
+            meter_submission_limit: env::var("METER_SUBMISSION_LIMIT")
+                .unwrap_or_else(|_| crate::constants::rate_limit::MAX_REQUESTS_PER_USER.to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid METER_SUBMISSION_LIMIT: {}", e))?,
+            delegation: DelegationConfig {
+                code_ttl_secs: env::var("DELEGATION_CODE_TTL_SECS")
+                    .unwrap_or_else(|_| "600".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DELEGATION_CODE_TTL_SECS: {}", e))?,
+                token_ttl_days: env::var("DELEGATION_TOKEN_TTL_DAYS")
+                    .unwrap_or_else(|_| "90".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DELEGATION_TOKEN_TTL_DAYS: {}", e))?,
+            },
             log_level: env::var("LOG_LEVEL")
                 .map_err(|_| anyhow::anyhow!("LOG_LEVEL environment variable is required"))?,
             audit_log_enabled: env::var("AUDIT_LOG_ENABLED")
@@ -197,6 +1366,14 @@ impl Config {
                     .map_err(|e| anyhow::anyhow!("Invalid EVENT_PROCESSOR_MAX_RETRIES: {}", e))?,
                 webhook_url: env::var("EVENT_PROCESSOR_WEBHOOK_URL").ok(),
                 webhook_secret: env::var("EVENT_PROCESSOR_WEBHOOK_SECRET").ok(),
+                max_lag_slots: env::var("EVENT_PROCESSOR_MAX_LAG_SLOTS")
+                    .unwrap_or_else(|_| "150".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid EVENT_PROCESSOR_MAX_LAG_SLOTS: {}", e))?,
+                max_pending_age_secs: env::var("EVENT_PROCESSOR_MAX_PENDING_AGE_SECS")
+                    .unwrap_or_else(|_| "600".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid EVENT_PROCESSOR_MAX_PENDING_AGE_SECS: {}", e))?,
             },
             solana_programs: SolanaProgramsConfig {
                 registry_program_id: env::var("SOLANA_REGISTRY_PROGRAM_ID")
@@ -210,6 +1387,787 @@ impl Config {
                 trading_program_id: env::var("SOLANA_TRADING_PROGRAM_ID")
                     .unwrap_or_else(|_| "8gHn9oeYcUQgNrMi8fNYGyMCKJTMwM6K413f41AANFt4".to_string()),
             },
+            grid_tariff: GridTariffConfig {
+                retail_per_kwh: env::var("GRID_RETAIL_TARIFF_PER_KWH")
+                    .unwrap_or_else(|_| "4.18".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid GRID_RETAIL_TARIFF_PER_KWH: {}", e))?,
+                feed_in_per_kwh: env::var("GRID_FEED_IN_TARIFF_PER_KWH")
+                    .unwrap_or_else(|_| "2.20".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid GRID_FEED_IN_TARIFF_PER_KWH: {}", e))?,
+            },
+            invoicing: InvoicingConfig {
+                storage_dir: env::var("INVOICE_STORAGE_DIR")
+                    .unwrap_or_else(|_| "./data/invoices".to_string()),
+                signed_urls: signed_urls_from_env("INVOICE", 900)?,
+            },
+            payments: PaymentsConfig {
+                provider: env::var("PAYMENT_PROVIDER")
+                    .unwrap_or_else(|_| "stripe".to_string())
+                    .to_lowercase(),
+                api_key: env::var("PAYMENT_API_KEY").ok().filter(|v| !v.is_empty()),
+                webhook_secret: env::var("PAYMENT_WEBHOOK_SECRET").ok().filter(|v| !v.is_empty()),
+                currency: env::var("PAYMENT_CURRENCY")
+                    .unwrap_or_else(|_| "thb".to_string())
+                    .to_lowercase(),
+                min_topup: env::var("PREPAID_MIN_TOPUP")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid PREPAID_MIN_TOPUP: {}", e))?,
+                max_topup: env::var("PREPAID_MAX_TOPUP")
+                    .unwrap_or_else(|_| "50000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid PREPAID_MAX_TOPUP: {}", e))?,
+                webhook_tolerance_secs: env::var("PAYMENT_WEBHOOK_TOLERANCE_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid PAYMENT_WEBHOOK_TOLERANCE_SECS: {}", e))?,
+            },
+            network_acl: NetworkAclConfig {
+                admin_allow: cidr_list_from_env("NETWORK_ACL_ADMIN_ALLOW", "")?,
+                ami_allow: cidr_list_from_env("NETWORK_ACL_AMI_ALLOW", "")?,
+                trusted_proxies: cidr_list_from_env("NETWORK_ACL_TRUSTED_PROXIES", "127.0.0.1/32,::1/128")?,
+            },
+            ami_gateway: AmiGatewayConfig {
+                require_client_cert: env::var("AMI_REQUIRE_CLIENT_CERT")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AMI_REQUIRE_CLIENT_CERT: {}", e))?,
+                fingerprint_header: env::var("AMI_CLIENT_CERT_FINGERPRINT_HEADER")
+                    .unwrap_or_else(|_| "x-client-cert-fingerprint".to_string())
+                    .to_lowercase(),
+                verify_header: env::var("AMI_CLIENT_CERT_VERIFY_HEADER")
+                    .unwrap_or_else(|_| "x-client-cert-verify".to_string())
+                    .to_lowercase(),
+                backfill_max_age_days: env::var("AMI_BACKFILL_MAX_AGE_DAYS")
+                    .unwrap_or_else(|_| "90".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AMI_BACKFILL_MAX_AGE_DAYS: {}", e))?,
+            },
+            request_signing: RequestSigningConfig {
+                clients: signing_clients_from_env()?,
+                tolerance_secs: env::var("REQUEST_SIGNING_TOLERANCE_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid REQUEST_SIGNING_TOLERANCE_SECS: {}", e))?,
+                max_body_bytes: env::var("REQUEST_SIGNING_MAX_BODY_BYTES")
+                    .unwrap_or_else(|_| "10485760".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid REQUEST_SIGNING_MAX_BODY_BYTES: {}", e))?,
+            },
+            order_balance: OrderBalanceConfig {
+                onchain_confirmation_kwh: env::var("ORDER_ONCHAIN_CONFIRMATION_KWH")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid ORDER_ONCHAIN_CONFIRMATION_KWH: {}", e))?,
+                oversubscription_pct: oversubscription_policy_from_env()?,
+            },
+            market_session: market_session_from_env()?,
+            trade_approval: TradeApprovalConfig {
+                required_approvals: env::var("TRADE_APPROVAL_REQUIRED")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse::<u32>()
+                    .map_err(|e| anyhow::anyhow!("Invalid TRADE_APPROVAL_REQUIRED: {}", e))?
+                    .max(1),
+                ttl_hours: env::var("TRADE_APPROVAL_TTL_HOURS")
+                    .unwrap_or_else(|_| "48".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid TRADE_APPROVAL_TTL_HOURS: {}", e))?,
+            },
+            order_router: OrderRouterConfig {
+                amm_enabled: env::var("ORDER_ROUTER_AMM_ENABLED")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid ORDER_ROUTER_AMM_ENABLED: {}", e))?,
+                max_slippage_bps: env::var("ORDER_ROUTER_MAX_SLIPPAGE_BPS")
+                    .unwrap_or_else(|_| "200".to_string())
+                    .parse::<u32>()
+                    .map_err(|e| anyhow::anyhow!("Invalid ORDER_ROUTER_MAX_SLIPPAGE_BPS: {}", e))?
+                    .min(10_000),
+            },
+            fix_gateway: FixGatewayConfig {
+                enabled: env::var("FIX_GATEWAY_ENABLED")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid FIX_GATEWAY_ENABLED: {}", e))?,
+                bind_addr: env::var("FIX_GATEWAY_ADDR").unwrap_or_else(|_| "0.0.0.0:9878".to_string()),
+                comp_id: env::var("FIX_GATEWAY_COMP_ID").unwrap_or_else(|_| "GRIDTOKENX".to_string()),
+                logon_timeout_secs: env::var("FIX_GATEWAY_LOGON_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid FIX_GATEWAY_LOGON_TIMEOUT_SECS: {}", e))?,
+            },
+            maker_incentives: MakerIncentiveConfig {
+                enabled: env::var("MAKER_INCENTIVES_ENABLED")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MAKER_INCENTIVES_ENABLED: {}", e))?,
+                sample_interval_secs: env::var("MAKER_SAMPLE_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MAKER_SAMPLE_INTERVAL_SECS: {}", e))?,
+                daily_pool_kwh: env::var("MAKER_REBATE_DAILY_POOL_KWH")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MAKER_REBATE_DAILY_POOL_KWH: {}", e))?,
+                max_spread_bps: env::var("MAKER_MAX_SPREAD_BPS")
+                    .unwrap_or_else(|_| "200".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MAKER_MAX_SPREAD_BPS: {}", e))?,
+                min_time_at_best_pct: env::var("MAKER_MIN_TIME_AT_BEST_PCT")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MAKER_MIN_TIME_AT_BEST_PCT: {}", e))?,
+            },
+            matching_shadow: MatchingShadowConfig {
+                enabled: env::var("MATCHING_SHADOW_ENABLED")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MATCHING_SHADOW_ENABLED: {}", e))?,
+                algorithm: env::var("MATCHING_SHADOW_ALGORITHM")
+                    .unwrap_or_else(|_| "uniform_price".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MATCHING_SHADOW_ALGORITHM: {}", e))?,
+            },
+            delivery_verification: DeliveryVerificationConfig {
+                enabled: env::var("DELIVERY_VERIFICATION_ENABLED")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DELIVERY_VERIFICATION_ENABLED: {}", e))?,
+                interval_secs: env::var("DELIVERY_VERIFICATION_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DELIVERY_VERIFICATION_INTERVAL_SECS: {}", e))?,
+                grace_minutes: env::var("DELIVERY_VERIFICATION_GRACE_MINUTES")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DELIVERY_VERIFICATION_GRACE_MINUTES: {}", e))?,
+                tolerance: env::var("DELIVERY_SHORTFALL_TOLERANCE")
+                    .unwrap_or_else(|_| "0.05".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DELIVERY_SHORTFALL_TOLERANCE: {}", e))?,
+                remedy: env::var("DELIVERY_SHORTFALL_REMEDY")
+                    .unwrap_or_else(|_| "flag".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DELIVERY_SHORTFALL_REMEDY: {}", e))?,
+            },
+            imbalance: ImbalanceConfig {
+                enabled: env::var("IMBALANCE_SETTLEMENT_ENABLED")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid IMBALANCE_SETTLEMENT_ENABLED: {}", e))?,
+                price_source: env::var("IMBALANCE_PRICE_SOURCE")
+                    .unwrap_or_else(|_| "clearing".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid IMBALANCE_PRICE_SOURCE: {}", e))?,
+                base_price: env::var("IMBALANCE_BASE_PRICE")
+                    .unwrap_or_else(|_| "4".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid IMBALANCE_BASE_PRICE: {}", e))?,
+                shortfall_multiplier: env::var("IMBALANCE_SHORTFALL_MULTIPLIER")
+                    .unwrap_or_else(|_| "1.5".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid IMBALANCE_SHORTFALL_MULTIPLIER: {}", e))?,
+                surplus_multiplier: env::var("IMBALANCE_SURPLUS_MULTIPLIER")
+                    .unwrap_or_else(|_| "0.5".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid IMBALANCE_SURPLUS_MULTIPLIER: {}", e))?,
+            },
+            autopilot: AutopilotConfig {
+                enabled: env::var("AUTOPILOT_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUTOPILOT_ENABLED: {}", e))?,
+                interval_secs: env::var("AUTOPILOT_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUTOPILOT_INTERVAL_SECS: {}", e))?,
+                forecast_days: env::var("AUTOPILOT_FORECAST_DAYS")
+                    .unwrap_or_else(|_| "7".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUTOPILOT_FORECAST_DAYS: {}", e))?,
+                min_order_kwh: env::var("AUTOPILOT_MIN_ORDER_KWH")
+                    .unwrap_or_else(|_| "0.1".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUTOPILOT_MIN_ORDER_KWH: {}", e))?,
+            },
+            grid_reconciliation: GridReconciliationConfig {
+                interval_secs: env::var("GRID_RECONCILIATION_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid GRID_RECONCILIATION_INTERVAL_SECS: {}", e))?,
+                tolerance: env::var("GRID_RECONCILIATION_TOLERANCE")
+                    .unwrap_or_else(|_| "0.05".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid GRID_RECONCILIATION_TOLERANCE: {}", e))?,
+                window: env::var("GRID_RECONCILIATION_WINDOW")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid GRID_RECONCILIATION_WINDOW: {}", e))?,
+                freeze_after: env::var("GRID_RECONCILIATION_FREEZE_AFTER")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid GRID_RECONCILIATION_FREEZE_AFTER: {}", e))?,
+            },
+            sandbox: SandboxConfig {
+                enabled: env::var("SANDBOX_ENABLED")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid SANDBOX_ENABLED: {}", e))?,
+                max_airdrop_sol: env::var("SANDBOX_MAX_AIRDROP_SOL")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid SANDBOX_MAX_AIRDROP_SOL: {}", e))?,
+                airdrops_per_hour: env::var("SANDBOX_AIRDROPS_PER_HOUR")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid SANDBOX_AIRDROPS_PER_HOUR: {}", e))?,
+                max_scenario_steps: env::var("SANDBOX_MAX_SCENARIO_STEPS")
+                    .unwrap_or_else(|_| "50".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid SANDBOX_MAX_SCENARIO_STEPS: {}", e))?,
+            },
+            referral: ReferralConfig {
+                referrer_reward_kwh: env::var("REFERRAL_REFERRER_REWARD_KWH")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid REFERRAL_REFERRER_REWARD_KWH: {}", e))?,
+                referee_reward_kwh: env::var("REFERRAL_REFEREE_REWARD_KWH")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid REFERRAL_REFEREE_REWARD_KWH: {}", e))?,
+                max_signups_per_ip: env::var("REFERRAL_MAX_SIGNUPS_PER_IP")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid REFERRAL_MAX_SIGNUPS_PER_IP: {}", e))?,
+                max_rewards_per_referrer: env::var("REFERRAL_MAX_REWARDS_PER_REFERRER")
+                    .unwrap_or_else(|_| "50".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid REFERRAL_MAX_REWARDS_PER_REFERRER: {}", e))?,
+            },
+            attachments: AttachmentsConfig {
+                max_bytes: env::var("ATTACHMENT_MAX_BYTES")
+                    .unwrap_or_else(|_| "10485760".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid ATTACHMENT_MAX_BYTES: {}", e))?,
+                backend: env::var("ATTACHMENT_STORAGE").unwrap_or_else(|_| "local".to_string()),
+                storage_dir: env::var("ATTACHMENT_STORAGE_DIR")
+                    .unwrap_or_else(|_| "./data/attachments".to_string()),
+                s3_endpoint: env::var("S3_ENDPOINT").unwrap_or_default(),
+                s3_bucket: env::var("S3_BUCKET").unwrap_or_default(),
+                s3_region: env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                s3_access_key: env::var("S3_ACCESS_KEY").unwrap_or_default(),
+                s3_secret_key: env::var("S3_SECRET_KEY").unwrap_or_default(),
+                s3_path_style: env::var("S3_PATH_STYLE")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid S3_PATH_STYLE: {}", e))?,
+                signed_urls: signed_urls_from_env("ATTACHMENT", 300)?,
+                scan_url: env::var("MALWARE_SCAN_URL").ok().filter(|url| !url.is_empty()),
+                scan_timeout_secs: env::var("MALWARE_SCAN_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MALWARE_SCAN_TIMEOUT_SECS: {}", e))?,
+            },
+            erc_expiry_warning_days: env::var("ERC_EXPIRY_WARNING_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid ERC_EXPIRY_WARNING_DAYS: {}", e))?,
+            balance_cache_ttl_secs: env::var("BALANCE_CACHE_TTL_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid BALANCE_CACHE_TTL_SECS: {}", e))?,
+            rpc_cache: RpcCacheConfig {
+                blockhash_ttl_ms: env::var("RPC_CACHE_BLOCKHASH_TTL_MS")
+                    .unwrap_or_else(|_| "2000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid RPC_CACHE_BLOCKHASH_TTL_MS: {}", e))?,
+                account_ttl_ms: env::var("RPC_CACHE_ACCOUNT_TTL_MS")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid RPC_CACHE_ACCOUNT_TTL_MS: {}", e))?,
+                token_balance_ttl_ms: env::var("RPC_CACHE_TOKEN_BALANCE_TTL_MS")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid RPC_CACHE_TOKEN_BALANCE_TTL_MS: {}", e))?,
+            },
+            confirmation: ConfirmationConfig {
+                mint: env::var("CONFIRMATION_MINT")
+                    .unwrap_or_else(|_| "confirmed".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid CONFIRMATION_MINT: {}", e))?,
+                settlement: env::var("CONFIRMATION_SETTLEMENT")
+                    .unwrap_or_else(|_| "finalized".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid CONFIRMATION_SETTLEMENT: {}", e))?,
+                transfer: env::var("CONFIRMATION_TRANSFER")
+                    .unwrap_or_else(|_| "confirmed".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid CONFIRMATION_TRANSFER: {}", e))?,
+                other: env::var("CONFIRMATION_DEFAULT")
+                    .unwrap_or_else(|_| "confirmed".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid CONFIRMATION_DEFAULT: {}", e))?,
+                timeout_secs: env::var("CONFIRMATION_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid CONFIRMATION_TIMEOUT_SECS: {}", e))?,
+            },
+            submission_queue: SubmissionQueueConfig {
+                max_in_flight: env::var("TX_QUEUE_MAX_IN_FLIGHT")
+                    .unwrap_or_else(|_| "8".to_string())
+                    .parse::<usize>()
+                    .map_err(|e| anyhow::anyhow!("Invalid TX_QUEUE_MAX_IN_FLIGHT: {}", e))?
+                    .max(1),
+                settlement_limit: env::var("TX_QUEUE_SETTLEMENT_LIMIT")
+                    .unwrap_or_else(|_| "4".to_string())
+                    .parse::<usize>()
+                    .map_err(|e| anyhow::anyhow!("Invalid TX_QUEUE_SETTLEMENT_LIMIT: {}", e))?
+                    .max(1),
+                mint_limit: env::var("TX_QUEUE_MINT_LIMIT")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse::<usize>()
+                    .map_err(|e| anyhow::anyhow!("Invalid TX_QUEUE_MINT_LIMIT: {}", e))?
+                    .max(1),
+                transfer_limit: env::var("TX_QUEUE_TRANSFER_LIMIT")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse::<usize>()
+                    .map_err(|e| anyhow::anyhow!("Invalid TX_QUEUE_TRANSFER_LIMIT: {}", e))?
+                    .max(1),
+                other_limit: env::var("TX_QUEUE_OTHER_LIMIT")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse::<usize>()
+                    .map_err(|e| anyhow::anyhow!("Invalid TX_QUEUE_OTHER_LIMIT: {}", e))?
+                    .max(1),
+                test_limit: env::var("TX_QUEUE_TEST_LIMIT")
+                    .unwrap_or_else(|_| "1".to_string())
+                    .parse::<usize>()
+                    .map_err(|e| anyhow::anyhow!("Invalid TX_QUEUE_TEST_LIMIT: {}", e))?
+                    .max(1),
+            },
+            startup: StartupConfig {
+                retry_attempts: env::var("STARTUP_RETRY_ATTEMPTS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse::<u32>()
+                    .map_err(|e| anyhow::anyhow!("Invalid STARTUP_RETRY_ATTEMPTS: {}", e))?
+                    .max(1),
+                retry_base_delay_ms: env::var("STARTUP_RETRY_DELAY_MS")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid STARTUP_RETRY_DELAY_MS: {}", e))?,
+                solana_rpc: env::var("STARTUP_POLICY_SOLANA_RPC")
+                    .unwrap_or_else(|_| "degraded".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid STARTUP_POLICY_SOLANA_RPC: {}", e))?,
+                email: env::var("STARTUP_POLICY_EMAIL")
+                    .unwrap_or_else(|_| "degraded".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid STARTUP_POLICY_EMAIL: {}", e))?,
+                authority_wallet: env::var("STARTUP_POLICY_AUTHORITY_WALLET")
+                    .unwrap_or_else(|_| "degraded".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid STARTUP_POLICY_AUTHORITY_WALLET: {}", e))?,
+                schema_check: env::var("STARTUP_POLICY_SCHEMA_CHECK")
+                    .unwrap_or_else(|_| "fail_fast".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid STARTUP_POLICY_SCHEMA_CHECK: {}", e))?,
+            },
+            docs: DocsConfig {
+                exposure: {
+                    let env_name = env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string());
+                    let default = if env_name == "production" { "disabled" } else { "public" };
+                    env::var("API_DOCS")
+                        .unwrap_or_else(|_| default.to_string())
+                        .parse()
+                        .map_err(|e| anyhow::anyhow!("Invalid API_DOCS: {}", e))?
+                },
+            },
+            audit_buffer: AuditBufferConfig {
+                max_events: env::var("AUDIT_BUFFER_MAX_EVENTS")
+                    .unwrap_or_else(|_| "10000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_BUFFER_MAX_EVENTS: {}", e))?,
+                flush_interval_secs: env::var("AUDIT_BUFFER_FLUSH_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse::<u64>()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_BUFFER_FLUSH_INTERVAL_SECS: {}", e))?
+                    .max(1),
+                flush_batch: env::var("AUDIT_BUFFER_FLUSH_BATCH")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse::<usize>()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_BUFFER_FLUSH_BATCH: {}", e))?
+                    .max(1),
+            },
+            geoip: GeoIpConfig {
+                city_db_path: env::var("GEOIP_CITY_DB").ok().filter(|s| !s.is_empty()),
+                asn_db_path: env::var("GEOIP_ASN_DB").ok().filter(|s| !s.is_empty()),
+                max_travel_kmh: env::var("GEOIP_MAX_TRAVEL_KMH")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid GEOIP_MAX_TRAVEL_KMH: {}", e))?,
+            },
+            public_api: PublicApiConfig {
+                rate_limit_per_minute: env::var("PUBLIC_API_RATE_LIMIT_PER_MINUTE")
+                    .unwrap_or_else(|_| crate::constants::rate_limit::MAX_REQUESTS_PER_IP.to_string())
+                    .parse::<u32>()
+                    .map_err(|e| anyhow::anyhow!("Invalid PUBLIC_API_RATE_LIMIT_PER_MINUTE: {}", e))?
+                    .max(1),
+                live_cache_secs: env::var("PUBLIC_API_LIVE_CACHE_SECS")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse::<u64>()
+                    .map_err(|e| anyhow::anyhow!("Invalid PUBLIC_API_LIVE_CACHE_SECS: {}", e))?
+                    .max(1),
+                history_cache_secs: env::var("PUBLIC_API_HISTORY_CACHE_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse::<u64>()
+                    .map_err(|e| anyhow::anyhow!("Invalid PUBLIC_API_HISTORY_CACHE_SECS: {}", e))?
+                    .max(1),
+                participant_id_rotation_hours: env::var("PUBLIC_PARTICIPANT_ID_ROTATION_HOURS")
+                    .unwrap_or_else(|_| "24".to_string())
+                    .parse::<i64>()
+                    .map_err(|e| anyhow::anyhow!("Invalid PUBLIC_PARTICIPANT_ID_ROTATION_HOURS: {}", e))?
+                    .max(1),
+                participant_id_secret: env::var("PUBLIC_PARTICIPANT_ID_SECRET").ok().filter(|v| !v.is_empty()),
+            },
+            emissions: EmissionsConfig {
+                region: env::var("EMISSION_REGION")
+                    .unwrap_or_else(|_| "TH".to_string())
+                    .trim()
+                    .to_uppercase(),
+                fallback_kg_per_kwh: env::var("EMISSION_FALLBACK_KG_PER_KWH")
+                    .unwrap_or_else(|_| "0.431".to_string())
+                    .parse::<f64>()
+                    .map_err(|e| anyhow::anyhow!("Invalid EMISSION_FALLBACK_KG_PER_KWH: {}", e))?
+                    .max(0.0),
+            },
+            audit_retention: AuditRetentionConfig {
+                enabled: env::var("AUDIT_RETENTION_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_RETENTION_ENABLED: {}", e))?,
+                security_days: env::var("AUDIT_RETENTION_SECURITY_DAYS")
+                    .unwrap_or_else(|_| "365".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_RETENTION_SECURITY_DAYS: {}", e))?,
+                account_days: env::var("AUDIT_RETENTION_ACCOUNT_DAYS")
+                    .unwrap_or_else(|_| "730".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_RETENTION_ACCOUNT_DAYS: {}", e))?,
+                trading_days: env::var("AUDIT_RETENTION_TRADING_DAYS")
+                    .unwrap_or_else(|_| "2555".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_RETENTION_TRADING_DAYS: {}", e))?,
+                financial_days: env::var("AUDIT_RETENTION_FINANCIAL_DAYS")
+                    .unwrap_or_else(|_| "2555".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_RETENTION_FINANCIAL_DAYS: {}", e))?,
+                admin_days: env::var("AUDIT_RETENTION_ADMIN_DAYS")
+                    .unwrap_or_else(|_| "2555".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_RETENTION_ADMIN_DAYS: {}", e))?,
+                wallet_days: env::var("AUDIT_RETENTION_WALLET_DAYS")
+                    .unwrap_or_else(|_| "730".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_RETENTION_WALLET_DAYS: {}", e))?,
+                other_days: env::var("AUDIT_RETENTION_OTHER_DAYS")
+                    .unwrap_or_else(|_| "365".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid AUDIT_RETENTION_OTHER_DAYS: {}", e))?,
+            },
+            currency: currency_from_env()?,
+            pii: PiiConfig {
+                kms_provider: env::var("PII_KMS_PROVIDER")
+                    .unwrap_or_else(|_| "local".to_string())
+                    .to_lowercase(),
+                master_key: env::var("PII_MASTER_KEY").ok().filter(|v| !v.is_empty()),
+                vault_addr: env::var("VAULT_ADDR").unwrap_or_else(|_| "http://127.0.0.1:8200".to_string()),
+                vault_token: env::var("VAULT_TOKEN").ok().filter(|v| !v.is_empty()),
+                vault_key_name: env::var("PII_VAULT_KEY_NAME").unwrap_or_else(|_| "gridtokenx-pii".to_string()),
+                rotation_interval_secs: env::var("PII_ROTATION_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid PII_ROTATION_INTERVAL_SECS: {}", e))?,
+                rotation_batch_size: env::var("PII_ROTATION_BATCH_SIZE")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid PII_ROTATION_BATCH_SIZE: {}", e))?,
+                key_max_age_days: env::var("PII_KEY_MAX_AGE_DAYS")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid PII_KEY_MAX_AGE_DAYS: {}", e))?,
+            },
+            step_up: StepUpConfig {
+                enabled: env::var("STEP_UP_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid STEP_UP_ENABLED: {}", e))?,
+                code_ttl_secs: env::var("STEP_UP_CODE_TTL_SECS")
+                    .unwrap_or_else(|_| "600".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid STEP_UP_CODE_TTL_SECS: {}", e))?,
+                max_attempts: env::var("STEP_UP_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid STEP_UP_MAX_ATTEMPTS: {}", e))?,
+                history_days: env::var("STEP_UP_HISTORY_DAYS")
+                    .unwrap_or_else(|_| "90".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid STEP_UP_HISTORY_DAYS: {}", e))?,
+            },
+            deployment: DeploymentConfig {
+                profile: env::var("DEPLOYMENT_PROFILE")
+                    .unwrap_or_else(|_| "gridtokenx".to_string())
+                    .trim()
+                    .to_lowercase(),
+                brand_name: env::var("BRAND_NAME").unwrap_or_else(|_| "GridTokenX".to_string()),
+                support_email: env::var("BRAND_SUPPORT_EMAIL")
+                    .unwrap_or_else(|_| "support@gridtokenx.com".to_string()),
+                logo_url: env::var("BRAND_LOGO_URL").ok().filter(|v| !v.is_empty()),
+                primary_color: env::var("BRAND_PRIMARY_COLOR").unwrap_or_else(|_| "#10b981".to_string()),
+                features: match env::var("DEPLOYMENT_FEATURES") {
+                    Ok(list) => list
+                        .split(',')
+                        .filter(|s| !s.trim().is_empty())
+                        .map(str::parse)
+                        .collect::<Result<Vec<Feature>>>()
+                        .map_err(|e| anyhow::anyhow!("Invalid DEPLOYMENT_FEATURES: {}", e))?,
+                    Err(_) => Feature::ALL.to_vec(),
+                },
+                timezone: env::var("DEPLOYMENT_TIMEZONE").unwrap_or_else(|_| "Asia/Bangkok".to_string()),
+                utc_offset_minutes: deployment_utc_offset()?,
+            },
+            meter_fleet: MeterFleetConfig {
+                stale_after_mins: env::var("METER_FLEET_STALE_AFTER_MINS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid METER_FLEET_STALE_AFTER_MINS: {}", e))?,
+                offline_after_hours: env::var("METER_FLEET_OFFLINE_AFTER_HOURS")
+                    .unwrap_or_else(|_| "24".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid METER_FLEET_OFFLINE_AFTER_HOURS: {}", e))?,
+                expected_interval_mins: env::var("METER_FLEET_EXPECTED_INTERVAL_MINS")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid METER_FLEET_EXPECTED_INTERVAL_MINS: {}", e))?,
+                retention_days: env::var("METER_FLEET_RETENTION_DAYS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid METER_FLEET_RETENTION_DAYS: {}", e))?,
+            },
+            wallet_session: WalletSessionConfig {
+                idle_timeout_hours: env::var("WALLET_SESSION_IDLE_TIMEOUT_HOURS")
+                    .unwrap_or_else(|_| "72".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid WALLET_SESSION_IDLE_TIMEOUT_HOURS: {}", e))?,
+                max_lifetime_days: env::var("WALLET_SESSION_MAX_LIFETIME_DAYS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid WALLET_SESSION_MAX_LIFETIME_DAYS: {}", e))?,
+                max_concurrent: env::var("WALLET_SESSION_MAX_CONCURRENT")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid WALLET_SESSION_MAX_CONCURRENT: {}", e))?,
+            },
+            fee_sponsorship: FeeSponsorshipConfig {
+                enabled: env::var("FEE_SPONSORSHIP_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid FEE_SPONSORSHIP_ENABLED: {}", e))?,
+                free_transfers: env::var("FEE_SPONSORSHIP_FREE_TRANSFERS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid FEE_SPONSORSHIP_FREE_TRANSFERS: {}", e))?,
+                user_monthly_lamports: env::var("FEE_SPONSORSHIP_USER_MONTHLY_LAMPORTS")
+                    .unwrap_or_else(|_| "100000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid FEE_SPONSORSHIP_USER_MONTHLY_LAMPORTS: {}", e))?,
+                daily_transfer_limit: env::var("FEE_SPONSORSHIP_DAILY_TRANSFER_LIMIT")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid FEE_SPONSORSHIP_DAILY_TRANSFER_LIMIT: {}", e))?,
+                daily_budget_lamports: env::var("FEE_SPONSORSHIP_DAILY_BUDGET_LAMPORTS")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid FEE_SPONSORSHIP_DAILY_BUDGET_LAMPORTS: {}", e))?,
+                min_transfer_kwh: env::var("FEE_SPONSORSHIP_MIN_TRANSFER_KWH")
+                    .unwrap_or_else(|_| "0.1".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid FEE_SPONSORSHIP_MIN_TRANSFER_KWH: {}", e))?,
+                fee_estimate_lamports: env::var("FEE_SPONSORSHIP_FEE_ESTIMATE_LAMPORTS")
+                    .unwrap_or_else(|_| "10000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid FEE_SPONSORSHIP_FEE_ESTIMATE_LAMPORTS: {}", e))?,
+            },
+            report_subscriptions: ReportSubscriptionConfig {
+                enabled: env::var("REPORT_SUBSCRIPTIONS_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid REPORT_SUBSCRIPTIONS_ENABLED: {}", e))?,
+                interval_secs: env::var("REPORT_SUBSCRIPTIONS_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid REPORT_SUBSCRIPTIONS_INTERVAL_SECS: {}", e))?,
+                max_per_user: env::var("REPORT_SUBSCRIPTIONS_MAX_PER_USER")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid REPORT_SUBSCRIPTIONS_MAX_PER_USER: {}", e))?,
+                max_failures: env::var("REPORT_SUBSCRIPTIONS_MAX_FAILURES")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid REPORT_SUBSCRIPTIONS_MAX_FAILURES: {}", e))?,
+            },
+            jobs: JobsConfig {
+                retention_days: env::var("JOBS_RETENTION_DAYS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid JOBS_RETENTION_DAYS: {}", e))?,
+                stale_after_mins: env::var("JOBS_STALE_AFTER_MINS")
+                    .unwrap_or_else(|_| "360".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid JOBS_STALE_AFTER_MINS: {}", e))?,
+            },
+            mint_guard: MintGuardConfig {
+                enabled: env::var("MINT_GUARD_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MINT_GUARD_ENABLED: {}", e))?,
+                mode: env::var("MINT_GUARD_MODE")
+                    .unwrap_or_else(|_| "quarantine".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MINT_GUARD_MODE: {}", e))?,
+                default_capacity_kw: env::var("MINT_GUARD_DEFAULT_CAPACITY_KW")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MINT_GUARD_DEFAULT_CAPACITY_KW: {}", e))?,
+                hourly_headroom: env::var("MINT_GUARD_HOURLY_HEADROOM")
+                    .unwrap_or_else(|_| "1.2".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MINT_GUARD_HOURLY_HEADROOM: {}", e))?,
+                daily_full_load_hours: env::var("MINT_GUARD_DAILY_FULL_LOAD_HOURS")
+                    .unwrap_or_else(|_| "12".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid MINT_GUARD_DAILY_FULL_LOAD_HOURS: {}", e))?,
+            },
+            degradation: DegradationConfig {
+                failure_threshold: env::var("DEGRADATION_FAILURE_THRESHOLD")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DEGRADATION_FAILURE_THRESHOLD: {}", e))?,
+                probe_interval_secs: env::var("DEGRADATION_PROBE_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DEGRADATION_PROBE_INTERVAL_SECS: {}", e))?,
+                email_queue_capacity: env::var("DEGRADATION_EMAIL_QUEUE_CAPACITY")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DEGRADATION_EMAIL_QUEUE_CAPACITY: {}", e))?,
+            },
+            data_exports: DataExportConfig {
+                storage_dir: env::var("DATA_EXPORT_STORAGE_DIR")
+                    .unwrap_or_else(|_| "./data/exports".to_string()),
+                signed_urls: signed_urls_from_env("DATA_EXPORT", 900)?,
+                daily_quota: env::var("DATA_EXPORT_DAILY_QUOTA")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DATA_EXPORT_DAILY_QUOTA: {}", e))?,
+                max_pending: env::var("DATA_EXPORT_MAX_PENDING")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DATA_EXPORT_MAX_PENDING: {}", e))?,
+                max_rows: env::var("DATA_EXPORT_MAX_ROWS")
+                    .unwrap_or_else(|_| "2000000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DATA_EXPORT_MAX_ROWS: {}", e))?,
+                max_range_days: env::var("DATA_EXPORT_MAX_RANGE_DAYS")
+                    .unwrap_or_else(|_| "366".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DATA_EXPORT_MAX_RANGE_DAYS: {}", e))?,
+                retention_days: env::var("DATA_EXPORT_RETENTION_DAYS")
+                    .unwrap_or_else(|_| "7".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DATA_EXPORT_RETENTION_DAYS: {}", e))?,
+                interval_secs: env::var("DATA_EXPORT_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid DATA_EXPORT_INTERVAL_SECS: {}", e))?,
+            },
+            broadcast_throttle: {
+                let throttle = BroadcastThrottleConfig {
+                    grid_status_ms: env::var("WS_GRID_STATUS_WINDOW_MS")
+                        .unwrap_or_else(|_| "500".to_string())
+                        .parse()
+                        .map_err(|e| anyhow::anyhow!("Invalid WS_GRID_STATUS_WINDOW_MS: {}", e))?,
+                    order_book_ms: env::var("WS_ORDER_BOOK_WINDOW_MS")
+                        .unwrap_or_else(|_| "100".to_string())
+                        .parse()
+                        .map_err(|e| anyhow::anyhow!("Invalid WS_ORDER_BOOK_WINDOW_MS: {}", e))?,
+                    market_stats_ms: env::var("WS_MARKET_STATS_WINDOW_MS")
+                        .unwrap_or_else(|_| "1000".to_string())
+                        .parse()
+                        .map_err(|e| anyhow::anyhow!("Invalid WS_MARKET_STATS_WINDOW_MS: {}", e))?,
+                };
+                throttle.validate().map_err(|e| anyhow::anyhow!("Invalid WebSocket throttle: {}", e))?;
+                throttle
+            },
+            query_governor: QueryGovernorConfig {
+                analytics_timeout_ms: env::var("QUERY_ANALYTICS_TIMEOUT_MS")
+                    .unwrap_or_else(|_| "10000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid QUERY_ANALYTICS_TIMEOUT_MS: {}", e))?,
+                audit_timeout_ms: env::var("QUERY_AUDIT_TIMEOUT_MS")
+                    .unwrap_or_else(|_| "15000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid QUERY_AUDIT_TIMEOUT_MS: {}", e))?,
+                export_timeout_ms: env::var("QUERY_EXPORT_TIMEOUT_MS")
+                    .unwrap_or_else(|_| "30000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid QUERY_EXPORT_TIMEOUT_MS: {}", e))?,
+                analytics_max_rows: env::var("QUERY_ANALYTICS_MAX_ROWS")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid QUERY_ANALYTICS_MAX_ROWS: {}", e))?,
+                audit_max_rows: env::var("QUERY_AUDIT_MAX_ROWS")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid QUERY_AUDIT_MAX_ROWS: {}", e))?,
+                slow_query_ms: env::var("SLOW_QUERY_MS")
+                    .unwrap_or_else(|_| "2000".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid SLOW_QUERY_MS: {}", e))?,
+            },
+            warmup: WarmupConfig {
+                enabled: env::var("WARMUP_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid WARMUP_ENABLED: {}", e))?,
+                timeout_secs: env::var("WARMUP_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid WARMUP_TIMEOUT_SECS: {}", e))?,
+                db_connections: env::var("WARMUP_DB_CONNECTIONS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid WARMUP_DB_CONNECTIONS: {}", e))?,
+            },
+            concurrency_limits: ConcurrencyLimitConfig {
+                export_max_in_flight: env::var("CONCURRENCY_EXPORT_MAX_IN_FLIGHT")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid CONCURRENCY_EXPORT_MAX_IN_FLIGHT: {}", e))?,
+                analytics_max_in_flight: env::var("CONCURRENCY_ANALYTICS_MAX_IN_FLIGHT")
+                    .unwrap_or_else(|_| "4".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid CONCURRENCY_ANALYTICS_MAX_IN_FLIGHT: {}", e))?,
+                orders_max_in_flight: env::var("CONCURRENCY_ORDERS_MAX_IN_FLIGHT")
+                    .unwrap_or_else(|_| "8".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid CONCURRENCY_ORDERS_MAX_IN_FLIGHT: {}", e))?,
+                queue_timeout_ms: env::var("CONCURRENCY_QUEUE_TIMEOUT_MS")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid CONCURRENCY_QUEUE_TIMEOUT_MS: {}", e))?,
+            },
             simulator_user_id: env::var("SIMULATOR_USER_ID")
                 .unwrap_or_else(|_| "63c1d015-6765-4843-9ca3-5ba21ee54d7e".to_string()),
             encryption_secret: env::var("ENCRYPTION_SECRET").map_err(|_| {
@@ -224,3 +2182,212 @@ impl Config {
         })
     }
 }
+
+/// Read settlement currency and FX settings (`SETTLEMENT_ASSET`, `STABLECOIN_*`, `FX_*`)
+fn currency_from_env() -> Result<CurrencyConfig> {
+    let config = CurrencyConfig {
+        display_currency: env::var("FIAT_DISPLAY_CURRENCY")
+            .unwrap_or_else(|_| "THB".to_string())
+            .trim()
+            .to_uppercase(),
+        settlement_asset: env::var("SETTLEMENT_ASSET")
+            .unwrap_or_else(|_| "token".to_string())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid SETTLEMENT_ASSET: {}", e))?,
+        currency_token_mint: env::var("CURRENCY_TOKEN_MINT")
+            .unwrap_or_else(|_| "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU".to_string()),
+        stablecoin_mint: env::var("STABLECOIN_MINT").ok().filter(|v| !v.trim().is_empty()),
+        stablecoin_decimals: env::var("STABLECOIN_DECIMALS")
+            .unwrap_or_else(|_| "6".to_string())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid STABLECOIN_DECIMALS: {}", e))?,
+        fx_max_age_secs: env::var("FX_RATE_MAX_AGE_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid FX_RATE_MAX_AGE_SECS: {}", e))?,
+        fx_fallback_rate: match env::var("FX_FALLBACK_RATE") {
+            Ok(v) if !v.trim().is_empty() => Some(
+                v.trim()
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid FX_FALLBACK_RATE: {}", e))?,
+            ),
+            _ => None,
+        },
+    };
+
+    if config.display_currency.len() != 3 || !config.display_currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        anyhow::bail!("FIAT_DISPLAY_CURRENCY must be a three-letter ISO code");
+    }
+    if config.settlement_asset == SettlementAsset::Stablecoin && config.stablecoin_mint.is_none() {
+        anyhow::bail!("STABLECOIN_MINT is required when SETTLEMENT_ASSET=stablecoin");
+    }
+    if config.fx_fallback_rate.is_some_and(|r| r <= Decimal::ZERO) {
+        anyhow::bail!("FX_FALLBACK_RATE must be positive");
+    }
+
+    Ok(config)
+}
+
+/// Read a comma-separated list of CIDRs (bare addresses are accepted as single hosts)
+fn cidr_list_from_env(var: &str, default: &str) -> Result<Vec<String>> {
+    env::var(var)
+        .unwrap_or_else(|_| default.to_string())
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<ipnetwork::IpNetwork>()
+                .map(|net| net.to_string())
+                .map_err(|e| anyhow::anyhow!("Invalid {} entry '{}': {}", var, s, e))
+        })
+        .collect()
+}
+
+/// Read `<PREFIX>_URL_SECRET` (falling back to `JWT_SECRET`),
+/// `<PREFIX>_URL_TTL_SECS` and the shared `PUBLIC_API_BASE_URL`
+fn signed_urls_from_env(prefix: &str, default_ttl_secs: i64) -> Result<SignedUrlConfig> {
+    let secret_var = format!("{}_URL_SECRET", prefix);
+    let ttl_var = format!("{}_URL_TTL_SECS", prefix);
+    Ok(SignedUrlConfig {
+        url_signing_secret: env::var(&secret_var)
+            .or_else(|_| env::var("JWT_SECRET"))
+            .map_err(|_| anyhow::anyhow!("{} or JWT_SECRET environment variable is required", secret_var))?,
+        url_ttl_secs: match env::var(&ttl_var) {
+            Ok(ttl) => ttl
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid {}: {}", ttl_var, e))?,
+            Err(_) => default_ttl_secs,
+        },
+        public_base_url: env::var("PUBLIC_API_BASE_URL")
+            .unwrap_or_else(|_| "http://localhost:4000".to_string()),
+    })
+}
+
+/// Read `REQUEST_SIGNING_CLIENTS` as `key_id:role:secret` entries, comma-separated
+fn signing_clients_from_env() -> Result<Vec<SigningClient>> {
+    env::var("REQUEST_SIGNING_CLIENTS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let mut parts = entry.splitn(3, ':');
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(key_id), Some(role), Some(secret))
+                    if !key_id.is_empty() && !role.is_empty() && secret.len() >= 32 =>
+                {
+                    Ok(SigningClient {
+                        key_id: key_id.to_string(),
+                        role: role.to_lowercase(),
+                        secret: secret.to_string(),
+                    })
+                }
+                _ => Err(anyhow::anyhow!(
+                    "Invalid REQUEST_SIGNING_CLIENTS entry (expected key_id:role:secret with a secret of at least 32 characters)"
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Read `ORDER_OVERSUBSCRIPTION_POLICY` as `role:percent` entries, comma-separated
+fn oversubscription_policy_from_env() -> Result<HashMap<String, Decimal>> {
+    env::var("ORDER_OVERSUBSCRIPTION_POLICY")
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let (role, pct) = entry.split_once(':').ok_or_else(|| {
+                anyhow::anyhow!("Invalid ORDER_OVERSUBSCRIPTION_POLICY entry '{}' (expected role:percent)", entry)
+            })?;
+            let pct: Decimal = pct
+                .trim()
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid ORDER_OVERSUBSCRIPTION_POLICY percent for '{}': {}", role, e))?;
+            if pct < Decimal::ZERO {
+                anyhow::bail!("ORDER_OVERSUBSCRIPTION_POLICY percent for '{}' must not be negative", role);
+            }
+            Ok((role.trim().to_lowercase(), pct))
+        })
+        .collect()
+}
+
+/// Read the trading calendar (`MARKET_SESSION_*`, `MARKET_OPEN_TIME`, ...)
+/// Market timezone offset: `DEPLOYMENT_UTC_OFFSET_MINUTES`, else the older
+/// `MARKET_UTC_OFFSET_MINUTES`, else Bangkok time
+fn deployment_utc_offset() -> Result<i32> {
+    let offset: i32 = env::var("DEPLOYMENT_UTC_OFFSET_MINUTES")
+        .or_else(|_| env::var("MARKET_UTC_OFFSET_MINUTES"))
+        .unwrap_or_else(|_| MarketSessionConfig::default().utc_offset_minutes.to_string())
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid DEPLOYMENT_UTC_OFFSET_MINUTES: {}", e))?;
+    if offset.abs() >= 24 * 60 {
+        anyhow::bail!("DEPLOYMENT_UTC_OFFSET_MINUTES must be within ±1439");
+    }
+    Ok(offset)
+}
+
+fn market_session_from_env() -> Result<MarketSessionConfig> {
+    let defaults = MarketSessionConfig::default();
+    let time = |var: &str, default: NaiveTime| -> Result<NaiveTime> {
+        match env::var(var) {
+            Ok(v) => NaiveTime::parse_from_str(v.trim(), "%H:%M")
+                .map_err(|e| anyhow::anyhow!("Invalid {} (expected HH:MM): {}", var, e)),
+            Err(_) => Ok(default),
+        }
+    };
+
+    let config = MarketSessionConfig {
+        enabled: env::var("MARKET_SESSION_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid MARKET_SESSION_ENABLED: {}", e))?,
+        utc_offset_minutes: match env::var("MARKET_UTC_OFFSET_MINUTES") {
+            Ok(v) => v
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid MARKET_UTC_OFFSET_MINUTES: {}", e))?,
+            Err(_) => deployment_utc_offset()?,
+        },
+        open_time: time("MARKET_OPEN_TIME", defaults.open_time)?,
+        close_time: time("MARKET_CLOSE_TIME", defaults.close_time)?,
+        pre_open_minutes: env::var("MARKET_PRE_OPEN_MINUTES")
+            .unwrap_or_else(|_| defaults.pre_open_minutes.to_string())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid MARKET_PRE_OPEN_MINUTES: {}", e))?,
+        trading_days: match env::var("MARKET_TRADING_DAYS") {
+            Ok(v) => v
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    s.parse::<Weekday>()
+                        .map_err(|_| anyhow::anyhow!("Invalid MARKET_TRADING_DAYS entry '{}'", s))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Err(_) => defaults.trading_days,
+        },
+        holidays: env::var("MARKET_HOLIDAYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map_err(|e| anyhow::anyhow!("Invalid MARKET_HOLIDAYS entry '{}': {}", s, e))
+            })
+            .collect::<Result<Vec<_>>>()?,
+    };
+
+    if config.close_time <= config.open_time {
+        anyhow::bail!("MARKET_CLOSE_TIME must be later than MARKET_OPEN_TIME");
+    }
+    if config.pre_open_minutes < 0 {
+        anyhow::bail!("MARKET_PRE_OPEN_MINUTES must not be negative");
+    }
+    if config.utc_offset_minutes.abs() >= 24 * 60 {
+        anyhow::bail!("MARKET_UTC_OFFSET_MINUTES must be within ±1439");
+    }
+
+    Ok(config)
+}
@@ -1,10 +1,12 @@
 use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::env;
 use tracing::{info, warn};
+use utoipa::ToSchema;
 
 /// Configuration for smart meter tokenization
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TokenizationConfig {
     /// Conversion ratio from kWh to tokens (default: 1.0)
     pub kwh_to_token_ratio: f64,
@@ -18,6 +20,16 @@ pub struct TokenizationConfig {
     /// Maximum age of a reading in days before it's too old to process (default: 7)
     pub reading_max_age_days: i64,
 
+    /// Device clock drift in seconds accepted without correction (default: 120)
+    pub clock_skew_tolerance_secs: i64,
+
+    /// How far in the future (seconds) a reading timestamp may be before rejection (default: 900)
+    pub max_future_skew_secs: i64,
+
+    /// Replace out-of-tolerance device timestamps with server receipt time (default: false)
+    /// The original device timestamp is always stored alongside the effective one
+    pub correct_clock_skew: bool,
+
     /// Whether automatic minting is enabled (default: true)
     pub auto_mint_enabled: bool,
 
@@ -42,7 +54,7 @@ pub struct TokenizationConfig {
     /// Timeout in seconds for blockchain transaction confirmation (default: 60)
     pub transaction_timeout_secs: u64,
 
-    /// Maximum number of transactions per batch (default: 20)
+    /// Maximum number of transactions per batch, at least `batch_size` (default: 50)
     pub max_transactions_per_batch: usize,
 
     /// Whether to use real blockchain transactions or mocks (default: false)
@@ -60,6 +72,9 @@ impl Default for TokenizationConfig {
             decimals: 9,
             max_reading_kwh: 100.0,
             reading_max_age_days: 7,
+            clock_skew_tolerance_secs: 120,
+            max_future_skew_secs: 900,
+            correct_clock_skew: false,
             auto_mint_enabled: true,
             polling_interval_secs: 60,
             batch_size: 50,
@@ -68,7 +83,7 @@ impl Default for TokenizationConfig {
             retry_backoff_multiplier: 2.0,
             max_retry_delay_secs: 3600, // 1 hour
             transaction_timeout_secs: 60,
-            max_transactions_per_batch: 20,
+            max_transactions_per_batch: 50,
             enable_real_blockchain: true, // Default to true for integration
             use_onchain_balance_for_escrow: false, // Default to DB balance check for compatibility
         }
@@ -140,6 +155,50 @@ impl TokenizationConfig {
             }
         }
 
+        if let Ok(val) = env::var("TOKENIZATION_CLOCK_SKEW_TOLERANCE_SECS") {
+            match val.parse::<i64>() {
+                Ok(secs) if secs >= 0 => {
+                    config.clock_skew_tolerance_secs = secs;
+                    info!("Using custom clock skew tolerance seconds: {}", secs);
+                }
+                Ok(_) => warn!(
+                    "Invalid clock skew tolerance seconds: {}, must be >= 0, using default",
+                    val
+                ),
+                Err(_) => warn!(
+                    "Failed to parse clock skew tolerance seconds: {}, using default",
+                    val
+                ),
+            }
+        }
+
+        if let Ok(val) = env::var("TOKENIZATION_MAX_FUTURE_SKEW_SECS") {
+            match val.parse::<i64>() {
+                Ok(secs) if secs >= 0 => {
+                    config.max_future_skew_secs = secs;
+                    info!("Using custom max future skew seconds: {}", secs);
+                }
+                Ok(_) => warn!(
+                    "Invalid max future skew seconds: {}, must be >= 0, using default",
+                    val
+                ),
+                Err(_) => warn!(
+                    "Failed to parse max future skew seconds: {}, using default",
+                    val
+                ),
+            }
+        }
+
+        if let Ok(val) = env::var("TOKENIZATION_CORRECT_CLOCK_SKEW") {
+            match val.parse::<bool>() {
+                Ok(enabled) => {
+                    config.correct_clock_skew = enabled;
+                    info!("Using clock skew correction: {}", enabled);
+                }
+                Err(_) => warn!("Failed to parse correct clock skew: {}, using default", val),
+            }
+        }
+
         if let Ok(val) = env::var("TOKENIZATION_AUTO_MINT_ENABLED") {
             match val.parse::<bool>() {
                 Ok(enabled) => {
@@ -281,6 +340,19 @@ impl TokenizationConfig {
             }
         }
 
+        if let Ok(val) = env::var("TOKENIZATION_USE_ONCHAIN_BALANCE_FOR_ESCROW") {
+            match val.parse::<bool>() {
+                Ok(enabled) => {
+                    config.use_onchain_balance_for_escrow = enabled;
+                    info!("Using on-chain balance for escrow checks: {}", enabled);
+                }
+                Err(_) => warn!(
+                    "Failed to parse use on-chain balance for escrow: {}, using default",
+                    val
+                ),
+            }
+        }
+
         if let Ok(val) = env::var("TOKENIZATION_ENABLE_REAL_BLOCKCHAIN") {
             match val.parse::<bool>() {
                 Ok(enabled) => {
@@ -294,28 +366,93 @@ impl TokenizationConfig {
             }
         }
 
-        // Validate configuration
-        if config.auto_mint_enabled && config.polling_interval_secs < 10 {
-            return Err(anyhow!(
-                "Polling interval must be at least 10 seconds when auto mint is enabled"
-            ));
-        }
+        config.validate().map_err(|e| anyhow!(e))?;
+        Ok(config)
+    }
 
-        if config.batch_size == 0 {
-            return Err(anyhow!("Batch size must be greater than 0"));
-        }
+    /// Check each parameter's range and the constraints between them,
+    /// reporting every violation at once
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
 
-        if config.decimals > 18 {
-            return Err(anyhow!("Token decimals cannot exceed 18"));
+        if !(self.kwh_to_token_ratio.is_finite() && self.kwh_to_token_ratio > 0.0) {
+            problems.push("kwh_to_token_ratio must be greater than 0".to_string());
+        }
+        if self.decimals > 18 {
+            problems.push("decimals cannot exceed 18".to_string());
+        }
+        if !(self.max_reading_kwh.is_finite() && self.max_reading_kwh > 0.0) {
+            problems.push("max_reading_kwh must be greater than 0".to_string());
+        }
+        if self.reading_max_age_days < 1 {
+            problems.push("reading_max_age_days must be at least 1".to_string());
+        }
+        if self.clock_skew_tolerance_secs < 0 {
+            problems.push("clock_skew_tolerance_secs cannot be negative".to_string());
+        }
+        if self.max_future_skew_secs < self.clock_skew_tolerance_secs {
+            problems.push("max_future_skew_secs must be at least clock_skew_tolerance_secs".to_string());
+        }
+        if self.auto_mint_enabled && self.polling_interval_secs < 10 {
+            problems.push(
+                "polling_interval_secs must be at least 10 when auto minting is enabled".to_string(),
+            );
+        }
+        if !(1..=1000).contains(&self.batch_size) {
+            problems.push("batch_size must be between 1 and 1000".to_string());
+        }
+        if self.max_transactions_per_batch == 0 {
+            problems.push("max_transactions_per_batch must be at least 1".to_string());
+        } else if self.batch_size > self.max_transactions_per_batch {
+            problems.push("batch_size cannot exceed max_transactions_per_batch".to_string());
+        }
+        if self.initial_retry_delay_secs < 1 {
+            problems.push("initial_retry_delay_secs must be at least 1".to_string());
+        }
+        if self.max_retry_delay_secs < self.initial_retry_delay_secs {
+            problems.push("max_retry_delay_secs must be at least initial_retry_delay_secs".to_string());
+        }
+        if !(self.retry_backoff_multiplier.is_finite() && self.retry_backoff_multiplier >= 1.0) {
+            problems.push("retry_backoff_multiplier must be at least 1.0".to_string());
+        }
+        if self.transaction_timeout_secs < 10 {
+            problems.push("transaction_timeout_secs must be at least 10".to_string());
         }
 
-        if config.max_retry_delay_secs < config.initial_retry_delay_secs {
-            return Err(anyhow!(
-                "Max retry delay must be at least the initial retry delay"
-            ));
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::IncompatibleValues(problems.join("; ")))
         }
+    }
 
-        Ok(config)
+    /// This configuration with `overrides` applied on top
+    pub fn with_overrides(&self, overrides: &TokenizationOverrides) -> Self {
+        let mut config = self.clone();
+        macro_rules! apply {
+            ($($field:ident),*) => {
+                $(if let Some(value) = overrides.$field {
+                    config.$field = value;
+                })*
+            };
+        }
+        apply!(
+            max_reading_kwh,
+            reading_max_age_days,
+            clock_skew_tolerance_secs,
+            max_future_skew_secs,
+            correct_clock_skew,
+            auto_mint_enabled,
+            polling_interval_secs,
+            batch_size,
+            max_retry_attempts,
+            initial_retry_delay_secs,
+            retry_backoff_multiplier,
+            max_retry_delay_secs,
+            transaction_timeout_secs,
+            max_transactions_per_batch
+        );
+        config
     }
 
     /// Convert kWh amount to token amount with decimals
@@ -357,6 +494,101 @@ impl TokenizationConfig {
     }
 }
 
+/// Tokenization parameters changeable at runtime; omitted fields keep their
+/// environment value. The token ratio, decimals and blockchain mode only
+/// change with a restart since they decide what already-minted tokens mean.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TokenizationOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_reading_kwh: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reading_max_age_days: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock_skew_tolerance_secs: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_future_skew_secs: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correct_clock_skew: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_mint_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub polling_interval_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retry_attempts: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_retry_delay_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_backoff_multiplier: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retry_delay_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_timeout_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_transactions_per_batch: Option<usize>,
+}
+
+impl TokenizationOverrides {
+    /// Names of the overridden parameters
+    pub fn fields(&self) -> Vec<String> {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(map)) => map.into_iter().map(|(key, _)| key).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Outcome of checking a device-reported reading timestamp against server time
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimestampAssessment {
+    /// Timestamp as reported by the meter
+    pub device_timestamp: DateTime<Utc>,
+    /// Timestamp to use for the reading (device or server receipt time)
+    pub effective_timestamp: DateTime<Utc>,
+    /// Device minus server time in seconds (positive = meter clock ahead)
+    pub drift_secs: i64,
+    /// Whether the drift is within the configured tolerance
+    pub within_tolerance: bool,
+    /// Whether the effective timestamp was replaced with receipt time
+    pub corrected: bool,
+}
+
+impl TokenizationConfig {
+    /// Validate a reading timestamp against server receipt time.
+    ///
+    /// Readings too far in the future or older than `reading_max_age_days` are
+    /// rejected. Drift beyond the tolerance is reported and, when
+    /// `correct_clock_skew` is enabled, replaced with the receipt time.
+    pub fn assess_reading_timestamp(
+        &self,
+        device_timestamp: DateTime<Utc>,
+        received_at: DateTime<Utc>,
+    ) -> Result<TimestampAssessment, ValidationError> {
+        let drift_secs = (device_timestamp - received_at).num_seconds();
+
+        if drift_secs > self.max_future_skew_secs {
+            return Err(ValidationError::ReadingInFuture(drift_secs));
+        }
+
+        if received_at - device_timestamp > Duration::days(self.reading_max_age_days) {
+            return Err(ValidationError::ReadingTooOld);
+        }
+
+        let within_tolerance = drift_secs.abs() <= self.clock_skew_tolerance_secs;
+        let corrected = !within_tolerance && self.correct_clock_skew;
+
+        Ok(TimestampAssessment {
+            device_timestamp,
+            effective_timestamp: if corrected { received_at } else { device_timestamp },
+            drift_secs,
+            within_tolerance,
+            corrected,
+        })
+    }
+}
+
 /// Errors that can occur during validation or conversion
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ValidationError {
@@ -378,6 +610,9 @@ pub enum ValidationError {
     #[error("Meter reading is too old")]
     ReadingTooOld,
 
+    #[error("Meter reading timestamp is {0}s in the future")]
+    ReadingInFuture(i64),
+
     #[error("Duplicate meter reading detected")]
     DuplicateReading,
 }
@@ -467,6 +702,78 @@ mod tests {
         assert!(config.calculate_retry_delay(large_attempt) <= config.max_retry_delay_secs);
     }
 
+    #[test]
+    fn test_reading_timestamp_assessment() {
+        let mut config = TokenizationConfig::default();
+        let now = Utc::now();
+
+        // Small drift is accepted as-is
+        let ok = config
+            .assess_reading_timestamp(now + Duration::seconds(30), now)
+            .expect("Small drift should be accepted");
+        assert!(ok.within_tolerance);
+        assert!(!ok.corrected);
+        assert_eq!(ok.drift_secs, 30);
+
+        // Out of tolerance but below the future limit is reported, not corrected by default
+        let skewed = config
+            .assess_reading_timestamp(now + Duration::seconds(600), now)
+            .expect("Drift below the future limit should be accepted");
+        assert!(!skewed.within_tolerance);
+        assert_eq!(skewed.effective_timestamp, now + Duration::seconds(600));
+
+        // Far future is rejected
+        assert!(matches!(
+            config.assess_reading_timestamp(now + Duration::hours(2), now),
+            Err(ValidationError::ReadingInFuture(_))
+        ));
+
+        // Too old is rejected
+        assert!(matches!(
+            config.assess_reading_timestamp(now - Duration::days(8), now),
+            Err(ValidationError::ReadingTooOld)
+        ));
+
+        // Correction replaces the effective timestamp but keeps the device one
+        config.correct_clock_skew = true;
+        let corrected = config
+            .assess_reading_timestamp(now - Duration::seconds(600), now)
+            .expect("Past drift should be accepted");
+        assert!(corrected.corrected);
+        assert_eq!(corrected.effective_timestamp, now);
+        assert_eq!(corrected.device_timestamp, now - Duration::seconds(600));
+    }
+
+    #[test]
+    fn test_validate_reports_cross_field_violations() {
+        assert!(TokenizationConfig::default().validate().is_ok());
+
+        let config = TokenizationConfig {
+            initial_retry_delay_secs: 600,
+            max_retry_delay_secs: 300,
+            batch_size: 100,
+            max_transactions_per_batch: 20,
+            ..Default::default()
+        };
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("max_retry_delay_secs"));
+        assert!(message.contains("max_transactions_per_batch"));
+    }
+
+    #[test]
+    fn test_overrides_apply_on_top() {
+        let overrides = TokenizationOverrides {
+            batch_size: Some(10),
+            correct_clock_skew: Some(true),
+            ..Default::default()
+        };
+        let config = TokenizationConfig::default().with_overrides(&overrides);
+        assert_eq!(config.batch_size, 10);
+        assert!(config.correct_clock_skew);
+        assert_eq!(config.max_reading_kwh, 100.0);
+        assert_eq!(overrides.fields(), vec!["batch_size", "correct_clock_skew"]);
+    }
+
     #[test]
     fn test_config_from_env() {
         // Clear any existing env vars first to ensure clean test state
@@ -130,6 +130,9 @@ pub mod energy {
     
     /// Energy token decimals
     pub const TOKEN_DECIMALS: u8 = 9;
+
+    /// Maximum decimal places for energy amounts and prices (NUMERIC(20, 8) columns)
+    pub const AMOUNT_DECIMAL_PLACES: u32 = 8;
     
     /// Standard epoch duration in minutes
     pub const EPOCH_DURATION_MINUTES: u32 = 15;
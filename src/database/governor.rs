@@ -0,0 +1,261 @@
+//! Query governor
+//!
+//! Guardrails for heavy analytics, audit and export queries. A governed
+//! query runs in a transaction with the statement timeout of its query
+//! class, its filter must include a predicate an index can serve, row
+//! counts are capped per class, and queries slower than `SLOW_QUERY_MS`
+//! are logged with the endpoint and filter parameters.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use metrics::counter;
+use sqlx::{PgConnection, PgPool, Postgres};
+use tracing::warn;
+
+use super::QueryFilter;
+use crate::config::QueryGovernorConfig;
+use crate::error::{ApiError, ErrorCode};
+
+/// SQLSTATE of a statement cancelled by `statement_timeout`
+const QUERY_CANCELED: &str = "57014";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryClass {
+    Analytics,
+    Audit,
+    Export,
+}
+
+impl QueryClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueryClass::Analytics => "analytics",
+            QueryClass::Audit => "audit",
+            QueryClass::Export => "export",
+        }
+    }
+}
+
+/// Predicates of which a governed filter must include at least one
+#[derive(Debug, Clone, Copy)]
+pub struct IndexedPredicates {
+    /// Indexed columns; an equality predicate on any of them suffices
+    pub columns: &'static [&'static str],
+    /// Indexed time column; a range on it suffices when it has a lower
+    /// bound and spans at most `max_range_days` (an open upper bound ends now)
+    pub time_column: Option<&'static str>,
+    pub max_range_days: i64,
+}
+
+impl IndexedPredicates {
+    pub fn check(&self, filter: &QueryFilter, now: DateTime<Utc>) -> Result<(), ApiError> {
+        if self.columns.iter().any(|column| filter.has_eq(column)) {
+            return Ok(());
+        }
+        if let Some(time_column) = self.time_column {
+            if let (Some(from), to) = filter.time_range(time_column) {
+                if (to.unwrap_or(now) - from).num_days() <= self.max_range_days {
+                    return Ok(());
+                }
+            }
+        }
+
+        let mut options: Vec<String> = self.columns.iter().map(|c| c.to_string()).collect();
+        if let Some(time_column) = self.time_column {
+            options.push(format!("a {} range of at most {} days", time_column, self.max_range_days));
+        }
+        Err(ApiError::Validation(format!(
+            "Filter too broad; filter by one of: {}",
+            options.join(", ")
+        )))
+    }
+}
+
+/// Statement timeout and row limit of a query class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryLimits {
+    pub timeout: Duration,
+    /// None when the class pages its results itself
+    pub max_rows: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryGovernor {
+    db: PgPool,
+    config: QueryGovernorConfig,
+}
+
+impl QueryGovernor {
+    pub fn new(db: PgPool, config: QueryGovernorConfig) -> Self {
+        Self { db, config }
+    }
+
+    pub fn limits(&self, class: QueryClass) -> QueryLimits {
+        let (timeout_ms, max_rows) = match class {
+            QueryClass::Analytics => (self.config.analytics_timeout_ms, Some(self.config.analytics_max_rows)),
+            QueryClass::Audit => (self.config.audit_timeout_ms, Some(self.config.audit_max_rows)),
+            QueryClass::Export => (self.config.export_timeout_ms, None),
+        };
+        QueryLimits {
+            timeout: Duration::from_millis(timeout_ms),
+            max_rows,
+        }
+    }
+
+    /// Requested row count capped at the class limit
+    pub fn row_limit(&self, class: QueryClass, requested: i64) -> i64 {
+        match self.limits(class).max_rows {
+            Some(max_rows) => requested.clamp(1, max_rows.max(1)),
+            None => requested,
+        }
+    }
+
+    /// Check the filter against the indexed predicates and open a
+    /// transaction with the statement timeout of the class. `endpoint` and
+    /// the filter are attached to the slow-query log.
+    pub async fn begin(
+        &self,
+        class: QueryClass,
+        endpoint: &'static str,
+        indexes: &IndexedPredicates,
+        filter: QueryFilter,
+    ) -> Result<GovernedQuery, ApiError> {
+        if let Err(e) = indexes.check(&filter, Utc::now()) {
+            counter!("query_governor_rejected_total", "endpoint" => endpoint).increment(1);
+            return Err(e);
+        }
+
+        let mut tx = self.db.begin().await?;
+        sqlx::query("SELECT set_config('statement_timeout', $1, true)")
+            .bind(format!("{}ms", self.limits(class).timeout.as_millis()))
+            .execute(&mut *tx)
+            .await?;
+
+        Ok(GovernedQuery {
+            tx,
+            class,
+            endpoint,
+            filter,
+            slow_after: Duration::from_millis(self.config.slow_query_ms),
+            started: Instant::now(),
+        })
+    }
+}
+
+/// Transaction of a governed query
+pub struct GovernedQuery {
+    tx: sqlx::Transaction<'static, Postgres>,
+    class: QueryClass,
+    endpoint: &'static str,
+    filter: QueryFilter,
+    slow_after: Duration,
+    started: Instant,
+}
+
+impl GovernedQuery {
+    pub fn conn(&mut self) -> &mut PgConnection {
+        &mut self.tx
+    }
+
+    /// End the transaction, log the query when it was slow and turn a
+    /// statement timeout into a `QUERY_TIMEOUT` error
+    pub async fn finish<T>(self, result: Result<T, sqlx::Error>) -> Result<T, ApiError> {
+        let elapsed = self.started.elapsed();
+        if elapsed >= self.slow_after {
+            counter!("query_governor_slow_total", "endpoint" => self.endpoint).increment(1);
+            warn!(
+                endpoint = self.endpoint,
+                class = self.class.as_str(),
+                elapsed_ms = elapsed.as_millis() as u64,
+                filter = %self.filter,
+                "🐢 Slow query"
+            );
+        }
+
+        match result {
+            Ok(value) => {
+                self.tx.commit().await?;
+                Ok(value)
+            }
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(QUERY_CANCELED) => {
+                counter!("query_governor_timeouts_total", "endpoint" => self.endpoint).increment(1);
+                warn!(
+                    endpoint = self.endpoint,
+                    class = self.class.as_str(),
+                    elapsed_ms = elapsed.as_millis() as u64,
+                    filter = %self.filter,
+                    "⏱️ Query cancelled by statement timeout"
+                );
+                Err(ApiError::with_code(
+                    ErrorCode::QueryTimeout,
+                    "Query exceeded its time limit; narrow the filters",
+                ))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+    use uuid::Uuid;
+
+    const INDEXES: IndexedPredicates = IndexedPredicates {
+        columns: &["user_id", "event_type"],
+        time_column: Some("created_at"),
+        max_range_days: 31,
+    };
+
+    #[test]
+    fn test_filter_predicates() {
+        let now = Utc::now();
+        let filter = QueryFilter::new()
+            .eq("status", "active")
+            .gt("amount", 100i64)
+            .is_not_null("email");
+
+        assert!(filter.has_eq("status"));
+        assert!(!filter.has_eq("amount"));
+        assert_eq!(filter.to_string(), "status = 'active' AND amount > 100 AND email IS NOT NULL");
+
+        let from = now - ChronoDuration::days(7);
+        let filter = QueryFilter::new().gte("created_at", from).lt("created_at", now);
+        assert_eq!(filter.time_range("created_at"), (Some(from), Some(now)));
+        assert_eq!(filter.time_range("updated_at"), (None, None));
+    }
+
+    #[test]
+    fn test_indexed_equality_passes() {
+        let now = Utc::now();
+        let filter = QueryFilter::new().eq("user_id", Uuid::new_v4()).eq("outcome", "failure");
+        assert!(INDEXES.check(&filter, now).is_ok());
+    }
+
+    #[test]
+    fn test_bounded_time_range_passes() {
+        let now = Utc::now();
+        let filter = QueryFilter::new().gte("created_at", now - ChronoDuration::days(7));
+        assert!(INDEXES.check(&filter, now).is_ok());
+
+        let filter = QueryFilter::new()
+            .gte("created_at", now - ChronoDuration::days(90))
+            .lt("created_at", now - ChronoDuration::days(60));
+        assert!(INDEXES.check(&filter, now).is_ok());
+    }
+
+    #[test]
+    fn test_unindexed_or_wide_filters_rejected() {
+        let now = Utc::now();
+        assert!(INDEXES.check(&QueryFilter::new(), now).is_err());
+        assert!(INDEXES.check(&QueryFilter::new().eq("outcome", "failure"), now).is_err());
+        assert!(INDEXES
+            .check(&QueryFilter::new().lt("created_at", now), now)
+            .is_err());
+        assert!(INDEXES
+            .check(&QueryFilter::new().gte("created_at", now - ChronoDuration::days(32)), now)
+            .is_err());
+    }
+}
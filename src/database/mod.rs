@@ -3,9 +3,12 @@ use sqlx::{PgPool, Pool, Postgres, postgres::PgPoolOptions};
 use tracing::{info, warn};
 use std::time::Duration;
 
+pub mod governor;
 pub mod repository;
 pub mod schema;
+pub mod schema_check;
 
+pub use governor::{IndexedPredicates, QueryClass, QueryGovernor};
 pub use repository::{PagedResult, Pagination, QueryFilter, Repository, SortOrder, Transaction};
 
 pub type DatabasePool = Pool<Postgres>;
@@ -5,7 +5,10 @@
 //! - Pagination and filtering support
 //! - Transaction management helpers
 
+use std::fmt;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -163,7 +166,6 @@ pub struct QueryFilter {
     conditions: Vec<FilterCondition>,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 struct FilterCondition {
     field: String,
@@ -186,6 +188,23 @@ enum FilterOperator {
     IsNotNull,
 }
 
+impl FilterOperator {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FilterOperator::Eq => "=",
+            FilterOperator::Ne => "<>",
+            FilterOperator::Gt => ">",
+            FilterOperator::Gte => ">=",
+            FilterOperator::Lt => "<",
+            FilterOperator::Lte => "<=",
+            FilterOperator::Like => "LIKE",
+            FilterOperator::In => "IN",
+            FilterOperator::IsNull => "IS NULL",
+            FilterOperator::IsNotNull => "IS NOT NULL",
+        }
+    }
+}
+
 /// Filter value types for query building
 #[derive(Debug, Clone)]
 pub enum FilterValue {
@@ -194,6 +213,7 @@ pub enum FilterValue {
     Float(f64),
     Bool(bool),
     Uuid(Uuid),
+    Timestamp(DateTime<Utc>),
     #[allow(dead_code)]
     StringList(Vec<String>),
     Null,
@@ -292,6 +312,60 @@ impl QueryFilter {
     pub fn len(&self) -> usize {
         self.conditions.len()
     }
+
+    /// Whether the filter has an equality predicate on `field`
+    pub fn has_eq(&self, field: &str) -> bool {
+        self.conditions
+            .iter()
+            .any(|c| c.field == field && matches!(c.operator, FilterOperator::Eq))
+    }
+
+    /// Lower and upper timestamp bounds the filter puts on `field`
+    pub fn time_range(&self, field: &str) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let mut range = (None, None);
+        for condition in self.conditions.iter().filter(|c| c.field == field) {
+            if let FilterValue::Timestamp(at) = condition.value {
+                match condition.operator {
+                    FilterOperator::Gt | FilterOperator::Gte => range.0 = Some(at),
+                    FilterOperator::Lt | FilterOperator::Lte => range.1 = Some(at),
+                    _ => {}
+                }
+            }
+        }
+        range
+    }
+}
+
+impl fmt::Display for FilterValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterValue::String(v) => write!(f, "'{}'", v),
+            FilterValue::Int(v) => write!(f, "{}", v),
+            FilterValue::Float(v) => write!(f, "{}", v),
+            FilterValue::Bool(v) => write!(f, "{}", v),
+            FilterValue::Uuid(v) => write!(f, "{}", v),
+            FilterValue::Timestamp(v) => write!(f, "{}", v.to_rfc3339()),
+            FilterValue::StringList(v) => write!(f, "({})", v.join(", ")),
+            FilterValue::Null => Ok(()),
+        }
+    }
+}
+
+/// Predicates joined with AND, e.g. `user_id = ... AND created_at >= ...`;
+/// used to attach the filter parameters to query logs
+impl fmt::Display for QueryFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, condition) in self.conditions.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" AND ")?;
+            }
+            write!(f, "{} {}", condition.field, condition.operator.as_str())?;
+            if !matches!(condition.value, FilterValue::Null) {
+                write!(f, " {}", condition.value)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 // Implement From traits for FilterValue
@@ -337,6 +411,12 @@ impl From<Uuid> for FilterValue {
     }
 }
 
+impl From<DateTime<Utc>> for FilterValue {
+    fn from(v: DateTime<Utc>) -> Self {
+        FilterValue::Timestamp(v)
+    }
+}
+
 /// Transaction wrapper for database operations
 pub struct Transaction<'a> {
     tx: sqlx::Transaction<'a, sqlx::Postgres>,
@@ -0,0 +1,697 @@
+//! Startup schema verification
+//!
+//! Much of the gateway uses runtime `sqlx::query` calls that are not checked
+//! against the database at compile time. After migrations run, the live
+//! schema is compared with the tables and columns those queries rely on so
+//! drift (a skipped or hand-edited migration, a restored dump) is reported
+//! at startup with the migration to look at, not at the first request.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use anyhow::Result;
+use sqlx::Row;
+
+use super::DatabasePool;
+
+/// Columns a migration adds to a table
+#[derive(Debug, Clone, Copy)]
+pub struct ExpectedColumns {
+    pub table: &'static str,
+    pub columns: &'static [&'static str],
+    pub migration: &'static str,
+}
+
+/// Tables and columns queried at runtime without compile-time checks
+pub const EXPECTED_SCHEMA: &[ExpectedColumns] = &[
+    ExpectedColumns {
+        table: "users",
+        columns: &["id", "email", "username", "role", "wallet_address", "created_at"],
+        migration: "20241101000001_initial_schema",
+    },
+    ExpectedColumns {
+        table: "audit_logs",
+        columns: &["event_type", "user_id", "ip_address", "event_data", "created_at"],
+        migration: "20241119000004_add_missing_tables",
+    },
+    ExpectedColumns {
+        table: "audit_logs",
+        columns: &["severity", "actor_id", "resource_type", "resource_id", "outcome"],
+        migration: "20260118000023_separate_audit_logs",
+    },
+    ExpectedColumns {
+        table: "market_epochs",
+        columns: &["id", "epoch_number", "start_time", "end_time", "status"],
+        migration: "20241101000001_initial_schema",
+    },
+    ExpectedColumns {
+        table: "market_epochs",
+        columns: &["clearing_started_at", "cleared_at"],
+        migration: "20260118000008_add_epoch_clearing",
+    },
+    ExpectedColumns {
+        table: "settlements",
+        columns: &["id", "epoch_id", "buyer_id", "seller_id", "status", "transaction_hash"],
+        migration: "20241101000001_initial_schema",
+    },
+    ExpectedColumns {
+        table: "settlements",
+        columns: &["commitment"],
+        migration: "20260118000021_add_transaction_commitment",
+    },
+    ExpectedColumns {
+        table: "meter_readings",
+        columns: &["kwh_amount", "minted", "mint_signature"],
+        migration: "20241119000004_add_missing_tables",
+    },
+    ExpectedColumns {
+        table: "meter_readings",
+        columns: &["mint_commitment"],
+        migration: "20260118000021_add_transaction_commitment",
+    },
+    ExpectedColumns {
+        table: "blockchain_transactions",
+        columns: &["signature", "user_id", "program_id", "status", "submitted_at", "confirmed_at"],
+        migration: "20241119000004_add_missing_tables",
+    },
+    ExpectedColumns {
+        table: "minting_retry_queue",
+        columns: &["id", "reading_id", "error_message", "attempts", "next_retry_at"],
+        migration: "20241120000001_add_minting_retry_queue",
+    },
+    ExpectedColumns {
+        table: "referral_rewards",
+        columns: &["id", "user_id", "status", "attempts", "last_error", "mint_tx_signature"],
+        migration: "20260118000003_add_referrals",
+    },
+    ExpectedColumns {
+        table: "sandbox_airdrops",
+        columns: &["wallet_address", "amount_sol", "cluster", "tx_signature", "created_at"],
+        migration: "20260118000020_add_sandbox_airdrops",
+    },
+    ExpectedColumns {
+        table: "api_keys",
+        columns: &["id", "name", "key_hash", "permissions", "is_active", "last_used_at", "revoked_at"],
+        migration: "20260118000022_add_api_keys",
+    },
+    ExpectedColumns {
+        table: "api_keys",
+        columns: &["owner_id"],
+        migration: "20260118000025_add_api_key_owner",
+    },
+    ExpectedColumns {
+        table: "delegated_apps",
+        columns: &[
+            "id", "name", "client_id", "client_secret_hash", "redirect_uris", "scopes", "created_by",
+            "created_at", "disabled_at",
+        ],
+        migration: "20260118000026_add_delegated_access",
+    },
+    ExpectedColumns {
+        table: "delegation_grants",
+        columns: &["id", "user_id", "app_id", "scopes", "created_at", "updated_at", "last_used_at", "revoked_at"],
+        migration: "20260118000026_add_delegated_access",
+    },
+    ExpectedColumns {
+        table: "delegation_codes",
+        columns: &["code_hash", "grant_id", "redirect_uri", "expires_at", "used_at"],
+        migration: "20260118000026_add_delegated_access",
+    },
+    ExpectedColumns {
+        table: "delegation_tokens",
+        columns: &["token_hash", "grant_id", "expires_at", "created_at"],
+        migration: "20260118000026_add_delegated_access",
+    },
+    ExpectedColumns {
+        table: "user_wallets",
+        columns: &["id", "user_id", "wallet_address", "label", "is_primary", "verified", "created_at"],
+        migration: "20260108200337_add_user_wallets",
+    },
+    ExpectedColumns {
+        table: "users",
+        columns: &["preferred_locale"],
+        migration: "20260118000027_add_user_locale",
+    },
+    ExpectedColumns {
+        table: "emission_factors",
+        columns: &["id", "region", "year", "kg_co2_per_kwh", "grid_mix_source", "notes", "created_by"],
+        migration: "20260118000028_add_emission_factors",
+    },
+    ExpectedColumns {
+        table: "grid_status_history",
+        columns: &["co2_factor"],
+        migration: "20260118000028_add_emission_factors",
+    },
+    ExpectedColumns {
+        table: "community_stats_daily",
+        columns: &["co2_factor"],
+        migration: "20260118000028_add_emission_factors",
+    },
+    ExpectedColumns {
+        table: "meter_registry",
+        columns: &["registry_pda", "registry_tx_signature", "registry_synced_at", "registry_sync_error"],
+        migration: "20260118000029_add_meter_registry_pda",
+    },
+    ExpectedColumns {
+        table: "wallet_link_history",
+        columns: &["id", "user_id", "wallet_address", "method", "proof", "linked_at", "unlinked_at"],
+        migration: "20260118000030_add_wallet_link_history",
+    },
+    ExpectedColumns {
+        table: "order_book_snapshots",
+        columns: &["id", "market_id", "reason", "epoch_id", "bid_levels", "ask_levels", "book", "captured_at"],
+        migration: "20260118000031_add_order_book_snapshots",
+    },
+    ExpectedColumns {
+        table: "epoch_matching_performance",
+        columns: &[
+            "epoch_id", "markets", "orders", "matches", "fetch_ms", "matching_ms", "db_write_ms",
+            "notify_ms", "settlement_ms", "total_ms", "recorded_at",
+        ],
+        migration: "20260118000032_add_epoch_matching_performance",
+    },
+    ExpectedColumns {
+        table: "resident_book_snapshots",
+        columns: &["market_id", "journal_position", "order_count", "book", "captured_at"],
+        migration: "20260118000033_add_resident_book_snapshots",
+    },
+    ExpectedColumns {
+        table: "matching_shadow_comparisons",
+        columns: &[
+            "epoch_id", "market_id", "algorithm", "live_volume", "shadow_volume", "live_price",
+            "shadow_price", "diverged_orders", "divergences", "compared_at",
+        ],
+        migration: "20260118000034_add_matching_shadow_comparisons",
+    },
+    ExpectedColumns {
+        table: "settlements",
+        columns: &["quarantined_at", "diagnosis"],
+        migration: "20260118000035_add_settlement_quarantine",
+    },
+    ExpectedColumns {
+        table: "markets",
+        columns: &[
+            "settlement_high_notional", "settlement_urgent_notional", "settlement_corporate_high",
+            "settlement_deadline_secs",
+        ],
+        migration: "20260118000036_add_settlement_priority",
+    },
+    ExpectedColumns {
+        table: "settlements",
+        columns: &["priority"],
+        migration: "20260118000036_add_settlement_priority",
+    },
+    ExpectedColumns {
+        table: "settlements",
+        columns: &["fee_payer", "fee_lamports"],
+        migration: "20260118000037_add_settlement_fee_payers",
+    },
+    ExpectedColumns {
+        table: "delivery_verifications",
+        columns: &["settlement_id", "shortfall_kwh", "status", "remedy", "refund_amount"],
+        migration: "20260118000038_add_delivery_verifications",
+    },
+    ExpectedColumns {
+        table: "imbalance_settlements",
+        columns: &["epoch_id", "user_id", "imbalance_kwh", "amount", "status"],
+        migration: "20260118000039_add_imbalance_settlements",
+    },
+    ExpectedColumns {
+        table: "meter_reconciliations",
+        columns: &["interval_id", "deviation", "divergent"],
+        migration: "20260118000040_add_grid_meter_reconciliation",
+    },
+    ExpectedColumns {
+        table: "meter_registry",
+        columns: &["mint_frozen_at", "mint_freeze_reason", "mint_unfrozen_at"],
+        migration: "20260118000040_add_grid_meter_reconciliation",
+    },
+    ExpectedColumns {
+        table: "maintenance_windows",
+        columns: &["components", "starts_at", "ends_at", "cancelled_at"],
+        migration: "20260118000041_add_maintenance_windows",
+    },
+    ExpectedColumns {
+        table: "trading_halts",
+        columns: &["market_id", "zone_id", "mode", "resume_at", "lifted_at"],
+        migration: "20260118000042_add_trading_halts",
+    },
+    ExpectedColumns {
+        table: "balance_journal",
+        columns: &["user_id", "entry_type", "balance", "locked_amount", "locked_energy", "recorded_at"],
+        migration: "20260118000043_add_balance_journal",
+    },
+    ExpectedColumns {
+        table: "erc_certificates",
+        columns: &["retired_at"],
+        migration: "20260118000043_add_balance_journal",
+    },
+    ExpectedColumns {
+        table: "pii_data_keys",
+        columns: &["purpose", "kms_provider", "wrapped_key", "retired_at"],
+        migration: "20260118000044_add_pii_encryption",
+    },
+    ExpectedColumns {
+        table: "users",
+        columns: &["email_encrypted", "email_digest", "first_name_encrypted", "last_name_encrypted", "pii_key_id"],
+        migration: "20260118000044_add_pii_encryption",
+    },
+    ExpectedColumns {
+        table: "meter_registry",
+        columns: &["location_address_encrypted", "pii_key_id"],
+        migration: "20260118000044_add_pii_encryption",
+    },
+    ExpectedColumns {
+        table: "login_step_up_challenges",
+        columns: &["code_hash", "signals", "attempts", "expires_at", "verified_at"],
+        migration: "20260118000045_add_login_step_up",
+    },
+    ExpectedColumns {
+        table: "deployment_profiles",
+        columns: &["profile_key", "brand_name", "features", "energy_token_mint", "trading_program_id"],
+        migration: "20260118000046_add_deployment_profiles",
+    },
+    ExpectedColumns {
+        table: "meter_registry",
+        columns: &["last_reading_at"],
+        migration: "20260118000047_add_meter_fleet_health",
+    },
+    ExpectedColumns {
+        table: "meter_health_hourly",
+        columns: &["meter_serial", "hour", "readings", "signature_checks", "signature_failures", "anomalies"],
+        migration: "20260118000047_add_meter_fleet_health",
+    },
+    ExpectedColumns {
+        table: "autopilot_settings",
+        columns: &["user_id", "enabled", "strategy", "index_offset_bps", "floor_price", "sell_fraction"],
+        migration: "20260118000048_add_trading_autopilot",
+    },
+    ExpectedColumns {
+        table: "autopilot_orders",
+        columns: &["user_id", "epoch_id", "order_id", "forecast_kwh", "status", "closed_at"],
+        migration: "20260118000048_add_trading_autopilot",
+    },
+    ExpectedColumns {
+        table: "budget_alerts",
+        columns: &["user_id", "kind", "threshold", "enabled", "triggered_period", "last_triggered_at"],
+        migration: "20260118000049_add_budget_alerts",
+    },
+    ExpectedColumns {
+        table: "swap_pool_hourly",
+        columns: &["pool_id", "hour", "swaps", "volume_kwh", "volume_currency", "fees_currency", "reserve_currency"],
+        migration: "20260118000050_add_swap_pool_stats",
+    },
+    ExpectedColumns {
+        table: "settlements",
+        columns: &["buy_order_id", "sell_order_id"],
+        migration: "20251206000009_add_settlement_order_ids",
+    },
+    ExpectedColumns {
+        table: "markets",
+        columns: &["max_order_kwh", "dust_threshold_kwh"],
+        migration: "20260118000052_add_order_size_limits_and_dust",
+    },
+    ExpectedColumns {
+        table: "order_dust",
+        columns: &["order_id", "user_id", "market_id", "side", "amount_kwh", "created_at"],
+        migration: "20260118000052_add_order_size_limits_and_dust",
+    },
+    ExpectedColumns {
+        table: "maintenance_rebuild_jobs",
+        columns: &["id", "target", "status", "steps_done", "steps_total", "requested_by", "error", "updated_at"],
+        migration: "20260118000053_add_maintenance_rebuild_jobs",
+    },
+    ExpectedColumns {
+        table: "ami_backfill_batches",
+        columns: &["id", "gateway_id", "window_start", "window_end", "status", "reviewed_by"],
+        migration: "20260118000054_add_ami_backfill_batches",
+    },
+    ExpectedColumns {
+        table: "ami_backfill_readings",
+        columns: &["id", "batch_id", "meter_serial", "reading_timestamp", "kwh_amount", "status"],
+        migration: "20260118000054_add_ami_backfill_batches",
+    },
+    ExpectedColumns {
+        table: "meter_readings",
+        columns: &["backfill_batch_id"],
+        migration: "20260118000054_add_ami_backfill_batches",
+    },
+    ExpectedColumns {
+        table: "wallet_sessions",
+        columns: &["session_token", "device_fingerprint", "user_agent_hash", "expires_at", "absolute_expires_at"],
+        migration: "20260118000055_harden_wallet_sessions",
+    },
+    ExpectedColumns {
+        table: "fee_sponsorship_programs",
+        columns: &["id", "name", "sponsor", "budget_lamports", "member_monthly_lamports", "active"],
+        migration: "20260118000056_add_fee_sponsorship",
+    },
+    ExpectedColumns {
+        table: "fee_sponsorship_members",
+        columns: &["program_id", "user_id"],
+        migration: "20260118000056_add_fee_sponsorship",
+    },
+    ExpectedColumns {
+        table: "fee_sponsorships",
+        columns: &["id", "user_id", "rule", "program_id", "reserved_lamports", "fee_lamports", "status"],
+        migration: "20260118000056_add_fee_sponsorship",
+    },
+    ExpectedColumns {
+        table: "report_subscriptions",
+        columns: &["id", "user_id", "report", "scope", "channel", "format", "webhook_url", "next_run_at", "last_period_start"],
+        migration: "20260118000057_add_report_subscriptions",
+    },
+    ExpectedColumns {
+        table: "report_deliveries",
+        columns: &["id", "subscription_id", "period_start", "status"],
+        migration: "20260118000057_add_report_subscriptions",
+    },
+    ExpectedColumns {
+        table: "background_jobs",
+        columns: &["id", "kind", "status", "attempt", "retry_of", "cancel_requested", "summary", "updated_at"],
+        migration: "20260118000058_add_background_jobs",
+    },
+    ExpectedColumns {
+        table: "background_job_logs",
+        columns: &["id", "job_id", "level", "message"],
+        migration: "20260118000058_add_background_jobs",
+    },
+    ExpectedColumns {
+        table: "meter_registry",
+        columns: &["capacity_kw"],
+        migration: "20260118000059_add_mint_guard",
+    },
+    ExpectedColumns {
+        table: "mint_quarantine",
+        columns: &["id", "reading_id", "wallet_address", "kwh_amount", "breached_window", "status"],
+        migration: "20260118000059_add_mint_guard",
+    },
+    ExpectedColumns {
+        table: "meter_registry",
+        columns: &[
+            "installation_date",
+            "panel_azimuth_deg",
+            "panel_tilt_deg",
+            "inverter_manufacturer",
+            "inverter_model",
+            "inverter_capacity_kw",
+        ],
+        migration: "20260118000060_add_meter_installation",
+    },
+    ExpectedColumns {
+        table: "tokenization_overrides",
+        columns: &["id", "overrides", "updated_by", "updated_at"],
+        migration: "20260118000061_add_tokenization_overrides",
+    },
+    ExpectedColumns {
+        table: "market_daily_rollups",
+        columns: &[
+            "rollup_date",
+            "volume_kwh",
+            "trade_count",
+            "epoch_count",
+            "close_clearing_price",
+            "participant_count",
+            "minted_kwh",
+        ],
+        migration: "20260118000062_add_market_daily_rollups",
+    },
+    ExpectedColumns {
+        table: "data_exports",
+        columns: &[
+            "user_id",
+            "dataset",
+            "format",
+            "range_from",
+            "range_to",
+            "status",
+            "storage_key",
+            "expires_at",
+        ],
+        migration: "20260118000063_add_data_exports",
+    },
+    ExpectedColumns {
+        table: "settlement_queue",
+        columns: &["settlement_id", "seq", "enqueued_at"],
+        migration: "20260118000064_add_settlement_scheduler_state",
+    },
+    ExpectedColumns {
+        table: "settlement_scheduler_state",
+        columns: &[
+            "batch_id",
+            "batch_settlement_ids",
+            "batch_position",
+            "last_settlement_id",
+            "batch_finished_at",
+        ],
+        migration: "20260118000064_add_settlement_scheduler_state",
+    },
+    ExpectedColumns {
+        table: "dashboard_metrics_read_model",
+        columns: &["metrics", "refreshed_at"],
+        migration: "20260118000065_add_dashboard_metrics_read_model",
+    },
+    ExpectedColumns {
+        table: "broadcast_throttle_settings",
+        columns: &["windows", "updated_by", "updated_at"],
+        migration: "20260118000066_add_broadcast_throttle",
+    },
+    ExpectedColumns {
+        table: "partner_organizations",
+        columns: &["id", "name", "slug", "rate_limit_tier", "created_by", "created_at"],
+        migration: "20260118000068_add_partner_onboarding",
+    },
+    ExpectedColumns {
+        table: "api_keys",
+        columns: &["partner_id"],
+        migration: "20260118000068_add_partner_onboarding",
+    },
+    ExpectedColumns {
+        table: "partner_webhook_endpoints",
+        columns: &["id", "partner_id", "url", "secret", "event_types", "is_active", "created_at"],
+        migration: "20260118000068_add_partner_onboarding",
+    },
+    ExpectedColumns {
+        table: "partner_sandbox_users",
+        columns: &["partner_id", "user_id", "created_at"],
+        migration: "20260118000068_add_partner_onboarding",
+    },
+    ExpectedColumns {
+        table: "market_epochs",
+        columns: &["rolled_back_at"],
+        migration: "20260118000069_add_epoch_rollbacks",
+    },
+    ExpectedColumns {
+        table: "epoch_rollbacks",
+        columns: &[
+            "id",
+            "epoch_id",
+            "approval_request_id",
+            "reopened_in_epoch_id",
+            "cancelled_settlement_ids",
+            "reopened_order_ids",
+            "narrative",
+            "reason",
+            "executed_by",
+            "created_at",
+        ],
+        migration: "20260118000069_add_epoch_rollbacks",
+    },
+    ExpectedColumns {
+        table: "meter_readings",
+        columns: &["mint_status"],
+        migration: "20260118000070_add_reading_mint_status",
+    },
+    ExpectedColumns {
+        table: "settlements",
+        columns: &["escrow_release_started_at", "escrow_release_signatures"],
+        migration: "20260118000072_add_settlement_escrow_releases",
+    },
+    ExpectedColumns {
+        table: "settlements",
+        columns: &["loss_transfer_signature", "loss_transfer_error"],
+        migration: "20260118000073_add_settlement_loss_transfer",
+    },
+];
+
+/// One expected table or column that is not in the live schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaIssue {
+    MissingTable {
+        table: &'static str,
+        migration: &'static str,
+    },
+    MissingColumn {
+        table: &'static str,
+        column: &'static str,
+        migration: &'static str,
+    },
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaIssue::MissingTable { table, migration } => {
+                write!(f, "table {} is missing (created by migration {})", table, migration)
+            }
+            SchemaIssue::MissingColumn { table, column, migration } => {
+                write!(f, "column {}.{} is missing (added by migration {})", table, column, migration)
+            }
+        }
+    }
+}
+
+/// Result of comparing the live schema with `EXPECTED_SCHEMA`
+#[derive(Debug, Clone, Default)]
+pub struct SchemaReport {
+    pub tables_checked: usize,
+    pub issues: Vec<SchemaIssue>,
+}
+
+impl SchemaReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Error listing every issue and how to fix it, if there are any
+    pub fn into_result(self) -> Result<Self> {
+        if self.is_clean() {
+            return Ok(self);
+        }
+        let issues: Vec<String> = self.issues.iter().map(|issue| format!("  - {}", issue)).collect();
+        Err(anyhow::anyhow!(
+            "Database schema does not match what the gateway expects:\n{}\n\
+             Check _sqlx_migrations for the listed migrations; if they are recorded as \
+             applied, the schema was changed outside of migrations",
+            issues.join("\n")
+        ))
+    }
+}
+
+/// Compare expected tables and columns with the live ones
+pub fn diff(expected: &[ExpectedColumns], live: &BTreeMap<String, BTreeSet<String>>) -> SchemaReport {
+    let mut issues = Vec::new();
+    let mut missing_tables = BTreeSet::new();
+    let mut tables = BTreeSet::new();
+
+    for entry in expected {
+        tables.insert(entry.table);
+        let Some(columns) = live.get(entry.table) else {
+            // Report the table once, against the migration that creates it
+            if missing_tables.insert(entry.table) {
+                issues.push(SchemaIssue::MissingTable {
+                    table: entry.table,
+                    migration: entry.migration,
+                });
+            }
+            continue;
+        };
+        for column in entry.columns {
+            if !columns.contains(*column) {
+                issues.push(SchemaIssue::MissingColumn {
+                    table: entry.table,
+                    column,
+                    migration: entry.migration,
+                });
+            }
+        }
+    }
+
+    SchemaReport {
+        tables_checked: tables.len(),
+        issues,
+    }
+}
+
+/// Check the live schema against `EXPECTED_SCHEMA`
+pub async fn verify_schema(pool: &DatabasePool) -> Result<SchemaReport> {
+    let tables: Vec<&str> = EXPECTED_SCHEMA.iter().map(|entry| entry.table).collect();
+    let rows = sqlx::query(
+        r#"
+        SELECT table_name::text AS table_name, column_name::text AS column_name
+        FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = ANY($1)
+        "#,
+    )
+    .bind(&tables)
+    .fetch_all(pool)
+    .await?;
+
+    let mut live: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for row in rows {
+        live.entry(row.get("table_name"))
+            .or_default()
+            .insert(row.get("column_name"));
+    }
+    Ok(diff(EXPECTED_SCHEMA, &live))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPECTED: &[ExpectedColumns] = &[
+        ExpectedColumns {
+            table: "settlements",
+            columns: &["id", "status"],
+            migration: "initial",
+        },
+        ExpectedColumns {
+            table: "settlements",
+            columns: &["commitment"],
+            migration: "commitment",
+        },
+        ExpectedColumns {
+            table: "api_keys",
+            columns: &["id"],
+            migration: "api_keys",
+        },
+        ExpectedColumns {
+            table: "api_keys",
+            columns: &["revoked_at"],
+            migration: "api_keys_revocation",
+        },
+    ];
+
+    #[test]
+    fn reports_missing_tables_once_and_columns_by_migration() {
+        let mut live = BTreeMap::new();
+        live.insert(
+            "settlements".to_string(),
+            ["id", "status"].iter().map(|c| c.to_string()).collect(),
+        );
+
+        let report = diff(EXPECTED, &live);
+        assert_eq!(report.tables_checked, 2);
+        assert_eq!(
+            report.issues,
+            vec![
+                SchemaIssue::MissingColumn {
+                    table: "settlements",
+                    column: "commitment",
+                    migration: "commitment",
+                },
+                SchemaIssue::MissingTable {
+                    table: "api_keys",
+                    migration: "api_keys",
+                },
+            ]
+        );
+        let message = report.into_result().unwrap_err().to_string();
+        assert!(message.contains("settlements.commitment"));
+    }
+
+    #[test]
+    fn matching_schema_is_clean() {
+        let mut live = BTreeMap::new();
+        live.insert(
+            "settlements".to_string(),
+            ["id", "status", "commitment", "extra"].iter().map(|c| c.to_string()).collect(),
+        );
+        live.insert(
+            "api_keys".to_string(),
+            ["id", "revoked_at"].iter().map(|c| c.to_string()).collect(),
+        );
+        assert!(diff(EXPECTED, &live).into_result().is_ok());
+    }
+}
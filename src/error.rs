@@ -29,6 +29,10 @@
 //! - `8xxx` - External service errors
 //! - `9xxx` - Rate limiting and internalerrors
 //!
+//! Every error body also carries `code_name`, the code's stable symbolic name
+//! (e.g. `ORDER_VIOLATES_MARKET_RULES`), and `retryable`, which tells client
+//! SDKs whether repeating the same request unchanged may succeed.
+//!
 //! ## Usage Examples
 //!
 //! ### In Services
@@ -80,6 +84,8 @@ use tracing::{error, warn};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::i18n::{self, Locale};
+
 pub type Result<T> = std::result::Result<T, ApiError>;
 
 /// Error codes for categorizing errors
@@ -150,6 +156,8 @@ pub enum ErrorCode {
     TokenMintingFailed,
     #[serde(rename = "BIZ_5006")]
     EpochNotActive,
+    #[serde(rename = "BIZ_5007")]
+    OrderViolatesMarketRules,
 
     // Blockchain errors (6xxx)
     #[serde(rename = "BC_6001")]
@@ -174,6 +182,8 @@ pub enum ErrorCode {
     DatabaseTransactionFailed,
     #[serde(rename = "DB_7004")]
     ConstraintViolation,
+    #[serde(rename = "DB_7005")]
+    QueryTimeout,
 
     // External service errors (8xxx)
     #[serde(rename = "EXT_8001")]
@@ -243,6 +253,7 @@ impl ErrorCode {
             ErrorCode::MeterReadingInvalid => 5004,
             ErrorCode::TokenMintingFailed => 5005,
             ErrorCode::EpochNotActive => 5006,
+            ErrorCode::OrderViolatesMarketRules => 5007,
 
             // Blockchain
             ErrorCode::BlockchainConnectionFailed => 6001,
@@ -257,6 +268,7 @@ impl ErrorCode {
             ErrorCode::QueryFailed => 7002,
             ErrorCode::DatabaseTransactionFailed => 7003,
             ErrorCode::ConstraintViolation => 7004,
+            ErrorCode::QueryTimeout => 7005,
 
             // External Service
             ErrorCode::ExternalServiceUnavailable => 8001,
@@ -320,6 +332,7 @@ impl ErrorCode {
             ErrorCode::MeterReadingInvalid => "Invalid meter reading provided",
             ErrorCode::TokenMintingFailed => "Failed to mint energy tokens",
             ErrorCode::EpochNotActive => "Trading epoch is not active",
+            ErrorCode::OrderViolatesMarketRules => "Order does not meet the market's trading rules",
 
             // Blockchain
             ErrorCode::BlockchainConnectionFailed => "Failed to connect to blockchain network",
@@ -334,6 +347,7 @@ impl ErrorCode {
             ErrorCode::QueryFailed => "Database query failed",
             ErrorCode::DatabaseTransactionFailed => "Database transaction failed",
             ErrorCode::ConstraintViolation => "Database constraint violation",
+            ErrorCode::QueryTimeout => "Query exceeded its time limit",
 
             // External Service
             ErrorCode::ExternalServiceUnavailable => "External service is currently unavailable",
@@ -352,6 +366,102 @@ impl ErrorCode {
             ErrorCode::UnexpectedError => "An unexpected error occurred",
         }
     }
+
+    /// User-friendly message in `locale`, falling back to English
+    pub fn localized_message(&self, locale: Locale) -> &'static str {
+        i18n::lookup(locale, &format!("error.{}", self.code())).unwrap_or(self.message())
+    }
+
+    /// Stable symbolic name for SDKs; never changes once published
+    pub fn name(&self) -> &'static str {
+        match self {
+            // Authentication
+            ErrorCode::InvalidCredentials => "INVALID_CREDENTIALS",
+            ErrorCode::TokenExpired => "TOKEN_EXPIRED",
+            ErrorCode::TokenInvalid => "TOKEN_INVALID",
+            ErrorCode::TokenMissing => "TOKEN_MISSING",
+            ErrorCode::EmailNotVerified => "EMAIL_NOT_VERIFIED",
+            ErrorCode::AccountLocked => "ACCOUNT_LOCKED",
+            ErrorCode::AccountDisabled => "ACCOUNT_DISABLED",
+
+            // Authorization
+            ErrorCode::InsufficientPermissions => "INSUFFICIENT_PERMISSIONS",
+            ErrorCode::ResourceAccessDenied => "RESOURCE_ACCESS_DENIED",
+            ErrorCode::RoleNotAuthorized => "ROLE_NOT_AUTHORIZED",
+
+            // Validation
+            ErrorCode::InvalidInput => "INVALID_INPUT",
+            ErrorCode::MissingRequiredField => "MISSING_REQUIRED_FIELD",
+            ErrorCode::InvalidFormat => "INVALID_FORMAT",
+            ErrorCode::InvalidWalletAddress => "INVALID_WALLET_ADDRESS",
+            ErrorCode::InvalidAmount => "INVALID_AMOUNT",
+            ErrorCode::InvalidEmail => "INVALID_EMAIL",
+            ErrorCode::InvalidPassword => "INVALID_PASSWORD",
+            ErrorCode::PasswordTooWeak => "PASSWORD_TOO_WEAK",
+
+            // Resource
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::AlreadyExists => "ALREADY_EXISTS",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::Gone => "GONE",
+
+            // Business Logic
+            ErrorCode::InsufficientBalance => "INSUFFICIENT_BALANCE",
+            ErrorCode::OrderNotMatched => "ORDER_NOT_MATCHED",
+            ErrorCode::TradingNotAllowed => "TRADING_NOT_ALLOWED",
+            ErrorCode::MeterReadingInvalid => "METER_READING_INVALID",
+            ErrorCode::TokenMintingFailed => "TOKEN_MINTING_FAILED",
+            ErrorCode::EpochNotActive => "EPOCH_NOT_ACTIVE",
+            ErrorCode::OrderViolatesMarketRules => "ORDER_VIOLATES_MARKET_RULES",
+
+            // Blockchain
+            ErrorCode::BlockchainConnectionFailed => "BLOCKCHAIN_CONNECTION_FAILED",
+            ErrorCode::BlockchainTransactionFailed => "BLOCKCHAIN_TRANSACTION_FAILED",
+            ErrorCode::TransactionTimeout => "TRANSACTION_TIMEOUT",
+            ErrorCode::InvalidSignature => "INVALID_SIGNATURE",
+            ErrorCode::InsufficientGasFee => "INSUFFICIENT_GAS_FEE",
+            ErrorCode::ProgramError => "PROGRAM_ERROR",
+
+            // Database
+            ErrorCode::DatabaseConnectionFailed => "DATABASE_CONNECTION_FAILED",
+            ErrorCode::QueryFailed => "QUERY_FAILED",
+            ErrorCode::DatabaseTransactionFailed => "DATABASE_TRANSACTION_FAILED",
+            ErrorCode::ConstraintViolation => "CONSTRAINT_VIOLATION",
+            ErrorCode::QueryTimeout => "QUERY_TIMEOUT",
+
+            // External Service
+            ErrorCode::ExternalServiceUnavailable => "EXTERNAL_SERVICE_UNAVAILABLE",
+            ErrorCode::ExternalServiceTimeout => "EXTERNAL_SERVICE_TIMEOUT",
+            ErrorCode::ExternalServiceError => "EXTERNAL_SERVICE_ERROR",
+            ErrorCode::EmailServiceFailed => "EMAIL_SERVICE_FAILED",
+            ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+
+            // Rate Limiting
+            ErrorCode::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            ErrorCode::TooManyRequests => "TOO_MANY_REQUESTS",
+
+            // Internal
+            ErrorCode::InternalServerError => "INTERNAL_SERVER_ERROR",
+            ErrorCode::ConfigurationError => "CONFIGURATION_ERROR",
+            ErrorCode::UnexpectedError => "UNEXPECTED_ERROR",
+        }
+    }
+
+    /// Whether the failure is transient, so the same request may succeed later
+    pub fn retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::BlockchainConnectionFailed
+                | ErrorCode::TransactionTimeout
+                | ErrorCode::DatabaseConnectionFailed
+                | ErrorCode::DatabaseTransactionFailed
+                | ErrorCode::ExternalServiceUnavailable
+                | ErrorCode::ExternalServiceTimeout
+                | ErrorCode::ServiceUnavailable
+                | ErrorCode::RateLimitExceeded
+                | ErrorCode::TooManyRequests
+        )
+    }
 }
 
 /// Structured error response
@@ -366,10 +476,37 @@ pub struct ErrorResponse {
 pub struct ErrorDetail {
     pub code: ErrorCode,
     pub code_number: u16,
+    /// Stable symbolic name of `code`, e.g. `TRANSACTION_TIMEOUT`
+    pub code_name: &'static str,
+    /// Repeating the same request unchanged may succeed (after a backoff)
+    pub retryable: bool,
     pub message: String,
     pub details: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub field: Option<String>,
+    /// Every offending field, for request validation failures (HTTP 422)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<FieldError>>,
+}
+
+/// A single failed validation rule
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Field path, e.g. `energy_amount` or `evidence[1].content`
+    pub field: String,
+    /// Rule that failed, e.g. `range`, `length`, `precision`
+    pub code: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            code: code.into(),
+            message: message.into(),
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -432,6 +569,10 @@ pub enum ApiError {
         field: String,
         message: String,
     },
+
+    /// Declarative request validation failure listing every offending field
+    #[error("Request validation failed: {} field(s)", .0.len())]
+    FieldErrors(Vec<FieldError>),
 }
 
 impl ApiError {
@@ -458,6 +599,11 @@ impl ApiError {
         }
     }
 
+    /// Create a 422 validation error for a single field
+    pub fn invalid_field(field: impl Into<String>, code: impl Into<String>, message: impl Into<String>) -> Self {
+        ApiError::FieldErrors(vec![FieldError::new(field, code, message)])
+    }
+
     /// Create general validation error
     pub fn validation_error(message: impl Into<String>, field: Option<&str>) -> Self {
         if let Some(field_name) = field {
@@ -609,6 +755,21 @@ impl ApiError {
             ApiError::WithCode(code, _) => *code,
             ApiError::WithCodeAndDetails(code, _, _) => *code,
             ApiError::ValidationWithField { code, .. } => *code,
+            ApiError::FieldErrors(_) => ErrorCode::InvalidInput,
+        }
+    }
+
+    /// Whether the same request may succeed if retried
+    pub fn retryable(&self) -> bool {
+        match self {
+            // Serialization failures, deadlocks and pool exhaustion clear up on their own
+            ApiError::Database(sqlx::Error::PoolTimedOut | sqlx::Error::Io(_)) => true,
+            ApiError::Database(sqlx::Error::Database(e)) => {
+                matches!(e.code().as_deref(), Some("40001") | Some("40P01"))
+            }
+            ApiError::Database(_) => false,
+            ApiError::Redis(e) => e.is_timeout() || e.is_connection_dropped() || e.is_connection_refusal(),
+            _ => self.error_code().retryable(),
         }
     }
 
@@ -624,6 +785,15 @@ impl ApiError {
     fn error_field(&self) -> Option<String> {
         match self {
             ApiError::ValidationWithField { field, .. } => Some(field.clone()),
+            ApiError::FieldErrors(errors) if errors.len() == 1 => Some(errors[0].field.clone()),
+            _ => None,
+        }
+    }
+
+    /// Get all field errors for declarative validation failures
+    fn field_errors(&self) -> Option<Vec<FieldError>> {
+        match self {
+            ApiError::FieldErrors(errors) => Some(errors.clone()),
             _ => None,
         }
     }
@@ -648,7 +818,14 @@ impl ApiError {
             | ApiError::ValidationWithField { .. }
             | ApiError::WithCode(ErrorCode::InvalidInput, _)
             | ApiError::WithCode(ErrorCode::InvalidWalletAddress, _)
-            | ApiError::WithCode(ErrorCode::InvalidAmount, _) => StatusCode::BAD_REQUEST,
+            | ApiError::WithCode(ErrorCode::InvalidAmount, _)
+            | ApiError::WithCode(ErrorCode::InsufficientBalance, _)
+            | ApiError::WithCode(ErrorCode::OrderViolatesMarketRules, _)
+            | ApiError::WithCodeAndDetails(ErrorCode::InsufficientBalance, _, _) => StatusCode::BAD_REQUEST,
+
+            ApiError::FieldErrors(_) | ApiError::WithCode(ErrorCode::QueryTimeout, _) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
 
             ApiError::NotFound(_) | ApiError::WithCode(ErrorCode::NotFound, _) => {
                 StatusCode::NOT_FOUND
@@ -656,13 +833,15 @@ impl ApiError {
 
             ApiError::Conflict(_)
             | ApiError::WithCode(ErrorCode::Conflict, _)
+            | ApiError::WithCode(ErrorCode::TradingNotAllowed, _)
             | ApiError::WithCode(ErrorCode::AlreadyExists, _) => StatusCode::CONFLICT,
 
             ApiError::Blockchain(_)
             | ApiError::ExternalService(_)
             | ApiError::WithCode(ErrorCode::BlockchainConnectionFailed, _)
             | ApiError::WithCode(ErrorCode::ExternalServiceUnavailable, _)
-            | ApiError::WithCode(ErrorCode::ServiceUnavailable, _) => StatusCode::BAD_GATEWAY,
+            | ApiError::WithCode(ErrorCode::ServiceUnavailable, _)
+            | ApiError::WithCode(ErrorCode::TransactionTimeout, _) => StatusCode::BAD_GATEWAY,
 
             ApiError::RateLimitExceeded(_)
             | ApiError::WithCode(ErrorCode::RateLimitExceeded, _) => StatusCode::TOO_MANY_REQUESTS,
@@ -696,33 +875,41 @@ impl ApiError {
             _ => {}
         }
     }
+
+    /// Client-facing error body, as returned by REST endpoints
+    pub fn detail(&self) -> ErrorDetail {
+        let code = self.error_code();
+        ErrorDetail {
+            code,
+            code_number: code.code(),
+            code_name: code.name(),
+            retryable: self.retryable(),
+            message: match self {
+                ApiError::WithCode(_, msg) | ApiError::WithCodeAndDetails(_, msg, _) => msg.clone(),
+                ApiError::BadRequest(msg) => msg.clone(),
+                ApiError::ValidationWithField { message, .. } => message.clone(),
+                ApiError::FieldErrors(errors) if errors.len() == 1 => errors[0].message.clone(),
+                ApiError::FieldErrors(_) => "Request validation failed".to_string(),
+                _ => code.localized_message(i18n::current()).to_string(),
+            },
+            details: self.error_details(),
+            field: self.error_field(),
+            errors: self.field_errors(),
+        }
+    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let request_id = Uuid::new_v4().to_string();
         let status = self.status_code();
-        let code = self.error_code();
 
         // Log the error
         self.log_error(&request_id);
 
         // Build error response
         let error_response = ErrorResponse {
-            error: ErrorDetail {
-                code,
-                code_number: code.code(),
-                message: match &self {
-                    ApiError::WithCode(_, msg) | ApiError::WithCodeAndDetails(_, msg, _) => {
-                        msg.clone()
-                    }
-                    ApiError::BadRequest(msg) => msg.clone(),
-                    ApiError::ValidationWithField { message, .. } => message.clone(),
-                    _ => code.message().to_string(),
-                },
-                details: self.error_details(),
-                field: self.error_field(),
-            },
+            error: self.detail(),
             request_id,
             timestamp: chrono::Utc::now().to_rfc3339(),
         };
@@ -762,7 +949,47 @@ impl ApiError {
                 ErrorCode::NotFound => "not_found",
                 _ => "error",
             },
-            ApiError::ValidationWithField { .. } => "validation_error",
+            ApiError::ValidationWithField { .. } | ApiError::FieldErrors(_) => "validation_error",
+        }
+    }
+}
+
+impl From<validator::ValidationErrors> for ApiError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        let mut out = Vec::new();
+        flatten_validation_errors("", &errors, &mut out);
+        out.sort_by(|a, b| a.field.cmp(&b.field));
+        ApiError::FieldErrors(out)
+    }
+}
+
+/// Flatten nested `validator` errors into `path.to[0].field` entries
+fn flatten_validation_errors(prefix: &str, errors: &validator::ValidationErrors, out: &mut Vec<FieldError>) {
+    use validator::ValidationErrorsKind;
+
+    for (field, kind) in errors.errors() {
+        let path = match (prefix.is_empty(), field.as_ref()) {
+            (_, "__all__") if !prefix.is_empty() => prefix.to_string(),
+            (true, name) => name.to_string(),
+            (false, name) => format!("{}.{}", prefix, name),
+        };
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                for e in field_errors {
+                    let message = e
+                        .message
+                        .as_ref()
+                        .map(|m| m.to_string())
+                        .unwrap_or_else(|| format!("{} is invalid ({})", path, e.code));
+                    out.push(FieldError::new(path.clone(), e.code.to_string(), message));
+                }
+            }
+            ValidationErrorsKind::Struct(nested) => flatten_validation_errors(&path, nested, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    flatten_validation_errors(&format!("{}[{}]", path, index), nested, out);
+                }
+            }
         }
     }
 }
@@ -795,3 +1022,24 @@ pub fn handle_rejection(err: JsonRejection) -> Response {
         .into_response(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detail_carries_code_name_and_retry_hint() {
+        let detail = ApiError::with_code(ErrorCode::TransactionTimeout, "Confirmation timed out").detail();
+        assert_eq!(detail.code_name, "TRANSACTION_TIMEOUT");
+        assert!(detail.retryable);
+
+        let detail = ApiError::with_code(ErrorCode::OrderViolatesMarketRules, "Minimum order size is 1 kWh").detail();
+        assert_eq!(detail.code_name, "ORDER_VIOLATES_MARKET_RULES");
+        assert!(!detail.retryable);
+
+        assert!(ApiError::Database(sqlx::Error::PoolTimedOut).retryable());
+        assert!(!ApiError::Database(sqlx::Error::RowNotFound).retryable());
+        assert!(ApiError::RateLimitExceeded("slow down".to_string()).retryable());
+        assert!(!ApiError::BadRequest("bad".to_string()).retryable());
+    }
+}
@@ -0,0 +1,292 @@
+//! FIX tag=value framing: building outgoing messages with BodyLength and
+//! CheckSum, and splitting the inbound byte stream into validated messages.
+
+use chrono::{DateTime, Utc};
+
+pub const BEGIN_STRING: &str = "FIX.4.4";
+pub const SOH: u8 = 0x01;
+
+/// Frames larger than this are treated as garbage and the session is dropped
+const MAX_FRAME_LEN: usize = 64 * 1024;
+
+pub mod tags {
+    pub const BEGIN_STRING: u32 = 8;
+    pub const BODY_LENGTH: u32 = 9;
+    pub const CHECKSUM: u32 = 10;
+    pub const MSG_TYPE: u32 = 35;
+    pub const SENDER_COMP_ID: u32 = 49;
+    pub const TARGET_COMP_ID: u32 = 56;
+    pub const MSG_SEQ_NUM: u32 = 34;
+    pub const POSS_DUP_FLAG: u32 = 43;
+    pub const SENDING_TIME: u32 = 52;
+    pub const BEGIN_SEQ_NO: u32 = 7;
+    pub const END_SEQ_NO: u32 = 16;
+    pub const NEW_SEQ_NO: u32 = 36;
+    pub const GAP_FILL_FLAG: u32 = 123;
+    pub const TEST_REQ_ID: u32 = 112;
+    pub const ENCRYPT_METHOD: u32 = 98;
+    pub const HEART_BT_INT: u32 = 108;
+    pub const RESET_SEQ_NUM_FLAG: u32 = 141;
+    pub const USERNAME: u32 = 553;
+    pub const PASSWORD: u32 = 554;
+    pub const TEXT: u32 = 58;
+    pub const REF_SEQ_NUM: u32 = 45;
+    pub const REF_MSG_TYPE: u32 = 372;
+    pub const BUSINESS_REJECT_REASON: u32 = 380;
+    pub const CL_ORD_ID: u32 = 11;
+    pub const ORIG_CL_ORD_ID: u32 = 41;
+    pub const ORDER_ID: u32 = 37;
+    pub const EXEC_ID: u32 = 17;
+    pub const EXEC_TYPE: u32 = 150;
+    pub const ORD_STATUS: u32 = 39;
+    pub const ORD_REJ_REASON: u32 = 103;
+    pub const SYMBOL: u32 = 55;
+    pub const SIDE: u32 = 54;
+    pub const ORDER_QTY: u32 = 38;
+    pub const ORD_TYPE: u32 = 40;
+    pub const PRICE: u32 = 44;
+    pub const EXPIRE_TIME: u32 = 126;
+    pub const TRANSACT_TIME: u32 = 60;
+    pub const CUM_QTY: u32 = 14;
+    pub const LEAVES_QTY: u32 = 151;
+    pub const AVG_PX: u32 = 6;
+    pub const LAST_QTY: u32 = 32;
+    pub const LAST_PX: u32 = 31;
+    pub const CXL_REJ_RESPONSE_TO: u32 = 434;
+    pub const CXL_REJ_REASON: u32 = 102;
+}
+
+pub mod msg_type {
+    pub const HEARTBEAT: &str = "0";
+    pub const TEST_REQUEST: &str = "1";
+    pub const RESEND_REQUEST: &str = "2";
+    pub const REJECT: &str = "3";
+    pub const SEQUENCE_RESET: &str = "4";
+    pub const LOGOUT: &str = "5";
+    pub const LOGON: &str = "A";
+    pub const NEW_ORDER_SINGLE: &str = "D";
+    pub const ORDER_CANCEL_REQUEST: &str = "F";
+    pub const EXECUTION_REPORT: &str = "8";
+    pub const ORDER_CANCEL_REJECT: &str = "9";
+    pub const BUSINESS_MESSAGE_REJECT: &str = "j";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CodecError {
+    #[error("Frame does not start with 8={}", BEGIN_STRING)]
+    BadBeginString,
+    #[error("Invalid BodyLength")]
+    BadBodyLength,
+    #[error("Frame exceeds {} bytes", MAX_FRAME_LEN)]
+    TooLarge,
+    #[error("CheckSum mismatch: expected {expected:03}, got {actual}")]
+    BadChecksum { expected: u8, actual: String },
+    #[error("Malformed field: {0}")]
+    BadField(String),
+}
+
+/// A decoded message; fields in wire order, header and trailer included
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixMessage {
+    fields: Vec<(u32, String)>,
+}
+
+impl FixMessage {
+    pub fn get(&self, tag: u32) -> Option<&str> {
+        self.fields.iter().find(|(t, _)| *t == tag).map(|(_, v)| v.as_str())
+    }
+
+    pub fn msg_type(&self) -> &str {
+        self.get(tags::MSG_TYPE).unwrap_or_default()
+    }
+
+    pub fn seq_num(&self) -> Option<u64> {
+        self.get(tags::MSG_SEQ_NUM).and_then(|v| v.parse().ok())
+    }
+
+    pub fn flag(&self, tag: u32) -> bool {
+        self.get(tag) == Some("Y")
+    }
+}
+
+/// Sum of bytes modulo 256, as carried in tag 10
+pub fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u32, |acc, b| acc + u32::from(*b)) as u8
+}
+
+/// FIX UTCTimestamp with milliseconds
+pub fn format_timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%d-%H:%M:%S%.3f").to_string()
+}
+
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    ["%Y%m%d-%H:%M:%S%.f", "%Y%m%d-%H:%M:%S"]
+        .iter()
+        .find_map(|fmt| chrono::NaiveDateTime::parse_from_str(value, fmt).ok())
+        .map(|naive| naive.and_utc())
+}
+
+/// Outgoing message body; the session adds the standard header and trailer
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageBuilder {
+    msg_type: &'static str,
+    body: Vec<(u32, String)>,
+}
+
+impl MessageBuilder {
+    pub fn new(msg_type: &'static str) -> Self {
+        Self { msg_type, body: Vec::new() }
+    }
+
+    pub fn msg_type(&self) -> &'static str {
+        self.msg_type
+    }
+
+    pub fn field(mut self, tag: u32, value: impl ToString) -> Self {
+        self.body.push((tag, value.to_string()));
+        self
+    }
+
+    pub fn opt_field(self, tag: u32, value: Option<impl ToString>) -> Self {
+        match value {
+            Some(value) => self.field(tag, value),
+            None => self,
+        }
+    }
+
+    /// Serialize with header (49/56/34/52, plus 43 when `poss_dup`) and trailer
+    pub fn encode(&self, sender: &str, target: &str, seq: u64, sent_at: DateTime<Utc>, poss_dup: bool) -> Vec<u8> {
+        let mut body = String::new();
+        let mut push = |tag: u32, value: &str| {
+            body.push_str(&tag.to_string());
+            body.push('=');
+            body.push_str(value);
+            body.push(SOH as char);
+        };
+        push(tags::MSG_TYPE, self.msg_type);
+        push(tags::SENDER_COMP_ID, sender);
+        push(tags::TARGET_COMP_ID, target);
+        push(tags::MSG_SEQ_NUM, &seq.to_string());
+        if poss_dup {
+            push(tags::POSS_DUP_FLAG, "Y");
+        }
+        push(tags::SENDING_TIME, &format_timestamp(sent_at));
+        for (tag, value) in &self.body {
+            push(*tag, value);
+        }
+
+        let mut out = format!("8={}\x019={}\x01", BEGIN_STRING, body.len()).into_bytes();
+        out.extend_from_slice(body.as_bytes());
+        let sum = checksum(&out);
+        out.extend_from_slice(format!("10={:03}\x01", sum).as_bytes());
+        out
+    }
+}
+
+/// Take one complete message off the front of `buf`.
+/// `Ok(None)` means more bytes are needed.
+pub fn decode(buf: &mut Vec<u8>) -> Result<Option<FixMessage>, CodecError> {
+    let prefix = format!("8={}\x019=", BEGIN_STRING);
+    if buf.len() < prefix.len() {
+        return if prefix.as_bytes().starts_with(buf) { Ok(None) } else { Err(CodecError::BadBeginString) };
+    }
+    if !buf.starts_with(prefix.as_bytes()) {
+        return Err(CodecError::BadBeginString);
+    }
+
+    let Some(len_end) = buf[prefix.len()..].iter().position(|b| *b == SOH).map(|p| p + prefix.len()) else {
+        return if buf.len() > prefix.len() + 8 { Err(CodecError::BadBodyLength) } else { Ok(None) };
+    };
+    let body_len: usize = std::str::from_utf8(&buf[prefix.len()..len_end])
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .ok_or(CodecError::BadBodyLength)?;
+
+    let body_start = len_end + 1;
+    let trailer_start = body_start + body_len;
+    let frame_end = trailer_start + 7; // "10=nnn\x01"
+    if frame_end > MAX_FRAME_LEN {
+        return Err(CodecError::TooLarge);
+    }
+    if buf.len() < frame_end {
+        return Ok(None);
+    }
+
+    let trailer = &buf[trailer_start..frame_end];
+    if !trailer.starts_with(b"10=") || trailer[6] != SOH {
+        return Err(CodecError::BadBodyLength);
+    }
+    let actual = String::from_utf8_lossy(&trailer[3..6]).to_string();
+    let expected = checksum(&buf[..trailer_start]);
+    if actual.parse::<u8>().ok() != Some(expected) {
+        buf.drain(..frame_end);
+        return Err(CodecError::BadChecksum { expected, actual });
+    }
+
+    let frame: Vec<u8> = buf.drain(..frame_end).collect();
+    let text = String::from_utf8_lossy(&frame[..frame.len() - 1]).to_string();
+    let fields = text
+        .split(SOH as char)
+        .map(|field| {
+            let (tag, value) = field.split_once('=').ok_or_else(|| CodecError::BadField(field.to_string()))?;
+            let tag = tag.parse::<u32>().map_err(|_| CodecError::BadField(field.to_string()))?;
+            Ok((tag, value.to_string()))
+        })
+        .collect::<Result<Vec<_>, CodecError>>()?;
+
+    Ok(Some(FixMessage { fields }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heartbeat(seq: u64) -> Vec<u8> {
+        MessageBuilder::new(msg_type::HEARTBEAT)
+            .field(tags::TEST_REQ_ID, "T1")
+            .encode("GRID", "DESK1", seq, Utc::now(), false)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut buf = heartbeat(7);
+        let msg = decode(&mut buf).unwrap().unwrap();
+        assert!(buf.is_empty());
+        assert_eq!(msg.msg_type(), msg_type::HEARTBEAT);
+        assert_eq!(msg.seq_num(), Some(7));
+        assert_eq!(msg.get(tags::SENDER_COMP_ID), Some("GRID"));
+        assert_eq!(msg.get(tags::TEST_REQ_ID), Some("T1"));
+    }
+
+    #[test]
+    fn test_partial_and_back_to_back_frames() {
+        let first = heartbeat(1);
+        let mut buf = first[..10].to_vec();
+        assert_eq!(decode(&mut buf).unwrap(), None);
+
+        let mut buf = first.clone();
+        buf.extend_from_slice(&heartbeat(2));
+        assert_eq!(decode(&mut buf).unwrap().unwrap().seq_num(), Some(1));
+        assert_eq!(decode(&mut buf).unwrap().unwrap().seq_num(), Some(2));
+        assert_eq!(decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn test_rejects_bad_checksum() {
+        let mut buf = heartbeat(1);
+        let len = buf.len();
+        buf[len - 2] = if buf[len - 2] == b'0' { b'1' } else { b'0' };
+        assert!(matches!(decode(&mut buf), Err(CodecError::BadChecksum { .. })));
+
+        let mut garbage = b"GET / HTTP/1.1\r\n".to_vec();
+        assert_eq!(decode(&mut garbage), Err(CodecError::BadBeginString));
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let at = parse_timestamp("20260118-09:30:00.250").unwrap();
+        assert_eq!(format_timestamp(at), "20260118-09:30:00.250");
+        assert!(parse_timestamp("20260118-09:30:00").is_some());
+        assert!(parse_timestamp("yesterday").is_none());
+    }
+}
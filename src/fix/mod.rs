@@ -0,0 +1,605 @@
+//! FIX 4.4 Gateway
+//!
+//! Optional acceptor on its own TCP listener for institutional clients.
+//! Order-entry sessions send NewOrderSingle (35=D) and OrderCancelRequest
+//! (35=F), which go through the same placement and cancel path as the REST
+//! API, and receive ExecutionReports (35=8) for their orders. Drop-copy
+//! sessions only receive ExecutionReports, for every order of their user
+//! whatever channel it was entered on.
+//!
+//! Sessions are provisioned by admins (`FixSessionService`). Inbound and
+//! outbound sequence numbers are persisted after every message so a session
+//! resumes where it left off unless the Logon carries ResetSeqNumFlag (141=Y).
+//! Outbound messages are not stored: ResendRequests are answered with a
+//! SequenceReset-GapFill.
+
+pub mod codec;
+pub mod session;
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::FixGatewayConfig;
+use crate::handlers::trading::orders::create::submit_order;
+use crate::handlers::trading::orders::management::cancel_user_order;
+use crate::models::trading::CreateOrderRequest;
+use crate::services::fix_sessions::FixSession;
+use crate::services::order_events::{self, OrderEventType, RecordedEvent};
+use crate::AppState;
+
+use codec::{decode, msg_type, tags, CodecError, FixMessage, MessageBuilder};
+use session::{check_sequence, parse_new_order, ExecKind, ExecReport, SeqCheck};
+
+const DEFAULT_HEARTBEAT_SECS: u64 = 30;
+
+/// Accept FIX connections until the process exits
+pub async fn serve(state: AppState, config: FixGatewayConfig) {
+    let listener = match TcpListener::bind(&config.bind_addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("❌ FIX gateway failed to bind {}: {}", config.bind_addr, e);
+            return;
+        }
+    };
+    info!("🚀 FIX gateway listening on {} as {}", config.bind_addr, config.comp_id);
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let state = state.clone();
+                let config = config.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = Connection::accept(state, &config, stream).await {
+                        warn!("FIX connection from {} closed: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) => warn!("FIX gateway accept failed: {}", e),
+        }
+    }
+}
+
+/// Whether the connection should stay up after handling a message
+enum Flow {
+    Continue,
+    Close,
+}
+
+struct Connection {
+    state: AppState,
+    stream: TcpStream,
+    buf: Vec<u8>,
+    comp_id: String,
+    session: FixSession,
+    next_in: u64,
+    next_out: u64,
+    heartbeat: Duration,
+    last_received: Instant,
+    last_sent: Instant,
+    /// TestReqID we are waiting on
+    test_request: Option<String>,
+    /// Highest MsgSeqNum seen while a ResendRequest is outstanding
+    resend_until: Option<u64>,
+    /// Orders canceled by this session; already reported synchronously
+    pending_cancels: HashSet<Uuid>,
+}
+
+impl Connection {
+    /// Run the Logon handshake, then the session until either side leaves
+    async fn accept(state: AppState, config: &FixGatewayConfig, mut stream: TcpStream) -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        let logon = tokio::time::timeout(
+            Duration::from_secs(config.logon_timeout_secs),
+            read_message(&mut stream, &mut buf),
+        )
+        .await
+        .map_err(|_| anyhow!("no Logon within {}s", config.logon_timeout_secs))??;
+
+        if logon.msg_type() != msg_type::LOGON {
+            return Err(anyhow!("first message was 35={}, expected Logon", logon.msg_type()));
+        }
+        if logon.get(tags::TARGET_COMP_ID) != Some(config.comp_id.as_str()) {
+            return Err(anyhow!("Logon addressed to {:?}", logon.get(tags::TARGET_COMP_ID)));
+        }
+        let sender = logon.get(tags::SENDER_COMP_ID).unwrap_or_default().to_string();
+        let password = logon.get(tags::PASSWORD).unwrap_or_default();
+        let session = state
+            .fix_sessions
+            .authenticate(&sender, password)
+            .await?
+            .ok_or_else(|| anyhow!("rejected Logon from {}: unknown, disabled or bad password", sender))?;
+        if !state.fix_sessions.claim(session.id) {
+            return Err(anyhow!("{} is already logged on", sender));
+        }
+
+        let mut connection = Connection {
+            state: state.clone(),
+            stream,
+            buf,
+            comp_id: config.comp_id.clone(),
+            next_in: session.next_in_seq.max(1) as u64,
+            next_out: session.next_out_seq.max(1) as u64,
+            session,
+            heartbeat: Duration::from_secs(DEFAULT_HEARTBEAT_SECS),
+            last_received: Instant::now(),
+            last_sent: Instant::now(),
+            test_request: None,
+            resend_until: None,
+            pending_cancels: HashSet::new(),
+        };
+        let result = connection.run(logon).await;
+
+        state.fix_sessions.release(connection.session.id);
+        if let Err(e) = state.fix_sessions.record_logout(connection.session.id).await {
+            warn!("Failed to record FIX logout of {}: {}", connection.session.sender_comp_id, e);
+        }
+        info!("🔌 FIX session {} logged out", connection.session.sender_comp_id);
+        result
+    }
+
+    async fn run(&mut self, logon: FixMessage) -> anyhow::Result<()> {
+        let reset = logon.flag(tags::RESET_SEQ_NUM_FLAG);
+        self.state.fix_sessions.record_logon(self.session.id, reset).await?;
+        if reset {
+            self.next_in = 1;
+            self.next_out = 1;
+        }
+        let heartbeat_secs = logon
+            .get(tags::HEART_BT_INT)
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_HEARTBEAT_SECS)
+            .clamp(5, 300);
+        self.heartbeat = Duration::from_secs(heartbeat_secs);
+
+        let seq = logon.seq_num().ok_or_else(|| anyhow!("Logon without MsgSeqNum"))?;
+        let check = check_sequence(self.next_in, seq, false);
+        if let SeqCheck::TooLow { expected } = check {
+            self.send(
+                MessageBuilder::new(msg_type::LOGOUT)
+                    .field(tags::TEXT, format!("MsgSeqNum too low, expecting {} but received {}", expected, seq)),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        self.send(
+            MessageBuilder::new(msg_type::LOGON)
+                .field(tags::ENCRYPT_METHOD, 0)
+                .field(tags::HEART_BT_INT, heartbeat_secs)
+                .opt_field(tags::RESET_SEQ_NUM_FLAG, reset.then_some("Y")),
+        )
+        .await?;
+        info!(
+            "🔌 FIX {} session {} logged on (in {}, out {})",
+            self.session.session_type, self.session.sender_comp_id, self.next_in, self.next_out
+        );
+
+        match check {
+            SeqCheck::Resend { from } => self.request_resend(from, seq).await?,
+            _ => self.advance_in(seq + 1).await?,
+        }
+
+        let mut feed = order_events::subscribe();
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            tokio::select! {
+                read = self.stream.read_buf(&mut self.buf) => {
+                    if read? == 0 {
+                        return Ok(());
+                    }
+                    self.last_received = Instant::now();
+                    loop {
+                        let msg = match decode(&mut self.buf) {
+                            Ok(Some(msg)) => msg,
+                            Ok(None) => break,
+                            // Garbled messages are dropped without consuming a sequence number
+                            Err(e @ CodecError::BadChecksum { .. }) => {
+                                warn!("FIX session {}: {}", self.session.sender_comp_id, e);
+                                continue;
+                            }
+                            Err(e) => return Err(e.into()),
+                        };
+                        if let Flow::Close = self.on_message(msg).await? {
+                            return Ok(());
+                        }
+                    }
+                }
+                event = feed.recv() => match event {
+                    Ok(event) => self.on_event(event).await?,
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("FIX session {} missed {} order events", self.session.sender_comp_id, missed);
+                    }
+                    Err(RecvError::Closed) => return Ok(()),
+                },
+                _ = ticker.tick() => {
+                    if let Flow::Close = self.on_tick().await? {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    async fn on_tick(&mut self) -> anyhow::Result<Flow> {
+        let silent = self.last_received.elapsed();
+        if self.test_request.is_some() && silent >= self.heartbeat * 2 {
+            warn!("FIX session {} timed out", self.session.sender_comp_id);
+            return Ok(Flow::Close);
+        }
+        if self.test_request.is_none() && silent >= self.heartbeat + self.heartbeat / 5 {
+            let id = Utc::now().timestamp_millis().to_string();
+            self.send(MessageBuilder::new(msg_type::TEST_REQUEST).field(tags::TEST_REQ_ID, &id))
+                .await?;
+            self.test_request = Some(id);
+        }
+        if self.last_sent.elapsed() >= self.heartbeat {
+            self.send(MessageBuilder::new(msg_type::HEARTBEAT)).await?;
+        }
+        Ok(Flow::Continue)
+    }
+
+    async fn on_message(&mut self, msg: FixMessage) -> anyhow::Result<Flow> {
+        if msg.get(tags::SENDER_COMP_ID) != Some(self.session.sender_comp_id.as_str())
+            || msg.get(tags::TARGET_COMP_ID) != Some(self.comp_id.as_str())
+        {
+            self.send(MessageBuilder::new(msg_type::LOGOUT).field(tags::TEXT, "CompID problem"))
+                .await?;
+            return Ok(Flow::Close);
+        }
+        let Some(seq) = msg.seq_num() else {
+            self.send(MessageBuilder::new(msg_type::LOGOUT).field(tags::TEXT, "Missing MsgSeqNum"))
+                .await?;
+            return Ok(Flow::Close);
+        };
+
+        // SequenceReset-Reset moves the expected number whatever the MsgSeqNum
+        if msg.msg_type() == msg_type::SEQUENCE_RESET && !msg.flag(tags::GAP_FILL_FLAG) {
+            match msg.get(tags::NEW_SEQ_NO).and_then(|v| v.parse::<u64>().ok()) {
+                Some(new_seq) if new_seq >= self.next_in => self.advance_in(new_seq).await?,
+                _ => self.reject(&msg, "NewSeqNo must not decrease").await?,
+            }
+            return Ok(Flow::Continue);
+        }
+
+        match check_sequence(self.next_in, seq, msg.flag(tags::POSS_DUP_FLAG)) {
+            SeqCheck::Process => {}
+            SeqCheck::Duplicate => return Ok(Flow::Continue),
+            SeqCheck::TooLow { expected } => {
+                self.send(
+                    MessageBuilder::new(msg_type::LOGOUT)
+                        .field(tags::TEXT, format!("MsgSeqNum too low, expecting {} but received {}", expected, seq)),
+                )
+                .await?;
+                return Ok(Flow::Close);
+            }
+            SeqCheck::Resend { from } => {
+                self.request_resend(from, seq).await?;
+                // The peer's own ResendRequest is answered even across a gap
+                if msg.msg_type() == msg_type::RESEND_REQUEST {
+                    self.answer_resend(&msg).await?;
+                }
+                return Ok(Flow::Continue);
+            }
+        }
+
+        let next = match msg.msg_type() {
+            msg_type::SEQUENCE_RESET => msg
+                .get(tags::NEW_SEQ_NO)
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|new_seq| *new_seq > seq)
+                .unwrap_or(seq + 1),
+            _ => seq + 1,
+        };
+        self.advance_in(next).await?;
+
+        match msg.msg_type() {
+            msg_type::HEARTBEAT => {
+                if self.test_request.as_deref().is_some_and(|id| msg.get(tags::TEST_REQ_ID) == Some(id)) {
+                    self.test_request = None;
+                }
+            }
+            msg_type::TEST_REQUEST => {
+                self.send(
+                    MessageBuilder::new(msg_type::HEARTBEAT).opt_field(tags::TEST_REQ_ID, msg.get(tags::TEST_REQ_ID)),
+                )
+                .await?;
+            }
+            msg_type::RESEND_REQUEST => self.answer_resend(&msg).await?,
+            msg_type::SEQUENCE_RESET => {}
+            msg_type::LOGOUT => {
+                self.send(MessageBuilder::new(msg_type::LOGOUT)).await?;
+                return Ok(Flow::Close);
+            }
+            msg_type::LOGON => self.reject(&msg, "Already logged on").await?,
+            msg_type::NEW_ORDER_SINGLE => self.on_new_order(&msg).await?,
+            msg_type::ORDER_CANCEL_REQUEST => self.on_cancel(&msg).await?,
+            _ => self.business_reject(&msg, 3, "Unsupported message type").await?,
+        }
+        Ok(Flow::Continue)
+    }
+
+    async fn on_new_order(&mut self, msg: &FixMessage) -> anyhow::Result<()> {
+        if self.session.is_drop_copy() {
+            return self.business_reject(msg, 6, "Drop copy sessions cannot enter orders").await;
+        }
+        let order = match parse_new_order(msg) {
+            Ok(order) => order,
+            Err(text) => return self.business_reject(msg, 5, &text).await,
+        };
+        let mut report = ExecReport {
+            kind: ExecKind::Rejected,
+            order_id: None,
+            cl_ord_id: Some(order.cl_ord_id.clone()),
+            orig_cl_ord_id: None,
+            symbol: order.symbol.clone(),
+            side: order.side,
+            order_qty: order.quantity,
+            cum_qty: Decimal::ZERO,
+            price: order.price,
+            last_qty: None,
+            last_px: None,
+            text: None,
+            reject_reason: None,
+        };
+
+        if self.state.fix_sessions.cl_ord_id_used(self.session.id, &order.cl_ord_id).await? {
+            report.reject_reason = Some(6);
+            report.text = Some("Duplicate ClOrdID".to_string());
+            return self.send(report.to_message()).await;
+        }
+        let market = match self.state.markets.by_code(&order.symbol).await {
+            Ok(market) => market,
+            Err(e) => {
+                report.reject_reason = Some(1);
+                report.text = Some(e.to_string());
+                return self.send(report.to_message()).await;
+            }
+        };
+
+        let payload = CreateOrderRequest {
+            side: order.side,
+            energy_amount: order.quantity,
+            price_per_kwh: order.price,
+            order_type: order.order_type,
+            expiry_time: order.expire_time,
+            zone_id: None,
+            meter_id: None,
+            signature: None,
+            timestamp: None,
+            session_token: None,
+            market_id: Some(market.id),
+        };
+        let placed = match payload.validate() {
+            Ok(()) => submit_order(&self.state, self.session.user_id, payload).await,
+            Err(e) => Err(e.into()),
+        };
+        match placed {
+            Ok(response) => {
+                self.state
+                    .fix_sessions
+                    .map_order(self.session.id, &order.cl_ord_id, response.id)
+                    .await?;
+                report.kind = ExecKind::New;
+                report.order_id = Some(response.id);
+            }
+            Err(e) => {
+                report.reject_reason = Some(0);
+                report.text = Some(e.to_string());
+            }
+        }
+        self.send(report.to_message()).await
+    }
+
+    async fn on_cancel(&mut self, msg: &FixMessage) -> anyhow::Result<()> {
+        if self.session.is_drop_copy() {
+            return self.business_reject(msg, 6, "Drop copy sessions cannot cancel orders").await;
+        }
+        let cl_ord_id = msg.get(tags::CL_ORD_ID).unwrap_or_default().to_string();
+        let orig_cl_ord_id = msg.get(tags::ORIG_CL_ORD_ID).unwrap_or_default().to_string();
+
+        let order_id = self
+            .state
+            .fix_sessions
+            .order_for_cl_ord_id(self.session.id, &orig_cl_ord_id)
+            .await?;
+        let Some(order_id) = order_id else {
+            return self.cancel_reject(None, &cl_ord_id, &orig_cl_ord_id, 1, "Unknown order").await;
+        };
+
+        match cancel_user_order(&self.state, self.session.user_id, order_id).await {
+            Ok(order) => {
+                self.pending_cancels.insert(order_id);
+                let symbol = self
+                    .state
+                    .fix_sessions
+                    .order_context(order_id)
+                    .await?
+                    .map(|ctx| ctx.symbol)
+                    .or_else(|| msg.get(tags::SYMBOL).map(str::to_string))
+                    .unwrap_or_default();
+                let report = ExecReport {
+                    kind: ExecKind::Canceled,
+                    order_id: Some(order_id),
+                    cl_ord_id: Some(cl_ord_id),
+                    orig_cl_ord_id: Some(orig_cl_ord_id),
+                    symbol,
+                    side: order.side,
+                    order_qty: order.energy_amount,
+                    cum_qty: order.filled_amount,
+                    price: Some(order.price_per_kwh),
+                    last_qty: None,
+                    last_px: None,
+                    text: None,
+                    reject_reason: None,
+                };
+                self.send(report.to_message()).await
+            }
+            Err(e) => {
+                self.cancel_reject(Some(order_id), &cl_ord_id, &orig_cl_ord_id, 0, &e.to_string())
+                    .await
+            }
+        }
+    }
+
+    /// Forward an order event as an ExecutionReport if this session should see it
+    async fn on_event(&mut self, recorded: RecordedEvent) -> anyhow::Result<()> {
+        let event = recorded.event;
+        if event.user_id != self.session.user_id {
+            return Ok(());
+        }
+        if !self.session.is_drop_copy() {
+            // New, Rejected and our own cancels were answered synchronously
+            match event.event_type {
+                OrderEventType::Accepted | OrderEventType::Rejected => return Ok(()),
+                OrderEventType::Cancelled if self.pending_cancels.remove(&event.order_id) => return Ok(()),
+                _ => {}
+            }
+        }
+
+        let Some(ctx) = self.state.fix_sessions.order_context(event.order_id).await? else {
+            return Ok(());
+        };
+        if !self.session.is_drop_copy() && ctx.session_id != Some(self.session.id) {
+            return Ok(());
+        }
+
+        let report = ExecReport {
+            kind: ExecKind::from_event(event.event_type),
+            order_id: Some(ctx.order_id),
+            cl_ord_id: ctx.cl_ord_id,
+            orig_cl_ord_id: None,
+            symbol: ctx.symbol,
+            side: ctx.side,
+            order_qty: ctx.energy_amount,
+            cum_qty: event.cumulative_filled.unwrap_or(Decimal::ZERO),
+            price: Some(ctx.price_per_kwh),
+            last_qty: event.fill_quantity,
+            last_px: event.price,
+            text: event.reason,
+            reject_reason: None,
+        };
+        self.send(report.to_message()).await
+    }
+
+    async fn request_resend(&mut self, from: u64, seen: u64) -> anyhow::Result<()> {
+        if self.resend_until.is_some_and(|until| until >= seen) {
+            return Ok(());
+        }
+        self.resend_until = Some(seen);
+        self.send(
+            MessageBuilder::new(msg_type::RESEND_REQUEST)
+                .field(tags::BEGIN_SEQ_NO, from)
+                .field(tags::END_SEQ_NO, 0),
+        )
+        .await
+    }
+
+    /// Outbound messages are not stored, so the whole range is gap-filled
+    async fn answer_resend(&mut self, msg: &FixMessage) -> anyhow::Result<()> {
+        let begin = msg
+            .get(tags::BEGIN_SEQ_NO)
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(1)
+            .max(1);
+        if begin >= self.next_out {
+            return Ok(());
+        }
+        let gap_fill = MessageBuilder::new(msg_type::SEQUENCE_RESET)
+            .field(tags::GAP_FILL_FLAG, "Y")
+            .field(tags::NEW_SEQ_NO, self.next_out);
+        self.write(&gap_fill.encode(&self.comp_id, &self.session.sender_comp_id, begin, Utc::now(), true))
+            .await
+    }
+
+    async fn advance_in(&mut self, next: u64) -> anyhow::Result<()> {
+        self.next_in = next;
+        if self.resend_until.is_some_and(|until| next > until) {
+            self.resend_until = None;
+        }
+        self.state.fix_sessions.set_next_in(self.session.id, next).await?;
+        Ok(())
+    }
+
+    async fn send(&mut self, message: MessageBuilder) -> anyhow::Result<()> {
+        let seq = self.next_out;
+        let bytes = message.encode(&self.comp_id, &self.session.sender_comp_id, seq, Utc::now(), false);
+        self.write(&bytes).await?;
+        self.next_out = seq + 1;
+        self.state.fix_sessions.set_next_out(self.session.id, self.next_out).await?;
+        Ok(())
+    }
+
+    async fn write(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
+        self.stream.write_all(bytes).await.context("write failed")?;
+        self.last_sent = Instant::now();
+        Ok(())
+    }
+
+    /// Session-level Reject (35=3)
+    async fn reject(&mut self, msg: &FixMessage, text: &str) -> anyhow::Result<()> {
+        self.send(
+            MessageBuilder::new(msg_type::REJECT)
+                .opt_field(tags::REF_SEQ_NUM, msg.seq_num())
+                .field(tags::REF_MSG_TYPE, msg.msg_type())
+                .field(tags::TEXT, text),
+        )
+        .await
+    }
+
+    /// BusinessMessageReject (35=j) with BusinessRejectReason (380)
+    async fn business_reject(&mut self, msg: &FixMessage, reason: u32, text: &str) -> anyhow::Result<()> {
+        self.send(
+            MessageBuilder::new(msg_type::BUSINESS_MESSAGE_REJECT)
+                .opt_field(tags::REF_SEQ_NUM, msg.seq_num())
+                .field(tags::REF_MSG_TYPE, msg.msg_type())
+                .field(tags::BUSINESS_REJECT_REASON, reason)
+                .field(tags::TEXT, text),
+        )
+        .await
+    }
+
+    /// OrderCancelReject (35=9) with CxlRejReason (102)
+    async fn cancel_reject(
+        &mut self,
+        order_id: Option<Uuid>,
+        cl_ord_id: &str,
+        orig_cl_ord_id: &str,
+        reason: u32,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.send(
+            MessageBuilder::new(msg_type::ORDER_CANCEL_REJECT)
+                .field(tags::ORDER_ID, order_id.map(|id| id.to_string()).unwrap_or_else(|| "NONE".to_string()))
+                .field(tags::CL_ORD_ID, cl_ord_id)
+                .field(tags::ORIG_CL_ORD_ID, orig_cl_ord_id)
+                .field(tags::ORD_STATUS, "8")
+                .field(tags::CXL_REJ_RESPONSE_TO, 1)
+                .field(tags::CXL_REJ_REASON, reason)
+                .field(tags::TEXT, text),
+        )
+        .await
+    }
+}
+
+/// Read until one complete message is buffered
+async fn read_message(stream: &mut TcpStream, buf: &mut Vec<u8>) -> anyhow::Result<FixMessage> {
+    loop {
+        if let Some(msg) = decode(buf)? {
+            return Ok(msg);
+        }
+        if stream.read_buf(buf).await? == 0 {
+            return Err(anyhow!("connection closed before Logon"));
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Session-level rules (sequence numbers) and translation between FIX
+//! application messages and the internal order model.
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::database::schema::types::{OrderSide, OrderType};
+use crate::services::order_events::OrderEventType;
+
+use super::codec::{msg_type, tags, FixMessage, MessageBuilder};
+
+/// What to do with an inbound message given the expected sequence number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeqCheck {
+    /// In order; process it and expect `seq + 1`
+    Process,
+    /// Messages were missed; ask for `from..` and do not process this one
+    Resend { from: u64 },
+    /// Already seen and flagged PossDup; ignore
+    Duplicate,
+    /// Lower than expected without PossDup; the session must be terminated
+    TooLow { expected: u64 },
+}
+
+pub fn check_sequence(expected: u64, seq: u64, poss_dup: bool) -> SeqCheck {
+    match seq.cmp(&expected) {
+        std::cmp::Ordering::Equal => SeqCheck::Process,
+        std::cmp::Ordering::Greater => SeqCheck::Resend { from: expected },
+        std::cmp::Ordering::Less if poss_dup => SeqCheck::Duplicate,
+        std::cmp::Ordering::Less => SeqCheck::TooLow { expected },
+    }
+}
+
+/// NewOrderSingle mapped onto the internal order fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewOrder {
+    pub cl_ord_id: String,
+    /// Market code
+    pub symbol: String,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub quantity: Decimal,
+    pub price: Option<Decimal>,
+    pub expire_time: Option<chrono::DateTime<Utc>>,
+}
+
+fn required<'a>(msg: &'a FixMessage, tag: u32, name: &str) -> Result<&'a str, String> {
+    msg.get(tag)
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| format!("Missing {} ({})", name, tag))
+}
+
+fn decimal(value: &str, name: &str) -> Result<Decimal, String> {
+    value.parse::<Decimal>().map_err(|_| format!("Invalid {}: {}", name, value))
+}
+
+pub fn parse_side(value: &str) -> Result<OrderSide, String> {
+    match value {
+        "1" => Ok(OrderSide::Buy),
+        "2" => Ok(OrderSide::Sell),
+        other => Err(format!("Unsupported Side: {}", other)),
+    }
+}
+
+pub fn side_code(side: OrderSide) -> &'static str {
+    match side {
+        OrderSide::Buy => "1",
+        OrderSide::Sell => "2",
+    }
+}
+
+/// Read a NewOrderSingle (35=D). Quantities are kWh and prices per kWh.
+pub fn parse_new_order(msg: &FixMessage) -> Result<NewOrder, String> {
+    let cl_ord_id = required(msg, tags::CL_ORD_ID, "ClOrdID")?.to_string();
+    let symbol = required(msg, tags::SYMBOL, "Symbol")?.to_string();
+    let side = parse_side(required(msg, tags::SIDE, "Side")?)?;
+    let quantity = decimal(required(msg, tags::ORDER_QTY, "OrderQty")?, "OrderQty")?;
+    let order_type = match required(msg, tags::ORD_TYPE, "OrdType")? {
+        "1" => OrderType::Market,
+        "2" => OrderType::Limit,
+        other => return Err(format!("Unsupported OrdType: {}", other)),
+    };
+    let price = msg.get(tags::PRICE).map(|p| decimal(p, "Price")).transpose()?;
+    if order_type == OrderType::Limit && price.is_none() {
+        return Err("Missing Price (44) for limit order".to_string());
+    }
+    let expire_time = msg
+        .get(tags::EXPIRE_TIME)
+        .map(|v| super::codec::parse_timestamp(v).ok_or_else(|| format!("Invalid ExpireTime: {}", v)))
+        .transpose()?;
+
+    Ok(NewOrder {
+        cl_ord_id,
+        symbol,
+        side,
+        order_type,
+        quantity,
+        price,
+        expire_time,
+    })
+}
+
+/// ExecType (150) and OrdStatus (39) of an execution report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecKind {
+    New,
+    PartialFill,
+    Fill,
+    Canceled,
+    Expired,
+    Rejected,
+    /// Reopened by an epoch rollback
+    Restated,
+}
+
+impl ExecKind {
+    pub fn from_event(event: OrderEventType) -> Self {
+        match event {
+            OrderEventType::Accepted => Self::New,
+            OrderEventType::PartiallyFilled => Self::PartialFill,
+            OrderEventType::Filled => Self::Fill,
+            OrderEventType::Cancelled => Self::Canceled,
+            OrderEventType::Expired => Self::Expired,
+            OrderEventType::Rejected => Self::Rejected,
+            OrderEventType::Reopened => Self::Restated,
+        }
+    }
+
+    /// (ExecType, OrdStatus); fills use ExecType F (Trade) as in FIX 4.4
+    pub fn codes(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::New => ("0", "0"),
+            Self::PartialFill => ("F", "1"),
+            Self::Fill => ("F", "2"),
+            Self::Canceled => ("4", "4"),
+            Self::Expired => ("C", "C"),
+            Self::Rejected => ("8", "8"),
+            Self::Restated => ("D", "0"),
+        }
+    }
+}
+
+/// Data for one ExecutionReport (35=8)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecReport {
+    pub kind: ExecKind,
+    pub order_id: Option<Uuid>,
+    pub cl_ord_id: Option<String>,
+    pub orig_cl_ord_id: Option<String>,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub order_qty: Decimal,
+    pub cum_qty: Decimal,
+    pub price: Option<Decimal>,
+    pub last_qty: Option<Decimal>,
+    pub last_px: Option<Decimal>,
+    pub text: Option<String>,
+    /// OrdRejReason (103), e.g. 6 for a duplicate ClOrdID
+    pub reject_reason: Option<u32>,
+}
+
+impl ExecReport {
+    pub fn to_message(&self) -> MessageBuilder {
+        let (exec_type, mut ord_status) = self.kind.codes();
+        // A reopened order keeps the fills it had before the rolled-back epoch
+        if self.kind == ExecKind::Restated && self.cum_qty > Decimal::ZERO {
+            ord_status = "1";
+        }
+        let leaves = match self.kind {
+            ExecKind::New | ExecKind::PartialFill | ExecKind::Restated => {
+                (self.order_qty - self.cum_qty).max(Decimal::ZERO)
+            }
+            _ => Decimal::ZERO,
+        };
+        MessageBuilder::new(msg_type::EXECUTION_REPORT)
+            .field(tags::ORDER_ID, self.order_id.map(|id| id.to_string()).unwrap_or_else(|| "NONE".to_string()))
+            .opt_field(tags::CL_ORD_ID, self.cl_ord_id.as_deref())
+            .opt_field(tags::ORIG_CL_ORD_ID, self.orig_cl_ord_id.as_deref())
+            .field(tags::EXEC_ID, Uuid::new_v4())
+            .field(tags::EXEC_TYPE, exec_type)
+            .field(tags::ORD_STATUS, ord_status)
+            .opt_field(tags::ORD_REJ_REASON, self.reject_reason)
+            .field(tags::SYMBOL, &self.symbol)
+            .field(tags::SIDE, side_code(self.side))
+            .field(tags::ORDER_QTY, self.order_qty.normalize())
+            .opt_field(tags::PRICE, self.price.map(|p| p.normalize()))
+            .opt_field(tags::LAST_QTY, self.last_qty.map(|q| q.normalize()))
+            .opt_field(tags::LAST_PX, self.last_px.map(|p| p.normalize()))
+            .field(tags::LEAVES_QTY, leaves.normalize())
+            .field(tags::CUM_QTY, self.cum_qty.normalize())
+            .field(tags::AVG_PX, Decimal::ZERO)
+            .field(tags::TRANSACT_TIME, super::codec::format_timestamp(Utc::now()))
+            .opt_field(tags::TEXT, self.text.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fix::codec::decode;
+
+    fn message(body: &[(u32, &str)]) -> FixMessage {
+        let builder = body
+            .iter()
+            .fold(MessageBuilder::new(msg_type::NEW_ORDER_SINGLE), |b, (tag, value)| b.field(*tag, value));
+        let mut bytes = builder.encode("DESK1", "GRID", 2, Utc::now(), false);
+        decode(&mut bytes).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_sequence_rules() {
+        assert_eq!(check_sequence(5, 5, false), SeqCheck::Process);
+        assert_eq!(check_sequence(5, 8, false), SeqCheck::Resend { from: 5 });
+        assert_eq!(check_sequence(5, 3, true), SeqCheck::Duplicate);
+        assert_eq!(check_sequence(5, 3, false), SeqCheck::TooLow { expected: 5 });
+    }
+
+    #[test]
+    fn test_parse_limit_order() {
+        let msg = message(&[
+            (tags::CL_ORD_ID, "C-1"),
+            (tags::SYMBOL, "ENERGY-SPOT"),
+            (tags::SIDE, "2"),
+            (tags::ORDER_QTY, "12.5"),
+            (tags::ORD_TYPE, "2"),
+            (tags::PRICE, "4.25"),
+        ]);
+        let order = parse_new_order(&msg).unwrap();
+        assert_eq!(order.side, OrderSide::Sell);
+        assert_eq!(order.order_type, OrderType::Limit);
+        assert_eq!(order.quantity, Decimal::new(125, 1));
+        assert_eq!(order.price, Some(Decimal::new(425, 2)));
+    }
+
+    #[test]
+    fn test_parse_rejects_incomplete_orders() {
+        let no_price = message(&[
+            (tags::CL_ORD_ID, "C-1"),
+            (tags::SYMBOL, "ENERGY-SPOT"),
+            (tags::SIDE, "1"),
+            (tags::ORDER_QTY, "1"),
+            (tags::ORD_TYPE, "2"),
+        ]);
+        assert!(parse_new_order(&no_price).unwrap_err().contains("Price"));
+
+        let no_id = message(&[(tags::SYMBOL, "ENERGY-SPOT")]);
+        assert!(parse_new_order(&no_id).unwrap_err().contains("ClOrdID"));
+    }
+
+    #[test]
+    fn test_exec_report_quantities() {
+        let report = ExecReport {
+            kind: ExecKind::PartialFill,
+            order_id: Some(Uuid::nil()),
+            cl_ord_id: Some("C-1".to_string()),
+            orig_cl_ord_id: None,
+            symbol: "ENERGY-SPOT".to_string(),
+            side: OrderSide::Buy,
+            order_qty: Decimal::from(10),
+            cum_qty: Decimal::from(4),
+            price: Some(Decimal::from(5)),
+            last_qty: Some(Decimal::from(4)),
+            last_px: Some(Decimal::from(5)),
+            text: None,
+            reject_reason: None,
+        };
+        let mut bytes = report.to_message().encode("GRID", "DESK1", 3, Utc::now(), false);
+        let msg = decode(&mut bytes).unwrap().unwrap();
+        assert_eq!(msg.get(tags::EXEC_TYPE), Some("F"));
+        assert_eq!(msg.get(tags::ORD_STATUS), Some("1"));
+        assert_eq!(msg.get(tags::LEAVES_QTY), Some("6"));
+        assert_eq!(msg.get(tags::CUM_QTY), Some("4"));
+    }
+}
@@ -56,6 +56,7 @@ pub async fn diagnose_all_wallets(
         state.config.encryption_secret.clone(),
         state.blockchain_service.clone(),
         state.config.solana_rpc_url.clone(),
+        state.pii.clone(),
     );
 
     match service.diagnose_all_users().await {
@@ -101,6 +102,7 @@ pub async fn diagnose_user_wallet(
         state.config.encryption_secret.clone(),
         state.blockchain_service.clone(),
         state.config.solana_rpc_url.clone(),
+        state.pii.clone(),
     );
 
     match service.diagnose_user_wallet(user_id).await {
@@ -150,6 +152,7 @@ pub async fn fix_user_wallet(
         state.config.encryption_secret.clone(),
         state.blockchain_service.clone(),
         state.config.solana_rpc_url.clone(),
+        state.pii.clone(),
     );
 
     match service
@@ -199,6 +202,7 @@ pub async fn fix_all_wallets(
         state.config.encryption_secret.clone(),
         state.blockchain_service.clone(),
         state.config.solana_rpc_url.clone(),
+        state.pii.clone(),
     );
 
     match service.fix_all_users().await {
@@ -245,6 +249,7 @@ pub async fn fix_test_users_wallets(
         state.config.encryption_secret.clone(),
         state.blockchain_service.clone(),
         state.config.solana_rpc_url.clone(),
+        state.pii.clone(),
     );
 
     let email_refs: Vec<&str> = request.emails.iter().map(|s| s.as_str()).collect();
@@ -0,0 +1,279 @@
+//! Address Book Handler
+//!
+//! Saved beneficiaries for transfers, ownership verification and
+//! confirm-on-first-use checks for the transfers UI
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::address_book::{AddressBookEntry, RecipientCheck};
+use crate::AppState;
+
+/// Save an address
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AddAddressRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub label: String,
+    /// Solana wallet address (base58 encoded)
+    #[validate(custom(function = "crate::utils::validation::rules::wallet_address"))]
+    pub wallet_address: String,
+}
+
+/// Rename an entry
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RenameAddressRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub label: String,
+}
+
+/// Proof of ownership
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct VerifyAddressRequest {
+    /// Base58 Ed25519 signature of `verification_message` by the address key
+    #[validate(length(min = 64, max = 100))]
+    pub signature: String,
+}
+
+/// Record a transfer to a saved address
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecordUseRequest {
+    /// Required for the first transfer to the address
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RecipientCheckQuery {
+    /// Recipient wallet address
+    pub address: String,
+}
+
+/// List saved addresses
+/// GET /api/v1/address-book
+#[utoipa::path(
+    get,
+    path = "/api/v1/address-book",
+    tag = "wallets",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved addresses by label", body = Vec<AddressBookEntry>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_addresses(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<Vec<AddressBookEntry>>> {
+    Ok(Json(state.address_book.list(user.0.sub).await?))
+}
+
+/// Save an address
+/// POST /api/v1/address-book
+///
+/// The new entry carries a `verification_message` to sign with the address
+/// key (see `/verify`).
+#[utoipa::path(
+    post,
+    path = "/api/v1/address-book",
+    tag = "wallets",
+    request_body = AddAddressRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Address saved", body = AddressBookEntry),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Wallet blocked after repeated failed signatures"),
+        (status = 409, description = "Address already saved or address book full"),
+        (status = 422, description = "Invalid label or address")
+    )
+)]
+pub async fn add_address(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<AddAddressRequest>,
+) -> Result<Json<AddressBookEntry>> {
+    Ok(Json(
+        state
+            .address_book
+            .add(user.0.sub, &payload.label, &payload.wallet_address)
+            .await?,
+    ))
+}
+
+/// Rename a saved address
+/// PATCH /api/v1/address-book/{id}
+#[utoipa::path(
+    patch,
+    path = "/api/v1/address-book/{id}",
+    tag = "wallets",
+    params(("id" = Uuid, Path, description = "Entry ID")),
+    request_body = RenameAddressRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Entry renamed", body = AddressBookEntry),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Entry not found")
+    )
+)]
+pub async fn rename_address(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(entry_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<RenameAddressRequest>,
+) -> Result<Json<AddressBookEntry>> {
+    Ok(Json(
+        state
+            .address_book
+            .rename(user.0.sub, entry_id, &payload.label)
+            .await?,
+    ))
+}
+
+/// Remove a saved address
+/// DELETE /api/v1/address-book/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/address-book/{id}",
+    tag = "wallets",
+    params(("id" = Uuid, Path, description = "Entry ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Entry removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Entry not found")
+    )
+)]
+pub async fn remove_address(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(entry_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>> {
+    state.address_book.remove(user.0.sub, entry_id).await?;
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Address removed"
+    })))
+}
+
+/// Issue a new verification challenge
+/// POST /api/v1/address-book/{id}/challenge
+#[utoipa::path(
+    post,
+    path = "/api/v1/address-book/{id}/challenge",
+    tag = "wallets",
+    params(("id" = Uuid, Path, description = "Entry ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Entry with a fresh verification message", body = AddressBookEntry),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Wallet blocked after repeated failed signatures"),
+        (status = 404, description = "Entry not found"),
+        (status = 409, description = "Already verified")
+    )
+)]
+pub async fn new_address_challenge(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(entry_id): Path<Uuid>,
+) -> Result<Json<AddressBookEntry>> {
+    Ok(Json(state.address_book.new_challenge(user.0.sub, entry_id).await?))
+}
+
+/// Verify ownership of a saved address
+/// POST /api/v1/address-book/{id}/verify
+#[utoipa::path(
+    post,
+    path = "/api/v1/address-book/{id}/verify",
+    tag = "wallets",
+    params(("id" = Uuid, Path, description = "Entry ID")),
+    request_body = VerifyAddressRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Address verified", body = AddressBookEntry),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Wallet blocked after repeated failed signatures"),
+        (status = 404, description = "Entry not found"),
+        (status = 409, description = "Challenge missing, expired or already used"),
+        (status = 422, description = "Invalid signature")
+    )
+)]
+pub async fn verify_address(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(entry_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<VerifyAddressRequest>,
+) -> Result<Json<AddressBookEntry>> {
+    Ok(Json(
+        state
+            .address_book
+            .verify(user.0.sub, entry_id, &payload.signature)
+            .await?,
+    ))
+}
+
+/// Check whether a transfer recipient needs confirmation
+/// GET /api/v1/address-book/check?address=
+#[utoipa::path(
+    get,
+    path = "/api/v1/address-book/check",
+    tag = "wallets",
+    params(RecipientCheckQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved entry and whether confirmation is required", body = RecipientCheck),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid address")
+    )
+)]
+pub async fn check_recipient(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<RecipientCheckQuery>,
+) -> Result<Json<RecipientCheck>> {
+    Ok(Json(
+        state
+            .address_book
+            .check_recipient(user.0.sub, &params.address)
+            .await?,
+    ))
+}
+
+/// Record a transfer to a saved address
+/// POST /api/v1/address-book/{id}/use
+///
+/// The first transfer to an address must be sent with `confirm: true`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/address-book/{id}/use",
+    tag = "wallets",
+    params(("id" = Uuid, Path, description = "Entry ID")),
+    request_body = RecordUseRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Use recorded", body = AddressBookEntry),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Entry not found"),
+        (status = 409, description = "First use not confirmed")
+    )
+)]
+pub async fn record_address_use(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(entry_id): Path<Uuid>,
+    Json(payload): Json<RecordUseRequest>,
+) -> Result<Json<AddressBookEntry>> {
+    Ok(Json(
+        state
+            .address_book
+            .record_use(user.0.sub, entry_id, payload.confirm)
+            .await?,
+    ))
+}
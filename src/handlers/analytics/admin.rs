@@ -4,9 +4,12 @@ use serde::Serialize;
 use utoipa::ToSchema;
 use tracing::info;
 use chrono::Utc;
+use rust_decimal::Decimal;
 use crate::AppState;
 use crate::auth::middleware::AuthenticatedUser;
+use crate::database::{IndexedPredicates, QueryClass, QueryFilter};
 use crate::error::Result;
+use crate::models::{EnergyKwh, TokenAmount};
 use super::types::*;
 use crate::services::audit_logger::AuditEventRecord;
 use crate::services::health_check::DetailedHealthStatus;
@@ -16,7 +19,7 @@ pub struct AdminStatsResponse {
     pub total_users: i64,
     pub total_meters: i64,
     pub active_meters: i64,
-    pub total_volume_kwh: f64,
+    pub total_volume_kwh: EnergyKwh,
     pub total_orders: i64,
     pub settlement_success_rate: f64,
 }
@@ -53,7 +56,7 @@ pub async fn get_admin_stats(
     .unwrap_or((0, 0));
 
     // 3. Trade Stats
-    let total_volume = sqlx::query_scalar::<_, Option<rust_decimal::Decimal>>(
+    let total_volume = sqlx::query_scalar::<_, Option<Decimal>>(
         "SELECT SUM(filled_amount) FROM trading_orders WHERE status = 'filled' OR status = 'settled'"
     )
     .fetch_one(&state.db)
@@ -72,7 +75,7 @@ pub async fn get_admin_stats(
         total_users,
         total_meters: meter_stats.0,
         active_meters: meter_stats.1,
-        total_volume_kwh: total_volume.to_string().parse().unwrap_or(0.0),
+        total_volume_kwh: EnergyKwh::from(total_volume),
         total_orders,
         settlement_success_rate,
     }))
@@ -128,6 +131,13 @@ pub async fn get_system_health(
     Ok(Json(health))
 }
 
+/// Zone insights scan completed settlements by processing time
+const ZONE_INSIGHT_INDEXES: IndexedPredicates = IndexedPredicates {
+    columns: &[],
+    time_column: Some("processed_at"),
+    max_range_days: 31,
+};
+
 /// Get economic insights broken down by zones (Admin only)
 #[utoipa::path(
     get,
@@ -136,7 +146,8 @@ pub async fn get_system_health(
     responses(
         (status = 200, description = "Zone economic insights retrieved", body = ZoneEconomicInsights),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - Admin only")
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 422, description = "Query exceeded its time limit")
     ),
     security(("bearer_auth" = []))
 )]
@@ -150,6 +161,16 @@ pub async fn get_zone_economic_insights(
     let duration = parse_timeframe(&params.timeframe)?;
     let start_time = Utc::now() - duration;
 
+    let mut query = state
+        .query_governor
+        .begin(
+            QueryClass::Analytics,
+            "GET /api/v1/analytics/admin/zones/economic",
+            &ZONE_INSIGHT_INDEXES,
+            QueryFilter::new().eq("status", "completed").gte("processed_at", start_time),
+        )
+        .await?;
+
     // 1. Cross-Zone Trade Stats
     let trade_row = sqlx::query(
         r#"
@@ -162,47 +183,61 @@ pub async fn get_zone_economic_insights(
         "#
     )
     .bind(start_time)
-    .fetch_one(&state.db)
-    .await?;
+    .fetch_one(query.conn())
+    .await;
 
-    let total_vol = decimal_to_f64(trade_row.get("total_vol"));
-    let intra_vol = decimal_to_f64(trade_row.get("intra_vol"));
-    let inter_vol = decimal_to_f64(trade_row.get("inter_vol"));
+    // 2. Zone Revenue Breakdown
+    let result = match trade_row {
+        Ok(trade_row) => sqlx::query(
+            r#"
+            SELECT 
+                buyer_zone_id as zone_id,
+                SUM(total_amount) as total_val,
+                SUM(fee_amount) as total_fees,
+                SUM(wheeling_charge) as total_wheeling,
+                AVG(price_per_kwh) as avg_price
+            FROM settlements
+            WHERE processed_at >= $1 AND status = 'completed' AND buyer_zone_id IS NOT NULL
+            GROUP BY buyer_zone_id
+            ORDER BY buyer_zone_id
+            LIMIT $2
+            "#
+        )
+        .bind(start_time)
+        .bind(state.query_governor.row_limit(QueryClass::Analytics, i64::MAX))
+        .fetch_all(query.conn())
+        .await
+        .map(|revenue_rows| (trade_row, revenue_rows)),
+        Err(e) => Err(e),
+    };
+    let (trade_row, revenue_rows) = query.finish(result).await?;
+
+    let total_vol = EnergyKwh::from(trade_row.get::<Decimal, _>("total_vol"));
+    let intra_vol = EnergyKwh::from(trade_row.get::<Decimal, _>("intra_vol"));
+    let inter_vol = EnergyKwh::from(trade_row.get::<Decimal, _>("inter_vol"));
+    let percent_of_total = |part: EnergyKwh| {
+        if total_vol.is_positive() {
+            (part.to_f64() / total_vol.to_f64()) * 100.0
+        } else {
+            0.0
+        }
+    };
 
     let trade_stats = ZoneTradeStats {
         timeframe: params.timeframe.clone(),
         total_volume_kwh: total_vol,
         intra_zone_volume_kwh: intra_vol,
         inter_zone_volume_kwh: inter_vol,
-        intra_zone_percent: if total_vol > 0.0 { (intra_vol / total_vol) * 100.0 } else { 0.0 },
-        inter_zone_percent: if total_vol > 0.0 { (inter_vol / total_vol) * 100.0 } else { 0.0 },
+        intra_zone_percent: percent_of_total(intra_vol),
+        inter_zone_percent: percent_of_total(inter_vol),
     };
 
-    // 2. Zone Revenue Breakdown
-    let revenue_rows = sqlx::query(
-        r#"
-        SELECT 
-            buyer_zone_id as zone_id,
-            SUM(total_amount) as total_val,
-            SUM(fee_amount) as total_fees,
-            SUM(wheeling_charge) as total_wheeling,
-            AVG(price_per_kwh) as avg_price
-        FROM settlements
-        WHERE processed_at >= $1 AND status = 'completed' AND buyer_zone_id IS NOT NULL
-        GROUP BY buyer_zone_id
-        ORDER BY buyer_zone_id
-        "#
-    )
-    .bind(start_time)
-    .fetch_all(&state.db)
-    .await?;
-
     let revenue_breakdown = revenue_rows.iter().map(|row| {
         ZoneRevenueBreakdown {
             zone_id: row.get::<i32, _>("zone_id"),
-            total_transaction_value: decimal_to_f64(row.get("total_val")),
-            total_platform_fees: decimal_to_f64(row.get("total_fees")),
-            total_wheeling_charges: decimal_to_f64(row.get("total_wheeling")),
+            total_transaction_value: TokenAmount::from(row.get::<Decimal, _>("total_val")),
+            total_platform_fees: TokenAmount::from(row.get::<Decimal, _>("total_fees")),
+            total_wheeling_charges: TokenAmount::from(row.get::<Decimal, _>("total_wheeling")),
             avg_price_per_kwh: decimal_to_f64(row.get("avg_price")),
         }
     }).collect();
@@ -0,0 +1,267 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::services::emission_factors::FactorProvenance;
+use crate::services::leaderboard::LeaderboardCategory;
+use crate::AppState;
+
+/// Leaderboards change once a day, so cached entries can live for an hour
+const LEADERBOARD_CACHE_TTL_SECS: u64 = 3600;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LeaderboardQuery {
+    /// Category: generation, co2_saved, traded_volume (default: generation)
+    pub category: Option<String>,
+    /// Number of entries (default 10, max 100)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LeaderboardEntry {
+    pub rank: i32,
+    pub display_name: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommunityStats {
+    pub participants: i64,
+    pub total_generated_kwh: f64,
+    pub total_co2_saved_kg: f64,
+    pub total_traded_kwh: f64,
+    /// Emission factor `total_co2_saved_kg` was computed with
+    pub co2_factor: Option<FactorProvenance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LeaderboardResponse {
+    pub category: LeaderboardCategory,
+    pub unit: String,
+    /// Date the snapshot was computed for (covers the preceding 30 days)
+    pub snapshot_date: Option<NaiveDate>,
+    pub entries: Vec<LeaderboardEntry>,
+    pub community: Option<CommunityStats>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LeaderboardPreferencesRequest {
+    /// Appear on public leaderboards
+    pub opt_in: bool,
+    /// Public name (3-30 characters), required when opting in
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LeaderboardPreferencesResponse {
+    pub opt_in: bool,
+    pub display_name: Option<String>,
+}
+
+/// Get community leaderboard
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/leaderboard",
+    params(LeaderboardQuery),
+    responses(
+        (status = 200, description = "Leaderboard retrieved", body = LeaderboardResponse),
+        (status = 400, description = "Invalid category")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_leaderboard(
+    State(state): State<AppState>,
+    Query(params): Query<LeaderboardQuery>,
+) -> Result<Response> {
+    let category = match params.category.as_deref() {
+        None => LeaderboardCategory::Generation,
+        Some(value) => LeaderboardCategory::parse(value).ok_or_else(|| {
+            ApiError::validation_field(
+                "category",
+                "Invalid category. Use: generation, co2_saved, or traded_volume",
+            )
+        })?,
+    };
+    let limit = params.limit.unwrap_or(10).clamp(1, 100);
+
+    let cache_key = format!("leaderboard:{}:{}", Utc::now().date_naive(), category.as_str());
+    let mut response = match state.cache_service.get::<LeaderboardResponse>(&cache_key).await {
+        Ok(Some(cached)) => cached,
+        _ => {
+            let fresh = load_leaderboard(&state, category).await?;
+            if let Err(e) = state
+                .cache_service
+                .set_with_ttl(&cache_key, &fresh, LEADERBOARD_CACHE_TTL_SECS)
+                .await
+            {
+                warn!("Failed to cache leaderboard {}: {}", cache_key, e);
+            }
+            fresh
+        }
+    };
+    response.entries.truncate(limit);
+
+    Ok((
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(response),
+    )
+        .into_response())
+}
+
+/// Update leaderboard privacy preferences
+#[utoipa::path(
+    put,
+    path = "/api/v1/analytics/leaderboard/preferences",
+    request_body = LeaderboardPreferencesRequest,
+    responses(
+        (status = 200, description = "Preferences updated", body = LeaderboardPreferencesResponse),
+        (status = 400, description = "Invalid display name"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_leaderboard_preferences(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(request): Json<LeaderboardPreferencesRequest>,
+) -> Result<Json<LeaderboardPreferencesResponse>> {
+    let display_name = request
+        .display_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+
+    if let Some(ref name) = display_name {
+        if name.chars().count() < 3 || name.chars().count() > 30 {
+            return Err(ApiError::validation_field(
+                "display_name",
+                "Display name must be 3-30 characters",
+            ));
+        }
+        if name.contains('@') {
+            return Err(ApiError::validation_field(
+                "display_name",
+                "Display name must not be an email address",
+            ));
+        }
+    } else if request.opt_in {
+        return Err(ApiError::validation_field(
+            "display_name",
+            "A display name is required to appear on leaderboards",
+        ));
+    }
+
+    let updated = sqlx::query_as::<_, (bool, Option<String>)>(
+        r#"
+        UPDATE users
+        SET leaderboard_opt_in = $2,
+            display_name = COALESCE($3, display_name),
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING leaderboard_opt_in, display_name
+        "#,
+    )
+    .bind(user.0.sub)
+    .bind(request.opt_in)
+    .bind(&display_name)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+
+    // Opting out takes effect immediately, not at the next daily snapshot
+    if !request.opt_in {
+        sqlx::query("DELETE FROM leaderboard_snapshots WHERE user_id = $1")
+            .bind(user.0.sub)
+            .execute(&state.db)
+            .await?;
+
+        let today = Utc::now().date_naive();
+        for category in LeaderboardCategory::ALL {
+            let _ = state
+                .cache_service
+                .delete(&format!("leaderboard:{}:{}", today, category.as_str()))
+                .await;
+        }
+    }
+
+    Ok(Json(LeaderboardPreferencesResponse {
+        opt_in: updated.0,
+        display_name: updated.1,
+    }))
+}
+
+// ==================== HELPER FUNCTIONS ====================
+
+async fn load_leaderboard(
+    state: &AppState,
+    category: LeaderboardCategory,
+) -> Result<LeaderboardResponse> {
+    let snapshot_date: Option<NaiveDate> = sqlx::query_scalar(
+        "SELECT MAX(snapshot_date) FROM community_stats_daily",
+    )
+    .fetch_one(&state.db)
+    .await?;
+
+    let Some(date) = snapshot_date else {
+        return Ok(LeaderboardResponse {
+            category,
+            unit: category.unit().to_string(),
+            snapshot_date: None,
+            entries: Vec::new(),
+            community: None,
+        });
+    };
+
+    let entries = sqlx::query_as::<_, (i32, String, f64)>(
+        r#"
+        SELECT rank, display_name, value
+        FROM leaderboard_snapshots
+        WHERE snapshot_date = $1 AND category = $2
+        ORDER BY rank ASC
+        "#,
+    )
+    .bind(date)
+    .bind(category.as_str())
+    .fetch_all(&state.db)
+    .await?
+    .into_iter()
+    .map(|(rank, display_name, value)| LeaderboardEntry {
+        rank,
+        display_name,
+        value,
+    })
+    .collect();
+
+    let community = sqlx::query_as::<_, (i64, f64, f64, f64, Option<serde_json::Value>)>(
+        r#"
+        SELECT participants, total_generated_kwh, total_co2_saved_kg, total_traded_kwh, co2_factor
+        FROM community_stats_daily
+        WHERE snapshot_date = $1
+        "#,
+    )
+    .bind(date)
+    .fetch_optional(&state.db)
+    .await?
+    .map(|(participants, generated, co2, traded, factor)| CommunityStats {
+        participants,
+        total_generated_kwh: generated,
+        total_co2_saved_kg: co2,
+        total_traded_kwh: traded,
+        co2_factor: factor.and_then(|f| serde_json::from_value(f).ok()),
+    });
+
+    Ok(LeaderboardResponse {
+        category,
+        unit: category.unit().to_string(),
+        snapshot_date: Some(date),
+        entries,
+        community,
+    })
+}
@@ -2,16 +2,25 @@ use axum::{
     extract::{Query, State},
     response::Json,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use rust_decimal::Decimal;
 use sqlx::Row;
 use uuid::Uuid;
 
-use crate::error::Result;
+use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
+use crate::error::{ApiError, Result};
+use crate::models::{EnergyKwh, TokenAmount};
+use crate::services::market_analytics::{vwap, TIME_TO_FILL_LABELS};
+use crate::services::price_index::ReferenceIndex;
 use crate::AppState;
 
 use super::types::*;
 
 /// Get market analytics
+///
+/// Top traders other than the caller are named by public participant IDs
+/// unless the caller is an admin.
 #[utoipa::path(
     get,
     path = "/api/v1/analytics/market",
@@ -24,6 +33,7 @@ use super::types::*;
 )]
 pub async fn get_market_analytics(
     State(state): State<AppState>,
+    user: AuthenticatedUser,
     Query(params): Query<AnalyticsTimeframe>,
 ) -> Result<Json<MarketAnalytics>> {
     // Parse timeframe
@@ -44,7 +54,15 @@ pub async fn get_market_analytics(
     let energy_source_breakdown = get_energy_source_breakdown(&state, start_time).await?;
 
     // Get top traders
-    let top_traders = get_top_traders(&state, start_time, 10).await?;
+    let mut top_traders = get_top_traders(&state, start_time, 10).await?;
+    if !matches!(Role::from_str(&user.0.role), Ok(Role::Admin)) {
+        let own_id = user.0.sub.to_string();
+        for trader in top_traders.iter_mut().filter(|t| t.user_id != own_id) {
+            let public_id = state.participant_ids.public_id(&trader.user_id);
+            trader.username = format!("Participant {}", &public_id.simple().to_string()[..8]);
+            trader.user_id = public_id.to_string();
+        }
+    }
 
     Ok(Json(MarketAnalytics {
         timeframe: params.timeframe,
@@ -56,8 +74,318 @@ pub async fn get_market_analytics(
     }))
 }
 
+/// Get VWAP per period from the hourly aggregates
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/market/vwap",
+    params(MarketSeriesQuery),
+    responses(
+        (status = 200, description = "VWAP series retrieved", body = VwapSeries),
+        (status = 400, description = "Invalid timeframe or interval")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_market_vwap(
+    State(state): State<AppState>,
+    Query(params): Query<MarketSeriesQuery>,
+) -> Result<Json<VwapSeries>> {
+    let buckets = fetch_hourly_buckets(&state, &params.timeframe).await?;
+    let periods = roll_up(&buckets, parse_interval(&params.interval)?)?;
+
+    let total_volume: f64 = periods.iter().map(|p| p.volume_kwh).sum();
+    let total_notional: f64 = periods.iter().map(|p| p.notional).sum();
+
+    Ok(Json(VwapSeries {
+        timeframe: params.timeframe,
+        interval: params.interval,
+        vwap: vwap(total_notional, total_volume),
+        points: periods
+            .into_iter()
+            .map(|p| VwapPoint {
+                period_start: p.period_start,
+                vwap: vwap(p.notional, p.volume_kwh),
+                volume_kwh: p.volume_kwh,
+                trade_count: p.trade_count,
+                low_price: p.low_price,
+                high_price: p.high_price,
+            })
+            .collect(),
+    }))
+}
+
+/// Get average bid-ask spread per period and the current spread
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/market/spread",
+    params(MarketSeriesQuery),
+    responses(
+        (status = 200, description = "Spread series retrieved", body = SpreadSeries),
+        (status = 400, description = "Invalid timeframe or interval")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_market_spread(
+    State(state): State<AppState>,
+    Query(params): Query<MarketSeriesQuery>,
+) -> Result<Json<SpreadSeries>> {
+    let buckets = fetch_hourly_buckets(&state, &params.timeframe).await?;
+    let periods = roll_up(&buckets, parse_interval(&params.interval)?)?;
+    let depth = state.market_analytics.depth_snapshot().await;
+
+    let spread_sum: f64 = periods.iter().map(|p| p.spread_sum).sum();
+    let spread_samples: i64 = periods.iter().map(|p| p.spread_samples).sum();
+
+    Ok(Json(SpreadSeries {
+        timeframe: params.timeframe,
+        interval: params.interval,
+        best_bid: depth.as_ref().and_then(|d| d.best_bid),
+        best_ask: depth.as_ref().and_then(|d| d.best_ask),
+        current_spread: depth.as_ref().and_then(|d| d.spread),
+        average_spread: average(spread_sum, spread_samples),
+        points: periods
+            .into_iter()
+            .map(|p| SpreadPoint {
+                period_start: p.period_start,
+                average_spread: average(p.spread_sum, p.spread_samples),
+                samples: p.spread_samples,
+            })
+            .collect(),
+    }))
+}
+
+/// Get liquidity metrics: fill ratio, time-to-fill distribution and orderbook depth
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/market/liquidity",
+    params(AnalyticsTimeframe),
+    responses(
+        (status = 200, description = "Liquidity metrics retrieved", body = LiquidityMetrics),
+        (status = 400, description = "Invalid timeframe")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_market_liquidity(
+    State(state): State<AppState>,
+    Query(params): Query<AnalyticsTimeframe>,
+) -> Result<Json<LiquidityMetrics>> {
+    let buckets = fetch_hourly_buckets(&state, &params.timeframe).await?;
+
+    let requested: f64 = buckets.iter().map(|b| decimal_to_f64(b.requested_kwh)).sum();
+    let filled: f64 = buckets.iter().map(|b| decimal_to_f64(b.filled_kwh)).sum();
+    let orders_filled: i64 = buckets.iter().map(|b| b.orders_filled).sum();
+    let ttf_sum: f64 = buckets.iter().map(|b| b.ttf_sum_secs).sum();
+
+    let mut distribution = vec![0i64; TIME_TO_FILL_LABELS.len()];
+    for bucket in &buckets {
+        for (total, count) in distribution.iter_mut().zip(&bucket.ttf_histogram) {
+            *total += count;
+        }
+    }
+
+    Ok(Json(LiquidityMetrics {
+        timeframe: params.timeframe,
+        orders_created: buckets.iter().map(|b| b.orders_created).sum(),
+        orders_filled,
+        fill_ratio: if requested > 0.0 { filled / requested } else { 0.0 },
+        average_time_to_fill_secs: average(ttf_sum, orders_filled),
+        time_to_fill_distribution: TIME_TO_FILL_LABELS
+            .iter()
+            .zip(distribution)
+            .map(|(label, count)| TimeToFillBucket {
+                label: label.to_string(),
+                count,
+            })
+            .collect(),
+        depth: state.market_analytics.depth_snapshot().await,
+    }))
+}
+
+/// Get the historical clearing price series
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/market/clearing-price/history",
+    params(ClearingPriceHistoryQuery),
+    responses(
+        (status = 200, description = "Clearing price series retrieved", body = ClearingPriceHistory),
+        (status = 400, description = "Invalid resolution or range")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_clearing_price_history(
+    State(state): State<AppState>,
+    Query(params): Query<ClearingPriceHistoryQuery>,
+) -> Result<Json<ClearingPriceHistory>> {
+    let resolution = parse_resolution(&params.resolution)?;
+    let to = params.to.unwrap_or_else(Utc::now);
+    let from = params.from.unwrap_or(to - Duration::days(7));
+
+    if from >= to {
+        return Err(ApiError::validation_field("from", "from must be before to"));
+    }
+    if to - from > max_history_range(resolution) {
+        return Err(ApiError::validation_field(
+            "from",
+            format!(
+                "Range too large for resolution {}; maximum is {} days",
+                params.resolution,
+                max_history_range(resolution).num_days()
+            ),
+        ));
+    }
+
+    let points = state
+        .price_index_service
+        .history(resolution, from, to)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to load clearing price history: {}", e)))?;
+
+    Ok(Json(ClearingPriceHistory {
+        resolution: params.resolution,
+        from,
+        to,
+        points,
+    }))
+}
+
+/// Get the published daily reference index (futures settlement index)
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/market/reference-index",
+    params(ReferenceIndexQuery),
+    responses(
+        (status = 200, description = "Reference index retrieved", body = ReferenceIndex),
+        (status = 404, description = "No reference index published for the date")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_reference_index(
+    State(state): State<AppState>,
+    Query(params): Query<ReferenceIndexQuery>,
+) -> Result<Json<ReferenceIndex>> {
+    let index = state
+        .price_index_service
+        .reference_index(params.date)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to load reference index: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound("No reference index published".to_string()))?;
+
+    Ok(Json(index))
+}
+
 // ==================== HELPER FUNCTIONS ====================
 
+/// Row of `market_analytics_hourly`, maintained by the market analytics aggregator
+#[derive(Debug, sqlx::FromRow)]
+struct HourlyBucket {
+    bucket_start: DateTime<Utc>,
+    volume_kwh: Decimal,
+    notional: Decimal,
+    trade_count: i64,
+    low_price: Option<Decimal>,
+    high_price: Option<Decimal>,
+    spread_sum: f64,
+    spread_samples: i64,
+    requested_kwh: Decimal,
+    filled_kwh: Decimal,
+    orders_created: i64,
+    orders_filled: i64,
+    ttf_sum_secs: f64,
+    ttf_histogram: Vec<i64>,
+}
+
+/// Hourly buckets rolled up to the requested interval
+struct Period {
+    period_start: DateTime<Utc>,
+    volume_kwh: f64,
+    notional: f64,
+    trade_count: i64,
+    low_price: Option<f64>,
+    high_price: Option<f64>,
+    spread_sum: f64,
+    spread_samples: i64,
+}
+
+async fn fetch_hourly_buckets(state: &AppState, timeframe: &str) -> Result<Vec<HourlyBucket>> {
+    let start_time = (Utc::now() - parse_timeframe(timeframe)?)
+        .duration_trunc(Duration::hours(1))
+        .map_err(|e| ApiError::Internal(format!("Invalid timeframe start: {}", e)))?;
+
+    let buckets = sqlx::query_as::<_, HourlyBucket>(
+        r#"
+        SELECT bucket_start, volume_kwh, notional, trade_count, low_price, high_price,
+               spread_sum, spread_samples, requested_kwh, filled_kwh, orders_created,
+               orders_filled, ttf_sum_secs, ttf_histogram
+        FROM market_analytics_hourly
+        WHERE bucket_start >= $1
+        ORDER BY bucket_start ASC
+        "#,
+    )
+    .bind(start_time)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(buckets)
+}
+
+fn roll_up(buckets: &[HourlyBucket], interval: Duration) -> Result<Vec<Period>> {
+    let mut periods: Vec<Period> = Vec::new();
+
+    for bucket in buckets {
+        let period_start = bucket
+            .bucket_start
+            .duration_trunc(interval)
+            .map_err(|e| ApiError::Internal(format!("Invalid interval: {}", e)))?;
+        let low = bucket.low_price.map(decimal_to_f64);
+        let high = bucket.high_price.map(decimal_to_f64);
+
+        match periods.last_mut() {
+            Some(period) if period.period_start == period_start => {
+                period.volume_kwh += decimal_to_f64(bucket.volume_kwh);
+                period.notional += decimal_to_f64(bucket.notional);
+                period.trade_count += bucket.trade_count;
+                period.low_price = min_opt(period.low_price, low);
+                period.high_price = max_opt(period.high_price, high);
+                period.spread_sum += bucket.spread_sum;
+                period.spread_samples += bucket.spread_samples;
+            }
+            _ => periods.push(Period {
+                period_start,
+                volume_kwh: decimal_to_f64(bucket.volume_kwh),
+                notional: decimal_to_f64(bucket.notional),
+                trade_count: bucket.trade_count,
+                low_price: low,
+                high_price: high,
+                spread_sum: bucket.spread_sum,
+                spread_samples: bucket.spread_samples,
+            }),
+        }
+    }
+
+    Ok(periods)
+}
+
+fn average(sum: f64, count: i64) -> Option<f64> {
+    if count > 0 {
+        Some(sum / count as f64)
+    } else {
+        None
+    }
+}
+
+fn min_opt(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(x), Some(y)) => Some(x.min(y)),
+        (x, y) => x.or(y),
+    }
+}
+
+fn max_opt(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(x), Some(y)) => Some(x.max(y)),
+        (x, y) => x.or(y),
+    }
+}
+
 async fn get_market_overview(
     state: &AppState,
     start_time: DateTime<Utc>,
@@ -122,19 +450,28 @@ async fn get_trading_volume(
     .fetch_one(&state.db)
     .await?;
 
-    let current_energy = decimal_to_f64(current.get("total_energy"));
-    let current_value = decimal_to_f64(current.get("total_value"));
-    let transaction_count: i64 = current.get("transaction_count");
-    let previous_energy = decimal_to_f64(previous.get("total_energy"));
+    // Fiat reference value from the rates recorded on settlements
+    let fiat = sqlx::query(
+        r#"
+        SELECT
+            COALESCE(SUM(fiat_total_value) FILTER (WHERE fiat_currency = $2), 0)::FLOAT8 AS total_fiat,
+            COUNT(*) FILTER (WHERE fiat_total_value IS NULL OR fiat_currency IS DISTINCT FROM $2) AS unpriced
+        FROM settlements
+        WHERE created_at >= $1
+        "#,
+    )
+    .bind(start_time)
+    .bind(state.fx_rates.display_currency())
+    .fetch_one(&state.db)
+    .await?;
 
-    let volume_trend = if previous_energy > 0.0 {
-        ((current_energy - previous_energy) / previous_energy) * 100.0
-    } else {
-        0.0
-    };
+    let current_energy = EnergyKwh::from(current.get::<Decimal, _>("total_energy"));
+    let current_value = TokenAmount::from(current.get::<Decimal, _>("total_value"));
+    let transaction_count: i64 = current.get("transaction_count");
+    let previous_energy = EnergyKwh::from(previous.get::<Decimal, _>("total_energy"));
 
-    let avg_transaction_size = if transaction_count > 0 {
-        current_energy / transaction_count as f64
+    let volume_trend = if previous_energy.is_positive() {
+        ((current_energy.to_f64() - previous_energy.to_f64()) / previous_energy.to_f64()) * 100.0
     } else {
         0.0
     };
@@ -143,8 +480,11 @@ async fn get_trading_volume(
         total_energy_traded_kwh: current_energy,
         total_value_usd: current_value,
         number_of_transactions: transaction_count,
-        average_transaction_size_kwh: avg_transaction_size,
+        average_transaction_size_kwh: current_energy.average_over(transaction_count),
         volume_trend_percent: volume_trend,
+        fiat_currency: state.fx_rates.display_currency().to_string(),
+        total_fiat_value: fiat.get("total_fiat"),
+        unpriced_settlements: fiat.get("unpriced"),
     })
 }
 
@@ -220,7 +560,7 @@ async fn get_energy_source_breakdown(
     Ok(vec![
         EnergySourceStats {
             energy_source: "Solar".to_string(),
-            total_volume_kwh: 0.0,
+            total_volume_kwh: EnergyKwh::ZERO,
             average_price_per_kwh: 0.0,
             transaction_count: 0,
             market_share_percent: 0.0,
@@ -269,7 +609,7 @@ async fn get_top_traders(
         .map(|row| TraderStats {
             user_id: row.get::<Uuid, _>("user_id").to_string(),
             username: row.get("username"),
-            total_volume_kwh: decimal_to_f64(row.get("total_volume")),
+            total_volume_kwh: EnergyKwh::from(row.get::<Decimal, _>("total_volume")),
             transaction_count: row.get("transaction_count"),
             average_price_per_kwh: decimal_to_f64(row.get("avg_price")),
             role: row.get("role"),
@@ -2,19 +2,36 @@ pub mod market;
 pub mod user;
 pub mod types;
 pub mod admin;
+pub mod leaderboard;
+pub mod reports;
 
-use axum::{routing::get, Router, middleware::from_fn};
+use axum::{routing::{get, post, put}, Router, middleware::from_fn};
 use crate::AppState;
 use crate::auth::middleware::require_admin_role;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/market", get(market::get_market_analytics))
+        .route("/market/vwap", get(market::get_market_vwap))
+        .route("/market/spread", get(market::get_market_spread))
+        .route("/market/liquidity", get(market::get_market_liquidity))
+        .route("/market/clearing-price/history", get(market::get_clearing_price_history))
+        .route("/market/reference-index", get(market::get_reference_index))
         .route("/my-stats", get(user::get_user_trading_stats))
         .route("/my-history", get(user::get_user_wealth_history))
+        .route("/my-performance", get(user::get_user_performance_report))
+        .route("/my-performance/export", get(user::export_user_performance_report))
         .route("/transactions", get(user::get_user_transactions))
+        .route("/transactions/export", get(user::export_user_transactions))
+        .route("/leaderboard", get(leaderboard::get_leaderboard))
+        .route("/leaderboard/preferences", put(leaderboard::update_leaderboard_preferences))
+        .route("/report-subscriptions", get(reports::list_report_subscriptions).post(reports::create_report_subscription))
+        .route("/report-subscriptions/{id}", put(reports::update_report_subscription).delete(reports::delete_report_subscription))
+        .route("/report-subscriptions/{id}/deliveries", get(reports::list_report_deliveries))
+        .route("/report-subscriptions/{id}/send", post(reports::send_report_now))
         .route("/admin/stats", get(admin::get_admin_stats).layer(from_fn(require_admin_role)))
         .route("/admin/activity", get(admin::get_admin_activity).layer(from_fn(require_admin_role)))
         .route("/admin/health", get(admin::get_system_health).layer(from_fn(require_admin_role)))
         .route("/admin/zones/economic", get(admin::get_zone_economic_insights).layer(from_fn(require_admin_role)))
+        .route("/admin/report-subscriptions", get(reports::list_all_report_subscriptions).layer(from_fn(require_admin_role)))
 }
@@ -0,0 +1,226 @@
+//! Scheduled Report Subscriptions Handler
+//!
+//! Daily trading, weekly generation and monthly carbon reports delivered
+//! by email or webhook once their period has closed
+
+use std::str::FromStr;
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::report_subscriptions::{
+    CreateReportSubscriptionRequest, CreatedReportSubscription, ReportDelivery, ReportKind, ReportSubscription,
+    UpdateReportSubscriptionRequest,
+};
+use crate::AppState;
+
+const MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReportDeliveriesQuery {
+    /// Default 50, at most 500
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AdminReportSubscriptionsQuery {
+    pub report: Option<ReportKind>,
+    /// Default 100, at most 500
+    pub limit: Option<i64>,
+}
+
+/// List report subscriptions
+/// GET /api/v1/analytics/report-subscriptions
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/report-subscriptions",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The user's report subscriptions", body = Vec<ReportSubscription>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_report_subscriptions(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<Vec<ReportSubscription>>> {
+    Ok(Json(state.report_subscriptions.list(user.0.sub).await?))
+}
+
+/// Subscribe to a scheduled report
+/// POST /api/v1/analytics/report-subscriptions
+///
+/// Webhook subscriptions get a secret signing their deliveries; it is only
+/// returned in this response.
+#[utoipa::path(
+    post,
+    path = "/api/v1/analytics/report-subscriptions",
+    request_body = CreateReportSubscriptionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Subscription created", body = CreatedReportSubscription),
+        (status = 400, description = "Email delivery unavailable or too many subscriptions"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Platform reports require the admin role"),
+        (status = 422, description = "Missing or non-public webhook URL")
+    )
+)]
+pub async fn create_report_subscription(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<CreateReportSubscriptionRequest>,
+) -> Result<Json<CreatedReportSubscription>> {
+    let is_admin = matches!(Role::from_str(&user.0.role), Ok(Role::Admin));
+    Ok(Json(
+        state
+            .report_subscriptions
+            .create(user.0.sub, is_admin, payload)
+            .await?,
+    ))
+}
+
+/// Change, pause or resume a report subscription
+/// PUT /api/v1/analytics/report-subscriptions/{id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/analytics/report-subscriptions/{id}",
+    params(("id" = Uuid, Path, description = "Subscription ID")),
+    request_body = UpdateReportSubscriptionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Subscription updated", body = ReportSubscription),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Subscription not found"),
+        (status = 422, description = "Non-public webhook URL")
+    )
+)]
+pub async fn update_report_subscription(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(subscription_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateReportSubscriptionRequest>,
+) -> Result<Json<ReportSubscription>> {
+    Ok(Json(
+        state
+            .report_subscriptions
+            .update(user.0.sub, subscription_id, payload)
+            .await?,
+    ))
+}
+
+/// Cancel a report subscription
+/// DELETE /api/v1/analytics/report-subscriptions/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/analytics/report-subscriptions/{id}",
+    params(("id" = Uuid, Path, description = "Subscription ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Subscription deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Subscription not found")
+    )
+)]
+pub async fn delete_report_subscription(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(subscription_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>> {
+    state.report_subscriptions.delete(user.0.sub, subscription_id).await?;
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Report subscription deleted"
+    })))
+}
+
+/// List deliveries of a report subscription
+/// GET /api/v1/analytics/report-subscriptions/{id}/deliveries
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/report-subscriptions/{id}/deliveries",
+    params(("id" = Uuid, Path, description = "Subscription ID"), ReportDeliveriesQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Delivery attempts, newest first", body = Vec<ReportDelivery>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Subscription not found")
+    )
+)]
+pub async fn list_report_deliveries(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(subscription_id): Path<Uuid>,
+    Query(params): Query<ReportDeliveriesQuery>,
+) -> Result<Json<Vec<ReportDelivery>>> {
+    let limit = params.limit.unwrap_or(50).clamp(1, MAX_LIMIT);
+    Ok(Json(
+        state
+            .report_subscriptions
+            .deliveries(user.0.sub, subscription_id, limit)
+            .await?,
+    ))
+}
+
+/// Send the latest report now
+/// POST /api/v1/analytics/report-subscriptions/{id}/send
+///
+/// Delivers the last closed period immediately, e.g. to test a webhook;
+/// the schedule is unchanged.
+#[utoipa::path(
+    post,
+    path = "/api/v1/analytics/report-subscriptions/{id}/send",
+    params(("id" = Uuid, Path, description = "Subscription ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Delivery attempt, delivered or failed", body = ReportDelivery),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Subscription not found")
+    )
+)]
+pub async fn send_report_now(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(subscription_id): Path<Uuid>,
+) -> Result<Json<ReportDelivery>> {
+    Ok(Json(
+        state
+            .report_subscriptions
+            .send_now(user.0.sub, subscription_id)
+            .await?,
+    ))
+}
+
+/// List all report subscriptions (Admin only)
+/// GET /api/v1/analytics/admin/report-subscriptions
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/admin/report-subscriptions",
+    params(AdminReportSubscriptionsQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Subscriptions of all users, newest first", body = Vec<ReportSubscription>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin only")
+    )
+)]
+pub async fn list_all_report_subscriptions(
+    State(state): State<AppState>,
+    Query(params): Query<AdminReportSubscriptionsQuery>,
+) -> Result<Json<Vec<ReportSubscription>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, MAX_LIMIT);
+    Ok(Json(
+        state
+            .report_subscriptions
+            .list_all(params.report, limit)
+            .await?,
+    ))
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use uuid::Uuid;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
 use crate::error::{ApiError, Result};
+use crate::models::{EnergyKwh, TokenAmount};
+use crate::services::price_index::{ClearingPricePoint, IndexResolution};
 
 // ==================== REQUEST/RESPONSE TYPES ====================
 
@@ -43,11 +45,17 @@ pub struct MarketOverview {
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct TradingVolume {
-    pub total_energy_traded_kwh: f64,
-    pub total_value_usd: f64,
+    pub total_energy_traded_kwh: EnergyKwh,
+    pub total_value_usd: TokenAmount,
     pub number_of_transactions: i64,
-    pub average_transaction_size_kwh: f64,
+    pub average_transaction_size_kwh: EnergyKwh,
     pub volume_trend_percent: f64, // Compared to previous period
+    /// Display currency of `total_fiat_value`
+    pub fiat_currency: String,
+    /// Settled value at the FX rate recorded per trade at clearing time
+    pub total_fiat_value: f64,
+    /// Settlements in the period without a recorded FX rate
+    pub unpriced_settlements: i64,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -63,7 +71,7 @@ pub struct PriceStatistics {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct EnergySourceStats {
     pub energy_source: String,
-    pub total_volume_kwh: f64,
+    pub total_volume_kwh: EnergyKwh,
     pub average_price_per_kwh: f64,
     pub transaction_count: i64,
     pub market_share_percent: f64,
@@ -73,7 +81,7 @@ pub struct EnergySourceStats {
 pub struct TraderStats {
     pub user_id: String,
     pub username: String,
-    pub total_volume_kwh: f64,
+    pub total_volume_kwh: EnergyKwh,
     pub transaction_count: i64,
     pub average_price_per_kwh: f64,
     pub role: String, // "user", "admin"
@@ -93,8 +101,8 @@ pub struct UserTradingStats {
 pub struct SellerStats {
     pub offers_created: i64,
     pub offers_fulfilled: i64,
-    pub total_energy_sold_kwh: f64,
-    pub total_revenue_usd: f64,
+    pub total_energy_sold_kwh: EnergyKwh,
+    pub total_revenue_usd: TokenAmount,
     pub average_price_per_kwh: f64,
 }
 
@@ -102,16 +110,16 @@ pub struct SellerStats {
 pub struct BuyerStats {
     pub orders_created: i64,
     pub orders_fulfilled: i64,
-    pub total_energy_purchased_kwh: f64,
-    pub total_spent_usd: f64,
+    pub total_energy_purchased_kwh: EnergyKwh,
+    pub total_spent_usd: TokenAmount,
     pub average_price_per_kwh: f64,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct OverallUserStats {
     pub total_transactions: i64,
-    pub total_volume_kwh: f64,
-    pub net_revenue_usd: f64, // revenue - spending
+    pub total_volume_kwh: EnergyKwh,
+    pub net_revenue_usd: TokenAmount, // revenue - spending
     pub favorite_energy_source: Option<String>,
 }
 
@@ -124,7 +132,7 @@ pub struct UserWealthHistory {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct WealthPoint {
     pub timestamp: DateTime<Utc>,
-    pub balance_usd: f64,
+    pub balance_usd: TokenAmount,
 }
 
 // ==================== HELPER FUNCTIONS ====================
@@ -151,9 +159,9 @@ pub fn decimal_to_f64(d: Decimal) -> f64 {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ZoneTradeStats {
     pub timeframe: String,
-    pub total_volume_kwh: f64,
-    pub intra_zone_volume_kwh: f64,
-    pub inter_zone_volume_kwh: f64,
+    pub total_volume_kwh: EnergyKwh,
+    pub intra_zone_volume_kwh: EnergyKwh,
+    pub inter_zone_volume_kwh: EnergyKwh,
     pub intra_zone_percent: f64,
     pub inter_zone_percent: f64,
 }
@@ -161,9 +169,9 @@ pub struct ZoneTradeStats {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ZoneRevenueBreakdown {
     pub zone_id: i32,
-    pub total_transaction_value: f64,
-    pub total_platform_fees: f64,
-    pub total_wheeling_charges: f64,
+    pub total_transaction_value: TokenAmount,
+    pub total_platform_fees: TokenAmount,
+    pub total_wheeling_charges: TokenAmount,
     pub avg_price_per_kwh: f64,
 }
 
@@ -215,8 +223,17 @@ pub struct SettlementMetadata {
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct TransactionQuery {
+    /// trading_order, settlement, meter_reading or user_registration
     pub transaction_type: Option<String>,
     pub status: Option<String>,
+    /// Created at or after
+    pub from_date: Option<DateTime<Utc>>,
+    /// Created before
+    pub to_date: Option<DateTime<Utc>>,
+    /// Part of the transaction signature
+    pub search: Option<String>,
+    /// `next_cursor` of the previous page; takes precedence over `offset`
+    pub cursor: Option<String>,
     pub limit: Option<i32>,
     pub offset: Option<i32>,
 }
@@ -225,4 +242,234 @@ pub struct TransactionQuery {
 pub struct UserTransactionsResponse {
     pub transactions: Vec<UserTransaction>,
     pub total: i64,
+    /// Cursor of the next page, absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+// ==================== MARKET MICROSTRUCTURE TYPES ====================
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MarketSeriesQuery {
+    /// Timeframe: 1h, 24h, 7d, 30d (default: 24h)
+    #[serde(default = "default_timeframe")]
+    pub timeframe: String,
+    /// Period size for the series: 1h or 1d (default: 1h)
+    #[serde(default = "default_interval")]
+    pub interval: String,
+}
+
+fn default_interval() -> String {
+    "1h".to_string()
+}
+
+pub fn parse_interval(interval: &str) -> Result<Duration> {
+    match interval {
+        "1h" => Ok(Duration::hours(1)),
+        "1d" | "24h" => Ok(Duration::days(1)),
+        _ => Err(ApiError::validation_field(
+            "interval",
+            "Invalid interval. Use: 1h or 1d",
+        )),
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VwapPoint {
+    pub period_start: DateTime<Utc>,
+    pub vwap: Option<f64>,
+    pub volume_kwh: f64,
+    pub trade_count: i64,
+    pub low_price: Option<f64>,
+    pub high_price: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VwapSeries {
+    pub timeframe: String,
+    pub interval: String,
+    /// VWAP across the whole timeframe
+    pub vwap: Option<f64>,
+    pub points: Vec<VwapPoint>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpreadPoint {
+    pub period_start: DateTime<Utc>,
+    pub average_spread: Option<f64>,
+    pub samples: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpreadSeries {
+    pub timeframe: String,
+    pub interval: String,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub current_spread: Option<f64>,
+    /// Average sampled spread across the whole timeframe
+    pub average_spread: Option<f64>,
+    pub points: Vec<SpreadPoint>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimeToFillBucket {
+    pub label: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LiquidityMetrics {
+    pub timeframe: String,
+    pub orders_created: i64,
+    pub orders_filled: i64,
+    /// Filled kWh / requested kWh for orders created in the timeframe
+    pub fill_ratio: f64,
+    pub average_time_to_fill_secs: Option<f64>,
+    pub time_to_fill_distribution: Vec<TimeToFillBucket>,
+    /// Latest orderbook depth snapshot from the aggregator
+    pub depth: Option<crate::services::market_analytics::DepthSnapshot>,
+}
+
+// ==================== CLEARING PRICE INDEX TYPES ====================
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ClearingPriceHistoryQuery {
+    /// Resolution: epoch, 1h or 1d (default: 1h)
+    #[serde(default = "default_interval")]
+    pub resolution: String,
+    /// Start of the range (default: 7 days before `to`)
+    pub from: Option<DateTime<Utc>>,
+    /// End of the range, exclusive (default: now)
+    pub to: Option<DateTime<Utc>>,
+}
+
+pub fn parse_resolution(resolution: &str) -> Result<IndexResolution> {
+    IndexResolution::parse(resolution).ok_or_else(|| {
+        ApiError::validation_field("resolution", "Invalid resolution. Use: epoch, 1h, or 1d")
+    })
+}
+
+/// Longest history range served at each resolution
+pub fn max_history_range(resolution: IndexResolution) -> Duration {
+    match resolution {
+        IndexResolution::Epoch => Duration::days(31),
+        IndexResolution::Hour => Duration::days(92),
+        IndexResolution::Day => Duration::days(1096),
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClearingPriceHistory {
+    pub resolution: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub points: Vec<ClearingPricePoint>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReferenceIndexQuery {
+    /// Index date (UTC); the latest published value when omitted
+    pub date: Option<NaiveDate>,
+}
+
+// ==================== PERFORMANCE REPORT TYPES ====================
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PerformanceReportQuery {
+    /// Timeframe: 1h, 24h, 7d, 30d (default: 30d)
+    #[serde(default = "default_report_timeframe")]
+    pub timeframe: String,
+    /// Reporting period: day, week or month (default: day)
+    #[serde(default = "default_report_period")]
+    pub period: String,
+    /// Export format: csv or pdf (export endpoint only, default: csv)
+    pub format: Option<String>,
+}
+
+fn default_report_timeframe() -> String {
+    "30d".to_string()
+}
+
+fn default_report_period() -> String {
+    "day".to_string()
+}
+
+pub fn parse_report_period(period: &str) -> Result<&'static str> {
+    match period {
+        "day" => Ok("day"),
+        "week" => Ok("week"),
+        "month" => Ok("month"),
+        _ => Err(ApiError::validation_field(
+            "period",
+            "Invalid period. Use: day, week, or month",
+        )),
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PerformancePeriod {
+    pub period_start: DateTime<Utc>,
+    pub energy_sold_kwh: f64,
+    pub energy_bought_kwh: f64,
+    /// Seller proceeds after platform fees and wheeling charges
+    pub sell_proceeds: f64,
+    pub purchase_cost: f64,
+    pub fees_paid: f64,
+    /// Net sell proceeds minus purchase cost
+    pub realized_pnl: f64,
+    /// Savings compared to trading the same energy with the grid
+    pub savings_vs_grid: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserPerformanceReport {
+    pub user_id: String,
+    pub username: String,
+    pub timeframe: String,
+    pub period: String,
+    pub average_buy_price_per_kwh: f64,
+    pub average_sell_price_per_kwh: f64,
+    pub total_fees_paid: f64,
+    pub realized_pnl: f64,
+    pub energy_generated_kwh: f64,
+    pub energy_self_consumed_kwh: f64,
+    /// Share of generated energy consumed on site (0.0 - 1.0)
+    pub self_consumption_ratio: f64,
+    pub grid_retail_tariff_per_kwh: f64,
+    pub grid_feed_in_tariff_per_kwh: f64,
+    pub savings_vs_grid: f64,
+    pub periods: Vec<PerformancePeriod>,
+}
+
+/// Savings from trading P2P instead of with the grid.
+///
+/// Buying saves the retail tariff minus what was paid; selling earns the
+/// net proceeds minus what the feed-in tariff would have paid.
+pub fn savings_vs_grid(
+    bought_kwh: f64,
+    purchase_cost: f64,
+    sold_kwh: f64,
+    sell_proceeds: f64,
+    retail_per_kwh: f64,
+    feed_in_per_kwh: f64,
+) -> f64 {
+    (bought_kwh * retail_per_kwh - purchase_cost) + (sell_proceeds - sold_kwh * feed_in_per_kwh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_savings_vs_grid() {
+        // Bought 10 kWh at 3.0 vs 4.0 retail, sold 5 kWh for 15.0 net vs 2.0 feed-in
+        let savings = savings_vs_grid(10.0, 30.0, 5.0, 15.0, 4.0, 2.0);
+        assert!((savings - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_report_period() {
+        assert_eq!(parse_report_period("week").unwrap(), "week");
+        assert!(parse_report_period("year").is_err());
+    }
 }
@@ -1,13 +1,21 @@
 use axum::{
+    body::{Body, Bytes},
     extract::{Query, State},
-    response::Json,
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
 };
 use chrono::{DateTime, Utc};
-use sqlx::Row;
+use rust_decimal::Decimal;
+use sqlx::{PgExecutor, Postgres, QueryBuilder, Row};
 use uuid::Uuid;
 
 use crate::auth::middleware::AuthenticatedUser;
-use crate::error::Result;
+use crate::database::{IndexedPredicates, QueryClass, QueryFilter, QueryGovernor};
+use crate::error::{ApiError, Result};
+use crate::handlers::common::csv_field;
+use crate::models::{EnergyKwh, TokenAmount};
+use crate::utils::pagination::{contains_pattern, FilterParams, PageCursor};
+use crate::utils::pdf;
 use crate::AppState;
 
 use super::types::*;
@@ -99,7 +107,7 @@ pub async fn get_user_wealth_history(
         .into_iter()
         .map(|row| WealthPoint {
             timestamp: row.get("timestamp"),
-            balance_usd: decimal_to_f64(row.get("balance")),
+            balance_usd: TokenAmount::from(row.get::<Decimal, _>("balance")),
         })
         .collect();
 
@@ -109,14 +117,185 @@ pub async fn get_user_wealth_history(
     }))
 }
 
+const TRANSACTION_TYPES: &[&str] = &["trading_order", "settlement", "meter_reading", "user_registration"];
+
+const TRANSACTION_COLUMNS: &str = "SELECT operation_type, operation_id, user_id, signature, tx_type, \
+     operation_status AS status, attempts, last_error, submitted_at, confirmed_at, created_at, updated_at, \
+     CASE
+         WHEN operation_type = 'settlement' THEN (
+             SELECT json_build_object(
+                 'energy_amount', energy_amount,
+                 'price_per_kwh', price_per_kwh,
+                 'total_amount', total_amount,
+                 'wheeling_charge', wheeling_charge,
+                 'loss_cost', loss_cost,
+                 'loss_factor', loss_factor,
+                 'effective_energy', effective_energy,
+                 'buyer_zone_id', buyer_zone_id,
+                 'seller_zone_id', seller_zone_id
+             ) FROM settlements WHERE id = operation_id
+         )
+         WHEN operation_type = 'trading_order' THEN (
+             SELECT json_build_object(
+                 'side', side,
+                 'energy_amount', energy_amount,
+                 'price_per_kwh', price_per_kwh,
+                 'zone_id', zone_id
+             ) FROM trading_orders WHERE id = operation_id
+         )
+         ELSE NULL
+     END AS metadata \
+     FROM blockchain_operations";
+
+const TRANSACTION_EXPORT_PAGE_SIZE: i64 = 1_000;
+
+const TRANSACTION_EXPORT_HEADER: &str =
+    "operation_type,operation_id,tx_type,status,signature,attempts,last_error,submitted_at,confirmed_at,created_at\n";
+
+/// Transaction history is always filtered by user
+const TRANSACTION_INDEXES: IndexedPredicates = IndexedPredicates {
+    columns: &["user_id"],
+    time_column: None,
+    max_range_days: 0,
+};
+
+/// Validated transaction filters
+#[derive(Debug, Clone)]
+struct UserTransactionFilter {
+    user_id: Uuid,
+    transaction_type: Option<String>,
+    filters: FilterParams,
+}
+
+impl UserTransactionFilter {
+    fn from_query(user_id: Uuid, params: &TransactionQuery) -> Result<Self> {
+        if let Some(transaction_type) = &params.transaction_type {
+            if !TRANSACTION_TYPES.contains(&transaction_type.as_str()) {
+                return Err(ApiError::validation_field(
+                    "transaction_type",
+                    format!("Must be one of {}", TRANSACTION_TYPES.join(", ")),
+                ));
+            }
+        }
+        if let (Some(from), Some(to)) = (params.from_date, params.to_date) {
+            if from >= to {
+                return Err(ApiError::validation_field("to_date", "Must be after from_date"));
+            }
+        }
+        let search = params.search.as_deref().map(str::trim).filter(|search| !search.is_empty());
+        if search.is_some_and(|search| search.len() > 88) {
+            return Err(ApiError::validation_field("search", "Must be at most 88 characters"));
+        }
+
+        Ok(Self {
+            user_id,
+            transaction_type: params.transaction_type.clone(),
+            filters: FilterParams {
+                status: params.status.as_deref().map(str::to_lowercase),
+                from_date: params.from_date,
+                to_date: params.to_date,
+                search: search.map(str::to_string),
+            },
+        })
+    }
+
+    /// The filters as query governor predicates
+    fn predicates(&self) -> QueryFilter {
+        let mut filter = QueryFilter::new().eq("user_id", self.user_id);
+        if let Some(transaction_type) = &self.transaction_type {
+            filter = filter.eq("operation_type", transaction_type.as_str());
+        }
+        if let Some(status) = &self.filters.status {
+            filter = filter.eq("operation_status", status.as_str());
+        }
+        if let Some(from) = self.filters.from_date {
+            filter = filter.gte("created_at", from);
+        }
+        if let Some(to) = self.filters.to_date {
+            filter = filter.lt("created_at", to);
+        }
+        if let Some(search) = &self.filters.search {
+            filter = filter.like("signature", contains_pattern(search));
+        }
+        filter
+    }
+
+    fn push_where(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        builder.push(" WHERE user_id = ").push_bind(self.user_id);
+        if let Some(transaction_type) = &self.transaction_type {
+            builder.push(" AND operation_type = ").push_bind(transaction_type.clone());
+        }
+        if let Some(status) = &self.filters.status {
+            builder.push(" AND LOWER(operation_status) = ").push_bind(status.clone());
+        }
+        if let Some(from) = self.filters.from_date {
+            builder.push(" AND created_at >= ").push_bind(from);
+        }
+        if let Some(to) = self.filters.to_date {
+            builder.push(" AND created_at < ").push_bind(to);
+        }
+        if let Some(search) = &self.filters.search {
+            builder.push(" AND signature ILIKE ").push_bind(contains_pattern(search));
+        }
+    }
+
+    async fn fetch_page<'e, E: PgExecutor<'e>>(
+        &self,
+        db: E,
+        after: Option<PageCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> std::result::Result<Vec<UserTransaction>, sqlx::Error> {
+        let mut builder = QueryBuilder::<Postgres>::new(TRANSACTION_COLUMNS);
+        self.push_where(&mut builder);
+        if let Some(after) = after {
+            builder
+                .push(" AND (created_at, operation_id) < (")
+                .push_bind(after.at)
+                .push(", ")
+                .push_bind(after.id)
+                .push(")");
+        }
+        builder.push(" ORDER BY created_at DESC, operation_id DESC LIMIT ").push_bind(limit);
+        if after.is_none() {
+            builder.push(" OFFSET ").push_bind(offset);
+        }
+        builder.build_query_as::<UserTransaction>().fetch_all(db).await
+    }
+
+    async fn count<'e, E: PgExecutor<'e>>(&self, db: E) -> std::result::Result<i64, sqlx::Error> {
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM blockchain_operations");
+        self.push_where(&mut builder);
+        builder.build_query_scalar::<i64>().fetch_one(db).await
+    }
+}
+
+fn transaction_cursor(transaction: &UserTransaction) -> PageCursor {
+    PageCursor {
+        at: transaction.created_at,
+        id: transaction.operation_id,
+    }
+}
+
+fn parse_cursor(cursor: Option<&str>) -> Result<Option<PageCursor>> {
+    cursor
+        .map(|token| PageCursor::decode(token).map_err(|e| ApiError::validation_field("cursor", e)))
+        .transpose()
+}
+
 /// Get user transaction history
+///
+/// Newest first. Pass `next_cursor` back as `cursor` to page through large
+/// histories; `offset` still works for the first pages.
 #[utoipa::path(
     get,
     path = "/api/v1/analytics/transactions",
     params(TransactionQuery),
     responses(
         (status = 200, description = "User transaction history retrieved", body = UserTransactionsResponse),
-        (status = 401, description = "Unauthorized")
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid filter or cursor, or the query exceeded its time limit"),
+        (status = 429, description = "Too many analytics requests in flight for this caller")
     ),
     security(("bearer_auth" = []))
 )]
@@ -125,96 +304,388 @@ pub async fn get_user_transactions(
     State(state): State<AppState>,
     Query(params): Query<TransactionQuery>,
 ) -> Result<Json<UserTransactionsResponse>> {
-    let limit = params.limit.unwrap_or(50).min(100);
-    let offset = params.offset.unwrap_or(0);
+    let filter = UserTransactionFilter::from_query(user.0.sub, &params)?;
+    let after = parse_cursor(params.cursor.as_deref())?;
+    let limit = state
+        .query_governor
+        .row_limit(QueryClass::Analytics, params.limit.unwrap_or(50).clamp(1, 100) as i64);
+    let offset = params.offset.unwrap_or(0).max(0) as i64;
 
-    let mut where_conditions = vec!["user_id = $1".to_string()];
-    let mut bind_count = 2;
+    let mut query = state
+        .query_governor
+        .begin(QueryClass::Analytics, "GET /api/v1/analytics/transactions", &TRANSACTION_INDEXES, filter.predicates())
+        .await?;
+    let result = match filter.fetch_page(query.conn(), after, limit, offset).await {
+        Ok(transactions) => filter.count(query.conn()).await.map(|total| (transactions, total)),
+        Err(e) => Err(e),
+    };
+    let (transactions, total) = query.finish(result).await?;
+    let next_cursor = PageCursor::after(&transactions, limit, transaction_cursor).map(|cursor| cursor.encode());
 
-    if let Some(_type) = &params.transaction_type {
-        where_conditions.push(format!("operation_type = ${}", bind_count));
-        bind_count += 1;
-    }
+    Ok(Json(UserTransactionsResponse {
+        transactions,
+        total,
+        next_cursor,
+    }))
+}
 
-    if let Some(_status) = &params.status {
-        where_conditions.push(format!("operation_status = ${}", bind_count));
-        bind_count += 1;
-    }
+/// One page of an export, governed by the export statement timeout
+async fn export_page(cursor: &TransactionExportCursor) -> Result<Vec<UserTransaction>> {
+    let mut query = cursor
+        .governor
+        .begin(
+            QueryClass::Export,
+            "GET /api/v1/analytics/transactions/export",
+            &TRANSACTION_INDEXES,
+            cursor.filter.predicates(),
+        )
+        .await?;
+    let result = cursor
+        .filter
+        .fetch_page(query.conn(), cursor.after, TRANSACTION_EXPORT_PAGE_SIZE, 0)
+        .await;
+    query.finish(result).await
+}
+
+fn transaction_export_line(transaction: &UserTransaction) -> String {
+    let timestamp = |t: Option<DateTime<Utc>>| t.map(|t| t.to_rfc3339()).unwrap_or_default();
+    format!(
+        "{},{},{},{},{},{},{},{},{},{}\n",
+        transaction.operation_type,
+        transaction.operation_id,
+        csv_field(transaction.tx_type.as_deref().unwrap_or_default()),
+        csv_field(&transaction.status),
+        transaction.signature.as_deref().unwrap_or_default(),
+        transaction.attempts,
+        csv_field(transaction.last_error.as_deref().unwrap_or_default()),
+        timestamp(transaction.submitted_at),
+        timestamp(transaction.confirmed_at),
+        transaction.created_at.to_rfc3339(),
+    )
+}
 
-    let where_clause = where_conditions.join(" AND ");
+struct TransactionExportCursor {
+    governor: QueryGovernor,
+    filter: UserTransactionFilter,
+    after: Option<PageCursor>,
+    header_sent: bool,
+    done: bool,
+}
 
-    // Count total
-    let count_query = format!("SELECT COUNT(*) FROM blockchain_operations WHERE {}", where_clause);
-    let mut count_sqlx = sqlx::query_scalar::<_, i64>(&count_query).bind(user.0.sub);
-    
-    if let Some(t) = &params.transaction_type {
-        count_sqlx = count_sqlx.bind(t);
-    }
-    if let Some(s) = &params.status {
-        count_sqlx = count_sqlx.bind(s);
+/// Export user transaction history as CSV
+///
+/// Uses the history filters; paging is ignored. Rows are streamed page by
+/// page, so large histories do not have to fit in memory.
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/transactions/export",
+    params(TransactionQuery),
+    responses(
+        (status = 200, description = "All transactions matching the filters", content_type = "text/csv"),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid filter")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_user_transactions(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(params): Query<TransactionQuery>,
+) -> Result<Response> {
+    let cursor = TransactionExportCursor {
+        governor: state.query_governor.clone(),
+        filter: UserTransactionFilter::from_query(user.0.sub, &params)?,
+        after: None,
+        header_sent: false,
+        done: false,
+    };
+
+    let stream = futures::stream::unfold(cursor, |mut cursor| async move {
+        if cursor.done {
+            return None;
+        }
+        match export_page(&cursor).await {
+            Ok(rows) => {
+                cursor.after = PageCursor::after(&rows, TRANSACTION_EXPORT_PAGE_SIZE, transaction_cursor);
+                cursor.done = cursor.after.is_none();
+                if rows.is_empty() && cursor.header_sent {
+                    return None;
+                }
+
+                let mut chunk = String::new();
+                if !cursor.header_sent {
+                    chunk.push_str(TRANSACTION_EXPORT_HEADER);
+                    cursor.header_sent = true;
+                }
+                rows.iter().for_each(|row| chunk.push_str(&transaction_export_line(row)));
+                Some((Ok(Bytes::from(chunk)), cursor))
+            }
+            Err(e) => {
+                tracing::error!("User transaction export failed: {}", e);
+                cursor.done = true;
+                Some((Err(e), cursor))
+            }
+        }
+    });
+
+    let filename = format!("gridtokenx_operations_{}.csv", Utc::now().format("%Y%m%d_%H%M%S"));
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+/// Get user trading performance report with P&L attribution
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/my-performance",
+    params(PerformanceReportQuery),
+    responses(
+        (status = 200, description = "Performance report retrieved", body = UserPerformanceReport),
+        (status = 400, description = "Invalid timeframe or period"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_user_performance_report(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(params): Query<PerformanceReportQuery>,
+) -> Result<Json<UserPerformanceReport>> {
+    let report = build_performance_report(&state, user.0.sub, &user.0.username, &params).await?;
+    Ok(Json(report))
+}
+
+/// Export user trading performance report as CSV or PDF
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/my-performance/export",
+    params(PerformanceReportQuery),
+    responses(
+        (status = 200, description = "CSV or PDF file download", content_type = "text/csv"),
+        (status = 400, description = "Invalid timeframe, period or format"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_user_performance_report(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(params): Query<PerformanceReportQuery>,
+) -> Result<Response> {
+    let format = params.format.clone().unwrap_or_else(|| "csv".to_string());
+    if format != "csv" && format != "pdf" {
+        return Err(ApiError::validation_field("format", "Invalid format. Use: csv or pdf"));
     }
 
-    let total = count_sqlx.fetch_one(&state.db).await.unwrap_or(0);
-
-    // Fetch data - map to the view columns
-    // The UserTransaction struct in types.rs uses standard names
-    let query = format!(
-        "SELECT 
-            operation_type, operation_id, user_id, signature, tx_type, 
-            operation_status as status, attempts, last_error, 
-            submitted_at, confirmed_at, created_at, updated_at,
-            CASE 
-                WHEN operation_type = 'settlement' THEN (
-                    SELECT json_build_object(
-                        'energy_amount', energy_amount,
-                        'price_per_kwh', price_per_kwh,
-                        'total_amount', total_amount,
-                        'wheeling_charge', wheeling_charge,
-                        'loss_cost', loss_cost,
-                        'loss_factor', loss_factor,
-                        'effective_energy', effective_energy,
-                        'buyer_zone_id', buyer_zone_id,
-                        'seller_zone_id', seller_zone_id
-                    ) FROM settlements WHERE id = operation_id
-                )
-                WHEN operation_type = 'trading_order' THEN (
-                    SELECT json_build_object(
-                        'side', side,
-                        'energy_amount', energy_amount,
-                        'price_per_kwh', price_per_kwh,
-                        'zone_id', zone_id
-                    ) FROM trading_orders WHERE id = operation_id
-                )
-                ELSE NULL
-            END as metadata
-         FROM blockchain_operations 
-         WHERE {} 
-         ORDER BY created_at DESC 
-         LIMIT ${} OFFSET ${}",
-        where_clause, bind_count, bind_count + 1
+    let report = build_performance_report(&state, user.0.sub, &user.0.username, &params).await?;
+    let filename = format!(
+        "gridtokenx_performance_{}.{}",
+        Utc::now().format("%Y%m%d_%H%M%S"),
+        format
     );
 
-    let mut sqlx_query = sqlx::query_as::<_, UserTransaction>(&query).bind(user.0.sub);
+    let (content_type, body) = if format == "pdf" {
+        let title = format!("GridTokenX Trading Performance - {}", report.username);
+        ("application/pdf", pdf::text_document(&title, &performance_report_lines(&report)))
+    } else {
+        ("text/csv; charset=utf-8", performance_report_csv(&report).into_bytes())
+    };
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+// ==================== HELPER FUNCTIONS ====================
+
+async fn build_performance_report(
+    state: &AppState,
+    user_id: Uuid,
+    username: &str,
+    params: &PerformanceReportQuery,
+) -> Result<UserPerformanceReport> {
+    let duration = parse_timeframe(&params.timeframe)?;
+    let period = parse_report_period(&params.period)?;
+    let start_time = Utc::now() - duration;
+    let tariff = &state.config.grid_tariff;
+
+    // Settlements carry the realized amounts: sellers receive net_amount
+    // (after platform fee and wheeling), buyers pay total_amount.
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            date_trunc($3, created_at) as period_start,
+            COALESCE(SUM(energy_amount) FILTER (WHERE seller_id = $1), 0) as sold_kwh,
+            COALESCE(SUM(energy_amount) FILTER (WHERE buyer_id = $1), 0) as bought_kwh,
+            COALESCE(SUM(total_amount) FILTER (WHERE seller_id = $1), 0) as sell_gross,
+            COALESCE(SUM(net_amount) FILTER (WHERE seller_id = $1), 0) as sell_proceeds,
+            COALESCE(SUM(total_amount) FILTER (WHERE buyer_id = $1), 0) as purchase_cost
+        FROM settlements
+        WHERE (seller_id = $1 OR buyer_id = $1)
+          AND created_at >= $2
+          AND status::text <> 'failed'
+        GROUP BY 1
+        ORDER BY 1 ASC
+        "#,
+    )
+    .bind(user_id)
+    .bind(start_time)
+    .bind(period)
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut periods = Vec::with_capacity(rows.len());
+    let (mut sold_total, mut bought_total, mut gross_total, mut cost_total) = (0.0, 0.0, 0.0, 0.0);
+
+    for row in rows {
+        let sold = decimal_to_f64(row.get("sold_kwh"));
+        let bought = decimal_to_f64(row.get("bought_kwh"));
+        let gross = decimal_to_f64(row.get("sell_gross"));
+        let proceeds = decimal_to_f64(row.get("sell_proceeds"));
+        let cost = decimal_to_f64(row.get("purchase_cost"));
+
+        sold_total += sold;
+        bought_total += bought;
+        gross_total += gross;
+        cost_total += cost;
 
-    if let Some(t) = &params.transaction_type {
-        sqlx_query = sqlx_query.bind(t);
+        periods.push(PerformancePeriod {
+            period_start: row.get("period_start"),
+            energy_sold_kwh: sold,
+            energy_bought_kwh: bought,
+            sell_proceeds: proceeds,
+            purchase_cost: cost,
+            fees_paid: gross - proceeds,
+            realized_pnl: proceeds - cost,
+            savings_vs_grid: savings_vs_grid(
+                bought,
+                cost,
+                sold,
+                proceeds,
+                tariff.retail_per_kwh,
+                tariff.feed_in_per_kwh,
+            ),
+        });
     }
-    if let Some(s) = &params.status {
-        sqlx_query = sqlx_query.bind(s);
+
+    // Self-consumption: generated energy that was not exported
+    let energy = sqlx::query(
+        r#"
+        SELECT
+            COALESCE(SUM(energy_generated), 0)::FLOAT8 as generated,
+            COALESCE(SUM(surplus_energy), 0)::FLOAT8 as exported
+        FROM meter_readings
+        WHERE user_id = $1 AND reading_timestamp >= $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(start_time)
+    .fetch_one(&state.db)
+    .await?;
+
+    let generated: f64 = energy.get("generated");
+    let self_consumed = (generated - energy.get::<f64, _>("exported")).max(0.0);
+
+    Ok(UserPerformanceReport {
+        user_id: user_id.to_string(),
+        username: username.to_string(),
+        timeframe: params.timeframe.clone(),
+        period: period.to_string(),
+        average_buy_price_per_kwh: if bought_total > 0.0 { cost_total / bought_total } else { 0.0 },
+        average_sell_price_per_kwh: if sold_total > 0.0 { gross_total / sold_total } else { 0.0 },
+        total_fees_paid: periods.iter().map(|p| p.fees_paid).sum(),
+        realized_pnl: periods.iter().map(|p| p.realized_pnl).sum(),
+        energy_generated_kwh: generated,
+        energy_self_consumed_kwh: self_consumed,
+        self_consumption_ratio: if generated > 0.0 { self_consumed / generated } else { 0.0 },
+        grid_retail_tariff_per_kwh: tariff.retail_per_kwh,
+        grid_feed_in_tariff_per_kwh: tariff.feed_in_per_kwh,
+        savings_vs_grid: periods.iter().map(|p| p.savings_vs_grid).sum(),
+        periods,
+    })
+}
+
+fn performance_report_csv(report: &UserPerformanceReport) -> String {
+    let mut csv = String::new();
+
+    csv.push_str("Period Start,Sold (kWh),Bought (kWh),Sell Proceeds,Purchase Cost,Fees Paid,Realized P&L,Savings vs Grid\n");
+    for p in &report.periods {
+        csv.push_str(&format!(
+            "{},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4}\n",
+            p.period_start.format("%Y-%m-%d"),
+            p.energy_sold_kwh,
+            p.energy_bought_kwh,
+            p.sell_proceeds,
+            p.purchase_cost,
+            p.fees_paid,
+            p.realized_pnl,
+            p.savings_vs_grid
+        ));
     }
 
-    sqlx_query = sqlx_query.bind(limit);
-    sqlx_query = sqlx_query.bind(offset);
+    csv.push('\n');
+    for line in performance_summary_lines(report) {
+        csv.push_str(&format!("# {}\n", line));
+    }
 
-    let transactions = sqlx_query.fetch_all(&state.db).await.unwrap_or_default();
+    csv
+}
 
-    Ok(Json(UserTransactionsResponse {
-        transactions,
-        total,
-    }))
+fn performance_report_lines(report: &UserPerformanceReport) -> Vec<String> {
+    let mut lines = performance_summary_lines(report);
+    lines.push(String::new());
+    lines.push(format!(
+        "{:<12}{:>10}{:>10}{:>12}{:>12}{:>10}{:>12}{:>12}",
+        "Period", "Sold", "Bought", "Proceeds", "Cost", "Fees", "P&L", "Savings"
+    ));
+    for p in &report.periods {
+        lines.push(format!(
+            "{:<12}{:>10.2}{:>10.2}{:>12.2}{:>12.2}{:>10.2}{:>12.2}{:>12.2}",
+            p.period_start.format("%Y-%m-%d"),
+            p.energy_sold_kwh,
+            p.energy_bought_kwh,
+            p.sell_proceeds,
+            p.purchase_cost,
+            p.fees_paid,
+            p.realized_pnl,
+            p.savings_vs_grid
+        ));
+    }
+    lines
 }
 
-// ==================== HELPER FUNCTIONS ====================
+fn performance_summary_lines(report: &UserPerformanceReport) -> Vec<String> {
+    vec![
+        format!("Timeframe: {} (per {})", report.timeframe, report.period),
+        format!("Realized P&L: {:.2}", report.realized_pnl),
+        format!("Fees paid: {:.2}", report.total_fees_paid),
+        format!("Average buy price: {:.4} per kWh", report.average_buy_price_per_kwh),
+        format!("Average sell price: {:.4} per kWh", report.average_sell_price_per_kwh),
+        format!(
+            "Self-consumption: {:.2} of {:.2} kWh generated ({:.1}%)",
+            report.energy_self_consumed_kwh,
+            report.energy_generated_kwh,
+            report.self_consumption_ratio * 100.0
+        ),
+        format!(
+            "Savings vs grid: {:.2} (retail {:.2}, feed-in {:.2} per kWh)",
+            report.savings_vs_grid, report.grid_retail_tariff_per_kwh, report.grid_feed_in_tariff_per_kwh
+        ),
+    ]
+}
 
 async fn get_seller_stats(
     state: &AppState,
@@ -242,8 +713,8 @@ async fn get_seller_stats(
     Ok(SellerStats {
         offers_created: row.try_get("offers_created").unwrap_or(0),
         offers_fulfilled: row.try_get("offers_fulfilled").unwrap_or(0),
-        total_energy_sold_kwh: decimal_to_f64(row.get("total_sold")),
-        total_revenue_usd: decimal_to_f64(row.get("total_revenue")),
+        total_energy_sold_kwh: EnergyKwh::from(row.get::<Decimal, _>("total_sold")),
+        total_revenue_usd: TokenAmount::from(row.get::<Decimal, _>("total_revenue")),
         average_price_per_kwh: decimal_to_f64(row.get("avg_price")),
     })
 }
@@ -274,8 +745,8 @@ async fn get_buyer_stats(
     Ok(BuyerStats {
         orders_created: row.try_get("orders_created").unwrap_or(0),
         orders_fulfilled: row.try_get("orders_fulfilled").unwrap_or(0),
-        total_energy_purchased_kwh: decimal_to_f64(row.get("total_purchased")),
-        total_spent_usd: decimal_to_f64(row.get("total_spent")),
+        total_energy_purchased_kwh: EnergyKwh::from(row.get::<Decimal, _>("total_purchased")),
+        total_spent_usd: TokenAmount::from(row.get::<Decimal, _>("total_spent")),
         average_price_per_kwh: decimal_to_f64(row.get("avg_price")),
     })
 }
@@ -313,8 +784,8 @@ async fn get_overall_user_stats(
 
     Ok(OverallUserStats {
         total_transactions: row.try_get("total_transactions").unwrap_or(0),
-        total_volume_kwh: decimal_to_f64(row.get("total_volume")),
-        net_revenue_usd: decimal_to_f64(row.get("net_revenue")),
+        total_volume_kwh: EnergyKwh::from(row.get::<Decimal, _>("total_volume")),
+        net_revenue_usd: TokenAmount::from(row.get::<Decimal, _>("net_revenue")),
         favorite_energy_source: None, // No source data
     })
 }
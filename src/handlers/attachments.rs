@@ -0,0 +1,272 @@
+//! Attachments Handler
+//!
+//! Upload of KYC documents, dispute evidence and certificate validation
+//! data, listing per owner and signed downloads
+
+use std::str::FromStr;
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::StreamExt;
+use serde::Deserialize;
+use tracing::{error, warn};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
+use crate::error::{ApiError, Result};
+use crate::services::attachments::{Attachment, AttachmentDownload, AttachmentOwner};
+use crate::services::dispute::{DisputeStatus, EvidenceKind};
+use crate::services::AuditEvent;
+use crate::AppState;
+
+/// What an upload or listing refers to
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AttachmentOwnerQuery {
+    /// kyc, dispute or certificate
+    pub owner_type: AttachmentOwner,
+    /// User ID for kyc, dispute ID or certificate ID
+    pub owner_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UploadQuery {
+    /// kyc, dispute or certificate
+    pub owner_type: AttachmentOwner,
+    /// User ID for kyc, dispute ID or certificate ID
+    pub owner_id: Uuid,
+    /// Original file name
+    pub file_name: String,
+}
+
+/// Signed download parameters
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AttachmentDownloadQuery {
+    /// Expiry as Unix timestamp
+    pub expires: i64,
+    /// Hex-encoded HMAC signature
+    pub signature: String,
+}
+
+fn is_admin(user: &AuthenticatedUser) -> bool {
+    matches!(Role::from_str(&user.0.role), Ok(Role::Admin))
+}
+
+/// Upload an attachment
+/// POST /api/v1/attachments
+///
+/// The request body is the file itself, with its type in `Content-Type`.
+/// Files uploaded to a dispute are added to its evidence.
+#[utoipa::path(
+    post,
+    path = "/api/v1/attachments",
+    tag = "attachments",
+    params(UploadQuery),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream", description = "PDF, PNG, JPEG, WebP, CSV or JSON file"),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Attachment stored", body = Attachment),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Owner not found"),
+        (status = 409, description = "Dispute is closed"),
+        (status = 422, description = "Empty, too large, unsupported or infected file"),
+        (status = 502, description = "Storage or malware scanner unavailable")
+    )
+)]
+pub async fn upload_attachment(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<UploadQuery>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Json<Attachment>> {
+    let admin = is_admin(&user);
+    let service = &state.attachment_service;
+    service
+        .ensure_access(user.0.sub, admin, params.owner_type, params.owner_id)
+        .await?;
+
+    if params.owner_type == AttachmentOwner::Dispute {
+        let dispute = state.dispute_service.get(params.owner_id).await?;
+        if !DisputeStatus::parse(&dispute.status).is_some_and(|s| s.is_active()) {
+            return Err(ApiError::Conflict(format!("Dispute is already {}", dispute.status)));
+        }
+    }
+
+    // Stop reading as soon as the upload grows past the limit
+    let limit = service.max_bytes();
+    let mut bytes = Vec::new();
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| ApiError::BadRequest(format!("Failed to read upload: {}", e)))?;
+        if bytes.len() + chunk.len() > limit {
+            return Err(ApiError::validation_field("file", format!("File exceeds {} bytes", limit)));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    let declared_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let attachment = service
+        .upload(
+            user.0.sub,
+            params.owner_type,
+            params.owner_id,
+            &params.file_name,
+            declared_type,
+            &bytes,
+        )
+        .await?;
+
+    if params.owner_type == AttachmentOwner::Dispute {
+        state
+            .dispute_service
+            .add_evidence(
+                user.0.sub,
+                admin,
+                params.owner_id,
+                EvidenceKind::Attachment,
+                &attachment.id.to_string(),
+            )
+            .await?;
+    }
+
+    Ok(Json(attachment))
+}
+
+/// List attachments of a KYC submission, dispute or certificate
+/// GET /api/v1/attachments
+#[utoipa::path(
+    get,
+    path = "/api/v1/attachments",
+    tag = "attachments",
+    params(AttachmentOwnerQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Attachments, newest first", body = Vec<Attachment>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Owner not found")
+    )
+)]
+pub async fn list_attachments(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<AttachmentOwnerQuery>,
+) -> Result<Json<Vec<Attachment>>> {
+    state
+        .attachment_service
+        .ensure_access(user.0.sub, is_admin(&user), params.owner_type, params.owner_id)
+        .await?;
+
+    Ok(Json(
+        state
+            .attachment_service
+            .list(params.owner_type, params.owner_id)
+            .await?,
+    ))
+}
+
+/// Get a short-lived signed download URL for an attachment
+/// GET /api/v1/attachments/{id}/download-url
+#[utoipa::path(
+    get,
+    path = "/api/v1/attachments/{id}/download-url",
+    tag = "attachments",
+    params(("id" = Uuid, Path, description = "Attachment ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Signed download URL", body = AttachmentDownload),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Attachment not found")
+    )
+)]
+pub async fn get_attachment_download_url(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<Json<AttachmentDownload>> {
+    let attachment = state.attachment_service.get(attachment_id).await?;
+    let owner = AttachmentOwner::parse(&attachment.owner_type)
+        .ok_or_else(|| ApiError::Internal(format!("Unknown attachment owner {}", attachment.owner_type)))?;
+    state
+        .attachment_service
+        .ensure_access(user.0.sub, is_admin(&user), owner, attachment.owner_id)
+        .await
+        .map_err(|_| ApiError::NotFound("Attachment not found".to_string()))?;
+
+    state.audit_logger.log_async(AuditEvent::DataAccess {
+        user_id: user.0.sub,
+        resource_type: "attachment".to_string(),
+        resource_id: attachment.id.to_string(),
+        action: "download".to_string(),
+    });
+
+    Ok(Json(state.attachment_service.download_url(&attachment)))
+}
+
+/// Download an attachment using a signed URL (no bearer token required)
+/// GET /api/v1/attachments/{id}/download
+#[utoipa::path(
+    get,
+    path = "/api/v1/attachments/{id}/download",
+    tag = "attachments",
+    params(("id" = Uuid, Path, description = "Attachment ID"), AttachmentDownloadQuery),
+    responses(
+        (status = 200, description = "File download"),
+        (status = 403, description = "Invalid or expired signature"),
+        (status = 404, description = "Attachment not found")
+    )
+)]
+pub async fn download_attachment(
+    State(state): State<AppState>,
+    Path(attachment_id): Path<Uuid>,
+    Query(params): Query<AttachmentDownloadQuery>,
+) -> Response {
+    if !state
+        .attachment_service
+        .verify_download(attachment_id, params.expires, &params.signature)
+    {
+        warn!("Rejected attachment download for {}: invalid or expired signature", attachment_id);
+        return (StatusCode::FORBIDDEN, "Invalid or expired download link").into_response();
+    }
+
+    let attachment = match state.attachment_service.get(attachment_id).await {
+        Ok(attachment) => attachment,
+        Err(ApiError::NotFound(_)) => return (StatusCode::NOT_FOUND, "Attachment not found").into_response(),
+        Err(e) => {
+            error!("Failed to load attachment {}: {}", attachment_id, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load attachment").into_response();
+        }
+    };
+
+    let bytes = match state.attachment_service.load_local(&attachment).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to read attachment {} from storage: {}", attachment_id, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load attachment").into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, attachment.content_type.clone()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", attachment.file_name),
+            ),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        bytes,
+    )
+        .into_response()
+}
@@ -0,0 +1,90 @@
+//! Audit Log Handler
+//!
+//! Admin search over the audit log by user, actor, event type, severity,
+//! outcome, resource, client location and time range
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::database::QueryClass;
+use crate::error::Result;
+use crate::services::audit_logger::{
+    AuditEventRecord, AuditLogFilter, AuditLogger, AuditOutcome, AuditSeverity, SEARCH_INDEXES,
+};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditLogQuery {
+    /// User the records belong to
+    pub user_id: Option<Uuid>,
+    /// User who performed the action
+    pub actor_id: Option<Uuid>,
+    pub event_type: Option<String>,
+    pub severity: Option<AuditSeverity>,
+    pub outcome: Option<AuditOutcome>,
+    /// e.g. order, dispute, legal_hold, endpoint
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound
+    pub to: Option<DateTime<Utc>>,
+    /// ISO country code of the client IP (GeoIP)
+    pub country: Option<String>,
+    /// Autonomous system number of the client IP (GeoIP)
+    pub asn: Option<i64>,
+    /// Default 100, at most `QUERY_AUDIT_MAX_ROWS` (500)
+    pub limit: Option<i64>,
+}
+
+/// Search the audit log
+/// GET /api/v1/admin/audit/logs
+///
+/// The search must filter by user, actor, event type, resource type, country,
+/// ASN, or a time range of at most 31 days.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit/logs",
+    tag = "admin",
+    params(AuditLogQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Matching audit records, newest first", body = Vec<AuditEventRecord>),
+        (status = 400, description = "Filter not served by an index"),
+        (status = 403, description = "Admin access required"),
+        (status = 422, description = "Search exceeded its time limit")
+    )
+)]
+pub async fn search_audit_logs(
+    State(state): State<AppState>,
+    Query(params): Query<AuditLogQuery>,
+) -> Result<Json<Vec<AuditEventRecord>>> {
+    let filter = AuditLogFilter {
+        user_id: params.user_id,
+        actor_id: params.actor_id,
+        event_type: params.event_type,
+        severity: params.severity,
+        outcome: params.outcome,
+        resource_type: params.resource_type,
+        resource_id: params.resource_id,
+        from: params.from,
+        to: params.to,
+        country: params.country,
+        asn: params.asn,
+        limit: state
+            .query_governor
+            .row_limit(QueryClass::Audit, params.limit.unwrap_or(100)),
+    };
+
+    let mut query = state
+        .query_governor
+        .begin(QueryClass::Audit, "GET /api/v1/admin/audit/logs", &SEARCH_INDEXES, filter.predicates())
+        .await?;
+    let result = AuditLogger::search_in(query.conn(), &filter).await;
+    Ok(Json(query.finish(result).await?))
+}
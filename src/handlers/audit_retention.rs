@@ -0,0 +1,142 @@
+//! Audit Retention Handler
+//!
+//! Admin view of audit retention per event class and management of legal
+//! holds that exempt audit records from purging
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::audit_retention::{LegalHold, RetentionPolicy};
+use crate::AppState;
+
+/// New legal hold. Without a user or range it covers all audit records.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct PlaceLegalHoldRequest {
+    /// User whose records are held
+    pub user_id: Option<Uuid>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    /// Case or matter reference
+    #[validate(length(min = 3, max = 500))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReleaseLegalHoldRequest {
+    #[validate(length(min = 3, max = 500))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LegalHoldQuery {
+    /// Include released holds
+    pub include_released: Option<bool>,
+}
+
+/// Audit retention policy
+/// GET /api/v1/admin/audit/retention
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit/retention",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Retention per event class and active hold count", body = RetentionPolicy),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_retention_policy(State(state): State<AppState>) -> Result<Json<RetentionPolicy>> {
+    Ok(Json(state.audit_retention.policy().await?))
+}
+
+/// List legal holds
+/// GET /api/v1/admin/audit/legal-holds
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit/legal-holds",
+    tag = "admin",
+    params(LegalHoldQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Legal holds, newest first", body = Vec<LegalHold>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_legal_holds(
+    State(state): State<AppState>,
+    Query(params): Query<LegalHoldQuery>,
+) -> Result<Json<Vec<LegalHold>>> {
+    Ok(Json(
+        state
+            .audit_retention
+            .list_holds(params.include_released.unwrap_or(false))
+            .await?,
+    ))
+}
+
+/// Place a legal hold
+/// POST /api/v1/admin/audit/legal-holds
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/audit/legal-holds",
+    tag = "admin",
+    request_body = PlaceLegalHoldRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Hold placed", body = LegalHold),
+        (status = 403, description = "Admin access required"),
+        (status = 422, description = "Invalid reason or time range")
+    )
+)]
+pub async fn place_legal_hold(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<PlaceLegalHoldRequest>,
+) -> Result<Json<LegalHold>> {
+    Ok(Json(
+        state
+            .audit_retention
+            .place_hold(user.0.sub, payload.user_id, payload.starts_at, payload.ends_at, &payload.reason)
+            .await?,
+    ))
+}
+
+/// Release a legal hold
+/// POST /api/v1/admin/audit/legal-holds/{id}/release
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/audit/legal-holds/{id}/release",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Hold ID")),
+    request_body = ReleaseLegalHoldRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Hold released", body = LegalHold),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Hold not found"),
+        (status = 409, description = "Hold already released")
+    )
+)]
+pub async fn release_legal_hold(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(hold_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ReleaseLegalHoldRequest>,
+) -> Result<Json<LegalHold>> {
+    Ok(Json(
+        state
+            .audit_retention
+            .release_hold(user.0.sub, hold_id, &payload.reason)
+            .await?,
+    ))
+}
@@ -3,11 +3,13 @@
 //! Authentication handlers for login and email verification.
 
 use axum::{
-    extract::State,
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap},
     Json,
     response::IntoResponse,
 };
 use solana_sdk::signer::Signer;
+use std::net::SocketAddr;
 use tracing::info;
 use uuid::Uuid;
 use base64::{engine::general_purpose, Engine as _};
@@ -15,25 +17,20 @@ use base64::{engine::general_purpose, Engine as _};
 use crate::AppState;
 use crate::auth::password::PasswordService;
 use crate::middleware::metrics::{track_auth_attempt, track_auth_failure};
+use crate::models::pii::{SealedUserPii, UserPii, USER_PII_COLUMNS};
+use crate::services::login_step_up::{LoginDecision, StepUpChallenge};
+use crate::services::AuditEvent;
 use super::types::{
-    LoginRequest, AuthResponse, UserResponse, UserRow,
-    VerifyEmailResponse, VerifyEmailRequest,
+    LoginRequest, AuthResponse, UserResponse, UserRow, user_row_columns,
+    StepUpVerifyRequest, VerifyEmailResponse, VerifyEmailRequest,
 };
 
 /// Row type for login query that includes password_hash
 #[derive(Debug, sqlx::FromRow)]
 struct LoginUserRow {
-    id: Uuid,
-    username: String,
-    email: String,
     password_hash: String,
-    role: String,
-    first_name: Option<String>,
-    last_name: Option<String>,
-    wallet_address: Option<String>,
-    balance: Option<rust_decimal::Decimal>,
-    locked_amount: Option<rust_decimal::Decimal>,
-    locked_energy: Option<rust_decimal::Decimal>,
+    #[sqlx(flatten)]
+    user: UserRow,
 }
 
 /// Login Handler - queries database for user and verifies password
@@ -43,23 +40,44 @@ struct LoginUserRow {
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login successful", body = AuthResponse),
-        (status = 401, description = "Unauthorized - Invalid credentials"),
+        (status = 401, description = "Invalid credentials, or a risky login that needs the emailed code (body is a StepUpChallenge)", body = StepUpChallenge),
         (status = 500, description = "Internal server error")
     ),
     tag = "auth"
 )]
 pub async fn login(
     State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(request): Json<LoginRequest>,
 ) -> impl IntoResponse {
     info!("🔐 Login attempt for identity: {}", request.username);
 
-    // Query database for user including password_hash, searching by either username or email
-    let user_result = sqlx::query_as::<_, LoginUserRow>(
-        "SELECT id, username, email, password_hash, role::text as role, first_name, last_name, wallet_address, balance, locked_amount, locked_energy
-         FROM users WHERE (username = $1 OR email = $1) AND is_active = true"
-    )
+    // Client IP and device, recorded with the login for the security overview
+    let client_ip = state
+        .network_acl
+        .client_ip(Some(peer.ip()), &headers)
+        .unwrap_or(peer.ip())
+        .to_string();
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.chars().take(256).collect::<String>());
+    let login_failed = |reason: &str| AuditEvent::LoginFailed {
+        email: request.username.clone(),
+        ip: client_ip.clone(),
+        reason: reason.to_string(),
+        user_agent: user_agent.clone(),
+    };
+
+    // Query database for user including password_hash, searching by either username or email digest
+    let user_result = sqlx::query_as::<_, LoginUserRow>(&format!(
+        "SELECT password_hash, {}
+         FROM users WHERE (username = $1 OR email_digest = $2 OR lower(email) = lower($1)) AND is_active = true",
+        user_row_columns()
+    ))
     .bind(&request.username)
+    .bind(state.pii.email_digest(&request.username))
     .fetch_optional(&state.db)
     .await;
 
@@ -68,25 +86,39 @@ pub async fn login(
             // Verify password using bcrypt
             match PasswordService::verify_password(&request.password, &u.password_hash) {
                 Ok(true) => {
-                    info!("✅ Password verified for user: {}", u.username);
+                    info!("✅ Password verified for user: {}", u.user.username);
                     track_auth_attempt(true, "password");
-                    UserRow {
-                        id: u.id,
-                        username: u.username,
-                        email: u.email,
-                        role: u.role,
-                        first_name: u.first_name,
-                        last_name: u.last_name,
-                        wallet_address: u.wallet_address,
-                        balance: u.balance,
-                        locked_amount: u.locked_amount,
-                        locked_energy: u.locked_energy,
+                    match u.user.into_response(&state.pii).await {
+                        Ok(user) => user,
+                        Err(e) => {
+                            tracing::error!("❌ Failed to decrypt user profile: {}", e);
+                            return (
+                                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                                Json(AuthResponse {
+                                    access_token: String::new(),
+                                    expires_in: 0,
+                                    user: UserResponse {
+                                        id: Uuid::nil(),
+                                        username: String::new(),
+                                        email: String::new(),
+                                        role: String::new(),
+                                        first_name: String::new(),
+                                        last_name: String::new(),
+                                        wallet_address: None,
+                                        balance: rust_decimal::Decimal::ZERO,
+                                        locked_amount: rust_decimal::Decimal::ZERO,
+                                        locked_energy: rust_decimal::Decimal::ZERO,
+                                    },
+                                })
+                            ).into_response();
+                        }
                     }
                 }
                 Ok(false) => {
-                    info!("❌ Invalid password for user: {}", u.username);
+                    info!("❌ Invalid password for user: {}", u.user.username);
                     track_auth_attempt(false, "password");
                     track_auth_failure("invalid_password");
+                    state.audit_logger.log_async(login_failed("invalid_password"));
                     return (
                         axum::http::StatusCode::UNAUTHORIZED,
                         Json(AuthResponse {
@@ -135,6 +167,7 @@ pub async fn login(
             info!("❌ User not found: {}", request.username);
             track_auth_attempt(false, "password");
             track_auth_failure("user_not_found");
+            state.audit_logger.log_async(login_failed("user_not_found"));
             return (
                 axum::http::StatusCode::UNAUTHORIZED,
                 Json(AuthResponse {
@@ -179,29 +212,102 @@ pub async fn login(
         }
     };
 
+    // Risky logins get a one-time code instead of a session token
+    match state
+        .login_step_up
+        .assess(user.id, &user.username, &client_ip, user_agent.as_deref())
+        .await
+    {
+        Ok(LoginDecision::Allow) => {}
+        Ok(LoginDecision::StepUp(challenge)) => {
+            info!("🔐 Step-up verification required for user: {}", user.username);
+            return (
+                axum::http::StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer error=\"insufficient_user_authentication\"")],
+                Json(challenge),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            tracing::error!("❌ Login step-up failed: {}", e);
+            return e.into_response();
+        }
+    }
+
+    issue_session(&state, user, client_ip, user_agent)
+}
+
+/// Complete a risky login with the emailed one-time code
+/// POST /api/v1/auth/token/step-up
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/token/step-up",
+    request_body = StepUpVerifyRequest,
+    responses(
+        (status = 200, description = "Code accepted; session issued", body = AuthResponse),
+        (status = 401, description = "Invalid code, or unknown, used or expired challenge"),
+        (status = 429, description = "Too many wrong codes; the challenge is locked")
+    ),
+    tag = "auth"
+)]
+pub async fn verify_login_step_up(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(request): Json<StepUpVerifyRequest>,
+) -> Result<axum::response::Response, crate::ApiError> {
+    let client_ip = state
+        .network_acl
+        .client_ip(Some(peer.ip()), &headers)
+        .unwrap_or(peer.ip())
+        .to_string();
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.chars().take(256).collect::<String>());
+
+    let user_id = state
+        .login_step_up
+        .verify(request.challenge_id, &request.code, &client_ip)
+        .await?;
+
+    let user = sqlx::query_as::<_, UserRow>(&format!(
+        "SELECT {} FROM users WHERE id = $1 AND is_active = true",
+        user_row_columns()
+    ))
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| crate::ApiError::Unauthorized("Account is not active".to_string()))?;
+    let user = user.into_response(&state.pii).await?;
+
+    Ok(issue_session(&state, user, client_ip, user_agent))
+}
+
+/// Session token for a user whose login is complete
+fn issue_session(
+    state: &AppState,
+    user: UserResponse,
+    client_ip: String,
+    user_agent: Option<String>,
+) -> axum::response::Response {
     // Generate token using JWT service
     let claims = crate::auth::Claims::new(user.id, user.username.clone(), user.role.clone());
     let token = state.jwt_service.encode_token(&claims).unwrap_or_else(|_| {
         format!("token_{}_{}", user.username, user.id)
     });
 
-    info!("✅ Login successful for: {} (email: {}, wallet: {:?})", user.username, user.email, user.wallet_address);
+    info!("✅ Login successful for: {} (wallet: {:?})", user.username, user.wallet_address);
+    state.audit_logger.log_async(AuditEvent::UserLogin {
+        user_id: user.id,
+        ip: client_ip,
+        user_agent,
+    });
 
     Json(AuthResponse {
         access_token: token,
         expires_in: 86400,
-        user: UserResponse {
-            id: user.id,
-            username: user.username,
-            email: user.email,
-            role: user.role,
-            first_name: user.first_name.unwrap_or_default(),
-            last_name: user.last_name.unwrap_or_default(),
-            wallet_address: user.wallet_address,
-            balance: user.balance.unwrap_or_default(),
-            locked_amount: user.locked_amount.unwrap_or_default(),
-            locked_energy: user.locked_energy.unwrap_or_default(),
-        },
+        user,
     }).into_response()
 }
 
@@ -323,7 +429,7 @@ pub async fn verify_email(
 
     // Try to find and update user by verification token
     // Try to find and update user by verification token
-    let update_result = sqlx::query(
+    let update_result = sqlx::query(&format!(
         "UPDATE users SET 
             email_verified = true, 
             wallet_address = $1, 
@@ -333,8 +439,9 @@ pub async fn verify_email(
             blockchain_registered = $5, 
             updated_at = NOW() 
          WHERE email_verification_token = $6 AND email_verified = false
-         RETURNING id, username, email, role::text as role, first_name, last_name"
-    )
+         RETURNING id, username, role::text as role, {}",
+        USER_PII_COLUMNS
+    ))
     .bind(&wallet_address)
     .bind(&encrypted_key_bytes)
     .bind(&salt_bytes)
@@ -349,15 +456,18 @@ pub async fn verify_email(
             use sqlx::Row;
             let user_id: Uuid = row.get("id");
             let username: String = row.get("username");
-            let email: String = row.get("email");
             let role: String = row.get("role");
-            let first_name: Option<String> = row.get("first_name");
-            let last_name: Option<String> = row.get("last_name");
             
             let chain_status = if blockchain_registered { " (on-chain)" } else { "" };
-            info!("✅ Email verified successfully for user: {} (email: {}), wallet assigned{}: {}", username, email, chain_status, wallet_address);
+            info!("✅ Email verified successfully for user: {}, wallet assigned{}: {}", username, chain_status, wallet_address);
             
-            let auth = generate_auth_response(user_id, username, email, role, first_name, last_name, Some(wallet_address.clone()));
+            let auth = match open_user_pii(&state, &row).await {
+                Ok(pii) => generate_auth_response(user_id, username, pii.email, role, pii.first_name, pii.last_name, Some(wallet_address.clone())),
+                Err(e) => {
+                    tracing::error!("❌ Failed to decrypt user profile: {}", e);
+                    None
+                }
+            };
             
             Json(VerifyEmailResponse {
                 success: true,
@@ -370,7 +480,7 @@ pub async fn verify_email(
             // For testing, auto-verify based on token pattern (verify_<username>)
             if token.starts_with("verify_") {
                 let username = token.strip_prefix("verify_").unwrap_or("");
-                let update_test = sqlx::query(
+                let update_test = sqlx::query(&format!(
                     "UPDATE users SET 
                         email_verified = true, 
                         wallet_address = $1, 
@@ -380,8 +490,9 @@ pub async fn verify_email(
                         blockchain_registered = $5, 
                         updated_at = NOW() 
                      WHERE username = $6 AND (wallet_address IS NULL OR wallet_address = '')
-                     RETURNING id, username, email, role::text as role, first_name, last_name"
-                )
+                     RETURNING id, username, role::text as role, {}",
+                    USER_PII_COLUMNS
+                ))
                 .bind(&wallet_address)
                 .bind(&encrypted_key_bytes)
                 .bind(&salt_bytes)
@@ -396,15 +507,18 @@ pub async fn verify_email(
                         use sqlx::Row;
                         let user_id: Uuid = row.get("id");
                         let username: String = row.get("username");
-                        let email: String = row.get("email");
                         let role: String = row.get("role");
-                        let first_name: Option<String> = row.get("first_name");
-                        let last_name: Option<String> = row.get("last_name");
                         
                         let chain_status = if blockchain_registered { " (on-chain)" } else { "" };
-                        info!("✅ Email verified (test mode) for user: {} (email: {}), wallet assigned{}: {}", username, email, chain_status, wallet_address);
+                        info!("✅ Email verified (test mode) for user: {}, wallet assigned{}: {}", username, chain_status, wallet_address);
                         
-                        let auth = generate_auth_response(user_id, username, email, role, first_name, last_name, Some(wallet_address.clone()));
+                        let auth = match open_user_pii(&state, &row).await {
+                            Ok(pii) => generate_auth_response(user_id, username, pii.email, role, pii.first_name, pii.last_name, Some(wallet_address.clone())),
+                            Err(e) => {
+                                tracing::error!("❌ Failed to decrypt user profile: {}", e);
+                                None
+                            }
+                        };
                         
                         Json(VerifyEmailResponse {
                             success: true,
@@ -415,7 +529,7 @@ pub async fn verify_email(
                     }
                     _ => {
                         // User may already have a wallet, just verify email and fetch user
-                        let user_result = sqlx::query(
+                        let user_result = sqlx::query(&format!(
                             "UPDATE users SET 
                                 email_verified = true,
                                 wallet_address = COALESCE(NULLIF(wallet_address, ''), $1),
@@ -423,8 +537,9 @@ pub async fn verify_email(
                                 wallet_salt = COALESCE(wallet_salt, $3),
                                 encryption_iv = COALESCE(encryption_iv, $4)
                              WHERE username = $5
-                             RETURNING id, username, email, role::text as role, first_name, last_name, wallet_address"
-                        )
+                             RETURNING id, username, role::text as role, wallet_address, {}",
+                            USER_PII_COLUMNS
+                        ))
                         .bind(&wallet_address)
                         .bind(&encrypted_key_bytes)
                         .bind(&salt_bytes)
@@ -438,13 +553,16 @@ pub async fn verify_email(
                                 use sqlx::Row;
                                 let user_id: Uuid = row.get("id");
                                 let username: String = row.get("username");
-                                let email: String = row.get("email");
                                 let role: String = row.get("role");
-                                let first_name: Option<String> = row.get("first_name");
-                                let last_name: Option<String> = row.get("last_name");
                                 let existing_wallet: Option<String> = row.get("wallet_address");
                                 
-                                let auth = generate_auth_response(user_id, username, email, role, first_name, last_name, existing_wallet.clone());
+                                let auth = match open_user_pii(&state, &row).await {
+                                    Ok(pii) => generate_auth_response(user_id, username, pii.email, role, pii.first_name, pii.last_name, existing_wallet.clone()),
+                                    Err(e) => {
+                                        tracing::error!("❌ Failed to decrypt user profile: {}", e);
+                                        None
+                                    }
+                                };
                                 
                                 Json(VerifyEmailResponse {
                                     success: true,
@@ -483,3 +601,8 @@ pub async fn verify_email(
     }
 }
 
+/// Email and names of a user row returned by an update
+async fn open_user_pii(state: &AppState, row: &sqlx::postgres::PgRow) -> crate::error::Result<UserPii> {
+    use sqlx::FromRow;
+    SealedUserPii::from_row(row)?.open(&state.pii).await
+}
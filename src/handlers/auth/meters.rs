@@ -5,13 +5,23 @@
 use axum::{
     extract::{State, Query},
     http::HeaderMap,
-    Json,
+    Extension, Json,
 };
 use tracing::{info, error, warn};
 use uuid::Uuid;
+use validator::Validate;
+use rust_decimal::prelude::ToPrimitive;
 use crate::auth::middleware::AuthenticatedUser;
 use serde_json;
-use crate::services::meter_analyzer::{check_alerts, calculate_health_score};
+use crate::services::meter_analyzer::{check_alerts, check_capacity, calculate_health_score};
+use crate::config::{MintGuardMode, TimestampAssessment};
+use crate::services::meter_gateway::GatewayIdentity;
+use crate::models::EnergyKwh;
+use crate::services::pii::sealed_key_id;
+use crate::error::ApiError;
+use crate::handlers::meter::stub::{
+    claim_reading_sequence, record_mint_result, verify_reading_envelope, ReadingEnvelope, MINT_PENDING,
+};
 
 use crate::AppState;
 use super::types::{
@@ -50,10 +60,11 @@ pub async fn get_my_meters(
 
     if let Ok(claims) = state.jwt_service.decode_token(token) {
         // Query meters from database including coordinates
-        let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>)>(
-            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id
+        let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>, Option<String>)>(
+            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id, r.registry_pda
              FROM meters m
              JOIN users u ON m.user_id = u.id
+             LEFT JOIN meter_registry r ON r.meter_serial = m.serial_number
              WHERE m.user_id = $1"
         )
         .bind(claims.sub)
@@ -61,7 +72,7 @@ pub async fn get_my_meters(
         .await;
 
         if let Ok(meters) = meters_result {
-            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone)| {
+            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone, registry_pda)| {
                 MeterResponse {
                     id: *id,
                     serial_number: serial.clone(),
@@ -72,6 +83,7 @@ pub async fn get_my_meters(
                     latitude: *lat,
                     longitude: *lng,
                     zone_id: *zone,
+                    registry_pda: registry_pda.clone(),
                 }
             }).collect();
             
@@ -97,10 +109,11 @@ pub async fn get_registered_meters(
 ) -> Json<Vec<MeterResponse>> {
     info!("📊 Get all registered meters");
     
-    let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>)>(
-        "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id
+    let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>, Option<String>)>(
+        "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id, r.registry_pda
          FROM meters m
          JOIN users u ON m.user_id = u.id
+         LEFT JOIN meter_registry r ON r.meter_serial = m.serial_number
          WHERE m.is_verified = true"
     )
     .fetch_all(&state.db)
@@ -108,7 +121,7 @@ pub async fn get_registered_meters(
 
     match meters_result {
         Ok(meters) => {
-            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone)| {
+            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone, registry_pda)| {
                 MeterResponse {
                     id: *id,
                     serial_number: serial.clone(),
@@ -119,6 +132,7 @@ pub async fn get_registered_meters(
                     latitude: *lat,
                     longitude: *lng,
                     zone_id: *zone,
+                    registry_pda: registry_pda.clone(),
                 }
             }).collect();
             
@@ -249,6 +263,7 @@ pub async fn public_grid_status(
         net_balance: metrics.net_balance,
         active_meters: metrics.active_meters,
         co2_saved_kg: metrics.co2_saved_kg,
+        co2_factor: metrics.co2_factor,
         timestamp: metrics.timestamp,
     })
 }
@@ -281,6 +296,7 @@ pub async fn public_grid_history(
                 net_balance: h.net_balance,
                 active_meters: h.active_meters,
                 co2_saved_kg: h.co2_saved_kg,
+                co2_factor: h.co2_factor,
                 timestamp: h.timestamp,
             }).collect();
             Json(response)
@@ -319,6 +335,14 @@ pub async fn register_meter(
     let meter_type = request.meter_type.unwrap_or_else(|| "solar".to_string());
     let location = request.location.unwrap_or_else(|| "Not specified".to_string());
 
+    if let Some(Err(e)) = request.installation.as_ref().map(Validate::validate) {
+        return Json(RegisterMeterResponse {
+            success: false,
+            message: format!("Invalid installation: {}", e),
+            meter: None,
+        });
+    }
+
     // Check if meter serial already exists
     let existing = sqlx::query_as::<_, (Uuid,)>(
         "SELECT id FROM meters WHERE serial_number = $1"
@@ -356,19 +380,34 @@ pub async fn register_meter(
             info!("✅ Meter {} registered for user {} (Zone: {:?})", request.serial_number, user_id, request.zone_id);
             
             // Sync to meter_registry for FK constraints
-            let _ = sqlx::query(
-                "INSERT INTO meter_registry (id, user_id, meter_serial, meter_type, location_address, meter_key_hash, verification_method, verification_status, zone_id)
-                 VALUES ($1, $2, $3, $4, $5, 'mock_hash', 'serial', 'verified', $6)"
-            )
-            .bind(meter_id)
-            .bind(user_id)
-            .bind(&request.serial_number)
-            .bind(&meter_type)
-            .bind(&location)
-            .bind(request.zone_id)
-            .execute(&state.db)
-            .await
-            .map_err(|e| error!("Failed to sync meter_registry: {}", e));
+            let registry_insert = match state.pii.seal(&location).await {
+                Ok(sealed_address) => sqlx::query(
+                    "INSERT INTO meter_registry (id, user_id, meter_serial, meter_type, location_address_encrypted, pii_key_id, meter_key_hash, verification_method, verification_status, zone_id)
+                     VALUES ($1, $2, $3, $4, $5, $6, 'mock_hash', 'serial', 'verified', $7)"
+                )
+                .bind(meter_id)
+                .bind(user_id)
+                .bind(&request.serial_number)
+                .bind(&meter_type)
+                .bind(&sealed_address)
+                .bind(sealed_key_id(&sealed_address))
+                .bind(request.zone_id)
+                .execute(&state.db)
+                .await
+                .map_err(|e| error!("Failed to sync meter_registry: {}", e)),
+                Err(e) => Err(error!("Failed to encrypt meter address: {}", e)),
+            };
+
+            // Meters are registered verified, so anchor the ownership proof on-chain
+            if registry_insert.is_ok() {
+                state.meter_registry_sync.spawn_sync(meter_id);
+
+                if let Some(installation) = &request.installation {
+                    if let Err(e) = state.meter_installations.update(&request.serial_number, installation).await {
+                        error!("Failed to store meter installation: {}", e);
+                    }
+                }
+            }
 
             // Get user wallet for response
             let wallet = sqlx::query_as::<_, (Option<String>,)>(
@@ -396,6 +435,7 @@ pub async fn register_meter(
                     latitude: request.latitude,
                     longitude: request.longitude,
                     zone_id: request.zone_id,
+                    registry_pda: None,
                 }),
             })
         }
@@ -476,6 +516,23 @@ pub async fn verify_meter(
     match update_result {
         Ok(result) if result.rows_affected() > 0 => {
             info!("✅ Meter {} verified", request.serial_number);
+
+            // Mirror the decision on the registry and anchor the ownership proof on-chain
+            let registry_id = sqlx::query_scalar::<_, Uuid>(
+                "UPDATE meter_registry
+                 SET verification_status = 'verified', verified_at = COALESCE(verified_at, NOW()), updated_at = NOW()
+                 WHERE meter_serial = $1
+                 RETURNING id"
+            )
+            .bind(&request.serial_number)
+            .fetch_optional(&state.db)
+            .await;
+            match registry_id {
+                Ok(Some(id)) => state.meter_registry_sync.spawn_sync(id),
+                Ok(None) => warn!("⚠️ Meter {} has no registry entry to sync", request.serial_number),
+                Err(e) => error!("Failed to sync meter_registry: {}", e),
+            }
+
             Json(RegisterMeterResponse {
                 success: true,
                 message: format!("Meter {} is now verified and ready to submit readings.", request.serial_number),
@@ -511,26 +568,31 @@ pub async fn get_registered_meters_filtered(
     
     let query = match params.status.as_deref() {
         Some("verified") | Some("active") => {
-            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id
-             FROM meters m JOIN users u ON m.user_id = u.id WHERE m.is_verified = true"
+            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id, r.registry_pda
+             FROM meters m JOIN users u ON m.user_id = u.id
+             LEFT JOIN meter_registry r ON r.meter_serial = m.serial_number
+             WHERE m.is_verified = true"
         }
         Some("pending") => {
-            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id
-             FROM meters m JOIN users u ON m.user_id = u.id WHERE m.is_verified = false"
+            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id, r.registry_pda
+             FROM meters m JOIN users u ON m.user_id = u.id
+             LEFT JOIN meter_registry r ON r.meter_serial = m.serial_number
+             WHERE m.is_verified = false"
         }
         _ => {
-            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id
-             FROM meters m JOIN users u ON m.user_id = u.id"
+            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id, r.registry_pda
+             FROM meters m JOIN users u ON m.user_id = u.id
+             LEFT JOIN meter_registry r ON r.meter_serial = m.serial_number"
         }
     };
 
-    let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>)>(query)
+    let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>, Option<String>)>(query)
         .fetch_all(&state.db)
         .await;
 
     match meters_result {
         Ok(meters) => {
-            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone)| {
+            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone, registry_pda)| {
                 MeterResponse {
                     id: *id,
                     serial_number: serial.clone(),
@@ -541,6 +603,7 @@ pub async fn get_registered_meters_filtered(
                     latitude: *lat,
                     longitude: *lng,
                     zone_id: *zone,
+                    registry_pda: registry_pda.clone(),
                 }
             }).collect();
             Json(responses)
@@ -573,6 +636,14 @@ pub async fn update_meter_status(
 ) -> Json<RegisterMeterResponse> {
     info!("🔧 Update meter {} request: {:?}", serial, request);
 
+    if let Some(Err(e)) = request.installation.as_ref().map(Validate::validate) {
+        return Json(RegisterMeterResponse {
+            success: false,
+            message: format!("Invalid installation: {}", e),
+            meter: None,
+        });
+    }
+
     // Build dynamic query
     let mut query_builder = sqlx::QueryBuilder::<sqlx::Postgres>::new("UPDATE meters SET updated_at = NOW()");
     
@@ -614,6 +685,16 @@ pub async fn update_meter_status(
                     .await;
             }
 
+            if let Some(installation) = &request.installation {
+                if let Err(e) = state.meter_installations.update(&serial, installation).await {
+                    return Json(RegisterMeterResponse {
+                        success: false,
+                        message: format!("Failed to update meter installation: {}", e),
+                        meter: None,
+                    });
+                }
+            }
+
             Json(RegisterMeterResponse {
                 success: true,
                 message: format!("Meter {} updated successfully", serial),
@@ -655,16 +736,17 @@ pub async fn create_reading(
     _headers: HeaderMap,
     Json(request): Json<CreateReadingRequest>,
 ) -> Json<CreateReadingResponse> {
-    Json(internal_create_reading(&state, serial, params, request).await)
+    Json(internal_create_reading(&state, serial, params, request).await.unwrap_or_else(|rejected| rejected))
 }
 
-/// Internal shared logic for creating a reading
+/// Internal shared logic for creating a reading.
+/// `Err` carries the response for a reading that was rejected and not recorded.
 async fn internal_create_reading(
     state: &AppState,
     serial: String,
     params: CreateReadingParams,
     request: CreateReadingRequest,
-) -> CreateReadingResponse {
+) -> Result<CreateReadingResponse, CreateReadingResponse> {
     let auto_mint = params.auto_mint.unwrap_or(true);
     let timeout_secs = params.timeout_secs.unwrap_or(30);
     
@@ -676,7 +758,7 @@ async fn internal_create_reading(
     // 1. Resolve Meter Context (ID, User, Wallet, Zone)
     let (meter_id, user_id, wallet_address, zone_id) = match resolve_meter_context(state, &serial, &request.wallet_address).await {
         Ok(ctx) => ctx,
-        Err(err_msg) => return CreateReadingResponse {
+        Err(err_msg) => return Err(CreateReadingResponse {
             id: Uuid::new_v4(),
             serial_number: serial,
             kwh: request.kwh,
@@ -684,18 +766,95 @@ async fn internal_create_reading(
             minted: false,
             tx_signature: None,
             message: err_msg,
-        },
+        }),
     };
 
-    // 2. Process Blockchain Minting
-    let (minted, tx_signature, mut message) = if auto_mint && request.kwh > 0.0 {
-        process_minting(state, timeout_secs, &wallet_address, request.kwh, &serial).await
-    } else {
-        (false, None, "Reading recorded (auto_mint disabled)".to_string())
+    // 1.5 Validate device clock against receipt time
+    let received_at = chrono::Utc::now();
+    let clock = match state
+        .tokenization
+        .current()
+        .assess_reading_timestamp(request.timestamp.unwrap_or(received_at), received_at)
+    {
+        Ok(clock) => clock,
+        Err(e) => {
+            warn!("⏱️ Rejecting reading for meter {}: {}", serial, e);
+            return Err(CreateReadingResponse {
+                id: Uuid::new_v4(),
+                serial_number: serial,
+                kwh: request.kwh,
+                timestamp: request.timestamp.unwrap_or(received_at),
+                minted: false,
+                tx_signature: None,
+                message: e.to_string(),
+            });
+        }
+    };
+    if request.timestamp.is_some() {
+        if !clock.within_tolerance {
+            warn!(
+                "⏱️ Clock drift of {}s for meter {}{}",
+                clock.drift_secs,
+                serial,
+                if clock.corrected { ", using server receipt time" } else { "" }
+            );
+        }
+        crate::handlers::meter::stub::record_clock_drift(&state.db, &serial, clock.drift_secs).await;
+    }
+
+    // 1.6 Check the meter's firmware against its manufacturer's minimum version
+    let mut firmware_warning = None;
+    match state
+        .meter_firmware
+        .check_reading(&serial, request.firmware_version.as_deref())
+        .await
+    {
+        Ok(verdict) if verdict.rejects() => {
+            warn!("📟 Rejecting reading from meter {}: {}", serial, verdict.message());
+            return Err(CreateReadingResponse {
+                id: Uuid::new_v4(),
+                serial_number: serial,
+                kwh: request.kwh,
+                timestamp: clock.effective_timestamp,
+                minted: false,
+                tx_signature: None,
+                message: verdict.message(),
+            });
+        }
+        Ok(verdict) if verdict.warns() => {
+            warn!("📟 Meter {}: {}", serial, verdict.message());
+            firmware_warning = Some(verdict.message());
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to check firmware for meter {}: {}", serial, e),
+    }
+
+    // 1.7 Meters diverging from official grid data keep submitting but do not mint
+    let mint_freeze = match state.grid_meter_data.meter_mint_freeze(&serial).await {
+        Ok(freeze) => freeze,
+        Err(e) => {
+            warn!("Failed to check mint freeze for meter {}: {}", serial, e);
+            None
+        }
     };
 
-    // 2.5 Check for alerts and calculate health score
-    let alerts = check_alerts(&serial, &request);
+    let power_val = request.power.or_else(|| {
+         request.voltage.zip(request.current).map(|(v, i)| v * i * request.power_factor.unwrap_or(1.0) / 1000.0) // kW
+    });
+
+    // 1.8 Check for alerts and calculate health score
+    let mut alerts = check_alerts(&serial, &request);
+    if let Some(power) = power_val {
+        match state.meter_installations.effective_capacity(&serial).await {
+            Ok(Some(capacity)) => {
+                if let Some(alert) = capacity.to_f64().and_then(|c| check_capacity(&serial, power, c)) {
+                    alerts.push(alert);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to load capacity of meter {}: {}", serial, e),
+        }
+    }
     if !alerts.is_empty() {
         for alert in &alerts {
             warn!("⚠️ Meter Alert: {} - {}", alert.alert_type, alert.message);
@@ -709,58 +868,156 @@ async fn internal_create_reading(
     
     let health_score = calculate_health_score(&request);
 
-    // 3. Persist Reading to Database
-    let reading_id = Uuid::new_v4();
-    let timestamp = request.timestamp.unwrap_or_else(chrono::Utc::now);
-
-    if let Err(e) = persist_reading_to_db(
-        state, 
-        reading_id, 
-        &serial, 
-        meter_id, 
-        user_id, 
-        &wallet_address, 
-        timestamp, 
-        &request, 
-        minted, 
-        &tx_signature,
-        health_score,
-    ).await {
-        error!("❌ CRITICAL: Failed to save reading {} to DB: {}", reading_id, e);
-        message = format!("{}. Database error: {}", message, e);
+    // 2. Verify the signed envelope and claim its sequence before minting,
+    // so a replayed reading is rejected rather than minted twice
+    let envelope = ReadingEnvelope {
+        meter_serial: &serial,
+        signature: request.meter_signature.as_deref(),
+        message_version: request.message_version,
+        sequence: request.sequence,
+        timestamp: request.timestamp.unwrap_or(received_at),
+        kwh_amount: format!("{:.6}", request.kwh),
+        wallet: &wallet_address,
+    };
+    let claim = async {
+        let sequence = verify_reading_envelope(state, &envelope).await?;
+        let mut tx = state.db.begin().await?;
+        if let Some(sequence) = sequence {
+            claim_reading_sequence(&mut tx, &serial, sequence).await?;
+        }
+        Ok::<_, ApiError>(tx)
+    };
+    let mut tx = match claim.await {
+        Ok(claim) => claim,
+        Err(e) => {
+            warn!("🔏 Rejecting reading for meter {}: {}", serial, e);
+            return Err(CreateReadingResponse {
+                id: Uuid::new_v4(),
+                serial_number: serial,
+                kwh: request.kwh,
+                timestamp: clock.effective_timestamp,
+                minted: false,
+                tx_signature: None,
+                message: e.to_string(),
+            });
+        }
+    };
+
+    // 2.5 Readings over the wallet's minting cap are refused or quarantined.
+    // The guard locks the wallet until the pending reading is committed.
+    let mint_breach = if mint_freeze.is_none() && auto_mint && request.kwh > 0.0 {
+        let kwh = EnergyKwh::from_f64(request.kwh).unwrap_or_default().value();
+        state
+            .mint_guard
+            .check(&mut tx, &wallet_address, kwh, clock.effective_timestamp)
+            .await
     } else {
-        info!("✅ Successfully saved reading {} to DB", reading_id);
-        
-        // 4. Trigger Post-Processing (Async)
-        // We pass the raw values needed for logic
-        let surplus = request.surplus_energy.unwrap_or(if request.kwh > 0.0 { request.kwh } else { 0.0 });
-        let deficit = request.deficit_energy.unwrap_or(if request.kwh < 0.0 { request.kwh.abs() } else { 0.0 });
-        
-        let power_val = request.power.or_else(|| {
-             request.voltage.zip(request.current).map(|(v, i)| v * i * request.power_factor.unwrap_or(1.0) / 1000.0) // kW
-        });
+        None
+    };
+    if let Some(breach) = &mint_breach {
+        if state.mint_guard.mode() == MintGuardMode::Reject {
+            return Err(CreateReadingResponse {
+                id: Uuid::new_v4(),
+                serial_number: serial,
+                kwh: request.kwh,
+                timestamp: clock.effective_timestamp,
+                minted: false,
+                tx_signature: None,
+                message: format!("Reading rejected by minting guard: {}", breach.message()),
+            });
+        }
+    }
 
-        // Update aggregate grid status in dashboard service
-        let _ = state.dashboard_service.handle_meter_reading(request.kwh, &serial, zone_id).await;
+    // 3. Persist Reading to Database, committing the sequence claim and any
+    // quarantine with it before anything reaches the chain: a replay is
+    // rejected even if the mint result cannot be recorded afterwards
+    let reading_id = Uuid::new_v4();
+    let timestamp = clock.effective_timestamp;
+    let mint = mint_freeze.is_none() && mint_breach.is_none() && auto_mint && request.kwh > 0.0;
+    let mint_status = mint.then_some(MINT_PENDING);
 
-        trigger_post_processing(
-            state.clone(),
-            serial.clone(),
+    let persisted = async {
+        persist_reading_to_db(
+            &mut *tx,
+            reading_id,
+            &serial,
             meter_id,
             user_id,
-            surplus,
-            deficit,
-            request.max_sell_price,
-            request.max_buy_price,
-            request.kwh,
-            wallet_address,
-            power_val,
-            request.voltage,
-            request.current
-        ).await;
+            &wallet_address,
+            &clock,
+            &request,
+            mint_status,
+            health_score,
+        )
+        .await?;
+        if let Some(breach) = &mint_breach {
+            state
+                .mint_guard
+                .quarantine(&mut tx, reading_id, &serial, &wallet_address, timestamp, breach)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok::<_, ApiError>(())
+    };
+
+    if let Err(e) = persisted.await {
+        error!("❌ CRITICAL: Failed to save reading {} to DB: {}", reading_id, e);
+        return Err(CreateReadingResponse {
+            id: reading_id,
+            serial_number: serial,
+            kwh: request.kwh,
+            timestamp,
+            minted: false,
+            tx_signature: None,
+            message: format!("Reading not recorded. Database error: {}", e),
+        });
+    }
+    info!("✅ Successfully saved reading {} to DB", reading_id);
+    state.meter_fleet_health.record_reading(&serial, alerts.len()).await;
+
+    // 4. Process Blockchain Minting
+    let (minted, tx_signature, mut message) = if let Some(reason) = mint_freeze {
+        (false, None, format!("Reading recorded (minting frozen: {})", reason))
+    } else if let Some(breach) = &mint_breach {
+        (false, None, format!("Reading recorded (minting quarantined for review: {})", breach.message()))
+    } else if mint {
+        let result = process_minting(state, timeout_secs, &wallet_address, request.kwh, &serial).await;
+        record_mint_result(&state.db, reading_id, result.0, result.1.as_deref()).await;
+        result
+    } else {
+        (false, None, "Reading recorded (auto_mint disabled)".to_string())
+    };
+
+    if let Some(warning) = firmware_warning {
+        message = format!("{}. {}", message, warning);
     }
 
-    CreateReadingResponse {
+    // 5. Trigger Post-Processing (Async)
+    // We pass the raw values needed for logic
+    let surplus = request.surplus_energy.unwrap_or(if request.kwh > 0.0 { request.kwh } else { 0.0 });
+    let deficit = request.deficit_energy.unwrap_or(if request.kwh < 0.0 { request.kwh.abs() } else { 0.0 });
+
+    // Update aggregate grid status in dashboard service
+    let kwh = EnergyKwh::from_f64(request.kwh).unwrap_or_default();
+    let _ = state.dashboard_service.handle_meter_reading(kwh, &serial, zone_id).await;
+
+    trigger_post_processing(
+        state.clone(),
+        serial.clone(),
+        meter_id,
+        user_id,
+        surplus,
+        deficit,
+        request.max_sell_price,
+        request.max_buy_price,
+        request.kwh,
+        wallet_address,
+        power_val,
+        request.voltage,
+        request.current
+    ).await;
+
+    Ok(CreateReadingResponse {
         id: reading_id,
         serial_number: serial,
         kwh: request.kwh,
@@ -768,7 +1025,7 @@ async fn internal_create_reading(
         minted,
         tx_signature,
         message,
-    }
+    })
 }
 
 // --- Helper Functions ---
@@ -860,17 +1117,16 @@ async fn process_minting(
     }
 }
 
-async fn persist_reading_to_db(
-    state: &AppState,
+async fn persist_reading_to_db<'e, E: sqlx::PgExecutor<'e>>(
+    executor: E,
     reading_id: Uuid,
     serial: &str,
     meter_id: Uuid,
     user_id: Uuid,
     wallet_address: &str,
-    timestamp: chrono::DateTime<chrono::Utc>,
+    clock: &TimestampAssessment,
     request: &CreateReadingRequest,
-    minted: bool,
-    tx_signature: &Option<String>,
+    mint_status: Option<&str>,
     health_score: f64,
 ) -> Result<(), sqlx::Error> {
     // Calculate derived energy values if not provided
@@ -890,19 +1146,21 @@ async fn persist_reading_to_db(
             thd_voltage, thd_current,
             latitude, longitude, battery_level, weather_condition, health_score,
             rec_eligible, carbon_offset, max_sell_price, max_buy_price,
-            meter_signature, meter_type,
-            minted, mint_tx_signature, created_at
+            meter_signature, meter_type, message_version, sequence,
+            mint_status,
+            device_timestamp, clock_drift_secs, timestamp_corrected, created_at
          ) VALUES ($1, $2, $3, $4, $5, $6, $6, $7, $8, $9, $10, $11, 
                    $12, $13, $14, $15, $16, $17, $18, 
                    $19, $20, $21, $22, $23,
-                   $24, $25, $26, $27, $28, $29, $30, $31, NOW())"
+                   $24, $25, $26, $27, $28, $29, $30, $31,
+                   $32, $33, $34, $35, NOW())"
     )
     .bind(reading_id)
     .bind(serial)
     .bind(meter_id)
     .bind(user_id)
     .bind(wallet_address)
-    .bind(timestamp)
+    .bind(clock.effective_timestamp)
     .bind(request.kwh)
     .bind(energy_gen)
     .bind(energy_cons)
@@ -933,10 +1191,15 @@ async fn persist_reading_to_db(
     // Security
     .bind(&request.meter_signature)
     .bind(&request.meter_type)
-    // Minting status
-    .bind(minted)
-    .bind(tx_signature.clone())
-    .execute(&state.db)
+    .bind(request.message_version.map(|v| v as i16))
+    .bind(request.sequence.and_then(|seq| i64::try_from(seq).ok()))
+    // Minting status; the result is recorded once the mint returns
+    .bind(mint_status)
+    // Clock drift
+    .bind(clock.device_timestamp)
+    .bind(clock.drift_secs as i32)
+    .bind(clock.corrected)
+    .execute(executor)
     .await
     .map(|_| ())
 }
@@ -967,7 +1230,7 @@ async fn trigger_post_processing(
             &user_id,
             &ws_wallet,
             &ws_meter_serial,
-            kwh,
+            EnergyKwh::from_f64(kwh).unwrap_or_default(),
             power,
             voltage,
             current
@@ -998,6 +1261,7 @@ async fn trigger_post_processing(
                         None,
                         Some(meter_id),
                         None,
+                        None,
                     ).await;
                     if let Err(e) = res {
                         error!("❌ [Auto-P2P] Failed to create Sell order for {}: {}", serial, e);
@@ -1022,6 +1286,7 @@ async fn trigger_post_processing(
                         None,
                         Some(meter_id),
                         None,
+                        None,
                     ).await;
                     if let Err(e) = res {
                         error!("❌ [Auto-P2P] Failed to create Buy order for {}: {}", serial, e);
@@ -1149,26 +1414,58 @@ pub async fn get_meter_stats(
 }
 
 /// Create multiple readings in a single batch
+///
+/// When submitted by an AMI gateway authenticated with a client certificate,
+/// only readings for meters bound to that gateway are accepted.
 #[utoipa::path(
     post,
     path = "/api/v1/meters/batch/readings",
     request_body = CreateBatchReadingRequest,
     responses(
-        (status = 200, description = "Batch processed", body = BatchReadingResponse)
+        (status = 200, description = "Batch processed", body = BatchReadingResponse),
+        (status = 401, description = "Gateway client certificate rejected")
     ),
     tag = "meters"
 )]
 pub async fn create_batch_readings(
     State(state): State<AppState>,
+    gateway: Option<Extension<GatewayIdentity>>,
     Json(request): Json<CreateBatchReadingRequest>,
 ) -> Json<BatchReadingResponse> {
     let mut success_count = 0;
     let mut failed_count = 0;
     
     info!("📊 Processing batch of {} readings", request.readings.len());
+
+    // Meters the submitting gateway is bound to (None = not a gateway submission)
+    let gateway_meters = match &gateway {
+        Some(Extension(GatewayIdentity(gw))) => {
+            let serials: Vec<String> = request
+                .readings
+                .iter()
+                .filter_map(|r| r.meter_serial.clone().or_else(|| r.meter_id.clone()))
+                .collect();
+            match state.meter_gateway_service.authorized_serials(gw.id, &serials).await {
+                Ok(allowed) => Some((gw, allowed)),
+                Err(e) => {
+                    error!("❌ Failed to load meters for gateway {}: {}", gw.id, e);
+                    Some((gw, Default::default()))
+                }
+            }
+        }
+        None => None,
+    };
     
     for reading in request.readings {
         let serial = reading.meter_serial.clone().or_else(|| reading.meter_id.clone());
+
+        if let (Some(serial), Some((gw, allowed))) = (&serial, &gateway_meters) {
+            if !allowed.contains(serial) {
+                warn!("🔐 Gateway {} is not bound to meter {}, skipping reading", gw.name, serial);
+                failed_count += 1;
+                continue;
+            }
+        }
         
         if let Some(serial) = serial {
             // Disable auto_mint for batch submissions to improve performance
@@ -1176,8 +1473,10 @@ pub async fn create_batch_readings(
                 auto_mint: Some(false),
                 timeout_secs: Some(30),
             };
-            let _ = internal_create_reading(&state, serial, params, reading).await;
-            success_count += 1;
+            match internal_create_reading(&state, serial, params, reading).await {
+                Ok(_) => success_count += 1,
+                Err(_) => failed_count += 1,
+            }
         } else {
             failed_count += 1;
         }
@@ -8,6 +8,7 @@
 //! - `login` - Login and email verification handlers
 //! - `registration` - User registration handlers
 //! - `profile` - User profile handlers
+//! - `security` - Account security overview
 //! - `meters` - Meter management handlers
 //! - `wallets` - Wallet/token balance handlers
 //! - `status` - Status endpoint handlers
@@ -22,6 +23,7 @@ pub mod login;
 pub mod registration;
 pub mod password_reset;
 pub mod profile;
+pub mod security;
 pub mod meters;
 pub mod wallets;
 pub mod status;
@@ -34,17 +36,18 @@ pub use routes::{
 };
 
 // Re-export handler functions
-pub use login::{login, verify_email};
+pub use login::{login, verify_email, verify_login_step_up};
 pub use registration::{register, resend_verification};
 pub use password_reset::{forgot_password, reset_password, change_password};
 pub use profile::profile;
+pub use security::security_overview;
 pub use meters::{
     get_my_meters, register_meter, get_registered_meters, 
     get_registered_meters_filtered, update_meter_status, verify_meter, create_reading,
     get_meter_stats,
 };
-pub use wallets::token_balance;
-pub use status::{system_status, meter_status, readiness_probe, liveness_probe};
+pub use wallets::{token_balance, batch_balances};
+pub use status::{system_status, meter_status, readiness_probe, readyz, liveness_probe, api_changelog};
 
 // Re-export types
 pub use types::{
@@ -59,4 +62,5 @@ pub use types::{
 pub use status::{
     HealthResponse, ServiceStatus, ServiceHealth,
     MeterStatusResponse, MeterCounts, ReadinessResponse, LivenessResponse,
+    EpochSummary, ChangelogQuery,
 };
@@ -12,6 +12,7 @@ use uuid::Uuid;
 
 use crate::AppState;
 use crate::auth::password::PasswordService;
+use crate::i18n;
 use super::types::{
     ForgotPasswordRequest, ResetPasswordRequest, VerifyEmailResponse,
     ChangePasswordRequest,
@@ -32,24 +33,25 @@ pub async fn forgot_password(
     State(state): State<AppState>,
     Json(request): Json<ForgotPasswordRequest>,
 ) -> Json<VerifyEmailResponse> {
-    info!("🔑 Password reset request for: {}", request.email);
+    info!("🔑 Password reset request");
 
-    // Look up user by email
+    // Look up user by email digest (or plaintext email not yet sealed)
     let user_result = sqlx::query_as::<_, (Uuid, String)>(
-        "SELECT id, username FROM users WHERE email = $1 AND is_active = true"
+        "SELECT id, username FROM users WHERE (email_digest = $1 OR lower(email) = lower($2)) AND is_active = true"
     )
+    .bind(state.pii.email_digest(&request.email))
     .bind(&request.email)
     .fetch_optional(&state.db)
     .await;
 
     let (user_id, username) = match user_result {
         Ok(Some(user)) => {
-            info!("🔑 Password reset initiated for user: {} (email: {})", user.1, request.email);
+            info!("🔑 Password reset initiated for user: {}", user.1);
             user
         }
         Ok(None) => {
             // Don't reveal if email exists (security best practice)
-            info!("Password reset requested for non-existent email: {}", request.email);
+            info!("Password reset requested for non-existent email");
             return Json(VerifyEmailResponse::simple(
                 true,
                 "If an account with that email exists, a password reset link has been sent."
@@ -96,9 +98,10 @@ pub async fn forgot_password(
             &request.email,
             &reset_token,
             &username,
+            i18n::current(),
         ).await {
             Ok(()) => {
-                info!("📧 Password reset email sent to {}", request.email);
+                info!("📧 Password reset email sent to {}", username);
             }
             Err(e) => {
                 tracing::error!("❌ Failed to send password reset email: {}", e);
@@ -11,7 +11,7 @@ use tracing::info;
 use uuid::Uuid;
 
 use crate::AppState;
-use super::types::{UserResponse, UserRow, UpdateWalletRequest};
+use super::types::{user_row_columns, UserResponse, UserRow, UpdateWalletRequest};
 use base64::{engine::general_purpose, Engine as _};
 use solana_sdk::signature::{Keypair, Signer};
 use crate::services::WalletService;
@@ -44,28 +44,20 @@ pub async fn profile(
 
     // Try to decode token and get user from database
     if let Ok(claims) = state.jwt_service.decode_token(token) {
-        let user_result = sqlx::query_as::<_, UserRow>(
-            "SELECT id, username, email, role::text as role, first_name, last_name, wallet_address, balance, locked_amount, locked_energy
-             FROM users WHERE id = $1"
-        )
+        let user_result = sqlx::query_as::<_, UserRow>(&format!(
+            "SELECT {} FROM users WHERE id = $1",
+            user_row_columns()
+        ))
         .bind(claims.sub)
         .fetch_optional(&state.db)
         .await;
 
         if let Ok(Some(user)) = user_result {
-            info!("✅ Returning profile for: {} (email: {}) (from database)", user.username, user.email);
-            return Json(UserResponse {
-                id: user.id,
-                username: user.username,
-                email: user.email,
-                role: user.role,
-                first_name: user.first_name.unwrap_or_default(),
-                last_name: user.last_name.unwrap_or_default(),
-                wallet_address: user.wallet_address,
-                balance: user.balance.unwrap_or_default(),
-                locked_amount: user.locked_amount.unwrap_or_default(),
-                locked_energy: user.locked_energy.unwrap_or_default(),
-            });
+            info!("✅ Returning profile for: {} (from database)", user.username);
+            match user.into_response(&state.pii).await {
+                Ok(user) => return Json(user),
+                Err(e) => tracing::error!("❌ Failed to decrypt user profile: {}", e),
+            }
         }
     }
 
@@ -86,6 +78,9 @@ pub async fn profile(
 }
 
 /// Update Wallet Handler
+///
+/// The wallet is linked only with proof of control answering the open
+/// wallet link challenge; the replaced wallet stays in the link history.
 #[utoipa::path(
     post,
     path = "/api/v1/users/wallet",
@@ -93,6 +88,9 @@ pub async fn profile(
     responses(
         (status = 200, description = "Wallet updated", body = UserResponse),
         (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Wallet blocked after repeated failed proofs"),
+        (status = 409, description = "No open challenge, challenge expired or wallet linked elsewhere"),
+        (status = 422, description = "Invalid wallet address or proof"),
         (status = 500, description = "Internal Server Error")
     ),
     security(
@@ -116,38 +114,23 @@ pub async fn update_wallet(
 
     info!("💼 Update wallet request for user: {}", claims.sub);
 
-    // Update wallet in database
-    let user = sqlx::query_as::<_, UserRow>(
-        r#"
-        UPDATE users 
-        SET wallet_address = $1, blockchain_registered = true, updated_at = NOW() 
-        WHERE id = $2
-        RETURNING id, username, email, role::text as role, first_name, last_name, wallet_address, balance, locked_amount, locked_energy
-        "#
-    )
-    .bind(&payload.wallet_address)
+    let proof = payload.proof()?;
+    state
+        .wallet_links
+        .link(claims.sub, &payload.wallet_address, proof)
+        .await?;
+
+    let user = sqlx::query_as::<_, UserRow>(&format!(
+        "SELECT {} FROM users WHERE id = $1",
+        user_row_columns()
+    ))
     .bind(claims.sub)
     .fetch_one(&state.db)
-    .await
-    .map_err(|e| {
-        tracing::error!("Failed to update wallet: {}", e);
-        crate::ApiError::Internal("Database error".to_string())
-    })?;
+    .await?;
 
     info!("✅ Wallet updated for user {}: {}", user.username, payload.wallet_address);
 
-    Ok(Json(UserResponse {
-        id: user.id,
-        username: user.username,
-        email: user.email,
-        role: user.role,
-        first_name: user.first_name.unwrap_or_default(),
-        last_name: user.last_name.unwrap_or_default(),
-        wallet_address: user.wallet_address,
-                balance: user.balance.unwrap_or_default(),
-                locked_amount: user.locked_amount.unwrap_or_default(),
-                locked_energy: user.locked_energy.unwrap_or_default(),
-    }))
+    Ok(Json(user.into_response(&state.pii).await?))
 }
 
 /// Generate Wallet Handler
@@ -196,15 +179,19 @@ pub async fn generate_wallet(
     let salt_bytes = general_purpose::STANDARD.decode(&salt_b64).unwrap_or_default();
     let iv_bytes = general_purpose::STANDARD.decode(&iv_b64).unwrap_or_default();
 
+    // Record the switch in the link history before storing the key
+    state.wallet_links.record_custodial(claims.sub, &pubkey).await?;
+
     // Update DB
-    let user = sqlx::query_as::<_, UserRow>(
+    let user = sqlx::query_as::<_, UserRow>(&format!(
         r#"
         UPDATE users 
         SET wallet_address = $1, encrypted_private_key = $2, wallet_salt = $3, encryption_iv = $4, blockchain_registered = true, updated_at = NOW() 
         WHERE id = $5
-        RETURNING id, username, email, role::text as role, first_name, last_name, wallet_address, balance, locked_amount, locked_energy
-        "#
-    )
+        RETURNING {}
+        "#,
+        user_row_columns()
+    ))
     .bind(&pubkey)
     .bind(&enc_key_bytes)
     .bind(&salt_bytes)
@@ -229,16 +216,5 @@ pub async fn generate_wallet(
         }
     }
 
-    Ok(Json(UserResponse {
-        id: user.id,
-        username: user.username,
-        email: user.email,
-        role: user.role,
-        first_name: user.first_name.unwrap_or_default(),
-        last_name: user.last_name.unwrap_or_default(),
-        wallet_address: user.wallet_address,
-                balance: user.balance.unwrap_or_default(),
-                locked_amount: user.locked_amount.unwrap_or_default(),
-                locked_energy: user.locked_energy.unwrap_or_default(),
-    }))
+    Ok(Json(user.into_response(&state.pii).await?))
 }
@@ -3,20 +3,27 @@
 //! User registration and verification email handlers.
 
 use axum::{
-    extract::State,
+    extract::{ConnectInfo, State},
+    http::HeaderMap,
     Json,
 };
 use chrono::{Duration, Utc};
+use std::net::SocketAddr;
 use tracing::info;
 use uuid::Uuid;
 use crate::AppState;
 use crate::error::ApiError;
+use crate::i18n;
 use crate::auth::password::PasswordService;
+use crate::models::pii::UserPii;
 use super::types::{
     RegistrationRequest, RegistrationResponse, AuthResponse, UserResponse,
     ResendVerificationRequest, VerifyEmailResponse,
 };
 
+/// Header carrying the client's device identifier, used for referral fraud checks
+const DEVICE_ID_HEADER: &str = "x-device-id";
+
 /// Register Handler - inserts user into database and sends verification email
 #[utoipa::path(
     post,
@@ -31,11 +38,41 @@ use super::types::{
 )]
 pub async fn register(
     State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(request): Json<RegistrationRequest>,
 ) -> Result<Json<RegistrationResponse>, ApiError> {
-    info!("📝 Registration for user: {} (email: {})", request.username, request.email);
+    info!("📝 Registration for user: {}", request.username);
 
     let id = Uuid::new_v4();
+
+    // Resolve the referral code before creating the account
+    let referral_code = request
+        .referral_code
+        .as_deref()
+        .map(str::trim)
+        .filter(|code| !code.is_empty());
+    let referrer_id = match referral_code {
+        Some(code) => match state.referral_service.resolve_code(code).await? {
+            Some(referrer_id) => Some(referrer_id),
+            None => {
+                return Ok(Json(RegistrationResponse {
+                    message: "Registration failed: unknown referral code".to_string(),
+                    email_verification_sent: false,
+                    auth: None,
+                }));
+            }
+        },
+        None => None,
+    };
+    let signup_ip = state
+        .network_acl
+        .client_ip(Some(peer.ip()), &headers)
+        .map(|ip| ip.to_string());
+    let signup_device = headers
+        .get(DEVICE_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.chars().take(128).collect::<String>());
     
     // Hash password with bcrypt
     let password_hash = match PasswordService::hash_password(&request.password) {
@@ -56,25 +93,40 @@ pub async fn register(
         state.config.email.verification_expiry_hours
     );
 
+    // Email and names are stored sealed, with a digest for email lookups
+    let pii = UserPii {
+        email: request.email.clone(),
+        first_name: Some(request.first_name.clone()),
+        last_name: Some(request.last_name.clone()),
+    }
+    .seal(&state.pii)
+    .await?;
+
     // Insert user into database with verification token
     // Note: Wallet columns are NULL until email verification
     let insert_result = sqlx::query(
         "INSERT INTO users (
-            id, username, email, password_hash, role, first_name, last_name, 
+            id, username, email_encrypted, email_digest, password_hash, role,
+            first_name_encrypted, last_name_encrypted, pii_key_id,
             is_active, email_verified, blockchain_registered, 
             email_verification_token, email_verification_sent_at, email_verification_expires_at,
-            created_at, updated_at
+            signup_ip, signup_device, preferred_locale, created_at, updated_at
         )
-         VALUES ($1, $2, $3, $4, 'user', $5, $6, true, false, false, $7, NOW(), $8, NOW(), NOW())"
+         VALUES ($1, $2, $3, $4, $5, 'user', $6, $7, $8, true, false, false, $9, NOW(), $10, $11, $12, $13, NOW(), NOW())"
     )
     .bind(id)
     .bind(&request.username)
-    .bind(&request.email)
+    .bind(&pii.email_encrypted)
+    .bind(&pii.email_digest)
     .bind(&password_hash)
-    .bind(&request.first_name)
-    .bind(&request.last_name)
+    .bind(&pii.first_name_encrypted)
+    .bind(&pii.last_name_encrypted)
+    .bind(pii.key_id)
     .bind(&verification_token)
     .bind(verification_expires_at)
+    .bind(&signup_ip)
+    .bind(&signup_device)
+    .bind(i18n::current().as_str())
     .execute(&state.db)
     .await;
 
@@ -88,7 +140,13 @@ pub async fn register(
         }));
     }
 
-    info!("✅ User created in database: {} (email: {}) (Pending Verification)", request.username, request.email);
+    info!("✅ User created in database: {} (Pending Verification)", request.username);
+
+    if let (Some(referrer_id), Some(code)) = (referrer_id, referral_code) {
+        if let Err(e) = state.referral_service.attach(referrer_id, id, code).await {
+            tracing::error!("❌ Failed to record referral for {}: {}", request.username, e);
+        }
+    }
 
     // Send verification email
     let email_sent = if let Some(ref email_service) = state.email_service {
@@ -96,9 +154,10 @@ pub async fn register(
             &request.email,
             &verification_token,
             &request.username,
+            i18n::current(),
         ).await {
             Ok(()) => {
-                info!("📧 Verification email sent to {}", request.email);
+                info!("📧 Verification email sent to {}", request.username);
                 true
             }
             Err(e) => {
@@ -164,12 +223,13 @@ pub async fn resend_verification(
     State(state): State<AppState>,
     Json(request): Json<ResendVerificationRequest>,
 ) -> Result<Json<VerifyEmailResponse>, ApiError> {
-    info!("📧 Resend verification request for: {}", request.email);
+    info!("📧 Resend verification request");
     
-    // Look up user by email
+    // Look up user by email digest (or plaintext email not yet sealed)
     let user_result = sqlx::query_as::<_, (Uuid, String, bool)>(
-        "SELECT id, username, email_verified FROM users WHERE email = $1"
+        "SELECT id, username, email_verified FROM users WHERE email_digest = $1 OR lower(email) = lower($2)"
     )
+    .bind(state.pii.email_digest(&request.email))
     .bind(&request.email)
     .fetch_optional(&state.db)
     .await;
@@ -241,9 +301,10 @@ pub async fn resend_verification(
             &request.email,
             &verification_token,
             &username,
+            i18n::current(),
         ).await {
             Ok(()) => {
-                info!("📧 Verification email resent to {}", request.email);
+                info!("📧 Verification email resent to {}", username);
                 true
             }
             Err(e) => {
@@ -9,7 +9,7 @@ use axum::{
 
 use crate::AppState;
 use super::{
-    login::{login, verify_email},
+    login::{login, verify_email, verify_login_step_up},
     registration::register,
     password_reset::{forgot_password, reset_password, change_password},
     profile::{profile, update_wallet, generate_wallet},
@@ -19,7 +19,7 @@ use super::{
         get_my_readings, get_meter_stats, create_batch_readings,
     },
     wallets::token_balance,
-    status::{system_status, meter_status, readiness_probe, liveness_probe},
+    status::{system_status, meter_status, readiness_probe, liveness_probe, api_changelog},
 };
 
 // ============================================================================
@@ -30,6 +30,7 @@ use super::{
 pub fn v1_auth_routes() -> Router<AppState> {
     Router::new()
         .route("/token", post(login))  // POST /api/v1/auth/token
+        .route("/token/step-up", post(verify_login_step_up))  // POST /api/v1/auth/token/step-up
         .route("/verify", get(verify_email))  // GET /api/v1/auth/verify
         .route("/forgot-password", post(forgot_password))  // POST /api/v1/auth/forgot-password
         .route("/reset-password", post(reset_password))  // POST /api/v1/auth/reset-password
@@ -55,6 +56,7 @@ pub fn v1_meters_routes() -> Router<AppState> {
         .route("/", get(get_registered_meters_filtered))  // GET /api/v1/meters?status=verified
         .route("/stats", get(get_meter_stats)) // GET /api/v1/meters/stats
         .route("/{serial}", axum::routing::patch(update_meter_status))  // PATCH /api/v1/meters/{serial}
+        .route("/{serial}/installation", get(crate::handlers::meter::installation::get_meter_installation).put(crate::handlers::meter::installation::update_meter_installation))  // GET/PUT /api/v1/meters/{serial}/installation
         .route("/{serial}/health", get(crate::handlers::meter::stub::get_meter_health))  // GET /api/v1/meters/{serial}/health
         .route("/readings", get(get_my_readings))  // GET /api/v1/meters/readings
         .route("/batch/readings", post(create_batch_readings)) // POST /api/v1/meters/batch/readings
@@ -78,5 +80,6 @@ pub fn v1_status_routes() -> Router<AppState> {
         .route("/meters", get(meter_status))  // GET /api/v1/status/meters
         .route("/ready", get(readiness_probe))  // GET /api/v1/status/ready
         .route("/live", get(liveness_probe))  // GET /api/v1/status/live
+        .route("/changelog", get(api_changelog))  // GET /api/v1/status/changelog
 }
 
@@ -0,0 +1,159 @@
+//! Security Overview Handler
+//!
+//! Lets users self-audit their account: recent sign-ins, linked wallets,
+//! API keys issued to them and MFA status.
+
+use axum::{extract::State, Json};
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::services::audit_logger::{AuditEventRecord, AuditLogFilter};
+use crate::AppState;
+use super::types::{ActiveApiKey, ConnectedWallet, MfaStatus, RecentLogin, SecurityOverview};
+
+/// Sign-ins shown in the overview
+const RECENT_LOGIN_LIMIT: i64 = 20;
+
+/// Get your account security overview
+/// GET /api/v1/auth/security-overview
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/security-overview",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Recent logins, connected wallets, API keys and MFA status", body = SecurityOverview),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn security_overview(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<SecurityOverview>> {
+    let user_id = user.0.sub;
+
+    let logins = state
+        .audit_logger
+        .search(&AuditLogFilter {
+            user_id: Some(user_id),
+            event_type: Some("user_login".to_string()),
+            limit: RECENT_LOGIN_LIMIT,
+            ..Default::default()
+        })
+        .await?;
+    let recent_logins = logins.into_iter().map(recent_login).collect();
+
+    // Linked wallets, plus the account wallet when it was set directly on
+    // the user rather than linked through user_wallets
+    let connected_wallets = sqlx::query_as::<_, ConnectedWallet>(
+        r#"
+        SELECT wallet_address, label,
+               COALESCE(is_primary, false) AS is_primary,
+               COALESCE(verified, false) AS verified,
+               created_at AS linked_at
+        FROM user_wallets
+        WHERE user_id = $1
+        UNION ALL
+        SELECT u.wallet_address, NULL::varchar, true, false, NULL::timestamptz
+        FROM users u
+        WHERE u.id = $1
+          AND u.wallet_address IS NOT NULL
+          AND NOT EXISTS (
+              SELECT 1 FROM user_wallets w
+              WHERE w.user_id = u.id AND w.wallet_address = u.wallet_address
+          )
+        ORDER BY is_primary DESC, linked_at DESC NULLS LAST
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let api_keys = sqlx::query_as::<_, ActiveApiKey>(
+        r#"
+        SELECT id, name, permissions, created_at, last_used_at
+        FROM api_keys
+        WHERE owner_id = $1 AND is_active
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(SecurityOverview {
+        recent_logins,
+        connected_wallets,
+        api_keys,
+        // Multi-factor authentication is not offered yet
+        mfa: MfaStatus {
+            enabled: false,
+            available: false,
+        },
+    }))
+}
+
+/// Overview entry for a `user_login` audit record
+fn recent_login(record: AuditEventRecord) -> RecentLogin {
+    let data = &record.event_data;
+    let text = |value: &serde_json::Value| value.as_str().map(str::to_string);
+    RecentLogin {
+        ip_address: record.ip_address.map(|ip| ip.ip().to_string()),
+        country: text(&data["geo"]["country"]),
+        city: text(&data["geo"]["city"]),
+        device: text(&data["user_agent"]),
+        logged_in_at: record.created_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::audit_logger::types::{AuditOutcome, AuditSeverity};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn login_record(ip: Option<&str>, event_data: serde_json::Value) -> AuditEventRecord {
+        AuditEventRecord {
+            id: Uuid::new_v4(),
+            event_type: "user_login".to_string(),
+            user_id: Some(Uuid::new_v4()),
+            ip_address: ip.map(|ip| ip.parse().unwrap()),
+            event_data,
+            created_at: Some(Utc::now()),
+            severity: AuditSeverity::Info,
+            actor_id: None,
+            resource_type: None,
+            resource_id: None,
+            outcome: AuditOutcome::Success,
+        }
+    }
+
+    #[test]
+    fn test_recent_login_from_audit_record() {
+        let record = login_record(
+            Some("203.0.113.7/32"),
+            serde_json::json!({
+                "user_agent": "Mozilla/5.0",
+                "geo": { "country": "TH", "city": "Bangkok" }
+            }),
+        );
+        let logged_in_at = record.created_at;
+
+        let login = recent_login(record);
+        assert_eq!(login.ip_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(login.country.as_deref(), Some("TH"));
+        assert_eq!(login.city.as_deref(), Some("Bangkok"));
+        assert_eq!(login.device.as_deref(), Some("Mozilla/5.0"));
+        assert_eq!(login.logged_in_at, logged_in_at);
+    }
+
+    #[test]
+    fn test_recent_login_without_geo_or_device() {
+        let login = recent_login(login_record(None, serde_json::json!({ "user_agent": null })));
+        assert!(login.ip_address.is_none());
+        assert!(login.country.is_none());
+        assert!(login.city.is_none());
+        assert!(login.device.is_none());
+    }
+}
@@ -1,18 +1,39 @@
 //! Status Handlers Module
 //!
 //! System and service status endpoint handlers with comprehensive health checks.
+//! `/api/v1/status` doubles as the machine-readable platform status partner
+//! integrations poll for readiness: component health, the current epoch and
+//! market session, planned maintenance and recent API changes.
 
 use axum::{
-    extract::State,
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
     Json,
 };
-use serde::Serialize;
-use utoipa::ToSchema;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
 use std::sync::OnceLock;
 use std::time::Instant;
 
+use crate::database::schema::types::EpochStatus;
+use crate::router::changelog::{self, ApiChange};
+use crate::services::degradation::SubsystemHealth;
+use crate::services::event_processor::EventProcessorHealth;
+use crate::services::maintenance::MaintenanceWindow;
+use crate::services::market_session::MarketSession;
+use crate::services::server_clock::{self, ServerTime};
+use crate::services::trading_halts::TradingHalt;
+use crate::startup::report::StartupReport;
+use crate::startup::warmup::WarmupReport;
 use crate::AppState;
 
+/// API changes listed on the status endpoint
+const RECENT_CHANGES_DAYS: i64 = 90;
+
 /// Global start time for uptime calculation
 static START_TIME: OnceLock<Instant> = OnceLock::new();
 
@@ -28,6 +49,33 @@ pub struct HealthResponse {
     pub uptime_seconds: u64,
     pub timestamp: String,
     pub services: ServiceStatus,
+    /// Epoch currently accepting orders; none between epochs or when unavailable
+    pub current_epoch: Option<EpochSummary>,
+    pub market_session: MarketSession,
+    /// Trading halts of the platform, a market or a zone currently in force
+    pub trading_halts: Vec<TradingHalt>,
+    /// Maintenance in progress or scheduled, soonest first
+    pub maintenance: Vec<MaintenanceWindow>,
+    /// Route changes and deprecations of the last 90 days, newest first
+    pub api_changes: Vec<ApiChange>,
+}
+
+/// The epoch in progress
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EpochSummary {
+    pub id: Uuid,
+    pub epoch_number: i64,
+    pub status: EpochStatus,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ChangelogQuery {
+    /// Only changes on or after this date
+    pub since: Option<NaiveDate>,
+    /// Only routes whose path starts with this prefix
+    pub path: Option<String>,
 }
 
 /// Status of individual services
@@ -67,6 +115,29 @@ pub async fn system_status(
     State(state): State<AppState>,
 ) -> Json<HealthResponse> {
     let health = state.health_checker.perform_health_check().await;
+
+    let current_epoch = match state.market_clearing.get_current_epoch().await {
+        Ok(epoch) => epoch.map(|e| EpochSummary {
+            id: e.id,
+            epoch_number: e.epoch_number,
+            status: e.status,
+            start_time: e.start_time,
+            end_time: e.end_time,
+        }),
+        Err(e) => {
+            warn!("Status: failed to load current epoch: {}", e);
+            None
+        }
+    };
+    let trading_halts = state.trading_halts.active().await.unwrap_or_else(|e| {
+        warn!("Status: failed to load trading halts: {}", e);
+        Vec::new()
+    });
+    let maintenance = state.maintenance.upcoming().await.unwrap_or_else(|e| {
+        warn!("Status: failed to load maintenance windows: {}", e);
+        Vec::new()
+    });
+    let recent = Utc::now().date_naive() - Duration::days(RECENT_CHANGES_DAYS);
     
     // Map dependencies to ServiceStatus
     let mut db_health = ServiceHealth {
@@ -137,9 +208,28 @@ pub async fn system_status(
             email: email_health,
             blockchain: blockchain_health,
         },
+        current_epoch,
+        market_session: state.market_session.current(),
+        trading_halts,
+        maintenance,
+        api_changes: changelog::changes(Some(recent), None),
     })
 }
 
+/// Developer API changelog
+#[utoipa::path(
+    get,
+    path = "/api/v1/status/changelog",
+    params(ChangelogQuery),
+    responses(
+        (status = 200, description = "Route changes and deprecations, newest first", body = Vec<ApiChange>),
+    ),
+    tag = "status"
+)]
+pub async fn api_changelog(Query(params): Query<ChangelogQuery>) -> Json<Vec<ApiChange>> {
+    Json(changelog::changes(params.since, params.path.as_deref()))
+}
+
 // These helper functions are now redundant as they are handled by health_checker service
 // Removing them to avoid confusion
 
@@ -269,6 +359,124 @@ pub struct CheckResult {
     pub passed: bool,
 }
 
+/// Readiness with the startup report
+///
+/// Returns 503 while PostgreSQL or Redis is unreachable, and after startup
+/// until the cold-start warmup in `warmup` has finished or timed out. Optional
+/// dependencies the gateway started without are listed in `startup`, those
+/// failing at runtime in `subsystems`; both set `degraded` but do not fail
+/// the probe.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    responses(
+        (status = 200, description = "Ready", body = ReadyzResponse),
+        (status = 503, description = "A required dependency is unreachable or warmup is running", body = ReadyzResponse),
+    ),
+    tag = "status"
+)]
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<ReadyzResponse>) {
+    let database = sqlx::query("SELECT 1").execute(&state.db).await.is_ok();
+    let redis = match state.redis.get_multiplexed_async_connection().await {
+        Ok(mut conn) => redis::cmd("PING")
+            .query_async::<String>(&mut conn)
+            .await
+            .is_ok(),
+        Err(_) => false,
+    };
+
+    let warmup = state.warmup.report();
+    let ready = database && redis && warmup.complete;
+    let subsystems = state.degradation.snapshot();
+    let degraded = state.startup_report.degraded
+        || subsystems.iter().any(|s| s.status == crate::services::degradation::SubsystemStatus::Down);
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(ReadyzResponse {
+            ready,
+            degraded,
+            checks: vec![
+                CheckResult {
+                    name: "database".to_string(),
+                    passed: database,
+                },
+                CheckResult {
+                    name: "redis".to_string(),
+                    passed: redis,
+                },
+                CheckResult {
+                    name: "warmup".to_string(),
+                    passed: warmup.complete,
+                },
+            ],
+            startup: (*state.startup_report).clone(),
+            warmup,
+            subsystems,
+        }),
+    )
+}
+
+/// Event processor internals and lag SLO
+///
+/// Per-event-type counts, lag behind the chain head, confirmation queue
+/// depth and recent errors. Returns 503 while lag or queue age exceeds
+/// `EVENT_PROCESSOR_MAX_LAG_SLOTS` / `EVENT_PROCESSOR_MAX_PENDING_AGE_SECS`.
+#[utoipa::path(
+    get,
+    path = "/health/event-processor",
+    responses(
+        (status = 200, description = "Within the lag SLO", body = EventProcessorHealth),
+        (status = 503, description = "Lag or queue age above the SLO", body = EventProcessorHealth),
+    ),
+    tag = "status"
+)]
+pub async fn event_processor_health(State(state): State<AppState>) -> (StatusCode, Json<EventProcessorHealth>) {
+    let health = state.event_processor.health();
+    let status = if health.healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(health))
+}
+
+/// Server time for client clock sync
+///
+/// Never cached. `sequence` increases with every reading of this instance
+/// and `monotonic_ms` is unaffected by wall clock steps. For latency, note
+/// the local time `t0` before the request and `t1` on the response: the
+/// round trip is `t1 - t0` and the clock offset `unix_ms - (t0 + (t1 - t0) / 2)`.
+/// The WebSocket feeds carry the same fields in heartbeats and pongs.
+#[utoipa::path(
+    get,
+    path = "/api/time",
+    responses(
+        (status = 200, description = "Current server time", body = ServerTime),
+    ),
+    tag = "status"
+)]
+pub async fn server_time() -> impl IntoResponse {
+    ([(header::CACHE_CONTROL, "no-store")], Json(server_clock::now()))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadyzResponse {
+    pub ready: bool,
+    /// Running without one or more optional dependencies
+    pub degraded: bool,
+    pub checks: Vec<CheckResult>,
+    pub startup: StartupReport,
+    pub warmup: WarmupReport,
+    /// Email, cache and metrics state; dependents skip or defer work while down
+    pub subsystems: Vec<SubsystemHealth>,
+}
+
 /// Simple liveness probe for kubernetes/docker
 #[utoipa::path(
     get,
@@ -6,24 +6,58 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::pii::{SealedUserPii, USER_PII_COLUMNS};
+use crate::models::EnergyKwh;
+use crate::services::emission_factors::FactorProvenance;
+use crate::services::meter_installation::MeterInstallation;
+use crate::services::pii::PiiCipher;
+use crate::services::wallet_links::WalletLinkProof;
 
 // ============================================================================
 // Database Models
 // ============================================================================
 
-/// User row from database
+/// User row from database; email and names are sealed
 #[derive(Debug, Clone, FromRow)]
 pub struct UserRow {
     pub id: Uuid,
     pub username: String,
-    pub email: String,
     pub role: String,
-    pub first_name: Option<String>,
-    pub last_name: Option<String>,
     pub wallet_address: Option<String>,
     pub balance: Option<rust_decimal::Decimal>,
     pub locked_amount: Option<rust_decimal::Decimal>,
     pub locked_energy: Option<rust_decimal::Decimal>,
+    #[sqlx(flatten)]
+    pub pii: SealedUserPii,
+}
+
+/// Select list matching `UserRow`
+pub fn user_row_columns() -> String {
+    format!(
+        "id, username, role::text as role, wallet_address, balance, locked_amount, locked_energy, {}",
+        USER_PII_COLUMNS
+    )
+}
+
+impl UserRow {
+    /// Response with email and names decrypted
+    pub async fn into_response(self, pii: &PiiCipher) -> crate::error::Result<UserResponse> {
+        let opened = self.pii.open(pii).await?;
+        Ok(UserResponse {
+            id: self.id,
+            username: self.username,
+            email: opened.email,
+            role: self.role,
+            first_name: opened.first_name.unwrap_or_default(),
+            last_name: opened.last_name.unwrap_or_default(),
+            wallet_address: self.wallet_address,
+            balance: self.balance.unwrap_or_default(),
+            locked_amount: self.locked_amount.unwrap_or_default(),
+            locked_energy: self.locked_energy.unwrap_or_default(),
+        })
+    }
 }
 
 // ============================================================================
@@ -38,6 +72,13 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+/// One-time code completing a login that required step-up verification
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StepUpVerifyRequest {
+    pub challenge_id: Uuid,
+    pub code: String,
+}
+
 /// Auth Response (Token)
 #[derive(Debug, Serialize, Clone, ToSchema)]
 pub struct AuthResponse {
@@ -58,6 +99,9 @@ pub struct RegistrationRequest {
     pub password: String,
     pub first_name: String,
     pub last_name: String,
+    /// Referral code of the user who invited this one
+    #[serde(default)]
+    pub referral_code: Option<String>,
 }
 
 /// Registration Response
@@ -85,10 +129,29 @@ pub struct UserResponse {
 }
 
 /// Update Wallet Request
+///
+/// Answers the challenge from `POST /api/v1/account/wallet/challenge` with
+/// exactly one proof of control.
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateWalletRequest {
     pub wallet_address: String,
-    pub verify_ownership: Option<bool>,
+    /// Base58 Ed25519 signature of the challenge message by the wallet key
+    pub signature: Option<String>,
+    /// Signature of a confirmed transaction, paid by the wallet, carrying the challenge memo
+    pub transaction_signature: Option<String>,
+}
+
+impl UpdateWalletRequest {
+    pub fn proof(&self) -> Result<WalletLinkProof, crate::ApiError> {
+        match (&self.signature, &self.transaction_signature) {
+            (Some(signature), None) => Ok(WalletLinkProof::Signature(signature.clone())),
+            (None, Some(tx_signature)) => Ok(WalletLinkProof::MemoTransaction(tx_signature.clone())),
+            _ => Err(crate::ApiError::validation_field(
+                "signature",
+                "Provide either a signature or a transaction_signature",
+            )),
+        }
+    }
 }
 
 /// Email Verification Request
@@ -178,6 +241,9 @@ pub struct MeterResponse {
     /// Zone ID for the meter
     #[serde(skip_serializing_if = "Option::is_none")]
     pub zone_id: Option<i32>,
+    /// On-chain registry account (ownership proof), once the verified meter is registered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry_pda: Option<String>,
 }
 
 /// Public Meter Response (for unauthenticated public API)
@@ -227,16 +293,18 @@ pub struct PublicMeterResponse {
 /// Public Grid Status Response (aggregate statistics)
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PublicGridStatusResponse {
-    /// Aggregate generation from all active meters (kW)
-    pub total_generation: f64,
-    /// Aggregate consumption from all active meters (kW)
-    pub total_consumption: f64,
-    /// Net balance (generation - consumption) (kW)
-    pub net_balance: f64,
+    /// Aggregate generation from all active meters (kWh)
+    pub total_generation: EnergyKwh,
+    /// Aggregate consumption from all active meters (kWh)
+    pub total_consumption: EnergyKwh,
+    /// Net balance (generation - consumption) (kWh)
+    pub net_balance: EnergyKwh,
     /// Number of currently reporting active meters
     pub active_meters: i64,
     /// Estimated CO2 saved today (kg)
     pub co2_saved_kg: f64,
+    /// Emission factor the CO2 estimate was computed with
+    pub co2_factor: Option<FactorProvenance>,
     /// Timestamp of the status calculation
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
@@ -260,6 +328,9 @@ pub struct RegisterMeterRequest {
     pub longitude: Option<f64>,
     /// Zone ID for the meter
     pub zone_id: Option<i32>,
+    /// Rated capacity, panel orientation, installation date and inverter
+    #[serde(default)]
+    pub installation: Option<MeterInstallation>,
 }
 
 /// Meter Registration Response
@@ -289,6 +360,9 @@ pub struct UpdateMeterStatusRequest {
     pub zone_id: Option<i32>,
     pub latitude: Option<f64>,
     pub longitude: Option<f64>,
+    /// Installation fields to update; omitted ones are kept
+    #[serde(default)]
+    pub installation: Option<MeterInstallation>,
 }
 
 /// Create reading request for v1 API with full telemetry support
@@ -303,6 +377,8 @@ pub struct CreateReadingRequest {
     pub meter_id: Option<String>,
     pub meter_serial: Option<String>,
     pub meter_type: Option<String>,
+    /// Firmware version running on the meter
+    pub firmware_version: Option<String>,
     
     // Energy Data (kWh)
     pub energy_generated: Option<f64>,
@@ -336,6 +412,10 @@ pub struct CreateReadingRequest {
     
     // Security
     pub meter_signature: Option<String>,
+    /// Canonical message version the signature was produced over (default: 1)
+    pub message_version: Option<u8>,
+    /// Per-meter monotonic sequence number, required for version >= 2
+    pub sequence: Option<u64>,
 }
 
 impl crate::handlers::meter::types::ReadingData for CreateReadingRequest {
@@ -458,6 +538,74 @@ pub struct TokenBalanceResponse {
     pub token_account: String,
 }
 
+/// Batch balance lookup request
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct BatchBalanceRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub wallet_addresses: Vec<String>,
+}
+
+/// Batch balance lookup response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchBalanceResponse {
+    pub token_mint: String,
+    pub balances: Vec<crate::services::wallet::WalletBalance>,
+}
+
+// ============================================================================
+// Security Overview Types
+// ============================================================================
+
+/// A successful sign-in, newest first in the overview
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecentLogin {
+    pub ip_address: Option<String>,
+    /// ISO country code of the IP, when GeoIP is configured
+    pub country: Option<String>,
+    pub city: Option<String>,
+    /// User-Agent of the client that signed in
+    pub device: Option<String>,
+    pub logged_in_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A wallet address linked to the account
+#[derive(Debug, Serialize, ToSchema, FromRow)]
+pub struct ConnectedWallet {
+    pub wallet_address: String,
+    pub label: Option<String>,
+    pub is_primary: bool,
+    pub verified: bool,
+    pub linked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// An active API key issued to the user
+#[derive(Debug, Serialize, ToSchema, FromRow)]
+pub struct ActiveApiKey {
+    pub id: Uuid,
+    pub name: String,
+    #[schema(value_type = Vec<String>)]
+    pub permissions: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Multi-factor authentication status
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MfaStatus {
+    pub enabled: bool,
+    /// Whether the platform offers MFA at all
+    pub available: bool,
+}
+
+/// Account security summary for self-audit
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SecurityOverview {
+    pub recent_logins: Vec<RecentLogin>,
+    pub connected_wallets: Vec<ConnectedWallet>,
+    pub api_keys: Vec<ActiveApiKey>,
+    pub mfa: MfaStatus,
+}
+
 // ============================================================================
 // Status Types
 // ============================================================================
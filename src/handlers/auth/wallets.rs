@@ -8,8 +8,10 @@ use axum::{
 };
 use tracing::info;
 
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
 use crate::AppState;
-use super::types::TokenBalanceResponse;
+use super::types::{BatchBalanceRequest, BatchBalanceResponse, TokenBalanceResponse};
 
 /// Token Balance Handler - queries blockchain for wallet balance
 #[utoipa::path(
@@ -79,3 +81,53 @@ pub async fn token_balance(
         token_account: format!("{}...token", &wallet_address[..8.min(wallet_address.len())]),
     })
 }
+
+/// Batch Balance Handler - SOL and energy token balances for many wallets
+///
+/// Accounts are fetched with batched RPC calls and cached briefly; entries
+/// the gateway has just minted to or transferred from are always fresh.
+#[utoipa::path(
+    post,
+    path = "/api/v1/wallets/balances",
+    request_body = BatchBalanceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Balances in request order", body = BatchBalanceResponse),
+        (status = 400, description = "Invalid wallet address"),
+        (status = 422, description = "Too many wallets"),
+        (status = 502, description = "RPC lookup failed"),
+    ),
+    tag = "wallets"
+)]
+pub async fn batch_balances(
+    State(state): State<AppState>,
+    ValidatedJson(payload): ValidatedJson<BatchBalanceRequest>,
+) -> Result<Json<BatchBalanceResponse>> {
+    let wallets = payload
+        .wallet_addresses
+        .iter()
+        .map(|address| {
+            crate::services::BlockchainService::parse_pubkey(address)
+                .map_err(|_| ApiError::BadRequest(format!("Invalid wallet address: {}", address)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mint = crate::services::BlockchainService::parse_pubkey(&state.config.energy_token_mint)
+        .map_err(|e| ApiError::Internal(format!("Invalid mint address: {}", e)))?;
+
+    let balances = state
+        .wallet_service
+        .get_balances(&wallets, &mint, 9)
+        .await
+        .map_err(|e| ApiError::ExternalService(format!("Balance lookup failed: {}", e)))?;
+
+    info!(
+        "💰 Batch balance lookup for {} wallets ({} cached)",
+        balances.len(),
+        balances.iter().filter(|b| b.cached).count()
+    );
+
+    Ok(Json(BatchBalanceResponse {
+        token_mint: state.config.energy_token_mint.clone(),
+        balances,
+    }))
+}
@@ -0,0 +1,438 @@
+//! Blockchain transaction history with decoded instructions and CSV export
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, TimeZone, Utc};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::str::FromStr;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::csv_field;
+use crate::services::blockchain::{DecodedInstruction, IdlRegistry};
+use crate::utils::pagination::{contains_pattern, PageCursor};
+use crate::AppState;
+
+use super::types::*;
+
+/// Undecoded transactions looked up on-chain per history request
+const MAX_DECODES_PER_REQUEST: usize = 20;
+
+const EXPORT_PAGE_SIZE: i64 = 1_000;
+
+const EXPORT_HEADER: &str = "signature,program_id,instruction_name,status,signer,fee,slot,block_time,submitted_at,summary\n";
+
+const STATUSES: &[&str] = &["pending", "confirmed", "failed"];
+
+#[derive(Debug, sqlx::FromRow)]
+struct TransactionRow {
+    id: Uuid,
+    signature: String,
+    program_id: String,
+    instruction_name: Option<String>,
+    status: String,
+    signer: Option<String>,
+    fee: Option<i64>,
+    compute_units_consumed: Option<i32>,
+    slot: Option<i64>,
+    block_time: Option<DateTime<Utc>>,
+    submitted_at: Option<DateTime<Utc>>,
+    error_message: Option<String>,
+    instructions: Option<sqlx::types::Json<Vec<DecodedInstruction>>>,
+}
+
+const SELECT_COLUMNS: &str = "SELECT id, signature, program_id, instruction_name, status, signer, fee, \
+     compute_units_consumed, slot, block_time, submitted_at, error_message, instructions \
+     FROM blockchain_transactions";
+
+/// Validated history filters
+#[derive(Debug, Clone)]
+struct HistoryFilter {
+    user_id: Uuid,
+    program_id: Option<String>,
+    status: Option<String>,
+    signer: Option<String>,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+    search: Option<String>,
+}
+
+impl HistoryFilter {
+    fn from_query(user_id: Uuid, params: &TransactionQuery) -> Result<Self> {
+        let pubkey = |field: &str, value: &Option<String>| -> Result<Option<String>> {
+            value
+                .as_deref()
+                .map(|v| {
+                    Pubkey::from_str(v.trim())
+                        .map(|key| key.to_string())
+                        .map_err(|_| ApiError::validation_field(field, "Must be a base58 address"))
+                })
+                .transpose()
+        };
+
+        let status = params.status.as_deref().map(str::to_lowercase);
+        if let Some(status) = &status {
+            if !STATUSES.contains(&status.as_str()) {
+                return Err(ApiError::validation_field("status", "Must be pending, confirmed or failed"));
+            }
+        }
+        if let (Some(start), Some(end)) = (params.start_time, params.end_time) {
+            if start >= end {
+                return Err(ApiError::validation_field("end_time", "Must be after start_time"));
+            }
+        }
+
+        Ok(Self {
+            user_id,
+            program_id: pubkey("program_id", &params.program_id)?,
+            status,
+            signer: pubkey("signer", &params.signer)?,
+            start_time: params.start_time,
+            end_time: params.end_time,
+            search: params
+                .search
+                .as_deref()
+                .map(str::trim)
+                .filter(|search| !search.is_empty())
+                .map(str::to_string),
+        })
+    }
+
+    fn push_where(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        builder.push(" WHERE user_id = ").push_bind(self.user_id);
+        if let Some(program_id) = &self.program_id {
+            builder.push(" AND program_id = ").push_bind(program_id.clone());
+        }
+        if let Some(status) = &self.status {
+            builder.push(" AND LOWER(status) = ").push_bind(status.clone());
+        }
+        if let Some(signer) = &self.signer {
+            builder.push(" AND signer = ").push_bind(signer.clone());
+        }
+        if let Some(start) = self.start_time {
+            builder.push(" AND submitted_at >= ").push_bind(start);
+        }
+        if let Some(end) = self.end_time {
+            builder.push(" AND submitted_at < ").push_bind(end);
+        }
+        if let Some(search) = &self.search {
+            builder.push(" AND signature ILIKE ").push_bind(contains_pattern(search));
+        }
+    }
+
+    async fn fetch_page(
+        &self,
+        db: &PgPool,
+        after: Option<PageCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> std::result::Result<Vec<TransactionRow>, sqlx::Error> {
+        let mut builder = QueryBuilder::<Postgres>::new(SELECT_COLUMNS);
+        self.push_where(&mut builder);
+        if let Some(after) = after {
+            builder
+                .push(" AND (submitted_at, id) < (")
+                .push_bind(after.at)
+                .push(", ")
+                .push_bind(after.id)
+                .push(")");
+        }
+        builder.push(" ORDER BY submitted_at DESC, id DESC LIMIT ").push_bind(limit);
+        if after.is_none() {
+            builder.push(" OFFSET ").push_bind(offset);
+        }
+        builder.build_query_as::<TransactionRow>().fetch_all(db).await
+    }
+
+    async fn count(&self, db: &PgPool) -> std::result::Result<i64, sqlx::Error> {
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM blockchain_transactions");
+        self.push_where(&mut builder);
+        builder.build_query_scalar::<i64>().fetch_one(db).await
+    }
+}
+
+/// Look up a transaction on-chain, decode its instructions and store the result
+async fn decode_and_store(state: &AppState, registry: &IdlRegistry, row: &mut TransactionRow) -> anyhow::Result<()> {
+    let signature = Signature::from_str(&row.signature)?;
+    let confirmed = state.blockchain_service.get_confirmed_transaction(&signature).await?;
+
+    let instructions: Vec<DecodedInstruction> = confirmed.instructions.iter().map(|ix| registry.decode(ix)).collect();
+    let status = if confirmed.failed { "failed" } else { "confirmed" };
+    let block_time = confirmed
+        .block_time
+        .and_then(|t| Utc.timestamp_opt(t, 0).single());
+    let signer = confirmed.fee_payer.map(|k| k.to_string());
+
+    sqlx::query(
+        "UPDATE blockchain_transactions
+         SET signer = $2, slot = $3, block_time = $4, instructions = $5, status = $6,
+             error_message = COALESCE($7, error_message),
+             confirmed_at = COALESCE(confirmed_at, $4, NOW()), updated_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(row.id)
+    .bind(&signer)
+    .bind(confirmed.slot as i64)
+    .bind(block_time)
+    .bind(sqlx::types::Json(&instructions))
+    .bind(status)
+    .bind(&confirmed.error)
+    .execute(&state.db)
+    .await?;
+
+    row.signer = signer;
+    row.slot = Some(confirmed.slot as i64);
+    row.block_time = block_time;
+    row.status = status.to_string();
+    if confirmed.error.is_some() {
+        row.error_message = confirmed.error;
+    }
+    row.instructions = Some(sqlx::types::Json(instructions));
+    Ok(())
+}
+
+fn to_entry(registry: &IdlRegistry, row: TransactionRow) -> TransactionHistoryEntry {
+    let program = Pubkey::from_str(&row.program_id)
+        .ok()
+        .and_then(|id| registry.program_name(&id))
+        .map(str::to_string);
+
+    TransactionHistoryEntry {
+        signature: row.signature,
+        program_id: row.program_id,
+        program,
+        instruction_name: row.instruction_name,
+        status: row.status.to_lowercase(),
+        signer: row.signer,
+        fee: row.fee,
+        compute_units_consumed: row.compute_units_consumed,
+        slot: row.slot,
+        block_time: row.block_time,
+        submitted_at: row.submitted_at,
+        error_message: row.error_message,
+        instructions: row.instructions.map(|json| json.0).unwrap_or_default(),
+    }
+}
+
+/// Get transaction history for authenticated user
+/// GET /api/v1/blockchain/transactions
+///
+/// Transactions not yet decoded are looked up on-chain (a bounded number per
+/// request) and their decoded instructions stored for later requests.
+#[utoipa::path(
+    get,
+    path = "/api/v1/blockchain/transactions",
+    tag = "blockchain",
+    params(TransactionQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Page of the user's blockchain transactions, newest first", body = TransactionHistoryResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid filter")
+    )
+)]
+pub async fn get_transaction_history(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<TransactionQuery>,
+) -> Result<Json<TransactionHistoryResponse>> {
+    params.validate()?;
+    let filter = HistoryFilter::from_query(user.0.sub, &params)?;
+    let after = params
+        .cursor
+        .as_deref()
+        .map(|token| PageCursor::decode(token).map_err(|e| ApiError::validation_field("cursor", e)))
+        .transpose()?;
+    let limit = params.limit.unwrap_or(50);
+    let offset = params.offset.unwrap_or(0);
+
+    let mut rows = filter.fetch_page(&state.db, after, limit as i64, offset as i64).await?;
+    let total = filter.count(&state.db).await?;
+    let next_cursor = PageCursor::after(&rows, limit as i64, row_cursor).map(|cursor| cursor.encode());
+
+    let registry = IdlRegistry::new(&state.config.energy_token_mint);
+    for row in rows
+        .iter_mut()
+        .filter(|row| row.instructions.is_none())
+        .take(MAX_DECODES_PER_REQUEST)
+    {
+        if let Err(e) = decode_and_store(&state, &registry, row).await {
+            // Not yet confirmed or RPC unavailable; retried on the next request
+            tracing::debug!("Could not decode transaction {}: {}", row.signature, e);
+        }
+    }
+
+    Ok(Json(TransactionHistoryResponse {
+        transactions: rows.into_iter().map(|row| to_entry(&registry, row)).collect(),
+        total,
+        limit,
+        offset,
+        next_cursor,
+    }))
+}
+
+/// Rows without a submission time sort first and end the cursor there
+fn row_cursor(row: &TransactionRow) -> PageCursor {
+    PageCursor {
+        at: row.submitted_at.unwrap_or(DateTime::<Utc>::MIN_UTC),
+        id: row.id,
+    }
+}
+
+fn export_line(row: &TransactionRow) -> String {
+    let timestamp = |t: Option<DateTime<Utc>>| t.map(|t| t.to_rfc3339()).unwrap_or_default();
+    let summary = row
+        .instructions
+        .as_ref()
+        .map(|json| json.0.iter().map(|ix| ix.summary.as_str()).collect::<Vec<_>>().join("; "))
+        .unwrap_or_default();
+
+    format!(
+        "{},{},{},{},{},{},{},{},{},{}\n",
+        row.signature,
+        row.program_id,
+        csv_field(row.instruction_name.as_deref().unwrap_or_default()),
+        row.status.to_lowercase(),
+        row.signer.as_deref().unwrap_or_default(),
+        row.fee.map(|f| f.to_string()).unwrap_or_default(),
+        row.slot.map(|s| s.to_string()).unwrap_or_default(),
+        timestamp(row.block_time),
+        timestamp(row.submitted_at),
+        csv_field(&summary),
+    )
+}
+
+struct ExportCursor {
+    db: PgPool,
+    filter: HistoryFilter,
+    after: Option<PageCursor>,
+    header_sent: bool,
+    done: bool,
+}
+
+/// Export transaction history as CSV
+/// GET /api/v1/blockchain/transactions/export
+///
+/// Uses the history filters; paging is ignored. Instructions not yet decoded
+/// are exported with an empty summary.
+#[utoipa::path(
+    get,
+    path = "/api/v1/blockchain/transactions/export",
+    tag = "blockchain",
+    params(TransactionQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "All transactions matching the filters", content_type = "text/csv"),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid filter")
+    )
+)]
+pub async fn export_transaction_history(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<TransactionQuery>,
+) -> Result<Response> {
+    let filter = HistoryFilter::from_query(user.0.sub, &params)?;
+    let cursor = ExportCursor {
+        db: state.db.clone(),
+        filter,
+        after: None,
+        header_sent: false,
+        done: false,
+    };
+
+    let stream = futures::stream::unfold(cursor, |mut cursor| async move {
+        if cursor.done {
+            return None;
+        }
+        match cursor.filter.fetch_page(&cursor.db, cursor.after, EXPORT_PAGE_SIZE, 0).await {
+            Ok(rows) => {
+                cursor.after = PageCursor::after(&rows, EXPORT_PAGE_SIZE, row_cursor);
+                cursor.done = cursor.after.is_none();
+                if rows.is_empty() && cursor.header_sent {
+                    return None;
+                }
+
+                let mut chunk = String::new();
+                if !cursor.header_sent {
+                    chunk.push_str(EXPORT_HEADER);
+                    cursor.header_sent = true;
+                }
+                rows.iter().for_each(|row| chunk.push_str(&export_line(row)));
+                Some((Ok(Bytes::from(chunk)), cursor))
+            }
+            Err(e) => {
+                tracing::error!("Transaction export failed: {}", e);
+                cursor.done = true;
+                Some((Err(e), cursor))
+            }
+        }
+    });
+
+    let filename = format!("gridtokenx_transactions_{}.csv", Utc::now().format("%Y%m%d_%H%M%S"));
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query() -> TransactionQuery {
+        TransactionQuery {
+            program_id: None,
+            status: None,
+            signer: None,
+            start_time: None,
+            end_time: None,
+            search: None,
+            cursor: None,
+            limit: None,
+            offset: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_validation() {
+        let user = Uuid::new_v4();
+        let filter = HistoryFilter::from_query(
+            user,
+            &TransactionQuery { status: Some("Confirmed".to_string()), ..query() },
+        )
+        .unwrap();
+        assert_eq!(filter.status.as_deref(), Some("confirmed"));
+
+        assert!(HistoryFilter::from_query(user, &TransactionQuery { status: Some("done".to_string()), ..query() }).is_err());
+        assert!(HistoryFilter::from_query(user, &TransactionQuery { signer: Some("not-a-key".to_string()), ..query() }).is_err());
+
+        let now = Utc::now();
+        let reversed = TransactionQuery {
+            start_time: Some(now),
+            end_time: Some(now - chrono::Duration::hours(1)),
+            ..query()
+        };
+        assert!(HistoryFilter::from_query(user, &reversed).is_err());
+    }
+
+    #[test]
+    fn test_csv_field_quotes_when_needed() {
+        assert_eq!(csv_field("mint 10 GRID to X"), "mint 10 GRID to X");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
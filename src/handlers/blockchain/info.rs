@@ -3,7 +3,7 @@ use axum::{
     response::Json,
 };
 use chrono::Utc;
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{pubkey::Pubkey, system_program};
 use std::str::FromStr;
 
 use crate::auth::middleware::AuthenticatedUser;
@@ -13,10 +13,13 @@ use crate::AppState;
 use super::types::*;
 
 /// Get account information for a given address
-/// GET /api/blockchain/accounts/:address
+/// GET /api/v1/blockchain/accounts/{address}
+///
+/// Returns the account's owner (labelled when it is one of the platform
+/// programs), rent-exemption status and the parsed token accounts it owns.
 #[utoipa::path(
     get,
-    path = "/api/blockchain/accounts/{address}",
+    path = "/api/v1/blockchain/accounts/{address}",
     tag = "blockchain",
     security(("bearer_auth" = [])),
     params(
@@ -26,7 +29,7 @@ use super::types::*;
         (status = 200, description = "Account information", body = AccountInfo),
         (status = 400, description = "Invalid address format"),
         (status = 401, description = "Unauthorized"),
-        (status = 500, description = "Internal server error")
+        (status = 502, description = "Solana RPC unavailable")
     )
 )]
 pub async fn get_account_info(
@@ -44,33 +47,118 @@ pub async fn get_account_info(
     let pubkey = Pubkey::from_str(&address)
         .map_err(|_| ApiError::BadRequest("Invalid address format".to_string()))?;
 
-    // Fetch real account info
-    let balance_lamports = state
-        .blockchain_service
-        .get_balance(&pubkey)
+    let blockchain = &state.blockchain_service;
+    let account = blockchain
+        .get_account(&pubkey)
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to fetch balance: {}", e)))?;
+        .map_err(|e| ApiError::ExternalService(format!("Failed to fetch account: {}", e)))?;
 
-    let data = state
-        .blockchain_service
-        .get_account_data(&pubkey)
-        .await
-        .unwrap_or_default();
-
-    let owner = "11111111111111111111111111111111".to_string(); // Default to system program if unknown
-                                                                // In a full implementation we would fetch the full Account object to get owner, executable, etc.
-
-    let account_info = AccountInfo {
-        address: address.clone(),
-        balance: rust_decimal::Decimal::from(balance_lamports)
-            / rust_decimal::Decimal::from(1_000_000_000),
-        executable: false, // Placeholder
-        owner,
-        rent_epoch: 0,
-        data_length: data.len(),
+    let (lamports, owner, executable, rent_epoch, data_length) = match &account {
+        Some(account) => (
+            account.lamports,
+            account.owner,
+            account.executable,
+            account.rent_epoch,
+            account.data.len(),
+        ),
+        // Accounts that were never funded behave like empty system accounts
+        None => (0, system_program::id(), false, 0, 0),
     };
 
-    Ok(Json(account_info))
+    let rent_exempt_minimum = blockchain
+        .get_minimum_balance_for_rent_exemption(data_length)
+        .await
+        .map_err(|e| ApiError::ExternalService(e.to_string()))?;
+
+    let token_accounts = blockchain
+        .get_token_accounts_by_owner(&pubkey)
+        .await
+        .map_err(|e| ApiError::ExternalService(e.to_string()))?
+        .into_iter()
+        .filter_map(|(token_address, info)| {
+            let parsed = parse_token_account(&token_address, &info);
+            if parsed.is_none() {
+                tracing::warn!("Skipping unparseable token account {}", token_address);
+            }
+            parsed
+        })
+        .collect();
+
+    let known_programs = known_programs(&state);
+
+    Ok(Json(AccountInfo {
+        address,
+        exists: account.is_some(),
+        balance: rust_decimal::Decimal::from(lamports) / rust_decimal::Decimal::from(1_000_000_000),
+        lamports,
+        executable,
+        owner: owner.to_string(),
+        owner_label: owner_label(&owner, &known_programs).map(str::to_string),
+        rent_epoch,
+        data_length,
+        rent_exempt: account.is_some() && lamports >= rent_exempt_minimum,
+        rent_exempt_minimum,
+        token_accounts,
+    }))
+}
+
+/// Platform programs from config plus the well-known Solana programs
+fn known_programs(state: &AppState) -> Vec<(Pubkey, &'static str)> {
+    let blockchain = &state.blockchain_service;
+    let configured = [
+        (blockchain.registry_program_id(), "registry"),
+        (blockchain.trading_program_id(), "trading"),
+        (blockchain.energy_token_program_id(), "energy-token"),
+        (blockchain.oracle_program_id(), "oracle"),
+        (blockchain.governance_program_id(), "governance"),
+    ];
+
+    let mut programs: Vec<(Pubkey, &'static str)> = configured
+        .into_iter()
+        .filter_map(|(id, label)| id.ok().map(|id| (id, label)))
+        .collect();
+    programs.extend(builtin_programs());
+    programs
+}
+
+fn builtin_programs() -> Vec<(Pubkey, &'static str)> {
+    [
+        (system_program::id().to_string(), "system"),
+        (spl_token::id().to_string(), "spl-token"),
+        ("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb".to_string(), "token-2022"),
+        ("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL".to_string(), "associated-token"),
+        ("BPFLoaderUpgradeab1e11111111111111111111111".to_string(), "bpf-loader-upgradeable"),
+    ]
+    .into_iter()
+    .filter_map(|(id, label)| Pubkey::from_str(&id).ok().map(|id| (id, label)))
+    .collect()
+}
+
+/// Label for an owning program, if it is a known one
+fn owner_label<'a>(owner: &Pubkey, known: &[(Pubkey, &'a str)]) -> Option<&'a str> {
+    known
+        .iter()
+        .find(|(id, _)| id == owner)
+        .map(|(_, label)| *label)
+}
+
+/// Parse the `jsonParsed` info of a token account
+fn parse_token_account(address: &str, info: &serde_json::Value) -> Option<TokenAccountInfo> {
+    let token_amount = info.get("tokenAmount")?;
+    let text = |value: &serde_json::Value, key: &str| value.get(key)?.as_str().map(str::to_string);
+
+    Some(TokenAccountInfo {
+        address: address.to_string(),
+        mint: text(info, "mint")?,
+        amount: text(token_amount, "amount")?,
+        decimals: u8::try_from(token_amount.get("decimals")?.as_u64()?).ok()?,
+        ui_amount: text(token_amount, "uiAmountString")?,
+        delegate: text(info, "delegate"),
+        delegated_amount: info
+            .get("delegatedAmount")
+            .and_then(|amount| text(amount, "amount")),
+        state: text(info, "state").unwrap_or_else(|| "initialized".to_string()),
+    })
 }
 
 /// Get current network status
@@ -115,7 +203,53 @@ pub async fn get_network_status(
             "degraded".to_string()
         },
         version: "unknown".to_string(),
+        rpc_cache: state.blockchain_service.rpc_cache_stats(),
+        submission_queue: state.blockchain_service.submission_queue_stats(),
     };
 
     Ok(Json(network_status))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owner_label() {
+        let registry = Pubkey::new_unique();
+        let mut known = vec![(registry, "registry")];
+        known.extend(builtin_programs());
+
+        assert_eq!(owner_label(&registry, &known), Some("registry"));
+        assert_eq!(owner_label(&system_program::id(), &known), Some("system"));
+        assert_eq!(owner_label(&spl_token::id(), &known), Some("spl-token"));
+        assert_eq!(owner_label(&Pubkey::new_unique(), &known), None);
+    }
+
+    #[test]
+    fn test_parse_token_account() {
+        let info = serde_json::json!({
+            "isNative": false,
+            "mint": "So11111111111111111111111111111111111111112",
+            "owner": "11111111111111111111111111111111",
+            "state": "initialized",
+            "tokenAmount": {
+                "amount": "1500000000",
+                "decimals": 9,
+                "uiAmount": 1.5,
+                "uiAmountString": "1.5"
+            },
+            "delegate": "Stake11111111111111111111111111111111111111",
+            "delegatedAmount": { "amount": "500", "decimals": 9, "uiAmountString": "0.0000005" }
+        });
+
+        let account = parse_token_account("addr", &info).unwrap();
+        assert_eq!(account.amount, "1500000000");
+        assert_eq!(account.decimals, 9);
+        assert_eq!(account.ui_amount, "1.5");
+        assert_eq!(account.delegated_amount.as_deref(), Some("500"));
+        assert_eq!(account.state, "initialized");
+
+        assert!(parse_token_account("addr", &serde_json::Value::Null).is_none());
+    }
+}
@@ -1,7 +1,10 @@
 //! Blockchain API Module - Minimal version
 //!
-//! Only includes types for now, handlers disabled
+//! Account info lookup and transaction history; submission and program
+//! handlers disabled
 
+pub mod history;
+pub mod info;
 pub mod types;
 
 pub use types::*;
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, State},
     response::Json,
 };
 use base64::{engine::general_purpose, Engine as _};
@@ -10,7 +10,7 @@ use std::str::FromStr;
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::error::{ApiError, Result};
-use crate::models::blockchain::{ProgramInteraction, TransactionStatus, TransactionSubmission};
+use crate::models::blockchain::{TransactionStatus, TransactionSubmission};
 use crate::AppState;
 
 use super::types::*;
@@ -91,70 +91,6 @@ pub async fn submit_transaction(
     Ok(Json(response))
 }
 
-/// Get transaction history for authenticated user
-/// GET /api/blockchain/transactions
-#[utoipa::path(
-    get,
-    path = "/api/blockchain/transactions",
-    tag = "blockchain",
-    params(TransactionQuery),
-    security(("bearer_auth" = [])),
-    responses(
-        (status = 200, description = "List of user's blockchain transactions", body = Vec<TransactionStatus>),
-        (status = 401, description = "Unauthorized"),
-        (status = 500, description = "Internal server error")
-    )
-)]
-pub async fn get_transaction_history(
-    State(_state): State<AppState>,
-    user: AuthenticatedUser,
-    Query(params): Query<TransactionQuery>,
-) -> Result<Json<Vec<TransactionStatus>>> {
-    tracing::info!("Fetching transaction history for user: {}", user.0.sub);
-
-    let limit = params.limit.unwrap_or(50).min(100);
-    let offset = params.offset.unwrap_or(0);
-
-    let mut query = "SELECT * FROM blockchain_transactions WHERE user_id = $1".to_string();
-    let mut param_count = 1;
-    let mut query_params: Vec<String> = vec![user.0.sub.to_string()];
-
-    // Add optional filters
-    if let Some(program_id) = &params.program_id {
-        param_count += 1;
-        query.push_str(&format!(" AND program_id = ${}", param_count));
-        query_params.push(program_id.clone());
-    }
-
-    if let Some(status) = &params.status {
-        param_count += 1;
-        query.push_str(&format!(" AND status = ${}", param_count));
-        query_params.push(status.clone());
-    }
-
-    query.push_str(" ORDER BY created_at DESC");
-    query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
-
-    // Simulate transaction status retrieval
-    // In production, this would query actual blockchain data
-    let transactions = vec![TransactionStatus {
-        signature: "tx_sample_12345".to_string(),
-        status: "confirmed".to_string(),
-        block_height: Some(1000000),
-        confirmation_status: "finalized".to_string(),
-        fee: rust_decimal::Decimal::new(5000, 9), // 0.000005 SOL
-        compute_units_consumed: Some(5000),
-        logs: vec!["Program log: Instruction processed successfully".to_string()],
-        program_interactions: vec![ProgramInteraction {
-            program_id: "EnergyTradingProgram".to_string(),
-            instruction_name: "place_order".to_string(),
-            success: true,
-        }],
-    }];
-
-    Ok(Json(transactions))
-}
-
 /// Get specific transaction status by signature
 /// GET /api/blockchain/transactions/:signature
 #[utoipa::path(
@@ -6,14 +6,59 @@ use validator::Validate;
 /// Query parameters for transaction history
 #[derive(Debug, Deserialize, Validate, ToSchema, IntoParams)]
 pub struct TransactionQuery {
+    /// Program the transaction was submitted to (base58)
     pub program_id: Option<String>,
+    /// pending, confirmed or failed
     pub status: Option<String>,
+    /// Fee payer / signing wallet (base58)
+    pub signer: Option<String>,
+    /// Submitted at or after
     pub start_time: Option<DateTime<Utc>>,
+    /// Submitted before
     pub end_time: Option<DateTime<Utc>>,
+    /// Part of the transaction signature
+    #[validate(length(max = 88))]
+    pub search: Option<String>,
+    /// `next_cursor` of the previous page; takes precedence over `offset`
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100))]
     pub limit: Option<i32>,
+    #[validate(range(min = 0))]
     pub offset: Option<i32>,
 }
 
+/// A transaction in the history, with its decoded instructions
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TransactionHistoryEntry {
+    pub signature: String,
+    pub program_id: String,
+    /// Known program name, e.g. `trading` or `energy-token`
+    pub program: Option<String>,
+    pub instruction_name: Option<String>,
+    pub status: String,
+    pub signer: Option<String>,
+    /// Fee in lamports
+    pub fee: Option<i64>,
+    pub compute_units_consumed: Option<i32>,
+    pub slot: Option<i64>,
+    pub block_time: Option<DateTime<Utc>>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub error_message: Option<String>,
+    /// Empty until the transaction has been found on-chain
+    pub instructions: Vec<crate::services::blockchain::DecodedInstruction>,
+}
+
+/// A page of transaction history
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TransactionHistoryResponse {
+    pub transactions: Vec<TransactionHistoryEntry>,
+    pub total: i64,
+    pub limit: i32,
+    pub offset: i32,
+    /// Cursor of the next page, absent on the last page
+    pub next_cursor: Option<String>,
+}
+
 /// Response for transaction submission
 #[derive(Debug, Serialize, ToSchema)]
 pub struct TransactionResponse {
@@ -27,12 +72,38 @@ pub struct TransactionResponse {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct AccountInfo {
     pub address: String,
+    /// Whether the account exists on-chain
+    pub exists: bool,
     #[schema(value_type = String)]
     pub balance: rust_decimal::Decimal,
+    pub lamports: u64,
     pub executable: bool,
     pub owner: String,
+    /// Known name of the owning program, e.g. `registry` or `energy-token`
+    pub owner_label: Option<String>,
     pub rent_epoch: u64,
     pub data_length: usize,
+    /// Whether the balance covers the rent-exempt minimum for the data size
+    pub rent_exempt: bool,
+    pub rent_exempt_minimum: u64,
+    /// Token accounts owned by this address
+    pub token_accounts: Vec<TokenAccountInfo>,
+}
+
+/// A parsed SPL Token or Token-2022 account
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenAccountInfo {
+    pub address: String,
+    pub mint: String,
+    /// Raw amount in base units
+    pub amount: String,
+    pub decimals: u8,
+    /// Amount scaled by the mint decimals
+    pub ui_amount: String,
+    pub delegate: Option<String>,
+    pub delegated_amount: Option<String>,
+    /// initialized or frozen
+    pub state: String,
 }
 
 /// Network status response
@@ -44,6 +115,10 @@ pub struct NetworkStatus {
     pub tps: f64,
     pub health: String,
     pub version: String,
+    /// RPC read cache hit rates, when the cache is enabled
+    pub rpc_cache: Option<crate::services::blockchain::RpcCacheStats>,
+    /// In-flight and queued transaction submissions per class
+    pub submission_queue: crate::services::blockchain::SubmissionQueueStats,
 }
 
 /// Program interaction request
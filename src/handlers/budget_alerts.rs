@@ -0,0 +1,110 @@
+//! Budget Alerts Handler
+//!
+//! Household spend, consumption and clearing price thresholds, delivered
+//! to the notification inbox when crossed
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::budget_alerts::{BudgetAlert, CreateBudgetAlertRequest, UpdateBudgetAlertRequest};
+use crate::AppState;
+
+/// List budget alerts
+/// GET /api/v1/notifications/budget-alerts
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/budget-alerts",
+    tag = "notifications",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The user's budget alerts", body = Vec<BudgetAlert>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_budget_alerts(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<Vec<BudgetAlert>>> {
+    Ok(Json(state.budget_alerts.list(user.0.sub).await?))
+}
+
+/// Create a budget alert
+/// POST /api/v1/notifications/budget-alerts
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/budget-alerts",
+    tag = "notifications",
+    request_body = CreateBudgetAlertRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Alert created", body = BudgetAlert),
+        (status = 400, description = "Threshold not positive or too many alerts"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn create_budget_alert(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<CreateBudgetAlertRequest>,
+) -> Result<Json<BudgetAlert>> {
+    Ok(Json(state.budget_alerts.create(user.0.sub, payload).await?))
+}
+
+/// Change or pause a budget alert
+/// PUT /api/v1/notifications/budget-alerts/{id}
+///
+/// A new threshold re-arms an alert that already fired this period.
+#[utoipa::path(
+    put,
+    path = "/api/v1/notifications/budget-alerts/{id}",
+    tag = "notifications",
+    params(("id" = Uuid, Path, description = "Alert ID")),
+    request_body = UpdateBudgetAlertRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Alert updated", body = BudgetAlert),
+        (status = 400, description = "Threshold not positive"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Alert not found")
+    )
+)]
+pub async fn update_budget_alert(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(alert_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateBudgetAlertRequest>,
+) -> Result<Json<BudgetAlert>> {
+    Ok(Json(state.budget_alerts.update(user.0.sub, alert_id, payload).await?))
+}
+
+/// Delete a budget alert
+/// DELETE /api/v1/notifications/budget-alerts/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/notifications/budget-alerts/{id}",
+    tag = "notifications",
+    params(("id" = Uuid, Path, description = "Alert ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Alert deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Alert not found")
+    )
+)]
+pub async fn delete_budget_alert(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(alert_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>> {
+    state.budget_alerts.delete(user.0.sub, alert_id).await?;
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Budget alert deleted"
+    })))
+}
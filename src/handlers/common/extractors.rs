@@ -3,9 +3,65 @@
 //! This module provides reusable types and utilities for request validation
 //! and parameter extraction that can be used across handlers.
 
+use axum::{
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::de::DeserializeOwned;
 use uuid::Uuid;
+use validator::Validate;
 
-use crate::error::ApiError;
+use crate::error::{handle_rejection, ApiError, FieldError};
+
+/// JSON body extractor that runs the DTO's `#[validate(...)]` rules.
+///
+/// Type errors (wrong type, unknown enum variant) and rule failures are both
+/// reported as a 422 listing each offending field.
+///
+/// # Example
+/// ```ignore
+/// pub async fn create_order(ValidatedJson(payload): ValidatedJson<CreateOrderRequest>) { ... }
+/// ```
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|rejection| match rejection {
+                JsonRejection::JsonDataError(e) => {
+                    ApiError::FieldErrors(vec![deserialize_field_error(&e.body_text())]).into_response()
+                }
+                other => handle_rejection(other),
+            })?;
+
+        value
+            .validate()
+            .map_err(|e| ApiError::from(e).into_response())?;
+
+        Ok(ValidatedJson(value))
+    }
+}
+
+/// Turn axum's "Failed to deserialize ...: path: message" text into a field error
+fn deserialize_field_error(body_text: &str) -> FieldError {
+    let detail = body_text
+        .split_once(": ")
+        .map(|(_, rest)| rest)
+        .unwrap_or(body_text);
+
+    match detail.split_once(": ") {
+        Some((path, message)) if !path.contains(' ') => FieldError::new(path, "type", message),
+        _ => FieldError::new("body", "type", detail),
+    }
+}
 
 /// Validated UUID helper
 /// 
@@ -225,6 +281,20 @@ mod tests {
         assert_eq!(SortOrder::Desc.as_sql(), "DESC");
     }
 
+    #[test]
+    fn test_deserialize_field_error() {
+        let e = deserialize_field_error(
+            "Failed to deserialize the JSON body into the target type: side: unknown variant `hold`, expected `buy` or `sell` at line 1 column 14",
+        );
+        assert_eq!(e.field, "side");
+        assert!(e.message.starts_with("unknown variant `hold`"));
+
+        let e = deserialize_field_error(
+            "Failed to deserialize the JSON body into the target type: missing field `side` at line 1 column 2",
+        );
+        assert_eq!(e.field, "body");
+    }
+
     #[test]
     fn test_validated_uuid() {
         assert!(ValidatedUuid::parse("550e8400-e29b-41d4-a716-446655440000").is_ok());
@@ -7,5 +7,7 @@ pub mod extractors;
 pub mod response;
 
 // Re-export commonly used types
-pub use extractors::{DateRangeParams, PaginationParams, SearchParams, SortOrder, ValidatedUuid};
-pub use response::{ApiResponse, ListResponse, PaginatedResponse};
+pub use extractors::{
+    DateRangeParams, PaginationParams, SearchParams, SortOrder, ValidatedJson, ValidatedUuid,
+};
+pub use response::{csv_field, ApiResponse, ListResponse, PaginatedResponse};
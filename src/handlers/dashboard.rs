@@ -1,4 +1,5 @@
 use crate::error::{ApiError, Result};
+use crate::services::admin_overview::AdminOverview;
 use crate::services::dashboard::{DashboardMetrics, DashboardService};
 use axum::{extract::State, routing::get, Json, Router};
 
@@ -7,13 +8,14 @@ pub fn v1_dashboard_routes() -> Router<crate::AppState> {
     Router::new().route("/metrics", get(get_dashboard_metrics))
 }
 
-/// Get dashboard metrics
+/// Get dashboard metrics from the read model
 #[utoipa::path(
     get,
     path = "/api/dashboard/metrics",
     tag = "Dashboard",
+    security(("bearer_auth" = [])),
     responses(
-        (status = 200, description = "Dashboard metrics", body = DashboardMetrics),
+        (status = 200, description = "Dashboard metrics, refreshed after each event processor cycle", body = DashboardMetrics),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -26,3 +28,21 @@ pub async fn get_dashboard_metrics(
         .map_err(|e| ApiError::Internal(e.to_string()))?;
     Ok(Json(metrics))
 }
+
+/// Get the admin operational overview
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/overview",
+    tag = "Dashboard",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Aggregated platform state, cached for 15s", body = AdminOverview),
+        (status = 403, description = "Admin access required"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_admin_overview(
+    State(state): State<crate::AppState>,
+) -> Result<Json<AdminOverview>> {
+    Ok(Json(state.admin_overview.overview().await?))
+}
@@ -0,0 +1,174 @@
+//! Data Exports Handler
+//!
+//! Asynchronous exports of the caller's readings, trades and audit trail,
+//! with export history, quota and signed downloads
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use tracing::{error, warn};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::invoices::InvoiceDownloadQuery;
+use crate::services::data_exports::{CreateDataExportRequest, DataExport, DataExportList};
+use crate::services::invoicing::SignedDownloadUrl;
+use crate::services::jobs::JobKind;
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DataExportListQuery {
+    /// Number of exports (default 20, max 100)
+    pub limit: Option<i64>,
+}
+
+/// Request an export; the file is built in the background
+/// POST /api/v1/exports
+#[utoipa::path(
+    post,
+    path = "/api/v1/exports",
+    tag = "exports",
+    request_body = CreateDataExportRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 202, description = "Export queued", body = DataExport),
+        (status = 400, description = "Invalid date range"),
+        (status = 401, description = "Unauthorized"),
+        (status = 429, description = "Daily quota, pending export limit or concurrent export limit reached")
+    )
+)]
+pub async fn create_data_export(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(request): Json<CreateDataExportRequest>,
+) -> Result<(StatusCode, Json<DataExport>)> {
+    let export = state.data_exports.request(user.0.sub, request).await?;
+    state.jobs.wake(JobKind::DataExports);
+
+    Ok((StatusCode::ACCEPTED, Json(export)))
+}
+
+/// List the caller's exports with the remaining quota
+/// GET /api/v1/exports
+#[utoipa::path(
+    get,
+    path = "/api/v1/exports",
+    tag = "exports",
+    params(DataExportListQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Exports, newest first, and quota", body = DataExportList),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_data_exports(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<DataExportListQuery>,
+) -> Result<Json<DataExportList>> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let exports = state.data_exports.list(user.0.sub, limit).await?;
+    let quota = state.data_exports.quota(user.0.sub).await?;
+
+    Ok(Json(DataExportList { exports, quota }))
+}
+
+/// Get one of the caller's exports
+/// GET /api/v1/exports/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/exports/{id}",
+    tag = "exports",
+    params(("id" = Uuid, Path, description = "Export ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Export", body = DataExport),
+        (status = 404, description = "Export not found")
+    )
+)]
+pub async fn get_data_export(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(export_id): Path<Uuid>,
+) -> Result<Json<DataExport>> {
+    Ok(Json(state.data_exports.get(user.0.sub, export_id).await?))
+}
+
+/// Get a short-lived signed download URL for a ready export
+/// GET /api/v1/exports/{id}/download-url
+#[utoipa::path(
+    get,
+    path = "/api/v1/exports/{id}/download-url",
+    tag = "exports",
+    params(("id" = Uuid, Path, description = "Export ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Signed download URL", body = SignedDownloadUrl),
+        (status = 404, description = "Export not found"),
+        (status = 409, description = "Export not ready or expired")
+    )
+)]
+pub async fn get_data_export_download_url(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(export_id): Path<Uuid>,
+) -> Result<Json<SignedDownloadUrl>> {
+    let export = state.data_exports.get(user.0.sub, export_id).await?;
+
+    Ok(Json(state.data_exports.signed_download_url(&export)?))
+}
+
+/// Download an export using a signed URL (no bearer token required)
+/// GET /api/v1/exports/{id}/download
+#[utoipa::path(
+    get,
+    path = "/api/v1/exports/{id}/download",
+    tag = "exports",
+    params(("id" = Uuid, Path, description = "Export ID"), InvoiceDownloadQuery),
+    responses(
+        (status = 200, description = "CSV or ZIP file download"),
+        (status = 403, description = "Invalid or expired signature"),
+        (status = 404, description = "Export not found or expired")
+    )
+)]
+pub async fn download_data_export(
+    State(state): State<AppState>,
+    Path(export_id): Path<Uuid>,
+    Query(params): Query<InvoiceDownloadQuery>,
+) -> Response {
+    if !state
+        .data_exports
+        .verify_download(export_id, params.expires, &params.signature)
+    {
+        warn!("Rejected export download for {}: invalid or expired signature", export_id);
+        return (StatusCode::FORBIDDEN, "Invalid or expired download link").into_response();
+    }
+
+    let (export, bytes) = match state.data_exports.load(export_id).await {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            error!("Failed to load export {}: {}", export_id, e);
+            return e.into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, export.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", export.file_name()),
+            ),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        bytes,
+    )
+        .into_response()
+}
@@ -0,0 +1,161 @@
+//! Admin Data Fix Handlers
+//!
+//! Guarded repairs of drifted data. Every endpoint is a dry run unless
+//! `dry_run` is explicitly false, and returns the corrections it found.
+
+use axum::{extract::State, Json};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::data_fixes::DataFixReport;
+use crate::AppState;
+
+fn default_dry_run() -> bool {
+    true
+}
+
+fn default_stuck_minutes() -> i32 {
+    30
+}
+
+/// Recompute epoch statistics
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct EpochStatsFixRequest {
+    /// One epoch; all cleared and settled epochs when omitted
+    pub epoch_id: Option<Uuid>,
+    /// Report without writing (default true)
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+/// Repair scoped to one user or to everyone
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UserDataFixRequest {
+    /// One user; all users when omitted
+    pub user_id: Option<Uuid>,
+    /// Report without writing (default true)
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+/// Fail settlements stuck in processing
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct StuckSettlementsFixRequest {
+    /// Minutes without progress after which a processing settlement is stuck (default 30)
+    #[serde(default = "default_stuck_minutes")]
+    #[validate(range(min = 5, max = 10080))]
+    pub older_than_minutes: i32,
+    /// Report without writing (default true)
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+/// Recompute epoch volume, match count and clearing price from order matches
+/// POST /api/v1/admin/data-fixes/epoch-stats
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/data-fixes/epoch-stats",
+    tag = "admin",
+    request_body = EpochStatsFixRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Corrections found, and applied unless a dry run", body = DataFixReport),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn fix_epoch_stats(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<EpochStatsFixRequest>,
+) -> Result<Json<DataFixReport>> {
+    Ok(Json(
+        state
+            .data_fixes
+            .recompute_epoch_stats(user.0.sub, payload.epoch_id, payload.dry_run)
+            .await?,
+    ))
+}
+
+/// Resync order filled amounts (and open order statuses) from order matches
+/// POST /api/v1/admin/data-fixes/order-fills
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/data-fixes/order-fills",
+    tag = "admin",
+    request_body = UserDataFixRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Corrections found, and applied unless a dry run", body = DataFixReport),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn fix_order_fills(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<UserDataFixRequest>,
+) -> Result<Json<DataFixReport>> {
+    Ok(Json(
+        state
+            .data_fixes
+            .resync_order_fills(user.0.sub, payload.user_id, payload.dry_run)
+            .await?,
+    ))
+}
+
+/// Rebuild prepaid balances from the prepaid ledger
+/// POST /api/v1/admin/data-fixes/prepaid-balances
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/data-fixes/prepaid-balances",
+    tag = "admin",
+    request_body = UserDataFixRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Corrections found, and applied unless a dry run", body = DataFixReport),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn fix_prepaid_balances(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<UserDataFixRequest>,
+) -> Result<Json<DataFixReport>> {
+    Ok(Json(
+        state
+            .data_fixes
+            .rebuild_prepaid_balances(user.0.sub, payload.user_id, payload.dry_run)
+            .await?,
+    ))
+}
+
+/// Mark settlements stuck in processing as failed
+/// POST /api/v1/admin/data-fixes/stuck-settlements
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/data-fixes/stuck-settlements",
+    tag = "admin",
+    request_body = StuckSettlementsFixRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Corrections found, and applied unless a dry run", body = DataFixReport),
+        (status = 422, description = "Request validation failed"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn fix_stuck_settlements(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<StuckSettlementsFixRequest>,
+) -> Result<Json<DataFixReport>> {
+    Ok(Json(
+        state
+            .data_fixes
+            .fail_stuck_settlements(user.0.sub, payload.older_than_minutes, payload.dry_run)
+            .await?,
+    ))
+}
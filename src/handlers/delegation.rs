@@ -0,0 +1,203 @@
+//! App Delegation Handlers
+//!
+//! OAuth2-style authorization code flow for third-party apps: admins
+//! register apps, users consent to a subset of the app's scopes, the app
+//! redeems the code for a scoped token, and users list and revoke grants.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::delegation::{
+    AuthorizationRequest, ConsentDecision, ConsentDetails, ConsentRedirect, DelegatedApp, DelegatedTokenResponse,
+    DelegationGrant, RegisterAppRequest, RegisteredApp, TokenRequest,
+};
+use crate::AppState;
+
+/// List registered third-party apps
+/// GET /api/v1/admin/delegated-apps
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/delegated-apps",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Apps, newest first", body = Vec<DelegatedApp>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_delegated_apps(State(state): State<AppState>) -> Result<Json<Vec<DelegatedApp>>> {
+    Ok(Json(state.delegation.list_apps().await?))
+}
+
+/// Register a third-party app
+/// POST /api/v1/admin/delegated-apps
+///
+/// Returns the app's client secret, which is not shown again.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/delegated-apps",
+    tag = "admin",
+    request_body = RegisterAppRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "App registered", body = RegisteredApp),
+        (status = 403, description = "Admin access required"),
+        (status = 422, description = "Invalid name, redirect URIs or scopes")
+    )
+)]
+pub async fn register_delegated_app(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<RegisterAppRequest>,
+) -> Result<(StatusCode, Json<RegisteredApp>)> {
+    let registered = state.delegation.register_app(user.0.sub, payload).await?;
+    Ok((StatusCode::CREATED, Json(registered)))
+}
+
+/// Disable a third-party app
+/// POST /api/v1/admin/delegated-apps/{id}/disable
+///
+/// The app's tokens stop working at once and users can no longer consent
+/// to it. Disabling an already disabled app is a no-op.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/delegated-apps/{id}/disable",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "App ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "App disabled", body = DelegatedApp),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "App not found")
+    )
+)]
+pub async fn disable_delegated_app(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(app_id): Path<Uuid>,
+) -> Result<Json<DelegatedApp>> {
+    Ok(Json(state.delegation.disable_app(user.0.sub, app_id).await?))
+}
+
+/// Show the consent screen for an app's authorization request
+/// GET /api/v1/oauth/authorize
+#[utoipa::path(
+    get,
+    path = "/api/v1/oauth/authorize",
+    tag = "auth",
+    params(AuthorizationRequest),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "App and requested scopes", body = ConsentDetails),
+        (status = 401, description = "Not signed in"),
+        (status = 404, description = "Unknown or disabled app"),
+        (status = 422, description = "Unregistered redirect URI, or scope the app may not request")
+    )
+)]
+pub async fn get_consent(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(request): Query<AuthorizationRequest>,
+) -> Result<Json<ConsentDetails>> {
+    Ok(Json(state.delegation.consent_details(user.0.sub, &request).await?))
+}
+
+/// Approve or deny an app's authorization request
+/// POST /api/v1/oauth/authorize
+///
+/// Approval grants the requested scopes (replacing any earlier grant to
+/// the app) and issues a short-lived authorization code.
+#[utoipa::path(
+    post,
+    path = "/api/v1/oauth/authorize",
+    tag = "auth",
+    request_body = ConsentDecision,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Where to send the user's browser", body = ConsentRedirect),
+        (status = 401, description = "Not signed in"),
+        (status = 404, description = "Unknown or disabled app"),
+        (status = 422, description = "Unregistered redirect URI, or scope the app may not request")
+    )
+)]
+pub async fn submit_consent(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(decision): Json<ConsentDecision>,
+) -> Result<Json<ConsentRedirect>> {
+    Ok(Json(state.delegation.decide(user.0.sub, &decision).await?))
+}
+
+/// Exchange an authorization code for a delegated token
+/// POST /api/v1/oauth/token
+///
+/// Called by the app's backend with its client secret. Each code can be
+/// redeemed once.
+#[utoipa::path(
+    post,
+    path = "/api/v1/oauth/token",
+    tag = "auth",
+    request_body = TokenRequest,
+    responses(
+        (status = 200, description = "Scoped access token", body = DelegatedTokenResponse),
+        (status = 400, description = "Unsupported grant type, or code invalid, expired, used or issued for another redirect URI"),
+        (status = 401, description = "Unknown client or wrong client secret")
+    )
+)]
+pub async fn exchange_token(
+    State(state): State<AppState>,
+    Json(request): Json<TokenRequest>,
+) -> Result<Json<DelegatedTokenResponse>> {
+    Ok(Json(state.delegation.exchange_code(&request).await?))
+}
+
+/// List the apps the current user has granted access
+/// GET /api/v1/oauth/grants
+#[utoipa::path(
+    get,
+    path = "/api/v1/oauth/grants",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Active grants", body = Vec<DelegationGrant>),
+        (status = 401, description = "Not signed in")
+    )
+)]
+pub async fn list_grants(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<Vec<DelegationGrant>>> {
+    Ok(Json(state.delegation.list_grants(user.0.sub).await?))
+}
+
+/// Revoke an app's access
+/// DELETE /api/v1/oauth/grants/{id}
+///
+/// The app's tokens stop working at once.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/oauth/grants/{id}",
+    tag = "auth",
+    params(("id" = Uuid, Path, description = "Grant ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Grant revoked"),
+        (status = 401, description = "Not signed in"),
+        (status = 404, description = "No active grant with this ID")
+    )
+)]
+pub async fn revoke_grant(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(grant_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    state.delegation.revoke_grant(user.0.sub, grant_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,24 @@
+//! Deployment Profile Handlers
+//!
+//! Branding, token mint, program IDs and enabled modules of the tenant this
+//! instance serves, for clients theming themselves and checking they talk to
+//! the deployment they expect.
+
+use axum::{extract::State, Json};
+
+use crate::services::deployment_profile::DeploymentProfile;
+use crate::AppState;
+
+/// Profile of this deployment
+/// GET /api/v1/public/profile
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/profile",
+    tag = "status",
+    responses(
+        (status = 200, description = "Deployment branding, token mint, program IDs, enabled modules and market timezone", body = DeploymentProfile)
+    )
+)]
+pub async fn get_deployment_profile(State(state): State<AppState>) -> Json<DeploymentProfile> {
+    Json(state.deployment.profile().clone())
+}
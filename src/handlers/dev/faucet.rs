@@ -24,15 +24,20 @@ pub struct FaucetResponse {
 }
 
 /// Request funds from the developer faucet
-/// POST /api/dev/faucet
+/// POST /api/v1/dev/faucet
+///
+/// Only available while the sandbox is enabled on devnet or localnet.
 #[utoipa::path(
     post,
-    path = "/api/dev/faucet",
+    path = "/api/v1/dev/faucet",
     tag = "dev",
     request_body = FaucetRequest,
+    security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Funds requested successfully", body = FaucetResponse),
         (status = 400, description = "Invalid request"),
+        (status = 403, description = "Sandbox disabled or not on devnet/localnet"),
+        (status = 429, description = "Wallet airdrop limit reached"),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -42,6 +47,9 @@ pub async fn request_faucet(
 ) -> Result<Json<FaucetResponse>> {
     tracing::info!("Faucet request for wallet: {}", payload.wallet_address);
 
+    // Only served by the devnet/localnet sandbox
+    state.sandbox.cluster()?;
+
     let wallet_pubkey = Pubkey::from_str(&payload.wallet_address)
         .map_err(|_| ApiError::BadRequest("Invalid wallet address".to_string()))?;
 
@@ -49,25 +57,12 @@ pub async fn request_faucet(
     let mut token_sig = None;
     let mut messages = Vec::new();
 
-    // 1. Airdrop SOL
+    // 1. Airdrop SOL (rate limited per wallet)
     if let Some(amount) = payload.amount_sol {
         if amount > 0.0 {
-            match state
-                .wallet_service
-                .request_airdrop(&wallet_pubkey, amount)
-                .await
-            {
-                Ok(sig) => {
-                    sol_sig = Some(sig.to_string());
-                    messages.push(format!("Airdropped {} SOL", amount));
-                }
-                Err(e) => {
-                    tracing::error!("Faucet Airdrop failed: {}", e);
-                    // Don't fail the whole request, but note it? 
-                    // Or fail? Let's fail if requested explicitly.
-                    return Err(ApiError::Internal(format!("Failed to airdrop SOL: {}", e)));
-                }
-            }
+            let sig = state.sandbox.airdrop(&wallet_pubkey, amount).await?;
+            sol_sig = Some(sig);
+            messages.push(format!("Airdropped {} SOL", amount));
         }
     }
 
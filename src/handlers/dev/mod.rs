@@ -1,2 +1,3 @@
 pub mod faucet;
 pub mod metrics;
+pub mod sandbox;
@@ -0,0 +1,271 @@
+//! Sandbox Handlers
+//!
+//! Devnet/localnet sandbox: rate-limited airdrops, funded test users with
+//! wallets and verified meters, and scripted scenarios that return the IDs
+//! of everything they created.
+
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use axum::{extract::State, Json};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::database::schema::types::{OrderSide, OrderType};
+use crate::error::{ApiError, Result};
+use crate::handlers::trading::orders::create::submit_order;
+use crate::models::trading::CreateOrderRequest;
+use crate::services::sandbox::{SandboxCluster, TestUser, TestUserSpec};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SandboxAirdropRequest {
+    pub wallet_address: String,
+    pub amount_sol: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SandboxAirdropResponse {
+    pub cluster: SandboxCluster,
+    pub tx_signature: String,
+}
+
+/// One scenario step. Users are created under an alias that later steps
+/// refer to.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ScenarioStep {
+    CreateUser {
+        alias: String,
+        #[serde(default)]
+        user: TestUserSpec,
+    },
+    Airdrop {
+        user: String,
+        amount_sol: f64,
+    },
+    MintTokens {
+        user: String,
+        #[schema(value_type = String, example = "50")]
+        kwh: Decimal,
+    },
+    PlaceOrder {
+        user: String,
+        side: OrderSide,
+        #[schema(value_type = String, example = "10")]
+        energy_amount: Decimal,
+        /// Omit for a market order
+        #[schema(value_type = Option<String>, example = "0.15")]
+        price_per_kwh: Option<Decimal>,
+        /// Market code; defaults to the main energy spot market
+        market: Option<String>,
+    },
+}
+
+impl ScenarioStep {
+    fn action(&self) -> &'static str {
+        match self {
+            ScenarioStep::CreateUser { .. } => "create_user",
+            ScenarioStep::Airdrop { .. } => "airdrop",
+            ScenarioStep::MintTokens { .. } => "mint_tokens",
+            ScenarioStep::PlaceOrder { .. } => "place_order",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RunScenarioRequest {
+    pub steps: Vec<ScenarioStep>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScenarioFailure {
+    /// Zero-based index of the failed step
+    pub step: usize,
+    pub action: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ScenarioResult {
+    pub completed_steps: usize,
+    /// Created users by alias
+    pub users: BTreeMap<String, TestUser>,
+    pub order_ids: Vec<Uuid>,
+    pub airdrop_signatures: Vec<String>,
+    pub mint_signatures: Vec<String>,
+    /// Set when a step failed; the steps after it were not run
+    pub failure: Option<ScenarioFailure>,
+}
+
+/// Airdrop SOL from the sandbox faucet
+/// POST /api/v1/dev/sandbox/airdrop
+#[utoipa::path(
+    post,
+    path = "/api/v1/dev/sandbox/airdrop",
+    tag = "dev",
+    request_body = SandboxAirdropRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Airdrop confirmed", body = SandboxAirdropResponse),
+        (status = 403, description = "Sandbox disabled or not on devnet/localnet"),
+        (status = 422, description = "Invalid wallet or amount"),
+        (status = 429, description = "Wallet airdrop limit reached")
+    )
+)]
+pub async fn sandbox_airdrop(
+    State(state): State<AppState>,
+    Json(payload): Json<SandboxAirdropRequest>,
+) -> Result<Json<SandboxAirdropResponse>> {
+    let cluster = state.sandbox.cluster()?;
+    let wallet = Pubkey::from_str(&payload.wallet_address)
+        .map_err(|_| ApiError::validation_field("wallet_address", "Invalid wallet address"))?;
+    let tx_signature = state.sandbox.airdrop(&wallet, payload.amount_sol).await?;
+    Ok(Json(SandboxAirdropResponse { cluster, tx_signature }))
+}
+
+/// Create a funded test user
+/// POST /api/v1/dev/sandbox/users
+///
+/// Creates a verified account with a custodial wallet, optionally airdrops
+/// SOL, mints energy tokens, credits fiat and registers verified meters.
+#[utoipa::path(
+    post,
+    path = "/api/v1/dev/sandbox/users",
+    tag = "dev",
+    request_body = TestUserSpec,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "User created with credentials and resource IDs", body = TestUser),
+        (status = 403, description = "Sandbox disabled or not on devnet/localnet"),
+        (status = 422, description = "Invalid role, amounts or meter count")
+    )
+)]
+pub async fn create_sandbox_user(
+    State(state): State<AppState>,
+    Json(payload): Json<TestUserSpec>,
+) -> Result<Json<TestUser>> {
+    Ok(Json(provision_user(&state, &payload).await?))
+}
+
+/// Run a sandbox scenario
+/// POST /api/v1/dev/sandbox/scenarios
+///
+/// Runs the steps in order and stops at the first failure. Everything
+/// created up to that point is returned either way.
+#[utoipa::path(
+    post,
+    path = "/api/v1/dev/sandbox/scenarios",
+    tag = "dev",
+    request_body = RunScenarioRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Scenario run with created resource IDs", body = ScenarioResult),
+        (status = 403, description = "Sandbox disabled or not on devnet/localnet"),
+        (status = 422, description = "Too many steps")
+    )
+)]
+pub async fn run_scenario(
+    State(state): State<AppState>,
+    Json(payload): Json<RunScenarioRequest>,
+) -> Result<Json<ScenarioResult>> {
+    state.sandbox.cluster()?;
+    let max_steps = state.sandbox.max_scenario_steps();
+    if payload.steps.len() > max_steps {
+        return Err(ApiError::validation_field(
+            "steps",
+            format!("At most {} steps per scenario", max_steps),
+        ));
+    }
+
+    let mut result = ScenarioResult::default();
+    let mut wallets: HashMap<String, (Uuid, Pubkey)> = HashMap::new();
+
+    for (index, step) in payload.steps.iter().enumerate() {
+        let outcome = run_step(&state, step, &mut result, &mut wallets).await;
+        if let Err(e) = outcome {
+            result.failure = Some(ScenarioFailure {
+                step: index,
+                action: step.action().to_string(),
+                message: e.to_string(),
+            });
+            break;
+        }
+        result.completed_steps += 1;
+    }
+
+    Ok(Json(result))
+}
+
+async fn run_step(
+    state: &AppState,
+    step: &ScenarioStep,
+    result: &mut ScenarioResult,
+    wallets: &mut HashMap<String, (Uuid, Pubkey)>,
+) -> Result<()> {
+    match step {
+        ScenarioStep::CreateUser { alias, user } => {
+            if wallets.contains_key(alias) {
+                return Err(ApiError::validation_field("alias", format!("Alias '{}' already used", alias)));
+            }
+            let created = provision_user(state, user).await?;
+            let wallet = Pubkey::from_str(&created.wallet_address)
+                .map_err(|e| ApiError::Internal(format!("Invalid sandbox wallet: {}", e)))?;
+            wallets.insert(alias.clone(), (created.user_id, wallet));
+            result.users.insert(alias.clone(), created);
+        }
+        ScenarioStep::Airdrop { user, amount_sol } => {
+            let (_, wallet) = resolve(wallets, user)?;
+            let signature = state.sandbox.airdrop(&wallet, *amount_sol).await?;
+            result.airdrop_signatures.push(signature);
+        }
+        ScenarioStep::MintTokens { user, kwh } => {
+            let (_, wallet) = resolve(wallets, user)?;
+            let signature = state.sandbox.mint_kwh(&wallet, *kwh).await?;
+            result.mint_signatures.push(signature);
+        }
+        ScenarioStep::PlaceOrder { user, side, energy_amount, price_per_kwh, market } => {
+            let (user_id, _) = resolve(wallets, user)?;
+            let market_id = match market {
+                Some(code) => Some(state.markets.by_code(code).await?.id),
+                None => None,
+            };
+            let payload = CreateOrderRequest {
+                side: *side,
+                energy_amount: *energy_amount,
+                price_per_kwh: *price_per_kwh,
+                order_type: if price_per_kwh.is_some() { OrderType::Limit } else { OrderType::Market },
+                expiry_time: None,
+                zone_id: None,
+                meter_id: None,
+                signature: None,
+                timestamp: None,
+                session_token: None,
+                market_id,
+            };
+            payload.validate()?;
+            let placed = submit_order(state, user_id, payload).await?;
+            result.order_ids.push(placed.id);
+        }
+    }
+    Ok(())
+}
+
+fn resolve(wallets: &HashMap<String, (Uuid, Pubkey)>, alias: &str) -> Result<(Uuid, Pubkey)> {
+    wallets
+        .get(alias)
+        .copied()
+        .ok_or_else(|| ApiError::validation_field("user", format!("Unknown user alias '{}'", alias)))
+}
+
+/// Create a test user and attach an access token for it
+async fn provision_user(state: &AppState, spec: &TestUserSpec) -> Result<TestUser> {
+    let mut user = state.sandbox.create_test_user(spec).await?;
+    let claims = crate::auth::Claims::new(user.user_id, user.username.clone(), user.role.clone());
+    user.access_token = Some(state.jwt_service.encode_token(&claims)?);
+    Ok(user)
+}
@@ -0,0 +1,165 @@
+//! Emission Factor Handler
+//!
+//! Admin management of the grid emission factors used to estimate CO2
+//! savings on the dashboard and in community reports
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::emission_factors::{EmissionFactor, EmissionFactorFields, FactorProvenance};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct EmissionFactorRequest {
+    /// Grid region code (e.g. TH, TH-MEA)
+    #[validate(length(min = 1, max = 32))]
+    pub region: String,
+    /// Year the factor applies from
+    pub year: i32,
+    /// kg CO2 per kWh of grid electricity displaced
+    pub kg_co2_per_kwh: f64,
+    /// Publication or dataset the grid mix was taken from
+    #[validate(length(max = 500), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub grid_mix_source: String,
+    #[validate(length(max = 1000))]
+    pub notes: Option<String>,
+}
+
+impl From<EmissionFactorRequest> for EmissionFactorFields {
+    fn from(request: EmissionFactorRequest) -> Self {
+        Self {
+            region: request.region,
+            year: request.year,
+            kg_co2_per_kwh: request.kg_co2_per_kwh,
+            grid_mix_source: request.grid_mix_source,
+            notes: request.notes,
+        }
+    }
+}
+
+/// Factor table and the factor new figures are computed with
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmissionFactorOverview {
+    /// Region the platform's CO2 figures are computed for
+    pub region: String,
+    pub current: FactorProvenance,
+    pub factors: Vec<EmissionFactor>,
+}
+
+/// List emission factors
+/// GET /api/v1/admin/emission-factors
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/emission-factors",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Factors by region and year, and the factor in effect", body = EmissionFactorOverview),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_emission_factors(State(state): State<AppState>) -> Result<Json<EmissionFactorOverview>> {
+    let factors = state.emission_factors.list().await?;
+    Ok(Json(EmissionFactorOverview {
+        region: state.emission_factors.region().to_string(),
+        current: state.emission_factors.current().await,
+        factors,
+    }))
+}
+
+/// Add an emission factor
+/// POST /api/v1/admin/emission-factors
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/emission-factors",
+    tag = "admin",
+    request_body = EmissionFactorRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Factor added", body = EmissionFactor),
+        (status = 403, description = "Admin access required"),
+        (status = 409, description = "A factor for this region and year already exists"),
+        (status = 422, description = "Invalid region, year, factor or source")
+    )
+)]
+pub async fn create_emission_factor(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<EmissionFactorRequest>,
+) -> Result<Json<EmissionFactor>> {
+    Ok(Json(
+        state
+            .emission_factors
+            .create(user.0.sub, payload.into(), &state.audit_logger)
+            .await?,
+    ))
+}
+
+/// Update an emission factor
+/// PUT /api/v1/admin/emission-factors/{id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/emission-factors/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Emission factor ID")),
+    request_body = EmissionFactorRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Factor updated", body = EmissionFactor),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Factor not found"),
+        (status = 409, description = "A factor for this region and year already exists"),
+        (status = 422, description = "Invalid region, year, factor or source")
+    )
+)]
+pub async fn update_emission_factor(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(factor_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<EmissionFactorRequest>,
+) -> Result<Json<EmissionFactor>> {
+    Ok(Json(
+        state
+            .emission_factors
+            .update(user.0.sub, factor_id, payload.into(), &state.audit_logger)
+            .await?,
+    ))
+}
+
+/// Delete an emission factor
+/// DELETE /api/v1/admin/emission-factors/{id}
+///
+/// Figures already computed keep the factor recorded with them.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/emission-factors/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Emission factor ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Deleted factor", body = EmissionFactor),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Factor not found")
+    )
+)]
+pub async fn delete_emission_factor(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(factor_id): Path<Uuid>,
+) -> Result<Json<EmissionFactor>> {
+    Ok(Json(
+        state
+            .emission_factors
+            .delete(user.0.sub, factor_id, &state.audit_logger)
+            .await?,
+    ))
+}
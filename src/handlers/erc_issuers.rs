@@ -0,0 +1,167 @@
+//! ERC Issuer Handler
+//!
+//! Admin management of the issuers authorized to sign renewable energy
+//! certificates and their issuance policy
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::erc::issuers::IssuerFields;
+use crate::services::erc::ErcIssuer;
+use crate::AppState;
+
+fn default_vintage_days() -> i32 {
+    365
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateIssuerRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    /// Base58 public key of the issuer's signing key
+    pub signing_key: String,
+    /// Renewable sources the issuer may certify (e.g. Solar, Wind)
+    pub allowed_sources: Vec<String>,
+    /// Days of metered production a certificate may cover
+    #[serde(default = "default_vintage_days")]
+    pub vintage_days: i32,
+    /// Sign certificates issued automatically at settlement
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateIssuerRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub allowed_sources: Vec<String>,
+    pub vintage_days: i32,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+/// List certificate issuers
+/// GET /api/v1/admin/erc-issuers
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/erc-issuers",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Issuers, active first", body = Vec<ErcIssuer>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_issuers(State(state): State<AppState>) -> Result<Json<Vec<ErcIssuer>>> {
+    Ok(Json(state.erc_service.issuers().list().await?))
+}
+
+/// Register a certificate issuer
+/// POST /api/v1/admin/erc-issuers
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/erc-issuers",
+    tag = "admin",
+    request_body = CreateIssuerRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Issuer registered", body = ErcIssuer),
+        (status = 403, description = "Admin access required"),
+        (status = 409, description = "Signing key already registered"),
+        (status = 422, description = "Invalid signing key, sources or vintage window")
+    )
+)]
+pub async fn create_issuer(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<CreateIssuerRequest>,
+) -> Result<Json<ErcIssuer>> {
+    let fields = IssuerFields {
+        name: payload.name,
+        allowed_sources: payload.allowed_sources,
+        vintage_days: payload.vintage_days,
+        is_default: payload.is_default,
+    };
+    Ok(Json(
+        state
+            .erc_service
+            .issuers()
+            .create(user.0.sub, &payload.signing_key, fields, &state.audit_logger)
+            .await?,
+    ))
+}
+
+/// Update a certificate issuer's policy
+/// PUT /api/v1/admin/erc-issuers/{id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/erc-issuers/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Issuer ID")),
+    request_body = UpdateIssuerRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Issuer updated", body = ErcIssuer),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Issuer not found"),
+        (status = 422, description = "Invalid sources or vintage window")
+    )
+)]
+pub async fn update_issuer(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(issuer_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateIssuerRequest>,
+) -> Result<Json<ErcIssuer>> {
+    let fields = IssuerFields {
+        name: payload.name,
+        allowed_sources: payload.allowed_sources,
+        vintage_days: payload.vintage_days,
+        is_default: payload.is_default,
+    };
+    Ok(Json(
+        state
+            .erc_service
+            .issuers()
+            .update(user.0.sub, issuer_id, fields, &state.audit_logger)
+            .await?,
+    ))
+}
+
+/// Revoke a certificate issuer
+/// POST /api/v1/admin/erc-issuers/{id}/revoke
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/erc-issuers/{id}/revoke",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Issuer ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Issuer revoked", body = ErcIssuer),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Issuer not found"),
+        (status = 409, description = "Issuer already revoked")
+    )
+)]
+pub async fn revoke_issuer(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(issuer_id): Path<Uuid>,
+) -> Result<Json<ErcIssuer>> {
+    Ok(Json(
+        state
+            .erc_service
+            .issuers()
+            .revoke(user.0.sub, issuer_id, &state.audit_logger)
+            .await?,
+    ))
+}
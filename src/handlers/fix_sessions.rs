@@ -0,0 +1,164 @@
+//! FIX Session Handlers
+//!
+//! Admin provisioning of FIX gateway sessions: CompIDs and credentials, the
+//! user orders are placed as, enable/disable, and sequence number resets
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::fix_sessions::{FixSession, FixSessionType};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateFixSessionRequest {
+    /// Counterparty SenderCompID (letters, digits, '-', '_' or '.')
+    #[validate(length(min = 1, max = 64))]
+    pub sender_comp_id: String,
+    /// User the session trades as
+    pub user_id: Uuid,
+    pub session_type: FixSessionType,
+    /// Sent by the counterparty in Password (554) at Logon
+    #[validate(length(min = 12, max = 128))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ResetFixSequenceRequest {
+    #[validate(range(min = 1))]
+    pub next_in_seq: i64,
+    #[validate(range(min = 1))]
+    pub next_out_seq: i64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetFixSessionActiveRequest {
+    pub is_active: bool,
+}
+
+/// List FIX sessions
+/// GET /api/v1/admin/fix/sessions
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/fix/sessions",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Sessions with sequence numbers and logon state", body = Vec<FixSession>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_sessions(State(state): State<AppState>) -> Result<Json<Vec<FixSession>>> {
+    Ok(Json(state.fix_sessions.list().await?))
+}
+
+/// Create a FIX session
+/// POST /api/v1/admin/fix/sessions
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/fix/sessions",
+    tag = "admin",
+    request_body = CreateFixSessionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Session created", body = FixSession),
+        (status = 403, description = "Admin access required"),
+        (status = 409, description = "CompID already in use"),
+        (status = 422, description = "Invalid CompID, user or password")
+    )
+)]
+pub async fn create_session(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<CreateFixSessionRequest>,
+) -> Result<Json<FixSession>> {
+    Ok(Json(
+        state
+            .fix_sessions
+            .create(
+                user.0.sub,
+                &payload.sender_comp_id,
+                payload.user_id,
+                payload.session_type,
+                &payload.password,
+                &state.audit_logger,
+            )
+            .await?,
+    ))
+}
+
+/// Reset a FIX session's sequence numbers
+/// PUT /api/v1/admin/fix/sessions/{id}/sequence
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/fix/sessions/{id}/sequence",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "FIX session ID")),
+    request_body = ResetFixSequenceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Sequence numbers set", body = FixSession),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "FIX session not found"),
+        (status = 409, description = "Session is logged on")
+    )
+)]
+pub async fn reset_sequence(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(session_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ResetFixSequenceRequest>,
+) -> Result<Json<FixSession>> {
+    Ok(Json(
+        state
+            .fix_sessions
+            .reset_sequence(
+                user.0.sub,
+                session_id,
+                payload.next_in_seq,
+                payload.next_out_seq,
+                &state.audit_logger,
+            )
+            .await?,
+    ))
+}
+
+/// Enable or disable a FIX session
+/// PUT /api/v1/admin/fix/sessions/{id}/active
+///
+/// Disabled sessions are refused at Logon; a connected session stays up
+/// until it logs out.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/fix/sessions/{id}/active",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "FIX session ID")),
+    request_body = SetFixSessionActiveRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Session updated", body = FixSession),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "FIX session not found")
+    )
+)]
+pub async fn set_session_active(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(session_id): Path<Uuid>,
+    Json(payload): Json<SetFixSessionActiveRequest>,
+) -> Result<Json<FixSession>> {
+    Ok(Json(
+        state
+            .fix_sessions
+            .set_active(user.0.sub, session_id, payload.is_active, &state.audit_logger)
+            .await?,
+    ))
+}
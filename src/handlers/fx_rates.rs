@@ -0,0 +1,78 @@
+//! FX Rate Handler
+//!
+//! Admin view of the settlement currency and the oracle feed of fiat
+//! reference rates used to value trades in the display currency
+
+use axum::{extract::State, Json};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::fx_rates::{CurrencyOverview, FxRateRecord};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct PublishFxRateRequest {
+    /// Display currency per one settlement token
+    #[schema(value_type = String, example = "35.25")]
+    pub rate: Decimal,
+    /// Feed or provider the rate came from
+    #[validate(length(max = 50), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub source: String,
+    /// When the rate was observed (defaults to now)
+    pub observed_at: Option<DateTime<Utc>>,
+}
+
+/// Settlement currency and FX rates
+/// GET /api/v1/admin/fx-rates
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/fx-rates",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Settlement asset, current rate and recent rates", body = CurrencyOverview),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_fx_rates(State(state): State<AppState>) -> Result<Json<CurrencyOverview>> {
+    Ok(Json(state.fx_rates.overview().await?))
+}
+
+/// Publish an FX rate
+/// POST /api/v1/admin/fx-rates
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/fx-rates",
+    tag = "admin",
+    request_body = PublishFxRateRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Rate recorded", body = FxRateRecord),
+        (status = 403, description = "Admin access required"),
+        (status = 422, description = "Rate not positive or observation time in the future")
+    )
+)]
+pub async fn publish_fx_rate(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<PublishFxRateRequest>,
+) -> Result<Json<FxRateRecord>> {
+    Ok(Json(
+        state
+            .fx_rates
+            .publish_rate(
+                user.0.sub,
+                payload.rate,
+                &payload.source,
+                payload.observed_at,
+                &state.audit_logger,
+            )
+            .await?,
+    ))
+}
@@ -0,0 +1,244 @@
+//! Invoices Handler
+//!
+//! Monthly statements: listing, on-demand generation and signed PDF downloads
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
+use crate::error::{ApiError, Result};
+use crate::services::invoicing::{Invoice, SignedDownloadUrl};
+use crate::AppState;
+
+/// Statement period
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GenerateInvoiceRequest {
+    pub year: i32,
+    /// Month (1-12)
+    pub month: u32,
+}
+
+/// Result of a bulk statement run
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GenerateInvoicesResponse {
+    pub year: i32,
+    pub month: u32,
+    pub invoices: usize,
+}
+
+/// Signed download parameters
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct InvoiceDownloadQuery {
+    /// Expiry as Unix timestamp
+    pub expires: i64,
+    /// Hex-encoded HMAC signature
+    pub signature: String,
+}
+
+/// List the caller's statements
+/// GET /api/v1/invoices
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices",
+    tag = "invoices",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Statements, newest first", body = Vec<Invoice>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_invoices(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<Vec<Invoice>>> {
+    let invoices = state
+        .invoice_service
+        .list_for_user(user.0.sub)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to list invoices: {}", e)))?;
+
+    Ok(Json(invoices))
+}
+
+/// Generate the caller's statement for a completed month
+/// POST /api/v1/invoices/generate
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/generate",
+    tag = "invoices",
+    request_body = GenerateInvoiceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Statement issued (or existing statement returned)", body = Invoice),
+        (status = 400, description = "Invalid or incomplete period"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn generate_invoice(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(request): Json<GenerateInvoiceRequest>,
+) -> Result<Json<Invoice>> {
+    validate_completed_month(&state, request.year, request.month)?;
+
+    let invoice = state
+        .invoice_service
+        .generate_for_user(user.0.sub, request.year, request.month)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to generate invoice: {}", e)))?;
+
+    Ok(Json(invoice))
+}
+
+/// Get a short-lived signed download URL for a statement PDF
+/// GET /api/v1/invoices/{id}/download-url
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/download-url",
+    tag = "invoices",
+    params(("id" = Uuid, Path, description = "Invoice ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Signed download URL", body = SignedDownloadUrl),
+        (status = 404, description = "Invoice not found")
+    )
+)]
+pub async fn get_invoice_download_url(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<SignedDownloadUrl>> {
+    let invoice = state
+        .invoice_service
+        .get(invoice_id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to load invoice: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound("Invoice not found".to_string()))?;
+
+    let is_admin = matches!(Role::from_str(&user.0.role), Ok(Role::Admin));
+    if invoice.user_id != user.0.sub && !is_admin {
+        // Do not reveal that another user's invoice exists
+        return Err(ApiError::NotFound("Invoice not found".to_string()));
+    }
+
+    Ok(Json(state.invoice_service.signed_download_url(invoice.id)))
+}
+
+/// Download a statement PDF using a signed URL (no bearer token required)
+/// GET /api/v1/invoices/{id}/download
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/download",
+    tag = "invoices",
+    params(("id" = Uuid, Path, description = "Invoice ID"), InvoiceDownloadQuery),
+    responses(
+        (status = 200, description = "PDF file download", content_type = "application/pdf"),
+        (status = 403, description = "Invalid or expired signature"),
+        (status = 404, description = "Invoice not found")
+    )
+)]
+pub async fn download_invoice(
+    State(state): State<AppState>,
+    Path(invoice_id): Path<Uuid>,
+    Query(params): Query<InvoiceDownloadQuery>,
+) -> Response {
+    if !state
+        .invoice_service
+        .verify_download(invoice_id, params.expires, &params.signature)
+    {
+        warn!("Rejected invoice download for {}: invalid or expired signature", invoice_id);
+        return (StatusCode::FORBIDDEN, "Invalid or expired download link").into_response();
+    }
+
+    let invoice = match state.invoice_service.get(invoice_id).await {
+        Ok(Some(invoice)) => invoice,
+        Ok(None) => return (StatusCode::NOT_FOUND, "Invoice not found").into_response(),
+        Err(e) => {
+            error!("Failed to load invoice {}: {}", invoice_id, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load invoice").into_response();
+        }
+    };
+
+    let pdf = match state.invoice_service.load_pdf(&invoice).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to read invoice {} from storage: {}", invoice_id, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load invoice").into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.pdf\"", invoice.invoice_number),
+            ),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        pdf,
+    )
+        .into_response()
+}
+
+/// Generate statements for all active accounts for a completed month (admin)
+/// POST /api/v1/admin/invoices/generate
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/invoices/generate",
+    tag = "invoices",
+    request_body = GenerateInvoiceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Statements issued", body = GenerateInvoicesResponse),
+        (status = 400, description = "Invalid or incomplete period"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn admin_generate_invoices(
+    State(state): State<AppState>,
+    Json(request): Json<GenerateInvoiceRequest>,
+) -> Result<Json<GenerateInvoicesResponse>> {
+    validate_completed_month(&state, request.year, request.month)?;
+
+    let invoices = state
+        .invoice_service
+        .generate_for_all(request.year, request.month)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to generate invoices: {}", e)))?;
+
+    Ok(Json(GenerateInvoicesResponse {
+        year: request.year,
+        month: request.month,
+        invoices,
+    }))
+}
+
+/// Statements can only be issued once the month is over in the market
+/// timezone
+fn validate_completed_month(state: &AppState, year: i32, month: u32) -> Result<()> {
+    if !(1..=12).contains(&month) {
+        return Err(ApiError::validation_field("month", "Month must be between 1 and 12"));
+    }
+
+    let today = state.invoice_service.timezone().date_of(Utc::now());
+    if (year, month) >= (today.year(), today.month()) {
+        return Err(ApiError::validation_field(
+            "month",
+            "Statements are only available for completed months",
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! Background Jobs Handler
+//!
+//! Admin view of background job runs across subsystems, with per-run logs,
+//! cancellation and retry
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::services::jobs::{Job, JobDetail, JobKind, JobKindSummary, JobLog, JobStatus};
+use crate::AppState;
+
+const MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct JobsQuery {
+    pub status: Option<JobStatus>,
+    pub kind: Option<JobKind>,
+    /// Default 100, at most 500
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct JobLogsQuery {
+    /// Only lines after this log line ID, for following a running job
+    pub after: Option<i64>,
+    /// Default 200, at most 500
+    pub limit: Option<i64>,
+}
+
+/// List background job runs
+/// GET /api/v1/admin/jobs
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/jobs",
+    tag = "admin",
+    params(JobsQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Job runs, newest first", body = Vec<Job>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_jobs(State(state): State<AppState>, Query(params): Query<JobsQuery>) -> Result<Json<Vec<Job>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, MAX_LIMIT);
+    Ok(Json(state.jobs.list(params.status, params.kind, limit).await?))
+}
+
+/// Summarize background jobs
+/// GET /api/v1/admin/jobs/summary
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/jobs/summary",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Runs per job kind over the last day, plus unfinished ones", body = Vec<JobKindSummary>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_jobs_summary(State(state): State<AppState>) -> Result<Json<Vec<JobKindSummary>>> {
+    Ok(Json(state.jobs.summary().await?))
+}
+
+/// Get a background job run with its log
+/// GET /api/v1/admin/jobs/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/jobs/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Job ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Job run and log", body = JobDetail),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Job not found")
+    )
+)]
+pub async fn get_job(State(state): State<AppState>, Path(job_id): Path<Uuid>) -> Result<Json<JobDetail>> {
+    Ok(Json(state.jobs.get(job_id).await?))
+}
+
+/// Read a background job's log
+/// GET /api/v1/admin/jobs/{id}/logs
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/jobs/{id}/logs",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Job ID"), JobLogsQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Log lines, oldest first", body = Vec<JobLog>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_job_logs(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+    Query(params): Query<JobLogsQuery>,
+) -> Result<Json<Vec<JobLog>>> {
+    let limit = params.limit.unwrap_or(200).clamp(1, MAX_LIMIT);
+    Ok(Json(state.jobs.logs(job_id, params.after, limit).await?))
+}
+
+/// Cancel a background job run
+/// POST /api/v1/admin/jobs/{id}/cancel
+///
+/// A queued run is cancelled outright; a running one stops at its next
+/// checkpoint.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/jobs/{id}/cancel",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Job ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Run cancelled or asked to stop", body = Job),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Job not found"),
+        (status = 409, description = "Job already finished")
+    )
+)]
+pub async fn cancel_job(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<Job>> {
+    Ok(Json(state.jobs.cancel(user.0.sub, job_id).await?))
+}
+
+/// Retry a failed or cancelled background job run
+/// POST /api/v1/admin/jobs/{id}/retry
+///
+/// Queues a new attempt, which the job's loop picks up immediately.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/jobs/{id}/retry",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Job ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Retry queued", body = Job),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Job not found"),
+        (status = 409, description = "Job not failed or cancelled, or a retry is already queued")
+    )
+)]
+pub async fn retry_job(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<Job>> {
+    Ok(Json(state.jobs.retry(user.0.sub, job_id).await?))
+}
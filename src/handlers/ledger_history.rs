@@ -0,0 +1,68 @@
+//! Ledger History Handlers
+//!
+//! Admin as-of queries for audits: an account or a certificate as it stood
+//! at a past point in time
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::services::ledger_history::{AccountAsOf, CertificateAsOf};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AsOfQuery {
+    /// Point in time (RFC 3339)
+    pub at: DateTime<Utc>,
+}
+
+/// Account as of a point in time
+/// GET /api/v1/admin/audit/accounts/{id}/as-of
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit/accounts/{id}/as-of",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User ID"), AsOfQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Balances, wallets, open orders and certificates at the time", body = AccountAsOf),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "User not found or not yet registered at the time"),
+        (status = 422, description = "Time in the future or before balance history starts")
+    )
+)]
+pub async fn get_account_as_of(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<AsOfQuery>,
+) -> Result<Json<AccountAsOf>> {
+    Ok(Json(state.ledger_history.account(id, params.at).await?))
+}
+
+/// Certificate as of a point in time
+/// GET /api/v1/admin/audit/certificates/{id}/as-of
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit/certificates/{id}/as-of",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Certificate ID"), AsOfQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Holder, retirement and expiry at the time", body = CertificateAsOf),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Certificate not found or not yet issued at the time")
+    )
+)]
+pub async fn get_certificate_as_of(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<AsOfQuery>,
+) -> Result<Json<CertificateAsOf>> {
+    Ok(Json(state.ledger_history.certificate(id, params.at).await?))
+}
@@ -0,0 +1,85 @@
+//! Locale Preference Handlers
+//!
+//! Users choose the language of notifications and emails sent to them
+//! outside a request; responses to requests follow `Accept-Language`.
+
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::i18n::{self, Locale};
+use crate::AppState;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LocalePreference {
+    pub locale: Locale,
+    /// Locales with message catalogs
+    pub available: Vec<Locale>,
+}
+
+impl LocalePreference {
+    fn new(locale: Locale) -> Self {
+        Self {
+            locale,
+            available: Locale::ALL.to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateLocaleRequest {
+    pub locale: Locale,
+}
+
+/// Get your preferred locale
+/// GET /api/v1/account/locale
+#[utoipa::path(
+    get,
+    path = "/api/v1/account/locale",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Preferred locale and the supported locales", body = LocalePreference),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_my_locale(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<LocalePreference>> {
+    let locale = i18n::user_locale(&state.db, user.0.sub).await;
+    Ok(Json(LocalePreference::new(locale)))
+}
+
+/// Set your preferred locale
+/// PUT /api/v1/account/locale
+#[utoipa::path(
+    put,
+    path = "/api/v1/account/locale",
+    tag = "users",
+    request_body = UpdateLocaleRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Preferred locale updated", body = LocalePreference),
+        (status = 400, description = "Unsupported locale"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn update_my_locale(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(request): Json<UpdateLocaleRequest>,
+) -> Result<Json<LocalePreference>> {
+    let result = sqlx::query("UPDATE users SET preferred_locale = $1, updated_at = NOW() WHERE id = $2")
+        .bind(request.locale.as_str())
+        .bind(user.0.sub)
+        .execute(&state.db)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("User not found".to_string()));
+    }
+
+    Ok(Json(LocalePreference::new(request.locale)))
+}
@@ -0,0 +1,198 @@
+//! Planned Maintenance Handler
+//!
+//! Admin scheduling of maintenance windows shown on the platform status
+//! endpoint, and rebuilds of caches and aggregates
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::maintenance::MaintenanceWindow;
+use crate::services::maintenance_rebuild::{RebuildJob, RebuildTarget};
+use crate::AppState;
+
+const MAX_LIMIT: i64 = 500;
+
+/// New maintenance window
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ScheduleMaintenanceRequest {
+    #[validate(length(max = 200), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub title: String,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    /// Affected components: api, trading, settlement, blockchain, meters, payments
+    #[schema(example = json!(["trading", "settlement"]))]
+    pub components: Vec<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MaintenanceQuery {
+    /// Default 100, at most 500
+    pub limit: Option<i64>,
+}
+
+/// List maintenance windows
+/// GET /api/v1/admin/maintenance
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/maintenance",
+    tag = "admin",
+    params(MaintenanceQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Windows including past and cancelled ones, newest first", body = Vec<MaintenanceWindow>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_maintenance_windows(
+    State(state): State<AppState>,
+    Query(params): Query<MaintenanceQuery>,
+) -> Result<Json<Vec<MaintenanceWindow>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, MAX_LIMIT);
+    Ok(Json(state.maintenance.list(limit).await?))
+}
+
+/// Schedule a maintenance window
+/// POST /api/v1/admin/maintenance
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/maintenance",
+    tag = "admin",
+    request_body = ScheduleMaintenanceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Window scheduled and published on /api/v1/status", body = MaintenanceWindow),
+        (status = 403, description = "Admin access required"),
+        (status = 422, description = "Unknown component, or window empty or already over")
+    )
+)]
+pub async fn schedule_maintenance(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<ScheduleMaintenanceRequest>,
+) -> Result<Json<MaintenanceWindow>> {
+    let description = payload
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+
+    Ok(Json(
+        state
+            .maintenance
+            .schedule(
+                user.0.sub,
+                &payload.title,
+                description,
+                &payload.components,
+                payload.starts_at,
+                payload.ends_at,
+            )
+            .await?,
+    ))
+}
+
+/// Cancel a maintenance window
+/// DELETE /api/v1/admin/maintenance/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/maintenance/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Maintenance window ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Window cancelled", body = MaintenanceWindow),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Window not found or already cancelled")
+    )
+)]
+pub async fn cancel_maintenance(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<MaintenanceWindow>> {
+    Ok(Json(state.maintenance.cancel(user.0.sub, id).await?))
+}
+
+/// Caches and aggregates to rebuild
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RebuildRequest {
+    /// Every target when omitted or empty
+    #[serde(default)]
+    #[schema(example = json!(["orderbook_cache", "analytics_rollups"]))]
+    pub targets: Vec<RebuildTarget>,
+}
+
+/// Rebuild caches and aggregates
+/// POST /api/v1/admin/maintenance/rebuild
+///
+/// Each target runs as a background job; poll the returned jobs for progress.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/maintenance/rebuild",
+    tag = "admin",
+    request_body = RebuildRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Jobs queued, one per target", body = Vec<RebuildJob>),
+        (status = 403, description = "Admin access required"),
+        (status = 409, description = "A requested target is already being rebuilt; nothing was queued")
+    )
+)]
+pub async fn start_rebuild(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<RebuildRequest>,
+) -> Result<Json<Vec<RebuildJob>>> {
+    Ok(Json(state.rebuilds.start(user.0.sub, &payload.targets).await?))
+}
+
+/// List rebuild jobs
+/// GET /api/v1/admin/maintenance/rebuild
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/maintenance/rebuild",
+    tag = "admin",
+    params(MaintenanceQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Rebuild jobs, newest first", body = Vec<RebuildJob>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_rebuilds(
+    State(state): State<AppState>,
+    Query(params): Query<MaintenanceQuery>,
+) -> Result<Json<Vec<RebuildJob>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, MAX_LIMIT);
+    Ok(Json(state.rebuilds.list(limit).await?))
+}
+
+/// Get a rebuild job's progress
+/// GET /api/v1/admin/maintenance/rebuild/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/maintenance/rebuild/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Rebuild job ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The job and its progress", body = RebuildJob),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Job not found")
+    )
+)]
+pub async fn get_rebuild(State(state): State<AppState>, Path(id): Path<Uuid>) -> Result<Json<RebuildJob>> {
+    Ok(Json(state.rebuilds.get(id).await?))
+}
@@ -0,0 +1,386 @@
+//! Market Handlers
+//!
+//! Market listing and per-market orderbooks, plus admin management of
+//! markets and their trading parameters and historical book snapshots
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::services::book_snapshots::{OrderBookSnapshot, SnapshotLookup};
+use crate::services::maker_incentives::MakerIncentiveBoard;
+use crate::services::markets::{
+    Market, MarketDepthChart, MarketOrderBook, MarketParams, MarketStatus, MarketType,
+};
+use crate::AppState;
+
+const DEFAULT_BOOK_LEVELS: i64 = 20;
+const MAX_BOOK_LEVELS: i64 = 100;
+const DEFAULT_CHART_LEVELS: usize = 50;
+const MAX_CHART_LEVELS: usize = 200;
+const DEFAULT_BAND_PCT: i64 = 5;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MarketOrderBookQuery {
+    /// Price levels per side (default 20, max 100)
+    pub levels: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DepthChartQuery {
+    /// Bucket width in price per kWh, the market's tick size (or 0.01) if omitted
+    #[param(value_type = Option<String>)]
+    pub bucket: Option<Decimal>,
+    /// Buckets per side (default 50, max 200)
+    pub levels: Option<usize>,
+    /// Width of the band around the last clearing price in percent (default 5, max 50)
+    #[param(value_type = Option<String>)]
+    pub band_pct: Option<Decimal>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BookSnapshotQuery {
+    /// Latest snapshot taken at or before this time (RFC 3339), now if omitted
+    pub as_of: Option<chrono::DateTime<chrono::Utc>>,
+    /// Snapshot taken right before this epoch was matched; overrides `as_of`
+    pub epoch_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MakerIncentivesQuery {
+    /// UTC day (YYYY-MM-DD), today if omitted
+    pub date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateMarketRequest {
+    /// Unique code, letters, digits and hyphens (e.g. SPOT-ZONE-2)
+    #[validate(length(min = 1, max = 32))]
+    pub code: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub market_type: MarketType,
+    /// Restrict a spot market to orders from one grid zone
+    #[validate(range(min = 0))]
+    pub zone_id: Option<i32>,
+    #[schema(value_type = String, example = "0.1")]
+    pub min_order_kwh: Decimal,
+    /// Largest order accepted; no limit when omitted
+    #[schema(value_type = Option<String>, example = "10000")]
+    pub max_order_kwh: Option<Decimal>,
+    /// Remainders below this are cancelled as dust; the minimum order size when omitted
+    #[schema(value_type = Option<String>, example = "0.05")]
+    pub dust_threshold_kwh: Option<Decimal>,
+    #[schema(value_type = Option<String>, example = "0.01")]
+    pub tick_size: Option<Decimal>,
+    /// Settlements of at least this value run at high priority
+    #[schema(value_type = Option<String>, example = "1000")]
+    pub settlement_high_notional: Option<Decimal>,
+    /// Settlements of at least this value run at urgent priority
+    #[schema(value_type = Option<String>, example = "10000")]
+    pub settlement_urgent_notional: Option<Decimal>,
+    /// Settlements with a corporate counterparty run at least at high priority
+    #[serde(default)]
+    pub settlement_corporate_high: bool,
+    /// Raise settlements created this many seconds before their epoch ends one level
+    pub settlement_deadline_secs: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateMarketRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(range(min = 0))]
+    pub zone_id: Option<i32>,
+    #[schema(value_type = String, example = "0.1")]
+    pub min_order_kwh: Decimal,
+    /// Largest order accepted; no limit when omitted
+    #[schema(value_type = Option<String>, example = "10000")]
+    pub max_order_kwh: Option<Decimal>,
+    /// Remainders below this are cancelled as dust; the minimum order size when omitted
+    #[schema(value_type = Option<String>, example = "0.05")]
+    pub dust_threshold_kwh: Option<Decimal>,
+    #[schema(value_type = Option<String>, example = "0.01")]
+    pub tick_size: Option<Decimal>,
+    /// Settlements of at least this value run at high priority
+    #[schema(value_type = Option<String>, example = "1000")]
+    pub settlement_high_notional: Option<Decimal>,
+    /// Settlements of at least this value run at urgent priority
+    #[schema(value_type = Option<String>, example = "10000")]
+    pub settlement_urgent_notional: Option<Decimal>,
+    /// Settlements with a corporate counterparty run at least at high priority
+    #[serde(default)]
+    pub settlement_corporate_high: bool,
+    /// Raise settlements created this many seconds before their epoch ends one level
+    pub settlement_deadline_secs: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetMarketStatusRequest {
+    pub status: MarketStatus,
+}
+
+/// List markets
+/// GET /api/v1/markets
+#[utoipa::path(
+    get,
+    path = "/api/v1/markets",
+    tag = "trading",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "All markets, default first", body = Vec<Market>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_markets(State(state): State<AppState>) -> Result<Json<Vec<Market>>> {
+    Ok(Json(state.markets.list().await?))
+}
+
+/// Get a market
+/// GET /api/v1/markets/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/markets/{id}",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Market ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Market and its parameters", body = Market),
+        (status = 404, description = "Market not found")
+    )
+)]
+pub async fn get_market(State(state): State<AppState>, Path(market_id): Path<Uuid>) -> Result<Json<Market>> {
+    Ok(Json(state.markets.get(market_id).await?))
+}
+
+/// Get a market's orderbook
+/// GET /api/v1/markets/{id}/orderbook
+#[utoipa::path(
+    get,
+    path = "/api/v1/markets/{id}/orderbook",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Market ID"), MarketOrderBookQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Open orders aggregated by price level", body = MarketOrderBook),
+        (status = 404, description = "Market not found")
+    )
+)]
+pub async fn get_market_order_book(
+    State(state): State<AppState>,
+    Path(market_id): Path<Uuid>,
+    Query(params): Query<MarketOrderBookQuery>,
+) -> Result<Json<MarketOrderBook>> {
+    let levels = params.levels.unwrap_or(DEFAULT_BOOK_LEVELS).clamp(1, MAX_BOOK_LEVELS);
+    Ok(Json(state.markets.order_book(market_id, levels).await?))
+}
+
+/// Get a market's depth chart
+/// GET /api/v1/markets/{id}/depth-chart
+///
+/// Open orders grouped into price buckets with cumulative volume from the
+/// best price outwards, plus the last clearing price and a band around it.
+#[utoipa::path(
+    get,
+    path = "/api/v1/markets/{id}/depth-chart",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Market ID"), DepthChartQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Bucketed cumulative depth per side", body = MarketDepthChart),
+        (status = 400, description = "Bucket or band out of range"),
+        (status = 404, description = "Market not found")
+    )
+)]
+pub async fn get_market_depth_chart(
+    State(state): State<AppState>,
+    Path(market_id): Path<Uuid>,
+    Query(params): Query<DepthChartQuery>,
+) -> Result<Json<MarketDepthChart>> {
+    if params.bucket.is_some_and(|b| b <= Decimal::ZERO) {
+        return Err(ApiError::validation_field("bucket", "Bucket must be positive"));
+    }
+    let band_pct = params.band_pct.unwrap_or(Decimal::from(DEFAULT_BAND_PCT));
+    if band_pct < Decimal::ZERO || band_pct > Decimal::from(50) {
+        return Err(ApiError::validation_field("band_pct", "Band must be between 0 and 50 percent"));
+    }
+    let levels = params.levels.unwrap_or(DEFAULT_CHART_LEVELS).clamp(1, MAX_CHART_LEVELS);
+
+    Ok(Json(
+        state
+            .markets
+            .depth_chart(market_id, params.bucket, levels, band_pct)
+            .await?,
+    ))
+}
+
+/// Get a market's maker incentive scores
+/// GET /api/v1/markets/{id}/maker-incentives
+///
+/// Time at best quote, spread tightness and score of every market maker for
+/// the day, with their rebate once the day is settled.
+#[utoipa::path(
+    get,
+    path = "/api/v1/markets/{id}/maker-incentives",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Market ID"), MakerIncentivesQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Maker scores, highest first", body = MakerIncentiveBoard),
+        (status = 404, description = "Market not found")
+    )
+)]
+pub async fn get_maker_incentives(
+    State(state): State<AppState>,
+    Path(market_id): Path<Uuid>,
+    Query(params): Query<MakerIncentivesQuery>,
+) -> Result<Json<MakerIncentiveBoard>> {
+    Ok(Json(state.maker_incentives.board(market_id, params.date).await?))
+}
+
+/// Create a market
+/// POST /api/v1/admin/markets
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/markets",
+    tag = "admin",
+    request_body = CreateMarketRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Market created", body = Market),
+        (status = 403, description = "Admin access required"),
+        (status = 409, description = "Market code already exists"),
+        (status = 422, description = "Invalid code or parameters")
+    )
+)]
+pub async fn create_market(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<CreateMarketRequest>,
+) -> Result<Json<Market>> {
+    let params = MarketParams {
+        name: payload.name,
+        zone_id: payload.zone_id,
+        min_order_kwh: payload.min_order_kwh,
+        max_order_kwh: payload.max_order_kwh,
+        dust_threshold_kwh: payload.dust_threshold_kwh,
+        tick_size: payload.tick_size,
+        settlement_high_notional: payload.settlement_high_notional,
+        settlement_urgent_notional: payload.settlement_urgent_notional,
+        settlement_corporate_high: payload.settlement_corporate_high,
+        settlement_deadline_secs: payload.settlement_deadline_secs,
+    };
+    Ok(Json(
+        state
+            .markets
+            .create(user.0.sub, &payload.code, payload.market_type, params, &state.audit_logger)
+            .await?,
+    ))
+}
+
+/// Update a market's parameters
+/// PUT /api/v1/admin/markets/{id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/markets/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Market ID")),
+    request_body = UpdateMarketRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Market updated", body = Market),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Market not found"),
+        (status = 422, description = "Invalid parameters")
+    )
+)]
+pub async fn update_market(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(market_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateMarketRequest>,
+) -> Result<Json<Market>> {
+    let params = MarketParams {
+        name: payload.name,
+        zone_id: payload.zone_id,
+        min_order_kwh: payload.min_order_kwh,
+        max_order_kwh: payload.max_order_kwh,
+        dust_threshold_kwh: payload.dust_threshold_kwh,
+        tick_size: payload.tick_size,
+        settlement_high_notional: payload.settlement_high_notional,
+        settlement_urgent_notional: payload.settlement_urgent_notional,
+        settlement_corporate_high: payload.settlement_corporate_high,
+        settlement_deadline_secs: payload.settlement_deadline_secs,
+    };
+    Ok(Json(
+        state
+            .markets
+            .update(user.0.sub, market_id, params, &state.audit_logger)
+            .await?,
+    ))
+}
+
+/// Halt, resume or close a market
+/// PUT /api/v1/admin/markets/{id}/status
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/markets/{id}/status",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Market ID")),
+    request_body = SetMarketStatusRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Market status changed", body = Market),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Market not found"),
+        (status = 409, description = "Default market, or open orders remain")
+    )
+)]
+pub async fn set_market_status(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(market_id): Path<Uuid>,
+    Json(payload): Json<SetMarketStatusRequest>,
+) -> Result<Json<Market>> {
+    Ok(Json(
+        state
+            .markets
+            .set_status(user.0.sub, market_id, payload.status, &state.audit_logger)
+            .await?,
+    ))
+}
+
+/// Get a market's order book as of a past time
+/// GET /api/v1/admin/markets/{id}/book-snapshot
+///
+/// Full L2 book from the latest snapshot at or before `as_of`, or the book
+/// an epoch was matched against.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/markets/{id}/book-snapshot",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Market ID"), BookSnapshotQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Stored order book", body = OrderBookSnapshot),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "No snapshot at or before the given time, or for the epoch")
+    )
+)]
+pub async fn get_book_snapshot(
+    State(state): State<AppState>,
+    Path(market_id): Path<Uuid>,
+    Query(params): Query<BookSnapshotQuery>,
+) -> Result<Json<OrderBookSnapshot>> {
+    let lookup = SnapshotLookup::new(params.as_of, params.epoch_id, chrono::Utc::now());
+    Ok(Json(state.book_snapshots.find(market_id, lookup).await?))
+}
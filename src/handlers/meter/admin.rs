@@ -0,0 +1,582 @@
+//! Admin Meter Management Handlers
+//!
+//! Registry search and lifecycle management for operators:
+//! - Search meters by serial, owner, status and zone
+//! - Approve / reject pending verifications with reviewer notes
+//! - Suspend verified meters
+//! - Per-meter verification history
+//! - On-chain registry account sync
+//! - Device clock drift report
+//! - Fleet health summary
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::services::meter_fleet_health::FleetHealthReport;
+use crate::services::meter_registry_sync::MeterRegistrySync;
+use crate::services::AuditEvent;
+use crate::utils::pagination::{PaginatedResponse, PaginationParams, SortOrder};
+use crate::AppState;
+
+/// Search filters for the meter registry
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MeterSearchQuery {
+    /// Partial, case-insensitive serial number match
+    pub serial: Option<String>,
+    /// Owner user ID, exact email or username (partial match)
+    pub owner: Option<String>,
+    /// Verification status: pending, verified, rejected, suspended
+    pub status: Option<String>,
+    /// Zone ID
+    pub zone_id: Option<i32>,
+    /// Page number (1-indexed)
+    pub page: Option<u32>,
+    /// Items per page (max 100)
+    pub page_size: Option<u32>,
+}
+
+/// Meter registry entry as seen by admins
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct AdminMeterRecord {
+    pub id: Uuid,
+    pub meter_serial: String,
+    pub meter_type: Option<String>,
+    pub verification_status: String,
+    pub zone_id: Option<i32>,
+    pub owner_id: Uuid,
+    pub owner_email: Option<String>,
+    #[serde(skip)]
+    pub owner_email_encrypted: Option<Vec<u8>>,
+    pub owner_username: Option<String>,
+    pub reviewer_notes: Option<String>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub verified_by: Option<Uuid>,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub suspension_reason: Option<String>,
+    /// On-chain registry account (ownership proof)
+    pub registry_pda: Option<String>,
+    pub registry_synced_at: Option<DateTime<Utc>>,
+    /// Why the last on-chain registry sync failed
+    pub registry_sync_error: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Review decision payload
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MeterReviewRequest {
+    /// Reviewer notes (required when rejecting or suspending)
+    pub notes: Option<String>,
+}
+
+/// Result of a lifecycle action
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MeterReviewResponse {
+    pub meter_id: Uuid,
+    pub previous_status: String,
+    pub new_status: String,
+    pub message: String,
+}
+
+/// A single verification history entry
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct MeterHistoryEntry {
+    pub id: Uuid,
+    pub action: String,
+    pub previous_status: Option<String>,
+    pub new_status: String,
+    pub reviewer_id: Option<Uuid>,
+    pub notes: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Filters for the clock drift report
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ClockDriftQuery {
+    /// Only include meters whose worst observed drift is at least this many seconds
+    pub min_drift_secs: Option<i32>,
+    /// Maximum number of meters to return (default 50, max 500)
+    pub limit: Option<i64>,
+}
+
+/// Observed device clock drift for a single meter
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct MeterClockDrift {
+    pub meter_id: Uuid,
+    pub meter_serial: String,
+    pub zone_id: Option<i32>,
+    /// Drift of the most recent reading (device minus server, seconds)
+    pub last_clock_drift_secs: Option<i32>,
+    /// Largest absolute drift observed
+    pub max_abs_clock_drift_secs: Option<i32>,
+    pub drift_sample_count: Option<i32>,
+    pub last_drift_at: Option<DateTime<Utc>>,
+}
+
+/// Lifecycle actions an admin can take on a meter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeterAction {
+    Approve,
+    Reject,
+    Suspend,
+}
+
+impl MeterAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MeterAction::Approve => "approve",
+            MeterAction::Reject => "reject",
+            MeterAction::Suspend => "suspend",
+        }
+    }
+
+    /// Resolve the target status for this action, or `None` if the
+    /// transition is not allowed from `current`.
+    pub fn transition(&self, current: &str) -> Option<&'static str> {
+        match (self, current) {
+            (MeterAction::Approve, "pending" | "rejected" | "suspended") => Some("verified"),
+            (MeterAction::Reject, "pending") => Some("rejected"),
+            (MeterAction::Suspend, "verified") => Some("suspended"),
+            _ => None,
+        }
+    }
+
+    fn requires_notes(&self) -> bool {
+        matches!(self, MeterAction::Reject | MeterAction::Suspend)
+    }
+}
+
+/// Search the meter registry
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/meters",
+    tag = "meters",
+    params(MeterSearchQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Matching meters", body = PaginatedResponse<AdminMeterRecord>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn search_meters(
+    State(state): State<AppState>,
+    Query(query): Query<MeterSearchQuery>,
+) -> Result<Json<PaginatedResponse<AdminMeterRecord>>> {
+    let mut pagination = PaginationParams {
+        page: query.page.unwrap_or(1),
+        page_size: query.page_size.unwrap_or(20),
+        sort_by: None,
+        sort_order: SortOrder::Desc,
+    };
+    pagination.validate().map_err(ApiError::BadRequest)?;
+
+    let serial = query.serial.as_deref().map(|s| format!("%{}%", s.trim().to_lowercase()));
+    let owner_uuid = query.owner.as_deref().and_then(|o| Uuid::parse_str(o.trim()).ok());
+    let owner = query.owner.as_deref().map(|o| format!("%{}%", o.trim().to_lowercase()));
+    let owner_digest = query.owner.as_deref().map(|o| state.pii.email_digest(o));
+
+    let total: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM meter_registry m
+        JOIN users u ON m.user_id = u.id
+        WHERE ($1::TEXT IS NULL OR LOWER(m.meter_serial) LIKE $1)
+          AND ($2::UUID IS NULL OR m.user_id = $2)
+          AND ($3::TEXT IS NULL OR $2::UUID IS NOT NULL
+               OR u.email_digest = $6 OR LOWER(u.email) LIKE $3 OR LOWER(u.username) LIKE $3)
+          AND ($4::TEXT IS NULL OR m.verification_status = $4)
+          AND ($5::INT IS NULL OR m.zone_id = $5)
+        "#,
+    )
+    .bind(&serial)
+    .bind(owner_uuid)
+    .bind(&owner)
+    .bind(&query.status)
+    .bind(query.zone_id)
+    .bind(&owner_digest)
+    .fetch_one(&state.db)
+    .await?;
+
+    let mut meters = sqlx::query_as::<_, AdminMeterRecord>(
+        r#"
+        SELECT m.id, m.meter_serial, m.meter_type, m.verification_status, m.zone_id,
+               m.user_id as owner_id, u.email as owner_email, u.email_encrypted as owner_email_encrypted,
+               u.username as owner_username,
+               m.reviewer_notes, m.verified_at, m.verified_by,
+               m.suspended_at, m.suspension_reason,
+               m.registry_pda, m.registry_synced_at, m.registry_sync_error, m.created_at
+        FROM meter_registry m
+        JOIN users u ON m.user_id = u.id
+        WHERE ($1::TEXT IS NULL OR LOWER(m.meter_serial) LIKE $1)
+          AND ($2::UUID IS NULL OR m.user_id = $2)
+          AND ($3::TEXT IS NULL OR $2::UUID IS NOT NULL
+               OR u.email_digest = $8 OR LOWER(u.email) LIKE $3 OR LOWER(u.username) LIKE $3)
+          AND ($4::TEXT IS NULL OR m.verification_status = $4)
+          AND ($5::INT IS NULL OR m.zone_id = $5)
+        ORDER BY m.created_at DESC
+        LIMIT $6 OFFSET $7
+        "#,
+    )
+    .bind(&serial)
+    .bind(owner_uuid)
+    .bind(&owner)
+    .bind(&query.status)
+    .bind(query.zone_id)
+    .bind(pagination.limit())
+    .bind(pagination.offset())
+    .bind(&owner_digest)
+    .fetch_all(&state.db)
+    .await?;
+
+    for meter in &mut meters {
+        if let Some(sealed) = meter.owner_email_encrypted.take() {
+            meter.owner_email = Some(state.pii.open(&sealed).await?);
+        }
+    }
+
+    Ok(Json(PaginatedResponse::new(meters, &pagination, total)))
+}
+
+/// Approve a pending (or previously rejected/suspended) meter
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/meters/{id}/approve",
+    tag = "meters",
+    request_body = MeterReviewRequest,
+    params(("id" = Uuid, Path, description = "Meter registry ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Meter approved", body = MeterReviewResponse),
+        (status = 404, description = "Meter not found"),
+        (status = 409, description = "Transition not allowed")
+    )
+)]
+pub async fn approve_meter(
+    State(state): State<AppState>,
+    AuthenticatedUser(admin): AuthenticatedUser,
+    Path(meter_id): Path<Uuid>,
+    Json(request): Json<MeterReviewRequest>,
+) -> Result<Json<MeterReviewResponse>> {
+    apply_meter_action(&state, admin.sub, meter_id, MeterAction::Approve, request.notes).await
+}
+
+/// Reject a pending meter verification
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/meters/{id}/reject",
+    tag = "meters",
+    request_body = MeterReviewRequest,
+    params(("id" = Uuid, Path, description = "Meter registry ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Meter rejected", body = MeterReviewResponse),
+        (status = 400, description = "Reviewer notes required"),
+        (status = 404, description = "Meter not found"),
+        (status = 409, description = "Transition not allowed")
+    )
+)]
+pub async fn reject_meter(
+    State(state): State<AppState>,
+    AuthenticatedUser(admin): AuthenticatedUser,
+    Path(meter_id): Path<Uuid>,
+    Json(request): Json<MeterReviewRequest>,
+) -> Result<Json<MeterReviewResponse>> {
+    apply_meter_action(&state, admin.sub, meter_id, MeterAction::Reject, request.notes).await
+}
+
+/// Suspend a verified meter
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/meters/{id}/suspend",
+    tag = "meters",
+    request_body = MeterReviewRequest,
+    params(("id" = Uuid, Path, description = "Meter registry ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Meter suspended", body = MeterReviewResponse),
+        (status = 400, description = "Suspension reason required"),
+        (status = 404, description = "Meter not found"),
+        (status = 409, description = "Transition not allowed")
+    )
+)]
+pub async fn suspend_meter(
+    State(state): State<AppState>,
+    AuthenticatedUser(admin): AuthenticatedUser,
+    Path(meter_id): Path<Uuid>,
+    Json(request): Json<MeterReviewRequest>,
+) -> Result<Json<MeterReviewResponse>> {
+    apply_meter_action(&state, admin.sub, meter_id, MeterAction::Suspend, request.notes).await
+}
+
+/// Get the verification history of a meter
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/meters/{id}/history",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Meter registry ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Verification history, newest first", body = Vec<MeterHistoryEntry>),
+        (status = 404, description = "Meter not found")
+    )
+)]
+pub async fn get_meter_history(
+    State(state): State<AppState>,
+    Path(meter_id): Path<Uuid>,
+) -> Result<Json<Vec<MeterHistoryEntry>>> {
+    let exists: Option<Uuid> = sqlx::query_scalar("SELECT id FROM meter_registry WHERE id = $1")
+        .bind(meter_id)
+        .fetch_optional(&state.db)
+        .await?;
+
+    if exists.is_none() {
+        return Err(ApiError::meter_not_found(&meter_id.to_string()));
+    }
+
+    let history = sqlx::query_as::<_, MeterHistoryEntry>(
+        r#"
+        SELECT id, action, previous_status, new_status, reviewer_id, notes, created_at
+        FROM meter_verification_history
+        WHERE meter_id = $1
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(meter_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(history))
+}
+
+/// Create the on-chain registry account of a verified meter if it is missing
+///
+/// Verification normally does this in the background; a failed attempt is
+/// reported in `registry_sync_error` and retried periodically.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/meters/{id}/registry-sync",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Meter registry ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "On-chain registry state after the sync", body = MeterRegistrySync),
+        (status = 404, description = "Meter not found"),
+        (status = 409, description = "Meter is not verified")
+    )
+)]
+pub async fn sync_meter_registry(
+    State(state): State<AppState>,
+    Path(meter_id): Path<Uuid>,
+) -> Result<Json<MeterRegistrySync>> {
+    Ok(Json(state.meter_registry_sync.sync_meter(meter_id).await?))
+}
+
+/// Report meters by observed device clock drift, worst first
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/meters/clock-drift",
+    tag = "meters",
+    params(ClockDriftQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Meters ordered by worst observed drift", body = Vec<MeterClockDrift>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_clock_drift_report(
+    State(state): State<AppState>,
+    Query(query): Query<ClockDriftQuery>,
+) -> Result<Json<Vec<MeterClockDrift>>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+
+    let report = sqlx::query_as::<_, MeterClockDrift>(
+        r#"
+        SELECT id AS meter_id, meter_serial, zone_id,
+               last_clock_drift_secs, max_abs_clock_drift_secs,
+               drift_sample_count, last_drift_at
+        FROM meter_registry
+        WHERE drift_sample_count > 0
+          AND ($1::INT IS NULL OR max_abs_clock_drift_secs >= $1)
+        ORDER BY max_abs_clock_drift_secs DESC NULLS LAST, meter_serial
+        LIMIT $2
+        "#,
+    )
+    .bind(query.min_drift_secs)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(report))
+}
+
+/// Fleet connectivity, signature failures, anomalies, firmware spread and
+/// under-reporting meters, as of the last scheduled aggregation
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/meters/fleet-health",
+    tag = "meters",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Cached fleet health report", body = FleetHealthReport),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_fleet_health(State(state): State<AppState>) -> Result<Json<FleetHealthReport>> {
+    Ok(Json(state.meter_fleet_health.report().await?))
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+async fn apply_meter_action(
+    state: &AppState,
+    admin_id: Uuid,
+    meter_id: Uuid,
+    action: MeterAction,
+    notes: Option<String>,
+) -> Result<Json<MeterReviewResponse>> {
+    let notes = notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    if action.requires_notes() && notes.is_none() {
+        return Err(ApiError::validation_field(
+            "notes",
+            format!("Notes are required to {} a meter", action.as_str()),
+        ));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let current: Option<(String, String)> = sqlx::query_as(
+        "SELECT meter_serial, verification_status FROM meter_registry WHERE id = $1 FOR UPDATE",
+    )
+    .bind(meter_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let (serial, previous_status) =
+        current.ok_or_else(|| ApiError::meter_not_found(&meter_id.to_string()))?;
+
+    let new_status = action.transition(&previous_status).ok_or_else(|| {
+        ApiError::Conflict(format!(
+            "Cannot {} meter in '{}' status",
+            action.as_str(),
+            previous_status
+        ))
+    })?;
+
+    match action {
+        MeterAction::Approve => {
+            sqlx::query(
+                "UPDATE meter_registry
+                 SET verification_status = $2, reviewer_notes = $3, verified_at = NOW(), verified_by = $4,
+                     suspended_at = NULL, suspension_reason = NULL, updated_at = NOW()
+                 WHERE id = $1",
+            )
+            .bind(meter_id)
+            .bind(new_status)
+            .bind(&notes)
+            .bind(admin_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        MeterAction::Reject => {
+            sqlx::query(
+                "UPDATE meter_registry
+                 SET verification_status = $2, reviewer_notes = $3, updated_at = NOW()
+                 WHERE id = $1",
+            )
+            .bind(meter_id)
+            .bind(new_status)
+            .bind(&notes)
+            .execute(&mut *tx)
+            .await?;
+        }
+        MeterAction::Suspend => {
+            sqlx::query(
+                "UPDATE meter_registry
+                 SET verification_status = $2, suspension_reason = $3, suspended_at = NOW(), updated_at = NOW()
+                 WHERE id = $1",
+            )
+            .bind(meter_id)
+            .bind(new_status)
+            .bind(&notes)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    // Keep the legacy meters table in sync so reading submission honours the decision
+    sqlx::query("UPDATE meters SET is_verified = $2, updated_at = NOW() WHERE serial_number = $1")
+        .bind(&serial)
+        .bind(new_status == "verified")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO meter_verification_history (meter_id, action, previous_status, new_status, reviewer_id, notes)
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(meter_id)
+    .bind(action.as_str())
+    .bind(&previous_status)
+    .bind(new_status)
+    .bind(admin_id)
+    .bind(&notes)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    if new_status == "verified" {
+        state.meter_registry_sync.spawn_sync(meter_id);
+    }
+
+    info!(
+        "🛠️ Admin {} performed '{}' on meter {} ({} -> {})",
+        admin_id,
+        action.as_str(),
+        serial,
+        previous_status,
+        new_status
+    );
+
+    state.audit_logger.log_async(AuditEvent::AdminAction {
+        admin_id,
+        action: format!("meter_{}", action.as_str()),
+        target_user_id: None,
+        details: format!("meter={} {} -> {}", serial, previous_status, new_status),
+    });
+
+    Ok(Json(MeterReviewResponse {
+        meter_id,
+        previous_status,
+        new_status: new_status.to_string(),
+        message: format!("Meter {} is now {}", serial, new_status),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_action_transitions() {
+        assert_eq!(MeterAction::Approve.transition("pending"), Some("verified"));
+        assert_eq!(MeterAction::Approve.transition("suspended"), Some("verified"));
+        assert_eq!(MeterAction::Approve.transition("verified"), None);
+
+        assert_eq!(MeterAction::Reject.transition("pending"), Some("rejected"));
+        assert_eq!(MeterAction::Reject.transition("verified"), None);
+
+        assert_eq!(MeterAction::Suspend.transition("verified"), Some("suspended"));
+        assert_eq!(MeterAction::Suspend.transition("pending"), None);
+    }
+}
@@ -0,0 +1,274 @@
+//! AMI Backfill Handlers
+//!
+//! Gateways declare a window of missed readings and submit the signed
+//! readings for it; admins review the batch before anything is imported
+//! or minted.
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::services::ami_backfill::{BackfillBatch, BackfillReading, BackfillSubmission};
+use crate::services::meter_gateway::{GatewayIdentity, MeterGateway};
+use crate::AppState;
+
+const MAX_LIMIT: i64 = 500;
+
+/// Time window of missed readings
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct DeclareBackfillRequest {
+    pub window_start: DateTime<Utc>,
+    /// Inclusive; must not be in the future
+    pub window_end: DateTime<Utc>,
+    /// Why the readings were missed, shown to the reviewing admin
+    #[validate(length(max = 1000), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub reason: String,
+}
+
+/// Signed readings for a backfill batch
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SubmitBackfillReadingsRequest {
+    #[validate(length(min = 1, max = 5000))]
+    pub readings: Vec<BackfillReading>,
+}
+
+/// Admin decision on a backfill batch
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReviewBackfillRequest {
+    #[validate(length(max = 1000))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BackfillQuery {
+    /// open, approved or rejected
+    pub status: Option<String>,
+    /// Default 100, at most 500
+    pub limit: Option<i64>,
+}
+
+fn gateway(identity: Option<Extension<GatewayIdentity>>) -> Result<MeterGateway> {
+    identity
+        .map(|Extension(GatewayIdentity(gateway))| gateway)
+        .ok_or_else(|| ApiError::Unauthorized("A verified gateway client certificate is required".to_string()))
+}
+
+/// Declare a backfill window
+/// POST /api/v1/public/meters/backfills
+///
+/// Readings for the window are submitted to the returned batch and held
+/// until an admin approves it.
+#[utoipa::path(
+    post,
+    path = "/api/v1/public/meters/backfills",
+    tag = "meters",
+    request_body = DeclareBackfillRequest,
+    responses(
+        (status = 200, description = "Backfill batch opened", body = BackfillBatch),
+        (status = 401, description = "Gateway client certificate required"),
+        (status = 422, description = "Window in the future, empty or too old")
+    )
+)]
+pub async fn declare_backfill(
+    State(state): State<AppState>,
+    identity: Option<Extension<GatewayIdentity>>,
+    ValidatedJson(payload): ValidatedJson<DeclareBackfillRequest>,
+) -> Result<Json<BackfillBatch>> {
+    let gateway = gateway(identity)?;
+    Ok(Json(
+        state
+            .ami_backfill
+            .declare(&gateway, payload.window_start, payload.window_end, payload.reason.trim())
+            .await?,
+    ))
+}
+
+/// Get one of the gateway's backfill batches
+/// GET /api/v1/public/meters/backfills/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/meters/backfills/{id}",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Backfill batch ID")),
+    responses(
+        (status = 200, description = "Batch and review status", body = BackfillBatch),
+        (status = 401, description = "Gateway client certificate required"),
+        (status = 404, description = "Batch not found")
+    )
+)]
+pub async fn get_gateway_backfill(
+    State(state): State<AppState>,
+    identity: Option<Extension<GatewayIdentity>>,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<BackfillBatch>> {
+    let gateway = gateway(identity)?;
+    Ok(Json(state.ami_backfill.get_for_gateway(&gateway, batch_id).await?))
+}
+
+/// Submit signed readings for a backfill batch
+/// POST /api/v1/public/meters/backfills/{id}/readings
+///
+/// Re-submitting a reading (same meter and timestamp) replaces the staged one.
+/// Readings are refused individually when outside the window, unsigned or
+/// for a meter not bound to the gateway.
+#[utoipa::path(
+    post,
+    path = "/api/v1/public/meters/backfills/{id}/readings",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Backfill batch ID")),
+    request_body = SubmitBackfillReadingsRequest,
+    responses(
+        (status = 200, description = "Readings staged; refused ones are listed", body = BackfillSubmission),
+        (status = 401, description = "Gateway client certificate required"),
+        (status = 404, description = "Batch not found"),
+        (status = 409, description = "Batch already reviewed")
+    )
+)]
+pub async fn submit_backfill_readings(
+    State(state): State<AppState>,
+    identity: Option<Extension<GatewayIdentity>>,
+    Path(batch_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<SubmitBackfillReadingsRequest>,
+) -> Result<Json<BackfillSubmission>> {
+    let gateway = gateway(identity)?;
+    Ok(Json(
+        state
+            .ami_backfill
+            .submit(&gateway, batch_id, &payload.readings)
+            .await?,
+    ))
+}
+
+/// List backfill batches
+/// GET /api/v1/admin/meter-backfills
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/meter-backfills",
+    tag = "meters",
+    params(BackfillQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Batches, newest first", body = Vec<BackfillBatch>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_backfills(
+    State(state): State<AppState>,
+    Query(params): Query<BackfillQuery>,
+) -> Result<Json<Vec<BackfillBatch>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, MAX_LIMIT);
+    Ok(Json(state.ami_backfill.list(params.status.as_deref(), limit).await?))
+}
+
+/// Get a backfill batch
+/// GET /api/v1/admin/meter-backfills/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/meter-backfills/{id}",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Backfill batch ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Batch with reading counts", body = BackfillBatch),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Batch not found")
+    )
+)]
+pub async fn get_backfill(State(state): State<AppState>, Path(batch_id): Path<Uuid>) -> Result<Json<BackfillBatch>> {
+    Ok(Json(state.ami_backfill.get(batch_id).await?))
+}
+
+/// Approve a backfill batch
+/// POST /api/v1/admin/meter-backfills/{id}/approve
+///
+/// Imports the staged readings, skipping any recorded live in the meantime,
+/// and mints them in the background when auto-mint is enabled.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/meter-backfills/{id}/approve",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Backfill batch ID")),
+    request_body = ReviewBackfillRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Batch approved and imported", body = BackfillBatch),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Batch not found"),
+        (status = 409, description = "Batch already reviewed")
+    )
+)]
+pub async fn approve_backfill(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(batch_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ReviewBackfillRequest>,
+) -> Result<Json<BackfillBatch>> {
+    Ok(Json(
+        state
+            .ami_backfill
+            .approve(user.0.sub, batch_id, payload.note.as_deref())
+            .await?,
+    ))
+}
+
+/// Reject a backfill batch
+/// POST /api/v1/admin/meter-backfills/{id}/reject
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/meter-backfills/{id}/reject",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Backfill batch ID")),
+    request_body = ReviewBackfillRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Batch rejected; staged readings discarded", body = BackfillBatch),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Batch not found"),
+        (status = 409, description = "Batch already reviewed")
+    )
+)]
+pub async fn reject_backfill(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(batch_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ReviewBackfillRequest>,
+) -> Result<Json<BackfillBatch>> {
+    Ok(Json(
+        state
+            .ami_backfill
+            .reject(user.0.sub, batch_id, payload.note.as_deref())
+            .await?,
+    ))
+}
+
+/// Retry minting of an approved backfill batch
+/// POST /api/v1/admin/meter-backfills/{id}/mint
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/meter-backfills/{id}/mint",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Backfill batch ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Minting restarted in the background", body = BackfillBatch),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Batch not found"),
+        (status = 409, description = "Batch not approved")
+    )
+)]
+pub async fn retry_backfill_mint(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<BackfillBatch>> {
+    Ok(Json(state.ami_backfill.retry_mint(user.0.sub, batch_id).await?))
+}
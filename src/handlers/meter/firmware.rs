@@ -0,0 +1,120 @@
+//! Meter Firmware Handlers
+//!
+//! Admin management of minimum firmware versions per manufacturer and the
+//! fleet firmware report.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::meter_firmware::{FirmwareAction, FirmwareFleetReport, FirmwarePolicy};
+use crate::AppState;
+
+/// Set the minimum firmware version for a manufacturer (replaces any existing policy)
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SetFirmwarePolicyRequest {
+    #[validate(length(max = 255), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub manufacturer: String,
+    /// Lowest supported version, dot-separated numbers (e.g. 2.4.1)
+    #[validate(length(max = 50))]
+    pub min_version: String,
+    /// `warn` accepts readings from older firmware and flags them; `reject` refuses them
+    pub action: FirmwareAction,
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+/// List firmware policies
+/// GET /api/v1/admin/meters/firmware/policies
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/meters/firmware/policies",
+    tag = "meters",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Minimum firmware per manufacturer", body = Vec<FirmwarePolicy>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_firmware_policies(State(state): State<AppState>) -> Result<Json<Vec<FirmwarePolicy>>> {
+    Ok(Json(state.meter_firmware.list_policies().await?))
+}
+
+/// Set a manufacturer's firmware policy
+/// PUT /api/v1/admin/meters/firmware/policies
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/meters/firmware/policies",
+    tag = "meters",
+    request_body = SetFirmwarePolicyRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Policy saved", body = FirmwarePolicy),
+        (status = 403, description = "Admin access required"),
+        (status = 422, description = "Invalid manufacturer or version")
+    )
+)]
+pub async fn set_firmware_policy(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<SetFirmwarePolicyRequest>,
+) -> Result<Json<FirmwarePolicy>> {
+    let note = payload
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+
+    Ok(Json(
+        state
+            .meter_firmware
+            .set_policy(user.0.sub, &payload.manufacturer, &payload.min_version, payload.action, note)
+            .await?,
+    ))
+}
+
+/// Remove a manufacturer's firmware policy
+/// DELETE /api/v1/admin/meters/firmware/policies/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/meters/firmware/policies/{id}",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Policy ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Policy removed", body = FirmwarePolicy),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Policy not found")
+    )
+)]
+pub async fn delete_firmware_policy(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(policy_id): Path<Uuid>,
+) -> Result<Json<FirmwarePolicy>> {
+    Ok(Json(state.meter_firmware.delete_policy(user.0.sub, policy_id).await?))
+}
+
+/// Fleet firmware report
+/// GET /api/v1/admin/meters/firmware/report
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/meters/firmware/report",
+    tag = "meters",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Meter counts per manufacturer and firmware version", body = FirmwareFleetReport),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_firmware_report(State(state): State<AppState>) -> Result<Json<FirmwareFleetReport>> {
+    Ok(Json(state.meter_firmware.fleet_report().await?))
+}
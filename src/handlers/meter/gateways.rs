@@ -0,0 +1,188 @@
+//! AMI Gateway Handlers
+//!
+//! Admin registration of utility data concentrators authenticated by mTLS
+//! client certificate, and binding of meters to gateways.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::services::meter_gateway::{fingerprint_from_pem, normalize_fingerprint, MeterGateway};
+use crate::AppState;
+
+/// Register a gateway by certificate fingerprint or PEM certificate
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RegisterGatewayRequest {
+    #[validate(length(max = 100), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub name: String,
+    /// Utility or operator running the gateway
+    #[validate(length(max = 100))]
+    pub operator: Option<String>,
+    /// SHA-256 certificate fingerprint (hex, colons optional)
+    pub cert_fingerprint: Option<String>,
+    /// PEM-encoded client certificate (alternative to `cert_fingerprint`)
+    pub cert_pem: Option<String>,
+}
+
+/// Bind meters to a gateway
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AssignGatewayMetersRequest {
+    #[validate(length(min = 1, max = 5000))]
+    pub meter_serials: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AssignGatewayMetersResponse {
+    pub gateway_id: Uuid,
+    pub assigned: usize,
+    /// Serials not found in the meter registry
+    pub not_found: Vec<String>,
+}
+
+/// List AMI gateways
+/// GET /api/v1/admin/meter-gateways
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/meter-gateways",
+    tag = "meters",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Registered gateways", body = Vec<MeterGateway>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_gateways(State(state): State<AppState>) -> Result<Json<Vec<MeterGateway>>> {
+    Ok(Json(state.meter_gateway_service.list().await?))
+}
+
+/// Register an AMI gateway
+/// POST /api/v1/admin/meter-gateways
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/meter-gateways",
+    tag = "meters",
+    request_body = RegisterGatewayRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Gateway registered", body = MeterGateway),
+        (status = 409, description = "Certificate already registered to an active gateway"),
+        (status = 422, description = "Invalid name or certificate")
+    )
+)]
+pub async fn register_gateway(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<RegisterGatewayRequest>,
+) -> Result<Json<MeterGateway>> {
+    let name = payload.name.trim();
+    let operator = payload
+        .operator
+        .as_deref()
+        .map(str::trim)
+        .filter(|o| !o.is_empty());
+
+    let fingerprint = match (&payload.cert_fingerprint, &payload.cert_pem) {
+        (Some(fp), None) => normalize_fingerprint(fp).ok_or_else(|| {
+            ApiError::invalid_field(
+                "cert_fingerprint",
+                "fingerprint_format",
+                "Expected a SHA-256 fingerprint (64 hex characters)",
+            )
+        })?,
+        (None, Some(pem)) => fingerprint_from_pem(pem).ok_or_else(|| {
+            ApiError::invalid_field("cert_pem", "certificate_format", "Invalid PEM certificate")
+        })?,
+        _ => {
+            return Err(ApiError::invalid_field(
+                "cert_fingerprint",
+                "required",
+                "Provide exactly one of cert_fingerprint or cert_pem",
+            ))
+        }
+    };
+
+    let gateway = state
+        .meter_gateway_service
+        .register(user.0.sub, name, operator, &fingerprint)
+        .await?;
+
+    Ok(Json(gateway))
+}
+
+/// Revoke an AMI gateway
+/// POST /api/v1/admin/meter-gateways/{id}/revoke
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/meter-gateways/{id}/revoke",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Gateway ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Gateway revoked", body = MeterGateway),
+        (status = 404, description = "Gateway not found"),
+        (status = 409, description = "Gateway already revoked")
+    )
+)]
+pub async fn revoke_gateway(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(gateway_id): Path<Uuid>,
+) -> Result<Json<MeterGateway>> {
+    Ok(Json(
+        state.meter_gateway_service.revoke(user.0.sub, gateway_id).await?,
+    ))
+}
+
+/// Bind meters to an AMI gateway
+/// PUT /api/v1/admin/meter-gateways/{id}/meters
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/meter-gateways/{id}/meters",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Gateway ID")),
+    request_body = AssignGatewayMetersRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Meters bound", body = AssignGatewayMetersResponse),
+        (status = 404, description = "Gateway not found"),
+        (status = 409, description = "Gateway is revoked"),
+        (status = 422, description = "Invalid serial list")
+    )
+)]
+pub async fn assign_gateway_meters(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(gateway_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<AssignGatewayMetersRequest>,
+) -> Result<Json<AssignGatewayMetersResponse>> {
+    let mut serials: Vec<String> = payload
+        .meter_serials
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    serials.sort();
+    serials.dedup();
+    if serials.is_empty() {
+        return Err(ApiError::invalid_field("meter_serials", "required", "Provide at least one meter serial"));
+    }
+
+    let not_found = state
+        .meter_gateway_service
+        .assign_meters(user.0.sub, gateway_id, &serials)
+        .await?;
+
+    Ok(Json(AssignGatewayMetersResponse {
+        gateway_id,
+        assigned: serials.len() - not_found.len(),
+        not_found,
+    }))
+}
@@ -0,0 +1,161 @@
+//! Grid Meter Data Handlers
+//!
+//! Upload of official DSO interval data (CSV or JSON), the discrepancy
+//! report against user-submitted readings and release of frozen meters.
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::services::grid_meter_data::{
+    parse_csv, GridDataImportReport, IntervalImportError, MeterDiscrepancy, OfficialInterval,
+};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GridDataUploadQuery {
+    /// Who supplied the data, default `dso`
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DiscrepancyQuery {
+    /// Start of the window, default 7 days before `to`
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive end of the window, default now
+    pub to: Option<DateTime<Utc>>,
+    /// Include meters without divergent intervals
+    #[serde(default)]
+    pub include_matching: bool,
+}
+
+/// Release a meter's minting freeze
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UnfreezeMeterRequest {
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+/// Upload official meter interval data
+/// POST /api/v1/admin/grid-data/intervals
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/grid-data/intervals",
+    tag = "meters",
+    params(GridDataUploadQuery),
+    request_body(
+        content = Vec<OfficialInterval>,
+        description = "JSON array, or text/csv with columns meter_serial,interval_start,interval_end,energy_generated,energy_consumed"
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Import report; nothing is stored when any row has errors", body = GridDataImportReport),
+        (status = 400, description = "Upload could not be read or has too many rows"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn upload_grid_intervals(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<GridDataUploadQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<GridDataImportReport>> {
+    let source = params
+        .source
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or("dso");
+    if source.len() > 100 {
+        return Err(ApiError::validation_field("source", "Source must be at most 100 characters"));
+    }
+
+    let is_csv = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/csv"));
+    let (intervals, errors): (Vec<(usize, OfficialInterval)>, Vec<IntervalImportError>) = if is_csv {
+        let text = std::str::from_utf8(&body).map_err(|_| ApiError::BadRequest("CSV must be UTF-8".into()))?;
+        parse_csv(text)
+    } else {
+        let intervals: Vec<OfficialInterval> = serde_json::from_slice(&body)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid interval JSON: {}", e)))?;
+        (intervals.into_iter().enumerate().collect(), Vec::new())
+    };
+
+    Ok(Json(
+        state
+            .grid_meter_data
+            .import(user.0.sub, source, intervals, errors)
+            .await?,
+    ))
+}
+
+/// Meter discrepancy report
+/// GET /api/v1/admin/grid-data/discrepancies
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/grid-data/discrepancies",
+    tag = "meters",
+    params(DiscrepancyQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Official vs submitted volumes per meter, most divergent first", body = Vec<MeterDiscrepancy>),
+        (status = 400, description = "Window ends before it starts"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_discrepancy_report(
+    State(state): State<AppState>,
+    Query(params): Query<DiscrepancyQuery>,
+) -> Result<Json<Vec<MeterDiscrepancy>>> {
+    let to = params.to.unwrap_or_else(Utc::now);
+    let from = params.from.unwrap_or(to - Duration::days(7));
+    if from >= to {
+        return Err(ApiError::BadRequest("from must be before to".into()));
+    }
+
+    Ok(Json(
+        state
+            .grid_meter_data
+            .discrepancy_report(from, to, !params.include_matching)
+            .await?,
+    ))
+}
+
+/// Release a meter whose minting was frozen by reconciliation
+/// POST /api/v1/admin/grid-data/meters/{serial}/unfreeze
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/grid-data/meters/{serial}/unfreeze",
+    tag = "meters",
+    params(("serial" = String, Path, description = "Meter serial number")),
+    request_body = UnfreezeMeterRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Minting released"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Meter is not frozen")
+    )
+)]
+pub async fn unfreeze_meter(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(serial): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UnfreezeMeterRequest>,
+) -> Result<Json<serde_json::Value>> {
+    let note = payload.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    state.grid_meter_data.unfreeze(user.0.sub, &serial, note).await?;
+    Ok(Json(serde_json::json!({ "meter_serial": serial, "mint_frozen": false })))
+}
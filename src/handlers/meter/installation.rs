@@ -0,0 +1,79 @@
+//! Meter Installation Handlers
+//!
+//! Rated capacity, panel orientation, installation date and inverter of a
+//! registered meter, readable and editable by its owner or an admin.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Claims;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::services::meter_installation::{MeterInstallation, MeterInstallationRecord};
+use crate::AppState;
+
+fn check_access(user: &Claims, record: &MeterInstallationRecord) -> Result<()> {
+    if record.user_id != user.sub && user.role.to_lowercase() != "admin" {
+        return Err(ApiError::Forbidden(
+            "You can only access installations of your own meters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Get a meter's installation
+/// GET /api/v1/meters/{serial}/installation
+#[utoipa::path(
+    get,
+    path = "/api/v1/meters/{serial}/installation",
+    tag = "meters",
+    params(("serial" = String, Path, description = "Meter serial number")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Installation and effective capacity", body = MeterInstallationRecord),
+        (status = 403, description = "Not your meter"),
+        (status = 404, description = "Meter not found")
+    )
+)]
+pub async fn get_meter_installation(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(serial): Path<String>,
+) -> Result<Json<MeterInstallationRecord>> {
+    let record = state.meter_installations.get(&serial).await?;
+    check_access(&user.0, &record)?;
+    Ok(Json(record))
+}
+
+/// Update a meter's installation
+/// PUT /api/v1/meters/{serial}/installation
+///
+/// Omitted fields keep their current value. The effective capacity feeds the
+/// minting guard's caps, over-capacity alerts and autopilot forecasts.
+#[utoipa::path(
+    put,
+    path = "/api/v1/meters/{serial}/installation",
+    tag = "meters",
+    params(("serial" = String, Path, description = "Meter serial number")),
+    request_body = MeterInstallation,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Installation updated", body = MeterInstallationRecord),
+        (status = 403, description = "Not your meter"),
+        (status = 404, description = "Meter not found"),
+        (status = 422, description = "Capacity, orientation or date out of range")
+    )
+)]
+pub async fn update_meter_installation(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(serial): Path<String>,
+    ValidatedJson(payload): ValidatedJson<MeterInstallation>,
+) -> Result<Json<MeterInstallationRecord>> {
+    let record = state.meter_installations.get(&serial).await?;
+    check_access(&user.0, &record)?;
+    Ok(Json(state.meter_installations.update(&serial, &payload).await?))
+}
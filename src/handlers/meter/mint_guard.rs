@@ -0,0 +1,172 @@
+//! Minting Guard Handlers
+//!
+//! Review of readings quarantined for exceeding their wallet's minting cap,
+//! wallet allowances and the rated capacity of meters the caps derive from.
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::mint_guard::{MeterCapacity, QuarantineStatus, QuarantinedReading, WalletMintAllowance};
+use crate::AppState;
+
+const MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct QuarantineQuery {
+    pub status: Option<QuarantineStatus>,
+    /// Default 100, at most 500
+    pub limit: Option<i64>,
+}
+
+/// Release or reject a quarantined reading
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReviewQuarantineRequest {
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+/// Rated capacity of a meter
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SetMeterCapacityRequest {
+    /// kW; null falls back to the configured default
+    #[schema(value_type = Option<String>, example = "5.5")]
+    pub capacity_kw: Option<Decimal>,
+}
+
+/// List readings quarantined by the minting guard
+/// GET /api/v1/admin/mint-guard/quarantine
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/mint-guard/quarantine",
+    tag = "meters",
+    params(QuarantineQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Quarantined readings, newest first", body = Vec<QuarantinedReading>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_quarantined_readings(
+    State(state): State<AppState>,
+    Query(params): Query<QuarantineQuery>,
+) -> Result<Json<Vec<QuarantinedReading>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, MAX_LIMIT);
+    Ok(Json(state.mint_guard.list(params.status, limit).await?))
+}
+
+/// Release a quarantined reading
+/// POST /api/v1/admin/mint-guard/quarantine/{id}/release
+///
+/// Mints the reading regardless of its wallet's cap. A failed mint leaves
+/// the reading pending with the error.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/mint-guard/quarantine/{id}/release",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Quarantine entry ID")),
+    request_body = ReviewQuarantineRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Reading minted", body = QuarantinedReading),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Quarantine entry not found"),
+        (status = 409, description = "Already reviewed"),
+        (status = 500, description = "Minting failed")
+    )
+)]
+pub async fn release_quarantined_reading(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ReviewQuarantineRequest>,
+) -> Result<Json<QuarantinedReading>> {
+    let note = payload.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    Ok(Json(state.mint_guard.release(user.0.sub, id, note).await?))
+}
+
+/// Reject a quarantined reading
+/// POST /api/v1/admin/mint-guard/quarantine/{id}/reject
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/mint-guard/quarantine/{id}/reject",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Quarantine entry ID")),
+    request_body = ReviewQuarantineRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Reading stays unminted", body = QuarantinedReading),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Quarantine entry not found"),
+        (status = 409, description = "Already reviewed")
+    )
+)]
+pub async fn reject_quarantined_reading(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ReviewQuarantineRequest>,
+) -> Result<Json<QuarantinedReading>> {
+    let note = payload.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    Ok(Json(state.mint_guard.reject(user.0.sub, id, note).await?))
+}
+
+/// Get a wallet's minting caps and recent mints
+/// GET /api/v1/admin/mint-guard/wallets/{wallet_address}
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/mint-guard/wallets/{wallet_address}",
+    tag = "meters",
+    params(("wallet_address" = String, Path, description = "Wallet address")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Caps and energy minted over the last hour and day", body = WalletMintAllowance),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_wallet_mint_allowance(
+    State(state): State<AppState>,
+    Path(wallet_address): Path<String>,
+) -> Result<Json<WalletMintAllowance>> {
+    Ok(Json(state.mint_guard.allowance(&wallet_address, Utc::now()).await?))
+}
+
+/// Set a meter's rated capacity
+/// PUT /api/v1/admin/meters/{id}/capacity
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/meters/{id}/capacity",
+    tag = "meters",
+    params(("id" = Uuid, Path, description = "Meter registry ID")),
+    request_body = SetMeterCapacityRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Capacity updated", body = MeterCapacity),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Meter not found"),
+        (status = 422, description = "Capacity not positive")
+    )
+)]
+pub async fn set_meter_capacity(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(meter_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<SetMeterCapacityRequest>,
+) -> Result<Json<MeterCapacity>> {
+    Ok(Json(
+        state
+            .mint_guard
+            .set_capacity(user.0.sub, meter_id, payload.capacity_kw)
+            .await?,
+    ))
+}
@@ -1,6 +1,7 @@
 //! Token minting from meter readings
 
 use axum::{extract::{State, Path}, Json};
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
 use tracing::{error, info};
@@ -8,6 +9,7 @@ use uuid::Uuid;
 
 use crate::{
     auth::middleware::AuthenticatedUser,
+    config::MintGuardMode,
     error::{ApiError, Result},
     services::BlockchainService,
     AppState,
@@ -46,15 +48,17 @@ async fn get_reading_by_id(db: &sqlx::PgPool, reading_id: Uuid) -> Result<MeterR
 }
 
 /// Helper to mark reading as minted
-async fn mark_as_minted(db: &sqlx::PgPool, reading_id: Uuid, tx_signature: &str) -> Result<()> {
+async fn mark_as_minted(db: &sqlx::PgPool, reading_id: Uuid, tx_signature: &str, commitment: &str) -> Result<()> {
     sqlx::query!(
         r#"
         UPDATE meter_readings 
-        SET minted = true, mint_tx_signature = $2
+        SET minted = true, mint_tx_signature = $2, mint_commitment = $3,
+            mint_status = 'completed', updated_at = NOW()
         WHERE id = $1
         "#,
         reading_id,
-        tx_signature
+        tx_signature,
+        commitment
     )
     .execute(db)
     .await
@@ -158,9 +162,9 @@ pub async fn mint_from_reading(
         .ok_or_else(|| ApiError::Internal("Failed to convert amount".to_string()))?;
 
     // Mint tokens using Energy Token program
-    let signature = state
+    let confirmation = state
         .blockchain_service
-        .mint_energy_tokens(
+        .mint_energy_tokens_confirmed(
             &authority_keypair,
             &_user_token_account, // create_ata_idempotent will handle this if needed, or we just pass it
             &wallet_pubkey,
@@ -173,14 +177,14 @@ pub async fn mint_from_reading(
             ApiError::Internal(format!("Blockchain minting failed: {}", e))
         })?;
 
-    let sig_str = signature.to_string();
+    let sig_str = confirmation.signature.to_string();
     info!(
         "Minted {} kWh for reading {}: {}",
         amount_f64, request.reading_id, sig_str
     );
 
     // Mark reading as minted
-    mark_as_minted(&state.db, request.reading_id, &sig_str).await?;
+    mark_as_minted(&state.db, request.reading_id, &sig_str, confirmation.commitment_str()).await?;
 
     Ok(Json(MintResponse {
         message: "Tokens minted successfully".to_string(),
@@ -206,8 +210,9 @@ pub async fn mint_from_reading(
         (status = 200, description = "Tokens minted successfully", body = MintResponse),
         (status = 400, description = "Invalid reading or already minted"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - You can only mint your own readings"),
+        (status = 403, description = "Forbidden - Not your reading, minting frozen, or over the wallet's minting cap"),
         (status = 404, description = "Reading not found"),
+        (status = 409, description = "Reading is already being minted"),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -241,69 +246,148 @@ pub async fn mint_user_reading(
         ));
     }
 
+    // Meters diverging from official grid data cannot mint until released
+    if let Some(reason) = state.grid_meter_data.reading_mint_freeze(reading_id).await? {
+        return Err(ApiError::Forbidden(format!("Minting is frozen for this meter: {}", reason)));
+    }
+
+    // Held readings are only minted by an admin release
+    if let Some(status) = state.mint_guard.reading_quarantine(reading_id).await? {
+        return Err(ApiError::Forbidden(format!(
+            "Reading is held by the minting guard ({})",
+            status
+        )));
+    }
+
     let kwh_amount = reading
         .kwh_amount
         .ok_or_else(|| ApiError::Internal("Missing kwh_amount".to_string()))?;
 
-    let wallet_address = reading.wallet_address.clone();
-
-    // Get authority keypair
-    let authority_keypair = state
-        .wallet_service
-        .get_authority_keypair()
-        .await
-        .map_err(|e| {
-            error!("Failed to get authority keypair: {}", e);
-            ApiError::Internal("Failed to access blockchain".to_string())
-        })?;
-
-    // Parse addresses
-    info!("Using token mint: {}", state.config.energy_token_mint);
-    let token_mint = BlockchainService::parse_pubkey(&state.config.energy_token_mint)
-        .map_err(|e| ApiError::Internal(format!("Invalid token mint: {}", e)))?;
-
-    let wallet_pubkey = BlockchainService::parse_pubkey(&wallet_address)
-        .map_err(|e| ApiError::BadRequest(format!("Invalid wallet address: {}", e)))?;
-
-    // Ensure user token account exists
-    let _user_token_account = state
-        .blockchain_service
-        .ensure_token_account_exists(&authority_keypair, &wallet_pubkey, &token_mint)
+    // Readings over the wallet's minting cap are refused or quarantined. The
+    // guard locks the wallet until the reading is committed as pending.
+    let (meter_serial, reading_timestamp): (Option<String>, DateTime<Utc>) = sqlx::query_as(
+        "SELECT meter_serial, COALESCE(reading_timestamp, timestamp) FROM meter_readings WHERE id = $1",
+    )
+    .bind(reading_id)
+    .fetch_one(&state.db)
+    .await?;
+    let mut tx = state.db.begin().await?;
+    if let Some(breach) = state
+        .mint_guard
+        .check(&mut tx, &reading.wallet_address, kwh_amount, reading_timestamp)
         .await
-        .map_err(|e| {
-            error!("Failed to ensure token account: {}", e);
-            ApiError::Internal("Failed to create token account".to_string())
-        })?;
-
-    // Mint tokens
-    let amount_f64 = kwh_amount
-        .to_f64()
-        .ok_or_else(|| ApiError::Internal("Failed to convert amount".to_string()))?;
+    {
+        if state.mint_guard.mode() == MintGuardMode::Quarantine {
+            state
+                .mint_guard
+                .quarantine(
+                    &mut tx,
+                    reading_id,
+                    meter_serial.as_deref().unwrap_or("unknown"),
+                    &reading.wallet_address,
+                    reading_timestamp,
+                    &breach,
+                )
+                .await?;
+            tx.commit().await?;
+            return Err(ApiError::Forbidden(format!(
+                "Reading quarantined for review by the minting guard: {}",
+                breach.message()
+            )));
+        }
+        return Err(ApiError::Forbidden(format!(
+            "Reading rejected by minting guard: {}",
+            breach.message()
+        )));
+    }
+    let claimed = sqlx::query(
+        "UPDATE meter_readings SET mint_status = 'pending', updated_at = NOW() \
+         WHERE id = $1 AND minted IS NOT TRUE AND mint_status IS DISTINCT FROM 'pending'",
+    )
+    .bind(reading_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if claimed == 0 {
+        return Err(ApiError::Conflict(
+            "Reading is already being minted".to_string(),
+        ));
+    }
+    tx.commit().await?;
 
-    // Mint tokens using Energy Token program
-    let signature = state
-        .blockchain_service
-        .mint_energy_tokens(
-            &authority_keypair,
-            &_user_token_account,
-            &wallet_pubkey,
-            &token_mint,
-            amount_f64,
-        )
-        .await
-        .map_err(|e| {
-            error!("Failed to mint tokens: {}", e);
-            ApiError::Internal(format!("Blockchain minting failed: {}", e))
-        })?;
+    let wallet_address = reading.wallet_address.clone();
 
-    let sig_str = signature.to_string();
+    let minted = async {
+        // Get authority keypair
+        let authority_keypair = state
+            .wallet_service
+            .get_authority_keypair()
+            .await
+            .map_err(|e| {
+                error!("Failed to get authority keypair: {}", e);
+                ApiError::Internal("Failed to access blockchain".to_string())
+            })?;
+
+        // Parse addresses
+        info!("Using token mint: {}", state.config.energy_token_mint);
+        let token_mint = BlockchainService::parse_pubkey(&state.config.energy_token_mint)
+            .map_err(|e| ApiError::Internal(format!("Invalid token mint: {}", e)))?;
+
+        let wallet_pubkey = BlockchainService::parse_pubkey(&wallet_address)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid wallet address: {}", e)))?;
+
+        // Ensure user token account exists
+        let _user_token_account = state
+            .blockchain_service
+            .ensure_token_account_exists(&authority_keypair, &wallet_pubkey, &token_mint)
+            .await
+            .map_err(|e| {
+                error!("Failed to ensure token account: {}", e);
+                ApiError::Internal("Failed to create token account".to_string())
+            })?;
+
+        // Mint tokens
+        let amount_f64 = kwh_amount
+            .to_f64()
+            .ok_or_else(|| ApiError::Internal("Failed to convert amount".to_string()))?;
+
+        // Mint tokens using Energy Token program
+        state
+            .blockchain_service
+            .mint_energy_tokens_confirmed(
+                &authority_keypair,
+                &_user_token_account,
+                &wallet_pubkey,
+                &token_mint,
+                amount_f64,
+            )
+            .await
+            .map_err(|e| {
+                error!("Failed to mint tokens: {}", e);
+                ApiError::Internal(format!("Blockchain minting failed: {}", e))
+            })
+    }
+    .await;
+    // A failed mint releases the reading for another attempt
+    let confirmation = match minted {
+        Ok(confirmation) => confirmation,
+        Err(e) => {
+            sqlx::query("UPDATE meter_readings SET mint_status = 'failed', updated_at = NOW() WHERE id = $1")
+                .bind(reading_id)
+                .execute(&state.db)
+                .await?;
+            return Err(e);
+        }
+    };
+
+    let sig_str = confirmation.signature.to_string();
     info!(
         "User {} minted {} kWh for reading {}: {}",
-        user.sub, amount_f64, reading_id, sig_str
+        user.sub, kwh_amount, reading_id, sig_str
     );
 
     // Mark reading as minted
-    mark_as_minted(&state.db, reading_id, &sig_str).await?;
+    mark_as_minted(&state.db, reading_id, &sig_str, confirmation.commitment_str()).await?;
 
     Ok(Json(MintResponse {
         message: "Tokens minted successfully".to_string(),
@@ -5,9 +5,25 @@
 //! - Retrieving reading history
 //! - Token minting from readings
 //! - Meter registration and verification
+//! - Admin registry search and lifecycle management
+//! - AMI gateway (mTLS client certificate) registry
+//! - AMI backfill of missed readings, minted after admin approval
+//! - Firmware version policies and fleet report
+//! - Official DSO interval data and reconciliation
+//! - Minting guard quarantine review and meter capacity
+//! - Meter installation metadata (capacity, orientation, inverter)
+//! - Tokenization parameters and runtime overrides
 
+pub mod admin;
+pub mod backfill;
+pub mod firmware;
+pub mod gateways;
+pub mod grid_data;
+pub mod installation;
+pub mod mint_guard;
 pub mod minting;
 pub mod stub;
+pub mod tokenization;
 pub mod types;
 pub mod zones;
 
@@ -9,16 +9,19 @@ use axum::{
 };
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 use serde_json;
 
 use crate::{
+    config::MintGuardMode,
     error::{ApiError, Result},
     services::{BlockchainService, meter_analyzer::{check_alerts, calculate_health_score}},
     handlers::meter::types::SubmitReadingRequest,
+    models::{pii::UserPii, EnergyKwh},
+    services::pii::sealed_key_id,
+    utils::{verify_signature, MeterReadingMessage, METER_MESSAGE_VERSION},
     AppState,
 };
 
@@ -370,12 +373,29 @@ pub async fn submit_reading(
     let submitted_at = Utc::now();
 
     // Validate the reading
-    let kwh_f64 = request.kwh_amount.to_f64().unwrap_or(0.0);
+    let kwh = EnergyKwh::from(request.kwh_amount);
+    let kwh_f64 = kwh.to_f64();
     
     if kwh_f64.abs() > 100.0 {
         return Err(ApiError::BadRequest("kWh amount exceeds maximum (100 kWh)".to_string()));
     }
 
+    // Check the device clock against receipt time (rejects far-future / stale readings)
+    let clock = state
+        .tokenization
+        .current()
+        .assess_reading_timestamp(request.reading_timestamp, submitted_at)
+        .map_err(|e| ApiError::validation_field("reading_timestamp", e.to_string()))?;
+    if !clock.within_tolerance {
+        warn!(
+            "⏱️ Clock drift of {}s for meter {:?}{}",
+            clock.drift_secs,
+            request.meter_serial,
+            if clock.corrected { ", using server receipt time" } else { "" }
+        );
+    }
+    let reading_timestamp = clock.effective_timestamp;
+
     info!("✅ Reading validated. ID: {}, Amount: {} kWh", reading_id, kwh_f64);
 
     // Validate meter is registered (if meter_serial provided)
@@ -401,8 +421,49 @@ pub async fn submit_reading(
         }
     }
 
+    // Verify signed envelope and reject replayed / out-of-order sequences
+    let mut sequence = None;
+    if let Some(ref meter_serial) = request.meter_serial {
+        let envelope = ReadingEnvelope {
+            meter_serial,
+            signature: request.meter_signature.as_deref(),
+            message_version: request.message_version,
+            sequence: request.sequence,
+            timestamp: request.reading_timestamp,
+            kwh_amount: format!("{:.6}", request.kwh_amount),
+            wallet: &wallet_address,
+        };
+        // Claimed with the reading insert below, before anything is minted
+        sequence = verify_reading_envelope(&state, &envelope).await?;
+    }
+
+    if let Some(ref meter_serial) = request.meter_serial {
+        record_clock_drift(&state.db, meter_serial, clock.drift_secs).await;
+    }
+
+    // Check the meter's firmware against its manufacturer's minimum version
+    let mut firmware_warning = None;
+    if let Some(ref meter_serial) = request.meter_serial {
+        match state
+            .meter_firmware
+            .check_reading(meter_serial, request.firmware_version.as_deref())
+            .await
+        {
+            Ok(verdict) if verdict.rejects() => {
+                warn!("📟 Rejecting reading from meter {}: {}", meter_serial, verdict.message());
+                return Err(ApiError::validation_field("firmware_version", verdict.message()));
+            }
+            Ok(verdict) if verdict.warns() => {
+                warn!("📟 Meter {}: {}", meter_serial, verdict.message());
+                firmware_warning = Some(verdict.message());
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to check firmware for meter {}: {}", meter_serial, e),
+        }
+    }
+
     // Update aggregate grid status in dashboard service immediately after validation
-    let _ = state.dashboard_service.handle_meter_reading(kwh_f64, request.meter_serial.as_deref().unwrap_or("unknown"), zone_id).await;
+    let _ = state.dashboard_service.handle_meter_reading(kwh, request.meter_serial.as_deref().unwrap_or("unknown"), zone_id).await;
 
     // Check for alerts and broadcast via WebSocket
     let meter_id = request.meter_serial.clone().unwrap_or_else(|| "unknown".to_string());
@@ -429,8 +490,129 @@ pub async fn submit_reading(
     let mut mint_tx_signature: Option<String> = None;
     let mut message = "Reading received".to_string();
 
+    // Meters diverging from official grid data keep submitting but do not mint
+    let mint_freeze = match request.meter_serial.as_deref() {
+        Some(serial) => state.grid_meter_data.meter_mint_freeze(serial).await.unwrap_or_else(|e| {
+            warn!("Failed to check mint freeze for meter {}: {}", serial, e);
+            None
+        }),
+        None => None,
+    };
+
+    // Store the reading, with its sequence claim, before anything reaches
+    // the chain: a replay is rejected even if the mint result cannot be
+    // recorded afterwards
+    let meter_serial = request.meter_serial.clone().unwrap_or_else(|| "unknown".to_string());
+    let (meter_uuid, user_uuid) = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT id, user_id FROM meters WHERE serial_number = $1"
+    )
+    .bind(&meter_serial)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| {
+        warn!("⚠️ Meter info not found for {}, reading not persisted", meter_serial);
+        ApiError::NotFound(format!("Meter {} is not registered. Please register the meter first.", meter_serial))
+    })?;
+
+    let mut tx = state.db.begin().await?;
+    if let Some(sequence) = sequence {
+        claim_reading_sequence(&mut tx, &meter_serial, sequence).await?;
+    }
+
+    // Readings over the wallet's minting cap are refused or quarantined. The
+    // guard locks the wallet until the pending reading is committed.
+    let mint_breach = if mint_freeze.is_none() && kwh_f64 > 0.0 {
+        state
+            .mint_guard
+            .check(&mut tx, &wallet_address, kwh.value(), reading_timestamp)
+            .await
+    } else {
+        None
+    };
+    if let Some(breach) = &mint_breach {
+        if state.mint_guard.mode() == MintGuardMode::Reject {
+            return Err(ApiError::BadRequest(format!(
+                "Reading rejected by minting guard: {}",
+                breach.message()
+            )));
+        }
+    }
+
+    let on_chain = mint_freeze.is_none() && mint_breach.is_none() && kwh_f64 != 0.0;
+    sqlx::query(
+        "INSERT INTO meter_readings (
+            id, meter_serial, meter_id, user_id, wallet_address, 
+            timestamp, reading_timestamp, kwh_amount,
+            energy_generated, energy_consumed, surplus_energy, deficit_energy,
+            voltage, current_amps, power_factor, frequency, temperature,
+            thd_voltage, thd_current,
+            latitude, longitude, battery_level, health_score,
+            mint_status, message_version, sequence,
+            device_timestamp, clock_drift_secs, timestamp_corrected, created_at
+         ) VALUES ($1, $2, $3, $4, $5, $6, $6, $7, $8, $9, $10, $11, 
+                   $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25,
+                   $26, $27, $28, NOW())"
+    )
+    .bind(reading_id)
+    .bind(&meter_serial)
+    .bind(meter_uuid)
+    .bind(user_uuid)
+    .bind(&wallet_address)
+    .bind(reading_timestamp)
+    .bind(kwh_f64)
+    // Energy data
+    .bind(request.energy_generated)
+    .bind(request.energy_consumed)
+    .bind(request.surplus_energy)
+    .bind(request.deficit_energy)
+    // Electrical parameters
+    .bind(request.voltage)
+    .bind(request.current)
+    .bind(request.power_factor)
+    .bind(request.frequency)
+    .bind(request.temperature)
+    // THD (Total Harmonic Distortion)
+    .bind(request.thd_voltage)
+    .bind(request.thd_current)
+    // GPS
+    .bind(request.latitude)
+    .bind(request.longitude)
+    // Battery
+    .bind(request.battery_level)
+    // Health score
+    .bind(health_score)
+    // Minting status; the result is recorded once the mint or burn returns
+    .bind(on_chain.then_some(MINT_PENDING))
+    // Signed envelope
+    .bind(request.message_version.map(|v| v as i16))
+    .bind(request.sequence.and_then(|seq| i64::try_from(seq).ok()))
+    // Clock drift
+    .bind(clock.device_timestamp)
+    .bind(clock.drift_secs as i32)
+    .bind(clock.corrected)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!("❌ Failed to save reading to database: {}", e);
+        e
+    })?;
+    if let Some(breach) = &mint_breach {
+        state
+            .mint_guard
+            .quarantine(&mut tx, reading_id, &meter_serial, &wallet_address, reading_timestamp, breach)
+            .await?;
+    }
+    tx.commit().await?;
+
+    info!("✅ Reading {} saved to database", reading_id);
+    state.meter_fleet_health.record_reading(&meter_serial, alerts.len()).await;
+
     // Attempt blockchain minting if amount is positive
-    if kwh_f64 > 0.0 {
+    if let Some(reason) = mint_freeze {
+        message = format!("Reading received (minting frozen: {})", reason);
+    } else if let Some(breach) = &mint_breach {
+        message = format!("Reading received (minting quarantined for review: {})", breach.message());
+    } else if kwh_f64 > 0.0 {
         info!("🔗 Triggering blockchain mint for {} kWh", kwh_f64);
 
         // Get authority keypair
@@ -460,7 +642,7 @@ pub async fn submit_reading(
                                 
                                 // Convert kWh to Wh for on-chain storage (u64)
                                 let energy_wh = (kwh_f64 * 1000.0) as u64;
-                                let reading_timestamp = request.reading_timestamp.timestamp();
+                                let reading_timestamp = reading_timestamp.timestamp();
                                 
                                 // Step 1: Update on-chain meter reading via Registry program
                                 // Note: Authority must be set as oracle via set_oracle_authority on Registry
@@ -514,7 +696,7 @@ pub async fn submit_reading(
                                                 &Uuid::nil(),
                                                 &wallet_address,
                                                 request.meter_serial.as_deref().unwrap_or("unknown"),
-                                                kwh_f64,
+                                                kwh,
                                                 Some(power),
                                                 request.voltage,
                                                 request.current,
@@ -529,7 +711,7 @@ pub async fn submit_reading(
                                                 &Uuid::nil(),
                                                 &wallet_address,
                                                 request.meter_serial.as_deref().unwrap_or("unknown"),
-                                                kwh_f64,
+                                                kwh,
                                                 tokens_minted,
                                                 &sig_str,
                                             )
@@ -590,7 +772,7 @@ pub async fn submit_reading(
                                 
                                 // Convert kWh to Wh for on-chain storage (u64)
                                 let energy_wh = (burn_amount * 1000.0) as u64;
-                                let reading_timestamp = request.reading_timestamp.timestamp();
+                                let reading_timestamp = reading_timestamp.timestamp();
                                 
                                 // Step 1: Update on-chain meter reading via Registry program
                                 // For consumption, energy_generated=0, energy_consumed=energy_wh
@@ -640,7 +822,7 @@ pub async fn submit_reading(
                                                 &Uuid::nil(),
                                                 &wallet_address,
                                                 request.meter_serial.as_deref().unwrap_or("unknown"),
-                                                -burn_amount, // Negative to indicate consumption
+                                                kwh, // Negative to indicate consumption
                                                 Some(-burn_amount), // power (negative for consumption)
                                                 request.voltage,
                                                 request.current,
@@ -672,79 +854,19 @@ pub async fn submit_reading(
         }
     }
 
-    // Store reading to database with all telemetry data
-    let meter_serial = request.meter_serial.clone().unwrap_or_else(|| "unknown".to_string());
-    
-    // Get meter_id and user_id from database
-    let meter_info = sqlx::query_as::<_, (Uuid, Uuid)>(
-        "SELECT id, user_id FROM meters WHERE serial_number = $1"
-    )
-    .bind(&meter_serial)
-    .fetch_optional(&state.db)
-    .await
-    .ok()
-    .flatten();
-
-    if let Some((meter_uuid, user_uuid)) = meter_info {
-        let insert_result = sqlx::query(
-            "INSERT INTO meter_readings (
-                id, meter_serial, meter_id, user_id, wallet_address, 
-                timestamp, reading_timestamp, kwh_amount,
-                energy_generated, energy_consumed, surplus_energy, deficit_energy,
-                voltage, current_amps, power_factor, frequency, temperature,
-                thd_voltage, thd_current,
-                latitude, longitude, battery_level, health_score,
-                minted, mint_tx_signature, created_at
-             ) VALUES ($1, $2, $3, $4, $5, $6, $6, $7, $8, $9, $10, $11, 
-                       $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, NOW())"
-        )
-        .bind(reading_id)
-        .bind(&meter_serial)
-        .bind(meter_uuid)
-        .bind(user_uuid)
-        .bind(&wallet_address)
-        .bind(request.reading_timestamp)
-        .bind(kwh_f64)
-        // Energy data
-        .bind(request.energy_generated)
-        .bind(request.energy_consumed)
-        .bind(request.surplus_energy)
-        .bind(request.deficit_energy)
-        // Electrical parameters
-        .bind(request.voltage)
-        .bind(request.current)
-        .bind(request.power_factor)
-        .bind(request.frequency)
-        .bind(request.temperature)
-        // THD (Total Harmonic Distortion)
-        .bind(request.thd_voltage)
-        .bind(request.thd_current)
-        // GPS
-        .bind(request.latitude)
-        .bind(request.longitude)
-        // Battery
-        .bind(request.battery_level)
-        // Health score
-        .bind(health_score)
-        // Minting status
-        .bind(minted)
-        .bind(&mint_tx_signature)
-        .execute(&state.db)
-        .await;
+    if on_chain {
+        record_mint_result(&state.db, reading_id, minted, mint_tx_signature.as_deref()).await;
+    }
 
-        match insert_result {
-            Ok(_) => info!("✅ Reading {} saved to database", reading_id),
-            Err(e) => error!("❌ Failed to save reading to database: {}", e),
-        }
-    } else {
-        warn!("⚠️ Meter info not found for {}, reading not persisted", meter_serial);
+    if let Some(warning) = firmware_warning {
+        message = format!("{}. {}", message, warning);
     }
 
     Ok(Json(MeterReadingResponse {
         id: reading_id,
         wallet_address,
         kwh_amount: request.kwh_amount,
-        reading_timestamp: request.reading_timestamp,
+        reading_timestamp,
         submitted_at,
         minted,
         mint_tx_signature,
@@ -752,6 +874,202 @@ pub async fn submit_reading(
     }))
 }
 
+/// Update per-meter clock drift statistics used by the admin drift report.
+///
+/// Failures are logged and ignored; drift tracking must never block a reading.
+pub(crate) async fn record_clock_drift(db: &sqlx::PgPool, meter_serial: &str, drift_secs: i64) {
+    let drift = drift_secs.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+    let result = sqlx::query(
+        "UPDATE meter_registry
+         SET last_clock_drift_secs = $2,
+             max_abs_clock_drift_secs = GREATEST(COALESCE(max_abs_clock_drift_secs, 0), ABS($2)),
+             drift_sample_count = COALESCE(drift_sample_count, 0) + 1,
+             last_drift_at = NOW()
+         WHERE meter_serial = $1"
+    )
+    .bind(meter_serial)
+    .bind(drift)
+    .execute(db)
+    .await;
+
+    if let Err(e) = result {
+        warn!("Failed to record clock drift for meter {}: {}", meter_serial, e);
+    }
+}
+
+/// Signed envelope fields of a submitted reading
+pub(crate) struct ReadingEnvelope<'a> {
+    pub meter_serial: &'a str,
+    pub signature: Option<&'a str>,
+    pub message_version: Option<u8>,
+    pub sequence: Option<u64>,
+    pub timestamp: DateTime<Utc>,
+    /// Amount in the fixed precision the meter signs
+    pub kwh_amount: String,
+    pub wallet: &'a str,
+}
+
+/// Verify the signed reading envelope and check per-meter sequence ordering.
+///
+/// Unsigned readings (simulator flow) and meters without a registered public key
+/// are accepted as before, until the meter has submitted a sequenced reading:
+/// from then on only signed v2 envelopes are accepted, so a captured payload
+/// cannot be replayed by downgrading it. Returns the sequence to claim with
+/// [`claim_reading_sequence`] in the transaction that stores the reading.
+pub(crate) async fn verify_reading_envelope(
+    state: &AppState,
+    envelope: &ReadingEnvelope<'_>,
+) -> Result<Option<i64>> {
+    let meter_serial = envelope.meter_serial;
+    let (public_key, last_sequence) = sqlx::query_as::<_, (Option<String>, Option<i64>)>(
+        "SELECT meter_public_key, last_sequence FROM meter_registry WHERE meter_serial = $1"
+    )
+    .bind(meter_serial)
+    .fetch_optional(&state.db)
+    .await?
+    .unwrap_or((None, None));
+
+    let Some(signature) = envelope.signature else {
+        if last_sequence.is_some() {
+            return Err(ApiError::validation_field(
+                "meter_signature",
+                format!("Meter {} submits sequenced readings; unsigned readings are rejected", meter_serial),
+            ));
+        }
+        return Ok(None);
+    };
+
+    let version = envelope.message_version.unwrap_or(1);
+    if version == 0 || version > METER_MESSAGE_VERSION {
+        return Err(ApiError::validation_field(
+            "message_version",
+            format!("Unsupported message version {} (max {})", version, METER_MESSAGE_VERSION),
+        ));
+    }
+
+    let Some(public_key) = public_key else {
+        warn!("⚠️ Meter {} has no registered public key, skipping signature check", meter_serial);
+        return Ok(None);
+    };
+
+    let message = MeterReadingMessage {
+        version,
+        meter_serial: meter_serial.to_string(),
+        timestamp: envelope.timestamp.to_rfc3339(),
+        kwh_amount: envelope.kwh_amount.clone(),
+        wallet: envelope.wallet.to_string(),
+        sequence: envelope.sequence.unwrap_or(0),
+    };
+
+    let verified = verify_signature(&public_key, signature, &message);
+    state
+        .meter_fleet_health
+        .record_signature_check(meter_serial, matches!(verified, Ok(true)))
+        .await;
+    match verified {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(ApiError::validation_field("meter_signature", "Meter signature does not match reading"));
+        }
+        Err(e) => return Err(ApiError::validation_field("meter_signature", e)),
+    }
+
+    if !message.has_sequence() {
+        if last_sequence.is_some() {
+            warn!("🚫 Rejected v1 reading for meter {} after it moved to sequenced messages", meter_serial);
+            return Err(ApiError::validation_field(
+                "message_version",
+                format!("Meter {} submits sequenced readings; v1 messages are rejected", meter_serial),
+            ));
+        }
+        warn!("⚠️ Meter {} submitted legacy v1 signed reading without replay protection", meter_serial);
+        return Ok(None);
+    }
+
+    let sequence = envelope
+        .sequence
+        .filter(|seq| *seq > 0)
+        .and_then(|seq| i64::try_from(seq).ok())
+        .ok_or_else(|| ApiError::validation_field("sequence", "A positive sequence number is required for v2 messages"))?;
+
+    if last_sequence.is_some_and(|last| sequence <= last) {
+        return Err(replayed_sequence(meter_serial, sequence));
+    }
+
+    Ok(Some(sequence))
+}
+
+/// Advance the meter's last accepted sequence.
+///
+/// Run it in the transaction that stores the reading, so a reading that is not
+/// stored does not use up its sequence. The compare-and-set keeps concurrent
+/// submissions from both passing, and its row lock holds back other readings of
+/// the meter until that transaction ends.
+pub(crate) async fn claim_reading_sequence(
+    conn: &mut sqlx::PgConnection,
+    meter_serial: &str,
+    sequence: i64,
+) -> Result<()> {
+    let accepted = sqlx::query(
+        "UPDATE meter_registry
+         SET last_sequence = $2, last_sequence_at = NOW()
+         WHERE meter_serial = $1 AND (last_sequence IS NULL OR last_sequence < $2)"
+    )
+    .bind(meter_serial)
+    .bind(sequence)
+    .execute(conn)
+    .await?
+    .rows_affected();
+
+    if accepted == 0 {
+        return Err(replayed_sequence(meter_serial, sequence));
+    }
+    Ok(())
+}
+
+/// `meter_readings.mint_status` of a reading stored before its mint or burn
+/// was sent to the chain
+pub(crate) const MINT_PENDING: &str = "pending";
+
+/// Record the outcome of the mint or burn of a reading stored as pending.
+///
+/// The chain operation has already happened, so a failure here is logged and
+/// the reading stays pending for reconciliation rather than failing the request.
+pub(crate) async fn record_mint_result(
+    db: &sqlx::PgPool,
+    reading_id: Uuid,
+    minted: bool,
+    tx_signature: Option<&str>,
+) {
+    let status = if tx_signature.is_some() { "completed" } else { "failed" };
+    let result = sqlx::query(
+        "UPDATE meter_readings
+         SET minted = $2, mint_tx_signature = $3, mint_status = $4, updated_at = NOW()
+         WHERE id = $1 AND mint_status = 'pending'"
+    )
+    .bind(reading_id)
+    .bind(minted)
+    .bind(tx_signature)
+    .bind(status)
+    .execute(db)
+    .await;
+
+    if let Err(e) = result {
+        error!(
+            "❌ CRITICAL: Failed to record mint result of reading {} (tx {:?}): {}",
+            reading_id, tx_signature, e
+        );
+    }
+}
+
+fn replayed_sequence(meter_serial: &str, sequence: i64) -> ApiError {
+    warn!("🚫 Rejected replayed reading for meter {} (sequence {})", meter_serial, sequence);
+    ApiError::Conflict(format!(
+        "Sequence {} for meter {} is not greater than the last accepted sequence",
+        sequence, meter_serial
+    ))
+}
+
 /// Health check for meter service
 pub async fn meter_health() -> &'static str {
     "Meter stub service is running"
@@ -843,16 +1161,18 @@ pub async fn register_meter_by_id(
             info!("✅ Meter {} registered successfully", request.meter_id);
             
             // Also insert into meter_registry for FK constraints
+            let sealed_address = state.pii.seal(&location).await?;
             let _ = sqlx::query(
-                "INSERT INTO meter_registry (id, user_id, meter_serial, meter_type, location_address, meter_key_hash, verification_method, verification_status, zone_id)
-                 VALUES ($1, $2, $3, $4, $5, 'simulator_hash', 'auto', 'verified', $6)
+                "INSERT INTO meter_registry (id, user_id, meter_serial, meter_type, location_address_encrypted, pii_key_id, meter_key_hash, verification_method, verification_status, zone_id)
+                 VALUES ($1, $2, $3, $4, $5, $6, 'simulator_hash', 'auto', 'verified', $7)
                  ON CONFLICT (meter_serial) DO NOTHING"
             )
             .bind(meter_id)
             .bind(system_user_id)
             .bind(&request.meter_id)
             .bind(&meter_type)
-            .bind(&location)
+            .bind(&sealed_address)
+            .bind(sealed_key_id(&sealed_address))
             .bind(request.zone_id)
             .execute(&state.db)
             .await;
@@ -887,15 +1207,23 @@ async fn get_or_create_simulator_user(state: &AppState, wallet_address: &str) ->
     // Create a new simulator user
     let user_id = Uuid::new_v4();
     let email = format!("simulator_{}@gridtokenx.local", &wallet_address[..8.min(wallet_address.len())]);
-    
+    let sealed = UserPii {
+        email: email.clone(),
+        ..Default::default()
+    }
+    .seal(&state.pii)
+    .await?;
+
     let insert_result = sqlx::query(
-        "INSERT INTO users (id, email, username, password_hash, wallet_address, role, email_verified, created_at)
-         VALUES ($1, $2, $3, 'simulator_no_password', $4, 'prosumer', true, NOW())
-         ON CONFLICT (email) DO UPDATE SET wallet_address = $4
+        "INSERT INTO users (id, email_encrypted, email_digest, pii_key_id, username, password_hash, wallet_address, role, email_verified, created_at)
+         VALUES ($1, $2, $3, $4, $5, 'simulator_no_password', $6, 'prosumer', true, NOW())
+         ON CONFLICT (email_digest) DO UPDATE SET wallet_address = $6
          RETURNING id"
     )
     .bind(user_id)
-    .bind(&email)
+    .bind(&sealed.email_encrypted)
+    .bind(&sealed.email_digest)
+    .bind(sealed.key_id)
     .bind(&email)
     .bind(wallet_address)
     .execute(&state.db)
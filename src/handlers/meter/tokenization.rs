@@ -0,0 +1,52 @@
+//! Tokenization Parameter Handlers
+//!
+//! Effective tokenization parameters and their runtime overrides.
+
+use axum::{extract::State, Json};
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::config::TokenizationOverrides;
+use crate::error::Result;
+use crate::services::tokenization_settings::EffectiveTokenization;
+use crate::AppState;
+
+/// Get the tokenization parameters in effect
+/// GET /api/v1/admin/tokenization
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/tokenization",
+    tag = "meters",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Environment configuration with runtime overrides applied", body = EffectiveTokenization),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_tokenization_parameters(State(state): State<AppState>) -> Json<EffectiveTokenization> {
+    Json(state.tokenization.effective())
+}
+
+/// Replace the runtime tokenization overrides
+/// PUT /api/v1/admin/tokenization
+///
+/// Omitted parameters return to their environment value; an empty body
+/// clears every override. Other instances pick the change up within a minute.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/tokenization",
+    tag = "meters",
+    request_body = TokenizationOverrides,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Overrides stored and applied", body = EffectiveTokenization),
+        (status = 400, description = "Resulting parameters out of range or inconsistent"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn update_tokenization_overrides(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(payload): Json<TokenizationOverrides>,
+) -> Result<Json<EffectiveTokenization>> {
+    Ok(Json(state.tokenization.update(user.0.sub, payload).await?))
+}
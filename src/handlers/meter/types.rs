@@ -25,6 +25,14 @@ pub struct SubmitReadingRequest {
     pub meter_serial: Option<String>,
     pub meter_id: Option<Uuid>,
 
+    // Signed message envelope (replay protection)
+    /// Canonical message version the signature was produced over (default: 1)
+    pub message_version: Option<u8>,
+    /// Per-meter monotonic sequence number, required for version >= 2
+    pub sequence: Option<u64>,
+    /// Firmware version running on the meter
+    pub firmware_version: Option<String>,
+
     // Energy Data (kWh)
     pub energy_generated: Option<f64>,
     pub energy_consumed: Option<f64>,
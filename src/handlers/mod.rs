@@ -6,6 +6,26 @@
 //! - `blockchain/` - Blockchain interaction handlers
 //! - `websocket/` - WebSocket handlers
 //! - `notifications` - Push notification handlers
+//! - `budget_alerts` - Household spend, consumption and price alerts
+//! - `invoices` - Monthly statements and signed downloads
+//! - `data_exports` - Asynchronous data exports and signed downloads
+//! - `prepaid` - Prepaid fiat credit and payment provider webhook
+//! - `rate_limits` - Admin meter submission limit overrides
+//! - `network_acl` - Admin management of network allow/deny lists
+//! - `audit_retention` - Audit retention policy and legal holds
+//! - `audit_logs` - Admin audit log search
+//! - `erc_issuers` - Admin management of certificate issuers
+//! - `emission_factors` - Admin management of grid emission factors
+//! - `fx_rates` - Settlement currency and fiat reference rates
+//! - `markets` - Market registry and per-market orderbooks
+//! - `swap_pools` - Swap pool statistics and price history
+//! - `public_market` - Anonymous, cached public market data
+//! - `locale` - Preferred language for notifications and emails
+//! - `fix_sessions` - Admin provisioning of FIX gateway sessions
+//! - `wallet_links` - Wallet link challenges and link history
+//! - `deployment` - Branding and modules of this deployment
+//! - `participants` - Admin lookup of public participant IDs
+//! - `route_permissions` - Admin route permission matrix
 //! - `common/` - Shared utilities (extractors, response types)
 //! - `_disabled/` - Disabled/legacy handlers (not exported)
 
@@ -23,7 +43,40 @@ pub mod websocket;
 pub mod rpc;
 pub mod proxy;
 pub mod notifications;
+pub mod budget_alerts;
+pub mod rate_limits;
+pub mod delegation;
 pub mod wallets;
+pub mod invoices;
+pub mod prepaid;
+pub mod vesting;
+pub mod referrals;
+pub mod usage;
+pub mod attachments;
+pub mod network_acl;
+pub mod address_book;
+pub mod audit_retention;
+pub mod audit_logs;
+pub mod erc_issuers;
+pub mod emission_factors;
+pub mod fx_rates;
+pub mod markets;
+pub mod swap_pools;
+pub mod public_market;
+pub mod locale;
+pub mod fix_sessions;
+pub mod wallet_links;
+pub mod data_fixes;
+pub mod maintenance;
+pub mod ledger_history;
+pub mod pii_keys;
+pub mod deployment;
+pub mod participants;
+pub mod route_permissions;
+pub mod transfers;
+pub mod jobs;
+pub mod data_exports;
+pub mod partners;
 
 // Shared utilities
 pub mod common;
@@ -0,0 +1,151 @@
+//! Network Access Control Handler
+//!
+//! Admin management of CIDR allow/deny rules for admin and AMI routes
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::services::network_acl::{ClientIp, NetworkRule, RouteGroup, RuleAction};
+use crate::AppState;
+
+/// Current rules and the caller's resolved address
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NetworkAclOverview {
+    /// Address the API sees for this request
+    pub client_ip: Option<String>,
+    pub rules: Vec<NetworkRule>,
+}
+
+/// New allow or deny rule
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateNetworkRuleRequest {
+    pub route_group: RouteGroup,
+    pub action: RuleAction,
+    /// IPv4/IPv6 CIDR, or a bare address for a single host
+    #[schema(example = "10.20.0.0/16")]
+    pub cidr: String,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    /// Apply even if it blocks the caller from admin routes
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeleteNetworkRuleQuery {
+    /// Apply even if it blocks the caller from admin routes
+    pub force: Option<bool>,
+}
+
+/// List network access rules
+/// GET /api/v1/admin/network-acl
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/network-acl",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Configured and runtime rules", body = NetworkAclOverview),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_network_rules(
+    State(state): State<AppState>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+) -> Result<Json<NetworkAclOverview>> {
+    Ok(Json(NetworkAclOverview {
+        client_ip: client_ip.map(|ip| ip.to_string()),
+        rules: state.network_acl.rules().await,
+    }))
+}
+
+/// Add a network access rule
+/// POST /api/v1/admin/network-acl
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/network-acl",
+    tag = "admin",
+    request_body = CreateNetworkRuleRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Rule added and applied", body = NetworkRule),
+        (status = 403, description = "Admin access required"),
+        (status = 409, description = "Duplicate rule, or the change would lock out the caller"),
+        (status = 422, description = "Invalid CIDR or description")
+    )
+)]
+pub async fn create_network_rule(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    ValidatedJson(payload): ValidatedJson<CreateNetworkRuleRequest>,
+) -> Result<Json<NetworkRule>> {
+    let cidr = payload
+        .cidr
+        .trim()
+        .parse()
+        .map_err(|_| {
+            ApiError::invalid_field("cidr", "cidr_format", "Invalid CIDR (e.g. 10.20.0.0/16 or 2001:db8::/32)")
+        })?;
+    let description = payload
+        .description
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+
+    let rule = state
+        .network_acl
+        .add_rule(
+            user.0.sub,
+            client_ip,
+            payload.route_group,
+            payload.action,
+            cidr,
+            description,
+            payload.force,
+        )
+        .await?;
+
+    Ok(Json(rule))
+}
+
+/// Remove a runtime network access rule
+/// DELETE /api/v1/admin/network-acl/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/network-acl/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Rule ID"),
+        DeleteNetworkRuleQuery
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Rule removed", body = NetworkRule),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Rule not found (configured rules cannot be removed)"),
+        (status = 409, description = "The change would lock out the caller")
+    )
+)]
+pub async fn delete_network_rule(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    Path(rule_id): Path<Uuid>,
+    Query(params): Query<DeleteNetworkRuleQuery>,
+) -> Result<Json<NetworkRule>> {
+    let rule = state
+        .network_acl
+        .remove_rule(user.0.sub, client_ip, rule_id, params.force.unwrap_or(false))
+        .await?;
+
+    Ok(Json(rule))
+}
@@ -1,6 +1,7 @@
 //! Notifications Handler
 //!
-//! Handles listing, reading, and managing notification preferences
+//! Handles the notification inbox (listing, unread counter, marking read)
+//! and notification preferences
 
 use axum::{extract::{State, Path, Query}, response::Json};
 use serde::Deserialize;
@@ -11,7 +12,7 @@ use crate::auth::middleware::AuthenticatedUser;
 use crate::error::{ApiError, Result};
 use crate::models::notification::{
     Notification, NotificationPreferences, UpdatePreferencesRequest,
-    NotificationListResponse, NotificationType,
+    NotificationListResponse, NotificationType, UnreadCountResponse,
 };
 use crate::AppState;
 
@@ -55,7 +56,7 @@ pub async fn list_notifications(
             Notification,
             r#"
             SELECT id, user_id, notification_type as "notification_type!: NotificationType",
-                   title, message, data, read as "read!", created_at as "created_at!"
+                   title, message, data, read as "read!", read_at, created_at as "created_at!"
             FROM notifications
             WHERE user_id = $1 AND read = false
             ORDER BY created_at DESC
@@ -70,7 +71,7 @@ pub async fn list_notifications(
             Notification,
             r#"
             SELECT id, user_id, notification_type as "notification_type!: NotificationType",
-                   title, message, data, read as "read!", created_at as "created_at!"
+                   title, message, data, read as "read!", read_at, created_at as "created_at!"
             FROM notifications
             WHERE user_id = $1
             ORDER BY created_at DESC
@@ -107,6 +108,32 @@ pub async fn list_notifications(
     }))
 }
 
+/// Get the unread notification counter
+/// GET /api/v1/notifications/unread-count
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/unread-count",
+    tag = "notifications",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Unread notifications in the inbox", body = UnreadCountResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_unread_count(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<UnreadCountResponse>> {
+    let unread_count = state
+        .notification_dispatcher
+        .unread_count(user.0.sub)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to count unread notifications: {}", e)))?;
+
+    Ok(Json(UnreadCountResponse { unread_count }))
+}
+
 /// Mark a notification as read
 /// PUT /api/v1/notifications/:id/read
 #[utoipa::path(
@@ -128,7 +155,7 @@ pub async fn mark_as_read(
     Path(notification_id): Path<Uuid>,
 ) -> Result<Json<serde_json::Value>> {
     let result = sqlx::query!(
-        "UPDATE notifications SET read = true WHERE id = $1 AND user_id = $2",
+        "UPDATE notifications SET read = true, read_at = COALESCE(read_at, NOW()) WHERE id = $1 AND user_id = $2",
         notification_id, user.0.sub
     )
     .execute(&state.db)
@@ -139,6 +166,10 @@ pub async fn mark_as_read(
         return Err(ApiError::NotFound("Notification not found".to_string()));
     }
 
+    if let Err(e) = state.notification_dispatcher.push_unread_count(user.0.sub).await {
+        error!("Failed to push unread count: {}", e);
+    }
+
     Ok(Json(serde_json::json!({
         "success": true,
         "message": "Notification marked as read"
@@ -163,13 +194,19 @@ pub async fn mark_all_as_read(
     user: AuthenticatedUser,
 ) -> Result<Json<serde_json::Value>> {
     let result = sqlx::query!(
-        "UPDATE notifications SET read = true WHERE user_id = $1 AND read = false",
+        "UPDATE notifications SET read = true, read_at = NOW() WHERE user_id = $1 AND read = false",
         user.0.sub
     )
     .execute(&state.db)
     .await
     .map_err(|e| ApiError::Internal(format!("Failed to mark all as read: {}", e)))?;
 
+    if result.rows_affected() > 0 {
+        if let Err(e) = state.notification_dispatcher.push_unread_count(user.0.sub).await {
+            error!("Failed to push unread count: {}", e);
+        }
+    }
+
     Ok(Json(serde_json::json!({
         "success": true,
         "message": format!("{} notifications marked as read", result.rows_affected())
@@ -0,0 +1,88 @@
+//! Participant Handlers
+//!
+//! Admin lookup of the user behind a public participant ID seen in the order
+//! book, market analytics or the market feed
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::AuditEvent;
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ResolveParticipantQuery {
+    /// When the ID was seen; defaults to now
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// User a public participant ID stood for
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResolvedParticipant {
+    pub public_id: Uuid,
+    pub user_id: Uuid,
+    pub username: String,
+    /// Rotation window the public ID was valid in
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: DateTime<Utc>,
+}
+
+/// Resolve a public participant ID to the user
+/// GET /api/v1/admin/participants/{public_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/participants/{public_id}",
+    tag = "admin",
+    params(
+        ("public_id" = Uuid, Path, description = "Public participant ID"),
+        ResolveParticipantQuery
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "User behind the public ID", body = ResolvedParticipant),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "No user had this public ID at the given time")
+    )
+)]
+pub async fn resolve_participant(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(public_id): Path<Uuid>,
+    Query(params): Query<ResolveParticipantQuery>,
+) -> Result<Json<ResolvedParticipant>> {
+    let at = params.at.unwrap_or_else(Utc::now);
+    let user_id = state
+        .participant_ids
+        .resolve(&state.db, public_id, at)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("No participant {} at {}", public_id, at)))?;
+    let username: String = sqlx::query_scalar("SELECT username FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    info!("🕵️ Participant {} resolved to user {} by admin {}", public_id, user_id, user.0.sub);
+    state.audit_logger.log_async(AuditEvent::AdminAction {
+        admin_id: user.0.sub,
+        action: "participant_id_resolved".to_string(),
+        target_user_id: Some(user_id),
+        details: serde_json::json!({ "public_id": public_id, "at": at }).to_string(),
+    });
+
+    let (valid_from, valid_until) = state.participant_ids.window_bounds(at);
+    Ok(Json(ResolvedParticipant {
+        public_id,
+        user_id,
+        username,
+        valid_from,
+        valid_until,
+    }))
+}
@@ -0,0 +1,55 @@
+//! Partner Onboarding Handlers
+//!
+//! Admin provisioning of B2B partners: organization, scoped API keys,
+//! webhook endpoints, sandbox users and rate-limit tier in one call.
+
+use axum::{extract::State, http::StatusCode, Json};
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::partners::{OnboardPartnerRequest, OnboardingBundle, PartnerOrganization};
+use crate::AppState;
+
+/// List partner organizations
+/// GET /api/v1/admin/partners
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/partners",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Partners, newest first", body = Vec<PartnerOrganization>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_partners(State(state): State<AppState>) -> Result<Json<Vec<PartnerOrganization>>> {
+    Ok(Json(state.partners.list().await?))
+}
+
+/// Onboard a partner
+/// POST /api/v1/admin/partners
+///
+/// Returns the onboarding bundle for the partner's integration team. The
+/// API keys and webhook secrets in it are not shown again.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/partners",
+    tag = "admin",
+    request_body = OnboardPartnerRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Partner provisioned", body = OnboardingBundle),
+        (status = 403, description = "Admin access required, or sandbox users requested while the sandbox is unavailable"),
+        (status = 409, description = "Partner slug already taken"),
+        (status = 422, description = "Invalid slug, scopes, webhook or sandbox user spec")
+    )
+)]
+pub async fn onboard_partner(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<OnboardPartnerRequest>,
+) -> Result<(StatusCode, Json<OnboardingBundle>)> {
+    let bundle = state.partners.onboard(user.0.sub, payload).await?;
+    Ok((StatusCode::CREATED, Json(bundle)))
+}
@@ -0,0 +1,56 @@
+//! PII Key Handlers
+//!
+//! Admin view of the data keys sealing personal data and manual rotation
+
+use axum::{extract::State, Json};
+use tracing::info;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::services::audit_logger::AuditEvent;
+use crate::services::pii::PiiKeyStatus;
+use crate::AppState;
+
+/// Active data key and rows still to be re-encrypted
+/// GET /api/v1/admin/pii/keys
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/pii/keys",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Active data key and rotation backlog", body = PiiKeyStatus),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_pii_key_status(State(state): State<AppState>) -> Result<Json<PiiKeyStatus>> {
+    Ok(Json(state.pii_rotation.status().await?))
+}
+
+/// Make a new data key active; existing rows are re-encrypted in the background
+/// POST /api/v1/admin/pii/keys/rotate
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/pii/keys/rotate",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "New data key active", body = PiiKeyStatus),
+        (status = 403, description = "Admin access required"),
+        (status = 502, description = "KMS unavailable")
+    )
+)]
+pub async fn rotate_pii_key(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<PiiKeyStatus>> {
+    let key_id = state.pii.rotate().await?;
+    info!("🔐 PII data key rotated to {} by admin {}", key_id, user.0.sub);
+    state.audit_logger.log_async(AuditEvent::AdminAction {
+        admin_id: user.0.sub,
+        action: "pii_key_rotated".to_string(),
+        target_user_id: None,
+        details: serde_json::json!({ "key_id": key_id }).to_string(),
+    });
+    Ok(Json(state.pii_rotation.status().await?))
+}
@@ -0,0 +1,201 @@
+//! Prepaid Credit Handler
+//!
+//! Fiat top-ups, credit ledger, refunds and the payment provider webhook
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::payments::{
+    PrepaidLedgerEntry, PrepaidRefund, PrepaidTopUp, TopUpCheckout, WebhookSignature,
+};
+use crate::AppState;
+
+/// Prepaid balance and recent ledger entries
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PrepaidSummary {
+    pub balance: Decimal,
+    pub currency: String,
+    pub entries: Vec<PrepaidLedgerEntry>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PrepaidLedgerQuery {
+    /// Number of ledger entries (default 50, max 200)
+    pub limit: Option<i64>,
+}
+
+/// Start a top-up
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateTopUpRequest {
+    /// Amount in major currency units (e.g. 500.00 THB)
+    #[validate(custom(function = "crate::utils::validation::rules::positive_amount"))]
+    pub amount: Decimal,
+}
+
+/// Refund unused credit from a top-up
+#[derive(Debug, Default, Deserialize, Validate, ToSchema)]
+pub struct RefundTopUpRequest {
+    /// Amount to refund; defaults to everything refundable
+    #[validate(custom(function = "crate::utils::validation::rules::positive_amount"))]
+    pub amount: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookAck {
+    pub received: bool,
+}
+
+/// Get prepaid credit balance and ledger
+/// GET /api/v1/prepaid
+#[utoipa::path(
+    get,
+    path = "/api/v1/prepaid",
+    tag = "prepaid",
+    params(PrepaidLedgerQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Prepaid balance and ledger", body = PrepaidSummary),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_prepaid_summary(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<PrepaidLedgerQuery>,
+) -> Result<Json<PrepaidSummary>> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let balance = state.prepaid_service.balance(user.0.sub).await?;
+    let entries = state.prepaid_service.ledger(user.0.sub, limit).await?;
+
+    Ok(Json(PrepaidSummary {
+        balance,
+        currency: state.config.payments.currency.clone(),
+        entries,
+    }))
+}
+
+/// List the caller's top-ups
+/// GET /api/v1/prepaid/topups
+#[utoipa::path(
+    get,
+    path = "/api/v1/prepaid/topups",
+    tag = "prepaid",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Top-ups, newest first", body = Vec<PrepaidTopUp>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_topups(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<Vec<PrepaidTopUp>>> {
+    Ok(Json(state.prepaid_service.list_topups(user.0.sub).await?))
+}
+
+/// Start a top-up with the payment provider
+/// POST /api/v1/prepaid/topups
+#[utoipa::path(
+    post,
+    path = "/api/v1/prepaid/topups",
+    tag = "prepaid",
+    request_body = CreateTopUpRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Top-up created; complete payment with the returned details", body = TopUpCheckout),
+        (status = 400, description = "Amount outside the configured top-up limits"),
+        (status = 422, description = "Request validation failed"),
+        (status = 502, description = "Payment provider unavailable")
+    )
+)]
+pub async fn create_topup(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(request): ValidatedJson<CreateTopUpRequest>,
+) -> Result<Json<TopUpCheckout>> {
+    let checkout = state
+        .prepaid_service
+        .create_topup(user.0.sub, request.amount)
+        .await?;
+
+    Ok(Json(checkout))
+}
+
+/// Refund unused credit from a top-up to the original payment method
+/// POST /api/v1/prepaid/topups/{id}/refund
+#[utoipa::path(
+    post,
+    path = "/api/v1/prepaid/topups/{id}/refund",
+    tag = "prepaid",
+    params(("id" = Uuid, Path, description = "Top-up ID")),
+    request_body = RefundTopUpRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Refund issued, or pending until the provider confirms it", body = PrepaidRefund),
+        (status = 400, description = "Invalid refund amount"),
+        (status = 422, description = "Request validation failed"),
+        (status = 404, description = "Top-up not found"),
+        (status = 409, description = "Top-up cannot be refunded"),
+        (status = 502, description = "Payment provider rejected the refund")
+    )
+)]
+pub async fn refund_topup(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(topup_id): Path<Uuid>,
+    ValidatedJson(request): ValidatedJson<RefundTopUpRequest>,
+) -> Result<Json<PrepaidRefund>> {
+    let refund = state
+        .prepaid_service
+        .request_refund(user.0.sub, topup_id, request.amount)
+        .await?;
+
+    Ok(Json(refund))
+}
+
+/// Payment provider webhook (signature-verified, no bearer token)
+/// POST /api/v1/payments/webhook/{provider}
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments/webhook/{provider}",
+    tag = "prepaid",
+    params(("provider" = String, Path, description = "Payment provider: stripe or omise")),
+    responses(
+        (status = 200, description = "Event accepted", body = WebhookAck),
+        (status = 400, description = "Malformed payload"),
+        (status = 401, description = "Invalid signature"),
+        (status = 404, description = "Unknown provider")
+    )
+)]
+pub async fn payment_webhook(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<WebhookAck>> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let signature = WebhookSignature {
+        stripe_signature: header("stripe-signature"),
+        omise_signature: header("omise-signature"),
+        omise_timestamp: header("omise-signature-timestamp"),
+    };
+
+    state
+        .prepaid_service
+        .handle_webhook(&provider, &signature, &body)
+        .await?;
+
+    Ok(Json(WebhookAck { received: true }))
+}
@@ -0,0 +1,390 @@
+//! Public Market Data Handlers
+//!
+//! Anonymous, read-only market data: orderbook snapshot, last trades,
+//! clearing prices, trade candles and the daily statistics archive. Responses are cached in Redis and
+//! carry `Cache-Control` headers so a CDN can serve them; clients are rate
+//! limited per IP by `public_rate_limit`.
+
+use std::future::Future;
+
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+use crate::handlers::analytics::types::{
+    max_history_range, parse_resolution, ClearingPriceHistory, ClearingPriceHistoryQuery,
+};
+use crate::services::market_analytics::DepthSnapshot;
+use crate::services::market_archive::{MarketDailyRollup, MAX_ROLLUP_DAYS};
+use crate::AppState;
+
+pub(crate) const CACHE_PREFIX: &str = "public:market:";
+const MAX_TRADES: i64 = 100;
+const MAX_CANDLES: i64 = 500;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PublicTradesQuery {
+    /// Market to list; every market when omitted
+    pub market_id: Option<Uuid>,
+    /// Default 50, at most 100
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CandlesQuery {
+    /// Candle width: 15m, 1h, 4h or 1d (default: 1h)
+    pub interval: Option<String>,
+    /// Market to aggregate; every market when omitted
+    pub market_id: Option<Uuid>,
+    /// Number of candles up to now, default 100, at most 500
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DailyStatsQuery {
+    /// First day, inclusive (default: 30 days before `to`)
+    pub from: Option<NaiveDate>,
+    /// Last day, inclusive (default: yesterday)
+    pub to: Option<NaiveDate>,
+}
+
+/// An executed trade, without the parties
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct PublicTrade {
+    pub id: Uuid,
+    pub market_id: Option<Uuid>,
+    #[schema(value_type = String)]
+    pub price_per_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub amount_kwh: Decimal,
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
+/// Trade prices and volume over one interval
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct Candle {
+    pub period_start: DateTime<Utc>,
+    #[schema(value_type = String)]
+    pub open: Decimal,
+    #[schema(value_type = String)]
+    pub high: Decimal,
+    #[schema(value_type = String)]
+    pub low: Decimal,
+    #[schema(value_type = String)]
+    pub close: Decimal,
+    #[schema(value_type = String)]
+    pub volume_kwh: Decimal,
+    pub trade_count: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CandleSeries {
+    pub interval: String,
+    pub market_id: Option<Uuid>,
+    pub candles: Vec<Candle>,
+}
+
+/// Archived days of a date range
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MarketDailySeries {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Oldest first
+    pub days: Vec<MarketDailyRollup>,
+}
+
+fn parse_candle_interval(interval: &str) -> Result<Duration> {
+    match interval {
+        "15m" => Ok(Duration::minutes(15)),
+        "1h" => Ok(Duration::hours(1)),
+        "4h" => Ok(Duration::hours(4)),
+        "1d" | "24h" => Ok(Duration::days(1)),
+        _ => Err(ApiError::validation_field(
+            "interval",
+            "Invalid interval. Use: 15m, 1h, 4h or 1d",
+        )),
+    }
+}
+
+/// Serve from the Redis cache, loading and storing on a miss. Cache errors
+/// only cost the cache; the data is still served.
+async fn cached<T, F, Fut>(state: &AppState, key: &str, ttl_secs: u64, load: F) -> Result<serde_json::Value>
+where
+    T: Serialize,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let key = format!("{}{}", CACHE_PREFIX, key);
+    if let Ok(Some(value)) = state.cache_service.get_json::<serde_json::Value>(&key).await {
+        return Ok(value);
+    }
+    let value = serde_json::to_value(load().await?)
+        .map_err(|e| ApiError::Internal(format!("Failed to serialize market data: {}", e)))?;
+    let _ = state.cache_service.set_json(&key, &value, Some(ttl_secs)).await;
+    Ok(value)
+}
+
+/// JSON response that shared caches may keep for `max_age` seconds
+fn cacheable(body: serde_json::Value, max_age: u64) -> Response {
+    let cache_control = format!(
+        "public, max-age={0}, s-maxage={0}, stale-while-revalidate={0}",
+        max_age
+    );
+    ([(header::CACHE_CONTROL, cache_control)], Json(body)).into_response()
+}
+
+/// Get the orderbook depth snapshot
+/// GET /api/v1/public/market/orderbook
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/market/orderbook",
+    tag = "market-data",
+    responses(
+        (status = 200, description = "Aggregated bid and ask levels", body = DepthSnapshot),
+        (status = 404, description = "No snapshot captured yet"),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+pub async fn get_public_orderbook(State(state): State<AppState>) -> Result<Response> {
+    let ttl = state.config.public_api.live_cache_secs;
+    let snapshot = state
+        .market_analytics
+        .depth_snapshot()
+        .await
+        .ok_or_else(|| ApiError::NotFound("No orderbook snapshot captured yet".to_string()))?;
+    let body = serde_json::to_value(snapshot)
+        .map_err(|e| ApiError::Internal(format!("Failed to serialize orderbook: {}", e)))?;
+    Ok(cacheable(body, ttl))
+}
+
+/// Get the last trades
+/// GET /api/v1/public/market/trades
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/market/trades",
+    tag = "market-data",
+    params(PublicTradesQuery),
+    responses(
+        (status = 200, description = "Most recent trades, newest first", body = Vec<PublicTrade>),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+pub async fn get_public_trades(
+    State(state): State<AppState>,
+    Query(params): Query<PublicTradesQuery>,
+) -> Result<Response> {
+    let ttl = state.config.public_api.live_cache_secs;
+    let limit = params.limit.unwrap_or(50).clamp(1, MAX_TRADES);
+    let key = format!("trades:{:?}:{}", params.market_id, limit);
+
+    let body = cached(&state, &key, ttl, || async {
+        let trades = sqlx::query_as::<_, PublicTrade>(
+            r#"
+            SELECT id, market_id, match_price AS price_per_kwh,
+                   matched_amount AS amount_kwh, match_time AS executed_at
+            FROM order_matches
+            WHERE status NOT IN ('busted', 'rolled_back') AND ($1::uuid IS NULL OR market_id = $1)
+            ORDER BY match_time DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(params.market_id)
+        .bind(limit)
+        .fetch_all(&state.db)
+        .await?;
+        Ok::<_, ApiError>(trades)
+    })
+    .await?;
+    Ok(cacheable(body, ttl))
+}
+
+/// Get the clearing price history
+/// GET /api/v1/public/market/clearing-prices
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/market/clearing-prices",
+    tag = "market-data",
+    params(ClearingPriceHistoryQuery),
+    responses(
+        (status = 200, description = "Clearing prices per epoch, hour or day", body = ClearingPriceHistory),
+        (status = 400, description = "Invalid resolution or range"),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+pub async fn get_public_clearing_prices(
+    State(state): State<AppState>,
+    Query(params): Query<ClearingPriceHistoryQuery>,
+) -> Result<Response> {
+    let ttl = state.config.public_api.history_cache_secs;
+    let resolution = parse_resolution(&params.resolution)?;
+    let key = format!(
+        "clearing:{}:{:?}:{:?}",
+        params.resolution,
+        params.from.map(|t| t.timestamp()),
+        params.to.map(|t| t.timestamp())
+    );
+
+    let body = cached(&state, &key, ttl, || async {
+        let to = params.to.unwrap_or_else(Utc::now);
+        let from = params.from.unwrap_or(to - Duration::days(7));
+        if from >= to {
+            return Err(ApiError::validation_field("from", "from must be before to"));
+        }
+        if to - from > max_history_range(resolution) {
+            return Err(ApiError::validation_field(
+                "from",
+                format!(
+                    "Range too large for resolution {}; maximum is {} days",
+                    params.resolution,
+                    max_history_range(resolution).num_days()
+                ),
+            ));
+        }
+
+        let points = state
+            .price_index_service
+            .history(resolution, from, to)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to load clearing price history: {}", e)))?;
+        Ok::<_, ApiError>(ClearingPriceHistory {
+            resolution: params.resolution.clone(),
+            from,
+            to,
+            points,
+        })
+    })
+    .await?;
+    Ok(cacheable(body, ttl))
+}
+
+/// Get trade candles (OHLCV)
+/// GET /api/v1/public/market/candles
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/market/candles",
+    tag = "market-data",
+    params(CandlesQuery),
+    responses(
+        (status = 200, description = "Candles oldest first; intervals without trades are omitted", body = CandleSeries),
+        (status = 400, description = "Invalid interval"),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+pub async fn get_public_candles(
+    State(state): State<AppState>,
+    Query(params): Query<CandlesQuery>,
+) -> Result<Response> {
+    let ttl = state.config.public_api.history_cache_secs;
+    let interval_label = params.interval.clone().unwrap_or_else(|| "1h".to_string());
+    let interval = parse_candle_interval(&interval_label)?;
+    let limit = params.limit.unwrap_or(100).clamp(1, MAX_CANDLES);
+    let key = format!("candles:{}:{:?}:{}", interval_label, params.market_id, limit);
+
+    let body = cached(&state, &key, ttl, || async {
+        let current = Utc::now()
+            .duration_trunc(interval)
+            .map_err(|e| ApiError::Internal(format!("Invalid interval: {}", e)))?;
+        let from = current - interval * (limit as i32 - 1);
+
+        let candles = sqlx::query_as::<_, Candle>(
+            r#"
+            SELECT
+                date_bin($1::float8 * INTERVAL '1 second', match_time, TIMESTAMPTZ '2000-01-01') AS period_start,
+                (array_agg(match_price ORDER BY match_time, id))[1] AS open,
+                MAX(match_price) AS high,
+                MIN(match_price) AS low,
+                (array_agg(match_price ORDER BY match_time DESC, id DESC))[1] AS close,
+                SUM(matched_amount) AS volume_kwh,
+                COUNT(*) AS trade_count
+            FROM order_matches
+            WHERE match_time >= $2
+              AND status NOT IN ('busted', 'rolled_back')
+              AND ($3::uuid IS NULL OR market_id = $3)
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(interval.num_seconds() as f64)
+        .bind(from)
+        .bind(params.market_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok::<_, ApiError>(CandleSeries {
+            interval: interval_label.clone(),
+            market_id: params.market_id,
+            candles,
+        })
+    })
+    .await?;
+    Ok(cacheable(body, ttl))
+}
+
+/// Get the daily market statistics archive
+/// GET /api/v1/public/market/daily
+///
+/// Immutable rollups of completed market-local days, written by a nightly
+/// job. Days still open or not archived yet are absent.
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/market/daily",
+    tag = "market-data",
+    params(DailyStatsQuery),
+    responses(
+        (status = 200, description = "Daily volume, clearing prices, participants and minted energy", body = MarketDailySeries),
+        (status = 400, description = "Invalid range"),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+pub async fn get_public_daily_stats(
+    State(state): State<AppState>,
+    Query(params): Query<DailyStatsQuery>,
+) -> Result<Response> {
+    let ttl = state.config.public_api.history_cache_secs;
+    let to = params
+        .to
+        .unwrap_or_else(|| state.market_archive.timezone().date_of(Utc::now()) - Duration::days(1));
+    let from = params.from.unwrap_or(to - Duration::days(29));
+    if from > to {
+        return Err(ApiError::validation_field("from", "from must not be after to"));
+    }
+    if (to - from).num_days() >= MAX_ROLLUP_DAYS {
+        return Err(ApiError::validation_field(
+            "from",
+            format!("Range too large; maximum is {} days", MAX_ROLLUP_DAYS),
+        ));
+    }
+    let key = format!("daily:{}:{}", from, to);
+
+    let body = cached(&state, &key, ttl, || async {
+        Ok::<_, ApiError>(MarketDailySeries {
+            from,
+            to,
+            days: state.market_archive.list(from, to).await?,
+        })
+    })
+    .await?;
+    Ok(cacheable(body, ttl))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candle_intervals() {
+        assert_eq!(parse_candle_interval("15m").unwrap(), Duration::minutes(15));
+        assert_eq!(parse_candle_interval("4h").unwrap(), Duration::hours(4));
+        assert_eq!(parse_candle_interval("24h").unwrap(), Duration::days(1));
+        assert!(parse_candle_interval("7d").is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! Rate Limit Overrides Handler
+//!
+//! Admin management of meter submission limit overrides for the simulator
+//! and partner integrations
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::services::rate_limiter::{CreateRateLimitOverride, RateLimitOverride};
+use crate::AppState;
+
+/// List recent rate limit overrides, including expired and revoked ones (admin)
+/// GET /api/v1/admin/rate-limit-overrides
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/rate-limit-overrides",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Overrides, newest first", body = Vec<RateLimitOverride>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_overrides(State(state): State<AppState>) -> Result<Json<Vec<RateLimitOverride>>> {
+    Ok(Json(state.rate_limiter.list_overrides().await?))
+}
+
+/// Raise or lift the meter submission limit for a user, API key or IP range (admin)
+/// POST /api/v1/admin/rate-limit-overrides
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/rate-limit-overrides",
+    tag = "admin",
+    request_body = CreateRateLimitOverride,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Override created", body = RateLimitOverride),
+        (status = 400, description = "Invalid principal, limit or expiry"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn create_override(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(request): Json<CreateRateLimitOverride>,
+) -> Result<Json<RateLimitOverride>> {
+    Ok(Json(state.rate_limiter.create_override(user.0.sub, request).await?))
+}
+
+/// Revoke an override; the default limit applies again immediately (admin)
+/// DELETE /api/v1/admin/rate-limit-overrides/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/rate-limit-overrides/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Override ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Override revoked", body = RateLimitOverride),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "No active override with this ID")
+    )
+)]
+pub async fn revoke_override(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RateLimitOverride>> {
+    Ok(Json(state.rate_limiter.revoke_override(user.0.sub, id).await?))
+}
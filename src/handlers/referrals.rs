@@ -0,0 +1,162 @@
+//! Referral Handler
+//!
+//! Users share their referral code and track rewards; admins review
+//! flagged referrals and report on the program
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::services::referral::{
+    Referral, ReferralReport, ReferralReward, ReferralStatus, ReferralSummary,
+};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReferralListQuery {
+    /// Filter by status: pending, qualified, flagged, rejected
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Decision on a flagged referral
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReviewReferralRequest {
+    /// Issue the rewards (true) or reject the referral (false)
+    pub approve: bool,
+    #[validate(length(max = 2000))]
+    pub note: Option<String>,
+}
+
+/// Get your referral code and rewards
+/// GET /api/v1/referrals
+#[utoipa::path(
+    get,
+    path = "/api/v1/referrals",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Referral code, referred users and rewards", body = ReferralSummary),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_referral_summary(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<ReferralSummary>> {
+    Ok(Json(state.referral_service.summary(user.0.sub).await?))
+}
+
+/// List referrals
+/// GET /api/v1/admin/referrals
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/referrals",
+    tag = "users",
+    params(ReferralListQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Referrals, newest first", body = Vec<Referral>),
+        (status = 400, description = "Invalid status filter"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn admin_list_referrals(
+    State(state): State<AppState>,
+    Query(params): Query<ReferralListQuery>,
+) -> Result<Json<Vec<Referral>>> {
+    let status = match params.status.as_deref() {
+        None => None,
+        Some(value) => Some(ReferralStatus::parse(value).ok_or_else(|| {
+            ApiError::validation_field(
+                "status",
+                "Invalid status. Use: pending, qualified, flagged, rejected",
+            )
+        })?),
+    };
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    Ok(Json(state.referral_service.list(status, limit, offset).await?))
+}
+
+/// Referral program report
+/// GET /api/v1/admin/referrals/report
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/referrals/report",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Referral totals, reward volume and top referrers", body = ReferralReport),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn admin_referral_report(
+    State(state): State<AppState>,
+) -> Result<Json<ReferralReport>> {
+    Ok(Json(state.referral_service.report().await?))
+}
+
+/// Approve or reject a flagged referral
+/// POST /api/v1/admin/referrals/{id}/review
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/referrals/{id}/review",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "Referral ID")),
+    request_body = ReviewReferralRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Referral reviewed", body = Referral),
+        (status = 422, description = "Request validation failed"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Referral not found"),
+        (status = 409, description = "Referral is not flagged")
+    )
+)]
+pub async fn admin_review_referral(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(referral_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ReviewReferralRequest>,
+) -> Result<Json<Referral>> {
+    let referral = state
+        .referral_service
+        .review(user.0.sub, referral_id, payload.approve, payload.note.as_deref())
+        .await?;
+
+    Ok(Json(referral))
+}
+
+/// Requeue a reward whose minting failed
+/// POST /api/v1/admin/referral-rewards/{id}/retry
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/referral-rewards/{id}/retry",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "Reward ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Reward queued for minting", body = ReferralReward),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Reward not found"),
+        (status = 409, description = "Reward has not failed")
+    )
+)]
+pub async fn admin_retry_referral_reward(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(reward_id): Path<Uuid>,
+) -> Result<Json<ReferralReward>> {
+    Ok(Json(state.referral_service.retry_reward(user.0.sub, reward_id).await?))
+}
@@ -0,0 +1,24 @@
+//! Route Permissions Handler
+//!
+//! Admin listing of every documented route with its access rule, allowed
+//! roles and rate limit class
+
+use axum::Json;
+
+use crate::router::permissions::{self, RoutePermission};
+
+/// List routes with their required access
+/// GET /api/v1/admin/routes
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/routes",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Documented routes by path and method", body = Vec<RoutePermission>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_route_permissions() -> Json<Vec<RoutePermission>> {
+    Json(permissions::route_matrix(&crate::router::api_spec()))
+}
@@ -0,0 +1,61 @@
+//! Swap Pool Handlers
+//!
+//! Statistics and price history of the liquidity pools the order router
+//! swaps against, for liquidity providers evaluating a pool
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::services::swap_pool_stats::{PoolStats, StatsInterval};
+use crate::AppState;
+
+const DEFAULT_HISTORY_DAYS: i64 = 7;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PoolStatsQuery {
+    /// History bucket width, `hour` (default) or `day`
+    #[param(value_type = Option<String>)]
+    pub interval: Option<StatsInterval>,
+    /// Days of history (default 7, max 90)
+    pub days: Option<i64>,
+}
+
+/// Get a swap pool's statistics
+/// GET /api/v1/swap/pools/{id}/stats
+///
+/// TVL, 24h and 7d volume and fees, fee APR and a volume, price and TVL
+/// time series, from the hourly rollups of the pool stats aggregator.
+#[utoipa::path(
+    get,
+    path = "/api/v1/swap/pools/{id}/stats",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Pool ID"), PoolStatsQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Pool statistics and history", body = PoolStats),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Pool not found")
+    )
+)]
+pub async fn get_pool_stats(
+    State(state): State<AppState>,
+    Path(pool_id): Path<Uuid>,
+    Query(params): Query<PoolStatsQuery>,
+) -> Result<Json<PoolStats>> {
+    Ok(Json(
+        state
+            .swap_pool_stats
+            .stats(
+                pool_id,
+                params.interval.unwrap_or_default(),
+                params.days.unwrap_or(DEFAULT_HISTORY_DAYS),
+            )
+            .await?,
+    ))
+}
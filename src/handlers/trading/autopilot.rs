@@ -0,0 +1,121 @@
+//! Trading Autopilot Endpoints
+//!
+//! Opt in to automatic sell orders for forecast surplus, review what
+//! autopilot placed each epoch and how it compares with manual selling
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::services::autopilot::{AutopilotOrder, AutopilotPerformance, AutopilotSettings, UpdateAutopilotRequest};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AutopilotOrdersQuery {
+    /// Maximum results, default 100
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AutopilotPerformanceQuery {
+    /// Days compared, default 30
+    pub days: Option<i64>,
+}
+
+/// The caller's autopilot settings
+/// GET /api/v1/trading/autopilot
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/autopilot",
+    tag = "trading",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Autopilot settings", body = AutopilotSettings),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Autopilot never enabled")
+    )
+)]
+pub async fn get_autopilot(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<AutopilotSettings>> {
+    state
+        .autopilot
+        .settings(user.0.sub)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound("Autopilot is not set up".to_string()))
+}
+
+/// Opt in to, change or pause autopilot
+/// PUT /api/v1/trading/autopilot
+#[utoipa::path(
+    put,
+    path = "/api/v1/trading/autopilot",
+    tag = "trading",
+    request_body = UpdateAutopilotRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Autopilot settings saved", body = AutopilotSettings),
+        (status = 400, description = "Invalid pricing or quantity settings"),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Request validation failed")
+    )
+)]
+pub async fn update_autopilot(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(request): ValidatedJson<UpdateAutopilotRequest>,
+) -> Result<Json<AutopilotSettings>> {
+    Ok(Json(state.autopilot.update_settings(user.0.sub, request).await?))
+}
+
+/// Orders autopilot planned for the caller, newest first
+/// GET /api/v1/trading/autopilot/orders
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/autopilot/orders",
+    tag = "trading",
+    params(AutopilotOrdersQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Autopilot plan per epoch", body = Vec<AutopilotOrder>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_autopilot_orders(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<AutopilotOrdersQuery>,
+) -> Result<Json<Vec<AutopilotOrder>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, 500);
+    Ok(Json(state.autopilot.list_orders(user.0.sub, limit).await?))
+}
+
+/// Autopilot sell results compared with the caller's manual sell orders
+/// GET /api/v1/trading/autopilot/performance
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/autopilot/performance",
+    tag = "trading",
+    params(AutopilotPerformanceQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Fill rate and realized price, autopilot vs manual", body = AutopilotPerformance),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_autopilot_performance(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<AutopilotPerformanceQuery>,
+) -> Result<Json<AutopilotPerformance>> {
+    let days = params.days.unwrap_or(30).clamp(1, 365);
+    Ok(Json(state.autopilot.performance(user.0.sub, days).await?))
+}
@@ -0,0 +1,96 @@
+//! Settlement Batch Endpoints
+//!
+//! Throughput and confirmation latency of the settlement batches produced
+//! by epoch clearing, and the fees their transfers cost each fee payer
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::error::{ApiError, Result};
+use crate::services::settlement::{FeePayerSpend, SettlementBatchStatistics};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BatchStatisticsQuery {
+    /// Start of the window, default 24 hours before `to`
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive end of the window, default now
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl BatchStatisticsQuery {
+    /// Resolve the window, filling in defaults relative to `now`
+    fn window(&self, now: DateTime<Utc>) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+        let to = self.to.unwrap_or(now);
+        let from = self.from.unwrap_or(to - Duration::hours(24));
+        if from >= to {
+            return Err(ApiError::BadRequest("from must be before to".into()));
+        }
+        Ok((from, to))
+    }
+}
+
+/// Get settlement batch statistics
+/// GET /api/v1/admin/batches/stats
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/batches/stats",
+    tag = "admin",
+    params(BatchStatisticsQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Batch states, settlement counts and confirmation latency for settlements created in the window", body = SettlementBatchStatistics),
+        (status = 400, description = "Window ends before it starts"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_batch_statistics(
+    State(state): State<AppState>,
+    Query(params): Query<BatchStatisticsQuery>,
+) -> Result<Json<SettlementBatchStatistics>> {
+    let (from, to) = params.window(Utc::now())?;
+    Ok(Json(state.settlement.get_batch_statistics(from, to).await?))
+}
+
+/// Get settlement fee payer spend
+/// GET /api/v1/admin/batches/fee-payers
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/batches/fee-payers",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Balance and fees paid per fee payer, configured pool first", body = Vec<FeePayerSpend>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_fee_payer_spend(State(state): State<AppState>) -> Result<Json<Vec<FeePayerSpend>>> {
+    Ok(Json(state.settlement.fee_payer_spend().await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_statistics_window() {
+        let now = Utc::now();
+        let query = |from, to| BatchStatisticsQuery { from, to };
+
+        assert_eq!(query(None, None).window(now).unwrap(), (now - Duration::hours(24), now));
+
+        let to = now - Duration::days(2);
+        assert_eq!(query(None, Some(to)).window(now).unwrap(), (to - Duration::hours(24), to));
+
+        let from = now - Duration::hours(1);
+        assert_eq!(query(Some(from), None).window(now).unwrap(), (from, now));
+
+        assert!(matches!(query(Some(now), Some(now)).window(now), Err(ApiError::BadRequest(_))));
+        assert!(query(Some(now), None).window(now - Duration::hours(1)).is_err());
+    }
+}
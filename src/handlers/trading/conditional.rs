@@ -10,6 +10,7 @@ use tracing::{info, error};
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
 use crate::models::trading::{
     CreateConditionalOrderRequest, ConditionalOrderResponse, ConditionalOrder,
     TriggerType, TriggerStatus,
@@ -29,28 +30,24 @@ use crate::AppState;
         (status = 200, description = "Conditional order created", body = ConditionalOrderResponse),
         (status = 400, description = "Invalid parameters"),
         (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Request validation failed"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn create_conditional_order(
     State(state): State<AppState>,
     user: AuthenticatedUser,
-    Json(payload): Json<CreateConditionalOrderRequest>,
+    ValidatedJson(payload): ValidatedJson<CreateConditionalOrderRequest>,
 ) -> Result<Json<ConditionalOrderResponse>> {
     info!("Creating conditional order for user: {}, type: {:?}", user.0.sub, payload.trigger_type);
 
-    // Validate trigger price
-    if payload.trigger_price <= Decimal::ZERO {
-        return Err(ApiError::BadRequest("Trigger price must be positive".to_string()));
-    }
-
-    if payload.energy_amount <= Decimal::ZERO {
-        return Err(ApiError::BadRequest("Energy amount must be positive".to_string()));
-    }
-
     // Validate trailing offset for trailing stop orders
     if payload.trigger_type == TriggerType::TrailingStop && payload.trailing_offset.is_none() {
-        return Err(ApiError::BadRequest("Trailing offset is required for trailing stop orders".to_string()));
+        return Err(ApiError::invalid_field(
+            "trailing_offset",
+            "required",
+            "Trailing offset is required for trailing stop orders",
+        ));
     }
 
     let order_id = Uuid::new_v4();
@@ -0,0 +1,60 @@
+//! Delivery Verification Endpoints
+//!
+//! Results of checking settled trades against metered generation and
+//! consumption, including shortfalls and the refunds paid for them
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+use crate::services::delivery_verification::{DeliveryVerification, DeliveryVerificationFilter};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeliveryVerificationQuery {
+    /// verified, shortfall or under_consumed
+    pub status: Option<String>,
+    pub epoch_id: Option<Uuid>,
+    /// Maximum results, default 100
+    pub limit: Option<i64>,
+}
+
+/// List delivery verifications
+/// GET /api/v1/admin/delivery-verifications
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/delivery-verifications",
+    tag = "admin",
+    params(DeliveryVerificationQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Delivery checks of settled trades, newest first", body = Vec<DeliveryVerification>),
+        (status = 400, description = "Unknown status"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_delivery_verifications(
+    State(state): State<AppState>,
+    Query(params): Query<DeliveryVerificationQuery>,
+) -> Result<Json<Vec<DeliveryVerification>>> {
+    if let Some(status) = &params.status {
+        if !["verified", "shortfall", "under_consumed"].contains(&status.as_str()) {
+            return Err(ApiError::validation_field(
+                "status",
+                "must be verified, shortfall or under_consumed",
+            ));
+        }
+    }
+
+    let filter = DeliveryVerificationFilter {
+        status: params.status,
+        epoch_id: params.epoch_id,
+        limit: params.limit.unwrap_or(100).clamp(1, 500),
+    };
+    Ok(Json(state.delivery_verification.list(filter).await?))
+}
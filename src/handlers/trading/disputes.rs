@@ -0,0 +1,315 @@
+//! Settlement Disputes Handler
+//!
+//! Trade parties open disputes and submit evidence; admins review and resolve them
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::services::dispute::{
+    DisputeDetail, DisputeEvidence, DisputeOutcome, DisputeReason, DisputeStatus, EvidenceKind,
+    SettlementDispute,
+};
+use crate::AppState;
+
+/// Evidence item
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct EvidenceInput {
+    pub kind: EvidenceKind,
+    /// Statement text, URL or meter reading reference
+    #[validate(length(max = 4000), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub content: String,
+}
+
+/// Open a dispute on a settlement
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct OpenDisputeRequest {
+    pub settlement_id: Uuid,
+    pub reason: DisputeReason,
+    /// What went wrong (10-4000 characters)
+    #[validate(length(min = 10, max = 4000))]
+    pub description: String,
+    /// Initial evidence
+    #[serde(default)]
+    #[validate(length(max = 20), nested)]
+    pub evidence: Vec<EvidenceInput>,
+}
+
+/// Admin decision on a dispute
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ResolveDisputeRequest {
+    pub outcome: DisputeOutcome,
+    /// Reasoning shared with both parties
+    #[validate(length(max = 4000), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub notes: String,
+    /// Paid from the counterparty to the raiser (upheld disputes only)
+    pub compensation: Option<Decimal>,
+    /// Revoke certificates issued for the disputed energy (upheld disputes only, default true)
+    pub revoke_certificates: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DisputeListQuery {
+    /// Filter by status: open, under_review, upheld, rejected, withdrawn
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Open a settlement dispute
+/// POST /api/v1/trading/disputes
+#[utoipa::path(
+    post,
+    path = "/api/v1/trading/disputes",
+    tag = "trading",
+    request_body = OpenDisputeRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Dispute opened", body = SettlementDispute),
+        (status = 400, description = "Dispute window closed"),
+        (status = 422, description = "Request validation failed"),
+        (status = 404, description = "Settlement not found"),
+        (status = 409, description = "Settlement already has an active dispute")
+    )
+)]
+pub async fn open_dispute(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<OpenDisputeRequest>,
+) -> Result<Json<SettlementDispute>> {
+    let evidence: Vec<(EvidenceKind, String)> = payload
+        .evidence
+        .into_iter()
+        .map(|e| (e.kind, e.content))
+        .collect();
+
+    let dispute = state
+        .dispute_service
+        .open(
+            user.0.sub,
+            payload.settlement_id,
+            payload.reason,
+            &payload.description,
+            &evidence,
+        )
+        .await?;
+
+    Ok(Json(dispute))
+}
+
+/// List disputes the caller is a party to
+/// GET /api/v1/trading/disputes
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/disputes",
+    tag = "trading",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Disputes, newest first", body = Vec<SettlementDispute>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_my_disputes(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<Vec<SettlementDispute>>> {
+    Ok(Json(state.dispute_service.list_for_user(user.0.sub).await?))
+}
+
+/// Get a dispute with evidence and adjustments
+/// GET /api/v1/trading/disputes/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/disputes/{id}",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Dispute ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Dispute detail", body = DisputeDetail),
+        (status = 404, description = "Dispute not found")
+    )
+)]
+pub async fn get_dispute(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(dispute_id): Path<Uuid>,
+) -> Result<Json<DisputeDetail>> {
+    let detail = state.dispute_service.detail(dispute_id).await?;
+
+    let is_party =
+        detail.dispute.raised_by == user.0.sub || detail.dispute.counterparty_id == user.0.sub;
+    if !is_party && !is_admin(&user) {
+        return Err(ApiError::NotFound("Dispute not found".to_string()));
+    }
+
+    Ok(Json(detail))
+}
+
+/// Add evidence to an active dispute
+/// POST /api/v1/trading/disputes/{id}/evidence
+#[utoipa::path(
+    post,
+    path = "/api/v1/trading/disputes/{id}/evidence",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Dispute ID")),
+    request_body = EvidenceInput,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Evidence added", body = DisputeEvidence),
+        (status = 422, description = "Request validation failed"),
+        (status = 404, description = "Dispute not found"),
+        (status = 409, description = "Dispute is closed")
+    )
+)]
+pub async fn add_dispute_evidence(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(dispute_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<EvidenceInput>,
+) -> Result<Json<DisputeEvidence>> {
+    let evidence = state
+        .dispute_service
+        .add_evidence(
+            user.0.sub,
+            is_admin(&user),
+            dispute_id,
+            payload.kind,
+            &payload.content,
+        )
+        .await?;
+
+    Ok(Json(evidence))
+}
+
+/// Withdraw a dispute you raised
+/// POST /api/v1/trading/disputes/{id}/withdraw
+#[utoipa::path(
+    post,
+    path = "/api/v1/trading/disputes/{id}/withdraw",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Dispute ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Dispute withdrawn", body = SettlementDispute),
+        (status = 404, description = "Dispute not found"),
+        (status = 409, description = "Dispute is closed")
+    )
+)]
+pub async fn withdraw_dispute(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(dispute_id): Path<Uuid>,
+) -> Result<Json<SettlementDispute>> {
+    Ok(Json(
+        state.dispute_service.withdraw(user.0.sub, dispute_id).await?,
+    ))
+}
+
+/// Admin dispute queue
+/// GET /api/v1/admin/disputes
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/disputes",
+    tag = "trading",
+    params(DisputeListQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Disputes, oldest first", body = Vec<SettlementDispute>),
+        (status = 400, description = "Invalid status filter"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn admin_list_disputes(
+    State(state): State<AppState>,
+    Query(params): Query<DisputeListQuery>,
+) -> Result<Json<Vec<SettlementDispute>>> {
+    let status = match params.status.as_deref() {
+        None => None,
+        Some(value) => Some(DisputeStatus::parse(value).ok_or_else(|| {
+            ApiError::validation_field(
+                "status",
+                "Invalid status. Use: open, under_review, upheld, rejected, withdrawn",
+            )
+        })?),
+    };
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    Ok(Json(state.dispute_service.list(status, limit, offset).await?))
+}
+
+/// Take an open dispute into review
+/// POST /api/v1/admin/disputes/{id}/review
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/disputes/{id}/review",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Dispute ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Dispute under review", body = SettlementDispute),
+        (status = 404, description = "Dispute not found"),
+        (status = 409, description = "Dispute is not open")
+    )
+)]
+pub async fn admin_review_dispute(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(dispute_id): Path<Uuid>,
+) -> Result<Json<SettlementDispute>> {
+    Ok(Json(
+        state.dispute_service.start_review(user.0.sub, dispute_id).await?,
+    ))
+}
+
+/// Resolve a dispute
+/// POST /api/v1/admin/disputes/{id}/resolve
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/disputes/{id}/resolve",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Dispute ID")),
+    request_body = ResolveDisputeRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Dispute resolved", body = SettlementDispute),
+        (status = 400, description = "Invalid resolution"),
+        (status = 422, description = "Request validation failed"),
+        (status = 404, description = "Dispute not found"),
+        (status = 409, description = "Dispute is closed")
+    )
+)]
+pub async fn admin_resolve_dispute(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(dispute_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ResolveDisputeRequest>,
+) -> Result<Json<SettlementDispute>> {
+    let dispute = state
+        .dispute_service
+        .resolve(
+            user.0.sub,
+            dispute_id,
+            payload.outcome,
+            &payload.notes,
+            payload.compensation,
+            payload.revoke_certificates.unwrap_or(true),
+        )
+        .await?;
+
+    Ok(Json(dispute))
+}
+
+fn is_admin(user: &AuthenticatedUser) -> bool {
+    matches!(Role::from_str(&user.0.role), Ok(Role::Admin))
+}
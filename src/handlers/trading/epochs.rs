@@ -0,0 +1,202 @@
+//! Market Epoch Endpoints
+//!
+//! Clearing results of individual market epochs, how each user took part in
+//! them, how long their matching took, how a shadow matcher would have
+//! cleared them, and what clearing them now would produce
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::services::market_clearing::curves::EpochCurves;
+use crate::services::market_clearing::participation::EpochParticipation;
+use crate::services::market_clearing::performance::EpochMatchingPerformance;
+use crate::services::market_clearing::shadow::ShadowComparison;
+use crate::services::market_clearing::simulation::ClearingSimulation;
+use crate::AppState;
+
+const DEFAULT_PERFORMANCE_LIMIT: i64 = 50;
+const MAX_PERFORMANCE_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MatchingPerformanceQuery {
+    /// Most recent epochs to return, default 50, at most 500
+    pub limit: Option<i64>,
+}
+
+/// Get supply and demand curves for an epoch
+/// GET /api/v1/trading/market/epochs/{id}/curves
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/market/epochs/{id}/curves",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Epoch ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Aggregated bid/ask curves and clearing summary", body = EpochCurves),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Epoch not found")
+    )
+)]
+pub async fn get_epoch_curves(
+    State(state): State<AppState>,
+    Path(epoch_id): Path<Uuid>,
+) -> Result<Json<EpochCurves>> {
+    let curves = state
+        .market_clearing
+        .get_epoch_curves(epoch_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("Epoch {} not found", epoch_id)))?;
+
+    Ok(Json(curves))
+}
+
+/// Get how an epoch's clearing treated the current user
+/// GET /api/v1/trading/market/epochs/{id}/my-participation
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/market/epochs/{id}/my-participation",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Epoch ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The user's orders, fills, allocation, fees and settlements in the epoch", body = EpochParticipation),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Epoch not found")
+    )
+)]
+pub async fn get_my_epoch_participation(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(epoch_id): Path<Uuid>,
+) -> Result<Json<EpochParticipation>> {
+    let participation = state
+        .market_clearing
+        .get_epoch_participation(epoch_id, user.0.sub)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("Epoch {} not found", epoch_id)))?;
+
+    Ok(Json(participation))
+}
+
+/// List matching timings of recent epochs
+/// GET /api/v1/admin/epochs/performance
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/epochs/performance",
+    tag = "admin",
+    params(MatchingPerformanceQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Per-stage matching timings, newest epoch first", body = Vec<EpochMatchingPerformance>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_matching_performance(
+    State(state): State<AppState>,
+    Query(params): Query<MatchingPerformanceQuery>,
+) -> Result<Json<Vec<EpochMatchingPerformance>>> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_PERFORMANCE_LIMIT)
+        .clamp(1, MAX_PERFORMANCE_LIMIT);
+    let records = state
+        .market_clearing
+        .recent_matching_performance(limit)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(records))
+}
+
+/// Get the matching timings of an epoch
+/// GET /api/v1/admin/epochs/{id}/performance
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/epochs/{id}/performance",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Epoch ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Per-stage matching timings", body = EpochMatchingPerformance),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Epoch has not been matched")
+    )
+)]
+pub async fn get_matching_performance(
+    State(state): State<AppState>,
+    Path(epoch_id): Path<Uuid>,
+) -> Result<Json<EpochMatchingPerformance>> {
+    let record = state
+        .market_clearing
+        .get_matching_performance(epoch_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("No matching performance recorded for epoch {}", epoch_id)))?;
+
+    Ok(Json(record))
+}
+
+/// Dry-run clearing of an epoch against its current book
+/// POST /api/v1/admin/epochs/{id}/simulate-clearing
+///
+/// Nothing is persisted: no matches, fills, order events or settlements.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/epochs/{id}/simulate-clearing",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Epoch ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Would-be clearing price, matched volume and per-order allocations", body = ClearingSimulation),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Epoch not found")
+    )
+)]
+pub async fn simulate_clearing(
+    State(state): State<AppState>,
+    Path(epoch_id): Path<Uuid>,
+) -> Result<Json<ClearingSimulation>> {
+    let simulation = state
+        .market_clearing
+        .simulate_clearing(epoch_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("Epoch {} not found", epoch_id)))?;
+
+    Ok(Json(simulation))
+}
+
+/// Get the shadow matcher comparisons of an epoch
+/// GET /api/v1/admin/epochs/{id}/shadow-comparisons
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/epochs/{id}/shadow-comparisons",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Epoch ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Live vs shadow matching per market; empty when shadow mode was off", body = Vec<ShadowComparison>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_shadow_comparisons(
+    State(state): State<AppState>,
+    Path(epoch_id): Path<Uuid>,
+) -> Result<Json<Vec<ShadowComparison>>> {
+    let comparisons = state
+        .market_clearing
+        .get_shadow_comparisons(epoch_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(comparisons))
+}
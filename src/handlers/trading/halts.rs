@@ -0,0 +1,166 @@
+//! Trading Halt Handlers
+//!
+//! Admin halts of the platform, one market or one zone, with optional
+//! scheduled resumption
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::trading_halts::{HaltMode, TradingHalt};
+use crate::AppState;
+
+const MAX_LIMIT: i64 = 500;
+
+/// New trading halt; leave market and zone empty to halt the whole platform
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateTradingHaltRequest {
+    pub market_id: Option<Uuid>,
+    #[validate(range(min = 0))]
+    pub zone_id: Option<i32>,
+    /// full stops entry, cancels and matching; cancel_only still allows cancels
+    pub mode: HaltMode,
+    #[validate(length(max = 500), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub reason: String,
+    /// Scheduled resumption; the halt stays until lifted if omitted
+    pub resume_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RescheduleTradingHaltRequest {
+    /// New resumption time, or null to keep the halt until lifted
+    pub resume_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TradingHaltQuery {
+    /// Include lifted and resumed halts (default false)
+    pub all: Option<bool>,
+    /// Default 100, at most 500; applies with `all`
+    pub limit: Option<i64>,
+}
+
+/// List trading halts
+/// GET /api/v1/admin/trading-halts
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/trading-halts",
+    tag = "admin",
+    params(TradingHaltQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Halts in force, or all halts with `all`, newest first", body = Vec<TradingHalt>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_trading_halts(
+    State(state): State<AppState>,
+    Query(params): Query<TradingHaltQuery>,
+) -> Result<Json<Vec<TradingHalt>>> {
+    if params.all.unwrap_or(false) {
+        let limit = params.limit.unwrap_or(100).clamp(1, MAX_LIMIT);
+        return Ok(Json(state.trading_halts.list(limit).await?));
+    }
+    Ok(Json(state.trading_halts.active().await?))
+}
+
+/// Halt trading
+/// POST /api/v1/admin/trading-halts
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/trading-halts",
+    tag = "admin",
+    request_body = CreateTradingHaltRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Halt in force and broadcast over WebSocket", body = TradingHalt),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Market not found"),
+        (status = 422, description = "Resumption not in the future")
+    )
+)]
+pub async fn create_trading_halt(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<CreateTradingHaltRequest>,
+) -> Result<Json<TradingHalt>> {
+    if let Some(market_id) = payload.market_id {
+        state.markets.get(market_id).await?;
+    }
+
+    Ok(Json(
+        state
+            .trading_halts
+            .halt(
+                user.0.sub,
+                payload.market_id,
+                payload.zone_id,
+                payload.mode,
+                &payload.reason,
+                payload.resume_at,
+                &state.audit_logger,
+            )
+            .await?,
+    ))
+}
+
+/// Schedule or clear the resumption of a halt
+/// PUT /api/v1/admin/trading-halts/{id}/resume-at
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/trading-halts/{id}/resume-at",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Trading halt ID")),
+    request_body = RescheduleTradingHaltRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Resumption updated", body = TradingHalt),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Halt not found or no longer in force"),
+        (status = 422, description = "Resumption not in the future")
+    )
+)]
+pub async fn reschedule_trading_halt(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RescheduleTradingHaltRequest>,
+) -> Result<Json<TradingHalt>> {
+    Ok(Json(
+        state
+            .trading_halts
+            .reschedule(user.0.sub, id, payload.resume_at, &state.audit_logger)
+            .await?,
+    ))
+}
+
+/// Lift a halt now
+/// POST /api/v1/admin/trading-halts/{id}/lift
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/trading-halts/{id}/lift",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Trading halt ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Halt lifted and broadcast over WebSocket", body = TradingHalt),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Halt not found or no longer in force")
+    )
+)]
+pub async fn lift_trading_halt(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TradingHalt>> {
+    Ok(Json(state.trading_halts.lift(user.0.sub, id, &state.audit_logger).await?))
+}
@@ -0,0 +1,83 @@
+//! Imbalance Settlement Endpoints
+//!
+//! Per-epoch gap between a participant's traded and metered net export and
+//! what it was charged or paid for it
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::services::imbalance::{ImbalanceFilter, ImbalanceSettlement};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ImbalanceQuery {
+    pub epoch_id: Option<Uuid>,
+    /// Maximum results, default 100
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AdminImbalanceQuery {
+    pub epoch_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    /// Maximum results, default 100
+    pub limit: Option<i64>,
+}
+
+/// List the caller's imbalances
+/// GET /api/v1/trading/imbalances
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/imbalances",
+    tag = "trading",
+    params(ImbalanceQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Imbalance per epoch, newest first", body = Vec<ImbalanceSettlement>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_my_imbalances(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<ImbalanceQuery>,
+) -> Result<Json<Vec<ImbalanceSettlement>>> {
+    let filter = ImbalanceFilter {
+        epoch_id: params.epoch_id,
+        user_id: Some(user.0.sub),
+        limit: params.limit.unwrap_or(100).clamp(1, 500),
+    };
+    Ok(Json(state.imbalance.list(filter).await?))
+}
+
+/// List imbalances of all participants
+/// GET /api/v1/admin/imbalances
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/imbalances",
+    tag = "admin",
+    params(AdminImbalanceQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Imbalance per user and epoch, newest first", body = Vec<ImbalanceSettlement>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn admin_list_imbalances(
+    State(state): State<AppState>,
+    Query(params): Query<AdminImbalanceQuery>,
+) -> Result<Json<Vec<ImbalanceSettlement>>> {
+    let filter = ImbalanceFilter {
+        epoch_id: params.epoch_id,
+        user_id: params.user_id,
+        limit: params.limit.unwrap_or(100).clamp(1, 500),
+    };
+    Ok(Json(state.imbalance.list(filter).await?))
+}
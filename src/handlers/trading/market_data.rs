@@ -216,5 +216,6 @@ pub async fn get_market_stats(
         active_orders,
         pending_orders,
         completed_matches,
+        halts: state.trading_halts.active().await?,
     }))
 }
@@ -1,6 +1,13 @@
+pub mod autopilot;
+pub mod batches;
 pub mod blockchain;
 pub mod conditional;
+pub mod delivery;
+pub mod disputes;
+pub mod epochs;
 pub mod export;
+pub mod halts;
+pub mod imbalance;
 pub mod market_data;
 pub mod orders;
 pub mod p2p;
@@ -10,6 +17,9 @@ pub mod status;
 pub mod types;
 pub mod routes;
 pub mod revenue;
+pub mod session;
+pub mod settlement_admin;
+pub mod trade_admin;
 
 pub use blockchain::*;
 pub use conditional::*;
@@ -0,0 +1,667 @@
+//! Bulk order import and export in the standard order CSV format
+//!
+//! Columns: `side,order_type,energy_amount,price_per_kwh,expiry_time,zone_id,meter_id,client_ref`.
+//! Columns are matched by header name and unknown ones are ignored, so an
+//! export can be edited and imported again. Both directions stream the file
+//! instead of holding it in memory.
+
+use std::str::FromStr;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::database::schema::types::{OrderSide, OrderStatus, OrderType};
+use crate::error::{ApiError, Result};
+use crate::handlers::trading::types::OrderQuery;
+use crate::models::trading::TradingOrderDb;
+use crate::utils::validation::rules;
+use crate::AppState;
+
+/// Most orders accepted in one file
+const MAX_IMPORT_ROWS: usize = 5_000;
+/// Longest accepted line; longer input is not a valid order row
+const MAX_LINE_BYTES: usize = 4_096;
+/// Rows fetched per export chunk
+const EXPORT_PAGE_SIZE: i64 = 1_000;
+
+const EXPORT_HEADER: &str = "order_id,side,order_type,energy_amount,price_per_kwh,expiry_time,zone_id,meter_id,filled_amount,status,created_at,filled_at\n";
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ImportQuery {
+    /// Validate the file without placing any order
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A row that failed validation
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportRowError {
+    /// 1-based line number in the file, the header being line 1
+    pub line: usize,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Outcome of placing one imported order
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportRowResult {
+    pub line: usize,
+    pub client_ref: Option<String>,
+    pub order_id: Option<Uuid>,
+    /// Why the order was rejected when it was placed
+    pub error: Option<String>,
+}
+
+/// Validation report of an order import
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrderImportReport {
+    pub dry_run: bool,
+    /// Order rows in the file, blank lines excluded
+    pub rows: usize,
+    pub valid: usize,
+    /// Validation failures; no order is placed while any row is invalid
+    pub errors: Vec<ImportRowError>,
+    pub placed: usize,
+    pub rejected: usize,
+    pub results: Vec<ImportRowResult>,
+}
+
+/// A validated order row
+#[derive(Debug, Clone, PartialEq)]
+struct ImportRow {
+    line: usize,
+    side: OrderSide,
+    order_type: OrderType,
+    energy_amount: Decimal,
+    price_per_kwh: Option<Decimal>,
+    expiry_time: Option<DateTime<Utc>>,
+    zone_id: Option<i32>,
+    meter_id: Option<Uuid>,
+    client_ref: Option<String>,
+}
+
+/// Positions of the known columns in the header
+#[derive(Debug, Default)]
+struct ImportColumns {
+    side: usize,
+    order_type: usize,
+    energy_amount: usize,
+    price_per_kwh: Option<usize>,
+    expiry_time: Option<usize>,
+    zone_id: Option<usize>,
+    meter_id: Option<usize>,
+    client_ref: Option<usize>,
+}
+
+impl ImportColumns {
+    fn from_header(fields: &[String]) -> std::result::Result<Self, String> {
+        let find = |name: &str| fields.iter().position(|f| f.trim().eq_ignore_ascii_case(name));
+        let required = |name: &str| find(name).ok_or_else(|| format!("Missing required column '{}'", name));
+
+        Ok(Self {
+            side: required("side")?,
+            order_type: required("order_type")?,
+            energy_amount: required("energy_amount")?,
+            price_per_kwh: find("price_per_kwh"),
+            expiry_time: find("expiry_time"),
+            zone_id: find("zone_id"),
+            meter_id: find("meter_id"),
+            client_ref: find("client_ref"),
+        })
+    }
+}
+
+/// Split one CSV line into fields. Quoted fields may contain commas and
+/// doubled quotes; `None` when a quote is left open.
+fn split_csv_line(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    fields.push(field);
+    Some(fields)
+}
+
+/// Validate one data row against the same rules as `POST /orders`
+fn parse_row(
+    columns: &ImportColumns,
+    fields: &[String],
+    line: usize,
+    now: DateTime<Utc>,
+) -> std::result::Result<ImportRow, Vec<ImportRowError>> {
+    let mut errors = Vec::new();
+    let mut fail = |field: &str, message: String| {
+        errors.push(ImportRowError { line, field: Some(field.to_string()), message });
+    };
+    let value = |index: Option<usize>| -> Option<&str> {
+        index
+            .and_then(|i| fields.get(i))
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    };
+
+    let side = match value(Some(columns.side)).map(str::to_ascii_lowercase).as_deref() {
+        Some("buy") => Some(OrderSide::Buy),
+        Some("sell") => Some(OrderSide::Sell),
+        _ => {
+            fail("side", "Must be buy or sell".to_string());
+            None
+        }
+    };
+
+    let order_type = match value(Some(columns.order_type)).map(str::to_ascii_lowercase).as_deref() {
+        Some("limit") => Some(OrderType::Limit),
+        Some("market") => Some(OrderType::Market),
+        _ => {
+            fail("order_type", "Must be limit or market".to_string());
+            None
+        }
+    };
+
+    let energy_amount = match value(Some(columns.energy_amount)).map(Decimal::from_str) {
+        Some(Ok(amount)) => match rules::energy_amount(&amount) {
+            Ok(()) => Some(amount),
+            Err(e) => {
+                fail("energy_amount", rule_message(e));
+                None
+            }
+        },
+        Some(Err(_)) => {
+            fail("energy_amount", "Not a decimal number".to_string());
+            None
+        }
+        None => {
+            fail("energy_amount", "Required".to_string());
+            None
+        }
+    };
+
+    // Market orders execute at the clearing price, so any price given is ignored
+    let price_per_kwh = match value(columns.price_per_kwh).map(Decimal::from_str) {
+        _ if order_type == Some(OrderType::Market) => None,
+        Some(Ok(price)) => match rules::price_per_kwh(&price) {
+            Ok(()) => Some(price),
+            Err(e) => {
+                fail("price_per_kwh", rule_message(e));
+                None
+            }
+        },
+        Some(Err(_)) => {
+            fail("price_per_kwh", "Not a decimal number".to_string());
+            None
+        }
+        None => {
+            if order_type == Some(OrderType::Limit) {
+                fail("price_per_kwh", "Required for limit orders".to_string());
+            }
+            None
+        }
+    };
+
+    let expiry_time = match value(columns.expiry_time).map(DateTime::parse_from_rfc3339) {
+        Some(Ok(expiry)) if expiry.with_timezone(&Utc) <= now => {
+            fail("expiry_time", "Must be in the future".to_string());
+            None
+        }
+        Some(Ok(expiry)) => Some(expiry.with_timezone(&Utc)),
+        Some(Err(_)) => {
+            fail("expiry_time", "Must be an RFC 3339 timestamp".to_string());
+            None
+        }
+        None => None,
+    };
+
+    let zone_id = match value(columns.zone_id).map(i32::from_str) {
+        Some(Ok(zone)) if zone >= 0 => Some(zone),
+        Some(_) => {
+            fail("zone_id", "Must be a non-negative integer".to_string());
+            None
+        }
+        None => None,
+    };
+
+    let meter_id = match value(columns.meter_id).map(Uuid::from_str) {
+        Some(Ok(meter)) => Some(meter),
+        Some(Err(_)) => {
+            fail("meter_id", "Must be a UUID".to_string());
+            None
+        }
+        None => None,
+    };
+
+    let client_ref = value(columns.client_ref).map(|r| r.chars().take(100).collect::<String>());
+
+    match (side, order_type, energy_amount) {
+        (Some(side), Some(order_type), Some(energy_amount)) if errors.is_empty() => Ok(ImportRow {
+            line,
+            side,
+            order_type,
+            energy_amount,
+            price_per_kwh,
+            expiry_time,
+            zone_id,
+            meter_id,
+            client_ref,
+        }),
+        _ => Err(errors),
+    }
+}
+
+fn rule_message(error: validator::ValidationError) -> String {
+    error
+        .message
+        .map(|m| m.to_string())
+        .unwrap_or_else(|| error.code.to_string())
+}
+
+/// Line-by-line validation of an uploaded file
+#[derive(Default)]
+struct ImportParser {
+    columns: Option<ImportColumns>,
+    line: usize,
+    rows: Vec<ImportRow>,
+    errors: Vec<ImportRowError>,
+    row_count: usize,
+}
+
+impl ImportParser {
+    fn push_line(&mut self, raw: &[u8], now: DateTime<Utc>) -> Result<()> {
+        self.line += 1;
+        let line = self.line;
+
+        let Ok(text) = std::str::from_utf8(raw) else {
+            if self.columns.is_none() {
+                return Err(ApiError::validation_field("file", "Header line is not valid UTF-8"));
+            }
+            self.errors.push(ImportRowError { line, field: None, message: "Line is not valid UTF-8".to_string() });
+            return Ok(());
+        };
+        let text = text.trim_end_matches(['\r', '\n']);
+
+        let Some(columns) = &self.columns else {
+            let header = text.trim_start_matches('\u{feff}');
+            let fields = split_csv_line(header)
+                .ok_or_else(|| ApiError::validation_field("file", "Header line has an unterminated quote"))?;
+            self.columns = Some(
+                ImportColumns::from_header(&fields).map_err(|message| ApiError::validation_field("file", message))?,
+            );
+            return Ok(());
+        };
+
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+        self.row_count += 1;
+        if self.row_count > MAX_IMPORT_ROWS {
+            return Err(ApiError::validation_field(
+                "file",
+                format!("A file may contain at most {} orders", MAX_IMPORT_ROWS),
+            ));
+        }
+
+        match split_csv_line(text) {
+            Some(fields) => match parse_row(columns, &fields, line, now) {
+                Ok(row) => self.rows.push(row),
+                Err(errors) => self.errors.extend(errors),
+            },
+            None => self.errors.push(ImportRowError {
+                line,
+                field: None,
+                message: "Unterminated quote".to_string(),
+            }),
+        }
+        Ok(())
+    }
+}
+
+/// Import orders from CSV
+/// POST /api/v1/trading/orders/import
+#[utoipa::path(
+    post,
+    path = "/api/v1/trading/orders/import",
+    tag = "trading",
+    params(ImportQuery),
+    request_body(content = String, content_type = "text/csv", description = "Orders in the standard order CSV format"),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Validation report and, unless a dry run, the placed orders", body = OrderImportReport),
+        (status = 400, description = "Upload could not be read"),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Missing header columns or too many rows")
+    )
+)]
+pub async fn import_orders(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<ImportQuery>,
+    body: Body,
+) -> Result<Json<OrderImportReport>> {
+    let now = Utc::now();
+    let mut parser = ImportParser::default();
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut stream = body.into_data_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| ApiError::BadRequest(format!("Failed to read upload: {}", e)))?;
+        buffer.extend_from_slice(&chunk);
+
+        while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            parser.push_line(&line, now)?;
+        }
+        if buffer.len() > MAX_LINE_BYTES {
+            return Err(ApiError::validation_field(
+                "file",
+                format!("Line {} exceeds {} bytes", parser.line + 1, MAX_LINE_BYTES),
+            ));
+        }
+    }
+    if !buffer.is_empty() {
+        parser.push_line(&buffer, now)?;
+    }
+    if parser.columns.is_none() {
+        return Err(ApiError::validation_field("file", "File is empty"));
+    }
+
+    let mut report = OrderImportReport {
+        dry_run: params.dry_run,
+        rows: parser.row_count,
+        valid: parser.rows.len(),
+        errors: parser.errors,
+        placed: 0,
+        rejected: 0,
+        results: Vec::new(),
+    };
+
+    if params.dry_run || !report.errors.is_empty() {
+        return Ok(Json(report));
+    }
+
+    tracing::info!("Importing {} orders for user {}", parser.rows.len(), user.0.sub);
+
+    for row in parser.rows {
+        let outcome = state
+            .market_clearing
+            .create_order(
+                user.0.sub,
+                row.side,
+                row.order_type,
+                row.energy_amount,
+                row.price_per_kwh,
+                row.expiry_time,
+                row.zone_id,
+                row.meter_id,
+                None,
+                None,
+            )
+            .await;
+
+        let (order_id, error) = match outcome {
+            Ok(order_id) => {
+                report.placed += 1;
+                (Some(order_id), None)
+            }
+            Err(e) => {
+                report.rejected += 1;
+                (None, Some(e.to_string()))
+            }
+        };
+        report.results.push(ImportRowResult { line: row.line, client_ref: row.client_ref, order_id, error });
+    }
+
+    Ok(Json(report))
+}
+
+/// Filters of an export, taken from the order list query
+struct ExportCursor {
+    db: PgPool,
+    user_id: Uuid,
+    status: Option<OrderStatus>,
+    side: Option<OrderSide>,
+    order_type: Option<OrderType>,
+    order_by: String,
+    offset: i64,
+    header_sent: bool,
+    done: bool,
+}
+
+impl ExportCursor {
+    async fn next_page(&self) -> std::result::Result<Vec<TradingOrderDb>, sqlx::Error> {
+        let mut where_conditions = vec!["user_id = $1".to_string()];
+        let mut bind_count = 2;
+        if self.status.is_some() {
+            where_conditions.push(format!("status = ${}", bind_count));
+            bind_count += 1;
+        }
+        if self.side.is_some() {
+            where_conditions.push(format!("side = ${}", bind_count));
+            bind_count += 1;
+        }
+        if self.order_type.is_some() {
+            where_conditions.push(format!("order_type = ${}", bind_count));
+            bind_count += 1;
+        }
+
+        let query = format!(
+            "SELECT id, user_id, order_type, side, energy_amount, price_per_kwh, filled_amount, status, expires_at, created_at, filled_at, epoch_id, zone_id, meter_id, refund_tx_signature, order_pda, session_token, trigger_price, trigger_type, trigger_status, trailing_offset, triggered_at
+             FROM trading_orders
+             WHERE {}
+             ORDER BY {}
+             LIMIT ${} OFFSET ${}",
+            where_conditions.join(" AND "),
+            self.order_by,
+            bind_count,
+            bind_count + 1
+        );
+
+        let mut sqlx_query = sqlx::query_as::<_, TradingOrderDb>(&query).bind(self.user_id);
+        if let Some(status) = &self.status {
+            sqlx_query = sqlx_query.bind(status);
+        }
+        if let Some(side) = &self.side {
+            sqlx_query = sqlx_query.bind(side);
+        }
+        if let Some(order_type) = &self.order_type {
+            sqlx_query = sqlx_query.bind(order_type);
+        }
+
+        sqlx_query
+            .bind(EXPORT_PAGE_SIZE)
+            .bind(self.offset)
+            .fetch_all(&self.db)
+            .await
+    }
+}
+
+fn export_line(order: &TradingOrderDb) -> String {
+    let timestamp = |t: Option<DateTime<Utc>>| t.map(|t| t.to_rfc3339()).unwrap_or_default();
+    format!(
+        "{},{},{},{},{},{},{},{},{},{},{},{}\n",
+        order.id,
+        order.side,
+        order.order_type,
+        order.energy_amount,
+        order.price_per_kwh,
+        timestamp(order.expires_at),
+        order.zone_id.map(|z| z.to_string()).unwrap_or_default(),
+        order.meter_id.map(|m| m.to_string()).unwrap_or_default(),
+        order.filled_amount.unwrap_or(Decimal::ZERO),
+        order.status.as_str(),
+        timestamp(order.created_at),
+        timestamp(order.filled_at),
+    )
+}
+
+/// Export orders as CSV
+/// GET /api/v1/trading/orders/export
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/orders/export",
+    tag = "trading",
+    params(OrderQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "All orders matching the list filters, paging ignored", content_type = "text/csv"),
+        (status = 400, description = "Invalid sort field"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn export_orders(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(mut params): Query<OrderQuery>,
+) -> Result<Response> {
+    params.validate_params()?;
+
+    let direction = params.sort_direction().to_string();
+    let cursor = ExportCursor {
+        db: state.db.clone(),
+        user_id: user.0.sub,
+        order_by: format!("{} {}, id {}", params.get_sort_field(), direction, direction),
+        status: params.status,
+        side: params.side,
+        order_type: params.order_type,
+        offset: 0,
+        header_sent: false,
+        done: false,
+    };
+
+    let stream = futures::stream::unfold(cursor, |mut cursor| async move {
+        if cursor.done {
+            return None;
+        }
+        match cursor.next_page().await {
+            Ok(orders) => {
+                cursor.done = (orders.len() as i64) < EXPORT_PAGE_SIZE;
+                cursor.offset += orders.len() as i64;
+                if orders.is_empty() && cursor.header_sent {
+                    return None;
+                }
+
+                let mut chunk = String::new();
+                if !cursor.header_sent {
+                    chunk.push_str(EXPORT_HEADER);
+                    cursor.header_sent = true;
+                }
+                orders.iter().for_each(|order| chunk.push_str(&export_line(order)));
+                Some((Ok(Bytes::from(chunk)), cursor))
+            }
+            Err(e) => {
+                tracing::error!("Order export failed at offset {}: {}", cursor.offset, e);
+                cursor.done = true;
+                Some((Err(e), cursor))
+            }
+        }
+    });
+
+    let filename = format!("gridtokenx_orders_{}.csv", Utc::now().format("%Y%m%d_%H%M%S"));
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> ImportColumns {
+        let header = split_csv_line("side,order_type,energy_amount,price_per_kwh,expiry_time,zone_id,meter_id,client_ref").unwrap();
+        ImportColumns::from_header(&header).unwrap()
+    }
+
+    #[test]
+    fn test_split_csv_line_handles_quotes() {
+        assert_eq!(
+            split_csv_line(r#"buy,"a, ""quoted"" ref",3"#).unwrap(),
+            vec!["buy", r#"a, "quoted" ref"#, "3"]
+        );
+        assert_eq!(split_csv_line("a,,b").unwrap(), vec!["a", "", "b"]);
+        assert!(split_csv_line(r#"buy,"open"#).is_none());
+    }
+
+    #[test]
+    fn test_header_requires_core_columns() {
+        let header = split_csv_line("order_type,energy_amount").unwrap();
+        assert!(ImportColumns::from_header(&header).is_err());
+    }
+
+    #[test]
+    fn test_parse_valid_limit_row() {
+        let fields = split_csv_line("Sell,limit,12.5,0.15,,3,,desk-1").unwrap();
+        let row = parse_row(&columns(), &fields, 2, Utc::now()).unwrap();
+        assert_eq!(row.side, OrderSide::Sell);
+        assert_eq!(row.energy_amount, Decimal::from_str("12.5").unwrap());
+        assert_eq!(row.price_per_kwh, Decimal::from_str("0.15").ok());
+        assert_eq!(row.zone_id, Some(3));
+        assert_eq!(row.client_ref.as_deref(), Some("desk-1"));
+    }
+
+    #[test]
+    fn test_market_row_ignores_price() {
+        let fields = split_csv_line("buy,market,5,0,,,,").unwrap();
+        let row = parse_row(&columns(), &fields, 2, Utc::now()).unwrap();
+        assert_eq!(row.price_per_kwh, None);
+    }
+
+    #[test]
+    fn test_parse_row_collects_every_error() {
+        let fields = split_csv_line("hold,limit,abc,,2000-01-01T00:00:00Z,-1,not-a-uuid,").unwrap();
+        let errors = parse_row(&columns(), &fields, 7, Utc::now()).unwrap_err();
+        let fields: Vec<_> = errors.iter().filter_map(|e| e.field.as_deref()).collect();
+        assert_eq!(
+            fields,
+            vec!["side", "energy_amount", "price_per_kwh", "expiry_time", "zone_id", "meter_id"]
+        );
+        assert!(errors.iter().all(|e| e.line == 7));
+    }
+
+    #[test]
+    fn test_parser_skips_blank_lines_and_counts_rows() {
+        let now = Utc::now();
+        let mut parser = ImportParser::default();
+        parser.push_line(b"\xef\xbb\xbfside,order_type,energy_amount,price_per_kwh\r\n", now).unwrap();
+        parser.push_line(b"buy,limit,1,0.2\r\n", now).unwrap();
+        parser.push_line(b"\r\n", now).unwrap();
+        parser.push_line(b"sell,limit,1,\n", now).unwrap();
+        assert_eq!(parser.row_count, 2);
+        assert_eq!(parser.rows.len(), 1);
+        assert_eq!(parser.errors.len(), 1);
+        assert_eq!(parser.errors[0].line, 4);
+    }
+}
@@ -1,11 +1,14 @@
-use axum::{extract::State, response::Json};
+use axum::{extract::State, http::HeaderMap, response::Json};
 use chrono::Utc;
+use uuid::Uuid;
 
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::database::schema::types::OrderStatus;
 use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
 use crate::models::trading::CreateOrderRequest;
+use crate::services::wallet::DeviceBinding;
 use crate::AppState;
 use crate::handlers::websocket::broadcaster::broadcast_p2p_order_update;
 
@@ -21,17 +24,33 @@ use crate::handlers::trading::types::CreateOrderResponse;
     security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Order created successfully", body = CreateOrderResponse),
-        (status = 400, description = "Invalid order parameters"),
-        (status = 401, description = "Unauthorized"),
+        (status = 400, description = "Invalid order parameters or insufficient balance/energy"),
+        (status = 401, description = "Unauthorized, or wallet session expired or bound to another device"),
+        (status = 409, description = "Market is closed"),
+        (status = 422, description = "Request validation failed"),
+        (status = 429, description = "Too many order requests in flight for this caller"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn create_order(
     State(state): State<AppState>,
     user: AuthenticatedUser,
-    Json(payload): Json<CreateOrderRequest>,
+    headers: HeaderMap,
+    ValidatedJson(payload): ValidatedJson<CreateOrderRequest>,
 ) -> Result<Json<CreateOrderResponse>> {
-    tracing::info!("Creating trading order for user: {}", user.0.sub);
+    // A wallet session may only be used from the device it was created on
+    if let Some(token) = payload.session_token.as_deref() {
+        state
+            .wallet_sessions
+            .touch(user.0.sub, token, DeviceBinding::from_headers(&headers).as_ref())
+            .await?;
+    }
+    Ok(Json(submit_order(&state, user.0.sub, payload).await?))
+}
+
+/// Place a validated order for a user. Shared by REST and WebSocket order entry.
+pub async fn submit_order(state: &AppState, user_id: Uuid, payload: CreateOrderRequest) -> Result<CreateOrderResponse> {
+    tracing::info!("Creating trading order for user: {}", user_id);
 
     // Verify signature if provided (P2P orders)
     if let (Some(signature), Some(timestamp)) = (&payload.signature, payload.timestamp) {
@@ -75,31 +94,13 @@ pub async fn create_order(
         tracing::info!("P2P Order signature verified successfully");
     }
 
-    // Auto-detect zone if not provided
-    let zone_id = if let Some(zid) = payload.zone_id {
-        Some(zid)
-    } else {
-        // Try to find user's zone from their registered meter
-        let meter_zone = sqlx::query!(
-            "SELECT zone_id FROM meter_registry WHERE user_id = $1 ORDER BY created_at DESC LIMIT 1",
-            user.0.sub
-        )
-        .fetch_optional(&state.db)
-        .await
-        .unwrap_or(None)
-        .and_then(|r| r.zone_id);
-        
-        if meter_zone.is_none() {
-            tracing::warn!("User {} has no registered meter/zone. Defaulting to unknown zone.", user.0.sub);
-        }
-        meter_zone
-    };
+    let zone_id = order_zone(state, user_id, payload.zone_id).await;
 
     // Call MarketClearingService to handle order creation (DB + On-Chain)
     let order_id = state
         .market_clearing
         .create_order(
-            user.0.sub,
+            user_id,
             payload.side,
             payload.order_type,
             payload.energy_amount,
@@ -108,11 +109,14 @@ pub async fn create_order(
             zone_id,
             payload.meter_id,
             payload.session_token.as_deref(),
+            payload.market_id,
         )
         .await
         .map_err(|e| {
             tracing::error!("Failed to create order via service: {}", e);
-            ApiError::Internal(format!("Order creation failed: {}", e))
+            // Keep client-facing errors (e.g. insufficient balance) intact
+            e.downcast::<ApiError>()
+                .unwrap_or_else(|e| ApiError::Internal(format!("Order creation failed: {}", e)))
         })?;
 
     // Get epoch info for response message
@@ -125,7 +129,7 @@ pub async fn create_order(
     // Broadcast P2P order creation via WebSocket
     if let Err(e) = broadcast_p2p_order_update(
         order_id,
-        user.0.sub,
+        user_id,
         payload.side.to_string(),
         "open".to_string(),
         payload.energy_amount.to_string(),
@@ -136,7 +140,7 @@ pub async fn create_order(
         tracing::warn!("Failed to broadcast order creation: {}", e);
     }
 
-    Ok(Json(CreateOrderResponse {
+    Ok(CreateOrderResponse {
         id: order_id,
         status: OrderStatus::Pending,
         created_at: now,
@@ -144,5 +148,26 @@ pub async fn create_order(
             "Order created successfully and assigned to epoch {} for matching.",
             epoch.epoch_number
         ),
-    }))
+    })
+}
+
+/// Zone of an order: the requested one, else the zone of the user's most recently registered meter
+pub(super) async fn order_zone(state: &AppState, user_id: Uuid, requested: Option<i32>) -> Option<i32> {
+    if requested.is_some() {
+        return requested;
+    }
+
+    let meter_zone = sqlx::query!(
+        "SELECT zone_id FROM meter_registry WHERE user_id = $1 ORDER BY created_at DESC LIMIT 1",
+        user_id
+    )
+    .fetch_optional(&state.db)
+    .await
+    .unwrap_or(None)
+    .and_then(|r| r.zone_id);
+
+    if meter_zone.is_none() {
+        tracing::warn!("User {} has no registered meter/zone. Defaulting to unknown zone.", user_id);
+    }
+    meter_zone
 }
@@ -6,7 +6,8 @@ use uuid::Uuid;
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::error::{ApiError, Result};
-use crate::models::trading::{CreateOrderRequest, TradingOrder};
+use crate::handlers::common::ValidatedJson;
+use crate::models::trading::{TradingOrder, UpdateOrderRequest};
 use crate::AppState;
 
 /// Cancel a trading order
@@ -29,12 +30,17 @@ pub async fn cancel_order(
     user: AuthenticatedUser,
     Path(order_id): Path<Uuid>,
 ) -> Result<Json<TradingOrder>> {
+    Ok(Json(cancel_user_order(&state, user.0.sub, order_id).await?))
+}
+
+/// Cancel a user's pending order and release its escrow. Shared by REST and WebSocket order entry.
+pub async fn cancel_user_order(state: &AppState, user_id: Uuid, order_id: Uuid) -> Result<TradingOrder> {
     // 1. Check if order exists and belongs to user
     let order = sqlx::query_as::<_, crate::models::trading::TradingOrderDb>(
         "SELECT * FROM trading_orders WHERE id = $1 AND user_id = $2",
     )
     .bind(order_id)
-    .bind(user.0.sub)
+    .bind(user_id)
     .fetch_optional(&state.db)
     .await
     .map_err(ApiError::Database)?;
@@ -71,12 +77,12 @@ pub async fn cancel_order(
         match updated_order.side {
             OrderSide::Buy => {
                 let refund_value = remaining_amount * updated_order.price_per_kwh;
-                if let Err(e) = state.market_clearing.unlock_funds(user.0.sub, order_id, refund_value, "Order Cancelled").await {
+                if let Err(e) = state.market_clearing.unlock_funds(user_id, order_id, refund_value, "Order Cancelled").await {
                     tracing::error!("Failed to refund funds for cancelled order {}: {}", order_id, e);
                 }
             }
             OrderSide::Sell => {
-                if let Err(e) = state.market_clearing.unlock_energy(user.0.sub, order_id, remaining_amount, "Order Cancelled").await {
+                if let Err(e) = state.market_clearing.unlock_energy(user_id, order_id, remaining_amount, "Order Cancelled").await {
                     tracing::error!("Failed to unlock energy for cancelled order {}: {}", order_id, e);
                 }
             }
@@ -84,7 +90,7 @@ pub async fn cancel_order(
     }
 
     // 5. Return updated order
-    Ok(Json(updated_order.into()))
+    Ok(updated_order.into())
 }
 
 /// Update a trading order
@@ -92,7 +98,7 @@ pub async fn cancel_order(
     put,
     path = "/api/trading/orders/{id}",
     tag = "trading",
-    request_body = CreateOrderRequest,
+    request_body = UpdateOrderRequest,
     security(("bearer_auth" = [])),
     params(
         ("id" = Uuid, Path, description = "Order ID to update")
@@ -100,30 +106,21 @@ pub async fn cancel_order(
     responses(
         (status = 200, description = "Order updated successfully", body = TradingOrder),
         (status = 404, description = "Order not found"),
-        (status = 400, description = "Order cannot be updated (not pending or validation failed)")
+        (status = 400, description = "Order cannot be updated (not pending)"),
+        (status = 409, description = "Market is closed"),
+        (status = 422, description = "Request validation failed")
     )
 )]
 pub async fn update_order(
     State(state): State<AppState>,
     user: AuthenticatedUser,
     Path(order_id): Path<Uuid>,
-    Json(payload): Json<crate::models::trading::UpdateOrderRequest>,
+    ValidatedJson(payload): ValidatedJson<crate::models::trading::UpdateOrderRequest>,
 ) -> Result<Json<TradingOrder>> {
-    // 1. Validate payload
-    if let Some(amt) = payload.energy_amount {
-        if amt <= rust_decimal::Decimal::ZERO {
-            return Err(ApiError::BadRequest(
-                "Energy amount must be positive".to_string(),
-            ));
-        }
-    }
-    if let Some(price) = payload.price_per_kwh {
-        if price <= rust_decimal::Decimal::ZERO {
-            return Err(ApiError::BadRequest("Price must be positive".to_string()));
-        }
-    }
+    // Amendments follow the same session rules as new orders
+    state.market_session.ensure_accepting_orders()?;
 
-    // 2. Fetch order
+    // 1. Fetch order
     let order = sqlx::query_as::<_, crate::models::trading::TradingOrderDb>(
         "SELECT * FROM trading_orders WHERE id = $1 AND user_id = $2",
     )
@@ -138,18 +135,18 @@ pub async fn update_order(
         None => return Err(ApiError::NotFound(format!("Order {} not found", order_id))),
     };
 
-    // 3. Validate status
+    // 2. Validate status
     if order.status != crate::database::schema::types::OrderStatus::Pending {
         return Err(ApiError::BadRequest(
             "Only pending orders can be updated".to_string(),
         ));
     }
 
-    // 4. Update fields
+    // 3. Update fields
     let new_energy = payload.energy_amount.unwrap_or(order.energy_amount);
     let new_price = payload.price_per_kwh.unwrap_or(order.price_per_kwh);
 
-    // 5. Adjust Escrow
+    // 4. Adjust Escrow
     use crate::database::schema::types::OrderSide;
     match order.side {
         OrderSide::Buy => {
@@ -178,7 +175,7 @@ pub async fn update_order(
         }
     }
 
-    // 6. Update DB
+    // 5. Update DB
     let updated_order = sqlx::query_as::<_, crate::models::trading::TradingOrderDb>(
         r#"
         UPDATE trading_orders 
@@ -1,7 +1,13 @@
+pub mod bulk;
 pub mod create;
 pub mod management;
 pub mod queries;
+pub mod routing;
+pub mod wait;
 
+pub use bulk::{export_orders, import_orders};
 pub use create::create_order;
 pub use management::{cancel_order, update_order};
-pub use queries::{get_order_book, get_user_orders, get_my_trades, get_token_balance};
+pub use routing::{route_order, list_execution_reports, get_execution_report};
+pub use queries::{get_order_book, get_user_orders, get_my_trades, get_token_balance, get_order_events, get_order_dust};
+pub use wait::wait_for_order;
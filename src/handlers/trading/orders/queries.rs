@@ -1,12 +1,15 @@
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
     response::Json,
 };
 use utoipa::{IntoParams, ToSchema};
 
 use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
 use crate::error::{ApiError, Result};
 use crate::models::trading::{TradingOrder, TradingOrderDb};
+use crate::services::order_dust::{self, DustSummary};
+use crate::services::order_events::{self, OrderEvent};
 use crate::utils::PaginationParams;
 use crate::AppState;
 
@@ -136,13 +139,30 @@ pub async fn get_user_orders(
     }))
 }
 
+/// Another participant's order as shown in the order book: the owner is a
+/// public participant ID and meter, chain and session details are withheld
+fn anonymize_order(order: TradingOrder, participant_ids: &crate::services::ParticipantIds) -> TradingOrder {
+    TradingOrder {
+        user_id: participant_ids.public_user_id(order.user_id),
+        meter_id: None,
+        refund_tx_signature: None,
+        order_pda: None,
+        session_token: None,
+        ..order
+    }
+}
+
 /// Get public order book
 /// GET /api/trading/orderbook
+///
+/// Other participants' orders carry a rotating public participant ID in
+/// `user_id`; admins see the real owners.
 #[utoipa::path(
     get,
     path = "/api/trading/orderbook",
     tag = "trading",
     params(OrderQuery),
+    security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Public order book", body = Vec<TradingOrder>),
         (status = 500, description = "Internal server error")
@@ -150,6 +170,7 @@ pub async fn get_user_orders(
 )]
 pub async fn get_order_book(
     State(_state): State<AppState>,
+    user: AuthenticatedUser,
     Query(mut params): Query<OrderQuery>,
 ) -> Result<Json<TradingOrdersResponse>> {
     tracing::info!("Fetching public order book");
@@ -241,6 +262,18 @@ pub async fn get_order_book(
         .map(|db_order| db_order.into())
         .collect::<Vec<TradingOrder>>();
 
+    let reveal_owners = matches!(Role::from_str(&user.0.role), Ok(Role::Admin));
+    let orders = orders
+        .into_iter()
+        .map(|order| {
+            if reveal_owners || order.user_id == user.0.sub {
+                order
+            } else {
+                anonymize_order(order, &_state.participant_ids)
+            }
+        })
+        .collect();
+
     let pagination = crate::utils::PaginationMeta::new(
         &PaginationParams {
             page: params.page,
@@ -359,6 +392,76 @@ pub struct TradeHistoryResponse {
     pub trades: Vec<TradeRecord>,
 }
 
+/// Get the lifecycle events of one of the user's orders
+/// GET /api/v1/trading/orders/{id}/events
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/orders/{id}/events",
+    tag = "trading",
+    params(
+        ("id" = Uuid, Path, description = "Order ID")
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Order lifecycle events, oldest first", body = Vec<OrderEvent>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Order not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_order_events(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(order_id): Path<uuid::Uuid>,
+) -> Result<Json<Vec<OrderEvent>>> {
+    let events = order_events::list_for_order(&state.db, order_id, user.0.sub)
+        .await
+        .map_err(ApiError::Database)?;
+
+    if events.is_empty() {
+        // Orders placed before event recording have no history; only 404 on unknown orders
+        let owned = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM trading_orders WHERE id = $1 AND user_id = $2)",
+        )
+        .bind(order_id)
+        .bind(user.0.sub)
+        .fetch_one(&state.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        if !owned {
+            return Err(ApiError::NotFound(format!("Order {} not found", order_id)));
+        }
+    }
+
+    Ok(Json(events))
+}
+
+/// Get the caller's dust cancellations
+/// GET /api/v1/trading/orders/dust
+///
+/// Order remainders cancelled for falling below their market's dust
+/// threshold, totalled per market.
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/orders/dust",
+    tag = "trading",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Dust cancelled per market, most recent first", body = DustSummary),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_order_dust(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<DustSummary>> {
+    let summary = order_dust::summary(&state.db, user.0.sub)
+        .await
+        .map_err(ApiError::Database)?;
+    Ok(Json(summary))
+}
+
 /// Get user's GRID token balance
 /// GET /api/v1/trading/balance
 #[utoipa::path(
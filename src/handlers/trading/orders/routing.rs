@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::models::trading::RouteOrderRequest;
+use crate::services::order_router::ExecutionReport;
+use crate::AppState;
+
+use super::create::order_zone;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExecutionReportsQuery {
+    /// Maximum reports to return (default 50, max 200)
+    pub limit: Option<i64>,
+}
+
+/// Route a market order
+/// POST /api/v1/trading/orders/route
+///
+/// Fills from the orderbook first; any remainder is swapped with the
+/// market's AMM pool when its effective price is within tolerance. The
+/// execution report shows the split.
+#[utoipa::path(
+    post,
+    path = "/api/v1/trading/orders/route",
+    tag = "trading",
+    request_body = RouteOrderRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Order routed", body = ExecutionReport),
+        (status = 400, description = "Invalid order for the market or insufficient balance/energy"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Market is closed"),
+        (status = 422, description = "Request validation failed")
+    )
+)]
+pub async fn route_order(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<RouteOrderRequest>,
+) -> Result<Json<ExecutionReport>> {
+    let zone_id = order_zone(&state, user.0.sub, payload.zone_id).await;
+
+    let report = state
+        .market_clearing
+        .route_market_order(
+            user.0.sub,
+            payload.side,
+            payload.energy_amount,
+            payload.market_id,
+            payload.max_slippage_bps,
+            zone_id,
+            payload.meter_id,
+            payload.session_token.as_deref(),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to route order: {}", e);
+            e.downcast::<ApiError>()
+                .unwrap_or_else(|e| ApiError::Internal(format!("Order routing failed: {}", e)))
+        })?;
+
+    Ok(Json(report))
+}
+
+/// List execution reports of routed orders
+/// GET /api/v1/trading/orders/route/reports
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/orders/route/reports",
+    tag = "trading",
+    params(ExecutionReportsQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Execution reports, newest first", body = Vec<ExecutionReport>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_execution_reports(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<ExecutionReportsQuery>,
+) -> Result<Json<Vec<ExecutionReport>>> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let reports = state
+        .market_clearing
+        .list_execution_reports(user.0.sub, limit)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to list execution reports: {}", e)))?;
+
+    Ok(Json(reports))
+}
+
+/// Get an execution report
+/// GET /api/v1/trading/orders/route/reports/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/orders/route/reports/{id}",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Execution report ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Orderbook and AMM split of a routed order", body = ExecutionReport),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Execution report not found")
+    )
+)]
+pub async fn get_execution_report(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(report_id): Path<Uuid>,
+) -> Result<Json<ExecutionReport>> {
+    let report = state
+        .market_clearing
+        .get_execution_report(user.0.sub, report_id)
+        .await
+        .map_err(|e| {
+            e.downcast::<ApiError>()
+                .unwrap_or_else(|e| ApiError::Internal(format!("Failed to load execution report: {}", e)))
+        })?;
+
+    Ok(Json(report))
+}
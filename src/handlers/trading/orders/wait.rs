@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::services::order_events::{self, OrderStatusChange};
+use crate::AppState;
+
+const DEFAULT_WAIT: Duration = Duration::from_secs(30);
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct WaitQuery {
+    /// How long to wait, e.g. `30s` or `30` (seconds, max 60, default 30)
+    pub timeout: Option<String>,
+}
+
+/// Result of waiting on an order
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrderWaitResponse {
+    pub order_id: Uuid,
+    /// Order status when the wait began
+    pub status: String,
+    /// Whether the order changed before the timeout
+    pub changed: bool,
+    pub change: Option<OrderStatusChange>,
+}
+
+/// Parse a wait timeout given in seconds, with or without an `s` suffix
+fn parse_wait_timeout(value: Option<&str>) -> Result<Duration> {
+    let Some(value) = value else {
+        return Ok(DEFAULT_WAIT);
+    };
+    let seconds = value
+        .trim()
+        .trim_end_matches('s')
+        .parse::<u64>()
+        .map_err(|_| ApiError::validation_field("timeout", "Use seconds, e.g. 30s"))?;
+
+    match Duration::from_secs(seconds) {
+        wait if wait.is_zero() || wait > MAX_WAIT => Err(ApiError::validation_field(
+            "timeout",
+            format!("Must be between 1s and {}s", MAX_WAIT.as_secs()),
+        )),
+        wait => Ok(wait),
+    }
+}
+
+/// Wait for an order to change
+/// GET /api/v1/trading/orders/{id}/wait
+///
+/// Long-polling fallback for clients that cannot hold a WebSocket: returns
+/// as soon as the order is filled, cancelled, expires or otherwise changes,
+/// or with `changed: false` once the timeout passes.
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/orders/{id}/wait",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Order ID"), WaitQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Order changed or the wait timed out", body = OrderWaitResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Order not found"),
+        (status = 422, description = "Invalid timeout")
+    )
+)]
+pub async fn wait_for_order(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(order_id): Path<Uuid>,
+    Query(params): Query<WaitQuery>,
+) -> Result<Json<OrderWaitResponse>> {
+    let timeout = parse_wait_timeout(params.timeout.as_deref())?;
+
+    // Subscribe first so a change between the status read and the wait is not lost
+    let mut watch = order_events::watch(order_id);
+
+    let status = sqlx::query_scalar::<_, String>(
+        "SELECT status::TEXT FROM trading_orders WHERE id = $1 AND user_id = $2",
+    )
+    .bind(order_id)
+    .bind(user.0.sub)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(ApiError::Database)?
+    .ok_or_else(|| ApiError::NotFound(format!("Order {} not found", order_id)))?;
+
+    let change = tokio::time::timeout(timeout, watch.changed())
+        .await
+        .ok()
+        .flatten();
+
+    Ok(Json(OrderWaitResponse {
+        order_id,
+        status,
+        changed: change.is_some(),
+        change,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wait_timeout() {
+        assert_eq!(parse_wait_timeout(None).unwrap(), DEFAULT_WAIT);
+        assert_eq!(parse_wait_timeout(Some("30s")).unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_wait_timeout(Some("5")).unwrap(), Duration::from_secs(5));
+        assert!(parse_wait_timeout(Some("0s")).is_err());
+        assert!(parse_wait_timeout(Some("61s")).is_err());
+        assert!(parse_wait_timeout(Some("1m")).is_err());
+    }
+}
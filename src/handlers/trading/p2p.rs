@@ -10,6 +10,7 @@ use std::collections::HashMap;
 use tracing::{error, instrument};
 
 use crate::error::{ApiError, ErrorCode, Result};
+use crate::handlers::common::ValidatedJson;
 use crate::AppState;
 
 use super::types::{P2PCalculateCostRequest, P2PMarketPrices, P2PTransactionCost};
@@ -53,7 +54,7 @@ fn get_simulator_url() -> String {
 #[instrument(skip(_state))]
 pub async fn calculate_p2p_cost(
     State(_state): State<AppState>,
-    Json(payload): Json<P2PCalculateCostRequest>,
+    ValidatedJson(payload): ValidatedJson<P2PCalculateCostRequest>,
 ) -> Result<Json<P2PTransactionCost>> {
     let client = Client::new();
     let simulator_url = get_simulator_url();
@@ -14,6 +14,7 @@ use validator::Validate;
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
 use crate::AppState;
 
 /// Alert condition type
@@ -60,6 +61,7 @@ pub struct PriceAlert {
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreatePriceAlertRequest {
     /// Target price that triggers the alert
+    #[validate(custom(function = "crate::utils::validation::rules::price_per_kwh"))]
     #[schema(value_type = String, example = "0.15")]
     pub target_price: Decimal,
     
@@ -70,6 +72,7 @@ pub struct CreatePriceAlertRequest {
     pub repeat: Option<bool>,
     
     /// User note for this alert
+    #[validate(length(max = 500))]
     pub note: Option<String>,
 }
 
@@ -97,21 +100,18 @@ pub struct PriceAlertResponse {
         (status = 200, description = "Price alert created", body = PriceAlertResponse),
         (status = 400, description = "Invalid parameters"),
         (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Request validation failed"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn create_price_alert(
     State(state): State<AppState>,
     user: AuthenticatedUser,
-    Json(payload): Json<CreatePriceAlertRequest>,
+    ValidatedJson(payload): ValidatedJson<CreatePriceAlertRequest>,
 ) -> Result<Json<PriceAlertResponse>> {
     info!("Creating price alert for user: {}, price: {}, condition: {:?}", 
           user.0.sub, payload.target_price, payload.condition);
 
-    if payload.target_price <= Decimal::ZERO {
-        return Err(ApiError::BadRequest("Target price must be positive".to_string()));
-    }
-
     let alert_id = Uuid::new_v4();
     let now = Utc::now();
 
@@ -4,12 +4,12 @@
 
 use axum::{extract::{State, Path}, response::Json};
 use chrono::{Utc, Duration};
-use rust_decimal::Decimal;
 use uuid::Uuid;
 use tracing::{info, error};
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
 use crate::models::trading::{
     CreateRecurringOrderRequest,
     RecurringOrderResponse, RecurringOrder,
@@ -41,20 +41,17 @@ fn calculate_next_execution(interval_type: IntervalType, interval_value: i32) ->
         (status = 200, description = "Recurring order created", body = RecurringOrderResponse),
         (status = 400, description = "Invalid parameters"),
         (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Request validation failed"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn create_recurring_order(
     State(state): State<AppState>,
     user: AuthenticatedUser,
-    Json(payload): Json<CreateRecurringOrderRequest>,
+    ValidatedJson(payload): ValidatedJson<CreateRecurringOrderRequest>,
 ) -> Result<Json<RecurringOrderResponse>> {
     info!("Creating recurring order for user: {}, interval: {:?}", user.0.sub, payload.interval_type);
 
-    if payload.energy_amount <= Decimal::ZERO {
-        return Err(ApiError::BadRequest("Energy amount must be positive".to_string()));
-    }
-
     let order_id = Uuid::new_v4();
     let now = Utc::now();
     let interval_value = payload.interval_value.unwrap_or(1);
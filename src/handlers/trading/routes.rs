@@ -4,7 +4,7 @@ use axum::{
 };
 
 use crate::app_state::AppState;
-use super::orders::{create_order, cancel_order, update_order, get_order_book, get_user_orders, get_my_trades, get_token_balance};
+use super::orders::{create_order, import_orders, export_orders, cancel_order, update_order, get_order_book, get_user_orders, get_my_trades, get_token_balance, get_order_events, get_order_dust, wait_for_order, route_order, list_execution_reports, get_execution_report};
 use super::blockchain::{get_blockchain_market_data, match_blockchain_orders};
 use super::conditional::{create_conditional_order, list_conditional_orders, cancel_conditional_order};
 use super::recurring::{create_recurring_order, list_recurring_orders, get_recurring_order, cancel_recurring_order, pause_recurring_order, resume_recurring_order};
@@ -13,13 +13,26 @@ use super::export::{export_csv, export_json};
 use super::p2p::{calculate_p2p_cost, get_p2p_market_prices};
 use super::status::{get_matching_status, get_settlement_stats};
 use super::revenue::{get_revenue_summary, get_revenue_records};
+use super::epochs::{get_epoch_curves, get_my_epoch_participation};
+use super::session::get_market_session;
+use super::disputes::{open_dispute, list_my_disputes, get_dispute, add_dispute_evidence, withdraw_dispute};
+use super::imbalance::list_my_imbalances;
+use super::autopilot::{get_autopilot, update_autopilot, list_autopilot_orders, get_autopilot_performance};
 
 /// Build the v1 trading routes
 pub fn v1_trading_routes() -> Router<AppState> {
     Router::new()
         // Orders
         .route("/orders", post(create_order).get(get_user_orders))
+        .route("/orders/import", post(import_orders))
+        .route("/orders/export", get(export_orders))
+        .route("/orders/dust", get(get_order_dust))
+        .route("/orders/route", post(route_order))
+        .route("/orders/route/reports", get(list_execution_reports))
+        .route("/orders/route/reports/{id}", get(get_execution_report))
         .route("/orders/{id}", delete(cancel_order).put(update_order))
+        .route("/orders/{id}/events", get(get_order_events))
+        .route("/orders/{id}/wait", get(wait_for_order))
         
         // Conditional Orders (Stop-Loss/Take-Profit)
         .route("/conditional", post(create_conditional_order).get(list_conditional_orders))
@@ -31,6 +44,11 @@ pub fn v1_trading_routes() -> Router<AppState> {
         .route("/recurring/{id}/pause", post(pause_recurring_order))
         .route("/recurring/{id}/resume", post(resume_recurring_order))
         
+        // Autopilot (forecast surplus sell orders)
+        .route("/autopilot", get(get_autopilot).put(update_autopilot))
+        .route("/autopilot/orders", get(list_autopilot_orders))
+        .route("/autopilot/performance", get(get_autopilot_performance))
+        
         // Price Alerts
         .route("/price-alerts", post(create_price_alert).get(list_price_alerts))
         .route("/price-alerts/{id}", delete(delete_price_alert))
@@ -50,11 +68,23 @@ pub fn v1_trading_routes() -> Router<AppState> {
         
         // Market Data
         .route("/market/blockchain", get(get_blockchain_market_data))
+        .route("/market/epochs/{id}/curves", get(get_epoch_curves))
+        .route("/market/epochs/{id}/my-participation", get(get_my_epoch_participation))
+        .route("/market/session", get(get_market_session))
         
         // P2P Transaction Cost & Pricing
         .route("/p2p/calculate-cost", post(calculate_p2p_cost))
         .route("/p2p/market-prices", get(get_p2p_market_prices))
         
+        // Settlement Disputes
+        .route("/disputes", post(open_dispute).get(list_my_disputes))
+        .route("/disputes/{id}", get(get_dispute))
+        .route("/disputes/{id}/evidence", post(add_dispute_evidence))
+        .route("/disputes/{id}/withdraw", post(withdraw_dispute))
+        
+        // Imbalance Settlements
+        .route("/imbalances", get(list_my_imbalances))
+        
         // Status & Monitoring
         .route("/matching-status", get(get_matching_status))
         .route("/settlement-stats", get(get_settlement_stats))
@@ -0,0 +1,40 @@
+//! Market Session Endpoint
+//!
+//! Trading calendar state: pre-open, open or closed, and the trading halts in force
+
+use axum::{extract::State, Json};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::error::Result;
+use crate::services::market_session::MarketSession;
+use crate::services::trading_halts::TradingHalt;
+use crate::AppState;
+
+/// Calendar session together with the halts that override it
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MarketSessionStatus {
+    #[serde(flatten)]
+    pub session: MarketSession,
+    /// Halts of the platform, a market or a zone currently in force
+    pub halts: Vec<TradingHalt>,
+}
+
+/// Get the current market session
+/// GET /api/v1/trading/market/session
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/market/session",
+    tag = "trading",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current session state, next transition and trading halts", body = MarketSessionStatus),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_market_session(State(state): State<AppState>) -> Result<Json<MarketSessionStatus>> {
+    Ok(Json(MarketSessionStatus {
+        session: state.market_session.current(),
+        halts: state.trading_halts.active().await?,
+    }))
+}
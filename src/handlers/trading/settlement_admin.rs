@@ -0,0 +1,154 @@
+//! Admin Settlement Operations Handler
+//!
+//! Pending batch changes, settlements quarantined by batch failure
+//! isolation, and their release once the cause is fixed
+
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::services::settlement::{BatchChange, QuarantinedSettlement, RequeuePriority};
+use crate::services::AuditEvent;
+use crate::AppState;
+
+/// New priority of a settlement ejected from its batch
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct EjectSettlementRequest {
+    #[serde(default)]
+    pub priority: RequeuePriority,
+}
+
+/// Result of releasing a quarantined settlement
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReleaseSettlementResponse {
+    pub settlement_id: Uuid,
+    pub message: String,
+}
+
+/// List settlements quarantined after failing on their own (admin)
+/// GET /api/v1/admin/settlements/quarantined
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/settlements/quarantined",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Quarantined settlements with their diagnosis", body = Vec<QuarantinedSettlement>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_quarantined_settlements(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<QuarantinedSettlement>>> {
+    Ok(Json(state.settlement.list_quarantined_settlements().await?))
+}
+
+/// Release a quarantined settlement into the next batch (admin)
+/// POST /api/v1/admin/settlements/{id}/release
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/settlements/{id}/release",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Settlement ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Settlement resubmitted", body = ReleaseSettlementResponse),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "No quarantined settlement with this ID")
+    )
+)]
+pub async fn release_quarantined_settlement(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(settlement_id): Path<Uuid>,
+) -> Result<Json<ReleaseSettlementResponse>> {
+    state.settlement.release_quarantined_settlement(settlement_id).await?;
+
+    state.audit_logger.log_async(AuditEvent::AdminAction {
+        admin_id: user.0.sub,
+        action: "settlement_release_quarantine".to_string(),
+        target_user_id: None,
+        details: format!("settlement={}", settlement_id),
+    });
+
+    Ok(Json(ReleaseSettlementResponse {
+        settlement_id,
+        message: "Settlement released and queued for the next batch".to_string(),
+    }))
+}
+
+/// Cancel an epoch's pending batch, returning its settlements to the pool (admin)
+/// POST /api/v1/admin/batches/{id}/cancel
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/batches/{id}/cancel",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Epoch ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Batch cancelled", body = BatchChange),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "No pending batch for this epoch")
+    )
+)]
+pub async fn cancel_batch(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(epoch_id): Path<Uuid>,
+) -> Result<Json<BatchChange>> {
+    let change = state.settlement.cancel_batch(epoch_id).await?;
+
+    state.audit_logger.log_async(AuditEvent::AdminAction {
+        admin_id: user.0.sub,
+        action: "settlement_batch_cancel".to_string(),
+        target_user_id: None,
+        details: format!("epoch={} settlements={}", epoch_id, change.settlement_ids.len()),
+    });
+
+    Ok(Json(change))
+}
+
+/// Eject a settlement from an epoch's pending batch with a new priority (admin)
+/// POST /api/v1/admin/batches/{id}/settlements/{settlement_id}/eject
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/batches/{id}/settlements/{settlement_id}/eject",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Epoch ID"),
+        ("settlement_id" = Uuid, Path, description = "Settlement ID")
+    ),
+    request_body = EjectSettlementRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Settlement ejected", body = BatchChange),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Settlement is not in the pending batch")
+    )
+)]
+pub async fn eject_batch_settlement(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path((epoch_id, settlement_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<EjectSettlementRequest>,
+) -> Result<Json<BatchChange>> {
+    let change = state
+        .settlement
+        .eject_from_batch(epoch_id, settlement_id, request.priority)
+        .await?;
+
+    state.audit_logger.log_async(AuditEvent::AdminAction {
+        admin_id: user.0.sub,
+        action: "settlement_batch_eject".to_string(),
+        target_user_id: None,
+        details: format!("epoch={} settlement={} priority={:?}", epoch_id, settlement_id, request.priority),
+    });
+
+    Ok(Json(change))
+}
@@ -0,0 +1,266 @@
+//! Admin Trade Operations Handler
+//!
+//! Trade busts, manual trade entry and epoch rollbacks, all executed only
+//! after multi-signature admin approval
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
+use crate::services::trade_admin::{
+    ApprovalDecision, ApprovalStatus, TradeAction, TradeApprovalDetail, TradeApprovalRequest,
+};
+use crate::AppState;
+
+/// Propose busting a settlement
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ProposeTradeBustRequest {
+    pub settlement_id: Uuid,
+    /// Why the trade is erroneous
+    #[validate(length(max = 2000), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub reason: String,
+}
+
+/// Propose an off-market trade
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ProposeManualTradeRequest {
+    pub buyer_id: Uuid,
+    pub seller_id: Uuid,
+    #[schema(value_type = String)]
+    pub energy_amount: Decimal,
+    #[schema(value_type = String)]
+    pub price_per_kwh: Decimal,
+    /// Where the trade was agreed and why it is entered manually
+    #[validate(length(max = 2000), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub reason: String,
+}
+
+/// Propose rolling back a cleared epoch
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ProposeEpochRollbackRequest {
+    pub epoch_id: Uuid,
+    /// What went wrong with the clearing
+    #[validate(length(max = 2000), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub reason: String,
+}
+
+/// Signature on a pending request
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ApprovalDecisionRequest {
+    pub decision: ApprovalDecision,
+    #[validate(length(max = 2000))]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TradeApprovalListQuery {
+    /// Filter by status: pending, executed, rejected, expired, failed
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Propose a trade bust
+/// POST /api/v1/admin/trades/bust
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/trades/bust",
+    tag = "trading",
+    request_body = ProposeTradeBustRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Bust proposed (executed immediately when one approval suffices)", body = TradeApprovalRequest),
+        (status = 422, description = "Request validation failed"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Settlement not found"),
+        (status = 409, description = "Settlement cannot be busted or a bust is already pending")
+    )
+)]
+pub async fn propose_trade_bust(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<ProposeTradeBustRequest>,
+) -> Result<Json<TradeApprovalRequest>> {
+    let request = state
+        .trade_admin_service
+        .propose(
+            user.0.sub,
+            TradeAction::TradeBust {
+                settlement_id: payload.settlement_id,
+            },
+            &payload.reason,
+        )
+        .await?;
+
+    Ok(Json(request))
+}
+
+/// Propose a manual trade
+/// POST /api/v1/admin/trades/manual
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/trades/manual",
+    tag = "trading",
+    request_body = ProposeManualTradeRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Manual trade proposed (executed immediately when one approval suffices)", body = TradeApprovalRequest),
+        (status = 422, description = "Request validation failed"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Buyer or seller not found")
+    )
+)]
+pub async fn propose_manual_trade(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<ProposeManualTradeRequest>,
+) -> Result<Json<TradeApprovalRequest>> {
+    let request = state
+        .trade_admin_service
+        .propose(
+            user.0.sub,
+            TradeAction::ManualTrade {
+                buyer_id: payload.buyer_id,
+                seller_id: payload.seller_id,
+                energy_amount: payload.energy_amount,
+                price_per_kwh: payload.price_per_kwh,
+            },
+            &payload.reason,
+        )
+        .await?;
+
+    Ok(Json(request))
+}
+
+/// Propose an epoch rollback
+/// POST /api/v1/admin/trades/epoch-rollback
+///
+/// Once approved, the epoch's pending settlements are cancelled and its
+/// orders reopen in the next epoch with the fill state they had before
+/// clearing. The detail of the request records every step taken.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/trades/epoch-rollback",
+    tag = "trading",
+    request_body = ProposeEpochRollbackRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Rollback proposed (executed immediately when one approval suffices)", body = TradeApprovalRequest),
+        (status = 422, description = "Request validation failed"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Epoch not found"),
+        (status = 409, description = "Epoch not cleared, already rolled back, has submitted settlements, or a rollback is already pending")
+    )
+)]
+pub async fn propose_epoch_rollback(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<ProposeEpochRollbackRequest>,
+) -> Result<Json<TradeApprovalRequest>> {
+    let request = state
+        .trade_admin_service
+        .propose(
+            user.0.sub,
+            TradeAction::EpochRollback {
+                epoch_id: payload.epoch_id,
+            },
+            &payload.reason,
+        )
+        .await?;
+
+    Ok(Json(request))
+}
+
+/// List trade approval requests
+/// GET /api/v1/admin/trade-approvals
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/trade-approvals",
+    tag = "trading",
+    params(TradeApprovalListQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Requests, newest first", body = Vec<TradeApprovalRequest>),
+        (status = 400, description = "Invalid status filter"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_trade_approvals(
+    State(state): State<AppState>,
+    Query(params): Query<TradeApprovalListQuery>,
+) -> Result<Json<Vec<TradeApprovalRequest>>> {
+    let status = match params.status.as_deref() {
+        None => None,
+        Some(value) => Some(ApprovalStatus::parse(value).ok_or_else(|| {
+            ApiError::validation_field(
+                "status",
+                "Invalid status. Use: pending, executed, rejected, expired, failed",
+            )
+        })?),
+    };
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    Ok(Json(state.trade_admin_service.list(status, limit, offset).await?))
+}
+
+/// Get a request with its signatures and ledger entries
+/// GET /api/v1/admin/trade-approvals/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/trade-approvals/{id}",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Approval request ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Request detail", body = TradeApprovalDetail),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Approval request not found")
+    )
+)]
+pub async fn get_trade_approval(
+    State(state): State<AppState>,
+    Path(request_id): Path<Uuid>,
+) -> Result<Json<TradeApprovalDetail>> {
+    Ok(Json(state.trade_admin_service.detail(request_id).await?))
+}
+
+/// Approve or veto a pending request
+/// POST /api/v1/admin/trade-approvals/{id}/decision
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/trade-approvals/{id}/decision",
+    tag = "trading",
+    params(("id" = Uuid, Path, description = "Approval request ID")),
+    request_body = ApprovalDecisionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Signature recorded; the request is executed once quorum is reached", body = TradeApprovalRequest),
+        (status = 422, description = "Request validation failed"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Approval request not found"),
+        (status = 409, description = "Request is closed, expired or already signed by this admin")
+    )
+)]
+pub async fn decide_trade_approval(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(request_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ApprovalDecisionRequest>,
+) -> Result<Json<TradeApprovalRequest>> {
+    let request = state
+        .trade_admin_service
+        .decide(user.0.sub, request_id, payload.decision, payload.comment.as_deref())
+        .await?;
+
+    Ok(Json(request))
+}
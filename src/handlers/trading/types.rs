@@ -168,6 +168,8 @@ pub struct MarketStats {
     pub active_orders: i64,
     pub pending_orders: i64,
     pub completed_matches: i64,
+    /// Trading halts currently in force
+    pub halts: Vec<crate::services::trading_halts::TradingHalt>,
 }
 #[derive(Debug, Serialize, ToSchema)]
 pub struct OrderBookEntry {
@@ -191,16 +193,19 @@ pub struct OrderBookResponse {
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct P2PCalculateCostRequest {
     /// Buyer's zone ID
+    #[validate(range(min = 0))]
     pub buyer_zone_id: i32,
 
     /// Seller's zone ID
+    #[validate(range(min = 0))]
     pub seller_zone_id: i32,
 
     /// Amount of energy to trade in kWh
-    #[validate(range(min = 0.001, message = "Energy amount must be positive"))]
+    #[validate(range(min = 0.001, max = 10_000_000.0, message = "Energy amount must be between 0.001 and 10000000 kWh"))]
     pub energy_amount: f64,
 
     /// Negotiated price per kWh in THB (optional, defaults to market base price)
+    #[validate(range(exclusive_min = 0.0, max = 1_000_000.0))]
     pub agreed_price: Option<f64>,
 }
 
@@ -0,0 +1,324 @@
+//! Token Transfers Handler
+//!
+//! User-initiated energy token transfers, with fees sponsored by the
+//! platform when the sponsorship policy covers them, and admin management
+//! of corporate sponsorship programs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::fee_sponsorship::{FeeSponsorship, ProgramChanges, SponsorshipProgram, SponsorshipQuote};
+use crate::services::token_transfer::TransferReceipt;
+use crate::services::wallet::DeviceBinding;
+use crate::AppState;
+
+const MAX_LIMIT: i64 = 500;
+
+/// Energy token transfer
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TransferRequest {
+    /// Recipient wallet address
+    #[validate(custom(function = "crate::utils::validation::rules::wallet_address"))]
+    pub recipient: String,
+    #[validate(custom(function = "crate::utils::validation::rules::positive_amount"))]
+    #[schema(value_type = String, example = "2.5")]
+    pub amount_kwh: Decimal,
+    /// Wallet session to sign with, bound to the requesting device
+    pub session_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SponsorshipQuoteQuery {
+    /// Size of the planned transfer
+    #[param(value_type = String)]
+    pub amount_kwh: Decimal,
+}
+
+/// New sponsorship program
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateSponsorshipProgramRequest {
+    #[validate(length(max = 100), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub name: String,
+    /// Organisation paying for the program
+    #[validate(length(max = 100), custom(function = "crate::utils::validation::rules::not_blank"))]
+    pub sponsor: String,
+    #[validate(range(min = 1))]
+    pub budget_lamports: i64,
+    /// Cap per member per calendar month
+    #[validate(range(min = 1))]
+    pub member_monthly_lamports: Option<i64>,
+    /// Default now
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Changes to a sponsorship program; omitted fields are kept
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateSponsorshipProgramRequest {
+    #[validate(range(min = 1))]
+    pub budget_lamports: Option<i64>,
+    #[validate(range(min = 1))]
+    pub member_monthly_lamports: Option<i64>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AddSponsorshipMemberRequest {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SponsorshipLedgerQuery {
+    pub user_id: Option<Uuid>,
+    pub program_id: Option<Uuid>,
+    /// Default 100, at most 500
+    pub limit: Option<i64>,
+}
+
+/// Check whether a transfer would be sponsored
+/// GET /api/v1/transfers/sponsorship
+#[utoipa::path(
+    get,
+    path = "/api/v1/transfers/sponsorship",
+    tag = "wallets",
+    params(SponsorshipQuoteQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Sponsorship decision and remaining allowance", body = SponsorshipQuote),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_sponsorship_quote(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<SponsorshipQuoteQuery>,
+) -> Result<Json<SponsorshipQuote>> {
+    Ok(Json(state.fee_sponsorship.quote(user.0.sub, params.amount_kwh).await?))
+}
+
+/// Transfer energy tokens
+/// POST /api/v1/transfers
+///
+/// The platform pays the network fee when the sponsorship policy covers the
+/// transfer; otherwise it is paid from the user's wallet.
+#[utoipa::path(
+    post,
+    path = "/api/v1/transfers",
+    tag = "wallets",
+    request_body = TransferRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Transfer sent", body = TransferReceipt),
+        (status = 400, description = "No wallet connected, or the transfer failed"),
+        (status = 401, description = "Unauthorized, or wallet session expired or bound to another device"),
+        (status = 422, description = "Invalid recipient or amount")
+    )
+)]
+pub async fn create_transfer(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    headers: HeaderMap,
+    ValidatedJson(payload): ValidatedJson<TransferRequest>,
+) -> Result<Json<TransferReceipt>> {
+    if let Some(token) = payload.session_token.as_deref() {
+        state
+            .wallet_sessions
+            .touch(user.0.sub, token, DeviceBinding::from_headers(&headers).as_ref())
+            .await?;
+    }
+    Ok(Json(
+        state
+            .token_transfers
+            .transfer(
+                user.0.sub,
+                &payload.recipient,
+                payload.amount_kwh,
+                payload.session_token.as_deref(),
+            )
+            .await?,
+    ))
+}
+
+/// List sponsorship programs
+/// GET /api/v1/admin/fee-sponsorship/programs
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/fee-sponsorship/programs",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Programs with members and spend, newest first", body = Vec<SponsorshipProgram>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_sponsorship_programs(State(state): State<AppState>) -> Result<Json<Vec<SponsorshipProgram>>> {
+    Ok(Json(state.fee_sponsorship.list_programs().await?))
+}
+
+/// Create a sponsorship program
+/// POST /api/v1/admin/fee-sponsorship/programs
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/fee-sponsorship/programs",
+    tag = "admin",
+    request_body = CreateSponsorshipProgramRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Program created", body = SponsorshipProgram),
+        (status = 403, description = "Admin access required"),
+        (status = 422, description = "Invalid budget or dates")
+    )
+)]
+pub async fn create_sponsorship_program(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<CreateSponsorshipProgramRequest>,
+) -> Result<Json<SponsorshipProgram>> {
+    Ok(Json(
+        state
+            .fee_sponsorship
+            .create_program(
+                user.0.sub,
+                &payload.name,
+                &payload.sponsor,
+                payload.budget_lamports,
+                payload.member_monthly_lamports,
+                payload.starts_at,
+                payload.ends_at,
+            )
+            .await?,
+    ))
+}
+
+/// Update a sponsorship program
+/// PUT /api/v1/admin/fee-sponsorship/programs/{id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/fee-sponsorship/programs/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Program ID")),
+    request_body = UpdateSponsorshipProgramRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Program updated", body = SponsorshipProgram),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Program not found")
+    )
+)]
+pub async fn update_sponsorship_program(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(program_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateSponsorshipProgramRequest>,
+) -> Result<Json<SponsorshipProgram>> {
+    let changes = ProgramChanges {
+        budget_lamports: payload.budget_lamports,
+        member_monthly_lamports: payload.member_monthly_lamports,
+        ends_at: payload.ends_at,
+        active: payload.active,
+    };
+    Ok(Json(
+        state
+            .fee_sponsorship
+            .update_program(user.0.sub, program_id, &changes)
+            .await?,
+    ))
+}
+
+/// Add a user to a sponsorship program
+/// POST /api/v1/admin/fee-sponsorship/programs/{id}/members
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/fee-sponsorship/programs/{id}/members",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Program ID")),
+    request_body = AddSponsorshipMemberRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Member added", body = SponsorshipProgram),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Program or user not found")
+    )
+)]
+pub async fn add_sponsorship_member(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(program_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<AddSponsorshipMemberRequest>,
+) -> Result<Json<SponsorshipProgram>> {
+    Ok(Json(
+        state
+            .fee_sponsorship
+            .add_member(user.0.sub, program_id, payload.user_id)
+            .await?,
+    ))
+}
+
+/// Remove a user from a sponsorship program
+/// DELETE /api/v1/admin/fee-sponsorship/programs/{id}/members/{user_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/fee-sponsorship/programs/{id}/members/{user_id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Program ID"),
+        ("user_id" = Uuid, Path, description = "Member user ID")
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Member removed", body = SponsorshipProgram),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Not a member of the program")
+    )
+)]
+pub async fn remove_sponsorship_member(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path((program_id, member_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SponsorshipProgram>> {
+    Ok(Json(
+        state
+            .fee_sponsorship
+            .remove_member(user.0.sub, program_id, member_id)
+            .await?,
+    ))
+}
+
+/// List sponsored transfers
+/// GET /api/v1/admin/fee-sponsorship/ledger
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/fee-sponsorship/ledger",
+    tag = "admin",
+    params(SponsorshipLedgerQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Sponsored transfers with reserved and charged fees, newest first", body = Vec<FeeSponsorship>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn list_fee_sponsorships(
+    State(state): State<AppState>,
+    Query(params): Query<SponsorshipLedgerQuery>,
+) -> Result<Json<Vec<FeeSponsorship>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, MAX_LIMIT);
+    Ok(Json(
+        state
+            .fee_sponsorship
+            .list_sponsorships(params.user_id, params.program_id, limit)
+            .await?,
+    ))
+}
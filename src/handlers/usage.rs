@@ -0,0 +1,176 @@
+//! API Usage Handler
+//!
+//! Users see which endpoints their credentials call; admins get the same
+//! report for any user, plus flagged access anomalies and the wallet
+//! signature challenge audit
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::services::api_usage::{SecurityAnomaly, UsageReport};
+use crate::services::wallet_challenges::{WalletAuthBlock, WalletChallengeReport};
+use crate::services::AuditEvent;
+use crate::AppState;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UsageQuery {
+    /// Days to cover, including today (1-90, default 30)
+    pub days: Option<i64>,
+}
+
+impl UsageQuery {
+    fn days(&self) -> Result<i64> {
+        match self.days {
+            None => Ok(30),
+            Some(days) if (1..=90).contains(&days) => Ok(days),
+            Some(_) => Err(ApiError::validation_field("days", "days must be between 1 and 90")),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AnomalyListQuery {
+    pub user_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct WalletChallengeReportQuery {
+    /// Hours to cover (1-720, default 24)
+    pub hours: Option<i64>,
+}
+
+/// Get your API usage
+/// GET /api/v1/account/usage
+#[utoipa::path(
+    get,
+    path = "/api/v1/account/usage",
+    tag = "users",
+    params(UsageQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Requests per endpoint and day, with error rates", body = UsageReport),
+        (status = 400, description = "Invalid period"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_my_usage(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<UsageQuery>,
+) -> Result<Json<UsageReport>> {
+    let days = params.days()?;
+    Ok(Json(state.api_usage.report(user.0.sub, days).await?))
+}
+
+/// Get a user's API usage
+/// GET /api/v1/admin/users/{id}/usage
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users/{id}/usage",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User ID"), UsageQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Usage report of the user", body = UsageReport),
+        (status = 400, description = "Invalid period"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn admin_get_user_usage(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(user_id): Path<Uuid>,
+    Query(params): Query<UsageQuery>,
+) -> Result<Json<UsageReport>> {
+    let days = params.days()?;
+    let report = state.api_usage.report(user_id, days).await?;
+
+    state.audit_logger.log_async(AuditEvent::DataAccess {
+        user_id: user.0.sub,
+        resource_type: "api_usage".to_string(),
+        resource_id: user_id.to_string(),
+        action: "view".to_string(),
+    });
+
+    Ok(Json(report))
+}
+
+/// List flagged access anomalies
+/// GET /api/v1/admin/security/anomalies
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/security/anomalies",
+    tag = "users",
+    params(AnomalyListQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Anomalies, newest first", body = Vec<SecurityAnomaly>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn admin_list_anomalies(
+    State(state): State<AppState>,
+    Query(params): Query<AnomalyListQuery>,
+) -> Result<Json<Vec<SecurityAnomaly>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, 500);
+    Ok(Json(state.api_usage.anomalies(params.user_id, limit).await?))
+}
+
+/// Wallet signature challenge report
+/// GET /api/v1/admin/security/wallet-challenges
+///
+/// Challenges issued, consumed, superseded and left to expire, failed
+/// signature attempts by reason and wallet, and wallets currently blocked.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/security/wallet-challenges",
+    tag = "users",
+    params(WalletChallengeReportQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Challenge and failure activity", body = WalletChallengeReport),
+        (status = 400, description = "Invalid period"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn admin_wallet_challenge_report(
+    State(state): State<AppState>,
+    Query(params): Query<WalletChallengeReportQuery>,
+) -> Result<Json<WalletChallengeReport>> {
+    let hours = match params.hours {
+        None => 24,
+        Some(hours) if (1..=720).contains(&hours) => hours,
+        Some(_) => return Err(ApiError::validation_field("hours", "hours must be between 1 and 720")),
+    };
+    Ok(Json(state.wallet_challenges.report(hours).await?))
+}
+
+/// Release a wallet block
+/// POST /api/v1/admin/security/wallet-blocks/{id}/release
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/security/wallet-blocks/{id}/release",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "Block ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Block released", body = WalletAuthBlock),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "No active block with this ID")
+    )
+)]
+pub async fn admin_release_wallet_block(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(block_id): Path<Uuid>,
+) -> Result<Json<WalletAuthBlock>> {
+    Ok(Json(state.wallet_challenges.release(user.0.sub, block_id).await?))
+}
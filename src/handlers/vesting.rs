@@ -0,0 +1,123 @@
+//! Vesting Handler
+//!
+//! Users view their token vesting schedules; admins create grant programs
+
+use axum::{extract::State, Json};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::vesting::{NewVestingProgram, VestingProgram, VestingSummary};
+use crate::AppState;
+
+/// Create a grant program
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateVestingProgramRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    /// Energy tokens (kWh) granted to each participant
+    #[validate(custom(function = "crate::utils::validation::rules::positive_amount"))]
+    #[schema(value_type = String)]
+    pub amount_per_user: Decimal,
+    /// Nothing unlocks before the cliff (default 0)
+    #[serde(default)]
+    #[validate(range(min = 0, max = 3650))]
+    pub cliff_days: i32,
+    /// Total vesting period, counted from the vesting start
+    #[validate(range(min = 1, max = 3650))]
+    pub vesting_days: i32,
+    /// Defaults to now
+    pub vesting_start: Option<DateTime<Utc>>,
+    /// Enroll every user registered before this time (early adopters)
+    pub eligible_registered_before: Option<DateTime<Utc>>,
+    /// Enroll these users as well
+    #[serde(default)]
+    #[validate(length(max = 10000))]
+    pub user_ids: Vec<Uuid>,
+}
+
+/// Get your vesting schedules
+/// GET /api/v1/vesting
+#[utoipa::path(
+    get,
+    path = "/api/v1/vesting",
+    tag = "trading",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Granted, vested and locked energy tokens per grant", body = VestingSummary),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_vesting_summary(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<VestingSummary>> {
+    Ok(Json(state.vesting_service.summary(user.0.sub).await?))
+}
+
+/// List grant programs
+/// GET /api/v1/admin/vesting/programs
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/vesting/programs",
+    tag = "trading",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Programs, newest first", body = Vec<VestingProgram>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn admin_list_vesting_programs(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<VestingProgram>>> {
+    Ok(Json(state.vesting_service.list_programs().await?))
+}
+
+/// Create a grant program and enroll eligible users
+/// POST /api/v1/admin/vesting/programs
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/vesting/programs",
+    tag = "trading",
+    request_body = CreateVestingProgramRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Program created with its grants", body = VestingProgram),
+        (status = 400, description = "No eligible users"),
+        (status = 422, description = "Request validation failed"),
+        (status = 403, description = "Admin access required"),
+        (status = 409, description = "Program name already used")
+    )
+)]
+pub async fn admin_create_vesting_program(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<CreateVestingProgramRequest>,
+) -> Result<Json<VestingProgram>> {
+    let program = state
+        .vesting_service
+        .create_program(
+            user.0.sub,
+            NewVestingProgram {
+                name: payload.name,
+                description: payload.description,
+                amount_per_user: payload.amount_per_user,
+                cliff_days: payload.cliff_days,
+                vesting_days: payload.vesting_days,
+                vesting_start: payload.vesting_start,
+                eligible_registered_before: payload.eligible_registered_before,
+                user_ids: payload.user_ids,
+            },
+        )
+        .await?;
+
+    Ok(Json(program))
+}
@@ -0,0 +1,92 @@
+//! Wallet Link Handlers
+//!
+//! Challenges proving control of a wallet before it becomes the account
+//! wallet (`POST /api/v1/users/wallet`), and the history of linked wallets
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::Result;
+use crate::handlers::common::ValidatedJson;
+use crate::services::wallet_links::{WalletLink, WalletLinkChallenge};
+use crate::AppState;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct WalletLinkChallengeRequest {
+    /// Solana wallet address (base58 encoded) to link
+    #[validate(custom(function = "crate::utils::validation::rules::wallet_address"))]
+    pub wallet_address: String,
+}
+
+/// Request a wallet link challenge
+/// POST /api/v1/account/wallet/challenge
+///
+/// Sign `message` with the wallet, or send a transaction paid by the wallet
+/// with `memo` as an SPL memo, then submit the proof to `POST /api/v1/users/wallet`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/account/wallet/challenge",
+    tag = "wallets",
+    request_body = WalletLinkChallengeRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Challenge to answer with a signature or memo transaction", body = WalletLinkChallenge),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Wallet blocked after repeated failed proofs"),
+        (status = 409, description = "Wallet already linked"),
+        (status = 422, description = "Invalid wallet address")
+    )
+)]
+pub async fn wallet_link_challenge(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<WalletLinkChallengeRequest>,
+) -> Result<Json<WalletLinkChallenge>> {
+    Ok(Json(state.wallet_links.challenge(user.0.sub, &payload.wallet_address).await?))
+}
+
+/// Get your wallet link history
+/// GET /api/v1/account/wallet/history
+#[utoipa::path(
+    get,
+    path = "/api/v1/account/wallet/history",
+    tag = "wallets",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current wallet first, then previously linked wallets", body = Vec<WalletLink>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_my_wallet_history(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<Vec<WalletLink>>> {
+    Ok(Json(state.wallet_links.history(user.0.sub).await?))
+}
+
+/// Get a user's wallet link history
+/// GET /api/v1/admin/users/{id}/wallet-history
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users/{id}/wallet-history",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current wallet first, then previously linked wallets", body = Vec<WalletLink>),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn admin_get_wallet_history(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<Vec<WalletLink>>> {
+    Ok(Json(state.wallet_links.history(user_id).await?))
+}
@@ -16,6 +16,7 @@ use validator::Validate;
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::error::{ApiError, Result};
+use crate::handlers::common::ValidatedJson;
 use crate::AppState;
 
 /// Linked wallet record
@@ -34,7 +35,7 @@ pub struct UserWallet {
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct LinkWalletRequest {
     /// Solana wallet address (base58 encoded)
-    #[validate(length(min = 32, max = 64))]
+    #[validate(custom(function = "crate::utils::validation::rules::wallet_address"))]
     pub wallet_address: String,
     
     /// Optional label for the wallet
@@ -111,23 +112,19 @@ pub async fn list_wallets(
     security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Wallet linked", body = WalletResponse),
-        (status = 400, description = "Invalid wallet address or already linked"),
+        (status = 400, description = "Wallet already linked"),
         (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid wallet address or label"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn link_wallet(
     State(state): State<AppState>,
     user: AuthenticatedUser,
-    Json(payload): Json<LinkWalletRequest>,
+    ValidatedJson(payload): ValidatedJson<LinkWalletRequest>,
 ) -> Result<Json<WalletResponse>> {
     info!("Linking wallet {} for user {}", payload.wallet_address, user.0.sub);
 
-    // Validate wallet address format (basic check)
-    if payload.wallet_address.len() < 32 || payload.wallet_address.len() > 64 {
-        return Err(ApiError::BadRequest("Invalid wallet address format".to_string()));
-    }
-
     let wallet_id = Uuid::new_v4();
     let now = Utc::now();
     let set_primary = payload.is_primary.unwrap_or(false);
@@ -355,3 +352,58 @@ pub async fn set_primary_wallet(
     }
 }
 
+
+/// Active wallet sessions of the user
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WalletSessionsResponse {
+    pub sessions: Vec<crate::services::wallet::WalletSession>,
+}
+
+/// Result of revoking wallet sessions
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevokeWalletSessionsResponse {
+    pub revoked: u64,
+}
+
+/// List active wallet sessions
+/// GET /api/v1/user-wallets/sessions
+#[utoipa::path(
+    get,
+    path = "/api/v1/user-wallets/sessions",
+    tag = "wallets",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Active sessions, newest first", body = WalletSessionsResponse),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_wallet_sessions(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<WalletSessionsResponse>> {
+    Ok(Json(WalletSessionsResponse {
+        sessions: state.wallet_sessions.list(user.0.sub).await?,
+    }))
+}
+
+/// Revoke all wallet sessions
+/// POST /api/v1/user-wallets/sessions/revoke-all
+///
+/// Signs the wallet out on every device; orders placed afterwards need a new session.
+#[utoipa::path(
+    post,
+    path = "/api/v1/user-wallets/sessions/revoke-all",
+    tag = "wallets",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Sessions revoked", body = RevokeWalletSessionsResponse),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn revoke_all_wallet_sessions(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<RevokeWalletSessionsResponse>> {
+    let revoked = state.wallet_sessions.revoke_all(user.0.sub, "user_revoked_all").await?;
+    Ok(Json(RevokeWalletSessionsResponse { revoked }))
+}
@@ -2,6 +2,7 @@ use uuid::Uuid;
 
 use super::types::{OrderBookData, OrderBookEntry, WsMessage};
 use super::get_connection_manager;
+use crate::models::notification::Notification;
 use crate::AppState;
 
 /// Broadcast order book update to all subscribers
@@ -200,3 +201,39 @@ pub async fn broadcast_settlement_complete(
 
     Ok(())
 }
+
+/// Push a new inbox notification and the unread counter to its owner
+pub async fn broadcast_inbox_notification(
+    notification: Notification,
+    unread_count: i64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let user_id = notification.user_id;
+    let message = WsMessage::InboxNotification {
+        notification,
+        unread_count,
+        timestamp: chrono::Utc::now(),
+    };
+
+    let manager = get_connection_manager();
+    manager.send_to_user(user_id, message).await?;
+
+    tracing::debug!("Sent inbox notification to user {} ({} unread)", user_id, unread_count);
+
+    Ok(())
+}
+
+/// Push the user's unread notification counter
+pub async fn broadcast_unread_notifications(
+    user_id: Uuid,
+    unread_count: i64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let message = WsMessage::UnreadNotifications {
+        unread_count,
+        timestamp: chrono::Utc::now(),
+    };
+
+    let manager = get_connection_manager();
+    manager.send_to_user(user_id, message).await?;
+
+    Ok(())
+}
@@ -5,14 +5,30 @@ use axum::{
 };
 use futures::{SinkExt, StreamExt};
 use serde_json::{json, Value};
+use tokio::sync::{broadcast, mpsc};
 use tracing::{info, error};
 use uuid::Uuid;
 
 
-use super::types::WsParams;
+use super::order_entry::OrderEntrySession;
+use super::types::{ClientPing, WsMessage, WsParams};
 use super::get_connection_manager;
+use crate::auth::Role;
+use crate::services::server_clock;
 use crate::AppState;
 
+/// Authenticated user WebSocket
+///
+/// Pushes the user's order, match and settlement updates, and inbox
+/// notifications with the unread counter. Orders can also be
+/// placed and cancelled on the socket by sending
+/// `{"action": "place_order", "request_id": "...", "seq": 1, "order": {...}}`
+/// or `{"action": "cancel_order", "request_id": "...", "seq": 2, "order_id": "..."}`;
+/// each request is answered with an `OrderEntryAck`.
+///
+/// A `Heartbeat` with the server clock is sent every 15 seconds, and
+/// `{"action": "ping", "client_time": <unix ms>}` is answered with a `Pong`
+/// echoing `client_time` for latency and clock offset measurement.
 #[utoipa::path(
     get,
     path = "/ws",
@@ -110,7 +126,7 @@ async fn websocket_handler_internal(
 }
 
 /// Handle authenticated WebSocket connection
-async fn handle_authenticated_socket(socket: WebSocket, user_id: Uuid, _state: AppState) {
+async fn handle_authenticated_socket(socket: WebSocket, user_id: Uuid, state: AppState) {
     let (mut sender, mut receiver) = socket.split();
     
     // Register with connection manager
@@ -121,26 +137,65 @@ async fn handle_authenticated_socket(socket: WebSocket, user_id: Uuid, _state: A
 
     // Also register with the general WebSocket service for market broadcasts
     // The state.websocket_service handles general market events
+
+    // Order entry acks are written by the same task as broadcasts
+    let (ack_tx, mut ack_rx) = mpsc::unbounded_channel::<String>();
     
-    // Spawn task to forward broadcasts to this client
+    // Spawn task to forward broadcasts, acks and heartbeats to this client
     let forward_task = tokio::spawn(async move {
-        while let Ok(message) = broadcast_rx.recv().await {
-            // Serialize message to JSON
-            if let Ok(json) = serde_json::to_string(&message) {
-                if sender.send(Message::Text(json.into())).await.is_err() {
-                    break; // Connection closed
-                }
+        let mut heartbeat = tokio::time::interval(server_clock::HEARTBEAT_INTERVAL);
+        loop {
+            let json = tokio::select! {
+                _ = heartbeat.tick() => match serde_json::to_string(&WsMessage::heartbeat(server_clock::now())) {
+                    Ok(json) => json,
+                    Err(_) => continue,
+                },
+                ack = ack_rx.recv() => match ack {
+                    Some(json) => json,
+                    None => break,
+                },
+                message = broadcast_rx.recv() => match message {
+                    // Serialize message to JSON
+                    Ok(message) => match serde_json::to_string(&message) {
+                        Ok(json) => json,
+                        Err(_) => continue,
+                    },
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            if sender.send(Message::Text(json.into())).await.is_err() {
+                break; // Connection closed
             }
         }
     });
 
-    // Handle incoming messages from client
+    let mut order_entry = OrderEntrySession::new(user_id);
+
+    // Handle incoming messages from client; order entry requests are processed
+    // one at a time so acks go out in sequence order
     while let Some(msg) = receiver.next().await {
         match msg {
             Ok(Message::Text(text)) => {
-                // Handle client messages (ping, subscribe, etc.)
-                if text.contains("ping") {
-                    // Pong handled automatically by axum
+                if OrderEntrySession::is_order_entry(&text) {
+                    let ack = order_entry.handle(&state, &text).await;
+                    match serde_json::to_string(&ack) {
+                        Ok(json) => {
+                            if ack_tx.send(json).is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => error!("Failed to serialize order entry ack for user {}: {}", user_id, e),
+                    }
+                } else if let Some(ping) = ClientPing::parse(&text) {
+                    // Protocol-level pings are answered by axum; this one
+                    // carries the client's clock for latency measurement
+                    let pong = WsMessage::pong(ping.client_time, server_clock::now());
+                    if let Ok(json) = serde_json::to_string(&pong) {
+                        if ack_tx.send(json).is_err() {
+                            break;
+                        }
+                    }
                 }
             }
             Ok(Message::Close(_)) => {
@@ -168,10 +223,21 @@ async fn handle_authenticated_socket(socket: WebSocket, user_id: Uuid, _state: A
 /// - Order matches
 /// - Transaction updates
 /// - Market statistics
+///
+/// Subscription-only feeds (futures mark price, funding, liquidations and
+/// trades) are joined with `?channels=` or by sending
+/// `{"action": "subscribe", "channels": [...]}` on the socket.
+///
+/// Users and wallets appear as rotating public participant IDs unless the
+/// feed is opened with an admin `?token=`.
 #[utoipa::path(
     get,
     path = "/api/market/ws",
     tag = "websocket",
+    params(
+        ("channels" = Option<String>, Query, description = "Comma-separated channels to subscribe to, e.g. futures.mark_price.*,futures.liquidations"),
+        ("token" = Option<String>, Query, description = "Admin JWT to receive user IDs instead of public participant IDs")
+    ),
     responses(
         (status = 101, description = "WebSocket connection upgraded"),
         (status = 500, description = "Internal server error")
@@ -180,11 +246,28 @@ async fn handle_authenticated_socket(socket: WebSocket, user_id: Uuid, _state: A
 pub async fn market_websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
+    Query(params): Query<WsParams>,
 ) -> impl IntoResponse {
     info!("📡 New WebSocket connection request for market feed");
 
+    let channels: Vec<String> = params
+        .channels
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let reveal_identities = params
+        .token
+        .as_deref()
+        .and_then(|token| state.jwt_service.decode_token(token).ok())
+        .is_some_and(|claims| matches!(Role::from_str(&claims.role), Ok(Role::Admin)));
+
     ws.on_upgrade(move |socket| async move {
-        state.websocket_service.register_client(socket).await;
+        state
+            .websocket_service
+            .register_client(socket, channels, reveal_identities)
+            .await;
     })
 }
 
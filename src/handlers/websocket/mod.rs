@@ -4,10 +4,13 @@
 //! - Connection management
 //! - Real-time market updates
 //! - Authenticated user notifications
+//! - Order entry over the authenticated connection
 
 pub mod broadcaster;
 pub mod handlers;
 pub mod manager;
+pub mod order_entry;
+pub mod throttle;
 pub mod types;
 
 pub use broadcaster::*;
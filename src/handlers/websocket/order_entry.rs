@@ -0,0 +1,242 @@
+//! WebSocket Order Entry
+//!
+//! Lets authenticated `/ws` clients place and cancel orders over the socket.
+//! Each request carries a client `request_id` and a per-connection `seq`
+//! that must increase by one, starting at 1. Requests are processed one at a
+//! time in arrival order and every request gets exactly one ack, so acks
+//! arrive in `seq` order. Orders go through the same validation and
+//! placement path as `POST /api/v1/trading/orders`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{ApiError, ErrorDetail};
+use crate::handlers::trading::orders::create::submit_order;
+use crate::handlers::trading::orders::management::cancel_user_order;
+use crate::models::trading::CreateOrderRequest;
+use crate::AppState;
+
+/// Order entry request sent by the client
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum OrderEntryRequest {
+    /// `order` has the same shape as the REST create-order body
+    PlaceOrder {
+        request_id: String,
+        seq: u64,
+        order: Value,
+    },
+    CancelOrder {
+        request_id: String,
+        seq: u64,
+        order_id: Uuid,
+    },
+}
+
+impl OrderEntryRequest {
+    fn request_id(&self) -> &str {
+        match self {
+            OrderEntryRequest::PlaceOrder { request_id, .. } | OrderEntryRequest::CancelOrder { request_id, .. } => {
+                request_id
+            }
+        }
+    }
+
+    fn seq(&self) -> u64 {
+        match self {
+            OrderEntryRequest::PlaceOrder { seq, .. } | OrderEntryRequest::CancelOrder { seq, .. } => *seq,
+        }
+    }
+
+    fn action(&self) -> &'static str {
+        match self {
+            OrderEntryRequest::PlaceOrder { .. } => "place_order",
+            OrderEntryRequest::CancelOrder { .. } => "cancel_order",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AckStatus {
+    Accepted,
+    Rejected,
+}
+
+/// Synchronous reply to one order entry request
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrderEntryAck {
+    /// Always `OrderEntryAck`, alongside the `type` tag of pushed messages
+    #[serde(rename = "type")]
+    pub message_type: &'static str,
+    pub request_id: Option<String>,
+    pub seq: Option<u64>,
+    pub action: Option<&'static str>,
+    pub status: AckStatus,
+    pub order_id: Option<Uuid>,
+    pub order_status: Option<String>,
+    /// Same body as REST error responses
+    pub error: Option<ErrorDetail>,
+    /// Next sequence number the server will accept
+    pub expected_seq: u64,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Outcome of checking a request's sequence number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceCheck {
+    InOrder,
+    /// Already processed (`seq` below the expected one); not executed again
+    Duplicate,
+    /// One or more sequence numbers were skipped
+    Gap,
+}
+
+/// Check `seq` against the next expected sequence number
+pub fn check_sequence(expected: u64, seq: u64) -> SequenceCheck {
+    match seq.cmp(&expected) {
+        std::cmp::Ordering::Equal => SequenceCheck::InOrder,
+        std::cmp::Ordering::Less => SequenceCheck::Duplicate,
+        std::cmp::Ordering::Greater => SequenceCheck::Gap,
+    }
+}
+
+/// Order entry state of one connection
+pub struct OrderEntrySession {
+    user_id: Uuid,
+    expected_seq: u64,
+}
+
+impl OrderEntrySession {
+    pub fn new(user_id: Uuid) -> Self {
+        Self { user_id, expected_seq: 1 }
+    }
+
+    /// Whether a client text frame is an order entry request
+    pub fn is_order_entry(text: &str) -> bool {
+        serde_json::from_str::<Value>(text)
+            .ok()
+            .and_then(|v| v.get("action").and_then(Value::as_str).map(str::to_string))
+            .is_some_and(|action| action == "place_order" || action == "cancel_order")
+    }
+
+    /// Process one request and build its ack
+    pub async fn handle(&mut self, state: &AppState, text: &str) -> OrderEntryAck {
+        let request = match serde_json::from_str::<OrderEntryRequest>(text) {
+            Ok(request) => request,
+            Err(e) => {
+                let request_id = serde_json::from_str::<Value>(text)
+                    .ok()
+                    .and_then(|v| v.get("request_id").and_then(Value::as_str).map(str::to_string));
+                return self.ack(
+                    request_id,
+                    None,
+                    None,
+                    Err(ApiError::validation_field("body", format!("Invalid order entry request: {}", e))),
+                );
+            }
+        };
+
+        let request_id = Some(request.request_id().to_string());
+        let (seq, action) = (request.seq(), request.action());
+        match check_sequence(self.expected_seq, seq) {
+            SequenceCheck::InOrder => {}
+            SequenceCheck::Duplicate => {
+                return self.ack(
+                    request_id,
+                    Some(seq),
+                    Some(action),
+                    Err(ApiError::validation_field("seq", "Duplicate sequence number; request not processed")),
+                );
+            }
+            SequenceCheck::Gap => {
+                return self.ack(
+                    request_id,
+                    Some(seq),
+                    Some(action),
+                    Err(ApiError::validation_field(
+                        "seq",
+                        format!("Sequence gap; expected {}", self.expected_seq),
+                    )),
+                );
+            }
+        }
+        self.expected_seq += 1;
+
+        let outcome = match request {
+            OrderEntryRequest::PlaceOrder { order, .. } => self.place(state, order).await,
+            OrderEntryRequest::CancelOrder { order_id, .. } => cancel_user_order(state, self.user_id, order_id)
+                .await
+                .map(|order| (order.id, order.status.to_string())),
+        };
+        self.ack(request_id, Some(seq), Some(action), outcome)
+    }
+
+    async fn place(&self, state: &AppState, order: Value) -> crate::error::Result<(Uuid, String)> {
+        let payload: CreateOrderRequest = serde_json::from_value(order)
+            .map_err(|e| ApiError::validation_field("order", e.to_string()))?;
+        payload.validate()?;
+        let response = submit_order(state, self.user_id, payload).await?;
+        Ok((response.id, response.status.to_string()))
+    }
+
+    fn ack(
+        &self,
+        request_id: Option<String>,
+        seq: Option<u64>,
+        action: Option<&'static str>,
+        outcome: crate::error::Result<(Uuid, String)>,
+    ) -> OrderEntryAck {
+        let (status, order_id, order_status, error) = match outcome {
+            Ok((order_id, order_status)) => (AckStatus::Accepted, Some(order_id), Some(order_status), None),
+            Err(e) => (AckStatus::Rejected, None, None, Some(e.detail())),
+        };
+        OrderEntryAck {
+            message_type: "OrderEntryAck",
+            request_id,
+            seq,
+            action,
+            status,
+            order_id,
+            order_status,
+            error,
+            expected_seq: self.expected_seq,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_check() {
+        assert_eq!(check_sequence(1, 1), SequenceCheck::InOrder);
+        assert_eq!(check_sequence(5, 3), SequenceCheck::Duplicate);
+        assert_eq!(check_sequence(5, 7), SequenceCheck::Gap);
+    }
+
+    #[test]
+    fn test_detects_order_entry_frames() {
+        assert!(OrderEntrySession::is_order_entry(
+            r#"{"action":"cancel_order","request_id":"r1","seq":1,"order_id":"00000000-0000-0000-0000-000000000000"}"#
+        ));
+        assert!(!OrderEntrySession::is_order_entry(r#"{"action":"subscribe","channels":[]}"#));
+        assert!(!OrderEntrySession::is_order_entry("ping"));
+    }
+
+    #[test]
+    fn test_parses_place_order() {
+        let request: OrderEntryRequest = serde_json::from_str(
+            r#"{"action":"place_order","request_id":"r1","seq":2,"order":{"side":"buy"}}"#,
+        )
+        .unwrap();
+        assert_eq!(request.action(), "place_order");
+        assert_eq!(request.request_id(), "r1");
+        assert_eq!(request.seq(), 2);
+    }
+}
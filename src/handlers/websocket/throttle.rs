@@ -0,0 +1,52 @@
+//! Broadcast Throttle Handlers
+//!
+//! Coalescing windows of high-frequency WebSocket broadcasts.
+
+use axum::{extract::State, Json};
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::config::BroadcastThrottleConfig;
+use crate::error::Result;
+use crate::services::websocket::throttle::EffectiveBroadcastThrottle;
+use crate::AppState;
+
+/// Get the broadcast coalescing windows in effect
+/// GET /api/v1/admin/websocket/throttle
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/websocket/throttle",
+    tag = "websocket",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Windows in effect and the environment defaults", body = EffectiveBroadcastThrottle),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn get_broadcast_throttle(State(state): State<AppState>) -> Result<Json<EffectiveBroadcastThrottle>> {
+    Ok(Json(state.broadcast_throttle.effective().await?))
+}
+
+/// Replace the broadcast coalescing windows
+/// PUT /api/v1/admin/websocket/throttle
+///
+/// A window of zero sends every event. Other instances pick the change up
+/// within a minute.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/websocket/throttle",
+    tag = "websocket",
+    request_body = BroadcastThrottleConfig,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Windows stored and applied", body = EffectiveBroadcastThrottle),
+        (status = 400, description = "Window longer than 10 seconds"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+pub async fn update_broadcast_throttle(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(payload): Json<BroadcastThrottleConfig>,
+) -> Result<Json<EffectiveBroadcastThrottle>> {
+    Ok(Json(state.broadcast_throttle.update(user.0.sub, payload).await?))
+}
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::services::server_clock::ServerTime;
+
 /// WebSocket message types
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(tag = "type")]
@@ -46,8 +48,20 @@ pub enum WsMessage {
     Ping {
         timestamp: chrono::DateTime<chrono::Utc>,
     },
-    /// Pong response
+    /// Pong response; echoes the ping's `client_time` so the client can
+    /// measure latency and clock offset (see `services::server_clock`)
     Pong {
+        client_time: Option<i64>,
+        unix_ms: i64,
+        monotonic_ms: u64,
+        sequence: u64,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Server clock, sent every `server_clock::HEARTBEAT_INTERVAL`
+    Heartbeat {
+        unix_ms: i64,
+        monotonic_ms: u64,
+        sequence: u64,
         timestamp: chrono::DateTime<chrono::Utc>,
     },
     /// Transaction status update (for user's own transactions)
@@ -72,6 +86,18 @@ pub enum WsMessage {
         price_per_kwh: String,
         timestamp: chrono::DateTime<chrono::Utc>,
     },
+    /// Order lifecycle event (accepted, partially_filled, filled, cancelled, expired, rejected)
+    OrderEvent {
+        order_id: Uuid,
+        event_type: crate::services::order_events::OrderEventType,
+        fill_quantity: Option<String>,
+        /// Total filled after this event
+        cumulative_filled: Option<String>,
+        remaining: Option<String>,
+        price: Option<String>,
+        reason: Option<String>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
     /// Settlement completed notification
     SettlementComplete {
         settlement_id: Uuid,
@@ -82,6 +108,17 @@ pub enum WsMessage {
         transaction_signature: Option<String>,
         timestamp: chrono::DateTime<chrono::Utc>,
     },
+    /// New inbox notification, with the user's unread count after it
+    InboxNotification {
+        notification: crate::models::notification::Notification,
+        unread_count: i64,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Unread inbox counter changed (notifications read or suppressed)
+    UnreadNotifications {
+        unread_count: i64,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
 }
 
 /// Order book entry
@@ -109,3 +146,70 @@ pub struct OrderBookData {
     pub buys: Vec<OrderBookEntry>,
     pub sells: Vec<OrderBookEntry>,
 }
+
+impl WsMessage {
+    pub fn heartbeat(time: ServerTime) -> Self {
+        Self::Heartbeat {
+            unix_ms: time.unix_ms,
+            monotonic_ms: time.monotonic_ms,
+            sequence: time.sequence,
+            timestamp: time.server_time,
+        }
+    }
+
+    pub fn pong(client_time: Option<i64>, time: ServerTime) -> Self {
+        Self::Pong {
+            client_time,
+            unix_ms: time.unix_ms,
+            monotonic_ms: time.monotonic_ms,
+            sequence: time.sequence,
+            timestamp: time.server_time,
+        }
+    }
+}
+
+/// Latency probe sent by a client, e.g. `{"action": "ping", "client_time": 1700000000000}`
+#[derive(Debug, Deserialize)]
+pub struct ClientPing {
+    action: String,
+    /// Client clock in Unix milliseconds when the ping was sent
+    pub client_time: Option<i64>,
+}
+
+impl ClientPing {
+    /// The ping in `text`, if it is one; a bare `ping` counts too
+    pub fn parse(text: &str) -> Option<Self> {
+        if text.trim() == "ping" {
+            return Some(Self {
+                action: "ping".to_string(),
+                client_time: None,
+            });
+        }
+        serde_json::from_str::<Self>(text)
+            .ok()
+            .filter(|ping| ping.action == "ping")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_client_pings() {
+        assert_eq!(ClientPing::parse(r#"{"action":"ping","client_time":42}"#).unwrap().client_time, Some(42));
+        assert_eq!(ClientPing::parse("ping").unwrap().client_time, None);
+        assert!(ClientPing::parse(r#"{"action":"place_order","request_id":"r1"}"#).is_none());
+    }
+
+    #[test]
+    fn test_unread_counter_message() {
+        let message = WsMessage::UnreadNotifications {
+            unread_count: 3,
+            timestamp: chrono::Utc::now(),
+        };
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["type"], "UnreadNotifications");
+        assert_eq!(json["unread_count"], 3);
+    }
+}
@@ -0,0 +1,169 @@
+//! English message catalog
+//!
+//! English error messages live with `ErrorCode::message`; every other key
+//! here must have a Thai translation in `th.rs`.
+
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    // Order sides, as used inside sentences
+    ("side.buy", "buy"),
+    ("side.sell", "sell"),
+    // In-app notifications
+    ("notification.order_filled.title", "Order Filled"),
+    ("notification.order_filled.message", "Your order for {amount} kWh was filled at {price}/kWh"),
+    ("notification.conditional_triggered.title", "{trigger_type} Triggered"),
+    (
+        "notification.conditional_triggered.message",
+        "Your {trigger_type} order was triggered at price {price}",
+    ),
+    ("notification.recurring_executed.title", "Recurring Order Executed"),
+    ("notification.recurring_executed.message", "Execution #{number} completed for {amount} kWh"),
+    ("notification.trade_matched.title", "Order Matched"),
+    (
+        "notification.trade_matched.message",
+        "Your {side} order for {amount} kWh has been matched at {price} GRIDX/kWh",
+    ),
+    ("notification.settlement_complete.title", "Settlement Complete"),
+    (
+        "notification.settlement_complete.message",
+        "Your settlement of {amount} kWh ({value} GRIDX) has been completed",
+    ),
+    ("notification.rec_issued.title", "REC Certificate Issued"),
+    (
+        "notification.rec_issued.message",
+        "A REC certificate ({certificate_id}) for {amount} kWh has been issued",
+    ),
+    ("notification.budget_alert.monthly_spend.title", "Monthly Budget Exceeded"),
+    (
+        "notification.budget_alert.monthly_spend.message",
+        "You have spent {value} GRIDX this month, above your budget of {threshold} GRIDX",
+    ),
+    ("notification.budget_alert.daily_consumption.title", "High Consumption Today"),
+    (
+        "notification.budget_alert.daily_consumption.message",
+        "You have used {value} kWh today, above your limit of {threshold} kWh",
+    ),
+    ("notification.budget_alert.clearing_price.title", "Market Price Alert"),
+    (
+        "notification.budget_alert.clearing_price.message",
+        "The market cleared at {value} GRIDX/kWh, above your alert price of {threshold} GRIDX/kWh",
+    ),
+    ("notification.data_export_ready.title", "Your Data Export Is Ready"),
+    (
+        "notification.data_export_ready.message",
+        "Your {dataset} export ({rows} rows) is ready to download",
+    ),
+    // Trading event emails
+    ("email.trade_matched.subject", "🤝 Your Order Has Been Matched"),
+    ("email.trade_matched.intro", "Great news! Your {side} order has been matched."),
+    ("email.trade_matched.outro", "Settlement will proceed automatically."),
+    ("email.settlement_complete.subject", "✅ Settlement Complete"),
+    (
+        "email.settlement_complete.intro",
+        "Your energy trade settlement has been completed successfully.",
+    ),
+    ("email.rec_issued.subject", "🏆 REC Issued"),
+    (
+        "email.rec_issued.intro",
+        "Congratulations! A Renewable Energy Certificate has been issued for your energy sale.",
+    ),
+    ("email.rec_issued.outro", "You can view your certificates in your dashboard."),
+    ("email.label.energy_amount", "Energy Amount"),
+    ("email.label.price", "Price"),
+    ("email.label.total_value", "Total Value"),
+    ("email.label.transaction", "Transaction"),
+    ("email.label.certificate_id", "Certificate ID"),
+    ("email.label.source", "Source"),
+    // Shared email text
+    ("email.footer.automated", "This is an automated message from GridTokenX Platform."),
+    ("email.footer.no_reply", "This is an automated email. Please do not reply to this message."),
+    ("email.footer.rights", "All rights reserved."),
+    ("email.link_fallback", "If the button doesn't work, copy and paste this link into your browser:"),
+    // Account emails
+    ("email.verification.subject", "Verify Your Email - GridTokenX Platform"),
+    ("email.verification.heading", "Welcome to GridTokenX Platform!"),
+    ("email.verification.greeting", "Welcome, {username}!"),
+    (
+        "email.verification.intro",
+        "Thank you for registering with GridTokenX Platform. We're excited to have you join our peer-to-peer energy trading network!",
+    ),
+    (
+        "email.verification.action",
+        "To complete your registration and start trading energy tokens, please verify your email address:",
+    ),
+    ("email.verification.button", "Verify Email Address"),
+    (
+        "email.verification.expiry",
+        "This verification link will expire in 24 hours for security purposes.",
+    ),
+    (
+        "email.verification.ignore",
+        "If you didn't create an account with GridTokenX, you can safely ignore this email.",
+    ),
+    ("email.welcome.subject", "Welcome to GridTokenX! 🎉"),
+    ("email.welcome.verified", "Your email has been verified"),
+    ("email.welcome.greeting", "Hello, {username}!"),
+    (
+        "email.welcome.intro",
+        "Congratulations! Your email has been successfully verified. You now have full access to all features of the GridTokenX Platform.",
+    ),
+    ("email.welcome.next", "What's Next?"),
+    (
+        "email.welcome.next.wallet",
+        "Connect Your Wallet: Link your Solana wallet for blockchain transactions",
+    ),
+    (
+        "email.welcome.next.dashboard",
+        "View Dashboard: Monitor your energy consumption and production in real-time",
+    ),
+    (
+        "email.welcome.next.trading",
+        "Start Trading: Buy and sell energy tokens with other users on the platform",
+    ),
+    ("email.welcome.next.prices", "Track Prices: View live energy market prices and trends"),
+    ("email.welcome.next.meters", "Manage Meters: Connect and manage your smart meters"),
+    ("email.welcome.button", "Go to Dashboard"),
+    ("email.welcome.help", "Need Help?"),
+    (
+        "email.welcome.help_text",
+        "If you have any questions or need assistance, feel free to contact our support team or visit our help center.",
+    ),
+    (
+        "email.welcome.closing",
+        "Thank you for joining GridTokenX. Together, we're building a sustainable energy future!",
+    ),
+    ("email.password_reset.subject", "Reset Your Password - GridTokenX Platform"),
+    ("email.password_reset.heading", "Password Reset Request"),
+    ("email.password_reset.greeting", "Hello {username},"),
+    (
+        "email.password_reset.intro",
+        "We received a request to reset the password for your GridTokenX account. Use the link below to create a new password:",
+    ),
+    ("email.password_reset.button", "Reset Password"),
+    ("email.password_reset.notice", "Security Notice"),
+    (
+        "email.password_reset.expiry",
+        "This link will expire in 1 hour. If you didn't request a password reset, please ignore this email or contact support if you have concerns.",
+    ),
+    ("email.login_code.subject", "Your Sign-In Code - GridTokenX Platform"),
+    ("email.login_code.heading", "Confirm It's You"),
+    ("email.login_code.greeting", "Hello {username},"),
+    (
+        "email.login_code.intro",
+        "We noticed a sign-in to your GridTokenX account from a new location or device. Enter this code to finish signing in:",
+    ),
+    ("email.login_code.notice", "Wasn't You?"),
+    (
+        "email.login_code.expiry",
+        "This code expires in {minutes} minutes. If you didn't try to sign in, change your password right away.",
+    ),
+    ("email.report.subject", "{report} ({period}) - GridTokenX Platform"),
+    ("email.report.greeting", "Hello {username},"),
+    ("email.report.intro", "Your {report} for {period} is attached. Highlights:"),
+    (
+        "email.report.manage",
+        "You receive this report because you subscribed to it. You can change or cancel the subscription under Analytics in your account.",
+    ),
+    ("report.daily_trading_summary", "Daily Trading Summary"),
+    ("report.weekly_generation", "Weekly Generation Report"),
+    ("report.monthly_carbon", "Monthly Carbon Report"),
+];
@@ -0,0 +1,183 @@
+//! Internationalization
+//!
+//! Message catalogs for user-visible text (error messages, notifications,
+//! emails) in English and Thai. Text produced while handling a request uses
+//! the request locale, negotiated from `Accept-Language` by
+//! `locale_middleware`; text produced outside a request (notifications,
+//! emails from background services) uses the user's preferred locale.
+//! Missing translations fall back to English.
+
+mod en;
+mod th;
+
+use std::fmt::{self, Display};
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+tokio::task_local! {
+    static CURRENT: Locale;
+}
+
+/// Supported locales
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Th,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Th];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Th => "th",
+        }
+    }
+
+    /// Locale of a language tag such as `th`, `th-TH` or `en_US`
+    pub fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Some(Locale::En),
+            "th" => Some(Locale::Th),
+            _ => None,
+        }
+    }
+
+    /// Best supported locale of an `Accept-Language` header, by quality value
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut best: Option<(Locale, f32)> = None;
+        for entry in header.split(',') {
+            let mut parts = entry.split(';');
+            let Some(locale) = parts.next().and_then(Locale::parse) else {
+                continue;
+            };
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((locale, quality));
+            }
+        }
+        best.map(|(locale, _)| locale)
+    }
+
+    fn catalog(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::En => en::MESSAGES,
+            Locale::Th => th::MESSAGES,
+        }
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Locale of the request being handled; English outside a request
+pub fn current() -> Locale {
+    CURRENT.try_with(|locale| *locale).unwrap_or_default()
+}
+
+/// Run `f` with `locale` as the current locale
+pub async fn scope<F: Future>(locale: Locale, f: F) -> F::Output {
+    CURRENT.scope(locale, f).await
+}
+
+/// Translation of `key` in `locale`, if the catalog has one
+pub fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    locale
+        .catalog()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, message)| *message)
+}
+
+/// Message for `key`, falling back to English and then to the key itself
+pub fn t(locale: Locale, key: &'static str) -> &'static str {
+    lookup(locale, key)
+        .or_else(|| lookup(Locale::En, key))
+        .unwrap_or(key)
+}
+
+/// Message for `key` with `{name}` placeholders filled in
+pub fn tf(locale: Locale, key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    let mut message = t(locale, key).to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    message
+}
+
+/// A user's preferred locale; English when unknown
+pub async fn user_locale(db: &PgPool, user_id: Uuid) -> Locale {
+    sqlx::query_scalar::<_, String>("SELECT preferred_locale FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(db)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|tag| Locale::parse(&tag))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(message: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = message
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_accept_language_negotiation() {
+        assert_eq!(Locale::from_accept_language("th-TH,th;q=0.9,en;q=0.8"), Some(Locale::Th));
+        assert_eq!(Locale::from_accept_language("en-US,th;q=0.5"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language("fr-FR, th;q=0.3, en;q=0.7"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language("th;q=0, en;q=0.1"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language("fr, de"), None);
+        assert_eq!(Locale::from_accept_language(""), None);
+    }
+
+    #[test]
+    fn test_catalogs_match() {
+        for (key, message) in en::MESSAGES {
+            let thai = lookup(Locale::Th, key).unwrap_or_else(|| panic!("missing Thai translation for {}", key));
+            assert_eq!(placeholders(message), placeholders(thai), "placeholders differ for {}", key);
+        }
+        for (key, _) in th::MESSAGES {
+            assert!(
+                lookup(Locale::En, key).is_some() || key.starts_with("error."),
+                "Thai message {} has no English original",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_fallback_and_formatting() {
+        assert_eq!(t(Locale::Th, "no.such.key"), "no.such.key");
+        let message = tf(
+            Locale::En,
+            "notification.order_filled.message",
+            &[("amount", &"1.50"), ("price", &"3.2000")],
+        );
+        assert_eq!(message, "Your order for 1.50 kWh was filled at 3.2000/kWh");
+    }
+}
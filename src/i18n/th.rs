@@ -0,0 +1,219 @@
+//! Thai message catalog
+//!
+//! `error.<code>` entries translate `ErrorCode::message` and are keyed by
+//! the numeric error code.
+
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    // Order sides, as used inside sentences
+    ("side.buy", "ซื้อ"),
+    ("side.sell", "ขาย"),
+    // In-app notifications
+    ("notification.order_filled.title", "คำสั่งสำเร็จแล้ว"),
+    (
+        "notification.order_filled.message",
+        "คำสั่งของคุณจำนวน {amount} kWh ได้รับการจับคู่ที่ราคา {price}/kWh",
+    ),
+    ("notification.conditional_triggered.title", "คำสั่ง {trigger_type} ทำงานแล้ว"),
+    (
+        "notification.conditional_triggered.message",
+        "คำสั่ง {trigger_type} ของคุณทำงานที่ราคา {price}",
+    ),
+    ("notification.recurring_executed.title", "คำสั่งซื้อขายประจำทำงานแล้ว"),
+    (
+        "notification.recurring_executed.message",
+        "การทำรายการครั้งที่ {number} เสร็จสิ้น จำนวน {amount} kWh",
+    ),
+    ("notification.trade_matched.title", "คำสั่งได้รับการจับคู่แล้ว"),
+    (
+        "notification.trade_matched.message",
+        "คำสั่ง{side}ของคุณจำนวน {amount} kWh ได้รับการจับคู่ที่ราคา {price} GRIDX/kWh",
+    ),
+    ("notification.settlement_complete.title", "ชำระราคาเสร็จสิ้น"),
+    (
+        "notification.settlement_complete.message",
+        "การชำระราคาจำนวน {amount} kWh ({value} GRIDX) ของคุณเสร็จสิ้นแล้ว",
+    ),
+    ("notification.rec_issued.title", "ออกใบรับรอง REC แล้ว"),
+    (
+        "notification.rec_issued.message",
+        "ออกใบรับรอง REC ({certificate_id}) สำหรับพลังงาน {amount} kWh แล้ว",
+    ),
+    ("notification.budget_alert.monthly_spend.title", "ค่าใช้จ่ายเดือนนี้เกินงบประมาณ"),
+    (
+        "notification.budget_alert.monthly_spend.message",
+        "เดือนนี้คุณใช้จ่ายไปแล้ว {value} GRIDX เกินงบประมาณ {threshold} GRIDX",
+    ),
+    ("notification.budget_alert.daily_consumption.title", "การใช้ไฟฟ้าวันนี้สูง"),
+    (
+        "notification.budget_alert.daily_consumption.message",
+        "วันนี้คุณใช้ไฟฟ้าไปแล้ว {value} kWh เกินขีดจำกัด {threshold} kWh",
+    ),
+    ("notification.budget_alert.clearing_price.title", "แจ้งเตือนราคาตลาด"),
+    (
+        "notification.budget_alert.clearing_price.message",
+        "ราคาตลาดล่าสุดอยู่ที่ {value} GRIDX/kWh สูงกว่าราคาแจ้งเตือน {threshold} GRIDX/kWh",
+    ),
+    ("notification.data_export_ready.title", "ข้อมูลที่ส่งออกพร้อมแล้ว"),
+    (
+        "notification.data_export_ready.message",
+        "ไฟล์ส่งออกข้อมูล {dataset} ({rows} แถว) พร้อมให้ดาวน์โหลดแล้ว",
+    ),
+    // Trading event emails
+    ("email.trade_matched.subject", "🤝 คำสั่งของคุณได้รับการจับคู่แล้ว"),
+    ("email.trade_matched.intro", "ข่าวดี! คำสั่ง{side}ของคุณได้รับการจับคู่แล้ว"),
+    ("email.trade_matched.outro", "ระบบจะดำเนินการชำระราคาโดยอัตโนมัติ"),
+    ("email.settlement_complete.subject", "✅ ชำระราคาเสร็จสิ้น"),
+    ("email.settlement_complete.intro", "การชำระราคาการซื้อขายพลังงานของคุณเสร็จสมบูรณ์แล้ว"),
+    ("email.rec_issued.subject", "🏆 ออกใบรับรอง REC แล้ว"),
+    (
+        "email.rec_issued.intro",
+        "ยินดีด้วย! ใบรับรองพลังงานหมุนเวียน (REC) ได้ถูกออกให้สำหรับการขายพลังงานของคุณ",
+    ),
+    ("email.rec_issued.outro", "คุณสามารถดูใบรับรองของคุณได้ที่แดชบอร์ด"),
+    ("email.label.energy_amount", "ปริมาณพลังงาน"),
+    ("email.label.price", "ราคา"),
+    ("email.label.total_value", "มูลค่ารวม"),
+    ("email.label.transaction", "ธุรกรรม"),
+    ("email.label.certificate_id", "เลขที่ใบรับรอง"),
+    ("email.label.source", "แหล่งพลังงาน"),
+    // Shared email text
+    ("email.footer.automated", "ข้อความนี้ส่งโดยอัตโนมัติจากแพลตฟอร์ม GridTokenX"),
+    ("email.footer.no_reply", "อีเมลนี้ส่งโดยอัตโนมัติ กรุณาอย่าตอบกลับ"),
+    ("email.footer.rights", "สงวนลิขสิทธิ์"),
+    ("email.link_fallback", "หากปุ่มไม่ทำงาน ให้คัดลอกลิงก์นี้ไปวางในเบราว์เซอร์ของคุณ:"),
+    // Account emails
+    ("email.verification.subject", "ยืนยันอีเมลของคุณ - แพลตฟอร์ม GridTokenX"),
+    ("email.verification.heading", "ยินดีต้อนรับสู่แพลตฟอร์ม GridTokenX!"),
+    ("email.verification.greeting", "ยินดีต้อนรับ คุณ{username}!"),
+    (
+        "email.verification.intro",
+        "ขอบคุณที่ลงทะเบียนกับแพลตฟอร์ม GridTokenX เรายินดีที่คุณเข้าร่วมเครือข่ายซื้อขายพลังงานแบบเพียร์ทูเพียร์ของเรา!",
+    ),
+    (
+        "email.verification.action",
+        "เพื่อลงทะเบียนให้เสร็จสมบูรณ์และเริ่มซื้อขายโทเค็นพลังงาน กรุณายืนยันอีเมลของคุณ:",
+    ),
+    ("email.verification.button", "ยืนยันอีเมล"),
+    ("email.verification.expiry", "เพื่อความปลอดภัย ลิงก์ยืนยันนี้จะหมดอายุภายใน 24 ชั่วโมง"),
+    (
+        "email.verification.ignore",
+        "หากคุณไม่ได้สร้างบัญชีกับ GridTokenX คุณสามารถเพิกเฉยต่ออีเมลนี้ได้",
+    ),
+    ("email.welcome.subject", "ยินดีต้อนรับสู่ GridTokenX! 🎉"),
+    ("email.welcome.verified", "อีเมลของคุณได้รับการยืนยันแล้ว"),
+    ("email.welcome.greeting", "สวัสดี คุณ{username}!"),
+    (
+        "email.welcome.intro",
+        "ยินดีด้วย! อีเมลของคุณได้รับการยืนยันเรียบร้อยแล้ว ตอนนี้คุณสามารถใช้งานทุกฟีเจอร์ของแพลตฟอร์ม GridTokenX ได้",
+    ),
+    ("email.welcome.next", "ขั้นตอนต่อไป"),
+    (
+        "email.welcome.next.wallet",
+        "เชื่อมต่อวอลเล็ต: เชื่อมวอลเล็ต Solana ของคุณเพื่อทำธุรกรรมบนบล็อกเชน",
+    ),
+    (
+        "email.welcome.next.dashboard",
+        "ดูแดชบอร์ด: ติดตามการใช้และการผลิตพลังงานของคุณแบบเรียลไทม์",
+    ),
+    (
+        "email.welcome.next.trading",
+        "เริ่มซื้อขาย: ซื้อและขายโทเค็นพลังงานกับผู้ใช้อื่นบนแพลตฟอร์ม",
+    ),
+    ("email.welcome.next.prices", "ติดตามราคา: ดูราคาตลาดพลังงานและแนวโน้มแบบสด"),
+    ("email.welcome.next.meters", "จัดการมิเตอร์: เชื่อมต่อและจัดการสมาร์ทมิเตอร์ของคุณ"),
+    ("email.welcome.button", "ไปที่แดชบอร์ด"),
+    ("email.welcome.help", "ต้องการความช่วยเหลือ?"),
+    (
+        "email.welcome.help_text",
+        "หากคุณมีคำถามหรือต้องการความช่วยเหลือ ติดต่อทีมสนับสนุนของเราหรือเยี่ยมชมศูนย์ช่วยเหลือได้เลย",
+    ),
+    (
+        "email.welcome.closing",
+        "ขอบคุณที่เข้าร่วม GridTokenX มาร่วมกันสร้างอนาคตพลังงานที่ยั่งยืน!",
+    ),
+    ("email.password_reset.subject", "รีเซ็ตรหัสผ่านของคุณ - แพลตฟอร์ม GridTokenX"),
+    ("email.password_reset.heading", "คำขอรีเซ็ตรหัสผ่าน"),
+    ("email.password_reset.greeting", "สวัสดี คุณ{username}"),
+    (
+        "email.password_reset.intro",
+        "เราได้รับคำขอรีเซ็ตรหัสผ่านสำหรับบัญชี GridTokenX ของคุณ ใช้ลิงก์ด้านล่างเพื่อตั้งรหัสผ่านใหม่:",
+    ),
+    ("email.password_reset.button", "รีเซ็ตรหัสผ่าน"),
+    ("email.password_reset.notice", "ประกาศด้านความปลอดภัย"),
+    (
+        "email.password_reset.expiry",
+        "ลิงก์นี้จะหมดอายุภายใน 1 ชั่วโมง หากคุณไม่ได้ขอรีเซ็ตรหัสผ่าน กรุณาเพิกเฉยต่ออีเมลนี้หรือติดต่อฝ่ายสนับสนุนหากมีข้อสงสัย",
+    ),
+    ("email.login_code.subject", "รหัสเข้าสู่ระบบของคุณ - แพลตฟอร์ม GridTokenX"),
+    ("email.login_code.heading", "ยืนยันว่าเป็นคุณ"),
+    ("email.login_code.greeting", "สวัสดี คุณ{username}"),
+    (
+        "email.login_code.intro",
+        "เราพบการเข้าสู่ระบบบัญชี GridTokenX ของคุณจากตำแหน่งหรืออุปกรณ์ใหม่ กรุณากรอกรหัสนี้เพื่อเข้าสู่ระบบให้เสร็จสมบูรณ์:",
+    ),
+    ("email.login_code.notice", "ไม่ใช่คุณใช่ไหม?"),
+    (
+        "email.login_code.expiry",
+        "รหัสนี้จะหมดอายุภายใน {minutes} นาที หากคุณไม่ได้พยายามเข้าสู่ระบบ กรุณาเปลี่ยนรหัสผ่านทันที",
+    ),
+    ("email.report.subject", "{report} ({period}) - แพลตฟอร์ม GridTokenX"),
+    ("email.report.greeting", "สวัสดี คุณ{username}"),
+    ("email.report.intro", "{report}ของคุณสำหรับช่วง {period} อยู่ในไฟล์แนบ สรุปสาระสำคัญ:"),
+    (
+        "email.report.manage",
+        "คุณได้รับรายงานนี้เนื่องจากได้สมัครรับไว้ คุณสามารถเปลี่ยนแปลงหรือยกเลิกการสมัครได้ที่เมนูการวิเคราะห์ในบัญชีของคุณ",
+    ),
+    ("report.daily_trading_summary", "สรุปการซื้อขายรายวัน"),
+    ("report.weekly_generation", "รายงานการผลิตไฟฟ้ารายสัปดาห์"),
+    ("report.monthly_carbon", "รายงานคาร์บอนรายเดือน"),
+    // Error messages, by ErrorCode::code
+    ("error.1001", "อีเมลหรือรหัสผ่านไม่ถูกต้อง"),
+    ("error.1002", "เซสชันของคุณหมดอายุแล้ว กรุณาเข้าสู่ระบบอีกครั้ง"),
+    ("error.1003", "โทเค็นยืนยันตัวตนไม่ถูกต้อง"),
+    ("error.1004", "ต้องยืนยันตัวตน กรุณาเข้าสู่ระบบ"),
+    ("error.1005", "กรุณายืนยันอีเมลของคุณก่อนดำเนินการต่อ"),
+    ("error.1006", "บัญชีของคุณถูกล็อก กรุณาติดต่อฝ่ายสนับสนุน"),
+    ("error.1007", "บัญชีของคุณถูกระงับ กรุณาติดต่อฝ่ายสนับสนุน"),
+    ("error.2001", "คุณไม่มีสิทธิ์ดำเนินการนี้"),
+    ("error.2002", "ไม่อนุญาตให้เข้าถึงทรัพยากรนี้"),
+    ("error.2003", "บทบาทของคุณไม่ได้รับอนุญาตให้ดำเนินการนี้"),
+    ("error.3001", "ข้อมูลที่ระบุไม่ถูกต้อง"),
+    ("error.3002", "ไม่ได้ระบุข้อมูลที่จำเป็น"),
+    ("error.3003", "รูปแบบข้อมูลไม่ถูกต้อง"),
+    ("error.3004", "รูปแบบที่อยู่วอลเล็ตไม่ถูกต้อง"),
+    ("error.3005", "จำนวนที่ระบุไม่ถูกต้อง"),
+    ("error.3006", "รูปแบบอีเมลไม่ถูกต้อง"),
+    ("error.3007", "รหัสผ่านไม่ถูกต้อง"),
+    ("error.3008", "รหัสผ่านไม่ปลอดภัยพอ ใช้อย่างน้อย 8 ตัวอักษรโดยมีทั้งตัวอักษรและตัวเลข"),
+    ("error.4001", "ไม่พบข้อมูลที่ร้องขอ"),
+    ("error.4002", "ข้อมูลนี้มีอยู่แล้ว"),
+    ("error.4003", "เกิดข้อขัดแย้งกับข้อมูลที่มีอยู่"),
+    ("error.4004", "ข้อมูลนี้ไม่มีให้บริการแล้ว"),
+    ("error.5001", "ยอดคงเหลือไม่เพียงพอสำหรับธุรกรรมนี้"),
+    ("error.5002", "ไม่พบคำสั่งที่จับคู่ได้"),
+    ("error.5003", "ไม่อนุญาตให้ซื้อขายในขณะนี้"),
+    ("error.5004", "ค่าที่อ่านได้จากมิเตอร์ไม่ถูกต้อง"),
+    ("error.5005", "ไม่สามารถสร้างโทเค็นพลังงานได้"),
+    ("error.5006", "รอบการซื้อขายยังไม่เปิด"),
+    ("error.5007", "คำสั่งไม่เป็นไปตามกฎการซื้อขายของตลาด"),
+    ("error.6001", "ไม่สามารถเชื่อมต่อเครือข่ายบล็อกเชนได้"),
+    ("error.6002", "ธุรกรรมบนบล็อกเชนล้มเหลว"),
+    ("error.6003", "ธุรกรรมบนบล็อกเชนหมดเวลา"),
+    ("error.6004", "ลายเซ็นธุรกรรมไม่ถูกต้อง"),
+    ("error.6005", "ค่าธรรมเนียมธุรกรรมไม่เพียงพอ"),
+    ("error.6006", "เกิดข้อผิดพลาดในโปรแกรมบล็อกเชน"),
+    ("error.7001", "ไม่สามารถเชื่อมต่อฐานข้อมูลได้"),
+    ("error.7002", "การสืบค้นฐานข้อมูลล้มเหลว"),
+    ("error.7003", "ธุรกรรมฐานข้อมูลล้มเหลว"),
+    ("error.7004", "ข้อมูลขัดกับข้อจำกัดของฐานข้อมูล"),
+    ("error.8001", "บริการภายนอกไม่พร้อมใช้งานในขณะนี้"),
+    ("error.8002", "คำขอไปยังบริการภายนอกหมดเวลา"),
+    ("error.8003", "เกิดข้อผิดพลาดจากบริการภายนอก"),
+    ("error.8004", "ไม่สามารถส่งอีเมลได้"),
+    ("error.8005", "บริการไม่พร้อมใช้งานในขณะนี้"),
+    ("error.9001", "เกินขีดจำกัดจำนวนคำขอ กรุณาลองใหม่ภายหลัง"),
+    ("error.9002", "มีคำขอมากเกินไป กรุณาลดความถี่"),
+    ("error.9997", "เกิดข้อผิดพลาดที่ไม่คาดคิด"),
+    ("error.9998", "การตั้งค่าเซิร์ฟเวอร์ผิดพลาด"),
+    ("error.9999", "เกิดข้อผิดพลาดภายในเซิร์ฟเวอร์"),
+];
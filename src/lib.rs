@@ -6,7 +6,9 @@ pub mod config;
 pub mod constants;
 pub mod database;
 pub mod error;
+pub mod fix;
 pub mod handlers;
+pub mod i18n;
 pub mod middleware;
 pub mod models;
 // pub mod openapi; // Disabled - references disabled modules
@@ -60,7 +60,8 @@ async fn main() -> Result<()> {
     let listener = tokio::net::TcpListener::bind(addr).await?;
 
     // Setup graceful shutdown
-    axum::serve(listener, app)
+    // Peer addresses are needed by the network ACL middleware
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(startup::shutdown_signal())
         .await?;
 
@@ -0,0 +1,34 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::services::api_usage::{normalize_endpoint, UsageIdentity};
+use crate::AppState;
+
+/// Count authenticated requests per user, credential and endpoint.
+/// The caller is identified by the `UsageIdentity` the auth middleware
+/// attaches to the response; unauthenticated requests are not counted.
+pub async fn api_usage_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let endpoint = normalize_endpoint(request.uri().path());
+
+    let response = next.run(request).await;
+
+    if let Some(identity) = response.extensions().get::<UsageIdentity>() {
+        state.api_usage.record(
+            identity,
+            &method,
+            &endpoint,
+            response.status().is_client_error() || response.status().is_server_error(),
+        );
+    }
+    response
+}
@@ -0,0 +1,54 @@
+use axum::{
+    body::Body,
+    extract::{OriginalUri, State},
+    http::{HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures::StreamExt;
+
+use crate::auth::Claims;
+use crate::router::permissions::concurrency_class;
+use crate::AppState;
+
+/// Cap the requests each caller has in flight on exports, analytics and
+/// order placement. Runs behind the auth middleware so the caller is known;
+/// other endpoints and unauthenticated requests pass through. The slot is
+/// held until the response body has been sent, so streamed exports count
+/// for their whole duration.
+pub async fn concurrency_limit(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.0.path().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let Some(class) = concurrency_class(request.method().as_str(), &path) else {
+        return next.run(request).await;
+    };
+    let Some(user_id) = request.extensions().get::<Claims>().map(|claims| claims.sub) else {
+        return next.run(request).await;
+    };
+
+    match state.concurrency_limiter.acquire(user_id, class).await {
+        Ok(None) => next.run(request).await,
+        Ok(Some(permit)) => {
+            let (parts, body) = next.run(request).await.into_parts();
+            let body = body.into_data_stream().map(move |chunk| {
+                let _ = &permit;
+                chunk
+            });
+            Response::from_parts(parts, Body::from_stream(body))
+        }
+        Err(e) => {
+            let mut response = e.into_response();
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, HeaderValue::from_static("1"));
+            response
+        }
+    }
+}
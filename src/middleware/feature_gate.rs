@@ -0,0 +1,23 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::config::Feature;
+use crate::AppState;
+
+/// Answer 404 for routes of a module the deployment profile switches off,
+/// as if the routes did not exist on this deployment.
+pub async fn feature_gate(
+    State((state, feature)): State<(AppState, Feature)>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    match state.deployment.require(feature) {
+        Ok(()) => next.run(request).await,
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,134 @@
+use std::net::SocketAddr;
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::error::ApiError;
+use crate::services::meter_gateway::{normalize_fingerprint, GatewayIdentity};
+use crate::AppState;
+
+/// Authenticate AMI gateways by mTLS client certificate.
+///
+/// TLS is terminated at a trusted reverse proxy, which forwards the
+/// verification result and certificate fingerprint. The headers are ignored
+/// unless the request came directly from a trusted proxy. A verified,
+/// registered certificate attaches a [`GatewayIdentity`] to the request;
+/// an unknown or revoked certificate is always rejected.
+pub async fn ami_gateway_auth(
+    State(state): State<AppState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = &state.config.ami_gateway;
+    let from_trusted_proxy = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .is_some_and(|info| state.network_acl.is_trusted_proxy(info.0.ip()));
+
+    let presented = if from_trusted_proxy {
+        presented_fingerprint(request.headers(), &config.verify_header, &config.fingerprint_header)
+    } else {
+        None
+    };
+
+    match presented {
+        Some(Ok(fingerprint)) => match state.meter_gateway_service.authenticate(&fingerprint).await {
+            Ok(Some(gateway)) => {
+                request.extensions_mut().insert(GatewayIdentity(gateway));
+            }
+            Ok(None) => {
+                warn!("🔐 Rejected unknown or revoked gateway certificate {}", fingerprint);
+                return ApiError::Unauthorized("Client certificate is not registered to an active gateway".to_string())
+                    .into_response();
+            }
+            Err(e) => {
+                error!("❌ Gateway lookup failed: {}", e);
+                return e.into_response();
+            }
+        },
+        Some(Err(reason)) => {
+            warn!("🔐 Rejected gateway client certificate: {}", reason);
+            return ApiError::Unauthorized(reason.to_string()).into_response();
+        }
+        None if config.require_client_cert => {
+            return ApiError::Unauthorized("A verified gateway client certificate is required".to_string())
+                .into_response();
+        }
+        None => {}
+    }
+
+    next.run(request).await
+}
+
+/// Fingerprint forwarded by the proxy: `None` when no certificate was
+/// presented, `Err` when one was presented but failed verification.
+fn presented_fingerprint(
+    headers: &HeaderMap,
+    verify_header: &str,
+    fingerprint_header: &str,
+) -> Option<Result<String, &'static str>> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    match header(verify_header) {
+        None | Some("NONE") => None,
+        Some("SUCCESS") => Some(
+            header(fingerprint_header)
+                .and_then(normalize_fingerprint)
+                .ok_or("Client certificate fingerprint missing or malformed"),
+        ),
+        Some(_) => Some(Err("Client certificate verification failed")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    const FP: &str = "3f2a9c0d1e4b5a6978877665544332211000ffeeddccbbaa9988776655443322";
+
+    fn headers(verify: Option<&'static str>, fingerprint: Option<&'static str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(v) = verify {
+            headers.insert("x-client-cert-verify", HeaderValue::from_static(v));
+        }
+        if let Some(f) = fingerprint {
+            headers.insert("x-client-cert-fingerprint", HeaderValue::from_static(f));
+        }
+        headers
+    }
+
+    fn presented(h: &HeaderMap) -> Option<Result<String, &'static str>> {
+        presented_fingerprint(h, "x-client-cert-verify", "x-client-cert-fingerprint")
+    }
+
+    #[test]
+    fn test_no_certificate() {
+        assert!(presented(&headers(None, None)).is_none());
+        assert!(presented(&headers(Some("NONE"), None)).is_none());
+    }
+
+    #[test]
+    fn test_verified_certificate() {
+        assert_eq!(presented(&headers(Some("SUCCESS"), Some(FP))), Some(Ok(FP.to_string())));
+    }
+
+    #[test]
+    fn test_failed_or_malformed_certificate() {
+        assert!(matches!(presented(&headers(Some("FAILED:certificate expired"), Some(FP))), Some(Err(_))));
+        assert!(matches!(presented(&headers(Some("SUCCESS"), Some("abc"))), Some(Err(_))));
+        assert!(matches!(presented(&headers(Some("SUCCESS"), None)), Some(Err(_))));
+    }
+}
@@ -0,0 +1,28 @@
+use axum::{
+    body::Body,
+    http::{header, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::i18n::{self, Locale};
+
+/// Negotiate the request locale from `Accept-Language`.
+///
+/// The locale is current for the rest of the request, so error messages and
+/// emails sent while handling it are localized; the response reports it in
+/// `Content-Language`.
+pub async fn locale_middleware(request: Request<Body>, next: Next) -> Response {
+    let locale = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Locale::from_accept_language)
+        .unwrap_or_default();
+
+    let mut response = i18n::scope(locale, next.run(request)).await;
+    response
+        .headers_mut()
+        .insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale.as_str()));
+    response
+}
@@ -0,0 +1,92 @@
+use axum::{
+    body::Body,
+    extract::{OriginalUri, State},
+    http::{header::AUTHORIZATION, HeaderMap, Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::auth::Claims;
+use crate::services::rate_limiter::{hash_api_key, RatePrincipal};
+use crate::utils::request_info::extract_ip_address;
+use crate::AppState;
+
+/// Meter reading submission endpoints covered by the limiter
+pub fn is_meter_submission(method: &Method, path: &str) -> bool {
+    method == Method::POST
+        && (path == "/api/meters/submit-reading"
+            || (path.contains("/meters/") && path.ends_with("/readings")))
+}
+
+/// API key presented by the caller: `X-API-Key`, or a Bearer token that is
+/// not a JWT (the simulator sends the engineering key that way)
+fn presented_api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get("X-API-Key").and_then(|v| v.to_str().ok()) {
+        return Some(key);
+    }
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .filter(|token| token.matches('.').count() != 2)
+}
+
+/// Limit meter submissions per principal, honouring admin overrides.
+/// Layer inside `auth_middleware` so authenticated callers count per user.
+pub async fn meter_rate_limit_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    if !is_meter_submission(request.method(), &path) {
+        return next.run(request).await;
+    }
+
+    let headers = request.headers();
+    let principal = RatePrincipal {
+        user_id: request.extensions().get::<Claims>().map(|claims| claims.sub),
+        api_key_hash: presented_api_key(headers).map(hash_api_key),
+        ip: extract_ip_address(headers).parse().ok(),
+    };
+
+    match state.rate_limiter.check(&principal, &path).await {
+        Ok(()) => next.run(request).await,
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_meter_submission() {
+        assert!(is_meter_submission(&Method::POST, "/api/meters/submit-reading"));
+        assert!(is_meter_submission(&Method::POST, "/api/v1/meters/SM-001/readings"));
+        assert!(is_meter_submission(&Method::POST, "/api/v1/meters/batch/readings"));
+        assert!(is_meter_submission(&Method::POST, "/api/v1/public/meters/batch/readings"));
+
+        assert!(!is_meter_submission(&Method::GET, "/api/v1/meters/SM-001/readings"));
+        assert!(!is_meter_submission(&Method::POST, "/api/v1/meters"));
+        assert!(!is_meter_submission(&Method::POST, "/api/v1/trading/orders"));
+    }
+
+    #[test]
+    fn test_presented_api_key() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, "Bearer engineering-key".parse().unwrap());
+        assert_eq!(presented_api_key(&headers), Some("engineering-key"));
+
+        headers.insert(AUTHORIZATION, "Bearer aaa.bbb.ccc".parse().unwrap());
+        assert_eq!(presented_api_key(&headers), None);
+
+        headers.insert("X-API-Key", "partner-key".parse().unwrap());
+        assert_eq!(presented_api_key(&headers), Some("partner-key"));
+    }
+}
@@ -139,6 +139,75 @@ pub fn track_blockchain_operation(operation: &str, duration_ms: f64, success: bo
     ).increment(1);
 }
 
+/// Track one stage of epoch matching
+pub fn track_matching_stage(stage: &str, duration_ms: f64) {
+    histogram!("matching_stage_duration_ms", "stage" => stage.to_string()).record(duration_ms);
+}
+
+/// Track the full matching run of an epoch
+pub fn track_matching_epoch(duration_ms: f64) {
+    histogram!("matching_epoch_duration_ms").record(duration_ms);
+}
+
+/// Track one shadow matcher comparison and the orders it would have filled differently
+pub fn track_matching_shadow(algorithm: &str, diverged_orders: usize) {
+    counter!(
+        "matching_shadow_runs_total",
+        "algorithm" => algorithm.to_string(),
+        "diverged" => (diverged_orders > 0).to_string()
+    )
+    .increment(1);
+    counter!("matching_shadow_diverged_orders_total", "algorithm" => algorithm.to_string()).increment(diverged_orders as u64);
+}
+
+/// Pending settlement pool and epoch settlement batches per state
+pub fn set_settlement_batches(pending_pool: i64, batches: &[(&str, i64)]) {
+    gauge!("settlement_pending_pool").set(pending_pool as f64);
+    for (state, count) in batches {
+        gauge!("settlement_batches", "state" => state.to_string()).set(*count as f64);
+    }
+}
+
+/// Track time from settlement creation to on-chain confirmation
+pub fn track_settlement_confirmation(latency_secs: f64) {
+    histogram!("settlement_confirmation_latency_seconds").record(latency_secs);
+}
+
+/// Track lamports a settlement fee payer spent
+pub fn track_settlement_fee(payer: &str, lamports: u64) {
+    counter!("settlement_fee_payer_spend_lamports_total", "payer" => payer.to_string()).increment(lamports);
+}
+
+/// Settlements found stuck in processing by the last watchdog pass
+pub fn set_settlements_stuck(count: usize) {
+    gauge!("settlements_stuck").set(count as f64);
+}
+
+/// Track how the watchdog resolved a stuck settlement
+pub fn track_settlement_recovery(outcome: &str) {
+    counter!("settlement_recoveries_total", "outcome" => outcome.to_string()).increment(1);
+}
+
+/// Track how a settlement that failed in a batch was diagnosed
+pub fn track_settlement_diagnosis(outcome: &str) {
+    counter!("settlement_diagnoses_total", "outcome" => outcome.to_string()).increment(1);
+}
+
+/// Track settlements checked against metered delivery, by outcome
+pub fn track_delivery_verification(status: &str) {
+    counter!("delivery_verifications_total", "status" => status.to_string()).increment(1);
+}
+
+/// Track imbalance settlements queued, by direction (shortfall or surplus)
+pub fn track_imbalance_settlement(direction: &str) {
+    counter!("imbalance_settlements_total", "direction" => direction.to_string()).increment(1);
+}
+
+/// Track official grid intervals reconciled with submitted readings
+pub fn track_grid_reconciliation(divergent: bool) {
+    counter!("grid_meter_reconciliations_total", "divergent" => divergent.to_string()).increment(1);
+}
+
 /// Track cache operations
 pub fn track_cache_operation(operation: &str, hit: bool) {
     counter!(
@@ -184,3 +253,21 @@ mod tests {
         track_websocket_connection(false);
     }
 }
+
+/// Track an event confirmed or failed by the event processor
+pub fn track_event_processed(event_type: &str, success: bool) {
+    counter!(
+        "event_processor_events_total",
+        "event_type" => event_type.to_string(),
+        "success" => success.to_string()
+    ).increment(1);
+}
+
+/// Track event processor lag behind the chain head and its confirmation queue
+pub fn track_event_processor_lag(lag_slots: Option<u64>, queue_depth: i64, oldest_pending_secs: Option<i64>) {
+    if let Some(lag) = lag_slots {
+        gauge!("event_processor_lag_slots").set(lag as f64);
+    }
+    gauge!("event_processor_queue_depth").set(queue_depth as f64);
+    gauge!("event_processor_oldest_pending_seconds").set(oldest_pending_secs.unwrap_or(0) as f64);
+}
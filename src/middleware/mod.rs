@@ -1,12 +1,28 @@
 // Middleware module - authentication, CORS, logging, security, etc.
 
+pub mod api_usage;
+pub mod concurrency_limit;
+pub mod feature_gate;
+pub mod gateway_auth;
 pub mod json_validation;
+pub mod locale;
+pub mod meter_rate_limit;
 pub mod metrics;
 pub mod metrics_middleware;
+pub mod network_acl;
+pub mod public_rate_limit;
 pub mod request_logger;
 pub mod security_headers;
 
+pub use api_usage::api_usage_middleware;
+pub use concurrency_limit::concurrency_limit;
+pub use feature_gate::feature_gate;
+pub use gateway_auth::ami_gateway_auth;
 pub use json_validation::json_validation_middleware;
+pub use locale::locale_middleware;
+pub use meter_rate_limit::meter_rate_limit_middleware;
 pub use metrics::{active_requests_middleware, metrics_middleware};
+pub use network_acl::{admin_network_acl, ami_network_acl};
+pub use public_rate_limit::public_rate_limit;
 pub use request_logger::{auth_logger_middleware, request_logger_middleware};
 pub use security_headers::add_security_headers;
@@ -0,0 +1,54 @@
+use std::net::SocketAddr;
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::warn;
+
+use crate::error::ApiError;
+use crate::services::network_acl::{ClientIp, RouteGroup};
+use crate::AppState;
+
+/// Restrict admin routes to the admin network allow list
+pub async fn admin_network_acl(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    enforce(&state, RouteGroup::Admin, request, next).await
+}
+
+/// Restrict AMI meter-reading ingestion to utility networks
+pub async fn ami_network_acl(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    enforce(&state, RouteGroup::Ami, request, next).await
+}
+
+async fn enforce(state: &AppState, group: RouteGroup, mut request: Request<Body>, next: Next) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip());
+    let client_ip = state.network_acl.client_ip(peer, request.headers());
+
+    if state.network_acl.check(group, client_ip).await {
+        request.extensions_mut().insert(ClientIp(client_ip));
+        return next.run(request).await;
+    }
+
+    warn!(
+        "🚫 Network ACL blocked {} {} from {} ({} group)",
+        request.method(),
+        request.uri().path(),
+        client_ip.map(|ip| ip.to_string()).unwrap_or_else(|| "unknown".to_string()),
+        group.as_str()
+    );
+    ApiError::Forbidden("Access from this network is not permitted".to_string()).into_response()
+}
@@ -0,0 +1,80 @@
+use std::net::SocketAddr;
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{HeaderName, HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use metrics::counter;
+use tracing::warn;
+
+use crate::constants::cache::RATE_LIMIT_PREFIX;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Fixed rate limit window
+const WINDOW_SECS: i64 = 60;
+
+/// Rate limit anonymous public API clients by IP, per minute.
+///
+/// Counters live in Redis so the limit holds across gateway instances. When
+/// Redis is unavailable requests are let through rather than failing the
+/// public API.
+pub async fn public_rate_limit(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let limit = state.config.public_api.rate_limit_per_minute as i64;
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip());
+    let Some(client_ip) = state.network_acl.client_ip(peer, request.headers()) else {
+        return next.run(request).await;
+    };
+
+    let now = Utc::now().timestamp();
+    let window = now / WINDOW_SECS;
+    let retry_after = WINDOW_SECS - now % WINDOW_SECS;
+    let key = format!("{}public:{}:{}", RATE_LIMIT_PREFIX, client_ip, window);
+
+    let count = match state
+        .cache_service
+        .increment_with_ttl(&key, WINDOW_SECS as u64 * 2)
+        .await
+    {
+        Ok(count) => count,
+        Err(e) => {
+            warn!("Public API rate limit unavailable, allowing request: {}", e);
+            return next.run(request).await;
+        }
+    };
+
+    let mut response = if count > limit {
+        counter!("public_api_rate_limited_total").increment(1);
+        let mut response = ApiError::RateLimitExceeded(format!(
+            "Public API limit of {} requests per minute exceeded",
+            limit
+        ))
+        .into_response();
+        response
+            .headers_mut()
+            .insert(axum::http::header::RETRY_AFTER, HeaderValue::from(retry_after));
+        response
+    } else {
+        next.run(request).await
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(HeaderName::from_static("x-ratelimit-limit"), HeaderValue::from(limit));
+    headers.insert(
+        HeaderName::from_static("x-ratelimit-remaining"),
+        HeaderValue::from((limit - count).max(0)),
+    );
+    headers.insert(HeaderName::from_static("x-ratelimit-reset"), HeaderValue::from(retry_after));
+    response
+}
@@ -0,0 +1,252 @@
+//! Decimal-backed quantity types
+//!
+//! `EnergyKwh` and `TokenAmount` wrap `rust_decimal::Decimal` so energy and
+//! token totals accumulate without floating-point drift. Both serialize as
+//! JSON numbers, like the float fields they replaced, and accept numbers or
+//! decimal strings (e.g. `"12.5"`) on input, map to `NUMERIC` columns, and
+//! round to the storage scale of `NUMERIC(20, 8)`.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use utoipa::ToSchema;
+
+use crate::constants::energy::AMOUNT_DECIMAL_PLACES;
+
+macro_rules! decimal_quantity {
+    ($(#[$meta:meta])* $name:ident, $example:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type, ToSchema)]
+        #[sqlx(transparent)]
+        #[schema(value_type = f64, example = $example)]
+        pub struct $name(Decimal);
+
+        impl $name {
+            pub const ZERO: Self = Self(Decimal::ZERO);
+
+            /// Wrap a decimal, rounding to storage precision
+            pub fn new(value: Decimal) -> Self {
+                Self(value.round_dp(AMOUNT_DECIMAL_PLACES))
+            }
+
+            /// Convert from a float at an API or device boundary.
+            /// Non-finite values map to `None`.
+            pub fn from_f64(value: f64) -> Option<Self> {
+                decimal_from_f64(value).map(Self::new)
+            }
+
+            pub fn value(self) -> Decimal {
+                self.0
+            }
+
+            /// Lossy conversion for chart libraries and legacy float APIs
+            pub fn to_f64(self) -> f64 {
+                self.0.to_f64().unwrap_or(0.0)
+            }
+
+            pub fn abs(self) -> Self {
+                Self(self.0.abs())
+            }
+
+            pub fn is_positive(self) -> bool {
+                self.0.is_sign_positive() && !self.0.is_zero()
+            }
+
+            pub fn is_zero(self) -> bool {
+                self.0.is_zero()
+            }
+
+            /// Mean over `count` items, or zero when there are none
+            pub fn average_over(self, count: i64) -> Self {
+                if count == 0 {
+                    Self::ZERO
+                } else {
+                    Self::new(self.0 / Decimal::from(count))
+                }
+            }
+        }
+
+        impl From<Decimal> for $name {
+            fn from(value: Decimal) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl From<$name> for Decimal {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = rust_decimal::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Decimal::from_str(s.trim()).map(Self::new)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0.normalize(), f)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_f64(self.to_f64())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserialize_decimal(deserializer).map(Self::new)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        /// Scale by a factor (price, emission factor, share)
+        impl Mul<Decimal> for $name {
+            type Output = Decimal;
+
+            fn mul(self, rhs: Decimal) -> Decimal {
+                self.0 * rhs
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::ZERO, |acc, x| acc + x)
+            }
+        }
+
+        impl<'a> Sum<&'a $name> for $name {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.copied().sum()
+            }
+        }
+    };
+}
+
+decimal_quantity!(
+    /// Quantity of energy in kilowatt-hours
+    EnergyKwh,
+    12.5
+);
+
+decimal_quantity!(
+    /// Token or settlement-currency amount in major units
+    TokenAmount,
+    100.25
+);
+
+impl EnergyKwh {
+    /// Value of this energy at a per-kWh price
+    pub fn priced_at(self, price_per_kwh: Decimal) -> TokenAmount {
+        TokenAmount::new(self.0 * price_per_kwh)
+    }
+}
+
+/// Convert via the shortest float representation so `0.1` becomes exactly `0.1`
+fn decimal_from_f64(value: f64) -> Option<Decimal> {
+    if !value.is_finite() {
+        return None;
+    }
+    Decimal::from_str(&value.to_string())
+        .ok()
+        .or_else(|| Decimal::from_f64(value))
+}
+
+/// Accept `"12.5"` or `12.5`
+fn deserialize_decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Str(String),
+        Int(i64),
+        Float(f64),
+    }
+
+    match Raw::deserialize(deserializer)? {
+        Raw::Str(s) => Decimal::from_str(s.trim()).map_err(serde::de::Error::custom),
+        Raw::Int(i) => Ok(Decimal::from(i)),
+        Raw::Float(f) => decimal_from_f64(f).ok_or_else(|| serde::de::Error::custom("number out of range for a decimal amount")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accumulation_is_exact() {
+        let total: EnergyKwh = (0..10).map(|_| EnergyKwh::from_f64(0.1).unwrap()).sum();
+        assert_eq!(total, EnergyKwh::from_str("1").unwrap());
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let kwh: EnergyKwh = serde_json::from_str("12.50").unwrap();
+        assert_eq!(serde_json::to_string(&kwh).unwrap(), "12.5");
+
+        let kwh: EnergyKwh = serde_json::from_str("\"0.30000000\"").unwrap();
+        assert_eq!(kwh.to_string(), "0.3");
+
+        let tokens: TokenAmount = serde_json::from_str("42").unwrap();
+        assert_eq!(serde_json::to_value(tokens).unwrap(), serde_json::json!(42.0));
+
+        assert!(serde_json::from_str::<EnergyKwh>("\"abc\"").is_err());
+    }
+
+    #[test]
+    fn test_rounds_to_storage_precision() {
+        let kwh = EnergyKwh::from_str("1.123456789").unwrap();
+        assert_eq!(kwh.to_string(), "1.12345679");
+    }
+
+    #[test]
+    fn test_priced_at() {
+        let kwh = EnergyKwh::from_str("2.5").unwrap();
+        assert_eq!(kwh.priced_at(Decimal::new(4, 1)), TokenAmount::from_str("1").unwrap());
+    }
+}
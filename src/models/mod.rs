@@ -1,3 +1,8 @@
+pub mod amounts;
 pub mod notification;
+pub mod pii;
 pub mod trading;
 pub mod transaction;
+
+pub use amounts::{EnergyKwh, TokenAmount};
+pub use pii::{SealedUserPii, UserPii, UserPiiColumns, USER_PII_COLUMNS};
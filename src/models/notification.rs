@@ -28,6 +28,20 @@ pub enum NotificationType {
     EscrowReleased,
     /// System announcement
     System,
+    /// Renewable energy certificate expires soon
+    CertificateExpiring,
+    /// Renewable energy certificate expired
+    CertificateExpired,
+    /// Trade settlement completed on-chain
+    SettlementComplete,
+    /// Renewable energy certificate issued
+    RecIssued,
+    /// Order was cancelled
+    OrderCancelled,
+    /// Household budget threshold crossed
+    BudgetAlert,
+    /// Requested data export is ready to download
+    DataExportReady,
 }
 
 impl std::fmt::Display for NotificationType {
@@ -40,6 +54,13 @@ impl std::fmt::Display for NotificationType {
             NotificationType::PriceAlert => write!(f, "price_alert"),
             NotificationType::EscrowReleased => write!(f, "escrow_released"),
             NotificationType::System => write!(f, "system"),
+            NotificationType::CertificateExpiring => write!(f, "certificate_expiring"),
+            NotificationType::CertificateExpired => write!(f, "certificate_expired"),
+            NotificationType::SettlementComplete => write!(f, "settlement_complete"),
+            NotificationType::RecIssued => write!(f, "rec_issued"),
+            NotificationType::OrderCancelled => write!(f, "order_cancelled"),
+            NotificationType::BudgetAlert => write!(f, "budget_alert"),
+            NotificationType::DataExportReady => write!(f, "data_export_ready"),
         }
     }
 }
@@ -54,6 +75,7 @@ pub struct Notification {
     pub message: Option<String>,
     pub data: Option<serde_json::Value>,
     pub read: bool,
+    pub read_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -121,3 +143,9 @@ pub struct NotificationListResponse {
     pub unread_count: i64,
     pub total: i64,
 }
+
+/// Unread notification counter
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UnreadCountResponse {
+    pub unread_count: i64,
+}
@@ -0,0 +1,77 @@
+//! Personal data columns
+//!
+//! User email and names and meter addresses are stored sealed by
+//! [`PiiCipher`]. Rows are read with the sealed columns next to the legacy
+//! plaintext ones, which hold data the rotation job has not encrypted yet;
+//! opening a row prefers the sealed value so callers never see the difference.
+
+use sqlx::FromRow;
+
+use crate::error::Result;
+use crate::services::pii::{sealed_key_id, PiiCipher};
+
+/// Select list matching [`SealedUserPii`]
+pub const USER_PII_COLUMNS: &str =
+    "email, first_name, last_name, email_encrypted, first_name_encrypted, last_name_encrypted";
+
+/// Personal columns of a user row as stored
+#[derive(Debug, Clone, Default, FromRow)]
+pub struct SealedUserPii {
+    pub email: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email_encrypted: Option<Vec<u8>>,
+    pub first_name_encrypted: Option<Vec<u8>>,
+    pub last_name_encrypted: Option<Vec<u8>>,
+}
+
+/// Personal data of a user in the clear
+#[derive(Debug, Clone, Default)]
+pub struct UserPii {
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+/// Column values to write for a user's personal data
+#[derive(Debug, Clone)]
+pub struct UserPiiColumns {
+    pub email_encrypted: Vec<u8>,
+    pub email_digest: Vec<u8>,
+    pub first_name_encrypted: Option<Vec<u8>>,
+    pub last_name_encrypted: Option<Vec<u8>>,
+    pub key_id: Option<i32>,
+}
+
+impl SealedUserPii {
+    pub async fn open(&self, pii: &PiiCipher) -> Result<UserPii> {
+        Ok(UserPii {
+            email: open_column(pii, self.email_encrypted.as_deref(), self.email.as_deref())
+                .await?
+                .unwrap_or_default(),
+            first_name: open_column(pii, self.first_name_encrypted.as_deref(), self.first_name.as_deref()).await?,
+            last_name: open_column(pii, self.last_name_encrypted.as_deref(), self.last_name.as_deref()).await?,
+        })
+    }
+}
+
+impl UserPii {
+    pub async fn seal(&self, pii: &PiiCipher) -> Result<UserPiiColumns> {
+        let email_encrypted = pii.seal(&self.email).await?;
+        Ok(UserPiiColumns {
+            key_id: sealed_key_id(&email_encrypted),
+            email_digest: pii.email_digest(&self.email),
+            first_name_encrypted: pii.seal_opt(self.first_name.as_deref()).await?,
+            last_name_encrypted: pii.seal_opt(self.last_name.as_deref()).await?,
+            email_encrypted,
+        })
+    }
+}
+
+/// Sealed value if present, otherwise the legacy plaintext
+pub async fn open_column(pii: &PiiCipher, sealed: Option<&[u8]>, legacy: Option<&str>) -> Result<Option<String>> {
+    match sealed {
+        Some(sealed) => Ok(Some(pii.open(sealed).await?)),
+        None => Ok(legacy.map(str::to_string)),
+    }
+}
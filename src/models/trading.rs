@@ -102,9 +102,11 @@ pub struct EscrowRecord {
 pub struct CreateOrderRequest {
     pub side: OrderSide,
     
+    #[validate(custom(function = "crate::utils::validation::rules::energy_amount"))]
     #[schema(value_type = String, example = "10.5")]
     pub energy_amount: Decimal,
     
+    #[validate(custom(function = "crate::utils::validation::rules::price_per_kwh"))]
     #[schema(value_type = String, example = "0.15")]
     pub price_per_kwh: Option<Decimal>,
 
@@ -112,6 +114,7 @@ pub struct CreateOrderRequest {
 
     pub expiry_time: Option<DateTime<Utc>>,
 
+    #[validate(range(min = 0))]
     pub zone_id: Option<i32>,
 
     pub meter_id: Option<Uuid>,
@@ -124,12 +127,43 @@ pub struct CreateOrderRequest {
 
     /// Session token for wallet decryption (auto-trading)
     pub session_token: Option<String>,
+
+    /// Market to place the order in; defaults to the main energy spot market
+    pub market_id: Option<Uuid>,
+}
+
+/// Market order split between the orderbook and the market's AMM pool
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RouteOrderRequest {
+    pub side: OrderSide,
+
+    #[validate(custom(function = "crate::utils::validation::rules::energy_amount"))]
+    #[schema(value_type = String, example = "10.5")]
+    pub energy_amount: Decimal,
+
+    /// Market to route in; defaults to the main energy spot market
+    pub market_id: Option<Uuid>,
+
+    /// Worst acceptable AMM price deviation from the reference price, in basis
+    /// points (defaults to, and is capped at, the platform maximum)
+    #[validate(range(max = 10000))]
+    pub max_slippage_bps: Option<u32>,
+
+    #[validate(range(min = 0))]
+    pub zone_id: Option<i32>,
+
+    pub meter_id: Option<Uuid>,
+
+    /// Session token for wallet decryption (auto-trading)
+    pub session_token: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateOrderRequest {
+    #[validate(custom(function = "crate::utils::validation::rules::energy_amount"))]
     #[schema(value_type = String)]
     pub energy_amount: Option<Decimal>,
+    #[validate(custom(function = "crate::utils::validation::rules::price_per_kwh"))]
     #[schema(value_type = String)]
     pub price_per_kwh: Option<Decimal>,
 }
@@ -218,10 +252,12 @@ pub struct CreateConditionalOrderRequest {
     pub side: OrderSide,
     
     /// Amount of energy to trade
+    #[validate(custom(function = "crate::utils::validation::rules::energy_amount"))]
     #[schema(value_type = String, example = "10.5")]
     pub energy_amount: Decimal,
     
     /// Price that triggers the order execution
+    #[validate(custom(function = "crate::utils::validation::rules::price_per_kwh"))]
     #[schema(value_type = String, example = "0.10")]
     pub trigger_price: Decimal,
     
@@ -229,10 +265,12 @@ pub struct CreateConditionalOrderRequest {
     pub trigger_type: TriggerType,
     
     /// Optional limit price for the order after triggering (if not set, uses market order)
+    #[validate(custom(function = "crate::utils::validation::rules::price_per_kwh"))]
     #[schema(value_type = String, example = "0.09")]
     pub limit_price: Option<Decimal>,
     
     /// For trailing stop: the offset from peak price
+    #[validate(custom(function = "crate::utils::validation::rules::positive_amount"))]
     #[schema(value_type = String, example = "0.02")]
     pub trailing_offset: Option<Decimal>,
     
@@ -329,14 +367,17 @@ pub struct CreateRecurringOrderRequest {
     pub side: OrderSide,
     
     /// Amount of energy per execution
+    #[validate(custom(function = "crate::utils::validation::rules::energy_amount"))]
     #[schema(value_type = String, example = "10.0")]
     pub energy_amount: Decimal,
     
     /// Max price for buy orders
+    #[validate(custom(function = "crate::utils::validation::rules::price_per_kwh"))]
     #[schema(value_type = String, example = "0.20")]
     pub max_price_per_kwh: Option<Decimal>,
     
     /// Min price for sell orders
+    #[validate(custom(function = "crate::utils::validation::rules::price_per_kwh"))]
     #[schema(value_type = String, example = "0.10")]
     pub min_price_per_kwh: Option<Decimal>,
     
@@ -344,15 +385,19 @@ pub struct CreateRecurringOrderRequest {
     pub interval_type: IntervalType,
     
     /// Execute every N intervals (default: 1)
+    #[validate(range(min = 1, max = 365))]
     pub interval_value: Option<i32>,
     
     /// Maximum number of executions (null = unlimited)
+    #[validate(range(min = 1))]
     pub max_executions: Option<i32>,
     
     /// User-friendly name for this order
+    #[validate(length(max = 100))]
     pub name: Option<String>,
     
     /// Optional description
+    #[validate(length(max = 500))]
     pub description: Option<String>,
 
     /// Session token for wallet decryption (auto-trading)
@@ -397,15 +442,22 @@ pub struct RecurringOrder {
 /// Request to update a recurring order
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateRecurringOrderRequest {
+    #[validate(custom(function = "crate::utils::validation::rules::energy_amount"))]
     #[schema(value_type = String)]
     pub energy_amount: Option<Decimal>,
+    #[validate(custom(function = "crate::utils::validation::rules::price_per_kwh"))]
     #[schema(value_type = String)]
     pub max_price_per_kwh: Option<Decimal>,
+    #[validate(custom(function = "crate::utils::validation::rules::price_per_kwh"))]
     #[schema(value_type = String)]
     pub min_price_per_kwh: Option<Decimal>,
     pub interval_type: Option<IntervalType>,
+    #[validate(range(min = 1, max = 365))]
     pub interval_value: Option<i32>,
+    #[validate(range(min = 1))]
     pub max_executions: Option<i32>,
+    #[validate(length(max = 100))]
     pub name: Option<String>,
+    #[validate(length(max = 500))]
     pub description: Option<String>,
 }
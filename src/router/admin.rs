@@ -1,18 +1,252 @@
-//! Admin-only routes (placeholder for future expansion).
+//! Admin-only routes.
 //!
-//! Most admin routes are currently in protected.rs.
-//! This module can be used to separate admin routes further if needed.
+//! Mounted at `/api/v1/admin`. Authentication is applied by the caller in
+//! `router/mod.rs`; every route here additionally requires the admin role.
+
+use axum::{
+    middleware::from_fn,
+    routing::{delete, get, post, put},
+    Router,
+};
 
-use axum::Router;
 use crate::app_state::AppState;
+use crate::auth::middleware::require_admin_role;
+use crate::handlers::audit_logs;
+use crate::handlers::audit_retention;
+use crate::handlers::dashboard;
+use crate::handlers::delegation;
+use crate::handlers::emission_factors;
+use crate::handlers::erc_issuers;
+use crate::handlers::fx_rates;
+use crate::handlers::markets;
+use crate::handlers::fix_sessions;
+use crate::handlers::invoices;
+use crate::handlers::jobs;
+use crate::handlers::ledger_history;
+use crate::handlers::meter::admin as meter_admin;
+use crate::handlers::meter::firmware as meter_firmware;
+use crate::handlers::meter::{backfill, gateways};
+use crate::handlers::meter::grid_data;
+use crate::handlers::meter::mint_guard;
+use crate::handlers::meter::tokenization;
+use crate::handlers::network_acl;
+use crate::handlers::participants;
+use crate::handlers::partners;
+use crate::handlers::pii_keys;
+use crate::handlers::rate_limits;
+use crate::handlers::referrals;
+use crate::handlers::route_permissions;
+use crate::handlers::transfers;
+use crate::handlers::trading::batches;
+use crate::handlers::trading::delivery;
+use crate::handlers::trading::disputes;
+use crate::handlers::trading::epochs;
+use crate::handlers::trading::halts;
+use crate::handlers::trading::imbalance;
+use crate::handlers::trading::settlement_admin;
+use crate::handlers::trading::trade_admin;
+use crate::handlers::usage;
+use crate::handlers::vesting;
+use crate::handlers::data_fixes;
+use crate::handlers::maintenance;
+use crate::handlers::wallet_links;
+use crate::handlers::websocket::throttle as ws_throttle;
 
 /// Build admin-only routes.
-///
-/// Currently, admin routes are integrated into protected_routes.
-/// This function is provided for future expansion when admin routes
-/// need to be separated into their own module with different middleware.
 pub fn admin_routes() -> Router<AppState> {
     Router::new()
-    // Future: Add admin-specific routes here with different middleware
-    // e.g., IP whitelist, additional audit logging, etc.
+        // Operational overview
+        .route("/overview", get(dashboard::get_admin_overview))
+        // Meter registry management
+        .route("/meters", get(meter_admin::search_meters))
+        .route("/meters/clock-drift", get(meter_admin::get_clock_drift_report))
+        .route("/meters/fleet-health", get(meter_admin::get_fleet_health))
+        .route("/meters/firmware/report", get(meter_firmware::get_firmware_report))
+        .route(
+            "/meters/firmware/policies",
+            get(meter_firmware::list_firmware_policies).put(meter_firmware::set_firmware_policy),
+        )
+        .route("/meters/firmware/policies/{id}", delete(meter_firmware::delete_firmware_policy))
+        // Official DSO interval data and reconciliation
+        .route("/grid-data/intervals", post(grid_data::upload_grid_intervals))
+        .route("/grid-data/discrepancies", get(grid_data::get_discrepancy_report))
+        .route("/grid-data/meters/{serial}/unfreeze", post(grid_data::unfreeze_meter))
+        .route("/meters/{id}/approve", post(meter_admin::approve_meter))
+        .route("/meters/{id}/reject", post(meter_admin::reject_meter))
+        .route("/meters/{id}/suspend", post(meter_admin::suspend_meter))
+        .route("/meters/{id}/history", get(meter_admin::get_meter_history))
+        .route("/meters/{id}/registry-sync", post(meter_admin::sync_meter_registry))
+        .route("/meters/{id}/capacity", put(mint_guard::set_meter_capacity))
+        // Minting guard quarantine and wallet allowances
+        .route("/mint-guard/quarantine", get(mint_guard::list_quarantined_readings))
+        .route("/mint-guard/quarantine/{id}/release", post(mint_guard::release_quarantined_reading))
+        .route("/mint-guard/quarantine/{id}/reject", post(mint_guard::reject_quarantined_reading))
+        .route("/mint-guard/wallets/{wallet_address}", get(mint_guard::get_wallet_mint_allowance))
+        // Tokenization parameters and runtime overrides
+        .route(
+            "/tokenization",
+            get(tokenization::get_tokenization_parameters).put(tokenization::update_tokenization_overrides),
+        )
+        // WebSocket broadcast coalescing windows
+        .route(
+            "/websocket/throttle",
+            get(ws_throttle::get_broadcast_throttle).put(ws_throttle::update_broadcast_throttle),
+        )
+        // AMI gateways (mTLS client certificates)
+        .route("/meter-gateways", get(gateways::list_gateways).post(gateways::register_gateway))
+        .route("/meter-gateways/{id}/revoke", post(gateways::revoke_gateway))
+        .route("/meter-gateways/{id}/meters", put(gateways::assign_gateway_meters))
+        // AMI backfill review
+        .route("/meter-backfills", get(backfill::list_backfills))
+        .route("/meter-backfills/{id}", get(backfill::get_backfill))
+        .route("/meter-backfills/{id}/approve", post(backfill::approve_backfill))
+        .route("/meter-backfills/{id}/reject", post(backfill::reject_backfill))
+        .route("/meter-backfills/{id}/mint", post(backfill::retry_backfill_mint))
+
+        // Meter submission rate limit overrides
+        .route(
+            "/rate-limit-overrides",
+            get(rate_limits::list_overrides).post(rate_limits::create_override),
+        )
+        .route("/rate-limit-overrides/{id}", delete(rate_limits::revoke_override))
+        // Third-party apps for delegated access
+        .route(
+            "/delegated-apps",
+            get(delegation::list_delegated_apps).post(delegation::register_delegated_app),
+        )
+        .route("/delegated-apps/{id}/disable", post(delegation::disable_delegated_app))
+        // Invoicing
+        .route("/invoices/generate", post(invoices::admin_generate_invoices))
+        // Settlement disputes
+        .route("/disputes", get(disputes::admin_list_disputes))
+        .route("/disputes/{id}/review", post(disputes::admin_review_dispute))
+        .route("/disputes/{id}/resolve", post(disputes::admin_resolve_dispute))
+        // Trade busts and manual trades (multi-signature approval)
+        .route("/trades/bust", post(trade_admin::propose_trade_bust))
+        .route("/trades/manual", post(trade_admin::propose_manual_trade))
+        .route("/trades/epoch-rollback", post(trade_admin::propose_epoch_rollback))
+        .route("/trade-approvals", get(trade_admin::list_trade_approvals))
+        .route("/trade-approvals/{id}", get(trade_admin::get_trade_approval))
+        .route("/trade-approvals/{id}/decision", post(trade_admin::decide_trade_approval))
+        // Settlements quarantined by batch failure isolation
+        .route("/settlements/quarantined", get(settlement_admin::list_quarantined_settlements))
+        .route("/settlements/{id}/release", post(settlement_admin::release_quarantined_settlement))
+        // Pending settlement batches
+        .route("/batches/{id}/cancel", post(settlement_admin::cancel_batch))
+        .route(
+            "/batches/{id}/settlements/{settlement_id}/eject",
+            post(settlement_admin::eject_batch_settlement),
+        )
+        // Epoch matching latency
+        .route("/epochs/performance", get(epochs::list_matching_performance))
+        .route("/epochs/{id}/performance", get(epochs::get_matching_performance))
+        // Clearing dry run
+        .route("/epochs/{id}/simulate-clearing", post(epochs::simulate_clearing))
+        // Shadow matcher comparisons
+        .route("/epochs/{id}/shadow-comparisons", get(epochs::get_shadow_comparisons))
+        // Settlement batch statistics and fee payers
+        .route("/batches/stats", get(batches::get_batch_statistics))
+        .route("/batches/fee-payers", get(batches::get_fee_payer_spend))
+        // Physical delivery checks of settled trades
+        .route("/delivery-verifications", get(delivery::list_delivery_verifications))
+        // Imbalance settlements per user and epoch
+        .route("/imbalances", get(imbalance::admin_list_imbalances))
+        // Guarded data repairs (dry run by default)
+        .route("/data-fixes/epoch-stats", post(data_fixes::fix_epoch_stats))
+        .route("/data-fixes/order-fills", post(data_fixes::fix_order_fills))
+        .route("/data-fixes/prepaid-balances", post(data_fixes::fix_prepaid_balances))
+        .route("/data-fixes/stuck-settlements", post(data_fixes::fix_stuck_settlements))
+        // Vesting grant programs
+        .route("/vesting/programs", get(vesting::admin_list_vesting_programs).post(vesting::admin_create_vesting_program))
+        // Referral program
+        .route("/referrals", get(referrals::admin_list_referrals))
+        .route("/referrals/report", get(referrals::admin_referral_report))
+        .route("/referrals/{id}/review", post(referrals::admin_review_referral))
+        .route("/referral-rewards/{id}/retry", post(referrals::admin_retry_referral_reward))
+        // API usage and access anomalies
+        .route("/users/{id}/usage", get(usage::admin_get_user_usage))
+        .route("/users/{id}/wallet-history", get(wallet_links::admin_get_wallet_history))
+        .route("/security/anomalies", get(usage::admin_list_anomalies))
+        .route("/security/wallet-challenges", get(usage::admin_wallet_challenge_report))
+        .route("/security/wallet-blocks/{id}/release", post(usage::admin_release_wallet_block))
+        // Network access control
+        .route("/network-acl", get(network_acl::list_network_rules).post(network_acl::create_network_rule))
+        .route("/network-acl/{id}", delete(network_acl::delete_network_rule))
+        // Audit retention and legal holds
+        .route("/audit/logs", get(audit_logs::search_audit_logs))
+        .route("/audit/retention", get(audit_retention::get_retention_policy))
+        .route("/audit/legal-holds", get(audit_retention::list_legal_holds).post(audit_retention::place_legal_hold))
+        .route("/audit/legal-holds/{id}/release", post(audit_retention::release_legal_hold))
+        // As-of ledger queries
+        .route("/audit/accounts/{id}/as-of", get(ledger_history::get_account_as_of))
+        .route("/audit/certificates/{id}/as-of", get(ledger_history::get_certificate_as_of))
+        // PII encryption keys
+        .route("/pii/keys", get(pii_keys::get_pii_key_status))
+        .route("/pii/keys/rotate", post(pii_keys::rotate_pii_key))
+        // B2B partner onboarding
+        .route("/partners", get(partners::list_partners).post(partners::onboard_partner))
+        // Public participant IDs in market data
+        .route("/participants/{public_id}", get(participants::resolve_participant))
+        // Certificate issuers
+        .route("/erc-issuers", get(erc_issuers::list_issuers).post(erc_issuers::create_issuer))
+        .route("/erc-issuers/{id}", put(erc_issuers::update_issuer))
+        .route("/erc-issuers/{id}/revoke", post(erc_issuers::revoke_issuer))
+        // Settlement currency and FX reference rates
+        .route("/fx-rates", get(fx_rates::get_fx_rates).post(fx_rates::publish_fx_rate))
+        // Grid emission factors for CO2 estimates
+        .route(
+            "/emission-factors",
+            get(emission_factors::list_emission_factors).post(emission_factors::create_emission_factor),
+        )
+        .route(
+            "/emission-factors/{id}",
+            put(emission_factors::update_emission_factor).delete(emission_factors::delete_emission_factor),
+        )
+        // Markets
+        .route("/markets", post(markets::create_market))
+        .route("/markets/{id}", put(markets::update_market))
+        .route("/markets/{id}/status", put(markets::set_market_status))
+        .route("/markets/{id}/book-snapshot", get(markets::get_book_snapshot))
+        // Trading halts per market, zone or platform-wide
+        .route("/trading-halts", get(halts::list_trading_halts).post(halts::create_trading_halt))
+        .route("/trading-halts/{id}/resume-at", put(halts::reschedule_trading_halt))
+        .route("/trading-halts/{id}/lift", post(halts::lift_trading_halt))
+        // FIX gateway sessions
+        .route("/fix/sessions", get(fix_sessions::list_sessions).post(fix_sessions::create_session))
+        .route("/fix/sessions/{id}/sequence", put(fix_sessions::reset_sequence))
+        .route("/fix/sessions/{id}/active", put(fix_sessions::set_session_active))
+        // Planned maintenance published on the platform status
+        .route(
+            "/maintenance",
+            get(maintenance::list_maintenance_windows).post(maintenance::schedule_maintenance),
+        )
+        // Background rebuilds of caches and aggregates
+        .route(
+            "/maintenance/rebuild",
+            get(maintenance::list_rebuilds).post(maintenance::start_rebuild),
+        )
+        .route("/maintenance/rebuild/{id}", get(maintenance::get_rebuild))
+        .route("/maintenance/{id}", delete(maintenance::cancel_maintenance))
+        // Background job runs across subsystems
+        .route("/jobs", get(jobs::list_jobs))
+        .route("/jobs/summary", get(jobs::get_jobs_summary))
+        .route("/jobs/{id}", get(jobs::get_job))
+        .route("/jobs/{id}/logs", get(jobs::get_job_logs))
+        .route("/jobs/{id}/cancel", post(jobs::cancel_job))
+        .route("/jobs/{id}/retry", post(jobs::retry_job))
+        // Fee sponsorship programs and ledger
+        .route(
+            "/fee-sponsorship/programs",
+            get(transfers::list_sponsorship_programs).post(transfers::create_sponsorship_program),
+        )
+        .route("/fee-sponsorship/programs/{id}", put(transfers::update_sponsorship_program))
+        .route("/fee-sponsorship/programs/{id}/members", post(transfers::add_sponsorship_member))
+        .route(
+            "/fee-sponsorship/programs/{id}/members/{user_id}",
+            delete(transfers::remove_sponsorship_member),
+        )
+        .route("/fee-sponsorship/ledger", get(transfers::list_fee_sponsorships))
+        // Route permission matrix
+        .route("/routes", get(route_permissions::list_route_permissions))
+        .layer(from_fn(require_admin_role))
 }
@@ -0,0 +1,514 @@
+//! Developer API changelog.
+//!
+//! One entry per route change, newest first, kept next to the routes so a
+//! change and its changelog entry ship together. Served on the platform
+//! status endpoint for partner integrations.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// What happened to a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiChangeKind {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+}
+
+/// A change to one route
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiChange {
+    #[schema(value_type = String, format = Date)]
+    pub date: &'static str,
+    pub method: &'static str,
+    pub path: &'static str,
+    pub kind: ApiChangeKind,
+    pub summary: &'static str,
+    /// Date a deprecated route stops working
+    #[schema(value_type = Option<String>, format = Date)]
+    pub sunset: Option<&'static str>,
+}
+
+const fn change(
+    date: &'static str,
+    method: &'static str,
+    path: &'static str,
+    kind: ApiChangeKind,
+    summary: &'static str,
+) -> ApiChange {
+    ApiChange { date, method, path, kind, summary, sunset: None }
+}
+
+/// Route changes, newest first
+pub const API_CHANGES: &[ApiChange] = &[
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/wallets/balances",
+        ApiChangeKind::Changed,
+        "Requires authentication; single wallet balance lookups stay public",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/dashboard/metrics",
+        ApiChangeKind::Changed,
+        "Requires authentication",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/dev/faucet",
+        ApiChangeKind::Changed,
+        "Requires authentication, as do the sandbox airdrop, user and scenario endpoints",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/rpc",
+        ApiChangeKind::Changed,
+        "The Solana RPC proxy requires authentication",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/admin/trades/epoch-rollback",
+        ApiChangeKind::Added,
+        "Propose rolling back a cleared epoch whose settlements are unsubmitted; once approved its settlements are cancelled and its orders reopen in the next epoch",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/admin/trade-approvals/{id}",
+        ApiChangeKind::Changed,
+        "Includes the step-by-step record of an executed epoch rollback",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/trading/orders",
+        ApiChangeKind::Changed,
+        "Returns 429 with Retry-After when the caller already has the maximum order requests in flight; exports and analytics have their own in-flight caps",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/readyz",
+        ApiChangeKind::Changed,
+        "Returns 503 until the cold-start warmup has finished or timed out; its steps are listed under warmup",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/admin/partners",
+        ApiChangeKind::Added,
+        "Onboard a B2B partner with scoped API keys, webhook endpoints, sandbox users and a rate-limit tier in one call",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/admin/audit/logs",
+        ApiChangeKind::Changed,
+        "Requires a user, actor, event type, resource type, country or ASN filter, or a time range of at most 31 days; \
+         searches over their time limit fail with QUERY_TIMEOUT",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/analytics/transactions",
+        ApiChangeKind::Changed,
+        "Runs under the analytics statement timeout; slow histories fail with QUERY_TIMEOUT (422)",
+    ),
+    change(
+        "2026-01-18",
+        "PUT",
+        "/api/v1/admin/websocket/throttle",
+        ApiChangeKind::Added,
+        "Read and change the coalescing windows of grid status, order book and market statistics broadcasts",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/dashboard/metrics",
+        ApiChangeKind::Changed,
+        "Served from a read model refreshed after each event processor cycle; includes generated_at",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/exports",
+        ApiChangeKind::Added,
+        "Request a CSV or ZIP export of readings, trades or audit records, built in the background",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/exports",
+        ApiChangeKind::Added,
+        "Export history with the remaining daily quota",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/exports/{id}/download-url",
+        ApiChangeKind::Added,
+        "Short-lived signed download link of a ready export",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/public/profile",
+        ApiChangeKind::Changed,
+        "Includes the market timezone; daily rollups, statements and report periods follow its calendar",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/public/market/daily",
+        ApiChangeKind::Added,
+        "Immutable daily market rollups: volume, clearing price range, participant counts and minted energy",
+    ),
+    change(
+        "2026-01-18",
+        "PUT",
+        "/api/v1/admin/tokenization",
+        ApiChangeKind::Added,
+        "Read the tokenization parameters in effect and replace their validated runtime overrides",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/readyz",
+        ApiChangeKind::Changed,
+        "Lists email, cache and metrics state under subsystems; a subsystem down at runtime sets degraded",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/trading/market/epochs/{id}/my-participation",
+        ApiChangeKind::Added,
+        "The caller's orders, fills, clearing allocation, fees and settlement status in an epoch",
+    ),
+    change(
+        "2026-01-18",
+        "PUT",
+        "/api/v1/meters/{serial}/installation",
+        ApiChangeKind::Added,
+        "Read and update a meter's rated capacity, panel orientation, installation date and inverter",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/meters",
+        ApiChangeKind::Changed,
+        "Accepts an optional validated `installation` with capacity, orientation and inverter details",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/admin/mint-guard/quarantine",
+        ApiChangeKind::Added,
+        "Review readings held for exceeding their wallet's hourly or daily minting cap, and release or reject them",
+    ),
+    change(
+        "2026-01-18",
+        "PUT",
+        "/api/v1/admin/meters/{id}/capacity",
+        ApiChangeKind::Added,
+        "Set a meter's rated capacity, from which per-wallet minting caps are derived",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/meters/readings/{reading_id}/mint",
+        ApiChangeKind::Changed,
+        "Refuses readings over the wallet's minting cap with 403, quarantining them for admin review",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/admin/jobs",
+        ApiChangeKind::Added,
+        "List background job runs across subsystems, with per-run logs and a per-kind summary",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/admin/jobs/{id}/retry",
+        ApiChangeKind::Added,
+        "Cancel queued or running background jobs and retry failed ones",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/analytics/report-subscriptions",
+        ApiChangeKind::Added,
+        "Subscribe to daily trading, weekly generation and monthly carbon reports delivered by email (CSV or PDF) or signed webhook",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/analytics/admin/report-subscriptions",
+        ApiChangeKind::Added,
+        "List report subscriptions of all users",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/transfers",
+        ApiChangeKind::Added,
+        "Energy token transfers with platform-paid fees when sponsored; GET /api/v1/transfers/sponsorship previews the decision",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/user-wallets/sessions/revoke-all",
+        ApiChangeKind::Added,
+        "Revoke every wallet session of the user; GET /api/v1/user-wallets/sessions lists the active ones",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/trading/orders",
+        ApiChangeKind::Changed,
+        "Orders with a session_token must come from the device that created the wallet session (X-Device-Fingerprint and User-Agent)",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/public/meters/backfills",
+        ApiChangeKind::Added,
+        "AMI gateways declare a backfill window and submit signed readings, minted after admin approval",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/time",
+        ApiChangeKind::Added,
+        "Server time with sequence for client clock sync; WebSocket feeds send heartbeats and answer pings",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/markets/{id}/depth-chart",
+        ApiChangeKind::Added,
+        "Bucketed cumulative depth per side with last clearing price and price band",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/trading/orders/dust",
+        ApiChangeKind::Added,
+        "Order remainders cancelled as dust, totalled per market",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/markets",
+        ApiChangeKind::Changed,
+        "Markets report max_order_kwh and dust_threshold_kwh",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/analytics/transactions/export",
+        ApiChangeKind::Added,
+        "Stream the filtered on-chain operation history as CSV",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/analytics/transactions",
+        ApiChangeKind::Changed,
+        "Filter by date range and signature search, page with cursor / next_cursor",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/blockchain/transactions",
+        ApiChangeKind::Changed,
+        "Signature search and cursor / next_cursor paging",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/swap/pools/{id}/stats",
+        ApiChangeKind::Added,
+        "TVL, volume, fee APR and price history of a swap pool",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/notifications/budget-alerts",
+        ApiChangeKind::Added,
+        "Alerts on monthly spend, daily consumption or clearing price delivered to the notification inbox",
+    ),
+    change(
+        "2026-01-18",
+        "PUT",
+        "/api/v1/trading/autopilot",
+        ApiChangeKind::Added,
+        "Opt in to autopilot sell orders for forecast surplus each epoch",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/trading/autopilot/performance",
+        ApiChangeKind::Added,
+        "Autopilot fill rate and realized price compared with manual sell orders",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/trading/orderbook",
+        ApiChangeKind::Changed,
+        "Other participants' orders carry a rotating public participant ID and omit meter, PDA and session fields",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/analytics/market",
+        ApiChangeKind::Changed,
+        "Top traders are named by rotating public participant IDs",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/market/ws",
+        ApiChangeKind::Changed,
+        "User IDs and wallets in feed events are replaced by rotating public participant IDs",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/public/profile",
+        ApiChangeKind::Added,
+        "Deployment branding, token mint, program IDs and enabled modules",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/auth/token",
+        ApiChangeKind::Changed,
+        "Risky logins (new country or device) answer 401 with a step-up challenge instead of a token",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/auth/token/step-up",
+        ApiChangeKind::Added,
+        "Completes a risky login with the one-time code sent by email",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/trading/market/session",
+        ApiChangeKind::Changed,
+        "Adds the trading halts in force",
+    ),
+    change(
+        "2026-01-18",
+        "*",
+        "/api/v1",
+        ApiChangeKind::Changed,
+        "Error bodies add code_name, a stable symbolic error code, and a retryable hint",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/status",
+        ApiChangeKind::Changed,
+        "Adds current epoch, market session, planned maintenance and recent API changes",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/status/changelog",
+        ApiChangeKind::Added,
+        "Developer API changelog, filterable by date and path",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/trading/imbalances",
+        ApiChangeKind::Added,
+        "Per-epoch imbalance between traded and metered net export",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/markets/{id}/maker-incentives",
+        ApiChangeKind::Added,
+        "Market maker scores and daily rebates",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/markets",
+        ApiChangeKind::Added,
+        "Market registry with per-market parameters",
+    ),
+    change(
+        "2026-01-18",
+        "POST",
+        "/api/v1/account/wallet/challenge",
+        ApiChangeKind::Added,
+        "Signature challenge required before linking a wallet",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/public/market/orderbook",
+        ApiChangeKind::Added,
+        "Public order book without an account, rate limited per IP",
+    ),
+    change(
+        "2026-01-18",
+        "GET",
+        "/api/v1/public/market/candles",
+        ApiChangeKind::Added,
+        "Public clearing price candles",
+    ),
+];
+
+/// Changes on or after `since` whose path starts with `path`
+pub fn changes(since: Option<NaiveDate>, path: Option<&str>) -> Vec<ApiChange> {
+    API_CHANGES
+        .iter()
+        .filter(|c| {
+            since.is_none_or(|since| {
+                NaiveDate::parse_from_str(c.date, "%Y-%m-%d").is_ok_and(|date| date >= since)
+            })
+        })
+        .filter(|c| path.is_none_or(|path| c.path.starts_with(path)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changelog_dates_are_valid_and_newest_first() {
+        let dates: Vec<NaiveDate> = API_CHANGES
+            .iter()
+            .map(|c| NaiveDate::parse_from_str(c.date, "%Y-%m-%d").expect("valid date"))
+            .collect();
+        assert!(dates.windows(2).all(|w| w[0] >= w[1]));
+        for change in API_CHANGES {
+            if let Some(sunset) = change.sunset {
+                assert!(NaiveDate::parse_from_str(sunset, "%Y-%m-%d").is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_changes_filter_by_path_prefix() {
+        assert!(changes(None, Some("/api/v1/public")).iter().all(|c| c.path.starts_with("/api/v1/public")));
+        assert!(changes(NaiveDate::from_ymd_opt(2100, 1, 1), None).is_empty());
+    }
+}
@@ -1,9 +1,13 @@
 use axum::{routing::post, Router};
 use crate::handlers::dev::faucet::request_faucet;
+use crate::handlers::dev::sandbox::{create_sandbox_user, run_scenario, sandbox_airdrop};
 use crate::AppState;
 
-/// Dev routes (faucet, etc.)
+/// Dev routes (faucet, sandbox)
 pub fn dev_routes() -> Router<AppState> {
     Router::new()
         .route("/faucet", post(request_faucet))
+        .route("/sandbox/airdrop", post(sandbox_airdrop))
+        .route("/sandbox/users", post(create_sandbox_user))
+        .route("/sandbox/scenarios", post(run_scenario))
 }
@@ -0,0 +1,402 @@
+//! API documentation routes.
+//!
+//! Swagger UI at `/api/docs`, exposed publicly, to admins only, or not at
+//! all depending on `API_DOCS`. One spec is generated per audience (public,
+//! user, AMI integration, admin) from the route metadata, so integrators only
+//! see relevant endpoints and the admin surface is never served publicly.
+//!
+//! Each spec is served at a versioned URL that changes whenever the spec
+//! does, so it can be cached indefinitely; `/api/docs/{audience}/openapi.json`
+//! redirects to the current version.
+
+use std::collections::BTreeSet;
+
+use axum::{
+    http::{header, HeaderValue},
+    middleware::{self, from_fn},
+    response::{IntoResponse, Redirect},
+    routing::get,
+    Router,
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use utoipa::openapi::OpenApi;
+use utoipa_swagger_ui::{SwaggerUi, Url};
+
+use crate::app_state::AppState;
+use crate::auth::middleware::{auth_middleware, require_admin_role};
+use crate::config::DocsExposure;
+use crate::middleware::admin_network_acl;
+
+/// Stable spec URL, kept for existing clients; redirects to the public spec
+const LATEST_SPEC_URL: &str = "/api/docs/openapi.json";
+
+const HTTP_METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Paths reserved for AMI gateways, with everything below them
+const AMI_PATHS: [&str; 3] = [
+    "/api/v1/meters/batch/readings",
+    "/api/meters/submit-reading",
+    "/api/v1/public/meters/backfills",
+];
+
+/// Internal surface: admin console, developer and test tooling, metrics
+const ADMIN_PREFIXES: [&str; 7] = [
+    "/api/v1/admin",
+    "/api/admin",
+    "/api/v1/dev",
+    "/api/dev",
+    "/api/test",
+    "/metrics",
+    "/health/metrics",
+];
+
+/// Reachable without an account
+const PUBLIC_PREFIXES: [&str; 5] = ["/health", "/api/health", "/readyz", "/api/v1/status", "/api/v1/public"];
+
+/// Account endpoints; operations here without a security requirement
+/// (login, registration, password reset) are public
+const ACCOUNT_PREFIXES: [&str; 2] = ["/api/v1/auth", "/api/v1/users"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecAudience {
+    Public,
+    /// Authenticated platform users
+    User,
+    /// AMI gateway integrators
+    Ami,
+    Admin,
+}
+
+impl SpecAudience {
+    pub const ALL: [SpecAudience; 4] = [Self::Public, Self::User, Self::Ami, Self::Admin];
+
+    pub fn slug(self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::User => "user",
+            Self::Ami => "ami",
+            Self::Admin => "admin",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Public => "Public",
+            Self::User => "User",
+            Self::Ami => "AMI Integration",
+            Self::Admin => "Admin",
+        }
+    }
+
+    /// Whether this audience's spec documents operations meant for `other`
+    pub fn includes(self, other: SpecAudience) -> bool {
+        match self {
+            Self::Admin => true,
+            Self::User | Self::Ami => other == self || other == Self::Public,
+            Self::Public => other == Self::Public,
+        }
+    }
+}
+
+fn matches_prefix(path: &str, prefix: &str) -> bool {
+    path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Audience of one operation, from its path, tags and security requirement
+pub fn operation_audience(path: &str, operation: &Value) -> SpecAudience {
+    let tags: Vec<&str> = operation
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    if AMI_PATHS.iter().any(|prefix| matches_prefix(path, prefix)) {
+        return SpecAudience::Ami;
+    }
+    if ADMIN_PREFIXES.iter().any(|prefix| matches_prefix(path, prefix))
+        || tags.iter().any(|tag| {
+            let tag = tag.to_lowercase();
+            tag.starts_with("admin") || tag == "dev" || tag == "testing"
+        })
+    {
+        return SpecAudience::Admin;
+    }
+    if PUBLIC_PREFIXES.iter().any(|prefix| matches_prefix(path, prefix)) {
+        return SpecAudience::Public;
+    }
+
+    let secured = operation
+        .get("security")
+        .and_then(Value::as_array)
+        .is_some_and(|security| !security.is_empty());
+    if !secured && ACCOUNT_PREFIXES.iter().any(|prefix| matches_prefix(path, prefix)) {
+        return SpecAudience::Public;
+    }
+
+    SpecAudience::User
+}
+
+/// Copy of the full spec restricted to one audience: other operations are
+/// dropped, along with the schemas and tags only they referenced
+pub fn scoped_spec(spec: &Value, audience: SpecAudience) -> Value {
+    let mut scoped = spec.clone();
+
+    if let Some(paths) = scoped.get_mut("paths").and_then(Value::as_object_mut) {
+        paths.retain(|path, item| {
+            let Some(item) = item.as_object_mut() else {
+                return false;
+            };
+            item.retain(|key, operation| {
+                !HTTP_METHODS.contains(&key.as_str())
+                    || audience.includes(operation_audience(path, operation))
+            });
+            item.keys().any(|key| HTTP_METHODS.contains(&key.as_str()))
+        });
+    }
+
+    // Keep schemas reachable from the remaining paths
+    let schemas = scoped
+        .pointer("/components/schemas")
+        .cloned()
+        .unwrap_or(Value::Null);
+    let mut reachable = BTreeSet::new();
+    let mut pending: Vec<String> = Vec::new();
+    collect_schema_refs(scoped.get("paths").unwrap_or(&Value::Null), &mut pending);
+    while let Some(name) = pending.pop() {
+        if reachable.insert(name.clone()) {
+            if let Some(schema) = schemas.get(&name) {
+                collect_schema_refs(schema, &mut pending);
+            }
+        }
+    }
+    if let Some(schemas) = scoped
+        .pointer_mut("/components/schemas")
+        .and_then(Value::as_object_mut)
+    {
+        schemas.retain(|name, _| reachable.contains(name));
+    }
+
+    // Keep tags still in use
+    let mut used_tags = BTreeSet::new();
+    if let Some(paths) = scoped.get("paths").and_then(Value::as_object) {
+        for item in paths.values().filter_map(Value::as_object) {
+            for operation in item.values() {
+                for tag in operation.get("tags").and_then(Value::as_array).into_iter().flatten() {
+                    if let Some(tag) = tag.as_str() {
+                        used_tags.insert(tag.to_string());
+                    }
+                }
+            }
+        }
+    }
+    if let Some(tags) = scoped.get_mut("tags").and_then(Value::as_array_mut) {
+        tags.retain(|tag| {
+            tag.get("name")
+                .and_then(Value::as_str)
+                .is_some_and(|name| used_tags.contains(name))
+        });
+    }
+
+    if let Some(title) = scoped.pointer_mut("/info/title") {
+        if let Some(scoped_title) = title.as_str().map(|base| format!("{} ({})", base, audience.title())) {
+            *title = Value::String(scoped_title);
+        }
+    }
+
+    scoped
+}
+
+fn collect_schema_refs(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("$ref", Value::String(reference)) => {
+                        if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+                            refs.push(name.to_string());
+                        }
+                    }
+                    _ => collect_schema_refs(value, refs),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_schema_refs(value, refs)),
+        _ => {}
+    }
+}
+
+/// Versioned spec URL: package version plus a digest of the spec
+pub fn versioned_spec_url(audience: SpecAudience, spec_json: &str) -> String {
+    let digest = Sha256::digest(spec_json.as_bytes());
+    format!(
+        "/api/docs/{}/openapi-{}-{}.json",
+        audience.slug(),
+        env!("CARGO_PKG_VERSION"),
+        &hex::encode(digest)[..12]
+    )
+}
+
+/// Versioned spec route plus no-cache redirects from the stable URLs
+fn spec_routes(latest_urls: &[&str], spec_url: String, spec_json: String) -> Router<AppState> {
+    let mut routes = Router::new().route(
+        &spec_url,
+        get(move || {
+            let spec_json = spec_json.clone();
+            async move {
+                (
+                    [
+                        (header::CONTENT_TYPE, HeaderValue::from_static("application/json")),
+                        (
+                            header::CACHE_CONTROL,
+                            HeaderValue::from_static("public, max-age=31536000, immutable"),
+                        ),
+                    ],
+                    spec_json,
+                )
+            }
+        }),
+    );
+
+    for latest_url in latest_urls {
+        let target = spec_url.clone();
+        routes = routes.route(
+            latest_url,
+            get(move || {
+                let target = target.clone();
+                async move {
+                    (
+                        [(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"))],
+                        Redirect::temporary(&target),
+                    )
+                        .into_response()
+                }
+            }),
+        );
+    }
+    routes
+}
+
+fn admin_only(routes: Router<AppState>, app_state: &AppState) -> Router<AppState> {
+    routes
+        .layer(from_fn(require_admin_role))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), admin_network_acl))
+}
+
+/// Build the documentation routes for the configured exposure.
+pub fn docs_routes(app_state: &AppState, openapi: OpenApi) -> Router<AppState> {
+    let exposure = app_state.config.docs.exposure;
+    if exposure == DocsExposure::Disabled {
+        tracing::info!("📕 API docs disabled");
+        return Router::new();
+    }
+
+    let full_spec = match serde_json::to_value(&openapi) {
+        Ok(spec) => spec,
+        Err(e) => {
+            tracing::error!("Failed to serialize OpenAPI spec, docs disabled: {}", e);
+            return Router::new();
+        }
+    };
+
+    let mut open_routes: Router<AppState> = Router::new();
+    let mut admin_routes: Router<AppState> = Router::new();
+    let mut ui_urls = Vec::new();
+
+    for audience in SpecAudience::ALL {
+        let spec_json = scoped_spec(&full_spec, audience).to_string();
+        let spec_url = versioned_spec_url(audience, &spec_json);
+        let latest_url = format!("/api/docs/{}/openapi.json", audience.slug());
+
+        if audience == SpecAudience::Admin {
+            // Only listed in the UI when the UI itself is admin-only
+            if exposure == DocsExposure::Admin {
+                ui_urls.push(Url::new(audience.title(), spec_url.clone()));
+            }
+            admin_routes = admin_routes.merge(spec_routes(&[latest_url.as_str()], spec_url, spec_json));
+        } else {
+            ui_urls.push(Url::new(audience.title(), spec_url.clone()));
+            let mut latest_urls = vec![latest_url.as_str()];
+            if audience == SpecAudience::Public {
+                latest_urls.push(LATEST_SPEC_URL);
+            }
+            open_routes = open_routes.merge(spec_routes(&latest_urls, spec_url, spec_json));
+        }
+    }
+    tracing::info!("📘 API docs at /api/docs ({:?})", exposure);
+
+    let swagger = SwaggerUi::new("/api/docs").config(utoipa_swagger_ui::Config::new(ui_urls));
+    let open_routes = open_routes.merge(swagger);
+
+    match exposure {
+        DocsExposure::Admin => admin_only(open_routes.merge(admin_routes), app_state),
+        _ => open_routes.merge(admin_only(admin_routes, app_state)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_versioned_spec_url_tracks_content() {
+        let a = versioned_spec_url(SpecAudience::Public, r#"{"openapi":"3.1.0"}"#);
+        let b = versioned_spec_url(SpecAudience::Public, r#"{"openapi":"3.1.0","paths":{}}"#);
+
+        assert!(a.starts_with(&format!("/api/docs/public/openapi-{}-", env!("CARGO_PKG_VERSION"))));
+        assert!(a.ends_with(".json"));
+        assert_ne!(a, b);
+        assert_eq!(a, versioned_spec_url(SpecAudience::Public, r#"{"openapi":"3.1.0"}"#));
+    }
+
+    #[test]
+    fn test_operation_audience() {
+        let secured = json!({ "tags": ["auth"], "security": [{ "bearer_auth": [] }] });
+        let open = json!({ "tags": ["auth"] });
+
+        assert_eq!(operation_audience("/api/v1/auth/token", &open), SpecAudience::Public);
+        assert_eq!(operation_audience("/api/v1/auth/change-password", &secured), SpecAudience::User);
+        assert_eq!(operation_audience("/api/v1/admin/users", &open), SpecAudience::Admin);
+        assert_eq!(operation_audience("/api/v1/meters/batch/readings", &open), SpecAudience::Ami);
+        assert_eq!(operation_audience("/api/v1/public/meters/backfills/{id}/readings", &open), SpecAudience::Ami);
+        assert_eq!(operation_audience("/api/v1/trading/orders", &open), SpecAudience::User);
+        assert_eq!(operation_audience("/api/v1/publicity", &open), SpecAudience::User);
+    }
+
+    #[test]
+    fn test_scoped_spec_drops_admin_surface() {
+        let spec = json!({
+            "info": { "title": "GridTokenX API" },
+            "tags": [{ "name": "status" }, { "name": "admin" }],
+            "paths": {
+                "/api/v1/status": { "get": { "tags": ["status"], "responses": {
+                    "200": { "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Status" } } } }
+                } } },
+                "/api/v1/admin/users": { "get": { "tags": ["admin"], "responses": {
+                    "200": { "content": { "application/json": { "schema": { "$ref": "#/components/schemas/AdminUser" } } } }
+                } } }
+            },
+            "components": { "schemas": {
+                "Status": { "properties": { "detail": { "$ref": "#/components/schemas/Detail" } } },
+                "Detail": {},
+                "AdminUser": {}
+            } }
+        });
+
+        let public = scoped_spec(&spec, SpecAudience::Public);
+        assert!(public.pointer("/paths/~1api~1v1~1admin~1users").is_none());
+        assert!(public.pointer("/components/schemas/AdminUser").is_none());
+        assert!(public.pointer("/components/schemas/Detail").is_some());
+        assert_eq!(public["tags"], json!([{ "name": "status" }]));
+        assert_eq!(public["info"]["title"], "GridTokenX API (Public)");
+
+        let admin = scoped_spec(&spec, SpecAudience::Admin);
+        assert!(admin.pointer("/paths/~1api~1v1~1admin~1users").is_some());
+        assert!(admin.pointer("/components/schemas/AdminUser").is_some());
+    }
+}
@@ -6,10 +6,14 @@ use axum::{routing::{get, post}, Router, extract::State, middleware};
 use tower::ServiceBuilder;
 use tower_http::{cors::CorsLayer, timeout::TimeoutLayer, trace::TraceLayer};
 use utoipa::OpenApi;
-use utoipa_swagger_ui::SwaggerUi;
 
+pub mod admin;
+pub mod changelog;
 pub mod dev;
+mod docs;
+pub mod permissions;
 pub mod public;
+pub mod public_market;
 
 use crate::app_state::AppState;
 use crate::handlers::{
@@ -18,7 +22,12 @@ use crate::handlers::{
     v1_trading_routes, v1_dashboard_routes,
 };
 use crate::auth::middleware::auth_middleware;
-use crate::middleware::{metrics_middleware, active_requests_middleware};
+use crate::middleware::{
+    metrics_middleware, active_requests_middleware, admin_network_acl, ami_gateway_auth, ami_network_acl,
+    api_usage_middleware, concurrency_limit, feature_gate, locale_middleware, meter_rate_limit_middleware,
+    public_rate_limit,
+};
+use crate::config::Feature;
 
 /// OpenAPI documentation for GridTokenX API
 #[derive(OpenApi)]
@@ -33,10 +42,12 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
         (name = "users", description = "User management"),
         (name = "trading", description = "P2P Energy Trading"),
         (name = "meters", description = "Smart Meter management"),
-        (name = "dev", description = "Developer tools")
+        (name = "dev", description = "Developer tools"),
+        (name = "market-data", description = "Public market data (no auth, rate limited per IP)")
     ),
     paths(
         crate::handlers::auth::login::login,
+        crate::handlers::auth::login::verify_login_step_up,
         crate::handlers::auth::login::verify_email,
         crate::handlers::auth::registration::register,
         crate::handlers::auth::registration::resend_verification,
@@ -44,6 +55,7 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
         crate::handlers::auth::password_reset::forgot_password,
         crate::handlers::auth::password_reset::reset_password,
         crate::handlers::auth::password_reset::change_password,
+        crate::handlers::auth::security::security_overview,
         crate::handlers::auth::meters::get_my_meters,
         crate::handlers::auth::meters::get_registered_meters,
         crate::handlers::auth::meters::register_meter,
@@ -53,45 +65,300 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
         crate::handlers::auth::meters::create_reading,
         crate::handlers::auth::meters::get_my_readings,
         crate::handlers::trading::orders::create::create_order,
+        crate::handlers::trading::orders::bulk::import_orders,
+        crate::handlers::trading::orders::bulk::export_orders,
+        crate::handlers::trading::orders::wait::wait_for_order,
+        crate::handlers::trading::orders::routing::route_order,
+        crate::handlers::trading::orders::routing::list_execution_reports,
+        crate::handlers::trading::orders::routing::get_execution_report,
         crate::handlers::trading::orders::queries::get_user_orders,
         crate::handlers::trading::orders::management::cancel_order,
         crate::handlers::trading::orders::management::update_order,
         crate::handlers::trading::orders::queries::get_order_book,
         crate::handlers::trading::orders::queries::get_my_trades,
+        crate::handlers::trading::orders::queries::get_order_events,
+        crate::handlers::trading::orders::queries::get_order_dust,
         crate::handlers::trading::orders::queries::get_token_balance,
         crate::handlers::trading::blockchain::get_blockchain_market_data,
+        crate::handlers::blockchain::info::get_account_info,
+        crate::handlers::blockchain::history::get_transaction_history,
+        crate::handlers::blockchain::history::export_transaction_history,
+        crate::handlers::address_book::list_addresses,
+        crate::handlers::address_book::add_address,
+        crate::handlers::address_book::rename_address,
+        crate::handlers::address_book::remove_address,
+        crate::handlers::address_book::new_address_challenge,
+        crate::handlers::address_book::verify_address,
+        crate::handlers::address_book::check_recipient,
+        crate::handlers::address_book::record_address_use,
         crate::handlers::trading::blockchain::match_blockchain_orders,
+        crate::handlers::trading::epochs::get_epoch_curves,
+        crate::handlers::trading::epochs::get_my_epoch_participation,
+        crate::handlers::trading::epochs::list_matching_performance,
+        crate::handlers::trading::epochs::get_matching_performance,
+        crate::handlers::trading::epochs::simulate_clearing,
+        crate::handlers::trading::epochs::get_shadow_comparisons,
+        crate::handlers::trading::batches::get_batch_statistics,
+        crate::handlers::trading::batches::get_fee_payer_spend,
+        crate::handlers::trading::delivery::list_delivery_verifications,
+        crate::handlers::trading::imbalance::list_my_imbalances,
+        crate::handlers::trading::autopilot::get_autopilot,
+        crate::handlers::trading::autopilot::update_autopilot,
+        crate::handlers::trading::autopilot::list_autopilot_orders,
+        crate::handlers::trading::autopilot::get_autopilot_performance,
+        crate::handlers::budget_alerts::list_budget_alerts,
+        crate::handlers::budget_alerts::create_budget_alert,
+        crate::handlers::budget_alerts::update_budget_alert,
+        crate::handlers::budget_alerts::delete_budget_alert,
+        crate::handlers::trading::imbalance::admin_list_imbalances,
+        crate::handlers::data_fixes::fix_epoch_stats,
+        crate::handlers::data_fixes::fix_order_fills,
+        crate::handlers::data_fixes::fix_prepaid_balances,
+        crate::handlers::data_fixes::fix_stuck_settlements,
+        crate::handlers::trading::session::get_market_session,
         crate::handlers::auth::wallets::token_balance,
+        crate::handlers::auth::wallets::batch_balances,
+        crate::handlers::wallets::list_wallet_sessions,
+        crate::handlers::wallets::revoke_all_wallet_sessions,
         crate::handlers::auth::status::system_status,
         crate::handlers::auth::status::meter_status,
         crate::handlers::auth::status::readiness_probe,
+        crate::handlers::auth::status::readyz,
+        crate::handlers::auth::status::event_processor_health,
+        crate::handlers::auth::status::server_time,
         crate::handlers::auth::status::liveness_probe,
+        crate::handlers::auth::status::api_changelog,
+        crate::handlers::transfers::create_transfer,
+        crate::handlers::transfers::get_sponsorship_quote,
+        crate::handlers::transfers::list_sponsorship_programs,
+        crate::handlers::transfers::create_sponsorship_program,
+        crate::handlers::transfers::update_sponsorship_program,
+        crate::handlers::transfers::add_sponsorship_member,
+        crate::handlers::transfers::remove_sponsorship_member,
+        crate::handlers::transfers::list_fee_sponsorships,
+        crate::handlers::maintenance::list_maintenance_windows,
+        crate::handlers::maintenance::schedule_maintenance,
+        crate::handlers::maintenance::cancel_maintenance,
+        crate::handlers::maintenance::start_rebuild,
+        crate::handlers::maintenance::list_rebuilds,
+        crate::handlers::maintenance::get_rebuild,
+        crate::handlers::jobs::list_jobs,
+        crate::handlers::jobs::get_jobs_summary,
+        crate::handlers::jobs::get_job,
+        crate::handlers::jobs::get_job_logs,
+        crate::handlers::jobs::cancel_job,
+        crate::handlers::jobs::retry_job,
+        crate::handlers::route_permissions::list_route_permissions,
+        crate::handlers::ledger_history::get_account_as_of,
+        crate::handlers::ledger_history::get_certificate_as_of,
+        crate::handlers::pii_keys::get_pii_key_status,
+        crate::handlers::pii_keys::rotate_pii_key,
+        crate::handlers::participants::resolve_participant,
         crate::handlers::analytics::market::get_market_analytics,
+        crate::handlers::analytics::market::get_market_vwap,
+        crate::handlers::analytics::market::get_market_spread,
+        crate::handlers::analytics::market::get_market_liquidity,
+        crate::handlers::analytics::market::get_clearing_price_history,
+        crate::handlers::analytics::market::get_reference_index,
         crate::handlers::analytics::user::get_user_trading_stats,
         crate::handlers::analytics::user::get_user_wealth_history,
+        crate::handlers::analytics::user::get_user_performance_report,
+        crate::handlers::analytics::user::export_user_performance_report,
         crate::handlers::analytics::user::get_user_transactions,
+        crate::handlers::analytics::user::export_user_transactions,
+        crate::handlers::analytics::leaderboard::get_leaderboard,
+        crate::handlers::analytics::leaderboard::update_leaderboard_preferences,
         crate::handlers::analytics::admin::get_admin_stats,
         crate::handlers::analytics::admin::get_admin_activity,
         crate::handlers::analytics::admin::get_system_health,
         crate::handlers::analytics::admin::get_zone_economic_insights,
+        crate::handlers::analytics::reports::list_report_subscriptions,
+        crate::handlers::analytics::reports::create_report_subscription,
+        crate::handlers::analytics::reports::update_report_subscription,
+        crate::handlers::analytics::reports::delete_report_subscription,
+        crate::handlers::analytics::reports::list_report_deliveries,
+        crate::handlers::analytics::reports::send_report_now,
+        crate::handlers::analytics::reports::list_all_report_subscriptions,
         crate::handlers::meter::stub::get_meter_readings,
         crate::handlers::meter::stub::get_meter_trends,
         crate::handlers::meter::stub::get_meter_health,
         crate::handlers::meter::get_zones,
         crate::handlers::meter::get_zone_stats,
+        crate::handlers::meter::admin::search_meters,
+        crate::handlers::meter::admin::approve_meter,
+        crate::handlers::meter::admin::reject_meter,
+        crate::handlers::meter::admin::suspend_meter,
+        crate::handlers::meter::admin::get_meter_history,
+        crate::handlers::meter::admin::sync_meter_registry,
+        crate::handlers::meter::admin::get_clock_drift_report,
+        crate::handlers::meter::admin::get_fleet_health,
+        crate::handlers::meter::gateways::list_gateways,
+        crate::handlers::meter::gateways::register_gateway,
+        crate::handlers::meter::gateways::revoke_gateway,
+        crate::handlers::meter::gateways::assign_gateway_meters,
+        crate::handlers::meter::backfill::declare_backfill,
+        crate::handlers::meter::backfill::get_gateway_backfill,
+        crate::handlers::meter::backfill::submit_backfill_readings,
+        crate::handlers::meter::backfill::list_backfills,
+        crate::handlers::meter::backfill::get_backfill,
+        crate::handlers::meter::backfill::approve_backfill,
+        crate::handlers::meter::backfill::reject_backfill,
+        crate::handlers::meter::backfill::retry_backfill_mint,
+        crate::handlers::meter::firmware::list_firmware_policies,
+        crate::handlers::meter::firmware::set_firmware_policy,
+        crate::handlers::meter::firmware::delete_firmware_policy,
+        crate::handlers::meter::firmware::get_firmware_report,
+        crate::handlers::meter::grid_data::upload_grid_intervals,
+        crate::handlers::meter::grid_data::get_discrepancy_report,
+        crate::handlers::meter::grid_data::unfreeze_meter,
+        crate::handlers::meter::mint_guard::list_quarantined_readings,
+        crate::handlers::meter::mint_guard::release_quarantined_reading,
+        crate::handlers::meter::mint_guard::reject_quarantined_reading,
+        crate::handlers::meter::mint_guard::get_wallet_mint_allowance,
+        crate::handlers::meter::mint_guard::set_meter_capacity,
+        crate::handlers::meter::installation::get_meter_installation,
+        crate::handlers::meter::installation::update_meter_installation,
+        crate::handlers::meter::tokenization::get_tokenization_parameters,
+        crate::handlers::meter::tokenization::update_tokenization_overrides,
+        crate::handlers::websocket::throttle::get_broadcast_throttle,
+        crate::handlers::websocket::throttle::update_broadcast_throttle,
+        crate::handlers::invoices::list_invoices,
+        crate::handlers::invoices::generate_invoice,
+        crate::handlers::invoices::get_invoice_download_url,
+        crate::handlers::invoices::download_invoice,
+        crate::handlers::invoices::admin_generate_invoices,
+        crate::handlers::attachments::upload_attachment,
+        crate::handlers::attachments::list_attachments,
+        crate::handlers::attachments::get_attachment_download_url,
+        crate::handlers::attachments::download_attachment,
+        crate::handlers::data_exports::create_data_export,
+        crate::handlers::data_exports::list_data_exports,
+        crate::handlers::data_exports::get_data_export,
+        crate::handlers::data_exports::get_data_export_download_url,
+        crate::handlers::data_exports::download_data_export,
+        crate::handlers::prepaid::get_prepaid_summary,
+        crate::handlers::prepaid::list_topups,
+        crate::handlers::rate_limits::list_overrides,
+        crate::handlers::rate_limits::create_override,
+        crate::handlers::rate_limits::revoke_override,
+        crate::handlers::delegation::list_delegated_apps,
+        crate::handlers::delegation::register_delegated_app,
+        crate::handlers::delegation::disable_delegated_app,
+        crate::handlers::delegation::get_consent,
+        crate::handlers::delegation::submit_consent,
+        crate::handlers::delegation::exchange_token,
+        crate::handlers::delegation::list_grants,
+        crate::handlers::delegation::revoke_grant,
+        crate::handlers::prepaid::create_topup,
+        crate::handlers::prepaid::refund_topup,
+        crate::handlers::prepaid::payment_webhook,
+        crate::handlers::trading::disputes::open_dispute,
+        crate::handlers::trading::disputes::list_my_disputes,
+        crate::handlers::trading::disputes::get_dispute,
+        crate::handlers::trading::disputes::add_dispute_evidence,
+        crate::handlers::trading::disputes::withdraw_dispute,
+        crate::handlers::trading::disputes::admin_list_disputes,
+        crate::handlers::trading::disputes::admin_review_dispute,
+        crate::handlers::trading::disputes::admin_resolve_dispute,
+        crate::handlers::trading::trade_admin::propose_trade_bust,
+        crate::handlers::trading::trade_admin::propose_manual_trade,
+        crate::handlers::trading::trade_admin::propose_epoch_rollback,
+        crate::handlers::trading::trade_admin::list_trade_approvals,
+        crate::handlers::trading::trade_admin::get_trade_approval,
+        crate::handlers::trading::trade_admin::decide_trade_approval,
+        crate::handlers::trading::settlement_admin::list_quarantined_settlements,
+        crate::handlers::trading::settlement_admin::release_quarantined_settlement,
+        crate::handlers::trading::settlement_admin::cancel_batch,
+        crate::handlers::trading::settlement_admin::eject_batch_settlement,
+        crate::handlers::vesting::get_vesting_summary,
+        crate::handlers::vesting::admin_list_vesting_programs,
+        crate::handlers::vesting::admin_create_vesting_program,
+        crate::handlers::referrals::get_referral_summary,
+        crate::handlers::referrals::admin_list_referrals,
+        crate::handlers::referrals::admin_referral_report,
+        crate::handlers::referrals::admin_review_referral,
+        crate::handlers::referrals::admin_retry_referral_reward,
+        crate::handlers::usage::get_my_usage,
+        crate::handlers::locale::get_my_locale,
+        crate::handlers::locale::update_my_locale,
+        crate::handlers::wallet_links::wallet_link_challenge,
+        crate::handlers::wallet_links::get_my_wallet_history,
+        crate::handlers::wallet_links::admin_get_wallet_history,
+        crate::handlers::usage::admin_get_user_usage,
+        crate::handlers::usage::admin_list_anomalies,
+        crate::handlers::usage::admin_wallet_challenge_report,
+        crate::handlers::usage::admin_release_wallet_block,
+        crate::handlers::network_acl::list_network_rules,
+        crate::handlers::network_acl::create_network_rule,
+        crate::handlers::network_acl::delete_network_rule,
+        crate::handlers::audit_logs::search_audit_logs,
+        crate::handlers::public_market::get_public_orderbook,
+        crate::handlers::public_market::get_public_trades,
+        crate::handlers::public_market::get_public_clearing_prices,
+        crate::handlers::public_market::get_public_candles,
+        crate::handlers::public_market::get_public_daily_stats,
+        crate::handlers::deployment::get_deployment_profile,
+        crate::handlers::audit_retention::get_retention_policy,
+        crate::handlers::audit_retention::list_legal_holds,
+        crate::handlers::audit_retention::place_legal_hold,
+        crate::handlers::audit_retention::release_legal_hold,
+        crate::handlers::partners::list_partners,
+        crate::handlers::partners::onboard_partner,
+        crate::handlers::erc_issuers::list_issuers,
+        crate::handlers::erc_issuers::create_issuer,
+        crate::handlers::erc_issuers::update_issuer,
+        crate::handlers::erc_issuers::revoke_issuer,
+        crate::handlers::emission_factors::list_emission_factors,
+        crate::handlers::emission_factors::create_emission_factor,
+        crate::handlers::emission_factors::update_emission_factor,
+        crate::handlers::emission_factors::delete_emission_factor,
+        crate::handlers::fx_rates::get_fx_rates,
+        crate::handlers::fx_rates::publish_fx_rate,
+        crate::handlers::markets::list_markets,
+        crate::handlers::markets::get_market,
+        crate::handlers::markets::get_market_order_book,
+        crate::handlers::markets::get_market_depth_chart,
+        crate::handlers::markets::get_maker_incentives,
+        crate::handlers::swap_pools::get_pool_stats,
+        crate::handlers::markets::create_market,
+        crate::handlers::markets::update_market,
+        crate::handlers::markets::set_market_status,
+        crate::handlers::markets::get_book_snapshot,
+        crate::handlers::trading::halts::list_trading_halts,
+        crate::handlers::trading::halts::create_trading_halt,
+        crate::handlers::trading::halts::reschedule_trading_halt,
+        crate::handlers::trading::halts::lift_trading_halt,
+        crate::handlers::fix_sessions::list_sessions,
+        crate::handlers::fix_sessions::create_session,
+        crate::handlers::fix_sessions::reset_sequence,
+        crate::handlers::fix_sessions::set_session_active,
         crate::handlers::dev::metrics::get_metrics,
+        crate::handlers::dev::faucet::request_faucet,
+        crate::handlers::dev::sandbox::sandbox_airdrop,
+        crate::handlers::dev::sandbox::create_sandbox_user,
+        crate::handlers::dev::sandbox::run_scenario,
         crate::handlers::dashboard::get_dashboard_metrics,
+        crate::handlers::dashboard::get_admin_overview,
     ),
     components(
         schemas(
+            crate::error::FieldError,
             crate::handlers::auth::types::LoginRequest,
             crate::handlers::auth::types::AuthResponse,
+            crate::handlers::auth::types::StepUpVerifyRequest,
+            crate::services::login_step_up::StepUpChallenge,
+            crate::services::login_step_up::RiskSignal,
             crate::handlers::auth::types::UserResponse,
             crate::handlers::auth::types::RegistrationRequest,
             crate::handlers::auth::types::RegistrationResponse,
             crate::handlers::auth::types::VerifyEmailRequest,
             crate::handlers::auth::types::VerifyEmailResponse,
             crate::handlers::auth::types::ResendVerificationRequest,
+            crate::handlers::auth::types::SecurityOverview,
+            crate::handlers::auth::types::RecentLogin,
+            crate::handlers::auth::types::ConnectedWallet,
+            crate::handlers::auth::types::ActiveApiKey,
+            crate::handlers::auth::types::MfaStatus,
             crate::handlers::auth::types::ForgotPasswordRequest,
             crate::handlers::auth::types::ResetPasswordRequest,
             crate::handlers::auth::types::ChangePasswordRequest,
@@ -105,32 +372,136 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
             crate::handlers::auth::types::MeterReadingResponse,
             crate::models::trading::TradingOrder,
             crate::models::trading::CreateOrderRequest,
+            crate::models::trading::RouteOrderRequest,
+            crate::services::order_router::ExecutionReport,
             crate::models::trading::UpdateOrderRequest,
             crate::models::trading::MarketData,
             crate::models::trading::OrderBook,
             crate::models::trading::Trade,
             crate::handlers::trading::types::TradingOrdersResponse,
             crate::handlers::trading::types::CreateOrderResponse,
+            crate::handlers::trading::orders::bulk::OrderImportReport,
+            crate::handlers::trading::orders::bulk::ImportRowError,
+            crate::handlers::trading::orders::bulk::ImportRowResult,
+            crate::handlers::trading::orders::wait::OrderWaitResponse,
+            crate::services::order_events::OrderStatusChange,
             crate::handlers::trading::types::TradingStats,
             crate::handlers::trading::types::BlockchainMarketData,
             crate::handlers::trading::types::CreateBlockchainOrderRequest,
             crate::handlers::trading::types::CreateBlockchainOrderResponse,
             crate::handlers::trading::types::MatchOrdersResponse,
             crate::handlers::trading::types::MarketStats,
+            crate::services::market_clearing::curves::EpochCurves,
+            crate::services::market_clearing::participation::EpochParticipation,
+            crate::services::market_clearing::participation::ParticipationOrder,
+            crate::services::market_clearing::participation::ParticipationFill,
+            crate::services::market_clearing::participation::ParticipationSettlement,
+            crate::services::market_clearing::participation::ParticipationAllocation,
+            crate::services::market_clearing::participation::ParticipationFees,
+            crate::services::market_clearing::participation::ParticipationSettlementStatus,
+            crate::services::market_clearing::performance::EpochMatchingPerformance,
+            crate::services::market_clearing::simulation::ClearingSimulation,
+            crate::services::market_clearing::simulation::OrderAllocation,
+            crate::services::market_clearing::shadow::ShadowComparison,
+            crate::services::market_clearing::shadow::OrderDivergence,
+            crate::services::settlement::SettlementBatchStatistics,
+            crate::services::settlement::FeePayerSpend,
+            crate::services::delivery_verification::DeliveryVerification,
+            crate::services::imbalance::ImbalanceSettlement,
+            crate::services::autopilot::AutopilotStrategy,
+            crate::services::autopilot::AutopilotSettings,
+            crate::services::autopilot::UpdateAutopilotRequest,
+            crate::services::autopilot::AutopilotOrder,
+            crate::services::autopilot::SellPerformance,
+            crate::services::autopilot::AutopilotPerformance,
+            crate::services::budget_alerts::BudgetAlertKind,
+            crate::services::budget_alerts::BudgetAlert,
+            crate::services::budget_alerts::CreateBudgetAlertRequest,
+            crate::services::budget_alerts::UpdateBudgetAlertRequest,
+            crate::services::report_subscriptions::ReportKind,
+            crate::services::report_subscriptions::ReportScope,
+            crate::services::report_subscriptions::DeliveryChannel,
+            crate::services::report_subscriptions::ReportFormat,
+            crate::services::report_subscriptions::ReportSubscription,
+            crate::services::report_subscriptions::CreatedReportSubscription,
+            crate::services::report_subscriptions::ReportDelivery,
+            crate::services::report_subscriptions::CreateReportSubscriptionRequest,
+            crate::services::report_subscriptions::UpdateReportSubscriptionRequest,
+            crate::services::data_fixes::DataFixReport,
+            crate::services::data_fixes::DataFixChange,
+            crate::handlers::data_fixes::EpochStatsFixRequest,
+            crate::handlers::data_fixes::UserDataFixRequest,
+            crate::handlers::data_fixes::StuckSettlementsFixRequest,
+            crate::services::market_clearing::curves::CurvePoint,
+            crate::services::market_session::MarketSession,
+            crate::services::market_session::SessionState,
+            crate::handlers::trading::session::MarketSessionStatus,
+            crate::services::trading_halts::TradingHalt,
+            crate::services::trading_halts::HaltMode,
+            crate::handlers::trading::halts::CreateTradingHaltRequest,
+            crate::handlers::trading::halts::RescheduleTradingHaltRequest,
             crate::handlers::trading::orders::queries::TradeRecord,
             crate::handlers::trading::orders::queries::TradeHistoryResponse,
+            crate::services::order_events::OrderEvent,
+            crate::services::order_events::OrderEventType,
+            crate::services::order_dust::DustSummary,
+            crate::services::order_dust::MarketDust,
             crate::handlers::trading::orders::queries::TokenBalanceResponse,
             crate::database::schema::types::OrderSide,
             crate::database::schema::types::OrderType,
             crate::database::schema::types::OrderStatus,
+            crate::database::schema::types::EpochStatus,
             crate::handlers::auth::status::HealthResponse,
             crate::handlers::auth::status::ServiceStatus,
             crate::handlers::auth::status::ServiceHealth,
             crate::handlers::auth::status::StatusResponse,
             crate::handlers::auth::status::MeterStatusResponse,
+            crate::handlers::auth::status::EpochSummary,
+            crate::router::changelog::ApiChange,
+            crate::router::changelog::ApiChangeKind,
+            crate::services::maintenance::MaintenanceWindow,
+            crate::services::fee_sponsorship::SponsorshipRule,
+            crate::services::fee_sponsorship::DeclineReason,
+            crate::services::fee_sponsorship::SponsorshipDecision,
+            crate::services::fee_sponsorship::SponsorshipQuote,
+            crate::services::fee_sponsorship::SponsorshipProgram,
+            crate::services::fee_sponsorship::FeeSponsorship,
+            crate::services::token_transfer::TransferReceipt,
+            crate::handlers::transfers::TransferRequest,
+            crate::handlers::transfers::CreateSponsorshipProgramRequest,
+            crate::handlers::transfers::UpdateSponsorshipProgramRequest,
+            crate::handlers::transfers::AddSponsorshipMemberRequest,
+            crate::handlers::maintenance::ScheduleMaintenanceRequest,
+            crate::handlers::maintenance::RebuildRequest,
+            crate::services::maintenance_rebuild::RebuildJob,
+            crate::services::maintenance_rebuild::RebuildTarget,
+            crate::services::jobs::Job,
+            crate::services::jobs::JobKind,
+            crate::services::jobs::JobStatus,
+            crate::services::jobs::LogLevel,
+            crate::services::jobs::JobLog,
+            crate::services::jobs::JobDetail,
+            crate::services::jobs::JobKindSummary,
+            crate::router::permissions::RoutePermission,
+            crate::router::permissions::RouteAccess,
+            crate::router::permissions::RateLimitClass,
+            crate::services::ledger_history::AccountAsOf,
+            crate::services::ledger_history::BalanceAsOf,
+            crate::services::ledger_history::OpenOrderAsOf,
+            crate::services::ledger_history::CertificateAsOf,
+            crate::services::pii::PiiKeyStatus,
             crate::handlers::auth::status::MeterCounts,
             crate::handlers::auth::status::ReadinessResponse,
             crate::handlers::auth::status::CheckResult,
+            crate::handlers::auth::status::ReadyzResponse,
+            crate::startup::report::StartupReport,
+            crate::startup::warmup::WarmupReport,
+            crate::startup::warmup::WarmupStep,
+            crate::services::degradation::Subsystem,
+            crate::services::degradation::SubsystemStatus,
+            crate::services::degradation::SubsystemHealth,
+            crate::startup::report::ComponentReport,
+            crate::startup::report::ComponentStatus,
             crate::handlers::auth::status::LivenessResponse,
             crate::handlers::analytics::types::MarketAnalytics,
             crate::handlers::analytics::types::MarketOverview,
@@ -138,12 +509,41 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
             crate::handlers::analytics::types::PriceStatistics,
             crate::handlers::analytics::types::EnergySourceStats,
             crate::handlers::analytics::types::TraderStats,
+            crate::handlers::analytics::types::VwapPoint,
+            crate::handlers::analytics::types::VwapSeries,
+            crate::handlers::analytics::types::SpreadPoint,
+            crate::handlers::analytics::types::SpreadSeries,
+            crate::handlers::analytics::types::TimeToFillBucket,
+            crate::handlers::analytics::types::LiquidityMetrics,
+            crate::handlers::analytics::types::ClearingPriceHistory,
+            crate::services::price_index::ClearingPricePoint,
+            crate::services::price_index::ReferenceIndex,
+            crate::services::market_analytics::DepthLevel,
+            crate::services::market_analytics::DepthSnapshot,
+            crate::handlers::public_market::PublicTrade,
+            crate::handlers::public_market::Candle,
+            crate::handlers::public_market::CandleSeries,
+            crate::handlers::public_market::MarketDailySeries,
+            crate::services::market_archive::MarketDailyRollup,
+            crate::services::deployment_profile::DeploymentProfile,
+            crate::services::deployment_profile::Branding,
+            crate::utils::MarketTimezone,
+            crate::services::deployment_profile::ProgramIds,
+            crate::handlers::participants::ResolvedParticipant,
             crate::handlers::analytics::types::UserTradingStats,
             crate::handlers::analytics::types::SellerStats,
             crate::handlers::analytics::types::BuyerStats,
             crate::handlers::analytics::types::OverallUserStats,
             crate::handlers::analytics::types::UserWealthHistory,
             crate::handlers::analytics::types::WealthPoint,
+            crate::handlers::analytics::types::PerformancePeriod,
+            crate::handlers::analytics::types::UserPerformanceReport,
+            crate::handlers::analytics::leaderboard::LeaderboardEntry,
+            crate::handlers::analytics::leaderboard::CommunityStats,
+            crate::handlers::analytics::leaderboard::LeaderboardResponse,
+            crate::handlers::analytics::leaderboard::LeaderboardPreferencesRequest,
+            crate::handlers::analytics::leaderboard::LeaderboardPreferencesResponse,
+            crate::services::leaderboard::LeaderboardCategory,
             crate::handlers::analytics::types::UserTransaction,
             crate::handlers::analytics::types::UserTransactionsResponse,
             crate::handlers::analytics::types::ZoneTradeStats,
@@ -151,22 +551,260 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
             crate::handlers::analytics::types::ZoneEconomicInsights,
             crate::handlers::analytics::admin::AdminStatsResponse,
             crate::services::audit_logger::types::AuditEventRecord,
+            crate::services::audit_logger::types::AuditSeverity,
+            crate::services::audit_logger::types::AuditOutcome,
             crate::services::health_check::types::DetailedHealthStatus,
             crate::services::health_check::types::DependencyHealth,
+            crate::services::health_check::types::HealthLevel,
             crate::services::health_check::types::HealthCheckStatus,
             crate::services::health_check::types::SystemMetrics,
             crate::services::dashboard::types::DashboardMetrics,
+            crate::services::admin_overview::AdminOverview,
+            crate::services::admin_overview::MeterOverview,
+            crate::services::admin_overview::TradingOverview,
+            crate::services::admin_overview::MintingOverview,
+            crate::services::admin_overview::PayerOverview,
+            crate::services::admin_overview::ErrorRateOverview,
             crate::services::event_processor::types::EventProcessorStats,
+            crate::services::event_processor::types::EventTypeCounts,
+            crate::services::event_processor::types::EventErrorSample,
+            crate::services::event_processor::types::EventProcessorHealth,
+            crate::services::server_clock::ServerTime,
             crate::handlers::trading::types::OrderBookResponse,
             crate::handlers::trading::types::OrderBookEntry,
             crate::handlers::auth::types::TrendResponse,
             crate::handlers::auth::types::TrendRecord,
             crate::handlers::meter::ZoneSummary,
             crate::handlers::meter::ZoneStats,
+            crate::handlers::meter::admin::AdminMeterRecord,
+            crate::handlers::meter::admin::MeterReviewRequest,
+            crate::handlers::meter::admin::MeterReviewResponse,
+            crate::handlers::meter::admin::MeterHistoryEntry,
+            crate::handlers::meter::admin::MeterClockDrift,
+            crate::services::meter_fleet_health::FleetHealthReport,
+            crate::services::meter_fleet_health::ConnectivityCounts,
+            crate::services::meter_fleet_health::SignatureHealth,
+            crate::services::meter_fleet_health::AnomalyHealth,
+            crate::services::meter_fleet_health::FirmwareCount,
+            crate::services::meter_fleet_health::UnderReportingMeter,
+            crate::services::meter_registry_sync::MeterRegistrySync,
+            crate::services::meter_gateway::MeterGateway,
+            crate::handlers::meter::gateways::RegisterGatewayRequest,
+            crate::handlers::meter::gateways::AssignGatewayMetersRequest,
+            crate::handlers::meter::gateways::AssignGatewayMetersResponse,
+            crate::services::wallet::WalletSession,
+            crate::handlers::wallets::WalletSessionsResponse,
+            crate::handlers::wallets::RevokeWalletSessionsResponse,
+            crate::services::ami_backfill::BackfillBatch,
+            crate::services::ami_backfill::BackfillReading,
+            crate::services::ami_backfill::BackfillSubmission,
+            crate::services::ami_backfill::RejectedBackfillReading,
+            crate::handlers::meter::backfill::DeclareBackfillRequest,
+            crate::handlers::meter::backfill::SubmitBackfillReadingsRequest,
+            crate::handlers::meter::backfill::ReviewBackfillRequest,
+            crate::services::meter_firmware::FirmwareAction,
+            crate::services::meter_firmware::FirmwarePolicy,
+            crate::services::meter_firmware::FirmwareStatus,
+            crate::services::meter_firmware::FirmwareFleetEntry,
+            crate::services::meter_firmware::FirmwareFleetReport,
+            crate::handlers::meter::firmware::SetFirmwarePolicyRequest,
+            crate::services::grid_meter_data::OfficialInterval,
+            crate::services::grid_meter_data::IntervalImportError,
+            crate::services::grid_meter_data::GridDataImportReport,
+            crate::services::grid_meter_data::MeterDiscrepancy,
+            crate::handlers::meter::grid_data::UnfreezeMeterRequest,
+            crate::services::mint_guard::GuardWindow,
+            crate::services::mint_guard::QuarantineStatus,
+            crate::services::mint_guard::MintCaps,
+            crate::services::mint_guard::WalletMintAllowance,
+            crate::services::mint_guard::QuarantinedReading,
+            crate::services::mint_guard::MeterCapacity,
+            crate::handlers::meter::mint_guard::ReviewQuarantineRequest,
+            crate::handlers::meter::mint_guard::SetMeterCapacityRequest,
+            crate::services::meter_installation::MeterInstallation,
+            crate::services::meter_installation::InverterDetails,
+            crate::services::meter_installation::MeterInstallationRecord,
+            crate::config::TokenizationConfig,
+            crate::config::TokenizationOverrides,
+            crate::services::tokenization_settings::EffectiveTokenization,
+            crate::config::BroadcastThrottleConfig,
+            crate::services::websocket::throttle::EffectiveBroadcastThrottle,
+            crate::services::invoicing::Invoice,
+            crate::services::invoicing::SignedDownloadUrl,
+            crate::handlers::invoices::GenerateInvoiceRequest,
+            crate::handlers::invoices::GenerateInvoicesResponse,
+            crate::services::attachments::Attachment,
+            crate::services::attachments::AttachmentDownload,
+            crate::services::attachments::AttachmentOwner,
+            crate::services::data_exports::ExportDataset,
+            crate::services::data_exports::ExportFormat,
+            crate::services::data_exports::DataExport,
+            crate::services::data_exports::CreateDataExportRequest,
+            crate::services::data_exports::ExportQuota,
+            crate::services::data_exports::DataExportList,
+            crate::handlers::blockchain::AccountInfo,
+            crate::handlers::blockchain::TokenAccountInfo,
+            crate::handlers::blockchain::TransactionHistoryEntry,
+            crate::handlers::blockchain::TransactionHistoryResponse,
+            crate::services::blockchain::DecodedInstruction,
+            crate::services::address_book::AddressBookEntry,
+            crate::services::address_book::RecipientCheck,
+            crate::handlers::address_book::AddAddressRequest,
+            crate::handlers::address_book::RenameAddressRequest,
+            crate::handlers::address_book::VerifyAddressRequest,
+            crate::handlers::address_book::RecordUseRequest,
+            crate::handlers::auth::types::BatchBalanceRequest,
+            crate::handlers::auth::types::BatchBalanceResponse,
+            crate::services::wallet::WalletBalance,
+            crate::services::payments::PrepaidTopUp,
+            crate::services::payments::PrepaidLedgerEntry,
+            crate::services::rate_limiter::RateLimitOverride,
+            crate::services::rate_limiter::CreateRateLimitOverride,
+            crate::services::rate_limiter::PrincipalType,
+            crate::services::delegation::DelegatedApp,
+            crate::services::delegation::RegisterAppRequest,
+            crate::services::delegation::RegisteredApp,
+            crate::services::delegation::AuthorizationRequest,
+            crate::services::delegation::ConsentScope,
+            crate::services::delegation::ConsentDetails,
+            crate::services::delegation::ConsentDecision,
+            crate::services::delegation::ConsentRedirect,
+            crate::services::delegation::TokenRequest,
+            crate::services::delegation::DelegatedTokenResponse,
+            crate::services::delegation::DelegationGrant,
+            crate::services::payments::PrepaidRefund,
+            crate::services::payments::TopUpCheckout,
+            crate::handlers::prepaid::PrepaidSummary,
+            crate::handlers::prepaid::CreateTopUpRequest,
+            crate::handlers::prepaid::RefundTopUpRequest,
+            crate::handlers::prepaid::WebhookAck,
+            crate::services::dispute::SettlementDispute,
+            crate::services::dispute::DisputeEvidence,
+            crate::services::dispute::LedgerAdjustment,
+            crate::services::dispute::DisputeDetail,
+            crate::services::dispute::DisputeReason,
+            crate::services::dispute::DisputeStatus,
+            crate::services::dispute::DisputeOutcome,
+            crate::services::dispute::EvidenceKind,
+            crate::handlers::trading::disputes::EvidenceInput,
+            crate::handlers::trading::disputes::OpenDisputeRequest,
+            crate::handlers::trading::disputes::ResolveDisputeRequest,
+            crate::services::trade_admin::TradeAction,
+            crate::services::trade_admin::TradeApprovalRequest,
+            crate::services::trade_admin::TradeApprovalDetail,
+            crate::services::trade_admin::EpochRollback,
+            crate::services::trade_admin::ApprovalVote,
+            crate::services::trade_admin::ApprovalStatus,
+            crate::services::trade_admin::ApprovalDecision,
+            crate::handlers::trading::trade_admin::ProposeTradeBustRequest,
+            crate::handlers::trading::trade_admin::ProposeManualTradeRequest,
+            crate::handlers::trading::trade_admin::ProposeEpochRollbackRequest,
+            crate::handlers::trading::trade_admin::ApprovalDecisionRequest,
+            crate::services::settlement::QuarantinedSettlement,
+            crate::handlers::trading::settlement_admin::ReleaseSettlementResponse,
+            crate::services::settlement::BatchChange,
+            crate::services::settlement::RequeuePriority,
+            crate::handlers::trading::settlement_admin::EjectSettlementRequest,
+            crate::services::vesting::VestingProgram,
+            crate::services::vesting::VestingSchedule,
+            crate::services::vesting::VestingSummary,
+            crate::handlers::vesting::CreateVestingProgramRequest,
+            crate::services::referral::Referral,
+            crate::services::referral::UserReferral,
+            crate::services::referral::ReferralReward,
+            crate::services::referral::ReferralSummary,
+            crate::services::referral::ReferralReport,
+            crate::services::referral::TopReferrer,
+            crate::services::referral::ReferralStatus,
+            crate::services::referral::FraudFlag,
+            crate::handlers::referrals::ReviewReferralRequest,
+            crate::services::api_usage::UsageReport,
+            crate::handlers::locale::LocalePreference,
+            crate::handlers::locale::UpdateLocaleRequest,
+            crate::handlers::wallet_links::WalletLinkChallengeRequest,
+            crate::services::wallet_links::WalletLinkChallenge,
+            crate::services::wallet_links::WalletLink,
+            crate::i18n::Locale,
+            crate::services::api_usage::EndpointUsage,
+            crate::services::api_usage::DailyUsage,
+            crate::services::api_usage::SecurityAnomaly,
+            crate::services::api_usage::AnomalyKind,
+            crate::services::wallet_challenges::WalletChallengeReport,
+            crate::services::wallet_challenges::WalletFailureSummary,
+            crate::services::wallet_challenges::FailureCount,
+            crate::services::wallet_challenges::WalletAuthBlock,
+            crate::services::network_acl::NetworkRule,
+            crate::services::network_acl::RouteGroup,
+            crate::services::network_acl::RuleAction,
+            crate::handlers::network_acl::NetworkAclOverview,
+            crate::handlers::network_acl::CreateNetworkRuleRequest,
+            crate::services::audit_retention::AuditClass,
+            crate::services::audit_retention::ClassRetention,
+            crate::services::audit_retention::RetentionPolicy,
+            crate::services::audit_retention::LegalHold,
+            crate::handlers::audit_retention::PlaceLegalHoldRequest,
+            crate::handlers::audit_retention::ReleaseLegalHoldRequest,
+            crate::services::erc::ErcIssuer,
+            crate::handlers::erc_issuers::CreateIssuerRequest,
+            crate::handlers::erc_issuers::UpdateIssuerRequest,
+            crate::services::emission_factors::EmissionFactor,
+            crate::services::emission_factors::FactorProvenance,
+            crate::handlers::emission_factors::EmissionFactorRequest,
+            crate::handlers::emission_factors::EmissionFactorOverview,
+            crate::services::fx_rates::CurrencyOverview,
+            crate::services::fx_rates::FxQuote,
+            crate::services::fx_rates::FxRateRecord,
+            crate::handlers::fx_rates::PublishFxRateRequest,
+            crate::services::markets::Market,
+            crate::services::markets::MarketOrderBook,
+            crate::services::markets::MarketDepthChart,
+            crate::services::markets::DepthChartPoint,
+            crate::services::markets::PriceBand,
+            crate::services::book_snapshots::OrderBookSnapshot,
+            crate::services::markets::MarketType,
+            crate::services::markets::MarketStatus,
+            crate::services::maker_incentives::MakerIncentiveBoard,
+            crate::services::swap_pool_stats::StatsInterval,
+            crate::services::swap_pool_stats::PoolStatsPoint,
+            crate::services::swap_pool_stats::PoolStats,
+            crate::services::maker_incentives::MakerScore,
+            crate::handlers::dev::faucet::FaucetRequest,
+            crate::handlers::dev::faucet::FaucetResponse,
+            crate::handlers::dev::sandbox::SandboxAirdropRequest,
+            crate::handlers::dev::sandbox::SandboxAirdropResponse,
+            crate::handlers::dev::sandbox::ScenarioStep,
+            crate::handlers::dev::sandbox::RunScenarioRequest,
+            crate::handlers::dev::sandbox::ScenarioFailure,
+            crate::handlers::dev::sandbox::ScenarioResult,
+            crate::services::sandbox::SandboxCluster,
+            crate::services::sandbox::TestUserSpec,
+            crate::services::sandbox::TestUser,
+            crate::services::partners::PartnerTier,
+            crate::services::partners::RateLimitTier,
+            crate::services::partners::PartnerApiKeySpec,
+            crate::services::partners::PartnerWebhookSpec,
+            crate::services::partners::OnboardPartnerRequest,
+            crate::services::partners::PartnerOrganization,
+            crate::services::partners::IssuedPartnerKey,
+            crate::services::partners::ProvisionedWebhook,
+            crate::services::partners::OnboardingBundle,
+            crate::handlers::markets::CreateMarketRequest,
+            crate::handlers::markets::UpdateMarketRequest,
+            crate::handlers::markets::SetMarketStatusRequest,
+            crate::services::fix_sessions::FixSession,
+            crate::services::fix_sessions::FixSessionType,
+            crate::handlers::fix_sessions::CreateFixSessionRequest,
+            crate::handlers::fix_sessions::ResetFixSequenceRequest,
+            crate::handlers::fix_sessions::SetFixSessionActiveRequest,
         )
     )
 )]
-struct ApiDoc;
+pub(crate) struct ApiDoc;
+
+/// The full API spec as JSON, for route metadata
+pub(crate) fn api_spec() -> serde_json::Value {
+    serde_json::to_value(ApiDoc::openapi()).unwrap_or(serde_json::Value::Null)
+}
 
 /// Build the application router with both v1 and legacy routes.
 pub fn build_router(app_state: AppState) -> Router {
@@ -174,12 +812,17 @@ pub fn build_router(app_state: AppState) -> Router {
     let health = Router::new()
         .route("/health", get(health_check))
         .route("/api/health", get(health_check))
+        .route("/readyz", get(crate::handlers::auth::status::readyz))
+        .route("/health/event-processor", get(crate::handlers::auth::status::event_processor_health))
+        .route("/api/time", get(crate::handlers::auth::status::server_time))
         .route("/metrics", get(crate::handlers::dev::metrics::get_metrics));
 
-    // Meter reading submission (auth required)
+    // Meter reading submission (auth required, AMI networks only)
     let meter_submit = Router::new()
         .route("/api/meters/submit-reading", post(crate::handlers::meter::submit_reading))
-        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+        .layer(middleware::from_fn_with_state(app_state.clone(), meter_rate_limit_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), ami_network_acl));
 
     // WebSocket endpoints
     let ws = Router::new()
@@ -187,20 +830,20 @@ pub fn build_router(app_state: AppState) -> Router {
         .route("/ws/{*channel}", get(crate::handlers::websocket::handlers::websocket_channel_handler))
         .route("/api/market/ws", get(crate::handlers::websocket::handlers::market_websocket_handler));
 
-    // Swagger UI
-    let swagger = SwaggerUi::new("/api/docs")
-        .url("/api/docs/openapi.json", ApiDoc::openapi());
-
     // =========================================================================
     // V1 RESTful API Routes (New)
     // =========================================================================
     let trading_routes = v1_trading_routes()
-        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+        .layer(middleware::from_fn_with_state(app_state.clone(), concurrency_limit))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .layer(middleware::from_fn_with_state((app_state.clone(), Feature::Trading), feature_gate));
 
     let analytics_routes = crate::handlers::analytics::routes()
+        .layer(middleware::from_fn_with_state(app_state.clone(), concurrency_limit))
         .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
 
     let meters_routes = v1_meters_routes()
+        .layer(middleware::from_fn_with_state(app_state.clone(), meter_rate_limit_middleware))
         .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
 
     // Public routes (no auth required)
@@ -208,19 +851,60 @@ pub fn build_router(app_state: AppState) -> Router {
         .route("/meters", get(crate::handlers::auth::meters::public_get_meters))
         .route("/grid-status", get(crate::handlers::auth::meters::public_grid_status))
         .route("/grid-status/history", get(crate::handlers::auth::meters::public_grid_history))
-        .route("/meters/batch/readings", post(crate::handlers::auth::meters::create_batch_readings));
+        .route("/profile", get(crate::handlers::deployment::get_deployment_profile))
+        .merge(
+            // AMI batch ingestion (gateway client certificate, AMI networks only)
+            Router::new()
+                .route("/meters/batch/readings", post(crate::handlers::auth::meters::create_batch_readings))
+                .route("/meters/backfills", post(crate::handlers::meter::backfill::declare_backfill))
+                .route("/meters/backfills/{id}", get(crate::handlers::meter::backfill::get_gateway_backfill))
+                .route(
+                    "/meters/backfills/{id}/readings",
+                    post(crate::handlers::meter::backfill::submit_backfill_readings),
+                )
+
+                .layer(middleware::from_fn_with_state(app_state.clone(), meter_rate_limit_middleware))
+                .layer(middleware::from_fn_with_state(app_state.clone(), ami_gateway_auth))
+                .layer(middleware::from_fn_with_state(app_state.clone(), ami_network_acl)),
+        )
+        .nest(
+            // Market data (anonymous, rate limited per IP)
+            "/market",
+            public_market::public_market_routes()
+                .layer(middleware::from_fn_with_state(app_state.clone(), public_rate_limit))
+                .layer(middleware::from_fn_with_state((app_state.clone(), Feature::PublicMarket), feature_gate)),
+        );
 
     // Simulator routes (auth required for meter registration)
     let simulator_routes = Router::new()
         .route("/meters/register", post(crate::handlers::meter::stub::register_meter_by_id))
         .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
 
+    // Market registry and per-market orderbooks (auth required)
+    let markets_routes = Router::new()
+        .route("/", get(crate::handlers::markets::list_markets))
+        .route("/{id}", get(crate::handlers::markets::get_market))
+        .route("/{id}/orderbook", get(crate::handlers::markets::get_market_order_book))
+        .route("/{id}/depth-chart", get(crate::handlers::markets::get_market_depth_chart))
+        .route("/{id}/maker-incentives", get(crate::handlers::markets::get_maker_incentives))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .layer(middleware::from_fn_with_state((app_state.clone(), Feature::Trading), feature_gate));
+
+    // Swap pool statistics (auth required)
+    let swap_routes = Router::new()
+        .route("/pools/{id}/stats", get(crate::handlers::swap_pools::get_pool_stats))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .layer(middleware::from_fn_with_state((app_state.clone(), Feature::Trading), feature_gate));
+
     // Notifications routes (auth required)
     let notifications_routes = Router::new()
         .route("/", get(crate::handlers::notifications::list_notifications))
         .route("/{id}/read", axum::routing::put(crate::handlers::notifications::mark_as_read))
         .route("/read-all", axum::routing::put(crate::handlers::notifications::mark_all_as_read))
+        .route("/unread-count", get(crate::handlers::notifications::get_unread_count))
         .route("/preferences", get(crate::handlers::notifications::get_preferences).put(crate::handlers::notifications::update_preferences))
+        .route("/budget-alerts", get(crate::handlers::budget_alerts::list_budget_alerts).post(crate::handlers::budget_alerts::create_budget_alert))
+        .route("/budget-alerts/{id}", axum::routing::put(crate::handlers::budget_alerts::update_budget_alert).delete(crate::handlers::budget_alerts::delete_budget_alert))
         .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
 
     // User wallets management routes (auth required)
@@ -228,23 +912,165 @@ pub fn build_router(app_state: AppState) -> Router {
         .route("/", get(crate::handlers::wallets::list_wallets).post(crate::handlers::wallets::link_wallet))
         .route("/{id}", axum::routing::delete(crate::handlers::wallets::remove_wallet))
         .route("/{id}/primary", axum::routing::put(crate::handlers::wallets::set_primary_wallet))
+        .route("/sessions", get(crate::handlers::wallets::list_wallet_sessions))
+        .route("/sessions/revoke-all", post(crate::handlers::wallets::revoke_all_wallet_sessions))
         .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
 
+    // Saved transfer beneficiaries (auth required)
+    let address_book_routes = Router::new()
+        .route("/", get(crate::handlers::address_book::list_addresses).post(crate::handlers::address_book::add_address))
+        .route("/check", get(crate::handlers::address_book::check_recipient))
+        .route(
+            "/{id}",
+            axum::routing::patch(crate::handlers::address_book::rename_address)
+                .delete(crate::handlers::address_book::remove_address),
+        )
+        .route("/{id}/challenge", post(crate::handlers::address_book::new_address_challenge))
+        .route("/{id}/verify", post(crate::handlers::address_book::verify_address))
+        .route("/{id}/use", post(crate::handlers::address_book::record_address_use))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+
+    // Token transfers with sponsored fees (auth required)
+    let transfers_routes = Router::new()
+        .route("/", post(crate::handlers::transfers::create_transfer))
+        .route("/sponsorship", get(crate::handlers::transfers::get_sponsorship_quote))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+
+    // Invoice routes (auth required, except the signed download link)
+    let invoices_routes = Router::new()
+        .route("/", get(crate::handlers::invoices::list_invoices))
+        .route("/generate", post(crate::handlers::invoices::generate_invoice))
+        .route("/{id}/download-url", get(crate::handlers::invoices::get_invoice_download_url))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .route("/{id}/download", get(crate::handlers::invoices::download_invoice))
+        .layer(middleware::from_fn_with_state((app_state.clone(), Feature::Invoicing), feature_gate));
+
+    // Attachment routes (auth required, except the signed download link)
+    let attachments_routes = Router::new()
+        .route("/", get(crate::handlers::attachments::list_attachments).post(crate::handlers::attachments::upload_attachment))
+        .route("/{id}/download-url", get(crate::handlers::attachments::get_attachment_download_url))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .route("/{id}/download", get(crate::handlers::attachments::download_attachment));
+
+    // Data export routes (auth required, except the signed download link)
+    let exports_routes = Router::new()
+        .route("/", get(crate::handlers::data_exports::list_data_exports).post(crate::handlers::data_exports::create_data_export))
+        .route("/{id}", get(crate::handlers::data_exports::get_data_export))
+        .route("/{id}/download-url", get(crate::handlers::data_exports::get_data_export_download_url))
+        .layer(middleware::from_fn_with_state(app_state.clone(), concurrency_limit))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .route("/{id}/download", get(crate::handlers::data_exports::download_data_export));
+
+    // Prepaid credit routes (auth required)
+    let prepaid_routes = Router::new()
+        .route("/", get(crate::handlers::prepaid::get_prepaid_summary))
+        .route("/topups", get(crate::handlers::prepaid::list_topups).post(crate::handlers::prepaid::create_topup))
+        .route("/topups/{id}/refund", post(crate::handlers::prepaid::refund_topup))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .layer(middleware::from_fn_with_state((app_state.clone(), Feature::Prepaid), feature_gate));
+
+    // Token vesting schedules (auth required)
+    let vesting_routes = Router::new()
+        .route("/", get(crate::handlers::vesting::get_vesting_summary))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .layer(middleware::from_fn_with_state((app_state.clone(), Feature::Vesting), feature_gate));
+
+    // Referral code and rewards (auth required)
+    let referral_routes = Router::new()
+        .route("/", get(crate::handlers::referrals::get_referral_summary))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .layer(middleware::from_fn_with_state((app_state.clone(), Feature::Referrals), feature_gate));
+
+    // On-chain account lookups (auth required)
+    let blockchain_routes = Router::new()
+        .route("/accounts/{address}", get(crate::handlers::blockchain::info::get_account_info))
+        .route("/transactions", get(crate::handlers::blockchain::history::get_transaction_history))
+        .route("/transactions/export", get(crate::handlers::blockchain::history::export_transaction_history))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+
+    // Account security overview (auth required)
+    let auth_security_routes = Router::new()
+        .route("/security-overview", get(crate::handlers::auth::security::security_overview))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+
+    // Account API usage, locale preference and wallet linking (auth required)
+    let account_routes = Router::new()
+        .route("/usage", get(crate::handlers::usage::get_my_usage))
+        .route(
+            "/locale",
+            get(crate::handlers::locale::get_my_locale).put(crate::handlers::locale::update_my_locale),
+        )
+        .route("/wallet/challenge", post(crate::handlers::wallet_links::wallet_link_challenge))
+        .route("/wallet/history", get(crate::handlers::wallet_links::get_my_wallet_history))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+
+    // Batch wallet balances (auth required); single balance lookups stay public
+    let wallets_routes = v1_wallets_routes().merge(
+        Router::new()
+            .route("/balances", post(crate::handlers::auth::wallets::batch_balances))
+            .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware)),
+    );
+
+    // Dashboard metrics, sandbox tooling and the Solana RPC proxy (auth required)
+    let dashboard_routes = v1_dashboard_routes()
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+    let dev_routes = dev::dev_routes()
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+    let rpc_routes = Router::new()
+        .route("/rpc", post(crate::handlers::rpc::rpc_handler))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+
+    // Third-party app consent and grant management (auth required); the
+    // token exchange is authenticated by the app's client secret
+    let oauth_routes = Router::new()
+        .route(
+            "/authorize",
+            get(crate::handlers::delegation::get_consent).post(crate::handlers::delegation::submit_consent),
+        )
+        .route("/grants", get(crate::handlers::delegation::list_grants))
+        .route("/grants/{id}", axum::routing::delete(crate::handlers::delegation::revoke_grant))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .route("/token", post(crate::handlers::delegation::exchange_token));
+
+    // Payment provider webhooks (signature-verified, no auth)
+    let payments_routes = Router::new()
+        .route("/webhook/{provider}", post(crate::handlers::prepaid::payment_webhook));
+
+    // Admin routes (admin networks only, auth + admin role required)
+    let admin_routes = admin::admin_routes()
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), admin_network_acl));
+
     let v1_api = Router::new()
-        .nest("/auth", v1_auth_routes())       // POST /api/v1/auth/token, GET /api/v1/auth/verify
+        .nest("/auth", v1_auth_routes().merge(auth_security_routes)) // POST /api/v1/auth/token, GET /api/v1/auth/verify
         .nest("/users", v1_users_routes())     // POST /api/v1/users, GET /api/v1/users/me
         .nest("/meters", meters_routes)        // POST /api/v1/meters, auth required for minting
-        .nest("/wallets", v1_wallets_routes()) // GET /api/v1/wallets/{address}/balance (legacy)
+        .nest("/wallets", wallets_routes)      // GET /api/v1/wallets/{address}/balance (legacy)
         .nest("/user-wallets", user_wallets_routes) // Multi-wallet management
+        .nest("/address-book", address_book_routes) // Saved transfer beneficiaries
+        .nest("/transfers", transfers_routes)  // POST /api/v1/transfers
         .nest("/status", v1_status_routes())   // GET /api/v1/status
         .nest("/trading", trading_routes)      // POST /api/v1/trading/orders
+        .nest("/markets", markets_routes)      // GET /api/v1/markets/{id}/orderbook
+        .nest("/swap", swap_routes)            // GET /api/v1/swap/pools/{id}/stats
         .nest("/analytics", analytics_routes)  // /api/v1/analytics
-        .nest("/dashboard", v1_dashboard_routes()) // /api/v1/dashboard/metrics
+        .nest("/dashboard", dashboard_routes)  // /api/v1/dashboard/metrics
         .nest("/notifications", notifications_routes) // /api/v1/notifications
-        .nest("/dev", dev::dev_routes())       // POST /api/v1/dev/faucet
-        .nest("/public", public_routes)        // GET /api/v1/public/meters (no auth)
+        .nest("/invoices", invoices_routes)    // /api/v1/invoices
+        .nest("/attachments", attachments_routes) // /api/v1/attachments
+        .nest("/exports", exports_routes)      // /api/v1/exports
+        .nest("/prepaid", prepaid_routes)      // /api/v1/prepaid
+        .nest("/vesting", vesting_routes)      // /api/v1/vesting
+        .nest("/referrals", referral_routes)   // /api/v1/referrals
+        .nest("/account", account_routes)      // /api/v1/account/usage, /locale, /wallet
+        .nest("/blockchain", blockchain_routes) // /api/v1/blockchain/accounts, /transactions
+        .nest("/payments", payments_routes)    // /api/v1/payments/webhook/{provider} (no auth)
+        .nest("/oauth", oauth_routes)          // /api/v1/oauth/authorize, /grants, /token
+        .nest("/admin", admin_routes)          // /api/v1/admin (admin role)
+        .nest("/dev", dev_routes)              // POST /api/v1/dev/faucet, /sandbox/*
+        .nest("/public", public_routes)        // GET /api/v1/public/meters, /market/* (no auth)
         .nest("/simulator", simulator_routes)  // POST /api/v1/simulator/meters/register (no auth)
-        .route("/rpc", axum::routing::post(crate::handlers::rpc::rpc_handler)); // /api/v1/rpc
+        .merge(rpc_routes);                    // POST /api/v1/rpc
 
     // Proxy routes implementation (at root /api/*)
     let proxy_routes = Router::new()
@@ -255,13 +1081,17 @@ pub fn build_router(app_state: AppState) -> Router {
         .merge(ws)
         .merge(meter_submit)
         .merge(proxy_routes)
-        .merge(swagger)  // Swagger UI at /api/docs
+        .merge(docs::docs_routes(&app_state, ApiDoc::openapi()))  // Swagger UI at /api/docs
         // V1 API
         .nest("/api/v1", v1_api)
+        // Backstop for protected routes merged without their auth layer
+        .layer(middleware::from_fn(permissions::require_route_credentials))
         .layer(
             ServiceBuilder::new()
                 .layer(middleware::from_fn(metrics_middleware))
                 .layer(middleware::from_fn(active_requests_middleware))
+                .layer(middleware::from_fn(locale_middleware))
+                .layer(middleware::from_fn_with_state(app_state.clone(), api_usage_middleware))
                 .layer(TraceLayer::new_for_http())
                 .layer(TimeoutLayer::with_status_code(
                     axum::http::StatusCode::REQUEST_TIMEOUT,
@@ -290,6 +1120,9 @@ pub fn build_router(app_state: AppState) -> Router {
                             axum::http::header::AUTHORIZATION,
                             axum::http::header::CONTENT_TYPE,
                             axum::http::header::ACCEPT,
+                            axum::http::HeaderName::from_static(
+                                crate::services::wallet::session::DEVICE_FINGERPRINT_HEADER,
+                            ),
                         ])
                         .allow_credentials(true)
                 }),
@@ -0,0 +1,400 @@
+//! Route permission matrix.
+//!
+//! Who may call each route and how it is rate limited, from one rule table
+//! matched against the documented routes. The table mirrors the auth layers
+//! in `router/mod.rs` and `router/admin.rs`; it backs the admin route listing
+//! at `/api/v1/admin/routes` and `require_route_credentials`, a backstop that
+//! turns away requests without any credential before they reach a route the
+//! table marks as protected. A protected route merged without its auth layer
+//! therefore still cannot be called anonymously. The tests below check every
+//! documented route against the backstop, and drive the real router with
+//! invalid and non-admin tokens.
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::auth::request_signing::SIGNATURE_HEADER;
+use crate::error::ApiError;
+use crate::services::concurrency_limiter::ConcurrencyClass;
+
+const HTTP_METHODS: [&str; 5] = ["get", "put", "post", "delete", "patch"];
+
+/// How a route authenticates its caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteAccess {
+    Public,
+    /// Bearer JWT, engineering API key or signed server-to-server request
+    Authenticated,
+    /// Authenticated with the admin role, from admin networks only
+    Admin,
+    /// AMI gateway client certificate, from AMI networks only
+    Gateway,
+    /// Verified by the handler: provider webhook signatures, signed download
+    /// links, third-party app client secrets
+    Signature,
+    /// WebSocket upgrade with a `?token=` JWT
+    SocketToken,
+}
+
+impl RouteAccess {
+    /// Whether the caller must present a credential header
+    pub fn requires_credentials(self) -> bool {
+        matches!(self, Self::Authenticated | Self::Admin)
+    }
+
+    /// Roles allowed to call the route; empty for any caller
+    pub fn roles(self) -> &'static [&'static str] {
+        match self {
+            Self::Admin => &["admin"],
+            Self::Gateway => &["ami"],
+            _ => &[],
+        }
+    }
+}
+
+/// How calls to a route are limited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitClass {
+    /// Fixed window per client IP (`PUBLIC_API_RATE_LIMIT_PER_MINUTE`)
+    PerIp,
+    /// Counted per caller for usage reports and spike detection
+    Metered,
+    Unlimited,
+}
+
+struct RouteRule {
+    /// Any method when `None`
+    method: Option<&'static str>,
+    /// `{param}` matches one segment, a trailing `/*` any rest (or none)
+    pattern: &'static str,
+    access: RouteAccess,
+}
+
+const fn rule(method: Option<&'static str>, pattern: &'static str, access: RouteAccess) -> RouteRule {
+    RouteRule { method, pattern, access }
+}
+
+/// First match wins; anything unmatched is `Authenticated`
+const ROUTE_RULES: &[RouteRule] = &[
+    // Exceptions inside otherwise authenticated groups
+    rule(None, "/api/v1/invoices/{id}/download", RouteAccess::Signature),
+    rule(None, "/api/v1/attachments/{id}/download", RouteAccess::Signature),
+    rule(None, "/api/v1/payments/*", RouteAccess::Signature),
+    rule(Some("POST"), "/api/v1/oauth/token", RouteAccess::Signature),
+    rule(None, "/api/v1/public/meters/batch/readings", RouteAccess::Gateway),
+    rule(None, "/api/v1/public/meters/backfills/*", RouteAccess::Gateway),
+    rule(None, "/api/v1/public/*", RouteAccess::Public),
+    rule(None, "/api/v1/admin/*", RouteAccess::Admin),
+    // Registration, login and password reset
+    rule(Some("POST"), "/api/v1/users", RouteAccess::Public),
+    rule(None, "/api/v1/auth/change-password", RouteAccess::Authenticated),
+    rule(None, "/api/v1/auth/security-overview", RouteAccess::Authenticated),
+    rule(None, "/api/v1/auth/*", RouteAccess::Public),
+    rule(None, "/api/v1/status/*", RouteAccess::Public),
+    rule(Some("GET"), "/api/v1/wallets/{address}/balance", RouteAccess::Public),
+    rule(None, "/api/v1/*", RouteAccess::Authenticated),
+    rule(None, "/api/meters/submit-reading", RouteAccess::Authenticated),
+    rule(None, "/ws/*", RouteAccess::SocketToken),
+    rule(None, "/api/market/ws", RouteAccess::Public),
+    // The admin spec is guarded by its own layers
+    rule(None, "/api/docs/*", RouteAccess::Public),
+    rule(None, "/health/*", RouteAccess::Public),
+    rule(None, "/api/health/*", RouteAccess::Public),
+    rule(None, "/readyz", RouteAccess::Public),
+    rule(None, "/metrics", RouteAccess::Public),
+    rule(None, "/api/time", RouteAccess::Public),
+    rule(None, "/api/zones", RouteAccess::Public),
+    rule(None, "/api/thailand/data", RouteAccess::Public),
+];
+
+fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let mut pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    if pattern.last() == Some(&"*") {
+        pattern.pop();
+        if segments.len() < pattern.len() {
+            return false;
+        }
+        segments.truncate(pattern.len());
+    }
+    pattern.len() == segments.len()
+        && pattern
+            .iter()
+            .zip(&segments)
+            .all(|(p, s)| p == s || (p.starts_with('{') && p.ends_with('}')))
+}
+
+/// Access rule for a request path (concrete or with `{param}` segments)
+pub fn route_access(method: &str, path: &str) -> RouteAccess {
+    ROUTE_RULES
+        .iter()
+        .find(|rule| {
+            rule.method.is_none_or(|m| m.eq_ignore_ascii_case(method)) && matches_pattern(rule.pattern, path)
+        })
+        .map(|rule| rule.access)
+        .unwrap_or(RouteAccess::Authenticated)
+}
+
+pub fn rate_limit_class(path: &str, access: RouteAccess) -> RateLimitClass {
+    if matches_pattern("/api/v1/public/market/*", path) {
+        RateLimitClass::PerIp
+    } else if access.requires_credentials() {
+        RateLimitClass::Metered
+    } else {
+        RateLimitClass::Unlimited
+    }
+}
+
+/// Endpoints with a per-caller in-flight cap; first match wins
+const CONCURRENCY_RULES: &[(&str, &str, ConcurrencyClass)] = &[
+    ("POST", "/api/v1/exports", ConcurrencyClass::Export),
+    ("GET", "/api/v1/analytics/transactions/export", ConcurrencyClass::Export),
+    ("GET", "/api/v1/analytics/my-performance/export", ConcurrencyClass::Export),
+    ("GET", "/api/v1/trading/orders/export", ConcurrencyClass::Export),
+    ("GET", "/api/v1/trading/export/*", ConcurrencyClass::Export),
+    ("GET", "/api/v1/analytics/*", ConcurrencyClass::Analytics),
+    ("POST", "/api/v1/trading/orders", ConcurrencyClass::Orders),
+    ("POST", "/api/v1/trading/orders/import", ConcurrencyClass::Orders),
+    ("POST", "/api/v1/trading/orders/route", ConcurrencyClass::Orders),
+    ("PUT", "/api/v1/trading/orders/{id}", ConcurrencyClass::Orders),
+];
+
+/// Concurrency class of a request, or None when its endpoint has no in-flight cap
+pub fn concurrency_class(method: &str, path: &str) -> Option<ConcurrencyClass> {
+    CONCURRENCY_RULES
+        .iter()
+        .find(|(m, pattern, _)| m.eq_ignore_ascii_case(method) && matches_pattern(pattern, path))
+        .map(|(_, _, class)| *class)
+}
+
+/// One row of the route permission matrix
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RoutePermission {
+    pub method: String,
+    pub path: String,
+    pub access: RouteAccess,
+    /// Roles allowed; empty for any caller with the required access
+    pub roles: Vec<String>,
+    pub rate_limit: RateLimitClass,
+    /// The API docs declare a security requirement
+    pub documented_security: bool,
+}
+
+/// Every documented operation with its access rule, sorted by path and method
+pub fn route_matrix(spec: &Value) -> Vec<RoutePermission> {
+    let mut matrix = Vec::new();
+    for (path, item) in spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        for (method, operation) in item
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(method, _)| HTTP_METHODS.contains(&method.as_str()))
+        {
+            let method = method.to_uppercase();
+            let access = route_access(&method, path);
+            matrix.push(RoutePermission {
+                rate_limit: rate_limit_class(path, access),
+                roles: access.roles().iter().map(|r| r.to_string()).collect(),
+                documented_security: operation
+                    .get("security")
+                    .and_then(Value::as_array)
+                    .is_some_and(|security| !security.is_empty()),
+                method,
+                path: path.clone(),
+                access,
+            });
+        }
+    }
+    matrix.sort_by(|a, b| (&a.path, &a.method).cmp(&(&b.path, &b.method)));
+    matrix
+}
+
+fn has_credentials(request: &Request<Body>) -> bool {
+    let headers = request.headers();
+    headers.contains_key(AUTHORIZATION) || headers.contains_key("x-api-key") || headers.contains_key(SIGNATURE_HEADER)
+}
+
+/// Reject requests without any credential to routes the rule table marks as
+/// protected. Credentials are validated by the auth layers behind it.
+pub async fn require_route_credentials(request: Request<Body>, next: Next) -> Response {
+    if request.method() != Method::OPTIONS
+        && route_access(request.method().as_str(), request.uri().path()).requires_credentials()
+        && !has_credentials(&request)
+    {
+        return ApiError::Unauthorized("Missing or invalid Authorization header".to_string()).into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::ConnectInfo, http::StatusCode, middleware::from_fn, Router};
+    use std::net::SocketAddr;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::auth::{jwt::JwtService, Claims};
+
+    #[test]
+    fn test_route_access() {
+        assert_eq!(route_access("GET", "/api/v1/admin/users"), RouteAccess::Admin);
+        assert_eq!(route_access("POST", "/api/v1/users"), RouteAccess::Public);
+        assert_eq!(route_access("GET", "/api/v1/users/me"), RouteAccess::Authenticated);
+        assert_eq!(route_access("POST", "/api/v1/auth/token"), RouteAccess::Public);
+        assert_eq!(route_access("POST", "/api/v1/auth/change-password"), RouteAccess::Authenticated);
+        assert_eq!(route_access("GET", "/api/v1/invoices/abc/download"), RouteAccess::Signature);
+        assert_eq!(route_access("GET", "/api/v1/invoices/abc/download-url"), RouteAccess::Authenticated);
+        assert_eq!(route_access("GET", "/api/v1/public/market/candles"), RouteAccess::Public);
+        assert_eq!(route_access("GET", "/health"), RouteAccess::Public);
+        assert_eq!(route_access("GET", "/ws"), RouteAccess::SocketToken);
+        assert_eq!(route_access("POST", "/api/v1/oauth/token"), RouteAccess::Signature);
+        assert_eq!(route_access("GET", "/api/v1/oauth/grants"), RouteAccess::Authenticated);
+        assert_eq!(route_access("GET", "/api/v1/wallets/abc/balance"), RouteAccess::Public);
+        assert_eq!(route_access("POST", "/api/v1/wallets/balances"), RouteAccess::Authenticated);
+        assert_eq!(route_access("GET", "/api/v1/dashboard/metrics"), RouteAccess::Authenticated);
+        assert_eq!(route_access("POST", "/api/v1/dev/faucet"), RouteAccess::Authenticated);
+        assert_eq!(route_access("POST", "/api/v1/rpc"), RouteAccess::Authenticated);
+        // Unknown routes are protected by default
+        assert_eq!(route_access("GET", "/api/v1/publicity"), RouteAccess::Authenticated);
+        assert_eq!(route_access("GET", "/api/new-feature"), RouteAccess::Authenticated);
+    }
+
+    #[test]
+    fn test_rate_limit_class() {
+        assert_eq!(rate_limit_class("/api/v1/public/market/trades", RouteAccess::Public), RateLimitClass::PerIp);
+        assert_eq!(rate_limit_class("/api/v1/trading/orders", RouteAccess::Authenticated), RateLimitClass::Metered);
+        assert_eq!(rate_limit_class("/health", RouteAccess::Public), RateLimitClass::Unlimited);
+    }
+
+    #[test]
+    fn test_concurrency_class() {
+        assert_eq!(concurrency_class("POST", "/api/v1/exports"), Some(ConcurrencyClass::Export));
+        assert_eq!(concurrency_class("GET", "/api/v1/exports"), None);
+        assert_eq!(
+            concurrency_class("GET", "/api/v1/analytics/transactions/export"),
+            Some(ConcurrencyClass::Export)
+        );
+        assert_eq!(concurrency_class("GET", "/api/v1/analytics/market"), Some(ConcurrencyClass::Analytics));
+        assert_eq!(concurrency_class("post", "/api/v1/trading/orders"), Some(ConcurrencyClass::Orders));
+        assert_eq!(concurrency_class("GET", "/api/v1/trading/orders"), None);
+    }
+
+    fn spec() -> Value {
+        crate::router::api_spec()
+    }
+
+    #[test]
+    fn test_documented_security_is_never_public() {
+        let exposed: Vec<String> = route_matrix(&spec())
+            .into_iter()
+            .filter(|route| route.documented_security && route.access == RouteAccess::Public)
+            .map(|route| format!("{} {}", route.method, route.path))
+            .collect();
+        assert!(exposed.is_empty(), "documented as secured but public: {:?}", exposed);
+    }
+
+    /// Documented path with its parameters as concrete segments
+    fn concrete_uri(path: &str) -> String {
+        path.split('/')
+            .map(|s| if s.starts_with('{') { "00000000-0000-0000-0000-000000000000" } else { s })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    #[tokio::test]
+    async fn test_protected_routes_reject_anonymous_requests() {
+        let app = Router::new()
+            .fallback(|| async { StatusCode::OK })
+            .layer(from_fn(require_route_credentials));
+
+        for route in route_matrix(&spec()) {
+            let uri = concrete_uri(&route.path);
+            let request = |authorization: Option<&str>| {
+                let mut builder = Request::builder().method(route.method.as_str()).uri(&uri);
+                if let Some(authorization) = authorization {
+                    builder = builder.header(AUTHORIZATION, authorization);
+                }
+                builder.body(Body::empty()).unwrap()
+            };
+
+            let anonymous = app.clone().oneshot(request(None)).await.unwrap().status();
+            if route.access.requires_credentials() {
+                assert_eq!(anonymous, StatusCode::UNAUTHORIZED, "{} {} is reachable anonymously", route.method, route.path);
+                let with_token = app.clone().oneshot(request(Some("Bearer token"))).await.unwrap().status();
+                assert_eq!(with_token, StatusCode::OK, "{} {}", route.method, route.path);
+            } else {
+                assert_eq!(anonymous, StatusCode::OK, "{} {} needs no credential", route.method, route.path);
+            }
+        }
+    }
+
+    /// Protected routes outside the admin group, including the ones that
+    /// used to be served without credentials
+    const AUTHENTICATED_ROUTES: &[(&str, &str)] = &[
+        ("GET", "/api/v1/users/me"),
+        ("GET", "/api/v1/account/usage"),
+        ("GET", "/api/v1/oauth/grants"),
+        ("POST", "/api/v1/wallets/balances"),
+        ("GET", "/api/v1/dashboard/metrics"),
+        ("POST", "/api/v1/dev/faucet"),
+        ("POST", "/api/v1/dev/sandbox/users"),
+        ("POST", "/api/v1/rpc"),
+    ];
+
+    async fn status(app: &Router, method: &str, uri: &str, authorization: &str) -> StatusCode {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(AUTHORIZATION, authorization)
+            .extension(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))))
+            .body(Body::empty())
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires PostgreSQL, Redis and JWT_SECRET
+    async fn test_router_rejects_invalid_and_non_admin_tokens() {
+        let config = crate::config::Config::from_env().expect("Failed to load config");
+        let state = crate::startup::initialize_app(&config).await.expect("Failed to initialize app");
+        let app = crate::router::build_router(state);
+
+        let user = Claims::new(Uuid::new_v4(), "route-matrix-test".to_string(), "user".to_string());
+        let user_token = format!("Bearer {}", JwtService::new().unwrap().encode_token(&user).unwrap());
+
+        for (method, uri) in AUTHENTICATED_ROUTES {
+            assert_eq!(route_access(method, uri), RouteAccess::Authenticated, "{} {}", method, uri);
+            let invalid = status(&app, method, uri, "Bearer not-a-valid-token").await;
+            assert_eq!(invalid, StatusCode::UNAUTHORIZED, "{} {} accepts an invalid token", method, uri);
+        }
+
+        let admin_routes: Vec<RoutePermission> = route_matrix(&spec())
+            .into_iter()
+            .filter(|route| route.access == RouteAccess::Admin)
+            .collect();
+        assert!(!admin_routes.is_empty());
+        for route in admin_routes {
+            let uri = concrete_uri(&route.path);
+            let invalid = status(&app, &route.method, &uri, "Bearer not-a-valid-token").await;
+            assert_eq!(invalid, StatusCode::UNAUTHORIZED, "{} {} accepts an invalid token", route.method, route.path);
+            let non_admin = status(&app, &route.method, &uri, &user_token).await;
+            assert_eq!(non_admin, StatusCode::FORBIDDEN, "{} {} is open to non-admins", route.method, route.path);
+        }
+    }
+}
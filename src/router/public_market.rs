@@ -0,0 +1,21 @@
+//! Public market data routes.
+//!
+//! Mounted at `/api/v1/public/market`. No authentication; anonymous clients
+//! are rate limited per IP by the caller in `router/mod.rs`. Kept apart from
+//! the authenticated trading routes so the group can be served from its own
+//! gateway instances or behind a CDN.
+
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+use crate::handlers::public_market;
+
+/// Build the public market data routes.
+pub fn public_market_routes() -> Router<AppState> {
+    Router::new()
+        .route("/orderbook", get(public_market::get_public_orderbook))
+        .route("/trades", get(public_market::get_public_trades))
+        .route("/clearing-prices", get(public_market::get_public_clearing_prices))
+        .route("/candles", get(public_market::get_public_candles))
+        .route("/daily", get(public_market::get_public_daily_stats))
+}
@@ -0,0 +1,413 @@
+//! Address Book
+//!
+//! Saved beneficiaries for transfers: labelled wallet addresses per user.
+//! An address is verified when its key signs a short-lived challenge, and
+//! the first transfer to an address has to be confirmed explicitly.
+//! Challenges and failed signatures go through the wallet challenge audit.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use rand::{rngs::OsRng, RngCore};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::wallet_challenges::{ChallengeFailure, WalletChallengeService};
+use crate::services::{AuditEvent, AuditLogger};
+use crate::utils::verify_message_signature;
+
+/// How long a verification challenge can be signed
+pub const CHALLENGE_TTL_MINUTES: i64 = 15;
+
+/// Purpose recorded with address verification challenges
+const CHALLENGE_PURPOSE: &str = "address_verification";
+
+/// Saved addresses per user
+pub const MAX_ENTRIES_PER_USER: i64 = 200;
+
+const ENTRY_COLUMNS: &str = "id, label, wallet_address, verification_message, verification_expires_at, \
+    verified_at, first_used_at, last_used_at, created_at";
+
+/// Saved beneficiary
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct AddressBookEntry {
+    pub id: Uuid,
+    pub label: String,
+    pub wallet_address: String,
+    /// Message to sign with the address key to prove ownership
+    pub verification_message: Option<String>,
+    pub verification_expires_at: Option<DateTime<Utc>>,
+    pub verified_at: Option<DateTime<Utc>>,
+    /// When the first transfer to this address was confirmed
+    pub first_used_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AddressBookEntry {
+    pub fn is_verified(&self) -> bool {
+        self.verified_at.is_some()
+    }
+}
+
+/// Whether a transfer to an address needs the user's confirmation
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecipientCheck {
+    pub wallet_address: String,
+    /// Address book entry for the address, if saved
+    pub entry: Option<AddressBookEntry>,
+    pub requires_confirmation: bool,
+    /// Why confirmation is needed
+    pub reason: Option<String>,
+}
+
+/// Challenge text signed to prove ownership of `wallet_address`
+pub fn challenge_message(user_id: Uuid, wallet_address: &str, nonce: &str, expires_at: DateTime<Utc>) -> String {
+    format!(
+        "GRIDTOKENX_ADDRESS_VERIFICATION\nuser: {}\naddress: {}\nnonce: {}\nexpires: {}",
+        user_id,
+        wallet_address,
+        nonce,
+        expires_at.to_rfc3339()
+    )
+}
+
+fn new_nonce() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn validate_label(label: &str) -> Result<String, ApiError> {
+    let label = label.trim();
+    if label.is_empty() || label.chars().count() > 50 {
+        return Err(ApiError::validation_field("label", "Label must be 1-50 characters"));
+    }
+    Ok(label.to_string())
+}
+
+fn parse_address(wallet_address: &str) -> Result<String, ApiError> {
+    Pubkey::from_str(wallet_address.trim())
+        .map(|key| key.to_string())
+        .map_err(|_| ApiError::validation_field("wallet_address", "Invalid Solana wallet address"))
+}
+
+#[derive(Clone)]
+pub struct AddressBookService {
+    db: PgPool,
+    audit: AuditLogger,
+    challenges: WalletChallengeService,
+}
+
+impl AddressBookService {
+    pub fn new(db: PgPool, audit: AuditLogger, challenges: WalletChallengeService) -> Self {
+        Self { db, audit, challenges }
+    }
+
+    /// Saved addresses, by label
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<AddressBookEntry>, ApiError> {
+        let entries = sqlx::query_as::<_, AddressBookEntry>(&format!(
+            "SELECT {} FROM address_book_entries WHERE user_id = $1 ORDER BY LOWER(label), created_at",
+            ENTRY_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(entries)
+    }
+
+    pub async fn get(&self, user_id: Uuid, entry_id: Uuid) -> Result<AddressBookEntry, ApiError> {
+        sqlx::query_as::<_, AddressBookEntry>(&format!(
+            "SELECT {} FROM address_book_entries WHERE id = $1 AND user_id = $2",
+            ENTRY_COLUMNS
+        ))
+        .bind(entry_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Address book entry not found".to_string()))
+    }
+
+    /// Save an address. The returned entry carries a verification challenge.
+    pub async fn add(&self, user_id: Uuid, label: &str, wallet_address: &str) -> Result<AddressBookEntry, ApiError> {
+        let label = validate_label(label)?;
+        let wallet_address = parse_address(wallet_address)?;
+        self.challenges.ensure_allowed(&wallet_address).await?;
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM address_book_entries WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        if count >= MAX_ENTRIES_PER_USER {
+            return Err(ApiError::Conflict(format!(
+                "Address book is full ({} entries)",
+                MAX_ENTRIES_PER_USER
+            )));
+        }
+
+        let expires_at = Utc::now() + Duration::minutes(CHALLENGE_TTL_MINUTES);
+        let nonce = new_nonce();
+        let message = challenge_message(user_id, &wallet_address, &nonce, expires_at);
+
+        let entry = sqlx::query_as::<_, AddressBookEntry>(&format!(
+            "INSERT INTO address_book_entries (user_id, label, wallet_address, verification_message, verification_expires_at)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING {}",
+            ENTRY_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(&label)
+        .bind(&wallet_address)
+        .bind(&message)
+        .bind(expires_at)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                ApiError::Conflict("This address is already in your address book".to_string())
+            }
+            e => ApiError::Database(e),
+        })?;
+
+        self.challenges
+            .issue(user_id, &wallet_address, CHALLENGE_PURPOSE, &nonce, &message, expires_at)
+            .await?;
+
+        info!("User {} saved address {} as '{}'", user_id, entry.wallet_address, entry.label);
+        self.audit_change(user_id, &entry, "added");
+        Ok(entry)
+    }
+
+    /// Rename an entry
+    pub async fn rename(&self, user_id: Uuid, entry_id: Uuid, label: &str) -> Result<AddressBookEntry, ApiError> {
+        let label = validate_label(label)?;
+        sqlx::query_as::<_, AddressBookEntry>(&format!(
+            "UPDATE address_book_entries SET label = $3, updated_at = NOW()
+             WHERE id = $1 AND user_id = $2
+             RETURNING {}",
+            ENTRY_COLUMNS
+        ))
+        .bind(entry_id)
+        .bind(user_id)
+        .bind(&label)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Address book entry not found".to_string()))
+    }
+
+    /// Issue a fresh verification challenge for an unverified entry
+    pub async fn new_challenge(&self, user_id: Uuid, entry_id: Uuid) -> Result<AddressBookEntry, ApiError> {
+        let entry = self.get(user_id, entry_id).await?;
+        if entry.is_verified() {
+            return Err(ApiError::Conflict("Address is already verified".to_string()));
+        }
+        self.challenges.ensure_allowed(&entry.wallet_address).await?;
+
+        let expires_at = Utc::now() + Duration::minutes(CHALLENGE_TTL_MINUTES);
+        let nonce = new_nonce();
+        let message = challenge_message(user_id, &entry.wallet_address, &nonce, expires_at);
+        let entry = sqlx::query_as::<_, AddressBookEntry>(&format!(
+            "UPDATE address_book_entries
+             SET verification_message = $3, verification_expires_at = $4, updated_at = NOW()
+             WHERE id = $1 AND user_id = $2
+             RETURNING {}",
+            ENTRY_COLUMNS
+        ))
+        .bind(entry_id)
+        .bind(user_id)
+        .bind(&message)
+        .bind(expires_at)
+        .fetch_one(&self.db)
+        .await?;
+
+        self.challenges
+            .issue(user_id, &entry.wallet_address, CHALLENGE_PURPOSE, &nonce, &message, expires_at)
+            .await?;
+        Ok(entry)
+    }
+
+    /// Verify ownership with a base58 Ed25519 signature of the challenge.
+    /// Each challenge can be used once; failures count towards a wallet block.
+    pub async fn verify(&self, user_id: Uuid, entry_id: Uuid, signature: &str) -> Result<AddressBookEntry, ApiError> {
+        let entry = self.get(user_id, entry_id).await?;
+        if entry.is_verified() {
+            return Ok(entry);
+        }
+        let wallet_address = entry.wallet_address.as_str();
+        self.challenges.ensure_allowed(wallet_address).await?;
+
+        let (Some(message), Some(expires_at)) = (&entry.verification_message, entry.verification_expires_at) else {
+            return Err(ApiError::Conflict("Request a new verification challenge".to_string()));
+        };
+        if expires_at <= Utc::now() {
+            self.challenges
+                .record_failure(user_id, wallet_address, Some(message), ChallengeFailure::Expired)
+                .await?;
+            return Err(ApiError::Conflict(
+                "Verification challenge expired, request a new one".to_string(),
+            ));
+        }
+
+        let signature = signature.trim();
+        let valid = match verify_message_signature(wallet_address, signature, message.as_bytes()) {
+            Ok(valid) => valid,
+            Err(e) => {
+                self.challenges
+                    .record_failure(user_id, wallet_address, Some(message), ChallengeFailure::MalformedSignature)
+                    .await?;
+                return Err(ApiError::validation_field("signature", e));
+            }
+        };
+        if !valid {
+            let failure = if self.challenges.is_replay(wallet_address, signature).await? {
+                ChallengeFailure::Replay
+            } else {
+                ChallengeFailure::BadSignature
+            };
+            self.challenges
+                .record_failure(user_id, wallet_address, Some(message), failure)
+                .await?;
+            return Err(ApiError::validation_field(
+                "signature",
+                "Signature does not match the address",
+            ));
+        }
+        if !self.challenges.consume(user_id, message).await? {
+            self.challenges
+                .record_failure(user_id, wallet_address, Some(message), ChallengeFailure::Replay)
+                .await?;
+            return Err(ApiError::Conflict(
+                "Verification challenge was already used, request a new one".to_string(),
+            ));
+        }
+
+        let entry = sqlx::query_as::<_, AddressBookEntry>(&format!(
+            "UPDATE address_book_entries
+             SET verified_at = NOW(), verification_message = NULL, verification_expires_at = NULL, updated_at = NOW()
+             WHERE id = $1 AND user_id = $2
+             RETURNING {}",
+            ENTRY_COLUMNS
+        ))
+        .bind(entry_id)
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        info!("User {} verified ownership of {}", user_id, entry.wallet_address);
+        self.audit_change(user_id, &entry, "verified");
+        Ok(entry)
+    }
+
+    pub async fn remove(&self, user_id: Uuid, entry_id: Uuid) -> Result<(), ApiError> {
+        let entry = sqlx::query_as::<_, AddressBookEntry>(&format!(
+            "DELETE FROM address_book_entries WHERE id = $1 AND user_id = $2 RETURNING {}",
+            ENTRY_COLUMNS
+        ))
+        .bind(entry_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Address book entry not found".to_string()))?;
+
+        info!("User {} removed address {} from their address book", user_id, entry.wallet_address);
+        self.audit_change(user_id, &entry, "removed");
+        Ok(())
+    }
+
+    /// Whether a transfer to `wallet_address` must be confirmed first: it is
+    /// not saved, or has never been used
+    pub async fn check_recipient(&self, user_id: Uuid, wallet_address: &str) -> Result<RecipientCheck, ApiError> {
+        let wallet_address = parse_address(wallet_address)?;
+        let entry = sqlx::query_as::<_, AddressBookEntry>(&format!(
+            "SELECT {} FROM address_book_entries WHERE user_id = $1 AND wallet_address = $2",
+            ENTRY_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(&wallet_address)
+        .fetch_optional(&self.db)
+        .await?;
+
+        let reason = match &entry {
+            None => Some("Address is not in your address book".to_string()),
+            Some(entry) if entry.first_used_at.is_none() => Some("First transfer to this address".to_string()),
+            Some(_) => None,
+        };
+
+        Ok(RecipientCheck {
+            wallet_address,
+            requires_confirmation: reason.is_some(),
+            reason,
+            entry,
+        })
+    }
+
+    /// Record a transfer to a saved address. The first use requires
+    /// `confirmed`; without it a conflict is returned and nothing changes.
+    pub async fn record_use(&self, user_id: Uuid, entry_id: Uuid, confirmed: bool) -> Result<AddressBookEntry, ApiError> {
+        let entry = self.get(user_id, entry_id).await?;
+        if entry.first_used_at.is_none() && !confirmed {
+            return Err(ApiError::Conflict(
+                "First transfer to this address must be confirmed".to_string(),
+            ));
+        }
+
+        let entry = sqlx::query_as::<_, AddressBookEntry>(&format!(
+            "UPDATE address_book_entries
+             SET first_used_at = COALESCE(first_used_at, NOW()), last_used_at = NOW(), updated_at = NOW()
+             WHERE id = $1 AND user_id = $2
+             RETURNING {}",
+            ENTRY_COLUMNS
+        ))
+        .bind(entry_id)
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(entry)
+    }
+
+    fn audit_change(&self, user_id: Uuid, entry: &AddressBookEntry, action: &str) {
+        self.audit.log_async(AuditEvent::AddressBookChanged {
+            user_id,
+            entry_id: entry.id,
+            wallet_address: entry.wallet_address.clone(),
+            action: action.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[test]
+    fn test_challenge_signed_by_address_key_verifies() {
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut secret);
+        let key = SigningKey::from_bytes(&secret);
+        let address = bs58::encode(key.verifying_key().as_bytes()).into_string();
+
+        let message = challenge_message(Uuid::new_v4(), &address, &new_nonce(), Utc::now());
+        let signature = bs58::encode(key.sign(message.as_bytes()).to_bytes()).into_string();
+
+        assert_eq!(verify_message_signature(&address, &signature, message.as_bytes()), Ok(true));
+        assert_eq!(
+            verify_message_signature(&address, &signature, b"another message"),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_validate_label_and_address() {
+        assert_eq!(validate_label("  Mom  ").unwrap(), "Mom");
+        assert!(validate_label(" ").is_err());
+        assert!(validate_label(&"x".repeat(51)).is_err());
+        assert!(parse_address("11111111111111111111111111111111").is_ok());
+        assert!(parse_address("not-an-address").is_err());
+    }
+}
@@ -0,0 +1,303 @@
+//! Admin Overview
+//!
+//! One aggregate of the platform's operational state for the admin
+//! dashboard. The subsystem queries run concurrently and the result is
+//! cached briefly so a dashboard polling every few seconds costs at most
+//! one round of queries per cache period.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use metrics_exporter_prometheus::PrometheusHandle;
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use tokio::sync::Mutex;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::error::ApiError;
+use crate::services::degradation::SubsystemHealth;
+use crate::services::{BlockchainService, DegradationManager, WalletService};
+
+/// How long an assembled overview is served from cache
+const CACHE_TTL: Duration = Duration::from_secs(15);
+/// Upper bound on the payer balance RPC call
+const RPC_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Request and 5xx response totals from a Prometheus text exposition
+pub fn response_totals(exposition: &str) -> (u64, u64) {
+    fn sum(exposition: &str, metric: &str) -> u64 {
+        exposition
+            .lines()
+            .filter(|line| {
+                line.strip_prefix(metric)
+                    .is_some_and(|rest| rest.starts_with('{') || rest.starts_with(' '))
+            })
+            .filter_map(|line| line.rsplit(' ').next()?.parse::<f64>().ok())
+            .sum::<f64>() as u64
+    }
+    (
+        sum(exposition, "http_responses_total"),
+        sum(exposition, "http_errors_total"),
+    )
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MeterOverview {
+    /// Verified meters
+    pub active: i64,
+    pub pending_verification: i64,
+    pub suspended: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TradingOverview {
+    pub open_orders: i64,
+    pub pending_settlements: i64,
+    pub failed_settlements: i64,
+    /// Cleared epochs whose settlements are not all done
+    pub pending_batches: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MintingOverview {
+    /// Failed mints waiting for another attempt
+    pub retry_queue: i64,
+    /// Mints that exhausted their retries and need operator action
+    pub dlq_depth: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PayerOverview {
+    pub address: Option<String>,
+    /// SOL balance of the fee payer; absent when the RPC node did not answer in time
+    pub balance_sol: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErrorRateOverview {
+    /// Responses served by this instance since it started
+    pub responses: u64,
+    pub server_errors: u64,
+    /// Share of responses with a 5xx status
+    pub error_rate: f64,
+}
+
+/// Platform-wide operational summary
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AdminOverview {
+    pub users_by_role: BTreeMap<String, i64>,
+    pub meters: MeterOverview,
+    pub trading: TradingOverview,
+    pub minting: MintingOverview,
+    pub payer: PayerOverview,
+    pub errors: ErrorRateOverview,
+    /// Email, cache and metrics state, current even when the rest is cached
+    pub subsystems: Vec<SubsystemHealth>,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct AdminOverviewService {
+    db: PgPool,
+    blockchain: BlockchainService,
+    wallet: WalletService,
+    metrics: PrometheusHandle,
+    degradation: DegradationManager,
+    max_mint_attempts: i32,
+    cached: Arc<Mutex<Option<(Instant, AdminOverview)>>>,
+}
+
+impl AdminOverviewService {
+    pub fn new(
+        db: PgPool,
+        blockchain: BlockchainService,
+        wallet: WalletService,
+        metrics: PrometheusHandle,
+        degradation: DegradationManager,
+        max_mint_attempts: u32,
+    ) -> Self {
+        Self {
+            db,
+            blockchain,
+            wallet,
+            metrics,
+            degradation,
+            max_mint_attempts: max_mint_attempts as i32,
+            cached: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Cached overview, assembled again once older than the cache period.
+    /// Concurrent callers wait for a single assembly.
+    pub async fn overview(&self) -> Result<AdminOverview, ApiError> {
+        let mut cached = self.cached.lock().await;
+        if let Some((at, overview)) = cached.as_ref() {
+            if at.elapsed() < CACHE_TTL {
+                return Ok(AdminOverview {
+                    subsystems: self.degradation.snapshot(),
+                    ..overview.clone()
+                });
+            }
+        }
+
+        let overview = self.assemble().await?;
+        *cached = Some((Instant::now(), overview.clone()));
+        Ok(overview)
+    }
+
+    async fn assemble(&self) -> Result<AdminOverview, ApiError> {
+        let (users_by_role, meters, trading, minting, payer) = tokio::join!(
+            self.users_by_role(),
+            self.meters(),
+            self.trading(),
+            self.minting(),
+            self.payer(),
+        );
+
+        let (responses, server_errors) = response_totals(&self.metrics.render());
+        let error_rate = if responses == 0 {
+            0.0
+        } else {
+            server_errors as f64 / responses as f64
+        };
+
+        Ok(AdminOverview {
+            users_by_role: users_by_role?,
+            meters: meters?,
+            trading: trading?,
+            minting: minting?,
+            payer,
+            errors: ErrorRateOverview {
+                responses,
+                server_errors,
+                error_rate,
+            },
+            subsystems: self.degradation.snapshot(),
+            generated_at: Utc::now(),
+        })
+    }
+
+    async fn users_by_role(&self) -> Result<BTreeMap<String, i64>, sqlx::Error> {
+        let rows = sqlx::query("SELECT role, COUNT(*) AS count FROM users GROUP BY role")
+            .fetch_all(&self.db)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get::<String, _>("role"), row.get::<i64, _>("count")))
+            .collect())
+    }
+
+    async fn meters(&self) -> Result<MeterOverview, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE verification_status = 'verified') AS active,
+                COUNT(*) FILTER (WHERE verification_status = 'pending') AS pending,
+                COUNT(*) FILTER (WHERE verification_status = 'suspended') AS suspended
+            FROM meter_registry
+            "#,
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(MeterOverview {
+            active: row.get("active"),
+            pending_verification: row.get("pending"),
+            suspended: row.get("suspended"),
+        })
+    }
+
+    async fn trading(&self) -> Result<TradingOverview, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM trading_orders
+                 WHERE status IN ('pending', 'active', 'partially_filled')) AS open_orders,
+                (SELECT COUNT(*) FROM settlements
+                 WHERE status IN ('pending', 'processing')) AS pending_settlements,
+                (SELECT COUNT(*) FROM settlements WHERE status = 'failed') AS failed_settlements,
+                (SELECT COUNT(*) FROM market_epochs WHERE status = 'cleared') AS pending_batches
+            "#,
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(TradingOverview {
+            open_orders: row.get("open_orders"),
+            pending_settlements: row.get("pending_settlements"),
+            failed_settlements: row.get("failed_settlements"),
+            pending_batches: row.get("pending_batches"),
+        })
+    }
+
+    async fn minting(&self) -> Result<MintingOverview, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM minting_retry_queue WHERE attempts < $1) AS retry_queue,
+                (SELECT COUNT(*) FROM minting_retry_queue WHERE attempts >= $1)
+                    + (SELECT COUNT(*) FROM referral_rewards WHERE status = 'failed') AS dlq_depth
+            "#,
+        )
+        .bind(self.max_mint_attempts)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(MintingOverview {
+            retry_queue: row.get("retry_queue"),
+            dlq_depth: row.get("dlq_depth"),
+        })
+    }
+
+    async fn payer(&self) -> PayerOverview {
+        let address = self.wallet.get_authority_pubkey_string().await.ok();
+        let balance_sol = match &address {
+            Some(address) => {
+                let lookup = async {
+                    let pubkey = BlockchainService::parse_pubkey(address)?;
+                    self.blockchain.get_balance_sol(&pubkey).await
+                };
+                match tokio::time::timeout(RPC_TIMEOUT, lookup).await {
+                    Ok(Ok(balance)) => Some(balance),
+                    Ok(Err(e)) => {
+                        warn!("⚠️ Could not read fee payer balance: {}", e);
+                        None
+                    }
+                    Err(_) => {
+                        warn!("⏱️ Fee payer balance lookup timed out");
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        PayerOverview { address, balance_sol }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_totals_sum_all_series() {
+        let exposition = "\
+# TYPE http_responses_total counter
+http_responses_total{method=\"GET\",path=\"/a\",status=\"200\"} 90
+http_responses_total{method=\"POST\",path=\"/b\",status=\"500\"} 10
+# TYPE http_errors_total counter
+http_errors_total{method=\"POST\",path=\"/b\",status=\"500\"} 10
+http_responses_total_other 5
+";
+        assert_eq!(response_totals(exposition), (100, 10));
+    }
+
+    #[test]
+    fn test_response_totals_empty() {
+        assert_eq!(response_totals(""), (0, 0));
+    }
+}
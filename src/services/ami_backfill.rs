@@ -0,0 +1,848 @@
+//! AMI Backfill
+//!
+//! Bulk backfill of readings an AMI gateway failed to deliver on time. The
+//! gateway first declares the time window it is about to fill; readings
+//! outside it are refused. The live freshness limit does not apply, but every
+//! reading must carry a v2 meter signature from a meter bound to the gateway.
+//!
+//! Readings are staged per meter and timestamp, so a repeated or replayed
+//! submission updates the staged reading instead of adding another one, and a
+//! reading already recorded live is never staged. Nothing reaches
+//! `meter_readings` or mints until an admin approves the batch; approval
+//! imports the staged readings and mints the positive ones in the background.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Row};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::{Config, MintGuardMode};
+use crate::error::{ApiError, Result};
+use crate::services::meter_gateway::MeterGateway;
+use crate::services::{
+    AuditEvent, AuditLogger, BlockchainService, GridMeterDataService, MintGuardService, WalletService,
+};
+use crate::utils::{verify_signature, MeterReadingMessage, METER_MESSAGE_VERSION};
+
+const BATCH_COLUMNS: &str = r#"
+    b.id, b.gateway_id, g.name AS gateway_name, b.window_start, b.window_end, b.reason, b.status,
+    b.reviewed_by, b.reviewed_at, b.review_note, b.created_at,
+    COALESCE(r.readings, 0) AS readings,
+    COALESCE(r.duplicates, 0) AS duplicates,
+    COALESCE(r.minted, 0) AS minted,
+    COALESCE(r.mint_failed, 0) AS mint_failed,
+    COALESCE(r.total_kwh, 0) AS total_kwh
+"#;
+
+const BATCH_FROM: &str = r#"
+    FROM ami_backfill_batches b
+    JOIN meter_gateways g ON g.id = b.gateway_id
+    LEFT JOIN LATERAL (
+        SELECT COUNT(*) AS readings,
+               COUNT(*) FILTER (WHERE status = 'duplicate') AS duplicates,
+               COUNT(*) FILTER (WHERE status = 'minted') AS minted,
+               COUNT(*) FILTER (WHERE status = 'mint_failed') AS mint_failed,
+               SUM(kwh_amount) FILTER (WHERE status NOT IN ('duplicate', 'rejected')) AS total_kwh
+        FROM ami_backfill_readings
+        WHERE batch_id = b.id
+    ) r ON TRUE
+"#;
+
+/// A declared backfill and the state of its readings
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct BackfillBatch {
+    pub id: Uuid,
+    pub gateway_id: Uuid,
+    pub gateway_name: String,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub reason: String,
+    /// open, approved or rejected
+    pub status: String,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Readings submitted, including duplicates
+    pub readings: i64,
+    /// Readings already recorded live, which are not imported
+    pub duplicates: i64,
+    pub minted: i64,
+    pub mint_failed: i64,
+    /// Energy of the readings to import, kWh
+    #[schema(value_type = String)]
+    pub total_kwh: Decimal,
+}
+
+/// One signed reading from the gateway's backlog
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct BackfillReading {
+    pub meter_serial: String,
+    pub reading_timestamp: DateTime<Utc>,
+    #[schema(value_type = f64)]
+    pub kwh_amount: Decimal,
+    /// Wallet the meter signed for, when the owner has none on their profile
+    pub wallet_address: Option<String>,
+    /// Base58 Ed25519 signature over the canonical v2 reading message
+    pub meter_signature: String,
+    /// Must be 2; the sequence is part of the signed message
+    pub message_version: u8,
+    pub sequence: u64,
+    pub energy_generated: Option<f64>,
+    pub energy_consumed: Option<f64>,
+    pub voltage: Option<f64>,
+    pub current: Option<f64>,
+    pub power_factor: Option<f64>,
+    pub frequency: Option<f64>,
+}
+
+/// A reading refused by a submission
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RejectedBackfillReading {
+    /// Position in the submitted list
+    pub index: usize,
+    pub meter_serial: String,
+    pub reason: String,
+}
+
+/// Outcome of one submission of readings
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BackfillSubmission {
+    pub batch_id: Uuid,
+    /// Readings staged for the first time
+    pub staged: usize,
+    /// Readings that replaced an earlier submission of the same reading
+    pub updated: usize,
+    /// Readings recorded live or staged by another batch, skipped
+    pub duplicates: usize,
+    pub rejected: Vec<RejectedBackfillReading>,
+}
+
+/// Check a declared window: in the past, and no older than `max_age_days`
+pub fn validate_window(
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    now: DateTime<Utc>,
+    max_age_days: i64,
+) -> std::result::Result<(), String> {
+    if window_start >= window_end {
+        return Err("window_start must be before window_end".to_string());
+    }
+    if window_end > now {
+        return Err("A backfill window cannot extend into the future".to_string());
+    }
+    if window_start < now - Duration::days(max_age_days) {
+        return Err(format!("A backfill window may reach back at most {} days", max_age_days));
+    }
+    Ok(())
+}
+
+/// Checks of a reading that need no database access
+pub fn check_reading(
+    reading: &BackfillReading,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    max_reading_kwh: f64,
+) -> std::result::Result<(), String> {
+    if reading.reading_timestamp < window_start || reading.reading_timestamp > window_end {
+        return Err("Reading is outside the declared backfill window".to_string());
+    }
+    if reading.kwh_amount.abs().to_f64().is_none_or(|kwh| kwh > max_reading_kwh) {
+        return Err(format!("kWh amount exceeds maximum ({} kWh)", max_reading_kwh));
+    }
+    if reading.message_version != METER_MESSAGE_VERSION {
+        return Err(format!("Backfilled readings must be signed with message version {}", METER_MESSAGE_VERSION));
+    }
+    if reading.sequence == 0 || i64::try_from(reading.sequence).is_err() {
+        return Err("A positive sequence number is required".to_string());
+    }
+    Ok(())
+}
+
+/// Registry data of a meter bound to the submitting gateway
+struct BoundMeter {
+    meter_id: Uuid,
+    user_id: Uuid,
+    public_key: Option<String>,
+    wallet_address: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct AmiBackfillService {
+    db: PgPool,
+    config: Config,
+    blockchain: BlockchainService,
+    wallet: WalletService,
+    grid_meter_data: GridMeterDataService,
+    mint_guard: MintGuardService,
+    audit_logger: AuditLogger,
+}
+
+impl AmiBackfillService {
+    pub fn new(
+        db: PgPool,
+        config: Config,
+        blockchain: BlockchainService,
+        wallet: WalletService,
+        grid_meter_data: GridMeterDataService,
+        mint_guard: MintGuardService,
+        audit_logger: AuditLogger,
+    ) -> Self {
+        Self {
+            db,
+            config,
+            blockchain,
+            wallet,
+            grid_meter_data,
+            mint_guard,
+            audit_logger,
+        }
+    }
+
+    /// Declare a window of missed readings the gateway is about to submit
+    pub async fn declare(
+        &self,
+        gateway: &MeterGateway,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+        reason: &str,
+    ) -> Result<BackfillBatch> {
+        validate_window(window_start, window_end, Utc::now(), self.config.ami_gateway.backfill_max_age_days)
+            .map_err(|e| ApiError::validation_field("window_start", e))?;
+
+        let batch_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO ami_backfill_batches (gateway_id, window_start, window_end, reason)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(gateway.id)
+        .bind(window_start)
+        .bind(window_end)
+        .bind(reason)
+        .fetch_one(&self.db)
+        .await?;
+
+        info!(
+            "📥 Gateway {} declared backfill {} ({} to {})",
+            gateway.name, batch_id, window_start, window_end
+        );
+        self.audit(batch_id, gateway.id, None, "declared", serde_json::json!({
+            "window_start": window_start,
+            "window_end": window_end,
+            "reason": reason,
+        }));
+        self.get(batch_id).await
+    }
+
+    pub async fn get(&self, batch_id: Uuid) -> Result<BackfillBatch> {
+        sqlx::query_as::<_, BackfillBatch>(&format!("SELECT {BATCH_COLUMNS} {BATCH_FROM} WHERE b.id = $1"))
+            .bind(batch_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Backfill batch not found".to_string()))
+    }
+
+    /// A batch as seen by its gateway; other gateways' batches are not found
+    pub async fn get_for_gateway(&self, gateway: &MeterGateway, batch_id: Uuid) -> Result<BackfillBatch> {
+        let batch = self.get(batch_id).await?;
+        if batch.gateway_id != gateway.id {
+            return Err(ApiError::NotFound("Backfill batch not found".to_string()));
+        }
+        Ok(batch)
+    }
+
+    /// Batches newest first, optionally with one status
+    pub async fn list(&self, status: Option<&str>, limit: i64) -> Result<Vec<BackfillBatch>> {
+        let batches = sqlx::query_as::<_, BackfillBatch>(&format!(
+            "SELECT {BATCH_COLUMNS} {BATCH_FROM} WHERE ($1::text IS NULL OR b.status = $1) \
+             ORDER BY b.created_at DESC LIMIT $2"
+        ))
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(batches)
+    }
+
+    /// Verify and stage readings for an open batch of the gateway
+    pub async fn submit(
+        &self,
+        gateway: &MeterGateway,
+        batch_id: Uuid,
+        readings: &[BackfillReading],
+    ) -> Result<BackfillSubmission> {
+        let batch = self.get_for_gateway(gateway, batch_id).await?;
+        if batch.status != "open" {
+            return Err(ApiError::Conflict(format!("Backfill batch is {}", batch.status)));
+        }
+
+        let serials: Vec<String> = readings
+            .iter()
+            .map(|r| r.meter_serial.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let meters = self.bound_meters(gateway.id, &serials).await?;
+
+        let mut submission = BackfillSubmission {
+            batch_id,
+            staged: 0,
+            updated: 0,
+            duplicates: 0,
+            rejected: Vec::new(),
+        };
+        let mut accepted = Vec::with_capacity(readings.len());
+        for (index, reading) in readings.iter().enumerate() {
+            match self.verify(reading, &batch, &meters) {
+                Ok(wallet) => accepted.push((reading, wallet)),
+                Err(reason) => submission.rejected.push(RejectedBackfillReading {
+                    index,
+                    meter_serial: reading.meter_serial.clone(),
+                    reason,
+                }),
+            }
+        }
+
+        // Readings recorded live are never staged
+        let live: HashSet<(String, DateTime<Utc>)> = sqlx::query_as::<_, (String, DateTime<Utc>)>(
+            r#"
+            SELECT m.meter_serial, m.reading_timestamp
+            FROM meter_readings m
+            JOIN UNNEST($1::text[], $2::timestamptz[]) AS s(meter_serial, reading_timestamp)
+              ON m.meter_serial = s.meter_serial AND m.reading_timestamp = s.reading_timestamp
+            "#,
+        )
+        .bind(accepted.iter().map(|(r, _)| r.meter_serial.clone()).collect::<Vec<_>>())
+        .bind(accepted.iter().map(|(r, _)| r.reading_timestamp).collect::<Vec<_>>())
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .collect();
+
+        let mut tx = self.db.begin().await?;
+        for (reading, wallet) in accepted {
+            if live.contains(&(reading.meter_serial.clone(), reading.reading_timestamp)) {
+                submission.duplicates += 1;
+                continue;
+            }
+            let meter = &meters[&reading.meter_serial];
+            let row = sqlx::query(
+                r#"
+                INSERT INTO ami_backfill_readings (
+                    batch_id, meter_serial, meter_id, user_id, wallet_address, reading_timestamp,
+                    kwh_amount, energy_generated, energy_consumed, voltage, current_amps,
+                    power_factor, frequency, meter_signature, message_version, sequence
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+                ON CONFLICT (meter_serial, reading_timestamp) DO UPDATE SET
+                    wallet_address = EXCLUDED.wallet_address,
+                    kwh_amount = EXCLUDED.kwh_amount,
+                    energy_generated = EXCLUDED.energy_generated,
+                    energy_consumed = EXCLUDED.energy_consumed,
+                    voltage = EXCLUDED.voltage,
+                    current_amps = EXCLUDED.current_amps,
+                    power_factor = EXCLUDED.power_factor,
+                    frequency = EXCLUDED.frequency,
+                    meter_signature = EXCLUDED.meter_signature,
+                    message_version = EXCLUDED.message_version,
+                    sequence = EXCLUDED.sequence,
+                    updated_at = NOW()
+                WHERE ami_backfill_readings.batch_id = EXCLUDED.batch_id
+                  AND ami_backfill_readings.status = 'staged'
+                RETURNING (xmax = 0) AS inserted
+                "#,
+            )
+            .bind(batch_id)
+            .bind(&reading.meter_serial)
+            .bind(meter.meter_id)
+            .bind(meter.user_id)
+            .bind(&wallet)
+            .bind(reading.reading_timestamp)
+            .bind(reading.kwh_amount)
+            .bind(reading.energy_generated)
+            .bind(reading.energy_consumed)
+            .bind(reading.voltage)
+            .bind(reading.current)
+            .bind(reading.power_factor)
+            .bind(reading.frequency)
+            .bind(&reading.meter_signature)
+            .bind(reading.message_version as i16)
+            .bind(reading.sequence as i64)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            match row {
+                Some(row) if row.get::<bool, _>("inserted") => submission.staged += 1,
+                Some(_) => submission.updated += 1,
+                None => submission.duplicates += 1,
+            }
+        }
+        tx.commit().await?;
+
+        if !submission.rejected.is_empty() {
+            warn!(
+                "📥 Backfill {}: {} of {} readings from gateway {} rejected",
+                batch_id,
+                submission.rejected.len(),
+                readings.len(),
+                gateway.name
+            );
+        }
+        self.audit(batch_id, gateway.id, None, "readings_submitted", serde_json::json!({
+            "submitted": readings.len(),
+            "staged": submission.staged,
+            "updated": submission.updated,
+            "duplicates": submission.duplicates,
+            "rejected": submission.rejected.len(),
+        }));
+        Ok(submission)
+    }
+
+    async fn bound_meters(&self, gateway_id: Uuid, serials: &[String]) -> Result<HashMap<String, BoundMeter>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT m.meter_serial, m.id, m.user_id, m.meter_public_key, u.wallet_address
+            FROM meter_registry m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.gateway_id = $1 AND m.meter_serial = ANY($2)
+            "#,
+        )
+        .bind(gateway_id)
+        .bind(serials)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get("meter_serial"),
+                    BoundMeter {
+                        meter_id: row.get("id"),
+                        user_id: row.get("user_id"),
+                        public_key: row.get("meter_public_key"),
+                        wallet_address: row.get("wallet_address"),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Check one reading; returns the wallet it is credited to
+    fn verify(
+        &self,
+        reading: &BackfillReading,
+        batch: &BackfillBatch,
+        meters: &HashMap<String, BoundMeter>,
+    ) -> std::result::Result<String, String> {
+        check_reading(
+            reading,
+            batch.window_start,
+            batch.window_end,
+            self.config.tokenization.max_reading_kwh,
+        )?;
+        let meter = meters
+            .get(&reading.meter_serial)
+            .ok_or_else(|| "Meter is not bound to this gateway".to_string())?;
+        let public_key = meter
+            .public_key
+            .as_deref()
+            .ok_or_else(|| "Meter has no registered public key".to_string())?;
+        let wallet = meter
+            .wallet_address
+            .clone()
+            .or_else(|| reading.wallet_address.clone())
+            .ok_or_else(|| "Wallet address required (not found on user profile)".to_string())?;
+
+        let message = MeterReadingMessage {
+            version: reading.message_version,
+            meter_serial: reading.meter_serial.clone(),
+            timestamp: reading.reading_timestamp.to_rfc3339(),
+            kwh_amount: format!("{:.6}", reading.kwh_amount),
+            wallet: wallet.clone(),
+            sequence: reading.sequence,
+        };
+        match verify_signature(public_key, &reading.meter_signature, &message) {
+            Ok(true) => Ok(wallet),
+            Ok(false) => Err("Meter signature does not match reading".to_string()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Import an open batch's staged readings and, when auto-mint is
+    /// enabled, mint them in the background
+    pub async fn approve(&self, admin_id: Uuid, batch_id: Uuid, note: Option<&str>) -> Result<BackfillBatch> {
+        let mut tx = self.db.begin().await?;
+        let gateway_id = self.review(&mut tx, admin_id, batch_id, "approved", note).await?;
+
+        let imported: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO meter_readings (
+                id, meter_serial, meter_id, user_id, wallet_address,
+                timestamp, reading_timestamp, device_timestamp, kwh_amount,
+                energy_generated, energy_consumed, voltage, current_amps, power_factor, frequency,
+                meter_signature, message_version, sequence, minted, backfill_batch_id, created_at
+            )
+            SELECT r.id, r.meter_serial, r.meter_id, r.user_id, r.wallet_address,
+                   r.reading_timestamp, r.reading_timestamp, r.reading_timestamp, r.kwh_amount,
+                   r.energy_generated, r.energy_consumed, r.voltage, r.current_amps, r.power_factor, r.frequency,
+                   r.meter_signature, r.message_version, r.sequence, FALSE, r.batch_id, NOW()
+            FROM ami_backfill_readings r
+            WHERE r.batch_id = $1 AND r.status = 'staged'
+              AND NOT EXISTS (
+                  SELECT 1 FROM meter_readings m
+                  WHERE m.meter_serial = r.meter_serial AND m.reading_timestamp = r.reading_timestamp
+              )
+            RETURNING id
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let duplicates = sqlx::query(
+            r#"
+            UPDATE ami_backfill_readings
+            SET status = CASE WHEN id = ANY($2) THEN 'imported' ELSE 'duplicate' END, updated_at = NOW()
+            WHERE batch_id = $1 AND status = 'staged'
+            "#,
+        )
+        .bind(batch_id)
+        .bind(&imported)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as usize
+            - imported.len();
+        tx.commit().await?;
+
+        let auto_mint = self.config.tokenization.auto_mint_enabled;
+        info!(
+            "✅ Backfill {} approved by {}: {} readings imported, {} duplicates",
+            batch_id,
+            admin_id,
+            imported.len(),
+            duplicates
+        );
+        self.audit(batch_id, gateway_id, Some(admin_id), "approved", serde_json::json!({
+            "imported": imported.len(),
+            "duplicates": duplicates,
+            "auto_mint": auto_mint,
+            "note": note,
+        }));
+
+        if auto_mint {
+            self.spawn_mint(batch_id, gateway_id);
+        }
+        self.get(batch_id).await
+    }
+
+    /// Discard an open batch's staged readings
+    pub async fn reject(&self, admin_id: Uuid, batch_id: Uuid, note: Option<&str>) -> Result<BackfillBatch> {
+        let mut tx = self.db.begin().await?;
+        let gateway_id = self.review(&mut tx, admin_id, batch_id, "rejected", note).await?;
+        let discarded = sqlx::query(
+            "UPDATE ami_backfill_readings SET status = 'rejected', updated_at = NOW() \
+             WHERE batch_id = $1 AND status = 'staged'",
+        )
+        .bind(batch_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+
+        info!("🚫 Backfill {} rejected by {} ({} readings discarded)", batch_id, admin_id, discarded);
+        self.audit(batch_id, gateway_id, Some(admin_id), "rejected", serde_json::json!({
+            "discarded": discarded,
+            "note": note,
+        }));
+        self.get(batch_id).await
+    }
+
+    /// Retry minting of an approved batch's unminted and failed readings
+    pub async fn retry_mint(&self, admin_id: Uuid, batch_id: Uuid) -> Result<BackfillBatch> {
+        let batch = self.get(batch_id).await?;
+        if batch.status != "approved" {
+            return Err(ApiError::Conflict(format!("Backfill batch is {}", batch.status)));
+        }
+        self.audit(batch_id, batch.gateway_id, Some(admin_id), "mint_retried", serde_json::json!({
+            "mint_failed": batch.mint_failed,
+        }));
+        self.spawn_mint(batch_id, batch.gateway_id);
+        Ok(batch)
+    }
+
+    /// Close an open batch with the review decision; returns its gateway
+    async fn review(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        admin_id: Uuid,
+        batch_id: Uuid,
+        status: &str,
+        note: Option<&str>,
+    ) -> Result<Uuid> {
+        let gateway_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            UPDATE ami_backfill_batches
+            SET status = $2, reviewed_by = $3, reviewed_at = NOW(), review_note = $4, updated_at = NOW()
+            WHERE id = $1 AND status = 'open'
+            RETURNING gateway_id
+            "#,
+        )
+        .bind(batch_id)
+        .bind(status)
+        .bind(admin_id)
+        .bind(note)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        match gateway_id {
+            Some(gateway_id) => Ok(gateway_id),
+            None => {
+                let batch = self.get(batch_id).await?;
+                Err(ApiError::Conflict(format!("Backfill batch is already {}", batch.status)))
+            }
+        }
+    }
+
+    fn spawn_mint(&self, batch_id: Uuid, gateway_id: Uuid) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            match service.mint_batch(batch_id).await {
+                Ok((minted, failed)) => {
+                    info!("💧 Backfill {}: {} readings minted, {} failed", batch_id, minted, failed);
+                    service.audit(batch_id, gateway_id, None, "minted", serde_json::json!({
+                        "minted": minted,
+                        "failed": failed,
+                    }));
+                }
+                Err(e) => warn!("Minting of backfill {} stopped: {}", batch_id, e),
+            }
+        });
+    }
+
+    /// Mint each imported positive reading once. A reading is claimed before
+    /// its transaction is sent, so concurrent runs cannot mint it twice.
+    /// Readings over their wallet's minting cap are quarantined or rejected;
+    /// the cap is checked and the reading marked pending under the guard's
+    /// wallet lock.
+    async fn mint_batch(&self, batch_id: Uuid) -> Result<(usize, usize)> {
+        let candidates: Vec<(Uuid, String, String, Decimal, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT id, meter_serial, wallet_address, kwh_amount, reading_timestamp FROM ami_backfill_readings
+            WHERE batch_id = $1 AND status IN ('imported', 'mint_failed') AND kwh_amount > 0
+            ORDER BY reading_timestamp
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let (mut minted, mut failed) = (0, 0);
+        let mut frozen: HashMap<String, Option<String>> = HashMap::new();
+        for (reading_id, meter_serial, wallet_address, kwh, reading_timestamp) in candidates {
+            if !frozen.contains_key(&meter_serial) {
+                let freeze = self.grid_meter_data.meter_mint_freeze(&meter_serial).await?;
+                frozen.insert(meter_serial.clone(), freeze);
+            }
+            if let Some(reason) = &frozen[&meter_serial] {
+                sqlx::query("UPDATE ami_backfill_readings SET error = $2, updated_at = NOW() WHERE id = $1")
+                    .bind(reading_id)
+                    .bind(format!("Minting frozen: {}", reason))
+                    .execute(&self.db)
+                    .await?;
+                continue;
+            }
+
+            // Held readings wait for review; only a release mints them
+            if self.mint_guard.reading_quarantine(reading_id).await?.is_some() {
+                continue;
+            }
+            let mut tx = self.db.begin().await?;
+            if let Some(breach) = self.mint_guard.check(&mut tx, &wallet_address, kwh, reading_timestamp).await {
+                let status = match self.mint_guard.mode() {
+                    MintGuardMode::Quarantine => {
+                        self.mint_guard
+                            .quarantine(&mut tx, reading_id, &meter_serial, &wallet_address, reading_timestamp, &breach)
+                            .await?;
+                        "imported"
+                    }
+                    MintGuardMode::Reject => "rejected",
+                };
+                sqlx::query("UPDATE ami_backfill_readings SET status = $2, error = $3, updated_at = NOW() WHERE id = $1")
+                    .bind(reading_id)
+                    .bind(status)
+                    .bind(format!("Minting guard: {}", breach.message()))
+                    .execute(&mut *tx)
+                    .await?;
+                tx.commit().await?;
+                continue;
+            }
+
+            // Readings minted by hand since the import are only marked
+            let claimed = sqlx::query(
+                r#"
+                UPDATE ami_backfill_readings r
+                SET status = CASE WHEN m.minted THEN 'minted' ELSE 'minting' END,
+                    mint_tx_signature = m.mint_tx_signature, updated_at = NOW()
+                FROM meter_readings m
+                WHERE r.id = $1 AND m.id = r.id AND r.status IN ('imported', 'mint_failed')
+                RETURNING COALESCE(m.minted, FALSE) AS already_minted
+                "#,
+            )
+            .bind(reading_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+            let Some(claimed) = claimed else { continue };
+            if claimed.get::<bool, _>("already_minted") {
+                tx.commit().await?;
+                continue;
+            }
+            // Counts against the wallet's cap until the mint is recorded
+            sqlx::query("UPDATE meter_readings SET mint_status = 'pending', updated_at = NOW() WHERE id = $1")
+                .bind(reading_id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+
+            match self.mint(&wallet_address, kwh).await {
+                Ok(signature) => {
+                    let mut tx = self.db.begin().await?;
+                    sqlx::query(
+                        "UPDATE meter_readings SET minted = TRUE, mint_tx_signature = $2, mint_status = 'completed', \
+                         updated_at = NOW() WHERE id = $1",
+                    )
+                    .bind(reading_id)
+                    .bind(&signature)
+                    .execute(&mut *tx)
+                    .await?;
+                    sqlx::query(
+                        "UPDATE ami_backfill_readings SET status = 'minted', mint_tx_signature = $2, error = NULL, \
+                         updated_at = NOW() WHERE id = $1",
+                    )
+                    .bind(reading_id)
+                    .bind(&signature)
+                    .execute(&mut *tx)
+                    .await?;
+                    tx.commit().await?;
+                    minted += 1;
+                }
+                Err(e) => {
+                    let mut tx = self.db.begin().await?;
+                    sqlx::query(
+                        "UPDATE ami_backfill_readings SET status = 'mint_failed', error = $2, updated_at = NOW() \
+                         WHERE id = $1",
+                    )
+                    .bind(reading_id)
+                    .bind(e.to_string())
+                    .execute(&mut *tx)
+                    .await?;
+                    sqlx::query("UPDATE meter_readings SET mint_status = 'failed', updated_at = NOW() WHERE id = $1")
+                        .bind(reading_id)
+                        .execute(&mut *tx)
+                        .await?;
+                    tx.commit().await?;
+                    warn!("⚠️ Mint of backfilled reading {} failed: {}", reading_id, e);
+                    failed += 1;
+                }
+            }
+        }
+        Ok((minted, failed))
+    }
+
+    async fn mint(&self, wallet_address: &str, amount: Decimal) -> anyhow::Result<String> {
+        let kwh = amount
+            .to_f64()
+            .ok_or_else(|| anyhow!("Reading amount {} out of range", amount))?;
+        let authority = self.wallet.get_authority_keypair().await?;
+        let mint = BlockchainService::parse_pubkey(&self.config.energy_token_mint)?;
+        let wallet = BlockchainService::parse_pubkey(wallet_address)?;
+
+        let signature = if self.config.tokenization.enable_real_blockchain {
+            let token_account = self
+                .blockchain
+                .ensure_token_account_exists(&authority, &wallet, &mint)
+                .await?;
+            self.blockchain
+                .mint_energy_tokens(&authority, &token_account, &wallet, &mint, kwh)
+                .await?
+        } else {
+            self.blockchain
+                .mint_spl_tokens(&authority, &wallet, &mint, kwh)
+                .await?
+        };
+        Ok(signature.to_string())
+    }
+
+    fn audit(&self, batch_id: Uuid, gateway_id: Uuid, admin_id: Option<Uuid>, action: &str, details: serde_json::Value) {
+        self.audit_logger.log_async(AuditEvent::AmiBackfill {
+            batch_id,
+            gateway_id,
+            admin_id,
+            action: action.to_string(),
+            details,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(timestamp: DateTime<Utc>) -> BackfillReading {
+        BackfillReading {
+            meter_serial: "M-1".to_string(),
+            reading_timestamp: timestamp,
+            kwh_amount: Decimal::new(25, 1),
+            wallet_address: None,
+            meter_signature: "sig".to_string(),
+            message_version: METER_MESSAGE_VERSION,
+            sequence: 7,
+            energy_generated: None,
+            energy_consumed: None,
+            voltage: None,
+            current: None,
+            power_factor: None,
+            frequency: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_window() {
+        let now = Utc::now();
+        assert!(validate_window(now - Duration::days(3), now - Duration::days(1), now, 90).is_ok());
+        // Empty, future and too old windows
+        assert!(validate_window(now - Duration::days(1), now - Duration::days(1), now, 90).is_err());
+        assert!(validate_window(now - Duration::days(1), now + Duration::hours(1), now, 90).is_err());
+        assert!(validate_window(now - Duration::days(91), now - Duration::days(1), now, 90).is_err());
+    }
+
+    #[test]
+    fn test_check_reading() {
+        let now = Utc::now();
+        let (start, end) = (now - Duration::days(2), now - Duration::days(1));
+        let inside = reading(start + Duration::hours(1));
+        assert!(check_reading(&inside, start, end, 100.0).is_ok());
+        // Readings older than the live freshness limit are fine inside the window
+        assert!(check_reading(&reading(start), start, end, 100.0).is_ok());
+        assert!(check_reading(&reading(end + Duration::seconds(1)), start, end, 100.0).is_err());
+
+        let mut unsigned_sequence = inside.clone();
+        unsigned_sequence.message_version = 1;
+        assert!(check_reading(&unsigned_sequence, start, end, 100.0).is_err());
+        unsigned_sequence.message_version = METER_MESSAGE_VERSION;
+        unsigned_sequence.sequence = 0;
+        assert!(check_reading(&unsigned_sequence, start, end, 100.0).is_err());
+
+        let mut too_large = inside;
+        too_large.kwh_amount = Decimal::from(-150);
+        assert!(check_reading(&too_large, start, end, 100.0).is_err());
+    }
+}
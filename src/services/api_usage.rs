@@ -0,0 +1,612 @@
+//! API Usage Analytics
+//!
+//! Authenticated requests are counted per user, credential and endpoint in
+//! memory and flushed periodically into hourly buckets and a daily rollup.
+//! Hourly buckets are kept for a week as the baseline for access anomaly
+//! detection; the daily rollup backs the usage reports. Logins located by
+//! GeoIP are also checked for impossible travel between them.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::geoip::distance_km;
+
+/// Hourly buckets older than this are pruned
+const HOURLY_RETENTION_DAYS: i64 = 7;
+/// An hour with at least this many requests, and this many times the
+/// user's usual hourly volume, is a spike
+const SPIKE_MIN_REQUESTS: i64 = 300;
+const SPIKE_FACTOR: f64 = 10.0;
+/// An hour in which most of at least this many requests failed
+const ERROR_BURST_MIN_REQUESTS: i64 = 50;
+/// Distinct endpoints touched within one hour that suggest enumeration
+const SCAN_MIN_ENDPOINTS: i64 = 40;
+/// Logins closer than this are within GeoIP accuracy and never flagged
+const TRAVEL_MIN_DISTANCE_KM: f64 = 300.0;
+/// Earlier logins compared against the checked hour's logins
+const TRAVEL_LOOKBACK_HOURS: i64 = 24;
+
+/// Caller identity, attached to the response by the auth middleware so the
+/// usage middleware can attribute the request
+#[derive(Debug, Clone)]
+pub struct UsageIdentity {
+    pub user_id: Uuid,
+    /// jwt, engineering_key or signed:<key id>
+    pub credential: String,
+}
+
+/// Path with identifier segments (UUIDs, numbers, addresses) replaced by `{id}`
+pub fn normalize_endpoint(path: &str) -> String {
+    fn is_identifier(segment: &str) -> bool {
+        !segment.is_empty()
+            && (segment.chars().all(|c| c.is_ascii_digit())
+                || Uuid::parse_str(segment).is_ok()
+                || (segment.len() >= 32 && segment.chars().all(|c| c.is_ascii_alphanumeric())))
+    }
+
+    path.split('/')
+        .map(|segment| if is_identifier(segment) { "{id}" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Unusual access pattern
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Request volume far above the user's usual hourly volume
+    RequestSpike,
+    /// Most requests failed, e.g. credential or permission probing
+    ErrorBurst,
+    /// Many distinct endpoints touched, e.g. enumeration
+    EndpointScan,
+    /// Repeated failed wallet signature challenges; reported by the wallet
+    /// challenge audit rather than detected from request counts
+    WalletSignatureFailures,
+    /// Consecutive logins from locations too far apart for the time between
+    /// them (GeoIP)
+    ImpossibleTravel,
+}
+
+impl AnomalyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RequestSpike => "request_spike",
+            Self::ErrorBurst => "error_burst",
+            Self::EndpointScan => "endpoint_scan",
+            Self::WalletSignatureFailures => "wallet_signature_failures",
+            Self::ImpossibleTravel => "impossible_travel",
+        }
+    }
+}
+
+/// A user's activity within one hour
+#[derive(Debug, Clone, Copy, Default, FromRow)]
+pub struct HourlyActivity {
+    pub requests: i64,
+    pub errors: i64,
+    pub endpoints: i64,
+}
+
+/// Anomalies in an hour of activity, given the user's average hourly volume
+/// over the preceding week
+pub fn detect_anomalies(activity: &HourlyActivity, baseline_per_hour: f64) -> Vec<AnomalyKind> {
+    let mut kinds = Vec::new();
+    if activity.requests >= SPIKE_MIN_REQUESTS
+        && activity.requests as f64 > SPIKE_FACTOR * baseline_per_hour.max(1.0)
+    {
+        kinds.push(AnomalyKind::RequestSpike);
+    }
+    if activity.requests >= ERROR_BURST_MIN_REQUESTS && activity.errors * 2 > activity.requests {
+        kinds.push(AnomalyKind::ErrorBurst);
+    }
+    if activity.endpoints >= SCAN_MIN_ENDPOINTS {
+        kinds.push(AnomalyKind::EndpointScan);
+    }
+    kinds
+}
+
+/// A successful login located by GeoIP
+#[derive(Debug, Clone, FromRow)]
+pub struct LoginLocation {
+    pub user_id: Uuid,
+    pub at: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub country: Option<String>,
+}
+
+/// Speed in km/h implied by travelling between two logins, or `None` when
+/// they are too close together to tell
+pub fn travel_speed_kmh(from: &LoginLocation, to: &LoginLocation) -> Option<f64> {
+    let km = distance_km((from.latitude, from.longitude), (to.latitude, to.longitude));
+    if km < TRAVEL_MIN_DISTANCE_KM {
+        return None;
+    }
+    // Logins in the same minute count as a minute apart
+    let hours = (to.at - from.at).num_seconds().abs().max(60) as f64 / 3600.0;
+    Some(km / hours)
+}
+
+fn humanize_gap(gap: Duration) -> String {
+    match gap.num_minutes() {
+        minutes if minutes < 60 => format!("{} min", minutes),
+        minutes => format!("{}h{:02}", minutes / 60, minutes % 60),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct UsageKey {
+    hour_start: DateTime<Utc>,
+    user_id: Uuid,
+    credential: String,
+    method: String,
+    endpoint: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct UsageCounter {
+    requests: i64,
+    errors: i64,
+    last_used_at: DateTime<Utc>,
+}
+
+/// Usage of one endpoint
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct EndpointUsage {
+    pub credential: String,
+    pub method: String,
+    pub endpoint: String,
+    pub requests: i64,
+    pub errors: i64,
+    pub error_rate: f64,
+    pub last_used_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct DailyUsage {
+    pub day: NaiveDate,
+    pub requests: i64,
+    pub errors: i64,
+}
+
+/// Flagged access pattern
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct SecurityAnomaly {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub details: String,
+    pub window_start: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// API usage of a user over a period
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UsageReport {
+    pub user_id: Uuid,
+    pub days: i64,
+    pub total_requests: i64,
+    pub total_errors: i64,
+    pub error_rate: f64,
+    /// Most used first
+    pub endpoints: Vec<EndpointUsage>,
+    pub daily: Vec<DailyUsage>,
+    pub anomalies: Vec<SecurityAnomaly>,
+}
+
+/// API usage tracker
+#[derive(Clone)]
+pub struct ApiUsageService {
+    db: PgPool,
+    pending: Arc<Mutex<HashMap<UsageKey, UsageCounter>>>,
+    /// Fastest plausible travel between logins; `None` disables the check
+    max_travel_kmh: Option<f64>,
+}
+
+impl ApiUsageService {
+    pub fn new(db: PgPool) -> Self {
+        Self {
+            db,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            max_travel_kmh: None,
+        }
+    }
+
+    /// Flag logins located (by GeoIP) too far from the user's previous login
+    pub fn with_geo_velocity(mut self, max_travel_kmh: f64) -> Self {
+        self.max_travel_kmh = (max_travel_kmh > 0.0).then_some(max_travel_kmh);
+        self
+    }
+
+    /// Count a request; persisted on the next flush
+    pub fn record(&self, identity: &UsageIdentity, method: &str, endpoint: &str, is_error: bool) {
+        let now = Utc::now();
+        let key = UsageKey {
+            hour_start: now.duration_trunc(Duration::hours(1)).unwrap_or(now),
+            user_id: identity.user_id,
+            credential: identity.credential.clone(),
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+        };
+
+        let Ok(mut pending) = self.pending.lock() else {
+            return;
+        };
+        let counter = pending.entry(key).or_insert(UsageCounter {
+            requests: 0,
+            errors: 0,
+            last_used_at: now,
+        });
+        counter.requests += 1;
+        counter.errors += is_error as i64;
+        counter.last_used_at = now;
+    }
+
+    /// Write counted requests to the hourly and daily tables.
+    /// Returns the number of buckets written.
+    pub async fn flush(&self) -> Result<usize, sqlx::Error> {
+        let batch: Vec<(UsageKey, UsageCounter)> = match self.pending.lock() {
+            Ok(mut pending) => pending.drain().collect(),
+            Err(_) => return Ok(0),
+        };
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        let mut tx = self.db.begin().await?;
+        for (key, counter) in &batch {
+            sqlx::query(
+                r#"
+                INSERT INTO api_usage_hourly
+                    (hour_start, user_id, credential, method, endpoint, request_count, error_count, last_used_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (hour_start, user_id, credential, method, endpoint) DO UPDATE SET
+                    request_count = api_usage_hourly.request_count + EXCLUDED.request_count,
+                    error_count = api_usage_hourly.error_count + EXCLUDED.error_count,
+                    last_used_at = GREATEST(api_usage_hourly.last_used_at, EXCLUDED.last_used_at)
+                "#,
+            )
+            .bind(key.hour_start)
+            .bind(key.user_id)
+            .bind(&key.credential)
+            .bind(&key.method)
+            .bind(&key.endpoint)
+            .bind(counter.requests)
+            .bind(counter.errors)
+            .bind(counter.last_used_at)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO api_usage_daily
+                    (day, user_id, credential, method, endpoint, request_count, error_count, last_used_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (day, user_id, credential, method, endpoint) DO UPDATE SET
+                    request_count = api_usage_daily.request_count + EXCLUDED.request_count,
+                    error_count = api_usage_daily.error_count + EXCLUDED.error_count,
+                    last_used_at = GREATEST(api_usage_daily.last_used_at, EXCLUDED.last_used_at)
+                "#,
+            )
+            .bind(key.hour_start.date_naive())
+            .bind(key.user_id)
+            .bind(&key.credential)
+            .bind(&key.method)
+            .bind(&key.endpoint)
+            .bind(counter.requests)
+            .bind(counter.errors)
+            .bind(counter.last_used_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(batch.len())
+    }
+
+    /// Check the last completed hour for unusual access patterns.
+    /// Returns the number of new anomalies.
+    pub async fn detect(&self) -> Result<usize, sqlx::Error> {
+        let now = Utc::now();
+        let hour_end = now.duration_trunc(Duration::hours(1)).unwrap_or(now);
+        let hour_start = hour_end - Duration::hours(1);
+        let baseline_start = hour_start - Duration::days(HOURLY_RETENTION_DAYS);
+        let baseline_hours = (HOURLY_RETENTION_DAYS * 24) as f64;
+
+        let rows: Vec<(Uuid, i64, i64, i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT cur.user_id, cur.requests, cur.errors, cur.endpoints,
+                   COALESCE((
+                       SELECT SUM(request_count)::BIGINT FROM api_usage_hourly b
+                       WHERE b.user_id = cur.user_id AND b.hour_start >= $2 AND b.hour_start < $1
+                   ), 0) AS baseline
+            FROM (
+                SELECT user_id,
+                       SUM(request_count)::BIGINT AS requests,
+                       SUM(error_count)::BIGINT AS errors,
+                       COUNT(DISTINCT (method, endpoint)) AS endpoints
+                FROM api_usage_hourly
+                WHERE hour_start = $1
+                GROUP BY user_id
+            ) cur
+            "#,
+        )
+        .bind(hour_start)
+        .bind(baseline_start)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut created = 0;
+        for (user_id, requests, errors, endpoints, baseline) in rows {
+            let activity = HourlyActivity {
+                requests,
+                errors,
+                endpoints,
+            };
+            let baseline_per_hour = baseline as f64 / baseline_hours;
+            for kind in detect_anomalies(&activity, baseline_per_hour) {
+                let details = format!(
+                    "{} requests ({} failed) across {} endpoints; usual hourly volume {:.1}",
+                    requests, errors, endpoints, baseline_per_hour
+                );
+                if self.flag(user_id, kind, &details, hour_start).await? {
+                    created += 1;
+                }
+            }
+        }
+        created += self.detect_travel(hour_start, hour_end).await?;
+        Ok(created)
+    }
+
+    /// Compare each login in the hour with the user's previous login
+    async fn detect_travel(&self, hour_start: DateTime<Utc>, hour_end: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+        let Some(max_kmh) = self.max_travel_kmh else {
+            return Ok(0);
+        };
+
+        let logins = sqlx::query_as::<_, LoginLocation>(
+            r#"
+            SELECT user_id, created_at AS at,
+                   (event_data->'geo'->>'latitude')::FLOAT8 AS latitude,
+                   (event_data->'geo'->>'longitude')::FLOAT8 AS longitude,
+                   event_data->'geo'->>'country' AS country
+            FROM audit_logs
+            WHERE event_type = 'user_login'
+              AND user_id IS NOT NULL
+              AND created_at >= $1 AND created_at < $2
+              AND event_data->'geo' ? 'latitude' AND event_data->'geo' ? 'longitude'
+            ORDER BY user_id, created_at
+            "#,
+        )
+        .bind(hour_start - Duration::hours(TRAVEL_LOOKBACK_HOURS))
+        .bind(hour_end)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut created = 0;
+        for pair in logins.windows(2) {
+            let (from, to) = (&pair[0], &pair[1]);
+            if from.user_id != to.user_id || to.at < hour_start {
+                continue;
+            }
+            let Some(speed) = travel_speed_kmh(from, to).filter(|speed| *speed > max_kmh) else {
+                continue;
+            };
+            let details = format!(
+                "Login from {} {} after login from {}; {:.0} km/h",
+                to.country.as_deref().unwrap_or("unknown country"),
+                humanize_gap(to.at - from.at),
+                from.country.as_deref().unwrap_or("unknown country"),
+                speed
+            );
+            if self.flag(to.user_id, AnomalyKind::ImpossibleTravel, &details, hour_start).await? {
+                created += 1;
+            }
+        }
+        Ok(created)
+    }
+
+    /// Record an anomaly, at most once per user, kind and window.
+    /// Returns whether it was new.
+    pub async fn flag(
+        &self,
+        user_id: Uuid,
+        kind: AnomalyKind,
+        details: &str,
+        window_start: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let inserted = sqlx::query(
+            "INSERT INTO security_anomalies (user_id, kind, details, window_start)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (user_id, kind, window_start) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .bind(details)
+        .bind(window_start)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+
+        if inserted > 0 {
+            warn!("🚨 Access anomaly for user {}: {} - {}", user_id, kind.as_str(), details);
+        }
+        Ok(inserted > 0)
+    }
+
+    /// Drop hourly buckets past the baseline window
+    pub async fn prune(&self) -> Result<u64, sqlx::Error> {
+        Ok(sqlx::query("DELETE FROM api_usage_hourly WHERE hour_start < $1")
+            .bind(Utc::now() - Duration::days(HOURLY_RETENTION_DAYS + 1))
+            .execute(&self.db)
+            .await?
+            .rows_affected())
+    }
+
+    /// A user's usage over the last `days` days
+    pub async fn report(&self, user_id: Uuid, days: i64) -> Result<UsageReport, ApiError> {
+        let since = (Utc::now() - Duration::days(days - 1)).date_naive();
+
+        let endpoints = sqlx::query_as::<_, EndpointUsage>(
+            r#"
+            SELECT credential, method, endpoint,
+                   SUM(request_count)::BIGINT AS requests,
+                   SUM(error_count)::BIGINT AS errors,
+                   (SUM(error_count)::FLOAT8 / GREATEST(SUM(request_count), 1)::FLOAT8) AS error_rate,
+                   MAX(last_used_at) AS last_used_at
+            FROM api_usage_daily
+            WHERE user_id = $1 AND day >= $2
+            GROUP BY credential, method, endpoint
+            ORDER BY requests DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?;
+
+        let daily = sqlx::query_as::<_, DailyUsage>(
+            r#"
+            SELECT day, SUM(request_count)::BIGINT AS requests, SUM(error_count)::BIGINT AS errors
+            FROM api_usage_daily
+            WHERE user_id = $1 AND day >= $2
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?;
+
+        let anomalies = self.anomalies(Some(user_id), 50).await?;
+
+        let total_requests: i64 = daily.iter().map(|d| d.requests).sum();
+        let total_errors: i64 = daily.iter().map(|d| d.errors).sum();
+        Ok(UsageReport {
+            user_id,
+            days,
+            total_requests,
+            total_errors,
+            error_rate: if total_requests == 0 {
+                0.0
+            } else {
+                total_errors as f64 / total_requests as f64
+            },
+            endpoints,
+            daily,
+            anomalies,
+        })
+    }
+
+    /// Flagged access patterns, newest first
+    pub async fn anomalies(
+        &self,
+        user_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<SecurityAnomaly>, ApiError> {
+        Ok(sqlx::query_as::<_, SecurityAnomaly>(
+            r#"
+            SELECT id, user_id, kind, details, window_start, created_at
+            FROM security_anomalies
+            WHERE ($1::UUID IS NULL OR user_id = $1)
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_endpoint_replaces_identifiers() {
+        assert_eq!(
+            normalize_endpoint("/api/v1/trading/orders/3f2b8c1e-9a4d-4b7e-8f1a-2c3d4e5f6a7b/events"),
+            "/api/v1/trading/orders/{id}/events"
+        );
+        assert_eq!(normalize_endpoint("/api/v1/invoices/42"), "/api/v1/invoices/{id}");
+        assert_eq!(
+            normalize_endpoint("/api/v1/wallets/9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin/balance"),
+            "/api/v1/wallets/{id}/balance"
+        );
+        assert_eq!(normalize_endpoint("/api/v1/users/me"), "/api/v1/users/me");
+    }
+
+    #[test]
+    fn test_normal_activity_is_not_flagged() {
+        let activity = HourlyActivity {
+            requests: 400,
+            errors: 10,
+            endpoints: 12,
+        };
+        assert!(detect_anomalies(&activity, 100.0).is_empty());
+    }
+
+    #[test]
+    fn test_spike_against_quiet_baseline() {
+        let activity = HourlyActivity {
+            requests: 400,
+            errors: 0,
+            endpoints: 3,
+        };
+        assert_eq!(detect_anomalies(&activity, 2.0), vec![AnomalyKind::RequestSpike]);
+    }
+
+    #[test]
+    fn test_travel_speed_between_logins() {
+        let at = Utc::now();
+        let bangkok = LoginLocation {
+            user_id: Uuid::nil(),
+            at,
+            latitude: 13.7563,
+            longitude: 100.5018,
+            country: Some("TH".to_string()),
+        };
+        let london = LoginLocation {
+            at: at + Duration::hours(2),
+            latitude: 51.5074,
+            longitude: -0.1278,
+            country: Some("GB".to_string()),
+            ..bangkok.clone()
+        };
+        let nearby = LoginLocation {
+            at: at + Duration::minutes(1),
+            latitude: 13.9,
+            longitude: 100.6,
+            ..bangkok.clone()
+        };
+
+        let speed = travel_speed_kmh(&bangkok, &london).unwrap();
+        assert!(speed > 4000.0 && speed < 5000.0, "{}", speed);
+        assert_eq!(travel_speed_kmh(&bangkok, &nearby), None);
+    }
+
+    #[test]
+    fn test_error_burst_and_scan() {
+        let activity = HourlyActivity {
+            requests: 120,
+            errors: 90,
+            endpoints: SCAN_MIN_ENDPOINTS,
+        };
+        assert_eq!(
+            detect_anomalies(&activity, 100.0),
+            vec![AnomalyKind::ErrorBurst, AnomalyKind::EndpointScan]
+        );
+    }
+}
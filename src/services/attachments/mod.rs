@@ -0,0 +1,400 @@
+//! Attachment Service
+//!
+//! Stores uploaded files for KYC submissions, settlement disputes and
+//! certificate validation data. Uploads are checked for size and type by
+//! their content, passed through the malware scan hook when one is
+//! configured, and kept in local or S3-compatible object storage. Downloads
+//! go through short-lived signed URLs.
+
+pub mod s3;
+pub mod types;
+
+use std::time::Duration as StdDuration;
+
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::AttachmentsConfig;
+use crate::error::ApiError;
+use crate::services::invoicing::ObjectStorage;
+use crate::utils::signed_url;
+
+pub use s3::S3Storage;
+pub use types::{Attachment, AttachmentDownload, AttachmentOwner, ScanVerdict};
+
+/// Resource kind signed into local download links
+const DOWNLOAD_KIND: &str = "attachment";
+
+const ATTACHMENT_COLUMNS: &str = "id, uploaded_by, owner_type, owner_id, file_name, content_type, \
+     size_bytes, sha256, scan_status, created_at, storage_key";
+
+/// Content types accepted for upload
+const ALLOWED_TYPES: [&str; 6] = [
+    "application/pdf",
+    "image/png",
+    "image/jpeg",
+    "image/webp",
+    "text/csv",
+    "application/json",
+];
+
+/// Content type of a file judged by its content. Binary formats are
+/// recognised by their signature; text formats must be valid UTF-8 and
+/// match the declared type.
+pub fn detect_content_type(bytes: &[u8], declared: &str) -> Option<&'static str> {
+    if bytes.starts_with(b"%PDF-") {
+        return Some("application/pdf");
+    }
+    if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        return Some("image/png");
+    }
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some("image/jpeg");
+    }
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+
+    let text = std::str::from_utf8(bytes).ok()?;
+    let declared = declared.split(';').next().unwrap_or_default().trim();
+    match declared {
+        "text/csv" if !text.contains('\0') => Some("text/csv"),
+        "application/json" if serde_json::from_str::<serde_json::Value>(text).is_ok() => {
+            Some("application/json")
+        }
+        _ => None,
+    }
+}
+
+/// File name safe to store and to put in a Content-Disposition header
+pub fn sanitize_file_name(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | ' ') { c } else { '_' })
+        .take(200)
+        .collect();
+    let cleaned = cleaned.trim().trim_start_matches('.').to_string();
+    if cleaned.is_empty() {
+        "attachment".to_string()
+    } else {
+        cleaned
+    }
+}
+
+fn extension_for(content_type: &str) -> &'static str {
+    match content_type {
+        "application/pdf" => "pdf",
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/webp" => "webp",
+        "text/csv" => "csv",
+        "application/json" => "json",
+        _ => "bin",
+    }
+}
+
+#[derive(Debug, Clone)]
+enum AttachmentStorage {
+    Local(ObjectStorage),
+    S3(S3Storage),
+}
+
+/// Response of the malware scanner
+#[derive(Debug, Deserialize)]
+struct ScanResponse {
+    infected: bool,
+    signature: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct AttachmentService {
+    db: PgPool,
+    config: AttachmentsConfig,
+    storage: AttachmentStorage,
+    http: Client,
+}
+
+impl AttachmentService {
+    pub fn new(db: PgPool, config: AttachmentsConfig) -> anyhow::Result<Self> {
+        let storage = match config.backend.as_str() {
+            "local" => AttachmentStorage::Local(ObjectStorage::new(config.storage_dir.clone())),
+            "s3" => AttachmentStorage::S3(S3Storage::new(&config)?),
+            other => return Err(anyhow::anyhow!("Unknown ATTACHMENT_STORAGE backend: {}", other)),
+        };
+        let http = Client::builder()
+            .timeout(StdDuration::from_secs(config.scan_timeout_secs))
+            .build()?;
+
+        Ok(Self { db, config, storage, http })
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        self.config.max_bytes
+    }
+
+    /// Validate, scan and store an upload, then record it against its owner.
+    /// The caller must have checked access with `ensure_access` first.
+    pub async fn upload(
+        &self,
+        user_id: Uuid,
+        owner: AttachmentOwner,
+        owner_id: Uuid,
+        file_name: &str,
+        declared_type: &str,
+        bytes: &[u8],
+    ) -> Result<Attachment, ApiError> {
+        if bytes.is_empty() {
+            return Err(ApiError::validation_field("file", "File is empty"));
+        }
+        if bytes.len() > self.config.max_bytes {
+            return Err(ApiError::validation_field(
+                "file",
+                format!("File exceeds {} bytes", self.config.max_bytes),
+            ));
+        }
+        let content_type = detect_content_type(bytes, declared_type).ok_or_else(|| {
+            ApiError::validation_field(
+                "file",
+                format!("Unsupported file type. Allowed: {}", ALLOWED_TYPES.join(", ")),
+            )
+        })?;
+
+        let scan_status = match self.scan(bytes).await? {
+            ScanVerdict::Clean => "clean",
+            ScanVerdict::Unscanned => "unscanned",
+            ScanVerdict::Infected(signature) => {
+                warn!(
+                    "🦠 Rejected upload from user {} for {} {}: {}",
+                    user_id,
+                    owner.as_str(),
+                    owner_id,
+                    signature
+                );
+                return Err(ApiError::validation_field("file", "File failed the malware scan"));
+            }
+        };
+
+        let id = Uuid::new_v4();
+        let storage_key = format!(
+            "attachments/{}/{}/{}.{}",
+            owner.as_str(),
+            owner_id,
+            id,
+            extension_for(content_type)
+        );
+        let stored = match &self.storage {
+            AttachmentStorage::Local(storage) => storage.put(&storage_key, bytes).await,
+            AttachmentStorage::S3(storage) => storage.put(&storage_key, bytes, content_type).await,
+        };
+        stored.map_err(|e| ApiError::ExternalService(format!("Failed to store attachment: {}", e)))?;
+
+        let attachment = sqlx::query_as::<_, Attachment>(&format!(
+            r#"
+            INSERT INTO attachments (
+                id, uploaded_by, owner_type, owner_id, file_name, content_type,
+                size_bytes, sha256, storage_key, scan_status
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING {}
+            "#,
+            ATTACHMENT_COLUMNS
+        ))
+        .bind(id)
+        .bind(user_id)
+        .bind(owner.as_str())
+        .bind(owner_id)
+        .bind(sanitize_file_name(file_name))
+        .bind(content_type)
+        .bind(bytes.len() as i64)
+        .bind(hex::encode(Sha256::digest(bytes)))
+        .bind(&storage_key)
+        .bind(scan_status)
+        .fetch_one(&self.db)
+        .await?;
+
+        info!(
+            "📎 Stored attachment {} ({} bytes, {}) for {} {}",
+            attachment.id,
+            attachment.size_bytes,
+            attachment.scan_status,
+            owner.as_str(),
+            owner_id
+        );
+        Ok(attachment)
+    }
+
+    /// Check that a user may read and add attachments of an owner. Unknown
+    /// owners and owners of other users are both reported as not found.
+    pub async fn ensure_access(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        owner: AttachmentOwner,
+        owner_id: Uuid,
+    ) -> Result<(), ApiError> {
+        if is_admin && owner != AttachmentOwner::Kyc {
+            let exists = match owner {
+                AttachmentOwner::Dispute => "SELECT EXISTS(SELECT 1 FROM settlement_disputes WHERE id = $1)",
+                _ => "SELECT EXISTS(SELECT 1 FROM erc_certificates WHERE id = $1)",
+            };
+            let found = sqlx::query_scalar::<_, bool>(exists).bind(owner_id).fetch_one(&self.db).await?;
+            return if found {
+                Ok(())
+            } else {
+                Err(ApiError::NotFound(format!("{} not found", owner.as_str())))
+            };
+        }
+
+        let allowed = match owner {
+            AttachmentOwner::Kyc => is_admin || owner_id == user_id,
+            AttachmentOwner::Dispute => {
+                sqlx::query_scalar::<_, bool>(
+                    r#"
+                    SELECT EXISTS(
+                        SELECT 1 FROM settlement_disputes
+                        WHERE id = $1 AND (raised_by = $2 OR counterparty_id = $2)
+                    )
+                    "#,
+                )
+                .bind(owner_id)
+                .bind(user_id)
+                .fetch_one(&self.db)
+                .await?
+            }
+            AttachmentOwner::Certificate => {
+                sqlx::query_scalar::<_, bool>(
+                    "SELECT EXISTS(SELECT 1 FROM erc_certificates WHERE id = $1 AND user_id = $2)",
+                )
+                .bind(owner_id)
+                .bind(user_id)
+                .fetch_one(&self.db)
+                .await?
+            }
+        };
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(ApiError::NotFound(format!("{} not found", owner.as_str())))
+        }
+    }
+
+    /// Attachments of an owner, newest first
+    pub async fn list(&self, owner: AttachmentOwner, owner_id: Uuid) -> Result<Vec<Attachment>, ApiError> {
+        Ok(sqlx::query_as::<_, Attachment>(&format!(
+            "SELECT {} FROM attachments WHERE owner_type = $1 AND owner_id = $2 ORDER BY created_at DESC",
+            ATTACHMENT_COLUMNS
+        ))
+        .bind(owner.as_str())
+        .bind(owner_id)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn get(&self, attachment_id: Uuid) -> Result<Attachment, ApiError> {
+        sqlx::query_as::<_, Attachment>(&format!(
+            "SELECT {} FROM attachments WHERE id = $1",
+            ATTACHMENT_COLUMNS
+        ))
+        .bind(attachment_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Attachment not found".to_string()))
+    }
+
+    /// Short-lived download URL: presigned by the object store for S3,
+    /// signed by this API for local storage
+    pub fn download_url(&self, attachment: &Attachment) -> AttachmentDownload {
+        let signed_urls = &self.config.signed_urls;
+        let expires_at = signed_url::expires_at(signed_urls);
+
+        let url = match &self.storage {
+            AttachmentStorage::S3(storage) => {
+                storage.presigned_get(&attachment.storage_key, &attachment.file_name, signed_urls.url_ttl_secs)
+            }
+            AttachmentStorage::Local(_) => signed_url::sign_url(
+                signed_urls,
+                DOWNLOAD_KIND,
+                &format!("/api/v1/attachments/{}/download", attachment.id),
+                attachment.id,
+                expires_at,
+            ),
+        };
+
+        AttachmentDownload { url, expires_at }
+    }
+
+    /// Check a local download signature and its expiry
+    pub fn verify_download(&self, attachment_id: Uuid, expires: i64, signature: &str) -> bool {
+        signed_url::verify(&self.config.signed_urls, DOWNLOAD_KIND, attachment_id, expires, signature)
+    }
+
+    /// Read an attachment from local storage; S3 objects are downloaded from the store directly
+    pub async fn load_local(&self, attachment: &Attachment) -> anyhow::Result<Vec<u8>> {
+        match &self.storage {
+            AttachmentStorage::Local(storage) => storage.get(&attachment.storage_key).await,
+            AttachmentStorage::S3(_) => Err(anyhow::anyhow!("Attachments are served by the object store")),
+        }
+    }
+
+    /// Malware scan hook. The scanner receives the file as the request body
+    /// and answers `{"infected": bool, "signature": string?}`.
+    async fn scan(&self, bytes: &[u8]) -> Result<ScanVerdict, ApiError> {
+        let Some(url) = &self.config.scan_url else {
+            return Ok(ScanVerdict::Unscanned);
+        };
+
+        let response = self
+            .http
+            .post(url)
+            .header("content-type", "application/octet-stream")
+            .body(bytes.to_vec())
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ApiError::ExternalService(format!("Malware scanner unavailable: {}", e)))?;
+        let verdict: ScanResponse = response
+            .json()
+            .await
+            .map_err(|e| ApiError::ExternalService(format!("Invalid malware scanner response: {}", e)))?;
+
+        Ok(if verdict.infected {
+            ScanVerdict::Infected(verdict.signature.unwrap_or_else(|| "unknown".to_string()))
+        } else {
+            ScanVerdict::Clean
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_content_type_by_signature() {
+        assert_eq!(detect_content_type(b"%PDF-1.7\n...", "image/png"), Some("application/pdf"));
+        assert_eq!(detect_content_type(&[0xFF, 0xD8, 0xFF, 0xE0, 0, 0], ""), Some("image/jpeg"));
+        assert_eq!(detect_content_type(b"RIFF\0\0\0\0WEBPVP8 ", ""), Some("image/webp"));
+    }
+
+    #[test]
+    fn test_detect_content_type_text_needs_declared_type() {
+        assert_eq!(detect_content_type(b"meter,kwh\nm1,3\n", "text/csv; charset=utf-8"), Some("text/csv"));
+        assert_eq!(detect_content_type(b"{\"reading\": 3}", "application/json"), Some("application/json"));
+        assert_eq!(detect_content_type(b"{not json", "application/json"), None);
+        assert_eq!(detect_content_type(b"MZ\x90\0 executable", "application/pdf"), None);
+        assert_eq!(detect_content_type(b"<html></html>", "text/html"), None);
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_file_name("C:\\docs\\id card.pdf"), "id card.pdf");
+        assert_eq!(sanitize_file_name("bill\"; x=.pdf"), "bill__ x_.pdf");
+        assert_eq!(sanitize_file_name(".."), "attachment");
+    }
+}
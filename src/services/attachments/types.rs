@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// What an attachment belongs to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentOwner {
+    /// Identity documents of a user; the owner ID is the user ID
+    Kyc,
+    /// Evidence for a settlement dispute
+    Dispute,
+    /// Validation data of an energy certificate
+    Certificate,
+}
+
+impl AttachmentOwner {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Kyc => "kyc",
+            Self::Dispute => "dispute",
+            Self::Certificate => "certificate",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "kyc" => Some(Self::Kyc),
+            "dispute" => Some(Self::Dispute),
+            "certificate" => Some(Self::Certificate),
+            _ => None,
+        }
+    }
+}
+
+/// Result of the malware scan of an upload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    Infected(String),
+    /// No scanner is configured
+    Unscanned,
+}
+
+/// Stored attachment
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub uploaded_by: Uuid,
+    pub owner_type: String,
+    pub owner_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Hex SHA-256 of the content
+    pub sha256: String,
+    /// clean or unscanned
+    pub scan_status: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub storage_key: String,
+}
+
+/// Time-limited download link for an attachment
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AttachmentDownload {
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,160 @@
+//! Audit Event Buffer
+//!
+//! Redis list holding audit events that could not be written to PostgreSQL.
+//! New events are pushed at the head and the list is trimmed to its limit,
+//! so the oldest events are dropped first when it is full. Replays pop from
+//! the tail, oldest first; a pop is exclusive, so gateway instances sharing
+//! the buffer never replay the same event twice.
+
+use std::num::NonZeroUsize;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use metrics::{counter, gauge};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::types::{AuditOutcome, AuditSeverity};
+
+const BUFFER_KEY: &str = "audit:buffer";
+
+/// An audit event as written to `audit_logs`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub event_type: String,
+    pub user_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub event_data: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub severity: AuditSeverity,
+    pub actor_id: Option<Uuid>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub outcome: AuditOutcome,
+}
+
+/// Records dropped from the tail when a push grew the list to `len`
+fn dropped_on_push(len: usize, max_events: usize) -> usize {
+    len.saturating_sub(max_events)
+}
+
+/// Payloads to push back at the tail so that `records[0]` is popped first
+fn restore_payloads(records: &[AuditRecord]) -> serde_json::Result<Vec<String>> {
+    records.iter().rev().map(serde_json::to_string).collect()
+}
+
+/// Bounded Redis buffer shared by every gateway instance
+#[derive(Debug, Clone)]
+pub struct AuditBuffer {
+    redis: redis::Client,
+    max_events: usize,
+}
+
+impl AuditBuffer {
+    pub fn new(redis: redis::Client, max_events: usize) -> Self {
+        Self { redis, max_events }
+    }
+
+    /// Buffer a record. Returns how many old records were dropped to make room.
+    pub async fn push(&self, record: &AuditRecord) -> Result<usize> {
+        let payload = serde_json::to_string(record)?;
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let (len,): (usize,) = redis::pipe()
+            .atomic()
+            .lpush(BUFFER_KEY, payload)
+            .ltrim(BUFFER_KEY, 0, self.max_events as isize - 1)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+
+        let dropped = dropped_on_push(len, self.max_events);
+        gauge!("audit_buffer_depth").set(len.min(self.max_events) as f64);
+        Ok(dropped)
+    }
+
+    /// Take up to `count` of the oldest records, oldest first
+    pub async fn pop_oldest(&self, count: usize) -> Result<Vec<AuditRecord>> {
+        let Some(count) = NonZeroUsize::new(count) else {
+            return Ok(Vec::new());
+        };
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let payloads: Vec<String> = conn.rpop(BUFFER_KEY, Some(count)).await?;
+
+        let mut records = Vec::with_capacity(payloads.len());
+        for payload in payloads {
+            match serde_json::from_str(&payload) {
+                Ok(record) => records.push(record),
+                Err(e) => {
+                    counter!("audit_events_dropped_total", "reason" => "corrupt").increment(1);
+                    tracing::error!(error = %e, "Discarding unreadable buffered audit event");
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    /// Put records that could not be replayed back at the tail, keeping
+    /// `records[0]` the oldest
+    pub async fn restore(&self, records: &[AuditRecord]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let payloads = restore_payloads(records)?;
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let _: () = conn.rpush(BUFFER_KEY, payloads).await?;
+        Ok(())
+    }
+
+    pub async fn depth(&self) -> Result<usize> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let len: usize = conn.llen(BUFFER_KEY).await?;
+        gauge!("audit_buffer_depth").set(len as f64);
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(event_type: &str) -> AuditRecord {
+        AuditRecord {
+            event_type: event_type.to_string(),
+            user_id: Some(Uuid::new_v4()),
+            ip_address: Some("10.0.0.1".to_string()),
+            event_data: serde_json::json!({ "type": event_type }),
+            created_at: Utc::now(),
+            severity: AuditSeverity::Warning,
+            actor_id: None,
+            resource_type: Some("order".to_string()),
+            resource_id: Some("42".to_string()),
+            outcome: AuditOutcome::Denied,
+        }
+    }
+
+    #[test]
+    fn test_record_round_trips_through_the_buffer() {
+        let original = record("login_failed");
+        let payload = serde_json::to_string(&original).unwrap();
+        assert_eq!(serde_json::from_str::<AuditRecord>(&payload).unwrap(), original);
+    }
+
+    #[test]
+    fn test_dropped_on_push() {
+        assert_eq!(dropped_on_push(3, 10), 0);
+        assert_eq!(dropped_on_push(10, 10), 0);
+        assert_eq!(dropped_on_push(11, 10), 1);
+    }
+
+    #[test]
+    fn test_restore_keeps_oldest_first() {
+        let records = vec![record("oldest"), record("middle"), record("newest")];
+        let payloads = restore_payloads(&records).unwrap();
+
+        // RPUSH appends in order, so the last payload ends up at the tail
+        // and is popped first
+        let popped: Vec<AuditRecord> = payloads.iter().rev().map(|p| serde_json::from_str(p).unwrap()).collect();
+        assert_eq!(popped, records);
+    }
+}
@@ -1,32 +1,136 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use metrics::counter;
 use sqlx::types::ipnetwork::IpNetwork;
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 use uuid::Uuid;
 
+use crate::database::{IndexedPredicates, QueryFilter};
+use crate::services::geoip::GeoIpService;
+
+pub mod buffer;
 pub mod types;
-pub use types::{AuditEvent, AuditEventRecord};
+pub use buffer::{AuditBuffer, AuditRecord};
+pub use types::{AuditEvent, AuditEventRecord, AuditOutcome, AuditSeverity};
+
+const RECORD_COLUMNS: &str = "id, event_type, user_id, ip_address, event_data, created_at, \
+    severity, actor_id, resource_type, resource_id, outcome";
+
+/// Audit log query; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub user_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub event_type: Option<String>,
+    pub severity: Option<AuditSeverity>,
+    pub outcome: Option<AuditOutcome>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// ISO country code of the client IP
+    pub country: Option<String>,
+    /// Autonomous system number of the client IP
+    pub asn: Option<i64>,
+    pub limit: i64,
+}
+
+/// Predicates of an audit log search that an index can serve
+pub const SEARCH_INDEXES: IndexedPredicates = IndexedPredicates {
+    columns: &["user_id", "actor_id", "event_type", "resource_type", "geo_country", "geo_asn"],
+    time_column: Some("created_at"),
+    max_range_days: 31,
+};
+
+impl AuditLogFilter {
+    /// The filter as query governor predicates
+    pub fn predicates(&self) -> QueryFilter {
+        let mut filter = QueryFilter::new();
+        if let Some(user_id) = self.user_id {
+            filter = filter.eq("user_id", user_id);
+        }
+        if let Some(actor_id) = self.actor_id {
+            filter = filter.eq("actor_id", actor_id);
+        }
+        if let Some(event_type) = &self.event_type {
+            filter = filter.eq("event_type", event_type.as_str());
+        }
+        if let Some(severity) = self.severity {
+            filter = filter.eq("severity", format!("{:?}", severity).to_lowercase());
+        }
+        if let Some(outcome) = self.outcome {
+            filter = filter.eq("outcome", format!("{:?}", outcome).to_lowercase());
+        }
+        if let Some(resource_type) = &self.resource_type {
+            filter = filter.eq("resource_type", resource_type.as_str());
+        }
+        if let Some(resource_id) = &self.resource_id {
+            filter = filter.eq("resource_id", resource_id.as_str());
+        }
+        if let Some(from) = self.from {
+            filter = filter.gte("created_at", from);
+        }
+        if let Some(to) = self.to {
+            filter = filter.lt("created_at", to);
+        }
+        if let Some(country) = &self.country {
+            filter = filter.eq("geo_country", country.to_uppercase());
+        }
+        if let Some(asn) = self.asn {
+            filter = filter.eq("geo_asn", asn);
+        }
+        filter
+    }
+}
 
 /// Audit logger service
 #[derive(Debug, Clone)]
 pub struct AuditLogger {
     db: PgPool,
+    /// Holds fire-and-forget events while PostgreSQL is unavailable
+    buffer: Option<AuditBuffer>,
+    geoip: GeoIpService,
 }
 
 impl AuditLogger {
     /// Create a new audit logger
     pub fn new(db: PgPool) -> Self {
-        Self { db }
+        Self {
+            db,
+            buffer: None,
+            geoip: GeoIpService::default(),
+        }
+    }
+
+    /// Buffer events from `log_async` that fail to insert
+    pub fn with_buffer(mut self, buffer: AuditBuffer) -> Self {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    /// Add client location and network to events that carry an IP
+    pub fn with_geoip(mut self, geoip: GeoIpService) -> Self {
+        self.geoip = geoip;
+        self
     }
 
     /// Log an audit event to the database
     pub async fn log(&self, event: AuditEvent) -> Result<(), sqlx::Error> {
+        self.insert(&self.enriched_record(&event)).await
+    }
+
+    /// Record for an event, with `geo` added to its data when the IP is known
+    fn enriched_record(&self, event: &AuditEvent) -> AuditRecord {
+        let mut record = Self::record(event);
+        let geo = record.ip_address.as_deref().and_then(|ip| self.geoip.lookup(ip));
+        if let (Some(geo), Some(data)) = (geo, record.event_data.as_object_mut()) {
+            data.insert("geo".to_string(), serde_json::json!(geo));
+        }
+        record
+    }
+
+    fn record(event: &AuditEvent) -> AuditRecord {
         let event_type = event.event_type();
-        let user_id = event.user_id();
-        let ip_address_str = event.ip_address().map(|s| s.to_string());
-        let ip_address = ip_address_str
-            .as_deref()
-            .and_then(|s| s.parse::<IpNetwork>().ok());
-        let event_data = match serde_json::to_value(&event) {
+        let event_data = match serde_json::to_value(event) {
             Ok(data) => data,
             Err(e) => {
                 tracing::error!("Failed to serialize audit event: {}. Event: {:?}", e, event);
@@ -38,28 +142,56 @@ impl AuditLogger {
                 })
             }
         };
-        let created_at = Utc::now();
+        let (resource_type, resource_id) = match event.resource() {
+            Some((resource_type, resource_id)) => (Some(resource_type.to_string()), Some(resource_id)),
+            None => (None, None),
+        };
+        AuditRecord {
+            event_type: event_type.to_string(),
+            user_id: event.user_id(),
+            ip_address: event.ip_address().map(|s| s.to_string()),
+            event_data,
+            created_at: Utc::now(),
+            severity: event.severity(),
+            actor_id: event.actor_id(),
+            resource_type,
+            resource_id,
+            outcome: event.outcome(),
+        }
+    }
+
+    async fn insert(&self, record: &AuditRecord) -> Result<(), sqlx::Error> {
+        let ip_address = record
+            .ip_address
+            .as_deref()
+            .and_then(|s| s.parse::<IpNetwork>().ok());
 
-        // Use user_activities table instead of audit_logs which might be missing
-        // Also use query() instead of query!() to avoid compile-time checks failing
         sqlx::query(
             r#"
-            INSERT INTO user_activities (activity_type, user_id, ip_address, metadata, created_at)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO audit_logs (
+                event_type, user_id, ip_address, event_data, created_at,
+                severity, actor_id, resource_type, resource_id, outcome
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             "#,
         )
-        .bind(event_type)
-        .bind(user_id)
+        .bind(&record.event_type)
+        .bind(record.user_id)
         .bind(ip_address)
-        .bind(event_data)
-        .bind(created_at)
+        .bind(&record.event_data)
+        .bind(record.created_at)
+        .bind(record.severity)
+        .bind(record.actor_id)
+        .bind(&record.resource_type)
+        .bind(&record.resource_id)
+        .bind(record.outcome)
         .execute(&self.db)
         .await?;
 
         // Log to application logs as well for immediate visibility
         tracing::info!(
-            event_type = event_type,
-            user_id = ?user_id,
+            event_type = %record.event_type,
+            user_id = ?record.user_id,
             ip = ?ip_address,
             "Audit event logged"
         );
@@ -68,38 +200,81 @@ impl AuditLogger {
     }
 
     /// Log event without awaiting (fire-and-forget)
-    /// Useful for non-critical logging that shouldn't block the request
+    /// Useful for non-critical logging that shouldn't block the request.
+    /// Events that fail to insert are buffered and replayed by `flush_buffer`.
     pub fn log_async(&self, event: AuditEvent) {
         let logger = self.clone();
         tokio::spawn(async move {
-            if let Err(e) = logger.log(event).await {
+            let record = logger.enriched_record(&event);
+            let Err(e) = logger.insert(&record).await else {
+                return;
+            };
+            let Some(buffer) = &logger.buffer else {
+                counter!("audit_events_dropped_total", "reason" => "unbuffered").increment(1);
                 tracing::error!(error = %e, "Failed to log audit event");
+                return;
+            };
+            match buffer.push(&record).await {
+                Ok(dropped) => {
+                    counter!("audit_events_buffered_total").increment(1);
+                    tracing::warn!(error = %e, event_type = %record.event_type, "Audit event buffered for replay");
+                    if dropped > 0 {
+                        counter!("audit_events_dropped_total", "reason" => "buffer_full").increment(dropped as u64);
+                        tracing::error!("Audit buffer full; dropped {} oldest event(s)", dropped);
+                    }
+                }
+                Err(buffer_error) => {
+                    counter!("audit_events_dropped_total", "reason" => "buffer_unavailable").increment(1);
+                    tracing::error!(
+                        error = %e,
+                        buffer_error = %buffer_error,
+                        event_type = %record.event_type,
+                        "Failed to log or buffer audit event"
+                    );
+                }
             }
         });
     }
 
+    /// Replay buffered events into the database, oldest first, up to
+    /// `batch` events. Stops at the first failed insert and keeps the rest
+    /// buffered. Returns the number of events replayed.
+    pub async fn flush_buffer(&self, batch: usize) -> anyhow::Result<usize> {
+        let Some(buffer) = &self.buffer else {
+            return Ok(0);
+        };
+        let records = buffer.pop_oldest(batch).await?;
+
+        let mut replayed = 0;
+        for record in &records {
+            if let Err(e) = self.insert(record).await {
+                buffer.restore(&records[replayed..]).await?;
+                buffer.depth().await?;
+                return Err(anyhow::anyhow!(
+                    "Audit replay stopped after {} event(s): {}",
+                    replayed,
+                    e
+                ));
+            }
+            replayed += 1;
+            counter!("audit_events_replayed_total").increment(1);
+        }
+        buffer.depth().await?;
+        Ok(replayed)
+    }
+
     /// Query recent events for a user
     pub async fn get_user_events(
         &self,
         user_id: Uuid,
         limit: i64,
     ) -> Result<Vec<AuditEventRecord>, sqlx::Error> {
-        // Map user_activities columns to AuditEventRecord fields
-        let records = sqlx::query_as::<_, AuditEventRecord>(
-            r#"
-            SELECT id, activity_type as event_type, user_id, ip_address, metadata as event_data, created_at
-            FROM user_activities
-            WHERE user_id = $1
-            ORDER BY created_at DESC
-            LIMIT $2
-            "#,
-        )
-        .bind(user_id)
-        .bind(limit)
-        .fetch_all(&self.db)
-        .await?;
-
-        Ok(records)
+        self.search(&AuditLogFilter {
+            user_id: Some(user_id),
+            limit,
+            ..Default::default()
+        })
+        .await
     }
 
     /// Query events by type
@@ -108,21 +283,12 @@ impl AuditLogger {
         event_type: &str,
         limit: i64,
     ) -> Result<Vec<AuditEventRecord>, sqlx::Error> {
-        let records = sqlx::query_as::<_, AuditEventRecord>(
-            r#"
-            SELECT id, activity_type as event_type, user_id, ip_address, metadata as event_data, created_at
-            FROM user_activities
-            WHERE activity_type = $1
-            ORDER BY created_at DESC
-            LIMIT $2
-            "#,
-        )
-        .bind(event_type)
-        .bind(limit)
-        .fetch_all(&self.db)
-        .await?;
-
-        Ok(records)
+        self.search(&AuditLogFilter {
+            event_type: Some(event_type.to_string()),
+            limit,
+            ..Default::default()
+        })
+        .await
     }
 
     /// Get recent security events (unauthorized access, failed logins, rate limits)
@@ -130,15 +296,16 @@ impl AuditLogger {
         &self,
         limit: i64,
     ) -> Result<Vec<AuditEventRecord>, sqlx::Error> {
-        let records = sqlx::query_as::<_, AuditEventRecord>(
+        let records = sqlx::query_as::<_, AuditEventRecord>(&format!(
             r#"
-            SELECT id, activity_type as event_type, user_id, ip_address, metadata as event_data, created_at
-            FROM user_activities
-            WHERE activity_type IN ('unauthorized_access', 'login_failed', 'rate_limit_exceeded')
+            SELECT {}
+            FROM audit_logs
+            WHERE event_type IN ('unauthorized_access', 'login_failed', 'rate_limit_exceeded')
             ORDER BY created_at DESC
             LIMIT $1
             "#,
-        )
+            RECORD_COLUMNS
+        ))
         .bind(limit)
         .fetch_all(&self.db)
         .await?;
@@ -151,16 +318,56 @@ impl AuditLogger {
         &self,
         limit: i64,
     ) -> Result<Vec<AuditEventRecord>, sqlx::Error> {
-        let records = sqlx::query_as::<_, AuditEventRecord>(
+        self.search(&AuditLogFilter {
+            limit,
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Events matching every set filter, newest first
+    pub async fn search(&self, filter: &AuditLogFilter) -> Result<Vec<AuditEventRecord>, sqlx::Error> {
+        Self::search_in(&self.db, filter).await
+    }
+
+    /// `search` on a given connection, e.g. of a governed query
+    pub async fn search_in<'e, E: PgExecutor<'e>>(
+        executor: E,
+        filter: &AuditLogFilter,
+    ) -> Result<Vec<AuditEventRecord>, sqlx::Error> {
+        let records = sqlx::query_as::<_, AuditEventRecord>(&format!(
             r#"
-            SELECT id, activity_type as event_type, user_id, ip_address, metadata as event_data, created_at
-            FROM user_activities
+            SELECT {}
+            FROM audit_logs
+            WHERE ($1::uuid IS NULL OR user_id = $1)
+              AND ($2::uuid IS NULL OR actor_id = $2)
+              AND ($3::text IS NULL OR event_type = $3)
+              AND ($4::audit_severity IS NULL OR severity = $4)
+              AND ($5::audit_outcome IS NULL OR outcome = $5)
+              AND ($6::text IS NULL OR resource_type = $6)
+              AND ($7::text IS NULL OR resource_id = $7)
+              AND ($8::timestamptz IS NULL OR created_at >= $8)
+              AND ($9::timestamptz IS NULL OR created_at < $9)
+              AND ($10::text IS NULL OR event_data->'geo'->>'country' = $10)
+              AND ($11::bigint IS NULL OR (event_data->'geo'->>'asn')::bigint = $11)
             ORDER BY created_at DESC
-            LIMIT $1
+            LIMIT $12
             "#,
-        )
-        .bind(limit)
-        .fetch_all(&self.db)
+            RECORD_COLUMNS
+        ))
+        .bind(filter.user_id)
+        .bind(filter.actor_id)
+        .bind(&filter.event_type)
+        .bind(filter.severity)
+        .bind(filter.outcome)
+        .bind(&filter.resource_type)
+        .bind(&filter.resource_id)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.country.as_deref().map(str::to_uppercase))
+        .bind(filter.asn)
+        .bind(filter.limit)
+        .fetch_all(executor)
         .await?;
 
         Ok(records)
@@ -235,4 +442,31 @@ mod tests {
         assert_eq!(json["type"], "order_created");
         assert!(json["order_id"].is_string());
     }
+
+    #[test]
+    fn test_typed_columns() {
+        let admin_id = Uuid::new_v4();
+        let hold_id = Uuid::new_v4();
+        let record = AuditLogger::record(&AuditEvent::AuditLegalHoldChanged {
+            admin_id,
+            hold_id,
+            held_user_id: None,
+            action: "placed".to_string(),
+            reason: "case 42".to_string(),
+        });
+        assert_eq!(record.actor_id, Some(admin_id));
+        assert_eq!(record.resource_type.as_deref(), Some("legal_hold"));
+        assert_eq!(record.resource_id, Some(hold_id.to_string()));
+        assert_eq!(record.severity, AuditSeverity::Info);
+        assert_eq!(record.outcome, AuditOutcome::Success);
+
+        let record = AuditLogger::record(&AuditEvent::RateLimitExceeded {
+            ip: "127.0.0.1".to_string(),
+            endpoint: "/api/auth/login".to_string(),
+        });
+        assert_eq!(record.actor_id, None);
+        assert_eq!(record.resource_type.as_deref(), Some("endpoint"));
+        assert_eq!(record.severity, AuditSeverity::Warning);
+        assert_eq!(record.outcome, AuditOutcome::Denied);
+    }
 }
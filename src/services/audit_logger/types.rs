@@ -73,6 +73,102 @@ pub enum AuditEvent {
         target_user_id: Option<Uuid>,
         details: String,
     },
+    /// Prepaid top-up payment created at the provider
+    PrepaidTopUpInitiated {
+        user_id: Uuid,
+        topup_id: Uuid,
+        provider: String,
+        amount: String,
+    },
+    /// Prepaid credit balance changed (top-up, settlement, refund)
+    PrepaidLedgerEntry {
+        user_id: Uuid,
+        entry_type: String,
+        amount: String,
+        balance_after: String,
+        reference: String,
+    },
+    /// Settlement dispute opened by a trade party
+    DisputeOpened {
+        user_id: Uuid,
+        dispute_id: Uuid,
+        settlement_id: Uuid,
+        reason: String,
+    },
+    /// Settlement dispute withdrawn or resolved
+    DisputeClosed {
+        user_id: Uuid,
+        dispute_id: Uuid,
+        status: String,
+        compensation: Option<String>,
+    },
+    /// Payment provider webhook rejected (bad signature, unknown payment)
+    PaymentWebhookRejected { provider: String, reason: String },
+    /// Saved beneficiary added, verified or removed from an address book
+    AddressBookChanged {
+        user_id: Uuid,
+        entry_id: Uuid,
+        wallet_address: String,
+        action: String,
+    },
+    /// Account wallet replaced after proof of control
+    WalletLinked {
+        user_id: Uuid,
+        wallet_address: String,
+        previous_wallet: Option<String>,
+        method: String,
+    },
+    /// Wallet refused for signature challenges after repeated failures
+    WalletAuthBlocked {
+        user_id: Uuid,
+        wallet_address: String,
+        failures: i64,
+        blocked_until: String,
+    },
+    /// Legal hold on audit data placed or released
+    AuditLegalHoldChanged {
+        admin_id: Uuid,
+        hold_id: Uuid,
+        held_user_id: Option<Uuid>,
+        action: String,
+        reason: String,
+    },
+    /// AMI backfill batch declared, filled, approved, rejected or minted
+    AmiBackfill {
+        batch_id: Uuid,
+        gateway_id: Uuid,
+        /// Reviewing admin; `None` for gateway submissions and minting
+        admin_id: Option<Uuid>,
+        /// declared, readings_submitted, approved, rejected or minted
+        action: String,
+        details: serde_json::Value,
+    },
+    /// Risky password login and whether step-up verification was required
+    LoginRiskAssessed {
+        user_id: Uuid,
+        ip: String,
+        user_agent: Option<String>,
+        signals: Vec<String>,
+        /// step_up, or allow_no_channel when no code could be delivered
+        decision: String,
+        challenge_id: Option<Uuid>,
+    },
+    /// Code entered for a login step-up challenge
+    LoginStepUpCompleted {
+        user_id: Uuid,
+        ip: String,
+        challenge_id: Uuid,
+        /// verified, invalid_code, expired or locked
+        outcome: String,
+    },
+    /// Optional integration (email, cache, metrics) went down or recovered
+    SubsystemStateChanged {
+        subsystem: String,
+        /// degraded or recovered
+        transition: String,
+        /// Error that took the subsystem down
+        detail: Option<String>,
+    },
 }
 
 impl AuditEvent {
@@ -93,6 +189,19 @@ impl AuditEvent {
             AuditEvent::RateLimitExceeded { .. } => "rate_limit_exceeded",
             AuditEvent::DataAccess { .. } => "data_access",
             AuditEvent::AdminAction { .. } => "admin_action",
+            AuditEvent::PrepaidTopUpInitiated { .. } => "prepaid_topup_initiated",
+            AuditEvent::PrepaidLedgerEntry { .. } => "prepaid_ledger_entry",
+            AuditEvent::PaymentWebhookRejected { .. } => "payment_webhook_rejected",
+            AuditEvent::DisputeOpened { .. } => "dispute_opened",
+            AuditEvent::DisputeClosed { .. } => "dispute_closed",
+            AuditEvent::AddressBookChanged { .. } => "address_book_changed",
+            AuditEvent::WalletLinked { .. } => "wallet_linked",
+            AuditEvent::WalletAuthBlocked { .. } => "wallet_auth_blocked",
+            AuditEvent::LoginRiskAssessed { .. } => "login_risk_assessed",
+            AuditEvent::LoginStepUpCompleted { .. } => "login_step_up_completed",
+            AuditEvent::AuditLegalHoldChanged { .. } => "audit_legal_hold_changed",
+            AuditEvent::AmiBackfill { .. } => "ami_backfill",
+            AuditEvent::SubsystemStateChanged { .. } => "subsystem_state_changed",
         }
     }
 
@@ -108,14 +217,114 @@ impl AuditEvent {
             | AuditEvent::OrderCreated { user_id, .. }
             | AuditEvent::OrderCancelled { user_id, .. }
             | AuditEvent::DataAccess { user_id, .. }
+            | AuditEvent::PrepaidTopUpInitiated { user_id, .. }
+            | AuditEvent::PrepaidLedgerEntry { user_id, .. }
+            | AuditEvent::DisputeOpened { user_id, .. }
+            | AuditEvent::DisputeClosed { user_id, .. }
+            | AuditEvent::AddressBookChanged { user_id, .. }
+            | AuditEvent::WalletLinked { user_id, .. }
+            | AuditEvent::WalletAuthBlocked { user_id, .. }
+            | AuditEvent::LoginRiskAssessed { user_id, .. }
+            | AuditEvent::LoginStepUpCompleted { user_id, .. }
             | AuditEvent::AdminAction {
                 admin_id: user_id, ..
+            }
+            | AuditEvent::AuditLegalHoldChanged {
+                admin_id: user_id, ..
             } => Some(*user_id),
             AuditEvent::OrderMatched { buyer_id, .. } => Some(*buyer_id), // Prioritize buyer for indexing
             _ => None,
         }
     }
 
+    pub fn severity(&self) -> AuditSeverity {
+        match self {
+            AuditEvent::UnauthorizedAccess { .. } | AuditEvent::WalletAuthBlocked { .. } => AuditSeverity::Critical,
+            AuditEvent::LoginStepUpCompleted { outcome, .. } if outcome == "locked" => AuditSeverity::Critical,
+            AuditEvent::LoginFailed { .. }
+            | AuditEvent::RateLimitExceeded { .. }
+            | AuditEvent::PaymentWebhookRejected { .. }
+            | AuditEvent::LoginRiskAssessed { .. } => AuditSeverity::Warning,
+            AuditEvent::LoginStepUpCompleted { outcome, .. } if outcome != "verified" => AuditSeverity::Warning,
+            AuditEvent::SubsystemStateChanged { transition, .. } if transition == "degraded" => AuditSeverity::Warning,
+            _ => AuditSeverity::Info,
+        }
+    }
+
+    pub fn outcome(&self) -> AuditOutcome {
+        match self {
+            AuditEvent::LoginFailed { .. } | AuditEvent::PaymentWebhookRejected { .. } => AuditOutcome::Failure,
+            AuditEvent::LoginStepUpCompleted { outcome, .. } if outcome != "verified" => AuditOutcome::Failure,
+            AuditEvent::SubsystemStateChanged { transition, .. } if transition == "degraded" => AuditOutcome::Failure,
+            AuditEvent::UnauthorizedAccess { .. }
+            | AuditEvent::RateLimitExceeded { .. }
+            | AuditEvent::WalletAuthBlocked { .. } => AuditOutcome::Denied,
+            // Session withheld pending verification
+            AuditEvent::LoginRiskAssessed { decision, .. } if decision == "step_up" => AuditOutcome::Denied,
+            _ => AuditOutcome::Success,
+        }
+    }
+
+    /// Who performed the action; `None` for anonymous requests and events
+    /// raised by the platform itself (matching, webhooks, auth blocks)
+    pub fn actor_id(&self) -> Option<Uuid> {
+        match self {
+            AuditEvent::AdminAction { admin_id, .. } | AuditEvent::AuditLegalHoldChanged { admin_id, .. } => {
+                Some(*admin_id)
+            }
+            AuditEvent::AmiBackfill { admin_id, .. } => *admin_id,
+            AuditEvent::LoginFailed { .. }
+            | AuditEvent::UnauthorizedAccess { .. }
+            | AuditEvent::RateLimitExceeded { .. }
+            | AuditEvent::PaymentWebhookRejected { .. }
+            | AuditEvent::WalletAuthBlocked { .. }
+            | AuditEvent::OrderMatched { .. }
+            | AuditEvent::SubsystemStateChanged { .. } => None,
+            _ => self.user_id(),
+        }
+    }
+
+    /// Type and identifier of the resource the event is about
+    pub fn resource(&self) -> Option<(&str, String)> {
+        match self {
+            AuditEvent::ApiKeyGenerated { key_id, .. } => Some(("api_key", key_id.to_string())),
+            AuditEvent::BlockchainRegistration { wallet_address, .. }
+            | AuditEvent::WalletLinked { wallet_address, .. }
+            | AuditEvent::WalletAuthBlocked { wallet_address, .. } => Some(("wallet", wallet_address.clone())),
+            AuditEvent::OrderCreated { order_id, .. }
+            | AuditEvent::OrderCancelled { order_id, .. }
+            | AuditEvent::OrderMatched { order_id, .. } => Some(("order", order_id.to_string())),
+            AuditEvent::UnauthorizedAccess { endpoint, .. } | AuditEvent::RateLimitExceeded { endpoint, .. } => {
+                Some(("endpoint", endpoint.clone()))
+            }
+            AuditEvent::DataAccess {
+                resource_type,
+                resource_id,
+                ..
+            } => Some((resource_type.as_str(), resource_id.clone())),
+            AuditEvent::AdminAction { target_user_id, .. } => target_user_id.map(|id| ("user", id.to_string())),
+            AuditEvent::PrepaidTopUpInitiated { topup_id, .. } => Some(("prepaid_topup", topup_id.to_string())),
+            AuditEvent::PrepaidLedgerEntry { reference, .. } => Some(("prepaid_ledger", reference.clone())),
+            AuditEvent::DisputeOpened { dispute_id, .. } | AuditEvent::DisputeClosed { dispute_id, .. } => {
+                Some(("dispute", dispute_id.to_string()))
+            }
+            AuditEvent::PaymentWebhookRejected { provider, .. } => Some(("payment_provider", provider.clone())),
+            AuditEvent::AddressBookChanged { entry_id, .. } => Some(("address_book_entry", entry_id.to_string())),
+            AuditEvent::AuditLegalHoldChanged { hold_id, .. } => Some(("legal_hold", hold_id.to_string())),
+            AuditEvent::AmiBackfill { batch_id, .. } => Some(("ami_backfill_batch", batch_id.to_string())),
+            AuditEvent::LoginStepUpCompleted { challenge_id, .. } => Some(("login_challenge", challenge_id.to_string())),
+            AuditEvent::LoginRiskAssessed { challenge_id, .. } => {
+                challenge_id.map(|id| ("login_challenge", id.to_string()))
+            }
+            AuditEvent::SubsystemStateChanged { subsystem, .. } => Some(("subsystem", subsystem.clone())),
+            AuditEvent::UserLogin { .. }
+            | AuditEvent::UserLogout { .. }
+            | AuditEvent::LoginFailed { .. }
+            | AuditEvent::PasswordChanged { .. }
+            | AuditEvent::EmailVerified { .. } => None,
+        }
+    }
+
     /// Extract IP address if present in the event
     pub fn ip_address(&self) -> Option<&str> {
         match self {
@@ -123,7 +332,9 @@ impl AuditEvent {
             | AuditEvent::LoginFailed { ip, .. }
             | AuditEvent::PasswordChanged { ip, .. }
             | AuditEvent::UnauthorizedAccess { ip, .. }
-            | AuditEvent::RateLimitExceeded { ip, .. } => Some(ip.as_str()),
+            | AuditEvent::RateLimitExceeded { ip, .. }
+            | AuditEvent::LoginRiskAssessed { ip, .. }
+            | AuditEvent::LoginStepUpCompleted { ip, .. } => Some(ip.as_str()),
             _ => None,
         }
     }
@@ -134,9 +345,35 @@ impl AuditEvent {
 pub struct AuditEventRecord {
     pub id: Uuid,
     pub event_type: String,
+    /// User the record belongs to
     pub user_id: Option<Uuid>,
     #[schema(value_type = Option<String>)]
     pub ip_address: Option<IpNetwork>,
     pub event_data: serde_json::Value,
     pub created_at: Option<chrono::DateTime<Utc>>,
+    pub severity: AuditSeverity,
+    /// Who performed the action; absent for anonymous or system events
+    pub actor_id: Option<Uuid>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub outcome: AuditOutcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "audit_severity", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AuditSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "audit_outcome", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+    /// Refused by access control, rate limiting or a block
+    Denied,
 }
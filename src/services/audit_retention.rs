@@ -0,0 +1,369 @@
+//! Audit Retention
+//!
+//! Purges audit records (`audit_logs` and `wallet_audit_log`) older
+//! than the retention period of their event class. Legal holds exempt a
+//! user's records, a time range, or both from purging until released; holds
+//! are never deleted and every change to them is audited.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::AuditRetentionConfig;
+use crate::error::ApiError;
+use crate::services::{AuditEvent, AuditLogger};
+
+/// Audit records not covered by an active legal hold (`a` is the record)
+const NOT_HELD: &str = r#"
+    NOT EXISTS (
+        SELECT 1 FROM audit_legal_holds h
+        WHERE h.released_at IS NULL
+          AND (h.user_id IS NULL OR h.user_id = a.user_id)
+          AND (h.starts_at IS NULL OR a.created_at >= h.starts_at)
+          AND (h.ends_at IS NULL OR a.created_at <= h.ends_at)
+    )"#;
+
+const HOLD_COLUMNS: &str = "id, user_id, starts_at, ends_at, reason, placed_by, placed_at, \
+    released_by, released_at, release_reason";
+
+/// Retention class of audit events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditClass {
+    Security,
+    Account,
+    Trading,
+    Financial,
+    Admin,
+    /// Wallet audit log
+    Wallet,
+    Other,
+}
+
+impl AuditClass {
+    pub const ALL: [AuditClass; 7] = [
+        Self::Security,
+        Self::Account,
+        Self::Trading,
+        Self::Financial,
+        Self::Admin,
+        Self::Wallet,
+        Self::Other,
+    ];
+
+    /// `audit_logs` event types in this class. `Other` covers every
+    /// type not listed here and `Wallet` lives in its own table.
+    pub fn event_types(self) -> &'static [&'static str] {
+        match self {
+            Self::Security => &["login_failed", "unauthorized_access", "rate_limit_exceeded", "payment_webhook_rejected"],
+            Self::Account => &[
+                "user_login",
+                "user_logout",
+                "password_changed",
+                "email_verified",
+                "api_key_generated",
+                "blockchain_registration",
+                "address_book_changed",
+                "wallet_linked",
+                "data_access",
+            ],
+            Self::Trading => &["order_created", "order_cancelled", "order_matched"],
+            Self::Financial => &[
+                "prepaid_topup_initiated",
+                "prepaid_ledger_entry",
+                "dispute_opened",
+                "dispute_closed",
+                "ami_backfill",
+            ],
+            Self::Admin => &["admin_action", "audit_legal_hold_changed"],
+            Self::Wallet | Self::Other => &[],
+        }
+    }
+
+    pub fn classify(event_type: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|class| class.event_types().iter().any(|t| *t == event_type))
+            .unwrap_or(Self::Other)
+    }
+
+    /// Retention period in days, `None` to keep forever
+    pub fn retention_days(self, config: &AuditRetentionConfig) -> Option<i64> {
+        let days = match self {
+            Self::Security => config.security_days,
+            Self::Account => config.account_days,
+            Self::Trading => config.trading_days,
+            Self::Financial => config.financial_days,
+            Self::Admin => config.admin_days,
+            Self::Wallet => config.wallet_days,
+            Self::Other => config.other_days,
+        };
+        (days > 0).then_some(days)
+    }
+}
+
+/// Hold exempting audit records from purging
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct LegalHold {
+    pub id: Uuid,
+    /// User whose records are held; all users when absent
+    pub user_id: Option<Uuid>,
+    /// Held time range; open-ended on a missing side
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub reason: String,
+    pub placed_by: Uuid,
+    pub placed_at: DateTime<Utc>,
+    pub released_by: Option<Uuid>,
+    pub released_at: Option<DateTime<Utc>>,
+    pub release_reason: Option<String>,
+}
+
+/// Retention setting of one class
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClassRetention {
+    pub class: AuditClass,
+    /// Days kept; absent when kept forever
+    pub retention_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RetentionPolicy {
+    pub enabled: bool,
+    pub classes: Vec<ClassRetention>,
+    pub active_holds: i64,
+}
+
+/// Records purged from one class
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClassPurge {
+    pub class: AuditClass,
+    pub deleted: u64,
+}
+
+#[derive(Clone)]
+pub struct AuditRetentionService {
+    db: PgPool,
+    config: AuditRetentionConfig,
+    audit: AuditLogger,
+}
+
+impl AuditRetentionService {
+    pub fn new(db: PgPool, config: AuditRetentionConfig, audit: AuditLogger) -> Self {
+        Self { db, config, audit }
+    }
+
+    pub async fn policy(&self) -> Result<RetentionPolicy, ApiError> {
+        let active_holds: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM audit_legal_holds WHERE released_at IS NULL")
+                .fetch_one(&self.db)
+                .await?;
+
+        Ok(RetentionPolicy {
+            enabled: self.config.enabled,
+            classes: AuditClass::ALL
+                .into_iter()
+                .map(|class| ClassRetention {
+                    class,
+                    retention_days: class.retention_days(&self.config),
+                })
+                .collect(),
+            active_holds,
+        })
+    }
+
+    /// Legal holds, newest first
+    pub async fn list_holds(&self, include_released: bool) -> Result<Vec<LegalHold>, ApiError> {
+        let holds = sqlx::query_as::<_, LegalHold>(&format!(
+            "SELECT {} FROM audit_legal_holds WHERE $1 OR released_at IS NULL ORDER BY placed_at DESC",
+            HOLD_COLUMNS
+        ))
+        .bind(include_released)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(holds)
+    }
+
+    pub async fn place_hold(
+        &self,
+        admin_id: Uuid,
+        user_id: Option<Uuid>,
+        starts_at: Option<DateTime<Utc>>,
+        ends_at: Option<DateTime<Utc>>,
+        reason: &str,
+    ) -> Result<LegalHold, ApiError> {
+        if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+            if starts_at > ends_at {
+                return Err(ApiError::validation_field("ends_at", "ends_at must not be before starts_at"));
+            }
+        }
+
+        let hold = sqlx::query_as::<_, LegalHold>(&format!(
+            r#"
+            INSERT INTO audit_legal_holds (user_id, starts_at, ends_at, reason, placed_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            HOLD_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(starts_at)
+        .bind(ends_at)
+        .bind(reason.trim())
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        info!("⚖️ Legal hold {} placed by {}", hold.id, admin_id);
+        self.audit_change(admin_id, &hold, "placed", &hold.reason);
+        Ok(hold)
+    }
+
+    pub async fn release_hold(&self, admin_id: Uuid, hold_id: Uuid, reason: &str) -> Result<LegalHold, ApiError> {
+        let hold = sqlx::query_as::<_, LegalHold>(&format!(
+            r#"
+            UPDATE audit_legal_holds
+            SET released_by = $2, released_at = NOW(), release_reason = $3
+            WHERE id = $1 AND released_at IS NULL
+            RETURNING {}
+            "#,
+            HOLD_COLUMNS
+        ))
+        .bind(hold_id)
+        .bind(admin_id)
+        .bind(reason.trim())
+        .fetch_optional(&self.db)
+        .await?;
+
+        let Some(hold) = hold else {
+            let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM audit_legal_holds WHERE id = $1)")
+                .bind(hold_id)
+                .fetch_one(&self.db)
+                .await?;
+            return Err(if exists {
+                ApiError::Conflict("Legal hold already released".to_string())
+            } else {
+                ApiError::NotFound("Legal hold not found".to_string())
+            });
+        };
+
+        info!("⚖️ Legal hold {} released by {}", hold.id, admin_id);
+        self.audit_change(admin_id, &hold, "released", reason.trim());
+        Ok(hold)
+    }
+
+    /// Delete records past their class's retention period that no active
+    /// hold covers
+    pub async fn purge(&self) -> Result<Vec<ClassPurge>, ApiError> {
+        let mut purged = Vec::new();
+        if !self.config.enabled {
+            return Ok(purged);
+        }
+
+        let classified: Vec<&str> = AuditClass::ALL
+            .into_iter()
+            .flat_map(|class| class.event_types().iter().copied())
+            .collect();
+
+        for class in AuditClass::ALL {
+            let Some(days) = class.retention_days(&self.config) else {
+                continue;
+            };
+
+            let result = match class {
+                AuditClass::Wallet => {
+                    sqlx::query(&format!(
+                        "DELETE FROM wallet_audit_log a WHERE a.created_at < NOW() - make_interval(days => $1::int) AND {}",
+                        NOT_HELD
+                    ))
+                    .bind(days as i32)
+                    .execute(&self.db)
+                    .await?
+                }
+                AuditClass::Other => {
+                    sqlx::query(&format!(
+                        "DELETE FROM audit_logs a WHERE NOT (a.event_type = ANY($1)) \
+                         AND a.created_at < NOW() - make_interval(days => $2::int) AND {}",
+                        NOT_HELD
+                    ))
+                    .bind(&classified)
+                    .bind(days as i32)
+                    .execute(&self.db)
+                    .await?
+                }
+                _ => {
+                    sqlx::query(&format!(
+                        "DELETE FROM audit_logs a WHERE a.event_type = ANY($1) \
+                         AND a.created_at < NOW() - make_interval(days => $2::int) AND {}",
+                        NOT_HELD
+                    ))
+                    .bind(class.event_types())
+                    .bind(days as i32)
+                    .execute(&self.db)
+                    .await?
+                }
+            };
+
+            if result.rows_affected() > 0 {
+                info!(
+                    "🗑️ Purged {} {:?} audit records older than {} days",
+                    result.rows_affected(),
+                    class,
+                    days
+                );
+            }
+            purged.push(ClassPurge {
+                class,
+                deleted: result.rows_affected(),
+            });
+        }
+
+        Ok(purged)
+    }
+
+    fn audit_change(&self, admin_id: Uuid, hold: &LegalHold, action: &str, reason: &str) {
+        self.audit.log_async(AuditEvent::AuditLegalHoldChanged {
+            admin_id,
+            hold_id: hold.id,
+            held_user_id: hold.user_id,
+            action: action.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AuditRetentionConfig {
+        AuditRetentionConfig {
+            enabled: true,
+            security_days: 365,
+            account_days: 730,
+            trading_days: 2555,
+            financial_days: 2555,
+            admin_days: 0,
+            wallet_days: 730,
+            other_days: 365,
+        }
+    }
+
+    #[test]
+    fn test_classify_event_types() {
+        assert_eq!(AuditClass::classify("login_failed"), AuditClass::Security);
+        assert_eq!(AuditClass::classify("order_matched"), AuditClass::Trading);
+        assert_eq!(AuditClass::classify("audit_legal_hold_changed"), AuditClass::Admin);
+        assert_eq!(AuditClass::classify("something_new"), AuditClass::Other);
+    }
+
+    #[test]
+    fn test_zero_days_keeps_forever() {
+        let config = config();
+        assert_eq!(AuditClass::Admin.retention_days(&config), None);
+        assert_eq!(AuditClass::Security.retention_days(&config), Some(365));
+    }
+}
@@ -0,0 +1,664 @@
+//! Trading Autopilot
+//!
+//! Prosumers who opt in have a sell order placed for their forecast surplus
+//! at the start of each epoch. The forecast is the average surplus the user's
+//! meters exported in the same time slot over the past days, capped at what
+//! their installations' rated capacity can produce in the epoch; the order's
+//! limit price follows the clearing price index or a fixed floor. Once the
+//! epoch has cleared, whatever is left of the order is cancelled so the
+//! escrowed energy is free for the next epoch.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Row};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::AutopilotConfig;
+use crate::database::schema::types::{OrderSide, OrderType};
+use crate::error::{ApiError, Result};
+use crate::services::market_clearing::MarketClearingService;
+use crate::services::meter_installation::max_output_kwh;
+
+/// Users planned per run
+const USERS_PER_RUN: i64 = 200;
+/// Placed orders closed per run
+const CLOSE_BATCH: i64 = 200;
+
+/// How autopilot prices its sell orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AutopilotStrategy {
+    /// Latest clearing price plus `index_offset_bps`, never below `floor_price`
+    IndexLinked,
+    /// Fixed limit at `floor_price`
+    LimitFloor,
+}
+
+impl AutopilotStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::IndexLinked => "index_linked",
+            Self::LimitFloor => "limit_floor",
+        }
+    }
+
+    /// Limit price of an order, `None` when nothing to price against
+    pub fn price(&self, index: Option<Decimal>, offset_bps: i32, floor: Option<Decimal>) -> Option<Decimal> {
+        match self {
+            Self::LimitFloor => floor,
+            Self::IndexLinked => {
+                let linked = index
+                    .map(|index| (index * (Decimal::ONE + Decimal::new(offset_bps as i64, 4))).round_dp(8));
+                match (linked, floor) {
+                    (Some(linked), Some(floor)) => Some(linked.max(floor)),
+                    (linked, floor) => linked.or(floor),
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for AutopilotStrategy {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "index_linked" => Ok(Self::IndexLinked),
+            "limit_floor" => Ok(Self::LimitFloor),
+            other => Err(ApiError::Internal(format!("Unknown autopilot strategy {}", other))),
+        }
+    }
+}
+
+/// Quantity to offer: the mean daily surplus of the slot, scaled by the
+/// share offered and capped per epoch
+pub fn plan_quantity(daily_surplus: &[Decimal], sell_fraction: Decimal, max_kwh: Option<Decimal>) -> Decimal {
+    if daily_surplus.is_empty() {
+        return Decimal::ZERO;
+    }
+    let mean = daily_surplus.iter().copied().sum::<Decimal>() / Decimal::from(daily_surplus.len() as u64);
+    let quantity = (mean.max(Decimal::ZERO) * sell_fraction).round_dp(3);
+    match max_kwh {
+        Some(max) => quantity.min(max),
+        None => quantity,
+    }
+}
+
+/// A prosumer's autopilot settings
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AutopilotSettings {
+    pub enabled: bool,
+    pub strategy: AutopilotStrategy,
+    pub index_offset_bps: i32,
+    #[schema(value_type = Option<String>)]
+    pub floor_price: Option<Decimal>,
+    #[schema(value_type = String)]
+    pub sell_fraction: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub max_kwh_per_epoch: Option<Decimal>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct SettingsRow {
+    user_id: Uuid,
+    enabled: bool,
+    strategy: String,
+    index_offset_bps: i32,
+    floor_price: Option<Decimal>,
+    sell_fraction: Decimal,
+    max_kwh_per_epoch: Option<Decimal>,
+    session_token: Option<String>,
+    updated_at: DateTime<Utc>,
+}
+
+impl SettingsRow {
+    fn settings(&self) -> Result<AutopilotSettings> {
+        Ok(AutopilotSettings {
+            enabled: self.enabled,
+            strategy: self.strategy.parse()?,
+            index_offset_bps: self.index_offset_bps,
+            floor_price: self.floor_price,
+            sell_fraction: self.sell_fraction,
+            max_kwh_per_epoch: self.max_kwh_per_epoch,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+/// Opt in, change or pause autopilot
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateAutopilotRequest {
+    pub enabled: bool,
+    pub strategy: AutopilotStrategy,
+    /// Premium over the clearing price index in basis points, may be negative
+    #[validate(range(min = -5000, max = 5000))]
+    #[serde(default)]
+    pub index_offset_bps: i32,
+    /// Lowest accepted price; required for limit_floor
+    #[schema(value_type = Option<String>, example = "3.5")]
+    pub floor_price: Option<Decimal>,
+    /// Share of the forecast surplus offered, default 0.8
+    #[schema(value_type = Option<String>, example = "0.8")]
+    pub sell_fraction: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub max_kwh_per_epoch: Option<Decimal>,
+    /// Session token for wallet decryption when orders are placed
+    pub session_token: Option<String>,
+}
+
+/// What autopilot did for the user in one epoch
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct AutopilotOrder {
+    pub id: Uuid,
+    pub epoch_id: Uuid,
+    pub order_id: Option<Uuid>,
+    #[schema(value_type = String)]
+    pub forecast_kwh: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub energy_amount: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub price_per_kwh: Option<Decimal>,
+    /// planning, placed, skipped, failed or closed
+    pub status: String,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+/// Sell results of one group of orders
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct SellPerformance {
+    pub orders: i64,
+    #[schema(value_type = String)]
+    pub offered_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub filled_kwh: Decimal,
+    /// Filled per offered energy
+    #[schema(value_type = String)]
+    pub fill_rate: Decimal,
+    #[schema(value_type = String)]
+    pub revenue: Decimal,
+    /// Revenue per filled kWh, absent when nothing filled
+    #[schema(value_type = Option<String>)]
+    pub average_price: Option<Decimal>,
+}
+
+impl SellPerformance {
+    fn new(orders: i64, offered_kwh: Decimal, filled_kwh: Decimal, revenue: Decimal) -> Self {
+        Self {
+            orders,
+            offered_kwh,
+            filled_kwh,
+            fill_rate: if offered_kwh > Decimal::ZERO {
+                (filled_kwh / offered_kwh).round_dp(4)
+            } else {
+                Decimal::ZERO
+            },
+            revenue,
+            average_price: (filled_kwh > Decimal::ZERO).then(|| (revenue / filled_kwh).round_dp(8)),
+        }
+    }
+}
+
+/// Autopilot sell orders compared with the user's own sell orders
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AutopilotPerformance {
+    pub days: i64,
+    pub autopilot: SellPerformance,
+    pub manual: SellPerformance,
+    /// Forecast surplus of the epochs autopilot planned
+    #[schema(value_type = String)]
+    pub forecast_kwh: Decimal,
+}
+
+/// Outcome of one autopilot run
+#[derive(Debug, Clone, Default)]
+pub struct AutopilotRun {
+    pub placed: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub closed: usize,
+}
+
+#[derive(Clone)]
+pub struct AutopilotService {
+    db: PgPool,
+    market_clearing: MarketClearingService,
+    config: AutopilotConfig,
+}
+
+impl AutopilotService {
+    pub fn new(db: PgPool, market_clearing: MarketClearingService, config: AutopilotConfig) -> Self {
+        Self {
+            db,
+            market_clearing,
+            config,
+        }
+    }
+
+    pub async fn settings(&self, user_id: Uuid) -> Result<Option<AutopilotSettings>> {
+        let row = sqlx::query_as::<_, SettingsRow>("SELECT * FROM autopilot_settings WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+        row.map(|row| row.settings()).transpose()
+    }
+
+    pub async fn update_settings(&self, user_id: Uuid, request: UpdateAutopilotRequest) -> Result<AutopilotSettings> {
+        let sell_fraction = request.sell_fraction.unwrap_or(Decimal::new(8, 1));
+        if sell_fraction <= Decimal::ZERO || sell_fraction > Decimal::ONE {
+            return Err(ApiError::validation_field("sell_fraction", "Must be above 0 and at most 1"));
+        }
+        if request.floor_price.is_some_and(|price| price <= Decimal::ZERO) {
+            return Err(ApiError::validation_field("floor_price", "Must be positive"));
+        }
+        if request.max_kwh_per_epoch.is_some_and(|max| max <= Decimal::ZERO) {
+            return Err(ApiError::validation_field("max_kwh_per_epoch", "Must be positive"));
+        }
+        if request.strategy == AutopilotStrategy::LimitFloor && request.floor_price.is_none() {
+            return Err(ApiError::validation_field("floor_price", "Required for the limit_floor strategy"));
+        }
+
+        let row = sqlx::query_as::<_, SettingsRow>(
+            r#"
+            INSERT INTO autopilot_settings (
+                user_id, enabled, strategy, index_offset_bps, floor_price,
+                sell_fraction, max_kwh_per_epoch, session_token
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (user_id) DO UPDATE SET
+                enabled = EXCLUDED.enabled,
+                strategy = EXCLUDED.strategy,
+                index_offset_bps = EXCLUDED.index_offset_bps,
+                floor_price = EXCLUDED.floor_price,
+                sell_fraction = EXCLUDED.sell_fraction,
+                max_kwh_per_epoch = EXCLUDED.max_kwh_per_epoch,
+                session_token = COALESCE(EXCLUDED.session_token, autopilot_settings.session_token),
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(request.enabled)
+        .bind(request.strategy.as_str())
+        .bind(request.index_offset_bps)
+        .bind(request.floor_price)
+        .bind(sell_fraction)
+        .bind(request.max_kwh_per_epoch)
+        .bind(request.session_token)
+        .fetch_one(&self.db)
+        .await?;
+
+        info!(
+            "🤖 Autopilot {} for user {} ({})",
+            if row.enabled { "enabled" } else { "paused" },
+            user_id,
+            row.strategy
+        );
+        row.settings()
+    }
+
+    pub async fn list_orders(&self, user_id: Uuid, limit: i64) -> Result<Vec<AutopilotOrder>> {
+        Ok(sqlx::query_as::<_, AutopilotOrder>(
+            r#"
+            SELECT id, epoch_id, order_id, forecast_kwh, energy_amount, price_per_kwh,
+                   status, detail, created_at, closed_at
+            FROM autopilot_orders
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Fill, fill rate and realized price of autopilot and manual sell orders
+    /// created in the last `days`
+    pub async fn performance(&self, user_id: Uuid, days: i64) -> Result<AutopilotPerformance> {
+        let rows = sqlx::query(
+            r#"
+            SELECT (a.id IS NOT NULL) AS autopilot,
+                   COUNT(*) AS orders,
+                   COALESCE(SUM(o.energy_amount), 0) AS offered_kwh,
+                   COALESCE(SUM(m.filled_kwh), 0) AS filled_kwh,
+                   COALESCE(SUM(m.revenue), 0) AS revenue
+            FROM trading_orders o
+            LEFT JOIN autopilot_orders a ON a.order_id = o.id
+            LEFT JOIN LATERAL (
+                SELECT SUM(matched_amount) AS filled_kwh, SUM(matched_amount * match_price) AS revenue
+                FROM order_matches
+                WHERE sell_order_id = o.id AND status <> 'failed'
+            ) m ON TRUE
+            WHERE o.user_id = $1
+              AND o.side = 'sell'
+              AND o.created_at >= NOW() - make_interval(days => $2::int)
+            GROUP BY 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(days)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut performance = AutopilotPerformance {
+            days,
+            autopilot: SellPerformance::default(),
+            manual: SellPerformance::default(),
+            forecast_kwh: Decimal::ZERO,
+        };
+        for row in rows {
+            let group = SellPerformance::new(
+                row.get("orders"),
+                row.get("offered_kwh"),
+                row.get("filled_kwh"),
+                row.get("revenue"),
+            );
+            if row.get::<bool, _>("autopilot") {
+                performance.autopilot = group;
+            } else {
+                performance.manual = group;
+            }
+        }
+
+        performance.forecast_kwh = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(forecast_kwh), 0) FROM autopilot_orders
+            WHERE user_id = $1 AND created_at >= NOW() - make_interval(days => $2::int)
+            "#,
+        )
+        .bind(user_id)
+        .bind(days)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(performance)
+    }
+
+    /// Close the orders of cleared epochs, then plan the current epoch for
+    /// every opted-in user not planned yet
+    pub async fn run_once(&self) -> Result<AutopilotRun> {
+        let mut run = AutopilotRun {
+            closed: self.close_cleared().await?,
+            ..Default::default()
+        };
+
+        let epoch = self
+            .market_clearing
+            .get_or_create_epoch(Utc::now())
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to resolve epoch: {}", e)))?;
+
+        let users = sqlx::query_as::<_, SettingsRow>(
+            r#"
+            SELECT s.* FROM autopilot_settings s
+            WHERE s.enabled
+              AND NOT EXISTS (SELECT 1 FROM autopilot_orders a WHERE a.user_id = s.user_id AND a.epoch_id = $1)
+            ORDER BY s.user_id
+            LIMIT $2
+            "#,
+        )
+        .bind(epoch.id)
+        .bind(USERS_PER_RUN)
+        .fetch_all(&self.db)
+        .await?;
+        if users.is_empty() {
+            return Ok(run);
+        }
+
+        let index: Option<Decimal> = sqlx::query_scalar(
+            "SELECT clearing_price FROM clearing_price_index ORDER BY epoch_start DESC LIMIT 1",
+        )
+        .fetch_optional(&self.db)
+        .await?;
+
+        for user in users {
+            // Claim the epoch; another instance may have planned it meanwhile
+            let Some(plan_id) = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO autopilot_orders (user_id, epoch_id)
+                VALUES ($1, $2)
+                ON CONFLICT (user_id, epoch_id) DO NOTHING
+                RETURNING id
+                "#,
+            )
+            .bind(user.user_id)
+            .bind(epoch.id)
+            .fetch_optional(&self.db)
+            .await?
+            else {
+                continue;
+            };
+
+            match self.plan(&user, plan_id, epoch.start_time, epoch.end_time, index).await {
+                Ok(true) => run.placed += 1,
+                Ok(false) => run.skipped += 1,
+                Err(e) => {
+                    warn!("🤖 Autopilot order for user {} failed: {}", user.user_id, e);
+                    self.finish(plan_id, "failed", None, None, None, Some(e.to_string())).await?;
+                    run.failed += 1;
+                }
+            }
+        }
+
+        if run.placed + run.failed > 0 {
+            info!(
+                "🤖 Autopilot epoch {}: {} placed, {} skipped, {} failed",
+                epoch.epoch_number, run.placed, run.skipped, run.failed
+            );
+        }
+        Ok(run)
+    }
+
+    /// Forecast, price and place one user's order. Returns false when the
+    /// epoch was skipped.
+    async fn plan(
+        &self,
+        user: &SettingsRow,
+        plan_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        index: Option<Decimal>,
+    ) -> Result<bool> {
+        let settings = user.settings()?;
+        let daily: Vec<Decimal> = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(r.surplus_energy), 0)::NUMERIC
+            FROM generate_series(1, $4::int) AS d
+            LEFT JOIN meter_readings r
+                ON r.user_id = $1
+               AND r.reading_timestamp >= $2 - make_interval(days => d)
+               AND r.reading_timestamp < $3 - make_interval(days => d)
+            GROUP BY d
+            "#,
+        )
+        .bind(user.user_id)
+        .bind(start)
+        .bind(end)
+        .bind(self.config.forecast_days)
+        .fetch_all(&self.db)
+        .await?;
+
+        // Forecasts never exceed what the user's installations can produce
+        // in the epoch; unknown when any meter lacks a rating
+        let capacity_kw: Option<Decimal> = sqlx::query_scalar(
+            r#"
+            SELECT CASE WHEN COUNT(*) = COUNT(LEAST(capacity_kw, inverter_capacity_kw))
+                THEN SUM(LEAST(capacity_kw, inverter_capacity_kw)) END
+            FROM meter_registry
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user.user_id)
+        .fetch_one(&self.db)
+        .await?;
+        let max_output = capacity_kw.map(|kw| max_output_kwh(kw, start, end));
+
+        let forecast = plan_quantity(&daily, Decimal::ONE, max_output);
+        sqlx::query("UPDATE autopilot_orders SET forecast_kwh = $2 WHERE id = $1")
+            .bind(plan_id)
+            .bind(forecast)
+            .execute(&self.db)
+            .await?;
+
+        let max_kwh = match (settings.max_kwh_per_epoch, max_output) {
+            (Some(max), Some(output)) => Some(max.min(output)),
+            (max, output) => max.or(output),
+        };
+        let quantity = plan_quantity(&daily, settings.sell_fraction, max_kwh);
+        if quantity < self.config.min_order_kwh {
+            self.finish(plan_id, "skipped", None, None, None, Some("Forecast surplus below minimum order".to_string()))
+                .await?;
+            return Ok(false);
+        }
+        let Some(price) = settings
+            .strategy
+            .price(index, settings.index_offset_bps, settings.floor_price)
+        else {
+            self.finish(plan_id, "skipped", None, None, None, Some("No clearing price index to link to".to_string()))
+                .await?;
+            return Ok(false);
+        };
+
+        let order_id = self
+            .market_clearing
+            .create_order(
+                user.user_id,
+                OrderSide::Sell,
+                OrderType::Limit,
+                quantity,
+                Some(price),
+                None,
+                None,
+                None,
+                user.session_token.as_deref(),
+                None,
+            )
+            .await
+            .map_err(|e| {
+                e.downcast::<ApiError>()
+                    .unwrap_or_else(|e| ApiError::Internal(format!("Order creation failed: {}", e)))
+            })?;
+
+        self.finish(plan_id, "placed", Some(order_id), Some(quantity), Some(price), None)
+            .await?;
+        Ok(true)
+    }
+
+    /// Cancel the unfilled remainder of orders whose epoch has cleared
+    async fn close_cleared(&self) -> Result<usize> {
+        let due = sqlx::query(
+            r#"
+            SELECT a.id, a.user_id, a.order_id, o.status::TEXT AS order_status
+            FROM autopilot_orders a
+            JOIN market_epochs e ON e.id = a.epoch_id
+            LEFT JOIN trading_orders o ON o.id = a.order_id
+            WHERE a.status = 'placed' AND e.cleared_at IS NOT NULL
+            LIMIT $1
+            "#,
+        )
+        .bind(CLOSE_BATCH)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut closed = 0;
+        for row in due {
+            let id: Uuid = row.get("id");
+            let user_id: Uuid = row.get("user_id");
+            let order_id: Option<Uuid> = row.get("order_id");
+            let order_status: Option<String> = row.get("order_status");
+
+            let detail = match (order_id, order_status.as_deref()) {
+                (Some(order_id), Some("pending" | "partially_filled")) => {
+                    match self.market_clearing.cancel_order(order_id, user_id).await {
+                        Ok(()) => Some("Unfilled remainder cancelled at epoch close".to_string()),
+                        Err(e) => {
+                            warn!("🤖 Failed to cancel autopilot order {}: {}", order_id, e);
+                            continue;
+                        }
+                    }
+                }
+                _ => None,
+            };
+
+            sqlx::query(
+                "UPDATE autopilot_orders SET status = 'closed', closed_at = NOW(), detail = COALESCE($2, detail) WHERE id = $1",
+            )
+            .bind(id)
+            .bind(detail)
+            .execute(&self.db)
+            .await?;
+            closed += 1;
+        }
+        Ok(closed)
+    }
+
+    async fn finish(
+        &self,
+        plan_id: Uuid,
+        status: &str,
+        order_id: Option<Uuid>,
+        energy_amount: Option<Decimal>,
+        price: Option<Decimal>,
+        detail: Option<String>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE autopilot_orders
+            SET status = $2, order_id = $3, energy_amount = $4, price_per_kwh = $5, detail = $6,
+                closed_at = CASE WHEN $2 IN ('skipped', 'failed') THEN NOW() END
+            WHERE id = $1
+            "#,
+        )
+        .bind(plan_id)
+        .bind(status)
+        .bind(order_id)
+        .bind(energy_amount)
+        .bind(price)
+        .bind(detail)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_plan_quantity_scales_and_caps_mean_surplus() {
+        let daily = [d("2.0"), d("4.0"), d("0")];
+        assert_eq!(plan_quantity(&daily, d("0.8"), None), d("1.6"));
+        assert_eq!(plan_quantity(&daily, Decimal::ONE, Some(d("1.5"))), d("1.5"));
+        assert_eq!(plan_quantity(&[], Decimal::ONE, None), Decimal::ZERO);
+        assert_eq!(plan_quantity(&[d("-3")], Decimal::ONE, None), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_index_linked_price_respects_floor() {
+        let strategy = AutopilotStrategy::IndexLinked;
+        assert_eq!(strategy.price(Some(d("4")), 250, None), Some(d("4.1")));
+        assert_eq!(strategy.price(Some(d("4")), -1000, Some(d("3.8"))), Some(d("3.8")));
+        assert_eq!(strategy.price(None, 0, Some(d("3"))), Some(d("3")));
+        assert_eq!(strategy.price(None, 0, None), None);
+    }
+
+    #[test]
+    fn test_limit_floor_ignores_index() {
+        assert_eq!(AutopilotStrategy::LimitFloor.price(Some(d("9")), 500, Some(d("3.5"))), Some(d("3.5")));
+    }
+}
@@ -107,4 +107,17 @@ impl AccountManager {
             _ => Err(anyhow!("Unsupported transaction encoding")),
         }
     }
+
+    /// Fee in lamports charged for a landed transaction
+    pub async fn get_transaction_fee(&self, signature: &Signature) -> Result<u64> {
+        let tx = self
+            .transaction_handler
+            .client()
+            .get_transaction(signature, solana_transaction_status::UiTransactionEncoding::Json)?;
+
+        tx.transaction
+            .meta
+            .map(|meta| meta.fee)
+            .ok_or_else(|| anyhow!("Transaction {} has no status metadata", signature))
+    }
 }
@@ -0,0 +1,603 @@
+//! IDL registry for decoding instructions of the GridTokenX programs.
+//!
+//! Maps instruction discriminators to names and argument layouts (the same
+//! layouts `InstructionBuilder` writes), and decodes System, SPL Token and
+//! Token-2022 instructions, so transaction history can show summaries such
+//! as "mint 10 GRID to <wallet>".
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use solana_sdk::pubkey::Pubkey;
+use spl_token::instruction::TokenInstruction;
+use utoipa::ToSchema;
+
+use super::instructions::{
+    ENERGY_TOKEN_PROGRAM_ID, GOVERNANCE_PROGRAM_ID, ORACLE_PROGRAM_ID, REGISTRY_PROGRAM_ID,
+    TRADING_PROGRAM_ID,
+};
+use crate::constants::energy::TOKEN_DECIMALS;
+
+const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+const ENERGY_TOKEN_SYMBOL: &str = "GRID";
+const LAMPORTS_PER_SOL_DECIMALS: u8 = 9;
+
+/// A decoded instruction of a transaction
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DecodedInstruction {
+    pub program_id: String,
+    /// Known program name, e.g. `trading` or `spl-token`
+    pub program: Option<String>,
+    /// Instruction name, when the program and discriminator are known
+    pub name: Option<String>,
+    /// Decoded arguments by name
+    #[schema(value_type = Object)]
+    pub args: Value,
+    pub accounts: Vec<String>,
+    /// Human-readable summary, e.g. "mint 10 GRID to <wallet>"
+    pub summary: String,
+}
+
+/// An instruction as it appears on-chain, with account indexes resolved
+#[derive(Debug, Clone)]
+pub struct RawInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<Pubkey>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ArgType {
+    U8,
+    U64,
+    Bool,
+    String,
+    Pubkey,
+    /// u64 amount in base units of the energy token
+    EnergyTokenAmount,
+}
+
+struct IdlArg {
+    name: &'static str,
+    ty: ArgType,
+}
+
+struct IdlInstruction {
+    name: &'static str,
+    discriminator: &'static [u8],
+    args: &'static [IdlArg],
+    /// `{arg}` is replaced by an argument, `{#n}` by the n-th account
+    summary: &'static str,
+}
+
+struct ProgramIdl {
+    name: &'static str,
+    instructions: &'static [IdlInstruction],
+}
+
+const fn arg(name: &'static str, ty: ArgType) -> IdlArg {
+    IdlArg { name, ty }
+}
+
+static TRADING_IDL: ProgramIdl = ProgramIdl {
+    name: "trading",
+    instructions: &[
+        IdlInstruction {
+            name: "initialize_market",
+            discriminator: &[35, 35, 189, 193, 155, 48, 170, 203],
+            args: &[],
+            summary: "initialize market {#0}",
+        },
+        IdlInstruction {
+            name: "create_sell_order",
+            discriminator: &[53, 52, 255, 44, 191, 74, 171, 225],
+            args: &[arg("energy_amount", ArgType::U64), arg("price_per_kwh", ArgType::U64)],
+            summary: "sell order {#1}: {energy_amount} at {price_per_kwh} per kWh",
+        },
+        IdlInstruction {
+            name: "create_buy_order",
+            discriminator: &[182, 87, 0, 160, 192, 66, 151, 130],
+            args: &[arg("energy_amount", ArgType::U64), arg("price_per_kwh", ArgType::U64)],
+            summary: "buy order {#1}: {energy_amount} at {price_per_kwh} per kWh",
+        },
+        IdlInstruction {
+            name: "match_orders",
+            discriminator: &[17, 1, 201, 93, 7, 51, 251, 134],
+            args: &[arg("match_amount", ArgType::U64)],
+            summary: "match {match_amount} between buy order {#1} and sell order {#2}",
+        },
+    ],
+};
+
+static REGISTRY_IDL: ProgramIdl = ProgramIdl {
+    name: "registry",
+    instructions: &[
+        IdlInstruction {
+            name: "initialize",
+            discriminator: &[175, 175, 109, 31, 13, 152, 155, 237],
+            args: &[],
+            summary: "initialize registry {#0}",
+        },
+        IdlInstruction {
+            name: "register_user",
+            discriminator: &[153, 150, 36, 97, 226, 70, 52, 72],
+            args: &[arg("user_type", ArgType::U8), arg("location", ArgType::String)],
+            summary: "register user {#2} (type {user_type}) in {location}",
+        },
+        IdlInstruction {
+            name: "register_meter",
+            discriminator: &[49, 106, 87, 72, 138, 214, 224, 125],
+            args: &[arg("meter_id", ArgType::String), arg("meter_type", ArgType::U8)],
+            summary: "register meter {meter_id} (type {meter_type}) as {#2}",
+        },
+    ],
+};
+
+static ORACLE_IDL: ProgramIdl = ProgramIdl {
+    name: "oracle",
+    instructions: &[
+        IdlInstruction {
+            name: "initialize",
+            discriminator: &[175, 175, 109, 31, 13, 152, 155, 237],
+            args: &[arg("api_gateway", ArgType::Pubkey)],
+            summary: "initialize oracle with API gateway {api_gateway}",
+        },
+        IdlInstruction {
+            name: "update_price",
+            discriminator: &[1, 0, 0, 0],
+            args: &[arg("price", ArgType::U64), arg("confidence", ArgType::U64)],
+            summary: "update price feed {#0} to {price} (confidence {confidence})",
+        },
+    ],
+};
+
+static GOVERNANCE_IDL: ProgramIdl = ProgramIdl {
+    name: "governance",
+    instructions: &[
+        IdlInstruction {
+            name: "initialize_poa",
+            discriminator: &[98, 199, 82, 10, 244, 161, 157, 46],
+            args: &[],
+            summary: "initialize proof-of-authority config {#0}",
+        },
+        IdlInstruction {
+            name: "issue_erc",
+            discriminator: &[174, 248, 149, 107, 155, 4, 196, 8],
+            args: &[
+                arg("certificate_id", ArgType::String),
+                arg("energy_amount", ArgType::U64),
+                arg("renewable_source", ArgType::String),
+                arg("validation_data", ArgType::String),
+            ],
+            summary: "issue ERC {certificate_id} for {energy_amount} of {renewable_source}",
+        },
+        IdlInstruction {
+            name: "transfer_erc",
+            discriminator: &[200, 15, 16, 13, 13, 143, 11, 11],
+            args: &[],
+            summary: "transfer ERC {#1} from {#2} to {#3}",
+        },
+        IdlInstruction {
+            name: "revoke_erc",
+            discriminator: &[16, 48, 113, 85, 118, 70, 185, 150],
+            args: &[arg("reason", ArgType::String)],
+            summary: "revoke ERC {#1}: {reason}",
+        },
+        IdlInstruction {
+            name: "vote",
+            discriminator: &[1, 0, 0, 0],
+            args: &[arg("proposal_id", ArgType::U64), arg("vote", ArgType::Bool)],
+            summary: "vote {vote} on proposal {proposal_id}",
+        },
+    ],
+};
+
+static ENERGY_TOKEN_IDL: ProgramIdl = ProgramIdl {
+    name: "energy-token",
+    instructions: &[
+        IdlInstruction {
+            name: "initialize_token",
+            discriminator: &[38, 209, 150, 50, 190, 117, 16, 54],
+            args: &[],
+            summary: "initialize energy token mint {#1}",
+        },
+        IdlInstruction {
+            name: "mint",
+            discriminator: &[1, 0, 0, 0],
+            args: &[arg("amount", ArgType::EnergyTokenAmount)],
+            summary: "mint {amount} to {#0}",
+        },
+        IdlInstruction {
+            name: "transfer",
+            discriminator: &[2, 0, 0, 0],
+            args: &[arg("amount", ArgType::EnergyTokenAmount)],
+            summary: "transfer {amount} from {#0} to {#1}",
+        },
+    ],
+};
+
+/// Decodes instructions of known programs
+#[derive(Clone)]
+pub struct IdlRegistry {
+    programs: HashMap<Pubkey, &'static ProgramIdl>,
+    system_program: Pubkey,
+    token_programs: HashMap<Pubkey, &'static str>,
+    /// Symbol and decimals of known mints
+    mints: HashMap<Pubkey, (&'static str, u8)>,
+}
+
+impl IdlRegistry {
+    /// Registry for the platform programs; `energy_token_mint` is labelled GRID
+    pub fn new(energy_token_mint: &str) -> Self {
+        let programs = [
+            (TRADING_PROGRAM_ID, &TRADING_IDL),
+            (REGISTRY_PROGRAM_ID, &REGISTRY_IDL),
+            (ORACLE_PROGRAM_ID, &ORACLE_IDL),
+            (GOVERNANCE_PROGRAM_ID, &GOVERNANCE_IDL),
+            (ENERGY_TOKEN_PROGRAM_ID, &ENERGY_TOKEN_IDL),
+        ]
+        .into_iter()
+        .filter_map(|(id, idl)| Pubkey::from_str(id).ok().map(|id| (id, idl)))
+        .collect();
+
+        let mut token_programs = HashMap::from([(spl_token::id(), "spl-token")]);
+        if let Ok(token_2022) = Pubkey::from_str(TOKEN_2022_PROGRAM_ID) {
+            token_programs.insert(token_2022, "token-2022");
+        }
+
+        let mints = Pubkey::from_str(energy_token_mint)
+            .map(|mint| HashMap::from([(mint, (ENERGY_TOKEN_SYMBOL, TOKEN_DECIMALS))]))
+            .unwrap_or_default();
+
+        Self {
+            programs,
+            system_program: Pubkey::from_str(SYSTEM_PROGRAM_ID).unwrap_or_default(),
+            token_programs,
+            mints,
+        }
+    }
+
+    /// Name of a known program
+    pub fn program_name(&self, program_id: &Pubkey) -> Option<&'static str> {
+        if let Some(idl) = self.programs.get(program_id) {
+            return Some(idl.name);
+        }
+        if *program_id == self.system_program {
+            return Some("system");
+        }
+        self.token_programs.get(program_id).copied()
+    }
+
+    /// Decode an instruction. Unknown programs or discriminators still yield
+    /// an entry, with the summary naming the program.
+    pub fn decode(&self, instruction: &RawInstruction) -> DecodedInstruction {
+        let program_id = &instruction.program_id;
+        let decoded = if let Some(idl) = self.programs.get(program_id) {
+            self.decode_idl(idl, instruction)
+        } else if *program_id == self.system_program {
+            self.decode_system(instruction)
+        } else if self.token_programs.contains_key(program_id) {
+            self.decode_token(instruction)
+        } else {
+            None
+        };
+
+        let program = self.program_name(program_id);
+        let (name, args, summary) = decoded.unwrap_or_else(|| {
+            let summary = format!("call {}", program.map(str::to_string).unwrap_or_else(|| program_id.to_string()));
+            (None, Value::Object(Map::new()), summary)
+        });
+
+        DecodedInstruction {
+            program_id: program_id.to_string(),
+            program: program.map(str::to_string),
+            name,
+            args,
+            accounts: instruction.accounts.iter().map(|a| a.to_string()).collect(),
+            summary,
+        }
+    }
+
+    fn decode_idl(
+        &self,
+        idl: &ProgramIdl,
+        instruction: &RawInstruction,
+    ) -> Option<(Option<String>, Value, String)> {
+        let ix = idl
+            .instructions
+            .iter()
+            .find(|ix| instruction.data.starts_with(ix.discriminator))?;
+
+        let mut reader = ArgReader(&instruction.data[ix.discriminator.len()..]);
+        let mut args = Map::new();
+        let mut rendered = Vec::with_capacity(ix.args.len());
+        for arg in ix.args {
+            let (value, display) = match arg.ty {
+                ArgType::U8 => {
+                    let v = reader.u8()?;
+                    (Value::from(v), v.to_string())
+                }
+                ArgType::U64 => {
+                    let v = reader.u64()?;
+                    (Value::from(v), v.to_string())
+                }
+                ArgType::Bool => {
+                    let v = reader.u8()? != 0;
+                    (Value::from(v), v.to_string())
+                }
+                ArgType::String => {
+                    let v = reader.string()?;
+                    (Value::from(v.clone()), v)
+                }
+                ArgType::Pubkey => {
+                    let v = reader.pubkey()?.to_string();
+                    (Value::from(v.clone()), v)
+                }
+                ArgType::EnergyTokenAmount => {
+                    let v = reader.u64()?;
+                    (Value::from(v), format_amount(v, TOKEN_DECIMALS, ENERGY_TOKEN_SYMBOL))
+                }
+            };
+            args.insert(arg.name.to_string(), value);
+            rendered.push((arg.name, display));
+        }
+
+        let summary = render_summary(ix.summary, &rendered, &instruction.accounts);
+        Some((Some(ix.name.to_string()), Value::Object(args), summary))
+    }
+
+    fn decode_system(&self, instruction: &RawInstruction) -> Option<(Option<String>, Value, String)> {
+        let mut reader = ArgReader(&instruction.data);
+        // Only transfers are summarised; other system instructions stay generic
+        if reader.u32()? != 2 {
+            return None;
+        }
+        let lamports = reader.u64()?;
+        let summary = format!(
+            "transfer {} from {} to {}",
+            format_amount(lamports, LAMPORTS_PER_SOL_DECIMALS, "SOL"),
+            account_at(&instruction.accounts, 0),
+            account_at(&instruction.accounts, 1)
+        );
+        Some((
+            Some("transfer".to_string()),
+            serde_json::json!({ "lamports": lamports }),
+            summary,
+        ))
+    }
+
+    fn decode_token(&self, instruction: &RawInstruction) -> Option<(Option<String>, Value, String)> {
+        let accounts = &instruction.accounts;
+        let token = TokenInstruction::unpack(&instruction.data).ok()?;
+
+        let (name, amount, mint, summary_for) = match token {
+            TokenInstruction::Transfer { amount } => ("transfer", amount, None, TokenAction::Transfer { from: 0, to: 1 }),
+            TokenInstruction::TransferChecked { amount, .. } => {
+                ("transfer_checked", amount, Some(1), TokenAction::Transfer { from: 0, to: 2 })
+            }
+            TokenInstruction::MintTo { amount } => ("mint_to", amount, Some(0), TokenAction::Mint { to: 1 }),
+            TokenInstruction::MintToChecked { amount, .. } => {
+                ("mint_to_checked", amount, Some(0), TokenAction::Mint { to: 1 })
+            }
+            TokenInstruction::Burn { amount } => ("burn", amount, Some(1), TokenAction::Burn { from: 0 }),
+            TokenInstruction::BurnChecked { amount, .. } => {
+                ("burn_checked", amount, Some(1), TokenAction::Burn { from: 0 })
+            }
+            TokenInstruction::Approve { amount } => ("approve", amount, None, TokenAction::Approve { delegate: 1 }),
+            TokenInstruction::ApproveChecked { amount, .. } => {
+                ("approve_checked", amount, Some(1), TokenAction::Approve { delegate: 2 })
+            }
+            _ => return None,
+        };
+
+        // Checked variants carry decimals; fall back to known mints, then raw units
+        let decimals = match token {
+            TokenInstruction::TransferChecked { decimals, .. }
+            | TokenInstruction::MintToChecked { decimals, .. }
+            | TokenInstruction::BurnChecked { decimals, .. }
+            | TokenInstruction::ApproveChecked { decimals, .. } => Some(decimals),
+            _ => None,
+        };
+        let mint_key = mint.and_then(|i| accounts.get(i));
+        let known_mint = mint_key.and_then(|m| self.mints.get(m));
+        let display = match (known_mint, decimals) {
+            (Some((symbol, mint_decimals)), _) => format_amount(amount, *mint_decimals, symbol),
+            (None, Some(decimals)) => format_amount(amount, decimals, "tokens"),
+            (None, None) => format!("{} base units", amount),
+        };
+
+        let summary = match summary_for {
+            TokenAction::Transfer { from, to } => format!(
+                "transfer {} from {} to {}",
+                display,
+                account_at(accounts, from),
+                account_at(accounts, to)
+            ),
+            TokenAction::Mint { to } => format!("mint {} to {}", display, account_at(accounts, to)),
+            TokenAction::Burn { from } => format!("burn {} from {}", display, account_at(accounts, from)),
+            TokenAction::Approve { delegate } => {
+                format!("approve {} to spend {}", account_at(accounts, delegate), display)
+            }
+        };
+
+        let mut args = serde_json::json!({ "amount": amount });
+        if let Some(mint) = mint_key {
+            args["mint"] = Value::from(mint.to_string());
+        }
+        if let Some(decimals) = decimals {
+            args["decimals"] = Value::from(decimals);
+        }
+        Some((Some(name.to_string()), args, summary))
+    }
+}
+
+/// Accounts referenced by a token instruction summary
+enum TokenAction {
+    Transfer { from: usize, to: usize },
+    Mint { to: usize },
+    Burn { from: usize },
+    Approve { delegate: usize },
+}
+
+/// Borsh-style little-endian reader
+struct ArgReader<'a>(&'a [u8]);
+
+impl ArgReader<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)?.try_into().ok().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8)?.try_into().ok().map(u64::from_le_bytes)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+
+    fn pubkey(&mut self) -> Option<Pubkey> {
+        Pubkey::try_from(self.take(32)?).ok()
+    }
+}
+
+fn account_at(accounts: &[Pubkey], index: usize) -> String {
+    accounts
+        .get(index)
+        .map(|a| a.to_string())
+        .unwrap_or_else(|| "?".to_string())
+}
+
+fn format_amount(amount: u64, decimals: u8, symbol: &str) -> String {
+    let value = Decimal::from_i128_with_scale(amount as i128, decimals as u32).normalize();
+    format!("{} {}", value, symbol)
+}
+
+fn render_summary(template: &str, args: &[(&str, String)], accounts: &[Pubkey]) -> String {
+    let mut summary = template.to_string();
+    for (name, value) in args {
+        summary = summary.replace(&format!("{{{}}}", name), value);
+    }
+    for index in 0..accounts.len().max(4) {
+        let placeholder = format!("{{#{}}}", index);
+        if summary.contains(&placeholder) {
+            summary = summary.replace(&placeholder, &account_at(accounts, index));
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: &str = "So11111111111111111111111111111111111111112";
+
+    fn registry() -> IdlRegistry {
+        IdlRegistry::new(MINT)
+    }
+
+    #[test]
+    fn test_decode_energy_token_mint() {
+        let recipient = Pubkey::new_unique();
+        let mut data = vec![1, 0, 0, 0];
+        data.extend_from_slice(&10_000_000_000u64.to_le_bytes());
+
+        let decoded = registry().decode(&RawInstruction {
+            program_id: Pubkey::from_str(ENERGY_TOKEN_PROGRAM_ID).unwrap(),
+            accounts: vec![recipient, Pubkey::new_unique()],
+            data,
+        });
+
+        assert_eq!(decoded.program.as_deref(), Some("energy-token"));
+        assert_eq!(decoded.name.as_deref(), Some("mint"));
+        assert_eq!(decoded.args["amount"], 10_000_000_000u64);
+        assert_eq!(decoded.summary, format!("mint 10 GRID to {}", recipient));
+    }
+
+    #[test]
+    fn test_decode_spl_mint_to_known_mint() {
+        let mint = Pubkey::from_str(MINT).unwrap();
+        let destination = Pubkey::new_unique();
+        let data = TokenInstruction::MintTo { amount: 2_500_000_000 }.pack();
+
+        let decoded = registry().decode(&RawInstruction {
+            program_id: spl_token::id(),
+            accounts: vec![mint, destination, Pubkey::new_unique()],
+            data,
+        });
+
+        assert_eq!(decoded.name.as_deref(), Some("mint_to"));
+        assert_eq!(decoded.summary, format!("mint 2.5 GRID to {}", destination));
+    }
+
+    #[test]
+    fn test_decode_register_user_args() {
+        let authority = Pubkey::new_unique();
+        let mut data = vec![153, 150, 36, 97, 226, 70, 52, 72, 2];
+        data.extend_from_slice(&7u32.to_le_bytes());
+        data.extend_from_slice(b"Bangkok");
+
+        let decoded = registry().decode(&RawInstruction {
+            program_id: Pubkey::from_str(REGISTRY_PROGRAM_ID).unwrap(),
+            accounts: vec![Pubkey::new_unique(), Pubkey::new_unique(), authority],
+            data,
+        });
+
+        assert_eq!(decoded.args["location"], "Bangkok");
+        assert_eq!(decoded.summary, format!("register user {} (type 2) in Bangkok", authority));
+    }
+
+    #[test]
+    fn test_decode_register_meter_names_meter_account() {
+        let meter_account = Pubkey::new_unique();
+        let mut data = vec![49, 106, 87, 72, 138, 214, 224, 125];
+        data.extend_from_slice(&8u32.to_le_bytes());
+        data.extend_from_slice(b"SM-00042");
+        data.push(0);
+
+        let decoded = registry().decode(&RawInstruction {
+            program_id: Pubkey::from_str(REGISTRY_PROGRAM_ID).unwrap(),
+            accounts: vec![Pubkey::new_unique(), Pubkey::new_unique(), meter_account, Pubkey::new_unique()],
+            data,
+        });
+
+        assert_eq!(decoded.name.as_deref(), Some("register_meter"));
+        assert_eq!(decoded.summary, format!("register meter SM-00042 (type 0) as {}", meter_account));
+    }
+
+    #[test]
+    fn test_decode_unknown_and_truncated() {
+        let registry = registry();
+        let program_id = Pubkey::new_unique();
+        let decoded = registry.decode(&RawInstruction { program_id, accounts: vec![], data: vec![9] });
+        assert!(decoded.name.is_none());
+        assert_eq!(decoded.summary, format!("call {}", program_id));
+
+        // Known discriminator but missing arguments
+        let decoded = registry.decode(&RawInstruction {
+            program_id: Pubkey::from_str(TRADING_PROGRAM_ID).unwrap(),
+            accounts: vec![],
+            data: vec![17, 1, 201, 93, 7, 51, 251, 134, 1],
+        });
+        assert!(decoded.name.is_none());
+        assert_eq!(decoded.summary, "call trading");
+    }
+}
@@ -604,6 +604,16 @@ impl InstructionBuilder {
         Ok(user_account_pda)
     }
 
+    /// Get meter account PDA from meter serial: seeds = ["meter", serial]
+    pub fn get_meter_account_pda(&self, meter_serial: &str) -> Result<Pubkey> {
+        let program_id = Pubkey::from_str(REGISTRY_PROGRAM_ID)?;
+        let (meter_account_pda, _) = Pubkey::find_program_address(
+            &[b"meter", meter_serial.as_bytes()],
+            &program_id,
+        );
+        Ok(meter_account_pda)
+    }
+
     /// Build instruction for initializing the Energy Token program
     pub fn build_initialize_energy_token_instruction(&self, authority: Pubkey) -> Result<Instruction> {
         let program_id = Pubkey::from_str(ENERGY_TOKEN_PROGRAM_ID)?;
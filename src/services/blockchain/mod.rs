@@ -1,15 +1,23 @@
 //! Blockchain services module
 
 pub mod account_management;
+pub mod idl;
 pub mod instructions;
 pub mod on_chain;
+pub mod rpc_cache;
 pub mod service;
+pub mod submission_queue;
 pub mod token_management;
 pub mod transactions;
 pub mod utils;
 
 // Re-exports
+pub use idl::{DecodedInstruction, IdlRegistry, RawInstruction};
 pub use instructions::InstructionBuilder;
-pub use service::BlockchainService;
-pub use transactions::{TransactionHandler, TransactionStatus, FeeEstimate, SolBalanceCheck};
+pub use rpc_cache::{RpcCache, RpcCacheStats};
+pub use service::{BlockchainService, ConfirmedTransaction};
+pub use submission_queue::{SubmissionPermit, SubmissionQueueStats};
+pub use transactions::{
+    commitment_str, Confirmation, FeeEstimate, SolBalanceCheck, TransactionHandler, TransactionStatus, TxOperation,
+};
 pub use utils::BlockchainUtils;
@@ -0,0 +1,288 @@
+//! RPC Read Cache
+//!
+//! Short-TTL caching with single-flight deduplication for hot RPC reads:
+//! the latest blockhash, account info and token balances. Callers asking
+//! for a key while it is being fetched wait for that fetch instead of
+//! issuing their own request. Errors are never cached.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use metrics::counter;
+use serde::Serialize;
+use solana_sdk::{account::Account, hash::Hash as Blockhash, pubkey::Pubkey};
+use utoipa::ToSchema;
+
+use crate::config::RpcCacheConfig;
+
+/// Slots kept per cache before expired, idle ones are pruned
+const PRUNE_THRESHOLD: usize = 4096;
+
+type Slot<V> = Arc<tokio::sync::Mutex<Option<(V, Instant)>>>;
+
+/// Outcome of a cache lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lookup {
+    /// Fresh value already cached
+    Hit,
+    /// Value fetched by another caller while this one waited
+    Coalesced,
+    /// Fetched from RPC
+    Miss,
+}
+
+impl Lookup {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Lookup::Hit => "hit",
+            Lookup::Coalesced => "coalesced",
+            Lookup::Miss => "miss",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    coalesced: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Lookups served per cache since startup
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RpcCacheKindStats {
+    pub hits: u64,
+    /// Requests that waited on an in-flight fetch of the same key
+    pub coalesced: u64,
+    pub misses: u64,
+    /// Share of lookups that did not reach the RPC node
+    pub hit_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RpcCacheStats {
+    pub blockhash: RpcCacheKindStats,
+    pub accounts: RpcCacheKindStats,
+    pub token_balances: RpcCacheKindStats,
+}
+
+/// TTL cache for one kind of RPC read
+#[derive(Debug)]
+struct SingleFlightCache<K, V> {
+    name: &'static str,
+    ttl: Duration,
+    slots: Mutex<HashMap<K, Slot<V>>>,
+    counters: Counters,
+}
+
+impl<K, V> SingleFlightCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    fn new(name: &'static str, ttl: Duration) -> Self {
+        Self {
+            name,
+            ttl,
+            slots: Mutex::new(HashMap::new()),
+            counters: Counters::default(),
+        }
+    }
+
+    fn slot(&self, key: &K) -> Slot<V> {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        if slots.len() >= PRUNE_THRESHOLD {
+            let ttl = self.ttl;
+            // A slot that can't be locked has a fetch in flight
+            slots.retain(|_, slot| match slot.try_lock() {
+                Ok(cached) => cached.as_ref().is_some_and(|(_, at)| at.elapsed() < ttl),
+                Err(_) => true,
+            });
+        }
+        slots.entry(key.clone()).or_default().clone()
+    }
+
+    async fn get_or_fetch<F, Fut>(&self, key: K, fetch: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V>>,
+    {
+        if self.ttl.is_zero() {
+            self.record(Lookup::Miss);
+            return fetch().await;
+        }
+
+        let slot = self.slot(&key);
+        let (mut cached, waited) = match slot.try_lock() {
+            Ok(guard) => (guard, false),
+            Err(_) => (slot.lock().await, true),
+        };
+        if let Some((value, at)) = cached.as_ref() {
+            if at.elapsed() < self.ttl {
+                self.record(if waited { Lookup::Coalesced } else { Lookup::Hit });
+                return Ok(value.clone());
+            }
+        }
+
+        self.record(Lookup::Miss);
+        let value = fetch().await?;
+        *cached = Some((value.clone(), Instant::now()));
+        Ok(value)
+    }
+
+    fn invalidate(&self, keys: &[K]) {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        for key in keys {
+            slots.remove(key);
+        }
+    }
+
+    fn record(&self, lookup: Lookup) {
+        let counter_ref = match lookup {
+            Lookup::Hit => &self.counters.hits,
+            Lookup::Coalesced => &self.counters.coalesced,
+            Lookup::Miss => &self.counters.misses,
+        };
+        counter_ref.fetch_add(1, Ordering::Relaxed);
+        counter!("rpc_cache_requests_total", "kind" => self.name, "outcome" => lookup.as_str()).increment(1);
+    }
+
+    fn stats(&self) -> RpcCacheKindStats {
+        let hits = self.counters.hits.load(Ordering::Relaxed);
+        let coalesced = self.counters.coalesced.load(Ordering::Relaxed);
+        let misses = self.counters.misses.load(Ordering::Relaxed);
+        RpcCacheKindStats {
+            hits,
+            coalesced,
+            misses,
+            hit_rate: hit_rate(hits + coalesced, misses),
+        }
+    }
+}
+
+fn hit_rate(served: u64, misses: u64) -> f64 {
+    let total = served + misses;
+    if total == 0 {
+        0.0
+    } else {
+        served as f64 / total as f64
+    }
+}
+
+/// Cached RPC reads used by `BlockchainService`
+#[derive(Debug, Clone)]
+pub struct RpcCache {
+    blockhash: Arc<SingleFlightCache<(), Blockhash>>,
+    accounts: Arc<SingleFlightCache<Pubkey, Option<Account>>>,
+    /// Keyed by token account address
+    token_balances: Arc<SingleFlightCache<Pubkey, u64>>,
+}
+
+impl RpcCache {
+    pub fn new(config: &RpcCacheConfig) -> Self {
+        Self {
+            blockhash: Arc::new(SingleFlightCache::new(
+                "blockhash",
+                Duration::from_millis(config.blockhash_ttl_ms),
+            )),
+            accounts: Arc::new(SingleFlightCache::new(
+                "account",
+                Duration::from_millis(config.account_ttl_ms),
+            )),
+            token_balances: Arc::new(SingleFlightCache::new(
+                "token_balance",
+                Duration::from_millis(config.token_balance_ttl_ms),
+            )),
+        }
+    }
+
+    pub async fn blockhash<F, Fut>(&self, fetch: F) -> Result<Blockhash>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Blockhash>>,
+    {
+        self.blockhash.get_or_fetch((), fetch).await
+    }
+
+    pub async fn account<F, Fut>(&self, pubkey: &Pubkey, fetch: F) -> Result<Option<Account>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Option<Account>>>,
+    {
+        self.accounts.get_or_fetch(*pubkey, fetch).await
+    }
+
+    pub async fn token_balance<F, Fut>(&self, token_account: &Pubkey, fetch: F) -> Result<u64>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<u64>>,
+    {
+        self.token_balances.get_or_fetch(*token_account, fetch).await
+    }
+
+    /// Drop cached account info and token balances for written accounts
+    pub fn invalidate(&self, accounts: &[Pubkey]) {
+        self.accounts.invalidate(accounts);
+        self.token_balances.invalidate(accounts);
+    }
+
+    pub fn stats(&self) -> RpcCacheStats {
+        RpcCacheStats {
+            blockhash: self.blockhash.stats(),
+            accounts: self.accounts.stats(),
+            token_balances: self.token_balances.stats(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[tokio::test]
+    async fn concurrent_lookups_share_one_fetch() {
+        let cache = Arc::new(SingleFlightCache::<u8, u64>::new("test", Duration::from_secs(60)));
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let lookups = (0..8).map(|_| {
+            let cache = cache.clone();
+            let fetches = fetches.clone();
+            tokio::spawn(async move {
+                cache
+                    .get_or_fetch(1, || async {
+                        fetches.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Ok(42)
+                    })
+                    .await
+                    .unwrap()
+            })
+        });
+        for lookup in lookups.collect::<Vec<_>>() {
+            assert_eq!(lookup.await.unwrap(), 42);
+        }
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        let stats = cache.stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits + stats.coalesced, 7);
+    }
+
+    #[tokio::test]
+    async fn errors_and_invalidated_keys_are_refetched() {
+        let cache = SingleFlightCache::<u8, u64>::new("test", Duration::from_secs(60));
+
+        assert!(cache.get_or_fetch(1, || async { Err(anyhow::anyhow!("rpc down")) }).await.is_err());
+        assert_eq!(cache.get_or_fetch(1, || async { Ok(5) }).await.unwrap(), 5);
+        assert_eq!(cache.get_or_fetch(1, || async { Ok(6) }).await.unwrap(), 5);
+
+        cache.invalidate(&[1]);
+        assert_eq!(cache.get_or_fetch(1, || async { Ok(7) }).await.unwrap(), 7);
+    }
+}
@@ -1,23 +1,45 @@
 use super::account_management::AccountManager;
+use super::idl::RawInstruction;
 use super::instructions::InstructionBuilder;
 use super::on_chain::OnChainManager;
+use super::rpc_cache::{RpcCache, RpcCacheStats};
 use super::token_management::TokenManager;
-use super::transactions::TransactionHandler;
+use super::submission_queue::{SubmissionPermit, SubmissionQueueStats};
+use super::transactions::{Confirmation, TransactionHandler, TxOperation};
 use super::utils::BlockchainUtils;
-use crate::config::SolanaProgramsConfig;
+use crate::config::{ConfirmationConfig, SolanaProgramsConfig, SubmissionQueueConfig};
+use crate::services::wallet::BalanceCache;
 // use crate::services::priority_fee::TransactionType; // DISABLED
 use anyhow::{anyhow, Result};
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_request::TokenAccountsFilter;
 use solana_sdk::{
+    account::Account,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
     instruction::Instruction,
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
     transaction::Transaction,
 };
+use solana_transaction_status::{EncodedTransaction, UiLoadedAddresses, UiMessage, UiTransactionEncoding};
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::{info, warn};
 
+/// A transaction as confirmed on-chain
+#[derive(Debug, Clone)]
+pub struct ConfirmedTransaction {
+    pub slot: u64,
+    /// Unix timestamp of the block, if known
+    pub block_time: Option<i64>,
+    pub failed: bool,
+    pub error: Option<String>,
+    /// First account key, which signs and pays the fee
+    pub fee_payer: Option<Pubkey>,
+    pub instructions: Vec<RawInstruction>,
+}
+
 /// Blockchain service for interacting with Solana programs
 #[derive(Clone)]
 pub struct BlockchainService {
@@ -31,6 +53,11 @@ pub struct BlockchainService {
     pub account_manager: AccountManager,
     pub token_manager: TokenManager,
     pub on_chain_manager: OnChainManager,
+
+    /// Cached balances dropped after the gateway mints or transfers
+    balance_cache: Option<BalanceCache>,
+    /// Short-TTL cache for blockhash, account and token balance reads
+    rpc_cache: Option<RpcCache>,
 }
 
 impl std::fmt::Debug for BlockchainService {
@@ -92,9 +119,65 @@ impl BlockchainService {
             account_manager,
             token_manager,
             on_chain_manager,
+            balance_cache: None,
+            rpc_cache: None,
         })
     }
 
+    /// Invalidate cached balances for accounts this service writes to
+    pub fn with_balance_cache(mut self, cache: BalanceCache) -> Self {
+        self.balance_cache = Some(cache);
+        self
+    }
+
+    /// Cache and deduplicate hot RPC reads
+    pub fn with_rpc_cache(mut self, cache: RpcCache) -> Self {
+        self.rpc_cache = Some(cache);
+        self
+    }
+
+    /// Commitment levels transactions wait for, per kind of operation
+    pub fn with_confirmation(self, config: ConfirmationConfig) -> Self {
+        self.transaction_handler.set_confirmation(config);
+        self
+    }
+
+    /// Per-class limits of the transaction submission queue
+    pub fn with_submission_queue(self, config: &SubmissionQueueConfig) -> Self {
+        self.transaction_handler.configure_submission_queue(config);
+        self
+    }
+
+    /// Wait for a submission slot for transactions sent outside this
+    /// service's send paths
+    pub async fn submission_permit(&self, operation: TxOperation) -> SubmissionPermit {
+        self.transaction_handler.submission_permit(operation).await
+    }
+
+    pub fn submission_queue_stats(&self) -> SubmissionQueueStats {
+        self.transaction_handler.submission_queue_stats()
+    }
+
+    /// Commitment an operation waits for
+    pub fn commitment_for(&self, operation: TxOperation) -> CommitmentConfig {
+        self.transaction_handler.commitment_for(operation)
+    }
+
+    /// Hit rates of the RPC read cache, if enabled
+    pub fn rpc_cache_stats(&self) -> Option<RpcCacheStats> {
+        self.rpc_cache.as_ref().map(RpcCache::stats)
+    }
+
+    /// Drop cached balances for accounts touched by a gateway transaction
+    async fn invalidate_balances(&self, accounts: &[Pubkey]) {
+        if let Some(cache) = &self.rpc_cache {
+            cache.invalidate(accounts);
+        }
+        if let Some(cache) = &self.balance_cache {
+            cache.invalidate(accounts).await;
+        }
+    }
+
     /// Get the RPC client
     pub fn client(&self) -> &RpcClient {
         &self.rpc_client
@@ -164,16 +247,36 @@ impl BlockchainService {
 
     /// Get SPL token balance for a user
     pub async fn get_token_balance(&self, owner: &Pubkey, mint: &Pubkey) -> Result<u64> {
-        self.token_manager.get_token_balance(owner, mint).await
+        let Some(cache) = &self.rpc_cache else {
+            return self.token_manager.get_token_balance(owner, mint).await;
+        };
+        let token_account = self.account_manager.calculate_ata_address(owner, mint)?;
+        cache
+            .token_balance(&token_account, || self.token_manager.get_token_balance(owner, mint))
+            .await
     }
 
-    /// Send and confirm a transaction
+    /// Send a transaction and wait for the commitment configured for the
+    /// operation
     pub async fn send_and_confirm_transaction(
         &self,
         transaction: &Transaction,
-    ) -> Result<Signature> {
+        operation: TxOperation,
+    ) -> Result<Confirmation> {
+        self.transaction_handler
+            .send_and_confirm_transaction(transaction, operation)
+            .await
+    }
+
+    /// Wait for a transaction sent elsewhere (e.g. by the spl-token CLI) to
+    /// reach the commitment configured for the operation
+    pub async fn confirm_signature(
+        &self,
+        signature: &Signature,
+        operation: TxOperation,
+    ) -> Result<CommitmentLevel> {
         self.transaction_handler
-            .send_and_confirm_transaction(transaction)
+            .wait_for_commitment(signature, self.commitment_for(operation))
             .await
     }
 
@@ -184,9 +287,24 @@ impl BlockchainService {
             .await
     }
 
+    /// Get transaction status at an operation's commitment, including
+    /// transactions that have left the recent status cache
+    pub async fn find_signature_status(
+        &self,
+        signature: &Signature,
+        operation: TxOperation,
+    ) -> Result<Option<bool>> {
+        self.transaction_handler
+            .find_signature_status(signature, operation)
+            .await
+    }
+
     /// Get recent blockhash
     pub async fn get_latest_blockhash(&self) -> Result<solana_sdk::hash::Hash> {
-        self.transaction_handler.get_latest_blockhash().await
+        match &self.rpc_cache {
+            Some(cache) => cache.blockhash(|| self.transaction_handler.get_latest_blockhash()).await,
+            None => self.transaction_handler.get_latest_blockhash().await,
+        }
     }
 
     /// Get slot height
@@ -196,7 +314,13 @@ impl BlockchainService {
 
     /// Get account data
     pub async fn get_account_data(&self, pubkey: &Pubkey) -> Result<Vec<u8>> {
-        self.account_manager.get_account_data(pubkey).await
+        if self.rpc_cache.is_none() {
+            return self.account_manager.get_account_data(pubkey).await;
+        }
+        self.get_account(pubkey)
+            .await?
+            .map(|account| account.data)
+            .ok_or_else(|| anyhow!("Account {} not found", pubkey))
     }
 
     /// Initialize the registry on-chain (localnet bootstrapping)
@@ -299,6 +423,115 @@ impl BlockchainService {
         self.account_manager.account_exists(pubkey).await
     }
 
+    /// Get the full account, or `None` if it does not exist on-chain
+    pub async fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>> {
+        let fetch = || async move {
+            let response = self
+                .rpc_client
+                .get_account_with_commitment(pubkey, self.rpc_client.commitment())
+                .map_err(|e| anyhow!("Failed to get account: {}", e))?;
+            Ok(response.value)
+        };
+        match &self.rpc_cache {
+            Some(cache) => cache.account(pubkey, fetch).await,
+            None => fetch().await,
+        }
+    }
+
+    /// Fetch a confirmed transaction with its instructions in raw form
+    pub async fn get_confirmed_transaction(&self, signature: &Signature) -> Result<ConfirmedTransaction> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let tx = self
+            .rpc_client
+            .get_transaction_with_config(signature, config)
+            .map_err(|e| anyhow!("Failed to get transaction {}: {}", signature, e))?;
+
+        let meta = tx.transaction.meta;
+        let EncodedTransaction::Json(ui_tx) = tx.transaction.transaction else {
+            return Err(anyhow!("Unsupported transaction encoding"));
+        };
+        let UiMessage::Raw(message) = ui_tx.message else {
+            return Err(anyhow!("Unexpected parsed transaction message"));
+        };
+
+        let mut keys = message
+            .account_keys
+            .iter()
+            .map(|k| Pubkey::from_str(k))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("Invalid pubkey in transaction: {}", e))?;
+        // Versioned transactions index into addresses loaded from lookup tables
+        if let Some(loaded) = meta
+            .as_ref()
+            .and_then(|m| Option::<UiLoadedAddresses>::from(m.loaded_addresses.clone()))
+        {
+            for address in loaded.writable.iter().chain(loaded.readonly.iter()) {
+                keys.push(Pubkey::from_str(address).map_err(|e| anyhow!("Invalid loaded address: {}", e))?);
+            }
+        }
+
+        let mut instructions = Vec::with_capacity(message.instructions.len());
+        for ix in &message.instructions {
+            let program_id = *keys
+                .get(ix.program_id_index as usize)
+                .ok_or_else(|| anyhow!("Instruction program index out of range"))?;
+            instructions.push(RawInstruction {
+                program_id,
+                accounts: ix.accounts.iter().filter_map(|i| keys.get(*i as usize).copied()).collect(),
+                data: bs58::decode(&ix.data)
+                    .into_vec()
+                    .map_err(|e| anyhow!("Invalid instruction data: {}", e))?,
+            });
+        }
+
+        Ok(ConfirmedTransaction {
+            slot: tx.slot,
+            block_time: tx.block_time,
+            failed: meta.as_ref().is_some_and(|m| m.err.is_some()),
+            error: meta.and_then(|m| m.err).map(|e| e.to_string()),
+            fee_payer: keys.first().copied(),
+            instructions,
+        })
+    }
+
+    /// Minimum lamports for an account of `data_len` bytes to be rent-exempt
+    pub async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64> {
+        self.rpc_client
+            .get_minimum_balance_for_rent_exemption(data_len)
+            .map_err(|e| anyhow!("Failed to get rent exemption minimum: {}", e))
+    }
+
+    /// List SPL Token and Token-2022 accounts owned by a wallet.
+    ///
+    /// Returns each token account address with its `jsonParsed` account info
+    /// (`mint`, `tokenAmount`, `delegate`, `delegatedAmount`, `state`).
+    pub async fn get_token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+    ) -> Result<Vec<(String, serde_json::Value)>> {
+        let mut accounts = Vec::new();
+        for program_id in [spl_token::id(), BlockchainUtils::get_token_program_id()?] {
+            let keyed = self
+                .rpc_client
+                .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(program_id))
+                .map_err(|e| anyhow!("Failed to list token accounts: {}", e))?;
+
+            for keyed_account in keyed {
+                let account = serde_json::to_value(&keyed_account.account)?;
+                let info = account
+                    .pointer("/data/parsed/info")
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+                accounts.push((keyed_account.pubkey, info));
+            }
+        }
+        Ok(accounts)
+    }
+
     /// Get transaction account keys
     pub async fn get_transaction_account_keys(&self, signature: &str) -> Result<Vec<Pubkey>> {
         self.account_manager
@@ -306,6 +539,11 @@ impl BlockchainService {
             .await
     }
 
+    /// Fee in lamports charged for a landed transaction
+    pub async fn get_transaction_fee(&self, signature: &Signature) -> Result<u64> {
+        self.account_manager.get_transaction_fee(signature).await
+    }
+
     /// Parse Pubkey from string
     pub fn parse_pubkey(pubkey_str: &str) -> Result<Pubkey> {
         AccountManager::parse_pubkey(pubkey_str)
@@ -643,6 +881,32 @@ impl BlockchainService {
         Ok(true)
     }
 
+    /// Simulate a token transfer on its own, unsigned and without sending
+    /// it. Returns why the transfer would fail, or None when it would succeed.
+    pub async fn simulate_token_transfer(
+        &self,
+        owner: &Pubkey,
+        from_token_account: &Pubkey,
+        to_token_account: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<Option<String>> {
+        let token_program_id = BlockchainUtils::get_token_program_id()?;
+        let instruction = spl_token::instruction::transfer_checked(
+            &token_program_id,
+            from_token_account,
+            mint,
+            to_token_account,
+            owner,
+            &[],
+            amount,
+            decimals,
+        )?;
+        let transaction = Transaction::new_with_payer(&[instruction], Some(owner));
+        self.transaction_handler.simulate_unsigned(&transaction).await
+    }
+
     /// Wait for transaction confirmation with timeout
     pub async fn wait_for_confirmation(
         &self,
@@ -703,17 +967,32 @@ impl BlockchainService {
         mint: &Pubkey,
         amount_kwh: f64,
     ) -> Result<Signature> {
-        if amount_kwh > 0.0 {
+        self.mint_energy_tokens_confirmed(authority, user_token_account, user_wallet, mint, amount_kwh)
+            .await
+            .map(|confirmation| confirmation.signature)
+    }
+
+    /// Mint or burn energy tokens, returning the commitment the transaction
+    /// was confirmed at
+    pub async fn mint_energy_tokens_confirmed(
+        &self,
+        authority: &Keypair,
+        user_token_account: &Pubkey,
+        user_wallet: &Pubkey,
+        mint: &Pubkey,
+        amount_kwh: f64,
+    ) -> Result<Confirmation> {
+        let confirmation = if amount_kwh > 0.0 {
             info!("Minting {} kWh tokens for wallet {}", amount_kwh, user_wallet);
             self.token_manager
                 .mint_energy_tokens(authority, user_token_account, user_wallet, mint, amount_kwh)
-                .await
+                .await?
         } else if amount_kwh < 0.0 {
             let burn_amount = amount_kwh.abs();
             info!("Burning {} kWh tokens from wallet {}", burn_amount, user_wallet);
             self.token_manager
                 .burn_energy_tokens(authority, user_token_account, mint, burn_amount)
-                .await
+                .await?
         } else {
             // Zero reading, no-op but return successful "signature" placeholder?
             // Or technically this shouldn't happen if validation works.
@@ -722,8 +1001,11 @@ impl BlockchainService {
             // Returning an error might fail the flow, but zero tokens is valid state.
             // We can return the last signature or a dummy one if we had one.
             // For now, let's treat it as a warning.
-            Err(anyhow!("Cannot mint/burn zero tokens"))
-        }
+            return Err(anyhow!("Cannot mint/burn zero tokens"));
+        };
+
+        self.invalidate_balances(&[*user_token_account, *user_wallet]).await;
+        Ok(confirmation)
     }
 
     /// Mint SPL tokens using standard spl-token CLI (for testing with standard SPL tokens)
@@ -733,11 +1015,30 @@ impl BlockchainService {
         user_wallet: &Pubkey,
         mint: &Pubkey,
         amount_kwh: f64,
+    ) -> Result<Signature> {
+        self.mint_spl_tokens_as(TxOperation::Mint, authority, user_wallet, mint, amount_kwh)
+            .await
+    }
+
+    async fn mint_spl_tokens_as(
+        &self,
+        operation: TxOperation,
+        authority: &Keypair,
+        user_wallet: &Pubkey,
+        mint: &Pubkey,
+        amount_kwh: f64,
     ) -> Result<Signature> {
         info!("Minting {} SPL tokens for wallet {} using CLI", amount_kwh, user_wallet);
-        self.token_manager
+        let _permit = self.submission_permit(operation).await;
+        let signature = self
+            .token_manager
             .mint_spl_tokens(authority, user_wallet, mint, amount_kwh)
-            .await
+            .await?;
+
+        let mut touched = vec![*user_wallet];
+        touched.extend(self.calculate_ata_address(user_wallet, mint).ok());
+        self.invalidate_balances(&touched).await;
+        Ok(signature)
     }
 
     /// Burn energy tokens from a user's token account
@@ -748,9 +1049,14 @@ impl BlockchainService {
         mint: &Pubkey,
         amount_kwh: f64,
     ) -> Result<Signature> {
-        self.token_manager
+        let signature = self
+            .token_manager
             .burn_energy_tokens(authority, user_token_account, mint, amount_kwh)
-            .await
+            .await?
+            .signature;
+
+        self.invalidate_balances(&[*user_token_account]).await;
+        Ok(signature)
     }
 
     /// Transfer energy tokens between accounts
@@ -762,7 +1068,8 @@ impl BlockchainService {
         mint: &Pubkey,
         amount_kwh: f64,
     ) -> Result<Signature> {
-        self.token_manager
+        let signature = self
+            .token_manager
             .transfer_energy_tokens(
                 authority,
                 from_token_account,
@@ -770,7 +1077,10 @@ impl BlockchainService {
                 mint,
                 amount_kwh,
             )
-            .await
+            .await?;
+
+        self.invalidate_balances(&[*from_token_account, *to_token_account]).await;
+        Ok(signature)
     }
 
     /// Ensures user has an Associated Token Account for the token mint
@@ -801,16 +1111,49 @@ impl BlockchainService {
         amount: u64,
         decimals: u8,
     ) -> Result<Signature> {
-        self.token_manager
+        self.transfer_tokens_as(
+            TxOperation::Transfer,
+            authority,
+            None,
+            from_token_account,
+            to_token_account,
+            mint,
+            amount,
+            decimals,
+        )
+        .await
+    }
+
+    /// Transfer SPL tokens, queued with the given operation's priority. The
+    /// authority pays the fee unless a separate fee payer is given.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn transfer_tokens_as(
+        &self,
+        operation: TxOperation,
+        authority: &Keypair,
+        fee_payer: Option<&Keypair>,
+        from_token_account: &Pubkey,
+        to_token_account: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<Signature> {
+        let _permit = self.submission_permit(operation).await;
+        let signature = self
+            .token_manager
             .transfer_tokens(
                 authority,
+                fee_payer,
                 from_token_account,
                 to_token_account,
                 mint,
                 amount,
                 decimals,
             )
-            .await
+            .await?;
+
+        self.invalidate_balances(&[*from_token_account, *to_token_account]).await;
+        Ok(signature)
     }
 
     /// Register a user on-chain
@@ -841,6 +1184,33 @@ impl BlockchainService {
             .await
     }
 
+    /// Registry account PDA of a meter, keyed by its serial
+    pub fn meter_account_pda(&self, meter_serial: &str) -> Result<Pubkey> {
+        self.instruction_builder.get_meter_account_pda(meter_serial)
+    }
+
+    /// Make sure a meter has its registry account on-chain.
+    ///
+    /// Returns the PDA and, when the account had to be created, the
+    /// registration signature; an existing account is left untouched.
+    pub async fn ensure_meter_registered(
+        &self,
+        authority: &Keypair,
+        meter_serial: &str,
+        meter_type: u8,
+    ) -> Result<(Pubkey, Option<Signature>)> {
+        let pda = self.meter_account_pda(meter_serial)?;
+        if self.account_exists(&pda).await? {
+            return Ok((pda, None));
+        }
+
+        let signature = self
+            .register_meter_on_chain(authority, meter_serial, meter_type)
+            .await?;
+        info!("📝 Meter {} registry account {} created: {}", meter_serial, pda, signature);
+        Ok((pda, Some(signature)))
+    }
+
     /// Submit meter reading on-chain (via Oracle)
     pub async fn submit_meter_reading_on_chain(
         &self,
@@ -901,9 +1271,13 @@ impl BlockchainService {
         amount: u64,
         decimals: u8,
     ) -> Result<Signature> {
-        self.transaction_handler
+        let signature = self
+            .transaction_handler
             .lock_tokens_to_escrow(buyer_authority, buyer_ata, escrow_ata, token_mint, amount, decimals)
-            .await
+            .await?;
+
+        self.invalidate_balances(&[*buyer_ata, *escrow_ata]).await;
+        Ok(signature)
     }
 
     /// Release escrow to seller
@@ -916,9 +1290,13 @@ impl BlockchainService {
         amount: u64,
         decimals: u8,
     ) -> Result<Signature> {
-        self.transaction_handler
+        let signature = self
+            .transaction_handler
             .release_escrow_to_seller(escrow_authority, escrow_ata, seller_ata, token_mint, amount, decimals)
-            .await
+            .await?;
+
+        self.invalidate_balances(&[*escrow_ata, *seller_ata]).await;
+        Ok(signature)
     }
 
     /// Refund escrow to buyer
@@ -931,9 +1309,13 @@ impl BlockchainService {
         amount: u64,
         decimals: u8,
     ) -> Result<Signature> {
-        self.transaction_handler
+        let signature = self
+            .transaction_handler
             .refund_escrow_to_buyer(escrow_authority, escrow_ata, buyer_ata, token_mint, amount, decimals)
-            .await
+            .await?;
+
+        self.invalidate_balances(&[*escrow_ata, *buyer_ata]).await;
+        Ok(signature)
     }
 
     /// Mint tokens directly to a user's wallet using the Anchor energy_token program
@@ -961,7 +1343,9 @@ impl BlockchainService {
             // Continue to mint, as it might just be an "already exists" error which is fine
         }
 
-        self.mint_spl_tokens(&authority, user_wallet, &mint, amount_kwh).await
+        // Only the sandbox and faucet mint this way
+        self.mint_spl_tokens_as(TxOperation::Test, &authority, user_wallet, &mint, amount_kwh)
+            .await
     }
 }
 
@@ -0,0 +1,254 @@
+//! Transaction Submission Queue
+//!
+//! Orders on-chain submissions by operation: settlements go first, then
+//! mints, transfers, everything else and finally sandbox transactions. Each
+//! class has its own in-flight limit under an overall cap, so a burst of
+//! meter mints waits its turn instead of starving settlements.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use metrics::{gauge, histogram};
+use serde::Serialize;
+use tokio::sync::oneshot;
+use utoipa::ToSchema;
+
+use super::transactions::TxOperation;
+use crate::config::SubmissionQueueConfig;
+
+/// Operations in dispatch order
+const CLASSES: [TxOperation; 5] = [
+    TxOperation::Settlement,
+    TxOperation::Mint,
+    TxOperation::Transfer,
+    TxOperation::Other,
+    TxOperation::Test,
+];
+
+fn class_index(operation: TxOperation) -> usize {
+    match operation {
+        TxOperation::Settlement => 0,
+        TxOperation::Mint => 1,
+        TxOperation::Transfer => 2,
+        TxOperation::Other => 3,
+        TxOperation::Test => 4,
+    }
+}
+
+#[derive(Debug)]
+struct QueueState {
+    max_in_flight: usize,
+    limits: [usize; 5],
+    in_flight: [usize; 5],
+    waiting: [VecDeque<oneshot::Sender<SubmissionPermit>>; 5],
+}
+
+impl QueueState {
+    fn new(config: &SubmissionQueueConfig) -> Self {
+        Self {
+            max_in_flight: config.max_in_flight,
+            limits: limits(config),
+            in_flight: [0; 5],
+            waiting: Default::default(),
+        }
+    }
+
+    fn total_in_flight(&self) -> usize {
+        self.in_flight.iter().sum()
+    }
+
+    /// Hand out free slots, highest priority class first
+    fn dispatch(&mut self, queue: &Arc<Mutex<QueueState>>) {
+        for waiting in self.waiting.iter_mut() {
+            waiting.retain(|waiter| !waiter.is_closed());
+        }
+        for index in 0..CLASSES.len() {
+            while self.total_in_flight() < self.max_in_flight && self.in_flight[index] < self.limits[index] {
+                let Some(waiter) = self.waiting[index].pop_front() else {
+                    break;
+                };
+                self.in_flight[index] += 1;
+                let permit = SubmissionPermit {
+                    queue: Some(queue.clone()),
+                    index,
+                };
+                // The caller stopped waiting; take the slot back
+                if let Err(mut permit) = waiter.send(permit) {
+                    permit.queue = None;
+                    self.in_flight[index] -= 1;
+                }
+            }
+        }
+        self.record();
+    }
+
+    fn record(&self) {
+        for (index, operation) in CLASSES.iter().enumerate() {
+            let class = operation.as_str();
+            gauge!("tx_queue_depth", "class" => class).set(self.waiting[index].len() as f64);
+            gauge!("tx_queue_in_flight", "class" => class).set(self.in_flight[index] as f64);
+        }
+    }
+}
+
+fn limits(config: &SubmissionQueueConfig) -> [usize; 5] {
+    [
+        config.settlement_limit,
+        config.mint_limit,
+        config.transfer_limit,
+        config.other_limit,
+        config.test_limit,
+    ]
+}
+
+/// A submission slot; released when dropped
+#[derive(Debug)]
+pub struct SubmissionPermit {
+    queue: Option<Arc<Mutex<QueueState>>>,
+    index: usize,
+}
+
+impl Drop for SubmissionPermit {
+    fn drop(&mut self) {
+        if let Some(queue) = self.queue.take() {
+            let mut state = queue.lock().unwrap_or_else(|e| e.into_inner());
+            state.in_flight[self.index] -= 1;
+            state.dispatch(&queue);
+        }
+    }
+}
+
+/// Load of one submission class
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SubmissionClassStats {
+    pub class: String,
+    pub in_flight: usize,
+    pub queued: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SubmissionQueueStats {
+    pub max_in_flight: usize,
+    /// In dispatch order
+    pub classes: Vec<SubmissionClassStats>,
+}
+
+/// Priority queue shared by every clone of the transaction handler
+#[derive(Debug, Clone)]
+pub struct SubmissionQueue {
+    state: Arc<Mutex<QueueState>>,
+}
+
+impl SubmissionQueue {
+    pub fn new(config: &SubmissionQueueConfig) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(QueueState::new(config))),
+        }
+    }
+
+    /// Replace the limits; waiting submissions are dispatched against them
+    pub fn configure(&self, config: &SubmissionQueueConfig) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.max_in_flight = config.max_in_flight;
+        state.limits = limits(config);
+        state.dispatch(&self.state);
+    }
+
+    /// Wait for a slot for the operation's class
+    pub async fn acquire(&self, operation: TxOperation) -> SubmissionPermit {
+        let index = class_index(operation);
+        let started = Instant::now();
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.waiting[index].push_back(tx);
+            state.dispatch(&self.state);
+        }
+
+        let permit = match rx.await {
+            Ok(permit) => permit,
+            // Only happens if the queue itself is gone; don't block the caller
+            Err(_) => SubmissionPermit { queue: None, index },
+        };
+        histogram!("tx_queue_wait_seconds", "class" => operation.as_str())
+            .record(started.elapsed().as_secs_f64());
+        permit
+    }
+
+    pub fn stats(&self) -> SubmissionQueueStats {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        SubmissionQueueStats {
+            max_in_flight: state.max_in_flight,
+            classes: CLASSES
+                .iter()
+                .enumerate()
+                .map(|(index, operation)| SubmissionClassStats {
+                    class: operation.as_str().to_string(),
+                    in_flight: state.in_flight[index],
+                    queued: state.waiting[index].len(),
+                    limit: state.limits[index],
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn config(max_in_flight: usize) -> SubmissionQueueConfig {
+        SubmissionQueueConfig {
+            max_in_flight,
+            settlement_limit: 1,
+            mint_limit: 1,
+            transfer_limit: 1,
+            other_limit: 1,
+            test_limit: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn freed_slots_go_to_settlements_before_mints() {
+        let queue = SubmissionQueue::new(&config(1));
+        let first = queue.acquire(TxOperation::Test).await;
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut waiters = Vec::new();
+        for operation in [TxOperation::Mint, TxOperation::Settlement] {
+            let queue = queue.clone();
+            let order = order.clone();
+            waiters.push(tokio::spawn(async move {
+                let _permit = queue.acquire(operation).await;
+                order.lock().unwrap().push(operation);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }));
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(queue.stats().classes[0].queued, 1);
+        assert_eq!(queue.stats().classes[1].queued, 1);
+        drop(first);
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec![TxOperation::Settlement, TxOperation::Mint]);
+    }
+
+    #[tokio::test]
+    async fn class_limit_holds_under_spare_capacity() {
+        let queue = SubmissionQueue::new(&config(4));
+        let _mint = queue.acquire(TxOperation::Mint).await;
+
+        let second = tokio::time::timeout(Duration::from_millis(20), queue.acquire(TxOperation::Mint)).await;
+        assert!(second.is_err());
+        // The abandoned wait gives its slot back
+        let _settlement = queue.acquire(TxOperation::Settlement).await;
+        let stats = queue.stats();
+        assert_eq!(stats.classes[1].in_flight, 1);
+        assert_eq!(stats.classes[1].queued, 0);
+    }
+}
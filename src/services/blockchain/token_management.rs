@@ -5,7 +5,7 @@ use std::str::FromStr;
 use std::time::Duration; // Added Duration
 
 use crate::services::blockchain::account_management::AccountManager; // Dependency
-use crate::services::blockchain::transactions::TransactionHandler;
+use crate::services::blockchain::transactions::{Confirmation, TransactionHandler, TxOperation};
 use crate::services::blockchain::utils::BlockchainUtils;
 
 /// Manages Token operations (mint, burn, transfer)
@@ -119,7 +119,7 @@ impl TokenManager {
         user_wallet: &Pubkey,
         _mint: &Pubkey, // Not used directly - we derive from program
         amount_kwh: f64,
-    ) -> Result<Signature> {
+    ) -> Result<Confirmation> {
         use solana_sdk::instruction::Instruction;
         use solana_sdk::signature::Signer;
 
@@ -189,11 +189,7 @@ impl TokenManager {
 
         let signers = vec![authority];
         self.transaction_handler
-            .build_and_send_transaction_with_priority(
-                instructions,
-                &signers,
-                "token_transaction",
-            )
+            .build_and_send_transaction_for(instructions, &signers, TxOperation::Mint)
             .await
     }
 
@@ -348,7 +344,7 @@ impl TokenManager {
         user_token_account: &Pubkey,
         mint: &Pubkey,
         amount_kwh: f64,
-    ) -> Result<Signature> {
+    ) -> Result<Confirmation> {
         let burn_instruction = BlockchainUtils::create_burn_instruction(
             authority,
             user_token_account,
@@ -358,11 +354,7 @@ impl TokenManager {
 
         let signers = vec![authority];
         self.transaction_handler
-            .build_and_send_transaction_with_priority(
-                vec![burn_instruction],
-                &signers,
-                "token_transaction", // Use Settlement priority for burning
-            )
+            .build_and_send_transaction_for(vec![burn_instruction], &signers, TxOperation::Mint)
             .await
     }
 
@@ -389,19 +381,18 @@ impl TokenManager {
 
         let signers = vec![authority];
         self.transaction_handler
-            .build_and_send_transaction_with_priority(
-                vec![transfer_instruction],
-                &signers,
-                "token_transaction",
-            )
+            .build_and_send_transaction_for(vec![transfer_instruction], &signers, TxOperation::Transfer)
             .await
+            .map(|confirmation| confirmation.signature)
     }
 
     /// Transfer SPL tokens from one account to another (generic)
     /// Uses CLI for Token-2022 compatibility
+    #[allow(clippy::too_many_arguments)]
     pub async fn transfer_tokens(
         &self,
         authority: &Keypair,
+        fee_payer: Option<&Keypair>,
         _from_token_account: &Pubkey,
         _to_token_account: &Pubkey,
         mint: &Pubkey,
@@ -429,6 +420,16 @@ impl TokenManager {
         let keypair_bytes = authority.to_bytes();
         let keypair_json = serde_json::to_string(&keypair_bytes.to_vec())?;
         fs::write(&temp_keypair_path, &keypair_json)?;
+
+        // Separate fee payer, if any, gets its own temp keypair file
+        let fee_payer_path = match fee_payer {
+            Some(payer) => {
+                let path = format!("/tmp/temp_fee_payer_{}.json", uuid::Uuid::new_v4());
+                fs::write(&path, serde_json::to_string(&payer.to_bytes().to_vec())?)?;
+                path
+            }
+            None => temp_keypair_path.clone(),
+        };
         
         // Get recipient wallet from to_token_account
         // We need to derive the owner from ATA. For now, assume caller ensures params are correct.
@@ -444,7 +445,7 @@ impl TokenManager {
             .arg("--from")
             .arg(_from_token_account.to_string())
             .arg("--fee-payer")
-            .arg(&temp_keypair_path)
+            .arg(&fee_payer_path)
             .arg("--owner")
             .arg(&temp_keypair_path)
             .arg("--program-2022")
@@ -455,8 +456,11 @@ impl TokenManager {
             .output()
             .map_err(|e| anyhow!("Failed to execute spl-token transfer: {}", e))?;
         
-        // Clean up temp file
+        // Clean up temp files
         let _ = fs::remove_file(&temp_keypair_path);
+        if fee_payer_path != temp_keypair_path {
+            let _ = fs::remove_file(&fee_payer_path);
+        }
         
         let stdout_str = String::from_utf8_lossy(&output.stdout);
         let stderr_str = String::from_utf8_lossy(&output.stderr);
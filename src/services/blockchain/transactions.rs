@@ -1,12 +1,16 @@
 // use crate::services::priority_fee::{PriorityFeeService, TransactionType};  // DISABLED
+use super::submission_queue::{SubmissionPermit, SubmissionQueue, SubmissionQueueStats};
+use crate::config::{ConfirmationConfig, SubmissionQueueConfig};
 use anyhow::{anyhow, Result};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
     // compute_budget::ComputeBudgetInstruction,
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
     transaction::Transaction,
 };
+use solana_transaction_status::TransactionConfirmationStatus;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -21,6 +25,10 @@ pub struct TransactionHandler {
     recent_blockhash: Arc<RwLock<Option<solana_sdk::hash::Hash>>>,
     /// Connection pool for better performance
     connection_pool: Arc<RwLock<Vec<Arc<RpcClient>>>>,
+    /// Commitment per operation, shared by every clone of the handler
+    confirmation: Arc<std::sync::RwLock<ConfirmationConfig>>,
+    /// Prioritized, per-class limited submission slots
+    queue: SubmissionQueue,
 }
 
 impl std::fmt::Debug for TransactionHandler {
@@ -39,6 +47,38 @@ impl TransactionHandler {
             rpc_client,
             recent_blockhash: Arc::new(RwLock::new(None)),
             connection_pool: Arc::new(RwLock::new(Vec::new())),
+            confirmation: Arc::new(std::sync::RwLock::new(ConfirmationConfig::default())),
+            queue: SubmissionQueue::new(&SubmissionQueueConfig::default()),
+        }
+    }
+
+    /// Replace the submission queue limits
+    pub fn configure_submission_queue(&self, config: &SubmissionQueueConfig) {
+        self.queue.configure(config);
+    }
+
+    /// Wait for a submission slot; hold it until the transaction is confirmed
+    pub async fn submission_permit(&self, operation: TxOperation) -> SubmissionPermit {
+        self.queue.acquire(operation).await
+    }
+
+    pub fn submission_queue_stats(&self) -> SubmissionQueueStats {
+        self.queue.stats()
+    }
+
+    /// Replace the per-operation commitment levels
+    pub fn set_confirmation(&self, config: ConfirmationConfig) {
+        *self.confirmation.write().unwrap_or_else(|e| e.into_inner()) = config;
+    }
+
+    fn confirmation(&self) -> ConfirmationConfig {
+        self.confirmation.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Commitment an operation waits for
+    pub fn commitment_for(&self, operation: TxOperation) -> CommitmentConfig {
+        CommitmentConfig {
+            commitment: commitment_level(&self.confirmation(), operation),
         }
     }
 
@@ -161,6 +201,31 @@ impl TransactionHandler {
         Ok(())
     }
 
+    /// Simulate a transaction without checking signatures, so it can be
+    /// built unsigned. Returns the program error, with the last log lines,
+    /// when it would fail.
+    pub async fn simulate_unsigned(&self, transaction: &Transaction) -> Result<Option<String>> {
+        let conn = self.get_connection().await;
+        let config = solana_client::rpc_config::RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            ..Default::default()
+        };
+        let simulation = conn.simulate_transaction_with_config(transaction, config);
+        self.return_connection(conn).await;
+
+        let simulation = simulation.map_err(|e| anyhow!("Transaction simulation failed: {}", e))?;
+        Ok(simulation.value.err.map(|err| {
+            let logs = simulation.value.logs.unwrap_or_default();
+            let tail = logs[logs.len().saturating_sub(3)..].join(" | ");
+            if tail.is_empty() {
+                format!("{:?}", err)
+            } else {
+                format!("{:?}: {}", err, tail)
+            }
+        }))
+    }
+
     /// Add priority fees to transaction based on type
     async fn add_priority_fees(
         &self,
@@ -296,12 +361,10 @@ impl TransactionHandler {
                 .try_sign(&[&self.get_payer_keypair().await?], recent_blockhash)
                 .map_err(|e| anyhow!("Failed to sign transaction: {}", e))?;
 
-            let conn = self.get_connection().await;
-
-            match conn.send_and_confirm_transaction(&transaction) {
-                Ok(sig) => {
+            match self.send_and_confirm_transaction(&transaction, TxOperation::Other).await {
+                Ok(confirmation) => {
                     info!("Transaction submitted successfully on attempt {}", attempts);
-                    return Ok(sig);
+                    return Ok(confirmation.signature);
                 }
                 Err(e) => {
                     error!(
@@ -461,14 +524,62 @@ impl TransactionHandler {
         Ok(lamports as f64 / 1_000_000_000.0)
     }
 
-    /// Send and confirm a transaction
+    /// Send a transaction through the submission queue and wait until it
+    /// reaches the commitment configured for the operation
     pub async fn send_and_confirm_transaction(
         &self,
         transaction: &Transaction,
-    ) -> Result<Signature> {
-        self.rpc_client
-            .send_and_confirm_transaction(transaction)
-            .map_err(|e| anyhow!("Failed to send and confirm transaction: {}", e))
+        operation: TxOperation,
+    ) -> Result<Confirmation> {
+        let commitment = self.commitment_for(operation);
+        let _permit = self.submission_permit(operation).await;
+        let signature = self
+            .rpc_client
+            .send_transaction(transaction)
+            .map_err(|e| anyhow!("Failed to send transaction: {}", e))?;
+        let commitment = self.wait_for_commitment(&signature, commitment).await?;
+        Ok(Confirmation { signature, commitment })
+    }
+
+    /// Wait for a sent transaction to reach a commitment level. Returns the
+    /// level it was seen at, which may be higher than the one asked for.
+    pub async fn wait_for_commitment(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<CommitmentLevel> {
+        let timeout = Duration::from_secs(self.confirmation().timeout_secs);
+        let start = std::time::Instant::now();
+
+        loop {
+            let status = self
+                .rpc_client
+                .get_signature_statuses(&[*signature])
+                .map_err(|e| anyhow!("Failed to get signature status: {}", e))?
+                .value
+                .into_iter()
+                .next()
+                .flatten();
+
+            if let Some(status) = status {
+                if let Some(err) = &status.err {
+                    return Err(anyhow!("Transaction {} failed: {:?}", signature, err));
+                }
+                if status.satisfies_commitment(commitment) {
+                    return Ok(achieved_commitment(status.confirmation_status()));
+                }
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(anyhow!(
+                    "Transaction {} did not reach {:?} within {}s",
+                    signature,
+                    commitment.commitment,
+                    timeout.as_secs()
+                ));
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
     }
 
     /// Get transaction status
@@ -481,6 +592,22 @@ impl TransactionHandler {
         Ok(status.map(|s| s.is_ok()))
     }
 
+    /// Status of a transaction at an operation's commitment, searching ledger
+    /// history so signatures older than the status cache are still found.
+    /// `None` if the cluster has no record of it at that commitment.
+    pub async fn find_signature_status(
+        &self,
+        signature: &Signature,
+        operation: TxOperation,
+    ) -> Result<Option<bool>> {
+        let status = self
+            .rpc_client
+            .get_signature_status_with_commitment_and_history(signature, self.commitment_for(operation), true)
+            .map_err(|e| anyhow!("Failed to get signature status: {}", e))?;
+
+        Ok(status.map(|s| s.is_ok()))
+    }
+
     /// Get recent blockhash
     pub async fn get_latest_blockhash(&self) -> Result<solana_sdk::hash::Hash> {
         self.rpc_client
@@ -535,6 +662,19 @@ impl TransactionHandler {
         instructions: Vec<solana_sdk::instruction::Instruction>,
         signers: &[&Keypair],
     ) -> Result<Signature> {
+        self.build_and_send_transaction_for(instructions, signers, TxOperation::Other)
+            .await
+            .map(|confirmation| confirmation.signature)
+    }
+
+    /// Build, sign, and send a transaction, waiting for the commitment
+    /// configured for the operation
+    pub async fn build_and_send_transaction_for(
+        &self,
+        instructions: Vec<solana_sdk::instruction::Instruction>,
+        signers: &[&Keypair],
+        operation: TxOperation,
+    ) -> Result<Confirmation> {
         let recent_blockhash = self
             .rpc_client
             .get_latest_blockhash()
@@ -544,9 +684,7 @@ impl TransactionHandler {
             Transaction::new_with_payer(&instructions, Some(&signers[0].pubkey()));
         transaction.sign(signers, recent_blockhash);
 
-        self.rpc_client
-            .send_and_confirm_transaction(&transaction)
-            .map_err(|e| anyhow!("Failed to send transaction: {}", e))
+        self.send_and_confirm_transaction(&transaction, operation).await
     }
 
     /// Build, sign, and send a transaction with priority
@@ -885,6 +1023,73 @@ impl TransactionHandler {
     }
 }
 
+/// Kind of on-chain operation, which decides the commitment it waits for
+/// and its place in the submission queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxOperation {
+    Mint,
+    Settlement,
+    Transfer,
+    Other,
+    /// Sandbox and faucet transactions, submitted last
+    Test,
+}
+
+impl TxOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TxOperation::Mint => "mint",
+            TxOperation::Settlement => "settlement",
+            TxOperation::Transfer => "transfer",
+            TxOperation::Other => "other",
+            TxOperation::Test => "test",
+        }
+    }
+}
+
+/// A sent transaction and the commitment it was confirmed at
+#[derive(Debug, Clone)]
+pub struct Confirmation {
+    pub signature: Signature,
+    pub commitment: CommitmentLevel,
+}
+
+impl Confirmation {
+    /// Commitment as stored in transaction records
+    pub fn commitment_str(&self) -> &'static str {
+        commitment_str(self.commitment)
+    }
+}
+
+/// Configured commitment of an operation; sandbox transactions use the
+/// default level
+fn commitment_level(config: &ConfirmationConfig, operation: TxOperation) -> CommitmentLevel {
+    match operation {
+        TxOperation::Mint => config.mint,
+        TxOperation::Settlement => config.settlement,
+        TxOperation::Transfer => config.transfer,
+        TxOperation::Other | TxOperation::Test => config.other,
+    }
+}
+
+/// Commitment level a signature status was seen at
+fn achieved_commitment(status: TransactionConfirmationStatus) -> CommitmentLevel {
+    match status {
+        TransactionConfirmationStatus::Processed => CommitmentLevel::Processed,
+        TransactionConfirmationStatus::Confirmed => CommitmentLevel::Confirmed,
+        TransactionConfirmationStatus::Finalized => CommitmentLevel::Finalized,
+    }
+}
+
+/// processed, confirmed or finalized
+pub fn commitment_str(level: CommitmentLevel) -> &'static str {
+    match level {
+        CommitmentLevel::Processed => "processed",
+        CommitmentLevel::Confirmed => "confirmed",
+        CommitmentLevel::Finalized => "finalized",
+    }
+}
+
 /// Transaction status for detailed tracking
 #[derive(Debug, Clone, PartialEq)]
 pub enum TransactionStatus {
@@ -1120,3 +1325,30 @@ pub mod utils {
 
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_level_per_operation() {
+        let config = ConfirmationConfig {
+            mint: CommitmentLevel::Processed,
+            other: CommitmentLevel::Finalized,
+            ..ConfirmationConfig::default()
+        };
+        assert_eq!(commitment_level(&config, TxOperation::Mint), CommitmentLevel::Processed);
+        assert_eq!(commitment_level(&config, TxOperation::Settlement), CommitmentLevel::Finalized);
+        assert_eq!(commitment_level(&config, TxOperation::Transfer), CommitmentLevel::Confirmed);
+        assert_eq!(commitment_level(&config, TxOperation::Other), CommitmentLevel::Finalized);
+        assert_eq!(commitment_level(&config, TxOperation::Test), CommitmentLevel::Finalized);
+    }
+
+    #[test]
+    fn test_achieved_commitment() {
+        let recorded = |status| commitment_str(achieved_commitment(status));
+        assert_eq!(recorded(TransactionConfirmationStatus::Processed), "processed");
+        assert_eq!(recorded(TransactionConfirmationStatus::Confirmed), "confirmed");
+        assert_eq!(recorded(TransactionConfirmationStatus::Finalized), "finalized");
+    }
+}
@@ -0,0 +1,257 @@
+//! Order Book Snapshot Service
+//!
+//! Persists full L2 books of every open market so the book can be
+//! reconstructed "as of" a past time for dispute resolution and
+//! surveillance. Books are captured periodically and once per market
+//! right before each epoch is matched, and pruned after a retention period.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+use crate::services::markets::{MarketOrderBook, MarketStatus};
+use crate::services::MarketService;
+
+/// Levels requested per side; large enough that the whole book is kept
+const FULL_DEPTH: i64 = 10_000;
+
+/// Why a snapshot was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotReason {
+    Periodic,
+    /// Book handed to the matcher when an epoch was cleared
+    Clearing,
+}
+
+impl SnapshotReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Periodic => "periodic",
+            Self::Clearing => "clearing",
+        }
+    }
+}
+
+/// Which stored book a request asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotLookup {
+    /// Latest snapshot taken at or before the time
+    AsOf(DateTime<Utc>),
+    /// Book an epoch was matched against
+    Epoch(Uuid),
+}
+
+impl SnapshotLookup {
+    /// An epoch overrides `as_of`; with neither, the latest snapshot
+    pub fn new(as_of: Option<DateTime<Utc>>, epoch_id: Option<Uuid>, now: DateTime<Utc>) -> Self {
+        match epoch_id {
+            Some(epoch_id) => Self::Epoch(epoch_id),
+            None => Self::AsOf(as_of.unwrap_or(now)),
+        }
+    }
+}
+
+/// Whether a market's book is snapshotted; halted markets keep their
+/// resting orders, closed ones have none
+fn is_snapshotted(status: &str) -> bool {
+    status != MarketStatus::Closed.as_str()
+}
+
+/// Stored book of one market
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrderBookSnapshot {
+    pub id: Uuid,
+    pub market_id: Uuid,
+    /// periodic or clearing
+    pub reason: String,
+    /// Epoch cleared right after a clearing snapshot
+    pub epoch_id: Option<Uuid>,
+    pub captured_at: DateTime<Utc>,
+    pub book: MarketOrderBook,
+}
+
+#[derive(FromRow)]
+struct SnapshotRow {
+    id: Uuid,
+    market_id: Uuid,
+    reason: String,
+    epoch_id: Option<Uuid>,
+    captured_at: DateTime<Utc>,
+    book: sqlx::types::Json<MarketOrderBook>,
+}
+
+impl From<SnapshotRow> for OrderBookSnapshot {
+    fn from(row: SnapshotRow) -> Self {
+        Self {
+            id: row.id,
+            market_id: row.market_id,
+            reason: row.reason,
+            epoch_id: row.epoch_id,
+            captured_at: row.captured_at,
+            book: row.book.0,
+        }
+    }
+}
+
+const SNAPSHOT_COLUMNS: &str = "id, market_id, reason, epoch_id, captured_at, book";
+
+#[derive(Clone)]
+pub struct OrderBookSnapshotService {
+    db: PgPool,
+    markets: MarketService,
+}
+
+impl OrderBookSnapshotService {
+    pub fn new(db: PgPool, markets: MarketService) -> Self {
+        Self { db, markets }
+    }
+
+    /// Snapshot the book of every market that is not closed (halted
+    /// markets keep their resting orders). Returns how many were stored;
+    /// a market that fails is logged and skipped.
+    pub async fn capture_all(
+        &self,
+        reason: SnapshotReason,
+        epoch_id: Option<Uuid>,
+    ) -> Result<usize> {
+        let markets = self.markets.list().await?;
+        let mut captured = 0;
+        for market in markets.iter().filter(|m| is_snapshotted(&m.status)) {
+            match self.capture(market.id, reason, epoch_id).await {
+                Ok(_) => captured += 1,
+                Err(e) => warn!("⚠️ Failed to snapshot order book of {}: {}", market.code, e),
+            }
+        }
+        Ok(captured)
+    }
+
+    /// Snapshot the full book of one market
+    pub async fn capture(
+        &self,
+        market_id: Uuid,
+        reason: SnapshotReason,
+        epoch_id: Option<Uuid>,
+    ) -> Result<Uuid> {
+        let book = self.markets.order_book(market_id, FULL_DEPTH).await?;
+        let id = sqlx::query_scalar(
+            r#"
+            INSERT INTO order_book_snapshots
+                (market_id, reason, epoch_id, bid_levels, ask_levels, book, captured_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id
+            "#,
+        )
+        .bind(market_id)
+        .bind(reason.as_str())
+        .bind(epoch_id)
+        .bind(book.bids.len() as i32)
+        .bind(book.asks.len() as i32)
+        .bind(sqlx::types::Json(&book))
+        .bind(book.captured_at)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(id)
+    }
+
+    /// Stored book of a market matching the lookup
+    pub async fn find(&self, market_id: Uuid, lookup: SnapshotLookup) -> Result<OrderBookSnapshot> {
+        match lookup {
+            SnapshotLookup::AsOf(at) => self.as_of(market_id, at).await,
+            SnapshotLookup::Epoch(epoch_id) => self.for_epoch(market_id, epoch_id).await,
+        }
+    }
+
+    /// Latest snapshot of a market taken at or before `at`
+    pub async fn as_of(&self, market_id: Uuid, at: DateTime<Utc>) -> Result<OrderBookSnapshot> {
+        sqlx::query_as::<_, SnapshotRow>(&format!(
+            r#"
+            SELECT {} FROM order_book_snapshots
+            WHERE market_id = $1 AND captured_at <= $2
+            ORDER BY captured_at DESC
+            LIMIT 1
+            "#,
+            SNAPSHOT_COLUMNS
+        ))
+        .bind(market_id)
+        .bind(at)
+        .fetch_optional(&self.db)
+        .await?
+        .map(OrderBookSnapshot::from)
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "No order book snapshot of market {} at or before {}",
+                market_id, at
+            ))
+        })
+    }
+
+    /// Snapshot of a market's book taken right before an epoch was matched
+    pub async fn for_epoch(&self, market_id: Uuid, epoch_id: Uuid) -> Result<OrderBookSnapshot> {
+        sqlx::query_as::<_, SnapshotRow>(&format!(
+            r#"
+            SELECT {} FROM order_book_snapshots
+            WHERE market_id = $1 AND epoch_id = $2 AND reason = 'clearing'
+            ORDER BY captured_at DESC
+            LIMIT 1
+            "#,
+            SNAPSHOT_COLUMNS
+        ))
+        .bind(market_id)
+        .bind(epoch_id)
+        .fetch_optional(&self.db)
+        .await?
+        .map(OrderBookSnapshot::from)
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "No clearing snapshot of market {} for epoch {}",
+                market_id, epoch_id
+            ))
+        })
+    }
+
+    /// Delete snapshots older than `days`; returns how many were removed
+    pub async fn purge_older_than(&self, days: i64) -> Result<u64> {
+        let purged = sqlx::query(
+            "DELETE FROM order_book_snapshots WHERE captured_at < NOW() - make_interval(days => $1::int)",
+        )
+        .bind(days as i32)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+        if purged > 0 {
+            info!(
+                "📚 Purged {} order book snapshots older than {} days",
+                purged, days
+            );
+        }
+        Ok(purged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_snapshot_lookup() {
+        let now = Utc::now();
+        let earlier = now - Duration::hours(2);
+        let epoch_id = Uuid::new_v4();
+
+        assert_eq!(SnapshotLookup::new(None, None, now), SnapshotLookup::AsOf(now));
+        assert_eq!(SnapshotLookup::new(Some(earlier), None, now), SnapshotLookup::AsOf(earlier));
+        assert_eq!(SnapshotLookup::new(Some(earlier), Some(epoch_id), now), SnapshotLookup::Epoch(epoch_id));
+    }
+
+    #[test]
+    fn test_closed_markets_are_not_snapshotted() {
+        assert!(is_snapshotted(MarketStatus::Active.as_str()));
+        assert!(is_snapshotted(MarketStatus::Halted.as_str()));
+        assert!(!is_snapshotted(MarketStatus::Closed.as_str()));
+    }
+}
@@ -0,0 +1,395 @@
+//! Household Budget Alerts
+//!
+//! Users set thresholds on their spend this month, their consumption today
+//! and the market clearing price. A scheduled job compares each enabled
+//! alert with the latest settlements, readings and clearing price and
+//! delivers a notification to the user's inbox when a threshold is crossed.
+//! Spend and consumption alerts fire at most once per month or day; a price
+//! alert fires once and re-arms when the price falls back under it.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{ApiError, Result};
+use crate::services::NotificationDispatcher;
+
+/// Alerts a user may keep
+const MAX_ALERTS_PER_USER: i64 = 20;
+
+const ALERT_COLUMNS: &str = "id, kind, threshold, enabled, note, last_triggered_at, last_value, created_at, updated_at";
+
+/// What a budget alert watches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetAlertKind {
+    /// Spend on energy bought this calendar month (UTC), in GRIDX
+    MonthlySpend,
+    /// Energy consumed today (UTC), in kWh
+    DailyConsumption,
+    /// Latest epoch clearing price, per kWh
+    ClearingPrice,
+}
+
+impl BudgetAlertKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MonthlySpend => "monthly_spend",
+            Self::DailyConsumption => "daily_consumption",
+            Self::ClearingPrice => "clearing_price",
+        }
+    }
+
+    /// Period an alert fires at most once in
+    pub fn period(&self, now: DateTime<Utc>) -> String {
+        match self {
+            Self::MonthlySpend => now.format("%Y-%m").to_string(),
+            Self::DailyConsumption => now.format("%Y-%m-%d").to_string(),
+            // Fires once per excursion above the threshold
+            Self::ClearingPrice => "above".to_string(),
+        }
+    }
+}
+
+impl FromStr for BudgetAlertKind {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "monthly_spend" => Ok(Self::MonthlySpend),
+            "daily_consumption" => Ok(Self::DailyConsumption),
+            "clearing_price" => Ok(Self::ClearingPrice),
+            other => Err(ApiError::Internal(format!("Unknown budget alert kind {}", other))),
+        }
+    }
+}
+
+/// What to do with an alert after comparing it with the current value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Evaluation {
+    Fire,
+    /// Back under the threshold; the alert may fire again
+    Rearm,
+    Hold,
+}
+
+pub fn evaluate(value: Decimal, threshold: Decimal, triggered_period: Option<&str>, period: &str) -> Evaluation {
+    if value > threshold {
+        if triggered_period == Some(period) {
+            Evaluation::Hold
+        } else {
+            Evaluation::Fire
+        }
+    } else if triggered_period.is_some() {
+        Evaluation::Rearm
+    } else {
+        Evaluation::Hold
+    }
+}
+
+/// A user's budget alert
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BudgetAlert {
+    pub id: Uuid,
+    pub kind: BudgetAlertKind,
+    #[schema(value_type = String)]
+    pub threshold: Decimal,
+    pub enabled: bool,
+    pub note: Option<String>,
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    /// Value that last fired the alert
+    #[schema(value_type = Option<String>)]
+    pub last_value: Option<Decimal>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct BudgetAlertRow {
+    id: Uuid,
+    kind: String,
+    threshold: Decimal,
+    enabled: bool,
+    note: Option<String>,
+    last_triggered_at: Option<DateTime<Utc>>,
+    last_value: Option<Decimal>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<BudgetAlertRow> for BudgetAlert {
+    type Error = ApiError;
+
+    fn try_from(row: BudgetAlertRow) -> Result<Self> {
+        Ok(Self {
+            id: row.id,
+            kind: row.kind.parse()?,
+            threshold: row.threshold,
+            enabled: row.enabled,
+            note: row.note,
+            last_triggered_at: row.last_triggered_at,
+            last_value: row.last_value,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateBudgetAlertRequest {
+    pub kind: BudgetAlertKind,
+    /// GRIDX for monthly_spend, kWh for daily_consumption, price per kWh for clearing_price
+    #[schema(value_type = String, example = "1500")]
+    pub threshold: Decimal,
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateBudgetAlertRequest {
+    #[schema(value_type = Option<String>)]
+    pub threshold: Option<Decimal>,
+    pub enabled: Option<bool>,
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+#[derive(FromRow)]
+struct DueAlert {
+    id: Uuid,
+    user_id: Uuid,
+    kind: String,
+    threshold: Decimal,
+    triggered_period: Option<String>,
+}
+
+/// Outcome of one evaluation run
+#[derive(Debug, Clone, Default)]
+pub struct BudgetAlertRun {
+    pub fired: usize,
+    pub rearmed: usize,
+}
+
+#[derive(Clone)]
+pub struct BudgetAlertService {
+    db: PgPool,
+    notifications: NotificationDispatcher,
+}
+
+impl BudgetAlertService {
+    pub fn new(db: PgPool, notifications: NotificationDispatcher) -> Self {
+        Self { db, notifications }
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<BudgetAlert>> {
+        sqlx::query_as::<_, BudgetAlertRow>(&format!(
+            "SELECT {} FROM budget_alerts WHERE user_id = $1 ORDER BY created_at",
+            ALERT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(BudgetAlert::try_from)
+        .collect()
+    }
+
+    pub async fn create(&self, user_id: Uuid, request: CreateBudgetAlertRequest) -> Result<BudgetAlert> {
+        if request.threshold <= Decimal::ZERO {
+            return Err(ApiError::validation_field("threshold", "Must be positive"));
+        }
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM budget_alerts WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        if count >= MAX_ALERTS_PER_USER {
+            return Err(ApiError::BadRequest(format!(
+                "At most {} budget alerts can be set",
+                MAX_ALERTS_PER_USER
+            )));
+        }
+
+        let row = sqlx::query_as::<_, BudgetAlertRow>(&format!(
+            "INSERT INTO budget_alerts (user_id, kind, threshold, note) VALUES ($1, $2, $3, $4) RETURNING {}",
+            ALERT_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(request.kind.as_str())
+        .bind(request.threshold)
+        .bind(request.note)
+        .fetch_one(&self.db)
+        .await?;
+        row.try_into()
+    }
+
+    /// A changed threshold re-arms the alert so it is checked afresh
+    pub async fn update(&self, user_id: Uuid, alert_id: Uuid, request: UpdateBudgetAlertRequest) -> Result<BudgetAlert> {
+        if request.threshold.is_some_and(|threshold| threshold <= Decimal::ZERO) {
+            return Err(ApiError::validation_field("threshold", "Must be positive"));
+        }
+        let row = sqlx::query_as::<_, BudgetAlertRow>(&format!(
+            r#"
+            UPDATE budget_alerts SET
+                threshold = COALESCE($3, threshold),
+                enabled = COALESCE($4, enabled),
+                note = COALESCE($5, note),
+                triggered_period = CASE WHEN $3 IS NULL THEN triggered_period END,
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING {}
+            "#,
+            ALERT_COLUMNS
+        ))
+        .bind(alert_id)
+        .bind(user_id)
+        .bind(request.threshold)
+        .bind(request.enabled)
+        .bind(request.note)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Budget alert {} not found", alert_id)))?;
+        row.try_into()
+    }
+
+    pub async fn delete(&self, user_id: Uuid, alert_id: Uuid) -> Result<()> {
+        let deleted = sqlx::query("DELETE FROM budget_alerts WHERE id = $1 AND user_id = $2")
+            .bind(alert_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?
+            .rows_affected();
+        if deleted == 0 {
+            return Err(ApiError::NotFound(format!("Budget alert {} not found", alert_id)));
+        }
+        Ok(())
+    }
+
+    /// Compare every enabled alert with current spend, consumption and price
+    pub async fn evaluate_all(&self) -> Result<BudgetAlertRun> {
+        let alerts = sqlx::query_as::<_, DueAlert>(
+            "SELECT id, user_id, kind, threshold, triggered_period FROM budget_alerts WHERE enabled",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        if alerts.is_empty() {
+            return Ok(BudgetAlertRun::default());
+        }
+
+        let user_ids: Vec<Uuid> = alerts.iter().map(|alert| alert.user_id).collect();
+        let spend: HashMap<Uuid, Decimal> = sqlx::query_as::<_, (Uuid, Decimal)>(
+            r#"
+            SELECT buyer_id, COALESCE(SUM(total_amount), 0)
+            FROM settlements
+            WHERE buyer_id = ANY($1) AND status <> 'failed'
+              AND created_at >= date_trunc('month', NOW())
+            GROUP BY buyer_id
+            "#,
+        )
+        .bind(&user_ids)
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .collect();
+        let consumption: HashMap<Uuid, Decimal> = sqlx::query_as::<_, (Uuid, Decimal)>(
+            r#"
+            SELECT user_id, COALESCE(SUM(energy_consumed), 0)::NUMERIC
+            FROM meter_readings
+            WHERE user_id = ANY($1) AND reading_timestamp >= date_trunc('day', NOW())
+            GROUP BY user_id
+            "#,
+        )
+        .bind(&user_ids)
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .collect();
+        let price: Option<Decimal> = sqlx::query_scalar(
+            "SELECT clearing_price FROM clearing_price_index ORDER BY epoch_start DESC LIMIT 1",
+        )
+        .fetch_optional(&self.db)
+        .await?;
+
+        let now = Utc::now();
+        let mut run = BudgetAlertRun::default();
+        for alert in alerts {
+            let kind: BudgetAlertKind = alert.kind.parse()?;
+            let value = match kind {
+                BudgetAlertKind::MonthlySpend => spend.get(&alert.user_id).copied().unwrap_or_default(),
+                BudgetAlertKind::DailyConsumption => consumption.get(&alert.user_id).copied().unwrap_or_default(),
+                BudgetAlertKind::ClearingPrice => match price {
+                    Some(price) => price,
+                    None => continue,
+                },
+            };
+            let period = kind.period(now);
+
+            match evaluate(value, alert.threshold, alert.triggered_period.as_deref(), &period) {
+                Evaluation::Fire => {
+                    if let Err(e) = self
+                        .notifications
+                        .notify_budget_alert(alert.user_id, alert.id, kind.as_str(), value, alert.threshold)
+                        .await
+                    {
+                        warn!("Failed to deliver budget alert {}: {}", alert.id, e);
+                        continue;
+                    }
+                    sqlx::query(
+                        r#"
+                        UPDATE budget_alerts
+                        SET triggered_period = $2, last_triggered_at = NOW(), last_value = $3
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(alert.id)
+                    .bind(&period)
+                    .bind(value)
+                    .execute(&self.db)
+                    .await?;
+                    run.fired += 1;
+                }
+                Evaluation::Rearm => {
+                    sqlx::query("UPDATE budget_alerts SET triggered_period = NULL WHERE id = $1")
+                        .bind(alert.id)
+                        .execute(&self.db)
+                        .await?;
+                    run.rearmed += 1;
+                }
+                Evaluation::Hold => {}
+            }
+        }
+
+        if run.fired > 0 {
+            info!("💸 Delivered {} budget alerts", run.fired);
+        }
+        Ok(run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alert_fires_once_per_period() {
+        let threshold = Decimal::new(100, 0);
+        let over = Decimal::new(120, 0);
+        assert_eq!(evaluate(over, threshold, None, "2026-10"), Evaluation::Fire);
+        assert_eq!(evaluate(over, threshold, Some("2026-10"), "2026-10"), Evaluation::Hold);
+        assert_eq!(evaluate(over, threshold, Some("2026-09"), "2026-10"), Evaluation::Fire);
+    }
+
+    #[test]
+    fn test_alert_rearms_under_threshold() {
+        let threshold = Decimal::new(5, 0);
+        assert_eq!(evaluate(Decimal::new(4, 0), threshold, Some("above"), "above"), Evaluation::Rearm);
+        assert_eq!(evaluate(threshold, threshold, None, "above"), Evaluation::Hold);
+    }
+}
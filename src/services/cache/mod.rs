@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use crate::services::degradation::{DegradationManager, Subsystem};
+
 /// Redis-based caching service for performance optimization
 #[derive(Clone)]
 pub struct CacheService {
@@ -12,6 +14,8 @@ pub struct CacheService {
     client: Client,
     connection_manager: ConnectionManager,
     default_ttl: u64, // Default TTL in seconds
+    /// Runtime failure tracking; reads and writes are skipped while the cache is down
+    degradation: Option<DegradationManager>,
 }
 
 impl CacheService {
@@ -32,9 +36,51 @@ impl CacheService {
             client,
             connection_manager,
             default_ttl: 300, // 5 minutes default TTL
+            degradation: None,
         })
     }
 
+    /// Report Redis failures to `degradation` and skip the cache while it is down
+    pub fn with_degradation(mut self, degradation: DegradationManager) -> Self {
+        self.degradation = Some(degradation);
+        self
+    }
+
+    /// True while the cache is marked down: reads miss and writes are dropped
+    fn skipped(&self) -> bool {
+        self.degradation.as_ref().is_some_and(|d| d.is_down(Subsystem::Cache))
+    }
+
+    fn succeeded(&self) {
+        if let Some(degradation) = &self.degradation {
+            degradation.success(Subsystem::Cache);
+        }
+    }
+
+    /// Record a Redis failure; logged by the degradation manager when one is attached
+    fn failed(&self, operation: &str, target: &str, e: &redis::RedisError) {
+        match &self.degradation {
+            Some(degradation) => degradation.failure(Subsystem::Cache, format!("{} {}: {}", operation, target, e)),
+            None => warn!("Cache {} failed for {}: {}", operation, target, e),
+        }
+    }
+
+    /// Check Redis answers, recording the outcome; used to probe for recovery
+    pub async fn ping(&self) -> Result<()> {
+        let mut conn = self.connection_manager.clone();
+        let result: RedisResult<String> = conn.ping().await;
+        match result {
+            Ok(_) => {
+                self.succeeded();
+                Ok(())
+            }
+            Err(e) => {
+                self.failed("PING", "server", &e);
+                Err(anyhow::anyhow!("Redis PING failed: {}", e))
+            }
+        }
+    }
+
     /// Set cache value with default TTL
     pub async fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
         self.set_with_ttl(key, value, self.default_ttl).await
@@ -47,6 +93,9 @@ impl CacheService {
         value: &T,
         ttl_seconds: u64,
     ) -> Result<()> {
+        if self.skipped() {
+            return Ok(());
+        }
         let serialized = serde_json::to_string(value)?;
         let mut conn = self.connection_manager.clone();
 
@@ -54,21 +103,58 @@ impl CacheService {
 
         match result {
             Ok(_) => {
+                self.succeeded();
                 debug!("Cache SET: {} (TTL: {}s)", key, ttl_seconds);
                 Ok(())
             }
             Err(e) => {
-                error!("Cache SET failed for key {}: {}", key, e);
+                self.failed("SET", key, &e);
                 Err(anyhow::anyhow!("Redis SET failed: {}", e))
             }
         }
     }
 
+    /// Set a marker key only if it does not exist yet (SET NX EX).
+    /// Returns false when the key was already present, and an error while
+    /// the cache is down since the answer is unknown.
+    pub async fn set_if_absent(&self, key: &str, ttl_seconds: u64) -> Result<bool> {
+        if self.skipped() {
+            return Err(anyhow::anyhow!("Cache is down"));
+        }
+        let mut conn = self.connection_manager.clone();
+
+        let result: RedisResult<Option<String>> = redis::cmd("SET")
+            .arg(key)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl_seconds)
+            .query_async(&mut conn)
+            .await;
+
+        match result {
+            Ok(reply) => {
+                self.succeeded();
+                Ok(reply.is_some())
+            }
+            Err(e) => {
+                self.failed("SET NX", key, &e);
+                Err(anyhow::anyhow!("Redis SET NX failed: {}", e))
+            }
+        }
+    }
+
     /// Get cache value
     pub async fn get<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Result<Option<T>> {
+        if self.skipped() {
+            return Ok(None);
+        }
         let mut conn = self.connection_manager.clone();
 
         let result: RedisResult<Option<String>> = conn.get(key).await;
+        if result.is_ok() {
+            self.succeeded();
+        }
 
         match result {
             Ok(Some(value)) => {
@@ -81,7 +167,7 @@ impl CacheService {
                 Ok(None)
             }
             Err(e) => {
-                warn!("Cache GET failed for key {}: {}", key, e);
+                self.failed("GET", key, &e);
                 Ok(None)
             }
         }
@@ -89,35 +175,166 @@ impl CacheService {
 
     /// Delete cache value
     pub async fn delete(&self, key: &str) -> Result<()> {
+        if self.skipped() {
+            return Ok(());
+        }
         let mut conn = self.connection_manager.clone();
 
         let result: RedisResult<i32> = conn.del(key).await;
 
         match result {
             Ok(deleted) => {
+                self.succeeded();
                 debug!("Cache DELETE: {} (deleted: {})", key, deleted);
                 Ok(())
             }
             Err(e) => {
-                error!("Cache DELETE failed for key {}: {}", key, e);
+                self.failed("DELETE", key, &e);
+                Err(anyhow::anyhow!("Redis DEL failed: {}", e))
+            }
+        }
+    }
+
+    /// Get several cache values in one round trip (MGET).
+    /// Missing or unreadable entries come back as `None`.
+    pub async fn get_many<T: for<'de> Deserialize<'de>>(&self, keys: &[String]) -> Result<Vec<Option<T>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.skipped() {
+            return Ok(keys.iter().map(|_| None).collect());
+        }
+
+        let mut conn = self.connection_manager.clone();
+
+        let result: RedisResult<Vec<Option<String>>> =
+            redis::cmd("MGET").arg(keys).query_async(&mut conn).await;
+
+        match result {
+            Ok(values) => {
+                self.succeeded();
+                let values: Vec<Option<T>> = values
+                    .into_iter()
+                    .map(|value| value.and_then(|v| serde_json::from_str(&v).ok()))
+                    .collect();
+                debug!(
+                    "Cache MGET: {} keys ({} hits)",
+                    keys.len(),
+                    values.iter().filter(|v| v.is_some()).count()
+                );
+                Ok(values)
+            }
+            Err(e) => {
+                self.failed("MGET", &format!("{} keys", keys.len()), &e);
+                Ok(keys.iter().map(|_| None).collect())
+            }
+        }
+    }
+
+    /// Set several cache values with the same TTL in one pipeline
+    pub async fn set_many_with_ttl<T: Serialize>(
+        &self,
+        entries: &[(String, T)],
+        ttl_seconds: u64,
+    ) -> Result<()> {
+        if entries.is_empty() || self.skipped() {
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+        for (key, value) in entries {
+            pipe.set_ex(key, serde_json::to_string(value)?, ttl_seconds).ignore();
+        }
+
+        let mut conn = self.connection_manager.clone();
+        let result: RedisResult<()> = pipe.query_async(&mut conn).await;
+
+        match result {
+            Ok(_) => {
+                self.succeeded();
+                debug!("Cache SET: {} keys (TTL: {}s)", entries.len(), ttl_seconds);
+                Ok(())
+            }
+            Err(e) => {
+                self.failed("pipelined SET", &format!("{} keys", entries.len()), &e);
+                Err(anyhow::anyhow!("Redis SET failed: {}", e))
+            }
+        }
+    }
+
+    /// Delete several cache values
+    pub async fn delete_many(&self, keys: &[String]) -> Result<()> {
+        if keys.is_empty() || self.skipped() {
+            return Ok(());
+        }
+
+        let mut conn = self.connection_manager.clone();
+
+        let result: RedisResult<i32> = conn.del(keys).await;
+
+        match result {
+            Ok(deleted) => {
+                self.succeeded();
+                debug!("Cache DELETE: {} keys (deleted: {})", keys.len(), deleted);
+                Ok(())
+            }
+            Err(e) => {
+                self.failed("DELETE", &format!("{} keys", keys.len()), &e);
                 Err(anyhow::anyhow!("Redis DEL failed: {}", e))
             }
         }
     }
 
+    /// Delete every key starting with `prefix`, walking the keyspace with
+    /// SCAN rather than blocking Redis with KEYS; returns how many were deleted
+    pub async fn delete_prefix(&self, prefix: &str) -> Result<usize> {
+        if self.skipped() {
+            return Err(anyhow::anyhow!("Cache is down"));
+        }
+        let mut conn = self.connection_manager.clone();
+        let pattern = format!("{}*", prefix);
+        let mut cursor: u64 = 0;
+        let mut deleted = 0;
+
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(500)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| anyhow::anyhow!("Redis SCAN failed: {}", e))?;
+            self.delete_many(&keys).await?;
+            deleted += keys.len();
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+
+        debug!("Cache DELETE prefix {}: {} keys", prefix, deleted);
+        Ok(deleted)
+    }
+
     /// Check if key exists
     pub async fn exists(&self, key: &str) -> Result<bool> {
+        if self.skipped() {
+            return Ok(false);
+        }
         let mut conn = self.connection_manager.clone();
 
         let result: RedisResult<bool> = conn.exists(key).await;
 
         match result {
             Ok(exists) => {
+                self.succeeded();
                 debug!("Cache EXISTS: {} -> {}", key, exists);
                 Ok(exists)
             }
             Err(e) => {
-                warn!("Cache EXISTS failed for key {}: {}", key, e);
+                self.failed("EXISTS", key, &e);
                 Ok(false)
             }
         }
@@ -141,17 +358,21 @@ impl CacheService {
 
     /// Increment counter
     pub async fn increment(&self, key: &str) -> Result<i64> {
+        if self.skipped() {
+            return Err(anyhow::anyhow!("Cache is down"));
+        }
         let mut conn = self.connection_manager.clone();
 
         let result: RedisResult<i64> = conn.incr(key, 1).await;
 
         match result {
             Ok(value) => {
+                self.succeeded();
                 debug!("Cache INCR: {} -> {}", key, value);
                 Ok(value)
             }
             Err(e) => {
-                error!("Cache INCR failed for key {}: {}", key, e);
+                self.failed("INCR", key, &e);
                 Err(anyhow::anyhow!("Redis INCR failed: {}", e))
             }
         }
@@ -162,13 +383,12 @@ impl CacheService {
         let value = self.increment(key).await?;
 
         // Set expiration only if this is a new key (value == 1)
-        // Note: For now, we skip expiration as it's causing Redis type issues
-        // In production, this would be implemented with proper Redis commands
         if value == 1 {
-            debug!(
-                "Would set expiration for new key: {} ({}s)",
-                key, ttl_seconds
-            );
+            let mut conn = self.connection_manager.clone();
+            let result: RedisResult<bool> = conn.expire(key, ttl_seconds as i64).await;
+            if let Err(e) = result {
+                warn!("Cache EXPIRE failed for key {}: {}", key, e);
+            }
         }
 
         Ok(value)
@@ -244,6 +464,11 @@ impl CacheKeys {
         format!("token:balance:{}:{}", wallet_address, mint)
     }
 
+    /// On-chain account balance cache key (wallet lamports or token account amount)
+    pub fn account_balance(address: &str) -> String {
+        format!("account:balance:{}", address)
+    }
+
     /// Settlement cache key
     pub fn settlement(settlement_id: &Uuid) -> String {
         format!("settlement:{}", settlement_id)
@@ -253,6 +478,11 @@ impl CacheKeys {
     pub fn erc_certificate(certificate_id: &str) -> String {
         format!("erc:certificate:{}", certificate_id)
     }
+
+    /// Dashboard metrics read model cache key
+    pub fn dashboard_metrics() -> String {
+        "dashboard:metrics".to_string()
+    }
 }
 
 #[cfg(test)]
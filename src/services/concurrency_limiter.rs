@@ -0,0 +1,177 @@
+//! Concurrency Limiter
+//!
+//! Caps the requests one caller has in flight on expensive endpoints
+//! (exports, analytics, order placement), independent of the per-minute
+//! rate limits. A request over the cap waits up to the queue timeout for a
+//! slot and is then rejected with 429. Slots are per gateway instance.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use metrics::counter;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use uuid::Uuid;
+
+use crate::config::ConcurrencyLimitConfig;
+use crate::error::ApiError;
+
+/// Endpoint group sharing one in-flight cap per caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConcurrencyClass {
+    Export,
+    Analytics,
+    Orders,
+}
+
+impl ConcurrencyClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConcurrencyClass::Export => "export",
+            ConcurrencyClass::Analytics => "analytics",
+            ConcurrencyClass::Orders => "orders",
+        }
+    }
+}
+
+type SlotKey = (Uuid, ConcurrencyClass);
+
+/// A held slot; released when dropped
+pub struct ConcurrencyPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    key: SlotKey,
+    slots: Arc<DashMap<SlotKey, Arc<Semaphore>>>,
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        drop(self.permit.take());
+        // Only the map holds the semaphore once nobody holds or awaits a slot
+        self.slots.remove_if(&self.key, |_, semaphore| Arc::strong_count(semaphore) == 1);
+    }
+}
+
+#[derive(Clone)]
+pub struct ConcurrencyLimiter {
+    config: ConcurrencyLimitConfig,
+    slots: Arc<DashMap<SlotKey, Arc<Semaphore>>>,
+}
+
+impl ConcurrencyLimiter {
+    pub fn new(config: ConcurrencyLimitConfig) -> Self {
+        Self {
+            config,
+            slots: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// In-flight requests allowed per caller; 0 disables the limit
+    pub fn limit(&self, class: ConcurrencyClass) -> usize {
+        match class {
+            ConcurrencyClass::Export => self.config.export_max_in_flight,
+            ConcurrencyClass::Analytics => self.config.analytics_max_in_flight,
+            ConcurrencyClass::Orders => self.config.orders_max_in_flight,
+        }
+    }
+
+    /// Take a slot for the caller, waiting up to the queue timeout.
+    /// Returns None when the class is not limited.
+    pub async fn acquire(&self, user_id: Uuid, class: ConcurrencyClass) -> Result<Option<ConcurrencyPermit>, ApiError> {
+        let limit = self.limit(class);
+        if limit == 0 {
+            return Ok(None);
+        }
+
+        let key = (user_id, class);
+        let semaphore = self
+            .slots
+            .entry(key)
+            .or_insert_with(|| Arc::new(Semaphore::new(limit)))
+            .clone();
+
+        let permit = match semaphore.clone().try_acquire_owned() {
+            Ok(permit) => Some(permit),
+            Err(_) if self.config.queue_timeout_ms > 0 => {
+                counter!("concurrency_limit_queued_total", "class" => class.as_str()).increment(1);
+                tokio::time::timeout(
+                    Duration::from_millis(self.config.queue_timeout_ms),
+                    semaphore.clone().acquire_owned(),
+                )
+                .await
+                .ok()
+                .and_then(|permit| permit.ok())
+            }
+            Err(_) => None,
+        };
+        drop(semaphore);
+
+        let held = ConcurrencyPermit {
+            permit,
+            key,
+            slots: self.slots.clone(),
+        };
+        if held.permit.is_none() {
+            counter!("concurrency_limit_rejected_total", "class" => class.as_str()).increment(1);
+            return Err(ApiError::RateLimitExceeded(format!(
+                "At most {} concurrent {} requests per caller; retry when one completes",
+                limit,
+                class.as_str()
+            )));
+        }
+        Ok(Some(held))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(queue_timeout_ms: u64) -> ConcurrencyLimiter {
+        ConcurrencyLimiter::new(ConcurrencyLimitConfig {
+            export_max_in_flight: 2,
+            analytics_max_in_flight: 4,
+            orders_max_in_flight: 0,
+            queue_timeout_ms,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_over_limit_rejected_until_slot_released() {
+        let limiter = limiter(0);
+        let user = Uuid::new_v4();
+
+        let first = limiter.acquire(user, ConcurrencyClass::Export).await.unwrap();
+        let _second = limiter.acquire(user, ConcurrencyClass::Export).await.unwrap();
+        assert!(limiter.acquire(user, ConcurrencyClass::Export).await.is_err());
+        // Other callers and classes have their own slots
+        assert!(limiter.acquire(Uuid::new_v4(), ConcurrencyClass::Export).await.is_ok());
+        assert!(limiter.acquire(user, ConcurrencyClass::Analytics).await.is_ok());
+
+        drop(first);
+        assert!(limiter.acquire(user, ConcurrencyClass::Export).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_queued_request_gets_released_slot() {
+        let limiter = limiter(1000);
+        let user = Uuid::new_v4();
+
+        let first = limiter.acquire(user, ConcurrencyClass::Export).await.unwrap();
+        let _second = limiter.acquire(user, ConcurrencyClass::Export).await.unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(first);
+        });
+        assert!(limiter.acquire(user, ConcurrencyClass::Export).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_class_and_idle_slots_removed() {
+        let limiter = limiter(0);
+        let user = Uuid::new_v4();
+
+        assert!(limiter.acquire(user, ConcurrencyClass::Orders).await.unwrap().is_none());
+        drop(limiter.acquire(user, ConcurrencyClass::Export).await.unwrap());
+        assert!(limiter.slots.is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod read_model;
 pub mod types;
  
 use std::sync::Arc;
@@ -9,7 +10,10 @@ use crate::services::health_check::HealthChecker;
 use crate::services::transaction::metrics::MetricsExporter;
 use std::collections::HashMap;
 pub use types::{DashboardMetrics, GridStatus, ZoneGridStatus};
+use crate::models::EnergyKwh;
 use crate::services::websocket::types::ZoneStatus as WsZoneStatus;
+use crate::services::EmissionFactorService;
+use crate::services::CacheService;
 
 #[derive(Clone)]
 pub struct DashboardService {
@@ -17,6 +21,9 @@ pub struct DashboardService {
     health_checker: HealthChecker,
     event_processor: EventProcessorService,
     websocket_service: WebSocketService,
+    emission_factors: EmissionFactorService,
+    /// Redis copy of the metrics read model
+    cache: CacheService,
     metrics: Arc<RwLock<GridStatus>>,
 }
 
@@ -26,18 +33,24 @@ impl DashboardService {
         health_checker: HealthChecker,
         event_processor: EventProcessorService,
         websocket_service: WebSocketService,
+        emission_factors: EmissionFactorService,
+        cache: CacheService,
     ) -> Self {
         Self {
             db,
             health_checker,
             event_processor,
             websocket_service,
+            emission_factors,
+            cache,
                 metrics: Arc::new(RwLock::new(GridStatus {
-                total_generation: 0.0,
-                total_consumption: 0.0,
-                net_balance: 0.0,
+                total_generation: EnergyKwh::ZERO,
+                total_consumption: EnergyKwh::ZERO,
+                net_balance: EnergyKwh::ZERO,
                 active_meters: 0,
                 co2_saved_kg: 0.0,
+                co2_factor: None,
+                co2_factor_data: None,
                 zones: HashMap::new(),
                 zones_data: None,
                 timestamp: Utc::now(),
@@ -46,11 +59,12 @@ impl DashboardService {
     }
 
     /// Handle a new meter reading to update aggregate grid status and broadcast
-    pub async fn handle_meter_reading(&self, kwh: f64, _meter_serial: &str, zone_id: Option<i32>) -> anyhow::Result<()> {
+    pub async fn handle_meter_reading(&self, kwh: EnergyKwh, _meter_serial: &str, zone_id: Option<i32>) -> anyhow::Result<()> {
+        let factor = self.emission_factors.current().await;
         let mut metrics = self.metrics.write().await;
         
         // Update aggregate totals
-        if kwh > 0.0 {
+        if kwh.is_positive() {
             metrics.total_generation += kwh;
         } else {
             metrics.total_consumption += kwh.abs();
@@ -60,13 +74,13 @@ impl DashboardService {
         if let Some(zid) = zone_id {
             let zone_status = metrics.zones.entry(zid).or_insert(ZoneGridStatus {
                 zone_id: zid,
-                generation: 0.0,
-                consumption: 0.0,
-                net_balance: 0.0,
+                generation: EnergyKwh::ZERO,
+                consumption: EnergyKwh::ZERO,
+                net_balance: EnergyKwh::ZERO,
                 active_meters: 0,
             });
 
-            if kwh > 0.0 {
+            if kwh.is_positive() {
                 zone_status.generation += kwh;
             } else {
                 zone_status.consumption += kwh.abs();
@@ -85,7 +99,8 @@ impl DashboardService {
         }
 
         metrics.net_balance = metrics.total_generation - metrics.total_consumption;
-        metrics.co2_saved_kg = metrics.total_generation * 0.431;
+        metrics.co2_saved_kg = metrics.total_generation.to_f64() * factor.kg_co2_per_kwh;
+        metrics.co2_factor = Some(factor);
         metrics.timestamp = Utc::now();
 
         // Broadcast to all connected clients
@@ -123,7 +138,7 @@ impl DashboardService {
     /// Retrieve historical grid status snapshots
     pub async fn get_grid_history(&self, limit: i64) -> anyhow::Result<Vec<GridStatus>> {
         let history = sqlx::query_as::<_, GridStatus>(
-            "SELECT total_generation, total_consumption, net_balance, active_meters, co2_saved_kg, timestamp, zones_data
+            "SELECT total_generation, total_consumption, net_balance, active_meters, co2_saved_kg, timestamp, zones_data, co2_factor AS co2_factor_data
              FROM grid_status_history 
              ORDER BY timestamp DESC 
              LIMIT $1"
@@ -139,12 +154,26 @@ impl DashboardService {
                     gs.zones = zones;
                 }
             }
+            if let Some(fd) = gs.co2_factor_data.take() {
+                gs.co2_factor = serde_json::from_value(fd).ok();
+            }
             gs
         }).collect();
 
         Ok(mapped_history)
     }
 
+    /// Replace the in-memory grid status with the last recorded snapshot.
+    /// Readings since that snapshot are dropped from the totals; returns
+    /// false when no snapshot was recorded yet.
+    pub async fn restore_grid_status(&self) -> anyhow::Result<bool> {
+        let Some(latest) = self.get_grid_history(1).await?.into_iter().next() else {
+            return Ok(false);
+        };
+        *self.metrics.write().await = latest;
+        Ok(true)
+    }
+
     /// Start a background task to record grid status snapshots periodically
     pub async fn start_history_recorder(&self) {
         let self_clone = self.clone();
@@ -163,11 +192,12 @@ impl DashboardService {
                 let current = self_clone.get_grid_status().await;
                 let snapshot_time = Utc::now();
                 let zones_json = serde_json::to_value(&current.zones).unwrap_or(serde_json::Value::Null);
+                let factor_json = current.co2_factor.as_ref().and_then(|f| serde_json::to_value(f).ok());
                 
                 // Only record if there's some activity or regularly
                 let result = sqlx::query(
-                    "INSERT INTO grid_status_history (total_generation, total_consumption, net_balance, active_meters, co2_saved_kg, timestamp, zones_data)
-                     VALUES ($1, $2, $3, $4, $5, $6, $7)"
+                    "INSERT INTO grid_status_history (total_generation, total_consumption, net_balance, active_meters, co2_saved_kg, timestamp, zones_data, co2_factor)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
                 )
                 .bind(current.total_generation)
                 .bind(current.total_consumption)
@@ -176,6 +206,7 @@ impl DashboardService {
                 .bind(current.co2_saved_kg)
                 .bind(snapshot_time)
                 .bind(zones_json)
+                .bind(factor_json)
                 .execute(&self_clone.db)
                 .await;
 
@@ -186,7 +217,9 @@ impl DashboardService {
         });
     }
 
-    pub async fn get_metrics(&self) -> anyhow::Result<DashboardMetrics> {
+    /// Assemble the metrics from their services; requests are served from
+    /// the read model instead
+    async fn assemble_metrics(&self) -> anyhow::Result<DashboardMetrics> {
         // Fetch metrics in parallel where possible
         let (health_status, event_stats) = tokio::join!(
             self.health_checker.perform_health_check(),
@@ -200,6 +233,7 @@ impl DashboardService {
             event_processor: event_stats?,
             pending_transactions,
             grid_status: self.get_grid_status().await,
+            generated_at: Utc::now(),
         })
     }
 }
@@ -0,0 +1,115 @@
+//! Dashboard metrics read model
+//!
+//! The dashboard metrics are assembled from the health checker, the event
+//! processor, the transaction metrics and the grid status. Instead of doing
+//! that on every request, the assembled metrics are written after each event
+//! processor cycle to `dashboard_metrics_read_model` and to Redis, and the
+//! endpoint reads the Redis copy, falling back to the table.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use super::{DashboardMetrics, DashboardService};
+use crate::services::cache::CacheKeys;
+
+/// Age after which a stored read model is assembled again on request
+const MAX_AGE_SECS: i64 = 300;
+
+/// Whether a read model generated at `generated_at` can still be served
+pub fn is_fresh(generated_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    (now - generated_at).num_seconds() <= MAX_AGE_SECS
+}
+
+impl DashboardService {
+    /// Dashboard metrics from the read model; assembled and stored when the
+    /// read model is missing or stale
+    pub async fn get_metrics(&self) -> anyhow::Result<DashboardMetrics> {
+        let now = Utc::now();
+        match self.cache.get::<DashboardMetrics>(&CacheKeys::dashboard_metrics()).await {
+            Ok(Some(metrics)) if is_fresh(metrics.generated_at, now) => return Ok(metrics),
+            Ok(_) => {}
+            Err(e) => warn!("Dashboard read model cache unavailable: {}", e),
+        }
+
+        let stored = sqlx::query_scalar::<_, sqlx::types::Json<DashboardMetrics>>(
+            "SELECT metrics FROM dashboard_metrics_read_model WHERE id",
+        )
+        .fetch_optional(&self.db)
+        .await?;
+        if let Some(sqlx::types::Json(metrics)) = stored {
+            if is_fresh(metrics.generated_at, now) {
+                return Ok(metrics);
+            }
+        }
+
+        self.refresh_read_model().await
+    }
+
+    /// Assemble the metrics and store them in the table and Redis
+    pub async fn refresh_read_model(&self) -> anyhow::Result<DashboardMetrics> {
+        let metrics = self.assemble_metrics().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO dashboard_metrics_read_model (id, metrics, refreshed_at)
+            VALUES (TRUE, $1, $2)
+            ON CONFLICT (id) DO UPDATE SET
+                metrics = EXCLUDED.metrics,
+                refreshed_at = EXCLUDED.refreshed_at
+            "#,
+        )
+        .bind(sqlx::types::Json(&metrics))
+        .bind(metrics.generated_at)
+        .execute(&self.db)
+        .await?;
+
+        if let Err(e) = self
+            .cache
+            .set_with_ttl(&CacheKeys::dashboard_metrics(), &metrics, MAX_AGE_SECS as u64)
+            .await
+        {
+            warn!("Failed to cache dashboard read model: {}", e);
+        }
+        Ok(metrics)
+    }
+
+    /// Start a background task refreshing the read model after each event
+    /// processor cycle, and at least every `DASHBOARD_REFRESH_INTERVAL_SECS`
+    /// when the event processor is idle or disabled
+    pub async fn start_read_model_refresher(&self) {
+        let self_clone = self.clone();
+        let interval_secs = std::env::var("DASHBOARD_REFRESH_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(30)
+            .max(1);
+
+        tokio::spawn(async move {
+            info!("🚀 Starting dashboard read model refresher (interval: {}s)", interval_secs);
+            loop {
+                if let Err(e) = self_clone.refresh_read_model().await {
+                    tracing::error!("❌ Failed to refresh dashboard read model: {}", e);
+                }
+                tokio::select! {
+                    _ = self_clone.event_processor.cycle_completed() => {}
+                    _ = tokio::time::sleep(Duration::from_secs(interval_secs)) => {}
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_model_freshness() {
+        let now = Utc::now();
+        assert!(is_fresh(now, now));
+        assert!(is_fresh(now - chrono::Duration::seconds(MAX_AGE_SECS), now));
+        assert!(!is_fresh(now - chrono::Duration::seconds(MAX_AGE_SECS + 1), now));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::models::EnergyKwh;
+use crate::services::emission_factors::FactorProvenance;
 use crate::services::event_processor::EventProcessorStats;
 use crate::services::health_check::DetailedHealthStatus;
 use serde::{Deserialize, Serialize};
@@ -6,11 +8,17 @@ use utoipa::ToSchema;
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
 pub struct GridStatus {
-    pub total_generation: f64,
-    pub total_consumption: f64,
-    pub net_balance: f64,
+    pub total_generation: EnergyKwh,
+    pub total_consumption: EnergyKwh,
+    pub net_balance: EnergyKwh,
     pub active_meters: i64,
     pub co2_saved_kg: f64,
+    /// Emission factor `co2_saved_kg` was computed with
+    #[sqlx(skip)]
+    pub co2_factor: Option<FactorProvenance>,
+    #[serde(skip)]
+    #[sqlx(default)]
+    pub co2_factor_data: Option<serde_json::Value>,
     #[sqlx(skip)]
     pub zones: HashMap<i32, ZoneGridStatus>,
     #[serde(skip)]
@@ -22,9 +30,9 @@ pub struct GridStatus {
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ZoneGridStatus {
     pub zone_id: i32,
-    pub generation: f64,
-    pub consumption: f64,
-    pub net_balance: f64,
+    pub generation: EnergyKwh,
+    pub consumption: EnergyKwh,
+    pub net_balance: EnergyKwh,
     pub active_meters: i32,
 }
 
@@ -34,4 +42,6 @@ pub struct DashboardMetrics {
     pub event_processor: EventProcessorStats,
     pub pending_transactions: HashMap<String, i64>,
     pub grid_status: GridStatus,
+    /// When the metrics were assembled
+    pub generated_at: chrono::DateTime<chrono::Utc>,
 }
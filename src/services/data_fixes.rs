@@ -0,0 +1,368 @@
+//! Admin Data Fixes
+//!
+//! Guarded repairs for data that drifted from its source of truth, so
+//! production is never patched with ad hoc SQL. Every operation computes
+//! its corrections in a transaction and returns them; nothing is written
+//! unless the caller turns off `dry_run`, and applied fixes are audited.
+//!
+//! - Epoch statistics are recomputed from the epoch's order matches.
+//! - Order `filled_amount`s are resynced from their order matches.
+//! - Prepaid balances are rebuilt from the prepaid ledger.
+//! - Settlements stuck in `processing` are marked failed.
+//!
+//! Busted matches do not count towards epochs or fills.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::{AuditEvent, AuditLogger};
+
+/// One field a fix corrects
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DataFixChange {
+    pub target_id: Uuid,
+    pub field: String,
+    pub current: Option<String>,
+    pub corrected: Option<String>,
+}
+
+/// What a fix found, and whether it was applied
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DataFixReport {
+    pub operation: String,
+    pub dry_run: bool,
+    /// Rows corrected, or that would be
+    pub affected: usize,
+    pub changes: Vec<DataFixChange>,
+}
+
+fn change<T: ToString>(target_id: Uuid, field: &str, current: Option<T>, corrected: Option<T>) -> DataFixChange {
+    DataFixChange {
+        target_id,
+        field: field.to_string(),
+        current: current.map(|v| v.to_string()),
+        corrected: corrected.map(|v| v.to_string()),
+    }
+}
+
+/// Status an open order should have given what its matches filled;
+/// `None` when it is right or the order is no longer open
+pub fn corrected_order_status(status: &str, matched: Decimal, energy_amount: Decimal) -> Option<&'static str> {
+    if !matches!(status, "pending" | "active" | "partially_filled") {
+        return None;
+    }
+    let expected = if matched >= energy_amount {
+        "filled"
+    } else if matched > Decimal::ZERO {
+        "partially_filled"
+    } else if status == "partially_filled" {
+        "active"
+    } else {
+        return None;
+    };
+    (expected != status).then_some(expected)
+}
+
+#[derive(Clone)]
+pub struct DataFixService {
+    db: PgPool,
+    audit: AuditLogger,
+}
+
+impl DataFixService {
+    pub fn new(db: PgPool, audit: AuditLogger) -> Self {
+        Self { db, audit }
+    }
+
+    /// Recompute volume, match count and clearing price of cleared or
+    /// settled epochs (one, or all) from their order matches
+    pub async fn recompute_epoch_stats(
+        &self,
+        admin_id: Uuid,
+        epoch_id: Option<Uuid>,
+        dry_run: bool,
+    ) -> Result<DataFixReport, ApiError> {
+        let mut tx = self.db.begin().await?;
+        let rows = sqlx::query(
+            r#"
+            SELECT e.id, e.total_volume, e.matched_orders, e.clearing_price,
+                   COALESCE(m.volume, 0) AS volume, COALESCE(m.match_count, 0) AS match_count, m.price
+            FROM market_epochs e
+            LEFT JOIN (
+                SELECT epoch_id, SUM(matched_amount) AS volume, COUNT(*) AS match_count,
+                       ROUND(SUM(matched_amount * match_price) / NULLIF(SUM(matched_amount), 0), 8) AS price
+                FROM order_matches
+                WHERE status NOT IN ('busted', 'rolled_back')
+                GROUP BY epoch_id
+            ) m ON m.epoch_id = e.id
+            WHERE e.status IN ('cleared', 'settled') AND ($1::uuid IS NULL OR e.id = $1)
+            ORDER BY e.epoch_number
+            "#,
+        )
+        .bind(epoch_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut changes = Vec::new();
+        let mut fixes: (Vec<Uuid>, Vec<Decimal>, Vec<i64>, Vec<Option<Decimal>>) = Default::default();
+        for row in rows {
+            let id: Uuid = row.get("id");
+            let (volume, count, price): (Decimal, i64, Option<Decimal>) =
+                (row.get("volume"), row.get("match_count"), row.get("price"));
+            let current_volume: Option<Decimal> = row.get("total_volume");
+            let current_count: Option<i64> = row.get("matched_orders");
+            let current_price: Option<Decimal> = row.get("clearing_price");
+
+            let before = changes.len();
+            if current_volume != Some(volume) {
+                changes.push(change(id, "total_volume", current_volume, Some(volume)));
+            }
+            if current_count != Some(count) {
+                changes.push(change(id, "matched_orders", current_count, Some(count)));
+            }
+            if current_price != price {
+                changes.push(change(id, "clearing_price", current_price, price));
+            }
+            if changes.len() > before {
+                fixes.0.push(id);
+                fixes.1.push(volume);
+                fixes.2.push(count);
+                fixes.3.push(price);
+            }
+        }
+
+        let affected = fixes.0.len();
+        if !dry_run && affected > 0 {
+            sqlx::query(
+                r#"
+                UPDATE market_epochs e
+                SET total_volume = f.volume, matched_orders = f.match_count, clearing_price = f.price
+                FROM UNNEST($1::uuid[], $2::numeric[], $3::bigint[], $4::numeric[])
+                     AS f(id, volume, match_count, price)
+                WHERE e.id = f.id
+                "#,
+            )
+            .bind(&fixes.0)
+            .bind(&fixes.1)
+            .bind(&fixes.2)
+            .bind(&fixes.3)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        self.finish(tx, admin_id, "recompute_epoch_stats", dry_run, affected, changes).await
+    }
+
+    /// Resync `filled_amount` of orders (one user's, or all) with what
+    /// their matches filled, and correct the status of open orders
+    pub async fn resync_order_fills(
+        &self,
+        admin_id: Uuid,
+        user_id: Option<Uuid>,
+        dry_run: bool,
+    ) -> Result<DataFixReport, ApiError> {
+        let mut tx = self.db.begin().await?;
+        let rows = sqlx::query(
+            r#"
+            SELECT o.id, o.filled_amount, o.energy_amount, o.status::text AS status,
+                   COALESCE(m.matched, 0) AS matched
+            FROM trading_orders o
+            LEFT JOIN (
+                SELECT order_id, SUM(matched_amount) AS matched
+                FROM (
+                    SELECT buy_order_id AS order_id, matched_amount FROM order_matches WHERE status NOT IN ('busted', 'rolled_back')
+                    UNION ALL
+                    SELECT sell_order_id, matched_amount FROM order_matches WHERE status NOT IN ('busted', 'rolled_back')
+                ) sides
+                GROUP BY order_id
+            ) m ON m.order_id = o.id
+            WHERE COALESCE(o.filled_amount, 0) <> COALESCE(m.matched, 0)
+              AND ($1::uuid IS NULL OR o.user_id = $1)
+            ORDER BY o.created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut changes = Vec::new();
+        let mut fixes: (Vec<Uuid>, Vec<Decimal>, Vec<Option<String>>) = Default::default();
+        for row in rows {
+            let id: Uuid = row.get("id");
+            let matched: Decimal = row.get("matched");
+            let status: String = row.get("status");
+            let corrected_status = corrected_order_status(&status, matched, row.get("energy_amount"));
+
+            changes.push(change(id, "filled_amount", row.get::<Option<Decimal>, _>("filled_amount"), Some(matched)));
+            if let Some(corrected) = corrected_status {
+                changes.push(change(id, "status", Some(status.as_str()), Some(corrected)));
+            }
+            fixes.0.push(id);
+            fixes.1.push(matched);
+            fixes.2.push(corrected_status.map(str::to_string));
+        }
+
+        let affected = fixes.0.len();
+        if !dry_run && affected > 0 {
+            sqlx::query(
+                r#"
+                UPDATE trading_orders o
+                SET filled_amount = f.matched,
+                    status = COALESCE(f.status::order_status, o.status),
+                    updated_at = NOW()
+                FROM UNNEST($1::uuid[], $2::numeric[], $3::text[]) AS f(id, matched, status)
+                WHERE o.id = f.id
+                "#,
+            )
+            .bind(&fixes.0)
+            .bind(&fixes.1)
+            .bind(&fixes.2)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        self.finish(tx, admin_id, "resync_order_fills", dry_run, affected, changes).await
+    }
+
+    /// Rebuild prepaid balances (one user's, or all) as the sum of their
+    /// prepaid ledger entries
+    pub async fn rebuild_prepaid_balances(
+        &self,
+        admin_id: Uuid,
+        user_id: Option<Uuid>,
+        dry_run: bool,
+    ) -> Result<DataFixReport, ApiError> {
+        let mut tx = self.db.begin().await?;
+        let rows = sqlx::query(
+            r#"
+            SELECT u.id, u.prepaid_balance, COALESCE(SUM(l.amount), 0) AS ledger_balance
+            FROM users u
+            LEFT JOIN prepaid_ledger l ON l.user_id = u.id
+            WHERE $1::uuid IS NULL OR u.id = $1
+            GROUP BY u.id
+            HAVING u.prepaid_balance <> COALESCE(SUM(l.amount), 0)
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut changes = Vec::new();
+        let mut fixes: (Vec<Uuid>, Vec<Decimal>) = Default::default();
+        for row in rows {
+            let id: Uuid = row.get("id");
+            let balance: Decimal = row.get("ledger_balance");
+            changes.push(change(id, "prepaid_balance", Some(row.get::<Decimal, _>("prepaid_balance")), Some(balance)));
+            fixes.0.push(id);
+            fixes.1.push(balance);
+        }
+
+        let affected = fixes.0.len();
+        if !dry_run && affected > 0 {
+            sqlx::query(
+                r#"
+                UPDATE users u SET prepaid_balance = f.balance
+                FROM UNNEST($1::uuid[], $2::numeric[]) AS f(id, balance)
+                WHERE u.id = f.id
+                "#,
+            )
+            .bind(&fixes.0)
+            .bind(&fixes.1)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        self.finish(tx, admin_id, "rebuild_prepaid_balances", dry_run, affected, changes).await
+    }
+
+    /// Mark settlements that have been `processing` for longer than
+    /// `older_than_minutes` as failed
+    pub async fn fail_stuck_settlements(
+        &self,
+        admin_id: Uuid,
+        older_than_minutes: i32,
+        dry_run: bool,
+    ) -> Result<DataFixReport, ApiError> {
+        let mut tx = self.db.begin().await?;
+        let ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM settlements
+            WHERE status = 'processing' AND updated_at < NOW() - make_interval(mins => $1)
+            ORDER BY updated_at
+            FOR UPDATE
+            "#,
+        )
+        .bind(older_than_minutes)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let changes = ids
+            .iter()
+            .map(|id| change(*id, "status", Some("processing"), Some("failed")))
+            .collect();
+
+        if !dry_run && !ids.is_empty() {
+            sqlx::query("UPDATE settlements SET status = 'failed', updated_at = NOW() WHERE id = ANY($1)")
+                .bind(&ids)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        self.finish(tx, admin_id, "fail_stuck_settlements", dry_run, ids.len(), changes).await
+    }
+
+    /// Commit an applied fix and audit it; a dry run is rolled back
+    async fn finish(
+        &self,
+        tx: Transaction<'_, Postgres>,
+        admin_id: Uuid,
+        operation: &str,
+        dry_run: bool,
+        affected: usize,
+        changes: Vec<DataFixChange>,
+    ) -> Result<DataFixReport, ApiError> {
+        if dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+            if affected > 0 {
+                info!("🔧 Admin {} applied data fix {}: {} rows corrected", admin_id, operation, affected);
+                self.audit.log_async(AuditEvent::AdminAction {
+                    admin_id,
+                    action: format!("data_fix_{}", operation),
+                    target_user_id: None,
+                    details: format!("{} rows, {} fields corrected", affected, changes.len()),
+                });
+            }
+        }
+
+        Ok(DataFixReport {
+            operation: operation.to_string(),
+            dry_run,
+            affected,
+            changes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corrected_order_status() {
+        let ten = Decimal::from(10);
+        assert_eq!(corrected_order_status("active", ten, ten), Some("filled"));
+        assert_eq!(corrected_order_status("pending", Decimal::from(4), ten), Some("partially_filled"));
+        assert_eq!(corrected_order_status("partially_filled", Decimal::ZERO, ten), Some("active"));
+        assert_eq!(corrected_order_status("partially_filled", Decimal::from(4), ten), None);
+        assert_eq!(corrected_order_status("pending", Decimal::ZERO, ten), None);
+        // Closed orders keep their status
+        assert_eq!(corrected_order_status("cancelled", Decimal::from(4), ten), None);
+    }
+}
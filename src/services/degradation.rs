@@ -0,0 +1,284 @@
+//! Degradation Manager
+//!
+//! One soft-failure policy for optional integrations at runtime. Email,
+//! cache and metrics report their failures and successes here instead of
+//! logging them ad hoc. After a few consecutive failures a subsystem is
+//! marked down, logged once, and its users adapt: emails are queued and
+//! cache reads and writes are skipped. A probe loop retries subsystems that
+//! are down; recoveries are logged and published like degradations, and
+//! the state is shown in `/readyz` and the admin overview.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+use utoipa::ToSchema;
+
+use crate::config::DegradationConfig;
+
+/// Transitions kept for late subscribers
+const EVENT_BUFFER: usize = 64;
+
+/// Optional integration the gateway keeps running without
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    Email,
+    Cache,
+    Metrics,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 3] = [Subsystem::Email, Subsystem::Cache, Subsystem::Metrics];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Subsystem::Email => "email",
+            Subsystem::Cache => "cache",
+            Subsystem::Metrics => "metrics",
+        }
+    }
+}
+
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubsystemStatus {
+    Up,
+    /// Failing; dependents skip or defer work
+    Down,
+}
+
+/// Current state of one optional subsystem
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SubsystemHealth {
+    pub subsystem: Subsystem,
+    pub status: SubsystemStatus,
+    pub consecutive_failures: u32,
+    pub down_since: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_recovered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubsystemTransition {
+    Degraded,
+    Recovered,
+}
+
+impl SubsystemTransition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubsystemTransition::Degraded => "degraded",
+            SubsystemTransition::Recovered => "recovered",
+        }
+    }
+}
+
+/// A subsystem going down or coming back
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SubsystemEvent {
+    pub subsystem: Subsystem,
+    pub transition: SubsystemTransition,
+    /// Error that took the subsystem down
+    pub detail: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+/// Failure bookkeeping of one subsystem
+#[derive(Debug, Clone, Default)]
+struct Tracker {
+    consecutive_failures: u32,
+    down_since: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    last_recovered_at: Option<DateTime<Utc>>,
+}
+
+impl Tracker {
+    /// Count a failure; true when it takes the subsystem down
+    fn fail(&mut self, error: String, threshold: u32, now: DateTime<Utc>) -> bool {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.last_error = Some(error);
+        if self.down_since.is_none() && self.consecutive_failures >= threshold.max(1) {
+            self.down_since = Some(now);
+            return true;
+        }
+        false
+    }
+
+    /// Count a success; true when it brings the subsystem back
+    fn succeed(&mut self, now: DateTime<Utc>) -> bool {
+        self.consecutive_failures = 0;
+        if self.down_since.take().is_some() {
+            self.last_recovered_at = Some(now);
+            return true;
+        }
+        false
+    }
+
+    fn health(&self, subsystem: Subsystem) -> SubsystemHealth {
+        SubsystemHealth {
+            subsystem,
+            status: if self.down_since.is_some() { SubsystemStatus::Down } else { SubsystemStatus::Up },
+            consecutive_failures: self.consecutive_failures,
+            down_since: self.down_since,
+            last_error: self.last_error.clone(),
+            last_recovered_at: self.last_recovered_at,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DegradationManager {
+    failure_threshold: u32,
+    trackers: Arc<Mutex<BTreeMap<Subsystem, Tracker>>>,
+    events: broadcast::Sender<SubsystemEvent>,
+}
+
+impl DegradationManager {
+    pub fn new(config: &DegradationConfig) -> Self {
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        Self {
+            failure_threshold: config.failure_threshold,
+            trackers: Arc::new(Mutex::new(
+                Subsystem::ALL.into_iter().map(|s| (s, Tracker::default())).collect(),
+            )),
+            events,
+        }
+    }
+
+    /// Record a failed operation; the subsystem goes down once failures
+    /// reach the threshold
+    pub fn failure(&self, subsystem: Subsystem, error: impl fmt::Display) {
+        self.fail(subsystem, error.to_string(), self.failure_threshold);
+    }
+
+    /// Mark a subsystem down straight away, e.g. when it failed to start
+    pub fn mark_down(&self, subsystem: Subsystem, error: impl fmt::Display) {
+        self.fail(subsystem, error.to_string(), 1);
+    }
+
+    fn fail(&self, subsystem: Subsystem, error: String, threshold: u32) {
+        let now = Utc::now();
+        let degraded = self.tracker(subsystem, |t| t.fail(error.clone(), threshold, now));
+        if degraded {
+            warn!("⚠️ {} degraded: {}", subsystem, error);
+            self.publish(subsystem, SubsystemTransition::Degraded, Some(error), now);
+        } else {
+            debug!("{} operation failed: {}", subsystem, error);
+        }
+    }
+
+    /// Record a successful operation, bringing a down subsystem back
+    pub fn success(&self, subsystem: Subsystem) {
+        let now = Utc::now();
+        if self.tracker(subsystem, |t| t.succeed(now)) {
+            info!("✅ {} recovered", subsystem);
+            self.publish(subsystem, SubsystemTransition::Recovered, None, now);
+        }
+    }
+
+    pub fn is_down(&self, subsystem: Subsystem) -> bool {
+        self.tracker(subsystem, |t| t.down_since.is_some())
+    }
+
+    /// Subsystems currently down
+    pub fn down(&self) -> Vec<Subsystem> {
+        self.snapshot()
+            .into_iter()
+            .filter(|h| h.status == SubsystemStatus::Down)
+            .map(|h| h.subsystem)
+            .collect()
+    }
+
+    pub fn snapshot(&self) -> Vec<SubsystemHealth> {
+        let trackers = self.trackers.lock().unwrap_or_else(|e| e.into_inner());
+        trackers.iter().map(|(s, t)| t.health(*s)).collect()
+    }
+
+    /// Degradation and recovery events from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<SubsystemEvent> {
+        self.events.subscribe()
+    }
+
+    fn tracker<T>(&self, subsystem: Subsystem, f: impl FnOnce(&mut Tracker) -> T) -> T {
+        let mut trackers = self.trackers.lock().unwrap_or_else(|e| e.into_inner());
+        f(trackers.entry(subsystem).or_default())
+    }
+
+    fn publish(&self, subsystem: Subsystem, transition: SubsystemTransition, detail: Option<String>, at: DateTime<Utc>) {
+        // No receivers is fine: the transition is already logged
+        let _ = self.events.send(SubsystemEvent {
+            subsystem,
+            transition,
+            detail,
+            at,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> DegradationManager {
+        DegradationManager::new(&DegradationConfig {
+            failure_threshold: 3,
+            probe_interval_secs: 30,
+            email_queue_capacity: 10,
+        })
+    }
+
+    #[test]
+    fn test_down_after_consecutive_failures() {
+        let degradation = manager();
+        let mut events = degradation.subscribe();
+
+        degradation.failure(Subsystem::Cache, "refused");
+        degradation.failure(Subsystem::Cache, "refused");
+        degradation.success(Subsystem::Cache);
+        degradation.failure(Subsystem::Cache, "refused");
+        assert!(!degradation.is_down(Subsystem::Cache));
+
+        degradation.failure(Subsystem::Cache, "refused");
+        degradation.failure(Subsystem::Cache, "timed out");
+        assert!(degradation.is_down(Subsystem::Cache));
+        assert_eq!(degradation.down(), vec![Subsystem::Cache]);
+
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.transition, SubsystemTransition::Degraded);
+        assert_eq!(event.detail.as_deref(), Some("timed out"));
+        // Further failures while down are not announced again
+        degradation.failure(Subsystem::Cache, "refused");
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_recovery_is_announced_once() {
+        let degradation = manager();
+        let mut events = degradation.subscribe();
+
+        degradation.mark_down(Subsystem::Email, "no smtp");
+        assert!(degradation.is_down(Subsystem::Email));
+        degradation.success(Subsystem::Email);
+        degradation.success(Subsystem::Email);
+
+        assert_eq!(events.try_recv().unwrap().transition, SubsystemTransition::Degraded);
+        assert_eq!(events.try_recv().unwrap().transition, SubsystemTransition::Recovered);
+        assert!(events.try_recv().is_err());
+
+        let email = &degradation.snapshot()[0];
+        assert_eq!(email.status, SubsystemStatus::Up);
+        assert!(email.last_recovered_at.is_some());
+    }
+}
@@ -0,0 +1,717 @@
+//! Delegated Access for Third-Party Apps
+//!
+//! An OAuth2-style authorization code flow. Admins register an app with
+//! its redirect URIs and the scopes it may ask for. A signed-in user
+//! reviews the app's request on the consent screen; approving it records a
+//! grant and sends the user back to the app with a single-use code, which
+//! the app redeems with its client secret for a delegated bearer token.
+//! The auth middleware admits a delegated token only on routes covered by
+//! the granted scopes. Revoking the grant invalidates its tokens at once.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::DelegationConfig;
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+
+/// Prefix telling delegated tokens apart from JWTs
+pub const TOKEN_PREFIX: &str = "gdt_";
+
+/// Scopes an app may request, with the wording shown on the consent screen
+pub const DELEGATION_SCOPES: &[(&str, &str)] = &[
+    ("profile:read", "See your username, email and role"),
+    ("market:read", "Read market data: order books, clearing prices and market statistics"),
+    ("readings:read", "Read your meters and their readings"),
+    ("orders:read", "Read your orders and trades"),
+    ("orders:write", "Place, amend and cancel orders on your behalf"),
+];
+
+const MAX_REDIRECT_URIS: usize = 5;
+const MAX_STATE_LENGTH: usize = 500;
+
+fn scope_description(scope: &str) -> Option<&'static str> {
+    DELEGATION_SCOPES
+        .iter()
+        .find(|(name, _)| *name == scope)
+        .map(|(_, description)| *description)
+}
+
+fn random_secret(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Codes, tokens and client secrets are random, so an unsalted digest is
+/// enough to keep them out of the database
+fn digest(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// Check a presented secret against its stored digest in constant time
+fn digest_matches(stored: &str, secret: &str) -> bool {
+    stored.as_bytes().ct_eq(digest(secret).as_bytes()).into()
+}
+
+/// Routes a delegated token may call, with the scope each needs; first
+/// match wins and anything unmatched is closed to delegated tokens
+const DELEGATION_SCOPE_RULES: &[(&str, &str, &str)] = &[
+    ("GET", "/api/v1/users/me", "profile:read"),
+    ("GET", "/api/v1/users/me/meters", "readings:read"),
+    ("GET", "/api/v1/meters/readings", "readings:read"),
+    ("GET", "/api/v1/trading/orderbook", "market:read"),
+    ("GET", "/api/v1/trading/market/session", "market:read"),
+    ("GET", "/api/v1/trading/market/epochs/{id}/curves", "market:read"),
+    ("GET", "/api/v1/trading/p2p/market-prices", "market:read"),
+    ("GET", "/api/v1/markets/*", "market:read"),
+    ("GET", "/api/v1/analytics/market/*", "market:read"),
+    ("GET", "/api/v1/trading/orders", "orders:read"),
+    ("GET", "/api/v1/trading/orders/{id}/events", "orders:read"),
+    ("GET", "/api/v1/trading/trades", "orders:read"),
+    ("POST", "/api/v1/trading/orders", "orders:write"),
+    ("PUT", "/api/v1/trading/orders/{id}", "orders:write"),
+    ("DELETE", "/api/v1/trading/orders/{id}", "orders:write"),
+];
+
+/// Match a path against a rule with `{param}` segments and an optional
+/// trailing `*` covering the prefix and everything below it
+fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let mut pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    if pattern.last() == Some(&"*") {
+        pattern.pop();
+        if segments.len() < pattern.len() {
+            return false;
+        }
+        segments.truncate(pattern.len());
+    }
+    pattern.len() == segments.len()
+        && pattern
+            .iter()
+            .zip(&segments)
+            .all(|(p, s)| p == s || (p.starts_with('{') && p.ends_with('}')))
+}
+
+/// Scope a delegated token needs for a request, or None when delegated
+/// tokens may not call the endpoint at all
+pub fn delegated_scope(method: &str, path: &str) -> Option<&'static str> {
+    DELEGATION_SCOPE_RULES
+        .iter()
+        .find(|(m, pattern, _)| m.eq_ignore_ascii_case(method) && matches_pattern(pattern, path))
+        .map(|(_, _, scope)| *scope)
+}
+
+/// HTTPS, or plain HTTP to the loopback interface for native apps
+pub fn validate_redirect_uri(uri: &str) -> Result<()> {
+    let invalid = |msg: &str| ApiError::validation_field("redirect_uri", msg);
+    let parsed = reqwest::Url::parse(uri).map_err(|_| invalid("Invalid URL"))?;
+    let loopback = matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    if parsed.scheme() != "https" && !(parsed.scheme() == "http" && loopback) {
+        return Err(invalid("Redirect URIs must use HTTPS, or HTTP to localhost"));
+    }
+    if parsed.fragment().is_some() {
+        return Err(invalid("Redirect URIs must not contain a fragment"));
+    }
+    Ok(())
+}
+
+/// Space-separated requested scopes, each one the app is registered for
+pub fn parse_scopes(requested: &str, allowed: &[String]) -> Result<Vec<String>> {
+    let mut scopes: Vec<String> = Vec::new();
+    for scope in requested.split_whitespace() {
+        if !allowed.iter().any(|a| a == scope) {
+            return Err(ApiError::validation_field(
+                "scope",
+                format!("{} is not a scope this app may request", scope),
+            ));
+        }
+        if !scopes.iter().any(|s| s == scope) {
+            scopes.push(scope.to_string());
+        }
+    }
+    if scopes.is_empty() {
+        return Err(ApiError::validation_field("scope", "At least one scope"));
+    }
+    Ok(scopes)
+}
+
+/// Append the query parameters of the redirect back to the app
+fn redirect_with(redirect_uri: &str, params: &[(&str, &str)]) -> Result<String> {
+    let mut url = reqwest::Url::parse(redirect_uri)
+        .map_err(|_| ApiError::Internal(format!("Stored redirect URI {} is invalid", redirect_uri)))?;
+    url.query_pairs_mut().extend_pairs(params);
+    Ok(url.to_string())
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct DelegatedApp {
+    pub id: Uuid,
+    pub name: String,
+    pub client_id: String,
+    pub redirect_uris: Vec<String>,
+    /// Scopes users may grant the app
+    pub scopes: Vec<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub disabled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct RegisterAppRequest {
+    #[validate(length(min = 1, max = 200))]
+    pub name: String,
+    /// Exact URIs the app may be redirected to after consent; at most 5
+    pub redirect_uris: Vec<String>,
+    /// Scopes the app may request, e.g. `market:read`
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RegisteredApp {
+    pub app: DelegatedApp,
+    /// Redeems authorization codes; not shown again
+    pub client_secret: String,
+}
+
+/// The app's authorization request, as received on the consent screen
+#[derive(Debug, Clone, Deserialize, IntoParams, ToSchema)]
+pub struct AuthorizationRequest {
+    pub client_id: String,
+    pub redirect_uri: String,
+    /// Space-separated scopes
+    pub scope: String,
+    /// Opaque value returned to the app unchanged
+    pub state: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConsentScope {
+    pub scope: String,
+    pub description: String,
+}
+
+/// What the consent screen shows the user
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConsentDetails {
+    pub app_id: Uuid,
+    pub app_name: String,
+    pub redirect_uri: String,
+    pub scopes: Vec<ConsentScope>,
+    /// Scopes the user has already granted the app, if any
+    pub granted_scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ConsentDecision {
+    #[serde(flatten)]
+    pub request: AuthorizationRequest,
+    pub approve: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConsentRedirect {
+    /// Send the user's browser here: carries `code` and `state` on
+    /// approval, `error=access_denied` otherwise
+    pub redirect_to: String,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct TokenRequest {
+    /// Always `authorization_code`
+    pub grant_type: String,
+    pub code: String,
+    pub redirect_uri: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DelegatedTokenResponse {
+    /// Send as `Authorization: Bearer <token>`
+    pub access_token: String,
+    /// Always `Bearer`
+    pub token_type: &'static str,
+    /// Seconds until the token expires
+    pub expires_in: i64,
+    /// Space-separated granted scopes
+    pub scope: String,
+}
+
+/// An app the user has granted access
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct DelegationGrant {
+    pub id: Uuid,
+    pub app_id: Uuid,
+    pub app_name: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Caller authenticated with a delegated token; added to the request
+/// extensions next to the user's claims
+#[derive(Debug, Clone)]
+pub struct DelegatedAccess {
+    pub grant_id: Uuid,
+    pub app_id: Uuid,
+    pub client_id: String,
+    pub scopes: Vec<String>,
+}
+
+impl DelegatedAccess {
+    pub fn allows(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+/// Delegated token resolved to the granting user
+#[derive(Debug, Clone, FromRow)]
+pub struct DelegatedCaller {
+    pub grant_id: Uuid,
+    pub app_id: Uuid,
+    pub client_id: String,
+    pub scopes: Vec<String>,
+    pub user_id: Uuid,
+    pub username: String,
+    pub role: String,
+}
+
+#[derive(FromRow)]
+struct AppCredentials {
+    id: Uuid,
+    name: String,
+    client_secret_hash: String,
+    redirect_uris: Vec<String>,
+    scopes: Vec<String>,
+}
+
+const APP_COLUMNS: &str = "id, name, client_id, redirect_uris, scopes, created_by, created_at, disabled_at";
+
+#[derive(Clone)]
+pub struct DelegationService {
+    db: PgPool,
+    audit_logger: AuditLogger,
+    config: DelegationConfig,
+}
+
+impl DelegationService {
+    pub fn new(db: PgPool, audit_logger: AuditLogger, config: DelegationConfig) -> Self {
+        Self {
+            db,
+            audit_logger,
+            config,
+        }
+    }
+
+    pub async fn register_app(&self, admin_id: Uuid, request: RegisterAppRequest) -> Result<RegisteredApp> {
+        if request.redirect_uris.is_empty() || request.redirect_uris.len() > MAX_REDIRECT_URIS {
+            return Err(ApiError::validation_field(
+                "redirect_uris",
+                format!("Between 1 and {} URIs", MAX_REDIRECT_URIS),
+            ));
+        }
+        for uri in &request.redirect_uris {
+            validate_redirect_uri(uri)?;
+        }
+        if request.scopes.is_empty() {
+            return Err(ApiError::validation_field("scopes", "At least one scope"));
+        }
+        if let Some(scope) = request.scopes.iter().find(|s| scope_description(s).is_none()) {
+            let known: Vec<&str> = DELEGATION_SCOPES.iter().map(|(name, _)| *name).collect();
+            return Err(ApiError::validation_field(
+                "scopes",
+                format!("{} is not a delegation scope; use {}", scope, known.join(", ")),
+            ));
+        }
+
+        let client_secret = random_secret(48);
+        let app = sqlx::query_as::<_, DelegatedApp>(&format!(
+            r#"
+            INSERT INTO delegated_apps (name, client_id, client_secret_hash, redirect_uris, scopes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            APP_COLUMNS
+        ))
+        .bind(request.name.trim())
+        .bind(format!("app_{}", random_secret(24)))
+        .bind(digest(&client_secret))
+        .bind(&request.redirect_uris)
+        .bind(&request.scopes)
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        info!("🔑 Admin {} registered delegated app {} ({})", admin_id, app.name, app.client_id);
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "delegated_app_registered".to_string(),
+            target_user_id: None,
+            details: format!("app {} ({}): {}", app.id, app.name, app.scopes.join(" ")),
+        });
+
+        Ok(RegisteredApp { app, client_secret })
+    }
+
+    pub async fn list_apps(&self) -> Result<Vec<DelegatedApp>> {
+        Ok(sqlx::query_as::<_, DelegatedApp>(&format!(
+            "SELECT {} FROM delegated_apps ORDER BY created_at DESC",
+            APP_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Stop accepting an app's tokens and consent requests
+    pub async fn disable_app(&self, admin_id: Uuid, app_id: Uuid) -> Result<DelegatedApp> {
+        let app = sqlx::query_as::<_, DelegatedApp>(&format!(
+            "UPDATE delegated_apps SET disabled_at = COALESCE(disabled_at, NOW()) WHERE id = $1 RETURNING {}",
+            APP_COLUMNS
+        ))
+        .bind(app_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("App not found".to_string()))?;
+
+        info!("🔒 Admin {} disabled delegated app {}", admin_id, app.client_id);
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "delegated_app_disabled".to_string(),
+            target_user_id: None,
+            details: format!("app {} ({})", app.id, app.name),
+        });
+        Ok(app)
+    }
+
+    async fn active_app(&self, client_id: &str) -> Result<AppCredentials> {
+        sqlx::query_as::<_, AppCredentials>(
+            r#"
+            SELECT id, name, client_secret_hash, redirect_uris, scopes
+            FROM delegated_apps
+            WHERE client_id = $1 AND disabled_at IS NULL
+            "#,
+        )
+        .bind(client_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Unknown or disabled app".to_string()))
+    }
+
+    /// Check an authorization request; errors here are shown to the user,
+    /// never redirected to an unverified URI
+    async fn verify_request(&self, request: &AuthorizationRequest) -> Result<(AppCredentials, Vec<String>)> {
+        let app = self.active_app(&request.client_id).await?;
+        if !app.redirect_uris.contains(&request.redirect_uri) {
+            return Err(ApiError::validation_field(
+                "redirect_uri",
+                "Not a redirect URI registered for this app",
+            ));
+        }
+        if request.state.as_ref().is_some_and(|s| s.len() > MAX_STATE_LENGTH) {
+            return Err(ApiError::validation_field(
+                "state",
+                format!("At most {} characters", MAX_STATE_LENGTH),
+            ));
+        }
+        let scopes = parse_scopes(&request.scope, &app.scopes)?;
+        Ok((app, scopes))
+    }
+
+    pub async fn consent_details(&self, user_id: Uuid, request: &AuthorizationRequest) -> Result<ConsentDetails> {
+        let (app, scopes) = self.verify_request(request).await?;
+        let granted_scopes: Vec<String> = sqlx::query_scalar(
+            "SELECT scopes FROM delegation_grants WHERE user_id = $1 AND app_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(app.id)
+        .fetch_optional(&self.db)
+        .await?
+        .unwrap_or_default();
+
+        Ok(ConsentDetails {
+            app_id: app.id,
+            app_name: app.name,
+            redirect_uri: request.redirect_uri.clone(),
+            scopes: scopes
+                .into_iter()
+                .map(|scope| ConsentScope {
+                    description: scope_description(&scope).unwrap_or_default().to_string(),
+                    scope,
+                })
+                .collect(),
+            granted_scopes,
+        })
+    }
+
+    /// Record the user's answer. Approval replaces the scopes of an existing
+    /// grant and issues an authorization code.
+    pub async fn decide(&self, user_id: Uuid, decision: &ConsentDecision) -> Result<ConsentRedirect> {
+        let request = &decision.request;
+        let (app, scopes) = self.verify_request(request).await?;
+        let state = request.state.as_deref();
+
+        if !decision.approve {
+            let mut params = vec![("error", "access_denied")];
+            params.extend(state.map(|s| ("state", s)));
+            return Ok(ConsentRedirect {
+                redirect_to: redirect_with(&request.redirect_uri, &params)?,
+            });
+        }
+
+        let mut tx = self.db.begin().await?;
+        let grant_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO delegation_grants (user_id, app_id, scopes)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, app_id) WHERE revoked_at IS NULL
+            DO UPDATE SET scopes = EXCLUDED.scopes, updated_at = NOW()
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(app.id)
+        .bind(&scopes)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let code = random_secret(40);
+        sqlx::query(
+            "INSERT INTO delegation_codes (code_hash, grant_id, redirect_uri, expires_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(digest(&code))
+        .bind(grant_id)
+        .bind(&request.redirect_uri)
+        .bind(Utc::now() + Duration::seconds(self.config.code_ttl_secs))
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        info!("🤝 User {} granted app {} {}", user_id, app.name, scopes.join(" "));
+        self.audit_logger.log_async(AuditEvent::DataAccess {
+            user_id,
+            resource_type: "delegation_grant".to_string(),
+            resource_id: grant_id.to_string(),
+            action: format!("granted {}: {}", app.name, scopes.join(" ")),
+        });
+
+        let mut params = vec![("code", code.as_str())];
+        params.extend(state.map(|s| ("state", s)));
+        Ok(ConsentRedirect {
+            redirect_to: redirect_with(&request.redirect_uri, &params)?,
+        })
+    }
+
+    /// Redeem an authorization code for a delegated token
+    pub async fn exchange_code(&self, request: &TokenRequest) -> Result<DelegatedTokenResponse> {
+        if request.grant_type != "authorization_code" {
+            return Err(ApiError::BadRequest("unsupported_grant_type".to_string()));
+        }
+        let app = match self.active_app(&request.client_id).await {
+            Ok(app) if digest_matches(&app.client_secret_hash, &request.client_secret) => app,
+            Ok(_) | Err(ApiError::NotFound(_)) => {
+                return Err(ApiError::Unauthorized("invalid_client".to_string()));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut tx = self.db.begin().await?;
+        // A code is spent by the first redemption attempt, even a failed one
+        let redeemed: Option<(Uuid, String, Vec<String>)> = sqlx::query_as(
+            r#"
+            UPDATE delegation_codes c SET used_at = NOW()
+            FROM delegation_grants g
+            WHERE c.code_hash = $1 AND c.used_at IS NULL AND c.expires_at > NOW()
+              AND g.id = c.grant_id AND g.app_id = $2 AND g.revoked_at IS NULL
+            RETURNING g.id, c.redirect_uri, g.scopes
+            "#,
+        )
+        .bind(digest(&request.code))
+        .bind(app.id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (grant_id, scopes) = match redeemed {
+            Some((grant_id, redirect_uri, scopes)) if redirect_uri == request.redirect_uri => (grant_id, scopes),
+            Some(_) => {
+                tx.commit().await?;
+                return Err(ApiError::BadRequest("invalid_grant: redirect_uri does not match".to_string()));
+            }
+            None => return Err(ApiError::BadRequest("invalid_grant".to_string())),
+        };
+
+        let token = format!("{}{}", TOKEN_PREFIX, random_secret(48));
+        let expires_at = Utc::now() + Duration::days(self.config.token_ttl_days);
+        sqlx::query("INSERT INTO delegation_tokens (token_hash, grant_id, expires_at) VALUES ($1, $2, $3)")
+            .bind(digest(&token))
+            .bind(grant_id)
+            .bind(expires_at)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        info!("🔑 Issued delegated token for grant {} to app {}", grant_id, app.name);
+        Ok(DelegatedTokenResponse {
+            access_token: token,
+            token_type: "Bearer",
+            expires_in: (expires_at - Utc::now()).num_seconds(),
+            scope: scopes.join(" "),
+        })
+    }
+
+    /// Resolve a delegated token; None when it is unknown, expired, revoked
+    /// or belongs to a disabled app
+    pub async fn authenticate(&self, token: &str) -> Result<Option<DelegatedCaller>> {
+        let caller = sqlx::query_as::<_, DelegatedCaller>(
+            r#"
+            SELECT g.id AS grant_id, g.app_id, a.client_id, g.scopes, u.id AS user_id, u.username,
+                   u.role::text AS role
+            FROM delegation_tokens t
+            JOIN delegation_grants g ON g.id = t.grant_id
+            JOIN delegated_apps a ON a.id = g.app_id
+            JOIN users u ON u.id = g.user_id
+            WHERE t.token_hash = $1 AND t.expires_at > NOW()
+              AND g.revoked_at IS NULL AND a.disabled_at IS NULL
+            "#,
+        )
+        .bind(digest(token))
+        .fetch_optional(&self.db)
+        .await?;
+
+        if let Some(caller) = &caller {
+            // At most one write a minute per grant
+            let db = self.db.clone();
+            let grant_id = caller.grant_id;
+            tokio::spawn(async move {
+                if let Err(e) = sqlx::query(
+                    r#"
+                    UPDATE delegation_grants SET last_used_at = NOW()
+                    WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')
+                    "#,
+                )
+                .bind(grant_id)
+                .execute(&db)
+                .await
+                {
+                    warn!("Failed to record use of delegation grant {}: {}", grant_id, e);
+                }
+            });
+        }
+        Ok(caller)
+    }
+
+    /// The user's active grants, most recently changed first
+    pub async fn list_grants(&self, user_id: Uuid) -> Result<Vec<DelegationGrant>> {
+        Ok(sqlx::query_as::<_, DelegationGrant>(
+            r#"
+            SELECT g.id, g.app_id, a.name AS app_name, g.scopes, g.created_at, g.updated_at, g.last_used_at
+            FROM delegation_grants g
+            JOIN delegated_apps a ON a.id = g.app_id
+            WHERE g.user_id = $1 AND g.revoked_at IS NULL
+            ORDER BY g.updated_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Revoke a grant and delete its tokens and unused codes
+    pub async fn revoke_grant(&self, user_id: Uuid, grant_id: Uuid) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+        let revoked = sqlx::query(
+            "UPDATE delegation_grants SET revoked_at = NOW(), updated_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(grant_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if revoked == 0 {
+            return Err(ApiError::NotFound("Grant not found".to_string()));
+        }
+        sqlx::query("DELETE FROM delegation_tokens WHERE grant_id = $1")
+            .bind(grant_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM delegation_codes WHERE grant_id = $1")
+            .bind(grant_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        info!("🚫 User {} revoked delegation grant {}", user_id, grant_id);
+        self.audit_logger.log_async(AuditEvent::DataAccess {
+            user_id,
+            resource_type: "delegation_grant".to_string(),
+            resource_id: grant_id.to_string(),
+            action: "revoked".to_string(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delegated_scope() {
+        assert_eq!(delegated_scope("GET", "/api/v1/users/me"), Some("profile:read"));
+        assert_eq!(delegated_scope("GET", "/api/v1/markets/abc/orderbook"), Some("market:read"));
+        assert_eq!(delegated_scope("GET", "/api/v1/analytics/market"), Some("market:read"));
+        assert_eq!(delegated_scope("DELETE", "/api/v1/trading/orders/abc"), Some("orders:write"));
+        assert_eq!(delegated_scope("GET", "/api/v1/trading/orders/export"), None);
+        assert_eq!(delegated_scope("POST", "/api/v1/transfers"), None);
+        assert_eq!(delegated_scope("GET", "/api/v1/admin/users"), None);
+        // Every rule names a scope users can grant
+        for (_, _, scope) in DELEGATION_SCOPE_RULES {
+            assert!(DELEGATION_SCOPES.iter().any(|(name, _)| name == scope), "{}", scope);
+        }
+    }
+
+    #[test]
+    fn test_digest_matches() {
+        let stored = digest("client-secret");
+        assert!(digest_matches(&stored, "client-secret"));
+        assert!(!digest_matches(&stored, "client-secreT"));
+        assert!(!digest_matches(&stored, ""));
+    }
+
+    #[test]
+    fn test_redirect_uri_rules() {
+        assert!(validate_redirect_uri("https://app.example.com/callback").is_ok());
+        assert!(validate_redirect_uri("http://localhost:8080/callback").is_ok());
+        assert!(validate_redirect_uri("http://app.example.com/callback").is_err());
+        assert!(validate_redirect_uri("https://app.example.com/callback#token").is_err());
+        assert!(validate_redirect_uri("not a url").is_err());
+    }
+
+    #[test]
+    fn test_parse_scopes() {
+        let allowed = vec!["market:read".to_string(), "readings:read".to_string()];
+        assert_eq!(
+            parse_scopes("market:read  readings:read market:read", &allowed).unwrap(),
+            vec!["market:read", "readings:read"]
+        );
+        assert!(parse_scopes("orders:write", &allowed).is_err());
+        assert!(parse_scopes("  ", &allowed).is_err());
+    }
+
+    #[test]
+    fn test_redirect_keeps_existing_query() {
+        let url = redirect_with("https://app.example.com/cb?x=1", &[("code", "abc"), ("state", "a b")]).unwrap();
+        assert_eq!(url, "https://app.example.com/cb?x=1&code=abc&state=a+b");
+    }
+}
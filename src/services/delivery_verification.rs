@@ -0,0 +1,402 @@
+//! Delivery Verification
+//!
+//! Once an epoch's meter readings are in, each financially completed
+//! settlement is checked against what was physically metered in the epoch:
+//! the seller's generation and the buyer's consumption. A party's readings
+//! are shared pro rata across all of its settlements in the epoch. Seller
+//! shortfalls beyond the configured tolerance are flagged and, with the
+//! refund remedy, the buyer gets the undelivered share of the settlement
+//! value back through compensating `token_ledger_adjustments` entries.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool, Row};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::{Config, DeliveryRemedy, DeliveryVerificationConfig};
+use crate::error::ApiError;
+use crate::middleware::metrics::track_delivery_verification;
+
+/// Epochs checked per run
+const EPOCHS_PER_RUN: i64 = 20;
+
+const VERIFICATION_COLUMNS: &str = "settlement_id, epoch_id, buyer_id, seller_id, matched_kwh, delivered_kwh, \
+    shortfall_kwh, buyer_shortfall_kwh, seller_generated_kwh, buyer_consumed_kwh, status, remedy, \
+    refund_amount, verified_at";
+
+/// Outcome of checking one settlement against meter readings
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct DeliveryVerification {
+    pub settlement_id: Uuid,
+    pub epoch_id: Uuid,
+    pub buyer_id: Uuid,
+    pub seller_id: Uuid,
+    #[schema(value_type = String)]
+    pub matched_kwh: Decimal,
+    /// Share of the matched volume covered by the seller's generation
+    #[schema(value_type = String)]
+    pub delivered_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub shortfall_kwh: Decimal,
+    /// Share of the matched volume the buyer did not consume
+    #[schema(value_type = String)]
+    pub buyer_shortfall_kwh: Decimal,
+    /// Seller's generation over the whole epoch, across all its settlements
+    #[schema(value_type = String)]
+    pub seller_generated_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub buyer_consumed_kwh: Decimal,
+    /// verified, shortfall or under_consumed
+    pub status: String,
+    /// Remedy applied to a shortfall: flag or refund
+    pub remedy: Option<String>,
+    #[schema(value_type = Option<String>)]
+    pub refund_amount: Option<Decimal>,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// Filters for listing verifications
+#[derive(Debug, Clone, Default)]
+pub struct DeliveryVerificationFilter {
+    pub status: Option<String>,
+    pub epoch_id: Option<Uuid>,
+    pub limit: i64,
+}
+
+/// Completed settlement of an epoch being verified
+#[derive(Debug, Clone)]
+struct EpochSettlement {
+    id: Uuid,
+    buyer_id: Uuid,
+    seller_id: Uuid,
+    energy_amount: Decimal,
+    total_amount: Decimal,
+    verified: bool,
+}
+
+/// Delivery figures of one settlement before a remedy is chosen
+#[derive(Debug, Clone, PartialEq)]
+struct Assessment {
+    delivered: Decimal,
+    shortfall: Decimal,
+    buyer_shortfall: Decimal,
+    status: &'static str,
+}
+
+/// Share of a party's traded volume covered by its metered volume, capped at one
+fn coverage(traded: Decimal, metered: Decimal) -> Decimal {
+    if traded <= Decimal::ZERO {
+        return Decimal::ONE;
+    }
+    (metered.max(Decimal::ZERO) / traded).min(Decimal::ONE)
+}
+
+/// Check a settlement given its seller's and buyer's coverage in the epoch.
+/// Shortfalls within `tolerance` of the matched volume are accepted.
+fn assess(matched: Decimal, seller_coverage: Decimal, buyer_coverage: Decimal, tolerance: Decimal) -> Assessment {
+    let delivered = (matched * seller_coverage).round_dp(8);
+    let shortfall = matched - delivered;
+    let buyer_shortfall = matched - (matched * buyer_coverage).round_dp(8);
+    let allowed = matched * tolerance;
+
+    let status = if shortfall > allowed {
+        "shortfall"
+    } else if buyer_shortfall > allowed {
+        "under_consumed"
+    } else {
+        "verified"
+    };
+    Assessment { delivered, shortfall, buyer_shortfall, status }
+}
+
+/// Buyer refund for an undelivered share of a settlement's value
+fn refund(total_amount: Decimal, matched: Decimal, shortfall: Decimal) -> Decimal {
+    if matched <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    (total_amount * shortfall / matched).round_dp(8)
+}
+
+#[derive(Clone)]
+pub struct DeliveryVerificationService {
+    db: PgPool,
+    config: DeliveryVerificationConfig,
+}
+
+impl DeliveryVerificationService {
+    pub fn new(db: PgPool, config: Config) -> Self {
+        Self {
+            db,
+            config: config.delivery_verification,
+        }
+    }
+
+    /// Verify completed settlements of epochs that ended at least the grace
+    /// period ago and are not verified yet. Returns (verified, shortfalls).
+    pub async fn verify_due(&self) -> Result<(usize, usize), ApiError> {
+        let epochs = sqlx::query(
+            r#"
+            SELECT e.id, e.start_time, e.end_time
+            FROM market_epochs e
+            WHERE e.end_time < NOW() - make_interval(mins => $1::int)
+              AND EXISTS (
+                  SELECT 1 FROM settlements s
+                  WHERE s.epoch_id = e.id AND s.status = 'completed'
+                    AND NOT EXISTS (SELECT 1 FROM delivery_verifications v WHERE v.settlement_id = s.id)
+              )
+            ORDER BY e.end_time
+            LIMIT $2
+            "#,
+        )
+        .bind(self.config.grace_minutes as i32)
+        .bind(EPOCHS_PER_RUN)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut verified = 0;
+        let mut shortfalls = 0;
+        for epoch in epochs {
+            let (v, s) = self
+                .verify_epoch(epoch.get("id"), epoch.get("start_time"), epoch.get("end_time"))
+                .await?;
+            verified += v;
+            shortfalls += s;
+        }
+        Ok((verified, shortfalls))
+    }
+
+    async fn verify_epoch(
+        &self,
+        epoch_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<(usize, usize), ApiError> {
+        let settlements: Vec<EpochSettlement> = sqlx::query(
+            r#"
+            SELECT s.id, s.buyer_id, s.seller_id, s.energy_amount, s.total_amount,
+                   EXISTS (SELECT 1 FROM delivery_verifications v WHERE v.settlement_id = s.id) AS verified
+            FROM settlements s
+            WHERE s.epoch_id = $1 AND s.status = 'completed'
+            "#,
+        )
+        .bind(epoch_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .into_iter()
+        .map(|row| EpochSettlement {
+            id: row.get("id"),
+            buyer_id: row.get("buyer_id"),
+            seller_id: row.get("seller_id"),
+            energy_amount: row.get("energy_amount"),
+            total_amount: row.get("total_amount"),
+            verified: row.get("verified"),
+        })
+        .collect();
+
+        let mut sold: HashMap<Uuid, Decimal> = HashMap::new();
+        let mut bought: HashMap<Uuid, Decimal> = HashMap::new();
+        for s in &settlements {
+            *sold.entry(s.seller_id).or_default() += s.energy_amount;
+            *bought.entry(s.buyer_id).or_default() += s.energy_amount;
+        }
+
+        let users: Vec<Uuid> = sold.keys().chain(bought.keys()).copied().collect();
+        let metered: HashMap<Uuid, (Decimal, Decimal)> = sqlx::query(
+            r#"
+            SELECT user_id,
+                   COALESCE(SUM(energy_generated), 0) AS generated,
+                   COALESCE(SUM(energy_consumed), 0) AS consumed
+            FROM meter_readings
+            WHERE user_id = ANY($1) AND reading_timestamp >= $2 AND reading_timestamp < $3
+            GROUP BY user_id
+            "#,
+        )
+        .bind(&users)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .into_iter()
+        .map(|row| (row.get("user_id"), (row.get("generated"), row.get("consumed"))))
+        .collect();
+
+        let mut verified = 0;
+        let mut shortfalls = 0;
+        for s in settlements.iter().filter(|s| !s.verified) {
+            let generated = metered.get(&s.seller_id).map(|m| m.0).unwrap_or_default();
+            let consumed = metered.get(&s.buyer_id).map(|m| m.1).unwrap_or_default();
+            let assessment = assess(
+                s.energy_amount,
+                coverage(sold[&s.seller_id], generated),
+                coverage(bought[&s.buyer_id], consumed),
+                self.config.tolerance,
+            );
+
+            if self.record(epoch_id, s, &assessment, generated, consumed).await? {
+                verified += 1;
+                track_delivery_verification(assessment.status);
+                if assessment.status == "shortfall" {
+                    shortfalls += 1;
+                    warn!(
+                        "⚡ Delivery shortfall on settlement {} in epoch {}: {} of {} kWh not generated by seller {}",
+                        s.id, epoch_id, assessment.shortfall, s.energy_amount, s.seller_id
+                    );
+                }
+            }
+        }
+
+        if verified > 0 {
+            info!(
+                "⚡ Verified delivery of {} settlements in epoch {} ({} shortfalls)",
+                verified, epoch_id, shortfalls
+            );
+        }
+        Ok((verified, shortfalls))
+    }
+
+    /// Store a verification and apply the remedy in one transaction. Returns
+    /// false when another instance verified the settlement first.
+    async fn record(
+        &self,
+        epoch_id: Uuid,
+        settlement: &EpochSettlement,
+        assessment: &Assessment,
+        generated: Decimal,
+        consumed: Decimal,
+    ) -> Result<bool, ApiError> {
+        let remedy = (assessment.status == "shortfall").then_some(self.config.remedy);
+        let refund_amount = (remedy == Some(DeliveryRemedy::Refund))
+            .then(|| refund(settlement.total_amount, settlement.energy_amount, assessment.shortfall))
+            .filter(|amount| *amount > Decimal::ZERO);
+
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO delivery_verifications (
+                settlement_id, epoch_id, buyer_id, seller_id, matched_kwh, delivered_kwh,
+                shortfall_kwh, buyer_shortfall_kwh, seller_generated_kwh, buyer_consumed_kwh,
+                status, remedy, refund_amount
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (settlement_id) DO NOTHING
+            "#,
+        )
+        .bind(settlement.id)
+        .bind(epoch_id)
+        .bind(settlement.buyer_id)
+        .bind(settlement.seller_id)
+        .bind(settlement.energy_amount)
+        .bind(assessment.delivered)
+        .bind(assessment.shortfall)
+        .bind(assessment.buyer_shortfall)
+        .bind(generated)
+        .bind(consumed)
+        .bind(assessment.status)
+        .bind(remedy.map(|r| r.as_str()))
+        .bind(refund_amount)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?
+        .rows_affected()
+            > 0;
+        if !inserted {
+            return Ok(false);
+        }
+
+        if let Some(amount) = refund_amount {
+            let reason = format!(
+                "Delivery shortfall refund: {} of {} kWh undelivered",
+                assessment.shortfall, settlement.energy_amount
+            );
+            for (user_id, amount) in [(settlement.buyer_id, amount), (settlement.seller_id, -amount)] {
+                sqlx::query("UPDATE users SET balance = COALESCE(balance, 0) + $1 WHERE id = $2")
+                    .bind(amount)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(ApiError::Database)?;
+                sqlx::query(
+                    r#"
+                    INSERT INTO token_ledger_adjustments (settlement_id, user_id, asset, amount, reason)
+                    VALUES ($1, $2, 'currency', $3, $4)
+                    "#,
+                )
+                .bind(settlement.id)
+                .bind(user_id)
+                .bind(amount)
+                .bind(&reason)
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::Database)?;
+            }
+        }
+
+        tx.commit().await.map_err(ApiError::Database)?;
+        Ok(true)
+    }
+
+    /// Verifications, newest first
+    pub async fn list(&self, filter: DeliveryVerificationFilter) -> Result<Vec<DeliveryVerification>, ApiError> {
+        sqlx::query_as::<_, DeliveryVerification>(&format!(
+            r#"
+            SELECT {} FROM delivery_verifications
+            WHERE ($1::text IS NULL OR status = $1)
+              AND ($2::uuid IS NULL OR epoch_id = $2)
+            ORDER BY verified_at DESC
+            LIMIT $3
+            "#,
+            VERIFICATION_COLUMNS
+        ))
+        .bind(filter.status)
+        .bind(filter.epoch_id)
+        .bind(filter.limit)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kwh(value: i64) -> Decimal {
+        Decimal::from(value)
+    }
+
+    #[test]
+    fn test_shortfall_is_shared_pro_rata_and_refunded_by_value() {
+        // Seller sold 10 kWh over two settlements but generated 6
+        let seller = coverage(kwh(10), kwh(6));
+        let tolerance = Decimal::new(5, 2);
+
+        let a = assess(kwh(4), seller, Decimal::ONE, tolerance);
+        assert_eq!(a.status, "shortfall");
+        assert_eq!(a.delivered, Decimal::new(24, 1));
+        assert_eq!(a.shortfall, Decimal::new(16, 1));
+        // 40 paid for 4 kWh, 1.6 kWh missing
+        assert_eq!(refund(kwh(40), kwh(4), a.shortfall), kwh(16));
+
+        // Buyer-side under-consumption is flagged but not a seller shortfall
+        let b = assess(kwh(6), Decimal::ONE, coverage(kwh(6), kwh(3)), tolerance);
+        assert_eq!(b.status, "under_consumed");
+        assert_eq!(b.shortfall, Decimal::ZERO);
+        assert_eq!(b.buyer_shortfall, kwh(3));
+    }
+
+    #[test]
+    fn test_shortfall_within_tolerance_is_verified() {
+        let a = assess(kwh(100), coverage(kwh(100), kwh(97)), Decimal::ONE, Decimal::new(5, 2));
+        assert_eq!(a.status, "verified");
+        assert_eq!(a.shortfall, kwh(3));
+        assert_eq!(coverage(kwh(5), kwh(50)), Decimal::ONE);
+        assert_eq!(coverage(Decimal::ZERO, Decimal::ZERO), Decimal::ONE);
+    }
+}
@@ -0,0 +1,333 @@
+//! Deployment Profile
+//!
+//! The tenant an instance is deployed for: branding, token mint, program IDs
+//! and enabled modules. Utilities run the same binary, so the profile is
+//! resolved once at startup from configuration and the tenant's row in
+//! `deployment_profiles`. The first start against an empty table records the
+//! profile; a later start with another profile key, mint or program ID fails
+//! rather than serve one tenant's users against another tenant's database.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::config::{Config, Feature};
+use crate::error::{ApiError, Result};
+use crate::utils::MarketTimezone;
+
+/// Names and styling shown to users of the deployment
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Branding {
+    pub brand_name: String,
+    pub support_email: String,
+    pub logo_url: Option<String>,
+    pub primary_color: String,
+}
+
+/// On-chain programs the deployment's accounts live in
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ProgramIds {
+    pub registry: String,
+    pub oracle: String,
+    pub governance: String,
+    pub energy_token: String,
+    pub trading: String,
+}
+
+/// Resolved profile of this instance
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeploymentProfile {
+    pub key: String,
+    pub branding: Branding,
+    pub energy_token_mint: String,
+    pub programs: ProgramIds,
+    /// Enabled modules
+    #[schema(value_type = Vec<String>, example = json!(["trading", "prepaid"]))]
+    pub features: BTreeSet<Feature>,
+    /// Timezone market days and billing periods are counted in
+    pub timezone: MarketTimezone,
+}
+
+impl DeploymentProfile {
+    pub fn from_config(config: &Config) -> Self {
+        let deployment = &config.deployment;
+        let programs = &config.solana_programs;
+        Self {
+            key: deployment.profile.clone(),
+            branding: Branding {
+                brand_name: deployment.brand_name.clone(),
+                support_email: deployment.support_email.clone(),
+                logo_url: deployment.logo_url.clone(),
+                primary_color: deployment.primary_color.clone(),
+            },
+            energy_token_mint: config.energy_token_mint.clone(),
+            programs: ProgramIds {
+                registry: programs.registry_program_id.clone(),
+                oracle: programs.oracle_program_id.clone(),
+                governance: programs.governance_program_id.clone(),
+                energy_token: programs.energy_token_program_id.clone(),
+                trading: programs.trading_program_id.clone(),
+            },
+            features: deployment.features.iter().copied().collect(),
+            timezone: MarketTimezone::new(deployment.timezone.clone(), deployment.utc_offset_minutes),
+        }
+    }
+
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
+/// Stored profile of the tenant the database belongs to
+#[derive(Debug, Clone, FromRow)]
+struct ProfileRow {
+    profile_key: String,
+    brand_name: Option<String>,
+    support_email: Option<String>,
+    logo_url: Option<String>,
+    primary_color: Option<String>,
+    features: Option<Vec<String>>,
+    energy_token_mint: String,
+    registry_program_id: String,
+    oracle_program_id: String,
+    governance_program_id: String,
+    energy_token_program_id: String,
+    trading_program_id: String,
+}
+
+impl ProfileRow {
+    fn programs(&self) -> ProgramIds {
+        ProgramIds {
+            registry: self.registry_program_id.clone(),
+            oracle: self.oracle_program_id.clone(),
+            governance: self.governance_program_id.clone(),
+            energy_token: self.energy_token_program_id.clone(),
+            trading: self.trading_program_id.clone(),
+        }
+    }
+}
+
+/// Apply the stored row of the configured profile, or `None` when the
+/// database has no profile yet and the configured one should be recorded
+fn resolve(configured: DeploymentProfile, stored: &[ProfileRow]) -> Result<Option<DeploymentProfile>> {
+    let Some(row) = stored.iter().find(|row| row.profile_key == configured.key) else {
+        if stored.is_empty() {
+            return Ok(None);
+        }
+        let keys: Vec<&str> = stored.iter().map(|row| row.profile_key.as_str()).collect();
+        return Err(ApiError::Configuration(format!(
+            "Database belongs to deployment profile {}, not {}",
+            keys.join(", "),
+            configured.key
+        )));
+    };
+
+    if row.energy_token_mint != configured.energy_token_mint {
+        return Err(ApiError::Configuration(format!(
+            "Deployment profile {} uses token mint {}, but ENERGY_TOKEN_MINT is {}",
+            row.profile_key, row.energy_token_mint, configured.energy_token_mint
+        )));
+    }
+    if row.programs() != configured.programs {
+        return Err(ApiError::Configuration(format!(
+            "Deployment profile {} pins program IDs {:?}, but SOLANA_*_PROGRAM_ID configure {:?}",
+            row.profile_key,
+            row.programs(),
+            configured.programs
+        )));
+    }
+
+    let features = match &row.features {
+        Some(names) => names
+            .iter()
+            .map(|name| name.parse::<Feature>())
+            .collect::<std::result::Result<BTreeSet<_>, _>>()
+            .map_err(|e| ApiError::Configuration(format!("Deployment profile {}: {}", row.profile_key, e)))?,
+        None => configured.features,
+    };
+    let branding = configured.branding;
+    Ok(Some(DeploymentProfile {
+        key: configured.key,
+        branding: Branding {
+            brand_name: row.brand_name.clone().unwrap_or(branding.brand_name),
+            support_email: row.support_email.clone().unwrap_or(branding.support_email),
+            logo_url: row.logo_url.clone().or(branding.logo_url),
+            primary_color: row.primary_color.clone().unwrap_or(branding.primary_color),
+        },
+        energy_token_mint: configured.energy_token_mint,
+        programs: configured.programs,
+        features,
+        timezone: configured.timezone,
+    }))
+}
+
+/// Profile of this instance, fixed for the life of the process
+#[derive(Debug, Clone)]
+pub struct DeploymentProfileService {
+    profile: Arc<DeploymentProfile>,
+}
+
+impl DeploymentProfileService {
+    /// Resolve the configured profile against the database, recording it on
+    /// first start
+    pub async fn load(db: &PgPool, config: &Config) -> Result<Self> {
+        let configured = DeploymentProfile::from_config(config);
+        let stored = sqlx::query_as::<_, ProfileRow>(
+            r#"
+            SELECT profile_key, brand_name, support_email, logo_url, primary_color, features,
+                   energy_token_mint, registry_program_id, oracle_program_id,
+                   governance_program_id, energy_token_program_id, trading_program_id
+            FROM deployment_profiles
+            "#,
+        )
+        .fetch_all(db)
+        .await?;
+
+        let profile = match resolve(configured.clone(), &stored)? {
+            Some(profile) => profile,
+            None => {
+                Self::record(db, &configured).await?;
+                info!("🏷️ Database bound to deployment profile {}", configured.key);
+                configured
+            }
+        };
+        Ok(Self {
+            profile: Arc::new(profile),
+        })
+    }
+
+    pub fn profile(&self) -> &DeploymentProfile {
+        &self.profile
+    }
+
+    pub fn branding(&self) -> &Branding {
+        &self.profile.branding
+    }
+
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.profile.is_enabled(feature)
+    }
+
+    pub fn timezone(&self) -> &MarketTimezone {
+        &self.profile.timezone
+    }
+
+    /// Not found when the module is switched off for this deployment
+    pub fn require(&self, feature: Feature) -> Result<()> {
+        if self.is_enabled(feature) {
+            Ok(())
+        } else {
+            Err(ApiError::NotFound(format!(
+                "{} is not available on this deployment",
+                feature.as_str()
+            )))
+        }
+    }
+
+    /// Branding and features stay NULL so later `BRAND_*` and
+    /// `DEPLOYMENT_FEATURES` changes apply until an operator sets them
+    async fn record(db: &PgPool, profile: &DeploymentProfile) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO deployment_profiles (
+                profile_key, energy_token_mint, registry_program_id, oracle_program_id,
+                governance_program_id, energy_token_program_id, trading_program_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (profile_key) DO NOTHING
+            "#,
+        )
+        .bind(&profile.key)
+        .bind(&profile.energy_token_mint)
+        .bind(&profile.programs.registry)
+        .bind(&profile.programs.oracle)
+        .bind(&profile.programs.governance)
+        .bind(&profile.programs.energy_token)
+        .bind(&profile.programs.trading)
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configured() -> DeploymentProfile {
+        DeploymentProfile {
+            key: "pea".to_string(),
+            branding: Branding {
+                brand_name: "GridTokenX".to_string(),
+                support_email: "support@gridtokenx.com".to_string(),
+                logo_url: None,
+                primary_color: "#10b981".to_string(),
+            },
+            energy_token_mint: "Mint111".to_string(),
+            programs: ProgramIds {
+                registry: "Reg111".to_string(),
+                oracle: "Ora111".to_string(),
+                governance: "Gov111".to_string(),
+                energy_token: "Tok111".to_string(),
+                trading: "Trd111".to_string(),
+            },
+            features: Feature::ALL.into_iter().collect(),
+            timezone: MarketTimezone::default(),
+        }
+    }
+
+    fn row(key: &str) -> ProfileRow {
+        ProfileRow {
+            profile_key: key.to_string(),
+            brand_name: None,
+            support_email: None,
+            logo_url: None,
+            primary_color: None,
+            features: None,
+            energy_token_mint: "Mint111".to_string(),
+            registry_program_id: "Reg111".to_string(),
+            oracle_program_id: "Ora111".to_string(),
+            governance_program_id: "Gov111".to_string(),
+            energy_token_program_id: "Tok111".to_string(),
+            trading_program_id: "Trd111".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_empty_database_records_configured_profile() {
+        assert!(resolve(configured(), &[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_stored_branding_and_features_override_config() {
+        let mut stored = row("pea");
+        stored.brand_name = Some("PEA Energy Exchange".to_string());
+        stored.features = Some(vec!["trading".to_string(), "prepaid".to_string()]);
+
+        let profile = resolve(configured(), &[stored]).unwrap().unwrap();
+        assert_eq!(profile.branding.brand_name, "PEA Energy Exchange");
+        assert_eq!(profile.branding.primary_color, "#10b981");
+        assert!(profile.is_enabled(Feature::Prepaid));
+        assert!(!profile.is_enabled(Feature::Referrals));
+    }
+
+    #[test]
+    fn test_other_tenants_database_is_refused() {
+        assert!(resolve(configured(), &[row("mea")]).is_err());
+    }
+
+    #[test]
+    fn test_mint_or_program_mismatch_is_refused() {
+        let mut stored = row("pea");
+        stored.energy_token_mint = "OtherMint".to_string();
+        assert!(resolve(configured(), &[stored]).is_err());
+
+        let mut stored = row("pea");
+        stored.trading_program_id = "OtherTrading".to_string();
+        assert!(resolve(configured(), &[stored]).is_err());
+    }
+}
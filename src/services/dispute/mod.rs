@@ -0,0 +1,645 @@
+//! Settlement Dispute Service
+//!
+//! Lets a trade party flag a settlement, collect evidence and have an admin
+//! decide it. While a dispute is active, certificates backed by the disputed
+//! energy are frozen and new REC issuance for the settlement is deferred.
+//! Upheld disputes can pay compensation, recorded in `token_ledger_adjustments`.
+
+pub mod types;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool, Row};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::{AuditEvent, AuditLogger, SettlementService};
+
+pub use types::*;
+
+/// Settlements can be disputed for this long after they were created
+pub const DISPUTE_WINDOW_DAYS: i64 = 30;
+
+const MAX_EVIDENCE_LENGTH: usize = 4000;
+
+const DISPUTE_COLUMNS: &str = "id, settlement_id, raised_by, counterparty_id, reason, description, \
+    status, rec_deferred, assigned_admin_id, resolution_notes, refund_amount, resolved_by, \
+    resolved_at, created_at, updated_at";
+
+#[derive(Clone)]
+pub struct DisputeService {
+    db: PgPool,
+    settlement: SettlementService,
+    audit: AuditLogger,
+}
+
+impl DisputeService {
+    pub fn new(db: PgPool, settlement: SettlementService, audit: AuditLogger) -> Self {
+        Self {
+            db,
+            settlement,
+            audit,
+        }
+    }
+
+    /// Open a dispute on a settlement the user took part in
+    pub async fn open(
+        &self,
+        user_id: Uuid,
+        settlement_id: Uuid,
+        reason: DisputeReason,
+        description: &str,
+        evidence: &[(EvidenceKind, String)],
+    ) -> Result<SettlementDispute, ApiError> {
+        let description = description.trim();
+        if description.len() < 10 || description.len() > MAX_EVIDENCE_LENGTH {
+            return Err(ApiError::validation_field(
+                "description",
+                format!("Description must be 10-{} characters", MAX_EVIDENCE_LENGTH),
+            ));
+        }
+        for (kind, content) in evidence {
+            validate_evidence(content)?;
+            if *kind == EvidenceKind::Attachment {
+                // Files are uploaded to an existing dispute
+                return Err(ApiError::validation_field(
+                    "evidence",
+                    "Attachments can be added once the dispute is open",
+                ));
+            }
+        }
+
+        let settlement = sqlx::query(
+            "SELECT buyer_id, seller_id, created_at FROM settlements WHERE id = $1",
+        )
+        .bind(settlement_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Settlement not found".to_string()))?;
+
+        let buyer_id: Uuid = settlement.get("buyer_id");
+        let seller_id: Uuid = settlement.get("seller_id");
+        let counterparty_id = if user_id == buyer_id {
+            seller_id
+        } else if user_id == seller_id {
+            buyer_id
+        } else {
+            // Only the parties to the trade may know it exists
+            return Err(ApiError::NotFound("Settlement not found".to_string()));
+        };
+
+        let created_at: Option<DateTime<Utc>> = settlement.get("created_at");
+        if created_at.is_some_and(|at| Utc::now() - at > Duration::days(DISPUTE_WINDOW_DAYS)) {
+            return Err(ApiError::BadRequest(format!(
+                "Settlements can only be disputed within {} days",
+                DISPUTE_WINDOW_DAYS
+            )));
+        }
+
+        let mut tx = self.db.begin().await?;
+
+        let dispute = sqlx::query_as::<_, SettlementDispute>(&format!(
+            r#"
+            INSERT INTO settlement_disputes (settlement_id, raised_by, counterparty_id, reason, description)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            DISPUTE_COLUMNS
+        ))
+        .bind(settlement_id)
+        .bind(user_id)
+        .bind(counterparty_id)
+        .bind(reason.as_str())
+        .bind(description)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.constraint() == Some("uq_settlement_disputes_active") => {
+                ApiError::Conflict("This settlement already has an active dispute".to_string())
+            }
+            other => ApiError::Database(other),
+        })?;
+
+        for (kind, content) in evidence {
+            insert_evidence(&mut tx, dispute.id, user_id, *kind, content).await?;
+        }
+
+        let frozen = sqlx::query(
+            r#"
+            UPDATE erc_certificates
+            SET dispute_id = $1, dispute_hold = 'frozen', updated_at = NOW()
+            WHERE settlement_id = $2 AND dispute_hold IS NULL
+            "#,
+        )
+        .bind(dispute.id)
+        .bind(settlement_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+
+        info!(
+            "⚖️ Dispute {} opened on settlement {} ({} certificates frozen)",
+            dispute.id, settlement_id, frozen
+        );
+        self.audit.log_async(AuditEvent::DisputeOpened {
+            user_id,
+            dispute_id: dispute.id,
+            settlement_id,
+            reason: reason.as_str().to_string(),
+        });
+
+        Ok(dispute)
+    }
+
+    /// Disputes the user raised or is the counterparty of
+    pub async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<SettlementDispute>, ApiError> {
+        Ok(sqlx::query_as::<_, SettlementDispute>(&format!(
+            r#"
+            SELECT {} FROM settlement_disputes
+            WHERE raised_by = $1 OR counterparty_id = $1
+            ORDER BY created_at DESC
+            "#,
+            DISPUTE_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Admin queue, oldest first
+    pub async fn list(
+        &self,
+        status: Option<DisputeStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SettlementDispute>, ApiError> {
+        Ok(sqlx::query_as::<_, SettlementDispute>(&format!(
+            r#"
+            SELECT {} FROM settlement_disputes
+            WHERE ($1::TEXT IS NULL OR status = $1)
+            ORDER BY created_at ASC
+            LIMIT $2 OFFSET $3
+            "#,
+            DISPUTE_COLUMNS
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn get(&self, dispute_id: Uuid) -> Result<SettlementDispute, ApiError> {
+        sqlx::query_as::<_, SettlementDispute>(&format!(
+            "SELECT {} FROM settlement_disputes WHERE id = $1",
+            DISPUTE_COLUMNS
+        ))
+        .bind(dispute_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Dispute not found".to_string()))
+    }
+
+    pub async fn detail(&self, dispute_id: Uuid) -> Result<DisputeDetail, ApiError> {
+        let dispute = self.get(dispute_id).await?;
+
+        let evidence = sqlx::query_as::<_, DisputeEvidence>(
+            r#"
+            SELECT id, submitted_by, kind, content, created_at
+            FROM dispute_evidence
+            WHERE dispute_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(dispute_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let adjustments = sqlx::query_as::<_, LedgerAdjustment>(
+            r#"
+            SELECT id, user_id, asset, amount, reason, created_at
+            FROM token_ledger_adjustments
+            WHERE dispute_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(dispute_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let held_certificates: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM erc_certificates WHERE dispute_id = $1 AND dispute_hold IS NOT NULL",
+        )
+        .bind(dispute_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(DisputeDetail {
+            dispute,
+            evidence,
+            adjustments,
+            held_certificates,
+        })
+    }
+
+    /// Attach evidence; parties and admins only, while the dispute is active
+    pub async fn add_evidence(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        dispute_id: Uuid,
+        kind: EvidenceKind,
+        content: &str,
+    ) -> Result<DisputeEvidence, ApiError> {
+        validate_evidence(content)?;
+
+        let dispute = self.get(dispute_id).await?;
+        if !is_admin && !is_party(&dispute, user_id) {
+            return Err(ApiError::NotFound("Dispute not found".to_string()));
+        }
+        ensure_active(&dispute)?;
+        if kind == EvidenceKind::Attachment {
+            self.ensure_dispute_attachment(dispute_id, content).await?;
+        }
+
+        let mut tx = self.db.begin().await?;
+        let evidence = insert_evidence(&mut tx, dispute_id, user_id, kind, content).await?;
+        sqlx::query("UPDATE settlement_disputes SET updated_at = NOW() WHERE id = $1")
+            .bind(dispute_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(evidence)
+    }
+
+    /// Attachment evidence must name a file uploaded to this dispute
+    async fn ensure_dispute_attachment(&self, dispute_id: Uuid, content: &str) -> Result<(), ApiError> {
+        let attachment_id = Uuid::parse_str(content.trim())
+            .map_err(|_| ApiError::validation_field("evidence", "Attachment evidence must be an attachment ID"))?;
+        let uploaded = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM attachments
+                WHERE id = $1 AND owner_type = 'dispute' AND owner_id = $2
+            )
+            "#,
+        )
+        .bind(attachment_id)
+        .bind(dispute_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        if uploaded {
+            Ok(())
+        } else {
+            Err(ApiError::validation_field("evidence", "Attachment was not uploaded to this dispute"))
+        }
+    }
+
+    /// Withdraw a dispute (raiser only); held certificates are released
+    pub async fn withdraw(&self, user_id: Uuid, dispute_id: Uuid) -> Result<SettlementDispute, ApiError> {
+        let mut tx = self.db.begin().await?;
+        let dispute = lock_dispute(&mut tx, dispute_id).await?;
+        if dispute.raised_by != user_id {
+            return Err(ApiError::NotFound("Dispute not found".to_string()));
+        }
+        ensure_active(&dispute)?;
+
+        let dispute = close(&mut tx, dispute_id, DisputeStatus::Withdrawn, None, None, None).await?;
+        release_certificates(&mut tx, dispute_id).await?;
+        tx.commit().await?;
+
+        self.audit.log_async(AuditEvent::DisputeClosed {
+            user_id,
+            dispute_id,
+            status: DisputeStatus::Withdrawn.as_str().to_string(),
+            compensation: None,
+        });
+        self.issue_deferred_rec(&dispute).await;
+
+        Ok(dispute)
+    }
+
+    /// Move an open dispute into admin review
+    pub async fn start_review(&self, admin_id: Uuid, dispute_id: Uuid) -> Result<SettlementDispute, ApiError> {
+        let dispute = sqlx::query_as::<_, SettlementDispute>(&format!(
+            r#"
+            UPDATE settlement_disputes
+            SET status = 'under_review', assigned_admin_id = $2, updated_at = NOW()
+            WHERE id = $1 AND status = 'open'
+            RETURNING {}
+            "#,
+            DISPUTE_COLUMNS
+        ))
+        .bind(dispute_id)
+        .bind(admin_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        match dispute {
+            Some(dispute) => Ok(dispute),
+            None => {
+                let existing = self.get(dispute_id).await?;
+                Err(ApiError::Conflict(format!(
+                    "Dispute is {} and cannot be moved to review",
+                    existing.status
+                )))
+            }
+        }
+    }
+
+    /// Decide a dispute
+    ///
+    /// An upheld dispute may pay `compensation` from the counterparty to the
+    /// raiser (capped at the settlement total) and, when `revoke_certificates`
+    /// is set, permanently revokes the held certificates instead of releasing them.
+    pub async fn resolve(
+        &self,
+        admin_id: Uuid,
+        dispute_id: Uuid,
+        outcome: DisputeOutcome,
+        notes: &str,
+        compensation: Option<Decimal>,
+        revoke_certificates: bool,
+    ) -> Result<SettlementDispute, ApiError> {
+        let notes = notes.trim();
+        if notes.is_empty() {
+            return Err(ApiError::validation_field("notes", "Resolution notes are required"));
+        }
+        let compensation = compensation.filter(|c| !c.is_zero());
+        if outcome == DisputeOutcome::Rejected && compensation.is_some() {
+            return Err(ApiError::validation_field(
+                "compensation",
+                "Rejected disputes cannot pay compensation",
+            ));
+        }
+
+        let mut tx = self.db.begin().await?;
+        let dispute = lock_dispute(&mut tx, dispute_id).await?;
+        ensure_active(&dispute)?;
+
+        if let Some(amount) = compensation {
+            let total: Decimal =
+                sqlx::query_scalar("SELECT total_amount FROM settlements WHERE id = $1")
+                    .bind(dispute.settlement_id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+            validate_compensation(amount, total)?;
+
+            let reason = format!("Dispute {} compensation", dispute_id);
+            post_adjustment(&mut tx, &dispute, dispute.raised_by, amount, &reason, admin_id).await?;
+            post_adjustment(&mut tx, &dispute, dispute.counterparty_id, -amount, &reason, admin_id)
+                .await?;
+        }
+
+        let revoke = outcome == DisputeOutcome::Upheld && revoke_certificates;
+        let resolved = close(
+            &mut tx,
+            dispute_id,
+            outcome.status(),
+            Some(admin_id),
+            Some(notes),
+            compensation,
+        )
+        .await?;
+
+        if revoke {
+            sqlx::query(
+                r#"
+                UPDATE erc_certificates SET dispute_hold = 'revoked', updated_at = NOW()
+                WHERE dispute_id = $1 AND dispute_hold = 'frozen'
+                "#,
+            )
+            .bind(dispute_id)
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            release_certificates(&mut tx, dispute_id).await?;
+        }
+
+        tx.commit().await?;
+
+        info!(
+            "⚖️ Dispute {} resolved as {} by admin {}",
+            dispute_id,
+            outcome.status().as_str(),
+            admin_id
+        );
+        self.audit.log_async(AuditEvent::DisputeClosed {
+            user_id: admin_id,
+            dispute_id,
+            status: outcome.status().as_str().to_string(),
+            compensation: compensation.map(|c| c.to_string()),
+        });
+
+        if !revoke {
+            self.issue_deferred_rec(&resolved).await;
+        }
+
+        Ok(resolved)
+    }
+
+    async fn issue_deferred_rec(&self, dispute: &SettlementDispute) {
+        if !dispute.rec_deferred {
+            return;
+        }
+        if let Err(e) = self.settlement.issue_deferred_rec(dispute.settlement_id).await {
+            error!(
+                "⚠️ Failed to issue deferred REC for settlement {}: {}",
+                dispute.settlement_id, e
+            );
+        }
+    }
+}
+
+/// Record that REC issuance for a settlement was skipped because of an active dispute
+///
+/// Returns true when the settlement is disputed and issuance must wait.
+pub async fn defer_rec_if_disputed(db: &PgPool, settlement_id: Uuid) -> Result<bool, sqlx::Error> {
+    let deferred = sqlx::query(
+        r#"
+        UPDATE settlement_disputes SET rec_deferred = TRUE, updated_at = NOW()
+        WHERE settlement_id = $1 AND status IN ('open', 'under_review')
+        "#,
+    )
+    .bind(settlement_id)
+    .execute(db)
+    .await?
+    .rows_affected();
+
+    Ok(deferred > 0)
+}
+
+fn is_party(dispute: &SettlementDispute, user_id: Uuid) -> bool {
+    dispute.raised_by == user_id || dispute.counterparty_id == user_id
+}
+
+fn ensure_active(dispute: &SettlementDispute) -> Result<(), ApiError> {
+    match DisputeStatus::parse(&dispute.status) {
+        Some(status) if status.is_active() => Ok(()),
+        _ => Err(ApiError::Conflict(format!("Dispute is already {}", dispute.status))),
+    }
+}
+
+fn validate_evidence(content: &str) -> Result<(), ApiError> {
+    let length = content.trim().len();
+    if length == 0 || length > MAX_EVIDENCE_LENGTH {
+        return Err(ApiError::validation_field(
+            "evidence",
+            format!("Evidence must be 1-{} characters", MAX_EVIDENCE_LENGTH),
+        ));
+    }
+    Ok(())
+}
+
+/// Compensation must be positive and cannot exceed what the trade was worth
+pub fn validate_compensation(amount: Decimal, settlement_total: Decimal) -> Result<(), ApiError> {
+    if amount <= Decimal::ZERO {
+        return Err(ApiError::validation_field(
+            "compensation",
+            "Compensation must be positive",
+        ));
+    }
+    if amount > settlement_total {
+        return Err(ApiError::validation_field(
+            "compensation",
+            format!("Compensation cannot exceed the settlement total of {}", settlement_total),
+        ));
+    }
+    Ok(())
+}
+
+async fn lock_dispute(tx: &mut PgConnection, dispute_id: Uuid) -> Result<SettlementDispute, ApiError> {
+    sqlx::query_as::<_, SettlementDispute>(&format!(
+        "SELECT {} FROM settlement_disputes WHERE id = $1 FOR UPDATE",
+        DISPUTE_COLUMNS
+    ))
+    .bind(dispute_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Dispute not found".to_string()))
+}
+
+async fn close(
+    tx: &mut PgConnection,
+    dispute_id: Uuid,
+    status: DisputeStatus,
+    resolved_by: Option<Uuid>,
+    notes: Option<&str>,
+    compensation: Option<Decimal>,
+) -> Result<SettlementDispute, ApiError> {
+    Ok(sqlx::query_as::<_, SettlementDispute>(&format!(
+        r#"
+        UPDATE settlement_disputes
+        SET status = $2, resolved_by = $3, resolution_notes = $4, refund_amount = $5,
+            resolved_at = NOW(), updated_at = NOW()
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        DISPUTE_COLUMNS
+    ))
+    .bind(dispute_id)
+    .bind(status.as_str())
+    .bind(resolved_by)
+    .bind(notes)
+    .bind(compensation)
+    .fetch_one(&mut *tx)
+    .await?)
+}
+
+async fn release_certificates(tx: &mut PgConnection, dispute_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE erc_certificates SET dispute_hold = NULL, updated_at = NOW()
+        WHERE dispute_id = $1 AND dispute_hold = 'frozen'
+        "#,
+    )
+    .bind(dispute_id)
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
+
+async fn insert_evidence(
+    tx: &mut PgConnection,
+    dispute_id: Uuid,
+    user_id: Uuid,
+    kind: EvidenceKind,
+    content: &str,
+) -> Result<DisputeEvidence, sqlx::Error> {
+    sqlx::query_as::<_, DisputeEvidence>(
+        r#"
+        INSERT INTO dispute_evidence (dispute_id, submitted_by, kind, content)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, submitted_by, kind, content, created_at
+        "#,
+    )
+    .bind(dispute_id)
+    .bind(user_id)
+    .bind(kind.as_str())
+    .bind(content.trim())
+    .fetch_one(&mut *tx)
+    .await
+}
+
+/// Apply a signed currency adjustment to a user's balance and record it
+async fn post_adjustment(
+    tx: &mut PgConnection,
+    dispute: &SettlementDispute,
+    user_id: Uuid,
+    amount: Decimal,
+    reason: &str,
+    admin_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE users SET balance = COALESCE(balance, 0) + $1 WHERE id = $2")
+        .bind(amount)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO token_ledger_adjustments
+            (dispute_id, settlement_id, user_id, asset, amount, reason, created_by)
+        VALUES ($1, $2, $3, 'currency', $4, $5, $6)
+        "#,
+    )
+    .bind(dispute.id)
+    .bind(dispute.settlement_id)
+    .bind(user_id)
+    .bind(amount)
+    .bind(reason)
+    .bind(admin_id)
+    .execute(&mut *tx)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_activity() {
+        assert!(DisputeStatus::Open.is_active());
+        assert!(DisputeStatus::UnderReview.is_active());
+        assert!(!DisputeStatus::Upheld.is_active());
+        assert!(!DisputeStatus::Withdrawn.is_active());
+        assert_eq!(DisputeStatus::parse("under_review"), Some(DisputeStatus::UnderReview));
+        assert_eq!(DisputeOutcome::Rejected.status(), DisputeStatus::Rejected);
+    }
+
+    #[test]
+    fn test_compensation_bounds() {
+        let total = Decimal::new(100, 0);
+        assert!(validate_compensation(Decimal::new(50, 0), total).is_ok());
+        assert!(validate_compensation(total, total).is_ok());
+        assert!(validate_compensation(Decimal::new(101, 0), total).is_err());
+        assert!(validate_compensation(Decimal::new(-1, 0), total).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Why a settlement is disputed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeReason {
+    NotDelivered,
+    QuantityMismatch,
+    PriceMismatch,
+    MeterError,
+    Other,
+}
+
+impl DisputeReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotDelivered => "not_delivered",
+            Self::QuantityMismatch => "quantity_mismatch",
+            Self::PriceMismatch => "price_mismatch",
+            Self::MeterError => "meter_error",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Dispute lifecycle: open -> under_review -> upheld | rejected, or withdrawn by the raiser
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeStatus {
+    Open,
+    UnderReview,
+    Upheld,
+    Rejected,
+    Withdrawn,
+}
+
+impl DisputeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::UnderReview => "under_review",
+            Self::Upheld => "upheld",
+            Self::Rejected => "rejected",
+            Self::Withdrawn => "withdrawn",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(Self::Open),
+            "under_review" => Some(Self::UnderReview),
+            "upheld" => Some(Self::Upheld),
+            "rejected" => Some(Self::Rejected),
+            "withdrawn" => Some(Self::Withdrawn),
+            _ => None,
+        }
+    }
+
+    /// Active disputes hold certificates and accept evidence
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Open | Self::UnderReview)
+    }
+}
+
+/// Admin decision on a dispute
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeOutcome {
+    Upheld,
+    Rejected,
+}
+
+impl DisputeOutcome {
+    pub fn status(&self) -> DisputeStatus {
+        match self {
+            Self::Upheld => DisputeStatus::Upheld,
+            Self::Rejected => DisputeStatus::Rejected,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceKind {
+    /// Free-text statement
+    Note,
+    /// Link to a document or photo
+    Url,
+    /// Reference to a meter reading (ID or serial + timestamp)
+    MeterReading,
+    /// ID of a file uploaded to the dispute
+    Attachment,
+}
+
+impl EvidenceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Note => "note",
+            Self::Url => "url",
+            Self::MeterReading => "meter_reading",
+            Self::Attachment => "attachment",
+        }
+    }
+}
+
+/// Dispute record
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct SettlementDispute {
+    pub id: Uuid,
+    pub settlement_id: Uuid,
+    pub raised_by: Uuid,
+    pub counterparty_id: Uuid,
+    pub reason: String,
+    pub description: String,
+    pub status: String,
+    pub rec_deferred: bool,
+    pub assigned_admin_id: Option<Uuid>,
+    pub resolution_notes: Option<String>,
+    pub refund_amount: Option<Decimal>,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Evidence attached to a dispute
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct DisputeEvidence {
+    pub id: Uuid,
+    pub submitted_by: Uuid,
+    pub kind: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Compensating entry in the token ledger
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct LedgerAdjustment {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub asset: String,
+    pub amount: Decimal,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Dispute with evidence, adjustments and held certificates
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DisputeDetail {
+    pub dispute: SettlementDispute,
+    pub evidence: Vec<DisputeEvidence>,
+    pub adjustments: Vec<LedgerAdjustment>,
+    /// Certificates currently frozen or revoked because of this dispute
+    pub held_certificates: i64,
+}
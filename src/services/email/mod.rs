@@ -1,16 +1,38 @@
 pub mod templates;
 
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
 use anyhow::{Context, Result};
 use lettre::{
-    message::{header::ContentType, Mailbox, MultiPart, SinglePart},
+    message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart},
     transport::smtp::authentication::Credentials,
     Message, SmtpTransport, Transport,
 };
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::config::EmailConfig;
+use crate::i18n::{self, Locale};
+use crate::services::degradation::{DegradationManager, Subsystem};
 use templates::EmailTemplates;
 
+/// Brand the templates and message catalogs are written for
+const DEFAULT_BRAND: &str = "GridTokenX";
+
+/// A scheduled report and the rendered file sent with it
+pub struct ReportEmail<'a> {
+    /// Localized report name
+    pub report: &'a str,
+    /// Period the report covers, as shown to the reader
+    pub period: &'a str,
+    /// Headline figures listed in the message body
+    pub highlights: &'a [String],
+    pub filename: &'a str,
+    pub content_type: &'a str,
+    pub body: &'a [u8],
+}
+
 /// Email service for sending transactional emails
 #[derive(Clone)]
 pub struct EmailService {
@@ -19,6 +41,12 @@ pub struct EmailService {
     from_name: String,
     base_url: String,
     enabled: bool,
+    /// Deployment brand replacing the default one in sent emails
+    brand: Option<String>,
+    /// Runtime failure tracking; messages are queued while email is down
+    degradation: Option<DegradationManager>,
+    queue: Arc<Mutex<VecDeque<(String, Message)>>>,
+    queue_capacity: usize,
 }
 
 impl EmailService {
@@ -57,15 +85,44 @@ impl EmailService {
             from_name: config.from_name.clone(),
             base_url: config.verification_base_url.clone(),
             enabled: config.verification_enabled,
+            brand: None,
+            degradation: None,
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+            queue_capacity: 0,
         })
     }
 
+    /// Report send failures to `degradation` and hold up to `queue_capacity`
+    /// messages while email is down
+    pub fn with_degradation(mut self, degradation: DegradationManager, queue_capacity: usize) -> Self {
+        self.degradation = Some(degradation);
+        self.queue_capacity = queue_capacity;
+        self
+    }
+
+    /// Name the deployment's brand instead of GridTokenX in sent emails
+    pub fn with_brand(mut self, brand_name: &str) -> Self {
+        self.brand = (brand_name != DEFAULT_BRAND).then(|| brand_name.to_string());
+        self
+    }
+
+    fn rebrand<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match &self.brand {
+            Some(brand) => Cow::Owned(
+                text.replace("GridTokenX Platform", brand)
+                    .replace(DEFAULT_BRAND, brand),
+            ),
+            None => Cow::Borrowed(text),
+        }
+    }
+
     /// Send email verification message to user
     pub async fn send_verification_email(
         &self,
         to_email: &str,
         token: &str,
         username: &str,
+        locale: Locale,
     ) -> Result<()> {
         if !self.enabled {
             info!(
@@ -79,13 +136,13 @@ impl EmailService {
         let verification_url = format!("{}/verify-email?token={}", self.base_url, token);
 
         // Generate HTML and text content
-        let html_body = EmailTemplates::verification_email(username, &verification_url);
-        let text_body = EmailTemplates::verification_email_text(username, &verification_url);
+        let html_body = EmailTemplates::verification_email(username, &verification_url, locale);
+        let text_body = EmailTemplates::verification_email_text(username, &verification_url, locale);
 
         // Build and send email
         self.send_email(
             to_email,
-            "Verify Your Email - GridTokenX Platform",
+            i18n::t(locale, "email.verification.subject"),
             &html_body,
             &text_body,
         )
@@ -97,7 +154,7 @@ impl EmailService {
     }
 
     /// Send welcome email after successful verification
-    pub async fn send_welcome_email(&self, to_email: &str, username: &str) -> Result<()> {
+    pub async fn send_welcome_email(&self, to_email: &str, username: &str, locale: Locale) -> Result<()> {
         if !self.enabled {
             info!(
                 "Email service disabled, skipping welcome email to {}",
@@ -110,13 +167,13 @@ impl EmailService {
         let dashboard_url = format!("{}/dashboard", self.base_url);
 
         // Generate HTML and text content
-        let html_body = EmailTemplates::welcome_email(username, &dashboard_url);
-        let text_body = EmailTemplates::welcome_email_text(username, &dashboard_url);
+        let html_body = EmailTemplates::welcome_email(username, &dashboard_url, locale);
+        let text_body = EmailTemplates::welcome_email_text(username, &dashboard_url, locale);
 
         // Build and send email
         self.send_email(
             to_email,
-            "Welcome to GridTokenX! 🎉",
+            i18n::t(locale, "email.welcome.subject"),
             &html_body,
             &text_body,
         )
@@ -133,6 +190,7 @@ impl EmailService {
         to_email: &str,
         token: &str,
         username: &str,
+        locale: Locale,
     ) -> Result<()> {
         if !self.enabled {
             info!(
@@ -146,13 +204,13 @@ impl EmailService {
         let reset_url = format!("{}/reset-password?token={}", self.base_url, token);
 
         // Generate HTML and text content
-        let html_body = EmailTemplates::password_reset_email(username, &reset_url);
-        let text_body = EmailTemplates::password_reset_email_text(username, &reset_url);
+        let html_body = EmailTemplates::password_reset_email(username, &reset_url, locale);
+        let text_body = EmailTemplates::password_reset_email_text(username, &reset_url, locale);
 
         // Build and send email
         self.send_email(
             to_email,
-            "Reset Your Password - GridTokenX Platform",
+            i18n::t(locale, "email.password_reset.subject"),
             &html_body,
             &text_body,
         )
@@ -163,6 +221,68 @@ impl EmailService {
         Ok(())
     }
 
+    /// Send a one-time code completing a risky login
+    pub async fn send_login_code(
+        &self,
+        to_email: &str,
+        code: &str,
+        username: &str,
+        minutes: i64,
+        locale: Locale,
+    ) -> Result<()> {
+        if !self.enabled {
+            info!("Email service disabled, skipping login code email to {}", username);
+            return Ok(());
+        }
+
+        let html_body = EmailTemplates::login_code_email(username, code, minutes, locale);
+        let text_body = EmailTemplates::login_code_email_text(username, code, minutes, locale);
+
+        self.send_email(
+            to_email,
+            i18n::t(locale, "email.login_code.subject"),
+            &html_body,
+            &text_body,
+        )
+        .await
+        .context("Failed to send login code email")?;
+
+        info!("Login code email sent to {}", username);
+        Ok(())
+    }
+
+    /// Send a scheduled report with the rendered file attached
+    pub async fn send_report_email(
+        &self,
+        to_email: &str,
+        username: &str,
+        report: &ReportEmail<'_>,
+        locale: Locale,
+    ) -> Result<()> {
+        if !self.enabled {
+            return Err(anyhow::anyhow!("Email service is disabled"));
+        }
+
+        let html_body = EmailTemplates::report_email(username, report.report, report.period, report.highlights, locale);
+        let text_body =
+            EmailTemplates::report_email_text(username, report.report, report.period, report.highlights, locale);
+        let subject = i18n::tf(
+            locale,
+            "email.report.subject",
+            &[("report", &report.report), ("period", &report.period)],
+        );
+        let content_type = ContentType::parse(report.content_type)
+            .map_err(|e| anyhow::anyhow!("Invalid attachment content type: {}", e))?;
+        let attachment = Attachment::new(report.filename.to_string()).body(report.body.to_vec(), content_type);
+
+        self.send_message(to_email, &subject, &html_body, &text_body, Some(attachment))
+            .await
+            .context("Failed to send report email")?;
+
+        info!("Report email ({}) sent to {}", report.filename, username);
+        Ok(())
+    }
+
     /// Internal method to send email with HTML and text parts
     async fn send_email(
         &self,
@@ -171,8 +291,24 @@ impl EmailService {
         html_body: &str,
         text_body: &str,
     ) -> Result<()> {
+        self.send_message(to_email, subject, html_body, text_body, None).await
+    }
+
+    /// Send HTML and text alternatives, with an optional attachment
+    async fn send_message(
+        &self,
+        to_email: &str,
+        subject: &str,
+        html_body: &str,
+        text_body: &str,
+        attachment: Option<SinglePart>,
+    ) -> Result<()> {
+        let subject = self.rebrand(subject);
+        let html_body = self.rebrand(html_body);
+        let text_body = self.rebrand(text_body);
+
         // Parse mailboxes
-        let from: Mailbox = format!("{} <{}>", self.from_name, self.from_email)
+        let from: Mailbox = format!("{} <{}>", self.rebrand(&self.from_name), self.from_email)
             .parse()
             .context("Failed to parse from address")?;
 
@@ -181,33 +317,114 @@ impl EmailService {
             .context("Failed to parse recipient address")?;
 
         // Build multipart email with HTML and plain text alternatives
+        let alternatives = MultiPart::alternative()
+            .singlepart(
+                SinglePart::builder()
+                    .header(ContentType::TEXT_PLAIN)
+                    .body(text_body.to_string()),
+            )
+            .singlepart(
+                SinglePart::builder()
+                    .header(ContentType::TEXT_HTML)
+                    .body(html_body.to_string()),
+            );
+        let body = match attachment {
+            Some(attachment) => MultiPart::mixed().multipart(alternatives).singlepart(attachment),
+            None => alternatives,
+        };
+
         let email = Message::builder()
             .from(from)
             .to(to)
-            .subject(subject)
-            .multipart(
-                MultiPart::alternative()
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::TEXT_PLAIN)
-                            .body(text_body.to_string()),
-                    )
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::TEXT_HTML)
-                            .body(html_body.to_string()),
-                    ),
-            )
+            .subject(subject.as_ref())
+            .multipart(body)
             .context("Failed to build email message")?;
 
+        let Some(degradation) = &self.degradation else {
+            return self.mailer.send(&email).map(|_| ()).map_err(|e| {
+                error!("Failed to send email to {}: {}", to_email, e);
+                anyhow::anyhow!("Failed to send email: {}", e)
+            });
+        };
+
+        // Held until the SMTP server is reachable again
+        if degradation.is_down(Subsystem::Email) {
+            self.enqueue(to_email, email);
+            return Ok(());
+        }
+
         // Send email via SMTP
         match self.mailer.send(&email) {
-            Ok(_) => Ok(()),
+            Ok(_) => {
+                degradation.success(Subsystem::Email);
+                Ok(())
+            }
             Err(e) => {
-                error!("Failed to send email to {}: {}", to_email, e);
-                Err(anyhow::anyhow!("Failed to send email: {}", e))
+                degradation.failure(Subsystem::Email, &e);
+                if degradation.is_down(Subsystem::Email) {
+                    self.enqueue(to_email, email);
+                    return Ok(());
+                }
+                Err(anyhow::anyhow!("Failed to send email to {}: {}", to_email, e))
+            }
+        }
+    }
+
+    fn enqueue(&self, to_email: &str, email: Message) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        if queue.len() >= self.queue_capacity {
+            if let Some((dropped, _)) = queue.pop_front() {
+                warn!("Email queue full, dropping queued email to {}", dropped);
+            }
+        }
+        if self.queue_capacity > 0 {
+            queue.push_back((to_email.to_string(), email));
+            info!("Email to {} queued while email is down ({} queued)", to_email, queue.len());
+        }
+    }
+
+    /// Emails waiting for email to recover
+    pub fn queued(&self) -> usize {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Check the SMTP server answers, recording the outcome
+    pub async fn probe(&self) -> Result<()> {
+        let result = match self.mailer.test_connection() {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(anyhow::anyhow!("SMTP server did not accept the connection")),
+            Err(e) => Err(anyhow::anyhow!("SMTP connection failed: {}", e)),
+        };
+        if let Some(degradation) = &self.degradation {
+            match &result {
+                Ok(()) => degradation.success(Subsystem::Email),
+                Err(e) => degradation.failure(Subsystem::Email, e),
             }
         }
+        result
+    }
+
+    /// Send queued emails, oldest first, stopping at the first failure.
+    /// Returns how many were sent.
+    pub async fn flush_queue(&self) -> usize {
+        let mut sent = 0;
+        loop {
+            let Some((to_email, email)) = self.queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front() else {
+                break;
+            };
+            if let Err(e) = self.mailer.send(&email) {
+                if let Some(degradation) = &self.degradation {
+                    degradation.failure(Subsystem::Email, &e);
+                }
+                self.queue.lock().unwrap_or_else(|e| e.into_inner()).push_front((to_email, email));
+                break;
+            }
+            sent += 1;
+        }
+        if sent > 0 {
+            info!("📧 Sent {} queued email(s)", sent);
+        }
+        sent
     }
 
     /// Check if email service is enabled
@@ -305,4 +522,28 @@ mod tests {
         let service = EmailService::new(&config).unwrap();
         assert!(!service.is_enabled());
     }
+
+    #[test]
+    fn test_emails_carry_deployment_brand() {
+        let config = EmailConfig {
+            smtp_host: "smtp.example.com".to_string(),
+            smtp_port: 587,
+            smtp_username: "test@example.com".to_string(),
+            smtp_password: "password".to_string(),
+            from_name: "GridTokenX Platform".to_string(),
+            from_address: "test@example.com".to_string(),
+            verification_expiry_hours: 24,
+            verification_base_url: "http://localhost:3000".to_string(),
+            verification_required: true,
+            verification_enabled: false,
+            auto_login_after_verification: false,
+        };
+
+        let service = EmailService::new(&config).unwrap();
+        assert_eq!(service.rebrand("© 2025 GridTokenX Platform."), "© 2025 GridTokenX Platform.");
+
+        let service = service.with_brand("PEA Energy Exchange");
+        assert_eq!(service.rebrand("© 2025 GridTokenX Platform."), "© 2025 PEA Energy Exchange.");
+        assert_eq!(service.rebrand("Welcome to GridTokenX! 🎉"), "Welcome to PEA Energy Exchange! 🎉");
+    }
 }
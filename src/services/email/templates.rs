@@ -1,18 +1,22 @@
 /// Email templates for the GridTokenX Platform
-/// Provides HTML email templates for verification, welcome, and other notifications
+/// Provides HTML email templates for verification, welcome, and other notifications,
+/// in the recipient's locale
+
+use crate::i18n::{self, Locale};
 
 pub struct EmailTemplates;
 
 impl EmailTemplates {
     /// HTML email template for email verification
-    pub fn verification_email(username: &str, verification_url: &str) -> String {
+    pub fn verification_email(username: &str, verification_url: &str, locale: Locale) -> String {
+        let t = |key| i18n::t(locale, key);
         format!(
             r#"<!DOCTYPE html>
-<html lang="en">
+<html lang="{}">
 <head>
   <meta charset="UTF-8">
   <meta name="viewport" content="width=device-width, initial-scale=1.0">
-  <title>Verify Your Email - GridTokenX</title>
+  <title>{}</title>
 </head>
 <body style="margin: 0; padding: 0; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; background-color: #f5f5f5;">
   <table role="presentation" style="width: 100%; border-collapse: collapse; background-color: #f5f5f5;">
@@ -23,16 +27,14 @@ impl EmailTemplates {
           <!-- Body -->
           <tr>
             <td style="padding: 40px 30px; background-color: #ffffff;">
-              <h2 style="color: #1f2937; margin: 0 0 20px 0; font-size: 24px; font-weight: 600;">Welcome, {}!</h2>
+              <h2 style="color: #1f2937; margin: 0 0 20px 0; font-size: 24px; font-weight: 600;">{}</h2>
               
               <p style="color: #4b5563; line-height: 1.6; margin: 0 0 20px 0; font-size: 16px;">
-                Thank you for registering with <strong>GridTokenX</strong> Platform. We're excited to have you join our 
-                peer-to-peer energy trading network!
+                {}
               </p>
               
               <p style="color: #4b5563; line-height: 1.6; margin: 0 0 30px 0; font-size: 16px;">
-                To complete your registration and start trading energy tokens, please verify your email address 
-                by clicking the button below:
+                {}
               </p>
               
               <!-- Button -->
@@ -43,7 +45,7 @@ impl EmailTemplates {
                       style="display: inline-block; background: linear-gradient(135deg, #10b981 0%, #059669 100%); 
                           color: #ffffff; padding: 16px 40px; text-decoration: none; 
                           font-weight: 600; font-size: 16px; box-shadow: 0 4px 6px rgba(16, 185, 129, 0.4);">
-                      Verify Email Address
+                      {}
                     </a>
                   </td>
                 </tr>
@@ -51,17 +53,17 @@ impl EmailTemplates {
               
               <!-- Fallback Link -->
               <p style="color: #6b7280; font-size: 14px; line-height: 1.6; margin: 0 0 10px 0;">
-                If the button doesn't work, copy and paste this link into your browser:
+                {}
               </p>
               <p style="background-color: #f3f4f6; padding: 12px; 
                     font-size: 13px; color: #10b981; margin: 0 0 30px 0;">
                 <a href="{}" style="color: #10b981; text-decoration: none;">{}</a>
               </p>
               <p style="color: #6b7280; margin: 0; font-size: 14px; line-height: 1.5; text-underline-offset: inherit;">
-                This verification link will expire in 24 hours for security purposes.
+                {}
               </p>
               <p style="color: #6b7280; font-size: 14px; line-height: 1.6; margin: 0;">
-                If you didn't create an account with GridTokenX, you can safely ignore this email.
+                {}
               </p>
             </td>
           </tr>
@@ -70,10 +72,10 @@ impl EmailTemplates {
           <tr>
             <td style="background-color: #f9fafb; padding: 10px; text-align: center; border-top: 1px solid #e5e7eb;">
               <p style="color: #9ca3af; margin: 0 0 10px 0; font-size: 13px;">
-                © 2025 GridTokenX Platform. All rights reserved.
+                © 2025 GridTokenX Platform. {}
               </p>
               <p style="color: #9ca3af; margin: 0; font-size: 12px;">
-                This is an automated email. Please do not reply to this message.
+                {}
               </p>
             </td>
           </tr>
@@ -83,19 +85,33 @@ impl EmailTemplates {
   </table>
 </body>
 </html>"#,
-            username, verification_url, verification_url, verification_url
+            locale,
+            t("email.verification.subject"),
+            i18n::tf(locale, "email.verification.greeting", &[("username", &username)]),
+            t("email.verification.intro"),
+            t("email.verification.action"),
+            verification_url,
+            t("email.verification.button"),
+            t("email.link_fallback"),
+            verification_url,
+            verification_url,
+            t("email.verification.expiry"),
+            t("email.verification.ignore"),
+            t("email.footer.rights"),
+            t("email.footer.no_reply")
         )
     }
 
     /// HTML email template for welcome message after verification
-    pub fn welcome_email(username: &str, dashboard_url: &str) -> String {
+    pub fn welcome_email(username: &str, dashboard_url: &str, locale: Locale) -> String {
+        let t = |key| i18n::t(locale, key);
         format!(
             r#"<!DOCTYPE html>
-            <html lang="en">
+            <html lang="{}">
             <head>
                 <meta charset="UTF-8">
                 <meta name="viewport" content="width=device-width, initial-scale=1.0">
-                <title>Welcome to GridTokenX!</title>
+                <title>{}</title>
             </head>
             <body style="margin: 0; padding: 0; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; background-color: #f5f5f5;">
                 <table role="presentation" style="width: 100%; border-collapse: collapse; background-color: #f5f5f5;">
@@ -105,38 +121,37 @@ impl EmailTemplates {
                                 <!-- Header -->
                                 <tr>
                                     <td style="background: linear-gradient(135deg, #667eea 0%, #764ba2 100%); padding: 40px 30px; text-align: center; border-radius: 10px 10px 0 0;">
-                                        <h1 style="color: #ffffff; margin: 0; font-size: 32px; font-weight: 600;">Welcome to GridTokenX! 🎉</h1>
-                                        <p style="color: #e0e7ff; margin: 10px 0 0 0; font-size: 14px;">Your email has been verified</p>
+                                        <h1 style="color: #ffffff; margin: 0; font-size: 32px; font-weight: 600;">{}</h1>
+                                        <p style="color: #e0e7ff; margin: 10px 0 0 0; font-size: 14px;">{}</p>
                                     </td>
                                 </tr>
                                 
                                 <!-- Body -->
                                 <tr>
                                     <td style="padding: 40px 30px; background-color: #ffffff;">
-                                        <h2 style="color: #1f2937; margin: 0 0 20px 0; font-size: 24px; font-weight: 600;">Hello, {}!</h2>
+                                        <h2 style="color: #1f2937; margin: 0 0 20px 0; font-size: 24px; font-weight: 600;">{}</h2>
                                         
                                         <p style="color: #4b5563; line-height: 1.6; margin: 0 0 20px 0; font-size: 16px;">
-                                            Congratulations! Your email has been successfully verified. You now have full access to all features 
-                                            of the GridTokenX Platform.
+                                            {}
                                         </p>
                                         
-                                        <h3 style="color: #1f2937; margin: 30px 0 15px 0; font-size: 20px; font-weight: 600;">What's Next?</h3>
+                                        <h3 style="color: #1f2937; margin: 30px 0 15px 0; font-size: 20px; font-weight: 600;">{}</h3>
                                         
                                         <ul style="color: #4b5563; line-height: 1.8; margin: 0 0 30px 0; padding-left: 20px; font-size: 15px;">
                                             <li style="margin-bottom: 10px;">
-                                                <strong>Connect Your Wallet:</strong> Link your Solana wallet for blockchain transactions
+                                                {}
                                             </li>
                                             <li style="margin-bottom: 10px;">
-                                                <strong>View Dashboard:</strong> Monitor your energy consumption and production in real-time
+                                                {}
                                             </li>
                                             <li style="margin-bottom: 10px;">
-                                                <strong>Start Trading:</strong> Buy and sell energy tokens with other users on the platform
+                                                {}
                                             </li>
                                             <li style="margin-bottom: 10px;">
-                                                <strong>Track Prices:</strong> View live energy market prices and trends
+                                                {}
                                             </li>
                                             <li style="margin-bottom: 10px;">
-                                                <strong>Manage Meters:</strong> Connect and manage your smart meters
+                                                {}
                                             </li>
                                         </ul>
                                         
@@ -148,7 +163,7 @@ impl EmailTemplates {
                                                       style="display: inline-block; background: linear-gradient(135deg, #667eea 0%, #764ba2 100%); 
                                                               color: #ffffff; padding: 16px 40px; text-decoration: none; border-radius: 8px; 
                                                               font-weight: 600; font-size: 16px; box-shadow: 0 4px 6px rgba(102, 126, 234, 0.4);">
-                                                        Go to Dashboard
+                                                        {}
                                                     </a>
                                                 </td>
                                             </tr>
@@ -157,16 +172,15 @@ impl EmailTemplates {
                                         <!-- Help Section -->
                                         <div style="background-color: #eff6ff; border-left: 4px solid #3b82f6; padding: 16px; border-radius: 6px; margin: 0 0 20px 0;">
                                             <p style="color: #1e40af; margin: 0 0 10px 0; font-size: 14px; font-weight: 600;">
-                                                Need Help?
+                                                {}
                                             </p>
                                             <p style="color: #1e40af; margin: 0; font-size: 14px; line-height: 1.5;">
-                                                If you have any questions or need assistance, feel free to contact our support team 
-                                                or visit our help center.
+                                                {}
                                             </p>
                                         </div>
                                         
                                         <p style="color: #4b5563; line-height: 1.6; margin: 0; font-size: 16px;">
-                                            Thank you for joining GridTokenX. Together, we're building a sustainable energy future!
+                                            {}
                                         </p>
                                     </td>
                                 </tr>
@@ -175,10 +189,10 @@ impl EmailTemplates {
                                 <tr>
                                     <td style="background-color: #f9fafb; padding: 30px; text-align: center; border-radius: 0 0 10px 10px; border-top: 1px solid #e5e7eb;">
                                         <p style="color: #9ca3af; margin: 0 0 10px 0; font-size: 13px;">
-                                            © 2025 GridTokenX Platform. All rights reserved.
+                                            © 2025 GridTokenX Platform. {}
                                         </p>
                                         <p style="color: #9ca3af; margin: 0; font-size: 12px;">
-                                            This is an automated email. Please do not reply to this message.
+                                            {}
                                         </p>
                                     </td>
                                 </tr>
@@ -188,77 +202,121 @@ impl EmailTemplates {
                 </table>
             </body>
             </html>"#,
-            username, dashboard_url
+            locale,
+            t("email.welcome.subject"),
+            t("email.welcome.subject"),
+            t("email.welcome.verified"),
+            i18n::tf(locale, "email.welcome.greeting", &[("username", &username)]),
+            t("email.welcome.intro"),
+            t("email.welcome.next"),
+            t("email.welcome.next.wallet"),
+            t("email.welcome.next.dashboard"),
+            t("email.welcome.next.trading"),
+            t("email.welcome.next.prices"),
+            t("email.welcome.next.meters"),
+            dashboard_url,
+            t("email.welcome.button"),
+            t("email.welcome.help"),
+            t("email.welcome.help_text"),
+            t("email.welcome.closing"),
+            t("email.footer.rights"),
+            t("email.footer.no_reply")
         )
     }
 
     /// Plain text email template for email verification
-    pub fn verification_email_text(username: &str, verification_url: &str) -> String {
+    pub fn verification_email_text(username: &str, verification_url: &str, locale: Locale) -> String {
+        let t = |key| i18n::t(locale, key);
         format!(
-            r#"Welcome to GridTokenX Platform!
+            r#"{}
 
-            Hello {},
+            {}
 
-            Thank you for registering with GridTokenX Platform. We're excited to have you join our peer-to-peer energy trading network!
+            {}
 
-            To complete your registration and start trading energy tokens, please verify your email address by visiting this link:
+            {}
 
             {}
 
-            IMPORTANT: This verification link will expire in 24 hours for security purposes.
+            {}
 
-            If you didn't create an account with GridTokenX, you can safely ignore this email.
+            {}
 
             ---
-            © 2025 GridTokenX Platform. All rights reserved.
-            This is an automated email. Please do not reply to this message.
+            © 2025 GridTokenX Platform. {}
+            {}
             "#,
-            username, verification_url
+            t("email.verification.heading"),
+            i18n::tf(locale, "email.verification.greeting", &[("username", &username)]),
+            t("email.verification.intro"),
+            t("email.verification.action"),
+            verification_url,
+            t("email.verification.expiry"),
+            t("email.verification.ignore"),
+            t("email.footer.rights"),
+            t("email.footer.no_reply")
         )
     }
 
     /// Plain text email template for welcome message after verification
-    pub fn welcome_email_text(username: &str, dashboard_url: &str) -> String {
+    pub fn welcome_email_text(username: &str, dashboard_url: &str, locale: Locale) -> String {
+        let t = |key| i18n::t(locale, key);
         format!(
-            r#"Welcome to GridTokenX!
+            r#"{}
 
-            Hello {},
+            {}
 
-            Congratulations! Your email has been successfully verified. You now have full access to all features of the GridTokenX Platform.
+            {}
 
-            What's Next?
+            {}
 
-            * Connect Your Wallet: Link your Solana wallet for blockchain transactions
-            * View Dashboard: Monitor your energy consumption and production in real-time
-            * Start Trading: Buy and sell energy tokens with other users on the platform
-            * Track Prices: View live energy market prices and trends
-            * Manage Meters: Connect and manage your smart meters
+            * {}
+            * {}
+            * {}
+            * {}
+            * {}
 
-            Get started by visiting your dashboard:
+            {}:
             {}
 
-            Need Help?
-            If you have any questions or need assistance, feel free to contact our support team or visit our help center.
+            {}
+            {}
 
-            Thank you for joining GridTokenX. Together, we're building a sustainable energy future!
+            {}
 
             ---
-            © 2025 GridTokenX Platform. All rights reserved.
-            This is an automated email. Please do not reply to this message.
+            © 2025 GridTokenX Platform. {}
+            {}
             "#,
-            username, dashboard_url
+            t("email.welcome.subject"),
+            i18n::tf(locale, "email.welcome.greeting", &[("username", &username)]),
+            t("email.welcome.intro"),
+            t("email.welcome.next"),
+            t("email.welcome.next.wallet"),
+            t("email.welcome.next.dashboard"),
+            t("email.welcome.next.trading"),
+            t("email.welcome.next.prices"),
+            t("email.welcome.next.meters"),
+            t("email.welcome.button"),
+            dashboard_url,
+            t("email.welcome.help"),
+            t("email.welcome.help_text"),
+            t("email.welcome.closing"),
+            t("email.footer.rights"),
+            t("email.footer.no_reply")
         )
     }
 
     /// HTML email template for password reset
-    pub fn password_reset_email(username: &str, reset_url: &str) -> String {
+    pub fn password_reset_email(username: &str, reset_url: &str, locale: Locale) -> String {
+        let t = |key| i18n::t(locale, key);
         format!(
             r#"<!DOCTYPE html>
-<html lang="en">
+<html lang="{}">
 <head>
   <meta charset="UTF-8">
   <meta name="viewport" content="width=device-width, initial-scale=1.0">
-  <title>Reset Your Password - GridTokenX</title>
+  <title>{}</title>
 </head>
 <body style="margin: 0; padding: 0; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; background-color: #f5f5f5;">
   <table role="presentation" style="width: 100%; border-collapse: collapse; background-color: #f5f5f5;">
@@ -269,15 +327,14 @@ impl EmailTemplates {
           <!-- Body -->
           <tr>
             <td style="padding: 40px 30px; background-color: #ffffff;">
-              <h2 style="color: #1f2937; margin: 0 0 20px 0; font-size: 24px; font-weight: 600;">Password Reset Request</h2>
+              <h2 style="color: #1f2937; margin: 0 0 20px 0; font-size: 24px; font-weight: 600;">{}</h2>
               
               <p style="color: #4b5563; line-height: 1.6; margin: 0 0 20px 0; font-size: 16px;">
-                Hello <strong>{}</strong>,
+                {}
               </p>
               
               <p style="color: #4b5563; line-height: 1.6; margin: 0 0 20px 0; font-size: 16px;">
-                We received a request to reset the password for your <strong>GridTokenX</strong> account. 
-                Click the button below to create a new password:
+                {}
               </p>
               
               <!-- Button -->
@@ -288,7 +345,7 @@ impl EmailTemplates {
                       style="display: inline-block; background: linear-gradient(135deg, #ef4444 0%, #dc2626 100%); 
                           color: #ffffff; padding: 16px 40px; text-decoration: none; 
                           font-weight: 600; font-size: 16px; box-shadow: 0 4px 6px rgba(239, 68, 68, 0.4);">
-                      Reset Password
+                      {}
                     </a>
                   </td>
                 </tr>
@@ -296,7 +353,7 @@ impl EmailTemplates {
               
               <!-- Fallback Link -->
               <p style="color: #6b7280; font-size: 14px; line-height: 1.6; margin: 0 0 10px 0;">
-                If the button doesn't work, copy and paste this link into your browser:
+                {}
               </p>
               <p style="background-color: #f3f4f6; padding: 12px; 
                     font-size: 13px; color: #ef4444; margin: 0 0 30px 0;">
@@ -306,17 +363,122 @@ impl EmailTemplates {
               <!-- Security Notice -->
               <div style="background-color: #fef3c7; border-left: 4px solid #f59e0b; padding: 16px; margin: 0 0 20px 0;">
                 <p style="color: #92400e; margin: 0 0 10px 0; font-size: 14px; font-weight: 600;">
-                  ⚠️ Security Notice
+                  ⚠️ {}
                 </p>
                 <p style="color: #92400e; margin: 0; font-size: 14px; line-height: 1.5;">
-                  This link will expire in 1 hour. If you didn't request a password reset, 
-                  please ignore this email or contact support if you have concerns.
+                  {}
                 </p>
               </div>
+            </td>
+          </tr>
+          
+          <!-- Footer -->
+          <tr>
+            <td style="background-color: #f9fafb; padding: 10px; text-align: center; border-top: 1px solid #e5e7eb;">
+              <p style="color: #9ca3af; margin: 0 0 10px 0; font-size: 13px;">
+                © 2025 GridTokenX Platform. {}
+              </p>
+              <p style="color: #9ca3af; margin: 0; font-size: 12px;">
+                {}
+              </p>
+            </td>
+          </tr>
+        </table>
+      </td>
+    </tr>
+  </table>
+</body>
+</html>"#,
+            locale,
+            t("email.password_reset.subject"),
+            t("email.password_reset.heading"),
+            i18n::tf(locale, "email.password_reset.greeting", &[("username", &username)]),
+            t("email.password_reset.intro"),
+            reset_url,
+            t("email.password_reset.button"),
+            t("email.link_fallback"),
+            reset_url,
+            reset_url,
+            t("email.password_reset.notice"),
+            t("email.password_reset.expiry"),
+            t("email.footer.rights"),
+            t("email.footer.no_reply")
+        )
+    }
+
+    /// Plain text email template for password reset
+    pub fn password_reset_email_text(username: &str, reset_url: &str, locale: Locale) -> String {
+        let t = |key| i18n::t(locale, key);
+        format!(
+            r#"{} - GridTokenX
+
+{}
+
+{}
+
+{}
+
+{}
+
+---
+© 2025 GridTokenX Platform. {}
+{}
+"#,
+            t("email.password_reset.heading"),
+            i18n::tf(locale, "email.password_reset.greeting", &[("username", &username)]),
+            t("email.password_reset.intro"),
+            reset_url,
+            t("email.password_reset.expiry"),
+            t("email.footer.rights"),
+            t("email.footer.no_reply")
+        )
+    }
+
+    /// HTML email template for a login step-up code
+    pub fn login_code_email(username: &str, code: &str, minutes: i64, locale: Locale) -> String {
+        let t = |key| i18n::t(locale, key);
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="{}">
+<head>
+  <meta charset="UTF-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1.0">
+  <title>{}</title>
+</head>
+<body style="margin: 0; padding: 0; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; background-color: #f5f5f5;">
+  <table role="presentation" style="width: 100%; border-collapse: collapse; background-color: #f5f5f5;">
+    <tr>
+      <td align="center" style="padding: 40px 0;">
+        <table role="presentation" style="width: 600px; max-width: 100%; border-collapse: collapse; background-color: #ffffff; box-shadow: 0 4px 6px rgba(0, 0, 0, 0.1);">
+          
+          <!-- Body -->
+          <tr>
+            <td style="padding: 40px 30px; background-color: #ffffff;">
+              <h2 style="color: #1f2937; margin: 0 0 20px 0; font-size: 24px; font-weight: 600;">{}</h2>
               
-              <p style="color: #6b7280; font-size: 14px; line-height: 1.6; margin: 0;">
-                For security, this request was received from your account.
+              <p style="color: #4b5563; line-height: 1.6; margin: 0 0 20px 0; font-size: 16px;">
+                {}
+              </p>
+              
+              <p style="color: #4b5563; line-height: 1.6; margin: 0 0 20px 0; font-size: 16px;">
+                {}
+              </p>
+              
+              <!-- Code -->
+              <p style="background-color: #f3f4f6; padding: 20px; text-align: center; 
+                    font-size: 32px; font-weight: 700; letter-spacing: 8px; color: #1f2937; margin: 0 0 30px 0;">
+                {}
               </p>
+              
+              <!-- Security Notice -->
+              <div style="background-color: #fef3c7; border-left: 4px solid #f59e0b; padding: 16px; margin: 0 0 20px 0;">
+                <p style="color: #92400e; margin: 0 0 10px 0; font-size: 14px; font-weight: 600;">
+                  ⚠️ {}
+                </p>
+                <p style="color: #92400e; margin: 0; font-size: 14px; line-height: 1.5;">
+                  {}
+                </p>
+              </div>
             </td>
           </tr>
           
@@ -324,10 +486,10 @@ impl EmailTemplates {
           <tr>
             <td style="background-color: #f9fafb; padding: 10px; text-align: center; border-top: 1px solid #e5e7eb;">
               <p style="color: #9ca3af; margin: 0 0 10px 0; font-size: 13px;">
-                © 2025 GridTokenX Platform. All rights reserved.
+                © 2025 GridTokenX Platform. {}
               </p>
               <p style="color: #9ca3af; margin: 0; font-size: 12px;">
-                This is an automated email. Please do not reply to this message.
+                {}
               </p>
             </td>
           </tr>
@@ -337,32 +499,146 @@ impl EmailTemplates {
   </table>
 </body>
 </html>"#,
-            username, reset_url, reset_url, reset_url
+            locale,
+            t("email.login_code.subject"),
+            t("email.login_code.heading"),
+            i18n::tf(locale, "email.login_code.greeting", &[("username", &username)]),
+            t("email.login_code.intro"),
+            code,
+            t("email.login_code.notice"),
+            i18n::tf(locale, "email.login_code.expiry", &[("minutes", &minutes)]),
+            t("email.footer.rights"),
+            t("email.footer.no_reply")
         )
     }
 
-    /// Plain text email template for password reset
-    pub fn password_reset_email_text(username: &str, reset_url: &str) -> String {
+    /// Plain text email template for a login step-up code
+    pub fn login_code_email_text(username: &str, code: &str, minutes: i64, locale: Locale) -> String {
+        let t = |key| i18n::t(locale, key);
         format!(
-            r#"Password Reset Request - GridTokenX
+            r#"{} - GridTokenX
 
-Hello {},
+{}
 
-We received a request to reset the password for your GridTokenX account.
+{}
+
+{}
+
+{}
+
+---
+© 2025 GridTokenX Platform. {}
+{}
+"#,
+            t("email.login_code.heading"),
+            i18n::tf(locale, "email.login_code.greeting", &[("username", &username)]),
+            t("email.login_code.intro"),
+            code,
+            i18n::tf(locale, "email.login_code.expiry", &[("minutes", &minutes)]),
+            t("email.footer.rights"),
+            t("email.footer.no_reply")
+        )
+    }
+
+    /// HTML email template for a scheduled report sent as an attachment
+    pub fn report_email(username: &str, report: &str, period: &str, highlights: &[String], locale: Locale) -> String {
+        let t = |key| i18n::t(locale, key);
+        let items: String = highlights
+            .iter()
+            .map(|line| format!(r#"<li style="margin: 0 0 6px 0;">{}</li>"#, line))
+            .collect();
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="{}">
+<head>
+  <meta charset="UTF-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1.0">
+  <title>{}</title>
+</head>
+<body style="margin: 0; padding: 0; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; background-color: #f5f5f5;">
+  <table role="presentation" style="width: 100%; border-collapse: collapse; background-color: #f5f5f5;">
+    <tr>
+      <td align="center" style="padding: 40px 0;">
+        <table role="presentation" style="width: 600px; max-width: 100%; border-collapse: collapse; background-color: #ffffff; box-shadow: 0 4px 6px rgba(0, 0, 0, 0.1);">
+          
+          <!-- Body -->
+          <tr>
+            <td style="padding: 40px 30px; background-color: #ffffff;">
+              <h2 style="color: #1f2937; margin: 0 0 20px 0; font-size: 24px; font-weight: 600;">{}</h2>
+              
+              <p style="color: #4b5563; line-height: 1.6; margin: 0 0 20px 0; font-size: 16px;">
+                {}
+              </p>
+              
+              <p style="color: #4b5563; line-height: 1.6; margin: 0 0 10px 0; font-size: 16px;">
+                {}
+              </p>
+              
+              <!-- Highlights -->
+              <ul style="color: #1f2937; line-height: 1.5; margin: 0 0 30px 0; font-size: 15px;">
+                {}
+              </ul>
+              
+              <p style="color: #6b7280; line-height: 1.5; margin: 0; font-size: 13px;">
+                {}
+              </p>
+            </td>
+          </tr>
+          
+          <!-- Footer -->
+          <tr>
+            <td style="background-color: #f9fafb; padding: 10px; text-align: center; border-top: 1px solid #e5e7eb;">
+              <p style="color: #9ca3af; margin: 0 0 10px 0; font-size: 13px;">
+                © 2025 GridTokenX Platform. {}
+              </p>
+              <p style="color: #9ca3af; margin: 0; font-size: 12px;">
+                {}
+              </p>
+            </td>
+          </tr>
+        </table>
+      </td>
+    </tr>
+  </table>
+</body>
+</html>"#,
+            locale,
+            report,
+            report,
+            i18n::tf(locale, "email.report.greeting", &[("username", &username)]),
+            i18n::tf(locale, "email.report.intro", &[("report", &report), ("period", &period)]),
+            items,
+            t("email.report.manage"),
+            t("email.footer.rights"),
+            t("email.footer.no_reply")
+        )
+    }
 
-To reset your password, please visit this link:
+    /// Plain text email template for a scheduled report
+    pub fn report_email_text(username: &str, report: &str, period: &str, highlights: &[String], locale: Locale) -> String {
+        let t = |key| i18n::t(locale, key);
+        let items: String = highlights.iter().map(|line| format!("- {}\n", line)).collect();
+        format!(
+            r#"{} - GridTokenX
 
 {}
 
-IMPORTANT: This link will expire in 1 hour for security purposes.
+{}
 
-If you didn't request a password reset, please ignore this email or contact support if you have concerns.
+{}
+{}
 
 ---
-© 2025 GridTokenX Platform. All rights reserved.
-This is an automated email. Please do not reply to this message.
+© 2025 GridTokenX Platform. {}
+{}
 "#,
-            username, reset_url
+            report,
+            i18n::tf(locale, "email.report.greeting", &[("username", &username)]),
+            i18n::tf(locale, "email.report.intro", &[("report", &report), ("period", &period)]),
+            items,
+            t("email.report.manage"),
+            t("email.footer.rights"),
+            t("email.footer.no_reply")
         )
     }
 }
@@ -374,32 +650,40 @@ mod tests {
     #[test]
     fn test_verification_email_contains_username() {
         let email =
-            EmailTemplates::verification_email("testuser", "http://example.com/verify?token=abc");
+            EmailTemplates::verification_email("testuser", "http://example.com/verify?token=abc", Locale::En);
         assert!(email.contains("testuser"));
     }
 
     #[test]
     fn test_verification_email_contains_url() {
         let url = "http://example.com/verify?token=abc123";
-        let email = EmailTemplates::verification_email("testuser", url);
+        let email = EmailTemplates::verification_email("testuser", url, Locale::En);
         assert!(email.contains(url));
     }
 
     #[test]
     fn test_welcome_email_contains_username() {
-        let email = EmailTemplates::welcome_email("testuser", "http://example.com/dashboard");
+        let email = EmailTemplates::welcome_email("testuser", "http://example.com/dashboard", Locale::En);
         assert!(email.contains("testuser"));
     }
 
     #[test]
     fn test_text_emails_are_generated() {
         let verification_text =
-            EmailTemplates::verification_email_text("testuser", "http://example.com");
-        let welcome_text = EmailTemplates::welcome_email_text("testuser", "http://example.com");
+            EmailTemplates::verification_email_text("testuser", "http://example.com", Locale::En);
+        let welcome_text = EmailTemplates::welcome_email_text("testuser", "http://example.com", Locale::En);
 
         assert!(!verification_text.is_empty());
         assert!(!welcome_text.is_empty());
         assert!(verification_text.contains("testuser"));
         assert!(welcome_text.contains("testuser"));
     }
+
+    #[test]
+    fn test_thai_email_is_localized() {
+        let email = EmailTemplates::password_reset_email("testuser", "http://example.com", Locale::Th);
+        assert!(email.contains(r#"<html lang="th">"#));
+        assert!(email.contains("รีเซ็ตรหัสผ่าน"));
+        assert!(email.contains("testuser"));
+    }
 }
@@ -0,0 +1,327 @@
+//! Emission Factor Service
+//!
+//! Keeps the table of grid emission factors (kg CO2 per kWh, by region and
+//! year) used to estimate CO2 savings on the dashboard and in community
+//! reports. Every computed figure carries the factor it was computed with,
+//! so a later change to the table never rewrites how an old figure was
+//! derived.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::EmissionsConfig;
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+
+/// Source recorded when no factor is configured for the region
+pub const FALLBACK_SOURCE: &str = "config_fallback";
+
+/// Highest plausible grid emission factor (coal-only grids are ~1.0)
+const MAX_KG_CO2_PER_KWH: f64 = 2.0;
+
+/// Configured emission factor
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct EmissionFactor {
+    pub id: Uuid,
+    /// Grid region code (e.g. TH, TH-MEA)
+    pub region: String,
+    pub year: i32,
+    pub kg_co2_per_kwh: f64,
+    /// Publication or dataset the grid mix was taken from
+    pub grid_mix_source: String,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Fields of a new or updated emission factor
+#[derive(Debug, Clone)]
+pub struct EmissionFactorFields {
+    pub region: String,
+    pub year: i32,
+    pub kg_co2_per_kwh: f64,
+    pub grid_mix_source: String,
+    pub notes: Option<String>,
+}
+
+/// Factor a CO2 figure was computed with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FactorProvenance {
+    /// `None` for the configured fallback factor
+    pub factor_id: Option<Uuid>,
+    pub region: String,
+    pub year: Option<i32>,
+    pub kg_co2_per_kwh: f64,
+    pub grid_mix_source: String,
+}
+
+impl FactorProvenance {
+    fn from_factor(factor: &EmissionFactor) -> Self {
+        Self {
+            factor_id: Some(factor.id),
+            region: factor.region.clone(),
+            year: Some(factor.year),
+            kg_co2_per_kwh: factor.kg_co2_per_kwh,
+            grid_mix_source: factor.grid_mix_source.clone(),
+        }
+    }
+}
+
+/// Choose the factor for `region` in `year`: the latest year not after
+/// `year`, else the earliest year on record, else the fallback factor
+pub fn select_factor(
+    factors: &[EmissionFactor],
+    region: &str,
+    year: i32,
+    fallback_kg_per_kwh: f64,
+) -> FactorProvenance {
+    let regional = factors.iter().filter(|f| f.region == region);
+    let chosen = regional
+        .clone()
+        .filter(|f| f.year <= year)
+        .max_by_key(|f| f.year)
+        .or_else(|| regional.min_by_key(|f| f.year));
+
+    match chosen {
+        Some(factor) => FactorProvenance::from_factor(factor),
+        None => FactorProvenance {
+            factor_id: None,
+            region: region.to_string(),
+            year: None,
+            kg_co2_per_kwh: fallback_kg_per_kwh,
+            grid_mix_source: FALLBACK_SOURCE.to_string(),
+        },
+    }
+}
+
+fn validate(fields: &EmissionFactorFields) -> Result<(String, String)> {
+    let region = fields.region.trim().to_uppercase();
+    if region.is_empty() || region.len() > 32 {
+        return Err(ApiError::validation_field("region", "Region must be 1-32 characters"));
+    }
+    if !(1990..=2100).contains(&fields.year) {
+        return Err(ApiError::validation_field("year", "Year must be between 1990 and 2100"));
+    }
+    if !(0.0..=MAX_KG_CO2_PER_KWH).contains(&fields.kg_co2_per_kwh) {
+        return Err(ApiError::validation_field(
+            "kg_co2_per_kwh",
+            format!("Factor must be between 0 and {} kg CO2/kWh", MAX_KG_CO2_PER_KWH),
+        ));
+    }
+    let source = fields.grid_mix_source.trim().to_string();
+    if source.is_empty() {
+        return Err(ApiError::validation_field("grid_mix_source", "Grid mix source is required"));
+    }
+    Ok((region, source))
+}
+
+const FACTOR_COLUMNS: &str =
+    "id, region, year, kg_co2_per_kwh, grid_mix_source, notes, created_by, created_at, updated_at";
+
+#[derive(Clone, Debug)]
+pub struct EmissionFactorService {
+    db: PgPool,
+    config: EmissionsConfig,
+    factors: Arc<RwLock<Vec<EmissionFactor>>>,
+}
+
+impl EmissionFactorService {
+    pub fn new(db: PgPool, config: EmissionsConfig) -> Self {
+        Self {
+            db,
+            config,
+            factors: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// Region the platform's CO2 figures are computed for
+    pub fn region(&self) -> &str {
+        &self.config.region
+    }
+
+    /// Reload the factor table; until the first load the fallback factor is used
+    pub async fn refresh(&self) -> Result<()> {
+        let factors = self.list().await?;
+        info!("🌱 Loaded {} emission factors (region {})", factors.len(), self.config.region);
+        *self.factors.write().await = factors;
+        Ok(())
+    }
+
+    /// Factor for the configured region in `year`
+    pub async fn factor_for(&self, year: i32) -> FactorProvenance {
+        let factors = self.factors.read().await;
+        select_factor(&factors, &self.config.region, year, self.config.fallback_kg_per_kwh)
+    }
+
+    /// Factor for the configured region this year
+    pub async fn current(&self) -> FactorProvenance {
+        self.factor_for(Utc::now().year()).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<EmissionFactor>> {
+        Ok(sqlx::query_as::<_, EmissionFactor>(&format!(
+            "SELECT {} FROM emission_factors ORDER BY region, year DESC",
+            FACTOR_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn create(
+        &self,
+        admin_id: Uuid,
+        fields: EmissionFactorFields,
+        audit: &AuditLogger,
+    ) -> Result<EmissionFactor> {
+        let (region, source) = validate(&fields)?;
+        let factor = sqlx::query_as::<_, EmissionFactor>(&format!(
+            r#"
+            INSERT INTO emission_factors (region, year, kg_co2_per_kwh, grid_mix_source, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (region, year) DO NOTHING
+            RETURNING {}
+            "#,
+            FACTOR_COLUMNS
+        ))
+        .bind(&region)
+        .bind(fields.year)
+        .bind(fields.kg_co2_per_kwh)
+        .bind(&source)
+        .bind(&fields.notes)
+        .bind(admin_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| {
+            ApiError::Conflict(format!("A factor for {} in {} already exists", region, fields.year))
+        })?;
+
+        info!(
+            "🌱 Emission factor {} {} set to {} kg CO2/kWh ({})",
+            factor.region, factor.year, factor.kg_co2_per_kwh, factor.grid_mix_source
+        );
+        self.changed(audit, admin_id, "emission_factor_created", &factor).await;
+        Ok(factor)
+    }
+
+    pub async fn update(
+        &self,
+        admin_id: Uuid,
+        factor_id: Uuid,
+        fields: EmissionFactorFields,
+        audit: &AuditLogger,
+    ) -> Result<EmissionFactor> {
+        let (region, source) = validate(&fields)?;
+        let factor = sqlx::query_as::<_, EmissionFactor>(&format!(
+            r#"
+            UPDATE emission_factors
+            SET region = $2, year = $3, kg_co2_per_kwh = $4, grid_mix_source = $5, notes = $6,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            FACTOR_COLUMNS
+        ))
+        .bind(factor_id)
+        .bind(&region)
+        .bind(fields.year)
+        .bind(fields.kg_co2_per_kwh)
+        .bind(&source)
+        .bind(&fields.notes)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                ApiError::Conflict(format!("A factor for {} in {} already exists", region, fields.year))
+            }
+            _ => ApiError::Database(e),
+        })?
+        .ok_or_else(|| ApiError::NotFound("Emission factor not found".to_string()))?;
+
+        self.changed(audit, admin_id, "emission_factor_updated", &factor).await;
+        Ok(factor)
+    }
+
+    pub async fn delete(&self, admin_id: Uuid, factor_id: Uuid, audit: &AuditLogger) -> Result<EmissionFactor> {
+        let factor = sqlx::query_as::<_, EmissionFactor>(&format!(
+            "DELETE FROM emission_factors WHERE id = $1 RETURNING {}",
+            FACTOR_COLUMNS
+        ))
+        .bind(factor_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Emission factor not found".to_string()))?;
+
+        self.changed(audit, admin_id, "emission_factor_deleted", &factor).await;
+        Ok(factor)
+    }
+
+    /// Audit a change to the table and pick it up for new figures
+    async fn changed(&self, audit: &AuditLogger, admin_id: Uuid, action: &str, factor: &EmissionFactor) {
+        audit.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "factor_id": factor.id,
+                "region": factor.region,
+                "year": factor.year,
+                "kg_co2_per_kwh": factor.kg_co2_per_kwh,
+                "grid_mix_source": factor.grid_mix_source,
+            })
+            .to_string(),
+        });
+        if let Err(e) = self.refresh().await {
+            warn!("⚠️ Failed to reload emission factors: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factor(region: &str, year: i32, kg: f64) -> EmissionFactor {
+        EmissionFactor {
+            id: Uuid::new_v4(),
+            region: region.to_string(),
+            year,
+            kg_co2_per_kwh: kg,
+            grid_mix_source: format!("{} {}", region, year),
+            notes: None,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_selects_latest_year_not_after_target() {
+        let factors = vec![factor("TH", 2022, 0.45), factor("TH", 2024, 0.43), factor("LA", 2024, 0.2)];
+        let selected = select_factor(&factors, "TH", 2023, 0.5);
+        assert_eq!(selected.year, Some(2022));
+        assert_eq!(selected.kg_co2_per_kwh, 0.45);
+
+        let selected = select_factor(&factors, "TH", 2026, 0.5);
+        assert_eq!(selected.year, Some(2024));
+        assert_eq!(selected.factor_id, Some(factors[1].id));
+    }
+
+    #[test]
+    fn test_earliest_year_then_fallback() {
+        let factors = vec![factor("TH", 2024, 0.43)];
+        assert_eq!(select_factor(&factors, "TH", 2020, 0.5).year, Some(2024));
+
+        let fallback = select_factor(&factors, "VN", 2024, 0.5);
+        assert_eq!(fallback.factor_id, None);
+        assert_eq!(fallback.kg_co2_per_kwh, 0.5);
+        assert_eq!(fallback.grid_mix_source, FALLBACK_SOURCE);
+    }
+}
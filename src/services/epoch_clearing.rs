@@ -0,0 +1,187 @@
+//! Epoch Clearing Scheduler
+//!
+//! Clears market epochs automatically once they close: snapshots the order
+//! books about to be matched, runs order matching for the epoch, which
+//! records matches and settlements, hands the new settlements to the
+//! settlement loop and broadcasts an `epoch_cleared` event with the epoch's
+//! final statistics.
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::MarketSessionConfig;
+use crate::services::book_snapshots::{OrderBookSnapshotService, SnapshotReason};
+use crate::services::market_clearing::MarketClearingService;
+use crate::services::market_session;
+use crate::services::{SettlementService, WebSocketService};
+
+/// Maximum epochs cleared per run
+const EPOCHS_PER_RUN: i64 = 10;
+
+/// A claim older than this is assumed abandoned (e.g. the instance stopped
+/// mid-run) and the epoch is cleared again
+const CLAIM_TIMEOUT_MINUTES: i64 = 10;
+
+#[derive(Clone)]
+pub struct EpochClearingService {
+    db: PgPool,
+    market_clearing: MarketClearingService,
+    settlement: SettlementService,
+    websocket: WebSocketService,
+    book_snapshots: OrderBookSnapshotService,
+    session_config: MarketSessionConfig,
+}
+
+impl EpochClearingService {
+    pub fn new(
+        db: PgPool,
+        market_clearing: MarketClearingService,
+        settlement: SettlementService,
+        websocket: WebSocketService,
+        book_snapshots: OrderBookSnapshotService,
+        session_config: MarketSessionConfig,
+    ) -> Self {
+        Self {
+            db,
+            market_clearing,
+            settlement,
+            websocket,
+            book_snapshots,
+            session_config,
+        }
+    }
+
+    /// Clear every closed epoch that has not been cleared yet.
+    /// Returns the number of epochs cleared.
+    pub async fn run_cycle(&self) -> Result<usize> {
+        let session = market_session::session_at(&self.session_config, Utc::now());
+        if !session.state.allows_clearing() {
+            return Ok(0);
+        }
+
+        let due: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM market_epochs
+            WHERE end_time <= NOW()
+              AND cleared_at IS NULL
+              AND (clearing_started_at IS NULL
+                   OR clearing_started_at < NOW() - make_interval(mins => $1::int))
+            ORDER BY end_time ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(CLAIM_TIMEOUT_MINUTES as i32)
+        .bind(EPOCHS_PER_RUN)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut cleared = 0;
+        for epoch_id in due {
+            if !self.claim(epoch_id).await? {
+                continue;
+            }
+
+            match self.clear_claimed(epoch_id).await {
+                Ok(()) => cleared += 1,
+                Err(e) => error!("❌ Failed to clear epoch {}: {}", epoch_id, e),
+            }
+        }
+
+        Ok(cleared)
+    }
+
+    /// Clear one epoch now, whether or not it has closed. Returns false if
+    /// it is already cleared or another run is clearing it.
+    pub async fn clear_now(&self, epoch_id: Uuid) -> Result<bool> {
+        if !self.claim(epoch_id).await? {
+            return Ok(false);
+        }
+        self.clear_claimed(epoch_id).await?;
+        Ok(true)
+    }
+
+    async fn clear_claimed(&self, epoch_id: Uuid) -> Result<()> {
+        let result = self.clear_epoch(epoch_id).await;
+        if result.is_err() {
+            // Release the claim so the next run retries
+            let _ = sqlx::query("UPDATE market_epochs SET clearing_started_at = NULL WHERE id = $1")
+                .bind(epoch_id)
+                .execute(&self.db)
+                .await;
+        }
+        result
+    }
+
+    /// Claim an epoch for this instance; false if another run holds it
+    async fn claim(&self, epoch_id: Uuid) -> Result<bool> {
+        let claimed = sqlx::query(
+            r#"
+            UPDATE market_epochs SET clearing_started_at = NOW()
+            WHERE id = $1
+              AND cleared_at IS NULL
+              AND (clearing_started_at IS NULL
+                   OR clearing_started_at < NOW() - make_interval(mins => $2::int))
+            "#,
+        )
+        .bind(epoch_id)
+        .bind(CLAIM_TIMEOUT_MINUTES as i32)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+
+        Ok(claimed == 1)
+    }
+
+    /// Match, settle and announce one claimed epoch
+    async fn clear_epoch(&self, epoch_id: Uuid) -> Result<()> {
+        // A missing snapshot must not hold up clearing
+        if let Err(e) = self
+            .book_snapshots
+            .capture_all(SnapshotReason::Clearing, Some(epoch_id))
+            .await
+        {
+            warn!("⚠️ Failed to snapshot order books before clearing epoch {}: {}", epoch_id, e);
+        }
+
+        let matches = self.market_clearing.run_order_matching(epoch_id).await?;
+
+        let match_ids: Vec<Uuid> = matches.iter().map(|m| m.id).collect();
+        let settlement_ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT settlement_id FROM order_matches WHERE id = ANY($1) AND settlement_id IS NOT NULL",
+        )
+        .bind(&match_ids)
+        .fetch_all(&self.db)
+        .await?;
+
+        self.settlement.enqueue_settlements(&settlement_ids).await;
+
+        sqlx::query(
+            "UPDATE market_epochs SET status = 'cleared'::epoch_status, cleared_at = NOW(), updated_at = NOW() WHERE id = $1",
+        )
+        .bind(epoch_id)
+        .execute(&self.db)
+        .await?;
+
+        let epoch = self
+            .market_clearing
+            .get_epoch_by_id(epoch_id)
+            .await?
+            .ok_or_else(|| anyhow!("Epoch {} disappeared during clearing", epoch_id))?;
+
+        info!(
+            "🧮 Epoch {} cleared: {} matches, {} settlements enqueued",
+            epoch.epoch_number,
+            matches.len(),
+            settlement_ids.len()
+        );
+
+        self.websocket
+            .broadcast_epoch_cleared(&epoch, settlement_ids)
+            .await;
+
+        Ok(())
+    }
+}
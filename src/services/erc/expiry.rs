@@ -0,0 +1,211 @@
+//! Certificate expiry monitoring
+//!
+//! Notifies owners once when an active certificate enters the warning
+//! window and moves certificates past their expiry date to `Expired`, which
+//! takes them out of retirement, transfers and active energy totals.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{FromRow, PgPool};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::models::notification::{CreateNotificationRequest, NotificationType};
+use crate::services::NotificationDispatcher;
+
+/// Certificates handled per run and phase
+const BATCH_SIZE: i64 = 500;
+
+#[derive(Debug, FromRow)]
+struct ExpiryCandidate {
+    id: Uuid,
+    certificate_id: String,
+    user_id: Option<Uuid>,
+    kwh_amount: Option<Decimal>,
+    expiry_date: Option<DateTime<Utc>>,
+}
+
+/// Outcome of one monitoring run
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ExpiryRun {
+    pub warned: usize,
+    pub expired: usize,
+}
+
+#[derive(Clone)]
+pub struct ErcExpiryMonitor {
+    db: PgPool,
+    notifications: NotificationDispatcher,
+    warning_days: i64,
+}
+
+impl ErcExpiryMonitor {
+    pub fn new(db: PgPool, notifications: NotificationDispatcher, warning_days: i64) -> Self {
+        Self {
+            db,
+            notifications,
+            warning_days,
+        }
+    }
+
+    pub async fn run(&self) -> Result<ExpiryRun> {
+        let expired = self.expire_certificates().await?;
+        let warned = self.warn_expiring().await?;
+        Ok(ExpiryRun { warned, expired })
+    }
+
+    /// Mark certificates past their expiry date as expired and tell owners
+    async fn expire_certificates(&self) -> Result<usize> {
+        let expired = sqlx::query_as::<_, ExpiryCandidate>(
+            r#"
+            UPDATE erc_certificates SET status = 'Expired', updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM erc_certificates
+                WHERE status = 'Active' AND expiry_date <= NOW()
+                ORDER BY expiry_date
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, certificate_id, user_id, kwh_amount, expiry_date
+            "#,
+        )
+        .bind(BATCH_SIZE)
+        .fetch_all(&self.db)
+        .await?;
+
+        for cert in &expired {
+            self.notify(expiry_notification(cert, NotificationType::CertificateExpired)).await;
+        }
+
+        Ok(expired.len())
+    }
+
+    /// Notify owners of active certificates entering the warning window,
+    /// once per certificate
+    async fn warn_expiring(&self) -> Result<usize> {
+        let expiring = sqlx::query_as::<_, ExpiryCandidate>(
+            r#"
+            UPDATE erc_certificates SET expiry_notified_at = NOW()
+            WHERE id IN (
+                SELECT id FROM erc_certificates
+                WHERE status = 'Active'
+                  AND expiry_notified_at IS NULL
+                  AND expiry_date > NOW()
+                  AND expiry_date <= NOW() + make_interval(days => $1::int)
+                ORDER BY expiry_date
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, certificate_id, user_id, kwh_amount, expiry_date
+            "#,
+        )
+        .bind(self.warning_days as i32)
+        .bind(BATCH_SIZE)
+        .fetch_all(&self.db)
+        .await?;
+
+        for cert in &expiring {
+            self.notify(expiry_notification(cert, NotificationType::CertificateExpiring)).await;
+        }
+
+        Ok(expiring.len())
+    }
+
+    async fn notify(&self, request: Option<CreateNotificationRequest>) {
+        let Some(request) = request else {
+            return;
+        };
+        let (user_id, title) = (request.user_id, request.title.clone());
+        if let Err(e) = self.notifications.send(request).await {
+            warn!("Failed to send '{}' notification to {}: {}", title, user_id, e);
+        }
+    }
+}
+
+/// Expiry or warning notification for a certificate's owner. Certificates
+/// issued without an owner account have no one to notify.
+fn expiry_notification(
+    cert: &ExpiryCandidate,
+    notification_type: NotificationType,
+) -> Option<CreateNotificationRequest> {
+    let user_id = cert.user_id?;
+    let kwh = cert.kwh_amount.unwrap_or_default().normalize();
+
+    let (title, message) = match notification_type {
+        NotificationType::CertificateExpired => (
+            "Certificate Expired",
+            format!("Your renewable energy certificate {} for {} kWh has expired", cert.certificate_id, kwh),
+        ),
+        _ => {
+            let expires = cert
+                .expiry_date
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            (
+                "Certificate Expiring Soon",
+                format!(
+                    "Your renewable energy certificate {} for {} kWh expires on {}. Retire or transfer it before then.",
+                    cert.certificate_id, kwh, expires
+                ),
+            )
+        }
+    };
+
+    Some(CreateNotificationRequest {
+        user_id,
+        notification_type,
+        title: title.to_string(),
+        message: Some(message),
+        data: Some(serde_json::json!({
+            "certificate_id": cert.certificate_id,
+            "certificate_uuid": cert.id,
+            "kwh_amount": kwh,
+            "expiry_date": cert.expiry_date,
+        })),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn candidate(user_id: Option<Uuid>) -> ExpiryCandidate {
+        ExpiryCandidate {
+            id: Uuid::new_v4(),
+            certificate_id: "ERC-2026-0001".to_string(),
+            user_id,
+            kwh_amount: Some(Decimal::new(125000, 4)),
+            expiry_date: Some(Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_expired_notification() {
+        let owner = Uuid::new_v4();
+        let request = expiry_notification(&candidate(Some(owner)), NotificationType::CertificateExpired).unwrap();
+
+        assert_eq!(request.user_id, owner);
+        assert_eq!(request.title, "Certificate Expired");
+        assert_eq!(
+            request.message.as_deref(),
+            Some("Your renewable energy certificate ERC-2026-0001 for 12.5 kWh has expired")
+        );
+        assert_eq!(request.data.unwrap()["certificate_id"], "ERC-2026-0001");
+    }
+
+    #[test]
+    fn test_expiring_notification_names_the_date() {
+        let request = expiry_notification(&candidate(Some(Uuid::new_v4())), NotificationType::CertificateExpiring).unwrap();
+
+        assert_eq!(request.title, "Certificate Expiring Soon");
+        assert!(request.message.unwrap().contains("expires on 2026-03-01"));
+    }
+
+    #[test]
+    fn test_certificates_without_owner_are_not_notified() {
+        assert!(expiry_notification(&candidate(None), NotificationType::CertificateExpired).is_none());
+        assert!(expiry_notification(&candidate(None), NotificationType::CertificateExpiring).is_none());
+    }
+}
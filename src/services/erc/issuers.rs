@@ -0,0 +1,262 @@
+//! ERC issuer registry
+//!
+//! Issuers authorized to sign certificates, each with its signing key and
+//! issuance policy (allowed renewable sources, vintage window). Issuers are
+//! revoked rather than deleted so issued certificates keep their issuer.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::{AuditEvent, AuditLogger};
+
+const ISSUER_COLUMNS: &str = "id, name, signing_key, allowed_sources, vintage_days, is_default, active, \
+    created_by, created_at, updated_at, revoked_at";
+
+/// Authorized certificate issuer
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ErcIssuer {
+    pub id: Uuid,
+    pub name: String,
+    /// Base58 public key of the issuer's signing key
+    pub signing_key: String,
+    /// Renewable sources the issuer may certify (e.g. Solar, Wind)
+    pub allowed_sources: Vec<String>,
+    /// Days of metered production a certificate may cover
+    pub vintage_days: i32,
+    /// Signs certificates issued automatically at settlement
+    pub is_default: bool,
+    pub active: bool,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Fields of a new or updated issuer
+#[derive(Debug, Clone)]
+pub struct IssuerFields {
+    pub name: String,
+    pub allowed_sources: Vec<String>,
+    pub vintage_days: i32,
+    pub is_default: bool,
+}
+
+/// Trimmed, deduplicated source names
+pub fn normalize_sources(sources: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for source in sources {
+        let source = source.trim();
+        if !source.is_empty() && !normalized.iter().any(|s| s.eq_ignore_ascii_case(source)) {
+            normalized.push(source.to_string());
+        }
+    }
+    normalized
+}
+
+fn validate_fields(fields: &IssuerFields) -> Result<Vec<String>, ApiError> {
+    if fields.name.trim().is_empty() {
+        return Err(ApiError::validation_field("name", "Name is required"));
+    }
+    if fields.vintage_days <= 0 {
+        return Err(ApiError::validation_field("vintage_days", "Vintage window must be at least one day"));
+    }
+    let sources = normalize_sources(&fields.allowed_sources);
+    if sources.is_empty() {
+        return Err(ApiError::validation_field("allowed_sources", "At least one renewable source is required"));
+    }
+    Ok(sources)
+}
+
+#[derive(Clone, Debug)]
+pub struct IssuerRegistry {
+    db: PgPool,
+}
+
+impl IssuerRegistry {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn list(&self) -> Result<Vec<ErcIssuer>, ApiError> {
+        let issuers = sqlx::query_as::<_, ErcIssuer>(&format!(
+            "SELECT {} FROM erc_issuers ORDER BY active DESC, name",
+            ISSUER_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        Ok(issuers)
+    }
+
+    pub async fn get(&self, issuer_id: Uuid) -> Result<ErcIssuer, ApiError> {
+        sqlx::query_as::<_, ErcIssuer>(&format!("SELECT {} FROM erc_issuers WHERE id = $1", ISSUER_COLUMNS))
+            .bind(issuer_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Issuer not found".to_string()))
+    }
+
+    /// Issuer by signing key, active or not
+    pub async fn find_by_key(&self, signing_key: &str) -> Result<Option<ErcIssuer>, sqlx::Error> {
+        sqlx::query_as::<_, ErcIssuer>(&format!(
+            "SELECT {} FROM erc_issuers WHERE signing_key = $1",
+            ISSUER_COLUMNS
+        ))
+        .bind(signing_key)
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Active issuer used for automated issuance
+    pub async fn default_issuer(&self) -> Result<Option<ErcIssuer>, sqlx::Error> {
+        sqlx::query_as::<_, ErcIssuer>(&format!(
+            "SELECT {} FROM erc_issuers WHERE is_default AND active",
+            ISSUER_COLUMNS
+        ))
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    pub async fn create(
+        &self,
+        admin_id: Uuid,
+        signing_key: &str,
+        fields: IssuerFields,
+        audit: &AuditLogger,
+    ) -> Result<ErcIssuer, ApiError> {
+        let sources = validate_fields(&fields)?;
+        let signing_key = Pubkey::from_str(signing_key.trim())
+            .map_err(|_| ApiError::validation_field("signing_key", "Invalid base58 public key"))?
+            .to_string();
+
+        let mut tx = self.db.begin().await?;
+        if fields.is_default {
+            sqlx::query("UPDATE erc_issuers SET is_default = FALSE, updated_at = NOW() WHERE is_default")
+                .execute(&mut *tx)
+                .await?;
+        }
+        let issuer = sqlx::query_as::<_, ErcIssuer>(&format!(
+            r#"
+            INSERT INTO erc_issuers (name, signing_key, allowed_sources, vintage_days, is_default, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (signing_key) DO NOTHING
+            RETURNING {}
+            "#,
+            ISSUER_COLUMNS
+        ))
+        .bind(fields.name.trim())
+        .bind(&signing_key)
+        .bind(&sources)
+        .bind(fields.vintage_days)
+        .bind(fields.is_default)
+        .bind(admin_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::Conflict("An issuer with this signing key already exists".to_string()))?;
+        tx.commit().await?;
+
+        info!("🏛️ ERC issuer {} ({}) registered", issuer.name, issuer.signing_key);
+        audit_change(audit, admin_id, "erc_issuer_registered", &issuer);
+        Ok(issuer)
+    }
+
+    pub async fn update(
+        &self,
+        admin_id: Uuid,
+        issuer_id: Uuid,
+        fields: IssuerFields,
+        audit: &AuditLogger,
+    ) -> Result<ErcIssuer, ApiError> {
+        let sources = validate_fields(&fields)?;
+
+        let mut tx = self.db.begin().await?;
+        if fields.is_default {
+            sqlx::query("UPDATE erc_issuers SET is_default = FALSE, updated_at = NOW() WHERE is_default AND id <> $1")
+                .bind(issuer_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        let issuer = sqlx::query_as::<_, ErcIssuer>(&format!(
+            r#"
+            UPDATE erc_issuers
+            SET name = $2, allowed_sources = $3, vintage_days = $4, is_default = $5 AND active, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            ISSUER_COLUMNS
+        ))
+        .bind(issuer_id)
+        .bind(fields.name.trim())
+        .bind(&sources)
+        .bind(fields.vintage_days)
+        .bind(fields.is_default)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Issuer not found".to_string()))?;
+        tx.commit().await?;
+
+        audit_change(audit, admin_id, "erc_issuer_updated", &issuer);
+        Ok(issuer)
+    }
+
+    /// Revoke an issuer; it can no longer sign certificates
+    pub async fn revoke(&self, admin_id: Uuid, issuer_id: Uuid, audit: &AuditLogger) -> Result<ErcIssuer, ApiError> {
+        let issuer = sqlx::query_as::<_, ErcIssuer>(&format!(
+            r#"
+            UPDATE erc_issuers
+            SET active = FALSE, is_default = FALSE, revoked_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND active
+            RETURNING {}
+            "#,
+            ISSUER_COLUMNS
+        ))
+        .bind(issuer_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        let Some(issuer) = issuer else {
+            // Distinguish unknown from already revoked
+            self.get(issuer_id).await?;
+            return Err(ApiError::Conflict("Issuer already revoked".to_string()));
+        };
+
+        info!("🏛️ ERC issuer {} revoked", issuer.name);
+        audit_change(audit, admin_id, "erc_issuer_revoked", &issuer);
+        Ok(issuer)
+    }
+}
+
+fn audit_change(audit: &AuditLogger, admin_id: Uuid, action: &str, issuer: &ErcIssuer) {
+    audit.log_async(AuditEvent::AdminAction {
+        admin_id,
+        action: action.to_string(),
+        target_user_id: None,
+        details: serde_json::json!({
+            "issuer_id": issuer.id,
+            "name": issuer.name,
+            "signing_key": issuer.signing_key,
+            "allowed_sources": issuer.allowed_sources,
+            "vintage_days": issuer.vintage_days,
+            "is_default": issuer.is_default,
+        })
+        .to_string(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_sources() {
+        let sources = vec![" Solar ".to_string(), "solar".to_string(), "".to_string(), "Wind".to_string()];
+        assert_eq!(normalize_sources(&sources), vec!["Solar".to_string(), "Wind".to_string()]);
+    }
+}
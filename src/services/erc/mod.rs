@@ -1,9 +1,14 @@
+pub mod expiry;
 pub mod issuance;
+pub mod issuers;
+pub mod policy;
 pub mod queries;
 pub mod retiring;
 pub mod transfer;
 pub mod types;
 
+pub use expiry::ErcExpiryMonitor;
+pub use issuers::{ErcIssuer, IssuerRegistry};
 pub use types::*;
 
 use anyhow::{anyhow, Result};
@@ -15,6 +20,7 @@ use tracing::{info, instrument};
 use uuid::Uuid;
 
 use self::issuance::AggregatedIssuance;
+use self::policy::IssuancePolicy;
 use self::queries::ErcQueryManager;
 use self::retiring::CertificateRetiring;
 use self::transfer::CertificateTransferManager;
@@ -27,6 +33,8 @@ pub struct ErcService {
     #[allow(dead_code)]
     blockchain_service: BlockchainService,
     issuance_manager: AggregatedIssuance,
+    issuance_policy: IssuancePolicy,
+    issuers: IssuerRegistry,
     retiring_manager: CertificateRetiring,
     transfer_manager: CertificateTransferManager,
     query_manager: ErcQueryManager,
@@ -36,6 +44,7 @@ impl ErcService {
     /// Create a new ERC service
     pub fn new(db_pool: PgPool, blockchain_service: BlockchainService) -> Self {
         let issuance_manager = AggregatedIssuance::new(db_pool.clone(), blockchain_service.clone());
+        let issuance_policy = IssuancePolicy::new(db_pool.clone());
         let retiring_manager =
             CertificateRetiring::new(db_pool.clone(), blockchain_service.clone());
         let transfer_manager =
@@ -46,12 +55,24 @@ impl ErcService {
             db_pool,
             blockchain_service,
             issuance_manager,
+            issuance_policy,
+            issuers: IssuerRegistry::new(db_pool.clone()),
             retiring_manager,
             transfer_manager,
             query_manager,
         }
     }
 
+    /// Registry of authorized certificate issuers
+    pub fn issuers(&self) -> &IssuerRegistry {
+        &self.issuers
+    }
+
+    /// Signing key of the default issuer used for automated issuance
+    pub async fn default_issuer_key(&self) -> Result<Option<String>> {
+        Ok(self.issuers.default_issuer().await?.map(|i| i.signing_key))
+    }
+
     /// Issue a new ERC certificate
     #[instrument(skip(self, request, issuer_wallet))]
     pub async fn issue_certificate(
@@ -63,6 +84,12 @@ impl ErcService {
     ) -> Result<ErcCertificate> {
         info!("Issuing certificate for user {}", user_id);
 
+        let violations = self.issuance_policy.check(issuer_wallet, &request).await?;
+        if !violations.is_empty() {
+            let reasons: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+            return Err(anyhow!("Issuance rejected by policy: {}", reasons.join("; ")));
+        }
+
         // Generate certificate ID
         let certificate_id = self.issuance_manager.generate_certificate_id()?;
 
@@ -103,9 +130,9 @@ impl ErcService {
             INSERT INTO erc_certificates (
                 id, certificate_id, user_id, wallet_address,
                 kwh_amount, issue_date, expiry_date,
-                issuer_wallet, status, metadata, settlement_id
+                issuer_wallet, status, metadata, settlement_id, meter_serial
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'Active', $9, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'Active', $9, $10, $11)
             RETURNING
                 id, certificate_id,
                 user_id as "user_id?",
@@ -130,7 +157,8 @@ impl ErcService {
             request.expiry_date,
             issuer_wallet,
             metadata_json,
-            settlement_id
+            settlement_id,
+            request.meter_id
         )
         .fetch_one(&self.db_pool)
         .await
@@ -209,6 +237,22 @@ impl ErcService {
             .await
     }
 
+    /// Certificates backed by disputed energy cannot be transferred or retired
+    async fn ensure_not_on_dispute_hold(&self, certificate_uuid: Uuid) -> Result<()> {
+        let hold: Option<String> =
+            sqlx::query_scalar("SELECT dispute_hold FROM erc_certificates WHERE id = $1")
+                .bind(certificate_uuid)
+                .fetch_optional(&self.db_pool)
+                .await?
+                .flatten();
+
+        match hold.as_deref() {
+            Some("revoked") => Err(anyhow!("Certificate was revoked after a settlement dispute")),
+            Some(_) => Err(anyhow!("Certificate is on hold pending a settlement dispute")),
+            None => Ok(()),
+        }
+    }
+
     // --- Transfer ---
 
     /// Transfer certificate
@@ -220,6 +264,7 @@ impl ErcService {
         to_wallet: &str,
         tx_signature: &str,
     ) -> Result<(ErcCertificate, CertificateTransfer)> {
+        self.ensure_not_on_dispute_hold(certificate_uuid).await?;
         self.transfer_manager
             .transfer_certificate(certificate_uuid, from_wallet, to_wallet, tx_signature)
             .await
@@ -247,6 +292,7 @@ impl ErcService {
     /// Retire certificate
     #[instrument(skip(self))]
     pub async fn retire_certificate(&self, certificate_uuid: Uuid) -> Result<ErcCertificate> {
+        self.ensure_not_on_dispute_hold(certificate_uuid).await?;
         self.retiring_manager
             .retire_certificate(certificate_uuid)
             .await
@@ -0,0 +1,251 @@
+//! Certificate issuance policy
+//!
+//! Checks an issuance request against the issuer's policy before a
+//! certificate is created: the issuer must be registered and active, the
+//! meter must be verified and of an allowed source, and the requested energy
+//! must be covered by metered generation within the issuer's vintage window
+//! that has not already been certified.
+
+use std::fmt;
+
+use anyhow::Result;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+use super::issuers::IssuerRegistry;
+use super::IssueErcRequest;
+
+/// Reason an issuance request is rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyViolation {
+    UnknownIssuer,
+    IssuerInactive,
+    DeviceRequired,
+    DeviceUnknown,
+    DeviceNotVerified,
+    SourceNotAllowed { source: String },
+    ExceedsMeteredEnergy { requested: Decimal, available: Decimal },
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownIssuer => write!(f, "issuer is not registered"),
+            Self::IssuerInactive => write!(f, "issuer has been revoked"),
+            Self::DeviceRequired => write!(f, "a meter is required"),
+            Self::DeviceUnknown => write!(f, "meter is not registered"),
+            Self::DeviceNotVerified => write!(f, "meter is not verified"),
+            Self::SourceNotAllowed { source } => {
+                write!(f, "source '{}' is not allowed for this issuer", source)
+            }
+            Self::ExceedsMeteredEnergy { requested, available } => write!(
+                f,
+                "{} kWh requested but only {} kWh of uncertified generation is available",
+                requested.normalize(),
+                available.normalize()
+            ),
+        }
+    }
+}
+
+/// Facts about a request gathered from the database
+#[derive(Debug, Clone, Default)]
+pub struct IssuanceFacts {
+    /// `None` when the issuer is not registered
+    pub issuer_active: Option<bool>,
+    pub allowed_sources: Vec<String>,
+    pub meter_serial: Option<String>,
+    /// `None` when the meter is not registered
+    pub meter_verified: Option<bool>,
+    pub source: String,
+    pub requested_kwh: Decimal,
+    /// Generation metered within the vintage window
+    pub metered_kwh: Decimal,
+    /// Energy already certified from the same meter within the window
+    pub certified_kwh: Decimal,
+}
+
+/// All policy violations for a request; empty when it may proceed
+pub fn evaluate(facts: &IssuanceFacts) -> Vec<PolicyViolation> {
+    let mut violations = Vec::new();
+
+    match facts.issuer_active {
+        None => violations.push(PolicyViolation::UnknownIssuer),
+        Some(false) => violations.push(PolicyViolation::IssuerInactive),
+        Some(true) => {
+            if !facts
+                .allowed_sources
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(&facts.source))
+            {
+                violations.push(PolicyViolation::SourceNotAllowed {
+                    source: facts.source.clone(),
+                });
+            }
+        }
+    }
+
+    if facts.meter_serial.is_none() {
+        violations.push(PolicyViolation::DeviceRequired);
+        return violations;
+    }
+    match facts.meter_verified {
+        None => violations.push(PolicyViolation::DeviceUnknown),
+        Some(false) => violations.push(PolicyViolation::DeviceNotVerified),
+        Some(true) => {
+            let available = (facts.metered_kwh - facts.certified_kwh).max(Decimal::ZERO);
+            if facts.requested_kwh > available {
+                violations.push(PolicyViolation::ExceedsMeteredEnergy {
+                    requested: facts.requested_kwh,
+                    available,
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+#[derive(Clone, Debug)]
+pub struct IssuancePolicy {
+    db: PgPool,
+    issuers: IssuerRegistry,
+}
+
+impl IssuancePolicy {
+    pub fn new(db: PgPool) -> Self {
+        Self {
+            issuers: IssuerRegistry::new(db.clone()),
+            db,
+        }
+    }
+
+    /// Policy violations for issuing `request` under `issuer_wallet`
+    pub async fn check(&self, issuer_wallet: &str, request: &IssueErcRequest) -> Result<Vec<PolicyViolation>> {
+        let issuer = self.issuers.find_by_key(issuer_wallet).await?;
+        let vintage_days = issuer.as_ref().map(|i| i.vintage_days).unwrap_or(365);
+
+        let metadata_source = request
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("renewable_source"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        let mut facts = IssuanceFacts {
+            issuer_active: issuer.as_ref().map(|i| i.active),
+            allowed_sources: issuer.map(|i| i.allowed_sources).unwrap_or_default(),
+            meter_serial: request.meter_id.clone(),
+            source: metadata_source.clone().unwrap_or_else(|| "Unknown".to_string()),
+            requested_kwh: request.kwh_amount,
+            ..Default::default()
+        };
+
+        if let Some(serial) = &request.meter_id {
+            let meter: Option<(Option<bool>, Option<String>)> =
+                sqlx::query_as("SELECT is_verified, meter_type FROM meters WHERE serial_number = $1")
+                    .bind(serial)
+                    .fetch_optional(&self.db)
+                    .await?;
+
+            if let Some((is_verified, meter_type)) = meter {
+                facts.meter_verified = Some(is_verified.unwrap_or(false));
+                // The registered meter type is authoritative over request metadata
+                if let Some(meter_type) = meter_type.filter(|t| !t.trim().is_empty()) {
+                    facts.source = meter_type;
+                }
+
+                let (metered, certified): (Decimal, Decimal) = sqlx::query_as(
+                    r#"
+                    SELECT
+                        (SELECT COALESCE(SUM(COALESCE(energy_generated, GREATEST(kwh_amount, 0))), 0)
+                         FROM meter_readings
+                         WHERE meter_serial = $1
+                           AND reading_timestamp >= NOW() - make_interval(days => $2)),
+                        (SELECT COALESCE(SUM(kwh_amount), 0)
+                         FROM erc_certificates
+                         WHERE meter_serial = $1
+                           AND status <> 'Revoked'
+                           AND issue_date >= NOW() - make_interval(days => $2))
+                    "#,
+                )
+                .bind(serial)
+                .bind(vintage_days)
+                .fetch_one(&self.db)
+                .await?;
+                facts.metered_kwh = metered;
+                facts.certified_kwh = certified;
+            }
+        }
+
+        Ok(evaluate(&facts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passing() -> IssuanceFacts {
+        IssuanceFacts {
+            issuer_active: Some(true),
+            allowed_sources: vec!["Solar".to_string(), "Wind".to_string()],
+            meter_serial: Some("M-001".to_string()),
+            meter_verified: Some(true),
+            source: "solar".to_string(),
+            requested_kwh: Decimal::from(10),
+            metered_kwh: Decimal::from(50),
+            certified_kwh: Decimal::from(30),
+        }
+    }
+
+    #[test]
+    fn test_allows_request_within_policy() {
+        assert!(evaluate(&passing()).is_empty());
+    }
+
+    #[test]
+    fn test_rejects_unknown_or_revoked_issuer() {
+        let facts = IssuanceFacts { issuer_active: None, ..passing() };
+        assert_eq!(evaluate(&facts), vec![PolicyViolation::UnknownIssuer]);
+
+        let facts = IssuanceFacts { issuer_active: Some(false), ..passing() };
+        assert_eq!(evaluate(&facts), vec![PolicyViolation::IssuerInactive]);
+    }
+
+    #[test]
+    fn test_rejects_disallowed_source() {
+        let facts = IssuanceFacts { source: "Diesel".to_string(), ..passing() };
+        assert_eq!(
+            evaluate(&facts),
+            vec![PolicyViolation::SourceNotAllowed { source: "Diesel".to_string() }]
+        );
+    }
+
+    #[test]
+    fn test_requires_verified_device() {
+        let facts = IssuanceFacts { meter_serial: None, ..passing() };
+        assert_eq!(evaluate(&facts), vec![PolicyViolation::DeviceRequired]);
+
+        let facts = IssuanceFacts { meter_verified: None, ..passing() };
+        assert_eq!(evaluate(&facts), vec![PolicyViolation::DeviceUnknown]);
+
+        let facts = IssuanceFacts { meter_verified: Some(false), ..passing() };
+        assert_eq!(evaluate(&facts), vec![PolicyViolation::DeviceNotVerified]);
+    }
+
+    #[test]
+    fn test_rejects_double_counting() {
+        let facts = IssuanceFacts { requested_kwh: Decimal::from(25), ..passing() };
+        assert_eq!(
+            evaluate(&facts),
+            vec![PolicyViolation::ExceedsMeteredEnergy { requested: Decimal::from(25), available: Decimal::from(20) }]
+        );
+
+        let facts = IssuanceFacts { certified_kwh: Decimal::from(80), ..passing() };
+        assert_eq!(
+            evaluate(&facts),
+            vec![PolicyViolation::ExceedsMeteredEnergy { requested: Decimal::from(10), available: Decimal::ZERO }]
+        );
+    }
+}
@@ -77,10 +77,26 @@ impl ErcQueryManager {
         .total
         .unwrap_or(rust_decimal::Decimal::ZERO);
 
+        // Expired certificates count towards neither active nor retired energy
+        let (active_kwh, retired_kwh): (rust_decimal::Decimal, rust_decimal::Decimal) = sqlx::query_as(
+            r#"
+            SELECT
+                COALESCE(SUM(kwh_amount) FILTER (
+                    WHERE status = 'Active' AND (expiry_date IS NULL OR expiry_date > NOW())
+                ), 0),
+                COALESCE(SUM(kwh_amount) FILTER (WHERE status = 'Retired'), 0)
+            FROM erc_certificates
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&self.db_pool)
+        .await?;
+
         Ok(CertificateStats {
             total_certificates,
-            active_kwh: rust_decimal::Decimal::ZERO, // Need to fetch active kwh?
-            retired_kwh: rust_decimal::Decimal::ZERO, // Need to fetch retired kwh?
+            active_kwh,
+            retired_kwh,
             total_kwh: total_energy,
         })
     }
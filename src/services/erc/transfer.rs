@@ -61,7 +61,8 @@ impl CertificateTransferManager {
             r#"
             UPDATE erc_certificates
             SET wallet_address = $2, status = 'Transferred'
-            WHERE id = $1
+            WHERE id = $1 AND status <> 'Expired'
+              AND (expiry_date IS NULL OR expiry_date > NOW())
             RETURNING
                 id, certificate_id,
                 user_id as "user_id?",
@@ -80,9 +81,10 @@ impl CertificateTransferManager {
             certificate_uuid,
             to_wallet,
         )
-        .fetch_one(&mut *tx)
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(|e| anyhow!("Failed to update certificate: {}", e))?;
+        .map_err(|e| anyhow!("Failed to update certificate: {}", e))?
+        .ok_or_else(|| anyhow!("Certificate not found or expired"))?;
 
         // Record transfer
         let transfer = sqlx::query_as!(
@@ -1,24 +1,55 @@
 pub mod types;
 
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::signature::Signature;
 use solana_transaction_status::UiTransactionEncoding;
 use sqlx::PgPool;
+use std::collections::{BTreeMap, VecDeque};
 use std::str::FromStr;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::Duration;
+use tokio::sync::Notify;
 use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 
 use crate::config::EventProcessorConfig;
+use crate::middleware::metrics::{track_event_processed, track_event_processor_lag};
 use crate::services::webhook::WebhookService;
 
 pub use types::*;
 
+/// Error samples kept for the health endpoint
+const MAX_ERROR_SAMPLES: usize = 20;
+
+/// Slots the processor is behind the chain head; caught up when nothing waits
+pub fn lag_slots(queue_depth: i64, chain_head: Option<u64>, last_processed: Option<u64>) -> Option<u64> {
+    if queue_depth == 0 {
+        return Some(0);
+    }
+    Some(chain_head?.saturating_sub(last_processed?))
+}
+
+/// Whether lag and queue age are within their limits; unknown values pass
+pub fn within_slo(lag: Option<u64>, max_lag: u64, oldest_pending_secs: Option<i64>, max_pending_secs: i64) -> bool {
+    lag.is_none_or(|lag| lag <= max_lag) && oldest_pending_secs.is_none_or(|age| age <= max_pending_secs)
+}
+
+/// Counters and last poll state behind `/health/event-processor`
+#[derive(Debug, Default)]
+struct Telemetry {
+    counts: BTreeMap<String, (u64, u64)>,
+    errors: VecDeque<EventErrorSample>,
+    last_poll_at: Option<DateTime<Utc>>,
+    chain_head_slot: Option<u64>,
+    last_processed_slot: Option<u64>,
+    queue_depth: i64,
+    oldest_pending_age_secs: Option<i64>,
+}
+
 #[derive(Clone)]
 pub struct EventProcessorService {
     rpc_client: Arc<RpcClient>,
@@ -30,7 +61,10 @@ pub struct EventProcessorService {
     // pubsub_client: Arc<PubsubClient>,
     retry_count: Arc<AtomicU64>,
     replay_status: Arc<Mutex<Option<ReplayStatus>>>,
+    telemetry: Arc<Mutex<Telemetry>>,
     webhook_service: WebhookService,
+    /// Signalled after each polling cycle
+    cycle_completed: Arc<Notify>,
 }
 
 impl EventProcessorService {
@@ -52,7 +86,9 @@ impl EventProcessorService {
             energy_token_mint,
             retry_count: Arc::new(AtomicU64::new(0)),
             replay_status: Arc::new(Mutex::new(None)),
+            telemetry: Arc::new(Mutex::new(Telemetry::default())),
             webhook_service,
+            cycle_completed: Arc::new(Notify::new()),
         }
     }
 
@@ -80,12 +116,19 @@ impl EventProcessorService {
             if let Err(e) = self.process_pending_transactions().await {
                 error!("Error processing pending transactions: {}", e);
             }
+            self.cycle_completed.notify_one();
         }
     }
 
+    /// Wait until the next polling cycle completes
+    pub async fn cycle_completed(&self) {
+        self.cycle_completed.notified().await;
+    }
+
     /// Process pending transactions that need confirmation
     async fn process_pending_transactions(&self) -> Result<()> {
         debug!("Processing pending transactions");
+        self.sample_lag().await;
 
         // Get pending minted readings that need confirmation
         let pending_readings = sqlx::query!(
@@ -139,8 +182,10 @@ impl EventProcessorService {
                             .await
                         {
                             error!("Failed to mark transaction as confirmed: {}", e);
+                            self.record_failure(EventType::TokenMint, Some(&signature_str), &e.to_string());
                             failed_count += 1;
                         } else {
+                            self.record_processed(EventType::TokenMint);
                             confirmed_count += 1;
                         }
                     } else {
@@ -149,6 +194,7 @@ impl EventProcessorService {
                 }
                 Err(e) => {
                     warn!("Error checking transaction {}: {}", signature_str, e);
+                    self.record_failure(EventType::TokenMint, Some(&signature_str), &e.to_string());
                     failed_count += 1;
                 }
             }
@@ -184,6 +230,8 @@ impl EventProcessorService {
                         if meta.err.is_none() {
                             // Transaction succeeded
 
+                            self.record_slot(tx.slot);
+
                             // Parse and store event
                             if let Err(e) = self
                                 .parse_and_store_event(tx.slot, tx.block_time, signature_str)
@@ -196,6 +244,11 @@ impl EventProcessorService {
                         } else {
                             // Transaction failed
                             warn!("Transaction {} failed: {:?}", signature_str, meta.err);
+                            self.record_failure(
+                                EventType::TokenMint,
+                                Some(signature_str),
+                                &format!("Transaction failed: {:?}", meta.err),
+                            );
                             return Ok(false);
                         }
                     }
@@ -440,6 +493,102 @@ impl EventProcessorService {
         }
     }
 
+    fn telemetry(&self) -> std::sync::MutexGuard<'_, Telemetry> {
+        self.telemetry.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn record_processed(&self, event_type: EventType) {
+        track_event_processed(event_type.as_str(), true);
+        self.telemetry().counts.entry(event_type.as_str().to_string()).or_default().0 += 1;
+    }
+
+    fn record_failure(&self, event_type: EventType, signature: Option<&str>, message: &str) {
+        track_event_processed(event_type.as_str(), false);
+        let mut telemetry = self.telemetry();
+        telemetry.counts.entry(event_type.as_str().to_string()).or_default().1 += 1;
+        telemetry.errors.push_front(EventErrorSample {
+            at: Utc::now(),
+            event_type: event_type.as_str().to_string(),
+            signature: signature.map(str::to_string),
+            message: message.to_string(),
+        });
+        telemetry.errors.truncate(MAX_ERROR_SAMPLES);
+    }
+
+    fn record_slot(&self, slot: u64) {
+        let mut telemetry = self.telemetry();
+        telemetry.last_processed_slot = Some(telemetry.last_processed_slot.map_or(slot, |last| last.max(slot)));
+    }
+
+    /// Sample the chain head and the confirmation queue, and publish the lag gauges
+    async fn sample_lag(&self) {
+        let queue = sqlx::query_as::<_, (i64, Option<i64>)>(
+            r#"
+            SELECT COUNT(*), EXTRACT(EPOCH FROM NOW() - MIN(submitted_at))::BIGINT
+            FROM meter_readings
+            WHERE minted = true
+              AND on_chain_confirmed = false
+              AND mint_tx_signature IS NOT NULL
+              AND mint_tx_signature != 'mock_signature'
+            "#,
+        )
+        .fetch_one(&*self.db)
+        .await;
+        let chain_head = self.rpc_client.get_slot();
+
+        let mut telemetry = self.telemetry();
+        telemetry.last_poll_at = Some(Utc::now());
+        match queue {
+            Ok((depth, oldest)) => {
+                telemetry.queue_depth = depth;
+                telemetry.oldest_pending_age_secs = oldest;
+            }
+            Err(e) => warn!("Failed to sample event processor queue: {}", e),
+        }
+        match chain_head {
+            Ok(slot) => telemetry.chain_head_slot = Some(slot),
+            Err(e) => warn!("Failed to fetch chain head slot: {}", e),
+        }
+
+        let lag = lag_slots(telemetry.queue_depth, telemetry.chain_head_slot, telemetry.last_processed_slot);
+        track_event_processor_lag(lag, telemetry.queue_depth, telemetry.oldest_pending_age_secs);
+    }
+
+    /// Internals and lag SLO as of the last poll
+    pub fn health(&self) -> EventProcessorHealth {
+        let telemetry = self.telemetry();
+        let lag = lag_slots(telemetry.queue_depth, telemetry.chain_head_slot, telemetry.last_processed_slot);
+        EventProcessorHealth {
+            enabled: self.config.enabled,
+            healthy: !self.config.enabled
+                || within_slo(
+                    lag,
+                    self.config.max_lag_slots,
+                    telemetry.oldest_pending_age_secs,
+                    self.config.max_pending_age_secs,
+                ),
+            last_poll_at: telemetry.last_poll_at,
+            chain_head_slot: telemetry.chain_head_slot,
+            last_processed_slot: telemetry.last_processed_slot,
+            lag_slots: lag,
+            max_lag_slots: self.config.max_lag_slots,
+            queue_depth: telemetry.queue_depth,
+            oldest_pending_age_secs: telemetry.oldest_pending_age_secs,
+            max_pending_age_secs: self.config.max_pending_age_secs,
+            total_retries: self.retry_count.load(Ordering::Relaxed),
+            events: telemetry
+                .counts
+                .iter()
+                .map(|(event_type, (processed, failed))| EventTypeCounts {
+                    event_type: event_type.clone(),
+                    processed: *processed,
+                    failed: *failed,
+                })
+                .collect(),
+            recent_errors: telemetry.errors.iter().cloned().collect(),
+        }
+    }
+
     /// Get processing statistics
     pub async fn get_stats(&self) -> Result<EventProcessorStats> {
         let total_events = sqlx::query_scalar!("SELECT COUNT(*) FROM blockchain_events")
@@ -475,3 +624,23 @@ impl EventProcessorService {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lag_is_zero_when_queue_is_empty() {
+        assert_eq!(lag_slots(0, Some(1_000), Some(10)), Some(0));
+        assert_eq!(lag_slots(5, Some(1_000), Some(900)), Some(100));
+        assert_eq!(lag_slots(5, Some(1_000), None), None);
+    }
+
+    #[test]
+    fn test_slo_checks_lag_and_queue_age() {
+        assert!(within_slo(Some(100), 150, Some(60), 600));
+        assert!(!within_slo(Some(200), 150, Some(60), 600));
+        assert!(!within_slo(Some(0), 150, Some(900), 600));
+        assert!(within_slo(None, 150, None, 600));
+    }
+}
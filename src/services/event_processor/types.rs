@@ -55,3 +55,43 @@ pub struct EventProcessorStats {
     pub pending_confirmations: i64,
     pub total_retries: u64,
 }
+
+/// Processed and failed events of one type since startup
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct EventTypeCounts {
+    pub event_type: String,
+    pub processed: u64,
+    pub failed: u64,
+}
+
+/// A recent processing failure
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EventErrorSample {
+    pub at: DateTime<Utc>,
+    pub event_type: String,
+    pub signature: Option<String>,
+    pub message: String,
+}
+
+/// Event processor internals, as of its last poll, and its lag SLO
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EventProcessorHealth {
+    pub enabled: bool,
+    /// Lag and queue age are within the SLO
+    pub healthy: bool,
+    pub last_poll_at: Option<DateTime<Utc>>,
+    pub chain_head_slot: Option<u64>,
+    pub last_processed_slot: Option<u64>,
+    /// Slots between the chain head and the last processed event; 0 when
+    /// nothing is waiting
+    pub lag_slots: Option<u64>,
+    pub max_lag_slots: u64,
+    /// Minted readings awaiting on-chain confirmation
+    pub queue_depth: i64,
+    pub oldest_pending_age_secs: Option<i64>,
+    pub max_pending_age_secs: i64,
+    pub total_retries: u64,
+    pub events: Vec<EventTypeCounts>,
+    /// Newest first
+    pub recent_errors: Vec<EventErrorSample>,
+}
@@ -0,0 +1,647 @@
+//! Fee Sponsorship
+//!
+//! Decides whether the platform payer covers the network fee of a user's
+//! token transfer. Members of an active sponsorship program are covered from
+//! the program's budget (and their monthly share of it); everyone else gets
+//! their first `free_transfers` transfers covered, up to a monthly budget.
+//! Abuse limits apply on top of both: a daily count of sponsored transfers
+//! per user, a platform-wide daily budget and a minimum transfer size.
+//!
+//! Every sponsored transfer is recorded in `fee_sponsorships`. The estimated
+//! fee is reserved, under a lock on the user, before the transfer is sent,
+//! and replaced by the fee actually charged once it lands; a failed transfer
+//! releases it. Budgets are counted from that ledger.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::FeeSponsorshipConfig;
+use crate::error::{ApiError, Result};
+use crate::services::{AuditEvent, AuditLogger};
+
+/// Fees counted against budgets: the charged fee once known, else the reservation
+const SPENT: &str = "COALESCE(s.fee_lamports, s.reserved_lamports)";
+
+const PROGRAM_COLUMNS: &str = "p.id, p.name, p.sponsor, p.budget_lamports, p.member_monthly_lamports, \
+    p.starts_at, p.ends_at, p.active, p.created_at, \
+    (SELECT COUNT(*) FROM fee_sponsorship_members m WHERE m.program_id = p.id) AS members, \
+    COALESCE((SELECT SUM(COALESCE(s.fee_lamports, s.reserved_lamports)) FROM fee_sponsorships s \
+              WHERE s.program_id = p.id AND s.status <> 'released'), 0)::BIGINT AS spent_lamports";
+
+const SPONSORSHIP_COLUMNS: &str = "id, user_id, rule, program_id, recipient, amount_kwh, fee_payer, \
+    reserved_lamports, fee_lamports, tx_signature, status, created_at, settled_at";
+
+/// Who pays for a sponsored transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SponsorshipRule {
+    /// One of the user's free transfers, paid by the platform
+    FreeTier,
+    /// Charged to a sponsorship program the user belongs to
+    Program,
+}
+
+impl SponsorshipRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FreeTier => "free_tier",
+            Self::Program => "program",
+        }
+    }
+}
+
+/// Why a transfer is not sponsored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeclineReason {
+    Disabled,
+    BelowMinimum,
+    /// The user reached the sponsored transfers allowed per day
+    DailyLimit,
+    /// The platform-wide daily budget is spent
+    PlatformBudget,
+    FreeTransfersUsed,
+    /// The user's free-tier budget for the month is spent
+    MonthlyBudget,
+}
+
+/// A user's sponsored transfers so far
+#[derive(Debug, Clone, Default, FromRow)]
+pub struct SponsorshipUsage {
+    /// Free-tier transfers ever sponsored
+    pub free_used: i64,
+    /// Sponsored transfers today, any rule
+    pub sponsored_today: i64,
+    /// Free-tier fees this month
+    pub month_lamports: i64,
+}
+
+/// A program the user belongs to, with what is left of it
+#[derive(Debug, Clone, FromRow)]
+pub struct ProgramAllowance {
+    pub id: Uuid,
+    pub name: String,
+    pub budget_lamports: i64,
+    pub spent_lamports: i64,
+    pub member_monthly_lamports: Option<i64>,
+    /// The user's fees charged to the program this month
+    pub member_month_lamports: i64,
+}
+
+impl ProgramAllowance {
+    fn covers(&self, fee: i64) -> bool {
+        self.spent_lamports + fee <= self.budget_lamports
+            && self
+                .member_monthly_lamports
+                .is_none_or(|cap| self.member_month_lamports + fee <= cap)
+    }
+}
+
+/// Whether a transfer is sponsored, and by whom
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SponsorshipDecision {
+    pub sponsored: bool,
+    pub rule: Option<SponsorshipRule>,
+    pub program_id: Option<Uuid>,
+    pub program_name: Option<String>,
+    /// Set when not sponsored
+    pub reason: Option<DeclineReason>,
+}
+
+impl SponsorshipDecision {
+    fn declined(reason: DeclineReason) -> Self {
+        Self {
+            sponsored: false,
+            rule: None,
+            program_id: None,
+            program_name: None,
+            reason: Some(reason),
+        }
+    }
+}
+
+/// Apply the sponsorship policy to a transfer. Programs are tried first, in
+/// the order given, then the free tier.
+pub fn decide(
+    config: &FeeSponsorshipConfig,
+    amount_kwh: Decimal,
+    usage: &SponsorshipUsage,
+    platform_today_lamports: i64,
+    programs: &[ProgramAllowance],
+) -> SponsorshipDecision {
+    let fee = config.fee_estimate_lamports;
+    if !config.enabled {
+        return SponsorshipDecision::declined(DeclineReason::Disabled);
+    }
+    if amount_kwh < config.min_transfer_kwh {
+        return SponsorshipDecision::declined(DeclineReason::BelowMinimum);
+    }
+    if usage.sponsored_today >= config.daily_transfer_limit {
+        return SponsorshipDecision::declined(DeclineReason::DailyLimit);
+    }
+    if config.daily_budget_lamports > 0 && platform_today_lamports + fee > config.daily_budget_lamports {
+        return SponsorshipDecision::declined(DeclineReason::PlatformBudget);
+    }
+    if let Some(program) = programs.iter().find(|program| program.covers(fee)) {
+        return SponsorshipDecision {
+            sponsored: true,
+            rule: Some(SponsorshipRule::Program),
+            program_id: Some(program.id),
+            program_name: Some(program.name.clone()),
+            reason: None,
+        };
+    }
+    if usage.free_used >= config.free_transfers {
+        return SponsorshipDecision::declined(DeclineReason::FreeTransfersUsed);
+    }
+    if usage.month_lamports + fee > config.user_monthly_lamports {
+        return SponsorshipDecision::declined(DeclineReason::MonthlyBudget);
+    }
+    SponsorshipDecision {
+        sponsored: true,
+        rule: Some(SponsorshipRule::FreeTier),
+        program_id: None,
+        program_name: None,
+        reason: None,
+    }
+}
+
+/// What the user's next transfer would cost them
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SponsorshipQuote {
+    #[serde(flatten)]
+    pub decision: SponsorshipDecision,
+    pub free_transfers_left: i64,
+    /// Free-tier fees left this month
+    pub monthly_lamports_left: i64,
+    pub sponsored_today: i64,
+    pub daily_transfer_limit: i64,
+}
+
+/// A sponsored transfer in the ledger
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct FeeSponsorship {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// free_tier or program
+    pub rule: String,
+    pub program_id: Option<Uuid>,
+    pub recipient: String,
+    pub amount_kwh: Decimal,
+    pub fee_payer: String,
+    pub reserved_lamports: i64,
+    /// Fee charged, once the transfer landed
+    pub fee_lamports: Option<i64>,
+    pub tx_signature: Option<String>,
+    /// reserved, charged or released
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub settled_at: Option<DateTime<Utc>>,
+}
+
+/// A corporate-sponsored fee program
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct SponsorshipProgram {
+    pub id: Uuid,
+    pub name: String,
+    pub sponsor: String,
+    pub budget_lamports: i64,
+    /// Cap per member per calendar month
+    pub member_monthly_lamports: Option<i64>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub members: i64,
+    /// Fees charged or reserved so far
+    pub spent_lamports: i64,
+}
+
+/// New program settings; `None` keeps the current value on update
+#[derive(Debug, Clone, Default)]
+pub struct ProgramChanges {
+    pub budget_lamports: Option<i64>,
+    pub member_monthly_lamports: Option<i64>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub active: Option<bool>,
+}
+
+#[derive(Clone)]
+pub struct FeeSponsorshipService {
+    db: PgPool,
+    config: FeeSponsorshipConfig,
+    audit_logger: AuditLogger,
+}
+
+impl FeeSponsorshipService {
+    pub fn new(db: PgPool, config: FeeSponsorshipConfig, audit_logger: AuditLogger) -> Self {
+        Self {
+            db,
+            config,
+            audit_logger,
+        }
+    }
+
+    /// Whether a transfer of `amount_kwh` would be sponsored now, without reserving anything
+    pub async fn quote(&self, user_id: Uuid, amount_kwh: Decimal) -> Result<SponsorshipQuote> {
+        let mut tx = self.db.begin().await?;
+        let (usage, platform_today, programs) = self.load(&mut tx, user_id, false).await?;
+        tx.commit().await?;
+
+        Ok(SponsorshipQuote {
+            decision: decide(&self.config, amount_kwh, &usage, platform_today, &programs),
+            free_transfers_left: (self.config.free_transfers - usage.free_used).max(0),
+            monthly_lamports_left: (self.config.user_monthly_lamports - usage.month_lamports).max(0),
+            sponsored_today: usage.sponsored_today,
+            daily_transfer_limit: self.config.daily_transfer_limit,
+        })
+    }
+
+    /// Reserve the fee of a transfer when the policy sponsors it. Returns the
+    /// reservation to charge or release once the transfer is sent.
+    pub async fn reserve(
+        &self,
+        user_id: Uuid,
+        recipient: &str,
+        amount_kwh: Decimal,
+        fee_payer: &str,
+    ) -> Result<Option<FeeSponsorship>> {
+        let mut tx = self.db.begin().await?;
+        let (usage, platform_today, programs) = self.load(&mut tx, user_id, true).await?;
+        let decision = decide(&self.config, amount_kwh, &usage, platform_today, &programs);
+        let Some(rule) = decision.rule else {
+            tx.commit().await?;
+            return Ok(None);
+        };
+
+        let sponsorship = sqlx::query_as::<_, FeeSponsorship>(&format!(
+            r#"
+            INSERT INTO fee_sponsorships (user_id, rule, program_id, recipient, amount_kwh, fee_payer, reserved_lamports)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            SPONSORSHIP_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(rule.as_str())
+        .bind(decision.program_id)
+        .bind(recipient)
+        .bind(amount_kwh)
+        .bind(fee_payer)
+        .bind(self.config.fee_estimate_lamports)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(Some(sponsorship))
+    }
+
+    /// Record the fee a sponsored transfer was charged; the reservation stands
+    /// when the fee could not be read
+    pub async fn charge(&self, id: Uuid, tx_signature: &str, fee_lamports: Option<u64>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE fee_sponsorships
+            SET status = 'charged', tx_signature = $2, fee_lamports = COALESCE($3, reserved_lamports), settled_at = NOW()
+            WHERE id = $1 AND status = 'reserved'
+            "#,
+        )
+        .bind(id)
+        .bind(tx_signature)
+        .bind(fee_lamports.map(|fee| fee as i64))
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Give back the reservation of a transfer that was not sent
+    pub async fn release(&self, id: Uuid) {
+        if let Err(e) = sqlx::query(
+            "UPDATE fee_sponsorships SET status = 'released', settled_at = NOW() WHERE id = $1 AND status = 'reserved'",
+        )
+        .bind(id)
+        .execute(&self.db)
+        .await
+        {
+            warn!("⚠️ Failed to release fee sponsorship {}: {}", id, e);
+        }
+    }
+
+    /// Usage of the user and the platform, and the user's open programs.
+    /// With `lock`, concurrent reservations for the user and their programs wait.
+    async fn load(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        lock: bool,
+    ) -> Result<(SponsorshipUsage, i64, Vec<ProgramAllowance>)> {
+        if lock {
+            sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+                .bind(user_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        let programs = sqlx::query_as::<_, ProgramAllowance>(&format!(
+            r#"
+            SELECT p.id, p.name, p.budget_lamports, p.member_monthly_lamports,
+                   COALESCE((SELECT SUM({spent}) FROM fee_sponsorships s
+                             WHERE s.program_id = p.id AND s.status <> 'released'), 0)::BIGINT AS spent_lamports,
+                   COALESCE((SELECT SUM({spent}) FROM fee_sponsorships s
+                             WHERE s.program_id = p.id AND s.user_id = $1 AND s.status <> 'released'
+                               AND s.created_at >= date_trunc('month', NOW())), 0)::BIGINT AS member_month_lamports
+            FROM fee_sponsorship_programs p
+            JOIN fee_sponsorship_members m ON m.program_id = p.id AND m.user_id = $1
+            WHERE p.active AND p.starts_at <= NOW() AND (p.ends_at IS NULL OR p.ends_at > NOW())
+            ORDER BY p.created_at
+            {lock}
+            "#,
+            spent = SPENT,
+            lock = if lock { "FOR UPDATE OF p" } else { "" }
+        ))
+        .bind(user_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        let usage = sqlx::query_as::<_, SponsorshipUsage>(&format!(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE s.rule = 'free_tier') AS free_used,
+                   COUNT(*) FILTER (WHERE s.created_at >= date_trunc('day', NOW())) AS sponsored_today,
+                   COALESCE(SUM({spent}) FILTER (
+                       WHERE s.rule = 'free_tier' AND s.created_at >= date_trunc('month', NOW())
+                   ), 0)::BIGINT AS month_lamports
+            FROM fee_sponsorships s
+            WHERE s.user_id = $1 AND s.status <> 'released'
+            "#,
+            spent = SPENT
+        ))
+        .bind(user_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        let platform_today: i64 = sqlx::query_scalar(&format!(
+            r#"
+            SELECT COALESCE(SUM({spent}), 0)::BIGINT FROM fee_sponsorships s
+            WHERE s.status <> 'released' AND s.created_at >= date_trunc('day', NOW())
+            "#,
+            spent = SPENT
+        ))
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok((usage, platform_today, programs))
+    }
+
+    /// Sponsored transfers, newest first
+    pub async fn list_sponsorships(
+        &self,
+        user_id: Option<Uuid>,
+        program_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<FeeSponsorship>> {
+        Ok(sqlx::query_as::<_, FeeSponsorship>(&format!(
+            r#"
+            SELECT {}
+            FROM fee_sponsorships
+            WHERE ($1::uuid IS NULL OR user_id = $1) AND ($2::uuid IS NULL OR program_id = $2)
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+            SPONSORSHIP_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(program_id)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn list_programs(&self) -> Result<Vec<SponsorshipProgram>> {
+        Ok(sqlx::query_as::<_, SponsorshipProgram>(&format!(
+            "SELECT {} FROM fee_sponsorship_programs p ORDER BY p.created_at DESC",
+            PROGRAM_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn get_program(&self, program_id: Uuid) -> Result<SponsorshipProgram> {
+        sqlx::query_as::<_, SponsorshipProgram>(&format!(
+            "SELECT {} FROM fee_sponsorship_programs p WHERE p.id = $1",
+            PROGRAM_COLUMNS
+        ))
+        .bind(program_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Sponsorship program not found".to_string()))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_program(
+        &self,
+        admin_id: Uuid,
+        name: &str,
+        sponsor: &str,
+        budget_lamports: i64,
+        member_monthly_lamports: Option<i64>,
+        starts_at: Option<DateTime<Utc>>,
+        ends_at: Option<DateTime<Utc>>,
+    ) -> Result<SponsorshipProgram> {
+        if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+            if ends_at <= starts_at {
+                return Err(ApiError::validation_field("ends_at", "ends_at must be after starts_at"));
+            }
+        }
+
+        let program_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO fee_sponsorship_programs
+                (name, sponsor, budget_lamports, member_monthly_lamports, starts_at, ends_at, created_by)
+            VALUES ($1, $2, $3, $4, COALESCE($5, NOW()), $6, $7)
+            RETURNING id
+            "#,
+        )
+        .bind(name.trim())
+        .bind(sponsor.trim())
+        .bind(budget_lamports)
+        .bind(member_monthly_lamports)
+        .bind(starts_at)
+        .bind(ends_at)
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        let program = self.get_program(program_id).await?;
+        self.audit(admin_id, "fee_sponsorship_program_created", &program, None);
+        info!(
+            "💸 Fee sponsorship program \"{}\" ({}) created with {} lamports",
+            program.name, program.sponsor, program.budget_lamports
+        );
+        Ok(program)
+    }
+
+    pub async fn update_program(
+        &self,
+        admin_id: Uuid,
+        program_id: Uuid,
+        changes: &ProgramChanges,
+    ) -> Result<SponsorshipProgram> {
+        let updated = sqlx::query(
+            r#"
+            UPDATE fee_sponsorship_programs
+            SET budget_lamports = COALESCE($2, budget_lamports),
+                member_monthly_lamports = COALESCE($3, member_monthly_lamports),
+                ends_at = COALESCE($4, ends_at),
+                active = COALESCE($5, active),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(program_id)
+        .bind(changes.budget_lamports)
+        .bind(changes.member_monthly_lamports)
+        .bind(changes.ends_at)
+        .bind(changes.active)
+        .execute(&self.db)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(ApiError::NotFound("Sponsorship program not found".to_string()));
+        }
+
+        let program = self.get_program(program_id).await?;
+        self.audit(admin_id, "fee_sponsorship_program_updated", &program, None);
+        Ok(program)
+    }
+
+    pub async fn add_member(&self, admin_id: Uuid, program_id: Uuid, user_id: Uuid) -> Result<SponsorshipProgram> {
+        let program = self.get_program(program_id).await?;
+        let user_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        if !user_exists {
+            return Err(ApiError::NotFound("User not found".to_string()));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO fee_sponsorship_members (program_id, user_id, added_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (program_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(program_id)
+        .bind(user_id)
+        .bind(admin_id)
+        .execute(&self.db)
+        .await?;
+
+        self.audit(admin_id, "fee_sponsorship_member_added", &program, Some(user_id));
+        self.get_program(program_id).await
+    }
+
+    pub async fn remove_member(&self, admin_id: Uuid, program_id: Uuid, user_id: Uuid) -> Result<SponsorshipProgram> {
+        let removed = sqlx::query("DELETE FROM fee_sponsorship_members WHERE program_id = $1 AND user_id = $2")
+            .bind(program_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        if removed.rows_affected() == 0 {
+            return Err(ApiError::NotFound("User is not a member of the program".to_string()));
+        }
+
+        let program = self.get_program(program_id).await?;
+        self.audit(admin_id, "fee_sponsorship_member_removed", &program, Some(user_id));
+        Ok(program)
+    }
+
+    fn audit(&self, admin_id: Uuid, action: &str, program: &SponsorshipProgram, target_user_id: Option<Uuid>) {
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id,
+            details: serde_json::json!({
+                "program_id": program.id,
+                "name": program.name,
+                "sponsor": program.sponsor,
+                "budget_lamports": program.budget_lamports,
+                "member_monthly_lamports": program.member_monthly_lamports,
+                "ends_at": program.ends_at,
+                "active": program.active,
+            })
+            .to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> FeeSponsorshipConfig {
+        FeeSponsorshipConfig {
+            enabled: true,
+            free_transfers: 5,
+            user_monthly_lamports: 100_000,
+            daily_transfer_limit: 10,
+            daily_budget_lamports: 1_000_000,
+            min_transfer_kwh: Decimal::new(1, 1),
+            fee_estimate_lamports: 10_000,
+        }
+    }
+
+    fn program(spent_lamports: i64, member_month_lamports: i64) -> ProgramAllowance {
+        ProgramAllowance {
+            id: Uuid::nil(),
+            name: "Acme employees".to_string(),
+            budget_lamports: 50_000,
+            spent_lamports,
+            member_monthly_lamports: Some(20_000),
+            member_month_lamports,
+        }
+    }
+
+    #[test]
+    fn test_free_tier_covers_first_transfers() {
+        let usage = SponsorshipUsage::default();
+        let decision = decide(&config(), Decimal::ONE, &usage, 0, &[]);
+        assert_eq!(decision.rule, Some(SponsorshipRule::FreeTier));
+
+        let used_up = SponsorshipUsage { free_used: 5, ..Default::default() };
+        let decision = decide(&config(), Decimal::ONE, &used_up, 0, &[]);
+        assert_eq!(decision.reason, Some(DeclineReason::FreeTransfersUsed));
+
+        let over_budget = SponsorshipUsage { month_lamports: 95_000, ..Default::default() };
+        let decision = decide(&config(), Decimal::ONE, &over_budget, 0, &[]);
+        assert_eq!(decision.reason, Some(DeclineReason::MonthlyBudget));
+    }
+
+    #[test]
+    fn test_programs_come_before_free_tier_while_budget_lasts() {
+        let usage = SponsorshipUsage { free_used: 5, ..Default::default() };
+        let decision = decide(&config(), Decimal::ONE, &usage, 0, &[program(0, 0)]);
+        assert_eq!(decision.rule, Some(SponsorshipRule::Program));
+
+        // Program budget spent, then the member's monthly share
+        let decision = decide(&config(), Decimal::ONE, &usage, 0, &[program(45_000, 0)]);
+        assert_eq!(decision.reason, Some(DeclineReason::FreeTransfersUsed));
+        let decision = decide(&config(), Decimal::ONE, &SponsorshipUsage::default(), 0, &[program(0, 15_000)]);
+        assert_eq!(decision.rule, Some(SponsorshipRule::FreeTier));
+    }
+
+    #[test]
+    fn test_abuse_limits_apply_to_every_rule() {
+        let busy = SponsorshipUsage { sponsored_today: 10, ..Default::default() };
+        assert_eq!(decide(&config(), Decimal::ONE, &busy, 0, &[program(0, 0)]).reason, Some(DeclineReason::DailyLimit));
+        let idle = SponsorshipUsage::default();
+        assert_eq!(decide(&config(), Decimal::new(1, 2), &idle, 0, &[]).reason, Some(DeclineReason::BelowMinimum));
+        assert_eq!(decide(&config(), Decimal::ONE, &idle, 995_000, &[]).reason, Some(DeclineReason::PlatformBudget));
+        let disabled = FeeSponsorshipConfig { enabled: false, ..config() };
+        assert_eq!(decide(&disabled, Decimal::ONE, &idle, 0, &[]).reason, Some(DeclineReason::Disabled));
+    }
+}
@@ -0,0 +1,380 @@
+//! FIX Session Registry
+//!
+//! Counterparty sessions of the FIX gateway: credentials, the user their
+//! orders are placed as, persisted inbound/outbound sequence numbers, and the
+//! ClOrdID to order mapping used for cancels and execution reports. Also
+//! tracks which sessions are currently logged on so a CompID can only be
+//! connected once.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::auth::password::PasswordService;
+use crate::database::schema::types::OrderSide;
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FixSessionType {
+    /// Enters and cancels orders, receives reports for its own orders
+    OrderEntry,
+    /// Receives reports for all of the user's orders, whatever the channel
+    DropCopy,
+}
+
+impl FixSessionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OrderEntry => "order_entry",
+            Self::DropCopy => "drop_copy",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct FixSession {
+    pub id: Uuid,
+    pub sender_comp_id: String,
+    pub user_id: Uuid,
+    /// order_entry or drop_copy
+    pub session_type: String,
+    /// MsgSeqNum expected on the next inbound message
+    pub next_in_seq: i64,
+    /// MsgSeqNum of the next outbound message
+    pub next_out_seq: i64,
+    pub is_active: bool,
+    pub last_logon_at: Option<DateTime<Utc>>,
+    pub last_logout_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Logged on to this instance right now
+    #[sqlx(skip)]
+    pub connected: bool,
+}
+
+impl FixSession {
+    pub fn is_drop_copy(&self) -> bool {
+        self.session_type == FixSessionType::DropCopy.as_str()
+    }
+}
+
+/// Order details needed to build an execution report
+#[derive(Debug, Clone, FromRow)]
+pub struct FixOrderContext {
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub side: OrderSide,
+    pub energy_amount: Decimal,
+    pub price_per_kwh: Decimal,
+    /// Market code, sent as Symbol
+    pub symbol: String,
+    pub session_id: Option<Uuid>,
+    pub cl_ord_id: Option<String>,
+}
+
+const SESSION_COLUMNS: &str = "id, sender_comp_id, user_id, session_type, next_in_seq, next_out_seq, \
+    is_active, last_logon_at, last_logout_at, created_at, updated_at";
+
+/// CompIDs are sent verbatim in every header
+fn validate_comp_id(comp_id: &str) -> Result<()> {
+    if comp_id.is_empty()
+        || comp_id.len() > 64
+        || !comp_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    {
+        return Err(ApiError::validation_field(
+            "sender_comp_id",
+            "CompID must be 1-64 letters, digits, '-', '_' or '.'",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub struct FixSessionService {
+    db: PgPool,
+    online: Arc<Mutex<HashSet<Uuid>>>,
+}
+
+impl FixSessionService {
+    pub fn new(db: PgPool) -> Self {
+        Self {
+            db,
+            online: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    fn mark_connected(&self, mut session: FixSession) -> FixSession {
+        let online = self.online.lock().unwrap_or_else(|e| e.into_inner());
+        session.connected = online.contains(&session.id);
+        session
+    }
+
+    pub async fn list(&self) -> Result<Vec<FixSession>> {
+        let sessions = sqlx::query_as::<_, FixSession>(&format!(
+            "SELECT {} FROM fix_sessions ORDER BY sender_comp_id",
+            SESSION_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        Ok(sessions.into_iter().map(|s| self.mark_connected(s)).collect())
+    }
+
+    pub async fn get(&self, session_id: Uuid) -> Result<FixSession> {
+        sqlx::query_as::<_, FixSession>(&format!("SELECT {} FROM fix_sessions WHERE id = $1", SESSION_COLUMNS))
+            .bind(session_id)
+            .fetch_optional(&self.db)
+            .await?
+            .map(|s| self.mark_connected(s))
+            .ok_or_else(|| ApiError::NotFound("FIX session not found".to_string()))
+    }
+
+    pub async fn create(
+        &self,
+        admin_id: Uuid,
+        sender_comp_id: &str,
+        user_id: Uuid,
+        session_type: FixSessionType,
+        password: &str,
+        audit_logger: &AuditLogger,
+    ) -> Result<FixSession> {
+        let sender_comp_id = sender_comp_id.trim();
+        validate_comp_id(sender_comp_id)?;
+        let password_hash = PasswordService::hash_password(password)?;
+
+        let user_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        if !user_exists {
+            return Err(ApiError::validation_field("user_id", "User not found"));
+        }
+
+        let session = sqlx::query_as::<_, FixSession>(&format!(
+            r#"
+            INSERT INTO fix_sessions (sender_comp_id, user_id, session_type, password_hash, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (sender_comp_id) DO NOTHING
+            RETURNING {}
+            "#,
+            SESSION_COLUMNS
+        ))
+        .bind(sender_comp_id)
+        .bind(user_id)
+        .bind(session_type.as_str())
+        .bind(password_hash)
+        .bind(admin_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::Conflict(format!("FIX session {} already exists", sender_comp_id)))?;
+
+        info!("🔌 Admin {} created FIX {} session {}", admin_id, session.session_type, session.sender_comp_id);
+        self.audit(admin_id, "fix_session_created", &session, audit_logger);
+        Ok(session)
+    }
+
+    /// Set the sequence numbers of a logged-out session, e.g. after a counterparty reset
+    pub async fn reset_sequence(
+        &self,
+        admin_id: Uuid,
+        session_id: Uuid,
+        next_in_seq: i64,
+        next_out_seq: i64,
+        audit_logger: &AuditLogger,
+    ) -> Result<FixSession> {
+        if next_in_seq < 1 || next_out_seq < 1 {
+            return Err(ApiError::validation_field("next_in_seq", "Sequence numbers start at 1"));
+        }
+        if self.get(session_id).await?.connected {
+            return Err(ApiError::Conflict("Session is logged on; log it out first".to_string()));
+        }
+
+        let session = sqlx::query_as::<_, FixSession>(&format!(
+            "UPDATE fix_sessions SET next_in_seq = $2, next_out_seq = $3, updated_at = NOW() WHERE id = $1 RETURNING {}",
+            SESSION_COLUMNS
+        ))
+        .bind(session_id)
+        .bind(next_in_seq)
+        .bind(next_out_seq)
+        .fetch_one(&self.db)
+        .await?;
+
+        self.audit(admin_id, "fix_session_sequence_reset", &session, audit_logger);
+        Ok(session)
+    }
+
+    /// Enable or disable a session; disabled sessions are refused at logon
+    pub async fn set_active(
+        &self,
+        admin_id: Uuid,
+        session_id: Uuid,
+        is_active: bool,
+        audit_logger: &AuditLogger,
+    ) -> Result<FixSession> {
+        let session = sqlx::query_as::<_, FixSession>(&format!(
+            "UPDATE fix_sessions SET is_active = $2, updated_at = NOW() WHERE id = $1 RETURNING {}",
+            SESSION_COLUMNS
+        ))
+        .bind(session_id)
+        .bind(is_active)
+        .fetch_optional(&self.db)
+        .await?
+        .map(|s| self.mark_connected(s))
+        .ok_or_else(|| ApiError::NotFound("FIX session not found".to_string()))?;
+
+        let action = if is_active { "fix_session_enabled" } else { "fix_session_disabled" };
+        self.audit(admin_id, action, &session, audit_logger);
+        Ok(session)
+    }
+
+    /// Active session of `sender_comp_id` if the password matches
+    pub async fn authenticate(&self, sender_comp_id: &str, password: &str) -> Result<Option<FixSession>> {
+        let row = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT id, password_hash FROM fix_sessions WHERE sender_comp_id = $1 AND is_active",
+        )
+        .bind(sender_comp_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        match row {
+            Some((id, hash)) if PasswordService::verify_password(password, &hash)? => Ok(Some(self.get(id).await?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Mark a session logged on; false if it already is
+    pub fn claim(&self, session_id: Uuid) -> bool {
+        self.online.lock().unwrap_or_else(|e| e.into_inner()).insert(session_id)
+    }
+
+    pub fn release(&self, session_id: Uuid) {
+        self.online.lock().unwrap_or_else(|e| e.into_inner()).remove(&session_id);
+    }
+
+    pub async fn record_logon(&self, session_id: Uuid, reset_seq: bool) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE fix_sessions
+            SET last_logon_at = NOW(),
+                next_in_seq = CASE WHEN $2 THEN 1 ELSE next_in_seq END,
+                next_out_seq = CASE WHEN $2 THEN 1 ELSE next_out_seq END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(session_id)
+        .bind(reset_seq)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_logout(&self, session_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE fix_sessions SET last_logout_at = NOW(), updated_at = NOW() WHERE id = $1")
+            .bind(session_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_next_in(&self, session_id: Uuid, next_in_seq: u64) -> Result<()> {
+        sqlx::query("UPDATE fix_sessions SET next_in_seq = $2 WHERE id = $1")
+            .bind(session_id)
+            .bind(next_in_seq as i64)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_next_out(&self, session_id: Uuid, next_out_seq: u64) -> Result<()> {
+        sqlx::query("UPDATE fix_sessions SET next_out_seq = $2 WHERE id = $1")
+            .bind(session_id)
+            .bind(next_out_seq as i64)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Whether the session already used a ClOrdID
+    pub async fn cl_ord_id_used(&self, session_id: Uuid, cl_ord_id: &str) -> Result<bool> {
+        Ok(sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM fix_orders WHERE session_id = $1 AND cl_ord_id = $2)")
+            .bind(session_id)
+            .bind(cl_ord_id)
+            .fetch_one(&self.db)
+            .await?)
+    }
+
+    pub async fn map_order(&self, session_id: Uuid, cl_ord_id: &str, order_id: Uuid) -> Result<()> {
+        sqlx::query("INSERT INTO fix_orders (session_id, cl_ord_id, order_id) VALUES ($1, $2, $3)")
+            .bind(session_id)
+            .bind(cl_ord_id)
+            .bind(order_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn order_for_cl_ord_id(&self, session_id: Uuid, cl_ord_id: &str) -> Result<Option<Uuid>> {
+        Ok(sqlx::query_scalar("SELECT order_id FROM fix_orders WHERE session_id = $1 AND cl_ord_id = $2")
+            .bind(session_id)
+            .bind(cl_ord_id)
+            .fetch_optional(&self.db)
+            .await?)
+    }
+
+    /// Order, market and FIX origin of an order, if it reached the orderbook
+    pub async fn order_context(&self, order_id: Uuid) -> Result<Option<FixOrderContext>> {
+        Ok(sqlx::query_as::<_, FixOrderContext>(
+            r#"
+            SELECT o.id AS order_id, o.user_id, o.side, o.energy_amount, o.price_per_kwh,
+                   m.code AS symbol, f.session_id, f.cl_ord_id
+            FROM trading_orders o
+            JOIN markets m ON m.id = o.market_id
+            LEFT JOIN fix_orders f ON f.order_id = o.id
+            WHERE o.id = $1
+            "#,
+        )
+        .bind(order_id)
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    fn audit(&self, admin_id: Uuid, action: &str, session: &FixSession, audit_logger: &AuditLogger) {
+        audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: Some(session.user_id),
+            details: serde_json::json!({
+                "session_id": session.id,
+                "sender_comp_id": session.sender_comp_id,
+                "session_type": session.session_type,
+                "next_in_seq": session.next_in_seq,
+                "next_out_seq": session.next_out_seq,
+                "is_active": session.is_active,
+            })
+            .to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comp_id_rules() {
+        assert!(validate_comp_id("EGAT-DESK_1").is_ok());
+        assert!(validate_comp_id("").is_err());
+        assert!(validate_comp_id("DESK 1").is_err());
+        assert!(validate_comp_id("DESK\u{1}1").is_err());
+        assert!(validate_comp_id(&"X".repeat(65)).is_err());
+    }
+}
@@ -0,0 +1,338 @@
+//! Futures market data: mark prices, funding rates, liquidations and trades,
+//! published on the `futures.*` WebSocket channels.
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::Row;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::FuturesService;
+use crate::error::{ApiError, Result};
+use crate::services::websocket::{channels, MarketEvent};
+
+/// Maintenance margin as a fraction of position notional
+pub const MAINTENANCE_MARGIN_RATE: Decimal = Decimal::from_parts(5, 0, 0, false, 3); // 0.005
+
+/// Largest funding rate per funding interval, in either direction
+pub const FUNDING_RATE_CAP: Decimal = Decimal::from_parts(75, 0, 0, false, 4); // 0.0075
+
+/// Price at which a position's margin falls to the maintenance requirement
+pub fn liquidation_price(side: &str, entry_price: Decimal, leverage: i32) -> Decimal {
+    let initial_margin = Decimal::ONE / Decimal::from(leverage.max(1));
+    let price = match side {
+        "short" => entry_price * (Decimal::ONE + initial_margin - MAINTENANCE_MARGIN_RATE),
+        _ => entry_price * (Decimal::ONE - initial_margin + MAINTENANCE_MARGIN_RATE),
+    };
+    price.max(Decimal::ZERO).round_dp(8)
+}
+
+/// Whether the mark price has crossed a position's liquidation price
+pub fn is_liquidatable(side: &str, mark_price: Decimal, liquidation_price: Decimal) -> bool {
+    match side {
+        "short" => mark_price >= liquidation_price,
+        _ => mark_price <= liquidation_price,
+    }
+}
+
+/// Unrealized profit of a position marked at `mark_price`
+pub fn unrealized_pnl(
+    side: &str,
+    quantity: Decimal,
+    contract_size: Decimal,
+    entry_price: Decimal,
+    mark_price: Decimal,
+) -> Decimal {
+    let move_per_unit = match side {
+        "short" => entry_price - mark_price,
+        _ => mark_price - entry_price,
+    };
+    (move_per_unit * quantity * contract_size).round_dp(8)
+}
+
+/// Funding rate from the premium of the mark over the spot index, capped
+pub fn funding_rate(mark_price: Decimal, index_price: Decimal) -> Option<Decimal> {
+    if index_price <= Decimal::ZERO {
+        return None;
+    }
+    let premium = (mark_price - index_price) / index_price;
+    Some(premium.clamp(-FUNDING_RATE_CAP, FUNDING_RATE_CAP).round_dp(8))
+}
+
+/// Active product with its current mark
+struct MarkedProduct {
+    id: Uuid,
+    symbol: String,
+    contract_size: Decimal,
+    mark_price: Decimal,
+}
+
+impl FuturesService {
+    async fn marked_products(&self) -> Result<Vec<MarkedProduct>> {
+        let rows = sqlx::query(
+            "SELECT id, symbol, contract_size, current_price FROM futures_products WHERE is_active = true",
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| MarkedProduct {
+                id: row.get("id"),
+                symbol: row.get("symbol"),
+                contract_size: row.get("contract_size"),
+                mark_price: row.get("current_price"),
+            })
+            .collect())
+    }
+
+    /// Latest spot clearing price, the index futures are funded against
+    async fn spot_index(&self) -> Result<Option<Decimal>> {
+        sqlx::query_scalar::<_, Decimal>(
+            "SELECT clearing_price FROM clearing_price_index ORDER BY epoch_start DESC LIMIT 1",
+        )
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))
+    }
+
+    pub(super) async fn product_symbol(&self, product_id: Uuid) -> Result<String> {
+        sqlx::query_scalar::<_, String>("SELECT symbol FROM futures_products WHERE id = $1")
+            .bind(product_id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?
+            .ok_or_else(|| ApiError::NotFound(format!("Futures product {} not found", product_id)))
+    }
+
+    /// Publish a fill on the product's trade stream
+    pub(super) async fn publish_trade(
+        &self,
+        product_id: Uuid,
+        side: &str,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> Result<()> {
+        let Some(ws) = &self.websocket_service else {
+            return Ok(());
+        };
+        let symbol = self.product_symbol(product_id).await?;
+        ws.publish(
+            &channels::futures_trades(&symbol),
+            MarketEvent::FuturesTrade {
+                product_id,
+                symbol,
+                side: side.to_string(),
+                quantity: quantity.to_string(),
+                price: price.to_string(),
+                executed_at: Utc::now(),
+            },
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Mark every open position to its product's price, liquidate positions
+    /// past their liquidation price and publish mark price ticks.
+    /// Returns the number of positions liquidated.
+    pub async fn refresh_marks(&self) -> Result<usize> {
+        let index = self.spot_index().await?;
+        let mut liquidated = 0;
+
+        for product in self.marked_products().await? {
+            let positions = sqlx::query(
+                r#"
+                SELECT id, side::text AS side, quantity, entry_price, liquidation_price
+                FROM futures_positions
+                WHERE product_id = $1
+                "#,
+            )
+            .bind(product.id)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+            for position in positions {
+                let position_id: Uuid = position.get("id");
+                let side: String = position.get("side");
+                let quantity: Decimal = position.get("quantity");
+                let entry_price: Decimal = position.get("entry_price");
+
+                if let Some(liquidation_price) = position.get::<Option<Decimal>, _>("liquidation_price") {
+                    if is_liquidatable(&side, product.mark_price, liquidation_price) {
+                        if self.liquidate(position_id, &product, &side, quantity, liquidation_price).await? {
+                            liquidated += 1;
+                        }
+                        continue;
+                    }
+                }
+
+                let pnl = unrealized_pnl(&side, quantity, product.contract_size, entry_price, product.mark_price);
+                sqlx::query("UPDATE futures_positions SET current_price = $1, unrealized_pnl = $2 WHERE id = $3")
+                    .bind(product.mark_price)
+                    .bind(pnl)
+                    .bind(position_id)
+                    .execute(&self.db)
+                    .await
+                    .map_err(|e| ApiError::Internal(e.to_string()))?;
+            }
+
+            if let Some(ws) = &self.websocket_service {
+                ws.publish(
+                    &channels::futures_mark_price(&product.symbol),
+                    MarketEvent::FuturesMarkPrice {
+                        product_id: product.id,
+                        symbol: product.symbol.clone(),
+                        mark_price: product.mark_price.to_string(),
+                        index_price: index.map(|i| i.to_string()),
+                        timestamp: Utc::now(),
+                    },
+                )
+                .await;
+            }
+        }
+
+        Ok(liquidated)
+    }
+
+    /// Close a position at the mark price and record the liquidation.
+    /// Returns false when the position was already closed.
+    async fn liquidate(
+        &self,
+        position_id: Uuid,
+        product: &MarkedProduct,
+        side: &str,
+        quantity: Decimal,
+        liquidation_price: Decimal,
+    ) -> Result<bool> {
+        let mut tx = self.db.begin().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        let Some(user_id) = sqlx::query_scalar::<_, Uuid>("DELETE FROM futures_positions WHERE id = $1 RETURNING user_id")
+            .bind(position_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?
+        else {
+            return Ok(false);
+        };
+
+        let close_side = if side == "long" { "short" } else { "long" };
+        sqlx::query(
+            r#"
+            INSERT INTO futures_orders (
+                user_id, product_id, side, order_type, quantity, price, leverage,
+                status, filled_quantity, average_fill_price
+            )
+            VALUES ($1, $2, $3::futures_order_side, 'market', $4, $5, 1, 'liquidated', $4, $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(product.id)
+        .bind(close_side)
+        .bind(quantity)
+        .bind(product.mark_price)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        warn!(
+            "💥 Liquidated {} position {} on {} ({} contracts, mark {} past {})",
+            side, position_id, product.symbol, quantity, product.mark_price, liquidation_price
+        );
+
+        if let Some(ws) = &self.websocket_service {
+            ws.publish(
+                channels::FUTURES_LIQUIDATIONS,
+                MarketEvent::FuturesLiquidation {
+                    position_id,
+                    product_id: product.id,
+                    symbol: product.symbol.clone(),
+                    side: side.to_string(),
+                    quantity: quantity.to_string(),
+                    liquidation_price: liquidation_price.to_string(),
+                    mark_price: product.mark_price.to_string(),
+                    timestamp: Utc::now(),
+                },
+            )
+            .await;
+        }
+
+        Ok(true)
+    }
+
+    /// Compute and publish each product's funding rate against the spot index
+    pub async fn publish_funding_rates(&self) -> Result<usize> {
+        let Some(index) = self.spot_index().await? else {
+            return Ok(0);
+        };
+
+        let mut published = 0;
+        for product in self.marked_products().await? {
+            let Some(rate) = funding_rate(product.mark_price, index) else {
+                continue;
+            };
+
+            if let Some(ws) = &self.websocket_service {
+                ws.publish(
+                    &channels::futures_funding(&product.symbol),
+                    MarketEvent::FuturesFundingRate {
+                        product_id: product.id,
+                        symbol: product.symbol.clone(),
+                        funding_rate: rate.to_string(),
+                        mark_price: product.mark_price.to_string(),
+                        index_price: index.to_string(),
+                        timestamp: Utc::now(),
+                    },
+                )
+                .await;
+            }
+            published += 1;
+        }
+
+        if published > 0 {
+            info!("📊 Published funding rates for {} futures products (index {})", published, index);
+        }
+        Ok(published)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(value: i64, scale: u32) -> Decimal {
+        Decimal::new(value, scale)
+    }
+
+    #[test]
+    fn test_liquidation_price() {
+        // 10x long at 4.00: 10% margin less 0.5% maintenance -> 3.62
+        assert_eq!(liquidation_price("long", d(400, 2), 10), d(362, 2));
+        assert_eq!(liquidation_price("short", d(400, 2), 10), d(438, 2));
+    }
+
+    #[test]
+    fn test_is_liquidatable() {
+        assert!(is_liquidatable("long", d(361, 2), d(362, 2)));
+        assert!(!is_liquidatable("long", d(370, 2), d(362, 2)));
+        assert!(is_liquidatable("short", d(440, 2), d(438, 2)));
+    }
+
+    #[test]
+    fn test_unrealized_pnl() {
+        // 2 contracts of 100 kWh, +0.10 per kWh
+        assert_eq!(unrealized_pnl("long", d(2, 0), d(100, 0), d(400, 2), d(410, 2)), d(20, 0));
+        assert_eq!(unrealized_pnl("short", d(2, 0), d(100, 0), d(400, 2), d(410, 2)), d(-20, 0));
+    }
+
+    #[test]
+    fn test_funding_rate_is_capped() {
+        assert_eq!(funding_rate(d(402, 2), d(400, 2)), Some(d(5, 3)));
+        assert_eq!(funding_rate(d(500, 2), d(400, 2)), Some(FUNDING_RATE_CAP));
+        assert_eq!(funding_rate(d(300, 2), d(400, 2)), Some(-FUNDING_RATE_CAP));
+        assert_eq!(funding_rate(d(400, 2), Decimal::ZERO), None);
+    }
+}
@@ -1,19 +1,32 @@
+pub mod feed;
+
 use chrono::Utc;
 use rust_decimal::Decimal;
+use tracing::warn;
 use uuid::Uuid;
 use crate::error::{ApiError, Result};
+use crate::services::WebSocketService;
 use utoipa::ToSchema;
 // Removed AppState
 
 #[derive(Debug, Clone)]
 pub struct FuturesService {
-    #[allow(dead_code)]
     db: sqlx::PgPool,
+    websocket_service: Option<WebSocketService>,
 }
 
 impl FuturesService {
     pub fn new(db: sqlx::PgPool) -> Self {
-        Self { db }
+        Self {
+            db,
+            websocket_service: None,
+        }
+    }
+
+    /// Set the WebSocket service for mark price, funding, liquidation and trade feeds
+    pub fn with_websocket(mut self, websocket_service: WebSocketService) -> Self {
+        self.websocket_service = Some(websocket_service);
+        self
     }
 
     pub async fn get_products(&self) -> Result<Vec<FuturesProduct>> {
@@ -78,10 +91,11 @@ impl FuturesService {
 
         // Auto-fill for MVP if market order
         if order_type == "market" {
+             let liquidation_price = feed::liquidation_price(&side, price, leverage);
              sqlx::query!(
                 r#"
-                INSERT INTO futures_positions (user_id, product_id, side, quantity, entry_price, current_price, leverage, margin_used, unrealized_pnl)
-                VALUES ($1, $2, $3::futures_order_side, $4, $5, $5, $6, $7, 0)
+                INSERT INTO futures_positions (user_id, product_id, side, quantity, entry_price, current_price, leverage, margin_used, unrealized_pnl, liquidation_price)
+                VALUES ($1, $2, $3::futures_order_side, $4, $5, $5, $6, $7, 0, $8)
                 "#,
                 user_id,
                 product_id,
@@ -89,7 +103,8 @@ impl FuturesService {
                 quantity,
                 price, // Using price as execution price for simplicity
                 leverage,
-                margin_required
+                margin_required,
+                liquidation_price
             )
             .execute(&self.db)
             .await
@@ -105,11 +120,28 @@ impl FuturesService {
             .execute(&self.db)
             .await
             .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+            self.record_trade(product_id, &side, quantity, price).await?;
         }
 
         Ok(order_id)
     }
 
+    /// Last traded price becomes the product's mark; the fill goes out on the trade stream
+    async fn record_trade(&self, product_id: Uuid, side: &str, quantity: Decimal, price: Decimal) -> Result<()> {
+        sqlx::query("UPDATE futures_products SET current_price = $1 WHERE id = $2")
+            .bind(price)
+            .bind(product_id)
+            .execute(&self.db)
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        if let Err(e) = self.publish_trade(product_id, side, quantity, price).await {
+            warn!("Failed to publish futures trade for product {}: {}", product_id, e);
+        }
+        Ok(())
+    }
+
     pub async fn get_positions(&self, user_id: Uuid) -> Result<Vec<FuturesPosition>> {
         sqlx::query_as!(
             FuturesPosition,
@@ -328,7 +360,11 @@ impl FuturesService {
 
         // 2. Calculate closing side
         let close_side = if position.side.as_deref() == Some("long") { "short" } else { "long" };
-        let price = position.current_price; // executing at current mark price for simplicity
+        // Expired contracts settle at the reference index; live ones at the current mark price
+        let price = match self.settlement_index(position.product_id).await? {
+            Some(index) => index,
+            None => position.current_price,
+        };
 
         // 3. Create closing order record (History)
         let order_id = sqlx::query!(
@@ -360,6 +396,27 @@ impl FuturesService {
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
+        if let Err(e) = self.publish_trade(position.product_id, close_side, position.quantity, price).await {
+            warn!("Failed to publish futures trade for product {}: {}", position.product_id, e);
+        }
+
         Ok(order_id)
     }
+
+    /// Settlement index of an expired product: the published reference
+    /// clearing price for its expiration date, if available
+    pub async fn settlement_index(&self, product_id: Uuid) -> Result<Option<Decimal>> {
+        sqlx::query_scalar::<_, Decimal>(
+            r#"
+            SELECT r.value
+            FROM futures_products p
+            JOIN reference_price_index r ON r.index_date = (p.expiration_date AT TIME ZONE 'UTC')::date
+            WHERE p.id = $1 AND p.expiration_date <= NOW()
+            "#,
+        )
+        .bind(product_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))
+    }
 }
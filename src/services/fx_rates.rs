@@ -0,0 +1,289 @@
+//! FX Rate Service
+//!
+//! Keeps the oracle feed of fiat reference rates for the settlement mint and
+//! stamps each settlement with the rate in effect at clearing time, so
+//! statements and analytics can show trade values in the display currency
+//! (e.g. THB) while orders remain priced and settled on-chain in tokens.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgExecutor, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::{CurrencyConfig, SettlementAsset};
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+
+/// Source recorded when the configured fallback rate is used
+pub const FALLBACK_SOURCE: &str = "config_fallback";
+
+/// Published oracle rate
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct FxRateRecord {
+    pub id: Uuid,
+    pub base_mint: String,
+    pub quote_currency: String,
+    #[schema(value_type = String)]
+    pub rate: Decimal,
+    pub source: String,
+    pub observed_at: DateTime<Utc>,
+    pub published_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Rate applied to new trades
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FxQuote {
+    /// Fiat per one settlement token
+    #[schema(value_type = String)]
+    pub rate: Decimal,
+    pub source: String,
+    /// `None` for the configured fallback rate
+    pub observed_at: Option<DateTime<Utc>>,
+}
+
+/// Settlement currency configuration and the rate currently in effect
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CurrencyOverview {
+    pub display_currency: String,
+    #[schema(value_type = String)]
+    pub settlement_asset: SettlementAsset,
+    pub settlement_mint: String,
+    pub max_rate_age_secs: i64,
+    pub current: Option<FxQuote>,
+    pub recent_rates: Vec<FxRateRecord>,
+}
+
+/// Choose the rate for a new trade: the latest oracle rate while it is fresh,
+/// otherwise the fallback rate if one is configured
+pub fn select_quote(
+    latest: Option<&FxRateRecord>,
+    now: DateTime<Utc>,
+    max_age_secs: i64,
+    fallback: Option<Decimal>,
+) -> Option<FxQuote> {
+    let fresh = latest.filter(|r| now - r.observed_at <= Duration::seconds(max_age_secs));
+    match (fresh, fallback) {
+        (Some(record), _) => Some(FxQuote {
+            rate: record.rate,
+            source: record.source.clone(),
+            observed_at: Some(record.observed_at),
+        }),
+        (None, Some(rate)) => Some(FxQuote {
+            rate,
+            source: FALLBACK_SOURCE.to_string(),
+            observed_at: None,
+        }),
+        (None, None) => None,
+    }
+}
+
+const RATE_COLUMNS: &str =
+    "id, base_mint, quote_currency, rate, source, observed_at, published_by, created_at";
+
+#[derive(Clone, Debug)]
+pub struct FxRateService {
+    db: PgPool,
+    config: CurrencyConfig,
+}
+
+impl FxRateService {
+    pub fn new(db: PgPool, config: CurrencyConfig) -> Self {
+        Self { db, config }
+    }
+
+    pub fn display_currency(&self) -> &str {
+        &self.config.display_currency
+    }
+
+    /// Record a rate from the oracle feed for the current settlement mint
+    pub async fn publish_rate(
+        &self,
+        publisher: Uuid,
+        rate: Decimal,
+        source: &str,
+        observed_at: Option<DateTime<Utc>>,
+        audit_logger: &AuditLogger,
+    ) -> Result<FxRateRecord> {
+        if rate <= Decimal::ZERO {
+            return Err(ApiError::validation_field("rate", "Rate must be positive"));
+        }
+        let observed_at = observed_at.unwrap_or_else(Utc::now);
+        if observed_at > Utc::now() + Duration::minutes(5) {
+            return Err(ApiError::validation_field("observed_at", "Observation time is in the future"));
+        }
+
+        let record = sqlx::query_as::<_, FxRateRecord>(&format!(
+            r#"
+            INSERT INTO fx_rates (base_mint, quote_currency, rate, source, observed_at, published_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            RATE_COLUMNS
+        ))
+        .bind(self.config.settlement_mint())
+        .bind(&self.config.display_currency)
+        .bind(rate)
+        .bind(source.trim())
+        .bind(observed_at)
+        .bind(publisher)
+        .fetch_one(&self.db)
+        .await?;
+
+        info!(
+            "💱 Published FX rate {} {} per token ({})",
+            record.rate, record.quote_currency, record.source
+        );
+        audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id: publisher,
+            action: "fx_rate_published".to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "rate_id": record.id,
+                "base_mint": record.base_mint,
+                "quote_currency": record.quote_currency,
+                "rate": record.rate,
+                "source": record.source,
+            })
+            .to_string(),
+        });
+
+        Ok(record)
+    }
+
+    /// Most recent rates for the current settlement mint and display currency
+    pub async fn list_rates(&self, limit: i64) -> Result<Vec<FxRateRecord>> {
+        Ok(sqlx::query_as::<_, FxRateRecord>(&format!(
+            r#"
+            SELECT {} FROM fx_rates
+            WHERE base_mint = $1 AND quote_currency = $2
+            ORDER BY observed_at DESC
+            LIMIT $3
+            "#,
+            RATE_COLUMNS
+        ))
+        .bind(self.config.settlement_mint())
+        .bind(&self.config.display_currency)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Rate that new trades are valued at, if any
+    pub async fn current_quote(&self) -> Result<Option<FxQuote>> {
+        let latest = self.list_rates(1).await?;
+        Ok(select_quote(
+            latest.first(),
+            Utc::now(),
+            self.config.fx_max_age_secs,
+            self.config.fx_fallback_rate,
+        ))
+    }
+
+    pub async fn overview(&self) -> Result<CurrencyOverview> {
+        let recent_rates = self.list_rates(50).await?;
+        let current = select_quote(
+            recent_rates.first(),
+            Utc::now(),
+            self.config.fx_max_age_secs,
+            self.config.fx_fallback_rate,
+        );
+        Ok(CurrencyOverview {
+            display_currency: self.config.display_currency.clone(),
+            settlement_asset: self.config.settlement_asset,
+            settlement_mint: self.config.settlement_mint().to_string(),
+            max_rate_age_secs: self.config.fx_max_age_secs,
+            current,
+            recent_rates,
+        })
+    }
+
+    /// Record the settlement mint and fiat reference value on a settlement.
+    /// Without a usable rate only the mint and currency are recorded.
+    pub async fn stamp_settlement<'e, E: PgExecutor<'e>>(
+        &self,
+        executor: E,
+        settlement_id: Uuid,
+    ) -> Result<Option<FxQuote>> {
+        let quote = self.current_quote().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE settlements
+            SET settlement_mint = $2,
+                fiat_currency = $3,
+                fx_rate = $4,
+                fx_rate_source = $5,
+                fx_observed_at = $6,
+                fiat_total_value = ROUND(total_amount * $4, 4)
+            WHERE id = $1
+            "#,
+        )
+        .bind(settlement_id)
+        .bind(self.config.settlement_mint())
+        .bind(&self.config.display_currency)
+        .bind(quote.as_ref().map(|q| q.rate))
+        .bind(quote.as_ref().map(|q| q.source.as_str()))
+        .bind(quote.as_ref().and_then(|q| q.observed_at))
+        .execute(executor)
+        .await?;
+
+        Ok(quote)
+    }
+
+    /// Stamp a settlement outside a transaction; a missing valuation never fails the trade
+    pub async fn record_settlement_value(&self, settlement_id: Uuid) {
+        match self.stamp_settlement(&self.db, settlement_id).await {
+            Ok(None) => warn!(
+                "⚠️ No {} rate available; settlement {} recorded without a fiat value",
+                self.config.display_currency, settlement_id
+            ),
+            Ok(Some(_)) => {}
+            Err(e) => warn!("⚠️ Failed to record fiat value for settlement {}: {}", settlement_id, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(rate: i64, age_secs: i64, now: DateTime<Utc>) -> FxRateRecord {
+        FxRateRecord {
+            id: Uuid::nil(),
+            base_mint: "mint".to_string(),
+            quote_currency: "THB".to_string(),
+            rate: Decimal::from(rate),
+            source: "oracle".to_string(),
+            observed_at: now - Duration::seconds(age_secs),
+            published_by: None,
+            created_at: now,
+        }
+    }
+
+    #[test]
+    fn test_uses_fresh_oracle_rate() {
+        let now = Utc::now();
+        let latest = record(35, 60, now);
+        let quote = select_quote(Some(&latest), now, 3600, Some(Decimal::from(30))).unwrap();
+        assert_eq!(quote.rate, Decimal::from(35));
+        assert_eq!(quote.source, "oracle");
+        assert_eq!(quote.observed_at, Some(latest.observed_at));
+    }
+
+    #[test]
+    fn test_stale_rate_falls_back() {
+        let now = Utc::now();
+        let latest = record(35, 7200, now);
+        let quote = select_quote(Some(&latest), now, 3600, Some(Decimal::from(30))).unwrap();
+        assert_eq!(quote.rate, Decimal::from(30));
+        assert_eq!(quote.source, FALLBACK_SOURCE);
+        assert_eq!(quote.observed_at, None);
+
+        assert_eq!(select_quote(Some(&latest), now, 3600, None), None);
+        assert_eq!(select_quote(None, now, 3600, None), None);
+    }
+}
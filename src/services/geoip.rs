@@ -0,0 +1,150 @@
+//! GeoIP Lookup
+//!
+//! Country, city, coordinates and ASN of client IPs from MaxMind databases
+//! (GeoLite2/GeoIP2 City and ASN). Either database may be left out; private
+//! and loopback addresses are never looked up.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use maxminddb::{geoip2, Reader};
+use serde::{Deserialize, Serialize};
+
+/// Mean Earth radius used for great-circle distances
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Location and network of an IP, stored with audit events under `geo`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeoInfo {
+    /// ISO 3166-1 alpha-2 code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asn: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub as_org: Option<String>,
+}
+
+impl GeoInfo {
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        Some((self.latitude?, self.longitude?))
+    }
+}
+
+/// Great-circle distance between two (latitude, longitude) points in km
+pub fn distance_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (a.0.to_radians(), a.1.to_radians());
+    let (lat2, lon2) = (b.0.to_radians(), b.1.to_radians());
+    let h = ((lat2 - lat1) / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().min(1.0).asin()
+}
+
+fn is_public(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation())
+        }
+        IpAddr::V6(ip) => {
+            let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00;
+            let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+            !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+        }
+    }
+}
+
+/// MaxMind database lookups; a service without databases finds nothing
+#[derive(Clone, Default)]
+pub struct GeoIpService {
+    city: Option<Arc<Reader<Vec<u8>>>>,
+    asn: Option<Arc<Reader<Vec<u8>>>>,
+}
+
+impl std::fmt::Debug for GeoIpService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GeoIpService")
+            .field("city", &self.city.is_some())
+            .field("asn", &self.asn.is_some())
+            .finish()
+    }
+}
+
+impl GeoIpService {
+    /// Open the configured databases
+    pub fn open(city_db: Option<&str>, asn_db: Option<&str>) -> Result<Self> {
+        let open = |path: &str| -> Result<Arc<Reader<Vec<u8>>>> {
+            Ok(Arc::new(
+                Reader::open_readfile(path).with_context(|| format!("Cannot open GeoIP database {}", path))?,
+            ))
+        };
+        Ok(Self {
+            city: city_db.map(open).transpose()?,
+            asn: asn_db.map(open).transpose()?,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.city.is_some() || self.asn.is_some()
+    }
+
+    /// Location and network of a client IP, `None` when nothing is known
+    pub fn lookup(&self, ip: &str) -> Option<GeoInfo> {
+        let ip: IpAddr = ip.trim().parse().ok()?;
+        if !is_public(&ip) || !self.is_enabled() {
+            return None;
+        }
+
+        let mut info = GeoInfo::default();
+        if let Some(city) = self.city.as_ref().and_then(|db| db.lookup::<geoip2::City>(ip).ok()) {
+            info.country = city.country.and_then(|c| c.iso_code).map(str::to_string);
+            info.city = city
+                .city
+                .and_then(|c| c.names)
+                .and_then(|names| names.get("en").map(|name| name.to_string()));
+            if let Some(location) = city.location {
+                info.latitude = location.latitude;
+                info.longitude = location.longitude;
+            }
+        }
+        if let Some(asn) = self.asn.as_ref().and_then(|db| db.lookup::<geoip2::Asn>(ip).ok()) {
+            info.asn = asn.autonomous_system_number;
+            info.as_org = asn.autonomous_system_organization.map(str::to_string);
+        }
+
+        (info != GeoInfo::default()).then_some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_between_cities() {
+        let bangkok = (13.7563, 100.5018);
+        let london = (51.5074, -0.1278);
+        let km = distance_km(bangkok, london);
+        assert!((9500.0..9600.0).contains(&km), "{}", km);
+        assert!(distance_km(bangkok, bangkok) < 1e-6);
+    }
+
+    #[test]
+    fn test_private_addresses_are_not_looked_up() {
+        assert!(!is_public(&"10.1.2.3".parse().unwrap()));
+        assert!(!is_public(&"127.0.0.1".parse().unwrap()));
+        assert!(!is_public(&"fd00::1".parse().unwrap()));
+        assert!(is_public(&"8.8.8.8".parse().unwrap()));
+        assert_eq!(GeoIpService::default().lookup("8.8.8.8"), None);
+    }
+}
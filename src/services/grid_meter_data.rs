@@ -0,0 +1,501 @@
+//! Grid Meter Data
+//!
+//! The distribution system operator (DSO) uploads official interval data
+//! per meter. A reconciliation job compares each official interval with the
+//! readings users submitted for the same meter and period. Meters whose
+//! submissions diverge from the official data in too many recent intervals
+//! have minting frozen until an admin releases them.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Row};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::{Config, GridReconciliationConfig};
+use crate::error::{ApiError, Result};
+use crate::middleware::metrics::track_grid_reconciliation;
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+
+/// Most intervals accepted in one upload
+pub const MAX_IMPORT_ROWS: usize = 50_000;
+/// Official intervals reconciled per run
+const RECONCILE_BATCH: i64 = 1_000;
+const MAX_SERIAL_LEN: usize = 100;
+
+const CSV_HEADER: [&str; 5] = ["meter_serial", "interval_start", "interval_end", "energy_generated", "energy_consumed"];
+
+/// One official interval of a meter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OfficialInterval {
+    pub meter_serial: String,
+    pub interval_start: DateTime<Utc>,
+    pub interval_end: DateTime<Utc>,
+    #[schema(value_type = String)]
+    pub energy_generated: Decimal,
+    #[schema(value_type = String)]
+    pub energy_consumed: Decimal,
+}
+
+/// An interval that was not imported
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct IntervalImportError {
+    /// 1-based CSV line (the header being line 1) or 0-based JSON array index
+    pub row: usize,
+    pub message: String,
+}
+
+/// Outcome of an official data upload; nothing is stored while any row is invalid
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GridDataImportReport {
+    pub rows: usize,
+    pub inserted: usize,
+    /// Intervals that replaced earlier data and will be reconciled again
+    pub updated: usize,
+    pub errors: Vec<IntervalImportError>,
+}
+
+/// Official and submitted volumes of one meter over a period
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct MeterDiscrepancy {
+    pub meter_serial: String,
+    pub intervals: i64,
+    pub divergent_intervals: i64,
+    #[schema(value_type = String)]
+    pub official_generated: Decimal,
+    #[schema(value_type = String)]
+    pub reported_generated: Decimal,
+    #[schema(value_type = String)]
+    pub official_consumed: Decimal,
+    #[schema(value_type = String)]
+    pub reported_consumed: Decimal,
+    /// Largest deviation of an interval as a fraction of its official volume
+    #[schema(value_type = String)]
+    pub max_deviation: Decimal,
+    /// Set while minting from the meter's readings is frozen
+    pub mint_frozen_at: Option<DateTime<Utc>>,
+    pub mint_freeze_reason: Option<String>,
+}
+
+/// Check an interval before it is stored
+fn validate(interval: &OfficialInterval) -> std::result::Result<(), String> {
+    let serial = interval.meter_serial.trim();
+    if serial.is_empty() || serial.len() > MAX_SERIAL_LEN {
+        return Err(format!("meter_serial must be 1 to {} characters", MAX_SERIAL_LEN));
+    }
+    if interval.interval_end <= interval.interval_start {
+        return Err("interval_end must be after interval_start".to_string());
+    }
+    if interval.energy_generated < Decimal::ZERO || interval.energy_consumed < Decimal::ZERO {
+        return Err("energy values must not be negative".to_string());
+    }
+    Ok(())
+}
+
+/// Parse official intervals from CSV with the columns of `CSV_HEADER`, in
+/// any order. Intervals are returned with their line number.
+pub fn parse_csv(text: &str) -> (Vec<(usize, OfficialInterval)>, Vec<IntervalImportError>) {
+    let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        return (Vec::new(), Vec::new());
+    };
+    let columns: Vec<&str> = header.split(',').map(str::trim).collect();
+    let positions: Vec<Option<usize>> = CSV_HEADER
+        .iter()
+        .map(|name| columns.iter().position(|c| c.eq_ignore_ascii_case(name)))
+        .collect();
+    if let Some(missing) = CSV_HEADER.iter().zip(&positions).find(|(_, p)| p.is_none()) {
+        return (
+            Vec::new(),
+            vec![IntervalImportError { row: 1, message: format!("Missing column {}", missing.0) }],
+        );
+    }
+    let positions: Vec<usize> = positions.into_iter().flatten().collect();
+
+    let mut intervals = Vec::new();
+    let mut errors = Vec::new();
+    for (index, line) in lines {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let field = |i: usize| fields.get(positions[i]).copied().unwrap_or_default();
+        let parsed = (|| {
+            Ok::<_, String>(OfficialInterval {
+                meter_serial: field(0).to_string(),
+                interval_start: field(1).parse().map_err(|_| format!("Invalid interval_start: {}", field(1)))?,
+                interval_end: field(2).parse().map_err(|_| format!("Invalid interval_end: {}", field(2)))?,
+                energy_generated: field(3).parse().map_err(|_| format!("Invalid energy_generated: {}", field(3)))?,
+                energy_consumed: field(4).parse().map_err(|_| format!("Invalid energy_consumed: {}", field(4)))?,
+            })
+        })();
+        match parsed {
+            Ok(interval) => intervals.push((index + 1, interval)),
+            Err(message) => errors.push(IntervalImportError { row: index + 1, message }),
+        }
+    }
+    (intervals, errors)
+}
+
+/// Deviation of the submitted volumes from the official ones, as a fraction
+/// of the official volume. Readings for a meter the DSO metered nothing on
+/// count as fully divergent.
+fn deviation(official: (Decimal, Decimal), reported: (Decimal, Decimal)) -> Decimal {
+    let official_total = official.0 + official.1;
+    let gap = (official.0 - reported.0).abs() + (official.1 - reported.1).abs();
+    if official_total.is_zero() {
+        return if gap.is_zero() { Decimal::ZERO } else { Decimal::ONE };
+    }
+    (gap / official_total).round_dp(6)
+}
+
+#[derive(Clone)]
+pub struct GridMeterDataService {
+    db: PgPool,
+    audit_logger: AuditLogger,
+    config: GridReconciliationConfig,
+}
+
+impl GridMeterDataService {
+    pub fn new(db: PgPool, audit_logger: AuditLogger, config: Config) -> Self {
+        Self {
+            db,
+            audit_logger,
+            config: config.grid_reconciliation,
+        }
+    }
+
+    /// Store official intervals, each with its row in the upload. `errors`
+    /// holds rows that failed to parse; the upload is rejected as a whole if
+    /// any row is invalid or names an unregistered meter. Replaced intervals
+    /// are reconciled again.
+    pub async fn import(
+        &self,
+        admin_id: Uuid,
+        source: &str,
+        intervals: Vec<(usize, OfficialInterval)>,
+        mut errors: Vec<IntervalImportError>,
+    ) -> Result<GridDataImportReport> {
+        let rows = intervals.len() + errors.len();
+        if rows > MAX_IMPORT_ROWS {
+            return Err(ApiError::BadRequest(format!("At most {} intervals per upload", MAX_IMPORT_ROWS)));
+        }
+
+        let serials: Vec<String> = intervals.iter().map(|(_, i)| i.meter_serial.trim().to_string()).collect();
+        let known: HashSet<String> =
+            sqlx::query_scalar("SELECT meter_serial FROM meter_registry WHERE meter_serial = ANY($1)")
+                .bind(&serials)
+                .fetch_all(&self.db)
+                .await?
+                .into_iter()
+                .collect();
+        for (row, interval) in &intervals {
+            let check = validate(interval).and_then(|_| {
+                if known.contains(interval.meter_serial.trim()) {
+                    Ok(())
+                } else {
+                    Err(format!("Unknown meter {}", interval.meter_serial.trim()))
+                }
+            });
+            if let Err(message) = check {
+                errors.push(IntervalImportError { row: *row, message });
+            }
+        }
+
+        let mut report = GridDataImportReport { rows, inserted: 0, updated: 0, errors };
+        if !report.errors.is_empty() {
+            report.errors.sort_by_key(|e| e.row);
+            return Ok(report);
+        }
+
+        let mut tx = self.db.begin().await?;
+        for (_, interval) in &intervals {
+            let row = sqlx::query(
+                r#"
+                INSERT INTO grid_meter_intervals (
+                    meter_serial, interval_start, interval_end, energy_generated, energy_consumed, source, imported_by
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (meter_serial, interval_start) DO UPDATE SET
+                    interval_end = EXCLUDED.interval_end,
+                    energy_generated = EXCLUDED.energy_generated,
+                    energy_consumed = EXCLUDED.energy_consumed,
+                    source = EXCLUDED.source,
+                    imported_by = EXCLUDED.imported_by,
+                    imported_at = NOW()
+                RETURNING id, (xmax = 0) AS inserted
+                "#,
+            )
+            .bind(interval.meter_serial.trim())
+            .bind(interval.interval_start)
+            .bind(interval.interval_end)
+            .bind(interval.energy_generated)
+            .bind(interval.energy_consumed)
+            .bind(source)
+            .bind(admin_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            if row.get::<bool, _>("inserted") {
+                report.inserted += 1;
+            } else {
+                report.updated += 1;
+                sqlx::query("DELETE FROM meter_reconciliations WHERE interval_id = $1")
+                    .bind(row.get::<Uuid, _>("id"))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "grid_meter_data_imported".to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "source": source,
+                "inserted": report.inserted,
+                "updated": report.updated,
+            })
+            .to_string(),
+        });
+        info!(
+            "🔌 Imported {} official intervals from {} ({} replaced)",
+            report.inserted + report.updated,
+            source,
+            report.updated
+        );
+        Ok(report)
+    }
+
+    /// Reconcile official intervals not compared yet with the readings
+    /// submitted for them, then freeze minting for meters that diverge
+    /// systematically. Returns (reconciled, divergent).
+    pub async fn reconcile(&self) -> Result<(usize, usize)> {
+        let rows = sqlx::query(
+            r#"
+            SELECT g.id, g.meter_serial, g.interval_start, g.energy_generated, g.energy_consumed,
+                   COALESCE(r.generated, 0) AS reported_generated,
+                   COALESCE(r.consumed, 0) AS reported_consumed
+            FROM grid_meter_intervals g
+            CROSS JOIN LATERAL (
+                SELECT SUM(m.energy_generated) AS generated, SUM(m.energy_consumed) AS consumed
+                FROM meter_readings m
+                WHERE m.meter_serial = g.meter_serial
+                  AND m.reading_timestamp >= g.interval_start AND m.reading_timestamp < g.interval_end
+            ) r
+            WHERE g.interval_end < NOW()
+              AND NOT EXISTS (SELECT 1 FROM meter_reconciliations c WHERE c.interval_id = g.id)
+            ORDER BY g.interval_start
+            LIMIT $1
+            "#,
+        )
+        .bind(RECONCILE_BATCH)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut divergent = 0;
+        let mut meters = HashSet::new();
+        for row in &rows {
+            let official = (row.get("energy_generated"), row.get("energy_consumed"));
+            let reported = (row.get("reported_generated"), row.get("reported_consumed"));
+            let deviation = deviation(official, reported);
+            let is_divergent = deviation > self.config.tolerance;
+            let meter_serial: String = row.get("meter_serial");
+
+            sqlx::query(
+                r#"
+                INSERT INTO meter_reconciliations (
+                    interval_id, meter_serial, interval_start, official_generated, official_consumed,
+                    reported_generated, reported_consumed, deviation, divergent
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (interval_id) DO NOTHING
+                "#,
+            )
+            .bind(row.get::<Uuid, _>("id"))
+            .bind(&meter_serial)
+            .bind(row.get::<DateTime<Utc>, _>("interval_start"))
+            .bind(official.0)
+            .bind(official.1)
+            .bind(reported.0)
+            .bind(reported.1)
+            .bind(deviation)
+            .bind(is_divergent)
+            .execute(&self.db)
+            .await?;
+
+            track_grid_reconciliation(is_divergent);
+            if is_divergent {
+                divergent += 1;
+                meters.insert(meter_serial);
+            }
+        }
+
+        for meter_serial in meters {
+            self.freeze_if_divergent(&meter_serial).await?;
+        }
+        Ok((rows.len(), divergent))
+    }
+
+    /// Freeze minting when enough of the meter's recent intervals diverge.
+    /// Intervals reconciled before the meter was last released do not count.
+    async fn freeze_if_divergent(&self, meter_serial: &str) -> Result<()> {
+        if self.config.freeze_after <= 0 {
+            return Ok(());
+        }
+        let reason = format!(
+            "{} or more of the last {} intervals diverge from official grid data by over {}%",
+            self.config.freeze_after,
+            self.config.window,
+            self.config.tolerance * Decimal::ONE_HUNDRED
+        );
+        let frozen = sqlx::query(
+            r#"
+            UPDATE meter_registry m
+            SET mint_frozen_at = NOW(), mint_freeze_reason = $4
+            WHERE m.meter_serial = $1 AND m.mint_frozen_at IS NULL
+              AND (
+                  SELECT COUNT(*) FILTER (WHERE recent.divergent)
+                  FROM (
+                      SELECT c.divergent FROM meter_reconciliations c
+                      WHERE c.meter_serial = $1
+                        AND (m.mint_unfrozen_at IS NULL OR c.reconciled_at > m.mint_unfrozen_at)
+                      ORDER BY c.interval_start DESC
+                      LIMIT $2
+                  ) recent
+              ) >= $3
+            "#,
+        )
+        .bind(meter_serial)
+        .bind(self.config.window)
+        .bind(self.config.freeze_after)
+        .bind(&reason)
+        .execute(&self.db)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if frozen {
+            warn!("🧊 Minting frozen for meter {}: {}", meter_serial, reason);
+        }
+        Ok(())
+    }
+
+    /// Why minting from a reading's meter is frozen, if it is
+    pub async fn reading_mint_freeze(&self, reading_id: Uuid) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(m.mint_freeze_reason, 'frozen')
+            FROM meter_readings r
+            JOIN meter_registry m ON m.meter_serial = r.meter_serial
+            WHERE r.id = $1 AND m.mint_frozen_at IS NOT NULL
+            "#,
+        )
+        .bind(reading_id)
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    /// Why minting for a meter is frozen, if it is
+    pub async fn meter_mint_freeze(&self, meter_serial: &str) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT COALESCE(mint_freeze_reason, 'frozen') FROM meter_registry \
+             WHERE meter_serial = $1 AND mint_frozen_at IS NOT NULL",
+        )
+        .bind(meter_serial)
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    /// Release a frozen meter; divergence before the release no longer counts
+    pub async fn unfreeze(&self, admin_id: Uuid, meter_serial: &str, note: Option<&str>) -> Result<()> {
+        let released = sqlx::query(
+            r#"
+            UPDATE meter_registry
+            SET mint_frozen_at = NULL, mint_freeze_reason = NULL, mint_unfrozen_at = NOW()
+            WHERE meter_serial = $1 AND mint_frozen_at IS NOT NULL
+            "#,
+        )
+        .bind(meter_serial)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+        if released == 0 {
+            return Err(ApiError::NotFound("No frozen meter with this serial".to_string()));
+        }
+
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "meter_mint_unfrozen".to_string(),
+            target_user_id: None,
+            details: serde_json::json!({ "meter_serial": meter_serial, "note": note }).to_string(),
+        });
+        info!("🔓 Minting released for meter {} by {}", meter_serial, admin_id);
+        Ok(())
+    }
+
+    /// Per-meter comparison of intervals starting in `[from, to)`, most
+    /// divergent first
+    pub async fn discrepancy_report(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        divergent_only: bool,
+    ) -> Result<Vec<MeterDiscrepancy>> {
+        Ok(sqlx::query_as::<_, MeterDiscrepancy>(
+            r#"
+            SELECT c.meter_serial,
+                   COUNT(*) AS intervals,
+                   COUNT(*) FILTER (WHERE c.divergent) AS divergent_intervals,
+                   SUM(c.official_generated) AS official_generated,
+                   SUM(c.reported_generated) AS reported_generated,
+                   SUM(c.official_consumed) AS official_consumed,
+                   SUM(c.reported_consumed) AS reported_consumed,
+                   MAX(c.deviation) AS max_deviation,
+                   m.mint_frozen_at, m.mint_freeze_reason
+            FROM meter_reconciliations c
+            LEFT JOIN meter_registry m ON m.meter_serial = c.meter_serial
+            WHERE c.interval_start >= $1 AND c.interval_start < $2
+            GROUP BY c.meter_serial, m.mint_frozen_at, m.mint_freeze_reason
+            HAVING NOT $3 OR COUNT(*) FILTER (WHERE c.divergent) > 0
+            ORDER BY divergent_intervals DESC, max_deviation DESC, c.meter_serial
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(divergent_only)
+        .fetch_all(&self.db)
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_matches_columns_by_name() {
+        let csv = "energy_consumed,meter_serial,interval_start,interval_end,energy_generated\n\
+                   1.5,MTR-1,2026-01-01T00:00:00Z,2026-01-01T00:15:00Z,3\n\
+                   \n\
+                   x,MTR-2,2026-01-01T00:00:00Z,2026-01-01T00:15:00Z,3\n";
+        let (intervals, errors) = parse_csv(csv);
+        assert_eq!(intervals.len(), 1);
+        assert_eq!(intervals[0].0, 2);
+        assert_eq!(intervals[0].1.meter_serial, "MTR-1");
+        assert_eq!(intervals[0].1.energy_consumed, Decimal::new(15, 1));
+        assert_eq!(errors, vec![IntervalImportError { row: 4, message: "Invalid energy_consumed: x".to_string() }]);
+
+        let (_, errors) = parse_csv("meter_serial,interval_start\n");
+        assert_eq!(errors[0].message, "Missing column interval_end");
+    }
+
+    #[test]
+    fn test_deviation_is_relative_to_official_volume() {
+        let d = |v: i64| Decimal::from(v);
+        assert_eq!(deviation((d(10), d(0)), (d(10), d(0))), Decimal::ZERO);
+        // 2 kWh over-reported generation and 0 consumption gap on 10 kWh official
+        assert_eq!(deviation((d(8), d(2)), (d(10), d(2))), Decimal::new(2, 1));
+        assert_eq!(deviation((d(0), d(0)), (d(1), d(0))), Decimal::ONE);
+    }
+}
@@ -1,10 +1,103 @@
 use chrono::Utc;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
 pub mod types;
-pub use types::{DependencyHealth, DetailedHealthStatus, HealthCheckStatus, SystemMetrics};
+pub use types::{DependencyHealth, DetailedHealthStatus, HealthCheckStatus, HealthLevel, SystemMetrics};
+
+/// How long a health check result is reused before dependencies are probed again
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Time budget of a dependency probe
+#[derive(Debug, Clone, Copy)]
+struct ProbeLimits {
+    /// The probe is abandoned and the dependency reported unhealthy after this
+    timeout: Duration,
+    /// A successful probe slower than this reports the dependency degraded
+    slow_after: Duration,
+    critical: bool,
+}
+
+const DATABASE_PROBE: ProbeLimits = ProbeLimits {
+    timeout: Duration::from_secs(2),
+    slow_after: Duration::from_millis(500),
+    critical: true,
+};
+const REDIS_PROBE: ProbeLimits = ProbeLimits {
+    timeout: Duration::from_secs(1),
+    slow_after: Duration::from_millis(250),
+    critical: false,
+};
+const BLOCKCHAIN_PROBE: ProbeLimits = ProbeLimits {
+    timeout: Duration::from_secs(3),
+    slow_after: Duration::from_millis(1500),
+    critical: false,
+};
+
+/// Run a probe within its time budget
+async fn run_probe<F>(name: &str, limits: ProbeLimits, probe: F) -> DependencyHealth
+where
+    F: Future<Output = DependencyHealth>,
+{
+    let start = Instant::now();
+    let mut health = match tokio::time::timeout(limits.timeout, probe).await {
+        Ok(health) => health,
+        Err(_) => DependencyHealth {
+            name: name.to_string(),
+            status: HealthCheckStatus::Unhealthy,
+            response_time_ms: Some(start.elapsed().as_millis() as u64),
+            last_check: Utc::now(),
+            error_message: Some(format!("Timed out after {}ms", limits.timeout.as_millis())),
+            details: None,
+            critical: false,
+        },
+    };
+    health.critical = limits.critical;
+
+    let elapsed_ms = health.response_time_ms.unwrap_or(0);
+    if health.status == HealthCheckStatus::Healthy && elapsed_ms > limits.slow_after.as_millis() as u64 {
+        health.status = HealthCheckStatus::Degraded;
+        health.error_message = Some(format!(
+            "Slow response: {}ms (threshold {}ms)",
+            elapsed_ms,
+            limits.slow_after.as_millis()
+        ));
+    }
+    health
+}
+
+/// Overall level and the reasons behind it. An unavailable critical
+/// dependency takes the service down; anything else short of healthy
+/// degrades it.
+pub fn assess(dependencies: &[DependencyHealth]) -> (HealthLevel, Vec<String>) {
+    let mut level = HealthLevel::Ok;
+    let mut reasons = Vec::new();
+
+    for dep in dependencies {
+        let cause = dep.error_message.as_deref().unwrap_or("no details");
+        let (dep_level, reason) = match dep.status {
+            HealthCheckStatus::Healthy => continue,
+            HealthCheckStatus::Unhealthy if dep.critical => {
+                (HealthLevel::Down, format!("{} is down: {}", dep.name, cause))
+            }
+            HealthCheckStatus::Unhealthy => {
+                (HealthLevel::Degraded, format!("{} is unavailable: {}", dep.name, cause))
+            }
+            HealthCheckStatus::Degraded => {
+                (HealthLevel::Degraded, format!("{} is degraded: {}", dep.name, cause))
+            }
+            HealthCheckStatus::Unknown => {
+                (HealthLevel::Degraded, format!("{} status is unknown", dep.name))
+            }
+        };
+        level = level.max(dep_level);
+        reasons.push(reason);
+    }
+
+    (level, reasons)
+}
 
 /// Health checker service
 #[derive(Clone)]
@@ -14,6 +107,8 @@ pub struct HealthChecker {
     redis_client: redis::Client,
     blockchain_url: String,
     last_check: Arc<RwLock<Option<DetailedHealthStatus>>>,
+    /// Held while dependencies are probed so concurrent callers share one run
+    refresh: Arc<Mutex<()>>,
     email_service_enabled: bool,
 }
 
@@ -30,6 +125,7 @@ impl HealthChecker {
             redis_client,
             blockchain_url,
             last_check: Arc::new(RwLock::new(None)),
+            refresh: Arc::new(Mutex::new(())),
             email_service_enabled,
         }
     }
@@ -51,6 +147,7 @@ impl HealthChecker {
                 last_check: Utc::now(),
                 error_message: None,
                 details: Some("Database connection successful".to_string()),
+                critical: false,
             },
             Err(e) => DependencyHealth {
                 name: "PostgreSQL".to_string(),
@@ -59,6 +156,7 @@ impl HealthChecker {
                 last_check: Utc::now(),
                 error_message: Some(e.to_string()),
                 details: None,
+                critical: false,
             },
         }
     }
@@ -78,6 +176,7 @@ impl HealthChecker {
                         last_check: Utc::now(),
                         error_message: None,
                         details: Some("Redis connection successful".to_string()),
+                        critical: false,
                     },
                     Err(e) => DependencyHealth {
                         name: "Redis".to_string(),
@@ -86,6 +185,7 @@ impl HealthChecker {
                         last_check: Utc::now(),
                         error_message: Some(e.to_string()),
                         details: None,
+                        critical: false,
                     },
                 }
             }
@@ -96,6 +196,7 @@ impl HealthChecker {
                 last_check: Utc::now(),
                 error_message: Some(e.to_string()),
                 details: None,
+                critical: false,
             },
         }
     }
@@ -128,6 +229,7 @@ impl HealthChecker {
                                 last_check: Utc::now(),
                                 error_message: None,
                                 details: Some("RPC endpoint responding".to_string()),
+                                critical: false,
                             }
                         } else {
                             DependencyHealth {
@@ -137,6 +239,7 @@ impl HealthChecker {
                                 last_check: Utc::now(),
                                 error_message: Some(format!("HTTP {}", response.status())),
                                 details: None,
+                                critical: false,
                             }
                         }
                     }
@@ -147,6 +250,7 @@ impl HealthChecker {
                         last_check: Utc::now(),
                         error_message: Some(e.to_string()),
                         details: None,
+                        critical: false,
                     },
                 }
             }
@@ -157,6 +261,7 @@ impl HealthChecker {
                 last_check: Utc::now(),
                 error_message: Some(e.to_string()),
                 details: None,
+                critical: false,
             },
         }
     }
@@ -171,6 +276,7 @@ impl HealthChecker {
                 last_check: Utc::now(),
                 error_message: None,
                 details: Some("Email service is configured and enabled".to_string()),
+                critical: false,
             }
         } else {
             DependencyHealth {
@@ -180,6 +286,7 @@ impl HealthChecker {
                 last_check: Utc::now(),
                 error_message: Some("Email service is NOT configured".to_string()),
                 details: None,
+                critical: false,
             }
         }
     }
@@ -201,35 +308,50 @@ impl HealthChecker {
         }
     }
 
-    /// Perform full health check
+    /// Cached result if still fresh
+    async fn fresh_health(&self) -> Option<DetailedHealthStatus> {
+        self.last_check
+            .read()
+            .await
+            .as_ref()
+            .filter(|status| {
+                (Utc::now() - status.timestamp)
+                    .to_std()
+                    .is_ok_and(|age| age < CACHE_TTL)
+            })
+            .cloned()
+    }
+
+    /// Perform full health check. Results are reused for a few seconds, and
+    /// every probe is bounded by its own timeout, so callers never wait on a
+    /// slow dependency for longer than the slowest probe budget.
     pub async fn perform_health_check(&self) -> DetailedHealthStatus {
+        if let Some(status) = self.fresh_health().await {
+            return status;
+        }
+
+        let _refresh = self.refresh.lock().await;
+        // Another caller may have refreshed while we waited
+        if let Some(status) = self.fresh_health().await {
+            return status;
+        }
+
         // Check all dependencies in parallel
         let (db_health, redis_health, blockchain_health) = tokio::join!(
-            self.check_database(),
-            self.check_redis(),
-            self.check_blockchain()
+            run_probe("PostgreSQL", DATABASE_PROBE, self.check_database()),
+            run_probe("Redis", REDIS_PROBE, self.check_redis()),
+            run_probe("Solana RPC", BLOCKCHAIN_PROBE, self.check_blockchain())
         );
 
         let email_health = self.check_email();
         let dependencies = vec![db_health, redis_health, blockchain_health, email_health];
 
-        // Determine overall status
-        let overall_status = if dependencies
-            .iter()
-            .all(|d| d.status == HealthCheckStatus::Healthy)
-        {
-            "healthy"
-        } else if dependencies
-            .iter()
-            .any(|d| d.status == HealthCheckStatus::Unhealthy)
-        {
-            "unhealthy"
-        } else {
-            "degraded"
-        };
+        let (level, reasons) = assess(&dependencies);
 
         let status = DetailedHealthStatus {
-            status: overall_status.to_string(),
+            status: level.as_status_str().to_string(),
+            level,
+            reasons,
             timestamp: Utc::now(),
             version: env!("CARGO_PKG_VERSION").to_string(),
             environment: std::env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()),
@@ -260,6 +382,66 @@ mod tests {
         assert_ne!(HealthCheckStatus::Healthy, HealthCheckStatus::Unhealthy);
     }
 
+    fn dependency(name: &str, status: HealthCheckStatus, critical: bool) -> DependencyHealth {
+        DependencyHealth {
+            name: name.to_string(),
+            status,
+            response_time_ms: Some(5),
+            last_check: Utc::now(),
+            error_message: Some("connection refused".to_string()),
+            details: None,
+            critical,
+        }
+    }
+
+    #[test]
+    fn test_assess_all_healthy() {
+        let deps = vec![
+            dependency("PostgreSQL", HealthCheckStatus::Healthy, true),
+            dependency("Redis", HealthCheckStatus::Healthy, false),
+        ];
+        assert_eq!(assess(&deps), (HealthLevel::Ok, vec![]));
+    }
+
+    #[test]
+    fn test_assess_non_critical_outage_degrades() {
+        let deps = vec![
+            dependency("PostgreSQL", HealthCheckStatus::Healthy, true),
+            dependency("Solana RPC", HealthCheckStatus::Unhealthy, false),
+        ];
+        let (level, reasons) = assess(&deps);
+        assert_eq!(level, HealthLevel::Degraded);
+        assert_eq!(reasons, vec!["Solana RPC is unavailable: connection refused"]);
+    }
+
+    #[test]
+    fn test_assess_critical_outage_is_down() {
+        let deps = vec![
+            dependency("PostgreSQL", HealthCheckStatus::Unhealthy, true),
+            dependency("Redis", HealthCheckStatus::Degraded, false),
+        ];
+        let (level, reasons) = assess(&deps);
+        assert_eq!(level, HealthLevel::Down);
+        assert_eq!(reasons.len(), 2);
+        assert_eq!(level.as_status_str(), "unhealthy");
+    }
+
+    #[tokio::test]
+    async fn test_probe_timeout_reports_unhealthy() {
+        let limits = ProbeLimits {
+            timeout: Duration::from_millis(10),
+            slow_after: Duration::from_millis(5),
+            critical: true,
+        };
+        let health = run_probe("Slow", limits, async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            dependency("Slow", HealthCheckStatus::Healthy, false)
+        })
+        .await;
+        assert_eq!(health.status, HealthCheckStatus::Unhealthy);
+        assert!(health.critical);
+    }
+
     #[test]
     fn test_system_metrics_serialization() {
         let metrics = SystemMetrics {
@@ -16,7 +16,11 @@ pub struct SystemMetrics {
 /// Detailed health status with metrics
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DetailedHealthStatus {
+    /// healthy, degraded or unhealthy (mirrors `level`)
     pub status: String,
+    pub level: HealthLevel,
+    /// Why the service is not fully healthy
+    pub reasons: Vec<String>,
     pub timestamp: DateTime<Utc>,
     pub version: String,
     pub environment: String,
@@ -34,6 +38,30 @@ pub struct DependencyHealth {
     pub last_check: DateTime<Utc>,
     pub error_message: Option<String>,
     pub details: Option<String>,
+    /// The service cannot work without this dependency
+    pub critical: bool,
+}
+
+/// Overall degradation level
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthLevel {
+    Ok,
+    /// Serving, but a non-critical dependency is slow or unavailable
+    Degraded,
+    /// A critical dependency is unavailable
+    Down,
+}
+
+impl HealthLevel {
+    /// Legacy overall status string
+    pub fn as_status_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "healthy",
+            Self::Degraded => "degraded",
+            Self::Down => "unhealthy",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
@@ -0,0 +1,332 @@
+//! Imbalance Settlement
+//!
+//! After an epoch's meter readings are in, each participant's metered net
+//! export (generation minus consumption) is compared with its traded net
+//! export (energy sold minus energy bought in completed settlements). The
+//! gap is priced at the imbalance rate: under-delivery is charged a penalty
+//! multiple of the reference price, over-delivery is paid a discounted one.
+//! Charges are queued as imbalance settlements and posted to balances by the
+//! settlement worker alongside trade settlements.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool, Row};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::{Config, ImbalanceConfig, ImbalancePriceSource};
+use crate::error::ApiError;
+use crate::middleware::metrics::track_imbalance_settlement;
+
+/// Epochs priced per run
+const EPOCHS_PER_RUN: i64 = 20;
+/// Charges posted per settlement cycle
+const SETTLE_BATCH: i64 = 100;
+
+const IMBALANCE_COLUMNS: &str = "id, epoch_id, user_id, contracted_kwh, metered_kwh, imbalance_kwh, \
+    price_per_kwh, amount, status, created_at, settled_at";
+
+/// Imbalance of one participant in one epoch
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ImbalanceSettlement {
+    pub id: Uuid,
+    pub epoch_id: Uuid,
+    pub user_id: Uuid,
+    /// Energy sold minus energy bought in the epoch's completed settlements
+    #[schema(value_type = String)]
+    pub contracted_kwh: Decimal,
+    /// Generation minus consumption metered in the epoch
+    #[schema(value_type = String)]
+    pub metered_kwh: Decimal,
+    /// Metered minus contracted; negative for under-delivery
+    #[schema(value_type = String)]
+    pub imbalance_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub price_per_kwh: Decimal,
+    /// Balance change of the participant: negative is a charge
+    #[schema(value_type = String)]
+    pub amount: Decimal,
+    /// balanced, pending or settled
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub settled_at: Option<DateTime<Utc>>,
+}
+
+/// Filters for listing imbalances
+#[derive(Debug, Clone, Default)]
+pub struct ImbalanceFilter {
+    pub epoch_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub limit: i64,
+}
+
+/// Imbalance of a participant and what it is charged or paid for it:
+/// (imbalance kWh, price per kWh, amount)
+fn price_imbalance(
+    contracted: Decimal,
+    metered: Decimal,
+    reference_price: Decimal,
+    config: &ImbalanceConfig,
+) -> (Decimal, Decimal, Decimal) {
+    let imbalance = metered - contracted;
+    let multiplier = if imbalance < Decimal::ZERO {
+        config.shortfall_multiplier
+    } else {
+        config.surplus_multiplier
+    };
+    let price = (reference_price * multiplier).round_dp(8);
+    (imbalance, price, (imbalance * price).round_dp(8))
+}
+
+#[derive(Clone)]
+pub struct ImbalanceService {
+    db: PgPool,
+    config: ImbalanceConfig,
+    grace_minutes: i64,
+}
+
+impl ImbalanceService {
+    pub fn new(db: PgPool, config: Config) -> Self {
+        Self {
+            db,
+            grace_minutes: config.delivery_verification.grace_minutes,
+            config: config.imbalance,
+        }
+    }
+
+    /// Price imbalances of epochs whose readings have had the delivery grace
+    /// period to arrive. Returns the number of charges queued.
+    pub async fn price_due(&self) -> Result<usize, ApiError> {
+        let epochs = sqlx::query(
+            r#"
+            SELECT e.id, e.start_time, e.end_time, e.clearing_price
+            FROM market_epochs e
+            WHERE e.end_time < NOW() - make_interval(mins => $1::int)
+              AND EXISTS (SELECT 1 FROM settlements s WHERE s.epoch_id = e.id AND s.status = 'completed')
+              AND NOT EXISTS (SELECT 1 FROM imbalance_settlements i WHERE i.epoch_id = e.id)
+            ORDER BY e.end_time
+            LIMIT $2
+            "#,
+        )
+        .bind(self.grace_minutes as i32)
+        .bind(EPOCHS_PER_RUN)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut queued = 0;
+        for epoch in epochs {
+            let clearing_price: Option<Decimal> = epoch.get("clearing_price");
+            let reference_price = match self.config.price_source {
+                ImbalancePriceSource::Clearing => clearing_price.unwrap_or(self.config.base_price),
+                ImbalancePriceSource::Fixed => self.config.base_price,
+            };
+            queued += self
+                .price_epoch(epoch.get("id"), epoch.get("start_time"), epoch.get("end_time"), reference_price)
+                .await?;
+        }
+        Ok(queued)
+    }
+
+    async fn price_epoch(
+        &self,
+        epoch_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        reference_price: Decimal,
+    ) -> Result<usize, ApiError> {
+        let positions = sqlx::query(
+            r#"
+            WITH traded AS (
+                SELECT user_id, SUM(kwh) AS net_kwh
+                FROM (
+                    SELECT seller_id AS user_id, energy_amount AS kwh
+                    FROM settlements WHERE epoch_id = $1 AND status = 'completed'
+                    UNION ALL
+                    SELECT buyer_id, -energy_amount
+                    FROM settlements WHERE epoch_id = $1 AND status = 'completed'
+                ) legs
+                GROUP BY user_id
+            ),
+            metered AS (
+                SELECT user_id, COALESCE(SUM(energy_generated), 0) - COALESCE(SUM(energy_consumed), 0) AS net_kwh
+                FROM meter_readings
+                WHERE user_id IN (SELECT user_id FROM traded)
+                  AND reading_timestamp >= $2 AND reading_timestamp < $3
+                GROUP BY user_id
+            )
+            SELECT t.user_id, t.net_kwh AS contracted_kwh, COALESCE(m.net_kwh, 0) AS metered_kwh
+            FROM traded t
+            LEFT JOIN metered m ON m.user_id = t.user_id
+            "#,
+        )
+        .bind(epoch_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+        let mut queued = 0;
+        for position in positions {
+            let user_id: Uuid = position.get("user_id");
+            let contracted: Decimal = position.get("contracted_kwh");
+            let metered: Decimal = position.get("metered_kwh");
+            let (imbalance, price, amount) = price_imbalance(contracted, metered, reference_price, &self.config);
+            let status = if amount.is_zero() { "balanced" } else { "pending" };
+
+            let inserted = sqlx::query(
+                r#"
+                INSERT INTO imbalance_settlements (
+                    epoch_id, user_id, contracted_kwh, metered_kwh, imbalance_kwh, price_per_kwh, amount, status
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (epoch_id, user_id) DO NOTHING
+                "#,
+            )
+            .bind(epoch_id)
+            .bind(user_id)
+            .bind(contracted)
+            .bind(metered)
+            .bind(imbalance)
+            .bind(price)
+            .bind(amount)
+            .bind(status)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::Database)?
+            .rows_affected()
+                > 0;
+
+            if inserted && status == "pending" {
+                queued += 1;
+                track_imbalance_settlement(if imbalance < Decimal::ZERO { "shortfall" } else { "surplus" });
+            }
+        }
+        tx.commit().await.map_err(ApiError::Database)?;
+
+        if queued > 0 {
+            info!(
+                "⚖️ Queued {} imbalance settlements for epoch {} at reference price {}",
+                queued, epoch_id, reference_price
+            );
+        }
+        Ok(queued)
+    }
+
+    /// Post pending imbalance charges and payments to participant balances.
+    /// Runs with the settlement worker; each charge is locked so concurrent
+    /// workers never post it twice.
+    pub async fn settle_pending(&self) -> Result<usize, ApiError> {
+        let pending: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM imbalance_settlements WHERE status = 'pending' ORDER BY created_at LIMIT $1",
+        )
+        .bind(SETTLE_BATCH)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut settled = 0;
+        for id in pending {
+            let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+            let Some(row) = sqlx::query(
+                r#"
+                SELECT epoch_id, user_id, imbalance_kwh, amount
+                FROM imbalance_settlements
+                WHERE id = $1 AND status = 'pending'
+                FOR UPDATE SKIP LOCKED
+                "#,
+            )
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(ApiError::Database)?
+            else {
+                continue;
+            };
+
+            let epoch_id: Uuid = row.get("epoch_id");
+            let user_id: Uuid = row.get("user_id");
+            let imbalance: Decimal = row.get("imbalance_kwh");
+            let amount: Decimal = row.get("amount");
+
+            sqlx::query("UPDATE users SET balance = COALESCE(balance, 0) + $1 WHERE id = $2")
+                .bind(amount)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::Database)?;
+            sqlx::query(
+                r#"
+                INSERT INTO token_ledger_adjustments (user_id, asset, amount, reason)
+                VALUES ($1, 'currency', $2, $3)
+                "#,
+            )
+            .bind(user_id)
+            .bind(amount)
+            .bind(format!("Imbalance settlement for epoch {}: {} kWh", epoch_id, imbalance))
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::Database)?;
+            sqlx::query("UPDATE imbalance_settlements SET status = 'settled', settled_at = NOW() WHERE id = $1")
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::Database)?;
+
+            match tx.commit().await {
+                Ok(()) => settled += 1,
+                Err(e) => warn!("⚠️ Failed to post imbalance settlement {}: {}", id, e),
+            }
+        }
+        Ok(settled)
+    }
+
+    /// Imbalances, newest epoch first
+    pub async fn list(&self, filter: ImbalanceFilter) -> Result<Vec<ImbalanceSettlement>, ApiError> {
+        sqlx::query_as::<_, ImbalanceSettlement>(&format!(
+            r#"
+            SELECT {} FROM imbalance_settlements
+            WHERE ($1::uuid IS NULL OR epoch_id = $1)
+              AND ($2::uuid IS NULL OR user_id = $2)
+            ORDER BY created_at DESC, user_id
+            LIMIT $3
+            "#,
+            IMBALANCE_COLUMNS
+        ))
+        .bind(filter.epoch_id)
+        .bind(filter.user_id)
+        .bind(filter.limit)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortfall_pays_penalty_and_surplus_is_paid_at_discount() {
+        let config = ImbalanceConfig::default();
+        let reference = Decimal::from(4);
+
+        // Sold 10 kWh net but exported 6: 4 kWh short at 4 * 1.5
+        let (imbalance, price, amount) = price_imbalance(Decimal::from(10), Decimal::from(6), reference, &config);
+        assert_eq!(imbalance, Decimal::from(-4));
+        assert_eq!(price, Decimal::from(6));
+        assert_eq!(amount, Decimal::from(-24));
+
+        // Bought 5 kWh net but consumed only 3: 2 kWh surplus at 4 * 0.5
+        let (imbalance, price, amount) = price_imbalance(Decimal::from(-5), Decimal::from(-3), reference, &config);
+        assert_eq!(imbalance, Decimal::from(2));
+        assert_eq!(price, Decimal::from(2));
+        assert_eq!(amount, Decimal::from(4));
+
+        let (_, _, amount) = price_imbalance(Decimal::from(3), Decimal::from(3), reference, &config);
+        assert!(amount.is_zero());
+    }
+}
@@ -0,0 +1,452 @@
+//! Invoicing Service
+//!
+//! Generates monthly statements (settled trades, fees, token minting and grid
+//! purchases) per account, renders them to PDF, stores them in object storage
+//! and issues time-limited signed download URLs.
+
+pub mod storage;
+pub mod templates;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool, Row};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::{GridTariffConfig, InvoicingConfig};
+use crate::models::pii::{SealedUserPii, USER_PII_COLUMNS};
+use crate::services::pii::PiiCipher;
+use crate::utils::{pdf, signed_url, MarketTimezone};
+
+pub use storage::ObjectStorage;
+pub use templates::InvoiceTemplates;
+
+/// Resource kind signed into invoice download links
+const DOWNLOAD_KIND: &str = "invoice-download";
+
+/// Stored invoice record
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct Invoice {
+    pub id: Uuid,
+    pub invoice_number: String,
+    pub user_id: Uuid,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub trade_count: i64,
+    pub energy_sold_kwh: f64,
+    pub energy_bought_kwh: f64,
+    pub sales_amount: f64,
+    pub purchases_amount: f64,
+    pub fees_amount: f64,
+    pub minted_kwh: f64,
+    pub grid_purchase_kwh: f64,
+    pub grid_purchase_amount: f64,
+    pub net_amount: f64,
+    /// Display currency of the fiat reference amounts
+    pub fiat_currency: Option<String>,
+    /// Trade amounts valued at the FX rate recorded at clearing time
+    pub sales_fiat_amount: Option<f64>,
+    pub purchases_fiat_amount: Option<f64>,
+    pub fees_fiat_amount: Option<f64>,
+    pub status: String,
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub storage_key: String,
+}
+
+/// Time-limited download link for a rendered statement
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SignedDownloadUrl {
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Values rendered into a statement
+#[derive(Debug, Clone)]
+pub struct InvoiceSummary {
+    pub invoice_number: String,
+    pub user_id: Uuid,
+    pub account_name: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub issued_at: DateTime<Utc>,
+    pub trade_count: i64,
+    pub energy_sold_kwh: f64,
+    pub energy_bought_kwh: f64,
+    pub sales_amount: f64,
+    pub purchases_amount: f64,
+    pub fees_amount: f64,
+    pub minted_kwh: f64,
+    pub grid_purchase_kwh: f64,
+    pub grid_tariff_per_kwh: f64,
+    pub grid_purchase_amount: f64,
+    pub net_amount: f64,
+    pub fiat_currency: String,
+    pub sales_fiat_amount: f64,
+    pub purchases_fiat_amount: f64,
+    pub fees_fiat_amount: f64,
+    /// Settled trades without a recorded FX rate (excluded from fiat amounts)
+    pub unpriced_trade_count: i64,
+}
+
+const INVOICE_COLUMNS: &str = r#"
+    id, invoice_number, user_id, period_start, period_end, trade_count,
+    energy_sold_kwh::FLOAT8 AS energy_sold_kwh, energy_bought_kwh::FLOAT8 AS energy_bought_kwh,
+    sales_amount::FLOAT8 AS sales_amount, purchases_amount::FLOAT8 AS purchases_amount,
+    fees_amount::FLOAT8 AS fees_amount, minted_kwh::FLOAT8 AS minted_kwh,
+    grid_purchase_kwh::FLOAT8 AS grid_purchase_kwh, grid_purchase_amount::FLOAT8 AS grid_purchase_amount,
+    net_amount::FLOAT8 AS net_amount, fiat_currency,
+    sales_fiat_amount::FLOAT8 AS sales_fiat_amount, purchases_fiat_amount::FLOAT8 AS purchases_fiat_amount,
+    fees_fiat_amount::FLOAT8 AS fees_fiat_amount, status, created_at, storage_key
+"#;
+
+#[derive(Clone)]
+pub struct InvoiceService {
+    db: PgPool,
+    storage: ObjectStorage,
+    config: InvoicingConfig,
+    tariff: GridTariffConfig,
+    /// Fiat currency for reference amounts on statements
+    display_currency: String,
+    pii: PiiCipher,
+    /// Statement months are calendar months in the market timezone
+    timezone: MarketTimezone,
+}
+
+impl InvoiceService {
+    pub fn new(
+        db: PgPool,
+        config: InvoicingConfig,
+        tariff: GridTariffConfig,
+        display_currency: String,
+        pii: PiiCipher,
+        timezone: MarketTimezone,
+    ) -> Self {
+        Self {
+            db,
+            storage: ObjectStorage::new(config.storage_dir.clone()),
+            config,
+            tariff,
+            display_currency,
+            pii,
+            timezone,
+        }
+    }
+
+    /// List a user's invoices, newest period first
+    pub async fn list_for_user(&self, user_id: Uuid) -> anyhow::Result<Vec<Invoice>> {
+        let query = format!(
+            "SELECT {} FROM invoices WHERE user_id = $1 ORDER BY period_start DESC",
+            INVOICE_COLUMNS
+        );
+        Ok(sqlx::query_as::<_, Invoice>(&query)
+            .bind(user_id)
+            .fetch_all(&self.db)
+            .await?)
+    }
+
+    pub async fn get(&self, invoice_id: Uuid) -> anyhow::Result<Option<Invoice>> {
+        let query = format!("SELECT {} FROM invoices WHERE id = $1", INVOICE_COLUMNS);
+        Ok(sqlx::query_as::<_, Invoice>(&query)
+            .bind(invoice_id)
+            .fetch_optional(&self.db)
+            .await?)
+    }
+
+    /// Generate (or return the existing) statement for a user and month
+    pub async fn generate_for_user(
+        &self,
+        user_id: Uuid,
+        year: i32,
+        month: u32,
+    ) -> anyhow::Result<Invoice> {
+        let (period_start, next_period) = month_bounds(year, month)
+            .ok_or_else(|| anyhow::anyhow!("Invalid statement period {}-{}", year, month))?;
+
+        let existing = format!(
+            "SELECT {} FROM invoices WHERE user_id = $1 AND period_start = $2",
+            INVOICE_COLUMNS
+        );
+        if let Some(invoice) = sqlx::query_as::<_, Invoice>(&existing)
+            .bind(user_id)
+            .bind(period_start)
+            .fetch_optional(&self.db)
+            .await?
+        {
+            return Ok(invoice);
+        }
+
+        let invoice_id = Uuid::new_v4();
+        let summary = self.summarize(invoice_id, user_id, period_start, next_period).await?;
+        let pdf_bytes = pdf::text_document(
+            &InvoiceTemplates::statement_title(&summary),
+            &InvoiceTemplates::statement_lines(&summary),
+        );
+
+        let storage_key = format!(
+            "invoices/{}/{}/{}.pdf",
+            user_id,
+            period_start.format("%Y-%m"),
+            invoice_id
+        );
+        self.storage.put(&storage_key, &pdf_bytes).await?;
+
+        let insert = format!(
+            r#"
+            INSERT INTO invoices (
+                id, invoice_number, user_id, period_start, period_end, trade_count,
+                energy_sold_kwh, energy_bought_kwh, sales_amount, purchases_amount, fees_amount,
+                minted_kwh, grid_purchase_kwh, grid_purchase_amount, net_amount, storage_key,
+                fiat_currency, sales_fiat_amount, purchases_fiat_amount, fees_fiat_amount
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
+            ON CONFLICT (user_id, period_start) DO NOTHING
+            RETURNING {}
+            "#,
+            INVOICE_COLUMNS
+        );
+        let inserted = sqlx::query_as::<_, Invoice>(&insert)
+            .bind(invoice_id)
+            .bind(&summary.invoice_number)
+            .bind(user_id)
+            .bind(period_start)
+            .bind(summary.period_end)
+            .bind(summary.trade_count)
+            .bind(summary.energy_sold_kwh)
+            .bind(summary.energy_bought_kwh)
+            .bind(summary.sales_amount)
+            .bind(summary.purchases_amount)
+            .bind(summary.fees_amount)
+            .bind(summary.minted_kwh)
+            .bind(summary.grid_purchase_kwh)
+            .bind(summary.grid_purchase_amount)
+            .bind(summary.net_amount)
+            .bind(&storage_key)
+            .bind(&summary.fiat_currency)
+            .bind(summary.sales_fiat_amount)
+            .bind(summary.purchases_fiat_amount)
+            .bind(summary.fees_fiat_amount)
+            .fetch_optional(&self.db)
+            .await?;
+
+        match inserted {
+            Some(invoice) => {
+                info!("🧾 Issued invoice {} for user {}", invoice.invoice_number, user_id);
+                Ok(invoice)
+            }
+            // A concurrent request generated the same statement first
+            None => sqlx::query_as::<_, Invoice>(&existing)
+                .bind(user_id)
+                .bind(period_start)
+                .fetch_one(&self.db)
+                .await
+                .map_err(Into::into),
+        }
+    }
+
+    /// Generate statements for every account with activity in the month
+    pub async fn generate_for_all(&self, year: i32, month: u32) -> anyhow::Result<usize> {
+        let (period_start, next_period) = month_bounds(year, month)
+            .ok_or_else(|| anyhow::anyhow!("Invalid statement period {}-{}", year, month))?;
+
+        let user_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT buyer_id FROM settlements WHERE created_at >= $1 AND created_at < $2
+            UNION
+            SELECT seller_id FROM settlements WHERE created_at >= $1 AND created_at < $2
+            UNION
+            SELECT user_id FROM meter_readings
+            WHERE reading_timestamp >= $1 AND reading_timestamp < $2 AND user_id IS NOT NULL
+            "#,
+        )
+        .bind(self.timezone.start_of_day(period_start))
+        .bind(self.timezone.start_of_day(next_period))
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut generated = 0;
+        for user_id in user_ids {
+            self.generate_for_user(user_id, year, month).await?;
+            generated += 1;
+        }
+        Ok(generated)
+    }
+
+    /// Issue statements for the previous market-local month; safe to call
+    /// repeatedly
+    pub async fn ensure_previous_month(&self) -> anyhow::Result<usize> {
+        let (year, month) = self.timezone.previous_month(Utc::now());
+        self.generate_for_all(year, month).await
+    }
+
+    pub fn timezone(&self) -> &MarketTimezone {
+        &self.timezone
+    }
+
+    /// Read a rendered statement from object storage
+    pub async fn load_pdf(&self, invoice: &Invoice) -> anyhow::Result<Vec<u8>> {
+        self.storage.get(&invoice.storage_key).await
+    }
+
+    /// Build a signed, time-limited download URL for an invoice
+    pub fn signed_download_url(&self, invoice_id: Uuid) -> SignedDownloadUrl {
+        let expires_at = signed_url::expires_at(&self.config.signed_urls);
+        SignedDownloadUrl {
+            url: signed_url::sign_url(
+                &self.config.signed_urls,
+                DOWNLOAD_KIND,
+                &format!("/api/v1/invoices/{}/download", invoice_id),
+                invoice_id,
+                expires_at,
+            ),
+            expires_at,
+        }
+    }
+
+    /// Check a download signature and its expiry
+    pub fn verify_download(&self, invoice_id: Uuid, expires: i64, signature: &str) -> bool {
+        signed_url::verify(&self.config.signed_urls, DOWNLOAD_KIND, invoice_id, expires, signature)
+    }
+
+    async fn summarize(
+        &self,
+        invoice_id: Uuid,
+        user_id: Uuid,
+        period_start: NaiveDate,
+        next_period: NaiveDate,
+    ) -> anyhow::Result<InvoiceSummary> {
+        let (username, sealed): (String, SealedUserPii) = {
+            let row = sqlx::query(&format!("SELECT username, {} FROM users WHERE id = $1", USER_PII_COLUMNS))
+                .bind(user_id)
+                .fetch_optional(&self.db)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("User {} not found", user_id))?;
+            (row.get("username"), SealedUserPii::from_row(&row)?)
+        };
+        let pii = sealed.open(&self.pii).await?;
+        let (from, to) = (self.timezone.start_of_day(period_start), self.timezone.start_of_day(next_period));
+        let full_name = format!("{} {}", pii.first_name.unwrap_or_default(), pii.last_name.unwrap_or_default());
+        let account_name = match full_name.trim() {
+            "" => username,
+            name => name.to_string(),
+        };
+
+        // Sellers receive net_amount; the difference to total_amount is fees and wheeling.
+        // Fiat amounts use the rate recorded per trade at clearing time.
+        let trades = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS trade_count,
+                COALESCE(SUM(energy_amount) FILTER (WHERE seller_id = $1), 0)::FLOAT8 AS sold_kwh,
+                COALESCE(SUM(energy_amount) FILTER (WHERE buyer_id = $1), 0)::FLOAT8 AS bought_kwh,
+                COALESCE(SUM(total_amount) FILTER (WHERE seller_id = $1), 0)::FLOAT8 AS sales,
+                COALESCE(SUM(total_amount - net_amount) FILTER (WHERE seller_id = $1), 0)::FLOAT8 AS fees,
+                COALESCE(SUM(total_amount) FILTER (WHERE buyer_id = $1), 0)::FLOAT8 AS purchases,
+                COALESCE(SUM(fiat_total_value) FILTER (WHERE seller_id = $1 AND fiat_currency = $4), 0)::FLOAT8 AS sales_fiat,
+                COALESCE(SUM((total_amount - net_amount) * fx_rate) FILTER (WHERE seller_id = $1 AND fiat_currency = $4), 0)::FLOAT8 AS fees_fiat,
+                COALESCE(SUM(fiat_total_value) FILTER (WHERE buyer_id = $1 AND fiat_currency = $4), 0)::FLOAT8 AS purchases_fiat,
+                COUNT(*) FILTER (WHERE fiat_total_value IS NULL OR fiat_currency IS DISTINCT FROM $4) AS unpriced_count
+            FROM settlements
+            WHERE (seller_id = $1 OR buyer_id = $1)
+              AND created_at >= $2 AND created_at < $3
+              AND status::text = 'completed'
+            "#,
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .bind(&self.display_currency)
+        .fetch_one(&self.db)
+        .await?;
+
+        let energy = sqlx::query(
+            r#"
+            SELECT
+                COALESCE(SUM(kwh_amount) FILTER (WHERE minted = TRUE), 0)::FLOAT8 AS minted_kwh,
+                COALESCE(SUM(deficit_energy), 0)::FLOAT8 AS deficit_kwh
+            FROM meter_readings
+            WHERE user_id = $1 AND reading_timestamp >= $2 AND reading_timestamp < $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.db)
+        .await?;
+
+        let bought: f64 = trades.get("bought_kwh");
+        let sales: f64 = trades.get("sales");
+        let fees: f64 = trades.get("fees");
+        let purchases: f64 = trades.get("purchases");
+
+        // Deficit not covered by P2P purchases was drawn from the grid
+        let grid_kwh = (energy.get::<f64, _>("deficit_kwh") - bought).max(0.0);
+        let grid_amount = grid_kwh * self.tariff.retail_per_kwh;
+        let issued_at = Utc::now();
+
+        Ok(InvoiceSummary {
+            invoice_number: invoice_number(period_start, invoice_id),
+            user_id,
+            account_name,
+            period_start,
+            period_end: next_period - Duration::days(1),
+            issued_at,
+            trade_count: trades.get("trade_count"),
+            energy_sold_kwh: trades.get("sold_kwh"),
+            energy_bought_kwh: bought,
+            sales_amount: sales,
+            purchases_amount: purchases,
+            fees_amount: fees,
+            minted_kwh: energy.get("minted_kwh"),
+            grid_purchase_kwh: grid_kwh,
+            grid_tariff_per_kwh: self.tariff.retail_per_kwh,
+            grid_purchase_amount: grid_amount,
+            net_amount: sales - purchases - fees - grid_amount,
+            fiat_currency: self.display_currency.clone(),
+            sales_fiat_amount: trades.get("sales_fiat"),
+            purchases_fiat_amount: trades.get("purchases_fiat"),
+            fees_fiat_amount: trades.get("fees_fiat"),
+            unpriced_trade_count: trades.get("unpriced_count"),
+        })
+    }
+}
+
+/// First day of the month and first day of the following month
+pub fn month_bounds(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)?
+    };
+    Some((start, next))
+}
+
+fn invoice_number(period_start: NaiveDate, invoice_id: Uuid) -> String {
+    let short = invoice_id.simple().to_string();
+    format!(
+        "INV-{}-{}",
+        period_start.format("%Y%m"),
+        short[..12].to_uppercase()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_bounds() {
+        let (start, next) = month_bounds(2025, 12).unwrap();
+        assert_eq!(start, NaiveDate::from_ymd_opt(2025, 12, 1).unwrap());
+        assert_eq!(next, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+        assert!(month_bounds(2025, 13).is_none());
+    }
+
+    #[test]
+    fn test_invoice_number_format() {
+        let id = Uuid::parse_str("0a1b2c3d-4e5f-0000-0000-000000000000").unwrap();
+        let number = invoice_number(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), id);
+        assert_eq!(number, "INV-202601-0A1B2C3D4E5F");
+    }
+}
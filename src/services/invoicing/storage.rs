@@ -0,0 +1,71 @@
+//! Object storage for rendered statements.
+//!
+//! Objects are addressed by slash-separated keys (e.g. `invoices/<user>/<id>.pdf`)
+//! and stored under a root directory, which can be a mounted bucket volume.
+
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Clone)]
+pub struct ObjectStorage {
+    root: PathBuf,
+}
+
+impl ObjectStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Store an object, replacing any existing object with the same key
+    pub async fn put(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, bytes).await?;
+        Ok(())
+    }
+
+    /// Read an object
+    pub async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let path = self.path_for(key)?;
+        Ok(tokio::fs::read(&path).await?)
+    }
+
+    /// Remove an object; a missing object is not an error
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Resolve a key to a path under the root, rejecting traversal
+    fn path_for(&self, key: &str) -> Result<PathBuf> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(anyhow!("Invalid object key: {}", key));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_traversal_keys() {
+        let storage = ObjectStorage::new("/tmp/objects");
+        assert!(storage.path_for("invoices/a/b.pdf").is_ok());
+        assert!(storage.path_for("../etc/passwd").is_err());
+        assert!(storage.path_for("/etc/passwd").is_err());
+        assert!(storage.path_for("").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+/// Statement templates for the GridTokenX Platform
+/// Lays out monthly invoice / fee statements as text lines for PDF rendering
+
+use super::InvoiceSummary;
+
+pub struct InvoiceTemplates;
+
+impl InvoiceTemplates {
+    /// Document title for a monthly statement
+    pub fn statement_title(summary: &InvoiceSummary) -> String {
+        format!("GridTokenX Monthly Statement {}", summary.invoice_number)
+    }
+
+    /// Body of a monthly statement
+    pub fn statement_lines(summary: &InvoiceSummary) -> Vec<String> {
+        let rule = "-".repeat(72);
+        let row = |label: &str, value: String| format!("{:<48}{:>24}", label, value);
+
+        vec![
+            format!("Account:       {}", summary.account_name),
+            format!("Account ID:    {}", summary.user_id),
+            format!(
+                "Period:        {} to {}",
+                summary.period_start.format("%Y-%m-%d"),
+                summary.period_end.format("%Y-%m-%d")
+            ),
+            format!("Issued:        {}", summary.issued_at.format("%Y-%m-%d %H:%M UTC")),
+            String::new(),
+            "P2P TRADING".to_string(),
+            rule.clone(),
+            row("Settled trades", summary.trade_count.to_string()),
+            row("Energy sold (kWh)", format!("{:.3}", summary.energy_sold_kwh)),
+            row("Sales", format!("{:.2}", summary.sales_amount)),
+            row("Energy bought (kWh)", format!("{:.3}", summary.energy_bought_kwh)),
+            row("Purchases", format!("{:.2}", summary.purchases_amount)),
+            String::new(),
+            "FEES".to_string(),
+            rule.clone(),
+            row("Platform fees and wheeling charges", format!("{:.2}", summary.fees_amount)),
+            String::new(),
+            "TOKEN MINTING".to_string(),
+            rule.clone(),
+            row("Energy minted as tokens (kWh)", format!("{:.3}", summary.minted_kwh)),
+            String::new(),
+            "GRID PURCHASES".to_string(),
+            rule.clone(),
+            row("Energy drawn from grid (kWh)", format!("{:.3}", summary.grid_purchase_kwh)),
+            row(
+                &format!("Grid cost at {:.2} per kWh", summary.grid_tariff_per_kwh),
+                format!("{:.2}", summary.grid_purchase_amount),
+            ),
+            String::new(),
+            rule.clone(),
+            row("NET (sales - purchases - fees - grid)", format!("{:.2}", summary.net_amount)),
+            rule.clone(),
+            String::new(),
+            format!("REFERENCE VALUE ({})", summary.fiat_currency),
+            rule.clone(),
+            row("Sales", format!("{:.2}", summary.sales_fiat_amount)),
+            row("Purchases", format!("{:.2}", summary.purchases_fiat_amount)),
+            row("Platform fees and wheeling charges", format!("{:.2}", summary.fees_fiat_amount)),
+            row("Trades without an exchange rate", summary.unpriced_trade_count.to_string()),
+            rule,
+            String::new(),
+            "This statement is generated automatically. Amounts reflect settled".to_string(),
+            "trades only; pending or failed settlements are excluded.".to_string(),
+            format!(
+                "Reference values use the {} rate recorded for each trade when it",
+                summary.fiat_currency
+            ),
+            "cleared; they are informational and do not change settled amounts.".to_string(),
+        ]
+    }
+}
@@ -0,0 +1,602 @@
+//! Background Jobs
+//!
+//! Common tracking of background job runs across subsystems. Each run of a
+//! tracked loop (report delivery, referral reward minting, monthly
+//! statements, audit retention, market rollups, data exports) is a row in `background_jobs`, with the
+//! lines it logs in `background_job_logs`. Admins list queued, running and
+//! failed runs, cancel them and retry failed ones. A retry is queued and
+//! claimed by the next run of its loop, which is woken early on the
+//! instance that accepted the retry. A running job stops at its next
+//! cancellation checkpoint; jobs without checkpoints finish their run.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tokio::sync::Notify;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::JobsConfig;
+use crate::error::{ApiError, Result};
+use crate::services::{AuditEvent, AuditLogger};
+
+/// Log lines returned with a job
+const MAX_LOG_LINES: i64 = 1000;
+
+const JOB_COLUMNS: &str = "id, kind, status, attempt, retry_of, requested_by, cancel_requested, cancelled_by, \
+    summary, error, created_at, started_at, finished_at, updated_at";
+
+/// Background jobs whose runs are tracked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    /// Scheduled report subscriptions whose period has closed
+    ReportDelivery,
+    /// Referral screening and reward minting
+    ReferralRewards,
+    /// Last month's statements
+    MonthlyStatements,
+    /// Purge of expired audit records
+    AuditRetention,
+    /// Daily market statistics archive
+    MarketRollups,
+    /// Files of queued user data exports
+    DataExports,
+}
+
+impl JobKind {
+    pub const ALL: [JobKind; 6] = [
+        Self::ReportDelivery,
+        Self::ReferralRewards,
+        Self::MonthlyStatements,
+        Self::AuditRetention,
+        Self::MarketRollups,
+        Self::DataExports,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ReportDelivery => "report_delivery",
+            Self::ReferralRewards => "referral_rewards",
+            Self::MonthlyStatements => "monthly_statements",
+            Self::AuditRetention => "audit_retention",
+            Self::MarketRollups => "market_rollups",
+            Self::DataExports => "data_exports",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Retry waiting for its loop
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        [Self::Queued, Self::Running, Self::Completed, Self::Failed, Self::Cancelled]
+            .into_iter()
+            .find(|status| status.as_str() == s)
+    }
+}
+
+/// Status of a run once its task returned
+pub fn finished_status(succeeded: bool, cancel_requested: bool) -> JobStatus {
+    match (succeeded, cancel_requested) {
+        (false, _) => JobStatus::Failed,
+        (true, true) => JobStatus::Cancelled,
+        (true, false) => JobStatus::Completed,
+    }
+}
+
+/// Only finished runs that did not complete can be retried
+pub fn can_retry(status: JobStatus) -> bool {
+    matches!(status, JobStatus::Failed | JobStatus::Cancelled)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A run of a background job
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct Job {
+    pub id: Uuid,
+    /// See `JobKind`
+    pub kind: String,
+    /// queued, running, completed, failed or cancelled
+    pub status: String,
+    /// 1 for a scheduled run, one more for each retry
+    pub attempt: i32,
+    /// Run this one retries
+    pub retry_of: Option<Uuid>,
+    /// Admin who queued the retry
+    pub requested_by: Option<Uuid>,
+    pub cancel_requested: bool,
+    pub cancelled_by: Option<Uuid>,
+    /// What a finished run did
+    pub summary: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Last log line or status change
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct JobLog {
+    pub id: i64,
+    /// info, warn or error
+    pub level: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A run with its log
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobDetail {
+    pub job: Job,
+    /// Oldest first, at most 1000 lines
+    pub logs: Vec<JobLog>,
+}
+
+/// Runs of one job kind over the last day, plus everything unfinished
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct JobKindSummary {
+    pub kind: String,
+    pub queued: i64,
+    pub running: i64,
+    pub completed: i64,
+    pub failed: i64,
+    pub cancelled: i64,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_status: Option<String>,
+}
+
+/// Handle of a run, passed to the job's task for logging and cancellation
+/// checkpoints. Tracking failures are logged and never stop the job.
+#[derive(Clone)]
+pub struct JobRun {
+    db: PgPool,
+    /// `None` when the run could not be recorded
+    id: Option<Uuid>,
+}
+
+impl JobRun {
+    pub fn id(&self) -> Option<Uuid> {
+        self.id
+    }
+
+    /// Append a line to the run's log
+    pub async fn log(&self, level: LogLevel, message: impl Into<String>) {
+        let Some(id) = self.id else {
+            return;
+        };
+        let message = message.into();
+        let result = sqlx::query(
+            r#"
+            WITH touched AS (UPDATE background_jobs SET updated_at = NOW() WHERE id = $1)
+            INSERT INTO background_job_logs (job_id, level, message) VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(id)
+        .bind(level.as_str())
+        .bind(&message)
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            warn!("Failed to log to background job {}: {}", id, e);
+        }
+    }
+
+    /// Whether an admin asked the run to stop; checked between units of work
+    pub async fn cancelled(&self) -> bool {
+        let Some(id) = self.id else {
+            return false;
+        };
+        sqlx::query_scalar::<_, bool>("SELECT cancel_requested FROM background_jobs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Clone)]
+pub struct JobQueue {
+    db: PgPool,
+    config: JobsConfig,
+    audit_logger: AuditLogger,
+    /// Wakes a kind's loop when a retry of it is queued on this instance
+    wakeups: Arc<HashMap<JobKind, Arc<Notify>>>,
+}
+
+impl JobQueue {
+    pub fn new(db: PgPool, config: JobsConfig, audit_logger: AuditLogger) -> Self {
+        let wakeups = JobKind::ALL
+            .into_iter()
+            .map(|kind| (kind, Arc::new(Notify::new())))
+            .collect();
+        Self {
+            db,
+            config,
+            audit_logger,
+            wakeups: Arc::new(wakeups),
+        }
+    }
+
+    /// Run `task` as a tracked run of `kind`. A queued retry of the kind is
+    /// claimed as the run; otherwise a new run is recorded. The task's
+    /// `Ok` value is kept as the run's summary.
+    pub async fn run<E, F, Fut>(&self, kind: JobKind, task: F) -> std::result::Result<String, E>
+    where
+        E: Display,
+        F: FnOnce(JobRun) -> Fut,
+        Fut: Future<Output = std::result::Result<String, E>>,
+    {
+        let run = JobRun {
+            db: self.db.clone(),
+            id: match self.begin(kind).await {
+                Ok(id) => Some(id),
+                Err(e) => {
+                    warn!("Failed to record {} run: {}", kind.as_str(), e);
+                    None
+                }
+            },
+        };
+
+        let result = task(run.clone()).await;
+        if let Some(id) = run.id {
+            let (summary, error) = match &result {
+                Ok(summary) => (Some(summary.as_str()), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            if let Err(e) = self.finish(id, summary, error.as_deref()).await {
+                warn!("Failed to record the end of background job {}: {}", id, e);
+            }
+        }
+        result
+    }
+
+    async fn begin(&self, kind: JobKind) -> Result<Uuid> {
+        let claimed: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            UPDATE background_jobs
+            SET status = 'running', started_at = NOW(), updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM background_jobs
+                WHERE kind = $1 AND status = 'queued'
+                ORDER BY created_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id
+            "#,
+        )
+        .bind(kind.as_str())
+        .fetch_optional(&self.db)
+        .await?;
+        if let Some(id) = claimed {
+            info!("🔁 Running queued retry {} of {}", id, kind.as_str());
+            return Ok(id);
+        }
+
+        Ok(sqlx::query_scalar(
+            "INSERT INTO background_jobs (kind, status, started_at) VALUES ($1, 'running', NOW()) RETURNING id",
+        )
+        .bind(kind.as_str())
+        .fetch_one(&self.db)
+        .await?)
+    }
+
+    async fn finish(&self, id: Uuid, summary: Option<&str>, error: Option<&str>) -> Result<()> {
+        let cancel_requested: bool =
+            sqlx::query_scalar("SELECT cancel_requested FROM background_jobs WHERE id = $1")
+                .bind(id)
+                .fetch_one(&self.db)
+                .await?;
+        let status = finished_status(error.is_none(), cancel_requested);
+        sqlx::query(
+            r#"
+            UPDATE background_jobs
+            SET status = $2, summary = $3, error = $4, finished_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(summary)
+        .bind(error)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Wake the loop of `kind` on this instance, e.g. when work was queued
+    /// for it
+    pub fn wake(&self, kind: JobKind) {
+        if let Some(wakeup) = self.wakeups.get(&kind) {
+            wakeup.notify_one();
+        }
+    }
+
+    /// Sleep for `interval`, or until a retry of `kind` is queued
+    pub async fn wait(&self, kind: JobKind, interval: Duration) {
+        match self.wakeups.get(&kind) {
+            Some(wakeup) => {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = wakeup.notified() => {}
+                }
+            }
+            None => tokio::time::sleep(interval).await,
+        }
+    }
+
+    /// Recent runs, newest first
+    pub async fn list(&self, status: Option<JobStatus>, kind: Option<JobKind>, limit: i64) -> Result<Vec<Job>> {
+        Ok(sqlx::query_as::<_, Job>(&format!(
+            r#"
+            SELECT {} FROM background_jobs
+            WHERE ($1::TEXT IS NULL OR status = $1)
+              AND ($2::TEXT IS NULL OR kind = $2)
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(status.map(|status| status.as_str()))
+        .bind(kind.map(|kind| kind.as_str()))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Run counts per kind
+    pub async fn summary(&self) -> Result<Vec<JobKindSummary>> {
+        Ok(sqlx::query_as::<_, JobKindSummary>(
+            r#"
+            SELECT
+                kind,
+                COUNT(*) FILTER (WHERE status = 'queued') AS queued,
+                COUNT(*) FILTER (WHERE status = 'running') AS running,
+                COUNT(*) FILTER (WHERE status = 'completed') AS completed,
+                COUNT(*) FILTER (WHERE status = 'failed') AS failed,
+                COUNT(*) FILTER (WHERE status = 'cancelled') AS cancelled,
+                MAX(started_at) AS last_run_at,
+                (ARRAY_AGG(status ORDER BY started_at DESC NULLS LAST))[1] AS last_status
+            FROM background_jobs
+            WHERE created_at >= NOW() - INTERVAL '1 day' OR status IN ('queued', 'running')
+            GROUP BY kind
+            ORDER BY kind
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<JobDetail> {
+        let job = self.find(id).await?;
+        let logs = self.logs(id, None, MAX_LOG_LINES).await?;
+        Ok(JobDetail { job, logs })
+    }
+
+    /// Log lines after `after` (a log line ID), oldest first
+    pub async fn logs(&self, id: Uuid, after: Option<i64>, limit: i64) -> Result<Vec<JobLog>> {
+        Ok(sqlx::query_as::<_, JobLog>(
+            r#"
+            SELECT id, level, message, created_at
+            FROM background_job_logs
+            WHERE job_id = $1 AND id > COALESCE($2, 0)
+            ORDER BY id
+            LIMIT $3
+            "#,
+        )
+        .bind(id)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Job> {
+        sqlx::query_as::<_, Job>(&format!("SELECT {} FROM background_jobs WHERE id = $1", JOB_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Background job {} not found", id)))
+    }
+
+    /// Cancel a queued run outright, or ask a running one to stop
+    pub async fn cancel(&self, admin_id: Uuid, id: Uuid) -> Result<Job> {
+        let job = sqlx::query_as::<_, Job>(&format!(
+            r#"
+            UPDATE background_jobs SET
+                status = CASE WHEN status = 'queued' THEN 'cancelled' ELSE status END,
+                finished_at = CASE WHEN status = 'queued' THEN NOW() ELSE finished_at END,
+                cancel_requested = true,
+                cancelled_by = $2,
+                updated_at = NOW()
+            WHERE id = $1 AND status IN ('queued', 'running')
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(id)
+        .bind(admin_id)
+        .fetch_optional(&self.db)
+        .await?;
+        let job = match job {
+            Some(job) => job,
+            None => {
+                let job = self.find(id).await?;
+                return Err(ApiError::Conflict(format!("Background job {} is already {}", id, job.status)));
+            }
+        };
+
+        self.audit(admin_id, "background_job_cancelled", &job);
+        Ok(job)
+    }
+
+    /// Queue another run of a failed or cancelled run's job
+    pub async fn retry(&self, admin_id: Uuid, id: Uuid) -> Result<Job> {
+        let original = self.find(id).await?;
+        let status = JobStatus::parse(&original.status)
+            .ok_or_else(|| ApiError::Internal(format!("Unknown job status {}", original.status)))?;
+        if !can_retry(status) {
+            return Err(ApiError::Conflict(format!(
+                "Only failed or cancelled jobs can be retried; {} is {}",
+                id, original.status
+            )));
+        }
+        let kind = JobKind::parse(&original.kind)
+            .ok_or_else(|| ApiError::BadRequest(format!("Jobs of kind {} cannot be retried", original.kind)))?;
+
+        let queued: Option<Uuid> =
+            sqlx::query_scalar("SELECT id FROM background_jobs WHERE kind = $1 AND status = 'queued' LIMIT 1")
+                .bind(kind.as_str())
+                .fetch_optional(&self.db)
+                .await?;
+        if let Some(queued) = queued {
+            return Err(ApiError::Conflict(format!(
+                "A {} run is already queued ({})",
+                kind.as_str(),
+                queued
+            )));
+        }
+
+        let job = sqlx::query_as::<_, Job>(&format!(
+            r#"
+            INSERT INTO background_jobs (kind, status, attempt, retry_of, requested_by)
+            VALUES ($1, 'queued', $2, $3, $4)
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(kind.as_str())
+        .bind(original.attempt + 1)
+        .bind(original.id)
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        self.wake(kind);
+        self.audit(admin_id, "background_job_retried", &job);
+        Ok(job)
+    }
+
+    fn audit(&self, admin_id: Uuid, action: &str, job: &Job) {
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "job_id": job.id,
+                "kind": job.kind,
+                "status": job.status,
+                "retry_of": job.retry_of,
+            })
+            .to_string(),
+        });
+    }
+
+    /// Fail runs that stopped logging (e.g. after a restart) and delete
+    /// finished runs past the retention period
+    pub async fn housekeep(&self) -> Result<(u64, u64)> {
+        let interrupted = sqlx::query(
+            r#"
+            UPDATE background_jobs
+            SET status = 'failed', error = 'Interrupted', finished_at = NOW(), updated_at = NOW()
+            WHERE status = 'running' AND updated_at < NOW() - make_interval(mins => $1::int)
+            "#,
+        )
+        .bind(self.config.stale_after_mins as i32)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+
+        let pruned = sqlx::query(
+            r#"
+            DELETE FROM background_jobs
+            WHERE status IN ('completed', 'failed', 'cancelled')
+              AND finished_at < NOW() - make_interval(days => $1::int)
+            "#,
+        )
+        .bind(self.config.retention_days as i32)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+
+        if interrupted > 0 {
+            warn!("⚠️ Marked {} interrupted background jobs as failed", interrupted);
+        }
+        Ok((interrupted, pruned))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finished_status() {
+        assert_eq!(finished_status(true, false), JobStatus::Completed);
+        assert_eq!(finished_status(true, true), JobStatus::Cancelled);
+        // A failure is reported as such even when a cancel was requested
+        assert_eq!(finished_status(false, true), JobStatus::Failed);
+    }
+
+    #[test]
+    fn test_only_unsuccessful_runs_are_retried() {
+        assert!(can_retry(JobStatus::Failed));
+        assert!(can_retry(JobStatus::Cancelled));
+        assert!(!can_retry(JobStatus::Completed));
+        assert!(!can_retry(JobStatus::Running));
+        assert!(!can_retry(JobStatus::Queued));
+        assert_eq!(JobKind::parse("report_delivery"), Some(JobKind::ReportDelivery));
+        assert_eq!(JobKind::parse("orderbook_cache"), None);
+    }
+}
@@ -0,0 +1,241 @@
+//! Leaderboard Service
+//!
+//! Computes daily community leaderboards (top generators, top CO2 savers,
+//! most traded volume) over a rolling 30-day window. Only users who opted in
+//! are ranked, and only their display name is published.
+
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::services::EmissionFactorService;
+
+/// Number of days of activity each daily leaderboard covers
+pub const LEADERBOARD_WINDOW_DAYS: i64 = 30;
+
+/// Maximum ranks stored per category
+pub const LEADERBOARD_SIZE: i64 = 100;
+
+/// Leaderboard categories
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardCategory {
+    /// Total energy generated (kWh)
+    Generation,
+    /// Grid consumption displaced by self-consumption and P2P purchases (kg CO2)
+    Co2Saved,
+    /// Energy traded as buyer or seller (kWh)
+    TradedVolume,
+}
+
+impl LeaderboardCategory {
+    pub const ALL: [LeaderboardCategory; 3] = [
+        LeaderboardCategory::Generation,
+        LeaderboardCategory::Co2Saved,
+        LeaderboardCategory::TradedVolume,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardCategory::Generation => "generation",
+            LeaderboardCategory::Co2Saved => "co2_saved",
+            LeaderboardCategory::TradedVolume => "traded_volume",
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            LeaderboardCategory::Co2Saved => "kg",
+            _ => "kWh",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == value)
+    }
+
+    /// Per-user value query; `$1` is the window start, `$2` the window end.
+    /// CO2 is estimated with `co2_kg_per_kwh`.
+    fn value_query(&self, co2_kg_per_kwh: f64) -> String {
+        let value_cte = match self {
+            LeaderboardCategory::Generation => r#"
+                SELECT user_id, COALESCE(SUM(energy_generated), 0)::FLOAT8 AS value
+                FROM meter_readings
+                WHERE reading_timestamp >= $1 AND reading_timestamp < $2
+                GROUP BY user_id
+            "#
+            .to_string(),
+            LeaderboardCategory::Co2Saved => format!(
+                r#"
+                SELECT user_id, SUM(kwh) * {} AS value
+                FROM (
+                    SELECT user_id,
+                           GREATEST(COALESCE(energy_generated, 0) - COALESCE(surplus_energy, 0), 0)::FLOAT8 AS kwh
+                    FROM meter_readings
+                    WHERE reading_timestamp >= $1 AND reading_timestamp < $2
+                    UNION ALL
+                    SELECT buyer_id, energy_amount::FLOAT8
+                    FROM settlements
+                    WHERE created_at >= $1 AND created_at < $2 AND status::text <> 'failed'
+                ) displaced
+                GROUP BY user_id
+                "#,
+                co2_kg_per_kwh
+            ),
+            LeaderboardCategory::TradedVolume => r#"
+                SELECT user_id, SUM(kwh) AS value
+                FROM (
+                    SELECT buyer_id AS user_id, energy_amount::FLOAT8 AS kwh
+                    FROM settlements
+                    WHERE created_at >= $1 AND created_at < $2 AND status::text <> 'failed'
+                    UNION ALL
+                    SELECT seller_id, energy_amount::FLOAT8
+                    FROM settlements
+                    WHERE created_at >= $1 AND created_at < $2 AND status::text <> 'failed'
+                ) traded
+                GROUP BY user_id
+            "#
+            .to_string(),
+        };
+
+        format!(
+            r#"
+            WITH per_user AS ({})
+            SELECT p.user_id, COALESCE(u.display_name, 'Anonymous') AS display_name, p.value
+            FROM per_user p
+            JOIN users u ON u.id = p.user_id
+            WHERE u.leaderboard_opt_in = TRUE AND u.is_active = TRUE AND p.value > 0
+            ORDER BY p.value DESC
+            LIMIT {}
+            "#,
+            value_cte, LEADERBOARD_SIZE
+        )
+    }
+}
+
+/// Leaderboard computation service
+#[derive(Clone)]
+pub struct LeaderboardService {
+    db: PgPool,
+    emission_factors: EmissionFactorService,
+}
+
+impl LeaderboardService {
+    pub fn new(db: PgPool, emission_factors: EmissionFactorService) -> Self {
+        Self { db, emission_factors }
+    }
+
+    /// Compute today's snapshot if it has not been computed yet.
+    /// Returns true when a new snapshot was written.
+    pub async fn ensure_daily_snapshot(&self) -> anyhow::Result<bool> {
+        let today = Utc::now().date_naive();
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM community_stats_daily WHERE snapshot_date = $1)",
+        )
+        .bind(today)
+        .fetch_one(&self.db)
+        .await?;
+
+        if exists {
+            return Ok(false);
+        }
+
+        self.compute_snapshot(today).await?;
+        Ok(true)
+    }
+
+    /// Rebuild the leaderboards and community totals for `date`
+    pub async fn compute_snapshot(&self, date: NaiveDate) -> anyhow::Result<()> {
+        let window_end = date
+            .and_hms_opt(0, 0, 0)
+            .map(|dt| dt.and_utc())
+            .ok_or_else(|| anyhow::anyhow!("Invalid snapshot date {}", date))?;
+        let window_start = window_end - Duration::days(LEADERBOARD_WINDOW_DAYS);
+        let factor = self.emission_factors.factor_for(date.year()).await;
+
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query("DELETE FROM leaderboard_snapshots WHERE snapshot_date = $1")
+            .bind(date)
+            .execute(&mut *tx)
+            .await?;
+
+        for category in LeaderboardCategory::ALL {
+            let rows = sqlx::query_as::<_, (uuid::Uuid, String, f64)>(&category.value_query(factor.kg_co2_per_kwh))
+                .bind(window_start)
+                .bind(window_end)
+                .fetch_all(&mut *tx)
+                .await?;
+
+            for (idx, (user_id, display_name, value)) in rows.into_iter().enumerate() {
+                sqlx::query(
+                    "INSERT INTO leaderboard_snapshots (snapshot_date, category, rank, user_id, display_name, value)
+                     VALUES ($1, $2, $3, $4, $5, $6)",
+                )
+                .bind(date)
+                .bind(category.as_str())
+                .bind(idx as i32 + 1)
+                .bind(user_id)
+                .bind(display_name)
+                .bind(value)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO community_stats_daily (
+                snapshot_date, participants, total_generated_kwh, total_co2_saved_kg, total_traded_kwh,
+                co2_factor, computed_at
+            )
+            SELECT
+                $1,
+                (SELECT COUNT(DISTINCT user_id) FROM meter_readings
+                 WHERE reading_timestamp >= $2 AND reading_timestamp < $3),
+                (SELECT COALESCE(SUM(energy_generated), 0)::FLOAT8 FROM meter_readings
+                 WHERE reading_timestamp >= $2 AND reading_timestamp < $3),
+                (SELECT COALESCE(SUM(energy_generated), 0)::FLOAT8 FROM meter_readings
+                 WHERE reading_timestamp >= $2 AND reading_timestamp < $3) * $4,
+                (SELECT COALESCE(SUM(energy_amount), 0)::FLOAT8 FROM settlements
+                 WHERE created_at >= $2 AND created_at < $3 AND status::text <> 'failed'),
+                $5,
+                NOW()
+            ON CONFLICT (snapshot_date) DO UPDATE SET
+                participants = EXCLUDED.participants,
+                total_generated_kwh = EXCLUDED.total_generated_kwh,
+                total_co2_saved_kg = EXCLUDED.total_co2_saved_kg,
+                total_traded_kwh = EXCLUDED.total_traded_kwh,
+                co2_factor = EXCLUDED.co2_factor,
+                computed_at = NOW()
+            "#,
+        )
+        .bind(date)
+        .bind(window_start)
+        .bind(window_end)
+        .bind(factor.kg_co2_per_kwh)
+        .bind(serde_json::to_value(&factor)?)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        info!("🏆 Leaderboard snapshot computed for {}", date);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_round_trip() {
+        for category in LeaderboardCategory::ALL {
+            assert_eq!(LeaderboardCategory::parse(category.as_str()), Some(category));
+        }
+        assert_eq!(LeaderboardCategory::parse("unknown"), None);
+    }
+}
@@ -0,0 +1,274 @@
+//! Ledger History
+//!
+//! Account state as of a past point in time for audits: balances from the
+//! balance journal, open orders from the order event journal, wallets from
+//! the wallet link history and certificate ownership from the transfer log.
+//! Every answer is derived only from journal rows recorded at or before the
+//! requested time, so a report run twice gives the same result.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+
+/// Balances of an account at a point in time
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct BalanceAsOf {
+    #[schema(value_type = String)]
+    pub balance: Decimal,
+    #[schema(value_type = String)]
+    pub locked_amount: Decimal,
+    #[schema(value_type = String)]
+    pub locked_energy: Decimal,
+    /// Journal entry the balances come from: baseline, opened or changed
+    pub entry_type: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// An order that was open at a point in time
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct OpenOrderAsOf {
+    pub order_id: Uuid,
+    pub market_id: Uuid,
+    pub side: String,
+    pub order_type: String,
+    #[schema(value_type = String)]
+    pub energy_amount: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub price_per_kwh: Option<Decimal>,
+    #[schema(value_type = String)]
+    pub filled: Decimal,
+    #[schema(value_type = String)]
+    pub remaining: Decimal,
+    /// accepted or partially_filled
+    pub last_event: String,
+    pub last_event_at: DateTime<Utc>,
+}
+
+/// A certificate and who held it at a point in time
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct CertificateAsOf {
+    pub id: Uuid,
+    pub certificate_id: String,
+    /// Wallet holding the certificate at the time
+    pub owner_wallet: String,
+    #[schema(value_type = Option<String>)]
+    pub kwh_amount: Option<Decimal>,
+    pub issue_date: Option<DateTime<Utc>>,
+    pub expiry_date: Option<DateTime<Utc>>,
+    pub retired: bool,
+    pub expired: bool,
+}
+
+/// An account as of a point in time
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountAsOf {
+    pub user_id: Uuid,
+    pub as_of: DateTime<Utc>,
+    pub balances: BalanceAsOf,
+    /// Wallets linked to the account at the time
+    pub wallets: Vec<String>,
+    /// Orders accepted and not yet filled, cancelled or expired; orders
+    /// placed before the order event journal existed are not included
+    pub open_orders: Vec<OpenOrderAsOf>,
+    /// Certificates held by the account's wallets
+    pub certificates: Vec<CertificateAsOf>,
+}
+
+/// Owner of each certificate issued by `$2`: the recipient of its last
+/// transfer up to then, else the sender of its first later transfer, else
+/// its current wallet
+const CERTIFICATES_AS_OF: &str = r#"
+    SELECT c.id, c.certificate_id,
+           COALESCE(
+               (SELECT t.to_wallet FROM erc_certificate_transfers t
+                WHERE t.certificate_id = c.id AND t.transfer_date <= $2
+                ORDER BY t.transfer_date DESC LIMIT 1),
+               (SELECT t.from_wallet FROM erc_certificate_transfers t
+                WHERE t.certificate_id = c.id AND t.transfer_date > $2
+                ORDER BY t.transfer_date ASC LIMIT 1),
+               c.wallet_address
+           ) AS owner_wallet,
+           c.kwh_amount, c.issue_date, c.expiry_date,
+           COALESCE(c.retired_at <= $2, FALSE) AS retired,
+           COALESCE(c.expiry_date <= $2, FALSE) AS expired
+    FROM erc_certificates c
+    WHERE c.created_at <= $2
+"#;
+
+/// History can only be asked about the past
+fn check_as_of(as_of: DateTime<Utc>, now: DateTime<Utc>) -> Result<()> {
+    if as_of > now {
+        return Err(ApiError::validation_field("at", "Time must not be in the future"));
+    }
+    Ok(())
+}
+
+/// Why an account has no balance journal entry at a time, given its first
+/// entry: before the journal baseline, before the account opened, or no
+/// such account
+fn missing_balance_error(first: Option<(String, DateTime<Utc>)>) -> ApiError {
+    match first {
+        Some((entry_type, recorded_at)) if entry_type == "baseline" => ApiError::validation_field(
+            "at",
+            format!("Balance history starts at {}", recorded_at.to_rfc3339()),
+        ),
+        Some(_) => ApiError::NotFound("Account did not exist at that time".to_string()),
+        None => ApiError::NotFound("User not found".to_string()),
+    }
+}
+
+#[derive(Clone)]
+pub struct LedgerHistoryService {
+    db: PgPool,
+}
+
+impl LedgerHistoryService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Balances, wallets, open orders and certificates of an account at `as_of`
+    pub async fn account(&self, user_id: Uuid, as_of: DateTime<Utc>) -> Result<AccountAsOf> {
+        check_as_of(as_of, Utc::now())?;
+        let balances = self.balances(user_id, as_of).await?;
+        let wallets = self.wallets(user_id, as_of).await?;
+        let open_orders = self.open_orders(user_id, as_of).await?;
+        let certificates = sqlx::query_as::<_, CertificateAsOf>(&format!(
+            "SELECT * FROM ({}) owned WHERE owner_wallet = ANY($1) ORDER BY issue_date, certificate_id",
+            CERTIFICATES_AS_OF
+        ))
+        .bind(&wallets)
+        .bind(as_of)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(AccountAsOf {
+            user_id,
+            as_of,
+            balances,
+            wallets,
+            open_orders,
+            certificates,
+        })
+    }
+
+    /// One certificate and its holder at `as_of`
+    pub async fn certificate(&self, certificate_id: Uuid, as_of: DateTime<Utc>) -> Result<CertificateAsOf> {
+        sqlx::query_as::<_, CertificateAsOf>(&format!("SELECT * FROM ({}) owned WHERE id = $1", CERTIFICATES_AS_OF))
+            .bind(certificate_id)
+            .bind(as_of)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Certificate not found or not yet issued at that time".to_string()))
+    }
+
+    async fn balances(&self, user_id: Uuid, as_of: DateTime<Utc>) -> Result<BalanceAsOf> {
+        let entry = sqlx::query_as::<_, BalanceAsOf>(
+            r#"
+            SELECT balance, locked_amount, locked_energy, entry_type, recorded_at
+            FROM balance_journal
+            WHERE user_id = $1 AND recorded_at <= $2
+            ORDER BY recorded_at DESC, id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(as_of)
+        .fetch_optional(&self.db)
+        .await?;
+        if let Some(entry) = entry {
+            return Ok(entry);
+        }
+
+        // Nothing yet: either the account did not exist or the journal had not started
+        let first: Option<(String, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT entry_type, recorded_at FROM balance_journal WHERE user_id = $1 ORDER BY recorded_at, id LIMIT 1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Err(missing_balance_error(first))
+    }
+
+    async fn wallets(&self, user_id: Uuid, as_of: DateTime<Utc>) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar(
+            r#"
+            SELECT wallet_address FROM wallet_link_history
+            WHERE user_id = $1
+              AND COALESCE(linked_at, created_at) <= $2
+              AND (unlinked_at IS NULL OR unlinked_at > $2)
+            ORDER BY COALESCE(linked_at, created_at)
+            "#,
+        )
+        .bind(user_id)
+        .bind(as_of)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    async fn open_orders(&self, user_id: Uuid, as_of: DateTime<Utc>) -> Result<Vec<OpenOrderAsOf>> {
+        Ok(sqlx::query_as::<_, OpenOrderAsOf>(
+            r#"
+            WITH last_event AS (
+                SELECT DISTINCT ON (order_id) order_id, event_type, cumulative_filled, remaining, created_at
+                FROM order_events
+                WHERE user_id = $1 AND created_at <= $2
+                ORDER BY order_id, created_at DESC
+            )
+            SELECT o.id AS order_id, o.market_id, o.side::text AS side, o.order_type::text AS order_type,
+                   o.energy_amount, o.price_per_kwh,
+                   COALESCE(e.cumulative_filled, 0) AS filled,
+                   COALESCE(e.remaining, o.energy_amount - COALESCE(e.cumulative_filled, 0)) AS remaining,
+                   e.event_type AS last_event, e.created_at AS last_event_at
+            FROM last_event e
+            JOIN trading_orders o ON o.id = e.order_id
+            WHERE e.event_type IN ('accepted', 'partially_filled', 'reopened')
+            ORDER BY o.created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(as_of)
+        .fetch_all(&self.db)
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_check_as_of() {
+        let now = Utc::now();
+        assert!(check_as_of(now - Duration::days(30), now).is_ok());
+        assert!(check_as_of(now, now).is_ok());
+        assert!(matches!(
+            check_as_of(now + Duration::seconds(1), now),
+            Err(ApiError::ValidationWithField { field, .. }) if field == "at"
+        ));
+    }
+
+    #[test]
+    fn test_missing_balance_error() {
+        let started = Utc::now() - Duration::days(10);
+
+        match missing_balance_error(Some(("baseline".to_string(), started))) {
+            ApiError::ValidationWithField { field, message, .. } => {
+                assert_eq!(field, "at");
+                assert!(message.contains(&started.to_rfc3339()));
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+        assert!(matches!(
+            missing_balance_error(Some(("opened".to_string(), started))),
+            ApiError::NotFound(message) if message.contains("did not exist")
+        ));
+        assert!(matches!(missing_balance_error(None), ApiError::NotFound(message) if message == "User not found"));
+    }
+}
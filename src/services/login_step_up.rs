@@ -0,0 +1,371 @@
+//! Login Step-Up Authentication
+//!
+//! Scores password logins against the user's recent login history. A login
+//! from a country or device the user has not logged in from before, or one
+//! following an access anomaly flagged in the last day, is risky: the session
+//! token is withheld until the user enters a one-time code sent to their
+//! email. The decision and the outcome of every challenge go to the audit log.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::StepUpConfig;
+use crate::error::{ApiError, ErrorCode, Result};
+use crate::i18n;
+use crate::models::pii::{SealedUserPii, USER_PII_COLUMNS};
+use crate::services::{AuditEvent, AuditLogger, EmailService, GeoIpService, PiiCipher};
+
+/// Access anomalies flagged within this many hours make a login risky
+const RECENT_ANOMALY_HOURS: i64 = 24;
+/// Only delivery method until the platform offers MFA
+const METHOD_EMAIL_OTP: &str = "email_otp";
+
+/// Why a login was scored risky
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskSignal {
+    /// Located (GeoIP) in a country the user has not logged in from
+    NewCountry,
+    /// User agent the user has not logged in with
+    NewDevice,
+    /// Access anomaly flagged for the user in the last day
+    RecentAnomaly,
+}
+
+impl RiskSignal {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NewCountry => "new_country",
+            Self::NewDevice => "new_device",
+            Self::RecentAnomaly => "recent_anomaly",
+        }
+    }
+}
+
+/// The user's successful logins within the history window, compared with
+/// the login being scored
+#[derive(Debug, Clone, Copy, Default, FromRow)]
+pub struct LoginHistory {
+    pub logins: i64,
+    /// Logins GeoIP placed in a country
+    pub located: i64,
+    pub same_country: i64,
+    pub same_device: i64,
+    pub recent_anomalies: i64,
+}
+
+/// Signals a login raises; a user without login history raises none, so
+/// first logins after registration go through
+pub fn score_login(history: &LoginHistory, located: bool) -> Vec<RiskSignal> {
+    let mut signals = Vec::new();
+    if history.logins == 0 {
+        return signals;
+    }
+    if located && history.located > 0 && history.same_country == 0 {
+        signals.push(RiskSignal::NewCountry);
+    }
+    if history.same_device == 0 {
+        signals.push(RiskSignal::NewDevice);
+    }
+    if history.recent_anomalies > 0 {
+        signals.push(RiskSignal::RecentAnomaly);
+    }
+    signals
+}
+
+/// Stored form of a one-time code, bound to its challenge
+pub fn code_hash(challenge_id: Uuid, code: &str) -> String {
+    hex::encode(Sha256::digest(format!("{}:{}", challenge_id, code.trim())))
+}
+
+/// Challenge returned instead of a session token
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StepUpChallenge {
+    pub challenge_id: Uuid,
+    /// email_otp
+    pub method: String,
+    pub signals: Vec<RiskSignal>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Outcome of scoring a login
+#[derive(Debug, Clone)]
+pub enum LoginDecision {
+    Allow,
+    StepUp(StepUpChallenge),
+}
+
+#[derive(FromRow)]
+struct ChallengeRow {
+    user_id: Uuid,
+    code_hash: String,
+    attempts: i32,
+    expires_at: DateTime<Utc>,
+    verified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+pub struct LoginStepUpService {
+    db: PgPool,
+    geoip: GeoIpService,
+    email: Option<EmailService>,
+    pii: PiiCipher,
+    audit: AuditLogger,
+    config: StepUpConfig,
+}
+
+impl LoginStepUpService {
+    pub fn new(
+        db: PgPool,
+        geoip: GeoIpService,
+        email: Option<EmailService>,
+        pii: PiiCipher,
+        audit: AuditLogger,
+        config: StepUpConfig,
+    ) -> Self {
+        Self {
+            db,
+            geoip,
+            email,
+            pii,
+            audit,
+            config,
+        }
+    }
+
+    /// Score a login whose password checked out and, when risky, send a
+    /// one-time code in place of the session token
+    pub async fn assess(
+        &self,
+        user_id: Uuid,
+        username: &str,
+        ip: &str,
+        user_agent: Option<&str>,
+    ) -> Result<LoginDecision> {
+        if !self.config.enabled {
+            return Ok(LoginDecision::Allow);
+        }
+
+        let country = self.geoip.lookup(ip).and_then(|geo| geo.country);
+        let now = Utc::now();
+        let history = sqlx::query_as::<_, LoginHistory>(
+            r#"
+            SELECT COUNT(*) AS logins,
+                   COUNT(*) FILTER (WHERE event_data->'geo' ? 'country') AS located,
+                   COUNT(*) FILTER (WHERE event_data->'geo'->>'country' = $3) AS same_country,
+                   COUNT(*) FILTER (WHERE event_data->>'user_agent' IS NOT DISTINCT FROM $4) AS same_device,
+                   (SELECT COUNT(*) FROM security_anomalies
+                    WHERE user_id = $1 AND created_at >= $5) AS recent_anomalies
+            FROM audit_logs
+            WHERE event_type = 'user_login' AND user_id = $1 AND created_at >= $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(now - Duration::days(self.config.history_days))
+        .bind(&country)
+        .bind(user_agent)
+        .bind(now - Duration::hours(RECENT_ANOMALY_HOURS))
+        .fetch_one(&self.db)
+        .await?;
+
+        let signals = score_login(&history, country.is_some());
+        if signals.is_empty() {
+            return Ok(LoginDecision::Allow);
+        }
+        let signal_names: Vec<String> = signals.iter().map(|s| s.as_str().to_string()).collect();
+
+        let Some(email_service) = self.email.as_ref().filter(|email| email.is_enabled()) else {
+            warn!(
+                "⚠️ Risky login for user {} ({}) allowed: no channel to deliver a code",
+                user_id,
+                signal_names.join(", ")
+            );
+            self.audit.log_async(AuditEvent::LoginRiskAssessed {
+                user_id,
+                ip: ip.to_string(),
+                user_agent: user_agent.map(str::to_string),
+                signals: signal_names,
+                decision: "allow_no_channel".to_string(),
+                challenge_id: None,
+            });
+            return Ok(LoginDecision::Allow);
+        };
+
+        let sealed = sqlx::query_as::<_, SealedUserPii>(&format!(
+            "SELECT {} FROM users WHERE id = $1",
+            USER_PII_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+        let email = sealed.open(&self.pii).await?.email;
+
+        let challenge_id = Uuid::new_v4();
+        let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000));
+        let expires_at = now + Duration::seconds(self.config.code_ttl_secs);
+        sqlx::query(
+            r#"
+            INSERT INTO login_step_up_challenges
+                (id, user_id, method, code_hash, signals, ip_address, user_agent, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(challenge_id)
+        .bind(user_id)
+        .bind(METHOD_EMAIL_OTP)
+        .bind(code_hash(challenge_id, &code))
+        .bind(&signal_names)
+        .bind(ip)
+        .bind(user_agent)
+        .bind(expires_at)
+        .execute(&self.db)
+        .await?;
+
+        let locale = i18n::user_locale(&self.db, user_id).await;
+        email_service
+            .send_login_code(&email, &code, username, self.config.code_ttl_secs / 60, locale)
+            .await
+            .map_err(|e| ApiError::ExternalService(format!("Failed to send sign-in code: {}", e)))?;
+
+        info!(
+            "🔐 Step-up challenge {} sent to user {} ({})",
+            challenge_id,
+            user_id,
+            signal_names.join(", ")
+        );
+        self.audit.log_async(AuditEvent::LoginRiskAssessed {
+            user_id,
+            ip: ip.to_string(),
+            user_agent: user_agent.map(str::to_string),
+            signals: signal_names,
+            decision: "step_up".to_string(),
+            challenge_id: Some(challenge_id),
+        });
+
+        Ok(LoginDecision::StepUp(StepUpChallenge {
+            challenge_id,
+            method: METHOD_EMAIL_OTP.to_string(),
+            signals,
+            expires_at,
+        }))
+    }
+
+    /// Check a code against its challenge; returns the user to issue the
+    /// session token to. A challenge is used at most once and locks after
+    /// too many wrong codes.
+    pub async fn verify(&self, challenge_id: Uuid, code: &str, ip: &str) -> Result<Uuid> {
+        let mut tx = self.db.begin().await?;
+        let challenge = sqlx::query_as::<_, ChallengeRow>(
+            r#"
+            SELECT user_id, code_hash, attempts, expires_at, verified_at
+            FROM login_step_up_challenges
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(challenge_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .filter(|c| c.verified_at.is_none())
+        .ok_or_else(|| ApiError::with_code(ErrorCode::TokenInvalid, "Unknown or already used sign-in challenge"))?;
+
+        if challenge.attempts >= self.config.max_attempts {
+            return Err(ApiError::RateLimitExceeded("Too many wrong codes; sign in again".to_string()));
+        }
+
+        let outcome = if challenge.expires_at < Utc::now() {
+            "expired"
+        } else if code_hash(challenge_id, code) != challenge.code_hash {
+            sqlx::query("UPDATE login_step_up_challenges SET attempts = attempts + 1 WHERE id = $1")
+                .bind(challenge_id)
+                .execute(&mut *tx)
+                .await?;
+            if challenge.attempts + 1 >= self.config.max_attempts {
+                "locked"
+            } else {
+                "invalid_code"
+            }
+        } else {
+            sqlx::query("UPDATE login_step_up_challenges SET verified_at = NOW() WHERE id = $1")
+                .bind(challenge_id)
+                .execute(&mut *tx)
+                .await?;
+            "verified"
+        };
+        tx.commit().await?;
+
+        self.audit.log_async(AuditEvent::LoginStepUpCompleted {
+            user_id: challenge.user_id,
+            ip: ip.to_string(),
+            challenge_id,
+            outcome: outcome.to_string(),
+        });
+
+        match outcome {
+            "verified" => {
+                info!("✅ Step-up challenge {} verified for user {}", challenge_id, challenge.user_id);
+                Ok(challenge.user_id)
+            }
+            "expired" => Err(ApiError::with_code(ErrorCode::TokenExpired, "Sign-in code expired; sign in again")),
+            "locked" => Err(ApiError::RateLimitExceeded("Too many wrong codes; sign in again".to_string())),
+            _ => Err(ApiError::Authentication("Invalid sign-in code".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(logins: i64, located: i64, same_country: i64, same_device: i64) -> LoginHistory {
+        LoginHistory {
+            logins,
+            located,
+            same_country,
+            same_device,
+            recent_anomalies: 0,
+        }
+    }
+
+    #[test]
+    fn test_first_login_is_not_risky() {
+        assert!(score_login(&history(0, 0, 0, 0), true).is_empty());
+    }
+
+    #[test]
+    fn test_known_country_and_device_pass() {
+        assert!(score_login(&history(5, 5, 3, 2), true).is_empty());
+    }
+
+    #[test]
+    fn test_new_country_and_device_are_flagged() {
+        assert_eq!(
+            score_login(&history(5, 5, 0, 0), true),
+            vec![RiskSignal::NewCountry, RiskSignal::NewDevice]
+        );
+        // Unlocated logins, or no located history, say nothing about the country
+        assert!(score_login(&history(5, 5, 0, 1), false).is_empty());
+        assert!(score_login(&history(5, 0, 0, 1), true).is_empty());
+    }
+
+    #[test]
+    fn test_recent_anomaly_is_flagged() {
+        let mut h = history(5, 0, 0, 1);
+        h.recent_anomalies = 1;
+        assert_eq!(score_login(&h, false), vec![RiskSignal::RecentAnomaly]);
+    }
+
+    #[test]
+    fn test_code_hash_is_bound_to_challenge() {
+        let id = Uuid::new_v4();
+        assert_eq!(code_hash(id, "123456"), code_hash(id, " 123456 "));
+        assert_ne!(code_hash(id, "123456"), code_hash(id, "123457"));
+        assert_ne!(code_hash(id, "123456"), code_hash(Uuid::new_v4(), "123456"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Planned Maintenance
+//!
+//! Admin-scheduled maintenance windows, published on the platform status
+//! endpoint so partner integrations can plan around them. Windows are
+//! cancelled rather than deleted and every change is audited.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::{AuditEvent, AuditLogger};
+
+/// Components a maintenance window can affect
+pub const COMPONENTS: [&str; 6] = ["api", "trading", "settlement", "blockchain", "meters", "payments"];
+
+const WINDOW_COLUMNS: &str = "id, title, description, components, starts_at, ends_at, created_by, created_at, \
+    cancelled_at";
+
+/// A scheduled maintenance window
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct MaintenanceWindow {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    /// Affected components, see `COMPONENTS`
+    pub components: Vec<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    #[serde(skip_serializing)]
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+pub struct MaintenanceService {
+    db: PgPool,
+    audit_logger: AuditLogger,
+}
+
+impl MaintenanceService {
+    pub fn new(db: PgPool, audit_logger: AuditLogger) -> Self {
+        Self { db, audit_logger }
+    }
+
+    /// Windows in progress or still to come, soonest first
+    pub async fn upcoming(&self) -> Result<Vec<MaintenanceWindow>, ApiError> {
+        sqlx::query_as::<_, MaintenanceWindow>(&format!(
+            "SELECT {} FROM maintenance_windows WHERE cancelled_at IS NULL AND ends_at > NOW() ORDER BY starts_at",
+            WINDOW_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+
+    /// All windows, including past and cancelled ones, newest first
+    pub async fn list(&self, limit: i64) -> Result<Vec<MaintenanceWindow>, ApiError> {
+        sqlx::query_as::<_, MaintenanceWindow>(&format!(
+            "SELECT {} FROM maintenance_windows ORDER BY starts_at DESC LIMIT $1",
+            WINDOW_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+
+    pub async fn schedule(
+        &self,
+        admin_id: Uuid,
+        title: &str,
+        description: Option<&str>,
+        components: &[String],
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> Result<MaintenanceWindow, ApiError> {
+        if ends_at <= starts_at {
+            return Err(ApiError::validation_field("ends_at", "ends_at must be after starts_at"));
+        }
+        if ends_at <= Utc::now() {
+            return Err(ApiError::validation_field("ends_at", "Window is already over"));
+        }
+        if components.is_empty() {
+            return Err(ApiError::validation_field("components", "At least one component is required"));
+        }
+        if let Some(unknown) = components.iter().find(|c| !COMPONENTS.contains(&c.as_str())) {
+            return Err(ApiError::validation_field(
+                "components",
+                format!("Unknown component {}; expected one of {}", unknown, COMPONENTS.join(", ")),
+            ));
+        }
+
+        let window = sqlx::query_as::<_, MaintenanceWindow>(&format!(
+            r#"
+            INSERT INTO maintenance_windows (title, description, components, starts_at, ends_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            WINDOW_COLUMNS
+        ))
+        .bind(title.trim())
+        .bind(description)
+        .bind(components)
+        .bind(starts_at)
+        .bind(ends_at)
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        self.audit(admin_id, "maintenance_scheduled", &window);
+        info!(
+            "🛠️ Maintenance \"{}\" scheduled {} to {} ({})",
+            window.title,
+            window.starts_at,
+            window.ends_at,
+            window.components.join(", ")
+        );
+        Ok(window)
+    }
+
+    pub async fn cancel(&self, admin_id: Uuid, id: Uuid) -> Result<MaintenanceWindow, ApiError> {
+        let window = sqlx::query_as::<_, MaintenanceWindow>(&format!(
+            r#"
+            UPDATE maintenance_windows SET cancelled_at = NOW()
+            WHERE id = $1 AND cancelled_at IS NULL
+            RETURNING {}
+            "#,
+            WINDOW_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .ok_or_else(|| ApiError::NotFound("Maintenance window not found or already cancelled".to_string()))?;
+
+        self.audit(admin_id, "maintenance_cancelled", &window);
+        Ok(window)
+    }
+
+    fn audit(&self, admin_id: Uuid, action: &str, window: &MaintenanceWindow) {
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "window_id": window.id,
+                "title": window.title,
+                "components": window.components,
+                "starts_at": window.starts_at,
+                "ends_at": window.ends_at,
+            })
+            .to_string(),
+        });
+    }
+}
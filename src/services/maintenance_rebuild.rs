@@ -0,0 +1,331 @@
+//! Maintenance Rebuilds
+//!
+//! Admin-requested rebuilds of derived state that can drift from the
+//! database: the orderbook depth snapshot and resident books, cached candle
+//! series, the dashboard grid status and the hourly analytics rollups. Each
+//! target runs as a background job on the instance that accepted the
+//! request, with its progress kept in `maintenance_rebuild_jobs`. A target
+//! has at most one unfinished job, so repeated requests cannot pile up
+//! rebuilds of the same state.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::handlers::public_market::CACHE_PREFIX;
+use crate::services::{
+    AuditEvent, AuditLogger, CacheService, DashboardService, MarketAnalyticsAggregator, OrderMatchingEngine,
+};
+
+/// A running job that has not reported progress for this long was
+/// interrupted (e.g. by a restart) and no longer blocks its target
+const STALE_AFTER_MINS: i32 = 10;
+/// Analytics buckets recomputed between progress updates
+const PROGRESS_EVERY_BUCKETS: usize = 24;
+
+const JOB_COLUMNS: &str = "id, target, status, steps_done, steps_total, requested_by, error, created_at, \
+    started_at, finished_at";
+
+/// Derived state a rebuild job can recompute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RebuildTarget {
+    /// Orderbook depth snapshot, plus a re-sync of the resident books
+    OrderbookCache,
+    /// Candle series cached for the public market data API
+    CandleAggregates,
+    /// In-memory grid status, restored from the last recorded snapshot
+    DashboardGridStatus,
+    /// Hourly market analytics buckets over the backfill window
+    AnalyticsRollups,
+}
+
+impl RebuildTarget {
+    pub const ALL: [RebuildTarget; 4] = [
+        Self::OrderbookCache,
+        Self::CandleAggregates,
+        Self::DashboardGridStatus,
+        Self::AnalyticsRollups,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OrderbookCache => "orderbook_cache",
+            Self::CandleAggregates => "candle_aggregates",
+            Self::DashboardGridStatus => "dashboard_grid_status",
+            Self::AnalyticsRollups => "analytics_rollups",
+        }
+    }
+}
+
+/// Targets to rebuild, each once and in request order; all of them when
+/// none were named
+pub fn requested_targets(targets: &[RebuildTarget]) -> Vec<RebuildTarget> {
+    if targets.is_empty() {
+        return RebuildTarget::ALL.to_vec();
+    }
+    let mut unique = Vec::with_capacity(targets.len());
+    for target in targets {
+        if !unique.contains(target) {
+            unique.push(*target);
+        }
+    }
+    unique
+}
+
+/// A rebuild job and its progress
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct RebuildJob {
+    pub id: Uuid,
+    /// See `RebuildTarget`
+    pub target: String,
+    /// queued, running, completed or failed
+    pub status: String,
+    pub steps_done: i32,
+    /// Known once the job started
+    pub steps_total: i32,
+    pub requested_by: Uuid,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+pub struct RebuildService {
+    db: PgPool,
+    cache: CacheService,
+    market_analytics: MarketAnalyticsAggregator,
+    dashboard: DashboardService,
+    matching_engine: OrderMatchingEngine,
+    audit_logger: AuditLogger,
+}
+
+impl RebuildService {
+    pub fn new(
+        db: PgPool,
+        cache: CacheService,
+        market_analytics: MarketAnalyticsAggregator,
+        dashboard: DashboardService,
+        matching_engine: OrderMatchingEngine,
+        audit_logger: AuditLogger,
+    ) -> Self {
+        Self {
+            db,
+            cache,
+            market_analytics,
+            dashboard,
+            matching_engine,
+            audit_logger,
+        }
+    }
+
+    /// Queue one job per target and start them in the background. Nothing is
+    /// queued when any target already has an unfinished job.
+    pub async fn start(&self, admin_id: Uuid, targets: &[RebuildTarget]) -> Result<Vec<RebuildJob>, ApiError> {
+        let targets = requested_targets(targets);
+
+        sqlx::query(
+            r#"
+            UPDATE maintenance_rebuild_jobs
+            SET status = 'failed', error = 'Interrupted', finished_at = NOW(), updated_at = NOW()
+            WHERE status IN ('queued', 'running')
+              AND updated_at < NOW() - make_interval(mins => $1::int)
+            "#,
+        )
+        .bind(STALE_AFTER_MINS)
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+        let mut jobs = Vec::with_capacity(targets.len());
+        for target in &targets {
+            let job = sqlx::query_as::<_, RebuildJob>(&format!(
+                "INSERT INTO maintenance_rebuild_jobs (target, requested_by) VALUES ($1, $2) RETURNING {}",
+                JOB_COLUMNS
+            ))
+            .bind(target.as_str())
+            .bind(admin_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => ApiError::Conflict(format!(
+                    "A {} rebuild is already queued or running",
+                    target.as_str()
+                )),
+                e => ApiError::Database(e),
+            })?;
+            jobs.push(job);
+        }
+        tx.commit().await.map_err(ApiError::Database)?;
+
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "maintenance_rebuild_started".to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "jobs": jobs.iter().map(|j| serde_json::json!({ "id": j.id, "target": j.target })).collect::<Vec<_>>(),
+            })
+            .to_string(),
+        });
+
+        for (job, target) in jobs.iter().zip(targets) {
+            let service = self.clone();
+            let job_id = job.id;
+            tokio::spawn(async move { service.run(job_id, target).await });
+        }
+        Ok(jobs)
+    }
+
+    /// Recent jobs, newest first
+    pub async fn list(&self, limit: i64) -> Result<Vec<RebuildJob>, ApiError> {
+        sqlx::query_as::<_, RebuildJob>(&format!(
+            "SELECT {} FROM maintenance_rebuild_jobs ORDER BY created_at DESC LIMIT $1",
+            JOB_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<RebuildJob, ApiError> {
+        sqlx::query_as::<_, RebuildJob>(&format!(
+            "SELECT {} FROM maintenance_rebuild_jobs WHERE id = $1",
+            JOB_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .ok_or_else(|| ApiError::NotFound("Rebuild job not found".to_string()))
+    }
+
+    async fn run(self, job_id: Uuid, target: RebuildTarget) {
+        info!("🔧 Rebuilding {} (job {})", target.as_str(), job_id);
+        let result = match target {
+            RebuildTarget::OrderbookCache => self.rebuild_orderbook(job_id).await,
+            RebuildTarget::CandleAggregates => self.rebuild_candles(job_id).await,
+            RebuildTarget::DashboardGridStatus => self.rebuild_grid_status(job_id).await,
+            RebuildTarget::AnalyticsRollups => self.rebuild_analytics(job_id).await,
+        };
+
+        let error = match &result {
+            Ok(()) => {
+                info!("✅ Rebuilt {} (job {})", target.as_str(), job_id);
+                None
+            }
+            Err(e) => {
+                error!("❌ Rebuild of {} failed (job {}): {}", target.as_str(), job_id, e);
+                Some(e.to_string())
+            }
+        };
+        let finished = sqlx::query(
+            r#"
+            UPDATE maintenance_rebuild_jobs
+            SET status = $2, error = $3, finished_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(job_id)
+        .bind(if error.is_none() { "completed" } else { "failed" })
+        .bind(error)
+        .execute(&self.db)
+        .await;
+        if let Err(e) = finished {
+            error!("Failed to record the end of rebuild job {}: {}", job_id, e);
+        }
+    }
+
+    /// Record progress; the first call also marks the job running.
+    /// Failures are logged and ignored, they must not stop the rebuild.
+    async fn progress(&self, job_id: Uuid, steps_done: usize, steps_total: usize) {
+        let result = sqlx::query(
+            r#"
+            UPDATE maintenance_rebuild_jobs
+            SET status = 'running', steps_done = $2, steps_total = $3,
+                started_at = COALESCE(started_at, NOW()), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(job_id)
+        .bind(steps_done as i32)
+        .bind(steps_total as i32)
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            warn!("Failed to record progress of rebuild job {}: {}", job_id, e);
+        }
+    }
+
+    async fn rebuild_orderbook(&self, job_id: Uuid) -> anyhow::Result<()> {
+        self.progress(job_id, 0, 2).await;
+        if self.matching_engine.request_book_resync() {
+            info!("Resident books flagged for re-sync");
+        }
+        self.progress(job_id, 1, 2).await;
+        self.market_analytics.refresh_depth().await?;
+        self.progress(job_id, 2, 2).await;
+        Ok(())
+    }
+
+    /// Candles are computed from `order_matches` on a cache miss, so dropping
+    /// the cached series rebuilds them on the next request
+    async fn rebuild_candles(&self, job_id: Uuid) -> anyhow::Result<()> {
+        self.progress(job_id, 0, 1).await;
+        let deleted = self
+            .cache
+            .delete_prefix(&format!("{}candles:", CACHE_PREFIX))
+            .await?;
+        info!("Dropped {} cached candle series", deleted);
+        self.progress(job_id, 1, 1).await;
+        Ok(())
+    }
+
+    async fn rebuild_grid_status(&self, job_id: Uuid) -> anyhow::Result<()> {
+        self.progress(job_id, 0, 1).await;
+        if !self.dashboard.restore_grid_status().await? {
+            anyhow::bail!("No grid status snapshot recorded yet");
+        }
+        self.progress(job_id, 1, 1).await;
+        Ok(())
+    }
+
+    async fn rebuild_analytics(&self, job_id: Uuid) -> anyhow::Result<()> {
+        let buckets = self.market_analytics.backfill_buckets()?;
+        self.progress(job_id, 0, buckets.len()).await;
+        for (done, bucket) in buckets.iter().enumerate() {
+            self.market_analytics.refresh_bucket(*bucket).await?;
+            if (done + 1) % PROGRESS_EVERY_BUCKETS == 0 {
+                self.progress(job_id, done + 1, buckets.len()).await;
+            }
+        }
+        self.progress(job_id, buckets.len(), buckets.len()).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_targets_means_all() {
+        assert_eq!(requested_targets(&[]), RebuildTarget::ALL.to_vec());
+    }
+
+    #[test]
+    fn test_targets_are_deduplicated_in_order() {
+        let targets = requested_targets(&[
+            RebuildTarget::AnalyticsRollups,
+            RebuildTarget::OrderbookCache,
+            RebuildTarget::AnalyticsRollups,
+        ]);
+        assert_eq!(targets, vec![RebuildTarget::AnalyticsRollups, RebuildTarget::OrderbookCache]);
+    }
+}
@@ -0,0 +1,545 @@
+//! Market Maker Incentives
+//!
+//! The incentive job samples every active market's resting orders at a fixed
+//! interval. Each maker earns points per sample for quoting at the best bid
+//! and/or best ask, with a bonus for quoting both sides at a tight spread.
+//! After a UTC day ends, the day's pool of energy tokens is shared among the
+//! makers who spent enough of the day at the best quote, in proportion to
+//! their points. Rebates are minted on-chain like referral rewards.
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool, Row};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::{Config, MakerIncentiveConfig};
+use crate::error::{ApiError, Result};
+use crate::services::{BlockchainService, WalletService};
+
+/// Rebates minted per job run
+const MINT_BATCH_SIZE: usize = 50;
+/// Rebate amounts are rounded down to this many decimal places
+const REBATE_SCALE: u32 = 8;
+
+/// A maker's best resting prices in one market at sampling time
+#[derive(Debug, Clone, PartialEq)]
+pub struct MakerQuote {
+    pub user_id: Uuid,
+    pub bid: Option<Decimal>,
+    pub ask: Option<Decimal>,
+}
+
+/// Points and statistics of one maker in one sample
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamplePoints {
+    pub at_best: bool,
+    /// Own spread in bps of mid, when quoting both sides
+    pub spread_bps: Option<Decimal>,
+    pub points: Decimal,
+}
+
+/// Spread in basis points of the mid price
+pub fn spread_bps(bid: Decimal, ask: Decimal) -> Option<Decimal> {
+    let mid = (bid + ask) / Decimal::TWO;
+    if mid <= Decimal::ZERO || ask < bid {
+        return None;
+    }
+    Some((ask - bid) / mid * Decimal::from(10_000))
+}
+
+/// 1 for a zero spread, falling linearly to 0 at `max_spread_bps`
+pub fn tightness(spread_bps: Decimal, max_spread_bps: Decimal) -> Decimal {
+    if max_spread_bps <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    (Decimal::ONE - spread_bps / max_spread_bps).clamp(Decimal::ZERO, Decimal::ONE)
+}
+
+/// Half a point per side quoted at the best price; quoting both sides
+/// multiplies that by up to 2 depending on the maker's own spread
+pub fn sample_points(
+    quote: &MakerQuote,
+    best_bid: Option<Decimal>,
+    best_ask: Option<Decimal>,
+    max_spread_bps: Decimal,
+) -> SamplePoints {
+    let half = Decimal::new(5, 1);
+    let at_bid = quote.bid.is_some() && quote.bid == best_bid;
+    let at_ask = quote.ask.is_some() && quote.ask == best_ask;
+    let presence = if at_bid { half } else { Decimal::ZERO } + if at_ask { half } else { Decimal::ZERO };
+
+    let spread = match (quote.bid, quote.ask) {
+        (Some(bid), Some(ask)) => spread_bps(bid, ask),
+        _ => None,
+    };
+    let multiplier = Decimal::ONE + spread.map(|s| tightness(s, max_spread_bps)).unwrap_or(Decimal::ZERO);
+
+    SamplePoints {
+        at_best: at_bid || at_ask,
+        spread_bps: spread,
+        points: presence * multiplier,
+    }
+}
+
+/// Share `pool` among makers in proportion to their score
+pub fn allocate_rebates(pool: Decimal, scores: &[(Uuid, Decimal)]) -> Vec<(Uuid, Decimal)> {
+    let total: Decimal = scores.iter().map(|(_, score)| *score).filter(|s| *s > Decimal::ZERO).sum();
+    if total <= Decimal::ZERO || pool <= Decimal::ZERO {
+        return Vec::new();
+    }
+    scores
+        .iter()
+        .filter(|(_, score)| *score > Decimal::ZERO)
+        .map(|(user_id, score)| {
+            let amount = (pool * *score / total).round_dp_with_strategy(REBATE_SCALE, RoundingStrategy::ToZero);
+            (*user_id, amount)
+        })
+        .filter(|(_, amount)| *amount > Decimal::ZERO)
+        .collect()
+}
+
+/// One maker's standing for a market day
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct MakerScore {
+    pub user_id: Uuid,
+    pub username: Option<String>,
+    /// Share of the day's samples at the best bid or ask, in percent
+    #[schema(value_type = String)]
+    pub time_at_best_pct: Decimal,
+    /// Share of the day's samples quoting both sides, in percent
+    #[schema(value_type = String)]
+    pub two_sided_pct: Decimal,
+    /// Average own spread when quoting both sides, bps of mid
+    #[schema(value_type = Option<String>)]
+    pub avg_spread_bps: Option<Decimal>,
+    #[schema(value_type = String)]
+    pub score: Decimal,
+    /// Meets the minimum time at best quote
+    pub eligible: bool,
+    /// Rebate once the day is settled
+    #[schema(value_type = Option<String>)]
+    pub rebate_amount: Option<Decimal>,
+    /// pending, minted or failed
+    pub rebate_status: Option<String>,
+}
+
+/// Scores of all makers in a market for one day
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MakerIncentiveBoard {
+    pub market_id: Uuid,
+    pub day: NaiveDate,
+    /// Times the book was sampled that day
+    pub samples: i32,
+    /// Energy tokens (kWh) shared among eligible makers
+    #[schema(value_type = String)]
+    pub pool_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub min_time_at_best_pct: Decimal,
+    pub settled: bool,
+    /// Highest score first
+    pub makers: Vec<MakerScore>,
+}
+
+#[derive(Clone)]
+pub struct MakerIncentiveService {
+    db: PgPool,
+    blockchain: BlockchainService,
+    wallet: WalletService,
+    config: Config,
+}
+
+impl MakerIncentiveService {
+    pub fn new(db: PgPool, blockchain: BlockchainService, wallet: WalletService, config: Config) -> Self {
+        Self {
+            db,
+            blockchain,
+            wallet,
+            config,
+        }
+    }
+
+    fn settings(&self) -> &MakerIncentiveConfig {
+        &self.config.maker_incentives
+    }
+
+    /// Sample every active market's best quotes once. Returns the number of
+    /// makers credited with points.
+    pub async fn sample(&self) -> Result<usize> {
+        let rows = sqlx::query(
+            r#"
+            SELECT m.id AS market_id, o.user_id,
+                   MAX(o.price_per_kwh) FILTER (WHERE o.side::text = 'buy') AS bid,
+                   MIN(o.price_per_kwh) FILTER (WHERE o.side::text = 'sell') AS ask
+            FROM markets m
+            LEFT JOIN trading_orders o
+              ON o.market_id = m.id
+             AND o.status IN ('pending', 'active', 'partially_filled')
+             AND o.trigger_type IS NULL
+             AND (o.expires_at IS NULL OR o.expires_at > NOW())
+            WHERE m.status = 'active'
+            GROUP BY m.id, o.user_id
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut books: HashMap<Uuid, Vec<MakerQuote>> = HashMap::new();
+        for row in rows {
+            let quotes = books.entry(row.get("market_id")).or_default();
+            if let Some(user_id) = row.get::<Option<Uuid>, _>("user_id") {
+                quotes.push(MakerQuote {
+                    user_id,
+                    bid: row.get("bid"),
+                    ask: row.get("ask"),
+                });
+            }
+        }
+
+        let day = Utc::now().date_naive();
+        let max_spread_bps = self.settings().max_spread_bps;
+        let mut credited = 0;
+        let mut tx = self.db.begin().await?;
+        for (market_id, quotes) in books {
+            sqlx::query(
+                "INSERT INTO maker_incentive_days (market_id, day, samples) VALUES ($1, $2, 1)
+                 ON CONFLICT (market_id, day) DO UPDATE SET samples = maker_incentive_days.samples + 1",
+            )
+            .bind(market_id)
+            .bind(day)
+            .execute(&mut *tx)
+            .await?;
+
+            let best_bid = quotes.iter().filter_map(|q| q.bid).max();
+            let best_ask = quotes.iter().filter_map(|q| q.ask).min();
+            for quote in &quotes {
+                let sample = sample_points(quote, best_bid, best_ask, max_spread_bps);
+                if !sample.at_best && sample.spread_bps.is_none() {
+                    continue;
+                }
+                sqlx::query(
+                    r#"
+                    INSERT INTO maker_quote_stats
+                        (market_id, user_id, day, at_best_samples, two_sided_samples, spread_bps_sum, score)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    ON CONFLICT (market_id, day, user_id) DO UPDATE SET
+                        at_best_samples = maker_quote_stats.at_best_samples + EXCLUDED.at_best_samples,
+                        two_sided_samples = maker_quote_stats.two_sided_samples + EXCLUDED.two_sided_samples,
+                        spread_bps_sum = maker_quote_stats.spread_bps_sum + EXCLUDED.spread_bps_sum,
+                        score = maker_quote_stats.score + EXCLUDED.score,
+                        updated_at = NOW()
+                    "#,
+                )
+                .bind(market_id)
+                .bind(quote.user_id)
+                .bind(day)
+                .bind(i32::from(sample.at_best))
+                .bind(i32::from(sample.spread_bps.is_some()))
+                .bind(sample.spread_bps.unwrap_or(Decimal::ZERO).round_dp(4))
+                .bind(sample.points)
+                .execute(&mut *tx)
+                .await?;
+                if sample.points > Decimal::ZERO {
+                    credited += 1;
+                }
+            }
+        }
+        tx.commit().await?;
+        Ok(credited)
+    }
+
+    /// Allocate rebates for every finished day not yet settled. Returns the
+    /// number of market days settled.
+    pub async fn settle_finished_days(&self) -> Result<usize> {
+        let today = Utc::now().date_naive();
+        let days = sqlx::query_as::<_, (Uuid, NaiveDate)>(
+            "SELECT market_id, day FROM maker_incentive_days WHERE settled_at IS NULL AND day < $1 ORDER BY day",
+        )
+        .bind(today)
+        .fetch_all(&self.db)
+        .await?;
+
+        let settings = self.settings().clone();
+        let mut settled = 0;
+        for (market_id, day) in days {
+            let mut tx = self.db.begin().await?;
+            let Some(samples) = sqlx::query_scalar::<_, i32>(
+                "SELECT samples FROM maker_incentive_days
+                 WHERE market_id = $1 AND day = $2 AND settled_at IS NULL
+                 FOR UPDATE SKIP LOCKED",
+            )
+            .bind(market_id)
+            .bind(day)
+            .fetch_optional(&mut *tx)
+            .await?
+            else {
+                continue;
+            };
+            // Only makers above the time-at-best threshold share the pool
+            let scores = sqlx::query_as::<_, (Uuid, Decimal)>(
+                r#"
+                SELECT user_id, score FROM maker_quote_stats
+                WHERE market_id = $1 AND day = $2 AND score > 0
+                  AND at_best_samples * 100.0 >= $3 * $4
+                "#,
+            )
+            .bind(market_id)
+            .bind(day)
+            .bind(settings.min_time_at_best_pct)
+            .bind(Decimal::from(samples))
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let rebates = allocate_rebates(settings.daily_pool_kwh, &scores);
+            for (user_id, amount) in &rebates {
+                let score = scores.iter().find(|(id, _)| id == user_id).map(|(_, s)| *s).unwrap_or_default();
+                sqlx::query(
+                    "INSERT INTO maker_rebates (market_id, user_id, day, score, amount) VALUES ($1, $2, $3, $4, $5)
+                     ON CONFLICT (market_id, day, user_id) DO NOTHING",
+                )
+                .bind(market_id)
+                .bind(user_id)
+                .bind(day)
+                .bind(score)
+                .bind(amount)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            sqlx::query(
+                "UPDATE maker_incentive_days SET pool_amount = $3, settled_at = NOW() WHERE market_id = $1 AND day = $2",
+            )
+            .bind(market_id)
+            .bind(day)
+            .bind(settings.daily_pool_kwh)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+
+            info!("💧 Maker rebates for market {} on {}: {} makers", market_id, day, rebates.len());
+            settled += 1;
+        }
+        Ok(settled)
+    }
+
+    /// Mint pending rebates to makers with a wallet. A rebate that keeps
+    /// failing is marked failed after the tokenization retry limit.
+    /// Returns the number of rebates minted.
+    pub async fn mint_pending_rebates(&self) -> Result<usize> {
+        let max_attempts = self.config.tokenization.max_retry_attempts.max(1) as i32;
+        let mut minted = 0;
+
+        for _ in 0..MINT_BATCH_SIZE {
+            let mut tx = self.db.begin().await?;
+
+            // Row lock keeps other instances from minting the same rebate
+            let Some(row) = sqlx::query(
+                r#"
+                SELECT r.id, r.amount, r.attempts, u.wallet_address
+                FROM maker_rebates r
+                JOIN users u ON u.id = r.user_id
+                WHERE r.status = 'pending' AND u.wallet_address IS NOT NULL
+                ORDER BY r.attempts ASC, r.created_at ASC
+                LIMIT 1
+                FOR UPDATE OF r SKIP LOCKED
+                "#,
+            )
+            .fetch_optional(&mut *tx)
+            .await?
+            else {
+                break;
+            };
+
+            let rebate_id: Uuid = row.get("id");
+            let amount: Decimal = row.get("amount");
+            let attempts: i32 = row.get::<i32, _>("attempts") + 1;
+            let wallet_address: String = row.get("wallet_address");
+
+            match self.mint(&wallet_address, amount).await {
+                Ok(signature) => {
+                    sqlx::query(
+                        "UPDATE maker_rebates SET status = 'minted', attempts = $2, mint_tx_signature = $3,
+                             last_error = NULL, minted_at = NOW()
+                         WHERE id = $1",
+                    )
+                    .bind(rebate_id)
+                    .bind(attempts)
+                    .bind(&signature)
+                    .execute(&mut *tx)
+                    .await?;
+                    tx.commit().await?;
+                    minted += 1;
+                    info!("💧 Minted maker rebate {} ({} kWh) - TX: {}", rebate_id, amount, signature);
+                }
+                Err(e) => {
+                    let status = if attempts >= max_attempts { "failed" } else { "pending" };
+                    sqlx::query("UPDATE maker_rebates SET status = $2, attempts = $3, last_error = $4 WHERE id = $1")
+                        .bind(rebate_id)
+                        .bind(status)
+                        .bind(attempts)
+                        .bind(e.to_string())
+                        .execute(&mut *tx)
+                        .await?;
+                    tx.commit().await?;
+                    warn!(
+                        "⚠️ Maker rebate {} mint attempt {}/{} failed: {}",
+                        rebate_id, attempts, max_attempts, e
+                    );
+                    // The chain is likely unavailable; retry on the next run
+                    break;
+                }
+            }
+        }
+
+        Ok(minted)
+    }
+
+    async fn mint(&self, wallet_address: &str, amount: Decimal) -> anyhow::Result<String> {
+        let kwh = amount
+            .to_f64()
+            .ok_or_else(|| anyhow!("Rebate amount {} out of range", amount))?;
+        let authority = self.wallet.get_authority_keypair().await?;
+        let mint = BlockchainService::parse_pubkey(&self.config.energy_token_mint)?;
+        let wallet = BlockchainService::parse_pubkey(wallet_address)?;
+
+        let signature = if self.config.tokenization.enable_real_blockchain {
+            let token_account = self
+                .blockchain
+                .ensure_token_account_exists(&authority, &wallet, &mint)
+                .await?;
+            self.blockchain
+                .mint_energy_tokens(&authority, &token_account, &wallet, &mint, kwh)
+                .await?
+        } else {
+            self.blockchain
+                .mint_spl_tokens(&authority, &wallet, &mint, kwh)
+                .await?
+        };
+        Ok(signature.to_string())
+    }
+
+    /// Sample quotes, then settle finished days and mint their rebates
+    pub async fn run_cycle(&self) -> Result<(usize, usize, usize)> {
+        let sampled = self.sample().await?;
+        let settled = self.settle_finished_days().await?;
+        let minted = self.mint_pending_rebates().await?;
+        Ok((sampled, settled, minted))
+    }
+
+    /// Every maker's score in a market for one day (today if not given)
+    pub async fn board(&self, market_id: Uuid, day: Option<NaiveDate>) -> Result<MakerIncentiveBoard> {
+        let day = day.unwrap_or_else(|| Utc::now().date_naive());
+        let market_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM markets WHERE id = $1)")
+            .bind(market_id)
+            .fetch_one(&self.db)
+            .await?;
+        if !market_exists {
+            return Err(ApiError::NotFound("Market not found".to_string()));
+        }
+
+        let (samples, pool_amount, settled) = sqlx::query_as::<_, (i32, Option<Decimal>, bool)>(
+            "SELECT samples, pool_amount, settled_at IS NOT NULL FROM maker_incentive_days WHERE market_id = $1 AND day = $2",
+        )
+        .bind(market_id)
+        .bind(day)
+        .fetch_optional(&self.db)
+        .await?
+        .unwrap_or((0, None, false));
+
+        let settings = self.settings();
+        let makers = sqlx::query_as::<_, MakerScore>(
+            r#"
+            SELECT s.user_id, u.username,
+                   ROUND(s.at_best_samples * 100.0 / GREATEST($3, 1), 2) AS time_at_best_pct,
+                   ROUND(s.two_sided_samples * 100.0 / GREATEST($3, 1), 2) AS two_sided_pct,
+                   ROUND(s.spread_bps_sum / NULLIF(s.two_sided_samples, 0), 2) AS avg_spread_bps,
+                   s.score,
+                   (s.at_best_samples * 100.0 >= $4 * $3) AS eligible,
+                   r.amount AS rebate_amount,
+                   r.status AS rebate_status
+            FROM maker_quote_stats s
+            JOIN users u ON u.id = s.user_id
+            LEFT JOIN maker_rebates r ON r.market_id = s.market_id AND r.day = s.day AND r.user_id = s.user_id
+            WHERE s.market_id = $1 AND s.day = $2
+            ORDER BY s.score DESC, s.at_best_samples DESC
+            "#,
+        )
+        .bind(market_id)
+        .bind(day)
+        .bind(samples)
+        .bind(settings.min_time_at_best_pct)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(MakerIncentiveBoard {
+            market_id,
+            day,
+            samples,
+            pool_kwh: pool_amount.unwrap_or(settings.daily_pool_kwh),
+            min_time_at_best_pct: settings.min_time_at_best_pct,
+            settled,
+            makers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(bid: Option<i64>, ask: Option<i64>) -> MakerQuote {
+        MakerQuote {
+            user_id: Uuid::new_v4(),
+            bid: bid.map(|p| Decimal::new(p, 2)),
+            ask: ask.map(|p| Decimal::new(p, 2)),
+        }
+    }
+
+    #[test]
+    fn test_spread_and_tightness() {
+        assert_eq!(spread_bps(Decimal::new(99, 0), Decimal::new(101, 0)), Some(Decimal::from(200)));
+        assert_eq!(spread_bps(Decimal::new(101, 0), Decimal::new(99, 0)), None);
+        assert_eq!(tightness(Decimal::from(50), Decimal::from(200)), Decimal::new(75, 2));
+        assert_eq!(tightness(Decimal::from(500), Decimal::from(200)), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_sample_points() {
+        let (best_bid, best_ask) = (Some(Decimal::new(499, 2)), Some(Decimal::new(501, 2)));
+        let max = Decimal::from(200);
+
+        // Both sides at the best, 40 bps wide: 1 point x (1 + 0.8)
+        let two_sided = sample_points(&quote(Some(499), Some(501)), best_bid, best_ask, max);
+        assert!(two_sided.at_best);
+        assert_eq!(two_sided.points, Decimal::new(18, 1));
+
+        let one_side = sample_points(&quote(Some(499), None), best_bid, best_ask, max);
+        assert_eq!(one_side.points, Decimal::new(5, 1));
+
+        // Two-sided but behind the best on both sides earns nothing
+        let behind = sample_points(&quote(Some(450), Some(550)), best_bid, best_ask, max);
+        assert!(!behind.at_best);
+        assert_eq!(behind.points, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_allocate_rebates_pro_rata() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let rebates = allocate_rebates(
+            Decimal::from(100),
+            &[(a, Decimal::from(3)), (b, Decimal::from(1)), (c, Decimal::ZERO)],
+        );
+        assert_eq!(rebates, vec![(a, Decimal::from(75)), (b, Decimal::from(25))]);
+        assert!(allocate_rebates(Decimal::from(100), &[(a, Decimal::ZERO)]).is_empty());
+
+        // Never pays out more than the pool
+        let thirds = allocate_rebates(Decimal::ONE, &[(a, Decimal::ONE), (b, Decimal::ONE), (c, Decimal::ONE)]);
+        assert!(thirds.iter().map(|(_, amount)| *amount).sum::<Decimal>() <= Decimal::ONE);
+    }
+}
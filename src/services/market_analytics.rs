@@ -0,0 +1,398 @@
+//! Market Analytics Aggregator
+//!
+//! Background service that incrementally rolls trading activity into hourly
+//! buckets (`market_analytics_hourly`) and keeps an in-memory orderbook depth
+//! snapshot. Analytics endpoints read these aggregates instead of scanning
+//! `order_matches` / `trading_orders` on every request.
+//!
+//! Requested and filled kWh are attributed to the bucket an order was created
+//! in, so an order filled hours later changes an older bucket: each pass also
+//! recomputes the buckets of orders updated since the previous pass.
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use std::collections::BTreeSet;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+use utoipa::ToSchema;
+
+use crate::services::markets::DEFAULT_MARKET_ID;
+
+/// Upper bounds (seconds) of the time-to-fill histogram buckets.
+/// A final open-ended bucket collects everything slower.
+pub const TIME_TO_FILL_BOUNDS_SECS: [i64; 5] = [60, 300, 900, 3600, 21600];
+
+/// Human-readable labels for the time-to-fill buckets (one more than the bounds)
+pub const TIME_TO_FILL_LABELS: [&str; 6] = ["<1m", "1-5m", "5-15m", "15-60m", "1-6h", ">6h"];
+
+/// Market analytics aggregator configuration
+#[derive(Debug, Clone)]
+pub struct MarketAnalyticsConfig {
+    /// How often to refresh aggregates (in seconds)
+    pub interval_secs: u64,
+    /// Number of price levels kept per side in the depth snapshot
+    pub depth_levels: i64,
+    /// How far back to backfill on first start (in days)
+    pub backfill_days: i64,
+}
+
+impl Default for MarketAnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 60,
+            depth_levels: 10,
+            backfill_days: 30,
+        }
+    }
+}
+
+/// Aggregated liquidity at a single price level
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DepthLevel {
+    pub price_per_kwh: f64,
+    pub total_kwh: f64,
+    pub order_count: i64,
+}
+
+/// Point-in-time orderbook depth
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DepthSnapshot {
+    pub bids: Vec<DepthLevel>,
+    pub asks: Vec<DepthLevel>,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub spread: Option<f64>,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// Market analytics aggregator service
+#[derive(Clone)]
+pub struct MarketAnalyticsAggregator {
+    db: PgPool,
+    config: MarketAnalyticsConfig,
+    depth: Arc<RwLock<Option<DepthSnapshot>>>,
+}
+
+impl MarketAnalyticsAggregator {
+    pub fn new(db: PgPool, config: MarketAnalyticsConfig) -> Self {
+        Self {
+            db,
+            config,
+            depth: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub fn interval_secs(&self) -> u64 {
+        self.config.interval_secs
+    }
+
+    /// Latest orderbook depth snapshot, if one has been captured
+    pub async fn depth_snapshot(&self) -> Option<DepthSnapshot> {
+        self.depth.read().await.clone()
+    }
+
+    /// Recapture the orderbook depth snapshot outside the aggregation pass
+    /// (e.g. after orders were expired)
+    pub async fn refresh_depth(&self) -> anyhow::Result<DepthSnapshot> {
+        let snapshot = self.capture_depth(Utc::now()).await?;
+        *self.depth.write().await = Some(snapshot.clone());
+        Ok(snapshot)
+    }
+
+    /// Run one aggregation pass: refresh open hourly buckets and the buckets
+    /// of orders filled or cancelled since the last pass, and sample the orderbook
+    pub async fn refresh(&self) -> anyhow::Result<()> {
+        let now = Utc::now();
+        let current_bucket = now.duration_trunc(Duration::hours(1))?;
+
+        // Resume from the most recent bucket (it may still have been open last pass)
+        let (last_bucket, last_pass): (Option<DateTime<Utc>>, Option<DateTime<Utc>>) =
+            sqlx::query_as("SELECT MAX(bucket_start), MAX(updated_at) FROM market_analytics_hourly")
+                .fetch_one(&self.db)
+                .await?;
+        let mut bucket = last_bucket.unwrap_or_else(|| {
+            current_bucket - Duration::days(self.config.backfill_days)
+        });
+        let mut buckets = BTreeSet::new();
+        while bucket <= current_bucket {
+            buckets.insert(bucket);
+            bucket += Duration::hours(1);
+        }
+
+        // Order timestamps are taken when their transaction starts, so look
+        // back one interval to catch updates committed during the last pass
+        if let Some(last_pass) = last_pass {
+            let since = last_pass - Duration::seconds(self.config.interval_secs as i64);
+            let changed: Vec<DateTime<Utc>> = sqlx::query_scalar(
+                r#"
+                SELECT DISTINCT date_trunc('hour', created_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+                FROM trading_orders
+                WHERE updated_at > $1 AND created_at >= $2
+                "#,
+            )
+            .bind(since)
+            .bind(current_bucket - Duration::days(self.config.backfill_days))
+            .fetch_all(&self.db)
+            .await?;
+            buckets.extend(changed);
+        }
+
+        for bucket in &buckets {
+            self.refresh_bucket(*bucket).await?;
+        }
+        debug!("Market analytics refreshed {} hourly bucket(s)", buckets.len());
+
+        let snapshot = self.capture_depth(now).await?;
+        if let Some(spread) = snapshot.spread {
+            self.record_spread_sample(current_bucket, spread).await?;
+        }
+        *self.depth.write().await = Some(snapshot);
+
+        Ok(())
+    }
+
+    /// Start of every hourly bucket in the backfill window, oldest first
+    pub fn backfill_buckets(&self) -> anyhow::Result<Vec<DateTime<Utc>>> {
+        let current_bucket = Utc::now().duration_trunc(Duration::hours(1))?;
+        let mut bucket = current_bucket - Duration::days(self.config.backfill_days);
+        let mut buckets = Vec::new();
+        while bucket <= current_bucket {
+            buckets.push(bucket);
+            bucket += Duration::hours(1);
+        }
+        Ok(buckets)
+    }
+
+    /// Recompute trade, fill and time-to-fill aggregates for one hourly bucket.
+    /// Spread samples are kept; they cannot be recovered after the fact.
+    pub async fn refresh_bucket(&self, bucket_start: DateTime<Utc>) -> anyhow::Result<()> {
+        let bucket_end = bucket_start + Duration::hours(1);
+
+        let trades = sqlx::query(
+            r#"
+            SELECT
+                COALESCE(SUM(matched_amount), 0) AS volume_kwh,
+                COALESCE(SUM(matched_amount * match_price), 0) AS notional,
+                COUNT(*) AS trade_count,
+                MIN(match_price) AS low_price,
+                MAX(match_price) AS high_price
+            FROM order_matches
+            WHERE match_time >= $1 AND match_time < $2
+            "#,
+        )
+        .bind(bucket_start)
+        .bind(bucket_end)
+        .fetch_one(&self.db)
+        .await?;
+
+        let orders = sqlx::query(
+            r#"
+            SELECT
+                COALESCE(SUM(energy_amount), 0) AS requested_kwh,
+                COALESCE(SUM(COALESCE(filled_amount, 0)), 0) AS filled_kwh,
+                COUNT(*) AS orders_created
+            FROM trading_orders
+            WHERE created_at >= $1 AND created_at < $2
+            "#,
+        )
+        .bind(bucket_start)
+        .bind(bucket_end)
+        .fetch_one(&self.db)
+        .await?;
+
+        let fill_secs: Vec<f64> = sqlx::query_scalar(
+            r#"
+            SELECT EXTRACT(EPOCH FROM (filled_at - created_at))::FLOAT8
+            FROM trading_orders
+            WHERE filled_at >= $1 AND filled_at < $2 AND created_at IS NOT NULL
+            "#,
+        )
+        .bind(bucket_start)
+        .bind(bucket_end)
+        .fetch_all(&self.db)
+        .await?;
+
+        let histogram = time_to_fill_histogram(&fill_secs);
+        let ttf_sum_secs: f64 = fill_secs.iter().map(|s| s.max(0.0)).sum();
+
+        sqlx::query(
+            r#"
+            INSERT INTO market_analytics_hourly (
+                bucket_start, volume_kwh, notional, trade_count, low_price, high_price,
+                requested_kwh, filled_kwh, orders_created,
+                orders_filled, ttf_sum_secs, ttf_histogram, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, NOW())
+            ON CONFLICT (bucket_start) DO UPDATE SET
+                volume_kwh = EXCLUDED.volume_kwh,
+                notional = EXCLUDED.notional,
+                trade_count = EXCLUDED.trade_count,
+                low_price = EXCLUDED.low_price,
+                high_price = EXCLUDED.high_price,
+                requested_kwh = EXCLUDED.requested_kwh,
+                filled_kwh = EXCLUDED.filled_kwh,
+                orders_created = EXCLUDED.orders_created,
+                orders_filled = EXCLUDED.orders_filled,
+                ttf_sum_secs = EXCLUDED.ttf_sum_secs,
+                ttf_histogram = EXCLUDED.ttf_histogram,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(bucket_start)
+        .bind(trades.get::<Decimal, _>("volume_kwh"))
+        .bind(trades.get::<Decimal, _>("notional"))
+        .bind(trades.get::<i64, _>("trade_count"))
+        .bind(trades.get::<Option<Decimal>, _>("low_price"))
+        .bind(trades.get::<Option<Decimal>, _>("high_price"))
+        .bind(orders.get::<Decimal, _>("requested_kwh"))
+        .bind(orders.get::<Decimal, _>("filled_kwh"))
+        .bind(orders.get::<i64, _>("orders_created"))
+        .bind(fill_secs.len() as i64)
+        .bind(ttf_sum_secs)
+        .bind(&histogram)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Aggregate open orders of the default market into price levels for both
+    /// sides of the book; other markets are served by their own orderbook endpoint
+    async fn capture_depth(&self, captured_at: DateTime<Utc>) -> anyhow::Result<DepthSnapshot> {
+        let bids = self.depth_side("buy", "DESC").await?;
+        let asks = self.depth_side("sell", "ASC").await?;
+
+        let best_bid = bids.first().map(|l| l.price_per_kwh);
+        let best_ask = asks.first().map(|l| l.price_per_kwh);
+
+        Ok(DepthSnapshot {
+            spread: bid_ask_spread(best_bid, best_ask),
+            bids,
+            asks,
+            best_bid,
+            best_ask,
+            captured_at,
+        })
+    }
+
+    async fn depth_side(&self, side: &str, order: &str) -> anyhow::Result<Vec<DepthLevel>> {
+        // `order` is one of two literals chosen above, never user input
+        let query = format!(
+            r#"
+            SELECT
+                price_per_kwh,
+                SUM(energy_amount - COALESCE(filled_amount, 0)) AS total_kwh,
+                COUNT(*) AS order_count
+            FROM trading_orders
+            WHERE side::text = $1
+              AND market_id = $3
+              AND status IN ('pending', 'active', 'partially_filled')
+              AND trigger_type IS NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+            GROUP BY price_per_kwh
+            ORDER BY price_per_kwh {}
+            LIMIT $2
+            "#,
+            order
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(side)
+            .bind(self.config.depth_levels)
+            .bind(DEFAULT_MARKET_ID)
+            .fetch_all(&self.db)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DepthLevel {
+                price_per_kwh: row.get::<Decimal, _>("price_per_kwh").to_f64().unwrap_or(0.0),
+                total_kwh: row.get::<Decimal, _>("total_kwh").to_f64().unwrap_or(0.0),
+                order_count: row.get("order_count"),
+            })
+            .collect())
+    }
+
+    async fn record_spread_sample(&self, bucket_start: DateTime<Utc>, spread: f64) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE market_analytics_hourly
+            SET spread_sum = spread_sum + $2,
+                spread_samples = spread_samples + 1
+            WHERE bucket_start = $1
+            "#,
+        )
+        .bind(bucket_start)
+        .bind(spread)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Start the aggregation loop
+pub async fn run(aggregator: MarketAnalyticsAggregator) {
+    info!(
+        "🚀 Starting market analytics aggregator (interval: {}s)",
+        aggregator.interval_secs()
+    );
+    loop {
+        if let Err(e) = aggregator.refresh().await {
+            tracing::error!("❌ Error in market analytics aggregator: {}", e);
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(aggregator.interval_secs())).await;
+    }
+}
+
+/// Volume-weighted average price, `None` when nothing traded
+pub fn vwap(notional: f64, volume_kwh: f64) -> Option<f64> {
+    if volume_kwh > 0.0 {
+        Some(notional / volume_kwh)
+    } else {
+        None
+    }
+}
+
+/// Spread between best ask and best bid, `None` unless both sides are quoted
+pub fn bid_ask_spread(best_bid: Option<f64>, best_ask: Option<f64>) -> Option<f64> {
+    match (best_bid, best_ask) {
+        (Some(bid), Some(ask)) => Some(ask - bid),
+        _ => None,
+    }
+}
+
+/// Count fill durations into the `TIME_TO_FILL_BOUNDS_SECS` buckets
+pub fn time_to_fill_histogram(fill_secs: &[f64]) -> Vec<i64> {
+    let mut histogram = vec![0i64; TIME_TO_FILL_LABELS.len()];
+    for secs in fill_secs {
+        let idx = TIME_TO_FILL_BOUNDS_SECS
+            .iter()
+            .position(|bound| *secs < *bound as f64)
+            .unwrap_or(TIME_TO_FILL_BOUNDS_SECS.len());
+        histogram[idx] += 1;
+    }
+    histogram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_to_fill_histogram() {
+        let histogram = time_to_fill_histogram(&[5.0, 59.9, 60.0, 400.0, 3599.0, 7200.0, 90000.0]);
+        assert_eq!(histogram, vec![2, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_vwap_and_spread() {
+        assert_eq!(vwap(0.0, 0.0), None);
+        assert_eq!(vwap(30.0, 10.0), Some(3.0));
+        assert_eq!(bid_ask_spread(Some(3.5), Some(4.0)), Some(0.5));
+        assert_eq!(bid_ask_spread(None, Some(4.0)), None);
+    }
+}
@@ -0,0 +1,224 @@
+//! Market Statistics Archive
+//!
+//! Immutable daily rollups of the market: traded volume, clearing price
+//! range, participant counts and minted energy per completed market-local
+//! day. A nightly job writes each day once to `market_daily_rollups`,
+//! catching up on days it missed, and the public archive endpoint reads only
+//! that table.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, Result};
+use crate::utils::MarketTimezone;
+
+/// Days rolled up in one run when catching up, and the widest archive query
+pub const MAX_ROLLUP_DAYS: i64 = 366;
+
+const ROLLUP_COLUMNS: &str = "rollup_date, volume_kwh, trade_count, epoch_count, high_clearing_price, \
+    low_clearing_price, close_clearing_price, vwap_clearing_price, buyer_count, seller_count, \
+    participant_count, minted_kwh, created_at";
+
+/// Market statistics of one market-local day
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct MarketDailyRollup {
+    pub rollup_date: NaiveDate,
+    /// Energy traded, busted and rolled-back trades excluded
+    #[schema(value_type = String)]
+    pub volume_kwh: Decimal,
+    pub trade_count: i64,
+    /// Epochs cleared; the clearing prices are null when none cleared
+    pub epoch_count: i32,
+    #[schema(value_type = Option<String>)]
+    pub high_clearing_price: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub low_clearing_price: Option<Decimal>,
+    /// Price of the day's last cleared epoch
+    #[schema(value_type = Option<String>)]
+    pub close_clearing_price: Option<Decimal>,
+    /// Volume-weighted clearing price
+    #[schema(value_type = Option<String>)]
+    pub vwap_clearing_price: Option<Decimal>,
+    pub buyer_count: i32,
+    pub seller_count: i32,
+    /// Distinct users on either side of a trade
+    pub participant_count: i32,
+    /// Energy tokenized from readings taken during the day
+    #[schema(value_type = String)]
+    pub minted_kwh: Decimal,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Completed days still to roll up, oldest first: the day after `last`, or
+/// `first_activity` for an empty archive, through yesterday. At most
+/// `MAX_ROLLUP_DAYS` per run.
+pub fn pending_days(last: Option<NaiveDate>, first_activity: Option<NaiveDate>, today: NaiveDate) -> Vec<NaiveDate> {
+    let Some(start) = last.map(|d| d + Duration::days(1)).or(first_activity) else {
+        return Vec::new();
+    };
+    start
+        .iter_days()
+        .take_while(|day| *day < today)
+        .take(MAX_ROLLUP_DAYS as usize)
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct MarketArchiveService {
+    db: PgPool,
+    timezone: MarketTimezone,
+}
+
+impl MarketArchiveService {
+    pub fn new(db: PgPool, timezone: MarketTimezone) -> Self {
+        Self { db, timezone }
+    }
+
+    pub fn timezone(&self) -> &MarketTimezone {
+        &self.timezone
+    }
+
+    /// Roll up every completed day not archived yet; returns the new rollups
+    pub async fn ensure_completed_days(&self) -> Result<Vec<MarketDailyRollup>> {
+        let last = sqlx::query_scalar::<_, Option<NaiveDate>>("SELECT MAX(rollup_date) FROM market_daily_rollups")
+            .fetch_one(&self.db)
+            .await?;
+        let first_activity = match last {
+            Some(_) => None,
+            None => sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+                r#"
+                SELECT LEAST(
+                    (SELECT MIN(match_time) FROM order_matches),
+                    (SELECT MIN(epoch_start) FROM clearing_price_index)
+                )
+                "#,
+            )
+            .fetch_one(&self.db)
+            .await?
+            .map(|at| self.timezone.date_of(at)),
+        };
+
+        let mut rollups = Vec::new();
+        for day in pending_days(last, first_activity, self.timezone.date_of(Utc::now())) {
+            if let Some(rollup) = self.roll_up_day(day).await? {
+                rollups.push(rollup);
+            }
+        }
+        Ok(rollups)
+    }
+
+    /// Archive a completed market-local day. A day already archived is kept
+    /// as is.
+    pub async fn roll_up_day(&self, date: NaiveDate) -> Result<Option<MarketDailyRollup>> {
+        let (day_start, day_end) = self.timezone.day_bounds(date);
+        if day_end > Utc::now() {
+            return Err(ApiError::BadRequest(format!(
+                "{} cannot be rolled up before the day ends",
+                date
+            )));
+        }
+
+        let rollup = sqlx::query_as::<_, MarketDailyRollup>(&format!(
+            r#"
+            WITH trades AS (
+                SELECT m.matched_amount, b.user_id AS buyer_id, s.user_id AS seller_id
+                FROM order_matches m
+                JOIN trading_orders b ON b.id = m.buy_order_id
+                JOIN trading_orders s ON s.id = m.sell_order_id
+                WHERE m.match_time >= $1 AND m.match_time < $2 AND m.status NOT IN ('busted', 'rolled_back')
+            ),
+            clearing AS (
+                SELECT
+                    COUNT(*)::INTEGER AS epoch_count,
+                    MAX(clearing_price) AS high,
+                    MIN(clearing_price) AS low,
+                    (array_agg(clearing_price ORDER BY epoch_start DESC))[1] AS close,
+                    ROUND(COALESCE(
+                        SUM(clearing_price * volume_kwh) / NULLIF(SUM(volume_kwh), 0),
+                        AVG(clearing_price)
+                    ), 8) AS vwap
+                FROM clearing_price_index
+                WHERE epoch_start >= $1 AND epoch_start < $2
+            ),
+            minted AS (
+                SELECT COALESCE(SUM(kwh_amount), 0) AS minted_kwh
+                FROM meter_readings
+                WHERE minted = true AND kwh_amount > 0
+                  AND reading_timestamp >= $1 AND reading_timestamp < $2
+            )
+            INSERT INTO market_daily_rollups (
+                rollup_date, volume_kwh, trade_count, epoch_count, high_clearing_price,
+                low_clearing_price, close_clearing_price, vwap_clearing_price, buyer_count,
+                seller_count, participant_count, minted_kwh
+            )
+            SELECT
+                $3,
+                (SELECT COALESCE(SUM(matched_amount), 0) FROM trades),
+                (SELECT COUNT(*) FROM trades),
+                c.epoch_count, c.high, c.low, c.close, c.vwap,
+                (SELECT COUNT(DISTINCT buyer_id) FROM trades)::INTEGER,
+                (SELECT COUNT(DISTINCT seller_id) FROM trades)::INTEGER,
+                (SELECT COUNT(*) FROM (SELECT buyer_id FROM trades UNION SELECT seller_id FROM trades) p)::INTEGER,
+                mi.minted_kwh
+            FROM clearing c, minted mi
+            ON CONFLICT (rollup_date) DO NOTHING
+            RETURNING {}
+            "#,
+            ROLLUP_COLUMNS
+        ))
+        .bind(day_start)
+        .bind(day_end)
+        .bind(date)
+        .fetch_optional(&self.db)
+        .await?;
+
+        if let Some(rollup) = &rollup {
+            info!(
+                "🗄️ Archived market day {}: {} kWh in {} trades, {} kWh minted",
+                rollup.rollup_date, rollup.volume_kwh, rollup.trade_count, rollup.minted_kwh
+            );
+        }
+        Ok(rollup)
+    }
+
+    /// Archived days between `from` and `to`, both inclusive, oldest first
+    pub async fn list(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<MarketDailyRollup>> {
+        Ok(sqlx::query_as::<_, MarketDailyRollup>(&format!(
+            "SELECT {} FROM market_daily_rollups WHERE rollup_date BETWEEN $1 AND $2 ORDER BY rollup_date",
+            ROLLUP_COLUMNS
+        ))
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, day).unwrap()
+    }
+
+    #[test]
+    fn test_pending_days_continue_after_last_rollup() {
+        assert_eq!(pending_days(Some(date(15)), None, date(18)), vec![date(16), date(17)]);
+        assert!(pending_days(Some(date(17)), None, date(18)).is_empty());
+    }
+
+    #[test]
+    fn test_empty_archive_starts_at_first_activity() {
+        assert_eq!(pending_days(None, Some(date(16)), date(18)), vec![date(16), date(17)]);
+        assert!(pending_days(None, None, date(18)).is_empty());
+        assert_eq!(
+            pending_days(None, Some(date(1) - Duration::days(1000)), date(18)).len(),
+            MAX_ROLLUP_DAYS as usize
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use sqlx::{Postgres, Row, Transaction};
+use std::str::FromStr;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ErrorCode};
+use crate::utils::decimal::from_base_units;
+use super::MarketClearingService;
+
+/// Decimals of the energy token mint (1 token = 1 kWh)
+const ENERGY_TOKEN_DECIMALS: u32 = 9;
+
+/// Energy a seller may still commit to new sell orders.
+///
+/// `holdings` is the energy the seller owns, `committed` what is already locked
+/// by open sell orders. The role's over-subscription percentage scales the
+/// holdings before the committed amount is taken off.
+pub fn sell_capacity(holdings: Decimal, committed: Decimal, oversubscription_pct: Decimal) -> Decimal {
+    let allowed = holdings * (Decimal::ONE + oversubscription_pct / Decimal::ONE_HUNDRED);
+    (allowed - committed).max(Decimal::ZERO)
+}
+
+/// Error returned when an order exceeds what the user can cover
+pub fn insufficient(what: &str, required: Decimal, available: Decimal) -> ApiError {
+    ApiError::with_details(
+        ErrorCode::InsufficientBalance,
+        format!("Insufficient {} for order", what),
+        format!("Required: {}, Available: {}", required, available),
+    )
+}
+
+impl MarketClearingService {
+    /// Off-chain energy ledger of a user: minted generation plus energy bought
+    /// minus energy sold in completed settlements, plus vesting grants,
+    /// minted referral rewards and energy swapped with AMM pools
+    pub(super) async fn ledger_energy_balance(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+    ) -> Result<Decimal> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                (SELECT COALESCE(SUM(kwh_amount), 0) FROM meter_readings
+                 WHERE user_id = $1 AND minted = true AND kwh_amount > 0) AS minted,
+                (SELECT COALESCE(SUM(energy_amount), 0) FROM settlements
+                 WHERE buyer_id = $1 AND status = 'completed') AS bought,
+                (SELECT COALESCE(SUM(energy_amount), 0) FROM settlements
+                 WHERE seller_id = $1 AND status = 'completed') AS sold,
+                (SELECT COALESCE(SUM(amount), 0) FROM vesting_grants
+                 WHERE user_id = $1) AS granted,
+                (SELECT COALESCE(SUM(amount), 0) FROM referral_rewards
+                 WHERE user_id = $1 AND status = 'minted') AS rewarded,
+                (SELECT COALESCE(SUM(CASE WHEN output_token = 'energy' THEN output_amount
+                                          ELSE -input_amount END), 0)
+                 FROM swap_transactions
+                 WHERE user_id = $1 AND status = 'completed'
+                   AND 'energy' IN (input_token, output_token)) AS swapped
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        let minted: Decimal = row.get("minted");
+        let bought: Decimal = row.get("bought");
+        let sold: Decimal = row.get("sold");
+        let granted: Decimal = row.get("granted");
+        let rewarded: Decimal = row.get("rewarded");
+        let swapped: Decimal = row.get("swapped");
+
+        Ok(minted + bought - sold + granted + rewarded + swapped)
+    }
+
+    /// On-chain energy token balance of the user's wallet, fetched for large
+    /// sell orders when on-chain escrow checks are enabled.
+    ///
+    /// Returns `None` when confirmation does not apply to this order.
+    pub(super) async fn onchain_energy_confirmation(
+        &self,
+        user_id: Uuid,
+        energy_amount: Decimal,
+    ) -> Result<Option<Decimal>> {
+        if !self.config.tokenization.use_onchain_balance_for_escrow
+            || energy_amount < self.config.order_balance.onchain_confirmation_kwh
+        {
+            return Ok(None);
+        }
+
+        let wallet = sqlx::query_scalar::<_, Option<String>>("SELECT wallet_address FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?
+            .filter(|w| !w.is_empty())
+            .ok_or_else(|| ApiError::BadRequest("A wallet is required to place large sell orders".to_string()))?;
+
+        let owner = solana_sdk::pubkey::Pubkey::from_str(&wallet)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid wallet address: {}", e)))?;
+        let mint = solana_sdk::pubkey::Pubkey::from_str(&self.config.energy_token_mint)
+            .map_err(|e| ApiError::Internal(format!("Invalid mint address: {}", e)))?;
+
+        let raw = self
+            .blockchain_service
+            .get_token_balance(&owner, &mint)
+            .await
+            .map_err(|e| {
+                warn!("On-chain balance lookup failed for user {}: {}", user_id, e);
+                ApiError::Blockchain(format!("Could not confirm on-chain energy balance: {}", e))
+            })?;
+        let balance = from_base_units(raw, ENERGY_TOKEN_DECIMALS)?;
+
+        info!(
+            "On-chain confirmation for {} kWh sell order of user {}: wallet holds {} kWh",
+            energy_amount, user_id, balance
+        );
+
+        Ok(Some(balance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(value: i64) -> Decimal {
+        Decimal::from(value)
+    }
+
+    #[test]
+    fn test_capacity_without_oversubscription() {
+        assert_eq!(sell_capacity(d(100), d(30), Decimal::ZERO), d(70));
+    }
+
+    #[test]
+    fn test_capacity_with_oversubscription() {
+        // 100 kWh held, 20% allowance -> 120 kWh, 30 already committed
+        assert_eq!(sell_capacity(d(100), d(30), d(20)), d(90));
+    }
+
+    #[test]
+    fn test_capacity_never_negative() {
+        assert_eq!(sell_capacity(d(10), d(30), Decimal::ZERO), Decimal::ZERO);
+        assert_eq!(sell_capacity(d(-5), Decimal::ZERO, d(50)), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use rust_decimal::Decimal;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::handlers::websocket::broadcaster::broadcast_p2p_order_update;
+use crate::services::order_dust::{self, NewDust};
+use crate::services::order_events::{self, NewOrderEvent};
+use super::types::{OrderBookEntry, OrderMatch};
+
+/// Order status update pushed to its owner once the batch is committed
+#[derive(Debug, Clone)]
+pub struct OrderUpdate {
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub side: &'static str,
+    /// filled, partially_filled or cancelled
+    pub status: &'static str,
+    pub original_amount: Decimal,
+    pub filled_amount: Decimal,
+    pub remaining_amount: Decimal,
+    pub price_per_kwh: Decimal,
+}
+
+/// Writes of one epoch's matching run. The matching loop only accumulates
+/// them; [`write`](Self::write) applies them with bulk `UNNEST` statements
+/// in the epoch's transaction, and nothing is announced until it commits.
+#[derive(Debug, Default)]
+pub struct MatchWriteBatch {
+    matches: Vec<OrderMatch>,
+    /// `filled_amount` increments of orders left partially filled by a match
+    fill_increments: HashMap<Uuid, Decimal>,
+    /// Orders fully filled by the run
+    filled_orders: Vec<Uuid>,
+    /// Orders left with a remainder below their market's dust threshold
+    dust: Vec<NewDust>,
+    events: Vec<NewOrderEvent>,
+    updates: Vec<OrderUpdate>,
+}
+
+impl MatchWriteBatch {
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty() && self.dust.is_empty()
+    }
+
+    pub fn push_match(&mut self, order_match: OrderMatch) {
+        self.matches.push(order_match);
+    }
+
+    pub fn push_event(&mut self, event: NewOrderEvent) {
+        self.events.push(event);
+    }
+
+    /// Record an order left partially filled by a match of `amount`
+    pub fn partially_filled(&mut self, update: OrderUpdate, amount: Decimal) {
+        *self.fill_increments.entry(update.order_id).or_insert(Decimal::ZERO) += amount;
+        self.updates.push(update);
+    }
+
+    /// Record an order fully filled by a match
+    pub fn filled(&mut self, update: OrderUpdate) {
+        self.filled_orders.push(update.order_id);
+        self.updates.push(update);
+    }
+
+    /// Record an order cancelled for its dust remainder
+    pub fn dust(&mut self, order: &OrderBookEntry) {
+        let dust = NewDust {
+            order_id: order.order_id,
+            user_id: order.user_id,
+            side: order.side.as_str(),
+            filled: order.original_amount - order.energy_amount,
+            remaining: order.energy_amount,
+        };
+        self.events.push(dust.event());
+        self.updates.push(OrderUpdate {
+            order_id: order.order_id,
+            user_id: order.user_id,
+            side: dust.side,
+            status: "cancelled",
+            original_amount: order.original_amount,
+            filled_amount: dust.filled,
+            remaining_amount: dust.remaining,
+            price_per_kwh: order.price_per_kwh,
+        });
+        self.dust.push(dust);
+    }
+
+    /// Apply the batch inside `tx`; returns the timestamps of the recorded
+    /// order events for [`publish`](Self::publish)
+    pub async fn write(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Vec<chrono::DateTime<chrono::Utc>>> {
+        if !self.matches.is_empty() {
+            sqlx::query(
+                r#"
+                INSERT INTO order_matches (
+                    id, epoch_id, buy_order_id, sell_order_id,
+                    matched_amount, match_price, match_time, status, market_id
+                )
+                SELECT m.id, m.epoch_id, m.buy_order_id, m.sell_order_id,
+                       m.matched_amount, m.match_price, m.match_time, m.status, o.market_id
+                FROM UNNEST($1::uuid[], $2::uuid[], $3::uuid[], $4::uuid[], $5::numeric[],
+                            $6::numeric[], $7::timestamptz[], $8::text[])
+                     AS m(id, epoch_id, buy_order_id, sell_order_id, matched_amount, match_price,
+                          match_time, status)
+                LEFT JOIN trading_orders o ON o.id = m.buy_order_id
+                "#,
+            )
+            .bind(self.matches.iter().map(|m| m.id).collect::<Vec<_>>())
+            .bind(self.matches.iter().map(|m| m.epoch_id).collect::<Vec<_>>())
+            .bind(self.matches.iter().map(|m| m.buy_order_id).collect::<Vec<_>>())
+            .bind(self.matches.iter().map(|m| m.sell_order_id).collect::<Vec<_>>())
+            .bind(self.matches.iter().map(|m| m.matched_amount).collect::<Vec<_>>())
+            .bind(self.matches.iter().map(|m| m.match_price).collect::<Vec<_>>())
+            .bind(self.matches.iter().map(|m| m.match_time).collect::<Vec<_>>())
+            .bind(self.matches.iter().map(|m| m.status.clone()).collect::<Vec<_>>())
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        if !self.fill_increments.is_empty() {
+            let (order_ids, amounts): (Vec<Uuid>, Vec<Decimal>) =
+                self.fill_increments.iter().map(|(id, amount)| (*id, *amount)).unzip();
+            // A fill past an order's amount means the book this run read is
+            // out of date: fail the whole write rather than over-fill
+            let filled = sqlx::query(
+                r#"
+                UPDATE trading_orders t
+                SET filled_amount = COALESCE(t.filled_amount, 0) + f.amount
+                FROM UNNEST($1::uuid[], $2::numeric[]) AS f(id, amount)
+                WHERE t.id = f.id AND COALESCE(t.filled_amount, 0) + f.amount <= t.energy_amount
+                "#,
+            )
+            .bind(&order_ids)
+            .bind(amounts)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+            if filled != order_ids.len() as u64 {
+                anyhow::bail!(
+                    "{} of {} orders could not take their fills; their book changed during the run",
+                    order_ids.len() as u64 - filled,
+                    order_ids.len()
+                );
+            }
+        }
+
+        if !self.filled_orders.is_empty() {
+            sqlx::query("UPDATE trading_orders SET status = 'filled'::order_status WHERE id = ANY($1)")
+                .bind(&self.filled_orders)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        if !self.dust.is_empty() {
+            sqlx::query(
+                r#"
+                UPDATE trading_orders SET status = 'cancelled'::order_status, updated_at = NOW()
+                WHERE id = ANY($1) AND status IN ('pending', 'active', 'partially_filled')
+                "#,
+            )
+            .bind(self.dust.iter().map(|d| d.order_id).collect::<Vec<_>>())
+            .execute(&mut **tx)
+            .await?;
+            order_dust::record_batch(tx, &self.dust).await?;
+        }
+
+        Ok(order_events::insert_batch(tx, &self.events).await?)
+    }
+
+    /// Announce the committed batch: order events to their waiters and
+    /// owners, and status updates over the P2P order channel
+    pub async fn publish(self, timestamps: Vec<chrono::DateTime<chrono::Utc>>) {
+        for (event, timestamp) in self.events.into_iter().zip(timestamps) {
+            order_events::publish(event, timestamp).await;
+        }
+
+        for update in self.updates {
+            let _ = broadcast_p2p_order_update(
+                update.order_id,
+                update.user_id,
+                update.side.to_string(),
+                update.status.to_string(),
+                update.original_amount.to_string(),
+                update.filled_amount.to_string(),
+                update.remaining_amount.to_string(),
+                update.price_per_kwh.to_string(),
+            )
+            .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(order_id: Uuid, status: &'static str) -> OrderUpdate {
+        OrderUpdate {
+            order_id,
+            user_id: Uuid::new_v4(),
+            side: "buy",
+            status,
+            original_amount: Decimal::from(10),
+            filled_amount: Decimal::ZERO,
+            remaining_amount: Decimal::ZERO,
+            price_per_kwh: Decimal::ONE,
+        }
+    }
+
+    #[test]
+    fn test_partial_fills_of_an_order_are_summed() {
+        let order_id = Uuid::new_v4();
+        let mut batch = MatchWriteBatch::default();
+        batch.partially_filled(update(order_id, "partially_filled"), Decimal::from(3));
+        batch.partially_filled(update(order_id, "partially_filled"), Decimal::from(4));
+        batch.filled(update(Uuid::new_v4(), "filled"));
+
+        assert_eq!(batch.fill_increments.len(), 1);
+        assert_eq!(batch.fill_increments[&order_id], Decimal::from(7));
+        assert_eq!(batch.filled_orders.len(), 1);
+        assert_eq!(batch.updates.len(), 3);
+    }
+}
@@ -1,6 +1,5 @@
 use anyhow::Result;
-use rust_decimal::prelude::ToPrimitive;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use sqlx::Row;
 use solana_sdk::pubkey::Pubkey;
 use uuid::Uuid;
@@ -8,9 +7,23 @@ use tracing::{error, info};
 
 use crate::database::schema::types::OrderSide;
 use crate::services::WalletService;
+use crate::utils::decimal::{to_base_units_rounded, to_lamports};
 use super::MarketClearingService;
 
 impl MarketClearingService {
+    /// Mint and decimals for an escrow asset ("currency" or "energy")
+    fn escrow_mint(&self, asset_type: &str) -> (String, u8) {
+        if asset_type == "energy" {
+            let mint = std::env::var("EnergyTokenMint")
+                .or_else(|_| std::env::var("ENERGY_TOKEN_MINT"))
+                .unwrap_or_else(|_| "Geq98m3Vw63AqrMEVoZsiW5DbNkScteZAdWDmm95ykYF".to_string());
+            (mint, 9)
+        } else {
+            let currency = &self.config.currency;
+            (currency.settlement_mint().to_string(), currency.settlement_decimals())
+        }
+    }
+
     pub(super) async fn execute_on_chain_order_creation(
         &self,
         user_id: Uuid,
@@ -91,9 +104,8 @@ impl MarketClearingService {
             let trading_program_id = self.blockchain_service.trading_program_id()?;
             let (market_pda, _) = Pubkey::find_program_address(&[b"market"], &trading_program_id);
 
-            let multiplier = Decimal::from(1_000_000_000);
-            let amount_u64 = (energy_amount * multiplier).to_u64().unwrap_or(0);
-            let price_u64 = (price_per_kwh * multiplier).to_u64().unwrap_or(0);
+            let amount_u64 = to_lamports(energy_amount)?;
+            let price_u64 = to_lamports(price_per_kwh)?;
 
             info!("Creating order on-chain with Payer: {}", keypair.pubkey());
             info!("Market PDA: {}", market_pda);
@@ -232,15 +244,7 @@ impl MarketClearingService {
         };
 
         // 2. Select Mint based on asset_type
-        let mint_str = if asset_type == "energy" {
-            std::env::var("EnergyTokenMint")
-             .or_else(|_| std::env::var("ENERGY_TOKEN_MINT"))
-             .unwrap_or_else(|_| "Geq98m3Vw63AqrMEVoZsiW5DbNkScteZAdWDmm95ykYF".to_string())
-        } else {
-            // Default to Currency (USDC)
-            std::env::var("CURRENCY_TOKEN_MINT")
-             .unwrap_or_else(|_| "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU".to_string()) // Devnet USDC
-        };
+        let (mint_str, decimals) = self.escrow_mint(asset_type);
         let mint = Pubkey::from_str(&mint_str)?;
 
         // 3. User ATA
@@ -258,11 +262,7 @@ impl MarketClearingService {
         ).await?;
 
         // 6. Lock Tokens
-        // Determine decimals - USDC is 6, Energy is 9?
-        // Ideally fetch from chain, but for now hardcode or config?
-        let decimals = if asset_type == "energy" { 9 } else { 6 };
-        let multiplier = Decimal::from(10_u64.pow(decimals as u32));
-        let amount_u64 = (amount * multiplier).to_u64().unwrap_or(0);
+        let amount_u64 = to_base_units_rounded(amount, decimals as u32, RoundingStrategy::ToZero)?;
 
         info!("Locking {} {} tokens ({} raw) from {} to API escrow {}", amount, asset_type, amount_u64, keypair.pubkey(), escrow_owner);
 
@@ -329,15 +329,7 @@ impl MarketClearingService {
         };
 
         // 2. Select Mint based on asset_type
-        let mint_str = if asset_type == "energy" {
-            std::env::var("EnergyTokenMint")
-             .or_else(|_| std::env::var("ENERGY_TOKEN_MINT"))
-             .unwrap_or_else(|_| "Geq98m3Vw63AqrMEVoZsiW5DbNkScteZAdWDmm95ykYF".to_string())
-        } else {
-            // Default to Currency (USDC)
-            std::env::var("CURRENCY_TOKEN_MINT")
-             .unwrap_or_else(|_| "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU".to_string())
-        };
+        let (mint_str, decimals) = self.escrow_mint(asset_type);
         let mint = Pubkey::from_str(&mint_str)?;
 
         // 3. API Authority (Escrow Owner)
@@ -354,9 +346,7 @@ impl MarketClearingService {
         ).await?;
 
         // 5. Release Tokens
-        let decimals = if asset_type == "energy" { 9 } else { 6 };
-        let multiplier = Decimal::from(10_u64.pow(decimals as u32));
-        let amount_u64 = (amount * multiplier).to_u64().unwrap_or(0);
+        let amount_u64 = to_base_units_rounded(amount, decimals as u32, RoundingStrategy::ToZero)?;
 
         info!("Releasing {} {} tokens from API escrow to receiver {}", amount, asset_type, receiver_wallet);
 
@@ -403,15 +393,7 @@ impl MarketClearingService {
         };
 
         // 2. Select Mint based on asset_type
-        let mint_str = if asset_type == "energy" {
-            std::env::var("EnergyTokenMint")
-             .or_else(|_| std::env::var("ENERGY_TOKEN_MINT"))
-             .unwrap_or_else(|_| "Geq98m3Vw63AqrMEVoZsiW5DbNkScteZAdWDmm95ykYF".to_string())
-        } else {
-            // Default to Currency (USDC)
-            std::env::var("CURRENCY_TOKEN_MINT")
-             .unwrap_or_else(|_| "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU".to_string())
-        };
+        let (mint_str, decimals) = self.escrow_mint(asset_type);
         let mint = Pubkey::from_str(&mint_str)?;
 
         // 3. API Authority (Escrow Owner)
@@ -428,9 +410,7 @@ impl MarketClearingService {
         ).await?;
 
         // 5. Refund Tokens
-        let decimals = if asset_type == "energy" { 9 } else { 6 };
-        let multiplier = Decimal::from(10_u64.pow(decimals as u32));
-        let amount_u64 = (amount * multiplier).to_u64().unwrap_or(0);
+        let amount_u64 = to_base_units_rounded(amount, decimals as u32, RoundingStrategy::ToZero)?;
 
         info!("Refunding {} {} tokens from API escrow to user {}", amount, asset_type, user_wallet);
 
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::Row;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::schema::types::{EpochStatus, OrderSide};
+use crate::models::EnergyKwh;
+use super::MarketClearingService;
+
+/// One price level of an aggregated supply or demand curve
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CurvePoint {
+    #[schema(value_type = String)]
+    pub price: Decimal,
+    /// Volume offered at exactly this price
+    pub volume: EnergyKwh,
+    /// Volume at this price or better (higher for bids, lower for asks)
+    pub cumulative_volume: EnergyKwh,
+    pub order_count: i64,
+}
+
+/// Bid and ask curves of an epoch with its clearing summary
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EpochCurves {
+    pub epoch_id: Uuid,
+    pub epoch_number: i64,
+    pub status: EpochStatus,
+    #[schema(value_type = Option<String>)]
+    pub clearing_price: Option<Decimal>,
+    pub total_volume: Option<EnergyKwh>,
+    pub total_orders: Option<i64>,
+    pub matched_orders: Option<i64>,
+    /// Orders that expired unfilled or partially filled
+    pub expired_orders: i32,
+    /// Unfilled energy of expired orders
+    pub expired_volume: EnergyKwh,
+    /// Demand curve, highest price first
+    pub bids: Vec<CurvePoint>,
+    /// Supply curve, lowest price first
+    pub asks: Vec<CurvePoint>,
+}
+
+/// Aggregate `(price, volume)` orders into a cumulative curve.
+/// Bids accumulate from the highest price down, asks from the lowest up.
+pub fn build_curve(orders: &[(Decimal, Decimal)], side: OrderSide) -> Vec<CurvePoint> {
+    let mut levels: Vec<(Decimal, Decimal, i64)> = Vec::new();
+    let mut sorted = orders.to_vec();
+    sorted.sort_by(|a, b| match side {
+        OrderSide::Buy => b.0.cmp(&a.0),
+        OrderSide::Sell => a.0.cmp(&b.0),
+    });
+
+    for (price, volume) in sorted {
+        match levels.last_mut() {
+            Some(level) if level.0 == price => {
+                level.1 += volume;
+                level.2 += 1;
+            }
+            _ => levels.push((price, volume, 1)),
+        }
+    }
+
+    let mut cumulative = Decimal::ZERO;
+    levels
+        .into_iter()
+        .map(|(price, volume, order_count)| {
+            cumulative += volume;
+            CurvePoint {
+                price,
+                volume: EnergyKwh::from(volume),
+                cumulative_volume: EnergyKwh::from(cumulative),
+                order_count,
+            }
+        })
+        .collect()
+}
+
+impl MarketClearingService {
+    /// Supply and demand curves for an epoch, built from every order that
+    /// took part in clearing (cancelled orders are excluded) at its original size
+    pub async fn get_epoch_curves(&self, epoch_id: Uuid) -> Result<Option<EpochCurves>> {
+        let Some(epoch) = self.get_epoch_by_id(epoch_id).await? else {
+            return Ok(None);
+        };
+
+        let rows = sqlx::query(
+            r#"
+            SELECT side::text AS side, price_per_kwh, energy_amount
+            FROM trading_orders
+            WHERE epoch_id = $1
+              AND price_per_kwh IS NOT NULL
+              AND status IN ('pending', 'partially_filled', 'filled')
+            "#,
+        )
+        .bind(epoch_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let expiry = sqlx::query("SELECT expired_orders, expired_volume FROM market_epochs WHERE id = $1")
+            .bind(epoch_id)
+            .fetch_one(&self.db)
+            .await?;
+
+        let mut bids = Vec::new();
+        let mut asks = Vec::new();
+        for row in rows {
+            let side: String = row.get("side");
+            let order = (row.get::<Decimal, _>("price_per_kwh"), row.get::<Decimal, _>("energy_amount"));
+            if side == "buy" {
+                bids.push(order);
+            } else {
+                asks.push(order);
+            }
+        }
+
+        Ok(Some(EpochCurves {
+            epoch_id: epoch.id,
+            epoch_number: epoch.epoch_number,
+            status: epoch.status,
+            clearing_price: epoch.clearing_price,
+            total_volume: epoch.total_volume.map(EnergyKwh::from),
+            total_orders: epoch.total_orders,
+            matched_orders: epoch.matched_orders,
+            expired_orders: expiry.get("expired_orders"),
+            expired_volume: EnergyKwh::from(expiry.get::<Decimal, _>("expired_volume")),
+            bids: build_curve(&bids, OrderSide::Buy),
+            asks: build_curve(&asks, OrderSide::Sell),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(value: i64, scale: u32) -> Decimal {
+        Decimal::new(value, scale)
+    }
+
+    #[test]
+    fn test_bid_curve_accumulates_from_highest_price() {
+        let orders = [(d(30, 1), d(5, 0)), (d(35, 1), d(2, 0)), (d(30, 1), d(1, 0))];
+        let curve = build_curve(&orders, OrderSide::Buy);
+
+        assert_eq!(curve.len(), 2);
+        assert_eq!(curve[0].price, d(35, 1));
+        assert_eq!(curve[0].cumulative_volume, EnergyKwh::from(d(2, 0)));
+        assert_eq!(curve[1].price, d(30, 1));
+        assert_eq!(curve[1].volume, EnergyKwh::from(d(6, 0)));
+        assert_eq!(curve[1].cumulative_volume, EnergyKwh::from(d(8, 0)));
+        assert_eq!(curve[1].order_count, 2);
+    }
+
+    #[test]
+    fn test_ask_curve_accumulates_from_lowest_price() {
+        let orders = [(d(40, 1), d(3, 0)), (d(25, 1), d(4, 0))];
+        let curve = build_curve(&orders, OrderSide::Sell);
+
+        assert_eq!(curve[0].price, d(25, 1));
+        assert_eq!(curve[1].price, d(40, 1));
+        assert_eq!(curve[1].cumulative_volume, EnergyKwh::from(d(7, 0)));
+    }
+
+    #[test]
+    fn test_empty_curve() {
+        assert!(build_curve(&[], OrderSide::Buy).is_empty());
+    }
+}
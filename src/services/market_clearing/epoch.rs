@@ -1,10 +1,12 @@
 use anyhow::Result;
 use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
 use rust_decimal::Decimal;
+use sqlx::{Postgres, Transaction};
 use uuid::Uuid;
 use tracing::info;
 
 use crate::database::schema::types::EpochStatus;
+use crate::utils::MarketTimezone;
 use super::MarketClearingService;
 use super::types::MarketEpoch;
 
@@ -34,19 +36,18 @@ impl MarketClearingService {
 
     /// Create or get market epoch for a specific timestamp
     pub async fn get_or_create_epoch(&self, timestamp: DateTime<Utc>) -> Result<MarketEpoch> {
-        // Calculate epoch number: YYYYMMDDHHMM (15-minute intervals)
-        let epoch_number = (timestamp.year() as i64) * 100_000_000
-            + (timestamp.month() as i64) * 1_000_000
-            + (timestamp.day() as i64) * 10_000
-            + (timestamp.hour() as i64) * 100
-            + ((timestamp.minute() / 15) * 15) as i64;
-
-        // Calculate epoch start and end times
-        let epoch_start = timestamp
-            .with_minute((timestamp.minute() / 15) * 15)
-            .and_then(|dt| dt.with_second(0))
-            .and_then(|dt| dt.with_nanosecond(0))
-            .unwrap_or(timestamp);
+        // Epochs are aligned to the market-local quarter hour
+        let deployment = &self.config.deployment;
+        let timezone = MarketTimezone::new(deployment.timezone.clone(), deployment.utc_offset_minutes);
+        let epoch_start = timezone.epoch_start(timestamp, 15);
+
+        // Calculate epoch number: YYYYMMDDHHMM of the UTC start, so numbers
+        // stay stable whatever timezone a deployment reports in
+        let epoch_number = (epoch_start.year() as i64) * 100_000_000
+            + (epoch_start.month() as i64) * 1_000_000
+            + (epoch_start.day() as i64) * 10_000
+            + (epoch_start.hour() as i64) * 100
+            + epoch_start.minute() as i64;
 
         let epoch_end = epoch_start + Duration::minutes(15);
 
@@ -138,9 +139,29 @@ impl MarketClearingService {
         Ok(epoch)
     }
 
+    /// Get epoch by ID
+    pub async fn get_epoch_by_id(&self, epoch_id: Uuid) -> Result<Option<MarketEpoch>> {
+        let epoch = sqlx::query_as!(
+            MarketEpoch,
+            r#"
+            SELECT 
+                id, epoch_number, start_time, end_time, status as "status: EpochStatus",
+                clearing_price, total_volume, total_orders, matched_orders
+            FROM market_epochs 
+            WHERE id = $1
+            "#,
+            epoch_id
+        )
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(epoch)
+    }
+
     /// Update epoch statistics
     pub(super) async fn update_epoch_statistics(
         &self,
+        tx: &mut Transaction<'_, Postgres>,
         epoch_id: Uuid,
         total_volume: Decimal,
         matched_orders: i64,
@@ -150,7 +171,7 @@ impl MarketClearingService {
             "SELECT COUNT(*) FROM trading_orders WHERE epoch_id = $1 AND status IN ('pending', 'filled')",
             epoch_id
         )
-        .fetch_one(&self.db)
+        .fetch_one(&mut **tx)
         .await?
         .unwrap_or(0);
 
@@ -166,7 +187,36 @@ impl MarketClearingService {
         .bind(matched_orders)
         .bind(total_orders)
         .bind(epoch_id)
-        .execute(&self.db)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Add an epoch's clearing price to the clearing price index series
+    pub(super) async fn record_clearing_price(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        epoch_id: Uuid,
+        clearing_price: Decimal,
+        volume: Decimal,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO clearing_price_index (epoch_id, epoch_number, epoch_start, clearing_price, volume_kwh)
+            SELECT id, epoch_number, start_time, $2, $3
+            FROM market_epochs
+            WHERE id = $1
+            ON CONFLICT (epoch_id) DO UPDATE
+            SET clearing_price = EXCLUDED.clearing_price,
+                volume_kwh = EXCLUDED.volume_kwh,
+                recorded_at = NOW()
+            "#,
+        )
+        .bind(epoch_id)
+        .bind(clearing_price)
+        .bind(volume)
+        .execute(&mut **tx)
         .await?;
 
         Ok(())
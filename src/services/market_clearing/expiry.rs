@@ -0,0 +1,252 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::Row;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::database::schema::types::{OrderSide, OrderStatus};
+use crate::handlers::websocket::broadcaster::broadcast_p2p_order_update;
+use crate::services::order_events::{self, NewOrderEvent, OrderEventType};
+use super::MarketClearingService;
+
+/// An order transitioned to `expired` by the sweeper
+#[derive(Debug, Clone)]
+pub struct ExpiredOrder {
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub side: OrderSide,
+    pub epoch_id: Option<Uuid>,
+    /// Unfilled energy released back to the owner
+    pub unfilled: Decimal,
+    /// Collateral released: currency for buys, energy for sells
+    pub released: Decimal,
+}
+
+/// Whether an order is still open and its expiry has passed
+pub fn is_expirable(status: &OrderStatus, expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    matches!(status, OrderStatus::Pending | OrderStatus::Active | OrderStatus::PartiallyFilled)
+        && expires_at.is_some_and(|at| at < now)
+}
+
+/// Energy left unfilled, never negative for overfilled orders
+pub fn unfilled_amount(original: Decimal, filled: Decimal) -> Decimal {
+    (original - filled).max(Decimal::ZERO)
+}
+
+/// Collateral released for the unfilled portion: currency at the limit price
+/// for buys, energy for sells, and nothing once the escrow is no longer locked
+pub fn escrow_release(side: OrderSide, unfilled: Decimal, price: Decimal, escrow_locked: bool) -> Decimal {
+    if !escrow_locked || unfilled <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    match side {
+        OrderSide::Buy => unfilled * price,
+        OrderSide::Sell => unfilled,
+    }
+}
+
+impl MarketClearingService {
+    /// Open orders whose expiry has passed, oldest first
+    pub async fn find_expired_orders(&self, limit: i64) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT id
+            FROM trading_orders
+            WHERE status IN ('pending', 'active', 'partially_filled')
+              AND expires_at < NOW()
+            ORDER BY expires_at ASC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Expire one order: mark it expired, release the escrow held for the
+    /// unfilled portion, count it against its epoch and notify the owner.
+    ///
+    /// Returns `None` when the order is no longer open (filled or cancelled
+    /// since it was selected), so collateral is never released twice.
+    pub async fn expire_order(&self, order_id: Uuid) -> Result<Option<ExpiredOrder>> {
+        let mut tx = self.db.begin().await?;
+
+        let Some(row) = sqlx::query(
+            r#"
+            SELECT user_id, side, status, expires_at, energy_amount,
+                   COALESCE(filled_amount, 0) AS filled_amount,
+                   COALESCE(price_per_kwh, 0) AS price_per_kwh, epoch_id
+            FROM trading_orders
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(order_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let status: OrderStatus = row.get("status");
+        if !is_expirable(&status, row.get("expires_at"), Utc::now()) {
+            return Ok(None);
+        }
+
+        sqlx::query("UPDATE trading_orders SET status = 'expired'::order_status, updated_at = NOW() WHERE id = $1")
+            .bind(order_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let user_id: Uuid = row.get("user_id");
+        let side: OrderSide = row.get("side");
+        let original: Decimal = row.get("energy_amount");
+        let filled: Decimal = row.get("filled_amount");
+        let price: Decimal = row.get("price_per_kwh");
+        let epoch_id: Option<Uuid> = row.get("epoch_id");
+        let unfilled = unfilled_amount(original, filled);
+
+        // Only release collateral that is still held in escrow
+        let escrow_locked = sqlx::query(
+            r#"
+            UPDATE escrow_records
+            SET status = 'released', description = $1, updated_at = NOW()
+            WHERE order_id = $2 AND status = 'locked'
+            "#,
+        )
+        .bind(format!("Order expired - released unfilled portion: {}", unfilled))
+        .bind(order_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+
+        let released = escrow_release(side, unfilled, price, escrow_locked);
+        if released > Decimal::ZERO {
+            let unlock = match side {
+                OrderSide::Buy => {
+                    "UPDATE users SET balance = balance + $1, locked_amount = locked_amount - $1 WHERE id = $2"
+                }
+                OrderSide::Sell => "UPDATE users SET locked_energy = locked_energy - $1 WHERE id = $2",
+            };
+            sqlx::query(unlock).bind(released).bind(user_id).execute(&mut *tx).await?;
+        }
+
+        if let Some(epoch_id) = epoch_id {
+            sqlx::query(
+                r#"
+                UPDATE market_epochs
+                SET expired_orders = expired_orders + 1,
+                    expired_volume = expired_volume + $1
+                WHERE id = $2
+                "#,
+            )
+            .bind(unfilled)
+            .bind(epoch_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        let side_str = match side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        };
+        info!(
+            "🕒 Expired {} order {} for user {} (unfilled: {} kWh, released: {})",
+            side_str, order_id, user_id, unfilled, released
+        );
+
+        let _ = broadcast_p2p_order_update(
+            order_id,
+            user_id,
+            side_str.to_string(),
+            "expired".to_string(),
+            original.to_string(),
+            filled.to_string(),
+            "0".to_string(),
+            price.to_string(),
+        )
+        .await;
+
+        order_events::record(
+            &self.db,
+            NewOrderEvent::new(order_id, user_id, OrderEventType::Expired)
+                .with_quantities(filled, Decimal::ZERO)
+                .with_price(price)
+                .with_reason(format!("Order expired with {} kWh unfilled", unfilled)),
+        )
+        .await;
+
+        if released > Decimal::ZERO {
+            let asset_type = match side {
+                OrderSide::Buy => "currency",
+                OrderSide::Sell => "energy",
+            };
+            if let Err(e) = self.execute_escrow_refund(user_id, released, asset_type).await {
+                error!("Failed to execute on-chain refund for expired order {}: {}", order_id, e);
+            }
+        }
+
+        Ok(Some(ExpiredOrder {
+            order_id,
+            user_id,
+            side,
+            epoch_id,
+            unfilled,
+            released,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_is_expirable() {
+        let now = Utc::now();
+        let past = Some(now - Duration::minutes(1));
+        let future = Some(now + Duration::minutes(1));
+
+        assert!(is_expirable(&OrderStatus::Pending, past, now));
+        assert!(is_expirable(&OrderStatus::Active, past, now));
+        assert!(is_expirable(&OrderStatus::PartiallyFilled, past, now));
+
+        // Not yet due, or without an expiry
+        assert!(!is_expirable(&OrderStatus::Active, future, now));
+        assert!(!is_expirable(&OrderStatus::Active, Some(now), now));
+        assert!(!is_expirable(&OrderStatus::Active, None, now));
+
+        // Closed since it was selected
+        for status in [OrderStatus::Filled, OrderStatus::Settled, OrderStatus::Cancelled, OrderStatus::Expired] {
+            assert!(!is_expirable(&status, past, now), "{}", status);
+        }
+    }
+
+    #[test]
+    fn test_unfilled_amount() {
+        assert_eq!(unfilled_amount(Decimal::new(10, 0), Decimal::ZERO), Decimal::new(10, 0));
+        assert_eq!(unfilled_amount(Decimal::new(10, 0), Decimal::new(45, 1)), Decimal::new(55, 1));
+        assert_eq!(unfilled_amount(Decimal::new(10, 0), Decimal::new(10, 0)), Decimal::ZERO);
+        assert_eq!(unfilled_amount(Decimal::new(10, 0), Decimal::new(102, 1)), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_escrow_release() {
+        // Buys get the currency for the unfilled energy at the limit price
+        assert_eq!(escrow_release(OrderSide::Buy, Decimal::new(55, 1), Decimal::new(4, 0), true), Decimal::new(220, 1));
+        // Sells get the unfilled energy back
+        assert_eq!(escrow_release(OrderSide::Sell, Decimal::new(55, 1), Decimal::new(4, 0), true), Decimal::new(55, 1));
+
+        // Escrow already released or nothing left unfilled
+        assert_eq!(escrow_release(OrderSide::Buy, Decimal::new(55, 1), Decimal::new(4, 0), false), Decimal::ZERO);
+        assert_eq!(escrow_release(OrderSide::Sell, Decimal::new(55, 1), Decimal::new(4, 0), false), Decimal::ZERO);
+        assert_eq!(escrow_release(OrderSide::Buy, Decimal::ZERO, Decimal::new(4, 0), true), Decimal::ZERO);
+    }
+}
@@ -5,198 +5,262 @@ use rust_decimal::Decimal;
 
 use sqlx::Row;
 use uuid::Uuid;
+use std::collections::HashSet;
 use std::str::FromStr;
-use tracing::{error, info};
+use std::time::Instant;
+use tracing::{error, info, warn};
 use reqwest::Client;
 
-use crate::database::schema::types::OrderStatus;
 use crate::error::ApiError;
-use crate::handlers::websocket::broadcaster::broadcast_p2p_order_update;
+use crate::middleware::metrics::track_matching_epoch;
+use crate::services::market_session;
+use crate::services::settlement::trade_priority;
+use crate::services::trading_halts::{blocking_halt, HaltedAction};
+use crate::services::order_events::NewOrderEvent;
+use crate::utils::decimal::round_to_token;
 use super::MarketClearingService;
-use super::types::{OrderMatch, Settlement};
+use super::batch::{MatchWriteBatch, OrderUpdate};
+use super::performance::{MatchingStage, MatchingTimings};
+use super::shadow::run_shadow;
+use super::types::{OrderBookEntry, OrderMatch, Settlement};
+
+/// Add a matched order's new state to the batch; returns whether it is
+/// fully filled and leaves the book
+fn record_fill(batch: &mut MatchWriteBatch, order: &OrderBookEntry, side: &'static str, amount: Decimal) -> bool {
+    let fully_filled = order.energy_amount <= Decimal::ZERO;
+    let update = OrderUpdate {
+        order_id: order.order_id,
+        user_id: order.user_id,
+        side,
+        status: if fully_filled { "filled" } else { "partially_filled" },
+        original_amount: order.original_amount,
+        filled_amount: order.original_amount - order.energy_amount.max(Decimal::ZERO),
+        remaining_amount: order.energy_amount.max(Decimal::ZERO),
+        price_per_kwh: order.price_per_kwh,
+    };
+
+    if fully_filled {
+        info!("{} order {} is fully filled", side, order.order_id);
+        batch.filled(update);
+    } else {
+        info!("{} order {} is partially filled, {} remaining", side, order.order_id, order.energy_amount);
+        batch.partially_filled(update, amount);
+    }
+    fully_filled
+}
+
+/// Match one market's book in price-time priority, reducing the orders'
+/// remaining amounts in place and dropping the ones fully filled. Writes
+/// go to `batch`; a dry run passes `None` and records nothing.
+pub(super) fn match_book(
+    epoch_id: Uuid,
+    buy_orders: &mut Vec<OrderBookEntry>,
+    sell_orders: &mut Vec<OrderBookEntry>,
+    mut batch: Option<&mut MatchWriteBatch>,
+) -> Vec<OrderMatch> {
+    let mut matches = Vec::new();
+    while let Some(buy_order) = buy_orders.first_mut() {
+        let Some(sell_order) = sell_orders.first_mut() else {
+            break;
+        };
+
+        // No more matches possible (best buy price < best sell price)
+        if buy_order.price_per_kwh < sell_order.price_per_kwh {
+            break;
+        }
+
+        // Calculate clearing price as midpoint of bid-ask spread
+        // This ensures fair pricing for both parties
+        let match_price = (buy_order.price_per_kwh + sell_order.price_per_kwh) / Decimal::from(2);
+
+        // Calculate match amount (minimum of remaining amounts)
+        let match_amount = buy_order.energy_amount.min(sell_order.energy_amount);
+        if match_amount <= Decimal::ZERO {
+            // Nothing left on one side; drop it rather than spin
+            if buy_order.energy_amount <= Decimal::ZERO {
+                buy_orders.remove(0);
+            } else {
+                sell_orders.remove(0);
+            }
+            continue;
+        }
+
+        let order_match = OrderMatch {
+            id: Uuid::new_v4(),
+            epoch_id,
+            buy_order_id: buy_order.order_id,
+            sell_order_id: sell_order.order_id,
+            matched_amount: match_amount,
+            match_price,
+            match_time: Utc::now(),
+            status: "pending".to_string(),
+        };
+
+        // Update order amounts
+        buy_order.energy_amount -= match_amount;
+        sell_order.energy_amount -= match_amount;
+
+        let (buy_filled, sell_filled) = match batch.as_deref_mut() {
+            Some(batch) => {
+                info!(
+                    "🤝 MATCHED: BuyOrder({}) vs SellOrder({}) | Amount: {} kWh | Price: {} GRIDX | MatchID: {}",
+                    order_match.buy_order_id,
+                    order_match.sell_order_id,
+                    order_match.matched_amount,
+                    order_match.match_price,
+                    order_match.id
+                );
+                batch.push_match(order_match.clone());
+
+                // Lifecycle events for both sides of the fill
+                for (order_id, user_id, original, remaining) in [
+                    (buy_order.order_id, buy_order.user_id, buy_order.original_amount, buy_order.energy_amount),
+                    (sell_order.order_id, sell_order.user_id, sell_order.original_amount, sell_order.energy_amount),
+                ] {
+                    batch.push_event(NewOrderEvent::fill(
+                        order_id,
+                        user_id,
+                        match_amount,
+                        original - remaining,
+                        original,
+                        match_price,
+                    ));
+                }
+
+                (
+                    record_fill(batch, buy_order, "buy", match_amount),
+                    record_fill(batch, sell_order, "sell", match_amount),
+                )
+            }
+            None => (
+                buy_order.energy_amount <= Decimal::ZERO,
+                sell_order.energy_amount <= Decimal::ZERO,
+            ),
+        };
+        matches.push(order_match);
+
+        // Remove fully filled orders
+        if buy_filled {
+            buy_orders.remove(0);
+        }
+        if sell_filled {
+            sell_orders.remove(0);
+        }
+    }
+    matches
+}
+
+/// What makes a settlement unique: one per matched order pair in an epoch
+pub(super) type SettlementKey = (Uuid, Uuid, Uuid);
+
+pub(super) fn settlement_key(order_match: &OrderMatch) -> SettlementKey {
+    (order_match.epoch_id, order_match.buy_order_id, order_match.sell_order_id)
+}
+
+/// Matches that still need a settlement, skipping keys already settled and
+/// repeats of a key within the run. A clearing run that follows another of
+/// the same epoch only settles what the other has not; the unique settlement
+/// key is the last guard against a duplicate.
+pub(super) fn unsettled<'a>(matches: &'a [OrderMatch], settled: &HashSet<SettlementKey>) -> Vec<&'a OrderMatch> {
+    let mut seen = HashSet::new();
+    matches
+        .iter()
+        .filter(|m| {
+            let key = settlement_key(m);
+            !settled.contains(&key) && seen.insert(key)
+        })
+        .collect()
+}
 
 impl MarketClearingService {
-    /// Run order matching algorithm for an epoch
+    /// Run order matching algorithm for an epoch.
+    ///
+    /// Matches, fills, order events and epoch statistics are written in one
+    /// transaction with bulk statements once every market is matched, so a
+    /// failed run leaves nothing behind and the epoch can simply be retried.
+    /// The transaction holds the epoch row from before the book is read, so
+    /// overlapping runs of an epoch are serialized, and fills that would take
+    /// an order past its amount fail the run. Fills are announced only after
+    /// the transaction commits.
     pub async fn run_order_matching(&self, epoch_id: Uuid) -> Result<Vec<OrderMatch>> {
         info!("Starting order matching for epoch: {}", epoch_id);
 
-        // Get current order book
-        let (mut buy_orders, mut sell_orders) = self.get_order_book(epoch_id).await?;
-
-        if buy_orders.is_empty() || sell_orders.is_empty() {
-            info!("No orders to match in epoch: {}", epoch_id);
+        let session = market_session::session_at(&self.config.market_session, Utc::now());
+        if !session.state.allows_clearing() {
+            info!("Market session {:?}, deferring clearing of epoch {}", session.state, epoch_id);
             return Ok(vec![]);
         }
 
+        // Clearing runs of an epoch (manual and scheduled triggers) take
+        // turns: a second run reads the book only once the first committed
+        let mut tx = self.db.begin().await?;
+        sqlx::query("SELECT id FROM market_epochs WHERE id = $1 FOR UPDATE")
+            .bind(epoch_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let started = Instant::now();
+        let mut timings = MatchingTimings::default();
+        let mut batch = MatchWriteBatch::default();
         let mut matches = Vec::new();
         let mut total_volume = Decimal::ZERO;
         let mut total_match_count = 0;
+        let shadow = &self.config.matching_shadow;
+        let mut shadow_comparisons = Vec::new();
+        let halts = self.trading_halts.active().await?;
+
+        // Each active spot market clears its own book; orders never cross markets
+        for market in self.markets.active_spot_markets().await? {
+            let fetch_started = Instant::now();
+            let (mut buy_orders, mut sell_orders) = self.get_order_book(epoch_id, market.id).await?;
+            // Halted markets and zones keep their resting orders out of the run
+            let unhalted =
+                |o: &OrderBookEntry| blocking_halt(&halts, market.id, o.zone_id, HaltedAction::Match).is_none();
+            buy_orders.retain(unhalted);
+            sell_orders.retain(unhalted);
+            timings.add(MatchingStage::Fetch, fetch_started.elapsed());
+            timings.markets += 1;
+            timings.orders += (buy_orders.len() + sell_orders.len()) as i32;
+
+            if buy_orders.is_empty() || sell_orders.is_empty() {
+                info!("No orders to match in market {} for epoch: {}", market.code, epoch_id);
+                continue;
+            }
 
-        // Order matching algorithm: price-time priority
-        while let Some(buy_order) = buy_orders.first_mut() {
-            if let Some(sell_order) = sell_orders.first_mut() {
-                // Check if orders can be matched (bid >= ask)
-                if buy_order.price_per_kwh >= sell_order.price_per_kwh {
-                    // Calculate clearing price as midpoint of bid-ask spread
-                    // This ensures fair pricing for both parties
-                    let match_price = (buy_order.price_per_kwh + sell_order.price_per_kwh) 
-                        / Decimal::from(2);
-
-                    // Calculate match amount (minimum of remaining amounts)
-                    let match_amount = buy_order
-                        .energy_amount
-                        .clone()
-                        .min(sell_order.energy_amount.clone());
-
-                    if match_amount > Decimal::ZERO {
-                        let match_amount_clone = match_amount.clone();
-                        let match_price_clone = match_price.clone();
-
-                        // Create order match
-                        let order_match = OrderMatch {
-                            id: Uuid::new_v4(),
-                            epoch_id,
-                            buy_order_id: buy_order.order_id,
-                            sell_order_id: sell_order.order_id,
-                            matched_amount: match_amount_clone.clone(),
-                            match_price: match_price_clone.clone(),
-                            match_time: Utc::now(),
-                            status: "pending".to_string(),
-                        };
-
-                        // Save match to database
-                        self.save_order_match(&order_match).await?;
-                        matches.push(order_match.clone());
-
-                        info!(
-                            "🤝 MATCHED: BuyOrder({}) vs SellOrder({}) | Amount: {} kWh | Price: {} GRIDX | MatchID: {}",
-                            order_match.buy_order_id,
-                            order_match.sell_order_id,
-                            order_match.matched_amount,
-                            order_match.match_price,
-                            order_match.id
-                        );
-
-                        // Update order amounts
-                        buy_order.energy_amount -= match_amount_clone.clone();
-                        sell_order.energy_amount -= match_amount_clone.clone();
-
-                        // Update totals
-                        total_volume += match_amount_clone.clone();
-                        total_match_count += 1;
-
-                        // Remove fully filled orders
-                        info!(
-                            "Buy order {} remaining amount: {}",
-                            buy_order.order_id, buy_order.energy_amount
-                        );
-                        if buy_order.energy_amount <= Decimal::ZERO {
-                            info!(
-                                "Buy order {} is fully filled, updating status",
-                                buy_order.order_id
-                            );
-                            self.update_order_status(buy_order.order_id, OrderStatus::Filled)
-                                .await?;
-                            
-                            // Broadcast fully filled status
-                            let _ = broadcast_p2p_order_update(
-                                buy_order.order_id,
-                                buy_order.user_id,
-                                "buy".to_string(),
-                                "filled".to_string(),
-                                buy_order.original_amount.to_string(),
-                                buy_order.original_amount.to_string(),
-                                "0".to_string(),
-                                buy_order.price_per_kwh.to_string(),
-                            ).await;
-                            
-                            buy_orders.remove(0);
-                        } else {
-                            info!(
-                                "Buy order {} is partially filled, updating amount",
-                                buy_order.order_id
-                            );
-                            self.update_order_filled_amount(
-                                buy_order.order_id,
-                                match_amount_clone.clone(),
-                            )
-                            .await?;
-                            
-                            // Broadcast partial fill status
-                            let filled = buy_order.original_amount - buy_order.energy_amount;
-                            let _ = broadcast_p2p_order_update(
-                                buy_order.order_id,
-                                buy_order.user_id,
-                                "buy".to_string(),
-                                "partially_filled".to_string(),
-                                buy_order.original_amount.to_string(),
-                                filled.to_string(),
-                                buy_order.energy_amount.to_string(),
-                                buy_order.price_per_kwh.to_string(),
-                            ).await;
-                        }
-
-                        info!(
-                            "Sell order {} remaining amount: {}",
-                            sell_order.order_id, sell_order.energy_amount
-                        );
-                        if sell_order.energy_amount <= Decimal::ZERO {
-                            info!(
-                                "Sell order {} is fully filled, updating status",
-                                sell_order.order_id
-                            );
-                            self.update_order_status(sell_order.order_id, OrderStatus::Filled)
-                                .await?;
-                            
-                            // Broadcast fully filled status
-                            let _ = broadcast_p2p_order_update(
-                                sell_order.order_id,
-                                sell_order.user_id,
-                                "sell".to_string(),
-                                "filled".to_string(),
-                                sell_order.original_amount.to_string(),
-                                sell_order.original_amount.to_string(),
-                                "0".to_string(),
-                                sell_order.price_per_kwh.to_string(),
-                            ).await;
-                            
-                            sell_orders.remove(0);
-                        } else {
-                            info!(
-                                "Sell order {} is partially filled, updating amount",
-                                sell_order.order_id
-                            );
-                            self.update_order_filled_amount(
-                                sell_order.order_id,
-                                match_amount_clone.clone(),
-                            )
-                            .await?;
-                            
-                            // Broadcast partial fill status
-                            let filled = sell_order.original_amount - sell_order.energy_amount;
-                            let _ = broadcast_p2p_order_update(
-                                sell_order.order_id,
-                                sell_order.user_id,
-                                "sell".to_string(),
-                                "partially_filled".to_string(),
-                                sell_order.original_amount.to_string(),
-                                filled.to_string(),
-                                sell_order.energy_amount.to_string(),
-                                sell_order.price_per_kwh.to_string(),
-                            ).await;
-                        }
-                    }
-                } else {
-                    // No more matches possible (best buy price < best sell price)
-                    break;
+            // The shadow matcher sees the book as the live one found it
+            let shadow_book = shadow
+                .enabled
+                .then(|| buy_orders.iter().chain(&sell_orders).cloned().collect::<Vec<_>>());
+
+            // Order matching algorithm: price-time priority
+            let loop_started = Instant::now();
+            let awaited_before = timings.awaited();
+            let market_matches = match_book(epoch_id, &mut buy_orders, &mut sell_orders, Some(&mut batch));
+            timings.add_loop(loop_started.elapsed(), awaited_before);
+
+            // Remainders too small to trade leave the book
+            for order in buy_orders.iter().chain(&sell_orders) {
+                if market.is_dust(order.energy_amount) {
+                    batch.dust(order);
                 }
-            } else {
-                break;
+            }
+
+            if let Some(book) = shadow_book {
+                shadow_comparisons.push(run_shadow(shadow.algorithm, epoch_id, market.id, &book, &market_matches));
+            }
+
+            for order_match in market_matches {
+                total_volume += order_match.matched_amount;
+                total_match_count += 1;
+                matches.push(order_match);
             }
         }
 
-        // Update epoch statistics
-        self.update_epoch_statistics(epoch_id, total_volume.clone(), total_match_count)
+        // Write the whole run, then the epoch statistics, atomically
+        let write_started = Instant::now();
+        let event_timestamps = batch.write(&mut tx).await?;
+
+        self.update_epoch_statistics(&mut tx, epoch_id, total_volume, total_match_count)
             .await?;
 
         // Calculate and set clearing price (average of match prices)
@@ -205,21 +269,42 @@ impl MarketClearingService {
                 .iter()
                 .map(|m| m.matched_amount * m.match_price)
                 .fold(Decimal::ZERO, |acc, val| acc + val);
-            let clearing_price = total_match_value / total_volume.clone();
+            let clearing_price = total_match_value / total_volume;
 
-            sqlx::query!(
-                "UPDATE market_epochs SET clearing_price = $1 WHERE id = $2",
-                clearing_price,
-                epoch_id
-            )
-            .execute(&self.db)
-            .await?;
+            sqlx::query("UPDATE market_epochs SET clearing_price = $1 WHERE id = $2")
+                .bind(clearing_price)
+                .bind(epoch_id)
+                .execute(&mut *tx)
+                .await?;
+
+            self.record_clearing_price(&mut tx, epoch_id, clearing_price, total_volume)
+                .await?;
         }
 
-        // Create settlements for all matches
-        for order_match in &matches {
+        tx.commit().await?;
+        timings.add(MatchingStage::DbWrite, write_started.elapsed());
+
+        if !batch.is_empty() {
+            let notify_started = Instant::now();
+            batch.publish(event_timestamps).await;
+            timings.add(MatchingStage::Notify, notify_started.elapsed());
+        }
+
+        if !shadow_comparisons.is_empty() {
+            if let Err(e) = self.record_shadow_comparisons(&shadow_comparisons).await {
+                warn!("Failed to record shadow matching comparisons of epoch {}: {}", epoch_id, e);
+            }
+        }
+
+        // Create settlements for all matches not already settled
+        let settlement_started = Instant::now();
+        let settled = self.settled_keys(epoch_id).await?;
+        for order_match in unsettled(&matches, &settled) {
             match self.create_settlement(order_match).await {
-                Ok(settlement) => {
+                Ok(None) => {
+                    info!("Match {} was already settled by another clearing run", order_match.id);
+                }
+                Ok(Some(settlement)) => {
                     // Broadcast trade executed event
                     self.websocket_service.broadcast_trade_executed(
                         settlement.id.to_string(),
@@ -241,6 +326,26 @@ impl MarketClearingService {
                 }
             }
         }
+        timings.add(MatchingStage::Settlement, settlement_started.elapsed());
+
+        timings.matches = matches.len() as i32;
+        timings.export();
+        let total = started.elapsed();
+        track_matching_epoch(total.as_secs_f64() * 1000.0);
+        if let Err(e) = self.record_matching_performance(epoch_id, &timings, total).await {
+            warn!("Failed to record matching performance of epoch {}: {}", epoch_id, e);
+        }
+
+        info!(
+            "⏱️ Epoch {} matched in {:?}: fetch={:?} matching={:?} db_write={:?} notify={:?} settlement={:?}",
+            epoch_id,
+            total,
+            timings.fetch,
+            timings.matching,
+            timings.db_write,
+            timings.notify,
+            timings.settlement
+        );
 
         info!(
             "🏆 MATCHING COMPLETE [Epoch {}]: matched_count={}, total_volume={} kWh, clearing_price={} GRIDX",
@@ -253,32 +358,27 @@ impl MarketClearingService {
         Ok(matches)
     }
 
-    /// Save order match to database
-    pub(super) async fn save_order_match(&self, order_match: &OrderMatch) -> Result<()> {
-        sqlx::query!(
+    /// Settlement keys already written for an epoch
+    async fn settled_keys(&self, epoch_id: Uuid) -> Result<HashSet<SettlementKey>> {
+        let keys: Vec<(Uuid, Uuid)> = sqlx::query_as(
             r#"
-            INSERT INTO order_matches (
-                id, epoch_id, buy_order_id, sell_order_id, 
-                matched_amount, match_price, match_time, status
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            SELECT buy_order_id, sell_order_id FROM settlements
+            WHERE epoch_id = $1 AND buy_order_id IS NOT NULL AND sell_order_id IS NOT NULL
             "#,
-            order_match.id,
-            order_match.epoch_id,
-            order_match.buy_order_id,
-            order_match.sell_order_id,
-            order_match.matched_amount,
-            order_match.match_price,
-            order_match.match_time,
-            order_match.status
         )
-        .execute(&self.db)
+        .bind(epoch_id)
+        .fetch_all(&self.db)
         .await?;
 
-        Ok(())
+        Ok(keys.into_iter().map(|(buy, sell)| (epoch_id, buy, sell)).collect())
     }
 
-    /// Create settlement for an order match
-    pub(super) async fn create_settlement(&self, order_match: &OrderMatch) -> Result<Settlement> {
+    /// Create settlement for an order match.
+    ///
+    /// The settlement row is claimed on (epoch, buy order, sell order) before
+    /// any escrow is released, so a concurrent clearing run that got there
+    /// first wins: the match is linked to its settlement and `None` returned.
+    pub(super) async fn create_settlement(&self, order_match: &OrderMatch) -> Result<Option<Settlement>> {
         // Get buyer and seller information from orders
         let buy_order = sqlx::query(
             "SELECT user_id, zone_id, session_token FROM trading_orders WHERE id = $1",
@@ -323,7 +423,8 @@ impl MarketClearingService {
                         wheeling_charge = Decimal::from_f64(cost_data["wheeling_charge"].as_f64().unwrap_or(0.0)).unwrap_or(Decimal::ZERO);
                         loss_factor = Decimal::from_f64(cost_data["loss_factor"].as_f64().unwrap_or(0.0)).unwrap_or(Decimal::ZERO);
                         loss_cost = Decimal::from_f64(cost_data["loss_cost"].as_f64().unwrap_or(0.0)).unwrap_or(Decimal::ZERO);
-                        effective_energy = Decimal::from_f64(cost_data["effective_energy"].as_f64().unwrap_or(0.0)).unwrap_or(order_match.matched_amount);
+                        // Rounded to the token's decimals so the settlement transfers it exactly
+                        effective_energy = round_to_token(Decimal::from_f64(cost_data["effective_energy"].as_f64().unwrap_or(0.0)).unwrap_or(order_match.matched_amount));
                         info!("P2P Costs: wheeling={}, loss_factor={}, loss_cost={}, effective_energy={}", 
                             wheeling_charge, loss_factor, loss_cost, effective_energy);
                     }
@@ -354,43 +455,6 @@ impl MarketClearingService {
 
 
 
-        // =================================================================
-        // NEW: Execute On-Chain Settlement (Escrow Release)
-        // =================================================================
-        // 1. Release Net Payment to Seller (Currency)
-        // Note: Fees and Wheeling Charges remain in Authority Escrow (as revenue)
-        // 4. Trigger Blockchain Settlement (Atomic Swap)
-        // In a real implementation, this would build a single atomic transaction
-        // For this demo, we'll do two transfers (USDC -> Seller, Energy -> Buyer)
-        // NOTE: This is not truly atomic but sufficient for the MVP demo.
-
-        // Transfer USDC from Escrow -> Seller
-        match self
-            .execute_escrow_release(
-                sell_order.get("user_id"), 
-                net_amount, 
-                "currency"
-            )
-            .await
-        {
-            Ok(_sig) => info!("Settlement Payment Release triggered: {} -> Seller {}", net_amount, sell_order.get::<Uuid, _>("user_id")),
-            Err(e) => error!("Failed to release payment escrow: {}", e),
-        }
-
-        // Transfer Energy from Escrow -> Buyer
-        match self
-            .execute_escrow_release(
-                buy_order.get("user_id"), 
-                effective_energy, 
-                "energy"
-            )
-            .await
-        {
-            Ok(_sig) => info!("Settlement Energy Release triggered: {} -> Buyer {}", effective_energy, buy_order.get::<Uuid, _>("user_id")),
-            Err(e) => error!("Failed to release energy escrow for {}: {}", buy_order.get::<Uuid, _>("user_id"), e),
-        }
-
-
         let settlement = Settlement {
             id: Uuid::new_v4(),
             epoch_id: order_match.epoch_id,
@@ -412,21 +476,35 @@ impl MarketClearingService {
             seller_session_token: sell_order.get("session_token"),
         };
 
-        // Save settlement
-        sqlx::query(
+        let priority = trade_priority(
+            &self.db,
+            order_match.buy_order_id,
+            settlement.epoch_id,
+            (settlement.buyer_id, settlement.seller_id),
+            settlement.total_amount,
+        )
+        .await?;
+
+        // Save settlement, unless another clearing run already has
+        let inserted: Option<Uuid> = sqlx::query_scalar(
             r#"
             INSERT INTO settlements (
-                id, epoch_id, buyer_id, seller_id, energy_amount, 
+                id, epoch_id, buyer_id, seller_id, buy_order_id, sell_order_id, energy_amount,
                 price_per_kwh, total_amount, fee_amount, wheeling_charge,
                 loss_factor, loss_cost, effective_energy, buyer_zone_id,
-                seller_zone_id, net_amount, status, buyer_session_token, seller_session_token
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+                seller_zone_id, net_amount, status, buyer_session_token, seller_session_token,
+                priority
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
+            ON CONFLICT (epoch_id, buy_order_id, sell_order_id) DO NOTHING
+            RETURNING id
             "#,
         )
         .bind(&settlement.id)
         .bind(&settlement.epoch_id)
         .bind(&settlement.buyer_id)
         .bind(&settlement.seller_id)
+        .bind(order_match.buy_order_id)
+        .bind(order_match.sell_order_id)
         .bind(&settlement.energy_amount)
         .bind(&settlement.price_per_kwh)
         .bind(&settlement.total_amount)
@@ -441,9 +519,84 @@ impl MarketClearingService {
         .bind(&settlement.status)
         .bind(&settlement.buyer_session_token)
         .bind(&settlement.seller_session_token)
-        .execute(&self.db)
+        .bind(priority.as_str())
+        .fetch_optional(&self.db)
         .await?;
 
+        if inserted.is_none() {
+            sqlx::query(
+                r#"
+                UPDATE order_matches m SET settlement_id = s.id
+                FROM settlements s
+                WHERE m.id = $1
+                  AND s.epoch_id = $2 AND s.buy_order_id = $3 AND s.sell_order_id = $4
+                "#,
+            )
+            .bind(order_match.id)
+            .bind(order_match.epoch_id)
+            .bind(order_match.buy_order_id)
+            .bind(order_match.sell_order_id)
+            .execute(&self.db)
+            .await?;
+            return Ok(None);
+        }
+
+        // =================================================================
+        // NEW: Execute On-Chain Settlement (Escrow Release)
+        // =================================================================
+        // 1. Release Net Payment to Seller (Currency)
+        // Note: Fees and Wheeling Charges remain in Authority Escrow (as revenue)
+        // 4. Trigger Blockchain Settlement (Atomic Swap)
+        // In a real implementation, this would build a single atomic transaction
+        // For this demo, we'll do two transfers (USDC -> Seller, Energy -> Buyer)
+        // NOTE: This is not truly atomic but sufficient for the MVP demo.
+        //
+        // An epoch cannot be rolled back once escrow may have moved, so the
+        // attempt is recorded before either transfer is sent.
+        let real_chain = self.config.tokenization.enable_real_blockchain;
+        if real_chain {
+            sqlx::query("UPDATE settlements SET escrow_release_started_at = NOW() WHERE id = $1")
+                .bind(settlement.id)
+                .execute(&self.db)
+                .await?;
+        }
+
+        // Transfer USDC from Escrow -> Seller
+        match self
+            .execute_escrow_release(
+                sell_order.get("user_id"), 
+                net_amount, 
+                "currency"
+            )
+            .await
+        {
+            Ok(sig) => {
+                info!("Settlement Payment Release triggered: {} -> Seller {}", net_amount, sell_order.get::<Uuid, _>("user_id"));
+                if real_chain {
+                    self.record_escrow_release(settlement.id, &sig).await;
+                }
+            }
+            Err(e) => error!("Failed to release payment escrow: {}", e),
+        }
+
+        // Transfer Energy from Escrow -> Buyer
+        match self
+            .execute_escrow_release(
+                buy_order.get("user_id"), 
+                effective_energy, 
+                "energy"
+            )
+            .await
+        {
+            Ok(sig) => {
+                info!("Settlement Energy Release triggered: {} -> Buyer {}", effective_energy, buy_order.get::<Uuid, _>("user_id"));
+                if real_chain {
+                    self.record_escrow_release(settlement.id, &sig).await;
+                }
+            }
+            Err(e) => error!("Failed to release energy escrow for {}: {}", buy_order.get::<Uuid, _>("user_id"), e),
+        }
+
         // Update order match with settlement ID
         sqlx::query(
             "UPDATE order_matches SET settlement_id = $1 WHERE id = $2",
@@ -453,6 +606,8 @@ impl MarketClearingService {
         .execute(&self.db)
         .await?;
 
+        self.fx_rates.record_settlement_value(settlement.id).await;
+
         // =================================================================
         // NEW: Automated REC Issuance
         // =================================================================
@@ -490,18 +645,159 @@ impl MarketClearingService {
                     })),
                 };
 
-                // Use a system/platform authority as issuer for automated issuance
-                // For now, using seller wallet as placeholder issuer if needed, 
-                // but ErcService::issue_certificate takes an issuer_wallet string.
-                let issuer_wallet = "PlatformAuthority"; 
+                // Automated issuance is signed by the registry's default issuer
+                let issuer_wallet = match erc_service.default_issuer_key().await {
+                    Ok(Some(key)) => key,
+                    Ok(None) => {
+                        warn!("No default ERC issuer registered, skipping REC for settlement {}", settlement_id);
+                        return;
+                    }
+                    Err(e) => {
+                        error!("❌ Failed to load default ERC issuer: {}", e);
+                        return;
+                    }
+                };
 
-                match erc_service.issue_certificate(seller_id, issuer_wallet, cert_request, Some(settlement_id)).await {
+                match erc_service.issue_certificate(seller_id, &issuer_wallet, cert_request, Some(settlement_id)).await {
                     Ok(cert) => info!("✅ Automated REC issued: {} for settlement {}", cert.certificate_id, settlement_id),
                     Err(e) => error!("❌ Failed to issue automated REC: {}", e),
                 }
             });
         }
 
-        Ok(settlement)
+        Ok(Some(settlement))
+    }
+
+    /// Record an executed escrow release. The transfer has already happened,
+    /// so a failure here is only logged.
+    async fn record_escrow_release(&self, settlement_id: Uuid, signature: &str) {
+        let result = sqlx::query(
+            "UPDATE settlements SET escrow_release_signatures = array_append(escrow_release_signatures, $2) \
+             WHERE id = $1",
+        )
+        .bind(settlement_id)
+        .bind(signature)
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            error!("Failed to record escrow release {} of settlement {}: {}", signature, settlement_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order_match(epoch_id: Uuid, buy_order_id: Uuid, sell_order_id: Uuid) -> OrderMatch {
+        OrderMatch {
+            id: Uuid::new_v4(),
+            epoch_id,
+            buy_order_id,
+            sell_order_id,
+            matched_amount: Decimal::from(5),
+            match_price: Decimal::ONE,
+            match_time: Utc::now(),
+            status: "pending".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_unsettled_skips_pairs_settled_by_an_earlier_run() {
+        let epoch_id = Uuid::new_v4();
+        let (buy_a, buy_b, sell) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        // The scheduled run settles its matches first
+        let scheduled = vec![order_match(epoch_id, buy_a, sell)];
+        let settled: HashSet<SettlementKey> = unsettled(&scheduled, &HashSet::new())
+            .into_iter()
+            .map(settlement_key)
+            .collect();
+        assert_eq!(settled.len(), 1);
+
+        // A manual trigger of the same epoch only settles the new pair
+        let manual = vec![order_match(epoch_id, buy_a, sell), order_match(epoch_id, buy_b, sell)];
+        let pending = unsettled(&manual, &settled);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].buy_order_id, buy_b);
+    }
+
+    #[test]
+    fn test_unsettled_skips_repeated_pair_in_a_run() {
+        let epoch_id = Uuid::new_v4();
+        let (buy, sell) = (Uuid::new_v4(), Uuid::new_v4());
+        let matches = vec![
+            order_match(epoch_id, buy, sell),
+            order_match(epoch_id, buy, sell),
+            // The same pair in another epoch is a different settlement
+            order_match(Uuid::new_v4(), buy, sell),
+        ];
+
+        let pending = unsettled(&matches, &HashSet::new());
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].id, matches[0].id);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires PostgreSQL, Redis and JWT_SECRET
+    async fn test_concurrent_clearing_runs_fill_each_order_once() {
+        let config = crate::config::Config::from_env().expect("Failed to load config");
+        let state = crate::startup::initialize_app(&config).await.expect("Failed to initialize app");
+        let clearing = &state.market_clearing;
+        let epoch = clearing.get_or_create_epoch(Utc::now()).await.unwrap();
+        let market = clearing.markets.active_spot_markets().await.unwrap().remove(0);
+
+        let mut orders = Vec::new();
+        for side in ["buy", "sell"] {
+            let user_id = Uuid::new_v4();
+            sqlx::query("INSERT INTO users (id, email, username, password_hash) VALUES ($1, $2, $3, 'x')")
+                .bind(user_id)
+                .bind(format!("{}@clearing.test", user_id))
+                .bind(format!("clearing-{}", user_id.simple()))
+                .execute(&state.db)
+                .await
+                .unwrap();
+            let order_id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO trading_orders (user_id, order_type, side, energy_amount, price_per_kwh,
+                                            filled_amount, status, epoch_id, market_id)
+                VALUES ($1, 'limit', $2::order_side, 10, 3, 0, 'pending', $3, $4)
+                RETURNING id
+                "#,
+            )
+            .bind(user_id)
+            .bind(side)
+            .bind(epoch.id)
+            .bind(market.id)
+            .fetch_one(&state.db)
+            .await
+            .unwrap();
+            orders.push(order_id);
+        }
+
+        // Manual and scheduled triggers of the same epoch at once
+        let (first, second) = tokio::join!(
+            clearing.run_order_matching(epoch.id),
+            clearing.run_order_matching(epoch.id)
+        );
+        first.unwrap();
+        second.unwrap();
+
+        let filled: Vec<Decimal> = sqlx::query_scalar("SELECT filled_amount FROM trading_orders WHERE id = ANY($1)")
+            .bind(&orders)
+            .fetch_all(&state.db)
+            .await
+            .unwrap();
+        assert!(filled.iter().all(|f| *f <= Decimal::from(10)), "orders over-filled: {:?}", filled);
+
+        let matched: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM order_matches WHERE buy_order_id = $1 AND sell_order_id = $2",
+        )
+        .bind(orders[0])
+        .bind(orders[1])
+        .fetch_one(&state.db)
+        .await
+        .unwrap();
+        assert!(matched <= 1, "pair matched {} times", matched);
     }
 }
@@ -1,10 +1,19 @@
 pub mod types;
 pub mod epoch;
+pub mod curves;
+pub mod participation;
 pub mod orders;
+pub mod balance;
 pub mod matching;
+pub mod batch;
+pub mod performance;
+pub mod simulation;
+pub mod shadow;
 pub mod blockchain;
 pub mod escrow;
+pub mod expiry;
 pub mod revenue;
+pub mod routing;
 
 use sqlx::PgPool;
 use rust_decimal::Decimal;
@@ -12,7 +21,7 @@ use rust_decimal::Decimal;
 pub use types::*;
 
 use crate::config::Config;
-use crate::services::{AuditLogger, BlockchainService, WalletService, WebSocketService, ErcService};
+use crate::services::{AuditLogger, BlockchainService, WalletService, WebSocketService, ErcService, FxRateService, MarketService, TradingHaltService};
 
 #[derive(Clone, Debug)]
 pub struct MarketClearingService {
@@ -23,6 +32,9 @@ pub struct MarketClearingService {
     audit_logger: AuditLogger,
     websocket_service: WebSocketService,
     erc_service: ErcService,
+    fx_rates: FxRateService,
+    markets: MarketService,
+    trading_halts: TradingHaltService,
 }
 
 impl MarketClearingService {
@@ -36,6 +48,9 @@ impl MarketClearingService {
         erc_service: ErcService,
     ) -> Self {
         Self {
+            fx_rates: FxRateService::new(db.clone(), config.currency.clone()),
+            markets: MarketService::new(db.clone()),
+            trading_halts: TradingHaltService::new(db.clone(), websocket_service.clone()),
             db,
             blockchain_service,
             config,
@@ -46,6 +61,11 @@ impl MarketClearingService {
         }
     }
 
+    /// FX rates used to value settlements in the display currency
+    pub fn fx_rates(&self) -> &FxRateService {
+        &self.fx_rates
+    }
+
     /// Calculate market clearing price from order book
     /// Uses midpoint of bid-ask spread where supply meets demand
     pub fn calculate_clearing_price(
@@ -1,23 +1,29 @@
 use anyhow::Result;
 use chrono::{DateTime, Duration, Utc};
-use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use sqlx::Row;
 use uuid::Uuid;
 use tracing::{info, error};
 
 use crate::database::schema::types::{OrderSide, OrderStatus, OrderType};
-use crate::error::ApiError;
-use super::MarketClearingService;
+use crate::error::{ApiError, ErrorCode};
+use crate::models::{EnergyKwh, TokenAmount};
+use crate::services::market_session;
+use crate::services::trading_halts::HaltedAction;
+use crate::services::markets;
+use crate::services::order_events::{self, NewOrderEvent, OrderEventType};
+use crate::services::vesting;
+use super::{balance, MarketClearingService};
 use super::types::{OrderBookEntry, Settlement};
 
 impl MarketClearingService {
-    /// Get current order book for an epoch
+    /// Get current order book of a market for an epoch
     pub async fn get_order_book(
         &self,
         epoch_id: Uuid,
+        market_id: Uuid,
     ) -> Result<(Vec<OrderBookEntry>, Vec<OrderBookEntry>)> {
-        info!("Getting order book for epoch: {}", epoch_id);
+        info!("Getting order book for epoch: {} (market {})", epoch_id, market_id);
 
         // Get pending buy orders (sorted by price descending, then time ascending)
         // energy_amount in the query is the remaining amount (original - filled)
@@ -30,10 +36,11 @@ impl MarketClearingService {
                 energy_amount as "original_amount!",
                 price_per_kwh as "price_per_kwh!", created_at as "created_at!", zone_id
             FROM trading_orders 
-            WHERE status IN ('pending', 'partially_filled') AND side = 'buy' AND epoch_id = $1 AND price_per_kwh IS NOT NULL
+            WHERE status IN ('pending', 'partially_filled') AND side = 'buy' AND epoch_id = $1 AND market_id = $2 AND price_per_kwh IS NOT NULL
             ORDER BY price_per_kwh DESC, created_at ASC
             "#,
-            epoch_id
+            epoch_id,
+            market_id
         )
         .fetch_all(&self.db)
         .await?;
@@ -54,10 +61,11 @@ impl MarketClearingService {
                 energy_amount as "original_amount!",
                 price_per_kwh as "price_per_kwh!", created_at as "created_at!", zone_id
             FROM trading_orders 
-            WHERE status IN ('pending', 'partially_filled') AND side = 'sell' AND epoch_id = $1 AND price_per_kwh IS NOT NULL
+            WHERE status IN ('pending', 'partially_filled') AND side = 'sell' AND epoch_id = $1 AND market_id = $2 AND price_per_kwh IS NOT NULL
             ORDER BY price_per_kwh ASC, created_at ASC
             "#,
-            epoch_id
+            epoch_id,
+            market_id
         )
         .fetch_all(&self.db)
         .await?;
@@ -71,7 +79,8 @@ impl MarketClearingService {
         Ok((buy_orders, sell_orders))
     }
 
-    /// Create a new trading order (DB and On-Chain)
+    /// Create a new trading order (DB and On-Chain).
+    /// The outcome is recorded as an `accepted` or `rejected` order event.
     pub async fn create_order(
         &self,
         user_id: Uuid,
@@ -83,13 +92,86 @@ impl MarketClearingService {
         zone_id: Option<i32>,
         meter_id: Option<Uuid>,
         session_token: Option<&str>,
+        market_id: Option<Uuid>,
     ) -> Result<Uuid> {
+        let order_id = Uuid::new_v4();
+        let price_per_kwh_val = match self
+            .place_order(order_id, user_id, side, order_type, energy_amount, price_per_kwh, expiry_time, zone_id, meter_id, market_id)
+            .await
+        {
+            Ok(price) => price,
+            Err(e) => {
+                let event = NewOrderEvent::new(order_id, user_id, OrderEventType::Rejected).with_reason(e.to_string());
+                order_events::record(&self.db, event).await;
+                return Err(e);
+            }
+        };
+
+        order_events::record(
+            &self.db,
+            NewOrderEvent::new(order_id, user_id, OrderEventType::Accepted)
+                .with_quantities(Decimal::ZERO, energy_amount)
+                .with_price(price_per_kwh_val),
+        )
+        .await;
+
+        // Broadcast order created event
+        self.websocket_service.broadcast_order_created(
+            order_id.to_string(),
+            EnergyKwh::from(energy_amount),
+            TokenAmount::from(price_per_kwh_val),
+            match side {
+                OrderSide::Buy => None,
+                OrderSide::Sell => Some("solar".to_string()), // Simplified assumption
+            },
+            user_id.to_string(),
+        ).await;
+
+        // Audit Log
+        self.audit_logger.log_async(crate::services::AuditEvent::OrderCreated {
+            user_id,
+            order_id,
+            order_type: format!("{:?}", side),
+            amount: energy_amount.to_string(),
+            price: price_per_kwh_val.to_string(),
+        });
+
+        // On-Chain Order Creation
+        self.execute_on_chain_order_creation(user_id, order_id, side, energy_amount, price_per_kwh_val, session_token).await?;
+
+        Ok(order_id)
+    }
+
+    /// Validate the order, insert it and lock its escrow; returns the accepted price
+    async fn place_order(
+        &self,
+        order_id: Uuid,
+        user_id: Uuid,
+        side: OrderSide,
+        order_type: OrderType,
+        energy_amount: Decimal,
+        price_per_kwh: Option<Decimal>,
+        expiry_time: Option<DateTime<Utc>>,
+        zone_id: Option<i32>,
+        meter_id: Option<Uuid>,
+        market_id: Option<Uuid>,
+    ) -> Result<Decimal> {
         info!("Creating order in MarketClearingService for user: {}, meter: {:?}", user_id, meter_id);
 
         if energy_amount <= Decimal::ZERO {
             return Err(anyhow::anyhow!("Energy amount must be positive"));
         }
 
+        market_session::ensure_accepting_orders(&self.config.market_session, Utc::now())?;
+
+        let market = self.markets.resolve(market_id).await?;
+        if let Some(reason) = markets::order_violation(&market, zone_id, energy_amount, price_per_kwh) {
+            return Err(ApiError::with_code(ErrorCode::OrderViolatesMarketRules, reason).into());
+        }
+        self.trading_halts
+            .ensure_allowed(market.id, zone_id, HaltedAction::Place)
+            .await?;
+
         let price_per_kwh_val = match order_type {
             OrderType::Limit => {
                 let price = price_per_kwh.ok_or_else(|| {
@@ -103,10 +185,15 @@ impl MarketClearingService {
             OrderType::Market => Decimal::ZERO,
         };
 
-        let order_id = Uuid::new_v4();
         let now = Utc::now();
         let expires_at = expiry_time.unwrap_or_else(|| now + Duration::days(1));
 
+        // Large sell orders are confirmed against the wallet before any rows are locked
+        let onchain_energy = match side {
+            OrderSide::Sell => self.onchain_energy_confirmation(user_id, energy_amount).await?,
+            OrderSide::Buy => None,
+        };
+
         // Get or create current epoch
         let epoch = self.get_or_create_epoch(now).await?;
 
@@ -118,8 +205,8 @@ impl MarketClearingService {
             r#"
             INSERT INTO trading_orders (
                 id, user_id, order_type, side, energy_amount, price_per_kwh,
-                filled_amount, status, expires_at, created_at, epoch_id, zone_id, meter_id
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                filled_amount, status, expires_at, created_at, epoch_id, zone_id, meter_id, market_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             "#,
             order_id,
             user_id,
@@ -133,7 +220,8 @@ impl MarketClearingService {
             now,
             epoch.id,
             zone_id,
-            meter_id
+            meter_id,
+            market.id
         )
         .execute(&mut *tx)
         .await?;
@@ -142,18 +230,15 @@ impl MarketClearingService {
         match side {
             OrderSide::Buy => {
                 let total_escrow_amount = energy_amount * price_per_kwh_val;
-                
-                // Check balance - DB balance check only for now
-                // TODO: On-chain balance check requires WalletService.get_token_balance() implementation
-                let _use_onchain_balance = self.config.tokenization.use_onchain_balance_for_escrow;
-                
-                // Use DB balance check
+
+                // Buyers must cover the full escrow from their ledger balance
                 let user = sqlx::query!("SELECT balance FROM users WHERE id = $1 FOR UPDATE", user_id)
                     .fetch_one(&mut *tx)
                     .await?;
+                let available = user.balance.unwrap_or(Decimal::ZERO);
 
-                if user.balance.unwrap_or(Decimal::ZERO) < total_escrow_amount {
-                    return Err(anyhow::anyhow!("Insufficient balance for escrow. Required: {}, Available: {}", total_escrow_amount, user.balance.unwrap_or(Decimal::ZERO)));
+                if available < total_escrow_amount {
+                    return Err(balance::insufficient("balance", total_escrow_amount, available).into());
                 }
 
                 // Update user balance and locked_amount
@@ -181,6 +266,28 @@ impl MarketClearingService {
                 .await?;
             }
             OrderSide::Sell => {
+                let user = sqlx::query(
+                    "SELECT role::text AS role, COALESCE(locked_energy, 0) AS locked_energy FROM users WHERE id = $1 FOR UPDATE",
+                )
+                .bind(user_id)
+                .fetch_one(&mut *tx)
+                .await?;
+                let role: String = user.get("role");
+                let committed: Decimal = user.get("locked_energy");
+                let oversubscription = self.config.order_balance.oversubscription_for(&role);
+
+                // Unvested grants count as holdings but cannot be sold yet
+                let locked = vesting::locked_amount(&mut tx, user_id, Utc::now()).await?;
+                let ledger = vesting::transferable(self.ledger_energy_balance(&mut tx, user_id).await?, locked);
+                let mut capacity = balance::sell_capacity(ledger, committed, oversubscription);
+                if let Some(onchain) = onchain_energy {
+                    capacity = capacity.min(balance::sell_capacity(onchain, committed, oversubscription));
+                }
+
+                if capacity < energy_amount {
+                    return Err(balance::insufficient("energy", energy_amount, capacity).into());
+                }
+
                 // Lock energy in DB
                 sqlx::query!(
                     "UPDATE users SET locked_energy = locked_energy + $1 WHERE id = $2",
@@ -212,74 +319,7 @@ impl MarketClearingService {
 
         info!("Created order {} for user {} with assets escrowed", order_id, user_id);
 
-        // Broadcast order created event
-        self.websocket_service.broadcast_order_created(
-            order_id.to_string(),
-            energy_amount.to_f64().unwrap_or(0.0),
-            price_per_kwh_val.to_f64().unwrap_or(0.0),
-            match side {
-                OrderSide::Buy => None,
-                OrderSide::Sell => Some("solar".to_string()), // Simplified assumption
-            },
-            user_id.to_string(),
-        ).await;
-
-        // 2. Audit Log
-        self.audit_logger.log_async(crate::services::AuditEvent::OrderCreated {
-            user_id,
-            order_id,
-            order_type: format!("{:?}", side),
-            amount: energy_amount.to_string(),
-            price: price_per_kwh_val.to_string(),
-        });
-
-        // 3. On-Chain Order Creation
-        self.execute_on_chain_order_creation(user_id, order_id, side, energy_amount, price_per_kwh_val, session_token).await?;
-
-        Ok(order_id)
-    }
-
-    /// Update order status
-    pub(super) async fn update_order_status(&self, order_id: Uuid, status: OrderStatus) -> Result<()> {
-        let status_str = match status {
-            OrderStatus::Pending => "pending",
-            OrderStatus::Active => "active",
-            OrderStatus::PartiallyFilled => "partially_filled",
-            OrderStatus::Filled => "filled",
-            OrderStatus::Settled => "settled",
-            OrderStatus::Cancelled => "cancelled",
-            OrderStatus::Expired => "expired",
-        };
-
-        info!("Updating order {} status to: {}", order_id, status_str);
-
-        let result =
-            sqlx::query("UPDATE trading_orders SET status = $1::order_status WHERE id = $2")
-                .bind(status_str)
-                .bind(order_id)
-                .execute(&self.db)
-                .await?;
-
-        info!(
-            "Updated order {} status, rows affected: {}",
-            order_id,
-            result.rows_affected()
-        );
-
-        Ok(())
-    }
-
-    /// Update order filled amount
-    pub(super) async fn update_order_filled_amount(&self, order_id: Uuid, amount: Decimal) -> Result<()> {
-        sqlx::query!(
-            "UPDATE trading_orders SET filled_amount = filled_amount + $1 WHERE id = $2",
-            amount,
-            order_id
-        )
-        .execute(&self.db)
-        .await?;
-
-        Ok(())
+        Ok(price_per_kwh_val)
     }
 
     /// Cancel an order and refund the unfilled escrow amount
@@ -306,6 +346,8 @@ impl MarketClearingService {
                 );
             }
 
+            self.trading_halts.ensure_cancel_allowed(order_id).await?;
+
             // Allow cancellation for pending or partially_filled orders
             if !matches!(order.status, OrderStatus::Pending | OrderStatus::PartiallyFilled) {
                 return Err(ApiError::BadRequest(format!(
@@ -399,6 +441,15 @@ impl MarketClearingService {
                 price.to_string(),
             ).await;
 
+            order_events::record(
+                &self.db,
+                NewOrderEvent::new(order_id, user_id, OrderEventType::Cancelled)
+                    .with_quantities(filled, Decimal::ZERO)
+                    .with_price(price)
+                    .with_reason("Cancelled by user"),
+            )
+            .await;
+
             info!("Order {} cancelled by user {} (filled: {}, refunded: {})", 
                 order_id, user_id, filled, unfilled);
 
@@ -0,0 +1,349 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::schema::types::{EpochStatus, OrderSide};
+use crate::models::EnergyKwh;
+use super::MarketClearingService;
+
+/// One of the user's orders in the epoch
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ParticipationOrder {
+    pub id: Uuid,
+    pub side: OrderSide,
+    #[schema(value_type = String)]
+    pub energy_amount: Decimal,
+    #[schema(value_type = String)]
+    pub price_per_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub filled_amount: Decimal,
+    pub status: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A match filling one of the user's orders. Counterparties are not disclosed.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ParticipationFill {
+    pub match_id: Uuid,
+    pub order_id: Uuid,
+    pub side: OrderSide,
+    #[schema(value_type = String)]
+    pub quantity: Decimal,
+    #[schema(value_type = String)]
+    pub price: Decimal,
+    pub status: String,
+    pub settlement_id: Option<Uuid>,
+    pub matched_at: Option<DateTime<Utc>>,
+}
+
+/// Settlement of one of the user's fills, from the user's side
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ParticipationSettlement {
+    pub id: Uuid,
+    pub side: OrderSide,
+    #[schema(value_type = String)]
+    pub energy_amount: Decimal,
+    /// Energy delivered to the buyer after technical losses
+    #[schema(value_type = String)]
+    pub effective_energy: Decimal,
+    #[schema(value_type = String)]
+    pub price_per_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub total_amount: Decimal,
+    /// Platform fee, deducted from the seller's proceeds
+    #[schema(value_type = String)]
+    pub fee_amount: Decimal,
+    /// Zone transmission charge, deducted from the seller's proceeds
+    #[schema(value_type = String)]
+    pub wheeling_charge: Decimal,
+    #[schema(value_type = String)]
+    pub loss_cost: Decimal,
+    /// Seller's proceeds after fee and wheeling charge
+    #[schema(value_type = String)]
+    pub net_amount: Decimal,
+    pub status: String,
+    pub transaction_hash: Option<String>,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+/// What clearing allocated to the user, per side
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct ParticipationAllocation {
+    pub bought: EnergyKwh,
+    /// Volume-weighted price paid; none when nothing was bought
+    #[schema(value_type = Option<String>)]
+    pub average_buy_price: Option<Decimal>,
+    pub sold: EnergyKwh,
+    /// Volume-weighted price received; none when nothing was sold
+    #[schema(value_type = Option<String>)]
+    pub average_sell_price: Option<Decimal>,
+}
+
+/// Charges the user bore in the epoch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct ParticipationFees {
+    /// Platform fees on the user's sales
+    #[schema(value_type = String)]
+    pub platform_fee: Decimal,
+    /// Wheeling charges on the user's sales
+    #[schema(value_type = String)]
+    pub wheeling_charge: Decimal,
+    /// Cost of energy lost in transit on the user's purchases
+    #[schema(value_type = String)]
+    pub loss_cost: Decimal,
+    #[schema(value_type = String)]
+    pub total: Decimal,
+}
+
+/// Settlement progress of the user's fills in the epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ParticipationSettlementStatus {
+    /// Nothing matched, so nothing to settle
+    NotApplicable,
+    /// Matched, awaiting or in settlement
+    Pending,
+    /// Some settlements failed
+    Failed,
+    Completed,
+}
+
+/// How an epoch's clearing treated one user
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EpochParticipation {
+    pub epoch_id: Uuid,
+    pub epoch_number: i64,
+    pub epoch_status: EpochStatus,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    /// Uniform clearing price of the epoch
+    #[schema(value_type = Option<String>)]
+    pub clearing_price: Option<Decimal>,
+    pub orders: Vec<ParticipationOrder>,
+    pub fills: Vec<ParticipationFill>,
+    pub allocation: ParticipationAllocation,
+    pub fees: ParticipationFees,
+    pub settlement_status: ParticipationSettlementStatus,
+    pub settlements: Vec<ParticipationSettlement>,
+}
+
+/// Per-side totals of the user's fills
+pub fn summarize_fills(fills: &[ParticipationFill]) -> ParticipationAllocation {
+    let (mut bought, mut bought_value, mut sold, mut sold_value) =
+        (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+    for fill in fills {
+        match fill.side {
+            OrderSide::Buy => {
+                bought += fill.quantity;
+                bought_value += fill.quantity * fill.price;
+            }
+            OrderSide::Sell => {
+                sold += fill.quantity;
+                sold_value += fill.quantity * fill.price;
+            }
+        }
+    }
+    let average = |value: Decimal, quantity: Decimal| (!quantity.is_zero()).then(|| value / quantity);
+    ParticipationAllocation {
+        bought: EnergyKwh::from(bought),
+        average_buy_price: average(bought_value, bought),
+        sold: EnergyKwh::from(sold),
+        average_sell_price: average(sold_value, sold),
+    }
+}
+
+/// Charges borne by the user: fees and wheeling on sales, losses on purchases
+pub fn summarize_fees(settlements: &[ParticipationSettlement]) -> ParticipationFees {
+    let mut fees = ParticipationFees::default();
+    for settlement in settlements {
+        match settlement.side {
+            OrderSide::Sell => {
+                fees.platform_fee += settlement.fee_amount;
+                fees.wheeling_charge += settlement.wheeling_charge;
+            }
+            OrderSide::Buy => fees.loss_cost += settlement.loss_cost,
+        }
+    }
+    fees.total = fees.platform_fee + fees.wheeling_charge + fees.loss_cost;
+    fees
+}
+
+/// Overall settlement status of the user's fills. Fills without a
+/// settlement yet count as pending.
+pub fn settlement_status(fills: &[ParticipationFill], settlements: &[ParticipationSettlement]) -> ParticipationSettlementStatus {
+    if fills.is_empty() {
+        return ParticipationSettlementStatus::NotApplicable;
+    }
+    if settlements.iter().any(|s| s.status == "failed") || fills.iter().any(|f| f.status == "failed") {
+        return ParticipationSettlementStatus::Failed;
+    }
+    let settled = |f: &ParticipationFill| {
+        f.settlement_id
+            .is_some_and(|id| settlements.iter().any(|s| s.id == id && s.status == "completed"))
+    };
+    if fills.iter().all(settled) {
+        ParticipationSettlementStatus::Completed
+    } else {
+        ParticipationSettlementStatus::Pending
+    }
+}
+
+impl MarketClearingService {
+    /// The user's orders, fills, allocation, fees and settlements in an epoch.
+    /// Epochs the user did not trade in come back with empty lists.
+    pub async fn get_epoch_participation(&self, epoch_id: Uuid, user_id: Uuid) -> Result<Option<EpochParticipation>> {
+        let Some(epoch) = self.get_epoch_by_id(epoch_id).await? else {
+            return Ok(None);
+        };
+
+        let orders = sqlx::query_as::<_, ParticipationOrder>(
+            r#"
+            SELECT id, side, energy_amount, price_per_kwh, COALESCE(filled_amount, 0) AS filled_amount,
+                   status::text AS status, created_at
+            FROM trading_orders
+            WHERE epoch_id = $1 AND user_id = $2
+            ORDER BY created_at
+            "#,
+        )
+        .bind(epoch_id)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let fills = sqlx::query_as::<_, ParticipationFill>(
+            r#"
+            SELECT m.id AS match_id, o.id AS order_id, o.side, m.matched_amount AS quantity,
+                   m.match_price AS price, m.status, m.settlement_id, m.match_time AS matched_at
+            FROM order_matches m
+            JOIN trading_orders o ON o.id IN (m.buy_order_id, m.sell_order_id)
+            WHERE m.epoch_id = $1 AND o.user_id = $2
+            ORDER BY m.match_time, m.id
+            "#,
+        )
+        .bind(epoch_id)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let settlements = sqlx::query_as::<_, ParticipationSettlement>(
+            r#"
+            SELECT id,
+                   (CASE WHEN buyer_id = $2 THEN 'buy' ELSE 'sell' END)::order_side AS side,
+                   energy_amount, COALESCE(effective_energy, energy_amount) AS effective_energy,
+                   price_per_kwh, total_amount, fee_amount,
+                   COALESCE(wheeling_charge, 0) AS wheeling_charge, COALESCE(loss_cost, 0) AS loss_cost,
+                   net_amount, status, transaction_hash, processed_at
+            FROM settlements
+            WHERE epoch_id = $1 AND (buyer_id = $2 OR seller_id = $2)
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(epoch_id)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(Some(EpochParticipation {
+            epoch_id: epoch.id,
+            epoch_number: epoch.epoch_number,
+            epoch_status: epoch.status,
+            start_time: epoch.start_time,
+            end_time: epoch.end_time,
+            clearing_price: epoch.clearing_price,
+            allocation: summarize_fills(&fills),
+            fees: summarize_fees(&settlements),
+            settlement_status: settlement_status(&fills, &settlements),
+            orders,
+            fills,
+            settlements,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(side: OrderSide, quantity: i64, price: i64, settlement_id: Option<Uuid>) -> ParticipationFill {
+        ParticipationFill {
+            match_id: Uuid::new_v4(),
+            order_id: Uuid::new_v4(),
+            side,
+            quantity: Decimal::from(quantity),
+            price: Decimal::from(price),
+            status: "pending".to_string(),
+            settlement_id,
+            matched_at: None,
+        }
+    }
+
+    fn settlement(id: Uuid, side: OrderSide, status: &str) -> ParticipationSettlement {
+        ParticipationSettlement {
+            id,
+            side,
+            energy_amount: Decimal::from(5),
+            effective_energy: Decimal::from(5),
+            price_per_kwh: Decimal::from(4),
+            total_amount: Decimal::from(20),
+            fee_amount: Decimal::new(2, 1),
+            wheeling_charge: Decimal::ONE,
+            loss_cost: Decimal::new(5, 1),
+            net_amount: Decimal::new(188, 1),
+            status: status.to_string(),
+            transaction_hash: None,
+            processed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_allocation_is_volume_weighted_per_side() {
+        let fills = [
+            fill(OrderSide::Sell, 2, 4, None),
+            fill(OrderSide::Sell, 6, 5, None),
+            fill(OrderSide::Buy, 1, 3, None),
+        ];
+        let allocation = summarize_fills(&fills);
+        assert_eq!(allocation.sold, EnergyKwh::from(Decimal::from(8)));
+        assert_eq!(allocation.average_sell_price, Some(Decimal::new(475, 2)));
+        assert_eq!(allocation.average_buy_price, Some(Decimal::from(3)));
+        assert_eq!(summarize_fills(&[]).average_sell_price, None);
+    }
+
+    #[test]
+    fn test_fees_follow_the_users_side() {
+        let settlements = [
+            settlement(Uuid::new_v4(), OrderSide::Sell, "completed"),
+            settlement(Uuid::new_v4(), OrderSide::Buy, "completed"),
+        ];
+        let fees = summarize_fees(&settlements);
+        assert_eq!(fees.platform_fee, Decimal::new(2, 1));
+        assert_eq!(fees.wheeling_charge, Decimal::ONE);
+        assert_eq!(fees.loss_cost, Decimal::new(5, 1));
+        assert_eq!(fees.total, Decimal::new(17, 1));
+    }
+
+    #[test]
+    fn test_settlement_status() {
+        let id = Uuid::new_v4();
+        let fills = [fill(OrderSide::Sell, 5, 4, Some(id))];
+        assert_eq!(settlement_status(&[], &[]), ParticipationSettlementStatus::NotApplicable);
+        assert_eq!(
+            settlement_status(&fills, &[settlement(id, OrderSide::Sell, "processing")]),
+            ParticipationSettlementStatus::Pending
+        );
+        assert_eq!(
+            settlement_status(&fills, &[settlement(id, OrderSide::Sell, "completed")]),
+            ParticipationSettlementStatus::Completed
+        );
+        assert_eq!(
+            settlement_status(&fills, &[settlement(id, OrderSide::Sell, "failed")]),
+            ParticipationSettlementStatus::Failed
+        );
+        let unsettled = [fill(OrderSide::Buy, 1, 4, None)];
+        assert_eq!(settlement_status(&unsettled, &[]), ParticipationSettlementStatus::Pending);
+    }
+}
@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::middleware::metrics::track_matching_stage;
+use super::MarketClearingService;
+
+/// Stage of epoch matching timed separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchingStage {
+    /// Loading the open orders of each market
+    Fetch,
+    /// The price-time priority loop itself, without the writes and broadcasts it awaits
+    Matching,
+    /// Matches, fills, order events and epoch statistics written to the database
+    DbWrite,
+    /// Order updates broadcast to the traders involved
+    Notify,
+    /// Settlements created for the epoch's matches
+    Settlement,
+}
+
+impl MatchingStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fetch => "fetch",
+            Self::Matching => "matching",
+            Self::DbWrite => "db_write",
+            Self::Notify => "notify",
+            Self::Settlement => "settlement",
+        }
+    }
+}
+
+/// Time spent in each stage while matching one epoch
+#[derive(Debug, Clone, Default)]
+pub struct MatchingTimings {
+    pub fetch: Duration,
+    pub matching: Duration,
+    pub db_write: Duration,
+    pub notify: Duration,
+    pub settlement: Duration,
+    pub markets: i32,
+    pub orders: i32,
+    pub matches: i32,
+}
+
+impl MatchingTimings {
+    pub fn add(&mut self, stage: MatchingStage, elapsed: Duration) {
+        match stage {
+            MatchingStage::Fetch => self.fetch += elapsed,
+            MatchingStage::Matching => self.matching += elapsed,
+            MatchingStage::DbWrite => self.db_write += elapsed,
+            MatchingStage::Notify => self.notify += elapsed,
+            MatchingStage::Settlement => self.settlement += elapsed,
+        }
+    }
+
+    /// Writes and broadcasts awaited so far
+    pub fn awaited(&self) -> Duration {
+        self.db_write + self.notify
+    }
+
+    /// Add a matching loop's wall time less the writes and broadcasts it
+    /// awaited, given [`awaited`](Self::awaited) from before the loop
+    pub fn add_loop(&mut self, elapsed: Duration, awaited_before: Duration) {
+        let awaited = self.awaited().saturating_sub(awaited_before);
+        self.add(MatchingStage::Matching, elapsed.saturating_sub(awaited));
+    }
+
+    /// Export the stages as `matching_stage_duration_ms` histograms
+    pub fn export(&self) {
+        for (stage, elapsed) in [
+            (MatchingStage::Fetch, self.fetch),
+            (MatchingStage::Matching, self.matching),
+            (MatchingStage::DbWrite, self.db_write),
+            (MatchingStage::Notify, self.notify),
+            (MatchingStage::Settlement, self.settlement),
+        ] {
+            track_matching_stage(stage.as_str(), millis(elapsed));
+        }
+    }
+}
+
+fn millis(elapsed: Duration) -> f64 {
+    elapsed.as_secs_f64() * 1000.0
+}
+
+/// Stored timing of one epoch's matching run
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct EpochMatchingPerformance {
+    pub epoch_id: Uuid,
+    /// Spot markets matched
+    pub markets: i32,
+    /// Open orders loaded across those markets
+    pub orders: i32,
+    pub matches: i32,
+    pub fetch_ms: f64,
+    pub matching_ms: f64,
+    pub db_write_ms: f64,
+    pub notify_ms: f64,
+    pub settlement_ms: f64,
+    pub total_ms: f64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+const PERFORMANCE_COLUMNS: &str = "epoch_id, markets, orders, matches, fetch_ms, matching_ms, db_write_ms, \
+                                   notify_ms, settlement_ms, total_ms, recorded_at";
+
+impl MarketClearingService {
+    /// Record the timing of an epoch's matching run; a re-run replaces it
+    pub(super) async fn record_matching_performance(
+        &self,
+        epoch_id: Uuid,
+        timings: &MatchingTimings,
+        total: Duration,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO epoch_matching_performance (
+                epoch_id, markets, orders, matches, fetch_ms, matching_ms,
+                db_write_ms, notify_ms, settlement_ms, total_ms
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (epoch_id) DO UPDATE SET
+                markets = EXCLUDED.markets, orders = EXCLUDED.orders, matches = EXCLUDED.matches,
+                fetch_ms = EXCLUDED.fetch_ms, matching_ms = EXCLUDED.matching_ms,
+                db_write_ms = EXCLUDED.db_write_ms, notify_ms = EXCLUDED.notify_ms,
+                settlement_ms = EXCLUDED.settlement_ms, total_ms = EXCLUDED.total_ms,
+                recorded_at = NOW()
+            "#,
+        )
+        .bind(epoch_id)
+        .bind(timings.markets)
+        .bind(timings.orders)
+        .bind(timings.matches)
+        .bind(millis(timings.fetch))
+        .bind(millis(timings.matching))
+        .bind(millis(timings.db_write))
+        .bind(millis(timings.notify))
+        .bind(millis(timings.settlement))
+        .bind(millis(total))
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Timing of an epoch's matching run, if it has been matched
+    pub async fn get_matching_performance(&self, epoch_id: Uuid) -> Result<Option<EpochMatchingPerformance>> {
+        Ok(sqlx::query_as::<_, EpochMatchingPerformance>(&format!(
+            "SELECT {} FROM epoch_matching_performance WHERE epoch_id = $1",
+            PERFORMANCE_COLUMNS
+        ))
+        .bind(epoch_id)
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    /// Timings of the most recently matched epochs, newest first
+    pub async fn recent_matching_performance(&self, limit: i64) -> Result<Vec<EpochMatchingPerformance>> {
+        Ok(sqlx::query_as::<_, EpochMatchingPerformance>(&format!(
+            "SELECT {} FROM epoch_matching_performance ORDER BY recorded_at DESC LIMIT $1",
+            PERFORMANCE_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loop_time_excludes_awaited_writes_and_broadcasts() {
+        let mut timings = MatchingTimings::default();
+        timings.add(MatchingStage::DbWrite, Duration::from_millis(100));
+
+        let before = timings.awaited();
+        timings.add(MatchingStage::DbWrite, Duration::from_millis(30));
+        timings.add(MatchingStage::Notify, Duration::from_millis(5));
+        timings.add_loop(Duration::from_millis(50), before);
+        assert_eq!(timings.matching, Duration::from_millis(15));
+        assert_eq!(timings.db_write, Duration::from_millis(130));
+    }
+}
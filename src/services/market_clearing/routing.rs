@@ -0,0 +1,380 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::Row;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::database::schema::types::{OrderSide, OrderType};
+use crate::error::{ApiError, ErrorCode};
+use crate::services::market_session;
+use crate::services::trading_halts::HaltedAction;
+use crate::services::markets::{self, Market};
+use crate::services::order_router::{
+    self, AmmQuote, BookLevel, ExecutionReport, PoolState, CURRENCY_TOKEN, ENERGY_TOKEN, REPORT_COLUMNS,
+};
+use crate::services::vesting;
+use super::{balance, MarketClearingService};
+
+/// Orderbook leg of a routed order
+struct BookLeg {
+    order_id: Option<Uuid>,
+    kwh: Decimal,
+    limit_price: Option<Decimal>,
+    avg_price: Option<Decimal>,
+}
+
+impl MarketClearingService {
+    /// Route a market order: take what the orderbook offers with a marketable
+    /// limit order, then fill the remainder from the market's AMM pool if its
+    /// effective price is within `max_slippage_bps` of the reference price.
+    ///
+    /// The requested tolerance is capped at the configured maximum.
+    pub async fn route_market_order(
+        &self,
+        user_id: Uuid,
+        side: OrderSide,
+        energy_amount: Decimal,
+        market_id: Option<Uuid>,
+        max_slippage_bps: Option<u32>,
+        zone_id: Option<i32>,
+        meter_id: Option<Uuid>,
+        session_token: Option<&str>,
+    ) -> Result<ExecutionReport> {
+        if energy_amount <= Decimal::ZERO {
+            return Err(anyhow::anyhow!("Energy amount must be positive"));
+        }
+        market_session::ensure_accepting_orders(&self.config.market_session, Utc::now())?;
+
+        let market = self.markets.resolve(market_id).await?;
+        if let Some(reason) = markets::order_violation(&market, zone_id, energy_amount, None) {
+            return Err(ApiError::with_code(ErrorCode::OrderViolatesMarketRules, reason).into());
+        }
+        self.trading_halts
+            .ensure_allowed(market.id, zone_id, HaltedAction::Place)
+            .await?;
+
+        let router_config = &self.config.order_router;
+        let tolerance_bps = max_slippage_bps
+            .unwrap_or(router_config.max_slippage_bps)
+            .min(router_config.max_slippage_bps);
+
+        let levels = self.opposite_book(&market, side, user_id).await?;
+        let pool = if router_config.amm_enabled {
+            self.market_pool(market.id).await?
+        } else {
+            None
+        };
+        let reference_price = order_router::reference_price(&levels, pool.as_ref());
+
+        let book = self
+            .place_book_leg(&market, user_id, side, energy_amount, &levels, zone_id, meter_id, session_token)
+            .await?;
+
+        let shortfall = energy_amount - book.kwh;
+        let mut amm: Option<(Uuid, AmmQuote)> = None;
+        let mut skip_reason = None;
+        if shortfall > Decimal::ZERO {
+            match (&pool, reference_price) {
+                _ if !router_config.amm_enabled => skip_reason = Some("AMM routing is disabled".to_string()),
+                (None, _) => skip_reason = Some(format!("No AMM pool serves market {}", market.code)),
+                (Some(_), None) => skip_reason = Some("No reference price available".to_string()),
+                (Some(pool), Some(reference)) => {
+                    match self
+                        .execute_amm_leg(user_id, side, pool.id, shortfall, reference, tolerance_bps)
+                        .await?
+                    {
+                        Ok(leg) => amm = Some(leg),
+                        Err(reason) => skip_reason = Some(reason),
+                    }
+                }
+            }
+        }
+
+        let amm_kwh = amm.as_ref().map(|(_, q)| q.kwh).unwrap_or(Decimal::ZERO);
+        let report = sqlx::query_as::<_, ExecutionReport>(&format!(
+            r#"
+            INSERT INTO order_routing_reports (
+                user_id, market_id, side, requested_kwh, reference_price, tolerance_bps,
+                book_order_id, book_kwh, book_limit_price, book_avg_price,
+                pool_id, swap_id, amm_kwh, amm_currency_amount, amm_fee_amount, amm_effective_price,
+                amm_skip_reason, unfilled_kwh
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+            RETURNING {}
+            "#,
+            REPORT_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(market.id)
+        .bind(side.as_str())
+        .bind(energy_amount)
+        .bind(reference_price)
+        .bind(tolerance_bps as i32)
+        .bind(book.order_id)
+        .bind(book.kwh)
+        .bind(book.limit_price)
+        .bind(book.avg_price)
+        .bind(pool.as_ref().map(|p| p.id))
+        .bind(amm.as_ref().map(|(swap_id, _)| *swap_id))
+        .bind(amm_kwh)
+        .bind(amm.as_ref().map(|(_, q)| q.currency_amount).unwrap_or(Decimal::ZERO))
+        .bind(amm.as_ref().map(|(_, q)| q.fee_amount).unwrap_or(Decimal::ZERO))
+        .bind(amm.as_ref().map(|(_, q)| q.effective_price))
+        .bind(&skip_reason)
+        .bind(energy_amount - book.kwh - amm_kwh)
+        .fetch_one(&self.db)
+        .await?;
+
+        info!(
+            "🔀 Routed {} {} kWh for user {} in {}: book {} kWh, AMM {} kWh, unfilled {} kWh",
+            side, energy_amount, user_id, market.code, report.book_kwh, report.amm_kwh, report.unfilled_kwh
+        );
+
+        Ok(report)
+    }
+
+    /// Execution reports of a user's routed orders, newest first
+    pub async fn list_execution_reports(&self, user_id: Uuid, limit: i64) -> Result<Vec<ExecutionReport>> {
+        Ok(sqlx::query_as::<_, ExecutionReport>(&format!(
+            "SELECT {} FROM order_routing_reports WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2",
+            REPORT_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn get_execution_report(&self, user_id: Uuid, report_id: Uuid) -> Result<ExecutionReport> {
+        sqlx::query_as::<_, ExecutionReport>(&format!(
+            "SELECT {} FROM order_routing_reports WHERE id = $1 AND user_id = $2",
+            REPORT_COLUMNS
+        ))
+        .bind(report_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Execution report not found".to_string()).into())
+    }
+
+    /// Open opposite-side liquidity in the market by price level, best first,
+    /// excluding the user's own orders
+    async fn opposite_book(&self, market: &Market, side: OrderSide, user_id: Uuid) -> Result<Vec<BookLevel>> {
+        let (opposite, order_by) = match side {
+            OrderSide::Buy => ("sell", "ASC"),
+            OrderSide::Sell => ("buy", "DESC"),
+        };
+        Ok(sqlx::query_as::<_, BookLevel>(&format!(
+            r#"
+            SELECT price_per_kwh, SUM(energy_amount - COALESCE(filled_amount, 0)) AS kwh
+            FROM trading_orders
+            WHERE market_id = $1 AND side::text = $2 AND user_id <> $3
+              AND status IN ('pending', 'partially_filled') AND price_per_kwh > 0
+            GROUP BY price_per_kwh
+            ORDER BY price_per_kwh {}
+            "#,
+            order_by
+        ))
+        .bind(market.id)
+        .bind(opposite)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Energy/currency pool serving the market, if it holds liquidity
+    async fn market_pool(&self, market_id: Uuid) -> Result<Option<PoolState>> {
+        Ok(sqlx::query_as::<_, PoolState>(
+            r#"
+            SELECT id, reserve_a AS reserve_energy, reserve_b AS reserve_currency, fee_rate
+            FROM liquidity_pools
+            WHERE market_id = $1 AND token_a = $2 AND token_b = $3
+              AND reserve_a > 0 AND reserve_b > 0
+            ORDER BY reserve_b DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(market_id)
+        .bind(ENERGY_TOKEN)
+        .bind(CURRENCY_TOKEN)
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    /// Place a marketable limit order for the liquidity currently on the book.
+    /// Fills below the market's minimum order size are left to the AMM.
+    async fn place_book_leg(
+        &self,
+        market: &Market,
+        user_id: Uuid,
+        side: OrderSide,
+        energy_amount: Decimal,
+        levels: &[BookLevel],
+        zone_id: Option<i32>,
+        meter_id: Option<Uuid>,
+        session_token: Option<&str>,
+    ) -> Result<BookLeg> {
+        let fill = match order_router::fill_from_book(levels, energy_amount) {
+            Some(fill) if fill.kwh >= market.min_order_kwh => fill,
+            _ => {
+                return Ok(BookLeg { order_id: None, kwh: Decimal::ZERO, limit_price: None, avg_price: None });
+            }
+        };
+
+        let order_id = self
+            .create_order(
+                user_id,
+                side,
+                OrderType::Limit,
+                fill.kwh,
+                Some(fill.limit_price),
+                None,
+                zone_id,
+                meter_id,
+                session_token,
+                Some(market.id),
+            )
+            .await?;
+
+        Ok(BookLeg {
+            order_id: Some(order_id),
+            kwh: fill.kwh,
+            limit_price: Some(fill.limit_price),
+            avg_price: Some(fill.avg_price),
+        })
+    }
+
+    /// Swap the shortfall with the pool at its current reserves. Returns the
+    /// reason instead when the price is out of tolerance or the user cannot
+    /// cover the leg, so the orderbook leg still stands.
+    async fn execute_amm_leg(
+        &self,
+        user_id: Uuid,
+        side: OrderSide,
+        pool_id: Uuid,
+        kwh: Decimal,
+        reference_price: Decimal,
+        tolerance_bps: u32,
+    ) -> Result<std::result::Result<(Uuid, AmmQuote), String>> {
+        let mut tx = self.db.begin().await?;
+
+        let pool = sqlx::query_as::<_, PoolState>(
+            r#"
+            SELECT id, reserve_a AS reserve_energy, reserve_b AS reserve_currency, fee_rate
+            FROM liquidity_pools WHERE id = $1 FOR UPDATE
+            "#,
+        )
+        .bind(pool_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let Some(quote) = order_router::quote_amm(side, &pool, kwh) else {
+            return Ok(Err("Insufficient pool liquidity".to_string()));
+        };
+        if !order_router::within_tolerance(side, quote.effective_price, reference_price, tolerance_bps) {
+            return Ok(Err(format!(
+                "AMM price {} is outside {} bps of reference {}",
+                quote.effective_price.round_dp(6),
+                tolerance_bps,
+                reference_price.round_dp(6)
+            )));
+        }
+
+        let (input_token, input_amount, output_token, output_amount) = match side {
+            OrderSide::Buy => {
+                let available: Decimal =
+                    sqlx::query_scalar("SELECT COALESCE(balance, 0) FROM users WHERE id = $1 FOR UPDATE")
+                        .bind(user_id)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                if available < quote.currency_amount {
+                    return Ok(Err(format!(
+                        "Insufficient balance for AMM leg: required {}, available {}",
+                        quote.currency_amount.round_dp(6),
+                        available
+                    )));
+                }
+                sqlx::query("UPDATE users SET balance = balance - $1 WHERE id = $2")
+                    .bind(quote.currency_amount)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query(
+                    "UPDATE liquidity_pools SET reserve_a = reserve_a - $1, reserve_b = reserve_b + $2, updated_at = NOW() WHERE id = $3",
+                )
+                .bind(kwh)
+                .bind(quote.currency_amount)
+                .bind(pool_id)
+                .execute(&mut *tx)
+                .await?;
+                (CURRENCY_TOKEN, quote.currency_amount, ENERGY_TOKEN, kwh)
+            }
+            OrderSide::Sell => {
+                let user = sqlx::query(
+                    "SELECT COALESCE(locked_energy, 0) AS locked_energy FROM users WHERE id = $1 FOR UPDATE",
+                )
+                .bind(user_id)
+                .fetch_one(&mut *tx)
+                .await?;
+                let committed: Decimal = user.get("locked_energy");
+                // Pools receive energy immediately, so no over-subscription applies
+                let locked = vesting::locked_amount(&mut tx, user_id, Utc::now()).await?;
+                let ledger = vesting::transferable(self.ledger_energy_balance(&mut tx, user_id).await?, locked);
+                let capacity = balance::sell_capacity(ledger, committed, Decimal::ZERO);
+                if capacity < kwh {
+                    return Ok(Err(format!(
+                        "Insufficient energy for AMM leg: required {}, available {}",
+                        kwh, capacity
+                    )));
+                }
+                sqlx::query("UPDATE users SET balance = COALESCE(balance, 0) + $1 WHERE id = $2")
+                    .bind(quote.currency_amount)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query(
+                    "UPDATE liquidity_pools SET reserve_a = reserve_a + $1, reserve_b = reserve_b - $2, updated_at = NOW() WHERE id = $3",
+                )
+                .bind(kwh)
+                .bind(quote.currency_amount)
+                .bind(pool_id)
+                .execute(&mut *tx)
+                .await?;
+                (ENERGY_TOKEN, kwh, CURRENCY_TOKEN, quote.currency_amount)
+            }
+        };
+
+        let swap_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO swap_transactions (
+                id, user_id, pool_id, input_token, input_amount, output_token, output_amount,
+                fee_amount, slippage_tolerance, status
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'completed')
+            "#,
+        )
+        .bind(swap_id)
+        .bind(user_id)
+        .bind(pool_id)
+        .bind(input_token)
+        .bind(input_amount)
+        .bind(output_token)
+        .bind(output_amount)
+        .bind(quote.fee_amount)
+        .bind(Decimal::from(tolerance_bps) / Decimal::from(10_000))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        info!(
+            "AMM leg for user {}: {} kWh at {} (reference {})",
+            user_id,
+            kwh,
+            quote.effective_price.round_dp(6),
+            reference_price
+        );
+
+        Ok(Ok((swap_id, quote)))
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::ShadowAlgorithm;
+use crate::database::schema::types::OrderSide;
+use crate::middleware::metrics::track_matching_shadow;
+use crate::models::EnergyKwh;
+use super::matching::match_book;
+use super::simulation::allocate;
+use super::types::{OrderBookEntry, OrderMatch};
+use super::MarketClearingService;
+
+/// An order the shadow matcher would have filled differently
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OrderDivergence {
+    pub order_id: Uuid,
+    pub side: OrderSide,
+    pub live_allocated: EnergyKwh,
+    pub shadow_allocated: EnergyKwh,
+    /// Volume-weighted price of the live allocation
+    #[schema(value_type = Option<String>)]
+    pub live_price: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub shadow_price: Option<Decimal>,
+}
+
+/// Live and shadow matching of one market's book in an epoch
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ShadowComparison {
+    pub epoch_id: Uuid,
+    pub market_id: Uuid,
+    /// uniform_price or seller_price
+    pub algorithm: String,
+    pub live_volume: EnergyKwh,
+    pub shadow_volume: EnergyKwh,
+    /// Volume-weighted match price of each run
+    #[schema(value_type = Option<String>)]
+    pub live_price: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub shadow_price: Option<Decimal>,
+    pub diverged_orders: i32,
+    #[schema(value_type = Vec<OrderDivergence>)]
+    pub divergences: sqlx::types::Json<Vec<OrderDivergence>>,
+    pub compared_at: DateTime<Utc>,
+}
+
+const SHADOW_COLUMNS: &str = "epoch_id, market_id, algorithm, live_volume, shadow_volume, live_price, \
+                              shadow_price, diverged_orders, divergences, compared_at";
+
+/// Match a book with an alternative pricing policy. Both policies keep the
+/// live price-time pairing and only reprice its matches.
+fn shadow_matches(algorithm: ShadowAlgorithm, epoch_id: Uuid, book: &[OrderBookEntry]) -> Vec<OrderMatch> {
+    let (mut buys, mut sells): (Vec<OrderBookEntry>, Vec<OrderBookEntry>) =
+        book.iter().cloned().partition(|o| o.side == OrderSide::Buy);
+    let mut matches = match_book(epoch_id, &mut buys, &mut sells, None);
+
+    let limits: HashMap<Uuid, Decimal> = book.iter().map(|o| (o.order_id, o.price_per_kwh)).collect();
+    match algorithm {
+        ShadowAlgorithm::UniformPrice => {
+            if let Some(marginal) = matches.last() {
+                let price = (limits[&marginal.buy_order_id] + limits[&marginal.sell_order_id]) / Decimal::from(2);
+                for m in &mut matches {
+                    m.match_price = price;
+                }
+            }
+        }
+        ShadowAlgorithm::SellerPrice => {
+            for m in &mut matches {
+                m.match_price = limits[&m.sell_order_id];
+            }
+        }
+    }
+    matches
+}
+
+fn vwap(matches: &[OrderMatch]) -> (Decimal, Option<Decimal>) {
+    let volume: Decimal = matches.iter().map(|m| m.matched_amount).sum();
+    let value: Decimal = matches.iter().map(|m| m.matched_amount * m.match_price).sum();
+    (volume, (volume > Decimal::ZERO).then(|| value / volume))
+}
+
+/// Run the shadow matcher on a market's book as the live matcher found it
+/// and compare the two; divergences are logged and counted
+pub(super) fn run_shadow(
+    algorithm: ShadowAlgorithm,
+    epoch_id: Uuid,
+    market_id: Uuid,
+    book: &[OrderBookEntry],
+    live: &[OrderMatch],
+) -> ShadowComparison {
+    let shadow = shadow_matches(algorithm, epoch_id, book);
+
+    let divergences: Vec<OrderDivergence> = allocate(market_id, book, live)
+        .into_iter()
+        .zip(allocate(market_id, book, &shadow))
+        .filter(|(l, s)| l.allocated != s.allocated || l.average_price != s.average_price)
+        .map(|(l, s)| OrderDivergence {
+            order_id: l.order_id,
+            side: l.side,
+            live_allocated: l.allocated,
+            shadow_allocated: s.allocated,
+            live_price: l.average_price,
+            shadow_price: s.average_price,
+        })
+        .collect();
+
+    let (live_volume, live_price) = vwap(live);
+    let (shadow_volume, shadow_price) = vwap(&shadow);
+
+    track_matching_shadow(algorithm.as_str(), divergences.len());
+    if divergences.is_empty() {
+        info!("🪞 Shadow {} matcher agrees with live matching of market {}", algorithm.as_str(), market_id);
+    } else {
+        warn!(
+            "🪞 Shadow {} matcher diverges on {} orders of market {} in epoch {}: volume {} vs {} kWh, price {:?} vs {:?}",
+            algorithm.as_str(),
+            divergences.len(),
+            market_id,
+            epoch_id,
+            live_volume,
+            shadow_volume,
+            live_price,
+            shadow_price
+        );
+    }
+
+    ShadowComparison {
+        epoch_id,
+        market_id,
+        algorithm: algorithm.as_str().to_string(),
+        live_volume: EnergyKwh::from(live_volume),
+        shadow_volume: EnergyKwh::from(shadow_volume),
+        live_price,
+        shadow_price,
+        diverged_orders: divergences.len() as i32,
+        divergences: sqlx::types::Json(divergences),
+        compared_at: Utc::now(),
+    }
+}
+
+impl MarketClearingService {
+    /// Store shadow comparisons; a re-run of the epoch replaces them
+    pub(super) async fn record_shadow_comparisons(&self, comparisons: &[ShadowComparison]) -> Result<()> {
+        for c in comparisons {
+            sqlx::query(
+                r#"
+                INSERT INTO matching_shadow_comparisons (
+                    epoch_id, market_id, algorithm, live_volume, shadow_volume,
+                    live_price, shadow_price, diverged_orders, divergences, compared_at
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                ON CONFLICT (epoch_id, market_id, algorithm) DO UPDATE SET
+                    live_volume = EXCLUDED.live_volume, shadow_volume = EXCLUDED.shadow_volume,
+                    live_price = EXCLUDED.live_price, shadow_price = EXCLUDED.shadow_price,
+                    diverged_orders = EXCLUDED.diverged_orders, divergences = EXCLUDED.divergences,
+                    compared_at = EXCLUDED.compared_at
+                "#,
+            )
+            .bind(c.epoch_id)
+            .bind(c.market_id)
+            .bind(&c.algorithm)
+            .bind(c.live_volume)
+            .bind(c.shadow_volume)
+            .bind(c.live_price)
+            .bind(c.shadow_price)
+            .bind(c.diverged_orders)
+            .bind(&c.divergences)
+            .bind(c.compared_at)
+            .execute(&self.db)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Shadow comparisons recorded for an epoch, one per market and algorithm
+    pub async fn get_shadow_comparisons(&self, epoch_id: Uuid) -> Result<Vec<ShadowComparison>> {
+        Ok(sqlx::query_as::<_, ShadowComparison>(&format!(
+            "SELECT {} FROM matching_shadow_comparisons WHERE epoch_id = $1 ORDER BY market_id, algorithm",
+            SHADOW_COLUMNS
+        ))
+        .bind(epoch_id)
+        .fetch_all(&self.db)
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(side: OrderSide, price: i64, amount: i64) -> OrderBookEntry {
+        OrderBookEntry {
+            order_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            side,
+            energy_amount: Decimal::from(amount),
+            original_amount: Decimal::from(amount),
+            price_per_kwh: Decimal::from(price),
+            created_at: Utc::now(),
+            zone_id: None,
+        }
+    }
+
+    #[test]
+    fn test_uniform_price_reprices_every_match_at_the_margin() {
+        let book = vec![
+            entry(OrderSide::Buy, 10, 4),
+            entry(OrderSide::Buy, 8, 4),
+            entry(OrderSide::Sell, 4, 4),
+            entry(OrderSide::Sell, 6, 4),
+        ];
+        let (mut buys, mut sells): (Vec<_>, Vec<_>) = book.iter().cloned().partition(|o| o.side == OrderSide::Buy);
+        let live = match_book(Uuid::nil(), &mut buys, &mut sells, None);
+
+        let comparison = run_shadow(ShadowAlgorithm::UniformPrice, Uuid::nil(), Uuid::nil(), &book, &live);
+        // Live pays 7 then 7; the margin (8 vs 6) clears everything at 7 too
+        assert_eq!(comparison.diverged_orders, 0);
+        assert_eq!(comparison.shadow_price, Some(Decimal::from(7)));
+
+        let comparison = run_shadow(ShadowAlgorithm::SellerPrice, Uuid::nil(), Uuid::nil(), &book, &live);
+        assert_eq!(comparison.diverged_orders, 4);
+        assert_eq!(comparison.live_volume, comparison.shadow_volume);
+        assert_eq!(comparison.shadow_price, Some(Decimal::from(5)));
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::schema::types::{EpochStatus, OrderSide};
+use crate::models::EnergyKwh;
+use super::matching::match_book;
+use super::types::{OrderBookEntry, OrderMatch};
+use super::MarketClearingService;
+
+/// What one open order would receive if the epoch were cleared now
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct OrderAllocation {
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub market_id: Uuid,
+    pub side: OrderSide,
+    #[schema(value_type = String)]
+    pub limit_price: Decimal,
+    /// Open quantity before clearing
+    pub remaining: EnergyKwh,
+    pub allocated: EnergyKwh,
+    /// Volume-weighted price of the allocation; none when nothing is allocated
+    #[schema(value_type = Option<String>)]
+    pub average_price: Option<Decimal>,
+}
+
+/// Outcome of matching an epoch's current book without persisting anything
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClearingSimulation {
+    pub epoch_id: Uuid,
+    pub epoch_status: EpochStatus,
+    /// Volume-weighted average of the would-be match prices
+    #[schema(value_type = Option<String>)]
+    pub clearing_price: Option<Decimal>,
+    pub matched_volume: EnergyKwh,
+    pub match_count: i64,
+    /// Every open order of the epoch, in book priority per market and side
+    pub allocations: Vec<OrderAllocation>,
+    pub simulated_at: DateTime<Utc>,
+}
+
+/// Allocation of each order of one market's book given the matches made on it
+pub(super) fn allocate(
+    market_id: Uuid,
+    book: &[OrderBookEntry],
+    matches: &[OrderMatch],
+) -> Vec<OrderAllocation> {
+    // (quantity, value) matched per order
+    let mut filled: HashMap<Uuid, (Decimal, Decimal)> = HashMap::new();
+    for m in matches {
+        for order_id in [m.buy_order_id, m.sell_order_id] {
+            let entry = filled.entry(order_id).or_insert((Decimal::ZERO, Decimal::ZERO));
+            entry.0 += m.matched_amount;
+            entry.1 += m.matched_amount * m.match_price;
+        }
+    }
+
+    book.iter()
+        .map(|order| {
+            let (quantity, value) = filled.get(&order.order_id).copied().unwrap_or_default();
+            OrderAllocation {
+                order_id: order.order_id,
+                user_id: order.user_id,
+                market_id,
+                side: order.side,
+                limit_price: order.price_per_kwh,
+                remaining: EnergyKwh::from(order.energy_amount),
+                allocated: EnergyKwh::from(quantity),
+                average_price: (quantity > Decimal::ZERO).then(|| value / quantity),
+            }
+        })
+        .collect()
+}
+
+impl MarketClearingService {
+    /// Run the matcher over an epoch's current book of every active spot
+    /// market without writing fills, events or statistics. Returns `None`
+    /// if the epoch does not exist.
+    pub async fn simulate_clearing(&self, epoch_id: Uuid) -> Result<Option<ClearingSimulation>> {
+        let Some(epoch) = self.get_epoch_by_id(epoch_id).await? else {
+            return Ok(None);
+        };
+
+        let mut allocations = Vec::new();
+        let mut matched_volume = Decimal::ZERO;
+        let mut matched_value = Decimal::ZERO;
+        let mut match_count = 0;
+
+        for market in self.markets.active_spot_markets().await? {
+            let (buy_orders, sell_orders) = self.get_order_book(epoch_id, market.id).await?;
+            let book: Vec<OrderBookEntry> = buy_orders.iter().chain(&sell_orders).cloned().collect();
+
+            let matches = match_book(epoch_id, &mut buy_orders.clone(), &mut sell_orders.clone(), None);
+            for m in &matches {
+                matched_volume += m.matched_amount;
+                matched_value += m.matched_amount * m.match_price;
+            }
+            match_count += matches.len() as i64;
+            allocations.extend(allocate(market.id, &book, &matches));
+        }
+
+        Ok(Some(ClearingSimulation {
+            epoch_id,
+            epoch_status: epoch.status,
+            clearing_price: (matched_volume > Decimal::ZERO).then(|| matched_value / matched_volume),
+            matched_volume: EnergyKwh::from(matched_volume),
+            match_count,
+            allocations,
+            simulated_at: Utc::now(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(side: OrderSide, price: i64, amount: i64) -> OrderBookEntry {
+        OrderBookEntry {
+            order_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            side,
+            energy_amount: Decimal::from(amount),
+            original_amount: Decimal::from(amount),
+            price_per_kwh: Decimal::from(price),
+            created_at: Utc::now(),
+            zone_id: None,
+        }
+    }
+
+    #[test]
+    fn test_dry_run_allocates_without_recording() {
+        let buys = vec![entry(OrderSide::Buy, 10, 6), entry(OrderSide::Buy, 4, 5)];
+        let sells = vec![entry(OrderSide::Sell, 6, 4), entry(OrderSide::Sell, 8, 4)];
+        let book: Vec<OrderBookEntry> = buys.iter().chain(&sells).cloned().collect();
+
+        let matches = match_book(Uuid::nil(), &mut buys.clone(), &mut sells.clone(), None);
+        assert_eq!(matches.len(), 2);
+
+        let allocations = allocate(Uuid::nil(), &book, &matches);
+        // 4 kWh at 8, then 2 kWh at 9
+        assert_eq!(allocations[0].allocated, EnergyKwh::from(Decimal::from(6)));
+        assert_eq!(allocations[0].average_price, Some(Decimal::from(50) / Decimal::from(6)));
+        // The low bid crosses nothing
+        assert_eq!(allocations[1].allocated, EnergyKwh::from(Decimal::ZERO));
+        assert_eq!(allocations[1].average_price, None);
+        assert_eq!(allocations[3].allocated, EnergyKwh::from(Decimal::from(2)));
+    }
+}
@@ -73,7 +73,7 @@ pub struct Settlement {
     pub seller_session_token: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct OrderBookEntry {
     pub order_id: Uuid,
     pub user_id: Uuid,
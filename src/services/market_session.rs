@@ -0,0 +1,295 @@
+//! Market Session Calendar
+//!
+//! Trading follows a daily calendar: a pre-open window in which orders are
+//! collected but not cleared, the open session, and a closed period outside
+//! trading hours, on non-trading weekdays and on holidays. Session changes
+//! are pushed to WebSocket subscribers as they happen.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::config::MarketSessionConfig;
+use crate::error::{ApiError, ErrorCode};
+use crate::services::WebSocketService;
+
+/// How far ahead to look for the next trading day
+const MAX_LOOKAHEAD_DAYS: i64 = 366;
+
+/// Interval at which session transitions are checked
+const WATCH_INTERVAL_SECS: u64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionState {
+    /// Orders are collected but not cleared
+    PreOpen,
+    Open,
+    Closed,
+}
+
+impl SessionState {
+    pub fn accepts_orders(&self) -> bool {
+        matches!(self, Self::PreOpen | Self::Open)
+    }
+
+    pub fn allows_clearing(&self) -> bool {
+        matches!(self, Self::Open)
+    }
+}
+
+/// Session state at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct MarketSession {
+    pub state: SessionState,
+    /// False when the calendar is disabled and the market trades around the clock
+    pub calendar_enabled: bool,
+    /// Market-local date of the current (or next) session
+    pub session_date: Option<NaiveDate>,
+    pub pre_open_at: Option<DateTime<Utc>>,
+    pub opens_at: Option<DateTime<Utc>>,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub next_state: Option<SessionState>,
+    pub next_transition_at: Option<DateTime<Utc>>,
+    /// Today is a configured market holiday
+    pub holiday: bool,
+}
+
+fn offset(config: &MarketSessionConfig) -> FixedOffset {
+    FixedOffset::east_opt(config.utc_offset_minutes * 60)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset"))
+}
+
+fn is_trading_day(config: &MarketSessionConfig, date: NaiveDate) -> bool {
+    config.trading_days.contains(&date.weekday()) && !config.holidays.contains(&date)
+}
+
+fn at_local(config: &MarketSessionConfig, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    offset(config)
+        .from_local_datetime(&date.and_time(time))
+        .single()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| date.and_time(time).and_utc())
+}
+
+/// Session state of the calendar at `now`
+pub fn session_at(config: &MarketSessionConfig, now: DateTime<Utc>) -> MarketSession {
+    if !config.enabled {
+        return MarketSession {
+            state: SessionState::Open,
+            calendar_enabled: false,
+            session_date: None,
+            pre_open_at: None,
+            opens_at: None,
+            closes_at: None,
+            next_state: None,
+            next_transition_at: None,
+            holiday: false,
+        };
+    }
+
+    let today = now.with_timezone(&offset(config)).date_naive();
+    let holiday = config.holidays.contains(&today);
+
+    // Today's session if it has not closed yet, otherwise the next trading day
+    let session_date = (0..=MAX_LOOKAHEAD_DAYS)
+        .map(|d| today + Duration::days(d))
+        .find(|date| {
+            is_trading_day(config, *date)
+                && (*date != today || now < at_local(config, *date, config.close_time))
+        });
+
+    let Some(session_date) = session_date else {
+        return MarketSession {
+            state: SessionState::Closed,
+            calendar_enabled: true,
+            session_date: None,
+            pre_open_at: None,
+            opens_at: None,
+            closes_at: None,
+            next_state: None,
+            next_transition_at: None,
+            holiday,
+        };
+    };
+
+    let opens_at = at_local(config, session_date, config.open_time);
+    let closes_at = at_local(config, session_date, config.close_time);
+    let pre_open_at = opens_at - Duration::minutes(config.pre_open_minutes);
+
+    let (state, next_state, next_transition_at) = if now >= opens_at {
+        (SessionState::Open, SessionState::Closed, closes_at)
+    } else if now >= pre_open_at && config.pre_open_minutes > 0 {
+        (SessionState::PreOpen, SessionState::Open, opens_at)
+    } else if config.pre_open_minutes > 0 {
+        (SessionState::Closed, SessionState::PreOpen, pre_open_at)
+    } else {
+        (SessionState::Closed, SessionState::Open, opens_at)
+    };
+
+    MarketSession {
+        state,
+        calendar_enabled: true,
+        session_date: Some(session_date),
+        pre_open_at: (config.pre_open_minutes > 0).then_some(pre_open_at),
+        opens_at: Some(opens_at),
+        closes_at: Some(closes_at),
+        next_state: Some(next_state),
+        next_transition_at: Some(next_transition_at),
+        holiday,
+    }
+}
+
+/// Market session service
+#[derive(Clone, Debug)]
+pub struct MarketSessionService {
+    config: MarketSessionConfig,
+    websocket_service: WebSocketService,
+}
+
+impl MarketSessionService {
+    pub fn new(config: MarketSessionConfig, websocket_service: WebSocketService) -> Self {
+        Self {
+            config,
+            websocket_service,
+        }
+    }
+
+    /// Current session state
+    pub fn current(&self) -> MarketSession {
+        session_at(&self.config, Utc::now())
+    }
+
+    /// Reject order placement outside the pre-open and open sessions
+    pub fn ensure_accepting_orders(&self) -> Result<(), ApiError> {
+        ensure_accepting_orders(&self.config, Utc::now())
+    }
+
+    /// Broadcast every session transition to WebSocket subscribers
+    pub async fn watch_transitions(self) {
+        let mut last = self.current();
+        info!(
+            "🚀 Starting market session watcher (state: {:?}, next: {:?} at {:?})",
+            last.state, last.next_state, last.next_transition_at
+        );
+
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(WATCH_INTERVAL_SECS)).await;
+
+            let session = self.current();
+            if session.state != last.state {
+                info!(
+                    "🔔 Market session changed: {:?} -> {:?}",
+                    last.state, session.state
+                );
+                self.websocket_service
+                    .broadcast_market_session_changed(last.state, session.clone())
+                    .await;
+            }
+            last = session;
+        }
+    }
+}
+
+/// Reject order placement when the calendar has the market closed
+pub fn ensure_accepting_orders(config: &MarketSessionConfig, now: DateTime<Utc>) -> Result<(), ApiError> {
+    let session = session_at(config, now);
+    if session.state.accepts_orders() {
+        return Ok(());
+    }
+
+    let reopens = session
+        .next_transition_at
+        .map(|at| format!("; orders are accepted again from {}", at.to_rfc3339()))
+        .unwrap_or_default();
+    Err(ApiError::with_code(
+        ErrorCode::TradingNotAllowed,
+        format!("Market is closed{}", reopens),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+
+    /// UTC+7, 06:00-22:00 weekdays, 30 minute pre-open
+    fn config() -> MarketSessionConfig {
+        MarketSessionConfig {
+            enabled: true,
+            utc_offset_minutes: 420,
+            open_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            close_time: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            pre_open_minutes: 30,
+            trading_days: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+            holidays: vec![NaiveDate::from_ymd_opt(2026, 1, 14).unwrap()],
+        }
+    }
+
+    /// Market-local time on a January 2026 day
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        at_local(
+            &config(),
+            NaiveDate::from_ymd_opt(2026, 1, day).unwrap(),
+            NaiveTime::from_hms_opt(hour, minute, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_disabled_calendar_is_always_open() {
+        let session = session_at(&MarketSessionConfig::default(), local(17, 3, 0));
+        assert_eq!(session.state, SessionState::Open);
+        assert!(!session.calendar_enabled);
+    }
+
+    #[test]
+    fn test_session_phases_within_a_trading_day() {
+        // Thursday 15 January 2026
+        let before = session_at(&config(), local(15, 5, 0));
+        assert_eq!(before.state, SessionState::Closed);
+        assert_eq!(before.next_state, Some(SessionState::PreOpen));
+        assert_eq!(before.next_transition_at, Some(local(15, 5, 30)));
+
+        let pre_open = session_at(&config(), local(15, 5, 45));
+        assert_eq!(pre_open.state, SessionState::PreOpen);
+        assert_eq!(pre_open.next_transition_at, Some(local(15, 6, 0)));
+
+        let open = session_at(&config(), local(15, 12, 0));
+        assert_eq!(open.state, SessionState::Open);
+        assert_eq!(open.closes_at, Some(local(15, 22, 0)));
+    }
+
+    #[test]
+    fn test_after_close_points_to_next_trading_day() {
+        // Friday evening rolls over the weekend to Monday 19 January
+        let session = session_at(&config(), local(16, 22, 30));
+        assert_eq!(session.state, SessionState::Closed);
+        assert_eq!(session.session_date, NaiveDate::from_ymd_opt(2026, 1, 19));
+        assert_eq!(session.next_transition_at, Some(local(19, 5, 30)));
+    }
+
+    #[test]
+    fn test_holiday_is_closed() {
+        let session = session_at(&config(), local(14, 12, 0));
+        assert_eq!(session.state, SessionState::Closed);
+        assert!(session.holiday);
+        assert_eq!(session.session_date, NaiveDate::from_ymd_opt(2026, 1, 15));
+    }
+
+    #[test]
+    fn test_without_pre_open_closed_goes_straight_to_open() {
+        let mut config = config();
+        config.pre_open_minutes = 0;
+        let session = session_at(&config, local(15, 5, 45));
+        assert_eq!(session.state, SessionState::Closed);
+        assert_eq!(session.next_state, Some(SessionState::Open));
+        assert_eq!(session.pre_open_at, None);
+    }
+
+    #[test]
+    fn test_order_acceptance() {
+        assert!(ensure_accepting_orders(&config(), local(15, 5, 45)).is_ok());
+        assert!(ensure_accepting_orders(&config(), local(17, 12, 0)).is_err());
+    }
+}
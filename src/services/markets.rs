@@ -0,0 +1,781 @@
+//! Market Registry
+//!
+//! Markets give each tradeable product its own orderbook: zonal energy spot
+//! markets, the ERC market and future products. Orders belong to exactly one
+//! market, the matching engine runs a separate matcher pass per active spot
+//! market, and per-market parameters (size limits, tick size, zone) are
+//! enforced when orders are placed. Remainders below a market's dust
+//! threshold are cancelled by the matchers rather than left on the book.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Row};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::schema::types::OrderSide;
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+use crate::services::market_analytics::{bid_ask_spread, DepthLevel};
+
+/// The original energy market, seeded by migration; orders without a market land here
+pub const DEFAULT_MARKET_ID: Uuid = Uuid::from_u128(1);
+
+/// Depth chart bucket for markets without a tick size
+const DEFAULT_DEPTH_BUCKET: Decimal = Decimal::from_parts(1, 0, 0, false, 2); // 0.01
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketType {
+    EnergySpot,
+    Erc,
+    Futures,
+}
+
+impl MarketType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EnergySpot => "energy_spot",
+            Self::Erc => "erc",
+            Self::Futures => "futures",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketStatus {
+    /// Accepting and matching orders
+    Active,
+    /// Resting orders are kept but nothing is accepted or matched
+    Halted,
+    /// Retired; requires an empty orderbook
+    Closed,
+}
+
+impl MarketStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Halted => "halted",
+            Self::Closed => "closed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct Market {
+    pub id: Uuid,
+    pub code: String,
+    pub name: String,
+    /// energy_spot, erc or futures
+    pub market_type: String,
+    pub zone_id: Option<i32>,
+    /// active, halted or closed
+    pub status: String,
+    #[schema(value_type = String)]
+    pub min_order_kwh: Decimal,
+    /// Largest order accepted; no limit when unset
+    #[schema(value_type = Option<String>)]
+    pub max_order_kwh: Option<Decimal>,
+    /// Remainders below this are cancelled; the minimum order size when unset
+    #[schema(value_type = Option<String>)]
+    pub dust_threshold_kwh: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub tick_size: Option<Decimal>,
+    /// Settlements of at least this value run at high priority
+    #[schema(value_type = Option<String>)]
+    pub settlement_high_notional: Option<Decimal>,
+    /// Settlements of at least this value run at urgent priority
+    #[schema(value_type = Option<String>)]
+    pub settlement_urgent_notional: Option<Decimal>,
+    /// Settlements with a corporate counterparty run at least at high priority
+    pub settlement_corporate_high: bool,
+    /// Settlements created this close to their epoch's end are raised one level
+    pub settlement_deadline_secs: Option<i32>,
+    pub is_default: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Market {
+    pub fn is_active(&self) -> bool {
+        self.status == MarketStatus::Active.as_str()
+    }
+
+    /// Orders placed through the trading endpoints go to energy spot markets only
+    pub fn accepts_energy_orders(&self) -> bool {
+        self.market_type == MarketType::EnergySpot.as_str()
+    }
+
+    /// Remainders below this size are dust and leave the book
+    pub fn dust_threshold(&self) -> Decimal {
+        self.dust_threshold_kwh.unwrap_or(self.min_order_kwh)
+    }
+
+    /// Whether an order with `remaining` kWh left is dust
+    pub fn is_dust(&self, remaining: Decimal) -> bool {
+        remaining > Decimal::ZERO && remaining < self.dust_threshold()
+    }
+}
+
+/// Aggregated orderbook of one market
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MarketOrderBook {
+    pub market_id: Uuid,
+    pub code: String,
+    pub status: String,
+    pub bids: Vec<DepthLevel>,
+    pub asks: Vec<DepthLevel>,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub spread: Option<f64>,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// One price bucket of a depth chart
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DepthChartPoint {
+    /// Bucket edge: bids round down, asks round up to it
+    #[schema(value_type = String)]
+    pub price_per_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub kwh: Decimal,
+    /// Volume from the best price out to this bucket
+    #[schema(value_type = String)]
+    pub cumulative_kwh: Decimal,
+    pub order_count: i64,
+}
+
+/// Band around the last clearing price
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PriceBand {
+    #[schema(value_type = String)]
+    pub band_pct: Decimal,
+    #[schema(value_type = String)]
+    pub lower: Decimal,
+    #[schema(value_type = String)]
+    pub upper: Decimal,
+}
+
+/// Bucketed cumulative depth of one market, ready to plot
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MarketDepthChart {
+    pub market_id: Uuid,
+    pub code: String,
+    #[schema(value_type = String)]
+    pub bucket_size: Decimal,
+    /// Best bid first
+    pub bids: Vec<DepthChartPoint>,
+    /// Best ask first
+    pub asks: Vec<DepthChartPoint>,
+    #[schema(value_type = Option<String>)]
+    pub best_bid: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub best_ask: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub mid_price: Option<Decimal>,
+    /// Volume-weighted price of the market's latest cleared matches
+    #[schema(value_type = Option<String>)]
+    pub last_clearing_price: Option<Decimal>,
+    /// Band around the last clearing price, when there is one
+    pub price_band: Option<PriceBand>,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// Group one side's price levels, best first, into `bucket`-wide buckets
+/// with running totals, keeping at most `max_levels` buckets
+pub fn bucket_depth(
+    levels: &[(Decimal, Decimal, i64)],
+    side: OrderSide,
+    bucket: Decimal,
+    max_levels: usize,
+) -> Vec<DepthChartPoint> {
+    let mut points: Vec<DepthChartPoint> = Vec::new();
+    let mut cumulative = Decimal::ZERO;
+    for &(price, kwh, order_count) in levels {
+        let steps = price / bucket;
+        let edge = match side {
+            OrderSide::Buy => steps.floor(),
+            OrderSide::Sell => steps.ceil(),
+        } * bucket;
+        cumulative += kwh;
+
+        match points.last_mut() {
+            Some(point) if point.price_per_kwh == edge => {
+                point.kwh += kwh;
+                point.cumulative_kwh = cumulative;
+                point.order_count += order_count;
+            }
+            _ if points.len() == max_levels => break,
+            _ => points.push(DepthChartPoint {
+                price_per_kwh: edge,
+                kwh,
+                cumulative_kwh: cumulative,
+                order_count,
+            }),
+        }
+    }
+    points
+}
+
+/// Adjustable market parameters
+#[derive(Debug, Clone)]
+pub struct MarketParams {
+    pub name: String,
+    pub zone_id: Option<i32>,
+    pub min_order_kwh: Decimal,
+    pub max_order_kwh: Option<Decimal>,
+    pub dust_threshold_kwh: Option<Decimal>,
+    pub tick_size: Option<Decimal>,
+    pub settlement_high_notional: Option<Decimal>,
+    pub settlement_urgent_notional: Option<Decimal>,
+    pub settlement_corporate_high: bool,
+    pub settlement_deadline_secs: Option<i32>,
+}
+
+/// Why an order does not fit a market, if it does not
+pub fn order_violation(
+    market: &Market,
+    zone_id: Option<i32>,
+    energy_amount: Decimal,
+    price_per_kwh: Option<Decimal>,
+) -> Option<String> {
+    if !market.accepts_energy_orders() {
+        return Some(format!("Market {} does not accept energy orders", market.code));
+    }
+    if !market.is_active() {
+        return Some(format!("Market {} is {}", market.code, market.status));
+    }
+    if let Some(market_zone) = market.zone_id {
+        if zone_id != Some(market_zone) {
+            return Some(format!("Market {} only accepts orders from zone {}", market.code, market_zone));
+        }
+    }
+    if energy_amount < market.min_order_kwh {
+        return Some(format!(
+            "Minimum order size in {} is {} kWh",
+            market.code,
+            market.min_order_kwh.normalize()
+        ));
+    }
+    if let Some(max) = market.max_order_kwh.filter(|max| energy_amount > *max) {
+        return Some(format!("Maximum order size in {} is {} kWh", market.code, max.normalize()));
+    }
+    if let (Some(tick), Some(price)) = (market.tick_size, price_per_kwh) {
+        if !(price % tick).is_zero() {
+            return Some(format!(
+                "Price must be a multiple of the {} tick size {}",
+                market.code,
+                tick.normalize()
+            ));
+        }
+    }
+    None
+}
+
+fn validate_params(params: &MarketParams) -> Result<()> {
+    if params.name.trim().is_empty() || params.name.len() > 100 {
+        return Err(ApiError::validation_field("name", "Name must be 1-100 characters"));
+    }
+    if params.min_order_kwh <= Decimal::ZERO {
+        return Err(ApiError::validation_field("min_order_kwh", "Minimum order size must be positive"));
+    }
+    if params.max_order_kwh.is_some_and(|max| max < params.min_order_kwh) {
+        return Err(ApiError::validation_field(
+            "max_order_kwh",
+            "Maximum order size must not be below the minimum",
+        ));
+    }
+    if params.dust_threshold_kwh.is_some_and(|d| d <= Decimal::ZERO) {
+        return Err(ApiError::validation_field("dust_threshold_kwh", "Dust threshold must be positive"));
+    }
+    // A higher threshold would cancel new orders the market accepts
+    if params.dust_threshold_kwh.is_some_and(|d| d > params.min_order_kwh) {
+        return Err(ApiError::validation_field(
+            "dust_threshold_kwh",
+            "Dust threshold must not exceed the minimum order size",
+        ));
+    }
+    if params.tick_size.is_some_and(|t| t <= Decimal::ZERO) {
+        return Err(ApiError::validation_field("tick_size", "Tick size must be positive"));
+    }
+    for (field, threshold) in [
+        ("settlement_high_notional", params.settlement_high_notional),
+        ("settlement_urgent_notional", params.settlement_urgent_notional),
+    ] {
+        if threshold.is_some_and(|t| t <= Decimal::ZERO) {
+            return Err(ApiError::validation_field(field, "Settlement priority thresholds must be positive"));
+        }
+    }
+    if let (Some(high), Some(urgent)) = (params.settlement_high_notional, params.settlement_urgent_notional) {
+        if urgent < high {
+            return Err(ApiError::validation_field(
+                "settlement_urgent_notional",
+                "Urgent threshold must not be below the high threshold",
+            ));
+        }
+    }
+    if params.settlement_deadline_secs.is_some_and(|s| s <= 0) {
+        return Err(ApiError::validation_field("settlement_deadline_secs", "Deadline window must be positive"));
+    }
+    Ok(())
+}
+
+const MARKET_COLUMNS: &str = "id, code, name, market_type, zone_id, status, min_order_kwh, max_order_kwh, \
+                              dust_threshold_kwh, tick_size, settlement_high_notional, settlement_urgent_notional, settlement_corporate_high, \
+                              settlement_deadline_secs, is_default, created_at, updated_at";
+
+#[derive(Clone, Debug)]
+pub struct MarketService {
+    db: PgPool,
+}
+
+impl MarketService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// All markets, default first
+    pub async fn list(&self) -> Result<Vec<Market>> {
+        Ok(sqlx::query_as::<_, Market>(&format!(
+            "SELECT {} FROM markets ORDER BY is_default DESC, code",
+            MARKET_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn get(&self, market_id: Uuid) -> Result<Market> {
+        sqlx::query_as::<_, Market>(&format!("SELECT {} FROM markets WHERE id = $1", MARKET_COLUMNS))
+            .bind(market_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Market not found".to_string()))
+    }
+
+    /// Market by its code, case-insensitively
+    pub async fn by_code(&self, code: &str) -> Result<Market> {
+        sqlx::query_as::<_, Market>(&format!("SELECT {} FROM markets WHERE code = $1", MARKET_COLUMNS))
+            .bind(code.trim().to_uppercase())
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Unknown market {}", code)))
+    }
+
+    /// The requested market, or the default market when none is given
+    pub async fn resolve(&self, market_id: Option<Uuid>) -> Result<Market> {
+        self.get(market_id.unwrap_or(DEFAULT_MARKET_ID)).await
+    }
+
+    /// Energy spot markets that the matching engine should run
+    pub async fn active_spot_markets(&self) -> Result<Vec<Market>> {
+        Ok(sqlx::query_as::<_, Market>(&format!(
+            "SELECT {} FROM markets WHERE status = 'active' AND market_type = 'energy_spot' ORDER BY is_default DESC, code",
+            MARKET_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn create(
+        &self,
+        admin_id: Uuid,
+        code: &str,
+        market_type: MarketType,
+        params: MarketParams,
+        audit_logger: &AuditLogger,
+    ) -> Result<Market> {
+        validate_params(&params)?;
+        let code = code.trim().to_uppercase();
+        if code.is_empty()
+            || code.len() > 32
+            || !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(ApiError::validation_field(
+                "code",
+                "Code must be 1-32 letters, digits or hyphens",
+            ));
+        }
+
+        let market = sqlx::query_as::<_, Market>(&format!(
+            r#"
+            INSERT INTO markets (
+                code, name, market_type, zone_id, min_order_kwh, tick_size, created_by,
+                settlement_high_notional, settlement_urgent_notional, settlement_corporate_high,
+                settlement_deadline_secs, max_order_kwh, dust_threshold_kwh
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (code) DO NOTHING
+            RETURNING {}
+            "#,
+            MARKET_COLUMNS
+        ))
+        .bind(&code)
+        .bind(params.name.trim())
+        .bind(market_type.as_str())
+        .bind(params.zone_id)
+        .bind(params.min_order_kwh)
+        .bind(params.tick_size)
+        .bind(admin_id)
+        .bind(params.settlement_high_notional)
+        .bind(params.settlement_urgent_notional)
+        .bind(params.settlement_corporate_high)
+        .bind(params.settlement_deadline_secs)
+        .bind(params.max_order_kwh)
+        .bind(params.dust_threshold_kwh)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::Conflict(format!("Market {} already exists", code)))?;
+
+        info!("🏛️ Admin {} created market {} ({})", admin_id, market.code, market.market_type);
+        self.audit(admin_id, "market_created", &market, audit_logger);
+        Ok(market)
+    }
+
+    pub async fn update(
+        &self,
+        admin_id: Uuid,
+        market_id: Uuid,
+        params: MarketParams,
+        audit_logger: &AuditLogger,
+    ) -> Result<Market> {
+        validate_params(&params)?;
+
+        let market = sqlx::query_as::<_, Market>(&format!(
+            r#"
+            UPDATE markets
+            SET name = $2, zone_id = $3, min_order_kwh = $4, tick_size = $5,
+                settlement_high_notional = $6, settlement_urgent_notional = $7,
+                settlement_corporate_high = $8, settlement_deadline_secs = $9, max_order_kwh = $10,
+                dust_threshold_kwh = $11, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            MARKET_COLUMNS
+        ))
+        .bind(market_id)
+        .bind(params.name.trim())
+        .bind(params.zone_id)
+        .bind(params.min_order_kwh)
+        .bind(params.tick_size)
+        .bind(params.settlement_high_notional)
+        .bind(params.settlement_urgent_notional)
+        .bind(params.settlement_corporate_high)
+        .bind(params.settlement_deadline_secs)
+        .bind(params.max_order_kwh)
+        .bind(params.dust_threshold_kwh)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Market not found".to_string()))?;
+
+        self.audit(admin_id, "market_updated", &market, audit_logger);
+        Ok(market)
+    }
+
+    /// Halt, resume or close a market
+    pub async fn set_status(
+        &self,
+        admin_id: Uuid,
+        market_id: Uuid,
+        status: MarketStatus,
+        audit_logger: &AuditLogger,
+    ) -> Result<Market> {
+        let current = self.get(market_id).await?;
+        if status == MarketStatus::Closed {
+            if current.is_default {
+                return Err(ApiError::Conflict("The default market cannot be closed".to_string()));
+            }
+            let open_orders: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM trading_orders WHERE market_id = $1 AND status IN ('pending', 'active', 'partially_filled')",
+            )
+            .bind(market_id)
+            .fetch_one(&self.db)
+            .await?;
+            if open_orders > 0 {
+                return Err(ApiError::Conflict(format!(
+                    "Market has {} open order(s); cancel them before closing",
+                    open_orders
+                )));
+            }
+        }
+
+        let market = sqlx::query_as::<_, Market>(&format!(
+            "UPDATE markets SET status = $2, updated_at = NOW() WHERE id = $1 RETURNING {}",
+            MARKET_COLUMNS
+        ))
+        .bind(market_id)
+        .bind(status.as_str())
+        .fetch_one(&self.db)
+        .await?;
+
+        info!("🏛️ Admin {} set market {} to {}", admin_id, market.code, market.status);
+        self.audit(admin_id, &format!("market_{}", market.status), &market, audit_logger);
+        Ok(market)
+    }
+
+    /// Open orders of a market aggregated into price levels
+    pub async fn order_book(&self, market_id: Uuid, levels: i64) -> Result<MarketOrderBook> {
+        let market = self.get(market_id).await?;
+        let bids = self.book_side(market_id, "buy", "DESC", levels).await?;
+        let asks = self.book_side(market_id, "sell", "ASC", levels).await?;
+
+        let best_bid = bids.first().map(|l| l.price_per_kwh);
+        let best_ask = asks.first().map(|l| l.price_per_kwh);
+
+        Ok(MarketOrderBook {
+            market_id,
+            code: market.code,
+            status: market.status,
+            spread: bid_ask_spread(best_bid, best_ask),
+            bids,
+            asks,
+            best_bid,
+            best_ask,
+            captured_at: Utc::now(),
+        })
+    }
+
+    /// Depth chart of a market's open orders in `bucket`-wide price
+    /// buckets, `bucket` defaulting to the tick size, with the last clearing
+    /// price and a `band_pct` percent band around it
+    pub async fn depth_chart(
+        &self,
+        market_id: Uuid,
+        bucket: Option<Decimal>,
+        max_levels: usize,
+        band_pct: Decimal,
+    ) -> Result<MarketDepthChart> {
+        let market = self.get(market_id).await?;
+        let bucket_size = bucket
+            .or(market.tick_size)
+            .unwrap_or(DEFAULT_DEPTH_BUCKET);
+
+        let levels = sqlx::query_as::<_, (String, Decimal, Decimal, i64)>(
+            r#"
+            SELECT side::text, price_per_kwh,
+                   SUM(energy_amount - COALESCE(filled_amount, 0)),
+                   COUNT(*)
+            FROM trading_orders
+            WHERE market_id = $1
+              AND status IN ('pending', 'active', 'partially_filled')
+              AND trigger_type IS NULL
+              AND price_per_kwh IS NOT NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+            GROUP BY side, price_per_kwh
+            "#,
+        )
+        .bind(market_id)
+        .fetch_all(&self.db);
+
+        // Latest epoch the market cleared in, priced by volume
+        let last_clearing_price = sqlx::query_scalar::<_, Option<Decimal>>(
+            r#"
+            SELECT SUM(matched_amount * match_price) / NULLIF(SUM(matched_amount), 0)
+            FROM order_matches
+            WHERE market_id = $1
+              AND epoch_id = (
+                  SELECT epoch_id FROM order_matches
+                  WHERE market_id = $1
+                  ORDER BY match_time DESC
+                  LIMIT 1
+              )
+            "#,
+        )
+        .bind(market_id)
+        .fetch_one(&self.db);
+
+        let (levels, last_clearing_price) = tokio::try_join!(levels, last_clearing_price)?;
+
+        let side = |name: &str| {
+            levels
+                .iter()
+                .filter(|(s, ..)| s == name)
+                .map(|(_, price, kwh, count)| (*price, *kwh, *count))
+                .collect::<Vec<_>>()
+        };
+        let mut bids = side("buy");
+        bids.sort_by(|a, b| b.0.cmp(&a.0));
+        let mut asks = side("sell");
+        asks.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let best_bid = bids.first().map(|l| l.0);
+        let best_ask = asks.first().map(|l| l.0);
+        let hundred = Decimal::from(100);
+
+        Ok(MarketDepthChart {
+            market_id,
+            code: market.code,
+            bucket_size,
+            bids: bucket_depth(&bids, OrderSide::Buy, bucket_size, max_levels),
+            asks: bucket_depth(&asks, OrderSide::Sell, bucket_size, max_levels),
+            best_bid,
+            best_ask,
+            mid_price: best_bid.zip(best_ask).map(|(bid, ask)| (bid + ask) / Decimal::TWO),
+            last_clearing_price,
+            price_band: last_clearing_price.map(|price| PriceBand {
+                band_pct,
+                lower: price * (hundred - band_pct) / hundred,
+                upper: price * (hundred + band_pct) / hundred,
+            }),
+            captured_at: Utc::now(),
+        })
+    }
+
+    async fn book_side(&self, market_id: Uuid, side: &str, order: &str, levels: i64) -> Result<Vec<DepthLevel>> {
+        // `order` is one of two literals chosen above, never user input
+        let query = format!(
+            r#"
+            SELECT
+                price_per_kwh,
+                SUM(energy_amount - COALESCE(filled_amount, 0)) AS total_kwh,
+                COUNT(*) AS order_count
+            FROM trading_orders
+            WHERE market_id = $1
+              AND side::text = $2
+              AND status IN ('pending', 'active', 'partially_filled')
+              AND trigger_type IS NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+            GROUP BY price_per_kwh
+            ORDER BY price_per_kwh {}
+            LIMIT $3
+            "#,
+            order
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(market_id)
+            .bind(side)
+            .bind(levels)
+            .fetch_all(&self.db)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DepthLevel {
+                price_per_kwh: row.get::<Decimal, _>("price_per_kwh").to_f64().unwrap_or(0.0),
+                total_kwh: row.get::<Decimal, _>("total_kwh").to_f64().unwrap_or(0.0),
+                order_count: row.get("order_count"),
+            })
+            .collect())
+    }
+
+    fn audit(&self, admin_id: Uuid, action: &str, market: &Market, audit_logger: &AuditLogger) {
+        audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "market_id": market.id,
+                "code": market.code,
+                "status": market.status,
+                "zone_id": market.zone_id,
+                "min_order_kwh": market.min_order_kwh,
+                "max_order_kwh": market.max_order_kwh,
+                "dust_threshold_kwh": market.dust_threshold_kwh,
+                "tick_size": market.tick_size,
+                "settlement_high_notional": market.settlement_high_notional,
+                "settlement_urgent_notional": market.settlement_urgent_notional,
+                "settlement_corporate_high": market.settlement_corporate_high,
+                "settlement_deadline_secs": market.settlement_deadline_secs,
+            })
+            .to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spot() -> Market {
+        Market {
+            id: DEFAULT_MARKET_ID,
+            code: "SPOT-Z1".to_string(),
+            name: "Zone 1 Spot".to_string(),
+            market_type: "energy_spot".to_string(),
+            zone_id: Some(1),
+            status: "active".to_string(),
+            min_order_kwh: Decimal::ONE,
+            max_order_kwh: Some(Decimal::from(100)),
+            dust_threshold_kwh: None,
+            tick_size: Some(Decimal::new(5, 2)),
+            settlement_high_notional: None,
+            settlement_urgent_notional: None,
+            settlement_corporate_high: false,
+            settlement_deadline_secs: None,
+            is_default: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_accepts_order_within_parameters() {
+        let price = Some(Decimal::new(315, 2));
+        assert_eq!(order_violation(&spot(), Some(1), Decimal::from(2), price), None);
+        // Market orders carry no price to check against the tick size
+        assert_eq!(order_violation(&spot(), Some(1), Decimal::from(2), None), None);
+    }
+
+    #[test]
+    fn test_rejects_orders_outside_market_parameters() {
+        let price = Some(Decimal::new(315, 2));
+        assert!(order_violation(&spot(), Some(2), Decimal::from(2), price).is_some());
+        assert!(order_violation(&spot(), None, Decimal::from(2), price).is_some());
+        assert!(order_violation(&spot(), Some(1), Decimal::new(5, 1), price).is_some());
+        assert!(order_violation(&spot(), Some(1), Decimal::from(101), price).is_some());
+        assert!(order_violation(&spot(), Some(1), Decimal::from(2), Some(Decimal::new(313, 2))).is_some());
+
+        let halted = Market { status: "halted".to_string(), ..spot() };
+        assert!(order_violation(&halted, Some(1), Decimal::from(2), price).is_some());
+
+        let erc = Market { market_type: "erc".to_string(), ..spot() };
+        assert!(order_violation(&erc, Some(1), Decimal::from(2), price).is_some());
+    }
+
+    #[test]
+    fn test_dust_threshold_defaults_to_minimum_size() {
+        let market = spot();
+        assert!(market.is_dust(Decimal::new(5, 1)));
+        assert!(!market.is_dust(Decimal::ONE));
+        // Nothing left is filled, not dust
+        assert!(!market.is_dust(Decimal::ZERO));
+
+        let market = Market { dust_threshold_kwh: Some(Decimal::new(1, 2)), ..spot() };
+        assert!(!market.is_dust(Decimal::new(5, 1)));
+        assert!(market.is_dust(Decimal::new(5, 3)));
+    }
+
+    #[test]
+    fn test_depth_buckets_accumulate_from_the_best_price() {
+        let bucket = Decimal::new(10, 2);
+        let bids = [
+            (Decimal::new(318, 2), Decimal::from(2), 1),
+            (Decimal::new(312, 2), Decimal::from(3), 2),
+            (Decimal::new(295, 2), Decimal::from(5), 1),
+        ];
+        let points = bucket_depth(&bids, OrderSide::Buy, bucket, 10);
+        // 3.18 and 3.12 both round down into 3.10
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].price_per_kwh, Decimal::new(310, 2));
+        assert_eq!(points[0].kwh, Decimal::from(5));
+        assert_eq!(points[0].order_count, 3);
+        assert_eq!(points[1].price_per_kwh, Decimal::new(290, 2));
+        assert_eq!(points[1].cumulative_kwh, Decimal::from(10));
+
+        let asks = [(Decimal::new(321, 2), Decimal::from(4), 1)];
+        assert_eq!(bucket_depth(&asks, OrderSide::Sell, bucket, 10)[0].price_per_kwh, Decimal::new(330, 2));
+    }
+
+    #[test]
+    fn test_depth_buckets_stop_at_max_levels() {
+        let asks: Vec<_> = (1..=5).map(|p| (Decimal::from(p), Decimal::ONE, 1)).collect();
+        let points = bucket_depth(&asks, OrderSide::Sell, Decimal::ONE, 3);
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[2].cumulative_kwh, Decimal::from(3));
+    }
+}
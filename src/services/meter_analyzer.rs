@@ -133,6 +133,30 @@ pub fn check_alerts<T: ReadingData>(
     alerts
 }
 
+/// Output above rated capacity tolerated before alerting, for meter and
+/// rating inaccuracy
+const CAPACITY_TOLERANCE: f64 = 1.1;
+
+/// Alert when a reading's power exceeds the installation's effective capacity
+pub fn check_capacity(meter_id: &str, power_kw: f64, capacity_kw: f64) -> Option<MeterAlert> {
+    let threshold = capacity_kw * CAPACITY_TOLERANCE;
+    if power_kw <= threshold {
+        return None;
+    }
+    Some(MeterAlert {
+        meter_id: meter_id.to_string(),
+        alert_type: "over_capacity".to_string(),
+        value: power_kw,
+        threshold,
+        severity: AlertSeverity::Critical,
+        message: format!(
+            "Power {:.2}kW exceeds rated capacity {:.2}kW",
+            power_kw, capacity_kw
+        ),
+        timestamp: Utc::now(),
+    })
+}
+
 /// Calculate health score (0-100) based on electrical parameters
 pub fn calculate_health_score<T: ReadingData>(data: &T) -> f64 {
     let mut total_weight = 0.0;
@@ -0,0 +1,445 @@
+//! Meter Firmware Service
+//!
+//! Tracks the firmware version each meter reports with its readings and
+//! enforces admin-managed minimum versions per manufacturer. Readings from
+//! meters below the minimum are flagged or rejected depending on the policy.
+
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+
+/// What happens to readings from firmware below the minimum
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FirmwareAction {
+    Warn,
+    Reject,
+}
+
+impl FirmwareAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Warn => "warn",
+            Self::Reject => "reject",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "warn" => Some(Self::Warn),
+            "reject" => Some(Self::Reject),
+            _ => None,
+        }
+    }
+}
+
+/// Minimum firmware version for a manufacturer
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FirmwarePolicy {
+    pub id: Uuid,
+    pub manufacturer: String,
+    pub min_version: String,
+    pub action: FirmwareAction,
+    pub note: Option<String>,
+    pub updated_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct PolicyRow {
+    id: Uuid,
+    manufacturer: String,
+    min_version: String,
+    action: String,
+    note: Option<String>,
+    updated_by: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<PolicyRow> for FirmwarePolicy {
+    fn from(row: PolicyRow) -> Self {
+        Self {
+            id: row.id,
+            manufacturer: row.manufacturer,
+            min_version: row.min_version,
+            action: FirmwareAction::parse(&row.action).unwrap_or(FirmwareAction::Warn),
+            note: row.note,
+            updated_by: row.updated_by,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// Firmware status of a meter against its manufacturer's policy
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FirmwareStatus {
+    /// At or above the minimum version
+    Compliant,
+    /// Below the minimum version (or an unparseable version under a policy)
+    Deprecated,
+    /// The meter has not reported a firmware version
+    Unreported,
+    /// No policy for the meter's manufacturer
+    NoPolicy,
+}
+
+/// Outcome of checking a reading's firmware
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirmwareVerdict {
+    pub status: FirmwareStatus,
+    pub firmware_version: Option<String>,
+    pub min_version: Option<String>,
+    pub action: Option<FirmwareAction>,
+}
+
+impl FirmwareVerdict {
+    /// Reading must be refused
+    pub fn rejects(&self) -> bool {
+        self.status == FirmwareStatus::Deprecated && self.action == Some(FirmwareAction::Reject)
+    }
+
+    /// Reading is accepted but the firmware should be flagged
+    pub fn warns(&self) -> bool {
+        self.status == FirmwareStatus::Deprecated && self.action == Some(FirmwareAction::Warn)
+    }
+
+    pub fn message(&self) -> String {
+        format!(
+            "Firmware {} is below the minimum supported version {}",
+            self.firmware_version.as_deref().unwrap_or("unknown"),
+            self.min_version.as_deref().unwrap_or("unknown")
+        )
+    }
+}
+
+/// Numeric components of a version string: `v1.2.3-beta` → `[1, 2, 3]`
+pub fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim();
+    let version = version
+        .strip_prefix('v')
+        .or_else(|| version.strip_prefix('V'))
+        .unwrap_or(version);
+    let core = version.split(['-', '+']).next()?;
+    if core.is_empty() {
+        return None;
+    }
+    core.split('.').map(|part| part.parse::<u64>().ok()).collect()
+}
+
+/// Compare two versions component-wise; missing components count as zero
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let a = parse_version(a)?;
+    let b = parse_version(b)?;
+    let len = a.len().max(b.len());
+    for i in 0..len {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return Some(ordering);
+        }
+    }
+    Some(Ordering::Equal)
+}
+
+/// Status of a firmware version under a policy's minimum
+pub fn evaluate(firmware_version: Option<&str>, min_version: Option<&str>) -> FirmwareStatus {
+    match (firmware_version, min_version) {
+        (None, _) => FirmwareStatus::Unreported,
+        (Some(_), None) => FirmwareStatus::NoPolicy,
+        (Some(version), Some(min)) => match compare_versions(version, min) {
+            Some(Ordering::Less) | None => FirmwareStatus::Deprecated,
+            Some(_) => FirmwareStatus::Compliant,
+        },
+    }
+}
+
+/// Meters sharing a manufacturer and firmware version
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FirmwareFleetEntry {
+    pub manufacturer: Option<String>,
+    pub firmware_version: Option<String>,
+    pub meter_count: i64,
+    pub status: FirmwareStatus,
+    /// Most recent firmware change among these meters
+    pub last_updated_at: Option<DateTime<Utc>>,
+}
+
+/// Firmware versions across the meter fleet
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FirmwareFleetReport {
+    pub generated_at: DateTime<Utc>,
+    pub total_meters: i64,
+    pub compliant_meters: i64,
+    pub deprecated_meters: i64,
+    pub unreported_meters: i64,
+    pub versions: Vec<FirmwareFleetEntry>,
+    pub policies: Vec<FirmwarePolicy>,
+}
+
+#[derive(sqlx::FromRow)]
+struct FleetRow {
+    manufacturer: Option<String>,
+    firmware_version: Option<String>,
+    min_version: Option<String>,
+    meter_count: i64,
+    last_updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ReportedFirmware {
+    firmware_version: Option<String>,
+    min_version: Option<String>,
+    action: Option<String>,
+}
+
+const MAX_VERSION_LEN: usize = 50;
+
+const POLICY_COLUMNS: &str = "id, manufacturer, min_version, action, note, updated_by, created_at, updated_at";
+
+#[derive(Clone)]
+pub struct MeterFirmwareService {
+    db: PgPool,
+    audit_logger: AuditLogger,
+}
+
+impl MeterFirmwareService {
+    pub fn new(db: PgPool, audit_logger: AuditLogger) -> Self {
+        Self { db, audit_logger }
+    }
+
+    /// Record the firmware a meter reported with a reading and check it
+    /// against its manufacturer's policy. Without a reported version the
+    /// last known version is checked.
+    pub async fn check_reading(&self, meter_serial: &str, reported: Option<&str>) -> Result<FirmwareVerdict> {
+        // Versions that do not fit the registry column are treated as not reported
+        let reported = reported
+            .map(str::trim)
+            .filter(|v| !v.is_empty() && v.len() <= MAX_VERSION_LEN);
+
+        let row = sqlx::query_as::<_, ReportedFirmware>(
+            r#"
+            WITH updated AS (
+                UPDATE meter_registry
+                SET firmware_version = $2, firmware_updated_at = NOW()
+                WHERE meter_serial = $1
+                  AND $2::TEXT IS NOT NULL
+                  AND firmware_version IS DISTINCT FROM $2
+                RETURNING manufacturer, firmware_version
+            ),
+            m AS (
+                SELECT manufacturer, firmware_version FROM updated
+                UNION ALL
+                SELECT manufacturer, firmware_version FROM meter_registry
+                WHERE meter_serial = $1 AND NOT EXISTS (SELECT 1 FROM updated)
+            )
+            SELECT m.firmware_version, p.min_version, p.action
+            FROM m
+            LEFT JOIN meter_firmware_policies p ON LOWER(p.manufacturer) = LOWER(m.manufacturer)
+            "#,
+        )
+        .bind(meter_serial)
+        .bind(reported)
+        .fetch_optional(&self.db)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(FirmwareVerdict {
+                status: FirmwareStatus::Unreported,
+                firmware_version: reported.map(str::to_string),
+                min_version: None,
+                action: None,
+            });
+        };
+
+        Ok(FirmwareVerdict {
+            status: evaluate(row.firmware_version.as_deref(), row.min_version.as_deref()),
+            firmware_version: row.firmware_version,
+            min_version: row.min_version,
+            action: row.action.as_deref().and_then(FirmwareAction::parse),
+        })
+    }
+
+    pub async fn list_policies(&self) -> Result<Vec<FirmwarePolicy>> {
+        let rows = sqlx::query_as::<_, PolicyRow>(&format!(
+            "SELECT {POLICY_COLUMNS} FROM meter_firmware_policies ORDER BY manufacturer"
+        ))
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Create or replace the policy for a manufacturer
+    pub async fn set_policy(
+        &self,
+        admin_id: Uuid,
+        manufacturer: &str,
+        min_version: &str,
+        action: FirmwareAction,
+        note: Option<&str>,
+    ) -> Result<FirmwarePolicy> {
+        let manufacturer = manufacturer.trim();
+        let min_version = min_version.trim();
+        if manufacturer.is_empty() {
+            return Err(ApiError::validation_field("manufacturer", "Manufacturer is required"));
+        }
+        if parse_version(min_version).is_none() {
+            return Err(ApiError::validation_field(
+                "min_version",
+                "Version must be dot-separated numbers (e.g. 2.4.1)",
+            ));
+        }
+
+        let row = sqlx::query_as::<_, PolicyRow>(&format!(
+            r#"
+            INSERT INTO meter_firmware_policies (manufacturer, min_version, action, note, updated_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT ((LOWER(manufacturer))) DO UPDATE
+            SET min_version = EXCLUDED.min_version,
+                action = EXCLUDED.action,
+                note = EXCLUDED.note,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            RETURNING {POLICY_COLUMNS}
+            "#
+        ))
+        .bind(manufacturer)
+        .bind(min_version)
+        .bind(action.as_str())
+        .bind(note)
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        let policy = FirmwarePolicy::from(row);
+        self.audit(admin_id, "meter_firmware_policy_set", &policy);
+        info!(
+            "📟 Firmware policy for {}: minimum {} ({})",
+            policy.manufacturer,
+            policy.min_version,
+            policy.action.as_str()
+        );
+
+        Ok(policy)
+    }
+
+    pub async fn delete_policy(&self, admin_id: Uuid, policy_id: Uuid) -> Result<FirmwarePolicy> {
+        let row = sqlx::query_as::<_, PolicyRow>(&format!(
+            "DELETE FROM meter_firmware_policies WHERE id = $1 RETURNING {POLICY_COLUMNS}"
+        ))
+        .bind(policy_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Firmware policy not found".to_string()))?;
+
+        let policy = FirmwarePolicy::from(row);
+        self.audit(admin_id, "meter_firmware_policy_deleted", &policy);
+        Ok(policy)
+    }
+
+    /// Meter counts per manufacturer and firmware version with policy status
+    pub async fn fleet_report(&self) -> Result<FirmwareFleetReport> {
+        let rows = sqlx::query_as::<_, FleetRow>(
+            r#"
+            SELECT m.manufacturer, m.firmware_version, p.min_version,
+                   COUNT(*) AS meter_count,
+                   MAX(m.firmware_updated_at) AS last_updated_at
+            FROM meter_registry m
+            LEFT JOIN meter_firmware_policies p ON LOWER(p.manufacturer) = LOWER(m.manufacturer)
+            GROUP BY m.manufacturer, m.firmware_version, p.min_version
+            ORDER BY m.manufacturer NULLS LAST, COUNT(*) DESC
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut report = FirmwareFleetReport {
+            generated_at: Utc::now(),
+            total_meters: 0,
+            compliant_meters: 0,
+            deprecated_meters: 0,
+            unreported_meters: 0,
+            versions: Vec::with_capacity(rows.len()),
+            policies: self.list_policies().await?,
+        };
+
+        for row in rows {
+            let status = evaluate(row.firmware_version.as_deref(), row.min_version.as_deref());
+            report.total_meters += row.meter_count;
+            match status {
+                FirmwareStatus::Compliant => report.compliant_meters += row.meter_count,
+                FirmwareStatus::Deprecated => report.deprecated_meters += row.meter_count,
+                FirmwareStatus::Unreported => report.unreported_meters += row.meter_count,
+                FirmwareStatus::NoPolicy => {}
+            }
+            report.versions.push(FirmwareFleetEntry {
+                manufacturer: row.manufacturer,
+                firmware_version: row.firmware_version,
+                meter_count: row.meter_count,
+                status,
+                last_updated_at: row.last_updated_at,
+            });
+        }
+
+        Ok(report)
+    }
+
+    fn audit(&self, admin_id: Uuid, action: &str, policy: &FirmwarePolicy) {
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "policy_id": policy.id,
+                "manufacturer": policy.manufacturer,
+                "min_version": policy.min_version,
+                "action": policy.action.as_str(),
+            })
+            .to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("2.4.1"), Some(vec![2, 4, 1]));
+        assert_eq!(parse_version("v1.10-beta.2"), Some(vec![1, 10]));
+        assert_eq!(parse_version("3+build7"), Some(vec![3]));
+        assert_eq!(parse_version("rev-a"), None);
+        assert_eq!(parse_version(""), None);
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10.0", "1.9.9"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("2.0", "2.0.0"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("v2.3.1", "2.4"), Some(Ordering::Less));
+        assert_eq!(compare_versions("abc", "1.0"), None);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(evaluate(None, Some("1.0")), FirmwareStatus::Unreported);
+        assert_eq!(evaluate(Some("1.0"), None), FirmwareStatus::NoPolicy);
+        assert_eq!(evaluate(Some("2.1"), Some("2.0.5")), FirmwareStatus::Compliant);
+        assert_eq!(evaluate(Some("2.0.5"), Some("2.0.5")), FirmwareStatus::Compliant);
+        assert_eq!(evaluate(Some("1.9"), Some("2.0")), FirmwareStatus::Deprecated);
+        // Versions that cannot be compared cannot be shown to be supported
+        assert_eq!(evaluate(Some("custom"), Some("2.0")), FirmwareStatus::Deprecated);
+    }
+}
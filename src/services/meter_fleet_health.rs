@@ -0,0 +1,322 @@
+//! Meter Fleet Health
+//!
+//! Operator summary of the verified meter fleet: how many meters are
+//! reporting, signature failure and anomaly rates, firmware spread and the
+//! meters sending the fewest readings. Readings, signature checks and alerts
+//! are counted per meter and hour as they arrive; a periodic job assembles
+//! the report from those counters and caches it for the admin endpoint.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tokio::sync::RwLock;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::config::MeterFleetConfig;
+use crate::error::Result;
+
+/// Hours of counters the rates and reading counts cover
+pub const WINDOW_HOURS: i64 = 24;
+/// Meters listed as under-reporting
+const UNDER_REPORTING_LIMIT: i64 = 10;
+
+/// Readings a meter keeping the expected interval sends in `window_hours`
+pub fn expected_readings(window_hours: i64, interval_mins: i64) -> i64 {
+    window_hours * 60 / interval_mins.max(1)
+}
+
+/// Share of `total` that `part` makes up, 0 when nothing was counted
+pub fn ratio(part: i64, total: i64) -> f64 {
+    if total > 0 {
+        part as f64 / total as f64
+    } else {
+        0.0
+    }
+}
+
+/// Verified meters by time since their last accepted reading
+#[derive(Debug, Clone, Default, Serialize, ToSchema, FromRow)]
+pub struct ConnectivityCounts {
+    pub online: i64,
+    pub stale: i64,
+    /// Includes meters that never reported
+    pub offline: i64,
+    pub never_reported: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct SignatureHealth {
+    pub checks: i64,
+    pub failures: i64,
+    /// Failures per signed reading checked
+    pub failure_rate: f64,
+    pub failing_meters: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct AnomalyHealth {
+    /// Alerts raised by reading analysis
+    pub total: i64,
+    pub affected_meters: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema, FromRow)]
+pub struct FirmwareCount {
+    pub firmware_version: Option<String>,
+    pub meter_count: i64,
+}
+
+/// Reporting meter sending fewer readings than its expected interval allows
+#[derive(Debug, Clone, Serialize, ToSchema, FromRow)]
+pub struct UnderReportingMeter {
+    pub meter_serial: String,
+    pub zone_id: Option<i32>,
+    pub readings: i64,
+    pub expected_readings: i64,
+    /// Readings received per reading expected
+    pub completeness: f64,
+    pub last_reading_at: Option<DateTime<Utc>>,
+}
+
+/// Health of the verified meter fleet over the last `window_hours`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FleetHealthReport {
+    pub generated_at: DateTime<Utc>,
+    pub window_hours: i64,
+    pub total_meters: i64,
+    pub connectivity: ConnectivityCounts,
+    pub signatures: SignatureHealth,
+    pub anomalies: AnomalyHealth,
+    pub firmware: Vec<FirmwareCount>,
+    pub under_reporting: Vec<UnderReportingMeter>,
+}
+
+#[derive(FromRow)]
+struct CounterTotals {
+    signature_checks: i64,
+    signature_failures: i64,
+    failing_meters: i64,
+    anomalies: i64,
+    anomalous_meters: i64,
+}
+
+#[derive(FromRow)]
+struct ReadingCount {
+    meter_serial: String,
+    zone_id: Option<i32>,
+    readings: i64,
+    last_reading_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+pub struct MeterFleetHealthService {
+    db: PgPool,
+    config: MeterFleetConfig,
+    cached: Arc<RwLock<Option<FleetHealthReport>>>,
+}
+
+impl MeterFleetHealthService {
+    pub fn new(db: PgPool, config: MeterFleetConfig) -> Self {
+        Self {
+            db,
+            config,
+            cached: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Count an accepted reading and the alerts it raised.
+    ///
+    /// Failures are logged and ignored; health tracking must never block a reading.
+    pub async fn record_reading(&self, meter_serial: &str, anomalies: usize) {
+        let result: std::result::Result<_, sqlx::Error> = async {
+            sqlx::query(
+                r#"
+                INSERT INTO meter_health_hourly (meter_serial, hour, readings, anomalies)
+                VALUES ($1, date_trunc('hour', NOW()), 1, $2)
+                ON CONFLICT (meter_serial, hour) DO UPDATE
+                SET readings = meter_health_hourly.readings + 1,
+                    anomalies = meter_health_hourly.anomalies + EXCLUDED.anomalies
+                "#,
+            )
+            .bind(meter_serial)
+            .bind(anomalies as i32)
+            .execute(&self.db)
+            .await?;
+            sqlx::query("UPDATE meter_registry SET last_reading_at = NOW() WHERE meter_serial = $1")
+                .bind(meter_serial)
+                .execute(&self.db)
+                .await
+        }
+        .await;
+
+        if let Err(e) = result {
+            warn!("Failed to record reading health for meter {}: {}", meter_serial, e);
+        }
+    }
+
+    /// Count a signed reading's signature check
+    pub async fn record_signature_check(&self, meter_serial: &str, passed: bool) {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO meter_health_hourly (meter_serial, hour, signature_checks, signature_failures)
+            VALUES ($1, date_trunc('hour', NOW()), 1, $2)
+            ON CONFLICT (meter_serial, hour) DO UPDATE
+            SET signature_checks = meter_health_hourly.signature_checks + 1,
+                signature_failures = meter_health_hourly.signature_failures + EXCLUDED.signature_failures
+            "#,
+        )
+        .bind(meter_serial)
+        .bind(if passed { 0 } else { 1 })
+        .execute(&self.db)
+        .await;
+
+        if let Err(e) = result {
+            warn!("Failed to record signature check for meter {}: {}", meter_serial, e);
+        }
+    }
+
+    /// Last assembled report, building one if the job has not run yet
+    pub async fn report(&self) -> Result<FleetHealthReport> {
+        if let Some(report) = self.cached.read().await.clone() {
+            return Ok(report);
+        }
+        self.refresh().await
+    }
+
+    /// Assemble and cache a new report and prune expired counters
+    pub async fn refresh(&self) -> Result<FleetHealthReport> {
+        let report = self.assemble().await?;
+        *self.cached.write().await = Some(report.clone());
+
+        sqlx::query("DELETE FROM meter_health_hourly WHERE hour < NOW() - make_interval(days => $1::int)")
+            .bind(self.config.retention_days)
+            .execute(&self.db)
+            .await?;
+
+        Ok(report)
+    }
+
+    async fn assemble(&self) -> Result<FleetHealthReport> {
+        let expected = expected_readings(WINDOW_HOURS, self.config.expected_interval_mins);
+
+        let connectivity = sqlx::query_as::<_, ConnectivityCounts>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE last_reading_at >= NOW() - make_interval(mins => $1::int)) AS online,
+                COUNT(*) FILTER (WHERE last_reading_at < NOW() - make_interval(mins => $1::int)
+                                   AND last_reading_at >= NOW() - make_interval(hours => $2::int)) AS stale,
+                COUNT(*) FILTER (WHERE last_reading_at IS NULL
+                                    OR last_reading_at < NOW() - make_interval(hours => $2::int)) AS offline,
+                COUNT(*) FILTER (WHERE last_reading_at IS NULL) AS never_reported
+            FROM meter_registry
+            WHERE verification_status = 'verified'
+            "#,
+        )
+        .bind(self.config.stale_after_mins)
+        .bind(self.config.offline_after_hours);
+
+        let totals = sqlx::query_as::<_, CounterTotals>(
+            r#"
+            SELECT
+                COALESCE(SUM(signature_checks), 0)::BIGINT AS signature_checks,
+                COALESCE(SUM(signature_failures), 0)::BIGINT AS signature_failures,
+                COUNT(DISTINCT meter_serial) FILTER (WHERE signature_failures > 0) AS failing_meters,
+                COALESCE(SUM(anomalies), 0)::BIGINT AS anomalies,
+                COUNT(DISTINCT meter_serial) FILTER (WHERE anomalies > 0) AS anomalous_meters
+            FROM meter_health_hourly
+            WHERE hour > NOW() - make_interval(hours => $1::int)
+            "#,
+        )
+        .bind(WINDOW_HOURS);
+
+        let firmware = sqlx::query_as::<_, FirmwareCount>(
+            r#"
+            SELECT firmware_version, COUNT(*) AS meter_count
+            FROM meter_registry
+            WHERE verification_status = 'verified'
+            GROUP BY firmware_version
+            ORDER BY COUNT(*) DESC, firmware_version NULLS LAST
+            "#,
+        );
+
+        // Offline meters are already counted above; this lists meters that
+        // report, but not as often as they should
+        let under_reporting = sqlx::query_as::<_, ReadingCount>(
+            r#"
+            SELECT m.meter_serial, m.zone_id, COALESCE(SUM(h.readings), 0)::BIGINT AS readings,
+                   m.last_reading_at
+            FROM meter_registry m
+            LEFT JOIN meter_health_hourly h
+                ON h.meter_serial = m.meter_serial AND h.hour > NOW() - make_interval(hours => $1::int)
+            WHERE m.verification_status = 'verified'
+              AND m.last_reading_at >= NOW() - make_interval(hours => $2::int)
+            GROUP BY m.meter_serial, m.zone_id, m.last_reading_at
+            HAVING COALESCE(SUM(h.readings), 0) < $3
+            ORDER BY readings, m.meter_serial
+            LIMIT $4
+            "#,
+        )
+        .bind(WINDOW_HOURS)
+        .bind(self.config.offline_after_hours)
+        .bind(expected)
+        .bind(UNDER_REPORTING_LIMIT);
+
+        let (connectivity, totals, firmware, under_reporting) = tokio::try_join!(
+            connectivity.fetch_one(&self.db),
+            totals.fetch_one(&self.db),
+            firmware.fetch_all(&self.db),
+            under_reporting.fetch_all(&self.db),
+        )?;
+
+        Ok(FleetHealthReport {
+            generated_at: Utc::now(),
+            window_hours: WINDOW_HOURS,
+            total_meters: connectivity.online + connectivity.stale + connectivity.offline,
+            connectivity,
+            signatures: SignatureHealth {
+                checks: totals.signature_checks,
+                failures: totals.signature_failures,
+                failure_rate: ratio(totals.signature_failures, totals.signature_checks),
+                failing_meters: totals.failing_meters,
+            },
+            anomalies: AnomalyHealth {
+                total: totals.anomalies,
+                affected_meters: totals.anomalous_meters,
+            },
+            firmware,
+            under_reporting: under_reporting
+                .into_iter()
+                .map(|row| UnderReportingMeter {
+                    meter_serial: row.meter_serial,
+                    zone_id: row.zone_id,
+                    readings: row.readings,
+                    expected_readings: expected,
+                    completeness: ratio(row.readings, expected),
+                    last_reading_at: row.last_reading_at,
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_readings_per_window() {
+        assert_eq!(expected_readings(24, 15), 96);
+        assert_eq!(expected_readings(24, 60), 24);
+        // A zero interval must not divide by zero
+        assert_eq!(expected_readings(24, 0), 1440);
+    }
+
+    #[test]
+    fn test_ratio_of_empty_total_is_zero() {
+        assert_eq!(ratio(3, 0), 0.0);
+        assert_eq!(ratio(1, 4), 0.25);
+    }
+}
@@ -0,0 +1,311 @@
+//! Meter Gateway Service
+//!
+//! Registry of AMI gateways (utility data concentrators) identified by the
+//! SHA-256 fingerprint of their mTLS client certificate. TLS is terminated by
+//! a trusted reverse proxy; a gateway may only submit readings for meters
+//! bound to it in `meter_registry.gateway_id`.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+
+/// Registered AMI gateway
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct MeterGateway {
+    pub id: Uuid,
+    pub name: String,
+    pub operator: Option<String>,
+    /// SHA-256 of the DER-encoded client certificate (lowercase hex)
+    pub cert_fingerprint: String,
+    /// active or revoked
+    pub status: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// Meters bound to this gateway
+    pub meter_count: i64,
+}
+
+/// Authenticated gateway, stored in request extensions by the gateway auth middleware
+#[derive(Debug, Clone)]
+pub struct GatewayIdentity(pub MeterGateway);
+
+/// Normalise a SHA-256 fingerprint: accepts `AB:CD:...`, `sha256:abcd...` or bare hex
+pub fn normalize_fingerprint(value: &str) -> Option<String> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix("sha256:")
+        .or_else(|| value.strip_prefix("SHA256:"))
+        .unwrap_or(value);
+    let hex: String = value
+        .chars()
+        .filter(|c| *c != ':' && !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+
+    (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit())).then_some(hex)
+}
+
+/// SHA-256 fingerprint of a PEM-encoded certificate
+pub fn fingerprint_from_pem(pem: &str) -> Option<String> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let body: String = pem
+        .lines()
+        .map(str::trim)
+        .skip_while(|l| !l.starts_with("-----BEGIN CERTIFICATE-----"))
+        .skip(1)
+        .take_while(|l| !l.starts_with("-----END CERTIFICATE-----"))
+        .collect();
+    if body.is_empty() {
+        return None;
+    }
+
+    let der = general_purpose::STANDARD.decode(body).ok()?;
+    Some(hex::encode(Sha256::digest(&der)))
+}
+
+const GATEWAY_COLUMNS: &str = r#"
+    g.id, g.name, g.operator, g.cert_fingerprint, g.status, g.created_by,
+    g.created_at, g.revoked_at, g.last_seen_at,
+    (SELECT COUNT(*) FROM meter_registry m WHERE m.gateway_id = g.id) AS meter_count
+"#;
+
+#[derive(Clone)]
+pub struct MeterGatewayService {
+    db: PgPool,
+    audit_logger: AuditLogger,
+}
+
+impl MeterGatewayService {
+    pub fn new(db: PgPool, audit_logger: AuditLogger) -> Self {
+        Self { db, audit_logger }
+    }
+
+    /// Look up the active gateway for a certificate fingerprint and record the contact
+    pub async fn authenticate(&self, fingerprint: &str) -> Result<Option<MeterGateway>> {
+        let gateway = sqlx::query_as::<_, MeterGateway>(&format!(
+            r#"
+            WITH touched AS (
+                UPDATE meter_gateways SET last_seen_at = NOW()
+                WHERE cert_fingerprint = $1 AND status = 'active'
+                RETURNING *
+            )
+            SELECT {GATEWAY_COLUMNS} FROM touched g
+            "#
+        ))
+        .bind(fingerprint)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(gateway)
+    }
+
+    pub async fn list(&self) -> Result<Vec<MeterGateway>> {
+        let gateways = sqlx::query_as::<_, MeterGateway>(&format!(
+            "SELECT {GATEWAY_COLUMNS} FROM meter_gateways g ORDER BY g.status, g.name"
+        ))
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(gateways)
+    }
+
+    pub async fn get(&self, gateway_id: Uuid) -> Result<MeterGateway> {
+        sqlx::query_as::<_, MeterGateway>(&format!(
+            "SELECT {GATEWAY_COLUMNS} FROM meter_gateways g WHERE g.id = $1"
+        ))
+        .bind(gateway_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Meter gateway not found".to_string()))
+    }
+
+    pub async fn register(
+        &self,
+        admin_id: Uuid,
+        name: &str,
+        operator: Option<&str>,
+        fingerprint: &str,
+    ) -> Result<MeterGateway> {
+        let id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO meter_gateways (name, operator, cert_fingerprint, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(name)
+        .bind(operator)
+        .bind(fingerprint)
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::Conflict(
+                "An active gateway is already registered for this certificate".to_string(),
+            ),
+            _ => ApiError::Database(e),
+        })?;
+
+        let gateway = self.get(id).await?;
+        self.audit(admin_id, "meter_gateway_registered", &gateway, None);
+        info!("🔐 Meter gateway registered: {} ({})", gateway.name, gateway.id);
+
+        Ok(gateway)
+    }
+
+    /// Revoke a gateway; its certificate is rejected from then on
+    pub async fn revoke(&self, admin_id: Uuid, gateway_id: Uuid) -> Result<MeterGateway> {
+        let updated = sqlx::query(
+            r#"
+            UPDATE meter_gateways SET status = 'revoked', revoked_at = NOW()
+            WHERE id = $1 AND status = 'active'
+            "#,
+        )
+        .bind(gateway_id)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+
+        let gateway = self.get(gateway_id).await?;
+        if updated == 0 {
+            return Err(ApiError::Conflict("Meter gateway is already revoked".to_string()));
+        }
+
+        self.audit(admin_id, "meter_gateway_revoked", &gateway, None);
+        info!("🔐 Meter gateway revoked: {} ({})", gateway.name, gateway.id);
+
+        Ok(gateway)
+    }
+
+    /// Bind meters to a gateway (replacing any previous gateway binding).
+    /// Returns the serials that were not found in the registry.
+    pub async fn assign_meters(
+        &self,
+        admin_id: Uuid,
+        gateway_id: Uuid,
+        serials: &[String],
+    ) -> Result<Vec<String>> {
+        let gateway = self.get(gateway_id).await?;
+        if gateway.status != "active" {
+            return Err(ApiError::Conflict("Meter gateway is revoked".to_string()));
+        }
+
+        let assigned: Vec<String> = sqlx::query_scalar(
+            r#"
+            UPDATE meter_registry SET gateway_id = $1, updated_at = NOW()
+            WHERE meter_serial = ANY($2)
+            RETURNING meter_serial
+            "#,
+        )
+        .bind(gateway_id)
+        .bind(serials)
+        .fetch_all(&self.db)
+        .await?;
+
+        let assigned: HashSet<String> = assigned.into_iter().collect();
+        let missing: Vec<String> = serials
+            .iter()
+            .filter(|s| !assigned.contains(*s))
+            .cloned()
+            .collect();
+
+        self.audit(
+            admin_id,
+            "meter_gateway_meters_assigned",
+            &gateway,
+            Some(serde_json::json!({ "assigned": assigned.len(), "missing": missing })),
+        );
+
+        Ok(missing)
+    }
+
+    /// Subset of `serials` the gateway may submit readings for
+    pub async fn authorized_serials(
+        &self,
+        gateway_id: Uuid,
+        serials: &[String],
+    ) -> Result<HashSet<String>> {
+        let rows: Vec<String> = sqlx::query_scalar(
+            "SELECT meter_serial FROM meter_registry WHERE gateway_id = $1 AND meter_serial = ANY($2)",
+        )
+        .bind(gateway_id)
+        .bind(serials)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    fn audit(
+        &self,
+        admin_id: Uuid,
+        action: &str,
+        gateway: &MeterGateway,
+        extra: Option<serde_json::Value>,
+    ) {
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "gateway_id": gateway.id,
+                "name": gateway.name,
+                "cert_fingerprint": gateway.cert_fingerprint,
+                "extra": extra,
+            })
+            .to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FP: &str = "3f2a9c0d1e4b5a6978877665544332211000ffeeddccbbaa9988776655443322";
+
+    #[test]
+    fn test_normalize_fingerprint_formats() {
+        assert_eq!(normalize_fingerprint(FP).as_deref(), Some(FP));
+
+        let colon = FP
+            .as_bytes()
+            .chunks(2)
+            .map(|c| std::str::from_utf8(c).unwrap().to_uppercase())
+            .collect::<Vec<_>>()
+            .join(":");
+        assert_eq!(normalize_fingerprint(&colon).as_deref(), Some(FP));
+        assert_eq!(normalize_fingerprint(&format!("sha256:{}", FP)).as_deref(), Some(FP));
+    }
+
+    #[test]
+    fn test_normalize_fingerprint_rejects_invalid() {
+        assert!(normalize_fingerprint("").is_none());
+        // SHA-1 length
+        assert!(normalize_fingerprint("a94a8fe5ccb19ba61c4c0873d391e987982fbbd3").is_none());
+        assert!(normalize_fingerprint(&FP.replace('a', "z")).is_none());
+    }
+
+    #[test]
+    fn test_fingerprint_from_pem() {
+        // Body is base64("hello"); fingerprint is sha256("hello")
+        let pem = "-----BEGIN CERTIFICATE-----\naGVsbG8=\n-----END CERTIFICATE-----\n";
+        assert_eq!(
+            fingerprint_from_pem(pem).as_deref(),
+            Some("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824")
+        );
+        assert!(fingerprint_from_pem("not a certificate").is_none());
+    }
+}
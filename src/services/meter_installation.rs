@@ -0,0 +1,237 @@
+//! Meter Installations
+//!
+//! Structured installation data of registered meters: rated capacity, panel
+//! orientation, installation date and inverter. The effective capacity, the
+//! lower of the array and inverter ratings, bounds the minting guard's caps,
+//! flags readings whose power exceeds it and caps autopilot surplus
+//! forecasts.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{ApiError, Result};
+
+const INSTALLATION_COLUMNS: &str = "id, user_id, meter_serial, capacity_kw, panel_azimuth_deg, panel_tilt_deg, \
+    installation_date, inverter_manufacturer, inverter_model, inverter_capacity_kw, updated_at";
+
+/// Inverter converting the array's output
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate, ToSchema)]
+pub struct InverterDetails {
+    #[validate(length(max = 100))]
+    pub manufacturer: Option<String>,
+    #[validate(length(max = 100))]
+    pub model: Option<String>,
+    /// AC rating, kW
+    #[validate(custom(function = "crate::utils::validation::rules::capacity_kw"))]
+    #[schema(value_type = Option<String>, example = "5")]
+    pub capacity_kw: Option<Decimal>,
+}
+
+/// Installation behind a meter. On update, omitted fields are kept.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate, ToSchema)]
+pub struct MeterInstallation {
+    /// Rated (DC) capacity of the array, kW
+    #[validate(custom(function = "crate::utils::validation::rules::capacity_kw"))]
+    #[schema(value_type = Option<String>, example = "5.5")]
+    pub capacity_kw: Option<Decimal>,
+    /// Panel azimuth in degrees clockwise from north (180 faces south)
+    #[validate(custom(function = "crate::utils::validation::rules::azimuth_degrees"))]
+    #[schema(value_type = Option<String>, example = "180")]
+    pub panel_azimuth_deg: Option<Decimal>,
+    /// Panel tilt in degrees from horizontal
+    #[validate(custom(function = "crate::utils::validation::rules::tilt_degrees"))]
+    #[schema(value_type = Option<String>, example = "15")]
+    pub panel_tilt_deg: Option<Decimal>,
+    #[validate(custom(function = "crate::utils::validation::rules::not_future_date"))]
+    pub installation_date: Option<NaiveDate>,
+    #[validate(nested)]
+    pub inverter: Option<InverterDetails>,
+}
+
+impl MeterInstallation {
+    /// Output the installation can deliver: the array rating, clipped by the
+    /// inverter's. `None` when neither is known.
+    pub fn effective_capacity_kw(&self) -> Option<Decimal> {
+        let inverter = self.inverter.as_ref().and_then(|inverter| inverter.capacity_kw);
+        match (self.capacity_kw, inverter) {
+            (Some(array), Some(inverter)) => Some(array.min(inverter)),
+            (array, inverter) => array.or(inverter),
+        }
+    }
+}
+
+/// Most energy `capacity_kw` produces between `start` and `end`
+pub fn max_output_kwh(capacity_kw: Decimal, start: DateTime<Utc>, end: DateTime<Utc>) -> Decimal {
+    let secs = (end - start).num_seconds().max(0);
+    (capacity_kw * Decimal::from(secs) / Decimal::from(3600)).round_dp(3)
+}
+
+/// A meter's installation
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MeterInstallationRecord {
+    /// Meter registry ID
+    pub meter_id: Uuid,
+    pub meter_serial: String,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub installation: MeterInstallation,
+    /// Array rating clipped by the inverter's; used for minting caps and forecasts
+    #[schema(value_type = Option<String>)]
+    pub effective_capacity_kw: Option<Decimal>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow)]
+struct InstallationRow {
+    id: Uuid,
+    user_id: Uuid,
+    meter_serial: String,
+    capacity_kw: Option<Decimal>,
+    panel_azimuth_deg: Option<Decimal>,
+    panel_tilt_deg: Option<Decimal>,
+    installation_date: Option<NaiveDate>,
+    inverter_manufacturer: Option<String>,
+    inverter_model: Option<String>,
+    inverter_capacity_kw: Option<Decimal>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<InstallationRow> for MeterInstallationRecord {
+    fn from(row: InstallationRow) -> Self {
+        let inverter = InverterDetails {
+            manufacturer: row.inverter_manufacturer,
+            model: row.inverter_model,
+            capacity_kw: row.inverter_capacity_kw,
+        };
+        let installation = MeterInstallation {
+            capacity_kw: row.capacity_kw,
+            panel_azimuth_deg: row.panel_azimuth_deg,
+            panel_tilt_deg: row.panel_tilt_deg,
+            installation_date: row.installation_date,
+            inverter: (inverter != InverterDetails::default()).then_some(inverter),
+        };
+        Self {
+            meter_id: row.id,
+            meter_serial: row.meter_serial,
+            user_id: row.user_id,
+            effective_capacity_kw: installation.effective_capacity_kw(),
+            installation,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MeterInstallationService {
+    db: PgPool,
+}
+
+impl MeterInstallationService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn get(&self, meter_serial: &str) -> Result<MeterInstallationRecord> {
+        sqlx::query_as::<_, InstallationRow>(&format!(
+            "SELECT {} FROM meter_registry WHERE meter_serial = $1",
+            INSTALLATION_COLUMNS
+        ))
+        .bind(meter_serial)
+        .fetch_optional(&self.db)
+        .await?
+        .map(MeterInstallationRecord::from)
+        .ok_or_else(|| ApiError::NotFound(format!("Meter {} not found", meter_serial)))
+    }
+
+    /// Store the given fields of a meter's installation, keeping the rest
+    pub async fn update(&self, meter_serial: &str, installation: &MeterInstallation) -> Result<MeterInstallationRecord> {
+        let inverter = installation.inverter.clone().unwrap_or_default();
+        sqlx::query_as::<_, InstallationRow>(&format!(
+            r#"
+            UPDATE meter_registry SET
+                capacity_kw = COALESCE($2, capacity_kw),
+                panel_azimuth_deg = COALESCE($3, panel_azimuth_deg),
+                panel_tilt_deg = COALESCE($4, panel_tilt_deg),
+                installation_date = COALESCE($5, installation_date),
+                inverter_manufacturer = COALESCE($6, inverter_manufacturer),
+                inverter_model = COALESCE($7, inverter_model),
+                inverter_capacity_kw = COALESCE($8, inverter_capacity_kw),
+                updated_at = NOW()
+            WHERE meter_serial = $1
+            RETURNING {}
+            "#,
+            INSTALLATION_COLUMNS
+        ))
+        .bind(meter_serial)
+        .bind(installation.capacity_kw)
+        .bind(installation.panel_azimuth_deg)
+        .bind(installation.panel_tilt_deg)
+        .bind(installation.installation_date)
+        .bind(inverter.manufacturer.as_deref().map(str::trim))
+        .bind(inverter.model.as_deref().map(str::trim))
+        .bind(inverter.capacity_kw)
+        .fetch_optional(&self.db)
+        .await?
+        .map(MeterInstallationRecord::from)
+        .ok_or_else(|| ApiError::NotFound(format!("Meter {} not found", meter_serial)))
+    }
+
+    /// Effective capacity of a meter, if its installation has a rating
+    pub async fn effective_capacity(&self, meter_serial: &str) -> Result<Option<Decimal>> {
+        Ok(sqlx::query_scalar::<_, Option<Decimal>>(
+            "SELECT LEAST(capacity_kw, inverter_capacity_kw) FROM meter_registry WHERE meter_serial = $1",
+        )
+        .bind(meter_serial)
+        .fetch_optional(&self.db)
+        .await?
+        .flatten())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_inverter_clips_effective_capacity() {
+        let mut installation = MeterInstallation::default();
+        assert_eq!(installation.effective_capacity_kw(), None);
+
+        installation.capacity_kw = Some(Decimal::new(66, 1));
+        assert_eq!(installation.effective_capacity_kw(), Some(Decimal::new(66, 1)));
+
+        installation.inverter = Some(InverterDetails {
+            capacity_kw: Some(Decimal::from(5)),
+            ..Default::default()
+        });
+        assert_eq!(installation.effective_capacity_kw(), Some(Decimal::from(5)));
+    }
+
+    #[test]
+    fn test_installation_validation() {
+        let installation = MeterInstallation {
+            capacity_kw: Some(Decimal::ZERO),
+            panel_azimuth_deg: Some(Decimal::from(400)),
+            panel_tilt_deg: Some(Decimal::from(30)),
+            ..Default::default()
+        };
+        let errors = installation.validate().unwrap_err();
+        let fields = errors.field_errors();
+        assert!(fields.contains_key("capacity_kw"));
+        assert!(fields.contains_key("panel_azimuth_deg"));
+        assert!(!fields.contains_key("panel_tilt_deg"));
+    }
+
+    #[test]
+    fn test_max_output_over_an_epoch() {
+        let start = Utc.with_ymd_and_hms(2026, 1, 18, 10, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2026, 1, 18, 10, 15, 0).unwrap();
+        assert_eq!(max_output_kwh(Decimal::from(5), start, end), Decimal::new(125, 2));
+    }
+}
@@ -0,0 +1,184 @@
+//! Meter Registry Sync Service
+//!
+//! Verified meters get an account in the on-chain registry program (PDA
+//! seeded by `["meter", serial]`) as proof of ownership. This service
+//! creates the account through `BlockchainService` and records the PDA,
+//! registration signature and last sync on `meter_registry`, so the
+//! off-chain registry always says which meters are anchored on-chain.
+//! Meters whose registration failed are retried by the reconcile loop.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+use crate::services::{BlockchainService, WalletService};
+
+/// Longest sync error kept on the meter (RPC errors can be verbose)
+const MAX_SYNC_ERROR_LEN: usize = 500;
+
+/// On-chain registry state of a meter
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct MeterRegistrySync {
+    pub meter_id: Uuid,
+    pub meter_serial: String,
+    /// Registry account PDA, set once the account exists on-chain
+    pub registry_pda: Option<String>,
+    /// Signature of the transaction that created the account
+    pub registry_tx_signature: Option<String>,
+    pub registry_synced_at: Option<DateTime<Utc>>,
+    /// Why the last sync attempt failed, cleared on success
+    pub registry_sync_error: Option<String>,
+}
+
+#[derive(FromRow)]
+struct MeterRow {
+    meter_serial: String,
+    meter_type: Option<String>,
+    verification_status: String,
+}
+
+/// Map a meter type to the registry program's `MeterType` enum
+pub fn meter_type_to_onchain(meter_type: &str) -> u8 {
+    match meter_type {
+        "solar" => 0, // MeterType::Solar
+        _ => 3,       // MeterType::Grid (residential, commercial, industrial)
+    }
+}
+
+const SYNC_COLUMNS: &str = "id AS meter_id, meter_serial, registry_pda, registry_tx_signature, \
+                            registry_synced_at, registry_sync_error";
+
+#[derive(Clone)]
+pub struct MeterRegistrySyncService {
+    db: PgPool,
+    blockchain: BlockchainService,
+    wallet: WalletService,
+}
+
+impl MeterRegistrySyncService {
+    pub fn new(db: PgPool, blockchain: BlockchainService, wallet: WalletService) -> Self {
+        Self { db, blockchain, wallet }
+    }
+
+    /// Create the registry account of a verified meter if it is missing and
+    /// record the outcome. A failed registration is stored on the meter
+    /// (and retried by [`reconcile`](Self::reconcile)) rather than returned
+    /// as an error.
+    pub async fn sync_meter(&self, meter_id: Uuid) -> Result<MeterRegistrySync> {
+        let meter = sqlx::query_as::<_, MeterRow>(
+            "SELECT meter_serial, meter_type, verification_status FROM meter_registry WHERE id = $1",
+        )
+        .bind(meter_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::meter_not_found(&meter_id.to_string()))?;
+
+        if meter.verification_status != "verified" {
+            return Err(ApiError::Conflict(format!(
+                "Meter {} is {}; only verified meters are registered on-chain",
+                meter.meter_serial, meter.verification_status
+            )));
+        }
+
+        let meter_type = meter_type_to_onchain(meter.meter_type.as_deref().unwrap_or_default());
+        let outcome = match self.wallet.get_authority_keypair().await {
+            Ok(authority) => {
+                self.blockchain
+                    .ensure_meter_registered(&authority, &meter.meter_serial, meter_type)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        let record = match outcome {
+            Ok((pda, signature)) => {
+                sqlx::query_as::<_, MeterRegistrySync>(&format!(
+                    r#"
+                    UPDATE meter_registry
+                    SET registry_pda = $2,
+                        registry_tx_signature = COALESCE($3, registry_tx_signature),
+                        registry_synced_at = NOW(), registry_sync_error = NULL
+                    WHERE id = $1
+                    RETURNING {}
+                    "#,
+                    SYNC_COLUMNS
+                ))
+                .bind(meter_id)
+                .bind(pda.to_string())
+                .bind(signature.map(|s| s.to_string()))
+                .fetch_one(&self.db)
+                .await?
+            }
+            Err(e) => {
+                warn!("⚠️ Registry sync failed for meter {}: {}", meter.meter_serial, e);
+                let mut error = e.to_string();
+                error.truncate(MAX_SYNC_ERROR_LEN);
+                sqlx::query_as::<_, MeterRegistrySync>(&format!(
+                    "UPDATE meter_registry SET registry_sync_error = $2 WHERE id = $1 RETURNING {}",
+                    SYNC_COLUMNS
+                ))
+                .bind(meter_id)
+                .bind(error)
+                .fetch_one(&self.db)
+                .await?
+            }
+        };
+
+        Ok(record)
+    }
+
+    /// Sync in the background so a slow or unavailable RPC never holds up
+    /// the request that verified the meter
+    pub fn spawn_sync(&self, meter_id: Uuid) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.sync_meter(meter_id).await {
+                warn!("⚠️ Registry sync skipped for meter {}: {}", meter_id, e);
+            }
+        });
+    }
+
+    /// Sync verified meters that have no registry account on record yet;
+    /// returns how many are now anchored on-chain
+    pub async fn reconcile(&self, limit: i64) -> Result<usize> {
+        let pending: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM meter_registry
+            WHERE verification_status = 'verified' AND registry_synced_at IS NULL
+            ORDER BY registry_sync_error IS NOT NULL, created_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut synced = 0;
+        for meter_id in pending {
+            if self.sync_meter(meter_id).await?.registry_pda.is_some() {
+                synced += 1;
+            }
+        }
+        if synced > 0 {
+            info!("⛓️ Reconciled {} meters with the on-chain registry", synced);
+        }
+        Ok(synced)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_type_mapping() {
+        assert_eq!(meter_type_to_onchain("solar"), 0);
+        assert_eq!(meter_type_to_onchain("residential"), 3);
+        assert_eq!(meter_type_to_onchain(""), 3);
+    }
+}
@@ -0,0 +1,624 @@
+//! Minting Guard
+//!
+//! Caps the energy minted to a wallet at what the wallet owner's registered
+//! meters can physically produce: their effective capacity (kW, the array
+//! rating clipped by the inverter's) times a small headroom per hour, and
+//! times the daily full-load hours per day. Windows trail the reading's
+//! timestamp. A reading that would take its wallet over either cap is
+//! refused or, in quarantine mode, recorded without minting and held until
+//! an admin releases it (minting it regardless of the cap) or rejects it.
+//!
+//! The check runs in the transaction that records the reading and holds a
+//! per-wallet advisory lock until it commits. Readings recorded for minting
+//! count against the cap while their mint is pending, so concurrent readings
+//! of a wallet cannot both fit under it. A check that fails is treated as a
+//! breach: the reading is held rather than minted unchecked.
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::{Config, MintGuardConfig, MintGuardMode};
+use crate::error::{ApiError, Result};
+use crate::services::{AuditEvent, AuditLogger, BlockchainService, WalletService};
+
+const QUARANTINE_COLUMNS: &str = "id, reading_id, meter_serial, wallet_address, kwh_amount, reading_timestamp, \
+    breached_window, limit_kwh, minted_kwh, status, reviewed_by, reviewed_at, review_note, mint_tx_signature, \
+    error, created_at";
+
+/// Window a cap applies to, trailing the reading's timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardWindow {
+    Hour,
+    Day,
+    /// The wallet's minted total could not be read
+    Unchecked,
+}
+
+impl GuardWindow {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Unchecked => "unchecked",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuarantineStatus {
+    /// Awaiting review
+    Pending,
+    /// Released and being minted
+    Releasing,
+    Released,
+    Rejected,
+}
+
+impl QuarantineStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Releasing => "releasing",
+            Self::Released => "released",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+/// Caps of a wallet, kWh
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+pub struct MintCaps {
+    /// Summed rating of the owner's meters, kW
+    #[schema(value_type = String)]
+    pub capacity_kw: Decimal,
+    #[schema(value_type = String)]
+    pub hourly_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub daily_kwh: Decimal,
+}
+
+impl MintCaps {
+    pub fn for_capacity(config: &MintGuardConfig, capacity_kw: Decimal) -> Self {
+        Self {
+            capacity_kw,
+            hourly_kwh: capacity_kw * config.hourly_headroom,
+            daily_kwh: capacity_kw * config.daily_full_load_hours,
+        }
+    }
+
+    /// The first cap minting `kwh` would exceed, given what was already minted
+    pub fn assess(&self, hour_minted: Decimal, day_minted: Decimal, kwh: Decimal) -> Option<GuardBreach> {
+        [
+            (GuardWindow::Hour, self.hourly_kwh, hour_minted),
+            (GuardWindow::Day, self.daily_kwh, day_minted),
+        ]
+        .into_iter()
+        .find(|(_, limit, minted)| minted + kwh > *limit)
+        .map(|(window, limit_kwh, minted_kwh)| GuardBreach {
+            window,
+            limit_kwh,
+            minted_kwh,
+            kwh,
+        })
+    }
+}
+
+/// A reading that would exceed its wallet's cap
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardBreach {
+    pub window: GuardWindow,
+    pub limit_kwh: Decimal,
+    /// Already minted to the wallet within the window
+    pub minted_kwh: Decimal,
+    pub kwh: Decimal,
+}
+
+impl GuardBreach {
+    /// Breach for a reading whose wallet could not be checked
+    pub fn unchecked(kwh: Decimal) -> Self {
+        Self {
+            window: GuardWindow::Unchecked,
+            limit_kwh: Decimal::ZERO,
+            minted_kwh: Decimal::ZERO,
+            kwh,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        if self.window == GuardWindow::Unchecked {
+            return format!("{} kWh could not be checked against the wallet's cap", self.kwh.normalize());
+        }
+        format!(
+            "{} kWh would exceed the wallet's cap of {} kWh per {} ({} kWh already minted)",
+            self.kwh.normalize(),
+            self.limit_kwh.round_dp(3).normalize(),
+            self.window.as_str(),
+            self.minted_kwh.normalize()
+        )
+    }
+}
+
+/// A wallet's caps and what it has minted in the trailing windows
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WalletMintAllowance {
+    pub wallet_address: String,
+    /// Registered meters of the wallet's owner
+    pub meters: i64,
+    pub caps: MintCaps,
+    #[schema(value_type = String)]
+    pub minted_last_hour_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub minted_last_day_kwh: Decimal,
+}
+
+/// A reading held back by the guard
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct QuarantinedReading {
+    pub id: Uuid,
+    pub reading_id: Uuid,
+    pub meter_serial: String,
+    pub wallet_address: String,
+    #[schema(value_type = String)]
+    pub kwh_amount: Decimal,
+    pub reading_timestamp: DateTime<Utc>,
+    /// hour, day or unchecked
+    pub breached_window: String,
+    #[schema(value_type = String)]
+    pub limit_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub minted_kwh: Decimal,
+    /// pending, releasing, released or rejected
+    pub status: String,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_note: Option<String>,
+    pub mint_tx_signature: Option<String>,
+    /// Why the last release failed to mint
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Rated capacity of a registered meter
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct MeterCapacity {
+    pub id: Uuid,
+    pub meter_serial: String,
+    /// Unset meters count with the configured default
+    #[schema(value_type = Option<String>)]
+    pub capacity_kw: Option<Decimal>,
+}
+
+#[derive(Clone)]
+pub struct MintGuardService {
+    db: PgPool,
+    config: Config,
+    blockchain: BlockchainService,
+    wallet: WalletService,
+    audit_logger: AuditLogger,
+}
+
+impl MintGuardService {
+    pub fn new(
+        db: PgPool,
+        config: Config,
+        blockchain: BlockchainService,
+        wallet: WalletService,
+        audit_logger: AuditLogger,
+    ) -> Self {
+        Self {
+            db,
+            config,
+            blockchain,
+            wallet,
+            audit_logger,
+        }
+    }
+
+    pub fn mode(&self) -> MintGuardMode {
+        self.config.mint_guard.mode
+    }
+
+    /// Caps of `wallet_address` and what it minted in the windows ending at `at`
+    pub async fn allowance(&self, wallet_address: &str, at: DateTime<Utc>) -> Result<WalletMintAllowance> {
+        let mut conn = self.db.acquire().await?;
+        self.allowance_on(&mut conn, wallet_address, at).await
+    }
+
+    /// Mints still pending count as minted
+    async fn allowance_on(
+        &self,
+        conn: &mut PgConnection,
+        wallet_address: &str,
+        at: DateTime<Utc>,
+    ) -> Result<WalletMintAllowance> {
+        let guard = &self.config.mint_guard;
+        let (meters, rated): (i64, Decimal) = sqlx::query_as(
+            r#"
+            SELECT COUNT(m.id), COALESCE(SUM(COALESCE(LEAST(m.capacity_kw, m.inverter_capacity_kw), $2)), 0)
+            FROM users u
+            JOIN meter_registry m ON m.user_id = u.id
+            WHERE u.wallet_address = $1
+            "#,
+        )
+        .bind(wallet_address)
+        .bind(guard.default_capacity_kw)
+        .fetch_one(&mut *conn)
+        .await?;
+        // Wallets without registered meters get one unrated meter's allowance
+        let capacity_kw = if meters > 0 { rated } else { guard.default_capacity_kw };
+
+        let (minted_last_hour_kwh, minted_last_day_kwh): (Decimal, Decimal) = sqlx::query_as(
+            r#"
+            SELECT
+                COALESCE(SUM(kwh_amount) FILTER (WHERE reading_timestamp > $2 - INTERVAL '1 hour'), 0),
+                COALESCE(SUM(kwh_amount), 0)
+            FROM meter_readings
+            WHERE wallet_address = $1 AND (minted = true OR mint_status = 'pending') AND kwh_amount > 0
+              AND reading_timestamp > $2 - INTERVAL '1 day' AND reading_timestamp <= $2
+            "#,
+        )
+        .bind(wallet_address)
+        .bind(at)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(WalletMintAllowance {
+            wallet_address: wallet_address.to_string(),
+            meters,
+            caps: MintCaps::for_capacity(guard, capacity_kw),
+            minted_last_hour_kwh,
+            minted_last_day_kwh,
+        })
+    }
+
+    /// The cap minting `kwh` for a reading taken at `at` would exceed, if any.
+    ///
+    /// `conn` must be the transaction that records the reading as pending (or
+    /// quarantines it): the wallet stays locked until it ends.
+    pub async fn check(
+        &self,
+        conn: &mut PgConnection,
+        wallet_address: &str,
+        kwh: Decimal,
+        at: DateTime<Utc>,
+    ) -> Option<GuardBreach> {
+        if !self.config.mint_guard.enabled || kwh <= Decimal::ZERO {
+            return None;
+        }
+        let breach = match self.assess(conn, wallet_address, kwh, at).await {
+            Ok(breach) => breach,
+            Err(e) => {
+                warn!("Failed to check minting guard for wallet {}: {}", wallet_address, e);
+                Some(GuardBreach::unchecked(kwh))
+            }
+        };
+        if let Some(breach) = &breach {
+            warn!("🛑 Minting guard for wallet {}: {}", wallet_address, breach.message());
+        }
+        breach
+    }
+
+    async fn assess(
+        &self,
+        conn: &mut PgConnection,
+        wallet_address: &str,
+        kwh: Decimal,
+        at: DateTime<Utc>,
+    ) -> Result<Option<GuardBreach>> {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+            .bind(format!("mint_guard:{}", wallet_address))
+            .execute(&mut *conn)
+            .await?;
+        // A failed read must leave the transaction usable to quarantine the reading
+        let mut savepoint = sqlx::Connection::begin(&mut *conn).await?;
+        let allowance = self.allowance_on(&mut savepoint, wallet_address, at).await?;
+        savepoint.commit().await?;
+        Ok(allowance
+            .caps
+            .assess(allowance.minted_last_hour_kwh, allowance.minted_last_day_kwh, kwh))
+    }
+
+    /// Hold a recorded, unminted reading for review, in the transaction that
+    /// ran the check
+    pub async fn quarantine(
+        &self,
+        conn: &mut PgConnection,
+        reading_id: Uuid,
+        meter_serial: &str,
+        wallet_address: &str,
+        reading_timestamp: DateTime<Utc>,
+        breach: &GuardBreach,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO mint_quarantine (
+                reading_id, meter_serial, wallet_address, kwh_amount, reading_timestamp,
+                breached_window, limit_kwh, minted_kwh
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (reading_id) DO NOTHING
+            "#,
+        )
+        .bind(reading_id)
+        .bind(meter_serial)
+        .bind(wallet_address)
+        .bind(breach.kwh)
+        .bind(reading_timestamp)
+        .bind(breach.window.as_str())
+        .bind(breach.limit_kwh)
+        .bind(breach.minted_kwh)
+        .execute(&mut *conn)
+        .await?;
+        info!("🔒 Reading {} of meter {} quarantined", reading_id, meter_serial);
+        Ok(())
+    }
+
+    /// Status of a reading held by the guard and not released
+    pub async fn reading_quarantine(&self, reading_id: Uuid) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT status FROM mint_quarantine WHERE reading_id = $1 AND status <> 'released'",
+        )
+        .bind(reading_id)
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    /// Quarantined readings, newest first
+    pub async fn list(&self, status: Option<QuarantineStatus>, limit: i64) -> Result<Vec<QuarantinedReading>> {
+        Ok(sqlx::query_as::<_, QuarantinedReading>(&format!(
+            r#"
+            SELECT {} FROM mint_quarantine
+            WHERE ($1::TEXT IS NULL OR status = $1)
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+            QUARANTINE_COLUMNS
+        ))
+        .bind(status.map(|status| status.as_str()))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    async fn find(&self, id: Uuid) -> Result<QuarantinedReading> {
+        sqlx::query_as::<_, QuarantinedReading>(&format!(
+            "SELECT {} FROM mint_quarantine WHERE id = $1",
+            QUARANTINE_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Quarantined reading {} not found", id)))
+    }
+
+    /// Claim a pending entry for review
+    async fn review(&self, admin_id: Uuid, id: Uuid, status: QuarantineStatus, note: Option<&str>) -> Result<QuarantinedReading> {
+        let claimed = sqlx::query_as::<_, QuarantinedReading>(&format!(
+            r#"
+            UPDATE mint_quarantine
+            SET status = $2, reviewed_by = $3, reviewed_at = NOW(), review_note = $4
+            WHERE id = $1 AND status = 'pending'
+            RETURNING {}
+            "#,
+            QUARANTINE_COLUMNS
+        ))
+        .bind(id)
+        .bind(status.as_str())
+        .bind(admin_id)
+        .bind(note)
+        .fetch_optional(&self.db)
+        .await?;
+        match claimed {
+            Some(entry) => Ok(entry),
+            None => {
+                let entry = self.find(id).await?;
+                Err(ApiError::Conflict(format!("Quarantined reading {} is already {}", id, entry.status)))
+            }
+        }
+    }
+
+    /// Mint a quarantined reading regardless of its wallet's cap. A failed
+    /// mint returns the entry to pending with the error.
+    pub async fn release(&self, admin_id: Uuid, id: Uuid, note: Option<&str>) -> Result<QuarantinedReading> {
+        let entry = self.review(admin_id, id, QuarantineStatus::Releasing, note).await?;
+
+        // Readings minted by hand since they were quarantined are only marked
+        let recorded: Option<(Option<bool>, Option<String>)> =
+            sqlx::query_as("SELECT minted, mint_tx_signature FROM meter_readings WHERE id = $1")
+                .bind(entry.reading_id)
+                .fetch_optional(&self.db)
+                .await?;
+        let minted = match recorded {
+            None => Err(anyhow!("Reading {} was not recorded", entry.reading_id)),
+            Some((Some(true), signature)) => Ok(signature.unwrap_or_default()),
+            Some(_) => self.mint(&entry.wallet_address, entry.kwh_amount).await,
+        };
+
+        let signature = match minted {
+            Ok(signature) => signature,
+            Err(e) => {
+                sqlx::query("UPDATE mint_quarantine SET status = 'pending', error = $2 WHERE id = $1")
+                    .bind(id)
+                    .bind(e.to_string())
+                    .execute(&self.db)
+                    .await?;
+                warn!("⚠️ Release of quarantined reading {} failed: {}", entry.reading_id, e);
+                return Err(ApiError::Internal(format!("Minting failed: {}", e)));
+            }
+        };
+
+        let mut tx = self.db.begin().await?;
+        sqlx::query("UPDATE meter_readings SET minted = TRUE, mint_tx_signature = $2 WHERE id = $1")
+            .bind(entry.reading_id)
+            .bind(&signature)
+            .execute(&mut *tx)
+            .await?;
+        // Backfilled readings carry their mint status in the batch as well
+        sqlx::query(
+            "UPDATE ami_backfill_readings SET status = 'minted', mint_tx_signature = $2, error = NULL, \
+             updated_at = NOW() WHERE id = $1 AND status IN ('imported', 'mint_failed')",
+        )
+        .bind(entry.reading_id)
+        .bind(&signature)
+        .execute(&mut *tx)
+        .await?;
+        let released = sqlx::query_as::<_, QuarantinedReading>(&format!(
+            r#"
+            UPDATE mint_quarantine
+            SET status = 'released', mint_tx_signature = $2, error = NULL
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            QUARANTINE_COLUMNS
+        ))
+        .bind(id)
+        .bind(&signature)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        self.audit(admin_id, "mint_quarantine_released", &released);
+        Ok(released)
+    }
+
+    /// Keep a quarantined reading unminted for good
+    pub async fn reject(&self, admin_id: Uuid, id: Uuid, note: Option<&str>) -> Result<QuarantinedReading> {
+        let rejected = self.review(admin_id, id, QuarantineStatus::Rejected, note).await?;
+        self.audit(admin_id, "mint_quarantine_rejected", &rejected);
+        Ok(rejected)
+    }
+
+    /// Set or clear a meter's rated capacity
+    pub async fn set_capacity(&self, admin_id: Uuid, meter_id: Uuid, capacity_kw: Option<Decimal>) -> Result<MeterCapacity> {
+        if capacity_kw.is_some_and(|kw| kw <= Decimal::ZERO) {
+            return Err(ApiError::validation_field("capacity_kw", "Capacity must be positive"));
+        }
+        let meter = sqlx::query_as::<_, MeterCapacity>(
+            r#"
+            UPDATE meter_registry SET capacity_kw = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, meter_serial, capacity_kw
+            "#,
+        )
+        .bind(meter_id)
+        .bind(capacity_kw)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Meter {} not found", meter_id)))?;
+
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "meter_capacity_set".to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "meter_id": meter.id,
+                "meter_serial": meter.meter_serial,
+                "capacity_kw": meter.capacity_kw,
+            })
+            .to_string(),
+        });
+        Ok(meter)
+    }
+
+    async fn mint(&self, wallet_address: &str, amount: Decimal) -> anyhow::Result<String> {
+        let kwh = amount
+            .to_f64()
+            .ok_or_else(|| anyhow!("Reading amount {} out of range", amount))?;
+        let authority = self.wallet.get_authority_keypair().await?;
+        let mint = BlockchainService::parse_pubkey(&self.config.energy_token_mint)?;
+        let wallet = BlockchainService::parse_pubkey(wallet_address)?;
+
+        let signature = if self.config.tokenization.enable_real_blockchain {
+            let token_account = self
+                .blockchain
+                .ensure_token_account_exists(&authority, &wallet, &mint)
+                .await?;
+            self.blockchain
+                .mint_energy_tokens(&authority, &token_account, &wallet, &mint, kwh)
+                .await?
+        } else {
+            self.blockchain
+                .mint_spl_tokens(&authority, &wallet, &mint, kwh)
+                .await?
+        };
+        Ok(signature.to_string())
+    }
+
+    fn audit(&self, admin_id: Uuid, action: &str, entry: &QuarantinedReading) {
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "quarantine_id": entry.id,
+                "reading_id": entry.reading_id,
+                "meter_serial": entry.meter_serial,
+                "wallet_address": entry.wallet_address,
+                "kwh_amount": entry.kwh_amount,
+                "breached_window": entry.breached_window,
+                "limit_kwh": entry.limit_kwh,
+                "note": entry.review_note,
+                "mint_tx_signature": entry.mint_tx_signature,
+            })
+            .to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> MintGuardConfig {
+        MintGuardConfig {
+            enabled: true,
+            mode: MintGuardMode::Quarantine,
+            default_capacity_kw: Decimal::from(10),
+            hourly_headroom: Decimal::new(12, 1),
+            daily_full_load_hours: Decimal::from(12),
+        }
+    }
+
+    #[test]
+    fn test_caps_follow_capacity() {
+        let caps = MintCaps::for_capacity(&config(), Decimal::new(55, 1));
+        assert_eq!(caps.hourly_kwh, Decimal::new(66, 1));
+        assert_eq!(caps.daily_kwh, Decimal::from(66));
+    }
+
+    #[test]
+    fn test_assess_reports_first_exceeded_window() {
+        let caps = MintCaps::for_capacity(&config(), Decimal::from(5));
+
+        // 6 kWh per hour, 60 kWh per day
+        assert_eq!(caps.assess(Decimal::from(2), Decimal::from(30), Decimal::from(4)), None);
+
+        let hourly = caps
+            .assess(Decimal::from(3), Decimal::from(3), Decimal::from(4))
+            .unwrap();
+        assert_eq!(hourly.window, GuardWindow::Hour);
+        assert_eq!(hourly.minted_kwh, Decimal::from(3));
+
+        let daily = caps
+            .assess(Decimal::ZERO, Decimal::from(58), Decimal::from(3))
+            .unwrap();
+        assert_eq!(daily.window, GuardWindow::Day);
+        assert_eq!(daily.limit_kwh, Decimal::from(60));
+    }
+
+    #[test]
+    fn test_unchecked_breach_names_no_cap() {
+        let breach = GuardBreach::unchecked(Decimal::new(25, 1));
+        assert_eq!(breach.window.as_str(), "unchecked");
+        assert_eq!(breach.message(), "2.5 kWh could not be checked against the wallet's cap");
+    }
+}
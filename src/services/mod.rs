@@ -6,6 +6,8 @@
 pub mod auth;
 pub mod blockchain;
 pub mod cache;
+pub mod rate_limiter;
+pub mod delegation;
 pub mod email;
 pub mod health_check;
 pub mod wallet;
@@ -29,15 +31,81 @@ pub mod price_monitor;
 pub mod recurring_scheduler;
 pub mod notification_dispatcher;
 pub mod meter_analyzer;
+pub mod market_analytics;
+pub mod leaderboard;
+pub mod price_index;
+pub mod market_session;
+pub mod order_dust;
+pub mod order_events;
+pub mod invoicing;
+pub mod payments;
+pub mod dispute;
+pub mod trade_admin;
+pub mod vesting;
+pub mod data_fixes;
+pub mod referral;
+pub mod admin_overview;
+pub mod autopilot;
+pub mod budget_alerts;
+pub mod api_usage;
+pub mod attachments;
+pub mod network_acl;
+pub mod meter_gateway;
+pub mod ami_backfill;
+pub mod fee_sponsorship;
+pub mod token_transfer;
+pub mod report_subscriptions;
+pub mod jobs;
+pub mod mint_guard;
+pub mod meter_installation;
+pub mod degradation;
+pub mod tokenization_settings;
+pub mod market_archive;
+pub mod data_exports;
+pub mod meter_firmware;
+pub mod meter_fleet_health;
+pub mod meter_registry_sync;
+pub mod address_book;
+pub mod swap_pool_stats;
+pub mod epoch_clearing;
+pub mod audit_retention;
+pub mod fx_rates;
+pub mod emission_factors;
+pub mod markets;
+pub mod book_snapshots;
+pub mod order_router;
+pub mod fix_sessions;
+pub mod maker_incentives;
+pub mod wallet_challenges;
+pub mod wallet_links;
+pub mod partners;
+pub mod sandbox;
+pub mod geoip;
+pub mod delivery_verification;
+pub mod imbalance;
+pub mod grid_meter_data;
+pub mod maintenance;
+pub mod maintenance_rebuild;
+pub mod server_clock;
+pub mod trading_halts;
+pub mod ledger_history;
+pub mod pii;
+pub mod login_step_up;
+pub mod deployment_profile;
+pub mod participant_ids;
+pub mod concurrency_limiter;
 
 // Re-exports
 pub use auth::AuthService;
 pub use blockchain::BlockchainService;
 pub use cache::CacheService;
+pub use rate_limiter::EnhancedRateLimiter;
+pub use delegation::DelegationService;
 pub use email::EmailService;
 pub use health_check::HealthChecker;
-pub use wallet::WalletService;
+pub use wallet::{WalletService, WalletSessionService};
 pub use websocket::WebSocketService;
+pub use websocket::throttle::BroadcastThrottleSettings;
 
 pub use audit_logger::{AuditLogger, AuditEvent};
 pub use market_clearing::MarketClearingService;
@@ -53,4 +121,63 @@ pub use notification::NotificationService;
 pub use price_monitor::{PriceMonitor, PriceMonitorConfig};
 pub use recurring_scheduler::{RecurringScheduler, RecurringSchedulerConfig};
 pub use notification_dispatcher::{NotificationDispatcher, NotificationDispatcherConfig};
+pub use market_analytics::{MarketAnalyticsAggregator, MarketAnalyticsConfig};
+pub use leaderboard::LeaderboardService;
+pub use price_index::PriceIndexService;
+pub use market_session::MarketSessionService;
+pub use invoicing::InvoiceService;
+pub use payments::PrepaidService;
+pub use dispute::DisputeService;
+pub use trade_admin::TradeAdminService;
+pub use vesting::VestingService;
+pub use data_fixes::DataFixService;
+pub use referral::ReferralService;
+pub use admin_overview::AdminOverviewService;
+pub use autopilot::AutopilotService;
+pub use budget_alerts::BudgetAlertService;
+pub use api_usage::ApiUsageService;
+pub use attachments::AttachmentService;
+pub use network_acl::NetworkAclService;
+pub use meter_gateway::MeterGatewayService;
+pub use ami_backfill::AmiBackfillService;
+pub use fee_sponsorship::FeeSponsorshipService;
+pub use token_transfer::TokenTransferService;
+pub use report_subscriptions::ReportSubscriptionService;
+pub use jobs::JobQueue;
+pub use mint_guard::MintGuardService;
+pub use meter_installation::MeterInstallationService;
+pub use degradation::DegradationManager;
+pub use tokenization_settings::TokenizationSettings;
+pub use market_archive::MarketArchiveService;
+pub use data_exports::DataExportService;
+pub use meter_firmware::MeterFirmwareService;
+pub use meter_fleet_health::MeterFleetHealthService;
+pub use meter_registry_sync::MeterRegistrySyncService;
+pub use address_book::AddressBookService;
+pub use swap_pool_stats::SwapPoolStatsService;
+pub use epoch_clearing::EpochClearingService;
+pub use audit_retention::AuditRetentionService;
+pub use fx_rates::FxRateService;
+pub use emission_factors::EmissionFactorService;
+pub use markets::MarketService;
+pub use book_snapshots::OrderBookSnapshotService;
+pub use fix_sessions::FixSessionService;
+pub use maker_incentives::MakerIncentiveService;
+pub use wallet_challenges::WalletChallengeService;
+pub use wallet_links::WalletLinkService;
+pub use partners::PartnerService;
+pub use sandbox::SandboxService;
+pub use geoip::GeoIpService;
+pub use delivery_verification::DeliveryVerificationService;
+pub use imbalance::ImbalanceService;
+pub use grid_meter_data::GridMeterDataService;
+pub use maintenance::MaintenanceService;
+pub use maintenance_rebuild::RebuildService;
+pub use trading_halts::TradingHaltService;
+pub use ledger_history::LedgerHistoryService;
+pub use pii::{PiiCipher, PiiRotationJob};
+pub use login_step_up::LoginStepUpService;
+pub use deployment_profile::DeploymentProfileService;
+pub use participant_ids::ParticipantIds;
+pub use concurrency_limiter::ConcurrencyLimiter;
 
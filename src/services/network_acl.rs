@@ -0,0 +1,524 @@
+//! Network Access Control Service
+//!
+//! CIDR allow/deny lists per route group. Admin routes are meant to be limited
+//! to office/VPN ranges and AMI ingestion to utility head-end ranges. Bootstrap
+//! allow rules come from configuration; runtime rules live in
+//! `network_access_rules`, are cached in memory and reloaded on every change.
+//!
+//! Evaluation: a matching deny rule always blocks; if the group has any allow
+//! rules the client must match one of them; a group without allow rules is open.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::NetworkAclConfig;
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+
+/// Route groups that can be restricted by network
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteGroup {
+    /// `/api/v1/admin/*`
+    Admin,
+    /// Smart meter (AMI) reading ingestion
+    Ami,
+}
+
+impl RouteGroup {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Ami => "ami",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "admin" => Some(Self::Admin),
+            "ami" => Some(Self::Ami),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Allow,
+    Deny,
+}
+
+impl RuleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "allow" => Some(Self::Allow),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// Client address resolved by the network ACL middleware, stored in request extensions
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+/// Network access rule
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NetworkRule {
+    /// `None` for rules from configuration (not removable at runtime)
+    pub id: Option<Uuid>,
+    pub route_group: RouteGroup,
+    pub action: RuleAction,
+    #[schema(value_type = String, example = "10.20.0.0/16")]
+    pub cidr: IpNetwork,
+    pub description: Option<String>,
+    /// "config" or "runtime"
+    pub source: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct NetworkRuleRow {
+    id: Uuid,
+    route_group: String,
+    action: String,
+    cidr: IpNetwork,
+    description: Option<String>,
+    created_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+impl NetworkRuleRow {
+    fn into_rule(self) -> Option<NetworkRule> {
+        Some(NetworkRule {
+            id: Some(self.id),
+            route_group: RouteGroup::parse(&self.route_group)?,
+            action: RuleAction::parse(&self.action)?,
+            cidr: self.cidr,
+            description: self.description,
+            source: "runtime".to_string(),
+            created_by: self.created_by,
+            created_at: Some(self.created_at),
+        })
+    }
+}
+
+/// Whether `ip` may reach routes in `group` under `rules`
+pub fn is_allowed(rules: &[NetworkRule], group: RouteGroup, ip: IpAddr) -> bool {
+    let mut has_allow = false;
+    let mut allowed = false;
+
+    for rule in rules.iter().filter(|r| r.route_group == group) {
+        let matches = rule.cidr.contains(ip);
+        match rule.action {
+            RuleAction::Deny if matches => return false,
+            RuleAction::Deny => {}
+            RuleAction::Allow => {
+                has_allow = true;
+                allowed |= matches;
+            }
+        }
+    }
+
+    !has_allow || allowed
+}
+
+/// Whether a group has any rules at all (unrestricted groups skip IP resolution)
+pub fn is_restricted(rules: &[NetworkRule], group: RouteGroup) -> bool {
+    rules.iter().any(|r| r.route_group == group)
+}
+
+/// Resolve the client address.
+///
+/// The socket peer is used unless it is a trusted proxy, in which case
+/// X-Forwarded-For is walked right to left and the first untrusted hop wins;
+/// entries left of it are client-supplied and cannot be trusted.
+pub fn resolve_client_ip(
+    peer: Option<IpAddr>,
+    headers: &HeaderMap,
+    trusted_proxies: &[IpNetwork],
+) -> Option<IpAddr> {
+    let is_trusted = |ip: IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+
+    if let Some(peer) = peer {
+        if !is_trusted(peer) {
+            return Some(peer);
+        }
+    }
+
+    if let Some(forwarded) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()) {
+        let hops: Vec<IpAddr> = forwarded
+            .split(',')
+            .filter_map(|hop| hop.trim().parse().ok())
+            .collect();
+        if let Some(client) = hops.iter().rev().find(|ip| !is_trusted(**ip)) {
+            return Some(*client);
+        }
+        if let Some(first) = hops.first() {
+            return Some(*first);
+        }
+    }
+
+    if let Some(real_ip) = headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+    {
+        return Some(real_ip);
+    }
+
+    peer
+}
+
+fn config_rules(group: RouteGroup, cidrs: &[String]) -> Vec<NetworkRule> {
+    cidrs
+        .iter()
+        .filter_map(|c| c.parse::<IpNetwork>().ok())
+        .map(|cidr| NetworkRule {
+            id: None,
+            route_group: group,
+            action: RuleAction::Allow,
+            cidr,
+            description: Some("Configured at startup".to_string()),
+            source: "config".to_string(),
+            created_by: None,
+            created_at: None,
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct NetworkAclService {
+    db: PgPool,
+    audit_logger: AuditLogger,
+    static_rules: Arc<Vec<NetworkRule>>,
+    trusted_proxies: Arc<Vec<IpNetwork>>,
+    runtime_rules: Arc<RwLock<Vec<NetworkRule>>>,
+}
+
+impl NetworkAclService {
+    pub fn new(db: PgPool, config: NetworkAclConfig, audit_logger: AuditLogger) -> Self {
+        let mut static_rules = config_rules(RouteGroup::Admin, &config.admin_allow);
+        static_rules.extend(config_rules(RouteGroup::Ami, &config.ami_allow));
+
+        Self {
+            db,
+            audit_logger,
+            static_rules: Arc::new(static_rules),
+            trusted_proxies: Arc::new(
+                config
+                    .trusted_proxies
+                    .iter()
+                    .filter_map(|c| c.parse().ok())
+                    .collect(),
+            ),
+            runtime_rules: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// Reload runtime rules from the database
+    pub async fn reload(&self) -> Result<usize> {
+        let rows = sqlx::query_as::<_, NetworkRuleRow>(
+            r#"
+            SELECT id, route_group, action, cidr, description, created_by, created_at
+            FROM network_access_rules
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let rules: Vec<NetworkRule> = rows.into_iter().filter_map(|r| r.into_rule()).collect();
+        let count = rules.len();
+        *self.runtime_rules.write().await = rules;
+        Ok(count)
+    }
+
+    /// Configured and runtime rules
+    pub async fn rules(&self) -> Vec<NetworkRule> {
+        let mut rules = self.static_rules.as_ref().clone();
+        rules.extend(self.runtime_rules.read().await.iter().cloned());
+        rules
+    }
+
+    /// Whether `ip` is a reverse proxy whose forwarded headers are trusted
+    pub fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(ip))
+    }
+
+    pub fn client_ip(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        resolve_client_ip(peer, headers, &self.trusted_proxies)
+    }
+
+    /// Check a request against a group. Unresolvable clients are only let
+    /// through when the group has no rules.
+    pub async fn check(&self, group: RouteGroup, ip: Option<IpAddr>) -> bool {
+        let rules = self.rules().await;
+        if !is_restricted(&rules, group) {
+            return true;
+        }
+        ip.is_some_and(|ip| is_allowed(&rules, group, ip))
+    }
+
+    /// Add a runtime rule. Changes to the admin group that would block the
+    /// caller are rejected unless `force` is set.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_rule(
+        &self,
+        admin_id: Uuid,
+        caller_ip: Option<IpAddr>,
+        route_group: RouteGroup,
+        action: RuleAction,
+        cidr: IpNetwork,
+        description: Option<String>,
+        force: bool,
+    ) -> Result<NetworkRule> {
+        // Normalise 10.1.2.3/8 to 10.0.0.0/8 so duplicates are caught
+        let cidr = IpNetwork::new(cidr.network(), cidr.prefix())
+            .map_err(|e| ApiError::validation_field("cidr", e.to_string()))?;
+
+        if route_group == RouteGroup::Admin && !force {
+            let mut proposed = self.rules().await;
+            proposed.push(NetworkRule {
+                id: None,
+                route_group,
+                action,
+                cidr,
+                description: None,
+                source: "runtime".to_string(),
+                created_by: None,
+                created_at: None,
+            });
+            self.ensure_not_locked_out(&proposed, caller_ip)?;
+        }
+
+        let row = sqlx::query_as::<_, NetworkRuleRow>(
+            r#"
+            INSERT INTO network_access_rules (route_group, action, cidr, description, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, route_group, action, cidr, description, created_by, created_at
+            "#,
+        )
+        .bind(route_group.as_str())
+        .bind(action.as_str())
+        .bind(cidr)
+        .bind(&description)
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::Conflict(format!(
+                "{} rule for {} already exists on the {} group",
+                action.as_str(),
+                cidr,
+                route_group.as_str()
+            )),
+            _ => ApiError::Database(e),
+        })?;
+
+        let rule = row
+            .into_rule()
+            .ok_or_else(|| ApiError::Internal("Stored rule could not be decoded".to_string()))?;
+
+        self.reload().await?;
+        self.audit_change(admin_id, "network_acl_rule_added", &rule, caller_ip);
+        info!(
+            "Network ACL rule added: {} {} {} by {}",
+            rule.route_group.as_str(),
+            rule.action.as_str(),
+            rule.cidr,
+            admin_id
+        );
+
+        Ok(rule)
+    }
+
+    /// Remove a runtime rule (configured rules cannot be removed)
+    pub async fn remove_rule(
+        &self,
+        admin_id: Uuid,
+        caller_ip: Option<IpAddr>,
+        rule_id: Uuid,
+        force: bool,
+    ) -> Result<NetworkRule> {
+        let rules = self.rules().await;
+        let rule = rules
+            .iter()
+            .find(|r| r.id == Some(rule_id))
+            .cloned()
+            .ok_or_else(|| ApiError::NotFound("Network rule not found".to_string()))?;
+
+        if rule.route_group == RouteGroup::Admin && !force {
+            let proposed: Vec<NetworkRule> = rules
+                .into_iter()
+                .filter(|r| r.id != Some(rule_id))
+                .collect();
+            self.ensure_not_locked_out(&proposed, caller_ip)?;
+        }
+
+        let deleted = sqlx::query("DELETE FROM network_access_rules WHERE id = $1")
+            .bind(rule_id)
+            .execute(&self.db)
+            .await?
+            .rows_affected();
+        if deleted == 0 {
+            return Err(ApiError::NotFound("Network rule not found".to_string()));
+        }
+
+        self.reload().await?;
+        self.audit_change(admin_id, "network_acl_rule_removed", &rule, caller_ip);
+        info!(
+            "Network ACL rule removed: {} {} {} by {}",
+            rule.route_group.as_str(),
+            rule.action.as_str(),
+            rule.cidr,
+            admin_id
+        );
+
+        Ok(rule)
+    }
+
+    fn ensure_not_locked_out(&self, proposed: &[NetworkRule], caller_ip: Option<IpAddr>) -> Result<()> {
+        let still_allowed = match caller_ip {
+            Some(ip) => is_allowed(proposed, RouteGroup::Admin, ip),
+            None => !is_restricted(proposed, RouteGroup::Admin),
+        };
+        if still_allowed {
+            Ok(())
+        } else {
+            Err(ApiError::Conflict(format!(
+                "This change would block your own address ({}) from admin routes; resend with force=true to apply anyway",
+                caller_ip.map(|ip| ip.to_string()).unwrap_or_else(|| "unknown".to_string())
+            )))
+        }
+    }
+
+    fn audit_change(&self, admin_id: Uuid, action: &str, rule: &NetworkRule, caller_ip: Option<IpAddr>) {
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "rule_id": rule.id,
+                "route_group": rule.route_group.as_str(),
+                "action": rule.action.as_str(),
+                "cidr": rule.cidr.to_string(),
+                "description": rule.description,
+                "caller_ip": caller_ip.map(|ip| ip.to_string()),
+            })
+            .to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn rule(group: RouteGroup, action: RuleAction, cidr: &str) -> NetworkRule {
+        NetworkRule {
+            id: None,
+            route_group: group,
+            action,
+            cidr: cidr.parse().unwrap(),
+            description: None,
+            source: "runtime".to_string(),
+            created_by: None,
+            created_at: None,
+        }
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_group_without_rules_is_open() {
+        let rules = vec![rule(RouteGroup::Ami, RuleAction::Allow, "10.50.0.0/16")];
+        assert!(!is_restricted(&rules, RouteGroup::Admin));
+        assert!(is_allowed(&rules, RouteGroup::Admin, ip("203.0.113.9")));
+    }
+
+    #[test]
+    fn test_allow_list_restricts_group() {
+        let rules = vec![
+            rule(RouteGroup::Admin, RuleAction::Allow, "10.0.0.0/8"),
+            rule(RouteGroup::Admin, RuleAction::Allow, "fd00::/8"),
+        ];
+        assert!(is_allowed(&rules, RouteGroup::Admin, ip("10.1.2.3")));
+        assert!(is_allowed(&rules, RouteGroup::Admin, ip("fd12::1")));
+        assert!(!is_allowed(&rules, RouteGroup::Admin, ip("192.168.1.1")));
+    }
+
+    #[test]
+    fn test_deny_overrides_allow() {
+        let rules = vec![
+            rule(RouteGroup::Admin, RuleAction::Allow, "10.0.0.0/8"),
+            rule(RouteGroup::Admin, RuleAction::Deny, "10.9.0.0/16"),
+        ];
+        assert!(is_allowed(&rules, RouteGroup::Admin, ip("10.1.0.1")));
+        assert!(!is_allowed(&rules, RouteGroup::Admin, ip("10.9.4.4")));
+    }
+
+    #[test]
+    fn test_deny_only_group_blocks_matches() {
+        let rules = vec![rule(RouteGroup::Ami, RuleAction::Deny, "198.51.100.0/24")];
+        assert!(is_restricted(&rules, RouteGroup::Ami));
+        assert!(is_allowed(&rules, RouteGroup::Ami, ip("203.0.113.1")));
+        assert!(!is_allowed(&rules, RouteGroup::Ami, ip("198.51.100.7")));
+    }
+
+    #[test]
+    fn test_untrusted_peer_ignores_forwarded_header() {
+        let proxies: Vec<IpNetwork> = vec!["127.0.0.1/32".parse().unwrap()];
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("10.0.0.5"));
+
+        let resolved = resolve_client_ip(Some(ip("203.0.113.1")), &headers, &proxies);
+        assert_eq!(resolved, Some(ip("203.0.113.1")));
+    }
+
+    #[test]
+    fn test_trusted_proxy_uses_rightmost_untrusted_hop() {
+        let proxies: Vec<IpNetwork> = vec![
+            "127.0.0.1/32".parse().unwrap(),
+            "172.16.0.0/12".parse().unwrap(),
+        ];
+        let mut headers = HeaderMap::new();
+        // Client spoofed 10.0.0.5; the real client is 198.51.100.4
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("10.0.0.5, 198.51.100.4, 172.16.0.2"),
+        );
+
+        let resolved = resolve_client_ip(Some(ip("127.0.0.1")), &headers, &proxies);
+        assert_eq!(resolved, Some(ip("198.51.100.4")));
+    }
+
+    #[test]
+    fn test_trusted_proxy_without_headers_falls_back_to_peer() {
+        let proxies: Vec<IpNetwork> = vec!["127.0.0.1/32".parse().unwrap()];
+        let resolved = resolve_client_ip(Some(ip("127.0.0.1")), &HeaderMap::new(), &proxies);
+        assert_eq!(resolved, Some(ip("127.0.0.1")));
+    }
+}
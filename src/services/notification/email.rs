@@ -6,7 +6,8 @@ use lettre::{
 };
 use tracing::{error, info};
 
-use super::types::EmailTemplate;
+use super::types::{side_label, EmailTemplate};
+use crate::i18n::{self, Locale};
 
 /// Email sender service
 #[derive(Clone)]
@@ -64,20 +65,21 @@ impl EmailService {
         self.enabled && !self.smtp_username.is_empty()
     }
 
-    /// Send an email using a template
+    /// Send an email using a template, written in `locale`
     pub async fn send_email(
         &self,
         to_email: &str,
         to_name: &str,
         template: EmailTemplate,
+        locale: Locale,
     ) -> Result<()> {
         if !self.is_enabled() {
             info!("Email service disabled, skipping email to {}", to_email);
             return Ok(());
         }
 
-        let subject = template.subject();
-        let body = self.render_template(&template)?;
+        let subject = template.subject(locale);
+        let body = self.render_template(&template, locale)?;
 
         self.send_raw(to_email, to_name, &subject, &body).await
     }
@@ -130,55 +132,65 @@ impl EmailService {
     }
 
     /// Render email template to HTML
-    fn render_template(&self, template: &EmailTemplate) -> Result<String> {
+    fn render_template(&self, template: &EmailTemplate, locale: Locale) -> Result<String> {
+        let label = |key: &'static str| i18n::t(locale, key);
         let (title, content) = match template {
             EmailTemplate::TradeMatched(data) => (
-                "Order Matched",
+                label("notification.trade_matched.title"),
                 format!(
                     r#"
-                    <p>Great news! Your {} order has been matched.</p>
+                    <p>{}</p>
                     <table style="border-collapse: collapse; margin: 20px 0;">
-                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>Energy Amount:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} kWh</td></tr>
-                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>Price:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} GRIDX/kWh</td></tr>
-                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>Total Value:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} GRIDX</td></tr>
+                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>{}:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} kWh</td></tr>
+                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>{}:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} GRIDX/kWh</td></tr>
+                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>{}:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} GRIDX</td></tr>
                     </table>
-                    <p>Settlement will proceed automatically.</p>
+                    <p>{}</p>
                     "#,
-                    data.side, data.energy_amount, data.price_per_kwh, data.total_value
+                    i18n::tf(locale, "email.trade_matched.intro", &[("side", &side_label(locale, &data.side))]),
+                    label("email.label.energy_amount"), data.energy_amount,
+                    label("email.label.price"), data.price_per_kwh,
+                    label("email.label.total_value"), data.total_value,
+                    label("email.trade_matched.outro")
                 ),
             ),
             EmailTemplate::SettlementComplete(data) => (
-                "Settlement Complete",
+                label("notification.settlement_complete.title"),
                 format!(
                     r#"
-                    <p>Your energy trade settlement has been completed successfully.</p>
+                    <p>{}</p>
                     <table style="border-collapse: collapse; margin: 20px 0;">
-                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>Energy Amount:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} kWh</td></tr>
-                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>Total Value:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} GRIDX</td></tr>
+                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>{}:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} kWh</td></tr>
+                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>{}:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} GRIDX</td></tr>
                         {}
                     </table>
                     "#,
-                    data.energy_amount,
-                    data.total_value,
+                    label("email.settlement_complete.intro"),
+                    label("email.label.energy_amount"), data.energy_amount,
+                    label("email.label.total_value"), data.total_value,
                     data.tx_signature.as_ref().map(|tx| format!(
-                        r#"<tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>Transaction:</strong></td><td style="padding: 8px; border: 1px solid #ddd;"><a href="https://explorer.solana.com/tx/{}?cluster=devnet">{}</a></td></tr>"#,
-                        tx, &tx[..16]
+                        r#"<tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>{}:</strong></td><td style="padding: 8px; border: 1px solid #ddd;"><a href="https://explorer.solana.com/tx/{}?cluster=devnet">{}</a></td></tr>"#,
+                        label("email.label.transaction"), tx, &tx[..16]
                     )).unwrap_or_default()
                 ),
             ),
             EmailTemplate::RecIssued(data) => (
-                "REC Certificate Issued",
+                label("notification.rec_issued.title"),
                 format!(
                     r#"
-                    <p>Congratulations! A Renewable Energy Certificate has been issued for your energy sale.</p>
+                    <p>{}</p>
                     <table style="border-collapse: collapse; margin: 20px 0;">
-                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>Certificate ID:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{}</td></tr>
-                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>Energy Amount:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} kWh</td></tr>
-                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>Source:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{}</td></tr>
+                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>{}:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{}</td></tr>
+                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>{}:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{} kWh</td></tr>
+                        <tr><td style="padding: 8px; border: 1px solid #ddd;"><strong>{}:</strong></td><td style="padding: 8px; border: 1px solid #ddd;">{}</td></tr>
                     </table>
-                    <p>You can view your certificates in your dashboard.</p>
+                    <p>{}</p>
                     "#,
-                    data.certificate_id, data.kwh_amount, data.renewable_source
+                    label("email.rec_issued.intro"),
+                    label("email.label.certificate_id"), data.certificate_id,
+                    label("email.label.energy_amount"), data.kwh_amount,
+                    label("email.label.source"), data.renewable_source,
+                    label("email.rec_issued.outro")
                 ),
             ),
         };
@@ -186,7 +198,7 @@ impl EmailService {
         Ok(format!(
             r#"
             <!DOCTYPE html>
-            <html>
+            <html lang="{}">
             <head>
                 <meta charset="utf-8">
                 <style>
@@ -205,13 +217,17 @@ impl EmailService {
                     {}
                 </div>
                 <div class="footer">
-                    <p>This is an automated message from GridTokenX Platform.</p>
-                    <p>© 2026 GridTokenX. All rights reserved.</p>
+                    <p>{}</p>
+                    <p>© 2026 GridTokenX. {}</p>
                 </div>
             </body>
             </html>
             "#,
-            title, content
+            locale,
+            title,
+            content,
+            label("email.footer.automated"),
+            label("email.footer.rights")
         ))
     }
 }
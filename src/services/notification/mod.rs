@@ -8,26 +8,42 @@ use anyhow::Result;
 use sqlx::PgPool;
 use tracing::{info, error};
 use uuid::Uuid;
-use chrono::Utc;
 
 use crate::error::ApiError;
-
-/// Notification service for sending emails and in-app notifications
+use crate::i18n;
+use crate::models::notification::{self as inbox, CreateNotificationRequest};
+use crate::services::notification_dispatcher::{NotificationDispatcher, NotificationDispatcherConfig};
+use crate::services::pii::PiiCipher;
+
+/// Notification service for sending emails and in-app notifications.
+///
+/// The in-app copy is written to the user's inbox before any email is tried,
+/// so an undeliverable email never loses the event.
 #[derive(Clone, Debug)]
 pub struct NotificationService {
     db: PgPool,
     email_service: EmailService,
+    dispatcher: NotificationDispatcher,
+    pii: Option<PiiCipher>,
 }
 
 impl NotificationService {
     pub fn new(db: PgPool) -> Self {
         Self {
+            dispatcher: NotificationDispatcher::new(db.clone(), NotificationDispatcherConfig::default()),
             db,
             email_service: EmailService::new(),
+            pii: None,
         }
     }
 
-    /// Send a notification to a user
+    /// Decrypt sealed user emails
+    pub fn with_pii(mut self, pii: PiiCipher) -> Self {
+        self.pii = Some(pii);
+        self
+    }
+
+    /// Send a notification to a user's inbox
     pub async fn send_notification(
         &self,
         user_id: Uuid,
@@ -35,20 +51,18 @@ impl NotificationService {
         title: String,
         message: String,
         data: Option<serde_json::Value>,
-    ) -> Result<Notification, ApiError> {
-        let notification = Notification {
-            id: Uuid::new_v4(),
-            user_id,
-            notification_type,
-            title,
-            message,
-            data,
-            created_at: Utc::now(),
-            read_at: None,
-        };
-
-        // Store notification in database (optional - implement if you have a notifications table)
-        // self.store_notification(&notification).await?;
+    ) -> Result<inbox::Notification, ApiError> {
+        let notification = self
+            .dispatcher
+            .send(CreateNotificationRequest {
+                user_id,
+                notification_type: inbox_type(&notification_type),
+                title,
+                message: Some(message),
+                data,
+            })
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to store notification: {}", e)))?;
 
         info!("📬 Notification created for user {}: {}", user_id, notification.title);
 
@@ -59,24 +73,36 @@ impl NotificationService {
     pub async fn notify_trade_matched(
         &self,
         user_id: Uuid,
-        user_email: &str,
+        user_email: Option<&str>,
         data: TradeMatchNotification,
     ) -> Result<(), ApiError> {
+        let locale = i18n::user_locale(&self.db, user_id).await;
+
         // Send in-app notification
-        let _ = self.send_notification(
+        if let Err(e) = self.send_notification(
             user_id,
             NotificationType::OrderMatched,
-            "Order Matched".to_string(),
-            format!("Your {} order for {} kWh has been matched at {} GRIDX/kWh",
-                data.side, data.energy_amount, data.price_per_kwh),
+            i18n::t(locale, "notification.trade_matched.title").to_string(),
+            i18n::tf(locale, "notification.trade_matched.message", &[
+                ("side", &side_label(locale, &data.side)),
+                ("amount", &data.energy_amount),
+                ("price", &data.price_per_kwh),
+            ]),
             Some(serde_json::to_value(&data).unwrap_or_default()),
-        ).await;
+        ).await {
+            error!("Failed to store notification for user {}: {}", user_id, e);
+        }
+
+        let Some(user_email) = user_email else {
+            return Ok(());
+        };
 
         // Send email notification
         if let Err(e) = self.email_service.send_email(
             user_email,
             "GridTokenX User",
             EmailTemplate::TradeMatched(data),
+            locale,
         ).await {
             error!("Failed to send trade match email: {}", e);
         }
@@ -88,22 +114,33 @@ impl NotificationService {
     pub async fn notify_settlement_complete(
         &self,
         user_id: Uuid,
-        user_email: &str,
+        user_email: Option<&str>,
         data: SettlementNotification,
     ) -> Result<(), ApiError> {
-        let _ = self.send_notification(
+        let locale = i18n::user_locale(&self.db, user_id).await;
+
+        if let Err(e) = self.send_notification(
             user_id,
             NotificationType::SettlementComplete,
-            "Settlement Complete".to_string(),
-            format!("Your settlement of {} kWh ({} GRIDX) has been completed",
-                data.energy_amount, data.total_value),
+            i18n::t(locale, "notification.settlement_complete.title").to_string(),
+            i18n::tf(locale, "notification.settlement_complete.message", &[
+                ("amount", &data.energy_amount),
+                ("value", &data.total_value),
+            ]),
             Some(serde_json::to_value(&data).unwrap_or_default()),
-        ).await;
+        ).await {
+            error!("Failed to store notification for user {}: {}", user_id, e);
+        }
+
+        let Some(user_email) = user_email else {
+            return Ok(());
+        };
 
         if let Err(e) = self.email_service.send_email(
             user_email,
             "GridTokenX User",
             EmailTemplate::SettlementComplete(data),
+            locale,
         ).await {
             error!("Failed to send settlement email: {}", e);
         }
@@ -115,22 +152,33 @@ impl NotificationService {
     pub async fn notify_rec_issued(
         &self,
         user_id: Uuid,
-        user_email: &str,
+        user_email: Option<&str>,
         data: RecIssuedNotification,
     ) -> Result<(), ApiError> {
-        let _ = self.send_notification(
+        let locale = i18n::user_locale(&self.db, user_id).await;
+
+        if let Err(e) = self.send_notification(
             user_id,
             NotificationType::RecIssued,
-            "REC Certificate Issued".to_string(),
-            format!("A REC certificate ({}) for {} kWh has been issued",
-                data.certificate_id, data.kwh_amount),
+            i18n::t(locale, "notification.rec_issued.title").to_string(),
+            i18n::tf(locale, "notification.rec_issued.message", &[
+                ("certificate_id", &data.certificate_id),
+                ("amount", &data.kwh_amount),
+            ]),
             Some(serde_json::to_value(&data).unwrap_or_default()),
-        ).await;
+        ).await {
+            error!("Failed to store notification for user {}: {}", user_id, e);
+        }
+
+        let Some(user_email) = user_email else {
+            return Ok(());
+        };
 
         if let Err(e) = self.email_service.send_email(
             user_email,
             "GridTokenX User",
             EmailTemplate::RecIssued(data),
+            locale,
         ).await {
             error!("Failed to send REC email: {}", e);
         }
@@ -140,14 +188,49 @@ impl NotificationService {
 
     /// Get user email from database
     pub async fn get_user_email(&self, user_id: &Uuid) -> Result<String, ApiError> {
-        let result: Option<String> = sqlx::query_scalar!(
-            "SELECT email FROM users WHERE id = $1",
-            user_id
-        )
-        .fetch_optional(&self.db)
-        .await
-        .map_err(ApiError::Database)?;
-
-        result.ok_or_else(|| ApiError::NotFound("User not found".into()))
+        let (email, email_encrypted): (Option<String>, Option<Vec<u8>>) =
+            sqlx::query_as("SELECT email, email_encrypted FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(ApiError::Database)?
+                .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+
+        match (email_encrypted, &self.pii) {
+            (Some(sealed), Some(pii)) => pii.open(&sealed).await,
+            (Some(_), None) => Err(ApiError::Internal("PII encryption is not configured".into())),
+            (None, _) => email.ok_or_else(|| ApiError::NotFound("User has no email".into())),
+        }
+    }
+}
+
+/// Inbox type for an email-path notification
+fn inbox_type(notification_type: &NotificationType) -> inbox::NotificationType {
+    match notification_type {
+        NotificationType::OrderMatched => inbox::NotificationType::OrderMatched,
+        NotificationType::SettlementComplete => inbox::NotificationType::SettlementComplete,
+        NotificationType::RecIssued => inbox::NotificationType::RecIssued,
+        NotificationType::OrderFilled | NotificationType::OrderPartiallyFilled => {
+            inbox::NotificationType::OrderFilled
+        }
+        NotificationType::OrderCancelled => inbox::NotificationType::OrderCancelled,
+        NotificationType::SystemAlert => inbox::NotificationType::System,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inbox_type() {
+        assert_eq!(inbox_type(&NotificationType::OrderMatched), inbox::NotificationType::OrderMatched);
+        assert_eq!(inbox_type(&NotificationType::SettlementComplete), inbox::NotificationType::SettlementComplete);
+        assert_eq!(inbox_type(&NotificationType::RecIssued), inbox::NotificationType::RecIssued);
+        assert_eq!(inbox_type(&NotificationType::OrderCancelled), inbox::NotificationType::OrderCancelled);
+        assert_eq!(inbox_type(&NotificationType::SystemAlert), inbox::NotificationType::System);
+        // Fills share the order-filled preference and inbox category
+        assert_eq!(inbox_type(&NotificationType::OrderFilled), inbox::NotificationType::OrderFilled);
+        assert_eq!(inbox_type(&NotificationType::OrderPartiallyFilled), inbox::NotificationType::OrderFilled);
     }
 }
@@ -3,6 +3,8 @@ use uuid::Uuid;
 use rust_decimal::Decimal;
 use chrono::{DateTime, Utc};
 
+use crate::i18n::{self, Locale};
+
 /// Types of notifications the system can send
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -75,11 +77,21 @@ pub enum EmailTemplate {
 }
 
 impl EmailTemplate {
-    pub fn subject(&self) -> String {
-        match self {
-            EmailTemplate::TradeMatched(_) => "🤝 Your Order Has Been Matched".to_string(),
-            EmailTemplate::SettlementComplete(_) => "✅ Settlement Complete".to_string(),
-            EmailTemplate::RecIssued(_) => "🏆 REC Issued".to_string(),
-        }
+    pub fn subject(&self, locale: Locale) -> String {
+        let key = match self {
+            EmailTemplate::TradeMatched(_) => "email.trade_matched.subject",
+            EmailTemplate::SettlementComplete(_) => "email.settlement_complete.subject",
+            EmailTemplate::RecIssued(_) => "email.rec_issued.subject",
+        };
+        i18n::t(locale, key).to_string()
+    }
+}
+
+/// An order side ("buy" or "sell") as a word in `locale`
+pub fn side_label(locale: Locale, side: &str) -> &str {
+    match side {
+        "buy" => i18n::t(locale, "side.buy"),
+        "sell" => i18n::t(locale, "side.sell"),
+        other => other,
     }
 }
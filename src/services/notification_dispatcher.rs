@@ -1,13 +1,20 @@
 //! Notification Dispatcher Service
 //!
-//! Handles creating, storing, and broadcasting notifications via WebSocket
+//! Handles creating, storing, and broadcasting notifications via WebSocket.
+//! Every notification lands in the user's inbox; the unread counter is pushed
+//! on the user's authenticated WebSocket whenever it changes.
 
+use rust_decimal::Decimal;
 use sqlx::PgPool;
 
 use tokio::sync::broadcast;
 use tracing::{info, error, warn};
 use uuid::Uuid;
 
+use crate::i18n;
+use crate::handlers::websocket::broadcaster::{
+    broadcast_inbox_notification, broadcast_unread_notifications,
+};
 use crate::models::notification::{
     Notification, NotificationType, CreateNotificationRequest,
 };
@@ -38,7 +45,7 @@ impl Default for NotificationDispatcherConfig {
 }
 
 /// Notification dispatcher service
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct NotificationDispatcher {
     db: PgPool,
     config: NotificationDispatcherConfig,
@@ -75,6 +82,27 @@ impl NotificationDispatcher {
         self.create_notification(request, true).await
     }
 
+    /// Number of unread notifications in the user's inbox
+    pub async fn unread_count(&self, user_id: Uuid) -> anyhow::Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM notifications WHERE user_id = $1 AND read = false"#,
+            user_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Push the user's current unread counter over WebSocket
+    pub async fn push_unread_count(&self, user_id: Uuid) -> anyhow::Result<i64> {
+        let unread = self.unread_count(user_id).await?;
+        if let Err(e) = broadcast_unread_notifications(user_id, unread).await {
+            warn!("Failed to push unread count to user {}: {}", user_id, e);
+        }
+        Ok(unread)
+    }
+
     /// Send notification to multiple users
     pub async fn send_bulk(&self, requests: Vec<CreateNotificationRequest>) -> anyhow::Result<Vec<Notification>> {
         let mut notifications = Vec::with_capacity(requests.len());
@@ -153,9 +181,17 @@ impl NotificationDispatcher {
             NotificationType::OrderMatched => prefs.order_matched.unwrap_or(true),
             NotificationType::ConditionalTriggered => prefs.conditional_triggered.unwrap_or(true),
             NotificationType::RecurringExecuted => prefs.recurring_executed.unwrap_or(true),
-            NotificationType::PriceAlert => prefs.price_alerts.unwrap_or(true),
+            NotificationType::PriceAlert | NotificationType::BudgetAlert => prefs.price_alerts.unwrap_or(true),
             NotificationType::EscrowReleased => prefs.escrow_events.unwrap_or(true),
             NotificationType::System => prefs.system_announcements.unwrap_or(true),
+            NotificationType::OrderCancelled => prefs.order_filled.unwrap_or(true),
+            // Certificate lifecycle and settlement notices cannot be opted out of
+            NotificationType::CertificateExpiring
+            | NotificationType::CertificateExpired
+            | NotificationType::SettlementComplete
+            | NotificationType::RecIssued => true,
+            // Requested by the user, so always delivered
+            NotificationType::DataExportReady => true,
         };
 
         Ok(enabled)
@@ -169,7 +205,7 @@ impl NotificationDispatcher {
             INSERT INTO notifications (user_id, notification_type, title, message, data)
             VALUES ($1, $2, $3, $4, $5)
             RETURNING id, user_id, notification_type as "notification_type!: NotificationType",
-                      title, message, data, read as "read!", created_at as "created_at!"
+                      title, message, data, read as "read!", read_at, created_at as "created_at!"
             "#,
             request.user_id,
             request.notification_type as NotificationType,
@@ -191,6 +227,18 @@ impl NotificationDispatcher {
                 // No receivers - this is fine, just means no one is connected
                 warn!("No WebSocket receivers for notification broadcast");
             }
+
+            match self.unread_count(notification.user_id).await {
+                Ok(unread) => {
+                    if let Err(e) = broadcast_inbox_notification(notification.clone(), unread).await {
+                        warn!("Failed to push notification {} over WebSocket: {}", notification.id, e);
+                    }
+                }
+                Err(e) => warn!("Failed to count unread notifications: {}", e),
+            }
+        } else if let Err(e) = self.push_unread_count(notification.user_id).await {
+            // Suppressed notifications still land in the inbox, so the counter moves
+            warn!("Failed to count unread notifications: {}", e);
         }
 
         info!("Created notification {} for user {}", notification.id, notification.user_id);
@@ -207,11 +255,15 @@ impl NotificationDispatcher {
         amount: f64,
         price: f64,
     ) -> anyhow::Result<Notification> {
+        let locale = i18n::user_locale(&self.db, user_id).await;
         self.send(CreateNotificationRequest {
             user_id,
             notification_type: NotificationType::OrderFilled,
-            title: "Order Filled".to_string(),
-            message: Some(format!("Your order for {:.2} kWh was filled at {:.4}/kWh", amount, price)),
+            title: i18n::t(locale, "notification.order_filled.title").to_string(),
+            message: Some(i18n::tf(locale, "notification.order_filled.message", &[
+                ("amount", &format!("{:.2}", amount)),
+                ("price", &format!("{:.4}", price)),
+            ])),
             data: Some(serde_json::json!({
                 "order_id": order_id,
                 "amount": amount,
@@ -227,11 +279,15 @@ impl NotificationDispatcher {
         trigger_type: &str,
         trigger_price: f64,
     ) -> anyhow::Result<Notification> {
+        let locale = i18n::user_locale(&self.db, user_id).await;
         self.send(CreateNotificationRequest {
             user_id,
             notification_type: NotificationType::ConditionalTriggered,
-            title: format!("{} Triggered", trigger_type),
-            message: Some(format!("Your {} order was triggered at price {:.4}", trigger_type, trigger_price)),
+            title: i18n::tf(locale, "notification.conditional_triggered.title", &[("trigger_type", &trigger_type)]),
+            message: Some(i18n::tf(locale, "notification.conditional_triggered.message", &[
+                ("trigger_type", &trigger_type),
+                ("price", &format!("{:.4}", trigger_price)),
+            ])),
             data: Some(serde_json::json!({
                 "order_id": order_id,
                 "trigger_type": trigger_type,
@@ -247,11 +303,15 @@ impl NotificationDispatcher {
         execution_number: i32,
         amount: f64,
     ) -> anyhow::Result<Notification> {
+        let locale = i18n::user_locale(&self.db, user_id).await;
         self.send(CreateNotificationRequest {
             user_id,
             notification_type: NotificationType::RecurringExecuted,
-            title: "Recurring Order Executed".to_string(),
-            message: Some(format!("Execution #{} completed for {:.2} kWh", execution_number, amount)),
+            title: i18n::t(locale, "notification.recurring_executed.title").to_string(),
+            message: Some(i18n::tf(locale, "notification.recurring_executed.message", &[
+                ("number", &execution_number),
+                ("amount", &format!("{:.2}", amount)),
+            ])),
             data: Some(serde_json::json!({
                 "recurring_id": recurring_id,
                 "execution_number": execution_number,
@@ -259,4 +319,68 @@ impl NotificationDispatcher {
             })),
         }).await
     }
+
+    pub async fn notify_budget_alert(
+        &self,
+        user_id: Uuid,
+        alert_id: Uuid,
+        kind: &str,
+        value: Decimal,
+        threshold: Decimal,
+    ) -> anyhow::Result<Notification> {
+        let (title_key, message_key) = match kind {
+            "monthly_spend" => (
+                "notification.budget_alert.monthly_spend.title",
+                "notification.budget_alert.monthly_spend.message",
+            ),
+            "daily_consumption" => (
+                "notification.budget_alert.daily_consumption.title",
+                "notification.budget_alert.daily_consumption.message",
+            ),
+            _ => (
+                "notification.budget_alert.clearing_price.title",
+                "notification.budget_alert.clearing_price.message",
+            ),
+        };
+        let locale = i18n::user_locale(&self.db, user_id).await;
+        self.send(CreateNotificationRequest {
+            user_id,
+            notification_type: NotificationType::BudgetAlert,
+            title: i18n::t(locale, title_key).to_string(),
+            message: Some(i18n::tf(locale, message_key, &[
+                ("value", &value.round_dp(2)),
+                ("threshold", &threshold.round_dp(2)),
+            ])),
+            data: Some(serde_json::json!({
+                "budget_alert_id": alert_id,
+                "kind": kind,
+                "value": value.to_string(),
+                "threshold": threshold.to_string()
+            })),
+        }).await
+    }
+
+    pub async fn notify_data_export_ready(
+        &self,
+        user_id: Uuid,
+        export_id: Uuid,
+        dataset: &str,
+        row_count: i64,
+    ) -> anyhow::Result<Notification> {
+        let locale = i18n::user_locale(&self.db, user_id).await;
+        self.send(CreateNotificationRequest {
+            user_id,
+            notification_type: NotificationType::DataExportReady,
+            title: i18n::t(locale, "notification.data_export_ready.title").to_string(),
+            message: Some(i18n::tf(locale, "notification.data_export_ready.message", &[
+                ("dataset", &dataset),
+                ("rows", &row_count),
+            ])),
+            data: Some(serde_json::json!({
+                "export_id": export_id,
+                "dataset": dataset,
+                "row_count": row_count
+            })),
+        }).await
+    }
 }
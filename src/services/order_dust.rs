@@ -0,0 +1,171 @@
+//! Order Dust
+//!
+//! Remainders too small to trade. Once what is left of an order falls below
+//! its market's dust threshold the matchers cancel it instead of leaving it
+//! on the book, and the cancelled quantity is recorded here per order so a
+//! participant can see how much of their volume ended as dust.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::services::order_events::{self, NewOrderEvent, OrderEventType};
+
+const DUST_REASON: &str = "Remaining quantity below the market's dust threshold";
+
+/// Remainder of one order cancelled as dust
+#[derive(Debug, Clone)]
+pub struct NewDust {
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    /// buy or sell
+    pub side: &'static str,
+    pub filled: Decimal,
+    pub remaining: Decimal,
+}
+
+impl NewDust {
+    /// Cancellation event of the order
+    pub fn event(&self) -> NewOrderEvent {
+        NewOrderEvent::new(self.order_id, self.user_id, OrderEventType::Cancelled)
+            .with_quantities(self.filled, self.remaining)
+            .with_reason(DUST_REASON)
+    }
+}
+
+/// Dust of one market
+#[derive(Debug, Clone, Serialize, ToSchema, FromRow)]
+pub struct MarketDust {
+    pub market_id: Option<Uuid>,
+    pub market_code: Option<String>,
+    pub orders: i64,
+    #[schema(value_type = String)]
+    pub buy_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub sell_kwh: Decimal,
+    pub last_cancelled_at: DateTime<Utc>,
+}
+
+/// Volume a participant has lost to dust cancellations
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DustSummary {
+    pub orders: i64,
+    #[schema(value_type = String)]
+    pub total_kwh: Decimal,
+    pub markets: Vec<MarketDust>,
+}
+
+/// Cancel an open order's dust remainder, releasing its escrow, and
+/// account for it. Orders no longer open are left alone.
+pub async fn cancel(db: &PgPool, dust: NewDust) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+    let cancelled = sqlx::query(
+        r#"
+        UPDATE trading_orders SET status = 'cancelled', updated_at = NOW()
+        WHERE id = $1 AND status IN ('pending', 'active', 'partially_filled')
+        "#,
+    )
+    .bind(dust.order_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if cancelled == 0 {
+        return Ok(());
+    }
+    record_batch(&mut tx, std::slice::from_ref(&dust)).await?;
+    tx.commit().await?;
+
+    info!("Cancelled dust {} order {} (rem: {})", dust.side, dust.order_id, dust.remaining);
+    order_events::record(db, dust.event()).await;
+    Ok(())
+}
+
+/// Release the escrow still held for dust cancelled inside `tx` and
+/// account for it
+pub async fn record_batch(tx: &mut Transaction<'_, Postgres>, dust: &[NewDust]) -> Result<(), sqlx::Error> {
+    if dust.is_empty() {
+        return Ok(());
+    }
+    let order_ids: Vec<Uuid> = dust.iter().map(|d| d.order_id).collect();
+    let remaining: Vec<Decimal> = dust.iter().map(|d| d.remaining).collect();
+
+    // Currency back to buyers, energy back to sellers, as on cancellation
+    sqlx::query(
+        r#"
+        WITH released AS (
+            UPDATE escrow_records e
+            SET status = 'released',
+                description = 'Dust cancelled - released unfilled portion: ' || d.amount,
+                updated_at = NOW()
+            FROM UNNEST($1::uuid[], $2::numeric[]) AS d(order_id, amount)
+            WHERE e.order_id = d.order_id AND e.status = 'locked'
+            RETURNING e.order_id, d.amount
+        ),
+        refunds AS (
+            SELECT o.user_id,
+                   SUM(CASE WHEN o.side = 'buy' THEN r.amount * COALESCE(o.price_per_kwh, 0) ELSE 0 END) AS currency,
+                   SUM(CASE WHEN o.side = 'sell' THEN r.amount ELSE 0 END) AS energy
+            FROM (SELECT DISTINCT order_id, amount FROM released) r
+            JOIN trading_orders o ON o.id = r.order_id
+            GROUP BY o.user_id
+        )
+        UPDATE users u
+        SET balance = u.balance + f.currency,
+            locked_amount = u.locked_amount - f.currency,
+            locked_energy = u.locked_energy - f.energy
+        FROM refunds f
+        WHERE u.id = f.user_id
+        "#,
+    )
+    .bind(&order_ids)
+    .bind(&remaining)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO order_dust (order_id, user_id, market_id, side, amount_kwh)
+        SELECT d.order_id, d.user_id, o.market_id, d.side, d.amount
+        FROM UNNEST($1::uuid[], $2::uuid[], $3::text[], $4::numeric[]) AS d(order_id, user_id, side, amount)
+        LEFT JOIN trading_orders o ON o.id = d.order_id
+        ON CONFLICT (order_id) DO NOTHING
+        "#,
+    )
+    .bind(&order_ids)
+    .bind(dust.iter().map(|d| d.user_id).collect::<Vec<_>>())
+    .bind(dust.iter().map(|d| d.side).collect::<Vec<_>>())
+    .bind(&remaining)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// A participant's dust, per market
+pub async fn summary(db: &PgPool, user_id: Uuid) -> Result<DustSummary, sqlx::Error> {
+    let markets = sqlx::query_as::<_, MarketDust>(
+        r#"
+        SELECT d.market_id, m.code AS market_code, COUNT(*) AS orders,
+               COALESCE(SUM(d.amount_kwh) FILTER (WHERE d.side = 'buy'), 0) AS buy_kwh,
+               COALESCE(SUM(d.amount_kwh) FILTER (WHERE d.side = 'sell'), 0) AS sell_kwh,
+               MAX(d.created_at) AS last_cancelled_at
+        FROM order_dust d
+        LEFT JOIN markets m ON m.id = d.market_id
+        WHERE d.user_id = $1
+        GROUP BY d.market_id, m.code
+        ORDER BY MAX(d.created_at) DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(DustSummary {
+        orders: markets.iter().map(|m| m.orders).sum(),
+        total_kwh: markets.iter().map(|m| m.buy_kwh + m.sell_kwh).sum(),
+        markets,
+    })
+}
@@ -0,0 +1,393 @@
+//! Order Lifecycle Events
+//!
+//! Every order state change (accepted, fills, cancellation, expiry, rejection,
+//! reopening by an epoch rollback) is appended to `order_events` and pushed to the owner's WebSocket channel.
+//! Recording is best-effort: a failure is logged and never fails the trade path.
+//! Long-polling clients wait on a per-order watch channel fed by the same calls,
+//! and in-process consumers (the FIX gateway) follow a feed of all events.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use tokio::sync::{broadcast, watch};
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::handlers::websocket::{get_connection_manager, WsMessage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderEventType {
+    Accepted,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+    Expired,
+    Rejected,
+    /// Back on the book after an epoch rollback undid its fills
+    Reopened,
+}
+
+impl OrderEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accepted => "accepted",
+            Self::PartiallyFilled => "partially_filled",
+            Self::Filled => "filled",
+            Self::Cancelled => "cancelled",
+            Self::Expired => "expired",
+            Self::Rejected => "rejected",
+            Self::Reopened => "reopened",
+        }
+    }
+
+    /// Fill event for an order with `cumulative` of `original` filled
+    pub fn for_fill(cumulative: Decimal, original: Decimal) -> Self {
+        if cumulative >= original {
+            Self::Filled
+        } else {
+            Self::PartiallyFilled
+        }
+    }
+}
+
+/// Event to be recorded
+#[derive(Debug, Clone)]
+pub struct NewOrderEvent {
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub event_type: OrderEventType,
+    pub fill_quantity: Option<Decimal>,
+    pub cumulative_filled: Option<Decimal>,
+    pub remaining: Option<Decimal>,
+    pub price: Option<Decimal>,
+    pub reason: Option<String>,
+}
+
+impl NewOrderEvent {
+    pub fn new(order_id: Uuid, user_id: Uuid, event_type: OrderEventType) -> Self {
+        Self {
+            order_id,
+            user_id,
+            event_type,
+            fill_quantity: None,
+            cumulative_filled: None,
+            remaining: None,
+            price: None,
+            reason: None,
+        }
+    }
+
+    /// A fill of `quantity` at `price`, leaving `cumulative` of `original` filled
+    pub fn fill(
+        order_id: Uuid,
+        user_id: Uuid,
+        quantity: Decimal,
+        cumulative: Decimal,
+        original: Decimal,
+        price: Decimal,
+    ) -> Self {
+        Self {
+            fill_quantity: Some(quantity),
+            cumulative_filled: Some(cumulative),
+            remaining: Some((original - cumulative).max(Decimal::ZERO)),
+            price: Some(price),
+            ..Self::new(order_id, user_id, OrderEventType::for_fill(cumulative, original))
+        }
+    }
+
+    pub fn with_quantities(mut self, cumulative_filled: Decimal, remaining: Decimal) -> Self {
+        self.cumulative_filled = Some(cumulative_filled);
+        self.remaining = Some(remaining);
+        self
+    }
+
+    pub fn with_price(mut self, price: Decimal) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+/// Recorded order event
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct OrderEvent {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub event_type: String,
+    #[schema(value_type = Option<String>)]
+    pub fill_quantity: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub cumulative_filled: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub remaining: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub price: Option<Decimal>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Latest change of an order, as seen by long-polling waiters
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OrderStatusChange {
+    pub event_type: OrderEventType,
+    pub cumulative_filled: Option<String>,
+    pub remaining: Option<String>,
+    pub price: Option<String>,
+    pub reason: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+type ChangeSender = watch::Sender<Option<OrderStatusChange>>;
+
+/// Channels of orders someone is waiting on; entries go away with their last waiter
+static WATCHERS: Lazy<Mutex<HashMap<Uuid, ChangeSender>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Subscription to the next changes of one order
+pub struct OrderWatch {
+    order_id: Uuid,
+    receiver: watch::Receiver<Option<OrderStatusChange>>,
+}
+
+impl OrderWatch {
+    /// Wait for the next change; the latest one wins when several arrive together
+    pub async fn changed(&mut self) -> Option<OrderStatusChange> {
+        self.receiver.changed().await.ok()?;
+        self.receiver.borrow_and_update().clone()
+    }
+}
+
+impl Drop for OrderWatch {
+    fn drop(&mut self) {
+        let mut watchers = WATCHERS.lock().unwrap_or_else(|e| e.into_inner());
+        // Our receiver is still counted while we are being dropped
+        if watchers.get(&self.order_id).is_some_and(|tx| tx.receiver_count() <= 1) {
+            watchers.remove(&self.order_id);
+        }
+    }
+}
+
+/// Subscribe to changes of an order. Subscribe before reading the current
+/// state so a change in between is not missed.
+pub fn watch(order_id: Uuid) -> OrderWatch {
+    let mut watchers = WATCHERS.lock().unwrap_or_else(|e| e.into_inner());
+    let receiver = watchers
+        .entry(order_id)
+        .or_insert_with(|| watch::channel(None).0)
+        .subscribe();
+    OrderWatch { order_id, receiver }
+}
+
+/// Event as delivered on the in-process feed
+#[derive(Debug, Clone)]
+pub struct RecordedEvent {
+    pub event: NewOrderEvent,
+    pub timestamp: DateTime<Utc>,
+}
+
+static FEED: Lazy<broadcast::Sender<RecordedEvent>> = Lazy::new(|| broadcast::channel(1024).0);
+
+/// Follow every order event recorded from now on. Slow consumers miss
+/// events (`RecvError::Lagged`) rather than holding up the trade path.
+pub fn subscribe() -> broadcast::Receiver<RecordedEvent> {
+    FEED.subscribe()
+}
+
+fn notify_waiters(order_id: Uuid, change: OrderStatusChange) {
+    let watchers = WATCHERS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(sender) = watchers.get(&order_id) {
+        sender.send_replace(Some(change));
+    }
+}
+
+/// Persist an order event and notify the order owner
+pub async fn record(db: &PgPool, event: NewOrderEvent) {
+    let inserted = sqlx::query_scalar::<_, DateTime<Utc>>(
+        r#"
+        INSERT INTO order_events (
+            order_id, user_id, event_type, fill_quantity, cumulative_filled, remaining, price, reason
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING created_at
+        "#,
+    )
+    .bind(event.order_id)
+    .bind(event.user_id)
+    .bind(event.event_type.as_str())
+    .bind(event.fill_quantity)
+    .bind(event.cumulative_filled)
+    .bind(event.remaining)
+    .bind(event.price)
+    .bind(&event.reason)
+    .fetch_one(db)
+    .await;
+
+    let timestamp = match inserted {
+        Ok(created_at) => created_at,
+        Err(e) => {
+            warn!(
+                "Failed to record {} event for order {}: {}",
+                event.event_type.as_str(),
+                event.order_id,
+                e
+            );
+            Utc::now()
+        }
+    };
+
+    publish(event, timestamp).await;
+}
+
+/// Append events in one statement inside `tx`, e.g. the fills of a whole
+/// matching run. Events of the same order keep their relative order.
+/// Returns the recorded timestamps in event order; nothing is pushed to
+/// waiters or owners until [`publish`] is called after commit.
+pub async fn insert_batch(
+    tx: &mut Transaction<'_, Postgres>,
+    events: &[NewOrderEvent],
+) -> Result<Vec<DateTime<Utc>>, sqlx::Error> {
+    if events.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // NOW() is fixed for the transaction, so the ordinality spaces the
+    // timestamps a microsecond apart to keep the events ordered
+    let mut timestamps = sqlx::query_scalar::<_, DateTime<Utc>>(
+        r#"
+        INSERT INTO order_events (
+            order_id, user_id, event_type, fill_quantity, cumulative_filled, remaining, price, reason, created_at
+        )
+        SELECT e.order_id, e.user_id, e.event_type, e.fill_quantity, e.cumulative_filled, e.remaining,
+               e.price, e.reason, NOW() + e.seq * INTERVAL '1 microsecond'
+        FROM UNNEST($1::uuid[], $2::uuid[], $3::text[], $4::numeric[], $5::numeric[], $6::numeric[],
+                    $7::numeric[], $8::text[])
+             WITH ORDINALITY AS e(order_id, user_id, event_type, fill_quantity, cumulative_filled,
+                                  remaining, price, reason, seq)
+        RETURNING created_at
+        "#,
+    )
+    .bind(events.iter().map(|e| e.order_id).collect::<Vec<_>>())
+    .bind(events.iter().map(|e| e.user_id).collect::<Vec<_>>())
+    .bind(events.iter().map(|e| e.event_type.as_str()).collect::<Vec<_>>())
+    .bind(events.iter().map(|e| e.fill_quantity).collect::<Vec<_>>())
+    .bind(events.iter().map(|e| e.cumulative_filled).collect::<Vec<_>>())
+    .bind(events.iter().map(|e| e.remaining).collect::<Vec<_>>())
+    .bind(events.iter().map(|e| e.price).collect::<Vec<_>>())
+    .bind(events.iter().map(|e| e.reason.clone()).collect::<Vec<_>>())
+    .fetch_all(&mut **tx)
+    .await?;
+
+    timestamps.sort();
+    Ok(timestamps)
+}
+
+/// Push a recorded event to its waiters, the event feed and the owner's
+/// WebSocket channel
+pub async fn publish(event: NewOrderEvent, timestamp: DateTime<Utc>) {
+    notify_waiters(
+        event.order_id,
+        OrderStatusChange {
+            event_type: event.event_type,
+            cumulative_filled: event.cumulative_filled.map(|d| d.to_string()),
+            remaining: event.remaining.map(|d| d.to_string()),
+            price: event.price.map(|d| d.to_string()),
+            reason: event.reason.clone(),
+            timestamp,
+        },
+    );
+
+    let _ = FEED.send(RecordedEvent {
+        event: event.clone(),
+        timestamp,
+    });
+
+    let message = WsMessage::OrderEvent {
+        order_id: event.order_id,
+        event_type: event.event_type,
+        fill_quantity: event.fill_quantity.map(|d| d.to_string()),
+        cumulative_filled: event.cumulative_filled.map(|d| d.to_string()),
+        remaining: event.remaining.map(|d| d.to_string()),
+        price: event.price.map(|d| d.to_string()),
+        reason: event.reason,
+        timestamp,
+    };
+    let _ = get_connection_manager().send_to_user(event.user_id, message).await;
+}
+
+/// Events of an order owned by `user_id`, oldest first
+pub async fn list_for_order(db: &PgPool, order_id: Uuid, user_id: Uuid) -> Result<Vec<OrderEvent>, sqlx::Error> {
+    sqlx::query_as::<_, OrderEvent>(
+        r#"
+        SELECT id, order_id, event_type, fill_quantity, cumulative_filled, remaining, price, reason, created_at
+        FROM order_events
+        WHERE order_id = $1 AND user_id = $2
+        ORDER BY created_at ASC, id ASC
+        "#,
+    )
+    .bind(order_id)
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_event_tracks_cumulative_quantity() {
+        let order_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+
+        let partial = NewOrderEvent::fill(order_id, user_id, Decimal::from(3), Decimal::from(3), Decimal::from(10), Decimal::ONE);
+        assert_eq!(partial.event_type, OrderEventType::PartiallyFilled);
+        assert_eq!(partial.remaining, Some(Decimal::from(7)));
+
+        let full = NewOrderEvent::fill(order_id, user_id, Decimal::from(7), Decimal::from(10), Decimal::from(10), Decimal::ONE);
+        assert_eq!(full.event_type, OrderEventType::Filled);
+        assert_eq!(full.remaining, Some(Decimal::ZERO));
+    }
+
+    fn change(event_type: OrderEventType) -> OrderStatusChange {
+        OrderStatusChange {
+            event_type,
+            cumulative_filled: None,
+            remaining: None,
+            price: None,
+            reason: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watch_receives_change_and_cleans_up() {
+        let order_id = Uuid::new_v4();
+        let mut first = watch(order_id);
+        let second = watch(order_id);
+
+        notify_waiters(order_id, change(OrderEventType::Cancelled));
+        let received = first.changed().await.unwrap();
+        assert_eq!(received.event_type, OrderEventType::Cancelled);
+
+        drop(second);
+        assert!(WATCHERS.lock().unwrap().contains_key(&order_id));
+        drop(first);
+        assert!(!WATCHERS.lock().unwrap().contains_key(&order_id));
+    }
+
+    #[test]
+    fn test_notify_without_waiters_is_a_no_op() {
+        let order_id = Uuid::new_v4();
+        notify_waiters(order_id, change(OrderEventType::Filled));
+        assert!(!WATCHERS.lock().unwrap().contains_key(&order_id));
+    }
+}
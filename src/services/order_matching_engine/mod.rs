@@ -1,4 +1,6 @@
 pub mod types;
+pub mod resident_book;
+mod resident;
 
 use anyhow::Result;
 use rust_decimal::prelude::ToPrimitive;
@@ -12,12 +14,32 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 
+use resident::ResidentState;
+
 use crate::{
     database::schema::types::{OrderStatus, OrderSide},
-    services::{market_clearing::{TradeMatch, MarketClearingService}, SettlementService, WebSocketService, GridTopologyService, BlockchainService},
+    models::{EnergyKwh, TokenAmount},
+    services::{market_clearing::{TradeMatch, MarketClearingService}, SettlementService, WebSocketService, GridTopologyService, BlockchainService, MarketAnalyticsAggregator, MarketSessionService, TradingHaltService},
+    services::market_analytics::DepthLevel,
+    services::markets::{Market, MarketService},
+    services::order_dust::{self, NewDust},
+    services::order_events::{self, NewOrderEvent},
+    services::trading_halts::{blocking_halt, HaltedAction, TradingHalt},
     middleware::metrics::{track_order_matched, track_trading_operation},
+    utils::decimal::to_lamports,
 };
 
+/// Dust record of a polled order with `filled` kWh filled
+fn dust_of(order: &crate::models::trading::TradingOrderDb, filled: Decimal) -> NewDust {
+    NewDust {
+        order_id: order.id,
+        user_id: order.user_id,
+        side: order.side.as_str(),
+        filled,
+        remaining: order.energy_amount - filled,
+    }
+}
+
 /// Background service that automatically matches orders with offers
 #[derive(Clone)]
 pub struct OrderMatchingEngine {
@@ -28,7 +50,13 @@ pub struct OrderMatchingEngine {
     settlement: Option<SettlementService>,
     market_clearing: Option<MarketClearingService>,
     blockchain_service: Option<BlockchainService>,
+    market_analytics: Option<MarketAnalyticsAggregator>,
+    market_session: Option<MarketSessionService>,
+    trading_halts: Option<TradingHaltService>,
     grid_topology: GridTopologyService,
+    markets: MarketService,
+    /// In-memory books when `MATCHING_MODE=resident`; polling otherwise
+    resident: Option<ResidentState>,
 }
 
 impl OrderMatchingEngine {
@@ -43,7 +71,21 @@ impl OrderMatchingEngine {
             info!("Order matching interval set to {} seconds", match_interval_secs);
         }
 
+        let resident = (std::env::var("MATCHING_MODE").as_deref() == Ok("resident")).then(|| {
+            let env_secs = |name: &str, default: u64| {
+                std::env::var(name)
+                    .ok()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .unwrap_or(default)
+            };
+            ResidentState::new(
+                env_secs("RESIDENT_BOOK_SNAPSHOT_SECS", 60),
+                env_secs("RESIDENT_BOOK_RESYNC_SECS", 300),
+            )
+        });
+
         Self {
+            markets: MarketService::new(db.clone()),
             db,
             running: Arc::new(RwLock::new(false)),
             match_interval_secs,
@@ -51,7 +93,11 @@ impl OrderMatchingEngine {
             settlement: None,
             market_clearing: None,
             blockchain_service: None,
+            market_analytics: None,
+            market_session: None,
+            trading_halts: None,
             grid_topology: GridTopologyService::new(),
+            resident,
         }
     }
 
@@ -73,6 +119,24 @@ impl OrderMatchingEngine {
         self
     }
 
+    /// Set the market analytics aggregator whose depth snapshot is refreshed after expiries
+    pub fn with_market_analytics(mut self, market_analytics: MarketAnalyticsAggregator) -> Self {
+        self.market_analytics = Some(market_analytics);
+        self
+    }
+
+    /// Set the market session calendar; orders are only matched while the market is open
+    pub fn with_market_session(mut self, market_session: MarketSessionService) -> Self {
+        self.market_session = Some(market_session);
+        self
+    }
+
+    /// Set the trading halts; halted markets and zones are left out of matching
+    pub fn with_trading_halts(mut self, trading_halts: TradingHaltService) -> Self {
+        self.trading_halts = Some(trading_halts);
+        self
+    }
+
     /// Set the Blockchain service for on-chain matching
     pub fn with_blockchain(mut self, blockchain_service: BlockchainService) -> Self {
         self.blockchain_service = Some(blockchain_service);
@@ -90,10 +154,15 @@ impl OrderMatchingEngine {
         drop(running);
 
         info!(
-            "🚀 Starting automated order matching engine (interval: {}s)",
-            self.match_interval_secs
+            "🚀 Starting automated order matching engine (interval: {}s, {} mode)",
+            self.match_interval_secs,
+            if self.resident.is_some() { "resident" } else { "polling" }
         );
 
+        if let Some(resident) = &self.resident {
+            self.start_resident(resident).await;
+        }
+
         let engine = self.clone();
         tokio::spawn(async move {
             engine.run_matching_loop().await;
@@ -107,109 +176,86 @@ impl OrderMatchingEngine {
         info!("⏹️  Stopped automated order matching engine");
     }
 
-    /// Minimum trade amount in kWh to avoid dust
-    const MIN_TRADE_AMOUNT: Decimal = Decimal::from_parts(100000000, 0, 0, false, 9); // 0.100000000
+    /// Have the resident books re-synced from `trading_orders`; false when
+    /// matching polls the database and holds no books
+    pub fn request_book_resync(&self) -> bool {
+        match &self.resident {
+            Some(state) => {
+                state.request_resync();
+                true
+            }
+            None => false,
+        }
+    }
 
-    /// Expire orders that have passed their expiration time
-    pub async fn expire_stale_orders(&self) -> Result<u64> {
-        let now = chrono::Utc::now();
-        
-        // Fetch stale orders that need expiry
-        let stale_orders_rows = sqlx::query(
-            r#"
-            SELECT 
-                id, user_id, order_type, side, 
-                energy_amount, price_per_kwh, filled_amount, status, 
-                expires_at, created_at, filled_at, epoch_id, zone_id, meter_id, refund_tx_signature, order_pda,
-                trigger_price, trigger_type, trigger_status, trailing_offset, session_token, triggered_at
-            FROM trading_orders 
-            WHERE status IN ('active', 'pending', 'partially_filled') 
-            AND expires_at < $1
-            "#,
-        )
-        .bind(now)
-        .fetch_all(&self.db)
-        .await?;
+    /// Maximum orders expired per sweep
+    const EXPIRY_BATCH_SIZE: i64 = 500;
 
-        let stale_orders: Vec<crate::models::trading::TradingOrderDb> = stale_orders_rows.into_iter().map(|row| {
-             crate::models::trading::TradingOrderDb {
-                id: row.get("id"),
-                user_id: row.get("user_id"),
-                order_type: row.get("order_type"),
-                side: row.get("side"),
-                energy_amount: row.get("energy_amount"),
-                price_per_kwh: row.get("price_per_kwh"),
-                filled_amount: row.get("filled_amount"),
-                status: row.get("status"),
-                expires_at: row.get("expires_at"),
-                created_at: row.get("created_at"),
-                filled_at: row.get("filled_at"),
-                epoch_id: row.get("epoch_id"),
-                zone_id: row.get("zone_id"),
-                meter_id: row.get("meter_id"),
-                refund_tx_signature: row.get("refund_tx_signature"),
-                order_pda: row.get("order_pda"),
-                session_token: row.get("session_token"),
-                trigger_price: row.get("trigger_price"),
-                trigger_type: row.get("trigger_type"),
-                trigger_status: row.get("trigger_status"),
-                trailing_offset: row.get("trailing_offset"),
-                triggered_at: row.get("triggered_at"),
-             }
-        }).collect();
+    /// Expire orders that have passed their expiration time, then refresh the
+    /// orderbook depth and push a new snapshot to market subscribers
+    pub async fn expire_stale_orders(&self) -> Result<u64> {
+        let Some(market_clearing) = &self.market_clearing else {
+            return Ok(0);
+        };
 
         let mut expired_count = 0;
-        for order in stale_orders {
-            info!("🕒 Expiring order {}: type={}, side={}, amount={}, status={}", 
-                order.id, order.order_type.as_str(), order.side.as_str(), order.energy_amount, order.status.as_str());
-
-            // 1. Update status to expired
-            sqlx::query(
-                "UPDATE trading_orders SET status = 'expired', updated_at = NOW() WHERE id = $1"
-            )
-            .bind(order.id)
-            .execute(&self.db)
-            .await?;
-
-            // 2. Process Refund/Unlock
-            if let Some(market_clearing) = &self.market_clearing {
-                let remaining_amount = order.energy_amount - order.filled_amount.unwrap_or(Decimal::ZERO);
-                
-                if remaining_amount > Decimal::ZERO {
-                    match order.side {
-                        OrderSide::Buy => {
-                            let refund_value = remaining_amount * order.price_per_kwh;
-                            // The provided snippet for `receiver_wallet_addr` and `receiver_wallet` is incomplete and refers to an undefined `db_user`.
-                            // Assuming it was meant to be part of a larger, separate change or a placeholder, it's omitted to maintain syntactic correctness.
-                            if let Err(e) = market_clearing.unlock_funds(order.user_id, order.id, refund_value, "Order Expired").await {
-                                error!("Failed to refund funds for expired order {}: {}", order.id, e);
-                            } else {
-                                info!("💰 Refunded {} for expired buy order {}", refund_value, order.id);
-                            }
-                        }
-                        OrderSide::Sell => {
-                            if let Err(e) = market_clearing.unlock_energy(order.user_id, order.id, remaining_amount, "Order Expired").await {
-                                error!("Failed to unlock energy for expired order {}: {}", order.id, e);
-                            } else {
-                                info!("⚡ Unlocked {} energy for expired sell order {}", remaining_amount, order.id);
-                            }
-                        }
-                    }
-                }
+        for order_id in market_clearing.find_expired_orders(Self::EXPIRY_BATCH_SIZE).await? {
+            match market_clearing.expire_order(order_id).await {
+                Ok(Some(_)) => expired_count += 1,
+                Ok(None) => debug!("Order {} was no longer open when expiring", order_id),
+                Err(e) => error!("Failed to expire order {}: {}", order_id, e),
             }
-
-            expired_count += 1;
         }
 
         if expired_count > 0 {
-            info!("🧹 Expired {} stale orders totaling", expired_count);
+            info!("🧹 Expired {} stale orders", expired_count);
+            self.refresh_order_book().await;
         }
-        
+
         Ok(expired_count)
     }
 
+    /// Recapture orderbook depth and broadcast it after the book changed
+    /// outside of matching
+    async fn refresh_order_book(&self) {
+        let Some(analytics) = &self.market_analytics else {
+            return;
+        };
+
+        let snapshot = match analytics.refresh_depth().await {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                error!("Failed to refresh orderbook depth: {}", e);
+                return;
+            }
+        };
+
+        if let Some(ws) = &self.websocket_service {
+            let levels = |side: &[DepthLevel]| -> Vec<(String, String)> {
+                side.iter()
+                    .map(|l| (l.price_per_kwh.to_string(), l.total_kwh.to_string()))
+                    .collect()
+            };
+            let mid_price = snapshot
+                .best_bid
+                .zip(snapshot.best_ask)
+                .map(|(bid, ask)| ((bid + ask) / 2.0).to_string());
+
+            ws.broadcast_order_book_snapshot(
+                levels(&snapshot.bids),
+                levels(&snapshot.asks),
+                snapshot.best_bid.map(|p| p.to_string()),
+                snapshot.best_ask.map(|p| p.to_string()),
+                mid_price,
+                snapshot.spread.map(|s| s.to_string()),
+            )
+            .await;
+        }
+    }
+
     /// Main matching loop
     async fn run_matching_loop(&self) {
+        let mut expire_due = true;
         loop {
             // Check if we should continue running
             {
@@ -219,9 +265,21 @@ impl OrderMatchingEngine {
                 }
             }
 
-            // Cleanup expired orders first
-            if let Err(e) = self.expire_stale_orders().await {
-                error!("❌ Error expiring stale orders: {}", e);
+            // Cleanup expired orders first (resident rounds woken early skip this)
+            if expire_due {
+                if let Err(e) = self.expire_stale_orders().await {
+                    error!("❌ Error expiring stale orders: {}", e);
+                }
+            }
+
+            // Orders collected during pre-open (or left over at the close) wait for the open
+            if let Some(session) = self.market_session.as_ref().map(|s| s.current()) {
+                if !session.state.allows_clearing() {
+                    debug!("Market session {:?}, skipping matching cycle", session.state);
+                    tokio::time::sleep(Duration::from_secs(self.match_interval_secs)).await;
+                    expire_due = true;
+                    continue;
+                }
             }
 
             // Run one matching cycle
@@ -241,17 +299,67 @@ impl OrderMatchingEngine {
                 }
             }
 
-            // Sleep before next cycle
-            tokio::time::sleep(Duration::from_secs(self.match_interval_secs)).await;
+            // Sleep before next cycle; in resident mode a new order cuts it short
+            expire_due = self.wait_for_next_cycle().await;
         }
 
         info!("Order matching loop terminated");
     }
 
-    /// Run one matching cycle
+    /// Wait out the matching interval; returns false when woken early by
+    /// an order entering a resident book
+    async fn wait_for_next_cycle(&self) -> bool {
+        let interval = tokio::time::sleep(Duration::from_secs(self.match_interval_secs));
+        match &self.resident {
+            Some(resident) => tokio::select! {
+                _ = interval => true,
+                _ = resident.woken() => false,
+            },
+            None => {
+                interval.await;
+                true
+            }
+        }
+    }
+
+    /// Run one matching cycle: a separate matcher pass per active spot market
     async fn match_orders_cycle(&self) -> Result<usize> {
+        let markets = match &self.resident {
+            Some(resident) => resident.markets(),
+            None => self.markets.active_spot_markets().await?,
+        };
+
+        let halts = match &self.trading_halts {
+            Some(trading_halts) => trading_halts.active().await?,
+            None => Vec::new(),
+        };
+
+        let mut matches_created = 0;
+        for market in markets {
+            match self.match_market(&market, &halts).await {
+                Ok(matches) => matches_created += matches,
+                Err(e) => error!("❌ Error matching market {}: {}", market.code, e),
+            }
+        }
+        Ok(matches_created)
+    }
+
+    /// Match the open orders of one market against each other
+    async fn match_market(&self, market: &Market, halts: &[TradingHalt]) -> Result<usize> {
         use crate::models::trading::TradingOrderDb;
 
+        if let Some(resident) = &self.resident {
+            // The resident book matches all zones in one pass, so any halt touching the market pauses it
+            if halts.iter().any(|h| h.touches_market(market.id)) {
+                debug!("Market {} halted, skipping resident matching", market.code);
+                return Ok(0);
+            }
+            return Ok(self.match_market_resident(resident, market));
+        }
+
+        // Remainders below the market's dust threshold are cancelled
+        let min_trade_amount = market.dust_threshold();
+
         // Get all pending buy orders
         let buy_orders_rows = sqlx::query(
             r#"
@@ -263,14 +371,15 @@ impl OrderMatchingEngine {
                 trigger_price, trigger_type, trigger_status,
                 trailing_offset, triggered_at
             FROM trading_orders
-            WHERE side = 'buy'::order_side AND status IN ('pending', 'active', 'partially_filled')
+            WHERE market_id = $1 AND side = 'buy'::order_side AND status IN ('pending', 'active', 'partially_filled')
             ORDER BY created_at ASC
             "#,
         )
+        .bind(market.id)
         .fetch_all(&self.db)
         .await?;
 
-        let buy_orders_db: Vec<TradingOrderDb> = buy_orders_rows.into_iter().map(|row| {
+        let mut buy_orders_db: Vec<TradingOrderDb> = buy_orders_rows.into_iter().map(|row| {
             TradingOrderDb {
                 id: row.get("id"),
                 user_id: row.get("user_id"),
@@ -297,7 +406,7 @@ impl OrderMatchingEngine {
             }
         }).collect();
 
-        info!("Fetched {} buy orders", buy_orders_db.len());
+        info!("Fetched {} buy orders in market {}", buy_orders_db.len(), market.code);
 
         // Get all pending sell orders
         // We load them into a mutable vector to track fills during this cycle
@@ -311,10 +420,11 @@ impl OrderMatchingEngine {
                 trigger_price, trigger_type, trigger_status,
                 trailing_offset, triggered_at
             FROM trading_orders
-            WHERE side = 'sell'::order_side AND status IN ('pending', 'active', 'partially_filled')
+            WHERE market_id = $1 AND side = 'sell'::order_side AND status IN ('pending', 'active', 'partially_filled')
             ORDER BY price_per_kwh ASC, created_at ASC
             "#,
         )
+        .bind(market.id)
         .fetch_all(&self.db)
         .await?;
 
@@ -345,7 +455,24 @@ impl OrderMatchingEngine {
             }
         }).collect();
 
-        info!("Fetched {} sell orders", sell_orders_db.len());
+        info!("Fetched {} sell orders in market {}", sell_orders_db.len(), market.code);
+
+        // Halted markets and zones keep their resting orders out of the cycle
+        let unhalted =
+            |o: &TradingOrderDb| blocking_halt(halts, market.id, o.zone_id, HaltedAction::Match).is_none();
+        buy_orders_db.retain(unhalted);
+        sell_orders_db.retain(unhalted);
+
+        // Sweep dust off both sides before matching
+        for order in buy_orders_db.iter().chain(&sell_orders_db) {
+            let filled = order.filled_amount.unwrap_or(Decimal::ZERO);
+            if market.is_dust(order.energy_amount - filled) {
+                self.cancel_dust(dust_of(order, filled)).await;
+            }
+        }
+        let open = |o: &TradingOrderDb| !market.is_dust(o.energy_amount - o.filled_amount.unwrap_or(Decimal::ZERO));
+        buy_orders_db.retain(open);
+        sell_orders_db.retain(open);
 
         if buy_orders_db.is_empty() || sell_orders_db.is_empty() {
             return Ok(0);
@@ -361,17 +488,9 @@ impl OrderMatchingEngine {
             // Calculate remaining amount needed
             let mut remaining_buy_amount = buy_energy_amount - buy_filled_amount;
             
-            // Dust protection: If remaining amount is too small, mark as filled/cancelled to stop matching
-            if remaining_buy_amount < Self::MIN_TRADE_AMOUNT {
-                if remaining_buy_amount > Decimal::ZERO {
-                    // Start a new logical block to avoid borrowing issues if we were scanning orders
-                    // But here we are just deciding to skip/close this buy order
-                    let _ = sqlx::query("UPDATE trading_orders SET status = 'cancelled', updated_at = NOW() WHERE id = $1")
-                        .bind(buy_order.id)
-                        .execute(&self.db).await;
-                    info!("Cancelled dust buy order {} (rem: {})", buy_order.id, remaining_buy_amount);
-                }
-                continue; 
+            // Dust was swept above; nothing left means nothing to match
+            if remaining_buy_amount < min_trade_amount {
+                continue;
             }
 
             // 1. Calculate Landed Cost for all available sellers relative to THIS buyer
@@ -395,7 +514,7 @@ impl OrderMatchingEngine {
                 let sell_energy = sell_order.energy_amount;
                 let remaining_sell = sell_energy - sell_filled;
                 
-                if remaining_sell < Self::MIN_TRADE_AMOUNT {
+                if remaining_sell < min_trade_amount {
                     continue; // Skip dust entries
                 }
 
@@ -506,6 +625,26 @@ impl OrderMatchingEngine {
                             .bind(new_sell_status)
                             .bind(sell_order.id)
                             .execute(&self.db).await;
+
+                         order_events::record(
+                             &self.db,
+                             NewOrderEvent::fill(
+                                 sell_order.id, sell_order.user_id, match_amount,
+                                 sell_filled + match_amount, sell_order.energy_amount, candidate.match_price,
+                             ),
+                         ).await;
+                         order_events::record(
+                             &self.db,
+                             NewOrderEvent::fill(
+                                 buy_order.id, buy_order.user_id, match_amount,
+                                 buy_filled_amount, buy_energy_amount, candidate.match_price,
+                             ),
+                         ).await;
+
+                         let sell_filled = sell_order.filled_amount.unwrap_or_default();
+                         if market.is_dust(sell_order.energy_amount - sell_filled) {
+                             self.cancel_dust(dust_of(sell_order, sell_filled)).await;
+                         }
                     },
                     Err(e) => {
                         error!("Failed to create match: {}", e);
@@ -527,11 +666,23 @@ impl OrderMatchingEngine {
                 .bind(new_buy_status)
                 .bind(buy_order.id)
                 .execute(&self.db).await;
+
+            if market.is_dust(remaining_buy_amount) {
+                self.cancel_dust(dust_of(buy_order, buy_filled_amount)).await;
+            }
         }
 
         Ok(matches_created)
     }
 
+    /// Cancel a dust remainder; on failure the order is swept next cycle
+    async fn cancel_dust(&self, dust: NewDust) {
+        let order_id = dust.order_id;
+        if let Err(e) = order_dust::cancel(&self.db, dust).await {
+            warn!("Failed to cancel dust order {}: {}", order_id, e);
+        }
+    }
+
     /// Create an order match record
     async fn create_order_match(
         &self,
@@ -560,8 +711,10 @@ impl OrderMatchingEngine {
                 match_time,
                 status,
                 created_at,
-                updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, NOW(), $7, NOW(), NOW())
+                updated_at,
+                market_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, NOW(), $7, NOW(), NOW(),
+                (SELECT market_id FROM trading_orders WHERE id = $3))
             "#,
         )
         .bind(match_id)
@@ -574,7 +727,23 @@ impl OrderMatchingEngine {
         .execute(&self.db)
         .await?;
 
+        self.announce_match(match_id, buy_order_id, sell_order_id, energy_amount, price_per_kwh, buy_order_pda, sell_order_pda)
+            .await?;
+
+        Ok(match_id)
+    }
 
+    /// Mirror a recorded match on-chain and broadcast it
+    async fn announce_match(
+        &self,
+        match_id: Uuid,
+        buy_order_id: Uuid,
+        sell_order_id: Uuid,
+        energy_amount: Decimal,
+        price_per_kwh: Decimal,
+        buy_order_pda: Option<&str>,
+        sell_order_pda: Option<&str>,
+    ) -> Result<()> {
         // 2. Execute On-Chain Match (if blockchain service is available)
         if let Some(blockchain) = &self.blockchain_service {
              // We need the authority keypair to sign the match
@@ -592,7 +761,7 @@ impl OrderMatchingEngine {
                      
                                              
                      if let (Some(b_pda), Some(s_pda)) = (buy_order_pda, sell_order_pda) {
-                         let match_u64 = (energy_amount * Decimal::from(1_000_000_000)).to_u64().unwrap_or(0);
+                         let match_u64 = to_lamports(energy_amount)?;
                          
                          info!("Executing on-chain match: Buyer {}, Seller {}, Amount {}", b_pda, s_pda, match_u64);
                          
@@ -617,9 +786,8 @@ impl OrderMatchingEngine {
 
         // Broadcast order matched event via WebSocket
         if let Some(ws_service) = &self.websocket_service {
-            // energy_amount and price_per_kwh are already f64, no need to parse
-            let energy_f64 = energy_amount.to_f64().unwrap_or(0.0);
-            let price_f64 = price_per_kwh.to_f64().unwrap_or(0.0);
+            let matched = EnergyKwh::from(energy_amount);
+            let price = TokenAmount::from(price_per_kwh);
 
             tokio::spawn({
                 let ws = ws_service.clone();
@@ -627,13 +795,13 @@ impl OrderMatchingEngine {
                 let buy_id = buy_order_id.to_string();
                 let sell_id = sell_order_id.to_string();
                 async move {
-                    ws.broadcast_order_matched(buy_id, sell_id, mid, energy_f64, price_f64)
+                    ws.broadcast_order_matched(buy_id, sell_id, mid, matched, price)
                         .await;
                 }
             });
         }
 
-        Ok(match_id)
+        Ok(())
     }
 
     /// Create settlement for the matched trade
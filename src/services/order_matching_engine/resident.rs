@@ -0,0 +1,660 @@
+//! Resident Matching
+//!
+//! Continuous matching against in-memory books (`MATCHING_MODE=resident`)
+//! instead of loading every open order from Postgres for each round.
+//!
+//! - Books are recovered at start from the latest book snapshot of each
+//!   market plus the order event journal after it (markets without a
+//!   snapshot are loaded from `trading_orders`).
+//! - They are kept current from the in-process order event feed, and
+//!   re-synced with `trading_orders` periodically for changes made without
+//!   an event (amendments, orders placed through other instances).
+//! - Fills are written behind by a persister task. Nothing is settled or
+//!   announced before its fill is written, so a crash only loses matches
+//!   that never took effect, and their orders match again after recovery.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use sqlx::Row;
+use tokio::sync::{broadcast::error::RecvError, mpsc, Notify};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use super::resident_book::{BookFill, ResidentBook, RestingOrder};
+use super::OrderMatchingEngine;
+use crate::database::schema::types::OrderStatus;
+use crate::middleware::metrics::{track_order_matched, track_trading_operation};
+use crate::services::markets::Market;
+use crate::services::order_dust::{self, NewDust};
+use crate::services::order_events::{self, NewOrderEvent, OrderEventType, RecordedEvent};
+
+/// Journal events this much older than a snapshot are replayed too; replay
+/// is idempotent, so the margin only guards against events that were in
+/// flight when the snapshot was taken
+const JOURNAL_REPLAY_MARGIN_SECS: f64 = 5.0;
+
+/// Write queued by matching for the persister
+enum ResidentWrite {
+    Fill(BookFill),
+    /// Order whose remainder fell below the market's dust threshold
+    Dust(RestingOrder),
+}
+
+/// Books and write-behind queue of the resident matcher
+#[derive(Clone)]
+pub struct ResidentState {
+    books: Arc<Mutex<HashMap<Uuid, ResidentBook>>>,
+    /// Active spot markets, refreshed with each re-sync
+    markets: Arc<Mutex<Vec<Market>>>,
+    writes: mpsc::UnboundedSender<ResidentWrite>,
+    receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<ResidentWrite>>>>,
+    /// Writes queued but not yet persisted
+    pending: Arc<AtomicUsize>,
+    /// Bumped by every round that queued writes
+    generation: Arc<AtomicU64>,
+    /// A write failed; the books must be re-synced from the database
+    stale: Arc<AtomicBool>,
+    /// Woken when an order enters a book
+    wake: Arc<Notify>,
+    snapshot_interval_secs: u64,
+    resync_interval_secs: u64,
+}
+
+impl ResidentState {
+    pub fn new(snapshot_interval_secs: u64, resync_interval_secs: u64) -> Self {
+        let (writes, receiver) = mpsc::unbounded_channel();
+        Self {
+            books: Arc::new(Mutex::new(HashMap::new())),
+            markets: Arc::new(Mutex::new(Vec::new())),
+            writes,
+            receiver: Arc::new(Mutex::new(Some(receiver))),
+            pending: Arc::new(AtomicUsize::new(0)),
+            generation: Arc::new(AtomicU64::new(0)),
+            stale: Arc::new(AtomicBool::new(false)),
+            wake: Arc::new(Notify::new()),
+            snapshot_interval_secs,
+            resync_interval_secs,
+        }
+    }
+
+    /// Wait until an order enters a book
+    pub async fn woken(&self) {
+        self.wake.notified().await
+    }
+
+    pub fn markets(&self) -> Vec<Market> {
+        self.lock_markets().clone()
+    }
+
+    /// Have the books re-synced from `trading_orders` on the next maintenance tick
+    pub fn request_resync(&self) {
+        self.stale.store(true, Ordering::SeqCst);
+    }
+
+    fn lock_books(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, ResidentBook>> {
+        self.books.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_markets(&self) -> std::sync::MutexGuard<'_, Vec<Market>> {
+        self.markets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn queue(&self, write: ResidentWrite) {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        if self.writes.send(write).is_err() {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+            self.stale.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+const RESIDENT_ORDER_COLUMNS: &str = "id, user_id, market_id, side, price_per_kwh, energy_amount, filled_amount, \
+                                      zone_id, epoch_id, order_pda, session_token, expires_at, created_at";
+
+fn resting_order(row: &sqlx::postgres::PgRow) -> (Uuid, RestingOrder) {
+    let original: Decimal = row.get("energy_amount");
+    let filled: Option<Decimal> = row.get("filled_amount");
+    let created_at: Option<DateTime<Utc>> = row.get("created_at");
+    (
+        row.get("market_id"),
+        RestingOrder {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            side: row.get("side"),
+            price_per_kwh: row.get("price_per_kwh"),
+            original_amount: original,
+            remaining: original - filled.unwrap_or(Decimal::ZERO),
+            zone_id: row.get("zone_id"),
+            epoch_id: row.get("epoch_id"),
+            order_pda: row.get("order_pda"),
+            session_token: row.get("session_token"),
+            expires_at: row.get("expires_at"),
+            created_at: created_at.unwrap_or_else(Utc::now),
+        },
+    )
+}
+
+impl OrderMatchingEngine {
+    /// Recover the books and start the feed follower, the persister and the
+    /// snapshot/re-sync loop
+    pub(super) async fn start_resident(&self, state: &ResidentState) {
+        // Subscribe before recovering so nothing recorded meanwhile is missed
+        let feed = order_events::subscribe();
+
+        if let Err(e) = self.recover_books(state).await {
+            error!(
+                "❌ Failed to recover resident books, loading them from orders: {}",
+                e
+            );
+            if let Err(e) = self.resync_books(state, true).await {
+                error!("❌ Failed to load resident books: {}", e);
+            }
+        }
+
+        let Some(receiver) = state
+            .receiver
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        else {
+            warn!("Resident matcher is already started");
+            return;
+        };
+
+        let engine = self.clone();
+        let follower_state = state.clone();
+        tokio::spawn(async move { engine.follow_order_events(&follower_state, feed).await });
+
+        let engine = self.clone();
+        let persister_state = state.clone();
+        tokio::spawn(async move { engine.persist_writes(&persister_state, receiver).await });
+
+        let engine = self.clone();
+        let maintenance_state = state.clone();
+        tokio::spawn(async move { engine.maintain_books(&maintenance_state).await });
+
+        let orders: usize = state.lock_books().values().map(|b| b.len()).sum();
+        info!("📖 Resident matcher started with {} resting orders", orders);
+    }
+
+    /// Match one market's book in memory and queue the results for persistence
+    pub(super) fn match_market_resident(&self, state: &ResidentState, market: &Market) -> usize {
+        let grid = &self.grid_topology;
+        let mut books = state.lock_books();
+        let Some(book) = books.get_mut(&market.id) else {
+            return 0;
+        };
+
+        let round = book.match_round(
+            market.dust_threshold(),
+            Utc::now(),
+            |seller_zone, buyer_zone| {
+                (
+                    grid.calculate_wheeling_charge(seller_zone, buyer_zone),
+                    grid.calculate_loss_factor(seller_zone, buyer_zone),
+                )
+            },
+        );
+
+        let matched = round.fills.len();
+        if matched > 0 || !round.dust.is_empty() {
+            state.generation.fetch_add(1, Ordering::SeqCst);
+        }
+        for fill in round.fills {
+            track_order_matched("p2p", fill.amount.to_f64().unwrap_or(0.0));
+            track_trading_operation("match", true);
+            state.queue(ResidentWrite::Fill(fill));
+        }
+        for order in round.dust {
+            state.queue(ResidentWrite::Dust(order));
+        }
+        matched
+    }
+
+    /// Load books from their snapshots and replay the journal after them
+    async fn recover_books(&self, state: &ResidentState) -> Result<()> {
+        let markets = self.markets.active_spot_markets().await?;
+        let snapshots = sqlx::query("SELECT market_id, book FROM resident_book_snapshots")
+            .fetch_all(&self.db)
+            .await?;
+        let mut snapshots: HashMap<Uuid, ResidentBook> = snapshots
+            .into_iter()
+            .filter_map(|row| {
+                let book: sqlx::types::Json<ResidentBook> = row.try_get("book").ok()?;
+                Some((row.get("market_id"), book.0))
+            })
+            .collect();
+
+        let mut books = HashMap::new();
+        for market in &markets {
+            let book = match snapshots.remove(&market.id) {
+                Some(book) => self.replay_journal(market.id, book).await?,
+                None => {
+                    let mut book = ResidentBook::default();
+                    book.journal_position = Some(self.db_now().await?);
+                    for (_, order) in self.load_open_orders(Some(market.id), None).await? {
+                        book.upsert(order);
+                    }
+                    book
+                }
+            };
+            books.insert(market.id, book);
+        }
+
+        *state.lock_books() = books;
+        *state.lock_markets() = markets;
+        Ok(())
+    }
+
+    /// Bring a snapshot up to date with the order events recorded after it
+    async fn replay_journal(
+        &self,
+        market_id: Uuid,
+        mut book: ResidentBook,
+    ) -> Result<ResidentBook> {
+        let events = sqlx::query(
+            r#"
+            SELECT e.order_id, e.event_type, e.remaining, e.created_at
+            FROM order_events e
+            JOIN trading_orders o ON o.id = e.order_id
+            WHERE o.market_id = $1
+              AND ($2::timestamptz IS NULL OR e.created_at > $2 - make_interval(secs => $3))
+            ORDER BY e.created_at, e.id
+            "#,
+        )
+        .bind(market_id)
+        .bind(book.journal_position)
+        .bind(JOURNAL_REPLAY_MARGIN_SECS)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut accepted = Vec::new();
+        let replayed = events.len();
+        for row in events {
+            let order_id: Uuid = row.get("order_id");
+            let event_type: String = row.get("event_type");
+            match event_type.as_str() {
+                "accepted" | "reopened" => accepted.push(order_id),
+                "partially_filled" | "filled" => {
+                    if let Some(remaining) = row.get::<Option<Decimal>, _>("remaining") {
+                        book.apply_remaining(&order_id, remaining);
+                    }
+                }
+                _ => {
+                    book.remove(&order_id);
+                }
+            }
+            book.journal_position = Some(row.get("created_at"));
+        }
+
+        // Accepted and reopened orders enter as they stand now, if still open
+        if !accepted.is_empty() {
+            for (_, order) in self
+                .load_open_orders(Some(market_id), Some(&accepted))
+                .await?
+            {
+                book.upsert(order);
+            }
+        }
+
+        info!(
+            "📖 Replayed {} journal events onto the book of market {}",
+            replayed, market_id
+        );
+        Ok(book)
+    }
+
+    /// Apply order events from the feed until it closes
+    async fn follow_order_events(
+        &self,
+        state: &ResidentState,
+        mut feed: tokio::sync::broadcast::Receiver<RecordedEvent>,
+    ) {
+        loop {
+            match feed.recv().await {
+                Ok(recorded) => self.apply_event(state, recorded).await,
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(
+                        "⚠️ Resident matcher missed {} order events, re-syncing books",
+                        missed
+                    );
+                    state.stale.store(true, Ordering::SeqCst);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+
+    async fn apply_event(&self, state: &ResidentState, recorded: RecordedEvent) {
+        let RecordedEvent { event, timestamp } = recorded;
+        match event.event_type {
+            OrderEventType::Accepted | OrderEventType::Reopened => {
+                match self.load_open_orders(None, Some(&[event.order_id])).await {
+                    Ok(orders) => {
+                        let mut books = state.lock_books();
+                        for (market_id, order) in orders {
+                            if let Some(book) = books.get_mut(&market_id) {
+                                book.upsert(order);
+                                book.journal_position = Some(timestamp);
+                            }
+                        }
+                        drop(books);
+                        state.wake.notify_one();
+                    }
+                    Err(e) => {
+                        warn!("⚠️ Failed to load accepted order {}: {}", event.order_id, e);
+                        state.stale.store(true, Ordering::SeqCst);
+                    }
+                }
+            }
+            OrderEventType::PartiallyFilled | OrderEventType::Filled => {
+                let Some(remaining) = event.remaining else {
+                    return;
+                };
+                for book in state.lock_books().values_mut() {
+                    if book.get(&event.order_id).is_some() {
+                        book.apply_remaining(&event.order_id, remaining);
+                        book.journal_position = Some(timestamp);
+                    }
+                }
+            }
+            OrderEventType::Cancelled | OrderEventType::Expired | OrderEventType::Rejected => {
+                for book in state.lock_books().values_mut() {
+                    if book.remove(&event.order_id).is_some() {
+                        book.journal_position = Some(timestamp);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Write queued fills and dust cancellations until matching stops
+    async fn persist_writes(
+        &self,
+        state: &ResidentState,
+        mut receiver: mpsc::UnboundedReceiver<ResidentWrite>,
+    ) {
+        while let Some(write) = receiver.recv().await {
+            let result = match write {
+                ResidentWrite::Fill(fill) => self.persist_fill(fill).await,
+                ResidentWrite::Dust(order) => self.persist_dust(order).await,
+            };
+            if let Err(e) = result {
+                error!(
+                    "❌ Failed to persist resident book write, re-syncing books: {}",
+                    e
+                );
+                state.stale.store(true, Ordering::SeqCst);
+            }
+            state.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Record a fill, then settle and announce it
+    async fn persist_fill(&self, fill: BookFill) -> Result<()> {
+        let match_id = Uuid::new_v4();
+        let events = [&fill.sell, &fill.buy].map(|order| {
+            NewOrderEvent::fill(
+                order.id,
+                order.user_id,
+                fill.amount,
+                order.filled(),
+                order.original_amount,
+                fill.price,
+            )
+        });
+
+        let mut tx = self.db.begin().await?;
+        // The book hears about cancels from other instances only at the next
+        // re-sync, so either order may already be closed and its escrow
+        // returned: both must still be open to take the fill
+        let mut updated = 0;
+        for order in [&fill.buy, &fill.sell] {
+            let status = if order.remaining <= Decimal::ZERO {
+                OrderStatus::Filled
+            } else {
+                OrderStatus::PartiallyFilled
+            };
+            // Filled amounts only grow, whichever write lands last
+            updated += sqlx::query(
+                r#"
+                UPDATE trading_orders
+                SET filled_amount = GREATEST(COALESCE(filled_amount, 0), $2), status = $3, updated_at = NOW()
+                WHERE id = $1 AND status IN ('pending', 'active', 'partially_filled')
+                  AND energy_amount >= $2
+                "#,
+            )
+            .bind(order.id)
+            .bind(order.filled())
+            .bind(status)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+        if updated != 2 {
+            // Dropping the transaction rolls back the other order's update
+            return Err(anyhow::anyhow!(
+                "Dropped fill of buy order {} with sell order {}: an order is no longer open",
+                fill.buy.id,
+                fill.sell.id
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO order_matches (
+                id, epoch_id, buy_order_id, sell_order_id, matched_amount, match_price,
+                match_time, status, created_at, updated_at, market_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, NOW(), $7, NOW(), NOW(),
+                (SELECT market_id FROM trading_orders WHERE id = $3))
+            "#,
+        )
+        .bind(match_id)
+        .bind(fill.epoch_id)
+        .bind(fill.buy.id)
+        .bind(fill.sell.id)
+        .bind(fill.amount)
+        .bind(fill.price)
+        .bind(OrderStatus::Pending)
+        .execute(&mut *tx)
+        .await?;
+
+        let timestamps = order_events::insert_batch(&mut tx, &events).await?;
+        tx.commit().await?;
+
+        for (event, timestamp) in events.into_iter().zip(timestamps) {
+            order_events::publish(event, timestamp).await;
+        }
+
+        info!(
+            "Matched buy order {} with sell order {}: {} kWh at ${}/kWh base (resident book)",
+            fill.buy.id, fill.sell.id, fill.amount, fill.price
+        );
+
+        let total_energy_cost = fill.amount * fill.price;
+        self.announce_match(
+            match_id,
+            fill.buy.id,
+            fill.sell.id,
+            fill.amount,
+            fill.price,
+            fill.buy.order_pda.as_deref(),
+            fill.sell.order_pda.as_deref(),
+        )
+        .await?;
+
+        self.trigger_settlement(
+            match_id,
+            fill.buy.id,
+            fill.sell.id,
+            fill.buy.user_id,
+            fill.sell.user_id,
+            fill.amount,
+            fill.price,
+            total_energy_cost,
+            fill.epoch_id,
+            (
+                fill.amount * fill.wheeling_charge_per_kwh,
+                fill.loss_factor,
+                fill.amount * fill.loss_cost_per_kwh,
+                fill.buy.zone_id,
+                fill.sell.zone_id,
+            ),
+            fill.buy.session_token.clone(),
+            fill.sell.session_token.clone(),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    async fn persist_dust(&self, order: RestingOrder) -> Result<()> {
+        let dust = NewDust {
+            order_id: order.id,
+            user_id: order.user_id,
+            side: order.side.as_str(),
+            filled: order.filled(),
+            remaining: order.remaining,
+        };
+        Ok(order_dust::cancel(&self.db, dust).await?)
+    }
+
+    /// Snapshot and re-sync the books on their intervals, and re-sync
+    /// early after a failed write or a missed event
+    async fn maintain_books(&self, state: &ResidentState) {
+        let tick = std::time::Duration::from_secs(1);
+        let mut since_snapshot = 0;
+        let mut since_resync = 0;
+        loop {
+            tokio::time::sleep(tick).await;
+            since_snapshot += 1;
+            since_resync += 1;
+
+            if since_resync >= state.resync_interval_secs || state.stale.load(Ordering::SeqCst) {
+                match self.resync_books(state, false).await {
+                    Ok(true) => since_resync = 0,
+                    Ok(false) => {}
+                    Err(e) => error!("❌ Failed to re-sync resident books: {}", e),
+                }
+            }
+
+            if since_snapshot >= state.snapshot_interval_secs {
+                match self.snapshot_books(state).await {
+                    Ok(true) => since_snapshot = 0,
+                    Ok(false) => {}
+                    Err(e) => error!("❌ Failed to snapshot resident books: {}", e),
+                }
+            }
+        }
+    }
+
+    /// Rebuild the books from `trading_orders`. Unless `force`d this only
+    /// happens while every write is persisted and no round ran during the
+    /// load, so no fill is lost from memory; returns whether it happened.
+    async fn resync_books(&self, state: &ResidentState, force: bool) -> Result<bool> {
+        if !force && state.pending.load(Ordering::SeqCst) > 0 {
+            return Ok(false);
+        }
+        let generation = state.generation.load(Ordering::SeqCst);
+
+        let markets = self.markets.active_spot_markets().await?;
+        let position = self.db_now().await?;
+        let mut books: HashMap<Uuid, ResidentBook> = markets
+            .iter()
+            .map(|m| {
+                let book = ResidentBook {
+                    journal_position: Some(position),
+                    ..Default::default()
+                };
+                (m.id, book)
+            })
+            .collect();
+        for (market_id, order) in self.load_open_orders(None, None).await? {
+            if let Some(book) = books.get_mut(&market_id) {
+                book.upsert(order);
+            }
+        }
+
+        let mut current = state.lock_books();
+        if !force
+            && (state.pending.load(Ordering::SeqCst) > 0
+                || state.generation.load(Ordering::SeqCst) != generation)
+        {
+            return Ok(false);
+        }
+        *current = books;
+        drop(current);
+        *state.lock_markets() = markets;
+        state.stale.store(false, Ordering::SeqCst);
+        state.wake.notify_one();
+        Ok(true)
+    }
+
+    /// Store each book as the recovery point of its market. Only books whose
+    /// fills are all persisted are stored; returns whether they were.
+    async fn snapshot_books(&self, state: &ResidentState) -> Result<bool> {
+        let books = {
+            let books = state.lock_books();
+            if state.pending.load(Ordering::SeqCst) > 0 || state.stale.load(Ordering::SeqCst) {
+                return Ok(false);
+            }
+            books.clone()
+        };
+
+        for (market_id, book) in books {
+            sqlx::query(
+                r#"
+                INSERT INTO resident_book_snapshots (market_id, journal_position, order_count, book)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (market_id) DO UPDATE SET
+                    journal_position = EXCLUDED.journal_position,
+                    order_count = EXCLUDED.order_count,
+                    book = EXCLUDED.book,
+                    captured_at = NOW()
+                "#,
+            )
+            .bind(market_id)
+            .bind(book.journal_position)
+            .bind(book.len() as i32)
+            .bind(sqlx::types::Json(&book))
+            .execute(&self.db)
+            .await?;
+        }
+        Ok(true)
+    }
+
+    /// Open orders, optionally of one market and/or with the given IDs
+    async fn load_open_orders(
+        &self,
+        market_id: Option<Uuid>,
+        order_ids: Option<&[Uuid]>,
+    ) -> Result<Vec<(Uuid, RestingOrder)>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM trading_orders
+            WHERE status IN ('pending', 'active', 'partially_filled')
+              AND ($1::uuid IS NULL OR market_id = $1)
+              AND ($2::uuid[] IS NULL OR id = ANY($2))
+            "#,
+            RESIDENT_ORDER_COLUMNS
+        ))
+        .bind(market_id)
+        .bind(order_ids.map(|ids| ids.to_vec()))
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.iter().map(resting_order).collect())
+    }
+
+    async fn db_now(&self) -> Result<DateTime<Utc>> {
+        Ok(sqlx::query_scalar("SELECT NOW()")
+            .fetch_one(&self.db)
+            .await?)
+    }
+}
@@ -0,0 +1,326 @@
+//! Resident Orderbook
+//!
+//! In-memory book of one market for continuous matching. Resting orders are
+//! kept with their remaining quantity and matched without touching the
+//! database, with the same rules as the polling matcher: buy orders in time
+//! priority, each filled from the sell orders with the lowest landed cost
+//! (price plus wheeling and losses) that does not exceed its limit.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::database::schema::types::OrderSide;
+
+/// Open order held in the book
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestingOrder {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub side: OrderSide,
+    pub price_per_kwh: Decimal,
+    pub original_amount: Decimal,
+    pub remaining: Decimal,
+    pub zone_id: Option<i32>,
+    pub epoch_id: Option<Uuid>,
+    pub order_pda: Option<String>,
+    pub session_token: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl RestingOrder {
+    pub fn filled(&self) -> Decimal {
+        self.original_amount - self.remaining
+    }
+}
+
+/// Transmission costs between a seller's and a buyer's zone:
+/// wheeling charge per kWh and loss factor
+pub type ZoneCosts = (Decimal, Decimal);
+
+/// One match made in the book. Orders are as they stand after the fill.
+#[derive(Debug, Clone)]
+pub struct BookFill {
+    pub epoch_id: Uuid,
+    pub buy: RestingOrder,
+    pub sell: RestingOrder,
+    pub amount: Decimal,
+    /// Seller's price
+    pub price: Decimal,
+    pub wheeling_charge_per_kwh: Decimal,
+    pub loss_factor: Decimal,
+    pub loss_cost_per_kwh: Decimal,
+}
+
+/// Result of one matching round
+#[derive(Debug, Default)]
+pub struct BookRound {
+    pub fills: Vec<BookFill>,
+    /// Orders removed because their remainder fell below the dust threshold
+    pub dust: Vec<RestingOrder>,
+}
+
+/// Resting orders of one market
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResidentBook {
+    orders: HashMap<Uuid, RestingOrder>,
+    /// Latest order event the book reflects; journal replay starts here
+    pub journal_position: Option<DateTime<Utc>>,
+}
+
+impl ResidentBook {
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    pub fn get(&self, order_id: &Uuid) -> Option<&RestingOrder> {
+        self.orders.get(order_id)
+    }
+
+    pub fn orders(&self) -> impl Iterator<Item = &RestingOrder> {
+        self.orders.values()
+    }
+
+    /// Add or replace an order; orders with nothing left are dropped
+    pub fn upsert(&mut self, order: RestingOrder) {
+        if order.remaining > Decimal::ZERO {
+            self.orders.insert(order.id, order);
+        } else {
+            self.orders.remove(&order.id);
+        }
+    }
+
+    pub fn remove(&mut self, order_id: &Uuid) -> Option<RestingOrder> {
+        self.orders.remove(order_id)
+    }
+
+    /// Apply a fill reported elsewhere. `remaining` is absolute, so applying
+    /// the same fill twice (e.g. during journal replay) changes nothing.
+    pub fn apply_remaining(&mut self, order_id: &Uuid, remaining: Decimal) {
+        if let Some(order) = self.orders.get_mut(order_id) {
+            order.remaining = order.remaining.min(remaining);
+            if order.remaining <= Decimal::ZERO {
+                self.orders.remove(order_id);
+            }
+        }
+    }
+
+    /// Match the book once. Orders with less than `min_trade` left are
+    /// taken out as dust first. `costs(seller_zone, buyer_zone)` prices the
+    /// transmission between two zones.
+    pub fn match_round<F>(&mut self, min_trade: Decimal, now: DateTime<Utc>, costs: F) -> BookRound
+    where
+        F: Fn(Option<i32>, Option<i32>) -> ZoneCosts,
+    {
+        let live = |o: &RestingOrder| o.expires_at.map_or(true, |at| at > now);
+
+        let mut round = BookRound::default();
+        let mut dust: Vec<Uuid> = self
+            .orders
+            .values()
+            .filter(|o| live(o) && o.remaining < min_trade)
+            .map(|o| o.id)
+            .collect();
+        dust.sort();
+        round.dust.extend(dust.iter().filter_map(|id| self.orders.remove(id)));
+
+        let mut buys: Vec<Uuid> = self
+            .orders
+            .values()
+            .filter(|o| o.side == OrderSide::Buy && live(o))
+            .map(|o| o.id)
+            .collect();
+        buys.sort_by_key(|id| (self.orders[id].created_at, *id));
+
+        for buy_id in buys {
+            let Some(buy) = self.orders.get(&buy_id).cloned() else {
+                continue;
+            };
+
+            // Left below the threshold by this round; swept next round
+            if buy.remaining < min_trade {
+                continue;
+            }
+
+            struct Candidate {
+                sell_id: Uuid,
+                landed: Decimal,
+                created_at: DateTime<Utc>,
+                wheeling: Decimal,
+                loss_factor: Decimal,
+                loss_cost: Decimal,
+            }
+
+            let mut candidates: Vec<Candidate> = self
+                .orders
+                .values()
+                .filter(|s| s.side == OrderSide::Sell && live(s) && s.remaining >= min_trade)
+                .filter_map(|s| {
+                    let (wheeling, loss_factor) = costs(s.zone_id, buy.zone_id);
+                    let loss_cost = s.price_per_kwh * loss_factor;
+                    let landed = s.price_per_kwh + wheeling + loss_cost;
+                    (landed <= buy.price_per_kwh).then_some(Candidate {
+                        sell_id: s.id,
+                        landed,
+                        created_at: s.created_at,
+                        wheeling,
+                        loss_factor,
+                        loss_cost,
+                    })
+                })
+                .collect();
+            candidates.sort_by(|a, b| {
+                a.landed
+                    .cmp(&b.landed)
+                    .then(a.created_at.cmp(&b.created_at))
+            });
+
+            let mut buy_remaining = buy.remaining;
+            for candidate in candidates {
+                if buy_remaining <= Decimal::ZERO {
+                    break;
+                }
+                let sell = &self.orders[&candidate.sell_id];
+                let Some(epoch_id) = buy.epoch_id.or(sell.epoch_id) else {
+                    continue;
+                };
+
+                let amount = buy_remaining.min(sell.remaining);
+                buy_remaining -= amount;
+
+                let mut sell = sell.clone();
+                sell.remaining -= amount;
+                let mut buy_after = buy.clone();
+                buy_after.remaining = buy_remaining;
+
+                round.fills.push(BookFill {
+                    epoch_id,
+                    buy: buy_after,
+                    price: sell.price_per_kwh,
+                    sell: sell.clone(),
+                    amount,
+                    wheeling_charge_per_kwh: candidate.wheeling,
+                    loss_factor: candidate.loss_factor,
+                    loss_cost_per_kwh: candidate.loss_cost,
+                });
+                self.upsert(sell);
+            }
+
+            let mut buy = buy;
+            buy.remaining = buy_remaining;
+            self.upsert(buy);
+        }
+
+        round
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(side: OrderSide, price: i64, amount: i64, zone: i32, age_secs: i64) -> RestingOrder {
+        RestingOrder {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            side,
+            price_per_kwh: Decimal::from(price),
+            original_amount: Decimal::from(amount),
+            remaining: Decimal::from(amount),
+            zone_id: Some(zone),
+            epoch_id: Some(Uuid::nil()),
+            order_pda: None,
+            session_token: None,
+            expires_at: None,
+            created_at: Utc::now() - chrono::Duration::seconds(age_secs),
+        }
+    }
+
+    fn free(_: Option<i32>, _: Option<i32>) -> ZoneCosts {
+        (Decimal::ZERO, Decimal::ZERO)
+    }
+
+    #[test]
+    fn test_fills_cheapest_landed_sell_first() {
+        let mut book = ResidentBook::default();
+        let buy = order(OrderSide::Buy, 10, 8, 1, 10);
+        let near = order(OrderSide::Sell, 6, 5, 1, 5);
+        let far = order(OrderSide::Sell, 5, 5, 2, 5);
+        book.upsert(buy.clone());
+        book.upsert(near.clone());
+        book.upsert(far.clone());
+
+        // Crossing zones costs 2 per kWh, so the nominally cheaper seller lands at 7
+        let round = book.match_round(Decimal::ONE, Utc::now(), |from, to| {
+            if from == to {
+                (Decimal::ZERO, Decimal::ZERO)
+            } else {
+                (Decimal::from(2), Decimal::ZERO)
+            }
+        });
+
+        assert_eq!(round.fills.len(), 2);
+        assert_eq!(round.fills[0].sell.id, near.id);
+        assert_eq!(round.fills[0].amount, Decimal::from(5));
+        assert_eq!(round.fills[1].sell.id, far.id);
+        assert_eq!(round.fills[1].amount, Decimal::from(3));
+        assert_eq!(round.fills[1].buy.remaining, Decimal::ZERO);
+
+        assert!(book.get(&buy.id).is_none());
+        assert!(book.get(&near.id).is_none());
+        assert_eq!(book.get(&far.id).unwrap().remaining, Decimal::from(2));
+    }
+
+    #[test]
+    fn test_no_fill_above_limit_and_dust_buys_leave() {
+        let mut book = ResidentBook::default();
+        let buy = order(OrderSide::Buy, 4, 5, 1, 10);
+        let dust = order(OrderSide::Buy, 9, 1, 1, 20);
+        book.upsert(buy.clone());
+        book.upsert(dust.clone());
+        book.upsert(order(OrderSide::Sell, 5, 5, 1, 5));
+
+        let round = book.match_round(Decimal::from(2), Utc::now(), free);
+        assert!(round.fills.is_empty());
+        assert_eq!(round.dust.len(), 1);
+        assert_eq!(round.dust[0].id, dust.id);
+        assert_eq!(book.len(), 2);
+    }
+
+    #[test]
+    fn test_sell_dust_leaves_the_book() {
+        let mut book = ResidentBook::default();
+        let dust = order(OrderSide::Sell, 3, 1, 1, 20);
+        let sell = order(OrderSide::Sell, 5, 5, 1, 5);
+        book.upsert(dust.clone());
+        book.upsert(sell.clone());
+
+        let round = book.match_round(Decimal::from(2), Utc::now(), free);
+        assert_eq!(round.dust.len(), 1);
+        assert_eq!(round.dust[0].id, dust.id);
+        assert!(book.get(&dust.id).is_none());
+        assert!(book.get(&sell.id).is_some());
+    }
+
+    #[test]
+    fn test_apply_remaining_is_idempotent() {
+        let mut book = ResidentBook::default();
+        let sell = order(OrderSide::Sell, 5, 10, 1, 5);
+        book.upsert(sell.clone());
+
+        book.apply_remaining(&sell.id, Decimal::from(6));
+        book.apply_remaining(&sell.id, Decimal::from(6));
+        assert_eq!(book.get(&sell.id).unwrap().remaining, Decimal::from(6));
+
+        book.apply_remaining(&sell.id, Decimal::ZERO);
+        assert!(book.is_empty());
+    }
+}
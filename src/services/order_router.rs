@@ -0,0 +1,267 @@
+//! Smart Order Router
+//!
+//! Splits a market order between the P2P orderbook and the market's AMM
+//! pool. The orderbook is always preferred; only the shortfall goes to the
+//! pool, and only while the pool's effective price stays within a tolerance
+//! of the reference price. Each routed order leaves an execution report
+//! recording the split.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::schema::types::OrderSide;
+
+/// Pool token names used by energy/currency pools (`token_a`/`token_b`)
+pub const ENERGY_TOKEN: &str = "energy";
+pub const CURRENCY_TOKEN: &str = "currency";
+
+const BPS: u32 = 10_000;
+
+/// Aggregated opposite-side liquidity at one price
+#[derive(Debug, Clone, Copy, PartialEq, FromRow)]
+pub struct BookLevel {
+    pub price_per_kwh: Decimal,
+    pub kwh: Decimal,
+}
+
+/// Reserves of an energy/currency constant-product pool
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct PoolState {
+    pub id: Uuid,
+    /// `reserve_a`, kWh
+    pub reserve_energy: Decimal,
+    /// `reserve_b`, settlement currency
+    pub reserve_currency: Decimal,
+    pub fee_rate: Decimal,
+}
+
+impl PoolState {
+    /// Marginal price before any trade, currency per kWh
+    pub fn spot_price(&self) -> Option<Decimal> {
+        (self.reserve_energy > Decimal::ZERO).then(|| self.reserve_currency / self.reserve_energy)
+    }
+}
+
+/// Part of an order that the orderbook can fill
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookFill {
+    pub kwh: Decimal,
+    /// Worst price level reached; used as the limit of the marketable order
+    pub limit_price: Decimal,
+    pub avg_price: Decimal,
+}
+
+/// Cost or proceeds of trading energy with a pool
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmmQuote {
+    pub kwh: Decimal,
+    /// Currency paid (buy) or received (sell)
+    pub currency_amount: Decimal,
+    /// Pool fee, in the input token
+    pub fee_amount: Decimal,
+    pub effective_price: Decimal,
+}
+
+/// Walk the opposite side of the book, best level first
+pub fn fill_from_book(levels: &[BookLevel], kwh: Decimal) -> Option<BookFill> {
+    let mut remaining = kwh;
+    let mut filled = Decimal::ZERO;
+    let mut cost = Decimal::ZERO;
+    let mut limit_price = Decimal::ZERO;
+
+    for level in levels {
+        if remaining <= Decimal::ZERO {
+            break;
+        }
+        let take = level.kwh.min(remaining);
+        if take <= Decimal::ZERO {
+            continue;
+        }
+        filled += take;
+        cost += take * level.price_per_kwh;
+        remaining -= take;
+        limit_price = level.price_per_kwh;
+    }
+
+    (filled > Decimal::ZERO).then(|| BookFill {
+        kwh: filled,
+        limit_price,
+        avg_price: cost / filled,
+    })
+}
+
+/// Constant-product quote for `kwh` of energy, with the pool fee taken on the input.
+/// `None` if the pool cannot supply the amount.
+pub fn quote_amm(side: OrderSide, pool: &PoolState, kwh: Decimal) -> Option<AmmQuote> {
+    if kwh <= Decimal::ZERO || pool.reserve_energy <= Decimal::ZERO || pool.reserve_currency <= Decimal::ZERO {
+        return None;
+    }
+    let (currency_amount, fee_amount) = match side {
+        OrderSide::Buy => {
+            if kwh >= pool.reserve_energy || pool.fee_rate >= Decimal::ONE {
+                return None;
+            }
+            let net_in = pool.reserve_currency * kwh / (pool.reserve_energy - kwh);
+            let gross_in = net_in / (Decimal::ONE - pool.fee_rate);
+            (gross_in, gross_in - net_in)
+        }
+        OrderSide::Sell => {
+            let fee = kwh * pool.fee_rate;
+            let net_in = kwh - fee;
+            let out = pool.reserve_currency * net_in / (pool.reserve_energy + net_in);
+            (out, fee)
+        }
+    };
+
+    Some(AmmQuote {
+        kwh,
+        currency_amount,
+        fee_amount,
+        effective_price: currency_amount / kwh,
+    })
+}
+
+/// Whether an effective price is no worse than the reference by more than `tolerance_bps`
+pub fn within_tolerance(side: OrderSide, effective: Decimal, reference: Decimal, tolerance_bps: u32) -> bool {
+    let tolerance = Decimal::from(tolerance_bps) / Decimal::from(BPS);
+    match side {
+        OrderSide::Buy => effective <= reference * (Decimal::ONE + tolerance),
+        OrderSide::Sell => effective >= reference * (Decimal::ONE - tolerance),
+    }
+}
+
+/// Price the AMM leg is judged against: the best opposite orderbook price,
+/// or the pool spot price when the book is empty
+pub fn reference_price(levels: &[BookLevel], pool: Option<&PoolState>) -> Option<Decimal> {
+    levels
+        .first()
+        .map(|l| l.price_per_kwh)
+        .or_else(|| pool.and_then(PoolState::spot_price))
+}
+
+/// How a routed market order was split between the orderbook and the AMM
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ExecutionReport {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub market_id: Uuid,
+    pub side: String,
+    #[schema(value_type = String)]
+    pub requested_kwh: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub reference_price: Option<Decimal>,
+    pub tolerance_bps: i32,
+    /// Marketable limit order placed for the orderbook leg; it fills in the next matching cycle
+    pub book_order_id: Option<Uuid>,
+    #[schema(value_type = String)]
+    pub book_kwh: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub book_limit_price: Option<Decimal>,
+    /// Volume-weighted price of the book liquidity at routing time
+    #[schema(value_type = Option<String>)]
+    pub book_avg_price: Option<Decimal>,
+    pub pool_id: Option<Uuid>,
+    pub swap_id: Option<Uuid>,
+    #[schema(value_type = String)]
+    pub amm_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub amm_currency_amount: Decimal,
+    #[schema(value_type = String)]
+    pub amm_fee_amount: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub amm_effective_price: Option<Decimal>,
+    /// Why the shortfall was not routed to the pool
+    pub amm_skip_reason: Option<String>,
+    #[schema(value_type = String)]
+    pub unfilled_kwh: Decimal,
+    pub created_at: DateTime<Utc>,
+}
+
+pub const REPORT_COLUMNS: &str = "id, user_id, market_id, side, requested_kwh, reference_price, tolerance_bps, \
+    book_order_id, book_kwh, book_limit_price, book_avg_price, pool_id, swap_id, amm_kwh, \
+    amm_currency_amount, amm_fee_amount, amm_effective_price, amm_skip_reason, unfilled_kwh, created_at";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(value: i64) -> Decimal {
+        Decimal::from(value)
+    }
+
+    fn pool(energy: i64, currency: i64) -> PoolState {
+        PoolState {
+            id: Uuid::nil(),
+            reserve_energy: d(energy),
+            reserve_currency: d(currency),
+            fee_rate: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_book_fill_walks_levels() {
+        let levels = [
+            BookLevel { price_per_kwh: d(4), kwh: d(10) },
+            BookLevel { price_per_kwh: d(5), kwh: d(10) },
+        ];
+        let fill = fill_from_book(&levels, d(15)).unwrap();
+        assert_eq!(fill.kwh, d(15));
+        assert_eq!(fill.limit_price, d(5));
+        // (10 * 4 + 5 * 5) / 15
+        assert_eq!(fill.avg_price, d(65) / d(15));
+
+        let short = fill_from_book(&levels, d(50)).unwrap();
+        assert_eq!(short.kwh, d(20));
+        assert_eq!(fill_from_book(&[], d(5)), None);
+    }
+
+    #[test]
+    fn test_amm_quotes() {
+        // 1000 kWh against 5000 currency, spot 5 per kWh
+        let p = pool(1000, 5000);
+        let buy = quote_amm(OrderSide::Buy, &p, d(100)).unwrap();
+        // 5000 * 100 / 900
+        assert_eq!(buy.currency_amount, d(500000) / d(900));
+        assert!(buy.effective_price > d(5));
+
+        let sell = quote_amm(OrderSide::Sell, &p, d(100)).unwrap();
+        // 5000 * 100 / 1100
+        assert_eq!(sell.currency_amount, d(500000) / d(1100));
+        assert!(sell.effective_price < d(5));
+
+        assert_eq!(quote_amm(OrderSide::Buy, &p, d(1000)), None);
+    }
+
+    #[test]
+    fn test_pool_fee_charged_on_input() {
+        let mut p = pool(1000, 5000);
+        p.fee_rate = Decimal::new(3, 3);
+        let sell = quote_amm(OrderSide::Sell, &p, d(100)).unwrap();
+        assert_eq!(sell.fee_amount, Decimal::new(3, 1));
+        let buy = quote_amm(OrderSide::Buy, &p, d(100)).unwrap();
+        assert_eq!(buy.currency_amount - buy.fee_amount, d(500000) / d(900));
+    }
+
+    #[test]
+    fn test_tolerance_is_one_sided() {
+        // 200 bps around a reference of 5
+        assert!(within_tolerance(OrderSide::Buy, Decimal::new(51, 1), d(5), 200));
+        assert!(!within_tolerance(OrderSide::Buy, Decimal::new(52, 1), d(5), 200));
+        assert!(within_tolerance(OrderSide::Buy, d(4), d(5), 0));
+        assert!(within_tolerance(OrderSide::Sell, Decimal::new(49, 1), d(5), 200));
+        assert!(!within_tolerance(OrderSide::Sell, Decimal::new(48, 1), d(5), 200));
+    }
+
+    #[test]
+    fn test_reference_prefers_book() {
+        let p = pool(1000, 5000);
+        let levels = [BookLevel { price_per_kwh: d(4), kwh: d(1) }];
+        assert_eq!(reference_price(&levels, Some(&p)), Some(d(4)));
+        assert_eq!(reference_price(&[], Some(&p)), Some(d(5)));
+        assert_eq!(reference_price(&[], None), None);
+    }
+}
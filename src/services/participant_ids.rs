@@ -0,0 +1,171 @@
+//! Public Participant IDs
+//!
+//! Orderbook, trade and market feed data shown to other participants names
+//! users by a public ID instead of their user ID or wallet. The ID is an HMAC
+//! of the user and the current rotation window, shaped as a UUID so it fits
+//! the fields it replaces: stable long enough to follow a market, but not
+//! linkable across windows. Only admins can map a public ID back to a user.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::services::websocket::MarketEvent;
+
+/// Maps user identities to rotating public participant IDs
+#[derive(Clone)]
+pub struct ParticipantIds {
+    key: Hmac<Sha256>,
+    rotation_secs: i64,
+}
+
+impl std::fmt::Debug for ParticipantIds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParticipantIds")
+            .field("rotation_secs", &self.rotation_secs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ParticipantIds {
+    pub fn new(secret: &[u8], rotation_hours: i64) -> Self {
+        Self {
+            key: <Hmac<Sha256> as Mac>::new_from_slice(secret).expect("HMAC accepts keys of any length"),
+            rotation_secs: rotation_hours.max(1) * 3600,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let secret = match &config.public_api.participant_id_secret {
+            Some(secret) => secret.as_bytes().to_vec(),
+            None => Sha256::digest(format!("gridtokenx-participants:{}", config.encryption_secret)).to_vec(),
+        };
+        Self::new(&secret, config.public_api.participant_id_rotation_hours)
+    }
+
+    /// Key unique to this process, for services built without configuration
+    pub fn ephemeral() -> Self {
+        Self::new(Uuid::new_v4().as_bytes(), 24)
+    }
+
+    fn window(&self, at: DateTime<Utc>) -> i64 {
+        at.timestamp().div_euclid(self.rotation_secs)
+    }
+
+    /// Start and end of the rotation window containing `at`
+    pub fn window_bounds(&self, at: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let start = self.window(at) * self.rotation_secs;
+        (
+            DateTime::from_timestamp(start, 0).unwrap_or(at),
+            DateTime::from_timestamp(start + self.rotation_secs, 0).unwrap_or(at),
+        )
+    }
+
+    /// Public ID of a user ID or wallet address in the window containing `at`
+    pub fn public_id_at(&self, identity: &str, at: DateTime<Utc>) -> Uuid {
+        let mut mac = self.key.clone();
+        mac.update(&self.window(at).to_be_bytes());
+        mac.update(identity.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        let bytes: [u8; 16] = digest[..16].try_into().expect("SHA-256 digest is 32 bytes");
+        uuid::Builder::from_random_bytes(bytes).into_uuid()
+    }
+
+    pub fn public_id(&self, identity: &str) -> Uuid {
+        self.public_id_at(identity, Utc::now())
+    }
+
+    pub fn public_user_id(&self, user_id: Uuid) -> Uuid {
+        self.public_id(&user_id.to_string())
+    }
+
+    /// User a public ID stood for in the window containing `at`
+    pub async fn resolve(&self, db: &PgPool, public_id: Uuid, at: DateTime<Utc>) -> Result<Option<Uuid>> {
+        let user_ids: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM users").fetch_all(db).await?;
+        Ok(user_ids
+            .into_iter()
+            .find(|user_id| self.public_id_at(&user_id.to_string(), at) == public_id))
+    }
+
+    /// Copy of a market feed event with user IDs and wallets replaced by public IDs
+    pub fn anonymize(&self, event: &MarketEvent) -> MarketEvent {
+        let public = |identity: &str| self.public_id(identity).to_string();
+        let mut event = event.clone();
+        match &mut event {
+            MarketEvent::OfferCreated { created_by, .. } | MarketEvent::OrderCreated { created_by, .. } => {
+                *created_by = public(created_by);
+            }
+            MarketEvent::TransactionUpdated { buyer_id, seller_id, .. }
+            | MarketEvent::TradeExecuted { buyer_id, seller_id, .. } => {
+                *buyer_id = public(buyer_id);
+                *seller_id = public(seller_id);
+            }
+            MarketEvent::MeterReadingReceived { user_id, wallet_address, .. }
+            | MarketEvent::TokensMinted { user_id, wallet_address, .. }
+            | MarketEvent::MeterReadingValidationFailed { user_id, wallet_address, .. } => {
+                let id = self.public_user_id(*user_id);
+                *user_id = id;
+                *wallet_address = id.to_string();
+            }
+            _ => {}
+        }
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_public_id_is_stable_within_window_and_rotates() {
+        let ids = ParticipantIds::new(b"secret", 24);
+        let user = Uuid::new_v4().to_string();
+        let start = DateTime::from_timestamp(86_400 * 100, 0).unwrap();
+
+        let id = ids.public_id_at(&user, start);
+        assert_eq!(ids.public_id_at(&user, start + Duration::hours(23)), id);
+        assert_ne!(ids.public_id_at(&user, start + Duration::hours(24)), id);
+        assert_ne!(id.to_string(), user);
+    }
+
+    #[test]
+    fn test_public_ids_differ_per_user_and_key() {
+        let at = Utc::now();
+        let ids = ParticipantIds::new(b"secret", 24);
+        assert_ne!(ids.public_id_at("alice", at), ids.public_id_at("bob", at));
+        assert_ne!(
+            ids.public_id_at("alice", at),
+            ParticipantIds::new(b"other", 24).public_id_at("alice", at)
+        );
+    }
+
+    #[test]
+    fn test_trade_event_is_anonymized() {
+        let ids = ParticipantIds::new(b"secret", 24);
+        let buyer = Uuid::new_v4().to_string();
+        let event = MarketEvent::TradeExecuted {
+            trade_id: "t1".to_string(),
+            buy_order_id: "b1".to_string(),
+            sell_order_id: "s1".to_string(),
+            buyer_id: buyer.clone(),
+            seller_id: "5Ff1rbSeLLerWa11et".to_string(),
+            quantity: "1".to_string(),
+            price: "3.5".to_string(),
+            total_value: "3.5".to_string(),
+            executed_at: Utc::now().to_rfc3339(),
+        };
+
+        let MarketEvent::TradeExecuted { buyer_id, seller_id, trade_id, .. } = ids.anonymize(&event) else {
+            panic!("event kind changed");
+        };
+        assert_eq!(trade_id, "t1");
+        assert_eq!(buyer_id, ids.public_id(&buyer).to_string());
+        assert!(!seller_id.contains("Wa11et"));
+    }
+}
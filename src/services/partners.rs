@@ -0,0 +1,488 @@
+//! B2B Partner Onboarding
+//!
+//! One admin call provisions a partner organization with its rate-limit
+//! tier, scoped API keys, webhook endpoints and sandbox users, and returns
+//! everything the partner's integration team needs as a single bundle.
+//! Plain API keys and webhook secrets appear in that bundle only.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::jwt::ApiKeyService;
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+use crate::services::report_subscriptions::{validate_webhook_url, webhook_secret};
+use crate::services::sandbox::{TestUser, TestUserSpec};
+use crate::services::SandboxService;
+
+/// Format identifier of the onboarding bundle
+const BUNDLE_FORMAT: &str = "gridtokenx.partner-onboarding";
+const BUNDLE_VERSION: u32 = 1;
+
+const MAX_API_KEYS: usize = 10;
+const MAX_WEBHOOKS: usize = 5;
+const MAX_SANDBOX_USERS: usize = 5;
+
+/// Scopes a partner key may be granted; admin and user management scopes
+/// are never issued to partners
+pub const PARTNER_SCOPES: &[&str] = &[
+    "energy:read",
+    "energy:submit",
+    "meters:read",
+    "meters:update",
+    "trading:read",
+    "trading:create",
+    "orders:*",
+    "offers:read",
+    "transactions:read",
+    "analytics:read",
+];
+
+/// Events a partner webhook endpoint may subscribe to
+pub const PARTNER_WEBHOOK_EVENTS: &[&str] = &[
+    "token_mint",
+    "token_transfer",
+    "order_created",
+    "order_matched",
+    "settlement",
+    "meter_registered",
+];
+
+/// Rate-limit tier of a partner's API keys
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PartnerTier {
+    #[default]
+    Standard,
+    Professional,
+    Enterprise,
+}
+
+impl PartnerTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PartnerTier::Standard => "standard",
+            PartnerTier::Professional => "professional",
+            PartnerTier::Enterprise => "enterprise",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "professional" => PartnerTier::Professional,
+            "enterprise" => PartnerTier::Enterprise,
+            _ => PartnerTier::Standard,
+        }
+    }
+
+    pub fn limits(&self) -> RateLimitTier {
+        let (requests_per_minute, burst) = match self {
+            PartnerTier::Standard => (60, 20),
+            PartnerTier::Professional => (600, 100),
+            PartnerTier::Enterprise => (3000, 500),
+        };
+        RateLimitTier {
+            tier: *self,
+            requests_per_minute,
+            burst,
+        }
+    }
+}
+
+/// Request limits of a tier, per API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct RateLimitTier {
+    pub tier: PartnerTier,
+    pub requests_per_minute: u32,
+    /// Requests allowed above the per-minute rate in a short spike
+    pub burst: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PartnerApiKeySpec {
+    /// Unique within the partner, e.g. `production-ingest`
+    pub name: String,
+    /// Scopes from the partner scope list, e.g. `energy:submit`
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PartnerWebhookSpec {
+    /// Public HTTPS endpoint
+    pub url: String,
+    /// e.g. order_matched, settlement
+    pub event_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct OnboardPartnerRequest {
+    #[validate(length(min = 1, max = 200))]
+    pub name: String,
+    /// Lowercase letters, digits and dashes; prefixes the partner's key names
+    pub slug: String,
+    #[serde(default)]
+    pub tier: PartnerTier,
+    /// At most 10 keys
+    pub api_keys: Vec<PartnerApiKeySpec>,
+    /// At most 5 endpoints
+    #[serde(default)]
+    pub webhooks: Vec<PartnerWebhookSpec>,
+    /// At most 5 users; requires the sandbox on devnet or localnet
+    #[serde(default)]
+    pub sandbox_users: Vec<TestUserSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct PartnerOrganization {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub rate_limit_tier: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct IssuedPartnerKey {
+    pub id: Uuid,
+    /// Stored key name, `partner:<slug>:<name>`
+    pub name: String,
+    /// Send as the `X-API-Key` header; not shown again
+    pub key: String,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProvisionedWebhook {
+    pub id: Uuid,
+    pub url: String,
+    /// Verifies the delivery signature; not shown again
+    pub secret: String,
+    pub event_types: Vec<String>,
+}
+
+/// Everything the partner's integration team needs, in one document
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OnboardingBundle {
+    /// Always `gridtokenx.partner-onboarding`
+    pub format: &'static str,
+    pub version: u32,
+    pub partner: PartnerOrganization,
+    pub api_base_url: String,
+    pub rate_limit: RateLimitTier,
+    pub api_keys: Vec<IssuedPartnerKey>,
+    pub webhooks: Vec<ProvisionedWebhook>,
+    pub sandbox_users: Vec<TestUser>,
+    /// Sandbox users that could not be provisioned
+    pub warnings: Vec<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+fn invalid_slug(slug: &str) -> bool {
+    slug.len() < 2
+        || slug.len() > 40
+        || slug.starts_with('-')
+        || slug.ends_with('-')
+        || !slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Check an onboarding request before anything is provisioned
+pub fn validate_request(request: &OnboardPartnerRequest) -> Result<()> {
+    if invalid_slug(&request.slug) {
+        return Err(ApiError::validation_field(
+            "slug",
+            "2-40 lowercase letters, digits or dashes, not starting or ending with a dash",
+        ));
+    }
+
+    if request.api_keys.is_empty() || request.api_keys.len() > MAX_API_KEYS {
+        return Err(ApiError::validation_field(
+            "api_keys",
+            format!("Between 1 and {} keys", MAX_API_KEYS),
+        ));
+    }
+    let mut names = Vec::with_capacity(request.api_keys.len());
+    for key in &request.api_keys {
+        if invalid_slug(&key.name) {
+            return Err(ApiError::validation_field(
+                "api_keys.name",
+                "2-40 lowercase letters, digits or dashes, not starting or ending with a dash",
+            ));
+        }
+        if names.contains(&key.name.as_str()) {
+            return Err(ApiError::validation_field(
+                "api_keys.name",
+                format!("Duplicate key name {}", key.name),
+            ));
+        }
+        names.push(key.name.as_str());
+        if key.scopes.is_empty() {
+            return Err(ApiError::validation_field("api_keys.scopes", "At least one scope"));
+        }
+        if let Some(scope) = key.scopes.iter().find(|s| !PARTNER_SCOPES.contains(&s.as_str())) {
+            return Err(ApiError::validation_field(
+                "api_keys.scopes",
+                format!("{} is not a partner scope; use {}", scope, PARTNER_SCOPES.join(", ")),
+            ));
+        }
+    }
+
+    if request.webhooks.len() > MAX_WEBHOOKS {
+        return Err(ApiError::validation_field(
+            "webhooks",
+            format!("At most {} endpoints", MAX_WEBHOOKS),
+        ));
+    }
+    for webhook in &request.webhooks {
+        validate_webhook_url(&webhook.url)?;
+        if webhook.event_types.is_empty() {
+            return Err(ApiError::validation_field("webhooks.event_types", "At least one event type"));
+        }
+        if let Some(event) = webhook
+            .event_types
+            .iter()
+            .find(|e| !PARTNER_WEBHOOK_EVENTS.contains(&e.as_str()))
+        {
+            return Err(ApiError::validation_field(
+                "webhooks.event_types",
+                format!("Unknown event {}; use {}", event, PARTNER_WEBHOOK_EVENTS.join(", ")),
+            ));
+        }
+    }
+
+    if request.sandbox_users.len() > MAX_SANDBOX_USERS {
+        return Err(ApiError::validation_field(
+            "sandbox_users",
+            format!("At most {} users", MAX_SANDBOX_USERS),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct PartnerService {
+    db: PgPool,
+    api_keys: ApiKeyService,
+    sandbox: SandboxService,
+    audit_logger: AuditLogger,
+    public_base_url: String,
+}
+
+impl PartnerService {
+    pub fn new(
+        db: PgPool,
+        api_keys: ApiKeyService,
+        sandbox: SandboxService,
+        audit_logger: AuditLogger,
+        public_base_url: String,
+    ) -> Self {
+        Self {
+            db,
+            api_keys,
+            sandbox,
+            audit_logger,
+            public_base_url,
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<PartnerOrganization>> {
+        Ok(sqlx::query_as::<_, PartnerOrganization>(
+            "SELECT id, name, slug, rate_limit_tier, created_by, created_at FROM partner_organizations ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Provision a partner organization with its keys, webhooks and sandbox
+    /// users. The organization, keys and webhooks are created together;
+    /// sandbox users are created afterwards and a failed one is reported
+    /// in the bundle's warnings.
+    pub async fn onboard(&self, admin_id: Uuid, request: OnboardPartnerRequest) -> Result<OnboardingBundle> {
+        validate_request(&request)?;
+        if !request.sandbox_users.is_empty() {
+            self.sandbox.cluster()?;
+        }
+
+        let mut tx = self.db.begin().await?;
+        let partner = sqlx::query_as::<_, PartnerOrganization>(
+            r#"
+            INSERT INTO partner_organizations (name, slug, rate_limit_tier, created_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (slug) DO NOTHING
+            RETURNING id, name, slug, rate_limit_tier, created_by, created_at
+            "#,
+        )
+        .bind(request.name.trim())
+        .bind(&request.slug)
+        .bind(request.tier.as_str())
+        .bind(admin_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::Conflict(format!("Partner {} already exists", request.slug)))?;
+
+        let mut api_keys = Vec::with_capacity(request.api_keys.len());
+        for spec in &request.api_keys {
+            let name = format!("partner:{}:{}", partner.slug, spec.name);
+            let (key, key_hash) = self.api_keys.generate_key(&name, spec.scopes.clone())?;
+            let id: Uuid = sqlx::query_scalar(
+                "INSERT INTO api_keys (name, key_hash, permissions, partner_id) VALUES ($1, $2, $3, $4) RETURNING id",
+            )
+            .bind(&name)
+            .bind(&key_hash)
+            .bind(serde_json::json!(spec.scopes))
+            .bind(partner.id)
+            .fetch_one(&mut *tx)
+            .await?;
+            api_keys.push(IssuedPartnerKey {
+                id,
+                name,
+                key,
+                scopes: spec.scopes.clone(),
+            });
+        }
+
+        let mut webhooks = Vec::with_capacity(request.webhooks.len());
+        for spec in &request.webhooks {
+            let secret = webhook_secret();
+            let id: Uuid = sqlx::query_scalar(
+                "INSERT INTO partner_webhook_endpoints (partner_id, url, secret, event_types) VALUES ($1, $2, $3, $4) RETURNING id",
+            )
+            .bind(partner.id)
+            .bind(spec.url.trim())
+            .bind(&secret)
+            .bind(&spec.event_types)
+            .fetch_one(&mut *tx)
+            .await?;
+            webhooks.push(ProvisionedWebhook {
+                id,
+                url: spec.url.trim().to_string(),
+                secret,
+                event_types: spec.event_types.clone(),
+            });
+        }
+        tx.commit().await?;
+
+        let mut sandbox_users = Vec::with_capacity(request.sandbox_users.len());
+        let mut warnings = Vec::new();
+        for (n, spec) in request.sandbox_users.iter().enumerate() {
+            match self.sandbox.create_test_user(spec).await {
+                Ok(user) => {
+                    sqlx::query("INSERT INTO partner_sandbox_users (partner_id, user_id) VALUES ($1, $2)")
+                        .bind(partner.id)
+                        .bind(user.user_id)
+                        .execute(&self.db)
+                        .await?;
+                    sandbox_users.push(user);
+                }
+                Err(e) => {
+                    warn!("Sandbox user {} for partner {} not created: {}", n, partner.slug, e);
+                    warnings.push(format!("sandbox_users[{}]: {}", n, e));
+                }
+            }
+        }
+
+        info!(
+            "🤝 Partner {} onboarded by {}: {} keys, {} webhooks, {} sandbox users",
+            partner.slug,
+            admin_id,
+            api_keys.len(),
+            webhooks.len(),
+            sandbox_users.len()
+        );
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "partner_onboarded".to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "partner_id": partner.id,
+                "slug": partner.slug,
+                "tier": request.tier,
+                "api_key_ids": api_keys.iter().map(|k| k.id).collect::<Vec<_>>(),
+                "webhook_ids": webhooks.iter().map(|w| w.id).collect::<Vec<_>>(),
+                "sandbox_user_ids": sandbox_users.iter().map(|u| u.user_id).collect::<Vec<_>>(),
+            })
+            .to_string(),
+        });
+
+        Ok(OnboardingBundle {
+            format: BUNDLE_FORMAT,
+            version: BUNDLE_VERSION,
+            rate_limit: PartnerTier::from_db(&partner.rate_limit_tier).limits(),
+            partner,
+            api_base_url: format!("{}/api/v1", self.public_base_url.trim_end_matches('/')),
+            api_keys,
+            webhooks,
+            sandbox_users,
+            warnings,
+            generated_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> OnboardPartnerRequest {
+        OnboardPartnerRequest {
+            name: "Acme Energy".to_string(),
+            slug: "acme-energy".to_string(),
+            tier: PartnerTier::Professional,
+            api_keys: vec![PartnerApiKeySpec {
+                name: "ingest".to_string(),
+                scopes: vec!["energy:submit".to_string(), "meters:read".to_string()],
+            }],
+            webhooks: vec![PartnerWebhookSpec {
+                url: "https://hooks.acme.example/gridtokenx".to_string(),
+                event_types: vec!["settlement".to_string()],
+            }],
+            sandbox_users: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_valid_request() {
+        assert!(validate_request(&request()).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_bad_slug_and_scopes() {
+        let mut bad = request();
+        bad.slug = "Acme_Energy".to_string();
+        assert!(validate_request(&bad).is_err());
+
+        let mut bad = request();
+        bad.api_keys[0].scopes = vec!["admin:*".to_string()];
+        assert!(validate_request(&bad).is_err());
+
+        let mut bad = request();
+        bad.api_keys.push(bad.api_keys[0].clone());
+        assert!(validate_request(&bad).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unknown_events_and_private_webhooks() {
+        let mut bad = request();
+        bad.webhooks[0].event_types = vec!["user_deleted".to_string()];
+        assert!(validate_request(&bad).is_err());
+
+        let mut bad = request();
+        bad.webhooks[0].url = "https://10.0.0.5/hook".to_string();
+        assert!(validate_request(&bad).is_err());
+    }
+
+    #[test]
+    fn test_tier_limits_increase() {
+        let standard = PartnerTier::Standard.limits();
+        let professional = PartnerTier::Professional.limits();
+        let enterprise = PartnerTier::Enterprise.limits();
+        assert!(standard.requests_per_minute < professional.requests_per_minute);
+        assert!(professional.requests_per_minute < enterprise.requests_per_minute);
+        assert_eq!(PartnerTier::from_db("enterprise"), PartnerTier::Enterprise);
+    }
+}
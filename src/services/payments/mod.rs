@@ -0,0 +1,883 @@
+//! Prepaid Credit Service
+//!
+//! Fiat top-ups through a payment provider, a prepaid credit ledger, credit
+//! application to settled buy trades and refunds. Every balance change is
+//! written to `prepaid_ledger` and the audit log.
+
+pub mod provider;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgConnection, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::PaymentsConfig;
+use crate::error::ApiError;
+use crate::services::{AuditEvent, AuditLogger};
+
+pub use provider::{
+    PaymentProvider, ProviderKind, RefundError, VerifiedWebhook, WebhookError, WebhookEvent,
+    WebhookSignature,
+};
+
+/// Prepaid top-up record
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct PrepaidTopUp {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub provider: String,
+    pub provider_payment_id: Option<String>,
+    pub amount: Decimal,
+    pub currency: String,
+    pub refunded_amount: Decimal,
+    pub status: String,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Prepaid ledger entry (signed amount)
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct PrepaidLedgerEntry {
+    pub id: Uuid,
+    pub entry_type: String,
+    pub amount: Decimal,
+    pub balance_after: Decimal,
+    pub topup_id: Option<Uuid>,
+    pub settlement_id: Option<Uuid>,
+    pub refund_id: Option<Uuid>,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Prepaid refund record
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct PrepaidRefund {
+    pub id: Uuid,
+    pub topup_id: Uuid,
+    pub amount: Decimal,
+    pub provider_refund_id: Option<String>,
+    pub initiated_by: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Newly created top-up with the data the client needs to complete payment
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TopUpCheckout {
+    pub topup: PrepaidTopUp,
+    /// Stripe PaymentIntent client secret
+    pub client_secret: Option<String>,
+    /// Omise authorize / PromptPay QR URL
+    pub next_action_url: Option<String>,
+}
+
+/// Ledger entry types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerEntryType {
+    TopUp,
+    Settlement,
+    Refund,
+    RefundReversal,
+}
+
+impl LedgerEntryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TopUp => "topup",
+            Self::Settlement => "settlement",
+            Self::Refund => "refund",
+            Self::RefundReversal => "refund_reversal",
+        }
+    }
+}
+
+/// References attached to a ledger entry
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LedgerRefs {
+    pub topup_id: Option<Uuid>,
+    pub settlement_id: Option<Uuid>,
+    pub refund_id: Option<Uuid>,
+}
+
+impl LedgerRefs {
+    fn describe(&self) -> String {
+        match (self.topup_id, self.settlement_id, self.refund_id) {
+            (_, Some(id), _) => format!("settlement:{}", id),
+            (_, _, Some(id)) => format!("refund:{}", id),
+            (Some(id), _, _) => format!("topup:{}", id),
+            _ => "none".to_string(),
+        }
+    }
+}
+
+/// Posted ledger change, returned so callers can audit after commit
+#[derive(Debug, Clone)]
+pub struct PostedEntry {
+    pub user_id: Uuid,
+    pub entry_type: LedgerEntryType,
+    pub amount: Decimal,
+    pub balance_after: Decimal,
+    pub refs: LedgerRefs,
+}
+
+impl PostedEntry {
+    pub fn audit_event(&self) -> AuditEvent {
+        AuditEvent::PrepaidLedgerEntry {
+            user_id: self.user_id,
+            entry_type: self.entry_type.as_str().to_string(),
+            amount: self.amount.to_string(),
+            balance_after: self.balance_after.to_string(),
+            reference: self.refs.describe(),
+        }
+    }
+}
+
+/// Prepaid credit service
+#[derive(Clone)]
+pub struct PrepaidService {
+    db: PgPool,
+    config: PaymentsConfig,
+    provider: Option<PaymentProvider>,
+    audit: AuditLogger,
+}
+
+impl PrepaidService {
+    pub fn new(db: PgPool, config: PaymentsConfig, audit: AuditLogger) -> Self {
+        let provider = match ProviderKind::parse(&config.provider) {
+            Some(kind) => Some(PaymentProvider::new(
+                kind,
+                config.api_key.clone(),
+                config.webhook_secret.clone(),
+                config.webhook_tolerance_secs,
+            )),
+            None => {
+                warn!("Unknown PAYMENT_PROVIDER '{}', prepaid top-ups disabled", config.provider);
+                None
+            }
+        };
+
+        Self {
+            db,
+            config,
+            provider,
+            audit,
+        }
+    }
+
+    pub async fn balance(&self, user_id: Uuid) -> Result<Decimal, ApiError> {
+        sqlx::query_scalar("SELECT prepaid_balance FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("User not found".to_string()))
+    }
+
+    pub async fn ledger(&self, user_id: Uuid, limit: i64) -> Result<Vec<PrepaidLedgerEntry>, ApiError> {
+        Ok(sqlx::query_as::<_, PrepaidLedgerEntry>(
+            r#"
+            SELECT id, entry_type, amount, balance_after, topup_id, settlement_id, refund_id,
+                   description, created_at
+            FROM prepaid_ledger
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn list_topups(&self, user_id: Uuid) -> Result<Vec<PrepaidTopUp>, ApiError> {
+        Ok(sqlx::query_as::<_, PrepaidTopUp>(&format!(
+            "SELECT {} FROM prepaid_topups WHERE user_id = $1 ORDER BY created_at DESC",
+            TOPUP_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Create a top-up and the matching payment at the provider
+    pub async fn create_topup(&self, user_id: Uuid, amount: Decimal) -> Result<TopUpCheckout, ApiError> {
+        let provider = self.enabled_provider()?;
+
+        let min = Decimal::try_from(self.config.min_topup).unwrap_or(Decimal::ONE);
+        let max = Decimal::try_from(self.config.max_topup).unwrap_or(Decimal::MAX);
+        if amount < min || amount > max {
+            return Err(ApiError::validation_field(
+                "amount",
+                format!("Top-up amount must be between {} and {}", min, max),
+            ));
+        }
+        if amount.round_dp(2) != amount {
+            return Err(ApiError::validation_field(
+                "amount",
+                "Top-up amount supports at most 2 decimal places",
+            ));
+        }
+
+        let topup_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO prepaid_topups (id, user_id, provider, amount, currency, status)
+            VALUES ($1, $2, $3, $4, $5, 'pending')
+            "#,
+        )
+        .bind(topup_id)
+        .bind(user_id)
+        .bind(provider.kind().as_str())
+        .bind(amount)
+        .bind(&self.config.currency)
+        .execute(&self.db)
+        .await?;
+
+        let payment = match provider
+            .create_payment(topup_id, user_id, to_minor(amount), &self.config.currency)
+            .await
+        {
+            Ok(payment) => payment,
+            Err(e) => {
+                warn!("Payment provider rejected top-up {}: {}", topup_id, e);
+                sqlx::query(
+                    "UPDATE prepaid_topups SET status = 'failed', failure_reason = $2 WHERE id = $1",
+                )
+                .bind(topup_id)
+                .bind(e.to_string())
+                .execute(&self.db)
+                .await?;
+                return Err(ApiError::ExternalService(
+                    "Payment provider unavailable, please try again".to_string(),
+                ));
+            }
+        };
+
+        let topup = sqlx::query_as::<_, PrepaidTopUp>(&format!(
+            "UPDATE prepaid_topups SET provider_payment_id = $2 WHERE id = $1 RETURNING {}",
+            TOPUP_COLUMNS
+        ))
+        .bind(topup_id)
+        .bind(&payment.provider_payment_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        self.audit.log_async(AuditEvent::PrepaidTopUpInitiated {
+            user_id,
+            topup_id,
+            provider: provider.kind().as_str().to_string(),
+            amount: amount.to_string(),
+        });
+
+        Ok(TopUpCheckout {
+            topup,
+            client_secret: payment.client_secret,
+            next_action_url: payment.next_action_url,
+        })
+    }
+
+    /// Refund unused credit from a completed top-up back to the original payment method
+    ///
+    /// Credit is debited before the provider call so it cannot be spent while
+    /// the refund is in flight; a provider failure reverses the debit.
+    pub async fn request_refund(
+        &self,
+        user_id: Uuid,
+        topup_id: Uuid,
+        amount: Option<Decimal>,
+    ) -> Result<PrepaidRefund, ApiError> {
+        let provider = self.enabled_provider()?;
+
+        let mut tx = self.db.begin().await?;
+
+        let topup = sqlx::query_as::<_, PrepaidTopUp>(&format!(
+            "SELECT {} FROM prepaid_topups WHERE id = $1 AND user_id = $2 FOR UPDATE",
+            TOPUP_COLUMNS
+        ))
+        .bind(topup_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Top-up not found".to_string()))?;
+
+        if topup.status != "succeeded" {
+            return Err(ApiError::Conflict(format!(
+                "Top-up is {} and cannot be refunded",
+                topup.status
+            )));
+        }
+        let provider_payment_id = topup
+            .provider_payment_id
+            .clone()
+            .ok_or_else(|| ApiError::Internal("Top-up has no provider payment".to_string()))?;
+
+        let available: Decimal = sqlx::query_scalar(
+            "SELECT prepaid_balance FROM users WHERE id = $1 FOR UPDATE",
+        )
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let refundable = refundable_amount(topup.amount, topup.refunded_amount, available);
+        let amount = amount.unwrap_or(refundable);
+        if amount <= Decimal::ZERO || amount.round_dp(2) != amount {
+            return Err(ApiError::validation_field(
+                "amount",
+                "Refund amount must be positive with at most 2 decimal places",
+            ));
+        }
+        if amount > refundable {
+            return Err(ApiError::validation_field(
+                "amount",
+                format!("At most {} of this top-up can be refunded", refundable),
+            ));
+        }
+
+        let refund_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO prepaid_refunds (id, topup_id, user_id, amount, initiated_by, status)
+            VALUES ($1, $2, $3, $4, 'user', 'pending')
+            "#,
+        )
+        .bind(refund_id)
+        .bind(topup_id)
+        .bind(user_id)
+        .bind(amount)
+        .execute(&mut *tx)
+        .await?;
+        add_refunded_amount(&mut tx, topup_id, amount).await?;
+
+        let debit = post_entry(
+            &mut tx,
+            user_id,
+            LedgerEntryType::Refund,
+            -amount,
+            LedgerRefs {
+                topup_id: Some(topup_id),
+                refund_id: Some(refund_id),
+                ..Default::default()
+            },
+            "Refund to original payment method",
+        )
+        .await?;
+        tx.commit().await?;
+        self.audit.log_async(debit.audit_event());
+
+        match provider
+            .refund(&provider_payment_id, to_minor(amount), refund_id)
+            .await
+        {
+            Ok(provider_refund_id) => {
+                let refund = sqlx::query_as::<_, PrepaidRefund>(&format!(
+                    r#"
+                    UPDATE prepaid_refunds
+                    SET provider_refund_id = COALESCE(provider_refund_id, $2), status = 'succeeded'
+                    WHERE id = $1
+                    RETURNING {}
+                    "#,
+                    REFUND_COLUMNS
+                ))
+                .bind(refund_id)
+                .bind(&provider_refund_id)
+                .fetch_one(&self.db)
+                .await?;
+                info!("💸 Refunded {} of top-up {} ({})", amount, topup_id, provider_refund_id);
+                Ok(refund)
+            }
+            Err(RefundError::Unknown(e)) => {
+                // The provider may have accepted it: keep the debit and let
+                // the refund webhook settle it
+                warn!("Refund {} of top-up {} left pending: {}", refund_id, topup_id, e);
+                let refund = sqlx::query_as::<_, PrepaidRefund>(&format!(
+                    "SELECT {} FROM prepaid_refunds WHERE id = $1",
+                    REFUND_COLUMNS
+                ))
+                .bind(refund_id)
+                .fetch_one(&self.db)
+                .await?;
+                Ok(refund)
+            }
+            Err(RefundError::Rejected(e)) => {
+                warn!("Provider refund failed for top-up {}: {}", topup_id, e);
+                let mut tx = self.db.begin().await?;
+                sqlx::query("UPDATE prepaid_refunds SET status = 'failed' WHERE id = $1")
+                    .bind(refund_id)
+                    .execute(&mut *tx)
+                    .await?;
+                add_refunded_amount(&mut tx, topup_id, -amount).await?;
+                let reversal = post_entry(
+                    &mut tx,
+                    user_id,
+                    LedgerEntryType::RefundReversal,
+                    amount,
+                    LedgerRefs {
+                        topup_id: Some(topup_id),
+                        refund_id: Some(refund_id),
+                        ..Default::default()
+                    },
+                    "Refund failed at payment provider",
+                )
+                .await?;
+                tx.commit().await?;
+                self.audit.log_async(reversal.audit_event());
+
+                Err(ApiError::ExternalService(
+                    "Payment provider could not process the refund".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Verify and apply a provider webhook; duplicate deliveries are ignored
+    pub async fn handle_webhook(
+        &self,
+        provider_name: &str,
+        signature: &WebhookSignature<'_>,
+        body: &[u8],
+    ) -> Result<(), ApiError> {
+        let provider = self
+            .provider
+            .as_ref()
+            .filter(|p| ProviderKind::parse(provider_name) == Some(p.kind()))
+            .ok_or_else(|| ApiError::NotFound("Unknown payment provider".to_string()))?;
+
+        let webhook = match provider.verify_webhook(signature, body, Utc::now().timestamp()) {
+            Ok(webhook) => webhook,
+            Err(e) => {
+                self.audit.log_async(AuditEvent::PaymentWebhookRejected {
+                    provider: provider_name.to_string(),
+                    reason: e.to_string(),
+                });
+                return Err(match e {
+                    WebhookError::Malformed(msg) => ApiError::BadRequest(msg),
+                    other => ApiError::Unauthorized(other.to_string()),
+                });
+            }
+        };
+
+        let mut tx = self.db.begin().await?;
+
+        let first_delivery = sqlx::query(
+            r#"
+            INSERT INTO payment_webhook_events (provider, event_id, event_type, payload)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (provider, event_id) DO NOTHING
+            "#,
+        )
+        .bind(provider.kind().as_str())
+        .bind(&webhook.event_id)
+        .bind(&webhook.event_type)
+        .bind(&webhook.payload)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+
+        if !first_delivery {
+            info!("Ignoring duplicate payment webhook {}", webhook.event_id);
+            return Ok(());
+        }
+
+        let posted = match webhook.event {
+            WebhookEvent::PaymentSucceeded {
+                provider_payment_id,
+                topup_id,
+                amount_minor,
+            } => {
+                self.on_payment_succeeded(&mut tx, &provider_payment_id, topup_id, amount_minor)
+                    .await?
+            }
+            WebhookEvent::PaymentFailed {
+                provider_payment_id,
+                topup_id,
+                reason,
+            } => {
+                sqlx::query(
+                    r#"
+                    UPDATE prepaid_topups
+                    SET status = 'failed', failure_reason = $3, completed_at = NOW()
+                    WHERE (id = $1 OR provider_payment_id = $2) AND status = 'pending'
+                    "#,
+                )
+                .bind(topup_id)
+                .bind(&provider_payment_id)
+                .bind(reason)
+                .execute(&mut *tx)
+                .await?;
+                None
+            }
+            WebhookEvent::Refunded {
+                provider_payment_id,
+                provider_refund_id,
+                refund_id,
+                amount_minor,
+            } => {
+                self.on_refunded(
+                    &mut tx,
+                    &provider_payment_id,
+                    &provider_refund_id,
+                    refund_id,
+                    amount_minor,
+                )
+                .await?
+            }
+            WebhookEvent::Ignored => None,
+        };
+
+        tx.commit().await?;
+        if let Some(entry) = posted {
+            self.audit.log_async(entry.audit_event());
+        }
+        Ok(())
+    }
+
+    async fn on_payment_succeeded(
+        &self,
+        tx: &mut PgConnection,
+        provider_payment_id: &str,
+        topup_id: Option<Uuid>,
+        amount_minor: i64,
+    ) -> Result<Option<PostedEntry>, ApiError> {
+        // Metadata id first: the webhook can beat the provider_payment_id update
+        let topup = sqlx::query_as::<_, PrepaidTopUp>(&format!(
+            "SELECT {} FROM prepaid_topups WHERE id = $1 OR provider_payment_id = $2 FOR UPDATE",
+            TOPUP_COLUMNS
+        ))
+        .bind(topup_id)
+        .bind(provider_payment_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(topup) = topup else {
+            self.reject_unknown(provider_payment_id);
+            return Ok(None);
+        };
+        if topup.status == "succeeded" || topup.status == "refunded" {
+            return Ok(None);
+        }
+
+        // Credit what was actually captured
+        let received = from_minor(amount_minor);
+        if received != topup.amount {
+            warn!(
+                "Top-up {} amount mismatch: expected {}, received {}",
+                topup.id, topup.amount, received
+            );
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE prepaid_topups
+            SET status = 'succeeded', amount = $2, provider_payment_id = $3,
+                failure_reason = NULL, completed_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(topup.id)
+        .bind(received)
+        .bind(provider_payment_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let entry = post_entry(
+            tx,
+            topup.user_id,
+            LedgerEntryType::TopUp,
+            received,
+            LedgerRefs {
+                topup_id: Some(topup.id),
+                ..Default::default()
+            },
+            &format!("Top-up via {}", topup.provider),
+        )
+        .await?;
+        info!("💳 Prepaid top-up {} credited {} to user {}", topup.id, received, topup.user_id);
+        Ok(Some(entry))
+    }
+
+    async fn on_refunded(
+        &self,
+        tx: &mut PgConnection,
+        provider_payment_id: &str,
+        provider_refund_id: &str,
+        refund_id: Option<Uuid>,
+        amount_minor: i64,
+    ) -> Result<Option<PostedEntry>, ApiError> {
+        // Refunds we initiated were already debited when requested, unless
+        // the provider rejected the request and the debit was reversed
+        let known = sqlx::query_as::<_, (Uuid, Uuid, Uuid, Decimal, String)>(
+            r#"
+            SELECT id, topup_id, user_id, amount, status FROM prepaid_refunds
+            WHERE id = $1 OR provider_refund_id = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(refund_id)
+        .bind(provider_refund_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some((known_id, topup_id, user_id, amount, status)) = known {
+            sqlx::query(
+                r#"
+                UPDATE prepaid_refunds
+                SET provider_refund_id = COALESCE(provider_refund_id, $2), status = 'succeeded'
+                WHERE id = $1
+                "#,
+            )
+            .bind(known_id)
+            .bind(provider_refund_id)
+            .execute(&mut *tx)
+            .await?;
+            if status != "failed" {
+                return Ok(None);
+            }
+
+            warn!("Refund {} reported rejected went through at the provider; debiting again", known_id);
+            add_refunded_amount(tx, topup_id, amount).await?;
+            let entry = post_entry(
+                tx,
+                user_id,
+                LedgerEntryType::Refund,
+                -amount,
+                LedgerRefs {
+                    topup_id: Some(topup_id),
+                    refund_id: Some(known_id),
+                    ..Default::default()
+                },
+                "Refund completed by payment provider after reversal",
+            )
+            .await?;
+            return Ok(Some(entry));
+        }
+
+        // Refund or chargeback issued outside the API: debit the credit in full,
+        // which may leave a negative balance that later top-ups settle
+        let topup = sqlx::query_as::<_, PrepaidTopUp>(&format!(
+            "SELECT {} FROM prepaid_topups WHERE provider_payment_id = $1 FOR UPDATE",
+            TOPUP_COLUMNS
+        ))
+        .bind(provider_payment_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(topup) = topup else {
+            self.reject_unknown(provider_payment_id);
+            return Ok(None);
+        };
+
+        let amount = from_minor(amount_minor);
+        let new_refund_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO prepaid_refunds (id, topup_id, user_id, amount, provider_refund_id, initiated_by, status)
+            VALUES ($1, $2, $3, $4, $5, 'provider', 'succeeded')
+            "#,
+        )
+        .bind(new_refund_id)
+        .bind(topup.id)
+        .bind(topup.user_id)
+        .bind(amount)
+        .bind(provider_refund_id)
+        .execute(&mut *tx)
+        .await?;
+        add_refunded_amount(tx, topup.id, amount).await?;
+
+        let entry = post_entry(
+            tx,
+            topup.user_id,
+            LedgerEntryType::Refund,
+            -amount,
+            LedgerRefs {
+                topup_id: Some(topup.id),
+                refund_id: Some(new_refund_id),
+                ..Default::default()
+            },
+            "Refund issued by payment provider",
+        )
+        .await?;
+        Ok(Some(entry))
+    }
+
+    fn enabled_provider(&self) -> Result<&PaymentProvider, ApiError> {
+        self.provider
+            .as_ref()
+            .filter(|p| p.is_enabled())
+            .ok_or_else(|| ApiError::service_unavailable("Prepaid top-up"))
+    }
+
+    fn reject_unknown(&self, provider_payment_id: &str) {
+        warn!("Payment webhook for unknown payment {}", provider_payment_id);
+        self.audit.log_async(AuditEvent::PaymentWebhookRejected {
+            provider: self.config.provider.clone(),
+            reason: format!("unknown payment {}", provider_payment_id),
+        });
+    }
+}
+
+/// Apply a buyer's prepaid credit to a settled trade inside the settlement transaction
+///
+/// Covers up to `amount_due` from credit and returns the same amount of escrowed
+/// funds to the buyer's balance. Returns the applied entry, if any, for auditing.
+pub async fn apply_to_settlement(
+    tx: &mut PgConnection,
+    buyer_id: Uuid,
+    settlement_id: Uuid,
+    amount_due: Decimal,
+) -> Result<Option<PostedEntry>, sqlx::Error> {
+    let already_applied: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM prepaid_ledger WHERE settlement_id = $1 AND entry_type = 'settlement')",
+    )
+    .bind(settlement_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if already_applied {
+        return Ok(None);
+    }
+
+    let available: Decimal = sqlx::query_scalar(
+        "SELECT prepaid_balance FROM users WHERE id = $1 FOR UPDATE",
+    )
+    .bind(buyer_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let applied = available.min(amount_due);
+    if applied <= Decimal::ZERO {
+        return Ok(None);
+    }
+
+    let entry = post_entry(
+        tx,
+        buyer_id,
+        LedgerEntryType::Settlement,
+        -applied,
+        LedgerRefs {
+            settlement_id: Some(settlement_id),
+            ..Default::default()
+        },
+        "Applied to buy trade",
+    )
+    .await?;
+
+    sqlx::query("UPDATE users SET balance = balance + $1 WHERE id = $2")
+        .bind(applied)
+        .bind(buyer_id)
+        .execute(&mut *tx)
+        .await?;
+
+    Ok(Some(entry))
+}
+
+/// Change a user's prepaid balance and record the ledger entry
+async fn post_entry(
+    tx: &mut PgConnection,
+    user_id: Uuid,
+    entry_type: LedgerEntryType,
+    amount: Decimal,
+    refs: LedgerRefs,
+    description: &str,
+) -> Result<PostedEntry, sqlx::Error> {
+    let balance_after: Decimal = sqlx::query_scalar(
+        "UPDATE users SET prepaid_balance = prepaid_balance + $1 WHERE id = $2 RETURNING prepaid_balance",
+    )
+    .bind(amount)
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO prepaid_ledger
+            (user_id, entry_type, amount, balance_after, topup_id, settlement_id, refund_id, description)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(user_id)
+    .bind(entry_type.as_str())
+    .bind(amount)
+    .bind(balance_after)
+    .bind(refs.topup_id)
+    .bind(refs.settlement_id)
+    .bind(refs.refund_id)
+    .bind(description)
+    .execute(&mut *tx)
+    .await?;
+
+    Ok(PostedEntry {
+        user_id,
+        entry_type,
+        amount,
+        balance_after,
+        refs,
+    })
+}
+
+async fn add_refunded_amount(
+    tx: &mut PgConnection,
+    topup_id: Uuid,
+    amount: Decimal,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE prepaid_topups
+        SET refunded_amount = refunded_amount + $2,
+            status = CASE WHEN refunded_amount + $2 >= amount THEN 'refunded' ELSE 'succeeded' END
+        WHERE id = $1
+        "#,
+    )
+    .bind(topup_id)
+    .bind(amount)
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
+
+const TOPUP_COLUMNS: &str = "id, user_id, provider, provider_payment_id, amount, currency, \
+    refunded_amount, status, failure_reason, created_at, completed_at";
+
+const REFUND_COLUMNS: &str =
+    "id, topup_id, amount, provider_refund_id, initiated_by, status, created_at";
+
+/// Unused credit that can still go back to a top-up's payment method
+pub fn refundable_amount(topup_amount: Decimal, already_refunded: Decimal, balance: Decimal) -> Decimal {
+    (topup_amount - already_refunded)
+        .min(balance)
+        .max(Decimal::ZERO)
+}
+
+/// Convert to provider minor units (satang / cents)
+pub fn to_minor(amount: Decimal) -> i64 {
+    (amount * Decimal::ONE_HUNDRED).round().to_i64().unwrap_or(0)
+}
+
+pub fn from_minor(amount_minor: i64) -> Decimal {
+    Decimal::new(amount_minor, 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minor_unit_conversion() {
+        assert_eq!(to_minor(Decimal::new(500, 0)), 50000);
+        assert_eq!(to_minor(Decimal::new(1234, 2)), 1234);
+        assert_eq!(from_minor(1234), Decimal::new(1234, 2));
+    }
+
+    #[test]
+    fn test_refundable_amount() {
+        let d = |v: i64| Decimal::new(v, 0);
+        // Limited by what is left of the top-up
+        assert_eq!(refundable_amount(d(1000), d(400), d(2000)), d(600));
+        // Limited by unspent credit
+        assert_eq!(refundable_amount(d(1000), d(0), d(250)), d(250));
+        // Negative balance after a chargeback
+        assert_eq!(refundable_amount(d(1000), d(0), d(-50)), Decimal::ZERO);
+    }
+}
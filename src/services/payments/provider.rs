@@ -0,0 +1,603 @@
+//! Payment provider integration (Stripe / Omise)
+//!
+//! Creates payments and refunds through the provider's REST API and verifies
+//! and normalizes incoming webhook events.
+
+use std::time::Duration;
+
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+use sha2::Sha256;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const STRIPE_API_BASE: &str = "https://api.stripe.com/v1";
+const OMISE_API_BASE: &str = "https://api.omise.co";
+
+/// Supported payment providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    Stripe,
+    Omise,
+}
+
+impl ProviderKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "stripe" => Some(Self::Stripe),
+            "omise" => Some(Self::Omise),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stripe => "stripe",
+            Self::Omise => "omise",
+        }
+    }
+}
+
+/// Payment created at the provider
+#[derive(Debug, Clone)]
+pub struct ProviderPayment {
+    pub provider_payment_id: String,
+    /// Stripe PaymentIntent client secret for the frontend SDK
+    pub client_secret: Option<String>,
+    /// URL the user must visit to complete payment (Omise authorize URI / PromptPay QR)
+    pub next_action_url: Option<String>,
+}
+
+/// Provider-agnostic webhook event
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookEvent {
+    PaymentSucceeded {
+        provider_payment_id: String,
+        /// Our top-up id from the payment metadata
+        topup_id: Option<Uuid>,
+        amount_minor: i64,
+    },
+    PaymentFailed {
+        provider_payment_id: String,
+        topup_id: Option<Uuid>,
+        reason: Option<String>,
+    },
+    Refunded {
+        provider_payment_id: String,
+        provider_refund_id: String,
+        /// Our refund id from the refund metadata (absent for dashboard refunds)
+        refund_id: Option<Uuid>,
+        amount_minor: i64,
+    },
+    /// Event type we do not act on
+    Ignored,
+}
+
+/// Verified webhook delivery
+#[derive(Debug, Clone)]
+pub struct VerifiedWebhook {
+    pub event_id: String,
+    pub event_type: String,
+    pub event: WebhookEvent,
+    pub payload: Value,
+}
+
+/// Signature headers sent with a webhook delivery
+#[derive(Debug, Clone, Default)]
+pub struct WebhookSignature<'a> {
+    /// `Stripe-Signature` header
+    pub stripe_signature: Option<&'a str>,
+    /// `Omise-Signature` header
+    pub omise_signature: Option<&'a str>,
+    /// `Omise-Signature-Timestamp` header
+    pub omise_timestamp: Option<&'a str>,
+}
+
+/// Why a refund request did not return a provider refund
+#[derive(Debug, thiserror::Error)]
+pub enum RefundError {
+    /// The provider declined the request or it was never sent: nothing was refunded
+    #[error("Refund rejected: {0}")]
+    Rejected(String),
+    /// No definitive answer (timeout, server error, unreadable response): the
+    /// refund may still have gone through and is settled by its webhook
+    #[error("Refund outcome unknown: {0}")]
+    Unknown(String),
+}
+
+/// Client errors mean the provider refused the request. A conflict is an
+/// idempotent retry racing the original, which may still succeed.
+fn is_definitive_rejection(status: StatusCode) -> bool {
+    status.is_client_error() && status != StatusCode::CONFLICT
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("Webhook secret is not configured")]
+    NotConfigured,
+    #[error("Missing or malformed signature header")]
+    MissingSignature,
+    #[error("Signature timestamp outside tolerance")]
+    Expired,
+    #[error("Signature mismatch")]
+    InvalidSignature,
+    #[error("Malformed payload: {0}")]
+    Malformed(String),
+}
+
+/// HTTP client for the configured provider
+#[derive(Clone)]
+pub struct PaymentProvider {
+    kind: ProviderKind,
+    client: Client,
+    api_key: Option<String>,
+    webhook_secret: Option<String>,
+    webhook_tolerance_secs: i64,
+}
+
+impl PaymentProvider {
+    pub fn new(
+        kind: ProviderKind,
+        api_key: Option<String>,
+        webhook_secret: Option<String>,
+        webhook_tolerance_secs: i64,
+    ) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            kind,
+            client,
+            api_key,
+            webhook_secret,
+            webhook_tolerance_secs,
+        }
+    }
+
+    pub fn kind(&self) -> ProviderKind {
+        self.kind
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    /// Create a payment for a top-up
+    pub async fn create_payment(
+        &self,
+        topup_id: Uuid,
+        user_id: Uuid,
+        amount_minor: i64,
+        currency: &str,
+    ) -> anyhow::Result<ProviderPayment> {
+        let api_key = self.api_key()?;
+        let amount = amount_minor.to_string();
+        let topup = topup_id.to_string();
+        let user = user_id.to_string();
+
+        match self.kind {
+            ProviderKind::Stripe => {
+                let response: Value = self
+                    .client
+                    .post(format!("{}/payment_intents", STRIPE_API_BASE))
+                    .basic_auth(api_key, Some(""))
+                    .header("Idempotency-Key", format!("topup-{}", topup_id))
+                    .form(&[
+                        ("amount", amount.as_str()),
+                        ("currency", currency),
+                        ("automatic_payment_methods[enabled]", "true"),
+                        ("metadata[topup_id]", topup.as_str()),
+                        ("metadata[user_id]", user.as_str()),
+                    ])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+
+                Ok(ProviderPayment {
+                    provider_payment_id: required_str(&response, "/id")?,
+                    client_secret: optional_str(&response, "/client_secret"),
+                    next_action_url: None,
+                })
+            }
+            ProviderKind::Omise => {
+                let response: Value = self
+                    .client
+                    .post(format!("{}/charges", OMISE_API_BASE))
+                    .basic_auth(api_key, Some(""))
+                    .form(&[
+                        ("amount", amount.as_str()),
+                        ("currency", currency),
+                        ("source[type]", "promptpay"),
+                        ("metadata[topup_id]", topup.as_str()),
+                        ("metadata[user_id]", user.as_str()),
+                    ])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+
+                Ok(ProviderPayment {
+                    provider_payment_id: required_str(&response, "/id")?,
+                    client_secret: None,
+                    next_action_url: optional_str(&response, "/authorize_uri").or_else(|| {
+                        optional_str(&response, "/source/scannable_code/image/download_uri")
+                    }),
+                })
+            }
+        }
+    }
+
+    /// Refund part or all of a payment, returning the provider refund id
+    pub async fn refund(
+        &self,
+        provider_payment_id: &str,
+        amount_minor: i64,
+        refund_id: Uuid,
+    ) -> Result<String, RefundError> {
+        let api_key = self.api_key().map_err(|e| RefundError::Rejected(e.to_string()))?;
+        let amount = amount_minor.to_string();
+        let refund = refund_id.to_string();
+
+        let request = match self.kind {
+            ProviderKind::Stripe => self
+                .client
+                .post(format!("{}/refunds", STRIPE_API_BASE))
+                .basic_auth(api_key, Some(""))
+                .header("Idempotency-Key", format!("refund-{}", refund_id))
+                .form(&[
+                    ("payment_intent", provider_payment_id),
+                    ("amount", amount.as_str()),
+                    ("metadata[refund_id]", refund.as_str()),
+                ]),
+            ProviderKind::Omise => self
+                .client
+                .post(format!("{}/charges/{}/refunds", OMISE_API_BASE, provider_payment_id))
+                .basic_auth(api_key, Some(""))
+                .form(&[
+                    ("amount", amount.as_str()),
+                    ("metadata[refund_id]", refund.as_str()),
+                ]),
+        };
+
+        // Only a request that never connected is known not to have been processed
+        let response = request.send().await.map_err(|e| {
+            if e.is_connect() {
+                RefundError::Rejected(e.to_string())
+            } else {
+                RefundError::Unknown(e.to_string())
+            }
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = format!("{}: {}", status, body);
+            return Err(if is_definitive_rejection(status) {
+                RefundError::Rejected(message)
+            } else {
+                RefundError::Unknown(message)
+            });
+        }
+
+        let response: Value = response
+            .json()
+            .await
+            .map_err(|e| RefundError::Unknown(e.to_string()))?;
+        required_str(&response, "/id").map_err(|e| RefundError::Unknown(e.to_string()))
+    }
+
+    /// Verify a webhook delivery and normalize its event
+    pub fn verify_webhook(
+        &self,
+        signature: &WebhookSignature<'_>,
+        body: &[u8],
+        now: i64,
+    ) -> Result<VerifiedWebhook, WebhookError> {
+        let secret = self
+            .webhook_secret
+            .as_deref()
+            .ok_or(WebhookError::NotConfigured)?;
+
+        match self.kind {
+            ProviderKind::Stripe => verify_stripe_signature(
+                secret,
+                signature.stripe_signature.ok_or(WebhookError::MissingSignature)?,
+                body,
+                now,
+                self.webhook_tolerance_secs,
+            )?,
+            ProviderKind::Omise => verify_omise_signature(
+                secret,
+                signature.omise_signature.ok_or(WebhookError::MissingSignature)?,
+                signature.omise_timestamp.ok_or(WebhookError::MissingSignature)?,
+                body,
+                now,
+                self.webhook_tolerance_secs,
+            )?,
+        }
+
+        let payload: Value =
+            serde_json::from_slice(body).map_err(|e| WebhookError::Malformed(e.to_string()))?;
+        parse_event(self.kind, payload)
+    }
+
+    fn api_key(&self) -> anyhow::Result<&str> {
+        self.api_key
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Payment provider API key is not configured"))
+    }
+}
+
+/// Stripe: `Stripe-Signature: t=<ts>,v1=<hex>[,v1=<hex>]`, HMAC over `<ts>.<body>`
+fn verify_stripe_signature(
+    secret: &str,
+    header: &str,
+    body: &[u8],
+    now: i64,
+    tolerance_secs: i64,
+) -> Result<(), WebhookError> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.push(value),
+            _ => {}
+        }
+    }
+    let timestamp = timestamp.ok_or(WebhookError::MissingSignature)?;
+    if signatures.is_empty() {
+        return Err(WebhookError::MissingSignature);
+    }
+    if (now - timestamp).abs() > tolerance_secs {
+        return Err(WebhookError::Expired);
+    }
+
+    if signatures
+        .iter()
+        .any(|sig| hmac_matches(secret.as_bytes(), timestamp, body, sig))
+    {
+        Ok(())
+    } else {
+        Err(WebhookError::InvalidSignature)
+    }
+}
+
+/// Omise: `Omise-Signature: <hex>[,<hex>]` and `Omise-Signature-Timestamp`,
+/// HMAC over `<ts>.<body>` keyed with the base64-decoded webhook secret
+fn verify_omise_signature(
+    secret: &str,
+    header: &str,
+    timestamp: &str,
+    body: &[u8],
+    now: i64,
+    tolerance_secs: i64,
+) -> Result<(), WebhookError> {
+    let timestamp: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| WebhookError::MissingSignature)?;
+    if (now - timestamp).abs() > tolerance_secs {
+        return Err(WebhookError::Expired);
+    }
+    let key = general_purpose::STANDARD
+        .decode(secret)
+        .map_err(|_| WebhookError::NotConfigured)?;
+
+    if header
+        .split(',')
+        .any(|sig| hmac_matches(&key, timestamp, body, sig.trim()))
+    {
+        Ok(())
+    } else {
+        Err(WebhookError::InvalidSignature)
+    }
+}
+
+fn hmac_matches(key: &[u8], timestamp: i64, body: &[u8], signature_hex: &str) -> bool {
+    let Ok(provided) = hex::decode(signature_hex) else {
+        return false;
+    };
+    let Ok(mut mac) = HmacSha256::new_from_slice(key) else {
+        return false;
+    };
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.verify_slice(&provided).is_ok()
+}
+
+fn parse_event(kind: ProviderKind, payload: Value) -> Result<VerifiedWebhook, WebhookError> {
+    let event_id = optional_str(&payload, "/id")
+        .ok_or_else(|| WebhookError::Malformed("missing event id".to_string()))?;
+
+    let (event_type, event) = match kind {
+        ProviderKind::Stripe => {
+            let event_type = optional_str(&payload, "/type").unwrap_or_default();
+            let object = payload.pointer("/data/object").cloned().unwrap_or(Value::Null);
+            let event = match event_type.as_str() {
+                "payment_intent.succeeded" => WebhookEvent::PaymentSucceeded {
+                    provider_payment_id: field(&object, "/id")?,
+                    topup_id: metadata_id(&object, "topup_id"),
+                    amount_minor: object
+                        .pointer("/amount_received")
+                        .and_then(Value::as_i64)
+                        .ok_or_else(|| WebhookError::Malformed("missing amount_received".to_string()))?,
+                },
+                "payment_intent.payment_failed" | "payment_intent.canceled" => {
+                    WebhookEvent::PaymentFailed {
+                        provider_payment_id: field(&object, "/id")?,
+                        topup_id: metadata_id(&object, "topup_id"),
+                        reason: optional_str(&object, "/last_payment_error/message")
+                            .or_else(|| optional_str(&object, "/cancellation_reason")),
+                    }
+                }
+                "refund.created" => WebhookEvent::Refunded {
+                    provider_payment_id: field(&object, "/payment_intent")?,
+                    provider_refund_id: field(&object, "/id")?,
+                    refund_id: metadata_id(&object, "refund_id"),
+                    amount_minor: amount(&object)?,
+                },
+                _ => WebhookEvent::Ignored,
+            };
+            (event_type, event)
+        }
+        ProviderKind::Omise => {
+            let event_type = optional_str(&payload, "/key").unwrap_or_default();
+            let object = payload.pointer("/data").cloned().unwrap_or(Value::Null);
+            let event = match event_type.as_str() {
+                "charge.complete" => match optional_str(&object, "/status").as_deref() {
+                    Some("successful") => WebhookEvent::PaymentSucceeded {
+                        provider_payment_id: field(&object, "/id")?,
+                        topup_id: metadata_id(&object, "topup_id"),
+                        amount_minor: amount(&object)?,
+                    },
+                    Some("failed") | Some("expired") => WebhookEvent::PaymentFailed {
+                        provider_payment_id: field(&object, "/id")?,
+                        topup_id: metadata_id(&object, "topup_id"),
+                        reason: optional_str(&object, "/failure_message"),
+                    },
+                    _ => WebhookEvent::Ignored,
+                },
+                "refund.create" => WebhookEvent::Refunded {
+                    provider_payment_id: field(&object, "/charge")?,
+                    provider_refund_id: field(&object, "/id")?,
+                    refund_id: metadata_id(&object, "refund_id"),
+                    amount_minor: amount(&object)?,
+                },
+                _ => WebhookEvent::Ignored,
+            };
+            (event_type, event)
+        }
+    };
+
+    Ok(VerifiedWebhook {
+        event_id,
+        event_type,
+        event,
+        payload,
+    })
+}
+
+fn field(object: &Value, pointer: &str) -> Result<String, WebhookError> {
+    optional_str(object, pointer)
+        .ok_or_else(|| WebhookError::Malformed(format!("missing {}", pointer)))
+}
+
+fn amount(object: &Value) -> Result<i64, WebhookError> {
+    object
+        .pointer("/amount")
+        .and_then(Value::as_i64)
+        .ok_or_else(|| WebhookError::Malformed("missing amount".to_string()))
+}
+
+fn metadata_id(object: &Value, key: &str) -> Option<Uuid> {
+    object
+        .pointer(&format!("/metadata/{}", key))
+        .and_then(Value::as_str)
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+fn optional_str(value: &Value, pointer: &str) -> Option<String> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn required_str(value: &Value, pointer: &str) -> anyhow::Result<String> {
+    optional_str(value, pointer)
+        .ok_or_else(|| anyhow::anyhow!("Provider response missing {}", pointer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definitive_refund_rejection() {
+        assert!(is_definitive_rejection(StatusCode::BAD_REQUEST));
+        assert!(is_definitive_rejection(StatusCode::PAYMENT_REQUIRED));
+        assert!(is_definitive_rejection(StatusCode::TOO_MANY_REQUESTS));
+
+        assert!(!is_definitive_rejection(StatusCode::CONFLICT));
+        assert!(!is_definitive_rejection(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(!is_definitive_rejection(StatusCode::GATEWAY_TIMEOUT));
+    }
+
+    fn sign(key: &[u8], timestamp: i64, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(key).unwrap();
+        mac.update(format!("{}.", timestamp).as_bytes());
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    #[test]
+    fn test_stripe_webhook_verification() {
+        let provider = PaymentProvider::new(
+            ProviderKind::Stripe,
+            None,
+            Some("whsec_test".to_string()),
+            300,
+        );
+        let body = br#"{"id":"evt_1","type":"payment_intent.succeeded","data":{"object":{"id":"pi_1","amount_received":50000}}}"#;
+        let header = format!("t=1000,v1={}", sign(b"whsec_test", 1000, body));
+        let signature = WebhookSignature {
+            stripe_signature: Some(&header),
+            ..Default::default()
+        };
+
+        let verified = provider.verify_webhook(&signature, body, 1100).unwrap();
+        assert_eq!(verified.event_id, "evt_1");
+        assert_eq!(
+            verified.event,
+            WebhookEvent::PaymentSucceeded {
+                provider_payment_id: "pi_1".to_string(),
+                topup_id: None,
+                amount_minor: 50000
+            }
+        );
+
+        assert!(matches!(
+            provider.verify_webhook(&signature, body, 2000),
+            Err(WebhookError::Expired)
+        ));
+        assert!(matches!(
+            provider.verify_webhook(&signature, br#"{"id":"evt_2"}"#, 1100),
+            Err(WebhookError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_omise_webhook_verification() {
+        let key = b"omise-secret";
+        let secret = general_purpose::STANDARD.encode(key);
+        let provider = PaymentProvider::new(ProviderKind::Omise, None, Some(secret), 300);
+        let body = br#"{"id":"evnt_1","key":"refund.create","data":{"id":"rfnd_1","charge":"chrg_1","amount":2500}}"#;
+        let sig = sign(key, 1000, body);
+        let signature = WebhookSignature {
+            omise_signature: Some(&sig),
+            omise_timestamp: Some("1000"),
+            ..Default::default()
+        };
+
+        let verified = provider.verify_webhook(&signature, body, 1000).unwrap();
+        assert_eq!(
+            verified.event,
+            WebhookEvent::Refunded {
+                provider_payment_id: "chrg_1".to_string(),
+                provider_refund_id: "rfnd_1".to_string(),
+                refund_id: None,
+                amount_minor: 2500
+            }
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Key management for PII data keys
+//!
+//! Data keys are stored wrapped by a master key that stays in the KMS. The
+//! `vault` provider uses a HashiCorp Vault transit key; the `local` provider
+//! wraps with a key from the environment and is meant for development.
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Deserialize;
+use serde_json::json;
+
+const NONCE_LEN: usize = 12;
+
+/// Wraps and unwraps data keys with a master key held by the KMS
+#[async_trait]
+pub trait KeyProvider: Send + Sync + std::fmt::Debug {
+    /// Provider name recorded with each wrapped key
+    fn name(&self) -> &'static str;
+
+    async fn wrap(&self, key: &[u8]) -> Result<Vec<u8>>;
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Master key from the environment (`PII_MASTER_KEY`)
+pub struct LocalKeyProvider {
+    cipher: Aes256Gcm,
+}
+
+impl std::fmt::Debug for LocalKeyProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalKeyProvider").finish_non_exhaustive()
+    }
+}
+
+impl LocalKeyProvider {
+    pub fn new(master_key: &[u8]) -> Result<Self> {
+        if master_key.len() != 32 {
+            return Err(anyhow!("PII master key must be 32 bytes, got {}", master_key.len()));
+        }
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(master_key)),
+        })
+    }
+}
+
+#[async_trait]
+impl KeyProvider for LocalKeyProvider {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn wrap(&self, key: &[u8]) -> Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, key)
+            .map_err(|e| anyhow!("Key wrap failure: {}", e))?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>> {
+        if wrapped.len() <= NONCE_LEN {
+            return Err(anyhow!("Wrapped key too short"));
+        }
+        let (nonce, ciphertext) = wrapped.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|e| anyhow!("Key unwrap failure: {}", e))
+    }
+}
+
+/// HashiCorp Vault transit secrets engine; the wrapped form is Vault's
+/// `vault:v<N>:...` ciphertext, so master key versions rotate in Vault
+pub struct VaultTransitProvider {
+    http: reqwest::Client,
+    addr: String,
+    token: String,
+    key_name: String,
+}
+
+impl std::fmt::Debug for VaultTransitProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VaultTransitProvider")
+            .field("addr", &self.addr)
+            .field("key_name", &self.key_name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Deserialize)]
+struct VaultResponse {
+    data: VaultData,
+}
+
+#[derive(Deserialize)]
+struct VaultData {
+    ciphertext: Option<String>,
+    plaintext: Option<String>,
+}
+
+impl VaultTransitProvider {
+    pub fn new(http: reqwest::Client, addr: &str, token: &str, key_name: &str) -> Self {
+        Self {
+            http,
+            addr: addr.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            key_name: key_name.to_string(),
+        }
+    }
+
+    async fn call(&self, operation: &str, body: serde_json::Value) -> Result<VaultData> {
+        let url = format!("{}/v1/transit/{}/{}", self.addr, operation, self.key_name);
+        let response = self
+            .http
+            .post(&url)
+            .header("X-Vault-Token", &self.token)
+            .json(&body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Vault transit {} failed with status {}", operation, response.status()));
+        }
+        Ok(response.json::<VaultResponse>().await?.data)
+    }
+}
+
+#[async_trait]
+impl KeyProvider for VaultTransitProvider {
+    fn name(&self) -> &'static str {
+        "vault"
+    }
+
+    async fn wrap(&self, key: &[u8]) -> Result<Vec<u8>> {
+        let data = self.call("encrypt", json!({ "plaintext": BASE64.encode(key) })).await?;
+        data.ciphertext
+            .map(String::into_bytes)
+            .ok_or_else(|| anyhow!("Vault transit encrypt returned no ciphertext"))
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>> {
+        let ciphertext = std::str::from_utf8(wrapped).map_err(|_| anyhow!("Wrapped key is not a Vault ciphertext"))?;
+        let data = self.call("decrypt", json!({ "ciphertext": ciphertext })).await?;
+        let plaintext = data
+            .plaintext
+            .ok_or_else(|| anyhow!("Vault transit decrypt returned no plaintext"))?;
+        Ok(BASE64.decode(plaintext)?)
+    }
+}
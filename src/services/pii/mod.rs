@@ -0,0 +1,273 @@
+//! PII Encryption
+//!
+//! Application-layer envelope encryption of personal data at rest: user
+//! email and names, and meter installation addresses. Values are sealed with
+//! AES-256-GCM under a data key; data keys live in `pii_data_keys` wrapped by
+//! the KMS master key and are only held unwrapped in memory. Equality lookups
+//! go through an HMAC digest of the normalized value, so the database never
+//! needs the plaintext.
+
+pub mod kms;
+pub mod rotation;
+
+pub use kms::{KeyProvider, LocalKeyProvider, VaultTransitProvider};
+pub use rotation::{PiiKeyStatus, PiiRotationJob, RotationRun};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::error::{ApiError, Result};
+
+/// Leading byte of every sealed value, bumped if the layout changes
+const SEAL_VERSION: u8 = 1;
+const KEY_ID_LEN: usize = 4;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = 1 + KEY_ID_LEN + NONCE_LEN;
+
+/// Data key id a sealed value was encrypted under
+pub fn sealed_key_id(sealed: &[u8]) -> Option<i32> {
+    if sealed.len() <= HEADER_LEN || sealed[0] != SEAL_VERSION {
+        return None;
+    }
+    Some(i32::from_be_bytes(sealed[1..1 + KEY_ID_LEN].try_into().ok()?))
+}
+
+/// Layout: version byte, data key id (big endian), nonce, ciphertext
+fn seal_with(cipher: &Aes256Gcm, key_id: i32, value: &str) -> Result<Vec<u8>> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, value.as_bytes())
+        .map_err(|e| ApiError::Internal(format!("PII encryption failure: {}", e)))?;
+
+    let mut sealed = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    sealed.push(SEAL_VERSION);
+    sealed.extend_from_slice(&key_id.to_be_bytes());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+fn open_with(cipher: &Aes256Gcm, sealed: &[u8]) -> Result<String> {
+    let nonce = Nonce::from_slice(&sealed[1 + KEY_ID_LEN..HEADER_LEN]);
+    let plaintext = cipher
+        .decrypt(nonce, &sealed[HEADER_LEN..])
+        .map_err(|e| ApiError::Internal(format!("PII decryption failure: {}", e)))?;
+    String::from_utf8(plaintext).map_err(|_| ApiError::Internal("Decrypted PII is not UTF-8".to_string()))
+}
+
+/// Email as compared for lookups
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+struct KeyRing {
+    active: i32,
+    ciphers: HashMap<i32, Aes256Gcm>,
+}
+
+/// Seals and opens PII values and computes lookup digests
+#[derive(Clone)]
+pub struct PiiCipher {
+    db: PgPool,
+    kms: Arc<dyn KeyProvider>,
+    digest: Hmac<Sha256>,
+    keys: Arc<RwLock<KeyRing>>,
+}
+
+impl std::fmt::Debug for PiiCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PiiCipher").field("kms", &self.kms).finish_non_exhaustive()
+    }
+}
+
+impl PiiCipher {
+    /// Unwrap the digest key and the active data key, creating both on first start
+    pub async fn load(db: PgPool, kms: Arc<dyn KeyProvider>) -> Result<Self> {
+        let digest_key = match Self::stored_key(&db, "digest").await? {
+            Some((_, wrapped)) => Self::unwrap(&kms, &wrapped).await?,
+            None => Self::create_key(&db, &kms, "digest").await?.1,
+        };
+        let (active, data_key) = match Self::stored_key(&db, "data").await? {
+            Some((id, wrapped)) => (id, Self::unwrap(&kms, &wrapped).await?),
+            None => Self::create_key(&db, &kms, "data").await?,
+        };
+        info!("🔐 PII data key {} active ({} KMS)", active, kms.name());
+
+        let digest = <Hmac<Sha256> as Mac>::new_from_slice(&digest_key)
+            .map_err(|e| ApiError::Internal(format!("Invalid PII digest key: {}", e)))?;
+        let mut ciphers = HashMap::new();
+        ciphers.insert(active, Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key)));
+
+        Ok(Self {
+            db,
+            kms,
+            digest,
+            keys: Arc::new(RwLock::new(KeyRing { active, ciphers })),
+        })
+    }
+
+    /// Lookup digest of an email; equal for any casing or surrounding whitespace
+    pub fn email_digest(&self, email: &str) -> Vec<u8> {
+        let mut mac = self.digest.clone();
+        mac.update(normalize_email(email).as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    pub async fn active_key_id(&self) -> i32 {
+        self.keys.read().await.active
+    }
+
+    /// Encrypt under the active data key
+    pub async fn seal(&self, value: &str) -> Result<Vec<u8>> {
+        let keys = self.keys.read().await;
+        let cipher = &keys.ciphers[&keys.active];
+        seal_with(cipher, keys.active, value)
+    }
+
+    pub async fn seal_opt(&self, value: Option<&str>) -> Result<Option<Vec<u8>>> {
+        match value {
+            Some(value) => Ok(Some(self.seal(value).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Decrypt a sealed value, unwrapping its data key on first use
+    pub async fn open(&self, sealed: &[u8]) -> Result<String> {
+        let key_id = sealed_key_id(sealed).ok_or_else(|| ApiError::Internal("Malformed sealed PII value".to_string()))?;
+        {
+            let keys = self.keys.read().await;
+            if let Some(cipher) = keys.ciphers.get(&key_id) {
+                return open_with(cipher, sealed);
+            }
+        }
+
+        // Retired keys stay readable until the rotation job has re-encrypted their rows
+        let wrapped: Option<Vec<u8>> =
+            sqlx::query_scalar("SELECT wrapped_key FROM pii_data_keys WHERE id = $1 AND purpose = 'data'")
+                .bind(key_id)
+                .fetch_optional(&self.db)
+                .await?;
+        let wrapped = wrapped.ok_or_else(|| ApiError::Internal(format!("Unknown PII data key {}", key_id)))?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&Self::unwrap(&self.kms, &wrapped).await?));
+        let plaintext = open_with(&cipher, sealed)?;
+        self.keys.write().await.ciphers.insert(key_id, cipher);
+        Ok(plaintext)
+    }
+
+    pub async fn open_opt(&self, sealed: Option<&[u8]>) -> Result<Option<String>> {
+        match sealed {
+            Some(sealed) => Ok(Some(self.open(sealed).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Make a new data key active; new writes use it at once and the
+    /// rotation job re-encrypts existing rows
+    pub async fn rotate(&self) -> Result<i32> {
+        let (id, key) = Self::create_key(&self.db, &self.kms, "data").await?;
+        let mut keys = self.keys.write().await;
+        keys.ciphers.insert(id, Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)));
+        keys.active = id;
+        info!("🔐 PII data key {} active", id);
+        Ok(id)
+    }
+
+    /// Pick up a data key made active by another instance
+    pub async fn refresh(&self) -> Result<()> {
+        let Some((id, wrapped)) = Self::stored_key(&self.db, "data").await? else {
+            return Ok(());
+        };
+        if self.keys.read().await.active == id {
+            return Ok(());
+        }
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&Self::unwrap(&self.kms, &wrapped).await?));
+        let mut keys = self.keys.write().await;
+        keys.ciphers.insert(id, cipher);
+        keys.active = id;
+        Ok(())
+    }
+
+    /// Newest key of a purpose that is not retired
+    async fn stored_key(db: &PgPool, purpose: &str) -> Result<Option<(i32, Vec<u8>)>> {
+        Ok(sqlx::query_as(
+            r#"
+            SELECT id, wrapped_key FROM pii_data_keys
+            WHERE purpose = $1 AND retired_at IS NULL
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(purpose)
+        .fetch_optional(db)
+        .await?)
+    }
+
+    async fn create_key(db: &PgPool, kms: &Arc<dyn KeyProvider>, purpose: &str) -> Result<(i32, Vec<u8>)> {
+        let mut key = vec![0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        let wrapped = kms
+            .wrap(&key)
+            .await
+            .map_err(|e| ApiError::ExternalService(format!("KMS wrap failed: {}", e)))?;
+        let id: i32 = sqlx::query_scalar(
+            "INSERT INTO pii_data_keys (purpose, kms_provider, wrapped_key) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(purpose)
+        .bind(kms.name())
+        .bind(&wrapped)
+        .fetch_one(db)
+        .await?;
+        Ok((id, key))
+    }
+
+    async fn unwrap(kms: &Arc<dyn KeyProvider>, wrapped: &[u8]) -> Result<Vec<u8>> {
+        let key = kms
+            .unwrap(wrapped)
+            .await
+            .map_err(|e| ApiError::ExternalService(format!("KMS unwrap failed: {}", e)))?;
+        if key.len() != 32 {
+            return Err(ApiError::Internal("PII data key has the wrong length".to_string()));
+        }
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher() -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&[7u8; 32]))
+    }
+
+    #[test]
+    fn test_sealed_value_round_trips_and_names_its_key() {
+        let sealed = seal_with(&cipher(), 42, "prosumer@example.com").unwrap();
+        assert_eq!(sealed_key_id(&sealed), Some(42));
+        assert_eq!(open_with(&cipher(), &sealed).unwrap(), "prosumer@example.com");
+    }
+
+    #[test]
+    fn test_tampered_value_does_not_open() {
+        let mut sealed = seal_with(&cipher(), 1, "Somchai").unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0x01;
+        assert!(open_with(&cipher(), &sealed).is_err());
+        assert_eq!(sealed_key_id(b"short"), None);
+    }
+
+    #[test]
+    fn test_email_normalization() {
+        assert_eq!(normalize_email("  Prosumer@Example.COM "), "prosumer@example.com");
+    }
+}
@@ -0,0 +1,205 @@
+//! PII key rotation job
+//!
+//! Re-encrypts rows sealed under an older data key, and encrypts legacy
+//! plaintext rows, under the active data key in small batches. Data keys no
+//! row is sealed under any more are retired. With `PII_KEY_MAX_AGE_DAYS` set
+//! the job also makes a new data key active once the current one is that old.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{sealed_key_id, PiiCipher};
+use crate::config::PiiConfig;
+use crate::error::Result;
+use crate::models::pii::{open_column, SealedUserPii, UserPii};
+
+/// Outcome of one pass of the rotation job
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct RotationRun {
+    /// Data key made active by this pass because the previous one was too old
+    pub rotated_to: Option<i32>,
+    pub users_sealed: u64,
+    pub meters_sealed: u64,
+    pub keys_retired: u64,
+}
+
+/// Data keys and the rows still to be sealed under the active one
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PiiKeyStatus {
+    pub active_key_id: i32,
+    pub kms_provider: String,
+    pub active_since: DateTime<Utc>,
+    /// Unretired data keys, including the active one
+    pub live_keys: i64,
+    pub users_pending: i64,
+    pub meters_pending: i64,
+}
+
+#[derive(FromRow)]
+struct UserPiiRow {
+    id: Uuid,
+    #[sqlx(flatten)]
+    pii: SealedUserPii,
+}
+
+#[derive(FromRow)]
+struct MeterAddressRow {
+    id: Uuid,
+    location_address: Option<String>,
+    location_address_encrypted: Option<Vec<u8>>,
+}
+
+#[derive(Clone)]
+pub struct PiiRotationJob {
+    db: PgPool,
+    pii: PiiCipher,
+    config: PiiConfig,
+}
+
+impl PiiRotationJob {
+    pub fn new(db: PgPool, pii: PiiCipher, config: PiiConfig) -> Self {
+        Self { db, pii, config }
+    }
+
+    /// Rotate an aged key, seal one batch of users and meters and retire unused keys
+    pub async fn run_once(&self) -> Result<RotationRun> {
+        self.pii.refresh().await?;
+        let mut run = RotationRun::default();
+
+        if self.config.key_max_age_days > 0 {
+            let active_since: DateTime<Utc> = sqlx::query_scalar("SELECT created_at FROM pii_data_keys WHERE id = $1")
+                .bind(self.pii.active_key_id().await)
+                .fetch_one(&self.db)
+                .await?;
+            if Utc::now() - active_since > Duration::days(self.config.key_max_age_days) {
+                run.rotated_to = Some(self.pii.rotate().await?);
+            }
+        }
+
+        let active = self.pii.active_key_id().await;
+        run.users_sealed = self.seal_users(active).await?;
+        run.meters_sealed = self.seal_meters(active).await?;
+        run.keys_retired = sqlx::query(
+            r#"
+            UPDATE pii_data_keys k SET retired_at = NOW()
+            WHERE k.purpose = 'data' AND k.retired_at IS NULL AND k.id <> $1
+              AND NOT EXISTS (SELECT 1 FROM users WHERE pii_key_id = k.id)
+              AND NOT EXISTS (SELECT 1 FROM meter_registry WHERE pii_key_id = k.id)
+            "#,
+        )
+        .bind(active)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+
+        Ok(run)
+    }
+
+    pub async fn status(&self) -> Result<PiiKeyStatus> {
+        let active = self.pii.active_key_id().await;
+        let (kms_provider, active_since): (String, DateTime<Utc>) =
+            sqlx::query_as("SELECT kms_provider, created_at FROM pii_data_keys WHERE id = $1")
+                .bind(active)
+                .fetch_one(&self.db)
+                .await?;
+        let (live_keys, users_pending, meters_pending): (i64, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM pii_data_keys WHERE purpose = 'data' AND retired_at IS NULL),
+                (SELECT COUNT(*) FROM users WHERE pii_key_id IS DISTINCT FROM $1),
+                (SELECT COUNT(*) FROM meter_registry
+                 WHERE pii_key_id IS DISTINCT FROM $1
+                   AND (location_address IS NOT NULL OR location_address_encrypted IS NOT NULL))
+            "#,
+        )
+        .bind(active)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(PiiKeyStatus {
+            active_key_id: active,
+            kms_provider,
+            active_since,
+            live_keys,
+            users_pending,
+            meters_pending,
+        })
+    }
+
+    async fn seal_users(&self, active: i32) -> Result<u64> {
+        let mut tx = self.db.begin().await?;
+        let rows = sqlx::query_as::<_, UserPiiRow>(&format!(
+            "SELECT id, {} FROM users WHERE pii_key_id IS DISTINCT FROM $1 LIMIT $2 FOR UPDATE SKIP LOCKED",
+            crate::models::USER_PII_COLUMNS
+        ))
+        .bind(active)
+        .bind(self.config.rotation_batch_size)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for row in &rows {
+            let pii: UserPii = row.pii.open(&self.pii).await?;
+            let sealed = pii.seal(&self.pii).await?;
+            sqlx::query(
+                r#"
+                UPDATE users SET
+                    email_encrypted = $2, email_digest = $3,
+                    first_name_encrypted = $4, last_name_encrypted = $5, pii_key_id = $6,
+                    email = NULL, first_name = NULL, last_name = NULL
+                WHERE id = $1
+                "#,
+            )
+            .bind(row.id)
+            .bind(&sealed.email_encrypted)
+            .bind(&sealed.email_digest)
+            .bind(&sealed.first_name_encrypted)
+            .bind(&sealed.last_name_encrypted)
+            .bind(sealed.key_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(rows.len() as u64)
+    }
+
+    async fn seal_meters(&self, active: i32) -> Result<u64> {
+        let mut tx = self.db.begin().await?;
+        let rows = sqlx::query_as::<_, MeterAddressRow>(
+            r#"
+            SELECT id, location_address, location_address_encrypted FROM meter_registry
+            WHERE pii_key_id IS DISTINCT FROM $1
+              AND (location_address IS NOT NULL OR location_address_encrypted IS NOT NULL)
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(active)
+        .bind(self.config.rotation_batch_size)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for row in &rows {
+            let address = open_column(&self.pii, row.location_address_encrypted.as_deref(), row.location_address.as_deref())
+                .await?
+                .unwrap_or_default();
+            let sealed = self.pii.seal(&address).await?;
+            sqlx::query(
+                r#"
+                UPDATE meter_registry
+                SET location_address_encrypted = $2, pii_key_id = $3, location_address = NULL
+                WHERE id = $1
+                "#,
+            )
+            .bind(row.id)
+            .bind(&sealed)
+            .bind(sealed_key_id(&sealed))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(rows.len() as u64)
+    }
+}
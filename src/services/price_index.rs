@@ -0,0 +1,296 @@
+//! Clearing Price Index
+//!
+//! Every cleared epoch's price is recorded in `clearing_price_index`. The
+//! series is served at epoch, hourly or daily resolution, and once a UTC day
+//! has ended its volume-weighted clearing price is published to
+//! `reference_price_index` as the reference value futures settle against.
+
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::models::EnergyKwh;
+
+/// Granularity of the clearing price history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexResolution {
+    /// One point per cleared epoch
+    Epoch,
+    Hour,
+    Day,
+}
+
+impl IndexResolution {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "epoch" | "15m" => Some(Self::Epoch),
+            "1h" => Some(Self::Hour),
+            "1d" | "24h" => Some(Self::Day),
+            _ => None,
+        }
+    }
+
+    fn bucket(&self) -> Option<Duration> {
+        match self {
+            Self::Epoch => None,
+            Self::Hour => Some(Duration::hours(1)),
+            Self::Day => Some(Duration::days(1)),
+        }
+    }
+}
+
+/// A recorded epoch clearing
+#[derive(Debug, Clone, FromRow)]
+pub struct IndexEntry {
+    pub epoch_start: DateTime<Utc>,
+    pub clearing_price: Decimal,
+    pub volume_kwh: Decimal,
+}
+
+/// Clearing prices over one period
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ClearingPricePoint {
+    pub period_start: DateTime<Utc>,
+    #[schema(value_type = String)]
+    pub open: Decimal,
+    #[schema(value_type = String)]
+    pub high: Decimal,
+    #[schema(value_type = String)]
+    pub low: Decimal,
+    #[schema(value_type = String)]
+    pub close: Decimal,
+    /// Volume-weighted clearing price (simple mean when no volume cleared)
+    #[schema(value_type = String)]
+    pub vwap: Decimal,
+    pub volume_kwh: EnergyKwh,
+    pub epoch_count: i64,
+}
+
+/// Published daily reference index value
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ReferenceIndex {
+    pub index_date: NaiveDate,
+    #[schema(value_type = String)]
+    pub value: Decimal,
+    pub volume_kwh: EnergyKwh,
+    pub epoch_count: i32,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Roll epoch entries (ordered by start time) up to `resolution`
+pub fn roll_up(entries: &[IndexEntry], resolution: IndexResolution) -> Vec<ClearingPricePoint> {
+    let mut points: Vec<(ClearingPricePoint, Decimal, Decimal)> = Vec::new();
+
+    for entry in entries {
+        let period_start = match resolution.bucket() {
+            Some(bucket) => entry.epoch_start.duration_trunc(bucket).unwrap_or(entry.epoch_start),
+            None => entry.epoch_start,
+        };
+        let price = entry.clearing_price;
+        let notional = price * entry.volume_kwh;
+
+        match points.last_mut() {
+            Some((point, point_notional, price_sum)) if point.period_start == period_start => {
+                point.high = point.high.max(price);
+                point.low = point.low.min(price);
+                point.close = price;
+                point.volume_kwh += EnergyKwh::from(entry.volume_kwh);
+                point.epoch_count += 1;
+                *point_notional += notional;
+                *price_sum += price;
+            }
+            _ => points.push((
+                ClearingPricePoint {
+                    period_start,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    vwap: price,
+                    volume_kwh: EnergyKwh::from(entry.volume_kwh),
+                    epoch_count: 1,
+                },
+                notional,
+                price,
+            )),
+        }
+    }
+
+    points
+        .into_iter()
+        .map(|(mut point, notional, price_sum)| {
+            point.vwap = weighted_price(notional, point.volume_kwh.value(), price_sum, point.epoch_count);
+            point
+        })
+        .collect()
+}
+
+fn weighted_price(notional: Decimal, volume: Decimal, price_sum: Decimal, count: i64) -> Decimal {
+    let price = if volume.is_zero() {
+        price_sum / Decimal::from(count.max(1))
+    } else {
+        notional / volume
+    };
+    price.round_dp(crate::constants::energy::AMOUNT_DECIMAL_PLACES)
+}
+
+/// Clearing price index service
+#[derive(Clone)]
+pub struct PriceIndexService {
+    db: PgPool,
+}
+
+impl PriceIndexService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Clearing price series between `from` (inclusive) and `to` (exclusive)
+    pub async fn history(
+        &self,
+        resolution: IndexResolution,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<ClearingPricePoint>> {
+        let entries = sqlx::query_as::<_, IndexEntry>(
+            r#"
+            SELECT epoch_start, clearing_price, volume_kwh
+            FROM clearing_price_index
+            WHERE epoch_start >= $1 AND epoch_start < $2
+            ORDER BY epoch_start ASC
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(roll_up(&entries, resolution))
+    }
+
+    /// Reference index for `date`, or the latest published value
+    pub async fn reference_index(&self, date: Option<NaiveDate>) -> anyhow::Result<Option<ReferenceIndex>> {
+        let index = sqlx::query_as::<_, ReferenceIndex>(
+            r#"
+            SELECT index_date, value, volume_kwh, epoch_count, published_at
+            FROM reference_price_index
+            WHERE $1::date IS NULL OR index_date = $1
+            ORDER BY index_date DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(date)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(index)
+    }
+
+    /// Publish yesterday's reference index if it has not been published yet.
+    /// Returns the newly published value.
+    pub async fn ensure_previous_day(&self) -> anyhow::Result<Option<ReferenceIndex>> {
+        let yesterday = Utc::now().date_naive() - Duration::days(1);
+        self.publish_daily_index(yesterday).await
+    }
+
+    /// Publish the reference index for a completed UTC day. Days without any
+    /// clearing are skipped; a value that was already published is kept.
+    pub async fn publish_daily_index(&self, date: NaiveDate) -> anyhow::Result<Option<ReferenceIndex>> {
+        let day_start = date
+            .and_hms_opt(0, 0, 0)
+            .map(|dt| dt.and_utc())
+            .ok_or_else(|| anyhow::anyhow!("Invalid index date {}", date))?;
+        if day_start + Duration::days(1) > Utc::now() {
+            anyhow::bail!("Reference index for {} cannot be published before the day ends", date);
+        }
+
+        let Some(day) = self
+            .history(IndexResolution::Day, day_start, day_start + Duration::days(1))
+            .await?
+            .into_iter()
+            .next()
+        else {
+            return Ok(None);
+        };
+
+        let published = sqlx::query_as::<_, ReferenceIndex>(
+            r#"
+            INSERT INTO reference_price_index (index_date, value, volume_kwh, epoch_count)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (index_date) DO NOTHING
+            RETURNING index_date, value, volume_kwh, epoch_count, published_at
+            "#,
+        )
+        .bind(date)
+        .bind(day.vwap)
+        .bind(day.volume_kwh)
+        .bind(day.epoch_count as i32)
+        .fetch_optional(&self.db)
+        .await?;
+
+        if let Some(index) = &published {
+            info!(
+                "📈 Published reference index for {}: {} ({} epochs, {} kWh)",
+                index.index_date, index.value, index.epoch_count, index.volume_kwh
+            );
+        }
+
+        Ok(published)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(hour: u32, minute: u32, price: i64, volume: i64) -> IndexEntry {
+        IndexEntry {
+            epoch_start: Utc.with_ymd_and_hms(2026, 1, 15, hour, minute, 0).unwrap(),
+            clearing_price: Decimal::new(price, 1),
+            volume_kwh: Decimal::from(volume),
+        }
+    }
+
+    #[test]
+    fn test_epoch_resolution_keeps_every_entry() {
+        let entries = [entry(10, 0, 30, 10), entry(10, 15, 40, 10)];
+        let points = roll_up(&entries, IndexResolution::Epoch);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].close, Decimal::new(40, 1));
+    }
+
+    #[test]
+    fn test_hourly_roll_up_is_volume_weighted() {
+        let entries = [entry(10, 0, 30, 30), entry(10, 45, 40, 10), entry(11, 0, 50, 5)];
+        let points = roll_up(&entries, IndexResolution::Hour);
+
+        assert_eq!(points.len(), 2);
+        let first = &points[0];
+        assert_eq!(first.open, Decimal::new(30, 1));
+        assert_eq!(first.close, Decimal::new(40, 1));
+        assert_eq!(first.high, Decimal::new(40, 1));
+        assert_eq!(first.low, Decimal::new(30, 1));
+        // (3.0 * 30 + 4.0 * 10) / 40
+        assert_eq!(first.vwap, Decimal::new(325, 2));
+        assert_eq!(first.volume_kwh, EnergyKwh::from(Decimal::from(40)));
+        assert_eq!(first.epoch_count, 2);
+    }
+
+    #[test]
+    fn test_zero_volume_falls_back_to_mean_price() {
+        let entries = [entry(10, 0, 30, 0), entry(10, 15, 40, 0)];
+        let points = roll_up(&entries, IndexResolution::Day);
+        assert_eq!(points[0].vwap, Decimal::new(35, 1));
+    }
+
+    #[test]
+    fn test_parse_resolution() {
+        assert_eq!(IndexResolution::parse("epoch"), Some(IndexResolution::Epoch));
+        assert_eq!(IndexResolution::parse("1d"), Some(IndexResolution::Day));
+        assert_eq!(IndexResolution::parse("1w"), None);
+    }
+}
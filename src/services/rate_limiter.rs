@@ -0,0 +1,484 @@
+//! Meter Submission Rate Limiter
+//!
+//! Fixed-window limit on meter reading submissions per principal (user, API
+//! key or client IP). Operators can raise or lift the limit for the simulator
+//! and partner integrations with admin-managed overrides; overrides live in
+//! `rate_limit_overrides` and are cached in memory for a short interval.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::{AuditEvent, AuditLogger};
+
+/// How long loaded overrides are trusted before re-reading the table
+const OVERRIDE_CACHE_TTL: Duration = Duration::from_secs(30);
+
+const OVERRIDE_COLUMNS: &str = "id, principal_type, user_id, api_key_hash, api_key_hint, \
+    ip_range, max_requests, reason, expires_at, created_by, created_at, revoked_at, revoked_by";
+
+/// Kind of principal an override applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PrincipalType {
+    User,
+    ApiKey,
+    IpRange,
+}
+
+impl PrincipalType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrincipalType::User => "user",
+            PrincipalType::ApiKey => "api_key",
+            PrincipalType::IpRange => "ip_range",
+        }
+    }
+}
+
+/// Admin-managed exception to the default submission limit
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct RateLimitOverride {
+    pub id: Uuid,
+    /// `user`, `api_key` or `ip_range`
+    pub principal_type: String,
+    pub user_id: Option<Uuid>,
+    #[serde(skip_serializing)]
+    pub api_key_hash: Option<String>,
+    /// Last characters of the API key, for identification
+    pub api_key_hint: Option<String>,
+    #[schema(value_type = Option<String>)]
+    pub ip_range: Option<IpNetwork>,
+    /// Submissions allowed per window; null exempts the principal
+    pub max_requests: Option<i32>,
+    pub reason: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoked_by: Option<Uuid>,
+}
+
+impl RateLimitOverride {
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires| expires > now)
+    }
+}
+
+/// New override as submitted by an admin
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateRateLimitOverride {
+    pub principal_type: PrincipalType,
+    pub user_id: Option<Uuid>,
+    /// Plain API key; only its hash is stored
+    pub api_key: Option<String>,
+    /// CIDR range, e.g. `10.0.0.0/24`
+    pub ip_range: Option<String>,
+    /// Submissions allowed per window; omit to exempt the principal
+    pub max_requests: Option<i32>,
+    pub reason: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Identity of a meter submission, as far as the request reveals it
+#[derive(Debug, Clone, Default)]
+pub struct RatePrincipal {
+    pub user_id: Option<Uuid>,
+    pub api_key_hash: Option<String>,
+    pub ip: Option<IpAddr>,
+}
+
+impl RatePrincipal {
+    /// Counter bucket: the most specific identity available
+    fn bucket(&self) -> String {
+        if let Some(user_id) = self.user_id {
+            format!("user:{}", user_id)
+        } else if let Some(hash) = &self.api_key_hash {
+            format!("key:{}", hash)
+        } else if let Some(ip) = self.ip {
+            format!("ip:{}", ip)
+        } else {
+            "anonymous".to_string()
+        }
+    }
+}
+
+/// SHA-256 hex digest used to match API keys against overrides
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// Pick the override governing `principal`: user beats API key beats IP
+/// range, and among IP ranges the narrowest match wins. Expired and revoked
+/// overrides are ignored.
+pub fn select_override<'a>(
+    overrides: &'a [RateLimitOverride],
+    principal: &RatePrincipal,
+    now: DateTime<Utc>,
+) -> Option<&'a RateLimitOverride> {
+    let active = || overrides.iter().filter(move |o| o.is_active(now));
+
+    if let Some(user_id) = principal.user_id {
+        if let Some(found) = active().find(|o| o.user_id == Some(user_id)) {
+            return Some(found);
+        }
+    }
+
+    if let Some(hash) = &principal.api_key_hash {
+        if let Some(found) = active().find(|o| o.api_key_hash.as_deref() == Some(hash.as_str())) {
+            return Some(found);
+        }
+    }
+
+    let ip = principal.ip?;
+    active()
+        .filter(|o| o.ip_range.is_some_and(|range| range.contains(ip)))
+        .max_by_key(|o| o.ip_range.map(|range| range.prefix()))
+}
+
+/// Limit applying to a principal; `None` means exempt
+fn effective_limit(chosen: Option<&RateLimitOverride>, default_limit: u32) -> Option<u32> {
+    match chosen {
+        Some(o) => o.max_requests.map(|max| max.max(0) as u32),
+        None if default_limit == 0 => None,
+        None => Some(default_limit),
+    }
+}
+
+struct OverrideCache {
+    loaded_at: Instant,
+    overrides: Arc<Vec<RateLimitOverride>>,
+}
+
+#[derive(Clone)]
+pub struct EnhancedRateLimiter {
+    db: PgPool,
+    audit: AuditLogger,
+    /// Default submissions per principal per window; 0 disables the limit
+    default_limit: u32,
+    window_secs: u64,
+    /// Per-bucket (window index, count)
+    counters: Arc<Mutex<HashMap<String, (u64, u32)>>>,
+    overrides: Arc<RwLock<Option<OverrideCache>>>,
+}
+
+impl EnhancedRateLimiter {
+    pub fn new(db: PgPool, audit: AuditLogger, default_limit: u32, window_secs: u64) -> Self {
+        Self {
+            db,
+            audit,
+            default_limit,
+            window_secs: window_secs.max(1),
+            counters: Arc::new(Mutex::new(HashMap::new())),
+            overrides: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Count one submission and reject it once the principal's limit is spent
+    pub async fn check(&self, principal: &RatePrincipal, endpoint: &str) -> Result<(), ApiError> {
+        let overrides = self.active_overrides().await;
+        let chosen = select_override(&overrides, principal, Utc::now());
+        let Some(limit) = effective_limit(chosen, self.default_limit) else {
+            return Ok(());
+        };
+
+        let window = Utc::now().timestamp().max(0) as u64 / self.window_secs;
+        let count = {
+            let mut counters = self.counters.lock().await;
+            // Drop buckets from earlier windows so idle principals do not accumulate
+            if counters.len() > 10_000 {
+                counters.retain(|_, (w, _)| *w == window);
+            }
+            let entry = counters.entry(principal.bucket()).or_insert((window, 0));
+            if entry.0 != window {
+                *entry = (window, 0);
+            }
+            entry.1 = entry.1.saturating_add(1);
+            entry.1
+        };
+
+        if count <= limit {
+            return Ok(());
+        }
+
+        // Audit once per window rather than for every rejected reading
+        if count == limit + 1 {
+            self.audit.log_async(AuditEvent::RateLimitExceeded {
+                ip: principal.ip.map(|ip| ip.to_string()).unwrap_or_default(),
+                endpoint: endpoint.to_string(),
+            });
+        }
+        Err(ApiError::RateLimitExceeded(format!(
+            "Meter submission limit of {} per {}s exceeded",
+            limit, self.window_secs
+        )))
+    }
+
+    /// Unrevoked, unexpired overrides; reloaded from the database when stale.
+    /// A failed reload keeps the previous set rather than dropping exemptions.
+    async fn active_overrides(&self) -> Arc<Vec<RateLimitOverride>> {
+        if let Some(cache) = self.overrides.read().await.as_ref() {
+            if cache.loaded_at.elapsed() < OVERRIDE_CACHE_TTL {
+                return cache.overrides.clone();
+            }
+        }
+
+        let query = format!(
+            "SELECT {} FROM rate_limit_overrides \
+             WHERE revoked_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())",
+            OVERRIDE_COLUMNS
+        );
+        let loaded = sqlx::query_as::<_, RateLimitOverride>(&query)
+            .fetch_all(&self.db)
+            .await;
+
+        let mut cache = self.overrides.write().await;
+        match loaded {
+            Ok(rows) => {
+                let overrides = Arc::new(rows);
+                *cache = Some(OverrideCache {
+                    loaded_at: Instant::now(),
+                    overrides: overrides.clone(),
+                });
+                overrides
+            }
+            Err(e) => {
+                warn!("Failed to load rate limit overrides: {}", e);
+                cache
+                    .as_ref()
+                    .map(|c| c.overrides.clone())
+                    .unwrap_or_default()
+            }
+        }
+    }
+
+    /// Force the next check to re-read overrides
+    async fn invalidate(&self) {
+        *self.overrides.write().await = None;
+    }
+
+    /// Most recent overrides, including expired and revoked ones
+    pub async fn list_overrides(&self) -> Result<Vec<RateLimitOverride>, ApiError> {
+        let query = format!(
+            "SELECT {} FROM rate_limit_overrides ORDER BY created_at DESC LIMIT 200",
+            OVERRIDE_COLUMNS
+        );
+        Ok(sqlx::query_as::<_, RateLimitOverride>(&query)
+            .fetch_all(&self.db)
+            .await?)
+    }
+
+    pub async fn create_override(
+        &self,
+        admin_id: Uuid,
+        request: CreateRateLimitOverride,
+    ) -> Result<RateLimitOverride, ApiError> {
+        if request.reason.trim().is_empty() {
+            return Err(ApiError::validation_field("reason", "Reason is required"));
+        }
+        if request.max_requests.is_some_and(|max| max <= 0) {
+            return Err(ApiError::validation_field(
+                "max_requests",
+                "max_requests must be positive; omit it to exempt the principal",
+            ));
+        }
+        if request.expires_at.is_some_and(|expires| expires <= Utc::now()) {
+            return Err(ApiError::validation_field("expires_at", "expires_at must be in the future"));
+        }
+
+        let (user_id, api_key_hash, api_key_hint, ip_range) = match request.principal_type {
+            PrincipalType::User => {
+                let user_id = request
+                    .user_id
+                    .ok_or_else(|| ApiError::validation_field("user_id", "user_id is required"))?;
+                (Some(user_id), None, None, None)
+            }
+            PrincipalType::ApiKey => {
+                let key = request
+                    .api_key
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|k| !k.is_empty())
+                    .ok_or_else(|| ApiError::validation_field("api_key", "api_key is required"))?;
+                let hint: String = key.chars().skip(key.chars().count().saturating_sub(4)).collect();
+                (None, Some(hash_api_key(key)), Some(hint), None)
+            }
+            PrincipalType::IpRange => {
+                let range = request
+                    .ip_range
+                    .as_deref()
+                    .ok_or_else(|| ApiError::validation_field("ip_range", "ip_range is required"))?
+                    .parse::<IpNetwork>()
+                    .map_err(|_| ApiError::validation_field("ip_range", "ip_range must be a valid CIDR"))?;
+                (None, None, None, Some(range))
+            }
+        };
+
+        let query = format!(
+            "INSERT INTO rate_limit_overrides \
+             (principal_type, user_id, api_key_hash, api_key_hint, ip_range, max_requests, reason, expires_at, created_by) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING {}",
+            OVERRIDE_COLUMNS
+        );
+        let created = sqlx::query_as::<_, RateLimitOverride>(&query)
+            .bind(request.principal_type.as_str())
+            .bind(user_id)
+            .bind(api_key_hash)
+            .bind(api_key_hint)
+            .bind(ip_range)
+            .bind(request.max_requests)
+            .bind(request.reason.trim())
+            .bind(request.expires_at)
+            .bind(admin_id)
+            .fetch_one(&self.db)
+            .await?;
+
+        self.invalidate().await;
+        info!(
+            "Rate limit override {} created for {} by {}",
+            created.id, created.principal_type, admin_id
+        );
+        self.audit.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "rate_limit_override_created".to_string(),
+            target_user_id: created.user_id,
+            details: format!(
+                "override {} ({}), max_requests {:?}, expires {:?}: {}",
+                created.id, created.principal_type, created.max_requests, created.expires_at, created.reason
+            ),
+        });
+
+        Ok(created)
+    }
+
+    pub async fn revoke_override(&self, admin_id: Uuid, id: Uuid) -> Result<RateLimitOverride, ApiError> {
+        let query = format!(
+            "UPDATE rate_limit_overrides SET revoked_at = NOW(), revoked_by = $2 \
+             WHERE id = $1 AND revoked_at IS NULL RETURNING {}",
+            OVERRIDE_COLUMNS
+        );
+        let revoked = sqlx::query_as::<_, RateLimitOverride>(&query)
+            .bind(id)
+            .bind(admin_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Active rate limit override not found".to_string()))?;
+
+        self.invalidate().await;
+        self.audit.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "rate_limit_override_revoked".to_string(),
+            target_user_id: revoked.user_id,
+            details: format!("override {} ({})", revoked.id, revoked.principal_type),
+        });
+
+        Ok(revoked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn override_for(principal_type: PrincipalType, max_requests: Option<i32>) -> RateLimitOverride {
+        RateLimitOverride {
+            id: Uuid::new_v4(),
+            principal_type: principal_type.as_str().to_string(),
+            user_id: None,
+            api_key_hash: None,
+            api_key_hint: None,
+            ip_range: None,
+            max_requests,
+            reason: "test".to_string(),
+            expires_at: None,
+            created_by: None,
+            created_at: Utc::now(),
+            revoked_at: None,
+            revoked_by: None,
+        }
+    }
+
+    #[test]
+    fn test_select_override_precedence() {
+        let user_id = Uuid::new_v4();
+        let principal = RatePrincipal {
+            user_id: Some(user_id),
+            api_key_hash: Some(hash_api_key("partner-key")),
+            ip: Some("10.1.2.3".parse().unwrap()),
+        };
+
+        let mut wide = override_for(PrincipalType::IpRange, Some(500));
+        wide.ip_range = Some("10.0.0.0/8".parse().unwrap());
+        let mut narrow = override_for(PrincipalType::IpRange, Some(1000));
+        narrow.ip_range = Some("10.1.2.0/24".parse().unwrap());
+        let mut key = override_for(PrincipalType::ApiKey, Some(2000));
+        key.api_key_hash = Some(hash_api_key("partner-key"));
+        let mut user = override_for(PrincipalType::User, None);
+        user.user_id = Some(user_id);
+
+        let now = Utc::now();
+        let all = vec![wide.clone(), narrow.clone(), key.clone(), user.clone()];
+        assert_eq!(select_override(&all, &principal, now).map(|o| o.id), Some(user.id));
+
+        let no_user = vec![wide.clone(), narrow.clone(), key.clone()];
+        assert_eq!(select_override(&no_user, &principal, now).map(|o| o.id), Some(key.id));
+
+        let ranges = vec![wide.clone(), narrow.clone()];
+        assert_eq!(select_override(&ranges, &principal, now).map(|o| o.id), Some(narrow.id));
+
+        let outside = RatePrincipal {
+            ip: Some("192.168.0.1".parse().unwrap()),
+            ..Default::default()
+        };
+        assert!(select_override(&all, &outside, now).is_none());
+    }
+
+    #[test]
+    fn test_select_override_skips_expired_and_revoked() {
+        let user_id = Uuid::new_v4();
+        let principal = RatePrincipal {
+            user_id: Some(user_id),
+            ..Default::default()
+        };
+        let now = Utc::now();
+
+        let mut expired = override_for(PrincipalType::User, None);
+        expired.user_id = Some(user_id);
+        expired.expires_at = Some(now - ChronoDuration::minutes(1));
+        let mut revoked = override_for(PrincipalType::User, None);
+        revoked.user_id = Some(user_id);
+        revoked.revoked_at = Some(now);
+        assert!(select_override(&[expired, revoked], &principal, now).is_none());
+
+        let mut current = override_for(PrincipalType::User, Some(10));
+        current.user_id = Some(user_id);
+        current.expires_at = Some(now + ChronoDuration::hours(1));
+        assert_eq!(select_override(&[current.clone()], &principal, now).map(|o| o.id), Some(current.id));
+    }
+
+    #[test]
+    fn test_effective_limit() {
+        let exempt = override_for(PrincipalType::User, None);
+        let raised = override_for(PrincipalType::User, Some(600));
+
+        assert_eq!(effective_limit(None, 120), Some(120));
+        assert_eq!(effective_limit(None, 0), None);
+        assert_eq!(effective_limit(Some(&exempt), 120), None);
+        assert_eq!(effective_limit(Some(&raised), 120), Some(600));
+        // An override still applies when the default limit is disabled
+        assert_eq!(effective_limit(Some(&raised), 0), Some(600));
+    }
+}
@@ -0,0 +1,723 @@
+//! Referral and Rewards Program
+//!
+//! Every user gets a shareable code; new users may register with one. A
+//! referral qualifies once the new user has submitted a reading from a
+//! verified meter and completed a trade. Qualified referrals earn energy
+//! token rewards for both sides, minted on-chain by the referral job.
+//!
+//! Before rewards are issued the referral is screened with device and IP
+//! heuristics. Suspicious referrals are flagged and held until an admin
+//! approves or rejects them.
+
+pub mod types;
+
+use anyhow::anyhow;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool, Row};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::{Config, ReferralConfig};
+use crate::error::ApiError;
+use crate::services::{AuditEvent, AuditLogger, BlockchainService, WalletService};
+
+pub use types::*;
+
+/// Rewards minted per job run
+const MINT_BATCH_SIZE: usize = 50;
+/// Referrals screened per job run
+const QUALIFY_BATCH_SIZE: i64 = 100;
+const CODE_LENGTH: usize = 8;
+
+const REFERRAL_COLUMNS: &str = "r.id, r.referrer_id, r.referee_id, u.username AS referee_username, r.code, \
+    r.status, r.first_reading_at, r.first_trade_at, r.fraud_flags, r.qualified_at, r.reviewed_by, \
+    r.reviewed_at, r.review_note, r.created_at";
+
+const REWARD_COLUMNS: &str = "id, referral_id, user_id, role, amount, status, attempts, mint_tx_signature, \
+    last_error, minted_at, created_at";
+
+/// Canonical form of a user-entered referral code
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_ascii_uppercase()
+}
+
+fn generate_code() -> String {
+    Uuid::new_v4().simple().to_string()[..CODE_LENGTH].to_ascii_uppercase()
+}
+
+/// Heuristics that hold a referral for review. An empty result means the
+/// referral may be rewarded automatically.
+pub fn fraud_flags(signals: &FraudSignals, config: &ReferralConfig) -> Vec<FraudFlag> {
+    fn same(a: &Option<String>, b: &Option<String>) -> bool {
+        matches!((a, b), (Some(a), Some(b)) if !a.is_empty() && a == b)
+    }
+
+    let mut flags = Vec::new();
+    if same(&signals.referrer_ip, &signals.referee_ip) {
+        flags.push(FraudFlag::SameIp);
+    }
+    if same(&signals.referrer_device, &signals.referee_device) {
+        flags.push(FraudFlag::SameDevice);
+    }
+    if signals.referee_ip.is_some() && signals.signups_from_ip > config.max_signups_per_ip {
+        flags.push(FraudFlag::IpVelocity);
+    }
+    if signals.referrer_rewarded >= config.max_rewards_per_referrer {
+        flags.push(FraudFlag::ReferrerCapReached);
+    }
+    flags
+}
+
+/// Referral service
+#[derive(Clone)]
+pub struct ReferralService {
+    db: PgPool,
+    blockchain: BlockchainService,
+    wallet: WalletService,
+    config: Config,
+    audit: AuditLogger,
+}
+
+impl ReferralService {
+    pub fn new(
+        db: PgPool,
+        blockchain: BlockchainService,
+        wallet: WalletService,
+        config: Config,
+        audit: AuditLogger,
+    ) -> Self {
+        Self {
+            db,
+            blockchain,
+            wallet,
+            config,
+            audit,
+        }
+    }
+
+    /// The user's referral code, created on first use
+    pub async fn code_for(&self, user_id: Uuid) -> Result<String, ApiError> {
+        for _ in 0..5 {
+            let result = sqlx::query_scalar::<_, String>(
+                "UPDATE users SET referral_code = COALESCE(referral_code, $2) WHERE id = $1 RETURNING referral_code",
+            )
+            .bind(user_id)
+            .bind(generate_code())
+            .fetch_optional(&self.db)
+            .await;
+
+            match result {
+                Ok(Some(code)) => return Ok(code),
+                Ok(None) => return Err(ApiError::NotFound("User not found".to_string())),
+                Err(sqlx::Error::Database(ref db)) if db.is_unique_violation() => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(ApiError::Internal("Could not allocate a referral code".to_string()))
+    }
+
+    /// Owner of a referral code
+    pub async fn resolve_code(&self, code: &str) -> Result<Option<Uuid>, ApiError> {
+        Ok(
+            sqlx::query_scalar::<_, Uuid>("SELECT id FROM users WHERE referral_code = $1")
+                .bind(normalize_code(code))
+                .fetch_optional(&self.db)
+                .await?,
+        )
+    }
+
+    /// Record that `referee_id` registered with `referrer_id`'s code
+    pub async fn attach(&self, referrer_id: Uuid, referee_id: Uuid, code: &str) -> Result<(), ApiError> {
+        if referrer_id == referee_id {
+            return Err(ApiError::validation_field("referral_code", "You cannot refer yourself"));
+        }
+
+        sqlx::query(
+            "INSERT INTO referrals (referrer_id, referee_id, code) VALUES ($1, $2, $3)
+             ON CONFLICT (referee_id) DO NOTHING",
+        )
+        .bind(referrer_id)
+        .bind(referee_id)
+        .bind(normalize_code(code))
+        .execute(&self.db)
+        .await?;
+
+        info!("🤝 User {} registered with referral code of {}", referee_id, referrer_id);
+        Ok(())
+    }
+
+    /// Record qualifying activity of pending referrals and screen those
+    /// that are complete. Returns the number of referrals screened.
+    pub async fn process_qualifications(&self) -> Result<usize, ApiError> {
+        sqlx::query(
+            r#"
+            UPDATE referrals r SET
+                first_reading_at = COALESCE(r.first_reading_at, (
+                    SELECT MIN(mr.created_at) FROM meter_readings mr
+                    JOIN meter_registry m ON m.id = mr.meter_id
+                    WHERE mr.user_id = r.referee_id AND m.verification_status = 'verified'
+                )),
+                first_trade_at = COALESCE(r.first_trade_at, (
+                    SELECT MIN(s.created_at) FROM settlements s
+                    WHERE (s.buyer_id = r.referee_id OR s.seller_id = r.referee_id)
+                      AND s.status = 'completed'
+                )),
+                updated_at = NOW()
+            WHERE r.status = 'pending'
+              AND (r.first_reading_at IS NULL OR r.first_trade_at IS NULL)
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        let ready: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM referrals
+             WHERE status = 'pending' AND first_reading_at IS NOT NULL AND first_trade_at IS NOT NULL
+             ORDER BY created_at ASC
+             LIMIT $1",
+        )
+        .bind(QUALIFY_BATCH_SIZE)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut screened = 0;
+        for referral_id in ready {
+            match self.screen(referral_id).await {
+                Ok(Some(status)) => {
+                    screened += 1;
+                    info!("🤝 Referral {} {}", referral_id, status.as_str());
+                }
+                Ok(None) => {}
+                Err(e) => warn!("⚠️ Failed to screen referral {}: {}", referral_id, e),
+            }
+        }
+        Ok(screened)
+    }
+
+    /// Run the fraud heuristics on a complete referral and either issue its
+    /// rewards or flag it for review
+    async fn screen(&self, referral_id: Uuid) -> Result<Option<ReferralStatus>, ApiError> {
+        let mut tx = self.db.begin().await?;
+
+        let Some(row) = sqlx::query(
+            r#"
+            SELECT r.referrer_id, r.referee_id, r.created_at,
+                   rr.signup_ip AS referrer_ip, rr.signup_device AS referrer_device,
+                   re.signup_ip AS referee_ip, re.signup_device AS referee_device
+            FROM referrals r
+            JOIN users rr ON rr.id = r.referrer_id
+            JOIN users re ON re.id = r.referee_id
+            WHERE r.id = $1 AND r.status = 'pending'
+            FOR UPDATE OF r
+            "#,
+        )
+        .bind(referral_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let referrer_id: Uuid = row.get("referrer_id");
+        let referee_id: Uuid = row.get("referee_id");
+        let created_at: chrono::DateTime<chrono::Utc> = row.get("created_at");
+        let referee_ip: Option<String> = row.get("referee_ip");
+
+        // Serialize screening per referrer so the reward cap holds
+        sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+            .bind(referrer_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let signups_from_ip: i64 = match &referee_ip {
+            Some(ip) => {
+                sqlx::query_scalar(
+                    r#"
+                    SELECT COUNT(*) FROM referrals r
+                    JOIN users u ON u.id = r.referee_id
+                    WHERE r.referrer_id = $1 AND u.signup_ip = $2
+                      AND r.created_at BETWEEN $3 - INTERVAL '24 hours' AND $3 + INTERVAL '24 hours'
+                    "#,
+                )
+                .bind(referrer_id)
+                .bind(ip)
+                .bind(created_at)
+                .fetch_one(&mut *tx)
+                .await?
+            }
+            None => 0,
+        };
+        let referrer_rewarded: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM referrals WHERE referrer_id = $1 AND status = 'qualified'",
+        )
+        .bind(referrer_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let signals = FraudSignals {
+            referrer_ip: row.get("referrer_ip"),
+            referee_ip,
+            referrer_device: row.get("referrer_device"),
+            referee_device: row.get("referee_device"),
+            signups_from_ip,
+            referrer_rewarded,
+        };
+        let flags = fraud_flags(&signals, &self.config.referral);
+
+        let status = if flags.is_empty() {
+            self.issue_rewards(&mut tx, referral_id, referrer_id, referee_id).await?;
+            ReferralStatus::Qualified
+        } else {
+            let flags: Vec<&str> = flags.iter().map(FraudFlag::as_str).collect();
+            sqlx::query(
+                "UPDATE referrals SET status = 'flagged', fraud_flags = $2, updated_at = NOW() WHERE id = $1",
+            )
+            .bind(referral_id)
+            .bind(&flags)
+            .execute(&mut *tx)
+            .await?;
+            ReferralStatus::Flagged
+        };
+
+        tx.commit().await?;
+        Ok(Some(status))
+    }
+
+    async fn issue_rewards(
+        &self,
+        conn: &mut PgConnection,
+        referral_id: Uuid,
+        referrer_id: Uuid,
+        referee_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let rewards = [
+            ("referrer", referrer_id, self.config.referral.referrer_reward_kwh),
+            ("referee", referee_id, self.config.referral.referee_reward_kwh),
+        ];
+        for (role, user_id, amount) in rewards {
+            if amount <= Decimal::ZERO {
+                continue;
+            }
+            sqlx::query(
+                "INSERT INTO referral_rewards (referral_id, user_id, role, amount) VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (referral_id, role) DO NOTHING",
+            )
+            .bind(referral_id)
+            .bind(user_id)
+            .bind(role)
+            .bind(amount)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        sqlx::query(
+            "UPDATE referrals SET status = 'qualified', qualified_at = NOW(), updated_at = NOW() WHERE id = $1",
+        )
+        .bind(referral_id)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Mint pending rewards to users with a wallet. A reward that keeps
+    /// failing is marked failed after the tokenization retry limit.
+    /// Returns the number of rewards minted.
+    pub async fn mint_pending_rewards(&self) -> Result<usize, ApiError> {
+        let max_attempts = self.config.tokenization.max_retry_attempts.max(1) as i32;
+        let mut minted = 0;
+
+        for _ in 0..MINT_BATCH_SIZE {
+            let mut tx = self.db.begin().await?;
+
+            // Row lock keeps other instances from minting the same reward
+            let Some(row) = sqlx::query(
+                r#"
+                SELECT rw.id, rw.amount, rw.attempts, u.wallet_address
+                FROM referral_rewards rw
+                JOIN users u ON u.id = rw.user_id
+                WHERE rw.status = 'pending' AND u.wallet_address IS NOT NULL
+                ORDER BY rw.attempts ASC, rw.created_at ASC
+                LIMIT 1
+                FOR UPDATE OF rw SKIP LOCKED
+                "#,
+            )
+            .fetch_optional(&mut *tx)
+            .await?
+            else {
+                break;
+            };
+
+            let reward_id: Uuid = row.get("id");
+            let amount: Decimal = row.get("amount");
+            let attempts: i32 = row.get::<i32, _>("attempts") + 1;
+            let wallet_address: String = row.get("wallet_address");
+
+            match self.mint(&wallet_address, amount).await {
+                Ok(signature) => {
+                    sqlx::query(
+                        "UPDATE referral_rewards SET status = 'minted', attempts = $2, mint_tx_signature = $3,
+                             last_error = NULL, minted_at = NOW()
+                         WHERE id = $1",
+                    )
+                    .bind(reward_id)
+                    .bind(attempts)
+                    .bind(&signature)
+                    .execute(&mut *tx)
+                    .await?;
+                    tx.commit().await?;
+                    minted += 1;
+                    info!("🎉 Minted referral reward {} ({} kWh) - TX: {}", reward_id, amount, signature);
+                }
+                Err(e) => {
+                    let status = if attempts >= max_attempts { "failed" } else { "pending" };
+                    sqlx::query(
+                        "UPDATE referral_rewards SET status = $2, attempts = $3, last_error = $4 WHERE id = $1",
+                    )
+                    .bind(reward_id)
+                    .bind(status)
+                    .bind(attempts)
+                    .bind(e.to_string())
+                    .execute(&mut *tx)
+                    .await?;
+                    tx.commit().await?;
+                    warn!(
+                        "⚠️ Referral reward {} mint attempt {}/{} failed: {}",
+                        reward_id, attempts, max_attempts, e
+                    );
+                    // The chain is likely unavailable; retry on the next run
+                    break;
+                }
+            }
+        }
+
+        Ok(minted)
+    }
+
+    async fn mint(&self, wallet_address: &str, amount: Decimal) -> anyhow::Result<String> {
+        let kwh = amount
+            .to_f64()
+            .ok_or_else(|| anyhow!("Reward amount {} out of range", amount))?;
+        let authority = self.wallet.get_authority_keypair().await?;
+        let mint = BlockchainService::parse_pubkey(&self.config.energy_token_mint)?;
+        let wallet = BlockchainService::parse_pubkey(wallet_address)?;
+
+        let signature = if self.config.tokenization.enable_real_blockchain {
+            let token_account = self
+                .blockchain
+                .ensure_token_account_exists(&authority, &wallet, &mint)
+                .await?;
+            self.blockchain
+                .mint_energy_tokens(&authority, &token_account, &wallet, &mint, kwh)
+                .await?
+        } else {
+            self.blockchain
+                .mint_spl_tokens(&authority, &wallet, &mint, kwh)
+                .await?
+        };
+        Ok(signature.to_string())
+    }
+
+    /// Screen complete referrals, then mint their rewards
+    pub async fn run_cycle(&self) -> Result<(usize, usize), ApiError> {
+        let screened = self.process_qualifications().await?;
+        let minted = self.mint_pending_rewards().await?;
+        Ok((screened, minted))
+    }
+
+    /// A user's code, referrals and rewards
+    pub async fn summary(&self, user_id: Uuid) -> Result<ReferralSummary, ApiError> {
+        let code = self.code_for(user_id).await?;
+
+        let referred_by: Option<String> = sqlx::query_scalar(
+            "SELECT u.username FROM referrals r JOIN users u ON u.id = r.referrer_id WHERE r.referee_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        let referrals = sqlx::query_as::<_, UserReferral>(
+            r#"
+            SELECT u.username AS referee_username, r.status, r.first_reading_at, r.first_trade_at, r.created_at
+            FROM referrals r
+            JOIN users u ON u.id = r.referee_id
+            WHERE r.referrer_id = $1
+            ORDER BY r.created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let rewards = sqlx::query_as::<_, ReferralReward>(&format!(
+            "SELECT {} FROM referral_rewards WHERE user_id = $1 ORDER BY created_at DESC",
+            REWARD_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let total = |status: &str| -> Decimal {
+            rewards
+                .iter()
+                .filter(|r| r.status == status)
+                .map(|r| r.amount)
+                .sum()
+        };
+
+        Ok(ReferralSummary {
+            code,
+            referred_by,
+            total_minted: total("minted"),
+            total_pending: total("pending"),
+            referrals,
+            rewards,
+        })
+    }
+
+    /// Referrals, newest first
+    pub async fn list(
+        &self,
+        status: Option<ReferralStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Referral>, ApiError> {
+        Ok(sqlx::query_as::<_, Referral>(&format!(
+            r#"
+            SELECT {} FROM referrals r
+            JOIN users u ON u.id = r.referee_id
+            WHERE ($1::TEXT IS NULL OR r.status = $1)
+            ORDER BY r.created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            REFERRAL_COLUMNS
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    async fn get(&self, referral_id: Uuid) -> Result<Referral, ApiError> {
+        sqlx::query_as::<_, Referral>(&format!(
+            "SELECT {} FROM referrals r JOIN users u ON u.id = r.referee_id WHERE r.id = $1",
+            REFERRAL_COLUMNS
+        ))
+        .bind(referral_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Referral not found".to_string()))
+    }
+
+    /// Approve (issue rewards) or reject a flagged referral
+    pub async fn review(
+        &self,
+        admin_id: Uuid,
+        referral_id: Uuid,
+        approve: bool,
+        note: Option<&str>,
+    ) -> Result<Referral, ApiError> {
+        let mut tx = self.db.begin().await?;
+
+        let row = sqlx::query(
+            "SELECT referrer_id, referee_id, status FROM referrals WHERE id = $1 FOR UPDATE",
+        )
+        .bind(referral_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Referral not found".to_string()))?;
+
+        let status: String = row.get("status");
+        if status != ReferralStatus::Flagged.as_str() {
+            return Err(ApiError::Conflict(format!(
+                "Only flagged referrals can be reviewed (status: {})",
+                status
+            )));
+        }
+        let referrer_id: Uuid = row.get("referrer_id");
+        let referee_id: Uuid = row.get("referee_id");
+
+        if approve {
+            self.issue_rewards(&mut tx, referral_id, referrer_id, referee_id).await?;
+        } else {
+            sqlx::query("UPDATE referrals SET status = 'rejected', updated_at = NOW() WHERE id = $1")
+                .bind(referral_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query(
+            "UPDATE referrals SET reviewed_by = $2, reviewed_at = NOW(), review_note = $3 WHERE id = $1",
+        )
+        .bind(referral_id)
+        .bind(admin_id)
+        .bind(note.map(str::trim).filter(|n| !n.is_empty()))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let action = if approve { "referral_approved" } else { "referral_rejected" };
+        self.audit.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: Some(referee_id),
+            details: format!("referral {} from {}", referral_id, referrer_id),
+        });
+
+        self.get(referral_id).await
+    }
+
+    /// Put a failed reward back in the mint queue
+    pub async fn retry_reward(&self, admin_id: Uuid, reward_id: Uuid) -> Result<ReferralReward, ApiError> {
+        let reward = sqlx::query_as::<_, ReferralReward>(&format!(
+            "UPDATE referral_rewards SET status = 'pending', attempts = 0
+             WHERE id = $1 AND status = 'failed'
+             RETURNING {}",
+            REWARD_COLUMNS
+        ))
+        .bind(reward_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        let Some(reward) = reward else {
+            let exists: bool =
+                sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM referral_rewards WHERE id = $1)")
+                    .bind(reward_id)
+                    .fetch_one(&self.db)
+                    .await?;
+            return Err(if exists {
+                ApiError::Conflict("Only failed rewards can be retried".to_string())
+            } else {
+                ApiError::NotFound("Referral reward not found".to_string())
+            });
+        };
+
+        self.audit.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "referral_reward_retried".to_string(),
+            target_user_id: Some(reward.user_id),
+            details: format!("reward {} ({} kWh)", reward.id, reward.amount),
+        });
+        Ok(reward)
+    }
+
+    /// Program-wide totals and the most successful referrers
+    pub async fn report(&self) -> Result<ReferralReport, ApiError> {
+        let counts = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE status = 'pending') AS pending,
+                COUNT(*) FILTER (WHERE status = 'qualified') AS qualified,
+                COUNT(*) FILTER (WHERE status = 'flagged') AS flagged,
+                COUNT(*) FILTER (WHERE status = 'rejected') AS rejected
+            FROM referrals
+            "#,
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        let rewards = sqlx::query(
+            r#"
+            SELECT
+                COALESCE(SUM(amount) FILTER (WHERE status = 'minted'), 0) AS minted,
+                COALESCE(SUM(amount) FILTER (WHERE status = 'pending'), 0) AS pending,
+                COUNT(*) FILTER (WHERE status = 'failed') AS failed
+            FROM referral_rewards
+            "#,
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        let top_referrers = sqlx::query_as::<_, TopReferrer>(
+            r#"
+            SELECT u.id AS user_id, u.username,
+                   COUNT(DISTINCT r.id) AS qualified,
+                   COALESCE(SUM(rw.amount) FILTER (WHERE rw.status = 'minted'), 0) AS rewarded_kwh
+            FROM referrals r
+            JOIN users u ON u.id = r.referrer_id
+            LEFT JOIN referral_rewards rw ON rw.referral_id = r.id AND rw.role = 'referrer'
+            WHERE r.status = 'qualified'
+            GROUP BY u.id, u.username
+            ORDER BY qualified DESC, rewarded_kwh DESC
+            LIMIT 10
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(ReferralReport {
+            total: counts.get("total"),
+            pending: counts.get("pending"),
+            qualified: counts.get("qualified"),
+            flagged: counts.get("flagged"),
+            rejected: counts.get("rejected"),
+            rewards_minted_kwh: rewards.get("minted"),
+            rewards_pending_kwh: rewards.get("pending"),
+            rewards_failed: rewards.get("failed"),
+            top_referrers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals() -> FraudSignals {
+        FraudSignals {
+            referrer_ip: Some("203.0.113.1".to_string()),
+            referee_ip: Some("198.51.100.7".to_string()),
+            referrer_device: Some("device-a".to_string()),
+            referee_device: Some("device-b".to_string()),
+            signups_from_ip: 1,
+            referrer_rewarded: 0,
+        }
+    }
+
+    #[test]
+    fn test_clean_referral_has_no_flags() {
+        assert!(fraud_flags(&signals(), &ReferralConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_shared_ip_and_device_are_flagged() {
+        let mut s = signals();
+        s.referee_ip = s.referrer_ip.clone();
+        s.referee_device = s.referrer_device.clone();
+        assert_eq!(
+            fraud_flags(&s, &ReferralConfig::default()),
+            vec![FraudFlag::SameIp, FraudFlag::SameDevice]
+        );
+    }
+
+    #[test]
+    fn test_missing_signals_do_not_match() {
+        let mut s = signals();
+        s.referrer_ip = None;
+        s.referee_ip = None;
+        s.referrer_device = Some(String::new());
+        s.referee_device = Some(String::new());
+        s.signups_from_ip = 10;
+        assert!(fraud_flags(&s, &ReferralConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_velocity_and_cap() {
+        let config = ReferralConfig::default();
+        let mut s = signals();
+        s.signups_from_ip = config.max_signups_per_ip + 1;
+        s.referrer_rewarded = config.max_rewards_per_referrer;
+        assert_eq!(
+            fraud_flags(&s, &config),
+            vec![FraudFlag::IpVelocity, FraudFlag::ReferrerCapReached]
+        );
+    }
+
+    #[test]
+    fn test_codes_are_normalized() {
+        assert_eq!(normalize_code("  ab12cd34 "), "AB12CD34");
+        assert_eq!(generate_code().len(), CODE_LENGTH);
+    }
+}
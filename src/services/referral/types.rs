@@ -0,0 +1,166 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Referral lifecycle: pending -> qualified | flagged; flagged -> qualified | rejected
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferralStatus {
+    /// Waiting for the new user's first verified reading and first trade
+    Pending,
+    /// Rewards issued
+    Qualified,
+    /// Qualified, but held for admin review by the fraud heuristics
+    Flagged,
+    Rejected,
+}
+
+impl ReferralStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Qualified => "qualified",
+            Self::Flagged => "flagged",
+            Self::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "qualified" => Some(Self::Qualified),
+            "flagged" => Some(Self::Flagged),
+            "rejected" => Some(Self::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// Fraud heuristic that held a referral for review
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FraudFlag {
+    /// Referee signed up from the referrer's own sign-up IP
+    SameIp,
+    /// Referee signed up from the referrer's device
+    SameDevice,
+    /// Too many of the referrer's sign-ups came from one IP within 24h
+    IpVelocity,
+    /// Referrer already collected the maximum number of rewards
+    ReferrerCapReached,
+}
+
+impl FraudFlag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SameIp => "same_ip",
+            Self::SameDevice => "same_device",
+            Self::IpVelocity => "ip_velocity",
+            Self::ReferrerCapReached => "referrer_cap_reached",
+        }
+    }
+}
+
+/// What the fraud heuristics know about a referral at qualification time
+#[derive(Debug, Clone, Default)]
+pub struct FraudSignals {
+    pub referrer_ip: Option<String>,
+    pub referee_ip: Option<String>,
+    pub referrer_device: Option<String>,
+    pub referee_device: Option<String>,
+    /// The referrer's sign-ups from the referee's IP in the 24h around the referee's sign-up
+    pub signups_from_ip: i64,
+    /// Referrals of the referrer already qualified
+    pub referrer_rewarded: i64,
+}
+
+/// Referral record (admin view)
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct Referral {
+    pub id: Uuid,
+    pub referrer_id: Uuid,
+    pub referee_id: Uuid,
+    pub referee_username: String,
+    pub code: String,
+    pub status: String,
+    pub first_reading_at: Option<DateTime<Utc>>,
+    pub first_trade_at: Option<DateTime<Utc>>,
+    pub fraud_flags: Vec<String>,
+    pub qualified_at: Option<DateTime<Utc>>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A user's referral of someone else
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct UserReferral {
+    pub referee_username: String,
+    pub status: String,
+    pub first_reading_at: Option<DateTime<Utc>>,
+    pub first_trade_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Token reward for a qualified referral
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ReferralReward {
+    pub id: Uuid,
+    pub referral_id: Uuid,
+    pub user_id: Uuid,
+    /// referrer or referee
+    pub role: String,
+    #[schema(value_type = String)]
+    pub amount: Decimal,
+    /// pending, minted or failed
+    pub status: String,
+    pub attempts: i32,
+    pub mint_tx_signature: Option<String>,
+    pub last_error: Option<String>,
+    pub minted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Referral overview of a user
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReferralSummary {
+    /// Code to share with new users
+    pub code: String,
+    /// Username of whoever referred this user
+    pub referred_by: Option<String>,
+    pub referrals: Vec<UserReferral>,
+    pub rewards: Vec<ReferralReward>,
+    #[schema(value_type = String)]
+    pub total_minted: Decimal,
+    #[schema(value_type = String)]
+    pub total_pending: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct TopReferrer {
+    pub user_id: Uuid,
+    pub username: String,
+    pub qualified: i64,
+    #[schema(value_type = String)]
+    pub rewarded_kwh: Decimal,
+}
+
+/// Program-wide referral report
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReferralReport {
+    pub total: i64,
+    pub pending: i64,
+    pub qualified: i64,
+    pub flagged: i64,
+    pub rejected: i64,
+    #[schema(value_type = String)]
+    pub rewards_minted_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub rewards_pending_kwh: Decimal,
+    pub rewards_failed: i64,
+    pub top_referrers: Vec<TopReferrer>,
+}
@@ -0,0 +1,1245 @@
+//! Scheduled Report Subscriptions
+//!
+//! Users subscribe to recurring analytics reports: a daily trading summary,
+//! a weekly generation report and a monthly carbon report, covering their
+//! own activity or, for admins, the whole platform. A scheduled job builds
+//! each report once its period has closed and delivers it by email with the
+//! report attached (CSV or PDF), or posts it as JSON to the subscriber's
+//! webhook, signed with the subscription's secret. Each period is delivered
+//! once; a subscription is paused after repeated failed deliveries. Periods
+//! that closed while the job was down are not back-filled.
+
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, Months, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{FromRow, PgPool, Row};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::ReportSubscriptionConfig;
+use crate::error::{ApiError, Result};
+use crate::i18n;
+use crate::models::pii::{SealedUserPii, USER_PII_COLUMNS};
+use crate::services::email::ReportEmail;
+use crate::services::jobs::{JobRun, LogLevel};
+use crate::services::{EmailService, EmissionFactorService, PiiCipher, WebhookService};
+use crate::utils::{pdf, MarketTimezone};
+
+/// Reports are built this long after their period closes, so late meter
+/// readings and settlements are included
+const DELIVERY_DELAY_MINS: i64 = 30;
+/// Wait before retrying a failed delivery
+const RETRY_DELAY_MINS: i64 = 60;
+/// A claimed subscription is left alone by other instances for this long
+const CLAIM_LEASE_MINS: i64 = 15;
+/// Due subscriptions handled per run
+const BATCH_SIZE: i64 = 50;
+const WEBHOOK_SECRET_LEN: usize = 32;
+
+const SUBSCRIPTION_COLUMNS: &str = r#"
+    id, user_id, report, scope, channel, format, webhook_url, active, next_run_at,
+    last_period_start, last_delivered_at, failure_count, last_error, created_at, updated_at
+"#;
+
+/// Report a subscription delivers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportKind {
+    /// Settled trades of the previous UTC day, per hour
+    DailyTradingSummary,
+    /// Generation, consumption and export of the previous ISO week, per day
+    WeeklyGeneration,
+    /// Generation and CO2 avoided in the previous calendar month, per day
+    MonthlyCarbon,
+}
+
+impl ReportKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DailyTradingSummary => "daily_trading_summary",
+            Self::WeeklyGeneration => "weekly_generation",
+            Self::MonthlyCarbon => "monthly_carbon",
+        }
+    }
+
+    /// Title of rendered files
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::DailyTradingSummary => "Daily Trading Summary",
+            Self::WeeklyGeneration => "Weekly Generation Report",
+            Self::MonthlyCarbon => "Monthly Carbon Report",
+        }
+    }
+
+    fn i18n_key(&self) -> &'static str {
+        match self {
+            Self::DailyTradingSummary => "report.daily_trading_summary",
+            Self::WeeklyGeneration => "report.weekly_generation",
+            Self::MonthlyCarbon => "report.monthly_carbon",
+        }
+    }
+
+    /// Start of the period in progress at `now`
+    fn period_start(&self, now: DateTime<Utc>, tz: &MarketTimezone) -> DateTime<Utc> {
+        match self {
+            Self::DailyTradingSummary => tz.start_of_day(tz.date_of(now)),
+            Self::WeeklyGeneration => tz.start_of_week(now),
+            Self::MonthlyCarbon => tz.start_of_month(now),
+        }
+    }
+
+    /// Shift a period start by one period on the market-local calendar
+    fn shift(&self, start: DateTime<Utc>, forward: bool, tz: &MarketTimezone) -> DateTime<Utc> {
+        let date = tz.date_of(start);
+        let shifted = match (self, forward) {
+            (Self::DailyTradingSummary, true) => date + Duration::days(1),
+            (Self::DailyTradingSummary, false) => date - Duration::days(1),
+            (Self::WeeklyGeneration, true) => date + Duration::weeks(1),
+            (Self::WeeklyGeneration, false) => date - Duration::weeks(1),
+            (Self::MonthlyCarbon, true) => date.checked_add_months(Months::new(1)).unwrap_or(date),
+            (Self::MonthlyCarbon, false) => date.checked_sub_months(Months::new(1)).unwrap_or(date),
+        };
+        tz.start_of_day(shifted)
+    }
+
+    /// The last period closed at `now`: the previous market-local day, ISO
+    /// week or calendar month
+    pub fn last_period(&self, now: DateTime<Utc>, tz: &MarketTimezone) -> (DateTime<Utc>, DateTime<Utc>) {
+        let end = self.period_start(now, tz);
+        (self.shift(end, false, tz), end)
+    }
+
+    /// When the report for the period in progress at `now` is due
+    pub fn next_run(&self, now: DateTime<Utc>, tz: &MarketTimezone) -> DateTime<Utc> {
+        self.shift(self.period_start(now, tz), true, tz) + Duration::minutes(DELIVERY_DELAY_MINS)
+    }
+
+    /// Period as shown to readers: a date, a date range or a month, in
+    /// market-local dates
+    pub fn period_label(&self, start: DateTime<Utc>, end: DateTime<Utc>, tz: &MarketTimezone) -> String {
+        let first = tz.date_of(start);
+        match self {
+            Self::DailyTradingSummary => first.format("%Y-%m-%d").to_string(),
+            Self::WeeklyGeneration => format!(
+                "{} - {}",
+                first.format("%Y-%m-%d"),
+                (tz.date_of(end) - Duration::days(1)).format("%Y-%m-%d")
+            ),
+            Self::MonthlyCarbon => first.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+impl FromStr for ReportKind {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "daily_trading_summary" => Ok(Self::DailyTradingSummary),
+            "weekly_generation" => Ok(Self::WeeklyGeneration),
+            "monthly_carbon" => Ok(Self::MonthlyCarbon),
+            other => Err(ApiError::Internal(format!("Unknown report kind {}", other))),
+        }
+    }
+}
+
+/// Whose activity a report covers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportScope {
+    /// The subscriber's own trades and meters
+    #[default]
+    User,
+    /// All participants; admins only
+    Platform,
+}
+
+impl ReportScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Platform => "platform",
+        }
+    }
+}
+
+impl FromStr for ReportScope {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "user" => Ok(Self::User),
+            "platform" => Ok(Self::Platform),
+            other => Err(ApiError::Internal(format!("Unknown report scope {}", other))),
+        }
+    }
+}
+
+/// How a report is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryChannel {
+    /// To the subscriber's email address, with the report attached
+    Email,
+    /// Posted as JSON to the subscription's webhook URL
+    Webhook,
+}
+
+impl DeliveryChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Webhook => "webhook",
+        }
+    }
+}
+
+impl FromStr for DeliveryChannel {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "email" => Ok(Self::Email),
+            "webhook" => Ok(Self::Webhook),
+            other => Err(ApiError::Internal(format!("Unknown delivery channel {}", other))),
+        }
+    }
+}
+
+/// File format of emailed reports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    #[default]
+    Csv,
+    Pdf,
+}
+
+impl ReportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Pdf => "pdf",
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "pdf" => Ok(Self::Pdf),
+            other => Err(ApiError::Internal(format!("Unknown report format {}", other))),
+        }
+    }
+}
+
+/// A scheduled report subscription
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReportSubscription {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub report: ReportKind,
+    pub scope: ReportScope,
+    pub channel: DeliveryChannel,
+    pub format: ReportFormat,
+    pub webhook_url: Option<String>,
+    /// Paused subscriptions are not delivered
+    pub active: bool,
+    pub next_run_at: DateTime<Utc>,
+    /// Start of the last period delivered
+    pub last_period_start: Option<DateTime<Utc>>,
+    pub last_delivered_at: Option<DateTime<Utc>>,
+    /// Failed deliveries since the last successful one
+    pub failure_count: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct ReportSubscriptionRow {
+    id: Uuid,
+    user_id: Uuid,
+    report: String,
+    scope: String,
+    channel: String,
+    format: String,
+    webhook_url: Option<String>,
+    active: bool,
+    next_run_at: DateTime<Utc>,
+    last_period_start: Option<DateTime<Utc>>,
+    last_delivered_at: Option<DateTime<Utc>>,
+    failure_count: i32,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<ReportSubscriptionRow> for ReportSubscription {
+    type Error = ApiError;
+
+    fn try_from(row: ReportSubscriptionRow) -> Result<Self> {
+        Ok(Self {
+            id: row.id,
+            user_id: row.user_id,
+            report: row.report.parse()?,
+            scope: row.scope.parse()?,
+            channel: row.channel.parse()?,
+            format: row.format.parse()?,
+            webhook_url: row.webhook_url,
+            active: row.active,
+            next_run_at: row.next_run_at,
+            last_period_start: row.last_period_start,
+            last_delivered_at: row.last_delivered_at,
+            failure_count: row.failure_count,
+            last_error: row.last_error,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+/// A new subscription; the webhook secret is only returned here
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedReportSubscription {
+    pub subscription: ReportSubscription,
+    /// HMAC-SHA256 key of the `signature` on webhook deliveries
+    pub webhook_secret: Option<String>,
+}
+
+/// One delivery attempt
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ReportDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    /// delivered or failed
+    pub status: String,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateReportSubscriptionRequest {
+    pub report: ReportKind,
+    /// Default user; platform requires the admin role
+    pub scope: Option<ReportScope>,
+    pub channel: DeliveryChannel,
+    /// Attachment format of emailed reports, default csv
+    pub format: Option<ReportFormat>,
+    /// HTTPS endpoint, required for the webhook channel
+    #[validate(length(max = 2048))]
+    pub webhook_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateReportSubscriptionRequest {
+    pub format: Option<ReportFormat>,
+    #[validate(length(max = 2048))]
+    pub webhook_url: Option<String>,
+    /// Resuming a paused subscription clears its failures
+    pub active: Option<bool>,
+}
+
+/// A headline figure of a report
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReportFigure {
+    pub label: String,
+    pub value: f64,
+    pub unit: String,
+}
+
+/// Values of one bucket (hour or day) of a report
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReportRow {
+    pub bucket_start: DateTime<Utc>,
+    pub values: Vec<f64>,
+}
+
+/// A report built for one period
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GeneratedReport {
+    pub report: ReportKind,
+    pub scope: ReportScope,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub summary: Vec<ReportFigure>,
+    /// Names of the row values, in order
+    pub columns: Vec<String>,
+    pub rows: Vec<ReportRow>,
+}
+
+impl GeneratedReport {
+    fn highlights(&self) -> Vec<String> {
+        self.summary
+            .iter()
+            .map(|figure| format!("{}: {:.2} {}", figure.label, figure.value, figure.unit).trim_end().to_string())
+            .collect()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        csv.push_str("Period Start");
+        for column in &self.columns {
+            csv.push_str(&format!(",{}", column));
+        }
+        csv.push('\n');
+        for row in &self.rows {
+            csv.push_str(&row.bucket_start.format("%Y-%m-%d %H:%M").to_string());
+            for value in &row.values {
+                csv.push_str(&format!(",{:.4}", value));
+            }
+            csv.push('\n');
+        }
+
+        csv.push('\n');
+        for line in self.highlights() {
+            csv.push_str(&format!("# {}\n", line));
+        }
+        csv
+    }
+
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "Period: {} to {} (UTC), {}",
+                self.period_start.format("%Y-%m-%d"),
+                self.period_end.format("%Y-%m-%d"),
+                self.scope.as_str()
+            ),
+            String::new(),
+        ];
+        lines.extend(self.highlights());
+        lines.push(String::new());
+
+        let mut header = format!("{:<18}", "Period");
+        for column in &self.columns {
+            header.push_str(&format!("{:>16}", column));
+        }
+        lines.push(header);
+        for row in &self.rows {
+            let mut line = format!("{:<18}", row.bucket_start.format("%Y-%m-%d %H:%M"));
+            for value in &row.values {
+                line.push_str(&format!("{:>16.2}", value));
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// File name, content type and body of the rendered report
+    pub fn render(&self, format: ReportFormat) -> (String, &'static str, Vec<u8>) {
+        let filename = format!(
+            "gridtokenx_{}_{}.{}",
+            self.report.as_str(),
+            self.period_start.format("%Y%m%d"),
+            format.as_str()
+        );
+        match format {
+            ReportFormat::Csv => (filename, "text/csv; charset=utf-8", self.to_csv().into_bytes()),
+            ReportFormat::Pdf => {
+                let title = format!("GridTokenX {}", self.report.title());
+                (filename, "application/pdf", pdf::text_document(&title, &self.to_lines()))
+            }
+        }
+    }
+}
+
+fn figure(label: &str, value: f64, unit: &str) -> ReportFigure {
+    ReportFigure {
+        label: label.to_string(),
+        value,
+        unit: unit.to_string(),
+    }
+}
+
+/// Webhook endpoints must be HTTPS and must not point at loopback, private
+/// or link-local addresses
+pub fn validate_webhook_url(url: &str) -> Result<()> {
+    let invalid = |message: &str| ApiError::validation_field("webhook_url", message);
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|_| invalid("Invalid URL"))?;
+    if parsed.scheme() != "https" {
+        return Err(invalid("Webhook URL must use https"));
+    }
+    let host = parsed.host_str().ok_or_else(|| invalid("Webhook URL must have a host"))?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.eq_ignore_ascii_case("localhost") || host.to_ascii_lowercase().ends_with(".localhost") {
+        return Err(invalid("Webhook URL must be publicly reachable"));
+    }
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        let internal = match ip {
+            IpAddr::V4(ip) => {
+                ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.is_unspecified() || ip.is_broadcast()
+            }
+            IpAddr::V6(ip) => {
+                let segment = ip.segments()[0];
+                ip.is_loopback() || ip.is_unspecified() || (segment & 0xfe00) == 0xfc00 || (segment & 0xffc0) == 0xfe80
+            }
+        };
+        if internal {
+            return Err(invalid("Webhook URL must be publicly reachable"));
+        }
+    }
+    Ok(())
+}
+
+/// Random secret for signing webhook deliveries
+pub(crate) fn webhook_secret() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(WEBHOOK_SECRET_LEN)
+        .map(char::from)
+        .collect()
+}
+
+/// A due subscription with its delivery details
+#[derive(FromRow)]
+struct DueSubscription {
+    id: Uuid,
+    user_id: Uuid,
+    report: String,
+    scope: String,
+    channel: String,
+    format: String,
+    webhook_url: Option<String>,
+    webhook_secret: Option<String>,
+    last_period_start: Option<DateTime<Utc>>,
+    failure_count: i32,
+}
+
+#[derive(Clone)]
+pub struct ReportSubscriptionService {
+    db: PgPool,
+    config: ReportSubscriptionConfig,
+    email: Option<EmailService>,
+    webhooks: WebhookService,
+    emission_factors: EmissionFactorService,
+    pii: PiiCipher,
+    /// Report periods follow the market-local calendar
+    timezone: MarketTimezone,
+}
+
+impl ReportSubscriptionService {
+    pub fn new(
+        db: PgPool,
+        config: ReportSubscriptionConfig,
+        email: Option<EmailService>,
+        webhooks: WebhookService,
+        emission_factors: EmissionFactorService,
+        pii: PiiCipher,
+        timezone: MarketTimezone,
+    ) -> Self {
+        Self {
+            db,
+            config,
+            email,
+            webhooks,
+            emission_factors,
+            pii,
+            timezone,
+        }
+    }
+
+    fn email_enabled(&self) -> bool {
+        self.email.as_ref().is_some_and(|email| email.is_enabled())
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<ReportSubscription>> {
+        sqlx::query_as::<_, ReportSubscriptionRow>(&format!(
+            "SELECT {} FROM report_subscriptions WHERE user_id = $1 ORDER BY created_at",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(ReportSubscription::try_from)
+        .collect()
+    }
+
+    /// All subscriptions, optionally only those of one report, newest first
+    pub async fn list_all(&self, report: Option<ReportKind>, limit: i64) -> Result<Vec<ReportSubscription>> {
+        sqlx::query_as::<_, ReportSubscriptionRow>(&format!(
+            r#"
+            SELECT {} FROM report_subscriptions
+            WHERE ($1::TEXT IS NULL OR report = $1)
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(report.map(|report| report.as_str()))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(ReportSubscription::try_from)
+        .collect()
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        request: CreateReportSubscriptionRequest,
+    ) -> Result<CreatedReportSubscription> {
+        let scope = request.scope.unwrap_or_default();
+        if scope == ReportScope::Platform && !is_admin {
+            return Err(ApiError::Forbidden("Platform reports require the admin role".to_string()));
+        }
+        let (webhook_url, secret) = match request.channel {
+            DeliveryChannel::Email => {
+                if !self.email_enabled() {
+                    return Err(ApiError::BadRequest("Email delivery is not available".to_string()));
+                }
+                (None, None)
+            }
+            DeliveryChannel::Webhook => {
+                let url = request
+                    .webhook_url
+                    .as_deref()
+                    .map(str::trim)
+                    .ok_or_else(|| ApiError::validation_field("webhook_url", "Required for webhook delivery"))?;
+                validate_webhook_url(url)?;
+                (Some(url.to_string()), Some(webhook_secret()))
+            }
+        };
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM report_subscriptions WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        if count >= self.config.max_per_user {
+            return Err(ApiError::BadRequest(format!(
+                "At most {} report subscriptions can be kept",
+                self.config.max_per_user
+            )));
+        }
+
+        let row = sqlx::query_as::<_, ReportSubscriptionRow>(&format!(
+            r#"
+            INSERT INTO report_subscriptions
+                (user_id, report, scope, channel, format, webhook_url, webhook_secret, next_run_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING {}
+            "#,
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(request.report.as_str())
+        .bind(scope.as_str())
+        .bind(request.channel.as_str())
+        .bind(request.format.unwrap_or_default().as_str())
+        .bind(&webhook_url)
+        .bind(&secret)
+        .bind(request.report.next_run(Utc::now(), &self.timezone))
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(CreatedReportSubscription {
+            subscription: row.try_into()?,
+            webhook_secret: secret,
+        })
+    }
+
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        subscription_id: Uuid,
+        request: UpdateReportSubscriptionRequest,
+    ) -> Result<ReportSubscription> {
+        let webhook_url = request.webhook_url.as_deref().map(str::trim);
+        if let Some(url) = webhook_url {
+            validate_webhook_url(url)?;
+        }
+        let row = sqlx::query_as::<_, ReportSubscriptionRow>(&format!(
+            r#"
+            UPDATE report_subscriptions SET
+                format = COALESCE($3, format),
+                webhook_url = CASE WHEN channel = 'webhook' THEN COALESCE($4, webhook_url) ELSE webhook_url END,
+                active = COALESCE($5, active),
+                failure_count = CASE WHEN $5 THEN 0 ELSE failure_count END,
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING {}
+            "#,
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(subscription_id)
+        .bind(user_id)
+        .bind(request.format.map(|format| format.as_str()))
+        .bind(webhook_url)
+        .bind(request.active)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Report subscription {} not found", subscription_id)))?;
+        row.try_into()
+    }
+
+    pub async fn delete(&self, user_id: Uuid, subscription_id: Uuid) -> Result<()> {
+        let deleted = sqlx::query("DELETE FROM report_subscriptions WHERE id = $1 AND user_id = $2")
+            .bind(subscription_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?
+            .rows_affected();
+        if deleted == 0 {
+            return Err(ApiError::NotFound(format!("Report subscription {} not found", subscription_id)));
+        }
+        Ok(())
+    }
+
+    /// Delivery attempts of one of the user's subscriptions, newest first
+    pub async fn deliveries(&self, user_id: Uuid, subscription_id: Uuid, limit: i64) -> Result<Vec<ReportDelivery>> {
+        self.owned(user_id, subscription_id).await?;
+        Ok(sqlx::query_as::<_, ReportDelivery>(
+            r#"
+            SELECT id, subscription_id, period_start, period_end, status, error, created_at
+            FROM report_deliveries
+            WHERE subscription_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Deliver the last closed period now, without changing the schedule
+    pub async fn send_now(&self, user_id: Uuid, subscription_id: Uuid) -> Result<ReportDelivery> {
+        self.owned(user_id, subscription_id).await?;
+        let due = sqlx::query_as::<_, DueSubscription>(
+            r#"
+            SELECT id, user_id, report, scope, channel, format, webhook_url, webhook_secret,
+                   last_period_start, failure_count
+            FROM report_subscriptions
+            WHERE id = $1
+            "#,
+        )
+        .bind(subscription_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        let report: ReportKind = due.report.parse()?;
+        let (start, end) = report.last_period(Utc::now(), &self.timezone);
+        let outcome = self.deliver(&due, start, end).await;
+        self.record_delivery(due.id, start, end, outcome.as_ref().err()).await
+    }
+
+    async fn owned(&self, user_id: Uuid, subscription_id: Uuid) -> Result<()> {
+        let exists: Option<Uuid> =
+            sqlx::query_scalar("SELECT id FROM report_subscriptions WHERE id = $1 AND user_id = $2")
+                .bind(subscription_id)
+                .bind(user_id)
+                .fetch_optional(&self.db)
+                .await?;
+        exists
+            .map(|_| ())
+            .ok_or_else(|| ApiError::NotFound(format!("Report subscription {} not found", subscription_id)))
+    }
+
+    /// Build and deliver every due subscription; returns how many were
+    /// delivered. A cancelled run stops between subscriptions, leaving the
+    /// rest to be picked up once their claim lapses.
+    pub async fn run_due(&self, job: &JobRun) -> Result<usize> {
+        // Claiming pushes next_run_at forward so another instance (or an
+        // overlapping run) skips the batch while it is being delivered
+        let due = sqlx::query_as::<_, DueSubscription>(
+            r#"
+            UPDATE report_subscriptions
+            SET next_run_at = NOW() + make_interval(mins => $2)
+            WHERE id IN (
+                SELECT id FROM report_subscriptions
+                WHERE active AND next_run_at <= NOW()
+                ORDER BY next_run_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, user_id, report, scope, channel, format, webhook_url, webhook_secret,
+                      last_period_start, failure_count
+            "#,
+        )
+        .bind(BATCH_SIZE)
+        .bind(CLAIM_LEASE_MINS as i32)
+        .fetch_all(&self.db)
+        .await?;
+
+        let now = Utc::now();
+        let mut delivered = 0;
+        for subscription in due {
+            if job.cancelled().await {
+                job.log(LogLevel::Warn, format!("Cancelled after {} deliveries", delivered))
+                    .await;
+                break;
+            }
+            let report: ReportKind = subscription.report.parse()?;
+            let (start, end) = report.last_period(now, &self.timezone);
+            let next_run = report.next_run(now, &self.timezone);
+
+            if subscription.last_period_start == Some(start) {
+                sqlx::query("UPDATE report_subscriptions SET next_run_at = $2 WHERE id = $1")
+                    .bind(subscription.id)
+                    .bind(next_run)
+                    .execute(&self.db)
+                    .await?;
+                continue;
+            }
+
+            match self.deliver(&subscription, start, end).await {
+                Ok(()) => {
+                    sqlx::query(
+                        r#"
+                        UPDATE report_subscriptions
+                        SET last_period_start = $2, last_delivered_at = NOW(), next_run_at = $3,
+                            failure_count = 0, last_error = NULL, updated_at = NOW()
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(subscription.id)
+                    .bind(start)
+                    .bind(next_run)
+                    .execute(&self.db)
+                    .await?;
+                    self.record_delivery(subscription.id, start, end, None).await?;
+                    delivered += 1;
+                }
+                Err(e) => {
+                    let failures = subscription.failure_count + 1;
+                    let pause = failures >= self.config.max_failures;
+                    let message = format!(
+                        "Report subscription {} failed ({} in a row{}): {}",
+                        subscription.id,
+                        failures,
+                        if pause { ", paused" } else { "" },
+                        e
+                    );
+                    warn!("{}", message);
+                    job.log(LogLevel::Error, message).await;
+                    sqlx::query(
+                        r#"
+                        UPDATE report_subscriptions
+                        SET failure_count = $2, last_error = $3, active = active AND NOT $4,
+                            next_run_at = NOW() + make_interval(mins => $5), updated_at = NOW()
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(subscription.id)
+                    .bind(failures)
+                    .bind(e.to_string())
+                    .bind(pause)
+                    .bind(RETRY_DELAY_MINS as i32)
+                    .execute(&self.db)
+                    .await?;
+                    self.record_delivery(subscription.id, start, end, Some(&e)).await?;
+                }
+            }
+        }
+
+        if delivered > 0 {
+            info!("📊 Delivered {} scheduled reports", delivered);
+        }
+        Ok(delivered)
+    }
+
+    async fn record_delivery(
+        &self,
+        subscription_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        error: Option<&ApiError>,
+    ) -> Result<ReportDelivery> {
+        Ok(sqlx::query_as::<_, ReportDelivery>(
+            r#"
+            INSERT INTO report_deliveries (subscription_id, period_start, period_end, status, error)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, subscription_id, period_start, period_end, status, error, created_at
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(start)
+        .bind(end)
+        .bind(if error.is_some() { "failed" } else { "delivered" })
+        .bind(error.map(|e| e.to_string()))
+        .fetch_one(&self.db)
+        .await?)
+    }
+
+    async fn deliver(&self, subscription: &DueSubscription, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<()> {
+        let report: ReportKind = subscription.report.parse()?;
+        let scope: ReportScope = subscription.scope.parse()?;
+
+        let (username, role): (String, String) =
+            sqlx::query_as("SELECT username, role::text FROM users WHERE id = $1")
+                .bind(subscription.user_id)
+                .fetch_one(&self.db)
+                .await?;
+        // Admins who lose the role stop receiving platform-wide figures
+        if scope == ReportScope::Platform && role != "admin" {
+            return Err(ApiError::Forbidden("Platform reports require the admin role".to_string()));
+        }
+        let subject = match scope {
+            ReportScope::User => Some(subscription.user_id),
+            ReportScope::Platform => None,
+        };
+        let generated = self.generate(report, scope, subject, start, end).await?;
+
+        let channel: DeliveryChannel = subscription.channel.parse()?;
+        match channel {
+            DeliveryChannel::Email => {
+                let email = self
+                    .email
+                    .as_ref()
+                    .filter(|email| email.is_enabled())
+                    .ok_or_else(|| ApiError::BadRequest("Email delivery is not available".to_string()))?;
+                let sealed = sqlx::query_as::<_, SealedUserPii>(&format!(
+                    "SELECT {} FROM users WHERE id = $1",
+                    USER_PII_COLUMNS
+                ))
+                .bind(subscription.user_id)
+                .fetch_one(&self.db)
+                .await?;
+                let to = sealed.open(&self.pii).await?.email;
+                let locale = i18n::user_locale(&self.db, subscription.user_id).await;
+
+                let format: ReportFormat = subscription.format.parse()?;
+                let (filename, content_type, body) = generated.render(format);
+                let highlights = generated.highlights();
+                let period = report.period_label(start, end, &self.timezone);
+                email
+                    .send_report_email(
+                        &to,
+                        &username,
+                        &ReportEmail {
+                            report: i18n::t(locale, report.i18n_key()),
+                            period: &period,
+                            highlights: &highlights,
+                            filename: &filename,
+                            content_type,
+                            body: &body,
+                        },
+                        locale,
+                    )
+                    .await
+                    .map_err(|e| ApiError::Internal(e.to_string()))
+            }
+            DeliveryChannel::Webhook => {
+                let (Some(url), Some(secret)) = (&subscription.webhook_url, &subscription.webhook_secret) else {
+                    return Err(ApiError::Internal(format!(
+                        "Webhook subscription {} has no endpoint",
+                        subscription.id
+                    )));
+                };
+                self.webhooks
+                    .send_to(
+                        url,
+                        secret,
+                        &format!("report.{}", report.as_str()),
+                        json!({ "subscription_id": subscription.id, "report": generated }),
+                    )
+                    .await
+                    .map_err(|e| ApiError::Internal(e.to_string()))
+            }
+        }
+    }
+
+    /// Build a report for a period; `user_id` limits it to one participant
+    pub async fn generate(
+        &self,
+        report: ReportKind,
+        scope: ReportScope,
+        user_id: Option<Uuid>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<GeneratedReport> {
+        let (summary, columns, rows) = match report {
+            ReportKind::DailyTradingSummary => self.trading_summary(user_id, start, end).await?,
+            ReportKind::WeeklyGeneration => self.generation_report(user_id, start, end).await?,
+            ReportKind::MonthlyCarbon => self.carbon_report(user_id, start, end).await?,
+        };
+        Ok(GeneratedReport {
+            report,
+            scope,
+            period_start: start,
+            period_end: end,
+            generated_at: Utc::now(),
+            summary,
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            rows,
+        })
+    }
+
+    /// Settled trades per hour. Platform-wide, sold and bought are both the
+    /// traded volume; fees are what sellers paid.
+    async fn trading_summary(
+        &self,
+        user_id: Option<Uuid>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<(Vec<ReportFigure>, &'static [&'static str], Vec<ReportRow>)> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                date_trunc('hour', created_at) AS bucket,
+                COUNT(*)::FLOAT8 AS trades,
+                COALESCE(SUM(energy_amount) FILTER (WHERE $1::UUID IS NULL OR seller_id = $1), 0)::FLOAT8 AS sold,
+                COALESCE(SUM(energy_amount) FILTER (WHERE $1::UUID IS NULL OR buyer_id = $1), 0)::FLOAT8 AS bought,
+                COALESCE(SUM(net_amount) FILTER (WHERE $1::UUID IS NULL OR seller_id = $1), 0)::FLOAT8 AS proceeds,
+                COALESCE(SUM(total_amount) FILTER (WHERE $1::UUID IS NULL OR buyer_id = $1), 0)::FLOAT8 AS cost,
+                COALESCE(SUM(total_amount - net_amount) FILTER (WHERE $1::UUID IS NULL OR seller_id = $1), 0)::FLOAT8 AS fees
+            FROM settlements
+            WHERE ($1::UUID IS NULL OR seller_id = $1 OR buyer_id = $1)
+              AND created_at >= $2 AND created_at < $3
+              AND status::text <> 'failed'
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.db)
+        .await?;
+
+        let rows: Vec<ReportRow> = rows
+            .into_iter()
+            .map(|row| ReportRow {
+                bucket_start: row.get("bucket"),
+                values: ["trades", "sold", "bought", "proceeds", "cost", "fees"]
+                    .iter()
+                    .map(|column| row.get::<f64, _>(*column))
+                    .collect(),
+            })
+            .collect();
+        let total = |index: usize| rows.iter().map(|row| row.values[index]).sum::<f64>();
+        let (trades, sold, bought, proceeds, cost, fees) = (total(0), total(1), total(2), total(3), total(4), total(5));
+
+        let mut summary = vec![
+            figure("Trades", trades, ""),
+            figure("Energy sold", sold, "kWh"),
+            figure("Energy bought", bought, "kWh"),
+            figure("Seller proceeds", proceeds, "GRIDX"),
+            figure("Buyer cost", cost, "GRIDX"),
+            figure("Fees", fees, "GRIDX"),
+            figure("Average price", if bought > 0.0 { cost / bought } else { 0.0 }, "GRIDX/kWh"),
+        ];
+        if user_id.is_some() {
+            summary.push(figure("Net result", proceeds - cost, "GRIDX"));
+        }
+        Ok((
+            summary,
+            &["Trades", "Sold (kWh)", "Bought (kWh)", "Proceeds", "Cost", "Fees"],
+            rows,
+        ))
+    }
+
+    /// Metered generation, consumption and export per market-local day
+    async fn generation_by_day(
+        &self,
+        user_id: Option<Uuid>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, f64, f64, f64)>> {
+        Ok(sqlx::query_as::<_, (DateTime<Utc>, f64, f64, f64)>(
+            r#"
+            SELECT
+                date_trunc('day', reading_timestamp + make_interval(mins => $4)) - make_interval(mins => $4),
+                COALESCE(SUM(energy_generated), 0)::FLOAT8,
+                COALESCE(SUM(energy_consumed), 0)::FLOAT8,
+                COALESCE(SUM(surplus_energy), 0)::FLOAT8
+            FROM meter_readings
+            WHERE ($1::UUID IS NULL OR user_id = $1)
+              AND reading_timestamp >= $2 AND reading_timestamp < $3
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .bind(self.timezone.utc_offset_minutes)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    async fn generation_report(
+        &self,
+        user_id: Option<Uuid>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<(Vec<ReportFigure>, &'static [&'static str], Vec<ReportRow>)> {
+        let days = self.generation_by_day(user_id, start, end).await?;
+        let generated: f64 = days.iter().map(|day| day.1).sum();
+        let consumed: f64 = days.iter().map(|day| day.2).sum();
+        let exported: f64 = days.iter().map(|day| day.3).sum();
+        let self_consumed = (generated - exported).max(0.0);
+        let peak = days.iter().map(|day| day.1).fold(0.0, f64::max);
+
+        let summary = vec![
+            figure("Energy generated", generated, "kWh"),
+            figure("Energy consumed", consumed, "kWh"),
+            figure("Energy exported", exported, "kWh"),
+            figure(
+                "Self-consumption",
+                if generated > 0.0 { self_consumed / generated * 100.0 } else { 0.0 },
+                "%",
+            ),
+            figure("Peak daily generation", peak, "kWh"),
+        ];
+        let rows = days
+            .into_iter()
+            .map(|(day, generated, consumed, exported)| ReportRow {
+                bucket_start: day,
+                values: vec![generated, consumed, exported],
+            })
+            .collect();
+        Ok((summary, &["Generated (kWh)", "Consumed (kWh)", "Exported (kWh)"], rows))
+    }
+
+    /// Generation per day and the grid emissions it avoided, using the
+    /// emission factor of the period's year
+    async fn carbon_report(
+        &self,
+        user_id: Option<Uuid>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<(Vec<ReportFigure>, &'static [&'static str], Vec<ReportRow>)> {
+        let factor = self.emission_factors.factor_for(self.timezone.date_of(start).year()).await;
+        let days = self.generation_by_day(user_id, start, end).await?;
+        let purchased: f64 = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(energy_amount), 0)::FLOAT8
+            FROM settlements
+            WHERE ($1::UUID IS NULL OR buyer_id = $1)
+              AND created_at >= $2 AND created_at < $3
+              AND status::text <> 'failed'
+            "#,
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_one(&self.db)
+        .await?;
+
+        let generated: f64 = days.iter().map(|day| day.1).sum();
+        let summary = vec![
+            figure("Energy generated", generated, "kWh"),
+            figure("CO2 avoided", generated * factor.kg_co2_per_kwh, "kg"),
+            figure("Renewable energy bought P2P", purchased, "kWh"),
+            figure(
+                &format!(
+                    "Emission factor ({} {})",
+                    factor.region,
+                    factor.year.map(|year| year.to_string()).unwrap_or_else(|| "fallback".to_string())
+                ),
+                factor.kg_co2_per_kwh,
+                "kg CO2/kWh",
+            ),
+        ];
+        let rows = days
+            .into_iter()
+            .map(|(day, generated, _, _)| ReportRow {
+                bucket_start: day,
+                values: vec![generated, generated * factor.kg_co2_per_kwh],
+            })
+            .collect();
+        Ok((summary, &["Generated (kWh)", "CO2 avoided (kg)"], rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime};
+
+    fn at(date: &str, time: &str) -> DateTime<Utc> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+            .and_utc()
+    }
+
+    #[test]
+    fn test_last_closed_periods() {
+        let utc = MarketTimezone::utc();
+        // Friday
+        let now = at("2026-10-16", "09:15");
+        assert_eq!(
+            ReportKind::DailyTradingSummary.last_period(now, &utc),
+            (at("2026-10-15", "00:00"), at("2026-10-16", "00:00"))
+        );
+        assert_eq!(
+            ReportKind::WeeklyGeneration.last_period(now, &utc),
+            (at("2026-10-05", "00:00"), at("2026-10-12", "00:00"))
+        );
+        assert_eq!(
+            ReportKind::MonthlyCarbon.last_period(now, &utc),
+            (at("2026-09-01", "00:00"), at("2026-10-01", "00:00"))
+        );
+        assert_eq!(
+            ReportKind::MonthlyCarbon.last_period(at("2026-01-10", "00:00"), &utc),
+            (at("2025-12-01", "00:00"), at("2026-01-01", "00:00"))
+        );
+    }
+
+    #[test]
+    fn test_next_run_follows_period_close() {
+        let utc = MarketTimezone::utc();
+        let now = at("2026-10-16", "09:15");
+        assert_eq!(ReportKind::DailyTradingSummary.next_run(now, &utc), at("2026-10-17", "00:30"));
+        assert_eq!(ReportKind::WeeklyGeneration.next_run(now, &utc), at("2026-10-19", "00:30"));
+        assert_eq!(ReportKind::MonthlyCarbon.next_run(now, &utc), at("2026-11-01", "00:30"));
+
+        // The run right after a close covers the period that just closed
+        let run = ReportKind::WeeklyGeneration.next_run(now, &utc);
+        assert_eq!(ReportKind::WeeklyGeneration.last_period(run, &utc).0, at("2026-10-12", "00:00"));
+        assert_eq!(
+            ReportKind::WeeklyGeneration.period_label(at("2026-10-12", "00:00"), at("2026-10-19", "00:00"), &utc),
+            "2026-10-12 - 2026-10-18"
+        );
+    }
+
+    #[test]
+    fn test_periods_follow_market_timezone() {
+        let bangkok = MarketTimezone::default();
+        // 18:00 UTC on 31 October is already 1 November in Bangkok
+        let now = at("2026-10-31", "18:00");
+        assert_eq!(
+            ReportKind::DailyTradingSummary.last_period(now, &bangkok),
+            (at("2026-10-30", "17:00"), at("2026-10-31", "17:00"))
+        );
+        assert_eq!(
+            ReportKind::MonthlyCarbon.last_period(now, &bangkok),
+            (at("2026-09-30", "17:00"), at("2026-10-31", "17:00"))
+        );
+        assert_eq!(ReportKind::MonthlyCarbon.next_run(now, &bangkok), at("2026-11-30", "17:30"));
+        assert_eq!(
+            ReportKind::MonthlyCarbon.period_label(at("2026-09-30", "17:00"), at("2026-10-31", "17:00"), &bangkok),
+            "2026-10"
+        );
+    }
+
+    #[test]
+    fn test_webhook_urls_must_be_public_https() {
+        assert!(validate_webhook_url("https://hooks.example.com/reports").is_ok());
+        assert!(validate_webhook_url("http://hooks.example.com/reports").is_err());
+        assert!(validate_webhook_url("https://localhost/reports").is_err());
+        assert!(validate_webhook_url("https://127.0.0.1/reports").is_err());
+        assert!(validate_webhook_url("https://10.1.2.3/reports").is_err());
+        assert!(validate_webhook_url("https://169.254.169.254/latest").is_err());
+        assert!(validate_webhook_url("https://[::1]/reports").is_err());
+        assert!(validate_webhook_url("not a url").is_err());
+    }
+}
@@ -0,0 +1,387 @@
+//! Developer Sandbox
+//!
+//! Faucet airdrops and funded test users for devnet and local validators.
+//! Every airdrop is written to a ledger, which is also what the per-wallet
+//! hourly limit counts. Nothing here runs unless the sandbox is enabled and
+//! the configured RPC endpoint is a test cluster.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use sqlx::PgPool;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::auth::password::PasswordService;
+use crate::config::{Config, SandboxConfig};
+use crate::error::{ApiError, Result};
+use crate::models::pii::UserPii;
+use crate::services::{BlockchainService, PiiCipher, WalletService};
+use crate::utils::decimal::{to_base_units, to_lamports};
+
+/// Verified meters registered per test user at most
+const MAX_TEST_METERS: u32 = 10;
+/// Roles a test user may be created with
+const TEST_USER_ROLES: &[&str] = &["user", "prosumer", "consumer", "corporate"];
+
+/// Test cluster the sandbox is serving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxCluster {
+    Devnet,
+    Localnet,
+}
+
+impl SandboxCluster {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SandboxCluster::Devnet => "devnet",
+            SandboxCluster::Localnet => "localnet",
+        }
+    }
+}
+
+/// Test cluster behind an RPC URL: loopback hosts and single-label hosts
+/// (docker compose service names) are localnet, hosts naming devnet are
+/// devnet. Anything else, mainnet and testnet included, is not a sandbox.
+pub fn detect_cluster(rpc_url: &str) -> Option<SandboxCluster> {
+    let url = reqwest::Url::parse(rpc_url).ok()?;
+    let host = url.host_str()?.trim_matches(|c| c == '[' || c == ']').to_ascii_lowercase();
+
+    if matches!(host.as_str(), "localhost" | "127.0.0.1" | "0.0.0.0" | "::1") || !host.contains('.') {
+        return Some(SandboxCluster::Localnet);
+    }
+    if host.split('.').any(|label| label == "devnet" || label.starts_with("devnet-") || label.ends_with("-devnet")) {
+        return Some(SandboxCluster::Devnet);
+    }
+    None
+}
+
+/// What to provision for a test user
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct TestUserSpec {
+    /// user, prosumer, consumer or corporate (default prosumer)
+    pub role: Option<String>,
+    /// SOL airdropped to the new wallet
+    pub airdrop_sol: Option<f64>,
+    /// Energy tokens (kWh) minted to the new wallet
+    #[schema(value_type = Option<String>, example = "100")]
+    pub mint_kwh: Option<Decimal>,
+    /// Fiat balance credited to the account
+    #[schema(value_type = Option<String>, example = "1000")]
+    pub fiat_balance: Option<Decimal>,
+    /// Verified meters to register (at most 10)
+    pub meters: Option<u32>,
+    /// Meter type for the registered meters (default solar)
+    pub meter_type: Option<String>,
+    pub zone_id: Option<i32>,
+}
+
+/// A provisioned test user and everything created for it
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TestUser {
+    pub user_id: Uuid,
+    pub username: String,
+    pub email: String,
+    /// Generated password, for logging in as the user
+    pub password: String,
+    pub role: String,
+    pub wallet_address: String,
+    pub meter_ids: Vec<Uuid>,
+    pub meter_serials: Vec<String>,
+    pub airdrop_signature: Option<String>,
+    pub mint_signature: Option<String>,
+    /// Funding steps that failed
+    pub warnings: Vec<String>,
+    /// Bearer token for the user, set by the sandbox handlers
+    pub access_token: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SandboxService {
+    db: PgPool,
+    blockchain: BlockchainService,
+    wallet: WalletService,
+    config: Config,
+    pii: PiiCipher,
+}
+
+impl SandboxService {
+    pub fn new(
+        db: PgPool,
+        blockchain: BlockchainService,
+        wallet: WalletService,
+        config: Config,
+        pii: PiiCipher,
+    ) -> Self {
+        Self {
+            db,
+            blockchain,
+            wallet,
+            config,
+            pii,
+        }
+    }
+
+    fn settings(&self) -> &SandboxConfig {
+        &self.config.sandbox
+    }
+
+    /// The test cluster being served, or Forbidden when the sandbox is off
+    /// or pointed at a real cluster
+    pub fn cluster(&self) -> Result<SandboxCluster> {
+        if !self.settings().enabled {
+            return Err(ApiError::Forbidden("Sandbox is disabled".to_string()));
+        }
+        detect_cluster(&self.config.solana_rpc_url).ok_or_else(|| {
+            ApiError::Forbidden("Sandbox is only available on devnet or localnet".to_string())
+        })
+    }
+
+    pub fn max_scenario_steps(&self) -> usize {
+        self.settings().max_scenario_steps
+    }
+
+    fn check_airdrop_amount(&self, amount_sol: f64) -> Result<Decimal> {
+        let max = self.settings().max_airdrop_sol;
+        if !amount_sol.is_finite() || amount_sol <= 0.0 || amount_sol > max {
+            return Err(ApiError::validation_field(
+                "amount_sol",
+                format!("Airdrops must be more than 0 and at most {} SOL", max),
+            ));
+        }
+        let amount = Decimal::try_from(amount_sol)
+            .map_err(|_| ApiError::validation_field("amount_sol", "Invalid amount"))?;
+        to_lamports(amount).map_err(|e| ApiError::validation_field("amount_sol", e.to_string()))?;
+        Ok(amount)
+    }
+
+    /// Airdrop SOL to a wallet, within the per-wallet hourly limit
+    pub async fn airdrop(&self, wallet: &Pubkey, amount_sol: f64) -> Result<String> {
+        let cluster = self.cluster()?;
+        let amount = self.check_airdrop_amount(amount_sol)?;
+
+        let wallet_address = wallet.to_string();
+        let recent: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM sandbox_airdrops WHERE wallet_address = $1 AND created_at > NOW() - INTERVAL '1 hour'",
+        )
+        .bind(&wallet_address)
+        .fetch_one(&self.db)
+        .await?;
+        if recent >= self.settings().airdrops_per_hour {
+            return Err(ApiError::RateLimitExceeded(format!(
+                "At most {} airdrops per wallet per hour",
+                self.settings().airdrops_per_hour
+            )));
+        }
+
+        let result = self
+            .wallet
+            .request_airdrop(wallet, amount_sol)
+            .await
+            .map(|sig| sig.to_string());
+        sqlx::query(
+            "INSERT INTO sandbox_airdrops (wallet_address, amount_sol, cluster, tx_signature, error) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&wallet_address)
+        .bind(amount)
+        .bind(cluster.as_str())
+        .bind(result.as_ref().ok())
+        .bind(result.as_ref().err().map(|e| e.to_string()))
+        .execute(&self.db)
+        .await?;
+
+        let signature = result.map_err(|e| ApiError::Internal(format!("Failed to airdrop SOL: {}", e)))?;
+        info!("🚰 Sandbox airdrop of {} SOL to {} ({})", amount_sol, wallet_address, cluster.as_str());
+        Ok(signature)
+    }
+
+    /// Mint energy tokens (kWh) straight to a wallet
+    pub async fn mint_kwh(&self, wallet: &Pubkey, kwh: Decimal) -> Result<String> {
+        self.cluster()?;
+        if kwh <= Decimal::ZERO {
+            return Err(ApiError::validation_field("mint_kwh", "Must be positive"));
+        }
+        let amount = to_base_units(kwh, 9).map_err(|e| ApiError::validation_field("mint_kwh", e.to_string()))?;
+        let signature = self
+            .blockchain
+            .mint_tokens_direct(wallet, amount)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to mint tokens: {}", e)))?;
+        Ok(signature.to_string())
+    }
+
+    /// Create a verified user with a custodial wallet, fund it and register
+    /// verified meters, all in one call
+    pub async fn create_test_user(&self, spec: &TestUserSpec) -> Result<TestUser> {
+        self.cluster()?;
+        let role = spec.role.clone().unwrap_or_else(|| "prosumer".to_string());
+        if !TEST_USER_ROLES.contains(&role.as_str()) {
+            return Err(ApiError::validation_field(
+                "role",
+                format!("Must be one of {}", TEST_USER_ROLES.join(", ")),
+            ));
+        }
+        let meter_count = spec.meters.unwrap_or(0);
+        if meter_count > MAX_TEST_METERS {
+            return Err(ApiError::validation_field(
+                "meters",
+                format!("At most {} meters per test user", MAX_TEST_METERS),
+            ));
+        }
+        if spec.fiat_balance.is_some_and(|b| b < Decimal::ZERO) {
+            return Err(ApiError::validation_field("fiat_balance", "Must not be negative"));
+        }
+        if let Some(sol) = spec.airdrop_sol {
+            self.check_airdrop_amount(sol)?;
+        }
+        if spec.mint_kwh.is_some_and(|kwh| kwh <= Decimal::ZERO) {
+            return Err(ApiError::validation_field("mint_kwh", "Must be positive"));
+        }
+
+        let user_id = Uuid::new_v4();
+        let tag = user_id.simple().to_string()[..12].to_string();
+        let username = format!("sandbox_{}", tag);
+        let email = format!("{}@sandbox.gridtokenx.local", username);
+        let password = Uuid::new_v4().simple().to_string();
+        let password_hash = PasswordService::hash_password(&password)?;
+
+        let keypair = WalletService::create_keypair();
+        let wallet = keypair.pubkey();
+        let (encrypted_key, salt, iv) =
+            crate::utils::crypto::encrypt_to_bytes(&keypair.to_bytes(), &self.config.encryption_secret)
+                .map_err(|e| ApiError::Internal(format!("Failed to encrypt wallet key: {}", e)))?;
+
+        let sealed = UserPii {
+            email: email.clone(),
+            first_name: Some("Sandbox".to_string()),
+            last_name: Some(tag.clone()),
+        }
+        .seal(&self.pii)
+        .await?;
+        let sealed_address = self.pii.seal("Sandbox").await?;
+
+        let meter_type = spec.meter_type.clone().unwrap_or_else(|| "solar".to_string());
+        let mut meter_ids = Vec::with_capacity(meter_count as usize);
+        let mut meter_serials = Vec::with_capacity(meter_count as usize);
+
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            "INSERT INTO users (
+                id, username, email_encrypted, email_digest, password_hash, role,
+                first_name_encrypted, last_name_encrypted, pii_key_id,
+                is_active, email_verified, blockchain_registered,
+                wallet_address, encrypted_private_key, wallet_salt, encryption_iv,
+                balance, created_at, updated_at
+            )
+             VALUES ($1, $2, $3, $4, $5, $6::text::user_role, $7, $8, $9, true, true, false, $10, $11, $12, $13, $14, NOW(), NOW())",
+        )
+        .bind(user_id)
+        .bind(&username)
+        .bind(&sealed.email_encrypted)
+        .bind(&sealed.email_digest)
+        .bind(&password_hash)
+        .bind(&role)
+        .bind(&sealed.first_name_encrypted)
+        .bind(&sealed.last_name_encrypted)
+        .bind(sealed.key_id)
+        .bind(wallet.to_string())
+        .bind(&encrypted_key[..])
+        .bind(&salt[..])
+        .bind(&iv[..])
+        .bind(spec.fiat_balance.unwrap_or(Decimal::ZERO))
+        .execute(&mut *tx)
+        .await?;
+
+        for n in 1..=meter_count {
+            let meter_id = Uuid::new_v4();
+            let serial = format!("SBX-{}-{:02}", tag.to_uppercase(), n);
+            sqlx::query(
+                "INSERT INTO meters (id, user_id, serial_number, meter_type, location, zone_id, is_verified, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, 'Sandbox', $5, true, NOW(), NOW())",
+            )
+            .bind(meter_id)
+            .bind(user_id)
+            .bind(&serial)
+            .bind(&meter_type)
+            .bind(spec.zone_id)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query(
+                "INSERT INTO meter_registry (id, user_id, meter_serial, meter_type, location_address_encrypted, pii_key_id, meter_key_hash, verification_method, verification_status, zone_id)
+                 VALUES ($1, $2, $3, $4, $5, $6, 'sandbox_hash', 'auto', 'verified', $7)",
+            )
+            .bind(meter_id)
+            .bind(user_id)
+            .bind(&serial)
+            .bind(&meter_type)
+            .bind(&sealed_address)
+            .bind(sealed.key_id)
+            .bind(spec.zone_id)
+            .execute(&mut *tx)
+            .await?;
+            meter_ids.push(meter_id);
+            meter_serials.push(serial);
+        }
+        tx.commit().await?;
+
+        info!("🧪 Created sandbox user {} ({}) with {} meters", username, wallet, meter_count);
+
+        // On-chain funding happens after the account exists; a failed airdrop
+        // or mint is reported on the user rather than failing the call
+        let mut warnings = Vec::new();
+        let airdrop_signature = match spec.airdrop_sol {
+            Some(sol) => self
+                .airdrop(&wallet, sol)
+                .await
+                .map_err(|e| warnings.push(format!("airdrop: {}", e)))
+                .ok(),
+            None => None,
+        };
+        let mint_signature = match spec.mint_kwh {
+            Some(kwh) => self
+                .mint_kwh(&wallet, kwh)
+                .await
+                .map_err(|e| warnings.push(format!("mint: {}", e)))
+                .ok(),
+            None => None,
+        };
+        if !warnings.is_empty() {
+            warn!("Sandbox user {} partially funded: {}", username, warnings.join("; "));
+        }
+
+        Ok(TestUser {
+            user_id,
+            username,
+            email,
+            password,
+            role,
+            wallet_address: wallet.to_string(),
+            meter_ids,
+            meter_serials,
+            airdrop_signature,
+            mint_signature,
+            warnings,
+            access_token: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_test_clusters_only() {
+        assert_eq!(detect_cluster("http://localhost:8899"), Some(SandboxCluster::Localnet));
+        assert_eq!(detect_cluster("http://127.0.0.1:8899"), Some(SandboxCluster::Localnet));
+        assert_eq!(detect_cluster("http://[::1]:8899"), Some(SandboxCluster::Localnet));
+        assert_eq!(detect_cluster("http://solana-validator:8899"), Some(SandboxCluster::Localnet));
+        assert_eq!(detect_cluster("https://api.devnet.solana.com"), Some(SandboxCluster::Devnet));
+        assert_eq!(detect_cluster("https://api.mainnet-beta.solana.com"), None);
+        assert_eq!(detect_cluster("https://api.testnet.solana.com"), None);
+        assert_eq!(detect_cluster("not a url"), None);
+    }
+}
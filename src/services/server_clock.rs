@@ -0,0 +1,69 @@
+//! Server Clock
+//!
+//! Server time for trading clients that sign orders and compute expiries,
+//! served at `GET /api/time` and in WebSocket heartbeats and pongs.
+//!
+//! Every reading carries a `sequence` that increases by one per reading
+//! within a process, and `monotonic_ms` since the process started, which
+//! never goes backwards when the wall clock is stepped. A sequence lower
+//! than one seen before means the client reached another instance or a
+//! restarted one, and earlier samples should be discarded.
+//!
+//! To measure latency and clock offset, a client notes its time `t0` when
+//! sending a request (or a WebSocket `ping` with `client_time = t0`) and
+//! `t1` when the answer arrives. Then `rtt = t1 - t0` and the offset to add
+//! to the local clock is `unix_ms - (t0 + rtt / 2)`; the error is at most
+//! `rtt / 2`, so keep the sample with the smallest round trip.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// How often WebSocket connections receive a heartbeat
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+static STARTED: Lazy<Instant> = Lazy::new(Instant::now);
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// One reading of the server clock
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub struct ServerTime {
+    pub server_time: DateTime<Utc>,
+    /// `server_time` as Unix milliseconds
+    pub unix_ms: i64,
+    /// Milliseconds since the process started, unaffected by clock steps
+    pub monotonic_ms: u64,
+    /// Increases by one with every reading of this process
+    pub sequence: u64,
+}
+
+/// Read the server clock
+pub fn now() -> ServerTime {
+    let monotonic_ms = STARTED.elapsed().as_millis() as u64;
+    let sequence = SEQUENCE.fetch_add(1, Ordering::SeqCst) + 1;
+    let server_time = Utc::now();
+    ServerTime {
+        server_time,
+        unix_ms: server_time.timestamp_millis(),
+        monotonic_ms,
+        sequence,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readings_are_sequenced_and_monotonic() {
+        let first = now();
+        let second = now();
+        assert!(second.sequence > first.sequence);
+        assert!(second.monotonic_ms >= first.monotonic_ms);
+        assert_eq!(first.unix_ms, first.server_time.timestamp_millis());
+    }
+}
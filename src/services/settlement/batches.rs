@@ -0,0 +1,144 @@
+//! Pending settlement batches
+//!
+//! Clearing an epoch enqueues its settlements as one batch ahead of the
+//! pending backlog. Until the settlement loop takes the queue, the batch can
+//! be cancelled, returning its settlements to the pool, and single
+//! settlements can be ejected from it with a new priority. The queue lock is
+//! held across each change so a batch is never half-submitted.
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::SettlementService;
+use crate::error::ApiError;
+
+/// Where an ejected settlement goes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RequeuePriority {
+    /// Back to the pool, settled with the backlog in creation order
+    #[default]
+    Pool,
+    /// Settled first in the next pass, ahead of every batch. Cancelling its
+    /// epoch's batch still returns it to the pool.
+    Next,
+}
+
+/// Settlements moved out of a pending batch
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BatchChange {
+    pub epoch_id: Uuid,
+    pub settlement_ids: Vec<Uuid>,
+    /// Settlements of the epoch still in the batch
+    pub remaining: usize,
+}
+
+/// Drop `members` from the queue, returning the ones that were queued
+fn remove_from_queue(queue: &mut Vec<Uuid>, members: &[Uuid]) -> Vec<Uuid> {
+    let removed: Vec<Uuid> = queue.iter().filter(|id| members.contains(id)).copied().collect();
+    queue.retain(|id| !members.contains(id));
+    removed
+}
+
+/// Put an ejected settlement back according to its new priority
+fn requeue(queue: &mut Vec<Uuid>, settlement_id: Uuid, priority: RequeuePriority) {
+    queue.retain(|id| *id != settlement_id);
+    if priority == RequeuePriority::Next {
+        queue.insert(0, settlement_id);
+    }
+}
+
+impl SettlementService {
+    /// Queued settlements of an epoch that are still pending
+    async fn batch_members(&self, epoch_id: Uuid, queued: &[Uuid]) -> Result<Vec<Uuid>, ApiError> {
+        if queued.is_empty() {
+            return Ok(Vec::new());
+        }
+        sqlx::query_scalar(
+            "SELECT id FROM settlements WHERE id = ANY($1) AND epoch_id = $2 AND status = 'pending'",
+        )
+        .bind(queued)
+        .bind(epoch_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+
+    /// Cancel an epoch's pending batch. Its settlements stay pending and are
+    /// settled with the backlog.
+    pub async fn cancel_batch(&self, epoch_id: Uuid) -> Result<BatchChange, ApiError> {
+        let mut queue = self.pending_settlements.write().await;
+        let members = self.batch_members(epoch_id, &queue).await?;
+        if members.is_empty() {
+            return Err(ApiError::NotFound(format!(
+                "No pending batch for epoch {}; it may already have been submitted",
+                epoch_id
+            )));
+        }
+
+        let settlement_ids = remove_from_queue(&mut queue, &members);
+        info!("🧺 Cancelled batch of epoch {}: {} settlements back to the pool", epoch_id, settlement_ids.len());
+        Ok(BatchChange {
+            epoch_id,
+            settlement_ids,
+            remaining: 0,
+        })
+    }
+
+    /// Eject one settlement from an epoch's pending batch with a new priority
+    pub async fn eject_from_batch(
+        &self,
+        epoch_id: Uuid,
+        settlement_id: Uuid,
+        priority: RequeuePriority,
+    ) -> Result<BatchChange, ApiError> {
+        let mut queue = self.pending_settlements.write().await;
+        let members = self.batch_members(epoch_id, &queue).await?;
+        if !members.contains(&settlement_id) {
+            return Err(ApiError::NotFound(format!(
+                "Settlement {} is not in the pending batch of epoch {}",
+                settlement_id, epoch_id
+            )));
+        }
+
+        requeue(&mut queue, settlement_id, priority);
+        if priority == RequeuePriority::Next {
+            self.work_ready.notify_one();
+        }
+        info!("🧺 Ejected settlement {} from batch of epoch {} ({:?})", settlement_id, epoch_id, priority);
+        Ok(BatchChange {
+            epoch_id,
+            settlement_ids: vec![settlement_id],
+            remaining: members.len() - 1,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_from_queue() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut queue = vec![a, b, c];
+
+        let removed = remove_from_queue(&mut queue, &[c, a, Uuid::new_v4()]);
+        assert_eq!(removed, vec![a, c]);
+        assert_eq!(queue, vec![b]);
+    }
+
+    #[test]
+    fn test_requeue() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let mut queue = vec![a, b, c];
+        requeue(&mut queue, c, RequeuePriority::Next);
+        assert_eq!(queue, vec![c, a, b]);
+
+        requeue(&mut queue, a, RequeuePriority::Pool);
+        assert_eq!(queue, vec![c, b]);
+    }
+}
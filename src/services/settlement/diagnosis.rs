@@ -0,0 +1,313 @@
+//! Failure isolation for settlement batches
+//!
+//! A failed batch only says that something in it went wrong. After each
+//! pass, every settlement that failed is diagnosed on its own: its transfer
+//! inputs are checked and the transfer is simulated alone. Settlements that
+//! fail again are quarantined with the diagnosis and skipped by automatic
+//! retries; the rest failed for transient reasons and are resubmitted in
+//! the next batch.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use sqlx::FromRow;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::SettlementService;
+use crate::error::ApiError;
+use crate::middleware::metrics::track_settlement_diagnosis;
+use crate::services::BlockchainService;
+use crate::utils::decimal::to_lamports;
+
+/// Outcome of diagnosing one failed settlement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnosis {
+    /// The settlement itself fails and would fail again: quarantine it
+    Offending(String),
+    /// The settlement is sound on its own: resubmit it in the next batch
+    Transient,
+    /// No verdict (RPC unavailable, mock mode): leave it to the retry job
+    Inconclusive(String),
+}
+
+impl Diagnosis {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Diagnosis::Offending(_) => "quarantined",
+            Diagnosis::Transient => "resubmitted",
+            Diagnosis::Inconclusive(_) => "inconclusive",
+        }
+    }
+}
+
+/// Verdict from the transfer simulation: an error from the program means the
+/// settlement is at fault, a clean run means the batch failure was transient
+pub fn classify_simulation(simulated: std::result::Result<Option<String>, String>) -> Diagnosis {
+    match simulated {
+        Ok(None) => Diagnosis::Transient,
+        Ok(Some(error)) => Diagnosis::Offending(format!("Transfer simulation failed: {}", error)),
+        Err(rpc) => Diagnosis::Inconclusive(format!("Simulation unavailable: {}", rpc)),
+    }
+}
+
+/// Whether a failed input check is a property of the settlement (missing or
+/// mismatched wallet, bad amount) rather than a database hiccup
+pub fn is_conclusive_input_error(error: &ApiError) -> bool {
+    match error {
+        ApiError::Database(e) => matches!(e, sqlx::Error::RowNotFound),
+        _ => true,
+    }
+}
+
+/// Failed settlement held back from retries
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct QuarantinedSettlement {
+    pub id: Uuid,
+    pub buyer_id: Uuid,
+    pub seller_id: Uuid,
+    #[schema(value_type = String)]
+    pub energy_amount: Decimal,
+    pub error_message: Option<String>,
+    pub diagnosis: Option<String>,
+    pub retry_count: Option<i32>,
+    pub quarantined_at: Option<DateTime<Utc>>,
+}
+
+/// Accounts and amount of a settlement transfer
+struct TransferInputs {
+    owner: Pubkey,
+    from: Pubkey,
+    to: Pubkey,
+    mint: Pubkey,
+    amount: u64,
+}
+
+impl SettlementService {
+    /// Diagnose the settlements that failed in a batch: quarantine those at
+    /// fault and send the rest back to pending for the next batch
+    pub(super) async fn isolate_batch_failures(&self, failed: &[Uuid]) {
+        if failed.is_empty() {
+            return;
+        }
+
+        let mut resubmit = Vec::new();
+        let mut quarantined = 0;
+        for &settlement_id in failed {
+            let diagnosis = self.diagnose_settlement(settlement_id).await;
+            track_settlement_diagnosis(diagnosis.label());
+
+            let recorded = match &diagnosis {
+                Diagnosis::Offending(reason) => {
+                    warn!("🧪 Settlement {} quarantined: {}", settlement_id, reason);
+                    quarantined += 1;
+                    self.quarantine_settlement(settlement_id, reason).await
+                }
+                Diagnosis::Transient => self
+                    .resubmit_failed_settlement(settlement_id)
+                    .await
+                    .map(|requeued| {
+                        if requeued {
+                            resubmit.push(settlement_id);
+                        }
+                    }),
+                Diagnosis::Inconclusive(reason) => {
+                    info!("Settlement {} left for retry: {}", settlement_id, reason);
+                    Ok(())
+                }
+            };
+            if let Err(e) = recorded {
+                warn!("Failed to record diagnosis of settlement {}: {}", settlement_id, e);
+            }
+        }
+
+        info!(
+            "🧪 Batch failure isolation: {} failed, {} quarantined, {} resubmitted",
+            failed.len(),
+            quarantined,
+            resubmit.len()
+        );
+        self.enqueue_settlements(&resubmit).await;
+    }
+
+    /// Check a failed settlement's transfer inputs and simulate the transfer alone
+    pub async fn diagnose_settlement(&self, settlement_id: Uuid) -> Diagnosis {
+        if !self.config.enable_real_blockchain {
+            return Diagnosis::Inconclusive("Mock blockchain mode".to_string());
+        }
+
+        let inputs = match self.transfer_inputs(settlement_id).await {
+            Ok(inputs) => inputs,
+            Err(e) if is_conclusive_input_error(&e) => {
+                return Diagnosis::Offending(format!("Invalid transfer inputs: {}", e));
+            }
+            Err(e) => return Diagnosis::Inconclusive(e.to_string()),
+        };
+
+        let simulated = self
+            .blockchain
+            .simulate_token_transfer(&inputs.owner, &inputs.from, &inputs.to, &inputs.mint, inputs.amount, 9)
+            .await
+            .map_err(|e| e.to_string());
+        classify_simulation(simulated)
+    }
+
+    /// Resolve the transfer exactly as `execute_blockchain_transfer` does,
+    /// without creating token accounts or signing anything
+    async fn transfer_inputs(&self, settlement_id: Uuid) -> Result<TransferInputs, ApiError> {
+        let settlement = self.get_settlement(settlement_id).await?;
+
+        let buyer_wallet = self.get_user_wallet(&settlement.buyer_id).await?;
+        let seller_wallet = self.get_user_wallet(&settlement.seller_id).await?;
+        let buyer = BlockchainService::parse_pubkey(&buyer_wallet)
+            .map_err(|e| ApiError::Internal(format!("Invalid buyer wallet: {}", e)))?;
+
+        let mint_str = std::env::var("ENERGY_TOKEN_MINT")
+            .map_err(|e| ApiError::Internal(format!("ENERGY_TOKEN_MINT not set: {}", e)))?;
+        let mint = BlockchainService::parse_pubkey(&mint_str)
+            .map_err(|e| ApiError::Internal(format!("Invalid mint config: {}", e)))?;
+
+        let seller = self
+            .get_user_keypair(&settlement.seller_id, settlement.seller_session_token.as_deref())
+            .await?
+            .pubkey();
+        if seller.to_string() != seller_wallet {
+            return Err(ApiError::Internal(format!(
+                "Wallet identity mismatch: DB={} Decrypted={}",
+                seller_wallet, seller
+            )));
+        }
+
+        let effective_energy = settlement.effective_energy.unwrap_or(settlement.energy_amount);
+        if effective_energy <= Decimal::ZERO {
+            return Err(ApiError::Internal(format!("Non-positive transfer amount {}", effective_energy)));
+        }
+        let amount = to_lamports(effective_energy)
+            .map_err(|e| ApiError::Internal(format!("Invalid transfer amount: {}", e)))?;
+
+        let ata = |wallet: &Pubkey| {
+            self.blockchain
+                .calculate_ata_address(wallet, &mint)
+                .map_err(|e| ApiError::Internal(format!("Failed to derive token account: {}", e)))
+        };
+        Ok(TransferInputs {
+            owner: seller,
+            from: ata(&seller)?,
+            to: ata(&buyer)?,
+            mint,
+            amount,
+        })
+    }
+
+    /// Keep a failed settlement out of automatic retries, with its diagnosis
+    async fn quarantine_settlement(&self, settlement_id: Uuid, diagnosis: &str) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            UPDATE settlements
+            SET quarantined_at = NOW(), diagnosis = $2, updated_at = NOW()
+            WHERE id = $1 AND status = 'failed'
+            "#,
+        )
+        .bind(settlement_id)
+        .bind(diagnosis)
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+        Ok(())
+    }
+
+    /// Move a failed settlement back to pending, counting the attempt.
+    /// Returns false once it has used up its retries.
+    async fn resubmit_failed_settlement(&self, settlement_id: Uuid) -> Result<bool, ApiError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE settlements
+            SET status = 'pending',
+                retry_count = COALESCE(retry_count, 0) + 1,
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'failed' AND quarantined_at IS NULL
+              AND COALESCE(retry_count, 0) < $2
+            "#,
+        )
+        .bind(settlement_id)
+        .bind(self.config.retry_attempts as i32)
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Release a quarantined settlement back to pending once its cause is
+    /// fixed, and put it in the next batch
+    pub async fn release_quarantined_settlement(&self, settlement_id: Uuid) -> Result<(), ApiError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE settlements
+            SET status = 'pending', quarantined_at = NULL, retry_count = 0, updated_at = NOW()
+            WHERE id = $1 AND status = 'failed' AND quarantined_at IS NOT NULL
+            "#,
+        )
+        .bind(settlement_id)
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+        if result.rows_affected() == 0 {
+            return Err(ApiError::NotFound("Quarantined settlement not found".to_string()));
+        }
+
+        info!("Settlement {} released from quarantine", settlement_id);
+        self.enqueue_settlements(&[settlement_id]).await;
+        Ok(())
+    }
+
+    /// Quarantined settlements, most recent first
+    pub async fn list_quarantined_settlements(&self) -> Result<Vec<QuarantinedSettlement>, ApiError> {
+        sqlx::query_as::<_, QuarantinedSettlement>(
+            r#"
+            SELECT id, buyer_id, seller_id, energy_amount, error_message, diagnosis, retry_count, quarantined_at
+            FROM settlements
+            WHERE quarantined_at IS NOT NULL AND status = 'failed'
+            ORDER BY quarantined_at DESC
+            LIMIT 200
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_simulation() {
+        assert_eq!(classify_simulation(Ok(None)), Diagnosis::Transient);
+        assert!(matches!(
+            classify_simulation(Ok(Some("InstructionError(0, Custom(1))".to_string()))),
+            Diagnosis::Offending(reason) if reason.contains("Custom(1)")
+        ));
+        assert!(matches!(
+            classify_simulation(Err("connection refused".to_string())),
+            Diagnosis::Inconclusive(_)
+        ));
+    }
+
+    #[test]
+    fn test_input_errors() {
+        assert!(is_conclusive_input_error(&ApiError::Internal("Wallet identity mismatch".to_string())));
+        assert!(is_conclusive_input_error(&ApiError::Database(sqlx::Error::RowNotFound)));
+        assert!(!is_conclusive_input_error(&ApiError::Database(sqlx::Error::PoolTimedOut)));
+    }
+
+    #[test]
+    fn test_diagnosis_labels() {
+        assert_eq!(Diagnosis::Offending(String::new()).label(), "quarantined");
+        assert_eq!(Diagnosis::Transient.label(), "resubmitted");
+        assert_eq!(Diagnosis::Inconclusive(String::new()).label(), "inconclusive");
+    }
+}
@@ -0,0 +1,218 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use sqlx::Row;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use super::SettlementService;
+use crate::error::ApiError;
+use crate::services::blockchain::BlockchainUtils;
+use crate::services::BlockchainService;
+
+/// How the next settlement fee payer is chosen from the pool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FeePayerSelection {
+    /// Each payer in turn
+    #[default]
+    RoundRobin,
+    /// The payer holding the most SOL
+    Balance,
+}
+
+impl FeePayerSelection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RoundRobin => "round_robin",
+            Self::Balance => "balance",
+        }
+    }
+}
+
+impl FromStr for FeePayerSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "round_robin" => Ok(Self::RoundRobin),
+            "balance" => Ok(Self::Balance),
+            other => Err(format!("Unknown fee payer selection: {}", other)),
+        }
+    }
+}
+
+/// Index of the payer with the largest known balance
+fn richest(balances: &[Option<u64>]) -> Option<usize> {
+    balances
+        .iter()
+        .enumerate()
+        .filter_map(|(i, balance)| balance.map(|b| (i, b)))
+        .max_by_key(|&(i, balance)| (balance, std::cmp::Reverse(i)))
+        .map(|(i, _)| i)
+}
+
+/// Keypairs that take turns paying settlement transaction fees, so no single
+/// hot key carries all settlement traffic
+#[derive(Clone)]
+pub struct FeePayerPool {
+    payers: Arc<Vec<Keypair>>,
+    selection: FeePayerSelection,
+    next: Arc<AtomicUsize>,
+}
+
+impl FeePayerPool {
+    /// Load the pool from keypair files; `None` when no paths are configured
+    pub fn load(paths: &[String], selection: FeePayerSelection) -> Result<Option<Self>> {
+        if paths.is_empty() {
+            return Ok(None);
+        }
+        let payers = paths
+            .iter()
+            .map(|path| {
+                BlockchainUtils::load_keypair_from_file(path)
+                    .map_err(|e| anyhow!("Failed to load fee payer {}: {}", path, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Self {
+            payers: Arc::new(payers),
+            selection,
+            next: Arc::new(AtomicUsize::new(0)),
+        }))
+    }
+
+    pub fn selection(&self) -> FeePayerSelection {
+        self.selection
+    }
+
+    pub fn pubkeys(&self) -> Vec<Pubkey> {
+        self.payers.iter().map(|payer| payer.pubkey()).collect()
+    }
+
+    /// Payer for the next settlement. Balance-aware selection falls back to
+    /// round-robin when no balance can be read.
+    pub async fn select(&self, blockchain: &BlockchainService) -> &Keypair {
+        if self.selection == FeePayerSelection::Balance {
+            let mut balances = Vec::with_capacity(self.payers.len());
+            for payer in self.payers.iter() {
+                balances.push(blockchain.get_balance(&payer.pubkey()).await.ok());
+            }
+            if let Some(index) = richest(&balances) {
+                return &self.payers[index];
+            }
+            warn!("⚠️ No fee payer balance readable, falling back to round-robin");
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.payers.len();
+        &self.payers[index]
+    }
+}
+
+/// Settlement fees paid by one fee payer
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FeePayerSpend {
+    pub payer: String,
+    /// Current SOL balance in lamports; none when it could not be read
+    pub balance_lamports: Option<u64>,
+    /// Whether the payer is in the configured pool; removed payers keep their history
+    pub in_pool: bool,
+    pub settlements: i64,
+    pub spent_lamports: i64,
+    pub spent_lamports_24h: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl SettlementService {
+    /// Fees paid per settlement fee payer, pool members first
+    pub async fn fee_payer_spend(&self) -> Result<Vec<FeePayerSpend>, ApiError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT fee_payer,
+                   COUNT(*) AS settlements,
+                   COALESCE(SUM(fee_lamports), 0)::bigint AS spent_lamports,
+                   COALESCE(SUM(fee_lamports) FILTER (WHERE processed_at > NOW() - INTERVAL '24 hours'), 0)::bigint
+                       AS spent_lamports_24h,
+                   MAX(processed_at) AS last_used_at
+            FROM settlements
+            WHERE fee_payer IS NOT NULL
+            GROUP BY fee_payer
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let pool: Vec<String> = self
+            .fee_payers
+            .as_ref()
+            .map(|pool| pool.pubkeys().iter().map(Pubkey::to_string).collect())
+            .unwrap_or_default();
+
+        let mut spend: Vec<FeePayerSpend> = pool
+            .iter()
+            .map(|payer| FeePayerSpend {
+                payer: payer.clone(),
+                balance_lamports: None,
+                in_pool: true,
+                settlements: 0,
+                spent_lamports: 0,
+                spent_lamports_24h: 0,
+                last_used_at: None,
+            })
+            .collect();
+        for row in rows {
+            let payer: String = row.get("fee_payer");
+            let index = match spend.iter().position(|s| s.payer == payer) {
+                Some(index) => index,
+                None => {
+                    spend.push(FeePayerSpend {
+                        payer,
+                        balance_lamports: None,
+                        in_pool: false,
+                        settlements: 0,
+                        spent_lamports: 0,
+                        spent_lamports_24h: 0,
+                        last_used_at: None,
+                    });
+                    spend.len() - 1
+                }
+            };
+            let entry = &mut spend[index];
+            entry.settlements = row.get("settlements");
+            entry.spent_lamports = row.get("spent_lamports");
+            entry.spent_lamports_24h = row.get("spent_lamports_24h");
+            entry.last_used_at = row.get("last_used_at");
+        }
+
+        for entry in &mut spend {
+            if let Ok(pubkey) = Pubkey::from_str(&entry.payer) {
+                entry.balance_lamports = self.blockchain.get_balance(&pubkey).await.ok();
+            }
+        }
+        Ok(spend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_richest_payer_skips_unreadable_balances() {
+        assert_eq!(richest(&[Some(5), None, Some(9), Some(9)]), Some(2));
+        assert_eq!(richest(&[None, Some(1)]), Some(1));
+        assert_eq!(richest(&[None, None]), None);
+    }
+
+    #[test]
+    fn test_fee_payer_selection_parsing() {
+        assert_eq!("balance".parse(), Ok(FeePayerSelection::Balance));
+        assert_eq!("round_robin".parse(), Ok(FeePayerSelection::RoundRobin));
+        assert!("random".parse::<FeePayerSelection>().is_err());
+    }
+}
@@ -1,3 +1,8 @@
+pub mod batches;
+pub mod diagnosis;
+pub mod fee_payers;
+pub mod scheduler;
+pub mod stats;
 pub mod types;
 
 use anyhow::Result;
@@ -6,20 +11,122 @@ use rust_decimal::Decimal;
 use sqlx::PgPool;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::Notify;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::error::ApiError;
 use crate::services::market_clearing::TradeMatch;
+use crate::services::blockchain::{commitment_str, TxOperation};
 use crate::services::BlockchainService;
+use crate::services::dispute;
+use crate::services::payments;
+use crate::services::AuditLogger;
+use crate::services::FxRateService;
+use crate::services::PiiCipher;
+use crate::utils::decimal::{round_to_token, to_lamports};
 use crate::services::erc::{ErcService, IssueErcRequest};
 use crate::services::notification::{NotificationService, SettlementNotification};
 use crate::handlers::websocket::broadcaster::broadcast_settlement_complete;
-use solana_sdk::signature::Signer;
-
+use crate::middleware::metrics::{
+    set_settlements_stuck, track_settlement_confirmation, track_settlement_fee, track_settlement_recovery,
+};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use std::str::FromStr;
+
+pub use batches::{BatchChange, RequeuePriority};
+pub use diagnosis::{Diagnosis, QuarantinedSettlement};
+pub use fee_payers::{FeePayerPool, FeePayerSelection, FeePayerSpend};
+pub use stats::SettlementBatchStatistics;
 pub use types::*;
 
+/// Order pending settlements so enqueued ones run first. Enqueued IDs that
+/// are no longer pending are dropped.
+fn prioritize_queued(pending: Vec<Uuid>, queued: &[Uuid]) -> Vec<Uuid> {
+    let (mut first, rest): (Vec<Uuid>, Vec<Uuid>) =
+        pending.into_iter().partition(|id| queued.contains(id));
+    first.sort_by_key(|id| queued.iter().position(|q| q == id));
+    first.extend(rest);
+    first
+}
+
+/// What the watchdog does with a stuck settlement
+#[derive(Debug, PartialEq, Eq)]
+enum StuckAction {
+    /// The transfer landed: complete the settlement
+    Resume,
+    /// Back to pending for another attempt, with the reason
+    Requeue(&'static str),
+    /// Out of retries: mark permanently failed, with the reason
+    Fail(&'static str),
+}
+
+/// Decide from the transfer's on-chain status (`None` when no usable
+/// signature was recorded) and the retries already used
+fn stuck_action(on_chain: Option<Option<bool>>, retry_count: i32, retry_attempts: u32) -> StuckAction {
+    let reason = match on_chain {
+        Some(Some(true)) => return StuckAction::Resume,
+        Some(Some(false)) => "transfer failed on-chain",
+        Some(None) => "transfer never landed",
+        None => "no transfer signature recorded",
+    };
+    if retry_count as u32 >= retry_attempts {
+        StuckAction::Fail(reason)
+    } else {
+        StuckAction::Requeue(reason)
+    }
+}
+
+/// Priority of a trade's settlement under the policy of its market. Trades
+/// whose order no longer resolves to a market settle at normal priority.
+pub async fn trade_priority(
+    db: &PgPool,
+    buy_order_id: Uuid,
+    epoch_id: Uuid,
+    (buyer_id, seller_id): (Uuid, Uuid),
+    notional: Decimal,
+) -> Result<SettlementPriority, sqlx::Error> {
+    use sqlx::Row;
+
+    let row = sqlx::query(
+        r#"
+        SELECT
+            m.settlement_high_notional, m.settlement_urgent_notional,
+            m.settlement_corporate_high, m.settlement_deadline_secs,
+            (SELECT EXTRACT(EPOCH FROM e.end_time - NOW())::float8
+             FROM market_epochs e WHERE e.id = $2) AS secs_to_epoch_end,
+            EXISTS (
+                SELECT 1 FROM users u WHERE u.id IN ($3, $4) AND u.role::text = 'corporate'
+            ) AS corporate_counterparty
+        FROM trading_orders o
+        JOIN markets m ON m.id = o.market_id
+        WHERE o.id = $1
+        "#,
+    )
+    .bind(buy_order_id)
+    .bind(epoch_id)
+    .bind(buyer_id)
+    .bind(seller_id)
+    .fetch_optional(db)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(SettlementPriority::Normal);
+    };
+    let policy = SettlementPriorityPolicy {
+        high_notional: row.get("settlement_high_notional"),
+        urgent_notional: row.get("settlement_urgent_notional"),
+        corporate_high: row.get("settlement_corporate_high"),
+        deadline_secs: row.get("settlement_deadline_secs"),
+    };
+    Ok(policy.priority(
+        notional,
+        row.get("corporate_counterparty"),
+        row.get("secs_to_epoch_end"),
+    ))
+}
+
 /// Settlement service for blockchain transaction execution
 #[derive(Clone)]
 pub struct SettlementService {
@@ -27,12 +134,16 @@ pub struct SettlementService {
     blockchain: BlockchainService,
     config: SettlementConfig,
     encryption_secret: String,
-    #[allow(dead_code)]
-    pending_settlements: Arc<RwLock<Vec<Uuid>>>,
+    /// Wakes the settlement loop when settlements are enqueued
+    work_ready: Arc<Notify>,
     /// ERC service for issuing RECs after settlement
     erc_service: Option<ErcService>,
     /// Notification service for email alerts
     notification_service: NotificationService,
+    /// Records the fiat reference value of each settlement
+    fx_rates: Option<FxRateService>,
+    /// Platform keys paying transfer fees; sellers pay when unset
+    fee_payers: Option<FeePayerPool>,
 }
 
 impl SettlementService {
@@ -51,18 +162,53 @@ impl SettlementService {
         
         // Create NotificationService
         let notification_service = NotificationService::new(db.clone());
+
+        let fee_payers = match FeePayerPool::load(&config.fee_payer_paths, config.fee_payer_selection) {
+            Ok(Some(pool)) => {
+                info!(
+                    "💸 Settlement fee payer pool: {} keys, {} selection",
+                    pool.pubkeys().len(),
+                    pool.selection().as_str()
+                );
+                Some(pool)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                error!("❌ Settlement fee payer pool not loaded, sellers pay fees: {}", e);
+                None
+            }
+        };
         
         Self {
             db,
             blockchain,
             config,
             encryption_secret,
-            pending_settlements: Arc::new(RwLock::new(Vec::new())),
+            work_ready: Arc::new(Notify::new()),
             erc_service,
             notification_service,
+            fx_rates: None,
+            fee_payers,
         }
     }
 
+    /// Record fiat reference values on new settlements
+    pub fn with_fx_rates(mut self, fx_rates: FxRateService) -> Self {
+        self.fx_rates = Some(fx_rates);
+        self
+    }
+
+    /// Decrypt recipient emails for settlement notifications
+    pub fn with_pii(mut self, pii: PiiCipher) -> Self {
+        self.notification_service = self.notification_service.with_pii(pii);
+        self
+    }
+
+    /// Platform fee rate applied to settlement totals
+    pub fn fee_rate(&self) -> Decimal {
+        self.config.fee_rate
+    }
+
     /// Create settlement records from matched trades
     pub async fn create_settlements_from_trades(
         &self,
@@ -159,9 +305,17 @@ impl SettlementService {
         // And I added `effective_energy` column to `settlements`.
         
         // I need to calculate `effective_energy` here.
-        let effective_energy = trade.quantity * (Decimal::ONE - trade.loss_factor);
+        let effective_energy = round_to_token(trade.quantity * (Decimal::ONE - trade.loss_factor));
+        let priority = trade_priority(
+            &self.db,
+            trade.buy_order_id,
+            trade.epoch_id,
+            (trade.buyer_id, trade.seller_id),
+            trade.total_value,
+        )
+        .await?;
         
-        let settlement = Settlement {
+        let mut settlement = Settlement {
             id: Uuid::new_v4(),
             trade_id: trade.id,
             buyer_id: trade.buyer_id,
@@ -183,6 +337,7 @@ impl SettlementService {
             seller_zone_id: trade.seller_zone_id,
             buyer_session_token: trade.buyer_session_token.clone(),
             seller_session_token: trade.seller_session_token.clone(),
+            priority,
             
             status: SettlementStatus::Pending,
             blockchain_tx: None,
@@ -190,15 +345,17 @@ impl SettlementService {
             confirmed_at: None,
         };
 
-        sqlx::query(
+        let inserted: Option<Uuid> = sqlx::query_scalar(
             r#"
             INSERT INTO settlements (
                 id, buyer_id, seller_id, buy_order_id, sell_order_id,
                 energy_amount, price_per_kwh, total_amount, fee_amount, net_amount, status, created_at,
                 wheeling_charge, loss_factor, loss_cost, effective_energy, buyer_zone_id, seller_zone_id, epoch_id,
-                buyer_session_token, seller_session_token
+                buyer_session_token, seller_session_token, priority
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22)
+            ON CONFLICT (epoch_id, buy_order_id, sell_order_id) DO NOTHING
+            RETURNING id
             "#,
         )
         .bind(settlement.id)
@@ -222,14 +379,37 @@ impl SettlementService {
         .bind(trade.epoch_id)
         .bind(&settlement.buyer_session_token)
         .bind(&settlement.seller_session_token)
-        .execute(&self.db)
+        .bind(settlement.priority.as_str())
+        .fetch_optional(&self.db)
         .await?;
 
+        // The pair was already settled in this epoch; hand back that settlement
+        if inserted.is_none() {
+            settlement.id = sqlx::query_scalar(
+                "SELECT id FROM settlements WHERE epoch_id = $1 AND buy_order_id = $2 AND sell_order_id = $3",
+            )
+            .bind(trade.epoch_id)
+            .bind(trade.buy_order_id)
+            .bind(trade.sell_order_id)
+            .fetch_one(&self.db)
+            .await?;
+            info!(
+                "Trade match {} already settled as {}, not creating another settlement",
+                trade.match_id, settlement.id
+            );
+            return Ok(settlement);
+        }
+
+        if let Some(fx_rates) = &self.fx_rates {
+            fx_rates.record_settlement_value(settlement.id).await;
+        }
+
         info!(
-            "📝 Created settlement {}: {} kWh at ${} (buyer: {}, seller: {})",
+            "📝 Created settlement {}: {} kWh at ${}, {} priority (buyer: {}, seller: {})",
             settlement.id,
             settlement.energy_amount,
             settlement.price,
+            settlement.priority.as_str(),
             settlement.buyer_id,
             settlement.seller_id
         );
@@ -252,46 +432,8 @@ impl SettlementService {
         // Execute blockchain transaction
         match self.execute_blockchain_transfer(&settlement).await {
             Ok(tx_result) => {
-                // Update settlement with transaction signature
-                self.update_settlement_confirmed(
-                    settlement_id,
-                    &tx_result.signature,
-                    SettlementStatus::Completed,
-                )
-                .await?;
-
-                // Finalize Escrow (Move funds and unlock energy)
-                if let Err(e) = self.finalize_escrow(&settlement).await {
-                    error!("⚠️ Failed to finalize escrow for settlement {}: {}", settlement_id, e);
-                    // We don't fail the whole method if escrow finalization fails here, 
-                    // but it should be noted. In production, this should be retryable.
-                }
-
-                // Broadcast settlement completion via WebSocket
-                if let Err(e) = broadcast_settlement_complete(
-                    settlement.id,
-                    settlement.buyer_id,
-                    settlement.seller_id,
-                    settlement.energy_amount.to_string(),
-                    settlement.total_value.to_string(),
-                    Some(tx_result.signature.clone()),
-                ).await {
-                    error!("⚠️ Failed to broadcast settlement: {}", e);
-                }
-
-                // Send email notifications to buyer and seller
-                self.send_settlement_notifications(&settlement, &tx_result.signature).await;
-
-                // Issue REC (Renewable Energy Certificate) to seller
-                if let Err(e) = self.issue_rec_for_settlement(&settlement).await {
-                    error!("⚠️ Failed to issue REC for settlement {}: {}", settlement_id, e);
-                    // Non-blocking - settlement completed, REC issuance is secondary
-                }
-
-                info!(
-                    "✅ Settlement {} completed: tx {}",
-                    settlement_id, tx_result.signature
-                );
+                self.complete_settlement(&settlement, &tx_result.signature, &tx_result.confirmation_status)
+                    .await?;
                 Ok(tx_result)
             }
             Err(e) => {
@@ -309,6 +451,63 @@ impl SettlementService {
         }
     }
 
+    /// Record a confirmed transfer and run the post-settlement steps:
+    /// escrow, broadcast, notifications and REC issuance
+    async fn complete_settlement(
+        &self,
+        settlement: &Settlement,
+        signature: &str,
+        commitment: &str,
+    ) -> Result<(), ApiError> {
+        let settlement_id = settlement.id;
+
+        // Update settlement with transaction signature
+        self.update_settlement_confirmed(
+            settlement_id,
+            signature,
+            commitment,
+            SettlementStatus::Completed,
+        )
+        .await?;
+        track_settlement_confirmation(
+            (Utc::now() - settlement.created_at).num_milliseconds() as f64 / 1000.0,
+        );
+
+        // Finalize Escrow (Move funds and unlock energy)
+        if let Err(e) = self.finalize_escrow(settlement).await {
+            error!("⚠️ Failed to finalize escrow for settlement {}: {}", settlement_id, e);
+            // We don't fail the whole method if escrow finalization fails here, 
+            // but it should be noted. In production, this should be retryable.
+        }
+
+        // Broadcast settlement completion via WebSocket
+        if let Err(e) = broadcast_settlement_complete(
+            settlement.id,
+            settlement.buyer_id,
+            settlement.seller_id,
+            settlement.energy_amount.to_string(),
+            settlement.total_value.to_string(),
+            Some(signature.to_string()),
+        ).await {
+            error!("⚠️ Failed to broadcast settlement: {}", e);
+        }
+
+        // Send email notifications to buyer and seller
+        self.send_settlement_notifications(settlement, signature).await;
+
+        // Issue REC (Renewable Energy Certificate) to seller
+        if let Err(e) = self.issue_rec_for_settlement(settlement).await {
+            error!("⚠️ Failed to issue REC for settlement {}: {}", settlement_id, e);
+            // Non-blocking - settlement completed, REC issuance is secondary
+        }
+
+        info!(
+            "✅ Settlement {} completed: tx {}",
+            settlement_id, signature
+        );
+        Ok(())
+    }
+
     /// Execute actual blockchain transfer
     async fn execute_blockchain_transfer(
         &self,
@@ -401,24 +600,37 @@ impl SettlementService {
         );
 
         // 8. Execute Token Transfer (Seller -> Buyer)
-        // Only transfer the EFFECTIVE energy to the buyer.
+        // Only transfer the EFFECTIVE energy to the buyer. Effective energy is
+        // rounded to the token's 9 decimals when the settlement is created, so
+        // an amount that does not convert exactly is refused, not truncated.
         let effective_energy = settlement.effective_energy.unwrap_or(settlement.energy_amount);
-        let amount_atomic = effective_energy * Decimal::from(1_000_000_000);
-        let transfer_amount = amount_atomic
-            .trunc()
-            .to_string()
-            .parse::<u64>()
-            .unwrap_or(0);
+        let transfer_amount = to_lamports(effective_energy)
+            .map_err(|e| ApiError::Internal(format!("Invalid transfer amount: {}", e)))?;
+        // Convert the grid loss up front so a bad amount fails before any transfer
+        let loss_energy = settlement.energy_amount - effective_energy;
+        let loss_atomic = if loss_energy > Decimal::ZERO {
+            to_lamports(loss_energy)
+                .map_err(|e| ApiError::Internal(format!("Invalid grid loss amount: {}", e)))?
+        } else {
+            0
+        };
 
         info!(
             "Executing Direct Token Transfer: From {} to {}, Amount: {} (atomic), Decimals: 9 (Effective Energy: {})",
             seller_token_account, buyer_token_account, transfer_amount, effective_energy
         );
 
+        let fee_payer = match &self.fee_payers {
+            Some(pool) => Some(pool.select(&self.blockchain).await),
+            None => None,
+        };
+
         let signature = self
             .blockchain
-            .transfer_tokens(
+            .transfer_tokens_as(
+                TxOperation::Settlement,
                 &seller_keypair,   // Signer (Owner of From Account)
+                fee_payer,             // Pool payer, or the seller when unset
                 &seller_token_account, // From (Seller ATA)
                 &buyer_token_account,  // To (Buyer ATA)
                 &mint,
@@ -428,24 +640,44 @@ impl SettlementService {
             .await
             .map_err(|e| ApiError::Internal(format!("Token transfer failed: {}", e)))?;
 
+        // Keep the signature before waiting on confirmation so the watchdog
+        // can look the transfer up if this task dies
+        let fee_payer_pubkey = fee_payer.map_or(seller_actual_pubkey, |payer| payer.pubkey());
+        self.record_settlement_signature(settlement.id, &signature.to_string(), &fee_payer_pubkey.to_string())
+            .await?;
+
         // Handle grid loss: the difference between energy_amount (gross) and effective_energy
         // remain in the seller's account if we only transfer the effective amount.
         // To properly account for it, we should 'burn' these tokens or transfer them to a loss sink.
-        let loss_energy = settlement.energy_amount - effective_energy;
-        if loss_energy > Decimal::ZERO {
-            let loss_atomic = (loss_energy * Decimal::from(1_000_000_000)).trunc().to_string().parse::<u64>().unwrap_or(0);
-            if loss_atomic > 0 {
-                let loss_sink_wallet = std::env::var("GRID_LOSS_SINK_WALLET").unwrap_or_else(|_| "LoSsSiNk1111111111111111111111111111111111".to_string());
-                if let Ok(sink_pubkey) = BlockchainService::parse_pubkey(&loss_sink_wallet) {
-                    if let Ok(sink_token_account) = self.blockchain.ensure_token_account_exists(&_platform_authority, &sink_pubkey, &mint).await {
-                        info!("📉 Recording {} loss tokens to grid loss sink", loss_atomic);
-                        let _ = self.blockchain.transfer_tokens(&seller_keypair, &seller_token_account, &sink_token_account, &mint, loss_atomic, 9).await;
-                    }
-                }
+        // A failed sweep leaves the loss with the seller; it is recorded on the
+        // settlement rather than failing a transfer that already went out.
+        if loss_atomic > 0 {
+            let loss_sink_wallet = std::env::var("GRID_LOSS_SINK_WALLET").unwrap_or_else(|_| "LoSsSiNk1111111111111111111111111111111111".to_string());
+            let swept = async {
+                let sink_pubkey = BlockchainService::parse_pubkey(&loss_sink_wallet)?;
+                let sink_token_account = self.blockchain.ensure_token_account_exists(&_platform_authority, &sink_pubkey, &mint).await?;
+                info!("📉 Recording {} loss tokens to grid loss sink", loss_atomic);
+                self.blockchain.transfer_tokens_as(TxOperation::Settlement, &seller_keypair, fee_payer, &seller_token_account, &sink_token_account, &mint, loss_atomic, 9).await
             }
+            .await;
+            self.record_loss_transfer(settlement.id, swept).await;
         }
 
-        info!("Settlement transfer completed. Signature: {}", signature);
+        // The CLI returns once the transfer lands; settlements only count
+        // once they reach the configured commitment
+        let commitment = self
+            .blockchain
+            .confirm_signature(&signature, TxOperation::Settlement)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Settlement transfer not confirmed: {}", e)))?;
+
+        info!(
+            "Settlement transfer completed. Signature: {} ({})",
+            signature,
+            commitment_str(commitment)
+        );
+        self.record_settlement_fee(settlement.id, &signature, &fee_payer_pubkey)
+            .await;
 
         // 9. Get current slot for confirmation
         let slot = self
@@ -459,7 +691,7 @@ impl SettlementService {
             settlement_id: settlement.id,
             signature: signature.to_string(),
             slot,
-            confirmation_status: "confirmed".to_string(),
+            confirmation_status: commitment_str(commitment).to_string(),
         })
     }
 
@@ -509,9 +741,28 @@ impl SettlementService {
             .ok_or_else(|| ApiError::Internal(format!("Order {} has no PDA stored", order_id)))
     }
 
-    /// Process all pending settlements
+    /// Enqueue settlements for the next batch and wake the settlement loop.
+    /// Settlements that cannot be queued still run from the backlog.
+    pub async fn enqueue_settlements(&self, settlement_ids: &[Uuid]) {
+        if settlement_ids.is_empty() {
+            return;
+        }
+        if let Err(e) = self.queue_settlements(settlement_ids).await {
+            warn!("⚠️ Failed to queue {} settlements: {}", settlement_ids.len(), e);
+        }
+        self.work_ready.notify_one();
+    }
+
+    /// Wait until settlements are enqueued or the interval elapses
+    pub async fn wait_for_work(&self, interval: Duration) {
+        let _ = tokio::time::timeout(interval, self.work_ready.notified()).await;
+    }
+
+    /// Process all pending settlements. The batch is checkpointed as it
+    /// runs, so a restart resumes it instead of losing its order.
     pub async fn process_pending_settlements(&self) -> Result<usize, ApiError> {
-        let pending_ids = self.get_pending_settlements().await?;
+        let scheduled = self.scheduled_settlements().await?;
+        let pending_ids = prioritize_queued(self.get_pending_settlements().await?, &scheduled);
 
         if pending_ids.is_empty() {
             debug!("No pending settlements to process");
@@ -519,28 +770,47 @@ impl SettlementService {
         }
 
         info!("🚀 Processing {} pending settlements...", pending_ids.len());
+        let batch_id = self.begin_batch(&pending_ids).await?;
         let total_count = pending_ids.len();
         let mut processed = 0;
+        let mut failed = Vec::new();
+
+        for (position, settlement_id) in pending_ids.into_iter().enumerate() {
+            match self.claim_pending(settlement_id).await {
+                Ok(true) => self.checkpoint_batch(batch_id, position + 1, settlement_id).await,
+                Ok(false) => {
+                    debug!("Settlement {} no longer pending, skipped", settlement_id);
+                    self.checkpoint_batch(batch_id, position + 1, settlement_id).await;
+                    continue;
+                }
+                // Left pending and queued for the next batch
+                Err(e) => {
+                    error!("❌ Failed to claim settlement {}: {}", settlement_id, e);
+                    continue;
+                }
+            }
 
-        for settlement_id in pending_ids {
             match self.execute_settlement(settlement_id).await {
                 Ok(_) => {
                     processed += 1;
                 }
                 Err(e) => {
                     error!("❌ Failed to process settlement {}: {}", settlement_id, e);
+                    failed.push(settlement_id);
                 }
             }
 
             // Small delay between settlements to avoid rate limiting
             tokio::time::sleep(Duration::from_millis(100)).await;
         }
+        self.finish_batch(batch_id).await;
 
         let success_rate = (processed as f64 / total_count as f64) * 100.0;
         info!(
             "🏁 BATCH SETTLEMENT COMPLETE: Success Rate: {:.1}% ({}/{})",
             success_rate, processed, total_count
         );
+        self.isolate_batch_failures(&failed).await;
         Ok(processed)
     }
 
@@ -555,7 +825,7 @@ impl SettlementService {
                 price_per_kwh, total_amount, fee_amount, net_amount,
                 status, transaction_hash, created_at, processed_at,
                 wheeling_charge, loss_factor, loss_cost, effective_energy, buyer_zone_id, seller_zone_id,
-                buyer_session_token, seller_session_token
+                buyer_session_token, seller_session_token, priority
             FROM settlements
             WHERE id = $1
             "#,
@@ -572,6 +842,8 @@ impl SettlementService {
             "processing" => SettlementStatus::Processing,
             "completed" | "confirmed" => SettlementStatus::Completed,
             "failed" => SettlementStatus::Failed,
+            "busted" => SettlementStatus::Busted,
+            "rolled_back" => SettlementStatus::RolledBack,
             _ => SettlementStatus::Pending,
         };
 
@@ -599,10 +871,14 @@ impl SettlementService {
             seller_zone_id: row.get("seller_zone_id"),
             buyer_session_token: row.get("buyer_session_token"),
             seller_session_token: row.get("seller_session_token"),
+            priority: row
+                .get::<String, _>("priority")
+                .parse()
+                .unwrap_or_default(),
         })
     }
 
-    /// Get all pending settlements
+    /// Get pending settlements, highest priority first
     pub async fn get_pending_settlements(&self) -> Result<Vec<Uuid>, ApiError> {
         use sqlx::Row;
 
@@ -611,7 +887,7 @@ impl SettlementService {
             SELECT id
             FROM settlements
             WHERE status = 'pending'
-            ORDER BY created_at ASC
+            ORDER BY CASE priority WHEN 'urgent' THEN 0 WHEN 'high' THEN 1 ELSE 2 END, created_at ASC
             LIMIT 100
             "#,
         )
@@ -649,6 +925,7 @@ impl SettlementService {
         &self,
         id: Uuid,
         tx_signature: &str,
+        commitment: &str,
         status: SettlementStatus,
     ) -> Result<(), ApiError> {
         sqlx::query(
@@ -656,13 +933,15 @@ impl SettlementService {
             UPDATE settlements
             SET status = $1,
                 transaction_hash = $2,
+                commitment = $3,
                 processed_at = NOW(),
                 updated_at = NOW()
-            WHERE id = $3
+            WHERE id = $4
             "#,
         )
         .bind(status.to_string())
         .bind(tx_signature)
+        .bind(commitment)
         .bind(id)
         .execute(&self.db)
         .await
@@ -671,6 +950,195 @@ impl SettlementService {
         Ok(())
     }
 
+    /// Store the transfer signature and fee payer of a settlement still in processing
+    async fn record_settlement_signature(
+        &self,
+        id: Uuid,
+        tx_signature: &str,
+        fee_payer: &str,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            UPDATE settlements
+            SET transaction_hash = $1, fee_payer = $2, updated_at = NOW()
+            WHERE id = $3
+            "#,
+        )
+        .bind(tx_signature)
+        .bind(fee_payer)
+        .bind(id)
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        Ok(())
+    }
+
+    /// Record the outcome of sweeping a settlement's grid loss to the loss
+    /// sink. Errors are only logged, like the sweep's own failure.
+    async fn record_loss_transfer(&self, id: Uuid, swept: anyhow::Result<Signature>) {
+        let (signature, failure) = match swept {
+            Ok(signature) => (Some(signature.to_string()), None),
+            Err(e) => {
+                error!("❌ Grid loss transfer of settlement {} failed: {}", id, e);
+                (None, Some(e.to_string()))
+            }
+        };
+        if let Err(e) = sqlx::query(
+            "UPDATE settlements SET loss_transfer_signature = $1, loss_transfer_error = $2 WHERE id = $3",
+        )
+        .bind(&signature)
+        .bind(&failure)
+        .bind(id)
+        .execute(&self.db)
+        .await
+        {
+            warn!("⚠️ Could not record grid loss transfer of settlement {}: {}", id, e);
+        }
+    }
+
+    /// Record the fee a confirmed transfer cost its payer. Spend tracking
+    /// must not fail the settlement, so errors are only logged.
+    async fn record_settlement_fee(&self, id: Uuid, signature: &Signature, fee_payer: &Pubkey) {
+        let fee = match self.blockchain.get_transaction_fee(signature).await {
+            Ok(fee) => fee,
+            Err(e) => {
+                warn!("⚠️ Could not read fee of settlement {} transfer {}: {}", id, signature, e);
+                return;
+            }
+        };
+        track_settlement_fee(&fee_payer.to_string(), fee);
+        if let Err(e) = sqlx::query("UPDATE settlements SET fee_lamports = $1 WHERE id = $2")
+            .bind(fee as i64)
+            .bind(id)
+            .execute(&self.db)
+            .await
+        {
+            warn!("⚠️ Could not record fee of settlement {}: {}", id, e);
+        }
+    }
+
+    /// Find settlements left in processing past the stuck threshold (their
+    /// executing task died or hung) and settle each from its transfer's
+    /// on-chain status: confirmed transfers are completed, failed or dropped
+    /// ones and ones that never recorded a signature go back to pending.
+    pub async fn recover_stuck_settlements(&self) -> Result<StuckSettlementRecovery, ApiError> {
+        use sqlx::Row;
+
+        // Claim by bumping updated_at so a concurrent pass skips them
+        let stuck = sqlx::query(
+            r#"
+            UPDATE settlements
+            SET updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM settlements
+                WHERE status = 'processing'
+                AND updated_at < NOW() - make_interval(secs => $1)
+                ORDER BY updated_at ASC
+                LIMIT 100
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, transaction_hash, COALESCE(retry_count, 0) AS retry_count
+            "#,
+        )
+        .bind(self.config.stuck_threshold_secs as f64)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut recovery = StuckSettlementRecovery::default();
+        set_settlements_stuck(stuck.len());
+        if stuck.is_empty() {
+            return Ok(recovery);
+        }
+        error!(
+            "🚨 {} settlements stuck in processing for over {}s",
+            stuck.len(),
+            self.config.stuck_threshold_secs
+        );
+
+        let mut requeued = Vec::new();
+        for row in stuck {
+            let id: Uuid = row.get("id");
+            let tx_hash: Option<String> = row.get("transaction_hash");
+            let retry_count: i32 = row.get("retry_count");
+
+            let on_chain = match tx_hash.as_deref().map(Signature::from_str) {
+                Some(Ok(signature)) => {
+                    match self
+                        .blockchain
+                        .find_signature_status(&signature, TxOperation::Settlement)
+                        .await
+                    {
+                        Ok(status) => Some(status),
+                        Err(e) => {
+                            warn!("⚠️ Could not check transfer {} of stuck settlement {}: {}", signature, id, e);
+                            recovery.unresolved += 1;
+                            track_settlement_recovery("unresolved");
+                            continue;
+                        }
+                    }
+                }
+                // Mock-mode or malformed signatures never reached a cluster
+                Some(Err(_)) | None => None,
+            };
+
+            match stuck_action(on_chain, retry_count, self.config.retry_attempts) {
+                StuckAction::Resume => {
+                    let settlement = self.get_settlement(id).await?;
+                    let signature = tx_hash.as_deref().unwrap_or_default();
+                    let commitment = commitment_str(
+                        self.blockchain.commitment_for(TxOperation::Settlement).commitment,
+                    );
+                    self.complete_settlement(&settlement, signature, commitment).await?;
+                    error!("🚨 Stuck settlement {} recovered: transfer {} had landed", id, signature);
+                    recovery.resumed += 1;
+                    track_settlement_recovery("resumed");
+                }
+                StuckAction::Fail(reason) => {
+                    error!("🚨 Stuck settlement {} out of retries: {}", id, reason);
+                    let error_message = format!("Stuck in processing: {}", reason);
+                    self.mark_settlement_permanent_failure(&id, &error_message).await?;
+                    recovery.failed += 1;
+                    track_settlement_recovery("failed");
+                }
+                StuckAction::Requeue(reason) => {
+                    error!("🚨 Stuck settlement {} requeued: {}", id, reason);
+                    let error_message = format!("Stuck in processing: {}", reason);
+                    self.requeue_settlement(&id, &error_message).await?;
+                    requeued.push(id);
+                    recovery.requeued += 1;
+                    track_settlement_recovery("requeued");
+                }
+            }
+        }
+
+        self.enqueue_settlements(&requeued).await;
+        Ok(recovery)
+    }
+
+    /// Send a settlement back to pending, dropping its stale signature
+    async fn requeue_settlement(&self, settlement_id: &Uuid, error_message: &str) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            UPDATE settlements
+            SET status = 'pending',
+                transaction_hash = NULL,
+                error_message = $1,
+                retry_count = COALESCE(retry_count, 0) + 1,
+                updated_at = NOW()
+            WHERE id = $2 AND status = 'processing'
+            "#,
+        )
+        .bind(error_message)
+        .bind(settlement_id)
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        Ok(())
+    }
+
     /// Retry failed settlements with exponential backoff (called by background job)
     /// Implements smart retry logic with error classification
     pub async fn retry_failed_settlements(&self, max_retries: u32) -> Result<usize, ApiError> {
@@ -680,6 +1148,7 @@ impl SettlementService {
             SELECT id, retry_count FROM settlements
             WHERE status = 'failed'
             AND retry_count < $1
+            AND quarantined_at IS NULL
             ORDER BY retry_count ASC, updated_at ASC
             "#,
             max_retries as i32
@@ -849,8 +1318,8 @@ impl SettlementService {
             total_settled_value: row.get("total_settled_value"),
         })
     }
-    /// Helper: Get user keypair from database
-    async fn get_user_keypair(
+    /// Helper: Get user keypair from database, from the wallet session when one is given
+    pub(crate) async fn get_user_keypair(
         &self,
         user_id: &Uuid,
         session_token: Option<&str>,
@@ -863,7 +1332,7 @@ impl SettlementService {
             // but we can query the session table.
             
             let session = sqlx::query(
-                "SELECT encrypted_private_key, encryption_salt, encryption_iv FROM wallet_sessions WHERE session_token = $1 AND user_id = $2 AND revoked_at IS NULL AND expires_at > NOW()"
+                "SELECT cached_key_encrypted, key_salt, key_iv FROM wallet_sessions WHERE session_token = $1 AND user_id = $2 AND is_active = true AND revoked_at IS NULL AND expires_at > NOW() AND absolute_expires_at > NOW()"
             )
             .bind(token)
             .bind(user_id)
@@ -876,9 +1345,9 @@ impl SettlementService {
                 info!("Using session-cached key for user {}", user_id);
                 
                 use base64::{engine::general_purpose, Engine as _};
-                let encrypted_pk_raw: Vec<u8> = s.get("encrypted_private_key");
-                let salt_raw: Vec<u8> = s.get("encryption_salt");
-                let iv_raw: Vec<u8> = s.get("encryption_iv");
+                let encrypted_pk_raw: Vec<u8> = s.get("cached_key_encrypted");
+                let salt_raw: Vec<u8> = s.get("key_salt");
+                let iv_raw: Vec<u8> = s.get("key_iv");
 
                 let encrypted_b64 = general_purpose::STANDARD.encode(&encrypted_pk_raw);
                 let salt_b64 = general_purpose::STANDARD.encode(&salt_raw);
@@ -995,6 +1464,12 @@ impl SettlementService {
         .execute(&mut *tx)
         .await.map_err(ApiError::Database)?;
 
+        // 2b. Buyer: Prepaid fiat credit pays first; the covered part of the escrow returns to balance
+        let prepaid_applied =
+            payments::apply_to_settlement(&mut tx, settlement.buyer_id, settlement.id, total_value)
+                .await
+                .map_err(ApiError::Database)?;
+
         // 3. Seller: Receive net_amount to their balance
         sqlx::query!(
             "UPDATE users SET balance = balance + $1 WHERE id = $2",
@@ -1052,11 +1527,22 @@ impl SettlementService {
         .await.map_err(ApiError::Database)?;
 
         tx.commit().await.map_err(ApiError::Database)?;
+
+        if let Some(entry) = prepaid_applied {
+            info!("💳 Applied {} prepaid credit to settlement {}", -entry.amount, settlement.id);
+            AuditLogger::new(self.db.clone()).log_async(entry.audit_event());
+        }
         
         info!("🔐 Escrow finalized for settlement {}: funds transferred and energy unlocked", settlement.id);
         Ok(())
     }
 
+    /// Issue the REC for a settlement whose issuance was deferred by a dispute
+    pub async fn issue_deferred_rec(&self, settlement_id: Uuid) -> Result<(), ApiError> {
+        let settlement = self.get_settlement(settlement_id).await?;
+        self.issue_rec_for_settlement(&settlement).await
+    }
+
     /// Issue a Renewable Energy Certificate (REC) to the seller after settlement
     async fn issue_rec_for_settlement(&self, settlement: &Settlement) -> Result<(), ApiError> {
         let erc_service = match &self.erc_service {
@@ -1067,9 +1553,37 @@ impl SettlementService {
             }
         };
 
+        // Disputed energy gets no certificate until the dispute is decided
+        if dispute::defer_rec_if_disputed(&self.db, settlement.id)
+            .await
+            .map_err(ApiError::Database)?
+        {
+            info!("⏸️ REC issuance for settlement {} deferred: settlement is disputed", settlement.id);
+            return Ok(());
+        }
+
+        // Automated issuance is signed by the registry's default issuer
+        let issuer_key = match erc_service.default_issuer_key().await {
+            Ok(Some(key)) => key,
+            Ok(None) => {
+                warn!("No default ERC issuer registered, skipping REC for settlement {}", settlement.id);
+                return Ok(());
+            }
+            Err(e) => return Err(ApiError::Internal(format!("Failed to load default ERC issuer: {}", e))),
+        };
+
         // Get seller wallet address
         let seller_wallet = self.get_user_wallet(&settlement.seller_id).await?;
 
+        // Certificates are tied to the meter that produced the energy
+        let meter_serial: Option<String> = sqlx::query_scalar(
+            "SELECT m.serial_number FROM trading_orders o JOIN meters m ON m.id = o.meter_id WHERE o.id = $1",
+        )
+        .bind(settlement.sell_order_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
         // Determine renewable source from meter info (try to fetch from DB)
         let renewable_source = self.get_meter_renewable_source(&settlement.sell_order_id)
             .await
@@ -1088,7 +1602,7 @@ impl SettlementService {
         // Issue the certificate
         let request = IssueErcRequest {
             wallet_address: seller_wallet.clone(),
-            meter_id: None, // Meter ID will be looked up by ErcService if needed
+            meter_id: meter_serial,
             kwh_amount,
             expiry_date: None, // RECs have default expiry
             metadata: Some(metadata),
@@ -1096,7 +1610,7 @@ impl SettlementService {
 
         match erc_service.issue_certificate(
             settlement.seller_id,
-            &seller_wallet,
+            &issuer_key,
             request,
             Some(settlement.id),
         ).await {
@@ -1138,7 +1652,7 @@ impl SettlementService {
         Ok(result.unwrap_or_else(|| "Solar".to_string()))
     }
 
-    /// Send email notifications to buyer and seller after settlement
+    /// Notify buyer and seller after settlement (inbox, then email)
     async fn send_settlement_notifications(&self, settlement: &Settlement, tx_signature: &str) {
         let notification_data = SettlementNotification {
             settlement_id: settlement.id,
@@ -1147,26 +1661,24 @@ impl SettlementService {
             tx_signature: Some(tx_signature.to_string()),
         };
 
-        // Notify buyer
-        if let Ok(buyer_email) = self.notification_service.get_user_email(&settlement.buyer_id).await {
-            if let Err(e) = self.notification_service.notify_settlement_complete(
-                settlement.buyer_id,
-                &buyer_email,
-                notification_data.clone(),
-            ).await {
-                error!("Failed to send settlement notification to buyer: {}", e);
-            }
+        // Notify buyer (the inbox copy is stored even without a resolvable email)
+        let buyer_email = self.notification_service.get_user_email(&settlement.buyer_id).await.ok();
+        if let Err(e) = self.notification_service.notify_settlement_complete(
+            settlement.buyer_id,
+            buyer_email.as_deref(),
+            notification_data.clone(),
+        ).await {
+            error!("Failed to send settlement notification to buyer: {}", e);
         }
 
         // Notify seller
-        if let Ok(seller_email) = self.notification_service.get_user_email(&settlement.seller_id).await {
-            if let Err(e) = self.notification_service.notify_settlement_complete(
-                settlement.seller_id,
-                &seller_email,
-                notification_data,
-            ).await {
-                error!("Failed to send settlement notification to seller: {}", e);
-            }
+        let seller_email = self.notification_service.get_user_email(&settlement.seller_id).await.ok();
+        if let Err(e) = self.notification_service.notify_settlement_complete(
+            settlement.seller_id,
+            seller_email.as_deref(),
+            notification_data,
+        ).await {
+            error!("Failed to send settlement notification to seller: {}", e);
         }
 
         info!("📧 Settlement notifications sent for {}", settlement.id);
@@ -1217,11 +1729,35 @@ mod tests {
             confirmed_at: None,
             buyer_session_token: None,
             seller_session_token: None,
+            priority: SettlementPriority::Normal,
         };
 
         assert_eq!(settlement.status, SettlementStatus::Pending);
     }
 
+    #[test]
+    fn test_settlement_priority_policy() {
+        let policy = SettlementPriorityPolicy {
+            high_notional: Some(Decimal::from(1_000)),
+            urgent_notional: Some(Decimal::from(10_000)),
+            corporate_high: true,
+            deadline_secs: Some(60),
+        };
+
+        assert_eq!(policy.priority(Decimal::from(50), false, Some(600.0)), SettlementPriority::Normal);
+        assert_eq!(policy.priority(Decimal::from(1_000), false, None), SettlementPriority::High);
+        assert_eq!(policy.priority(Decimal::from(50), true, None), SettlementPriority::High);
+        assert_eq!(policy.priority(Decimal::from(10_000), false, None), SettlementPriority::Urgent);
+        // Near the epoch deadline raises one level, capped at urgent
+        assert_eq!(policy.priority(Decimal::from(50), false, Some(30.0)), SettlementPriority::High);
+        assert_eq!(policy.priority(Decimal::from(50), true, Some(-5.0)), SettlementPriority::Urgent);
+        assert_eq!(policy.priority(Decimal::from(10_000), false, Some(30.0)), SettlementPriority::Urgent);
+
+        // Without a policy everything stays normal
+        let none = SettlementPriorityPolicy::default();
+        assert_eq!(none.priority(Decimal::from(1_000_000), true, Some(0.0)), SettlementPriority::Normal);
+    }
+
     #[test]
     fn test_fee_calculation() {
         let config = SettlementConfig {
@@ -1230,6 +1766,9 @@ mod tests {
             retry_attempts: 3,
             retry_delay_secs: 5,
             enable_real_blockchain: true,
+            stuck_threshold_secs: 600,
+            fee_payer_paths: Vec::new(),
+            fee_payer_selection: FeePayerSelection::RoundRobin,
         };
 
         let trade_amount = Decimal::from(100);
@@ -1280,8 +1819,43 @@ mod tests {
             retry_attempts: 5,
             retry_delay_secs: 10,
             enable_real_blockchain: true,
+            stuck_threshold_secs: 600,
+            fee_payer_paths: Vec::new(),
+            fee_payer_selection: FeePayerSelection::RoundRobin,
         };
 
         assert_eq!(custom_config.fee_rate, Decimal::from_str("0.005").unwrap());
     }
+
+    #[test]
+    fn test_prioritize_queued() {
+        let (a, b, c, d) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        // c and b were enqueued (in that order); d is no longer pending
+        let ordered = prioritize_queued(vec![a, b, c], &[c, d, b]);
+        assert_eq!(ordered, vec![c, b, a]);
+    }
+
+    #[test]
+    fn test_stuck_action() {
+        assert_eq!(stuck_action(Some(Some(true)), 3, 3), StuckAction::Resume);
+        assert_eq!(
+            stuck_action(Some(Some(false)), 0, 3),
+            StuckAction::Requeue("transfer failed on-chain")
+        );
+        assert_eq!(stuck_action(Some(None), 2, 3), StuckAction::Requeue("transfer never landed"));
+        assert_eq!(stuck_action(None, 3, 3), StuckAction::Fail("no transfer signature recorded"));
+    }
+
+    #[test]
+    fn test_stuck_settlement_recovery_total() {
+        let recovery = StuckSettlementRecovery {
+            resumed: 1,
+            requeued: 2,
+            failed: 3,
+            unresolved: 4,
+        };
+        assert_eq!(recovery.total(), 10);
+        assert_eq!(StuckSettlementRecovery::default().total(), 0);
+    }
 }
@@ -0,0 +1,195 @@
+//! Persistent settlement scheduler state
+//!
+//! The queue of enqueued settlements lives in `settlement_queue` and the
+//! batch being executed is checkpointed in `settlement_scheduler_state`, so
+//! a restarted instance resumes where the previous one stopped. Each
+//! settlement is claimed from `pending` before it executes, which keeps a
+//! resumed or concurrent batch from executing it twice.
+
+use sqlx::Row;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::SettlementService;
+use crate::error::ApiError;
+
+/// Settlements of an interrupted batch not attempted yet
+pub fn remaining_in_batch(batch: &[Uuid], position: i32) -> Vec<Uuid> {
+    batch
+        .iter()
+        .skip(position.max(0) as usize)
+        .copied()
+        .collect()
+}
+
+impl SettlementService {
+    /// Add settlements to the persistent queue. Settlements already queued
+    /// keep their place.
+    pub(super) async fn queue_settlements(&self, settlement_ids: &[Uuid]) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            INSERT INTO settlement_queue (settlement_id)
+            SELECT id FROM UNNEST($1::uuid[]) WITH ORDINALITY AS q(id, ord)
+            ORDER BY ord
+            ON CONFLICT (settlement_id) DO NOTHING
+            "#,
+        )
+        .bind(settlement_ids)
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+        Ok(())
+    }
+
+    /// Settlements to run ahead of the backlog, in order: the rest of an
+    /// interrupted batch, then the queue. Queue entries whose settlement is
+    /// no longer pending are dropped.
+    pub(super) async fn scheduled_settlements(&self) -> Result<Vec<Uuid>, ApiError> {
+        sqlx::query(
+            r#"
+            DELETE FROM settlement_queue q
+            USING settlements s
+            WHERE s.id = q.settlement_id AND s.status <> 'pending'
+            "#,
+        )
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let interrupted = sqlx::query(
+            r#"
+            SELECT batch_id, batch_settlement_ids, batch_position
+            FROM settlement_scheduler_state
+            WHERE id AND batch_finished_at IS NULL
+            "#,
+        )
+        .fetch_optional(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut scheduled = match interrupted {
+            Some(row) => {
+                let batch: Vec<Uuid> = row.get("batch_settlement_ids");
+                let position: i32 = row.get("batch_position");
+                let remaining = remaining_in_batch(&batch, position);
+                info!(
+                    "⏯️ Resuming interrupted settlement batch {} at {}/{}",
+                    row.get::<Uuid, _>("batch_id"),
+                    position,
+                    batch.len()
+                );
+                remaining
+            }
+            None => Vec::new(),
+        };
+
+        let queued: Vec<Uuid> =
+            sqlx::query_scalar("SELECT settlement_id FROM settlement_queue ORDER BY seq")
+                .fetch_all(&self.db)
+                .await
+                .map_err(ApiError::Database)?;
+        for id in queued {
+            if !scheduled.contains(&id) {
+                scheduled.push(id);
+            }
+        }
+        Ok(scheduled)
+    }
+
+    /// Checkpoint the start of a batch
+    pub(super) async fn begin_batch(&self, settlement_ids: &[Uuid]) -> Result<Uuid, ApiError> {
+        let batch_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO settlement_scheduler_state (id, batch_id, batch_settlement_ids)
+            VALUES (TRUE, $1, $2)
+            ON CONFLICT (id) DO UPDATE SET
+                batch_id = EXCLUDED.batch_id,
+                batch_settlement_ids = EXCLUDED.batch_settlement_ids,
+                batch_position = 0,
+                last_settlement_id = NULL,
+                batch_started_at = NOW(),
+                batch_finished_at = NULL,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(batch_id)
+        .bind(settlement_ids)
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+        Ok(batch_id)
+    }
+
+    /// Claim a pending settlement for execution; false when it is no longer
+    /// pending, e.g. claimed by another instance
+    pub(super) async fn claim_pending(&self, settlement_id: Uuid) -> Result<bool, ApiError> {
+        let claimed = sqlx::query(
+            r#"
+            UPDATE settlements
+            SET status = 'processing', updated_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            "#,
+        )
+        .bind(settlement_id)
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+        Ok(claimed.rows_affected() == 1)
+    }
+
+    /// Checkpoint an attempted settlement and remove it from the queue. A
+    /// failed checkpoint is logged; the claim already keeps the settlement
+    /// from running twice.
+    pub(super) async fn checkpoint_batch(&self, batch_id: Uuid, position: usize, settlement_id: Uuid) {
+        let result = sqlx::query(
+            r#"
+            WITH dequeued AS (
+                DELETE FROM settlement_queue WHERE settlement_id = $3
+            )
+            UPDATE settlement_scheduler_state
+            SET batch_position = $2, last_settlement_id = $3, updated_at = NOW()
+            WHERE id AND batch_id = $1
+            "#,
+        )
+        .bind(batch_id)
+        .bind(position as i32)
+        .bind(settlement_id)
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            warn!("⚠️ Failed to checkpoint settlement batch {}: {}", batch_id, e);
+        }
+    }
+
+    /// Checkpoint the end of a batch
+    pub(super) async fn finish_batch(&self, batch_id: Uuid) {
+        let result = sqlx::query(
+            r#"
+            UPDATE settlement_scheduler_state
+            SET batch_finished_at = NOW(), updated_at = NOW()
+            WHERE id AND batch_id = $1
+            "#,
+        )
+        .bind(batch_id)
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            warn!("⚠️ Failed to checkpoint end of settlement batch {}: {}", batch_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining_in_batch() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(remaining_in_batch(&[a, b, c], 0), vec![a, b, c]);
+        assert_eq!(remaining_in_batch(&[a, b, c], 2), vec![c]);
+        assert!(remaining_in_batch(&[a, b, c], 3).is_empty());
+        assert!(remaining_in_batch(&[a, b, c], 7).is_empty());
+    }
+}
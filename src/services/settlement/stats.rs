@@ -0,0 +1,157 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::Row;
+use utoipa::ToSchema;
+
+use super::SettlementService;
+use crate::error::ApiError;
+use crate::middleware::metrics::set_settlement_batches;
+
+/// Settlement batch statistics over a time window. A batch is the set of
+/// settlements created by clearing one epoch.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SettlementBatchStatistics {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Settlements currently waiting to execute, regardless of the window
+    pub pending_pool: i64,
+    /// Batches with settlements still pending or processing
+    pub batches_in_flight: i64,
+    /// Batches whose settlements all completed
+    pub batches_settled: i64,
+    /// Finished batches with at least one failed settlement
+    pub batches_with_failures: i64,
+    pub avg_batch_size: f64,
+    pub pending: i64,
+    pub processing: i64,
+    pub completed: i64,
+    pub failed: i64,
+    pub permanently_failed: i64,
+    /// Seconds from settlement creation to confirmation on-chain
+    pub avg_confirmation_secs: Option<f64>,
+    pub p95_confirmation_secs: Option<f64>,
+}
+
+impl SettlementBatchStatistics {
+    /// Batch counts per state, as labelled on the batch gauge
+    fn batch_states(&self) -> [(&'static str, i64); 3] {
+        [
+            ("in_flight", self.batches_in_flight),
+            ("settled", self.batches_settled),
+            ("with_failures", self.batches_with_failures),
+        ]
+    }
+}
+
+impl SettlementService {
+    /// Statistics of settlements created in `[from, to)`, grouped into their
+    /// epoch batches. Refreshes the batch gauges as a side effect.
+    pub async fn get_batch_statistics(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<SettlementBatchStatistics, ApiError> {
+        let pending_pool: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM settlements WHERE status = 'pending'")
+            .fetch_one(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        let batches = sqlx::query(
+            r#"
+            WITH batches AS (
+                SELECT epoch_id,
+                       COUNT(*) AS size,
+                       COUNT(*) FILTER (WHERE status IN ('pending', 'processing')) AS open,
+                       COUNT(*) FILTER (WHERE status IN ('failed', 'permanently_failed')) AS failed
+                FROM settlements
+                WHERE epoch_id IS NOT NULL AND created_at >= $1 AND created_at < $2
+                GROUP BY epoch_id
+            )
+            SELECT
+                COUNT(*) FILTER (WHERE open > 0) AS in_flight,
+                COUNT(*) FILTER (WHERE open = 0 AND failed = 0) AS settled,
+                COUNT(*) FILTER (WHERE open = 0 AND failed > 0) AS with_failures,
+                COALESCE(AVG(size), 0)::float8 AS avg_batch_size
+            FROM batches
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let settlements = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE status = 'pending') AS pending,
+                COUNT(*) FILTER (WHERE status = 'processing') AS processing,
+                COUNT(*) FILTER (WHERE status = 'completed') AS completed,
+                COUNT(*) FILTER (WHERE status = 'failed') AS failed,
+                COUNT(*) FILTER (WHERE status = 'permanently_failed') AS permanently_failed,
+                AVG(EXTRACT(EPOCH FROM processed_at - created_at)::float8)
+                    FILTER (WHERE status = 'completed') AS avg_confirmation_secs,
+                percentile_cont(0.95) WITHIN GROUP (ORDER BY EXTRACT(EPOCH FROM processed_at - created_at)::float8)
+                    FILTER (WHERE status = 'completed') AS p95_confirmation_secs
+            FROM settlements
+            WHERE created_at >= $1 AND created_at < $2
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let stats = SettlementBatchStatistics {
+            from,
+            to,
+            pending_pool,
+            batches_in_flight: batches.get("in_flight"),
+            batches_settled: batches.get("settled"),
+            batches_with_failures: batches.get("with_failures"),
+            avg_batch_size: batches.get("avg_batch_size"),
+            pending: settlements.get("pending"),
+            processing: settlements.get("processing"),
+            completed: settlements.get("completed"),
+            failed: settlements.get("failed"),
+            permanently_failed: settlements.get("permanently_failed"),
+            avg_confirmation_secs: settlements.get("avg_confirmation_secs"),
+            p95_confirmation_secs: settlements.get("p95_confirmation_secs"),
+        };
+
+        set_settlement_batches(stats.pending_pool, &stats.batch_states());
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_states() {
+        let now = Utc::now();
+        let stats = SettlementBatchStatistics {
+            from: now,
+            to: now,
+            pending_pool: 7,
+            batches_in_flight: 2,
+            batches_settled: 5,
+            batches_with_failures: 1,
+            avg_batch_size: 4.0,
+            pending: 3,
+            processing: 1,
+            completed: 20,
+            failed: 2,
+            permanently_failed: 0,
+            avg_confirmation_secs: Some(12.5),
+            p95_confirmation_secs: None,
+        };
+
+        assert_eq!(
+            stats.batch_states(),
+            [("in_flight", 2), ("settled", 5), ("with_failures", 1)]
+        );
+    }
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use uuid::Uuid;
 
+use super::fee_payers::FeePayerSelection;
+
 /// Settlement status
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SettlementStatus {
@@ -11,6 +13,10 @@ pub enum SettlementStatus {
     Processing,
     Completed,
     Failed,
+    /// Reversed by an approved admin trade bust
+    Busted,
+    /// Cancelled before submission by an approved epoch rollback
+    RolledBack,
 }
 
 impl std::fmt::Display for SettlementStatus {
@@ -20,8 +26,89 @@ impl std::fmt::Display for SettlementStatus {
             Self::Processing => write!(f, "processing"),
             Self::Completed => write!(f, "completed"),
             Self::Failed => write!(f, "failed"),
+            Self::Busted => write!(f, "busted"),
+            Self::RolledBack => write!(f, "rolled_back"),
+        }
+    }
+}
+
+/// Order in which pending settlements are executed, highest first
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SettlementPriority {
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl SettlementPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::High => "high",
+            Self::Urgent => "urgent",
         }
     }
+
+    /// One level up, capped at urgent
+    pub fn raised(self) -> Self {
+        match self {
+            Self::Normal => Self::High,
+            Self::High | Self::Urgent => Self::Urgent,
+        }
+    }
+}
+
+impl FromStr for SettlementPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(Self::Normal),
+            "high" => Ok(Self::High),
+            "urgent" => Ok(Self::Urgent),
+            other => Err(format!("Unknown settlement priority: {}", other)),
+        }
+    }
+}
+
+/// A market's rules for raising settlement priority; unset thresholds
+/// disable their rule
+#[derive(Debug, Clone, Default)]
+pub struct SettlementPriorityPolicy {
+    pub high_notional: Option<Decimal>,
+    pub urgent_notional: Option<Decimal>,
+    pub corporate_high: bool,
+    pub deadline_secs: Option<i32>,
+}
+
+impl SettlementPriorityPolicy {
+    /// Priority of a settlement of `notional` value; `secs_to_epoch_end` is
+    /// how long its epoch still runs when the settlement is created
+    pub fn priority(
+        &self,
+        notional: Decimal,
+        corporate_counterparty: bool,
+        secs_to_epoch_end: Option<f64>,
+    ) -> SettlementPriority {
+        let mut priority = if self.urgent_notional.is_some_and(|t| notional >= t) {
+            SettlementPriority::Urgent
+        } else if self.high_notional.is_some_and(|t| notional >= t)
+            || (self.corporate_high && corporate_counterparty)
+        {
+            SettlementPriority::High
+        } else {
+            SettlementPriority::Normal
+        };
+
+        if let (Some(window), Some(remaining)) = (self.deadline_secs, secs_to_epoch_end) {
+            if remaining <= f64::from(window) {
+                priority = priority.raised();
+            }
+        }
+        priority
+    }
 }
 
 /// Settlement record
@@ -52,6 +139,7 @@ pub struct Settlement {
     pub effective_energy: Option<Decimal>,
     pub buyer_session_token: Option<String>,
     pub seller_session_token: Option<String>,
+    pub priority: SettlementPriority,
 }
 
 /// Settlement transaction result
@@ -71,6 +159,9 @@ pub struct SettlementConfig {
     pub retry_attempts: u32,          // Number of retry attempts for failed transactions
     pub retry_delay_secs: u64,        // Delay between retries
     pub enable_real_blockchain: bool, // Enable/disable real blockchain interactions
+    pub stuck_threshold_secs: u64,    // Age after which a processing settlement counts as stuck
+    pub fee_payer_paths: Vec<String>, // Keypair files paying transfer fees; empty lets sellers pay
+    pub fee_payer_selection: FeePayerSelection,
 }
 
 impl Default for SettlementConfig {
@@ -81,6 +172,9 @@ impl Default for SettlementConfig {
             retry_attempts: 3,
             retry_delay_secs: 5,
             enable_real_blockchain: true, // Default to true for safety
+            stuck_threshold_secs: 600,
+            fee_payer_paths: Vec::new(),
+            fee_payer_selection: FeePayerSelection::RoundRobin,
         }
     }
 }
@@ -120,6 +214,29 @@ impl SettlementConfig {
             }
         }
 
+        // Read fee payer pool from environment (comma-separated keypair files)
+        if let Ok(val) = std::env::var("SETTLEMENT_FEE_PAYER_PATHS") {
+            config.fee_payer_paths = val
+                .split(',')
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(String::from)
+                .collect();
+        }
+        if let Ok(val) = std::env::var("SETTLEMENT_FEE_PAYER_SELECTION") {
+            match val.parse::<FeePayerSelection>() {
+                Ok(selection) => config.fee_payer_selection = selection,
+                Err(e) => tracing::warn!("{}, using round_robin", e),
+            }
+        }
+
+        // Read stuck settlement threshold from environment
+        if let Ok(val) = std::env::var("SETTLEMENT_STUCK_THRESHOLD_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.stuck_threshold_secs = secs;
+            }
+        }
+
         config
     }
 }
@@ -134,3 +251,22 @@ pub struct SettlementStats {
     pub failed_count: i64,
     pub total_settled_value: Decimal,
 }
+
+/// Outcome of one watchdog pass over settlements stuck in processing
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StuckSettlementRecovery {
+    /// Transfer found on-chain; completion was resumed
+    pub resumed: usize,
+    /// Transfer failed, was dropped or never sent; back to pending
+    pub requeued: usize,
+    /// Out of retries; marked permanently failed
+    pub failed: usize,
+    /// Signature status could not be checked; left for the next pass
+    pub unresolved: usize,
+}
+
+impl StuckSettlementRecovery {
+    pub fn total(&self) -> usize {
+        self.resumed + self.requeued + self.failed + self.unresolved
+    }
+}
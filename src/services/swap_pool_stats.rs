@@ -0,0 +1,322 @@
+//! Swap Pool Statistics
+//!
+//! TVL, volume, fee APR and price history of the energy/currency pools the
+//! order router swaps against. A periodic aggregator rolls completed swaps
+//! into hourly buckets per pool and samples the pool reserves, so the stats
+//! endpoint reads a bounded number of rows however busy the pool is.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+use crate::services::order_router::{CURRENCY_TOKEN, ENERGY_TOKEN};
+
+/// Longest history a stats request may cover
+pub const MAX_HISTORY_DAYS: i64 = 90;
+/// Days of fees the APR annualizes
+const APR_WINDOW_DAYS: i64 = 7;
+
+/// Value locked in a pool in currency, energy valued at the spot price
+pub fn pool_tvl(reserve_energy: Decimal, reserve_currency: Decimal) -> Decimal {
+    if reserve_energy > Decimal::ZERO {
+        // reserve_currency + reserve_energy * (reserve_currency / reserve_energy)
+        reserve_currency * Decimal::TWO
+    } else {
+        reserve_currency
+    }
+}
+
+/// Fees earned over `days` annualized against the current TVL, as a fraction
+pub fn fee_apr(fees: Decimal, tvl: Decimal, days: i64) -> f64 {
+    if tvl <= Decimal::ZERO || days <= 0 {
+        return 0.0;
+    }
+    (fees / tvl * Decimal::from(365) / Decimal::from(days)).to_f64().unwrap_or(0.0)
+}
+
+/// Width of a history bucket
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsInterval {
+    #[default]
+    Hour,
+    Day,
+}
+
+impl StatsInterval {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+}
+
+/// One bucket of a pool's history
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PoolStatsPoint {
+    pub bucket: DateTime<Utc>,
+    pub swaps: i64,
+    #[schema(value_type = String)]
+    pub volume_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub volume_currency: Decimal,
+    #[schema(value_type = String)]
+    pub fees_currency: Decimal,
+    /// Volume-weighted swap price, currency per kWh; absent without swaps
+    #[schema(value_type = Option<String>)]
+    pub avg_price: Option<Decimal>,
+    /// Spot price at the last reserve sample in the bucket
+    #[schema(value_type = Option<String>)]
+    pub spot_price: Option<Decimal>,
+    /// TVL at the last reserve sample in the bucket
+    #[schema(value_type = Option<String>)]
+    pub tvl: Option<Decimal>,
+}
+
+/// Current state, recent activity and history of a pool
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PoolStats {
+    pub pool_id: Uuid,
+    pub name: String,
+    pub market_id: Option<Uuid>,
+    #[schema(value_type = String)]
+    pub fee_rate: Decimal,
+    #[schema(value_type = String)]
+    pub reserve_energy: Decimal,
+    #[schema(value_type = String)]
+    pub reserve_currency: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub spot_price: Option<Decimal>,
+    #[schema(value_type = String)]
+    pub tvl: Decimal,
+    pub swaps_24h: i64,
+    #[schema(value_type = String)]
+    pub volume_kwh_24h: Decimal,
+    #[schema(value_type = String)]
+    pub volume_currency_24h: Decimal,
+    #[schema(value_type = String)]
+    pub fees_24h: Decimal,
+    #[schema(value_type = String)]
+    pub volume_currency_7d: Decimal,
+    #[schema(value_type = String)]
+    pub fees_7d: Decimal,
+    /// Last 7 days of fees annualized against the current TVL
+    pub fee_apr: f64,
+    pub interval: String,
+    pub history: Vec<PoolStatsPoint>,
+}
+
+#[derive(FromRow)]
+struct PoolRow {
+    id: Uuid,
+    name: String,
+    market_id: Option<Uuid>,
+    reserve_energy: Decimal,
+    reserve_currency: Decimal,
+    fee_rate: Decimal,
+}
+
+#[derive(FromRow)]
+struct WindowTotals {
+    swaps_24h: i64,
+    volume_kwh_24h: Decimal,
+    volume_currency_24h: Decimal,
+    fees_24h: Decimal,
+    volume_currency_7d: Decimal,
+    fees_7d: Decimal,
+}
+
+#[derive(FromRow)]
+struct BucketRow {
+    bucket: DateTime<Utc>,
+    swaps: i64,
+    volume_kwh: Decimal,
+    volume_currency: Decimal,
+    fees_currency: Decimal,
+    reserve_energy: Option<Decimal>,
+    reserve_currency: Option<Decimal>,
+}
+
+#[derive(Clone)]
+pub struct SwapPoolStatsService {
+    db: PgPool,
+}
+
+impl SwapPoolStatsService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Roll swaps since the last aggregated hour into hourly buckets and
+    /// sample the current reserves of every pool
+    pub async fn aggregate(&self) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+
+        // The last bucket may have been built mid-hour, so it is rebuilt too
+        let since: Option<DateTime<Utc>> = sqlx::query_scalar("SELECT MAX(hour) FROM swap_pool_hourly")
+            .fetch_one(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO swap_pool_hourly (pool_id, hour, swaps, volume_kwh, volume_currency, fees_currency)
+            SELECT pool_id, date_trunc('hour', created_at), COUNT(*),
+                   SUM(CASE WHEN input_token = $1 THEN input_amount ELSE output_amount END),
+                   SUM(CASE WHEN input_token = $1 THEN output_amount ELSE input_amount END),
+                   SUM(CASE WHEN input_token = $1
+                            THEN COALESCE(fee_amount * output_amount / NULLIF(input_amount, 0), 0)
+                            ELSE fee_amount END)
+            FROM swap_transactions
+            WHERE status = 'completed' AND ($2::TIMESTAMPTZ IS NULL OR created_at >= $2)
+            GROUP BY pool_id, date_trunc('hour', created_at)
+            ON CONFLICT (pool_id, hour) DO UPDATE
+            SET swaps = EXCLUDED.swaps,
+                volume_kwh = EXCLUDED.volume_kwh,
+                volume_currency = EXCLUDED.volume_currency,
+                fees_currency = EXCLUDED.fees_currency
+            "#,
+        )
+        .bind(ENERGY_TOKEN)
+        .bind(since)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO swap_pool_hourly (pool_id, hour, reserve_energy, reserve_currency)
+            SELECT id, date_trunc('hour', NOW()), reserve_a, reserve_b
+            FROM liquidity_pools
+            WHERE token_a = $1 AND token_b = $2
+            ON CONFLICT (pool_id, hour) DO UPDATE
+            SET reserve_energy = EXCLUDED.reserve_energy,
+                reserve_currency = EXCLUDED.reserve_currency
+            "#,
+        )
+        .bind(ENERGY_TOKEN)
+        .bind(CURRENCY_TOKEN)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn stats(&self, pool_id: Uuid, interval: StatsInterval, days: i64) -> Result<PoolStats> {
+        let days = days.clamp(1, MAX_HISTORY_DAYS);
+        let pool = sqlx::query_as::<_, PoolRow>(
+            r#"
+            SELECT id, name, market_id, reserve_a AS reserve_energy, reserve_b AS reserve_currency, fee_rate
+            FROM liquidity_pools
+            WHERE id = $1 AND token_a = $2 AND token_b = $3
+            "#,
+        )
+        .bind(pool_id)
+        .bind(ENERGY_TOKEN)
+        .bind(CURRENCY_TOKEN)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Swap pool {} not found", pool_id)))?;
+
+        let totals = sqlx::query_as::<_, WindowTotals>(
+            r#"
+            SELECT
+                COALESCE(SUM(swaps) FILTER (WHERE hour > NOW() - INTERVAL '24 hours'), 0)::BIGINT AS swaps_24h,
+                COALESCE(SUM(volume_kwh) FILTER (WHERE hour > NOW() - INTERVAL '24 hours'), 0) AS volume_kwh_24h,
+                COALESCE(SUM(volume_currency) FILTER (WHERE hour > NOW() - INTERVAL '24 hours'), 0) AS volume_currency_24h,
+                COALESCE(SUM(fees_currency) FILTER (WHERE hour > NOW() - INTERVAL '24 hours'), 0) AS fees_24h,
+                COALESCE(SUM(volume_currency), 0) AS volume_currency_7d,
+                COALESCE(SUM(fees_currency), 0) AS fees_7d
+            FROM swap_pool_hourly
+            WHERE pool_id = $1 AND hour > NOW() - make_interval(days => $2::int)
+            "#,
+        )
+        .bind(pool_id)
+        .bind(APR_WINDOW_DAYS);
+
+        let history = sqlx::query_as::<_, BucketRow>(
+            r#"
+            SELECT date_trunc($2, hour) AS bucket,
+                   SUM(swaps)::BIGINT AS swaps,
+                   SUM(volume_kwh) AS volume_kwh,
+                   SUM(volume_currency) AS volume_currency,
+                   SUM(fees_currency) AS fees_currency,
+                   (array_agg(reserve_energy ORDER BY hour DESC) FILTER (WHERE reserve_energy IS NOT NULL))[1]
+                       AS reserve_energy,
+                   (array_agg(reserve_currency ORDER BY hour DESC) FILTER (WHERE reserve_currency IS NOT NULL))[1]
+                       AS reserve_currency
+            FROM swap_pool_hourly
+            WHERE pool_id = $1 AND hour >= date_trunc($2, NOW() - make_interval(days => $3::int))
+            GROUP BY date_trunc($2, hour)
+            ORDER BY bucket
+            "#,
+        )
+        .bind(pool_id)
+        .bind(interval.as_str())
+        .bind(days);
+
+        let (totals, history) = tokio::try_join!(totals.fetch_one(&self.db), history.fetch_all(&self.db))?;
+
+        let tvl = pool_tvl(pool.reserve_energy, pool.reserve_currency);
+        Ok(PoolStats {
+            pool_id: pool.id,
+            name: pool.name,
+            market_id: pool.market_id,
+            fee_rate: pool.fee_rate,
+            reserve_energy: pool.reserve_energy,
+            reserve_currency: pool.reserve_currency,
+            spot_price: spot_price(pool.reserve_energy, pool.reserve_currency),
+            tvl,
+            swaps_24h: totals.swaps_24h,
+            volume_kwh_24h: totals.volume_kwh_24h,
+            volume_currency_24h: totals.volume_currency_24h,
+            fees_24h: totals.fees_24h,
+            volume_currency_7d: totals.volume_currency_7d,
+            fees_7d: totals.fees_7d,
+            fee_apr: fee_apr(totals.fees_7d, tvl, APR_WINDOW_DAYS),
+            interval: interval.as_str().to_string(),
+            history: history
+                .into_iter()
+                .map(|row| {
+                    let reserves = row.reserve_energy.zip(row.reserve_currency);
+                    PoolStatsPoint {
+                        bucket: row.bucket,
+                        swaps: row.swaps,
+                        avg_price: (row.volume_kwh > Decimal::ZERO).then(|| row.volume_currency / row.volume_kwh),
+                        volume_kwh: row.volume_kwh,
+                        volume_currency: row.volume_currency,
+                        fees_currency: row.fees_currency,
+                        spot_price: reserves.and_then(|(energy, currency)| spot_price(energy, currency)),
+                        tvl: reserves.map(|(energy, currency)| pool_tvl(energy, currency)),
+                    }
+                })
+                .collect(),
+        })
+    }
+}
+
+fn spot_price(reserve_energy: Decimal, reserve_currency: Decimal) -> Option<Decimal> {
+    (reserve_energy > Decimal::ZERO).then(|| reserve_currency / reserve_energy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tvl_values_energy_at_spot() {
+        assert_eq!(pool_tvl(Decimal::new(1000, 0), Decimal::new(4000, 0)), Decimal::new(8000, 0));
+        assert_eq!(pool_tvl(Decimal::ZERO, Decimal::new(50, 0)), Decimal::new(50, 0));
+    }
+
+    #[test]
+    fn test_fee_apr_annualizes_window() {
+        // 70 in fees over 7 days on 3650 locked is 10/day, 100% a year
+        assert_eq!(fee_apr(Decimal::new(70, 0), Decimal::new(3650, 0), 7), 1.0);
+        assert_eq!(fee_apr(Decimal::new(70, 0), Decimal::ZERO, 7), 0.0);
+    }
+}
@@ -0,0 +1,180 @@
+//! Token Transfers
+//!
+//! Energy token transfers initiated by a user from their custodial wallet.
+//! The fee is paid by the platform authority when the sponsorship policy
+//! covers the transfer, otherwise by the user's wallet.
+
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Serialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+use crate::services::blockchain::TxOperation;
+use crate::services::fee_sponsorship::{FeeSponsorshipService, SponsorshipRule};
+use crate::services::{BlockchainService, SettlementService};
+use crate::utils::decimal::to_base_units_rounded;
+
+const TOKEN_DECIMALS: u32 = 9;
+
+/// A sent transfer
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TransferReceipt {
+    pub signature: String,
+    pub recipient: String,
+    pub amount_kwh: Decimal,
+    /// Whether the platform paid the fee
+    pub sponsored: bool,
+    pub rule: Option<SponsorshipRule>,
+    pub fee_payer: String,
+    /// Fee charged, when it could be read
+    pub fee_lamports: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct TokenTransferService {
+    db: sqlx::PgPool,
+    blockchain: BlockchainService,
+    settlement: SettlementService,
+    sponsorship: FeeSponsorshipService,
+    energy_token_mint: String,
+}
+
+impl TokenTransferService {
+    pub fn new(
+        db: sqlx::PgPool,
+        blockchain: BlockchainService,
+        settlement: SettlementService,
+        sponsorship: FeeSponsorshipService,
+        energy_token_mint: String,
+    ) -> Self {
+        Self {
+            db,
+            blockchain,
+            settlement,
+            sponsorship,
+            energy_token_mint,
+        }
+    }
+
+    /// Send energy tokens from the user's wallet to `recipient`
+    pub async fn transfer(
+        &self,
+        user_id: Uuid,
+        recipient: &str,
+        amount_kwh: Decimal,
+        session_token: Option<&str>,
+    ) -> Result<TransferReceipt> {
+        let recipient_pubkey = Pubkey::from_str(recipient.trim())
+            .map_err(|_| ApiError::validation_field("recipient", "Invalid Solana wallet address"))?;
+        let amount = to_base_units_rounded(amount_kwh, TOKEN_DECIMALS, RoundingStrategy::ToZero)
+            .map_err(|e| ApiError::validation_field("amount_kwh", e.to_string()))?;
+        if amount == 0 {
+            return Err(ApiError::validation_field("amount_kwh", "Amount must be positive"));
+        }
+
+        let wallet: Option<String> = sqlx::query_scalar("SELECT wallet_address FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?
+            .flatten();
+        let wallet = wallet.ok_or_else(|| ApiError::BadRequest("No wallet connected".to_string()))?;
+        if wallet == recipient_pubkey.to_string() {
+            return Err(ApiError::validation_field("recipient", "Cannot transfer to your own wallet"));
+        }
+
+        let owner = self.settlement.get_user_keypair(&user_id, session_token).await?;
+        if owner.pubkey().to_string() != wallet {
+            return Err(ApiError::Internal(format!("Wallet key of user {} does not match its address", user_id)));
+        }
+        let authority = self
+            .blockchain
+            .get_authority_keypair()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to get authority: {}", e)))?;
+        let mint = BlockchainService::parse_pubkey(&self.energy_token_mint)
+            .map_err(|e| ApiError::Internal(format!("Invalid mint config: {}", e)))?;
+
+        let from = self
+            .blockchain
+            .ensure_token_account_exists(&authority, &owner.pubkey(), &mint)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to prepare sender token account: {}", e)))?;
+        let to = self
+            .blockchain
+            .ensure_token_account_exists(&authority, &recipient_pubkey, &mint)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to prepare recipient token account: {}", e)))?;
+
+        let sponsorship = self
+            .sponsorship
+            .reserve(user_id, &recipient_pubkey.to_string(), amount_kwh, &authority.pubkey().to_string())
+            .await?;
+        let fee_payer = if sponsorship.is_some() { &authority } else { &owner };
+
+        let signature = match self
+            .blockchain
+            .transfer_tokens_as(
+                TxOperation::Transfer,
+                &owner,
+                sponsorship.as_ref().map(|_| &authority),
+                &from,
+                &to,
+                &mint,
+                amount,
+                TOKEN_DECIMALS as u8,
+            )
+            .await
+        {
+            Ok(signature) => signature,
+            Err(e) => {
+                if let Some(sponsorship) = &sponsorship {
+                    self.sponsorship.release(sponsorship.id).await;
+                }
+                return Err(ApiError::BadRequest(format!("Transfer failed: {}", e)));
+            }
+        };
+
+        let fee_lamports = match self.blockchain.get_transaction_fee(&signature).await {
+            Ok(fee) => Some(fee),
+            Err(e) => {
+                warn!("⚠️ Could not read fee of transfer {}: {}", signature, e);
+                None
+            }
+        };
+        let rule = sponsorship.as_ref().map(|sponsorship| {
+            if sponsorship.program_id.is_some() {
+                SponsorshipRule::Program
+            } else {
+                SponsorshipRule::FreeTier
+            }
+        });
+        if let Some(sponsorship) = &sponsorship {
+            self.sponsorship
+                .charge(sponsorship.id, &signature.to_string(), fee_lamports)
+                .await?;
+        }
+
+        info!(
+            "💸 User {} sent {} kWh to {} ({}, fee paid by {})",
+            user_id,
+            amount_kwh,
+            recipient_pubkey,
+            signature,
+            fee_payer.pubkey()
+        );
+        Ok(TransferReceipt {
+            signature: signature.to_string(),
+            recipient: recipient_pubkey.to_string(),
+            amount_kwh,
+            sponsored: sponsorship.is_some(),
+            rule,
+            fee_payer: fee_payer.pubkey().to_string(),
+            fee_lamports,
+        })
+    }
+}
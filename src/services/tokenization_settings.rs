@@ -0,0 +1,160 @@
+//! Tokenization Settings
+//!
+//! Effective tokenization parameters: the environment configuration with
+//! the overrides stored in `tokenization_overrides` applied on top. Updates
+//! are validated as a whole before they are stored, so a change that breaks
+//! a constraint between parameters is rejected instead of half applied.
+//! Every instance reloads the overrides periodically.
+
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::{TokenizationConfig, TokenizationOverrides};
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+
+/// Tokenization parameters in effect
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EffectiveTokenization {
+    pub parameters: TokenizationConfig,
+    /// Overrides applied on top of the environment configuration
+    pub overrides: TokenizationOverrides,
+    /// Names of the overridden parameters
+    pub overridden: Vec<String>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow)]
+struct OverridesRow {
+    overrides: sqlx::types::Json<TokenizationOverrides>,
+    updated_by: Option<Uuid>,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct TokenizationSettings {
+    db: PgPool,
+    audit_logger: AuditLogger,
+    base: Arc<TokenizationConfig>,
+    current: Arc<RwLock<EffectiveTokenization>>,
+}
+
+impl TokenizationSettings {
+    pub fn new(db: PgPool, audit_logger: AuditLogger, base: TokenizationConfig) -> Self {
+        let current = EffectiveTokenization {
+            parameters: base.clone(),
+            overrides: TokenizationOverrides::default(),
+            overridden: Vec::new(),
+            updated_by: None,
+            updated_at: None,
+        };
+        Self {
+            db,
+            audit_logger,
+            base: Arc::new(base),
+            current: Arc::new(RwLock::new(current)),
+        }
+    }
+
+    /// Parameters in effect
+    pub fn current(&self) -> TokenizationConfig {
+        self.current.read().unwrap_or_else(|e| e.into_inner()).parameters.clone()
+    }
+
+    /// Parameters in effect with the overrides behind them
+    pub fn effective(&self) -> EffectiveTokenization {
+        self.current.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Load the stored overrides. Overrides that no longer validate against
+    /// the environment configuration are ignored and the previous parameters
+    /// stay in effect.
+    pub async fn reload(&self) -> Result<()> {
+        let row = sqlx::query_as::<_, OverridesRow>(
+            "SELECT overrides, updated_by, updated_at FROM tokenization_overrides WHERE id",
+        )
+        .fetch_optional(&self.db)
+        .await?;
+
+        let (overrides, updated_by, updated_at) = match row {
+            Some(row) => (row.overrides.0, row.updated_by, Some(row.updated_at)),
+            None => (TokenizationOverrides::default(), None, None),
+        };
+        if overrides == self.effective().overrides {
+            return Ok(());
+        }
+
+        match self.resolve(&overrides) {
+            Ok(parameters) => {
+                info!("🔧 Tokenization overrides applied: {:?}", overrides.fields());
+                self.set(parameters, overrides, updated_by, updated_at);
+            }
+            Err(e) => warn!("⚠️ Ignoring stored tokenization overrides: {}", e),
+        }
+        Ok(())
+    }
+
+    /// Replace the overrides; omitted parameters return to their environment
+    /// value. Rejected when the resulting parameters do not validate.
+    pub async fn update(&self, admin_id: Uuid, overrides: TokenizationOverrides) -> Result<EffectiveTokenization> {
+        let parameters = self.resolve(&overrides)?;
+
+        let row = sqlx::query_as::<_, OverridesRow>(
+            r#"
+            INSERT INTO tokenization_overrides (id, overrides, updated_by, updated_at)
+            VALUES (TRUE, $1, $2, NOW())
+            ON CONFLICT (id) DO UPDATE SET
+                overrides = EXCLUDED.overrides,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = EXCLUDED.updated_at
+            RETURNING overrides, updated_by, updated_at
+            "#,
+        )
+        .bind(sqlx::types::Json(&overrides))
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "tokenization_overrides_updated".to_string(),
+            target_user_id: None,
+            details: serde_json::to_string(&overrides).unwrap_or_default(),
+        });
+        info!("🔧 Tokenization overrides updated by {}: {:?}", admin_id, overrides.fields());
+
+        self.set(parameters, row.overrides.0, row.updated_by, Some(row.updated_at));
+        Ok(self.effective())
+    }
+
+    fn resolve(&self, overrides: &TokenizationOverrides) -> Result<TokenizationConfig> {
+        let parameters = self.base.with_overrides(overrides);
+        parameters
+            .validate()
+            .map_err(|e| ApiError::Validation(e.to_string()))?;
+        Ok(parameters)
+    }
+
+    fn set(
+        &self,
+        parameters: TokenizationConfig,
+        overrides: TokenizationOverrides,
+        updated_by: Option<Uuid>,
+        updated_at: Option<DateTime<Utc>>,
+    ) {
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = EffectiveTokenization {
+            parameters,
+            overridden: overrides.fields(),
+            overrides,
+            updated_by,
+            updated_at,
+        };
+    }
+}
@@ -0,0 +1,770 @@
+//! Admin Trade Operations
+//!
+//! Operators can bust (cancel) an erroneous trade, enter an off-market
+//! trade or roll back a whole epoch whose clearing went wrong. None takes
+//! effect on a single admin's word: each is proposed as
+//! an approval request and executed once the configured number of distinct
+//! admins have signed it (the proposer's signature counts). Any admin may
+//! veto a pending request.
+//!
+//! A bust never deletes history. A completed settlement is reversed with
+//! compensating entries in `token_ledger_adjustments`; a settlement whose
+//! escrow was not yet finalized has its escrow released instead.
+
+mod rollback;
+pub mod types;
+
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool, Row};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::TradeApprovalConfig;
+use crate::error::ApiError;
+use crate::services::dispute::LedgerAdjustment;
+use crate::services::market_clearing::balance::insufficient;
+use crate::services::{AuditEvent, AuditLogger, MarketClearingService, SettlementService};
+
+pub use types::*;
+
+const MAX_REASON_LENGTH: usize = 2000;
+
+const REQUEST_COLUMNS: &str = "id, action_type, payload, reason, status, required_approvals, approvals, \
+    proposed_by, settlement_id, failure_reason, expires_at, executed_at, created_at, updated_at";
+
+#[derive(Clone)]
+pub struct TradeAdminService {
+    db: PgPool,
+    market_clearing: MarketClearingService,
+    settlement: SettlementService,
+    audit: AuditLogger,
+    config: TradeApprovalConfig,
+}
+
+impl TradeAdminService {
+    pub fn new(
+        db: PgPool,
+        market_clearing: MarketClearingService,
+        settlement: SettlementService,
+        audit: AuditLogger,
+        config: TradeApprovalConfig,
+    ) -> Self {
+        Self {
+            db,
+            market_clearing,
+            settlement,
+            audit,
+            config,
+        }
+    }
+
+    /// Propose a bust, manual trade or epoch rollback; the proposer's
+    /// approval is recorded with it
+    pub async fn propose(
+        &self,
+        admin_id: Uuid,
+        action: TradeAction,
+        reason: &str,
+    ) -> Result<TradeApprovalRequest, ApiError> {
+        let reason = reason.trim();
+        if reason.is_empty() || reason.len() > MAX_REASON_LENGTH {
+            return Err(ApiError::validation_field(
+                "reason",
+                format!("Reason must be 1-{} characters", MAX_REASON_LENGTH),
+            ));
+        }
+
+        match &action {
+            TradeAction::TradeBust { settlement_id } => {
+                let status: String = sqlx::query_scalar("SELECT status FROM settlements WHERE id = $1")
+                    .bind(settlement_id)
+                    .fetch_optional(&self.db)
+                    .await?
+                    .ok_or_else(|| ApiError::NotFound("Settlement not found".to_string()))?;
+                ensure_bustable(&status)?;
+            }
+            TradeAction::ManualTrade {
+                buyer_id,
+                seller_id,
+                energy_amount,
+                price_per_kwh,
+            } => {
+                validate_manual_trade(*buyer_id, *seller_id, *energy_amount, *price_per_kwh)?;
+                let found: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE id = ANY($1)")
+                    .bind(vec![*buyer_id, *seller_id])
+                    .fetch_one(&self.db)
+                    .await?;
+                if found != 2 {
+                    return Err(ApiError::NotFound("Buyer or seller not found".to_string()));
+                }
+            }
+            TradeAction::EpochRollback { epoch_id } => {
+                let mut conn = self.db.acquire().await?;
+                rollback::load_target(&mut conn, *epoch_id, false).await?;
+            }
+        }
+
+        let payload = serde_json::to_value(&action)
+            .map_err(|e| ApiError::Internal(format!("Failed to encode trade action: {}", e)))?;
+
+        let mut tx = self.db.begin().await?;
+
+        let request = sqlx::query_as::<_, TradeApprovalRequest>(&format!(
+            r#"
+            INSERT INTO trade_approval_requests
+                (action_type, payload, reason, required_approvals, proposed_by, settlement_id, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            REQUEST_COLUMNS
+        ))
+        .bind(action.as_str())
+        .bind(&payload)
+        .bind(reason)
+        .bind(self.config.required_approvals as i32)
+        .bind(admin_id)
+        .bind(match &action {
+            TradeAction::TradeBust { settlement_id } => Some(*settlement_id),
+            TradeAction::ManualTrade { .. } | TradeAction::EpochRollback { .. } => None,
+        })
+        .bind(Utc::now() + Duration::hours(self.config.ttl_hours))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.constraint() == Some("uq_trade_approval_pending_bust") => {
+                ApiError::Conflict("A bust of this settlement is already awaiting approval".to_string())
+            }
+            sqlx::Error::Database(ref db) if db.constraint() == Some("uq_trade_approval_pending_rollback") => {
+                ApiError::Conflict("A rollback of this epoch is already awaiting approval".to_string())
+            }
+            other => ApiError::Database(other),
+        })?;
+
+        info!(
+            "🛂 Admin {} proposed {} ({} approvals required): {}",
+            admin_id, request.action_type, request.required_approvals, request.id
+        );
+        self.audit.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: format!("{}_proposed", action.as_str()),
+            target_user_id: None,
+            details: format!("request {}: {}", request.id, reason),
+        });
+
+        let request = self
+            .sign(&mut tx, request, admin_id, ApprovalDecision::Approve, None)
+            .await?;
+        tx.commit().await?;
+        self.publish_reopened(&request).await;
+
+        Ok(request)
+    }
+
+    /// Approve or veto a pending request; the approval that reaches quorum executes it
+    pub async fn decide(
+        &self,
+        admin_id: Uuid,
+        request_id: Uuid,
+        decision: ApprovalDecision,
+        comment: Option<&str>,
+    ) -> Result<TradeApprovalRequest, ApiError> {
+        let mut tx = self.db.begin().await?;
+
+        let request = sqlx::query_as::<_, TradeApprovalRequest>(&format!(
+            "SELECT {} FROM trade_approval_requests WHERE id = $1 FOR UPDATE",
+            REQUEST_COLUMNS
+        ))
+        .bind(request_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Approval request not found".to_string()))?;
+
+        if request.status != ApprovalStatus::Pending.as_str() {
+            return Err(ApiError::Conflict(format!("Request is already {}", request.status)));
+        }
+        if request.expires_at <= Utc::now() {
+            set_status(&mut tx, request_id, ApprovalStatus::Expired, None).await?;
+            tx.commit().await?;
+            return Err(ApiError::Conflict("Request has expired".to_string()));
+        }
+
+        let request = self
+            .sign(&mut tx, request, admin_id, decision, comment.map(str::trim).filter(|c| !c.is_empty()))
+            .await?;
+        tx.commit().await?;
+        self.publish_reopened(&request).await;
+
+        Ok(request)
+    }
+
+    /// Record a signature and execute the action once quorum is reached
+    async fn sign(
+        &self,
+        tx: &mut PgConnection,
+        request: TradeApprovalRequest,
+        admin_id: Uuid,
+        decision: ApprovalDecision,
+        comment: Option<&str>,
+    ) -> Result<TradeApprovalRequest, ApiError> {
+        sqlx::query(
+            "INSERT INTO trade_approval_votes (request_id, admin_id, decision, comment) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(request.id)
+        .bind(admin_id)
+        .bind(decision.as_str())
+        .bind(comment)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.constraint() == Some("uq_trade_approval_votes_admin") => {
+                ApiError::Conflict("You have already signed this request".to_string())
+            }
+            other => ApiError::Database(other),
+        })?;
+
+        if decision == ApprovalDecision::Reject {
+            let rejected = set_status(tx, request.id, ApprovalStatus::Rejected, None).await?;
+            info!("🛂 Admin {} vetoed {} request {}", admin_id, request.action_type, request.id);
+            self.audit_decision(admin_id, &rejected);
+            return Ok(rejected);
+        }
+
+        let approvals: i32 = sqlx::query_scalar(
+            r#"
+            UPDATE trade_approval_requests SET approvals = approvals + 1, updated_at = NOW()
+            WHERE id = $1
+            RETURNING approvals
+            "#,
+        )
+        .bind(request.id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if !quorum_reached(approvals, request.required_approvals) {
+            return get_request(tx, request.id).await;
+        }
+
+        let action: TradeAction = serde_json::from_value(request.payload.clone())
+            .map_err(|e| ApiError::Internal(format!("Invalid trade action payload: {}", e)))?;
+
+        // Execute in a savepoint so a failed execution still records the approval
+        let mut savepoint = sqlx::Connection::begin(&mut *tx).await?;
+        let outcome = match &action {
+            TradeAction::TradeBust { settlement_id } => {
+                self.bust_settlement(&mut savepoint, request.id, *settlement_id, &request.reason, admin_id)
+                    .await
+                    .map(Some)
+            }
+            TradeAction::ManualTrade {
+                buyer_id,
+                seller_id,
+                energy_amount,
+                price_per_kwh,
+            } => {
+                self.enter_manual_trade(&mut savepoint, *buyer_id, *seller_id, *energy_amount, *price_per_kwh)
+                    .await
+                    .map(Some)
+            }
+            TradeAction::EpochRollback { epoch_id } => self
+                .rollback_epoch(&mut savepoint, request.id, *epoch_id, &request.reason, admin_id)
+                .await
+                .map(|_| None),
+        };
+
+        let executed = match outcome {
+            Ok(settlement_id) => {
+                savepoint.commit().await?;
+                let executed = sqlx::query_as::<_, TradeApprovalRequest>(&format!(
+                    r#"
+                    UPDATE trade_approval_requests
+                    SET status = 'executed', settlement_id = $2, executed_at = NOW(), updated_at = NOW()
+                    WHERE id = $1
+                    RETURNING {}
+                    "#,
+                    REQUEST_COLUMNS
+                ))
+                .bind(request.id)
+                .bind(settlement_id)
+                .fetch_one(&mut *tx)
+                .await?;
+                info!(
+                    "✅ Executed {} request {}{}",
+                    executed.action_type,
+                    executed.id,
+                    settlement_id
+                        .map(|id| format!(" (settlement {})", id))
+                        .unwrap_or_default()
+                );
+                executed
+            }
+            Err(e) => {
+                savepoint.rollback().await?;
+                warn!("⚠️ Approved {} request {} failed: {}", request.action_type, request.id, e);
+                set_status(tx, request.id, ApprovalStatus::Failed, Some(&e.to_string())).await?
+            }
+        };
+
+        self.audit_decision(admin_id, &executed);
+        Ok(executed)
+    }
+
+    fn audit_decision(&self, admin_id: Uuid, request: &TradeApprovalRequest) {
+        self.audit.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: format!("{}_{}", request.action_type, request.status),
+            target_user_id: None,
+            details: format!(
+                "request {} ({}/{} approvals){}",
+                request.id,
+                request.approvals,
+                request.required_approvals,
+                request
+                    .settlement_id
+                    .map(|id| format!(", settlement {}", id))
+                    .unwrap_or_default()
+            ),
+        });
+    }
+
+    /// Reverse a settlement. Returns the busted settlement's ID.
+    async fn bust_settlement(
+        &self,
+        tx: &mut PgConnection,
+        request_id: Uuid,
+        settlement_id: Uuid,
+        reason: &str,
+        admin_id: Uuid,
+    ) -> Result<Uuid, ApiError> {
+        let row = sqlx::query(
+            r#"
+            SELECT buyer_id, seller_id, energy_amount, total_amount, net_amount, status
+            FROM settlements WHERE id = $1 FOR UPDATE
+            "#,
+        )
+        .bind(settlement_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Settlement not found".to_string()))?;
+
+        let buyer_id: Uuid = row.get("buyer_id");
+        let seller_id: Uuid = row.get("seller_id");
+        let energy: Decimal = row.get("energy_amount");
+        let total: Decimal = row.get("total_amount");
+        let net: Decimal = row.get("net_amount");
+        let status: String = row.get("status");
+        ensure_bustable(&status)?;
+
+        if status == "completed" {
+            let reason = format!("Trade bust {}: {}", request_id, reason);
+            for entry in reversal_entries(total, net, energy) {
+                let user_id = match entry.party {
+                    Party::Buyer => Some(buyer_id),
+                    Party::Seller => Some(seller_id),
+                    Party::Platform => None,
+                };
+                // Energy holdings follow completed settlements, so only currency moves balances
+                if let (Some(user_id), "currency") = (user_id, entry.asset) {
+                    sqlx::query("UPDATE users SET balance = COALESCE(balance, 0) + $1 WHERE id = $2")
+                        .bind(entry.amount)
+                        .bind(user_id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                sqlx::query(
+                    r#"
+                    INSERT INTO token_ledger_adjustments
+                        (settlement_id, approval_request_id, user_id, asset, amount, reason, created_by)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    "#,
+                )
+                .bind(settlement_id)
+                .bind(request_id)
+                .bind(user_id)
+                .bind(entry.asset)
+                .bind(entry.amount)
+                .bind(&reason)
+                .bind(admin_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+        } else {
+            // Escrow was never finalized: hand the locked funds and energy back
+            sqlx::query(
+                "UPDATE users SET balance = COALESCE(balance, 0) + $1, locked_amount = locked_amount - $1 WHERE id = $2",
+            )
+            .bind(total)
+            .bind(buyer_id)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query("UPDATE users SET locked_energy = locked_energy - $1 WHERE id = $2")
+                .bind(energy)
+                .bind(seller_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(
+            "UPDATE settlements SET status = 'busted', busted_at = NOW(), updated_at = NOW() WHERE id = $1",
+        )
+        .bind(settlement_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE order_matches SET status = 'busted', updated_at = NOW() WHERE settlement_id = $1")
+            .bind(settlement_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // Certificates backed by the busted energy are no longer valid
+        sqlx::query(
+            "UPDATE erc_certificates SET dispute_hold = 'revoked', updated_at = NOW() WHERE settlement_id = $1",
+        )
+        .bind(settlement_id)
+        .execute(&mut *tx)
+        .await?;
+
+        info!(
+            "💥 Busted {} settlement {} ({} kWh, {} total)",
+            status, settlement_id, energy, total
+        );
+        Ok(settlement_id)
+    }
+
+    /// Book an off-market trade as filled orders, a match and a completed
+    /// settlement. Returns the new settlement's ID.
+    async fn enter_manual_trade(
+        &self,
+        tx: &mut PgConnection,
+        buyer_id: Uuid,
+        seller_id: Uuid,
+        energy_amount: Decimal,
+        price_per_kwh: Decimal,
+    ) -> Result<Uuid, ApiError> {
+        validate_manual_trade(buyer_id, seller_id, energy_amount, price_per_kwh)?;
+
+        let epoch = self
+            .market_clearing
+            .get_or_create_epoch(Utc::now())
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to resolve market epoch: {}", e)))?;
+
+        let total = (energy_amount * price_per_kwh).round_dp(8);
+        let fee = (total * self.settlement.fee_rate()).round_dp(8);
+        let net = total - fee;
+
+        let debited = sqlx::query(
+            "UPDATE users SET balance = balance - $1 WHERE id = $2 AND COALESCE(balance, 0) >= $1",
+        )
+        .bind(total)
+        .bind(buyer_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if debited == 0 {
+            let available: Option<Decimal> = sqlx::query_scalar("SELECT balance FROM users WHERE id = $1")
+                .bind(buyer_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .flatten();
+            return Err(insufficient("buyer balance", total, available.unwrap_or(Decimal::ZERO)));
+        }
+
+        sqlx::query("UPDATE users SET balance = COALESCE(balance, 0) + $1 WHERE id = $2")
+            .bind(net)
+            .bind(seller_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut order_ids = Vec::with_capacity(2);
+        for (user_id, side) in [(buyer_id, "buy"), (seller_id, "sell")] {
+            let order_id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO trading_orders (
+                    user_id, order_type, side, energy_amount, price_per_kwh,
+                    filled_amount, status, epoch_id, filled_at
+                ) VALUES ($1, 'limit'::order_type, $2::order_side, $3, $4, $3, 'filled'::order_status, $5, NOW())
+                RETURNING id
+                "#,
+            )
+            .bind(user_id)
+            .bind(side)
+            .bind(energy_amount)
+            .bind(price_per_kwh)
+            .bind(epoch.id)
+            .fetch_one(&mut *tx)
+            .await?;
+            order_ids.push(order_id);
+        }
+
+        let settlement_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO settlements (
+                epoch_id, buyer_id, seller_id, buy_order_id, sell_order_id, energy_amount,
+                price_per_kwh, total_amount, fee_amount, net_amount, status, origin, processed_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 'completed', 'manual', NOW())
+            RETURNING id
+            "#,
+        )
+        .bind(epoch.id)
+        .bind(buyer_id)
+        .bind(seller_id)
+        .bind(order_ids[0])
+        .bind(order_ids[1])
+        .bind(energy_amount)
+        .bind(price_per_kwh)
+        .bind(total)
+        .bind(fee)
+        .bind(net)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.market_clearing
+            .fx_rates()
+            .stamp_settlement(&mut *tx, settlement_id)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO order_matches (
+                epoch_id, buy_order_id, sell_order_id, matched_amount, match_price, status, settlement_id, market_id
+            ) VALUES ($1, $2, $3, $4, $5, 'settled', $6, (SELECT market_id FROM trading_orders WHERE id = $2))
+            "#,
+        )
+        .bind(epoch.id)
+        .bind(order_ids[0])
+        .bind(order_ids[1])
+        .bind(energy_amount)
+        .bind(price_per_kwh)
+        .bind(settlement_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if fee > Decimal::ZERO {
+            sqlx::query(
+                "INSERT INTO platform_revenue (settlement_id, amount, revenue_type, description) VALUES ($1, $2, 'platform_fee', $3)",
+            )
+            .bind(settlement_id)
+            .bind(fee)
+            .bind(format!("Platform fee for manual settlement {}", settlement_id))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        info!(
+            "✍️ Entered manual trade {}: {} kWh at {} (buyer: {}, seller: {})",
+            settlement_id, energy_amount, price_per_kwh, buyer_id, seller_id
+        );
+        Ok(settlement_id)
+    }
+
+    /// Requests, newest first
+    pub async fn list(
+        &self,
+        status: Option<ApprovalStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<TradeApprovalRequest>, ApiError> {
+        Ok(sqlx::query_as::<_, TradeApprovalRequest>(&format!(
+            r#"
+            SELECT {} FROM trade_approval_requests
+            WHERE ($1::TEXT IS NULL OR status = $1)
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            REQUEST_COLUMNS
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn detail(&self, request_id: Uuid) -> Result<TradeApprovalDetail, ApiError> {
+        let mut conn = self.db.acquire().await?;
+        let request = get_request(&mut *conn, request_id).await?;
+
+        let votes = sqlx::query_as::<_, ApprovalVote>(
+            r#"
+            SELECT id, admin_id, decision, comment, created_at
+            FROM trade_approval_votes
+            WHERE request_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(request_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let adjustments = sqlx::query_as::<_, LedgerAdjustment>(
+            r#"
+            SELECT id, user_id, asset, amount, reason, created_at
+            FROM token_ledger_adjustments
+            WHERE approval_request_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(request_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let rollback = sqlx::query_as::<_, EpochRollback>(
+            r#"
+            SELECT id, epoch_id, approval_request_id, reopened_in_epoch_id, cancelled_settlement_ids,
+                   reopened_order_ids, narrative, reason, executed_by, created_at
+            FROM epoch_rollbacks
+            WHERE approval_request_id = $1
+            "#,
+        )
+        .bind(request_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(TradeApprovalDetail {
+            request,
+            votes,
+            adjustments,
+            rollback,
+        })
+    }
+}
+
+/// Whether enough distinct admins have approved
+pub fn quorum_reached(approvals: i32, required: i32) -> bool {
+    approvals >= required.max(1)
+}
+
+/// Settlements can be busted unless already busted or mid-transfer on chain
+fn ensure_bustable(status: &str) -> Result<(), ApiError> {
+    match status {
+        "completed" | "pending" | "failed" => Ok(()),
+        "processing" => Err(ApiError::Conflict(
+            "Settlement is being transferred on chain; retry once it completes or fails".to_string(),
+        )),
+        other => Err(ApiError::Conflict(format!("Settlement is {} and cannot be busted", other))),
+    }
+}
+
+/// Terms of a manual trade must describe a real trade between two users
+pub fn validate_manual_trade(
+    buyer_id: Uuid,
+    seller_id: Uuid,
+    energy_amount: Decimal,
+    price_per_kwh: Decimal,
+) -> Result<(), ApiError> {
+    if buyer_id == seller_id {
+        return Err(ApiError::validation_field("seller_id", "Buyer and seller must differ"));
+    }
+    if energy_amount <= Decimal::ZERO {
+        return Err(ApiError::validation_field("energy_amount", "Energy amount must be positive"));
+    }
+    if price_per_kwh <= Decimal::ZERO {
+        return Err(ApiError::validation_field("price_per_kwh", "Price per kWh must be positive"));
+    }
+    Ok(())
+}
+
+/// Compensating entries that undo a completed settlement: the buyer is
+/// refunded what they paid, the seller returns their net proceeds, the
+/// platform returns its fee, and the energy goes back to the seller.
+pub fn reversal_entries(total: Decimal, net: Decimal, energy: Decimal) -> Vec<ReversalEntry> {
+    let mut entries = vec![
+        ReversalEntry { party: Party::Buyer, asset: "currency", amount: total },
+        ReversalEntry { party: Party::Seller, asset: "currency", amount: -net },
+    ];
+    if total != net {
+        entries.push(ReversalEntry { party: Party::Platform, asset: "currency", amount: net - total });
+    }
+    entries.push(ReversalEntry { party: Party::Seller, asset: "energy", amount: energy });
+    entries.push(ReversalEntry { party: Party::Buyer, asset: "energy", amount: -energy });
+    entries
+}
+
+async fn get_request(conn: &mut PgConnection, request_id: Uuid) -> Result<TradeApprovalRequest, ApiError> {
+    sqlx::query_as::<_, TradeApprovalRequest>(&format!(
+        "SELECT {} FROM trade_approval_requests WHERE id = $1",
+        REQUEST_COLUMNS
+    ))
+    .bind(request_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Approval request not found".to_string()))
+}
+
+async fn set_status(
+    conn: &mut PgConnection,
+    request_id: Uuid,
+    status: ApprovalStatus,
+    failure_reason: Option<&str>,
+) -> Result<TradeApprovalRequest, ApiError> {
+    Ok(sqlx::query_as::<_, TradeApprovalRequest>(&format!(
+        r#"
+        UPDATE trade_approval_requests
+        SET status = $2, failure_reason = $3, updated_at = NOW()
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        REQUEST_COLUMNS
+    ))
+    .bind(request_id)
+    .bind(status.as_str())
+    .bind(failure_reason)
+    .fetch_one(&mut *conn)
+    .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(value: i64) -> Decimal {
+        Decimal::from(value)
+    }
+
+    #[test]
+    fn test_reversal_entries_balance_out() {
+        // 10 kWh for 50, seller netted 49 after a 1 fee
+        let entries = reversal_entries(d(50), d(49), d(10));
+        let currency: Decimal = entries.iter().filter(|e| e.asset == "currency").map(|e| e.amount).sum();
+        let energy: Decimal = entries.iter().filter(|e| e.asset == "energy").map(|e| e.amount).sum();
+        assert_eq!(currency, Decimal::ZERO);
+        assert_eq!(energy, Decimal::ZERO);
+        assert!(entries.contains(&ReversalEntry { party: Party::Platform, asset: "currency", amount: d(-1) }));
+    }
+
+    #[test]
+    fn test_reversal_without_fee_has_no_platform_entry() {
+        let entries = reversal_entries(d(50), d(50), d(10));
+        assert!(entries.iter().all(|e| e.party != Party::Platform));
+    }
+
+    #[test]
+    fn test_quorum() {
+        assert!(!quorum_reached(1, 2));
+        assert!(quorum_reached(2, 2));
+        assert!(quorum_reached(1, 0));
+    }
+
+    #[test]
+    fn test_manual_trade_validation() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert!(validate_manual_trade(a, b, d(10), d(4)).is_ok());
+        assert!(validate_manual_trade(a, a, d(10), d(4)).is_err());
+        assert!(validate_manual_trade(a, b, Decimal::ZERO, d(4)).is_err());
+        assert!(validate_manual_trade(a, b, d(10), d(-1)).is_err());
+    }
+
+    #[test]
+    fn test_action_payload_round_trip() {
+        let action = TradeAction::TradeBust { settlement_id: Uuid::new_v4() };
+        let payload = serde_json::to_value(&action).unwrap();
+        assert_eq!(payload["action"], "trade_bust");
+        assert_eq!(serde_json::from_value::<TradeAction>(payload).unwrap(), action);
+
+        let action = TradeAction::EpochRollback { epoch_id: Uuid::new_v4() };
+        let payload = serde_json::to_value(&action).unwrap();
+        assert_eq!(payload["action"], "epoch_rollback");
+        assert!(payload["epoch_id"].is_string());
+        assert_eq!(serde_json::from_value::<TradeAction>(payload).unwrap(), action);
+    }
+}
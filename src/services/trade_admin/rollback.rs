@@ -0,0 +1,556 @@
+//! Epoch rollback
+//!
+//! Undoes a clearing run that produced bad results (an oracle failure, a
+//! matching bug) while its settlements are still waiting to be submitted.
+//! The epoch's settlements are cancelled and taken off the settlement queue,
+//! each order's fill state is restored from the last journal event before
+//! the clearing run, and the orders are reopened in the next epoch. Escrow
+//! stays locked since no settlement moved it; remainders the run cancelled
+//! as dust are locked again. Every step is recorded in `epoch_rollbacks`.
+//!
+//! Only an untouched clearing can be rolled back. Once a settlement was
+//! submitted or busted, its escrow was released on-chain, or an order
+//! changed after clearing, the trades have to be busted one by one instead.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, Postgres, Row, Transaction};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::{TradeAdminService, TradeApprovalRequest};
+use crate::error::ApiError;
+use crate::services::order_events::{self, NewOrderEvent, OrderEventType};
+use crate::services::AuditEvent;
+
+/// A cleared epoch with all its market settlements still pending
+pub(super) struct RollbackTarget {
+    pub epoch_number: i64,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub clearing_started_at: DateTime<Utc>,
+    pub cleared_at: DateTime<Utc>,
+    pub clearing_price: Option<Decimal>,
+    pub total_volume: Option<Decimal>,
+    pub settlement_ids: Vec<Uuid>,
+}
+
+/// Order touched by the clearing run
+struct AffectedOrder {
+    id: Uuid,
+    user_id: Uuid,
+    side: String,
+    status: String,
+    energy_amount: Decimal,
+    filled_amount: Decimal,
+}
+
+/// Load an epoch and check it can be rolled back; `lock` takes row locks
+/// on the epoch and its settlements for execution
+pub(super) async fn load_target(
+    conn: &mut PgConnection,
+    epoch_id: Uuid,
+    lock: bool,
+) -> Result<RollbackTarget, ApiError> {
+    let lock_clause = if lock { "FOR UPDATE" } else { "" };
+
+    let row = sqlx::query(&format!(
+        r#"
+        SELECT epoch_number, start_time, end_time, clearing_started_at, cleared_at, rolled_back_at,
+               clearing_price, total_volume
+        FROM market_epochs WHERE id = $1
+        {}
+        "#,
+        lock_clause
+    ))
+    .bind(epoch_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Epoch not found".to_string()))?;
+
+    if row.get::<Option<DateTime<Utc>>, _>("rolled_back_at").is_some() {
+        return Err(ApiError::Conflict("Epoch has already been rolled back".to_string()));
+    }
+    let (Some(clearing_started_at), Some(cleared_at)) = (
+        row.get::<Option<DateTime<Utc>>, _>("clearing_started_at"),
+        row.get::<Option<DateTime<Utc>>, _>("cleared_at"),
+    ) else {
+        return Err(ApiError::Conflict(
+            "Epoch has not been cleared by the clearing scheduler, so its fills cannot be traced in the order journal"
+                .to_string(),
+        ));
+    };
+
+    let settlements = sqlx::query(&format!(
+        "SELECT id, status, escrow_release_started_at, escrow_release_signatures \
+         FROM settlements WHERE epoch_id = $1 AND origin = 'market' {}",
+        lock_clause
+    ))
+    .bind(epoch_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    let statuses: Vec<String> = settlements.iter().map(|r| r.get("status")).collect();
+    ensure_unsubmitted(&statuses)?;
+    let releases: Vec<EscrowRelease> = settlements
+        .iter()
+        .filter(|r| r.get::<Option<DateTime<Utc>>, _>("escrow_release_started_at").is_some())
+        .map(|r| EscrowRelease {
+            settlement_id: r.get("id"),
+            signatures: r.get("escrow_release_signatures"),
+        })
+        .collect();
+    ensure_escrow_unreleased(&releases)?;
+
+    Ok(RollbackTarget {
+        epoch_number: row.get("epoch_number"),
+        start_time: row.get("start_time"),
+        end_time: row.get("end_time"),
+        clearing_started_at,
+        cleared_at,
+        clearing_price: row.get("clearing_price"),
+        total_volume: row.get("total_volume"),
+        settlement_ids: settlements.iter().map(|r| r.get("id")).collect(),
+    })
+}
+
+impl TradeAdminService {
+    /// Cancel a cleared epoch's settlements and reopen its orders in the
+    /// next epoch with the fill state they had before clearing
+    pub(super) async fn rollback_epoch(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        request_id: Uuid,
+        epoch_id: Uuid,
+        reason: &str,
+        admin_id: Uuid,
+    ) -> Result<(), ApiError> {
+        let target = load_target(&mut **tx, epoch_id, true).await?;
+        let mut narrative = vec![format!(
+            "Epoch {} ({} to {}) cleared at {} with clearing price {} and volume {} kWh",
+            target.epoch_number,
+            target.start_time.to_rfc3339(),
+            target.end_time.to_rfc3339(),
+            target.cleared_at.to_rfc3339(),
+            target
+                .clearing_price
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+            target.total_volume.unwrap_or(Decimal::ZERO)
+        )];
+
+        // Orders the clearing run filled or cancelled as dust, per the journal
+        let orders: Vec<AffectedOrder> = sqlx::query(
+            r#"
+            SELECT o.id, o.user_id, o.side::text AS side, o.status::text AS status,
+                   o.energy_amount, COALESCE(o.filled_amount, 0) AS filled_amount
+            FROM trading_orders o
+            WHERE o.epoch_id = $1
+              AND (EXISTS (
+                       SELECT 1 FROM order_events e
+                       WHERE e.order_id = o.id
+                         AND e.event_type IN ('partially_filled', 'filled')
+                         AND e.created_at BETWEEN $2 AND $3
+                   )
+                   OR EXISTS (
+                       SELECT 1 FROM order_dust d
+                       WHERE d.order_id = o.id AND d.created_at BETWEEN $2 AND $3
+                   ))
+            ORDER BY o.created_at
+            FOR UPDATE
+            "#,
+        )
+        .bind(epoch_id)
+        .bind(target.clearing_started_at)
+        .bind(target.cleared_at)
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|row| AffectedOrder {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            side: row.get("side"),
+            status: row.get("status"),
+            energy_amount: row.get("energy_amount"),
+            filled_amount: row.get("filled_amount"),
+        })
+        .collect();
+        let order_ids: Vec<Uuid> = orders.iter().map(|o| o.id).collect();
+
+        let changed: i64 = sqlx::query_scalar(
+            "SELECT COUNT(DISTINCT order_id) FROM order_events WHERE order_id = ANY($1) AND created_at > $2",
+        )
+        .bind(&order_ids)
+        .bind(target.cleared_at)
+        .fetch_one(&mut **tx)
+        .await?;
+        if changed > 0 {
+            return Err(ApiError::Conflict(format!(
+                "{} of the epoch's orders changed after clearing; bust their trades individually instead",
+                changed
+            )));
+        }
+
+        // Fill state each order had when the clearing run started
+        let filled_before: HashMap<Uuid, Decimal> = sqlx::query_as::<_, (Uuid, Decimal)>(
+            r#"
+            SELECT DISTINCT ON (order_id) order_id, cumulative_filled
+            FROM order_events
+            WHERE order_id = ANY($1) AND created_at < $2 AND cumulative_filled IS NOT NULL
+            ORDER BY order_id, created_at DESC, id DESC
+            "#,
+        )
+        .bind(&order_ids)
+        .bind(target.clearing_started_at)
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let next = self
+            .market_clearing
+            .get_or_create_epoch(Utc::now().max(target.end_time))
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to resolve the epoch to reopen orders in: {}", e)))?;
+
+        let cancelled = sqlx::query(
+            "UPDATE settlements SET status = 'rolled_back', updated_at = NOW() WHERE id = ANY($1)",
+        )
+        .bind(&target.settlement_ids)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+        let dequeued = sqlx::query("DELETE FROM settlement_queue WHERE settlement_id = ANY($1)")
+            .bind(&target.settlement_ids)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+        narrative.push(format!(
+            "Cancelled {} pending settlements ({} taken off the settlement queue)",
+            cancelled, dequeued
+        ));
+
+        let matches = sqlx::query(
+            "UPDATE order_matches SET status = 'rolled_back', updated_at = NOW() WHERE epoch_id = $1 AND status <> 'busted'",
+        )
+        .bind(epoch_id)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+        let revoked = sqlx::query(
+            "UPDATE erc_certificates SET dispute_hold = 'revoked', updated_at = NOW() WHERE settlement_id = ANY($1)",
+        )
+        .bind(&target.settlement_ids)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+        narrative.push(format!(
+            "Marked {} matches rolled back and revoked {} certificates issued against them",
+            matches, revoked
+        ));
+
+        // Dust cancellation released the remainder's escrow; take it back
+        let dust: Vec<(Uuid, Uuid, String, Decimal)> = sqlx::query_as(
+            r#"
+            DELETE FROM order_dust
+            WHERE order_id = ANY($1) AND created_at BETWEEN $2 AND $3
+            RETURNING order_id, user_id, side, amount_kwh
+            "#,
+        )
+        .bind(&order_ids)
+        .bind(target.clearing_started_at)
+        .bind(target.cleared_at)
+        .fetch_all(&mut **tx)
+        .await?;
+        if !dust.is_empty() {
+            self.relock_dust(tx, &dust).await?;
+            narrative.push(format!(
+                "Locked the escrow of {} orders cancelled as dust again",
+                dust.len()
+            ));
+        }
+
+        let event_reason = format!("Epoch {} rolled back: {}", target.epoch_number, reason);
+        let mut statuses = Vec::with_capacity(orders.len());
+        let mut filled = Vec::with_capacity(orders.len());
+        let mut events = Vec::with_capacity(orders.len());
+        for order in &orders {
+            let before = filled_before.get(&order.id).copied().unwrap_or(Decimal::ZERO);
+            let status = reopened_status(before);
+            narrative.push(format!(
+                "Order {} ({} {} kWh): {} with {} kWh filled, reopened {} with {} kWh filled",
+                order.id, order.side, order.energy_amount, order.status, order.filled_amount, status, before
+            ));
+            statuses.push(status);
+            filled.push(before);
+            events.push(
+                NewOrderEvent::new(order.id, order.user_id, OrderEventType::Reopened)
+                    .with_quantities(before, order.energy_amount - before)
+                    .with_reason(event_reason.clone()),
+            );
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE trading_orders o
+            SET filled_amount = r.filled,
+                status = r.status::order_status,
+                epoch_id = $4,
+                filled_at = NULL,
+                updated_at = NOW()
+            FROM UNNEST($1::uuid[], $2::numeric[], $3::text[]) AS r(id, filled, status)
+            WHERE o.id = r.id
+            "#,
+        )
+        .bind(&order_ids)
+        .bind(&filled)
+        .bind(&statuses)
+        .bind(next.id)
+        .execute(&mut **tx)
+        .await?;
+        order_events::insert_batch(tx, &events).await?;
+        narrative.push(format!(
+            "Reopened {} orders in epoch {}",
+            orders.len(),
+            next.epoch_number
+        ));
+
+        sqlx::query(
+            r#"
+            UPDATE market_epochs
+            SET rolled_back_at = NOW(), clearing_price = NULL, total_volume = 0, matched_orders = 0, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(epoch_id)
+        .execute(&mut **tx)
+        .await?;
+        sqlx::query("DELETE FROM clearing_price_index WHERE epoch_id = $1")
+            .bind(epoch_id)
+            .execute(&mut **tx)
+            .await?;
+        narrative.push(format!(
+            "Cleared the epoch's clearing price and volume and removed it from the clearing price index: {}",
+            reason
+        ));
+
+        sqlx::query(
+            r#"
+            INSERT INTO epoch_rollbacks (
+                epoch_id, approval_request_id, reopened_in_epoch_id, cancelled_settlement_ids,
+                reopened_order_ids, narrative, reason, executed_by
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(epoch_id)
+        .bind(request_id)
+        .bind(next.id)
+        .bind(&target.settlement_ids)
+        .bind(&order_ids)
+        .bind(serde_json::json!(narrative))
+        .bind(reason)
+        .bind(admin_id)
+        .execute(&mut **tx)
+        .await?;
+
+        self.audit.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "epoch_rolled_back".to_string(),
+            target_user_id: None,
+            details: format!("request {}: {}", request_id, narrative.join("; ")),
+        });
+
+        info!(
+            "⏪ Rolled back epoch {}: {} settlements cancelled, {} orders reopened in epoch {}",
+            target.epoch_number,
+            cancelled,
+            orders.len(),
+            next.epoch_number
+        );
+        Ok(())
+    }
+
+    /// Lock the escrow dust cancellation released: currency of buy orders,
+    /// energy of sell orders
+    async fn relock_dust(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        dust: &[(Uuid, Uuid, String, Decimal)],
+    ) -> Result<(), ApiError> {
+        let order_ids: Vec<Uuid> = dust.iter().map(|d| d.0).collect();
+        let amounts: Vec<Decimal> = dust.iter().map(|d| d.3).collect();
+
+        let owners: i64 = sqlx::query_scalar(
+            "SELECT COUNT(DISTINCT user_id) FROM trading_orders WHERE id = ANY($1)",
+        )
+        .bind(&order_ids)
+        .fetch_one(&mut **tx)
+        .await?;
+        let relocked = sqlx::query(
+            r#"
+            WITH amounts AS (
+                SELECT o.user_id,
+                       SUM(CASE WHEN o.side = 'buy' THEN d.amount * COALESCE(o.price_per_kwh, 0) ELSE 0 END) AS currency,
+                       SUM(CASE WHEN o.side = 'sell' THEN d.amount ELSE 0 END) AS energy
+                FROM UNNEST($1::uuid[], $2::numeric[]) AS d(order_id, amount)
+                JOIN trading_orders o ON o.id = d.order_id
+                GROUP BY o.user_id
+            )
+            UPDATE users u
+            SET balance = COALESCE(u.balance, 0) - a.currency,
+                locked_amount = u.locked_amount + a.currency,
+                locked_energy = u.locked_energy + a.energy
+            FROM amounts a
+            WHERE u.id = a.user_id AND COALESCE(u.balance, 0) >= a.currency
+            "#,
+        )
+        .bind(&order_ids)
+        .bind(&amounts)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+        if relocked as i64 != owners {
+            return Err(ApiError::Conflict(
+                "A participant has already spent the escrow released by a dust cancellation in this epoch"
+                    .to_string(),
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE escrow_records
+            SET status = 'locked', description = 'Relocked by epoch rollback', updated_at = NOW()
+            WHERE order_id = ANY($1) AND status = 'released' AND description LIKE 'Dust cancelled%'
+            "#,
+        )
+        .bind(&order_ids)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Push the reopened events of an executed rollback to order watchers
+    /// and owners; they are journalled inside the approval transaction and
+    /// can only be announced once it has committed
+    pub(super) async fn publish_reopened(&self, request: &TradeApprovalRequest) {
+        if request.action_type != "epoch_rollback" || request.status != "executed" {
+            return;
+        }
+
+        let events = sqlx::query(
+            r#"
+            SELECT DISTINCT ON (e.order_id)
+                   e.order_id, e.user_id, e.cumulative_filled, e.remaining, e.reason, e.created_at
+            FROM epoch_rollbacks r
+            JOIN order_events e ON e.order_id = ANY(r.reopened_order_ids) AND e.event_type = 'reopened'
+            WHERE r.approval_request_id = $1
+            ORDER BY e.order_id, e.created_at DESC
+            "#,
+        )
+        .bind(request.id)
+        .fetch_all(&self.db)
+        .await;
+
+        match events {
+            Ok(rows) => {
+                for row in rows {
+                    let mut event = NewOrderEvent::new(row.get("order_id"), row.get("user_id"), OrderEventType::Reopened);
+                    event.cumulative_filled = row.get("cumulative_filled");
+                    event.remaining = row.get("remaining");
+                    event.reason = row.get("reason");
+                    order_events::publish(event, row.get("created_at")).await;
+                }
+            }
+            Err(e) => warn!("Failed to announce reopened orders of rollback {}: {}", request.id, e),
+        }
+    }
+}
+
+/// All of an epoch's market settlements must still be pending
+fn ensure_unsubmitted(statuses: &[String]) -> Result<(), ApiError> {
+    let submitted = statuses.iter().filter(|s| s.as_str() != "pending").count();
+    if submitted > 0 {
+        return Err(ApiError::Conflict(format!(
+            "{} of the epoch's {} settlements are no longer pending; bust them individually instead",
+            submitted,
+            statuses.len()
+        )));
+    }
+    Ok(())
+}
+
+/// A settlement whose escrow release was sent on-chain
+struct EscrowRelease {
+    settlement_id: Uuid,
+    /// Confirmed transfers; empty when the release failed or is unconfirmed
+    signatures: Vec<String>,
+}
+
+/// Reopening orders would re-lock escrow without returning tokens the
+/// clearing run already released to the counterparties
+fn ensure_escrow_unreleased(releases: &[EscrowRelease]) -> Result<(), ApiError> {
+    if releases.is_empty() {
+        return Ok(());
+    }
+    let details: Vec<String> = releases
+        .iter()
+        .map(|r| match r.signatures.as_slice() {
+            [] => format!("{} (unconfirmed)", r.settlement_id),
+            signatures => format!("{} ({})", r.settlement_id, signatures.join(", ")),
+        })
+        .collect();
+    Err(ApiError::Conflict(format!(
+        "Escrow of {} settlements was already released on-chain: {}; bust them individually instead",
+        releases.len(),
+        details.join("; ")
+    )))
+}
+
+/// Status an order reopens with, given what was filled before the clearing run
+fn reopened_status(filled_before: Decimal) -> &'static str {
+    if filled_before > Decimal::ZERO {
+        "partially_filled"
+    } else {
+        "pending"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_all_pending_settlements_roll_back() {
+        assert!(ensure_unsubmitted(&[]).is_ok());
+        assert!(ensure_unsubmitted(&["pending".to_string(), "pending".to_string()]).is_ok());
+        assert!(ensure_unsubmitted(&["pending".to_string(), "processing".to_string()]).is_err());
+        assert!(ensure_unsubmitted(&["busted".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_released_escrow_blocks_rollback() {
+        assert!(ensure_escrow_unreleased(&[]).is_ok());
+
+        let settlement_id = Uuid::new_v4();
+        let err = ensure_escrow_unreleased(&[EscrowRelease {
+            settlement_id,
+            signatures: vec!["sig1".to_string()],
+        }])
+        .unwrap_err();
+        assert!(err.to_string().contains(&format!("{} (sig1)", settlement_id)));
+
+        // A release that was sent but never confirmed may still have landed
+        assert!(ensure_escrow_unreleased(&[EscrowRelease {
+            settlement_id,
+            signatures: vec![],
+        }])
+        .is_err());
+    }
+
+    #[test]
+    fn test_reopened_status() {
+        assert_eq!(reopened_status(Decimal::ZERO), "pending");
+        assert_eq!(reopened_status(Decimal::new(25, 1)), "partially_filled");
+    }
+}
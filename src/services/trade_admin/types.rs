@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::services::dispute::LedgerAdjustment;
+
+/// Operator action awaiting approval
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TradeAction {
+    /// Reverse a settlement with compensating ledger entries
+    TradeBust { settlement_id: Uuid },
+    /// Record an off-market trade between two users
+    ManualTrade {
+        buyer_id: Uuid,
+        seller_id: Uuid,
+        #[schema(value_type = String)]
+        energy_amount: Decimal,
+        #[schema(value_type = String)]
+        price_per_kwh: Decimal,
+    },
+    /// Undo a cleared epoch whose settlements have not been submitted:
+    /// cancel the settlements and reopen its orders in the next epoch
+    EpochRollback { epoch_id: Uuid },
+}
+
+impl TradeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TradeBust { .. } => "trade_bust",
+            Self::ManualTrade { .. } => "manual_trade",
+            Self::EpochRollback { .. } => "epoch_rollback",
+        }
+    }
+}
+
+/// Request lifecycle: pending -> executed | rejected | expired | failed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    Pending,
+    Executed,
+    Rejected,
+    Expired,
+    /// Approved, but execution failed (e.g. the buyer could no longer pay)
+    Failed,
+}
+
+impl ApprovalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Executed => "executed",
+            Self::Rejected => "rejected",
+            Self::Expired => "expired",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "executed" => Some(Self::Executed),
+            "rejected" => Some(Self::Rejected),
+            "expired" => Some(Self::Expired),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    Approve,
+    Reject,
+}
+
+impl ApprovalDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Approve => "approve",
+            Self::Reject => "reject",
+        }
+    }
+}
+
+/// Approval request record
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct TradeApprovalRequest {
+    pub id: Uuid,
+    pub action_type: String,
+    /// The `TradeAction` to be executed
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+    pub reason: String,
+    pub status: String,
+    pub required_approvals: i32,
+    pub approvals: i32,
+    pub proposed_by: Uuid,
+    /// Busted settlement, or the settlement created by a manual trade
+    pub settlement_id: Option<Uuid>,
+    pub failure_reason: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Admin signature on a request
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ApprovalVote {
+    pub id: Uuid,
+    pub admin_id: Uuid,
+    pub decision: String,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Executed epoch rollback
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct EpochRollback {
+    pub id: Uuid,
+    pub epoch_id: Uuid,
+    pub approval_request_id: Option<Uuid>,
+    /// Epoch the orders were reopened in
+    pub reopened_in_epoch_id: Option<Uuid>,
+    pub cancelled_settlement_ids: Vec<Uuid>,
+    pub reopened_order_ids: Vec<Uuid>,
+    /// Every step taken, in order
+    #[schema(value_type = Vec<String>)]
+    pub narrative: serde_json::Value,
+    pub reason: String,
+    pub executed_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request with its signatures, posted ledger entries and, for an executed
+/// epoch rollback, what it did
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TradeApprovalDetail {
+    pub request: TradeApprovalRequest,
+    pub votes: Vec<ApprovalVote>,
+    pub adjustments: Vec<LedgerAdjustment>,
+    pub rollback: Option<EpochRollback>,
+}
+
+/// Party receiving a compensating entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Party {
+    Buyer,
+    Seller,
+    Platform,
+}
+
+/// Compensating entry of a trade bust
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReversalEntry {
+    pub party: Party,
+    pub asset: &'static str,
+    pub amount: Decimal,
+}
@@ -0,0 +1,360 @@
+//! Trading Halts
+//!
+//! Admin halts scoped to the whole platform, one market or one zone. A full
+//! halt freezes the affected orders: nothing is placed, cancelled or
+//! matched. A cancel-only halt stops order entry and matching but lets
+//! participants withdraw resting orders. Halts end when an admin lifts them
+//! or at their scheduled resumption; every change is pushed over WebSocket.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ErrorCode, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+use crate::services::WebSocketService;
+
+/// Interval at which scheduled resumptions are applied
+const RESUME_INTERVAL_SECS: u64 = 5;
+
+const HALT_COLUMNS: &str = "id, market_id, zone_id, mode, reason, resume_at, created_by, created_at, \
+    lifted_at, lifted_by";
+
+/// Halts in force: not lifted and not past their scheduled resumption
+const ACTIVE: &str = "lifted_at IS NULL AND (resume_at IS NULL OR resume_at > NOW())";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HaltMode {
+    /// No order entry, cancels or matching
+    Full,
+    /// No order entry or matching; resting orders can be cancelled
+    CancelOnly,
+}
+
+impl HaltMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::CancelOnly => "cancel_only",
+        }
+    }
+}
+
+/// What a participant is trying to do with an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HaltedAction {
+    Place,
+    Cancel,
+    Match,
+}
+
+/// A trading halt; without market and zone it covers the whole platform
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct TradingHalt {
+    pub id: Uuid,
+    pub market_id: Option<Uuid>,
+    pub zone_id: Option<i32>,
+    /// full or cancel_only
+    pub mode: String,
+    pub reason: String,
+    /// Scheduled resumption; none until lifted by an admin
+    pub resume_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing)]
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub lifted_at: Option<DateTime<Utc>>,
+    /// Admin who lifted the halt; none for a scheduled resumption
+    pub lifted_by: Option<Uuid>,
+}
+
+impl TradingHalt {
+    /// Whether the halt covers an order in `market_id` from `zone_id`
+    pub fn covers(&self, market_id: Uuid, zone_id: Option<i32>) -> bool {
+        self.market_id.is_none_or(|m| m == market_id) && self.zone_id.is_none_or(|z| zone_id == Some(z))
+    }
+
+    /// Whether the halt covers any order in `market_id`
+    pub fn touches_market(&self, market_id: Uuid) -> bool {
+        self.market_id.is_none_or(|m| m == market_id)
+    }
+
+    pub fn blocks(&self, action: HaltedAction) -> bool {
+        action != HaltedAction::Cancel || self.mode != HaltMode::CancelOnly.as_str()
+    }
+
+    fn scope(&self) -> String {
+        match (self.market_id, self.zone_id) {
+            (None, None) => "Trading".to_string(),
+            (Some(_), None) => "Trading in this market".to_string(),
+            (None, Some(zone)) => format!("Trading in zone {}", zone),
+            (Some(_), Some(zone)) => format!("Trading in zone {} of this market", zone),
+        }
+    }
+}
+
+/// The first of `halts` that stops `action` on an order in `market_id` from `zone_id`
+pub fn blocking_halt(
+    halts: &[TradingHalt],
+    market_id: Uuid,
+    zone_id: Option<i32>,
+    action: HaltedAction,
+) -> Option<&TradingHalt> {
+    halts
+        .iter()
+        .find(|h| h.covers(market_id, zone_id) && h.blocks(action))
+}
+
+fn halted_error(halt: &TradingHalt, action: HaltedAction) -> ApiError {
+    let verb = match action {
+        HaltedAction::Place => "orders are not accepted",
+        HaltedAction::Cancel => "orders cannot be cancelled",
+        HaltedAction::Match => "orders are not matched",
+    };
+    let resumes = halt
+        .resume_at
+        .map(|at| format!("; trading resumes at {}", at.to_rfc3339()))
+        .unwrap_or_default();
+    ApiError::with_code(
+        ErrorCode::TradingNotAllowed,
+        format!("{} is halted ({}): {}{}", halt.scope(), halt.reason, verb, resumes),
+    )
+}
+
+#[derive(Clone, Debug)]
+pub struct TradingHaltService {
+    db: PgPool,
+    websocket_service: WebSocketService,
+}
+
+impl TradingHaltService {
+    pub fn new(db: PgPool, websocket_service: WebSocketService) -> Self {
+        Self { db, websocket_service }
+    }
+
+    /// Halts in force, newest first
+    pub async fn active(&self) -> Result<Vec<TradingHalt>> {
+        Ok(sqlx::query_as::<_, TradingHalt>(&format!(
+            "SELECT {} FROM trading_halts WHERE {} ORDER BY created_at DESC",
+            HALT_COLUMNS, ACTIVE
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// All halts including lifted ones, newest first
+    pub async fn list(&self, limit: i64) -> Result<Vec<TradingHalt>> {
+        Ok(sqlx::query_as::<_, TradingHalt>(&format!(
+            "SELECT {} FROM trading_halts ORDER BY created_at DESC LIMIT $1",
+            HALT_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Reject `action` on an order in `market_id` from `zone_id` while a halt covers it
+    pub async fn ensure_allowed(&self, market_id: Uuid, zone_id: Option<i32>, action: HaltedAction) -> Result<()> {
+        let halts = self.active().await?;
+        match blocking_halt(&halts, market_id, zone_id, action) {
+            Some(halt) => Err(halted_error(halt, action)),
+            None => Ok(()),
+        }
+    }
+
+    /// Reject cancelling an existing order while a full halt covers it
+    pub async fn ensure_cancel_allowed(&self, order_id: Uuid) -> Result<()> {
+        let order: Option<(Uuid, Option<i32>)> =
+            sqlx::query_as("SELECT market_id, zone_id FROM trading_orders WHERE id = $1")
+                .bind(order_id)
+                .fetch_optional(&self.db)
+                .await?;
+        match order {
+            Some((market_id, zone_id)) => self.ensure_allowed(market_id, zone_id, HaltedAction::Cancel).await,
+            None => Ok(()),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn halt(
+        &self,
+        admin_id: Uuid,
+        market_id: Option<Uuid>,
+        zone_id: Option<i32>,
+        mode: HaltMode,
+        reason: &str,
+        resume_at: Option<DateTime<Utc>>,
+        audit_logger: &AuditLogger,
+    ) -> Result<TradingHalt> {
+        if resume_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(ApiError::validation_field("resume_at", "Resumption must be in the future"));
+        }
+
+        let halt = sqlx::query_as::<_, TradingHalt>(&format!(
+            r#"
+            INSERT INTO trading_halts (market_id, zone_id, mode, reason, resume_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            HALT_COLUMNS
+        ))
+        .bind(market_id)
+        .bind(zone_id)
+        .bind(mode.as_str())
+        .bind(reason.trim())
+        .bind(resume_at)
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        info!("⛔ Admin {} halted trading: {} ({})", admin_id, halt.scope(), halt.mode);
+        self.audit(admin_id, "trading_halted", &halt, audit_logger);
+        self.websocket_service.broadcast_trading_halt_changed(&halt, true).await;
+        Ok(halt)
+    }
+
+    /// Move or clear the scheduled resumption of a halt in force
+    pub async fn reschedule(
+        &self,
+        admin_id: Uuid,
+        halt_id: Uuid,
+        resume_at: Option<DateTime<Utc>>,
+        audit_logger: &AuditLogger,
+    ) -> Result<TradingHalt> {
+        if resume_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(ApiError::validation_field("resume_at", "Resumption must be in the future"));
+        }
+
+        let halt = sqlx::query_as::<_, TradingHalt>(&format!(
+            "UPDATE trading_halts SET resume_at = $2 WHERE id = $1 AND {} RETURNING {}",
+            ACTIVE, HALT_COLUMNS
+        ))
+        .bind(halt_id)
+        .bind(resume_at)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Trading halt not found or no longer in force".to_string()))?;
+
+        self.audit(admin_id, "trading_halt_rescheduled", &halt, audit_logger);
+        self.websocket_service.broadcast_trading_halt_changed(&halt, true).await;
+        Ok(halt)
+    }
+
+    pub async fn lift(&self, admin_id: Uuid, halt_id: Uuid, audit_logger: &AuditLogger) -> Result<TradingHalt> {
+        let halt = sqlx::query_as::<_, TradingHalt>(&format!(
+            "UPDATE trading_halts SET lifted_at = NOW(), lifted_by = $2 WHERE id = $1 AND {} RETURNING {}",
+            ACTIVE, HALT_COLUMNS
+        ))
+        .bind(halt_id)
+        .bind(admin_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Trading halt not found or no longer in force".to_string()))?;
+
+        info!("✅ Admin {} lifted trading halt {}", admin_id, halt.id);
+        self.audit(admin_id, "trading_halt_lifted", &halt, audit_logger);
+        self.websocket_service.broadcast_trading_halt_changed(&halt, false).await;
+        Ok(halt)
+    }
+
+    /// Close halts whose scheduled resumption has passed and announce it
+    pub async fn resume_due(&self) -> Result<usize> {
+        let resumed = sqlx::query_as::<_, TradingHalt>(&format!(
+            r#"
+            UPDATE trading_halts SET lifted_at = resume_at
+            WHERE lifted_at IS NULL AND resume_at <= NOW()
+            RETURNING {}
+            "#,
+            HALT_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?;
+
+        for halt in &resumed {
+            info!("✅ Trading halt {} ended at its scheduled resumption", halt.id);
+            self.websocket_service.broadcast_trading_halt_changed(halt, false).await;
+        }
+        Ok(resumed.len())
+    }
+
+    /// Apply scheduled resumptions as they fall due
+    pub async fn watch_resumptions(self) {
+        info!("🚀 Starting trading halt resumption watcher");
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(RESUME_INTERVAL_SECS)).await;
+            if let Err(e) = self.resume_due().await {
+                tracing::error!("❌ Failed to resume trading halts: {}", e);
+            }
+        }
+    }
+
+    fn audit(&self, admin_id: Uuid, action: &str, halt: &TradingHalt, audit_logger: &AuditLogger) {
+        audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: action.to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "halt_id": halt.id,
+                "market_id": halt.market_id,
+                "zone_id": halt.zone_id,
+                "mode": halt.mode,
+                "reason": halt.reason,
+                "resume_at": halt.resume_at,
+            })
+            .to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn halt(market_id: Option<Uuid>, zone_id: Option<i32>, mode: HaltMode) -> TradingHalt {
+        TradingHalt {
+            id: Uuid::new_v4(),
+            market_id,
+            zone_id,
+            mode: mode.as_str().to_string(),
+            reason: "grid incident".to_string(),
+            resume_at: None,
+            created_by: Uuid::nil(),
+            created_at: Utc::now(),
+            lifted_at: None,
+            lifted_by: None,
+        }
+    }
+
+    #[test]
+    fn test_halt_scope() {
+        let spot = Uuid::from_u128(1);
+        let other = Uuid::from_u128(2);
+
+        let halts = [halt(Some(spot), None, HaltMode::Full)];
+        assert!(blocking_halt(&halts, spot, Some(3), HaltedAction::Place).is_some());
+        assert!(blocking_halt(&halts, other, Some(3), HaltedAction::Place).is_none());
+
+        let halts = [halt(None, Some(3), HaltMode::Full)];
+        assert!(blocking_halt(&halts, other, Some(3), HaltedAction::Match).is_some());
+        assert!(blocking_halt(&halts, other, Some(4), HaltedAction::Match).is_none());
+        assert!(blocking_halt(&halts, other, None, HaltedAction::Match).is_none());
+
+        let halts = [halt(None, None, HaltMode::Full)];
+        assert!(blocking_halt(&halts, other, None, HaltedAction::Place).is_some());
+    }
+
+    #[test]
+    fn test_cancel_only_allows_cancels() {
+        let spot = Uuid::from_u128(1);
+        let halts = [halt(Some(spot), None, HaltMode::CancelOnly)];
+        assert!(blocking_halt(&halts, spot, None, HaltedAction::Place).is_some());
+        assert!(blocking_halt(&halts, spot, None, HaltedAction::Match).is_some());
+        assert!(blocking_halt(&halts, spot, None, HaltedAction::Cancel).is_none());
+
+        let halts = [halt(Some(spot), None, HaltMode::Full)];
+        assert!(blocking_halt(&halts, spot, None, HaltedAction::Cancel).is_some());
+    }
+}
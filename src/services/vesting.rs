@@ -0,0 +1,363 @@
+//! Energy Token Vesting
+//!
+//! Incentive programs grant energy tokens that unlock over time: nothing
+//! before the cliff, then linearly from the vesting start until the end of
+//! the schedule. Granted tokens count towards a user's holdings, but the
+//! still-locked part is excluded from what can be sold or transferred.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgConnection, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::{AuditEvent, AuditLogger};
+
+/// Amount of a grant vested at `now`
+pub fn vested_amount(
+    amount: Decimal,
+    start_at: DateTime<Utc>,
+    cliff_at: DateTime<Utc>,
+    end_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Decimal {
+    if now < cliff_at {
+        return Decimal::ZERO;
+    }
+    if now >= end_at || end_at <= start_at {
+        return amount;
+    }
+    let elapsed = Decimal::from((now - start_at).num_seconds());
+    let duration = Decimal::from((end_at - start_at).num_seconds());
+    (amount * elapsed / duration).round_dp(8).min(amount)
+}
+
+/// Part of `holdings` that may be sold or transferred while `locked` is still vesting
+pub fn transferable(holdings: Decimal, locked: Decimal) -> Decimal {
+    (holdings - locked).max(Decimal::ZERO)
+}
+
+/// Grant program
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct VestingProgram {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    #[schema(value_type = String)]
+    pub amount_per_user: Decimal,
+    pub cliff_days: i32,
+    pub vesting_days: i32,
+    pub vesting_start: DateTime<Utc>,
+    pub eligible_registered_before: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// Users holding a grant from this program
+    pub participants: i64,
+    #[schema(value_type = String)]
+    pub total_granted: Decimal,
+}
+
+/// Parameters of a new grant program
+#[derive(Debug, Clone)]
+pub struct NewVestingProgram {
+    pub name: String,
+    pub description: Option<String>,
+    pub amount_per_user: Decimal,
+    pub cliff_days: i32,
+    pub vesting_days: i32,
+    /// Defaults to now
+    pub vesting_start: Option<DateTime<Utc>>,
+    /// Enroll every user registered before this time
+    pub eligible_registered_before: Option<DateTime<Utc>>,
+    /// Enroll these users as well
+    pub user_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct GrantRow {
+    id: Uuid,
+    program_id: Uuid,
+    program_name: String,
+    amount: Decimal,
+    start_at: DateTime<Utc>,
+    cliff_at: DateTime<Utc>,
+    end_at: DateTime<Utc>,
+}
+
+/// A user's grant with its current vesting position
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VestingSchedule {
+    pub grant_id: Uuid,
+    pub program_id: Uuid,
+    pub program_name: String,
+    #[schema(value_type = String)]
+    pub amount: Decimal,
+    #[schema(value_type = String)]
+    pub vested: Decimal,
+    #[schema(value_type = String)]
+    pub locked: Decimal,
+    pub start_at: DateTime<Utc>,
+    pub cliff_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+}
+
+/// All of a user's grants
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VestingSummary {
+    #[schema(value_type = String)]
+    pub total_granted: Decimal,
+    #[schema(value_type = String)]
+    pub vested: Decimal,
+    /// Not yet sellable or transferable
+    #[schema(value_type = String)]
+    pub locked: Decimal,
+    pub schedules: Vec<VestingSchedule>,
+}
+
+impl GrantRow {
+    fn schedule(self, now: DateTime<Utc>) -> VestingSchedule {
+        let vested = vested_amount(self.amount, self.start_at, self.cliff_at, self.end_at, now);
+        VestingSchedule {
+            grant_id: self.id,
+            program_id: self.program_id,
+            program_name: self.program_name,
+            amount: self.amount,
+            vested,
+            locked: self.amount - vested,
+            start_at: self.start_at,
+            cliff_at: self.cliff_at,
+            end_at: self.end_at,
+        }
+    }
+}
+
+const PROGRAM_COLUMNS: &str = "p.id, p.name, p.description, p.amount_per_user, p.cliff_days, p.vesting_days, \
+    p.vesting_start, p.eligible_registered_before, p.created_by, p.created_at, \
+    COUNT(g.id) AS participants, COALESCE(SUM(g.amount), 0) AS total_granted";
+
+/// Vesting service
+#[derive(Clone)]
+pub struct VestingService {
+    db: PgPool,
+    audit: AuditLogger,
+}
+
+impl VestingService {
+    pub fn new(db: PgPool, audit: AuditLogger) -> Self {
+        Self { db, audit }
+    }
+
+    /// Create a program and grant its allocation to every eligible user
+    pub async fn create_program(
+        &self,
+        admin_id: Uuid,
+        program: NewVestingProgram,
+    ) -> Result<VestingProgram, ApiError> {
+        let name = program.name.trim();
+        if name.is_empty() {
+            return Err(ApiError::validation_field("name", "Program name is required"));
+        }
+        if program.amount_per_user <= Decimal::ZERO {
+            return Err(ApiError::validation_field("amount_per_user", "Grant amount must be positive"));
+        }
+        if program.vesting_days <= 0 {
+            return Err(ApiError::validation_field("vesting_days", "Vesting period must be at least one day"));
+        }
+        if program.cliff_days < 0 || program.cliff_days > program.vesting_days {
+            return Err(ApiError::validation_field(
+                "cliff_days",
+                "Cliff must be between 0 and the vesting period",
+            ));
+        }
+        if program.eligible_registered_before.is_none() && program.user_ids.is_empty() {
+            return Err(ApiError::validation_field(
+                "user_ids",
+                "Provide user_ids or an eligible_registered_before cutoff",
+            ));
+        }
+
+        let start = program.vesting_start.unwrap_or_else(Utc::now);
+        let cliff = start + Duration::days(program.cliff_days as i64);
+        let end = start + Duration::days(program.vesting_days as i64);
+
+        let mut tx = self.db.begin().await?;
+
+        let program_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO vesting_programs (
+                name, description, amount_per_user, cliff_days, vesting_days,
+                vesting_start, eligible_registered_before, created_by
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+        )
+        .bind(name)
+        .bind(program.description.as_deref().map(str::trim).filter(|d| !d.is_empty()))
+        .bind(program.amount_per_user)
+        .bind(program.cliff_days)
+        .bind(program.vesting_days)
+        .bind(start)
+        .bind(program.eligible_registered_before)
+        .bind(admin_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                ApiError::Conflict(format!("A vesting program named '{}' already exists", name))
+            }
+            other => ApiError::Database(other),
+        })?;
+
+        let granted = sqlx::query(
+            r#"
+            INSERT INTO vesting_grants (program_id, user_id, amount, start_at, cliff_at, end_at)
+            SELECT $1, u.id, $2, $3, $4, $5
+            FROM users u
+            WHERE u.id = ANY($6) OR ($7::TIMESTAMPTZ IS NOT NULL AND u.created_at < $7)
+            ON CONFLICT (program_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(program_id)
+        .bind(program.amount_per_user)
+        .bind(start)
+        .bind(cliff)
+        .bind(end)
+        .bind(&program.user_ids)
+        .bind(program.eligible_registered_before)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if granted == 0 {
+            return Err(ApiError::BadRequest("No users are eligible for this program".to_string()));
+        }
+
+        tx.commit().await?;
+
+        info!(
+            "🎁 Vesting program '{}' created: {} grants of {} kWh ({} day cliff, {} day vesting)",
+            name, granted, program.amount_per_user, program.cliff_days, program.vesting_days
+        );
+        self.audit.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "vesting_program_created".to_string(),
+            target_user_id: None,
+            details: format!("program {} '{}': {} grants", program_id, name, granted),
+        });
+
+        self.get_program(program_id).await
+    }
+
+    pub async fn get_program(&self, program_id: Uuid) -> Result<VestingProgram, ApiError> {
+        sqlx::query_as::<_, VestingProgram>(&format!(
+            r#"
+            SELECT {} FROM vesting_programs p
+            LEFT JOIN vesting_grants g ON g.program_id = p.id
+            WHERE p.id = $1
+            GROUP BY p.id
+            "#,
+            PROGRAM_COLUMNS
+        ))
+        .bind(program_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Vesting program not found".to_string()))
+    }
+
+    /// Programs, newest first
+    pub async fn list_programs(&self) -> Result<Vec<VestingProgram>, ApiError> {
+        Ok(sqlx::query_as::<_, VestingProgram>(&format!(
+            r#"
+            SELECT {} FROM vesting_programs p
+            LEFT JOIN vesting_grants g ON g.program_id = p.id
+            GROUP BY p.id
+            ORDER BY p.created_at DESC
+            "#,
+            PROGRAM_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// A user's grants and how much of each has vested
+    pub async fn summary(&self, user_id: Uuid) -> Result<VestingSummary, ApiError> {
+        let mut conn = self.db.acquire().await?;
+        let now = Utc::now();
+        let schedules: Vec<VestingSchedule> = user_grants(&mut *conn, user_id)
+            .await?
+            .into_iter()
+            .map(|grant| grant.schedule(now))
+            .collect();
+
+        Ok(VestingSummary {
+            total_granted: schedules.iter().map(|s| s.amount).sum(),
+            vested: schedules.iter().map(|s| s.vested).sum(),
+            locked: schedules.iter().map(|s| s.locked).sum(),
+            schedules,
+        })
+    }
+}
+
+async fn user_grants(conn: &mut PgConnection, user_id: Uuid) -> Result<Vec<GrantRow>, sqlx::Error> {
+    sqlx::query_as::<_, GrantRow>(
+        r#"
+        SELECT g.id, g.program_id, p.name AS program_name, g.amount, g.start_at, g.cliff_at, g.end_at
+        FROM vesting_grants g
+        JOIN vesting_programs p ON p.id = g.program_id
+        WHERE g.user_id = $1
+        ORDER BY g.start_at ASC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(&mut *conn)
+    .await
+}
+
+/// Granted energy of a user that has not vested by `now`
+pub async fn locked_amount(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Decimal, sqlx::Error> {
+    Ok(user_grants(conn, user_id)
+        .await?
+        .into_iter()
+        .map(|grant| grant.schedule(now).locked)
+        .sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, d, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_nothing_vests_before_cliff() {
+        // 100 kWh over 20 days from the 1st, 5 day cliff
+        assert_eq!(vested_amount(Decimal::from(100), day(1), day(6), day(21), day(5)), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_linear_from_start_after_cliff() {
+        assert_eq!(vested_amount(Decimal::from(100), day(1), day(6), day(21), day(6)), Decimal::from(25));
+        assert_eq!(vested_amount(Decimal::from(100), day(1), day(6), day(21), day(11)), Decimal::from(50));
+    }
+
+    #[test]
+    fn test_fully_vested_at_end() {
+        assert_eq!(vested_amount(Decimal::from(100), day(1), day(6), day(21), day(25)), Decimal::from(100));
+    }
+
+    #[test]
+    fn test_transferable_excludes_locked() {
+        assert_eq!(transferable(Decimal::from(120), Decimal::from(75)), Decimal::from(45));
+        assert_eq!(transferable(Decimal::from(50), Decimal::from(75)), Decimal::ZERO);
+    }
+}
@@ -107,6 +107,27 @@ impl WalletAuditLogger {
         .await
     }
 
+    /// Log a wallet session being created, refused or revoked
+    pub async fn log_session(
+        &self,
+        user_id: Uuid,
+        operation: &str,
+        success: bool,
+        error: Option<String>,
+        metadata: serde_json::Value,
+    ) -> Result<()> {
+        self.log_operation(
+            user_id,
+            &format!("session_{}", operation),
+            success,
+            None,
+            None,
+            error,
+            Some(metadata),
+        )
+        .await
+    }
+
     /// Generic operation logger
     async fn log_operation(
         &self,
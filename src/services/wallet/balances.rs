@@ -0,0 +1,167 @@
+//! Batched wallet balance lookups
+//!
+//! Balances for many wallets are read with `getMultipleAccounts` (wallet
+//! accounts and their energy token ATAs together) and cached per account in
+//! Redis. The gateway drops cached entries for accounts it mints to or
+//! transfers from, so its own writes are visible before the TTL expires.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+
+use crate::services::cache::{CacheKeys, CacheService};
+
+/// Accounts per `getMultipleAccounts` call (RPC limit)
+pub const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Offset of the little-endian `amount` field in an SPL token account.
+/// The base layout is shared by Token-2022, extensions follow it.
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// Cached snapshot of one on-chain account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedAccount {
+    pub exists: bool,
+    pub lamports: u64,
+    /// Token amount in base units, for token accounts
+    pub token_amount: Option<u64>,
+}
+
+impl CachedAccount {
+    pub fn missing() -> Self {
+        Self {
+            exists: false,
+            lamports: 0,
+            token_amount: None,
+        }
+    }
+
+    pub fn from_account(account: &Account) -> Self {
+        Self {
+            exists: true,
+            lamports: account.lamports,
+            token_amount: token_account_amount(&account.data),
+        }
+    }
+}
+
+/// SOL and energy token balance of a wallet
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WalletBalance {
+    pub wallet_address: String,
+    pub token_account: String,
+    pub lamports: u64,
+    pub balance_sol: f64,
+    /// Energy token amount in base units
+    pub token_amount: u64,
+    pub token_balance: f64,
+    pub decimals: u8,
+    /// Whether both accounts were served from cache
+    pub cached: bool,
+}
+
+/// Redis cache of account balances, shared by the wallet and blockchain
+/// services
+#[derive(Clone)]
+pub struct BalanceCache {
+    cache: CacheService,
+    ttl_secs: u64,
+}
+
+impl BalanceCache {
+    pub fn new(cache: CacheService, ttl_secs: u64) -> Self {
+        Self { cache, ttl_secs }
+    }
+
+    /// Cached entries for the given accounts, in order
+    pub async fn get_many(&self, accounts: &[Pubkey]) -> Vec<Option<CachedAccount>> {
+        let keys = cache_keys(accounts);
+        match self.cache.get_many(&keys).await {
+            Ok(values) => values,
+            Err(e) => {
+                warn!("Balance cache read failed: {}", e);
+                accounts.iter().map(|_| None).collect()
+            }
+        }
+    }
+
+    /// Store freshly fetched accounts
+    pub async fn store(&self, entries: &[(Pubkey, CachedAccount)]) {
+        let entries: Vec<(String, CachedAccount)> = entries
+            .iter()
+            .map(|(pubkey, account)| (CacheKeys::account_balance(&pubkey.to_string()), *account))
+            .collect();
+        if let Err(e) = self.cache.set_many_with_ttl(&entries, self.ttl_secs).await {
+            warn!("Balance cache write failed: {}", e);
+        }
+    }
+
+    /// Drop cached balances for accounts the gateway has just written to
+    pub async fn invalidate(&self, accounts: &[Pubkey]) {
+        let keys = cache_keys(accounts);
+        match self.cache.delete_many(&keys).await {
+            Ok(()) => debug!("Invalidated cached balances for {} accounts", accounts.len()),
+            Err(e) => warn!("Balance cache invalidation failed: {}", e),
+        }
+    }
+}
+
+fn cache_keys(accounts: &[Pubkey]) -> Vec<String> {
+    accounts
+        .iter()
+        .map(|pubkey| CacheKeys::account_balance(&pubkey.to_string()))
+        .collect()
+}
+
+/// Token amount of an SPL token account, or `None` for other accounts
+pub fn token_account_amount(data: &[u8]) -> Option<u64> {
+    // Base token account layout is 165 bytes
+    if data.len() < 165 {
+        return None;
+    }
+    let bytes: [u8; 8] = data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+        .try_into()
+        .ok()?;
+    Some(u64::from_le_bytes(bytes))
+}
+
+/// Accounts missing from the cache, deduplicated
+pub fn cache_misses(accounts: &[Pubkey], cached: &[Option<CachedAccount>]) -> Vec<Pubkey> {
+    let mut misses: Vec<Pubkey> = Vec::new();
+    for (pubkey, entry) in accounts.iter().zip(cached) {
+        if entry.is_none() && !misses.contains(pubkey) {
+            misses.push(*pubkey);
+        }
+    }
+    misses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_account_amount() {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&1_500_000_000u64.to_le_bytes());
+        assert_eq!(token_account_amount(&data), Some(1_500_000_000));
+
+        // Token-2022 accounts with extensions are longer
+        data.extend_from_slice(&[0u8; 20]);
+        assert_eq!(token_account_amount(&data), Some(1_500_000_000));
+
+        // System accounts carry no data
+        assert_eq!(token_account_amount(&[]), None);
+    }
+
+    #[test]
+    fn test_cache_misses_deduplicated() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let hit = Some(CachedAccount::missing());
+
+        let misses = cache_misses(&[a, b, a], &[None, hit, None]);
+        assert_eq!(misses, vec![a]);
+    }
+}
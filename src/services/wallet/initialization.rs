@@ -14,7 +14,9 @@ use tracing::{info, warn};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::models::pii::open_column;
 use crate::services::blockchain::BlockchainService;
+use crate::services::pii::PiiCipher;
 use crate::services::wallet::service::WalletService;
 
 /// Standard nonce size for AES-GCM (12 bytes)
@@ -28,6 +30,7 @@ pub struct WalletInitializationService {
     encryption_secret: String,
     blockchain_service: BlockchainService,
     solana_rpc_url: String,
+    pii: PiiCipher,
 }
 
 /// Status of a user's wallet
@@ -89,12 +92,14 @@ impl WalletInitializationService {
         encryption_secret: String,
         blockchain_service: BlockchainService,
         solana_rpc_url: String,
+        pii: PiiCipher,
     ) -> Self {
         Self {
             db,
             encryption_secret,
             blockchain_service,
             solana_rpc_url,
+            pii,
         }
     }
 
@@ -102,7 +107,7 @@ impl WalletInitializationService {
     pub async fn diagnose_user_wallet(&self, user_id: Uuid) -> Result<WalletDiagnosis> {
         let user = sqlx::query!(
             r#"
-            SELECT id, username, email, wallet_address,
+            SELECT id, username, email, email_encrypted, wallet_address,
                    encrypted_private_key, wallet_salt, encryption_iv
             FROM users WHERE id = $1
             "#,
@@ -111,11 +116,14 @@ impl WalletInitializationService {
         .fetch_optional(&self.db)
         .await?
         .ok_or_else(|| anyhow!("User not found"))?;
+        let email = open_column(&self.pii, user.email_encrypted.as_deref(), user.email.as_deref())
+            .await?
+            .unwrap_or_default();
 
         self.diagnose_wallet_data(
             user.id,
             &user.username,
-            &user.email,
+            &email,
             user.wallet_address.as_deref(),
             user.encrypted_private_key.as_deref(),
             user.wallet_salt.as_deref(),
@@ -128,7 +136,7 @@ impl WalletInitializationService {
     pub async fn diagnose_all_users(&self) -> Result<Vec<WalletDiagnosis>> {
         let users = sqlx::query!(
             r#"
-            SELECT id, username, email, wallet_address,
+            SELECT id, username, email, email_encrypted, wallet_address,
                    encrypted_private_key, wallet_salt, encryption_iv
             FROM users
             ORDER BY created_at DESC
@@ -139,11 +147,14 @@ impl WalletInitializationService {
 
         let mut diagnoses = Vec::new();
         for user in users {
+            let email = open_column(&self.pii, user.email_encrypted.as_deref(), user.email.as_deref())
+                .await?
+                .unwrap_or_default();
             let diagnosis = self
                 .diagnose_wallet_data(
                     user.id,
                     &user.username,
-                    &user.email,
+                    &email,
                     user.wallet_address.as_deref(),
                     user.encrypted_private_key.as_deref(),
                     user.wallet_salt.as_deref(),
@@ -546,9 +557,13 @@ impl WalletInitializationService {
         let mut results = Vec::new();
 
         for email in emails {
-            let user = sqlx::query!("SELECT id FROM users WHERE email = $1", *email)
-                .fetch_optional(&self.db)
-                .await?;
+            let user = sqlx::query!(
+                "SELECT id FROM users WHERE email_digest = $1 OR lower(email) = lower($2)",
+                self.pii.email_digest(email),
+                *email
+            )
+            .fetch_optional(&self.db)
+            .await?;
 
             match user {
                 Some(u) => {
@@ -1,10 +1,14 @@
 //! Wallet services module
 
 pub mod audit_logger;
+pub mod balances;
 pub mod initialization;
 pub mod service;
+pub mod session;
 
 // Re-exports
 pub use audit_logger::*;
+pub use balances::{BalanceCache, WalletBalance};
 pub use initialization::*;
 pub use service::*;
+pub use session::{DeviceBinding, WalletSession, WalletSessionService};
@@ -13,6 +13,7 @@ use solana_sdk::{
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
 };
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
@@ -20,6 +21,9 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+use super::balances::{cache_misses, BalanceCache, CachedAccount, WalletBalance, MAX_ACCOUNTS_PER_REQUEST};
+use crate::services::blockchain::BlockchainUtils;
+
 /// Service for managing Solana wallets in development environment
 #[derive(Clone)]
 pub struct WalletService {
@@ -28,6 +32,8 @@ pub struct WalletService {
     authority_keypair: Arc<RwLock<Option<Arc<Keypair>>>>,
     /// Path to wallet file (if loading from file)
     wallet_path: Option<String>,
+    /// Redis cache for batched balance lookups
+    balance_cache: Option<BalanceCache>,
 }
 
 impl std::fmt::Debug for WalletService {
@@ -46,6 +52,7 @@ impl WalletService {
             rpc_client: Arc::new(RpcClient::new(rpc_url.to_string())),
             authority_keypair: Arc::new(RwLock::new(None)),
             wallet_path: None,
+            balance_cache: None,
         }
     }
 
@@ -59,9 +66,16 @@ impl WalletService {
             rpc_client: Arc::new(RpcClient::new(rpc_url.to_string())),
             authority_keypair: Arc::new(RwLock::new(None)),
             wallet_path: Some(wallet_path),
+            balance_cache: None,
         }
     }
 
+    /// Cache batched balance lookups in Redis
+    pub fn with_balance_cache(mut self, cache: BalanceCache) -> Self {
+        self.balance_cache = Some(cache);
+        self
+    }
+
     /// Create a new Solana keypair for development
     pub fn create_keypair() -> Keypair {
         let keypair = Keypair::new();
@@ -83,6 +97,99 @@ impl WalletService {
         }
     }
 
+    /// SOL and energy token balances for a list of wallets, in order.
+    ///
+    /// Wallet accounts and their ATAs are read with `getMultipleAccounts`
+    /// in chunks of 100; cached accounts are not fetched again.
+    pub async fn get_balances(
+        &self,
+        wallets: &[Pubkey],
+        mint: &Pubkey,
+        decimals: u8,
+    ) -> Result<Vec<WalletBalance>> {
+        let token_program_id = BlockchainUtils::get_token_program_id()?;
+        let token_accounts: Vec<Pubkey> = wallets
+            .iter()
+            .map(|wallet| {
+                spl_associated_token_account::get_associated_token_address_with_program_id(
+                    wallet,
+                    mint,
+                    &token_program_id,
+                )
+            })
+            .collect();
+
+        let accounts: Vec<Pubkey> = wallets.iter().chain(token_accounts.iter()).copied().collect();
+        let mut cached = match &self.balance_cache {
+            Some(cache) => cache.get_many(&accounts).await,
+            None => accounts.iter().map(|_| None).collect(),
+        };
+        let hits: Vec<bool> = cached.iter().map(Option::is_some).collect();
+
+        let misses = cache_misses(&accounts, &cached);
+        let mut fetched: HashMap<Pubkey, CachedAccount> = HashMap::with_capacity(misses.len());
+        for chunk in misses.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+            let results = self
+                .rpc_client
+                .get_multiple_accounts(chunk)
+                .with_context(|| format!("Failed to fetch {} accounts", chunk.len()))?;
+            for (pubkey, account) in chunk.iter().zip(results) {
+                let entry = account
+                    .as_ref()
+                    .map(CachedAccount::from_account)
+                    .unwrap_or_else(CachedAccount::missing);
+                fetched.insert(*pubkey, entry);
+            }
+        }
+
+        if !fetched.is_empty() {
+            debug!(
+                "Fetched {} of {} balance accounts from RPC",
+                fetched.len(),
+                accounts.len()
+            );
+            if let Some(cache) = &self.balance_cache {
+                let entries: Vec<(Pubkey, CachedAccount)> = fetched.iter().map(|(k, v)| (*k, *v)).collect();
+                cache.store(&entries).await;
+            }
+        }
+
+        for (entry, pubkey) in cached.iter_mut().zip(&accounts) {
+            if entry.is_none() {
+                *entry = fetched.get(pubkey).copied();
+            }
+        }
+
+        let scale = 10f64.powi(decimals as i32);
+        let count = wallets.len();
+        Ok(wallets
+            .iter()
+            .enumerate()
+            .map(|(i, wallet)| {
+                let wallet_account = cached[i].unwrap_or_else(CachedAccount::missing);
+                let token_account = cached[count + i].unwrap_or_else(CachedAccount::missing);
+                let token_amount = token_account.token_amount.unwrap_or(0);
+                WalletBalance {
+                    wallet_address: wallet.to_string(),
+                    token_account: token_accounts[i].to_string(),
+                    lamports: wallet_account.lamports,
+                    balance_sol: wallet_account.lamports as f64 / 1_000_000_000.0,
+                    token_amount,
+                    token_balance: token_amount as f64 / scale,
+                    decimals,
+                    cached: hits[i] && hits[count + i],
+                }
+            })
+            .collect())
+    }
+
+    /// Drop cached balances after the gateway writes to these accounts
+    pub async fn invalidate_balances(&self, accounts: &[Pubkey]) {
+        if let Some(cache) = &self.balance_cache {
+            cache.invalidate(accounts).await;
+        }
+    }
+
     /// Request airdrop for development (localhost only)
     pub async fn request_airdrop(&self, pubkey: &Pubkey, amount_sol: f64) -> Result<Signature> {
         let lamports = (amount_sol * 1_000_000_000.0) as u64; // Convert SOL to lamports
@@ -98,6 +205,7 @@ impl WalletService {
 
                 // Wait for confirmation in development
                 let _ = self.confirm_transaction(&signature).await;
+                self.invalidate_balances(&[*pubkey]).await;
 
                 Ok(signature)
             }
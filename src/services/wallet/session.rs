@@ -0,0 +1,337 @@
+//! Wallet Sessions
+//!
+//! A wallet session caches a user's unlocked trading key so orders can be
+//! signed without asking for the password each time. Sessions are bound to
+//! the device that created them: every use must present the same device
+//! fingerprint (`X-Device-Fingerprint`) and User-Agent, otherwise the session
+//! is revoked as possibly stolen. Each use slides `expires_at` forward by the
+//! idle timeout, but never past `absolute_expires_at`, fixed at creation.
+//! A user holds at most `max_concurrent` active sessions; creating another
+//! revokes the oldest.
+
+use std::net::IpAddr;
+
+use axum::http::{header, HeaderMap};
+use chrono::{DateTime, Duration, Utc};
+use rand::{rngs::OsRng, RngCore};
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::WalletAuditLogger;
+use crate::config::WalletSessionConfig;
+use crate::error::{ApiError, Result};
+
+/// Header carrying the client's device fingerprint
+pub const DEVICE_FINGERPRINT_HEADER: &str = "x-device-fingerprint";
+
+/// The device a session was created from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceBinding {
+    pub fingerprint: String,
+    /// SHA-256 hex of the User-Agent, when the client sent one
+    pub user_agent_hash: Option<String>,
+}
+
+impl DeviceBinding {
+    /// Binding of the requesting device; `None` without a fingerprint header
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let fingerprint = headers
+            .get(DEVICE_FINGERPRINT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())?;
+        Some(Self {
+            fingerprint: fingerprint.chars().take(255).collect(),
+            user_agent_hash: headers
+                .get(header::USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .map(user_agent_hash),
+        })
+    }
+
+    /// Whether a request from `other` may use a session bound to this device
+    pub fn matches(&self, other: &DeviceBinding) -> bool {
+        self.fingerprint == other.fingerprint && self.user_agent_hash == other.user_agent_hash
+    }
+}
+
+pub fn user_agent_hash(user_agent: &str) -> String {
+    hex::encode(Sha256::digest(user_agent.trim().as_bytes()))
+}
+
+/// Expiry after a use at `now`: the idle timeout from now, capped by the absolute end
+pub fn slide_expiry(now: DateTime<Utc>, idle_timeout: Duration, absolute_expires_at: DateTime<Utc>) -> DateTime<Utc> {
+    (now + idle_timeout).min(absolute_expires_at)
+}
+
+/// An active wallet session as shown to its owner
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct WalletSession {
+    pub id: Uuid,
+    pub device_name: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    /// Moves forward with each use
+    pub expires_at: DateTime<Utc>,
+    /// The session ends here regardless of use
+    pub absolute_expires_at: DateTime<Utc>,
+}
+
+/// Cached trading key, encrypted as stored on the user
+pub struct CachedWalletKey {
+    pub encrypted: Vec<u8>,
+    pub salt: Vec<u8>,
+    pub iv: Vec<u8>,
+}
+
+#[derive(FromRow)]
+struct SessionRow {
+    id: Uuid,
+    device_fingerprint: String,
+    user_agent_hash: Option<String>,
+    expires_at: DateTime<Utc>,
+    absolute_expires_at: DateTime<Utc>,
+}
+
+const SESSION_COLUMNS: &str = "id, device_name, host(ip_address) AS ip_address, created_at, \
+     COALESCE(last_used_at, created_at) AS last_used_at, expires_at, absolute_expires_at";
+
+#[derive(Clone)]
+pub struct WalletSessionService {
+    db: PgPool,
+    audit: WalletAuditLogger,
+    config: WalletSessionConfig,
+}
+
+impl WalletSessionService {
+    pub fn new(db: PgPool, config: WalletSessionConfig) -> Self {
+        Self {
+            audit: WalletAuditLogger::new(db.clone()),
+            db,
+            config,
+        }
+    }
+
+    fn idle_timeout(&self) -> Duration {
+        Duration::hours(self.config.idle_timeout_hours)
+    }
+
+    /// Open a session for a device. Returns the session token, which is shown
+    /// only once, and revokes the user's oldest sessions beyond the limit.
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        device: &DeviceBinding,
+        device_name: Option<&str>,
+        ip_address: Option<IpAddr>,
+        key: &CachedWalletKey,
+    ) -> Result<(String, WalletSession)> {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+
+        let now = Utc::now();
+        let absolute_expires_at = now + Duration::days(self.config.max_lifetime_days);
+        let expires_at = slide_expiry(now, self.idle_timeout(), absolute_expires_at);
+
+        let mut tx = self.db.begin().await?;
+        // Serialize session creation per user so the limit holds
+        sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let session = sqlx::query_as::<_, WalletSession>(&format!(
+            r#"
+            INSERT INTO wallet_sessions
+                (user_id, session_token, device_fingerprint, device_name, ip_address, user_agent_hash,
+                 cached_key_encrypted, key_salt, key_iv, expires_at, absolute_expires_at, last_used_at)
+            VALUES ($1, $2, $3, $4, $5::inet, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING {}
+            "#,
+            SESSION_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(&token)
+        .bind(&device.fingerprint)
+        .bind(device_name)
+        .bind(ip_address.map(|ip| ip.to_string()))
+        .bind(&device.user_agent_hash)
+        .bind(&key.encrypted)
+        .bind(&key.salt)
+        .bind(&key.iv)
+        .bind(expires_at)
+        .bind(absolute_expires_at)
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Everything past the newest `max_concurrent` active sessions
+        let evicted = sqlx::query(
+            r#"
+            UPDATE wallet_sessions
+            SET is_active = false, revoked_at = NOW(), revoked_reason = 'session_limit'
+            WHERE id IN (
+                SELECT id FROM wallet_sessions
+                WHERE user_id = $1 AND is_active = true AND revoked_at IS NULL
+                ORDER BY created_at DESC, id DESC
+                OFFSET $2
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(self.config.max_concurrent.max(1))
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+
+        info!("Wallet session {} opened for user {} ({} evicted)", session.id, user_id, evicted);
+        self.log(user_id, "create", true, None, json!({ "session_id": session.id, "evicted": evicted }))
+            .await;
+        Ok((token, session))
+    }
+
+    /// Check a session token presented from `device` and extend its expiry.
+    /// A token used from another device is revoked.
+    pub async fn touch(&self, user_id: Uuid, token: &str, device: Option<&DeviceBinding>) -> Result<()> {
+        let session = sqlx::query_as::<_, SessionRow>(
+            r#"
+            SELECT id, device_fingerprint, user_agent_hash, expires_at, absolute_expires_at
+            FROM wallet_sessions
+            WHERE session_token = $1 AND user_id = $2 AND is_active = true AND revoked_at IS NULL
+            "#,
+        )
+        .bind(token)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::Unauthorized("Wallet session not found or revoked".to_string()))?;
+
+        let now = Utc::now();
+        if session.expires_at <= now || session.absolute_expires_at <= now {
+            self.revoke(session.id, "expired").await?;
+            return Err(ApiError::Unauthorized("Wallet session expired".to_string()));
+        }
+
+        let bound = DeviceBinding {
+            fingerprint: session.device_fingerprint,
+            user_agent_hash: session.user_agent_hash,
+        };
+        if !device.is_some_and(|device| bound.matches(device)) {
+            warn!("Wallet session {} of user {} used from another device; revoking", session.id, user_id);
+            self.revoke(session.id, "device_mismatch").await?;
+            self.log(
+                user_id,
+                "device_mismatch",
+                false,
+                Some("Session used from another device".to_string()),
+                json!({ "session_id": session.id }),
+            )
+            .await;
+            return Err(ApiError::Unauthorized("Wallet session is bound to another device".to_string()));
+        }
+
+        sqlx::query("UPDATE wallet_sessions SET expires_at = $2, last_used_at = $3 WHERE id = $1")
+            .bind(session.id)
+            .bind(slide_expiry(now, self.idle_timeout(), session.absolute_expires_at))
+            .bind(now)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// The user's active sessions, newest first
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<WalletSession>> {
+        Ok(sqlx::query_as::<_, WalletSession>(&format!(
+            r#"
+            SELECT {}
+            FROM wallet_sessions
+            WHERE user_id = $1 AND is_active = true AND revoked_at IS NULL
+              AND expires_at > NOW() AND absolute_expires_at > NOW()
+            ORDER BY created_at DESC
+            "#,
+            SESSION_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Revoke every active session of the user; returns how many were revoked
+    pub async fn revoke_all(&self, user_id: Uuid, reason: &str) -> Result<u64> {
+        let revoked = sqlx::query(
+            r#"
+            UPDATE wallet_sessions
+            SET is_active = false, revoked_at = NOW(), revoked_reason = $2
+            WHERE user_id = $1 AND is_active = true AND revoked_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(reason)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+
+        info!("Revoked {} wallet sessions of user {} ({})", revoked, user_id, reason);
+        self.log(user_id, "revoke_all", true, None, json!({ "revoked": revoked, "reason": reason }))
+            .await;
+        Ok(revoked)
+    }
+
+    async fn revoke(&self, session_id: Uuid, reason: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE wallet_sessions SET is_active = false, revoked_at = NOW(), revoked_reason = $2 WHERE id = $1",
+        )
+        .bind(session_id)
+        .bind(reason)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn log(&self, user_id: Uuid, operation: &str, success: bool, error: Option<String>, metadata: serde_json::Value) {
+        if let Err(e) = self.audit.log_session(user_id, operation, success, error, metadata).await {
+            warn!("Failed to audit wallet session {}: {}", operation, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_slide_expiry_is_capped_by_absolute_lifetime() {
+        let now = Utc::now();
+        let idle = Duration::hours(72);
+        assert_eq!(slide_expiry(now, idle, now + Duration::days(30)), now + idle);
+        assert_eq!(slide_expiry(now, idle, now + Duration::hours(1)), now + Duration::hours(1));
+    }
+
+    #[test]
+    fn test_device_binding() {
+        let mut headers = HeaderMap::new();
+        assert!(DeviceBinding::from_headers(&headers).is_none());
+
+        headers.insert(DEVICE_FINGERPRINT_HEADER, HeaderValue::from_static("device-a"));
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("GridTokenX/1.0"));
+        let bound = DeviceBinding::from_headers(&headers).unwrap();
+        assert_eq!(bound.user_agent_hash.as_deref(), Some(user_agent_hash("GridTokenX/1.0").as_str()));
+        assert!(bound.matches(&bound.clone()));
+
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("curl/8.0"));
+        assert!(!bound.matches(&DeviceBinding::from_headers(&headers).unwrap()));
+
+        headers.insert(DEVICE_FINGERPRINT_HEADER, HeaderValue::from_static("device-b"));
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("GridTokenX/1.0"));
+        assert!(!bound.matches(&DeviceBinding::from_headers(&headers).unwrap()));
+    }
+}
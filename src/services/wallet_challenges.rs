@@ -0,0 +1,428 @@
+//! Wallet Challenge Audit
+//!
+//! Replay protection and nonce audit for wallet signature challenges. Every
+//! challenge is recorded when issued and consumed at most once; issuing a
+//! new one supersedes the open one. Failed signature attempts are logged per
+//! wallet, and a wallet with too many failures within the window is refused
+//! further challenges for a while. Blocks are reported to the access anomaly
+//! log alongside the API usage anomalies.
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::api_usage::{AnomalyKind, ApiUsageService};
+use crate::services::{AuditEvent, AuditLogger};
+use crate::utils::verify_message_signature;
+
+/// Failed attempts counted towards a block
+pub const FAILURE_WINDOW_MINUTES: i64 = 60;
+/// Failures within the window that block a wallet
+pub const MAX_FAILURES_PER_WINDOW: i64 = 5;
+/// How long a blocked wallet is refused
+pub const BLOCK_HOURS: i64 = 24;
+/// Earlier challenges checked when classifying a failed signature as a replay
+const REPLAY_LOOKBACK: i64 = 20;
+
+/// Why a signature challenge failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeFailure {
+    /// Well-formed signature that does not match the challenge
+    BadSignature,
+    /// Not a valid base58 signature
+    MalformedSignature,
+    /// Challenge expired before it was signed
+    Expired,
+    /// Signature of an already consumed or superseded challenge
+    Replay,
+}
+
+impl ChallengeFailure {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BadSignature => "bad_signature",
+            Self::MalformedSignature => "malformed_signature",
+            Self::Expired => "expired",
+            Self::Replay => "replay",
+        }
+    }
+}
+
+/// Whether the failures counted within the window warrant a block
+pub fn should_block(failures_in_window: i64) -> bool {
+    failures_in_window >= MAX_FAILURES_PER_WINDOW
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct WalletAuthBlock {
+    pub id: Uuid,
+    pub wallet_address: String,
+    /// Failures within the window when the block was placed
+    pub failures: i32,
+    pub blocked_at: DateTime<Utc>,
+    pub blocked_until: DateTime<Utc>,
+    pub released_by: Option<Uuid>,
+    pub released_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct FailureCount {
+    pub reason: String,
+    pub count: i64,
+}
+
+/// Failed attempts against one wallet
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct WalletFailureSummary {
+    pub wallet_address: String,
+    pub failures: i64,
+    pub replays: i64,
+    /// Distinct users that attempted the wallet
+    pub users: i64,
+    pub last_failure_at: DateTime<Utc>,
+    pub blocked: bool,
+}
+
+/// Challenge issuance, consumption and failures over a period
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WalletChallengeReport {
+    pub since: DateTime<Utc>,
+    pub issued: i64,
+    pub consumed: i64,
+    pub superseded: i64,
+    /// Issued, never consumed and past expiry
+    pub expired_unused: i64,
+    pub failures: Vec<FailureCount>,
+    /// Most failures first
+    pub top_wallets: Vec<WalletFailureSummary>,
+    /// Blocks in force now
+    pub active_blocks: Vec<WalletAuthBlock>,
+    pub max_failures_per_window: i64,
+    pub failure_window_minutes: i64,
+}
+
+const BLOCK_COLUMNS: &str = "id, wallet_address, failures, blocked_at, blocked_until, released_by, released_at";
+
+#[derive(Clone)]
+pub struct WalletChallengeService {
+    db: PgPool,
+    api_usage: ApiUsageService,
+    audit: AuditLogger,
+}
+
+impl WalletChallengeService {
+    pub fn new(db: PgPool, api_usage: ApiUsageService, audit: AuditLogger) -> Self {
+        Self { db, api_usage, audit }
+    }
+
+    /// Refuse wallets under an active block
+    pub async fn ensure_allowed(&self, wallet_address: &str) -> Result<(), ApiError> {
+        let blocked_until: Option<DateTime<Utc>> = sqlx::query_scalar(
+            "SELECT MAX(blocked_until) FROM wallet_auth_blocks
+             WHERE wallet_address = $1 AND released_at IS NULL AND blocked_until > NOW()",
+        )
+        .bind(wallet_address)
+        .fetch_one(&self.db)
+        .await?;
+
+        match blocked_until {
+            Some(until) => Err(ApiError::Forbidden(format!(
+                "Wallet is blocked after repeated failed signature attempts until {}",
+                until.to_rfc3339()
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Record a challenge handed out, superseding the open one for the same
+    /// user, wallet and purpose
+    pub async fn issue(
+        &self,
+        user_id: Uuid,
+        wallet_address: &str,
+        purpose: &str,
+        nonce: &str,
+        message: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), ApiError> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            "UPDATE wallet_challenges SET status = 'superseded'
+             WHERE user_id = $1 AND wallet_address = $2 AND purpose = $3 AND status = 'issued'",
+        )
+        .bind(user_id)
+        .bind(wallet_address)
+        .bind(purpose)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "INSERT INTO wallet_challenges (user_id, wallet_address, purpose, nonce, message, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(user_id)
+        .bind(wallet_address)
+        .bind(purpose)
+        .bind(nonce)
+        .bind(message)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Open challenge for a user, wallet and purpose as (nonce, message, expiry)
+    pub async fn open_challenge(
+        &self,
+        user_id: Uuid,
+        wallet_address: &str,
+        purpose: &str,
+    ) -> Result<Option<(String, String, DateTime<Utc>)>, ApiError> {
+        Ok(sqlx::query_as(
+            "SELECT nonce, message, expires_at FROM wallet_challenges
+             WHERE user_id = $1 AND wallet_address = $2 AND purpose = $3 AND status = 'issued'
+             ORDER BY issued_at DESC
+             LIMIT 1",
+        )
+        .bind(user_id)
+        .bind(wallet_address)
+        .bind(purpose)
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    /// Consume the challenge with this exact message. False if it was
+    /// already consumed or superseded; challenges issued before the audit
+    /// existed have no record and are let through.
+    pub async fn consume(&self, user_id: Uuid, message: &str) -> Result<bool, ApiError> {
+        let consumed = sqlx::query_scalar::<_, Uuid>(
+            "UPDATE wallet_challenges SET status = 'consumed', consumed_at = NOW()
+             WHERE user_id = $1 AND message = $2 AND status = 'issued'
+             RETURNING id",
+        )
+        .bind(user_id)
+        .bind(message)
+        .fetch_optional(&self.db)
+        .await?;
+        if consumed.is_some() {
+            return Ok(true);
+        }
+
+        let known: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM wallet_challenges WHERE message = $1)")
+            .bind(message)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(!known)
+    }
+
+    /// Whether a failed signature is a valid signature of one of the wallet's
+    /// earlier, no longer open challenges
+    pub async fn is_replay(&self, wallet_address: &str, signature: &str) -> Result<bool, ApiError> {
+        let messages: Vec<String> = sqlx::query_scalar(
+            "SELECT message FROM wallet_challenges
+             WHERE wallet_address = $1 AND status <> 'issued'
+             ORDER BY issued_at DESC
+             LIMIT $2",
+        )
+        .bind(wallet_address)
+        .bind(REPLAY_LOOKBACK)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(messages
+            .iter()
+            .any(|message| verify_message_signature(wallet_address, signature, message.as_bytes()) == Ok(true)))
+    }
+
+    /// Log a failed attempt and block the wallet once it crosses the threshold
+    pub async fn record_failure(
+        &self,
+        user_id: Uuid,
+        wallet_address: &str,
+        message: Option<&str>,
+        failure: ChallengeFailure,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO wallet_auth_failures (wallet_address, user_id, challenge_id, reason)
+             VALUES ($1, $2, (SELECT id FROM wallet_challenges WHERE message = $3 LIMIT 1), $4)",
+        )
+        .bind(wallet_address)
+        .bind(user_id)
+        .bind(message)
+        .bind(failure.as_str())
+        .execute(&self.db)
+        .await?;
+        warn!(
+            "🔐 Failed wallet challenge for {} by user {}: {}",
+            wallet_address,
+            user_id,
+            failure.as_str()
+        );
+
+        let now = Utc::now();
+        let failures: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM wallet_auth_failures WHERE wallet_address = $1 AND created_at > $2",
+        )
+        .bind(wallet_address)
+        .bind(now - Duration::minutes(FAILURE_WINDOW_MINUTES))
+        .fetch_one(&self.db)
+        .await?;
+        if !should_block(failures) {
+            return Ok(());
+        }
+
+        let blocked_until = now + Duration::hours(BLOCK_HOURS);
+        let placed = sqlx::query(
+            "INSERT INTO wallet_auth_blocks (wallet_address, failures, blocked_until)
+             SELECT $1, $2, $3
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM wallet_auth_blocks
+                 WHERE wallet_address = $1 AND released_at IS NULL AND blocked_until > NOW()
+             )",
+        )
+        .bind(wallet_address)
+        .bind(failures as i32)
+        .bind(blocked_until)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+        if placed == 0 {
+            return Ok(());
+        }
+
+        warn!(
+            "🚫 Wallet {} blocked until {} after {} failed signature attempts",
+            wallet_address, blocked_until, failures
+        );
+        self.audit.log_async(AuditEvent::WalletAuthBlocked {
+            user_id,
+            wallet_address: wallet_address.to_string(),
+            failures,
+            blocked_until: blocked_until.to_rfc3339(),
+        });
+        let details = format!(
+            "{} failed signature challenges for wallet {} within {} minutes; blocked until {}",
+            failures,
+            wallet_address,
+            FAILURE_WINDOW_MINUTES,
+            blocked_until.to_rfc3339()
+        );
+        let window_start = now.duration_trunc(Duration::hours(1)).unwrap_or(now);
+        self.api_usage
+            .flag(user_id, AnomalyKind::WalletSignatureFailures, &details, window_start)
+            .await?;
+        Ok(())
+    }
+
+    /// Challenge activity over the last `hours` hours
+    pub async fn report(&self, hours: i64) -> Result<WalletChallengeReport, ApiError> {
+        let since = Utc::now() - Duration::hours(hours);
+
+        let (issued, consumed, superseded, expired_unused): (i64, i64, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*),
+                   COUNT(*) FILTER (WHERE status = 'consumed'),
+                   COUNT(*) FILTER (WHERE status = 'superseded'),
+                   COUNT(*) FILTER (WHERE status = 'issued' AND expires_at <= NOW())
+            FROM wallet_challenges
+            WHERE issued_at >= $1
+            "#,
+        )
+        .bind(since)
+        .fetch_one(&self.db)
+        .await?;
+
+        let failures = sqlx::query_as::<_, FailureCount>(
+            "SELECT reason, COUNT(*) AS count FROM wallet_auth_failures
+             WHERE created_at >= $1 GROUP BY reason ORDER BY count DESC",
+        )
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?;
+
+        let top_wallets = sqlx::query_as::<_, WalletFailureSummary>(
+            r#"
+            SELECT f.wallet_address,
+                   COUNT(*) AS failures,
+                   COUNT(*) FILTER (WHERE f.reason = 'replay') AS replays,
+                   COUNT(DISTINCT f.user_id) AS users,
+                   MAX(f.created_at) AS last_failure_at,
+                   EXISTS(
+                       SELECT 1 FROM wallet_auth_blocks b
+                       WHERE b.wallet_address = f.wallet_address AND b.released_at IS NULL AND b.blocked_until > NOW()
+                   ) AS blocked
+            FROM wallet_auth_failures f
+            WHERE f.created_at >= $1
+            GROUP BY f.wallet_address
+            ORDER BY failures DESC
+            LIMIT 20
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?;
+
+        let active_blocks = sqlx::query_as::<_, WalletAuthBlock>(&format!(
+            "SELECT {} FROM wallet_auth_blocks WHERE released_at IS NULL AND blocked_until > NOW() ORDER BY blocked_at DESC",
+            BLOCK_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(WalletChallengeReport {
+            since,
+            issued,
+            consumed,
+            superseded,
+            expired_unused,
+            failures,
+            top_wallets,
+            active_blocks,
+            max_failures_per_window: MAX_FAILURES_PER_WINDOW,
+            failure_window_minutes: FAILURE_WINDOW_MINUTES,
+        })
+    }
+
+    /// Lift a block before it runs out
+    pub async fn release(&self, admin_id: Uuid, block_id: Uuid) -> Result<WalletAuthBlock, ApiError> {
+        let block = sqlx::query_as::<_, WalletAuthBlock>(&format!(
+            "UPDATE wallet_auth_blocks SET released_by = $2, released_at = NOW()
+             WHERE id = $1 AND released_at IS NULL
+             RETURNING {}",
+            BLOCK_COLUMNS
+        ))
+        .bind(block_id)
+        .bind(admin_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Active wallet block not found".to_string()))?;
+
+        self.audit.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "wallet_auth_block_released".to_string(),
+            target_user_id: None,
+            details: serde_json::json!({
+                "block_id": block.id,
+                "wallet_address": block.wallet_address,
+            })
+            .to_string(),
+        });
+        Ok(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_threshold() {
+        assert!(!should_block(MAX_FAILURES_PER_WINDOW - 1));
+        assert!(should_block(MAX_FAILURES_PER_WINDOW));
+        assert_eq!(ChallengeFailure::Replay.as_str(), "replay");
+    }
+}
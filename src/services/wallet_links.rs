@@ -0,0 +1,426 @@
+//! Wallet Linking
+//!
+//! A wallet becomes the account wallet only with proof that the user
+//! controls it: an Ed25519 signature of a short-lived challenge, or a
+//! confirmed transaction paid by the wallet that carries the challenge memo.
+//! Challenges and failed proofs go through the wallet challenge audit, and
+//! every wallet the account has held is kept in `wallet_link_history`.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use rand::{rngs::OsRng, RngCore};
+use serde::Serialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::blockchain::ConfirmedTransaction;
+use crate::services::wallet_challenges::{ChallengeFailure, WalletChallengeService};
+use crate::services::{AuditEvent, AuditLogger, BlockchainService};
+use crate::utils::verify_message_signature;
+
+/// How long a link challenge can be answered
+pub const CHALLENGE_TTL_MINUTES: i64 = 15;
+
+/// Purpose recorded with wallet link challenges
+const CHALLENGE_PURPOSE: &str = "wallet_link";
+
+/// SPL Memo program (v2 and v1)
+const MEMO_PROGRAM_IDS: [&str; 2] = [
+    "MemoSq4gqABAXKb96qnH8TYDNMUT6RtyrL9TEBPpy4PS",
+    "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo",
+];
+
+const LINK_COLUMNS: &str = "id, wallet_address, method, proof, linked_at, unlinked_at";
+
+/// Challenge to answer with either proof
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WalletLinkChallenge {
+    pub wallet_address: String,
+    /// Message to sign with the wallet key
+    pub message: String,
+    /// Memo to send in a transaction paid by the wallet
+    pub memo: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Proof that the user controls a wallet
+#[derive(Debug, Clone)]
+pub enum WalletLinkProof {
+    /// Base58 Ed25519 signature of the challenge message
+    Signature(String),
+    /// Signature of a confirmed transaction carrying the challenge memo
+    MemoTransaction(String),
+}
+
+impl WalletLinkProof {
+    pub fn method(&self) -> &'static str {
+        match self {
+            Self::Signature(_) => "signature",
+            Self::MemoTransaction(_) => "memo_transaction",
+        }
+    }
+}
+
+/// A wallet the account holds or has held
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct WalletLink {
+    pub id: Uuid,
+    pub wallet_address: String,
+    /// signature, memo_transaction, custodial, or unverified for wallets
+    /// set before proof was required
+    pub method: String,
+    /// Message signature or memo transaction signature
+    pub proof: Option<String>,
+    /// `None` when the wallet predates the history
+    pub linked_at: Option<DateTime<Utc>>,
+    /// `None` for the current wallet
+    pub unlinked_at: Option<DateTime<Utc>>,
+}
+
+/// Challenge text signed to prove control of `wallet_address`
+pub fn challenge_message(user_id: Uuid, wallet_address: &str, nonce: &str, expires_at: DateTime<Utc>) -> String {
+    format!(
+        "GRIDTOKENX_WALLET_LINK\nuser: {}\naddress: {}\nnonce: {}\nexpires: {}",
+        user_id,
+        wallet_address,
+        nonce,
+        expires_at.to_rfc3339()
+    )
+}
+
+/// Memo a proof transaction has to carry
+pub fn challenge_memo(nonce: &str) -> String {
+    format!("gridtokenx-wallet-link:{}", nonce)
+}
+
+/// Whether `tx` succeeded, was paid by `wallet` and carries `memo`
+pub fn transaction_proves(tx: &ConfirmedTransaction, wallet: &Pubkey, memo: &str) -> bool {
+    let memo_programs: Vec<Pubkey> = MEMO_PROGRAM_IDS
+        .iter()
+        .filter_map(|id| Pubkey::from_str(id).ok())
+        .collect();
+    !tx.failed
+        && tx.fee_payer.as_ref() == Some(wallet)
+        && tx
+            .instructions
+            .iter()
+            .any(|ix| memo_programs.contains(&ix.program_id) && ix.data == memo.as_bytes())
+}
+
+fn new_nonce() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn parse_address(wallet_address: &str) -> Result<Pubkey, ApiError> {
+    Pubkey::from_str(wallet_address.trim())
+        .map_err(|_| ApiError::validation_field("wallet_address", "Invalid Solana wallet address"))
+}
+
+#[derive(Clone)]
+pub struct WalletLinkService {
+    db: PgPool,
+    audit: AuditLogger,
+    challenges: WalletChallengeService,
+    blockchain: BlockchainService,
+}
+
+impl WalletLinkService {
+    pub fn new(
+        db: PgPool,
+        audit: AuditLogger,
+        challenges: WalletChallengeService,
+        blockchain: BlockchainService,
+    ) -> Self {
+        Self { db, audit, challenges, blockchain }
+    }
+
+    /// Issue a challenge for linking `wallet_address`, superseding the open one
+    pub async fn challenge(&self, user_id: Uuid, wallet_address: &str) -> Result<WalletLinkChallenge, ApiError> {
+        let wallet_address = parse_address(wallet_address)?.to_string();
+        self.challenges.ensure_allowed(&wallet_address).await?;
+        self.ensure_available(user_id, &wallet_address).await?;
+
+        let expires_at = Utc::now() + Duration::minutes(CHALLENGE_TTL_MINUTES);
+        let nonce = new_nonce();
+        let message = challenge_message(user_id, &wallet_address, &nonce, expires_at);
+        self.challenges
+            .issue(user_id, &wallet_address, CHALLENGE_PURPOSE, &nonce, &message, expires_at)
+            .await?;
+
+        Ok(WalletLinkChallenge {
+            memo: challenge_memo(&nonce),
+            wallet_address,
+            message,
+            expires_at,
+        })
+    }
+
+    /// Make `wallet_address` the account wallet once `proof` answers the open
+    /// challenge. Each challenge can be used once; failures count towards a
+    /// wallet block.
+    pub async fn link(&self, user_id: Uuid, wallet_address: &str, proof: WalletLinkProof) -> Result<WalletLink, ApiError> {
+        let wallet = parse_address(wallet_address)?;
+        let wallet_address = wallet.to_string();
+        self.challenges.ensure_allowed(&wallet_address).await?;
+        self.ensure_available(user_id, &wallet_address).await?;
+
+        let Some((nonce, message, expires_at)) = self
+            .challenges
+            .open_challenge(user_id, &wallet_address, CHALLENGE_PURPOSE)
+            .await?
+        else {
+            return Err(ApiError::Conflict("Request a wallet link challenge first".to_string()));
+        };
+        if expires_at <= Utc::now() {
+            self.challenges
+                .record_failure(user_id, &wallet_address, Some(&message), ChallengeFailure::Expired)
+                .await?;
+            return Err(ApiError::Conflict("Wallet link challenge expired, request a new one".to_string()));
+        }
+
+        let proof_reference = match &proof {
+            WalletLinkProof::Signature(signature) => {
+                let signature = signature.trim();
+                self.verify_signature(user_id, &wallet_address, &message, signature).await?;
+                signature.to_string()
+            }
+            WalletLinkProof::MemoTransaction(tx_signature) => {
+                let tx_signature = tx_signature.trim();
+                self.verify_memo_transaction(user_id, &wallet, &message, &challenge_memo(&nonce), tx_signature)
+                    .await?;
+                tx_signature.to_string()
+            }
+        };
+
+        if !self.challenges.consume(user_id, &message).await? {
+            self.challenges
+                .record_failure(user_id, &wallet_address, Some(&message), ChallengeFailure::Replay)
+                .await?;
+            return Err(ApiError::Conflict(
+                "Wallet link challenge was already used, request a new one".to_string(),
+            ));
+        }
+
+        self.record_link(user_id, &wallet_address, proof.method(), Some(&proof_reference))
+            .await
+    }
+
+    /// Record a custodial wallet generated by the platform for the user
+    pub async fn record_custodial(&self, user_id: Uuid, wallet_address: &str) -> Result<WalletLink, ApiError> {
+        self.record_link(user_id, wallet_address, "custodial", None).await
+    }
+
+    /// Wallets the account holds and has held, newest first
+    pub async fn history(&self, user_id: Uuid) -> Result<Vec<WalletLink>, ApiError> {
+        Ok(sqlx::query_as::<_, WalletLink>(&format!(
+            "SELECT {} FROM wallet_link_history WHERE user_id = $1
+             ORDER BY unlinked_at DESC NULLS FIRST, linked_at DESC NULLS LAST",
+            LINK_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Refuse wallets that are already another account's wallet
+    async fn ensure_available(&self, user_id: Uuid, wallet_address: &str) -> Result<(), ApiError> {
+        let owner: Option<Uuid> = sqlx::query_scalar("SELECT id FROM users WHERE wallet_address = $1")
+            .bind(wallet_address)
+            .fetch_optional(&self.db)
+            .await?;
+        match owner {
+            Some(owner) if owner == user_id => Err(ApiError::Conflict("This wallet is already your account wallet".to_string())),
+            Some(_) => Err(ApiError::Conflict("This wallet is linked to another account".to_string())),
+            None => Ok(()),
+        }
+    }
+
+    async fn verify_signature(&self, user_id: Uuid, wallet_address: &str, message: &str, signature: &str) -> Result<(), ApiError> {
+        let valid = match verify_message_signature(wallet_address, signature, message.as_bytes()) {
+            Ok(valid) => valid,
+            Err(e) => {
+                self.challenges
+                    .record_failure(user_id, wallet_address, Some(message), ChallengeFailure::MalformedSignature)
+                    .await?;
+                return Err(ApiError::validation_field("signature", e));
+            }
+        };
+        if !valid {
+            let failure = if self.challenges.is_replay(wallet_address, signature).await? {
+                ChallengeFailure::Replay
+            } else {
+                ChallengeFailure::BadSignature
+            };
+            self.challenges
+                .record_failure(user_id, wallet_address, Some(message), failure)
+                .await?;
+            return Err(ApiError::validation_field("signature", "Signature does not match the wallet"));
+        }
+        Ok(())
+    }
+
+    async fn verify_memo_transaction(
+        &self,
+        user_id: Uuid,
+        wallet: &Pubkey,
+        message: &str,
+        memo: &str,
+        tx_signature: &str,
+    ) -> Result<(), ApiError> {
+        let wallet_address = wallet.to_string();
+        let Ok(signature) = Signature::from_str(tx_signature) else {
+            self.challenges
+                .record_failure(user_id, &wallet_address, Some(message), ChallengeFailure::MalformedSignature)
+                .await?;
+            return Err(ApiError::validation_field("transaction_signature", "Invalid transaction signature"));
+        };
+
+        // Not found usually means not confirmed yet; the user can retry
+        let tx = self.blockchain.get_confirmed_transaction(&signature).await.map_err(|e| {
+            warn!("Wallet link transaction {} not available: {}", signature, e);
+            ApiError::validation_field("transaction_signature", "Transaction not found or not confirmed yet")
+        })?;
+
+        if !transaction_proves(&tx, wallet, memo) {
+            self.challenges
+                .record_failure(user_id, &wallet_address, Some(message), ChallengeFailure::BadSignature)
+                .await?;
+            return Err(ApiError::validation_field(
+                "transaction_signature",
+                "Transaction must succeed, be paid by the wallet and carry the challenge memo",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Close the current link, keeping a wallet set outside this flow in the
+    /// history, and make `wallet_address` the account wallet
+    async fn record_link(
+        &self,
+        user_id: Uuid,
+        wallet_address: &str,
+        method: &str,
+        proof: Option<&str>,
+    ) -> Result<WalletLink, ApiError> {
+        let mut tx = self.db.begin().await?;
+
+        let previous_wallet: Option<String> =
+            sqlx::query_scalar("SELECT wallet_address FROM users WHERE id = $1 FOR UPDATE")
+                .bind(user_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO wallet_link_history (user_id, wallet_address, method, unlinked_at)
+             SELECT u.id, u.wallet_address, 'unverified', NOW() FROM users u
+             WHERE u.id = $1 AND u.wallet_address IS NOT NULL
+               AND NOT EXISTS (
+                   SELECT 1 FROM wallet_link_history h
+                   WHERE h.user_id = u.id AND h.wallet_address = u.wallet_address AND h.unlinked_at IS NULL
+               )",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE wallet_link_history SET unlinked_at = NOW() WHERE user_id = $1 AND unlinked_at IS NULL")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let link = sqlx::query_as::<_, WalletLink>(&format!(
+            "INSERT INTO wallet_link_history (user_id, wallet_address, method, proof, linked_at)
+             VALUES ($1, $2, $3, $4, NOW())
+             RETURNING {}",
+            LINK_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(wallet_address)
+        .bind(method)
+        .bind(proof)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE users SET wallet_address = $2, blockchain_registered = true, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(user_id)
+        .bind(wallet_address)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                ApiError::Conflict("This wallet is linked to another account".to_string())
+            }
+            e => ApiError::Database(e),
+        })?;
+
+        tx.commit().await?;
+
+        info!("User {} linked wallet {} ({})", user_id, wallet_address, method);
+        self.audit.log_async(AuditEvent::WalletLinked {
+            user_id,
+            wallet_address: wallet_address.to_string(),
+            previous_wallet,
+            method: method.to_string(),
+        });
+        Ok(link)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::blockchain::RawInstruction;
+
+    fn memo_tx(payer: Pubkey, program: &str, memo: &str) -> ConfirmedTransaction {
+        ConfirmedTransaction {
+            slot: 1,
+            block_time: None,
+            failed: false,
+            error: None,
+            fee_payer: Some(payer),
+            instructions: vec![RawInstruction {
+                program_id: Pubkey::from_str(program).unwrap(),
+                accounts: vec![payer],
+                data: memo.as_bytes().to_vec(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_memo_transaction_proves_control() {
+        let wallet = Pubkey::new_unique();
+        let memo = challenge_memo("abc123");
+        assert!(transaction_proves(&memo_tx(wallet, MEMO_PROGRAM_IDS[0], &memo), &wallet, &memo));
+        assert!(transaction_proves(&memo_tx(wallet, MEMO_PROGRAM_IDS[1], &memo), &wallet, &memo));
+    }
+
+    #[test]
+    fn test_memo_transaction_rejections() {
+        let wallet = Pubkey::new_unique();
+        let memo = challenge_memo("abc123");
+
+        // Paid by someone else
+        assert!(!transaction_proves(&memo_tx(Pubkey::new_unique(), MEMO_PROGRAM_IDS[0], &memo), &wallet, &memo));
+        // Different memo
+        assert!(!transaction_proves(
+            &memo_tx(wallet, MEMO_PROGRAM_IDS[0], &challenge_memo("other")),
+            &wallet,
+            &memo
+        ));
+        // Memo text sent to another program
+        assert!(!transaction_proves(&memo_tx(wallet, "11111111111111111111111111111111", &memo), &wallet, &memo));
+        // Failed transaction
+        let mut failed = memo_tx(wallet, MEMO_PROGRAM_IDS[0], &memo);
+        failed.failed = true;
+        assert!(!transaction_proves(&failed, &wallet, &memo));
+    }
+}
@@ -8,6 +8,9 @@ use tracing::{error, info, warn};
 pub mod types;
 pub use types::WebhookPayload;
 
+/// Delivery attempts per event
+const MAX_RETRIES: u32 = 3;
+
 /// Webhook Dispatcher Service
 #[derive(Clone)]
 pub struct WebhookService {
@@ -37,6 +40,26 @@ impl WebhookService {
             None => return Ok(()), // Webhook disabled
         };
 
+        let payload = self.build_payload(event_type, data, self.webhook_secret.as_deref())?;
+        if !self.post_with_retries(url, &payload).await {
+            error!("Failed to send webhook after {} attempts", MAX_RETRIES);
+        }
+
+        Ok(())
+    }
+
+    /// Send an event to a subscriber's own endpoint, signed with its secret.
+    /// Unlike `send_webhook`, fails when every attempt failed.
+    pub async fn send_to(&self, url: &str, secret: &str, event_type: &str, data: serde_json::Value) -> Result<()> {
+        let payload = self.build_payload(event_type, data, Some(secret))?;
+        if self.post_with_retries(url, &payload).await {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Webhook delivery failed after {} attempts", MAX_RETRIES))
+        }
+    }
+
+    fn build_payload(&self, event_type: &str, data: serde_json::Value, secret: Option<&str>) -> Result<WebhookPayload> {
         let event_id = uuid::Uuid::new_v4().to_string();
         let timestamp = chrono::Utc::now().to_rfc3339();
 
@@ -49,23 +72,26 @@ impl WebhookService {
         };
 
         // Sign payload if secret is provided
-        if let Some(secret) = &self.webhook_secret {
+        if let Some(secret) = secret {
             let signature = self.sign_payload(&payload, secret)?;
             payload.signature = Some(signature);
         }
 
-        // Send request with retries
+        Ok(payload)
+    }
+
+    /// POST the payload with retries; true once a request succeeded
+    async fn post_with_retries(&self, url: &str, payload: &WebhookPayload) -> bool {
         let mut attempts = 0;
-        let max_retries = 3;
         let mut backoff = Duration::from_millis(500);
 
         loop {
             attempts += 1;
-            match self.client.post(url).json(&payload).send().await {
+            match self.client.post(url).json(payload).send().await {
                 Ok(res) => {
                     if res.status().is_success() {
                         info!("Webhook sent successfully for event {}", payload.event_type);
-                        return Ok(());
+                        return true;
                     } else {
                         warn!(
                             "Webhook failed with status {}: {}",
@@ -79,16 +105,13 @@ impl WebhookService {
                 }
             }
 
-            if attempts >= max_retries {
-                error!("Failed to send webhook after {} attempts", max_retries);
-                break;
+            if attempts >= MAX_RETRIES {
+                return false;
             }
 
             tokio::time::sleep(backoff).await;
             backoff *= 2;
         }
-
-        Ok(())
     }
 
     /// Sign payload using HMAC-SHA256
@@ -0,0 +1,146 @@
+//! Broadcast coalescing
+//!
+//! Grid status, order book and market statistics events carry full state, so
+//! a client only needs the latest one. The first event of a kind is sent at
+//! once; further events within the kind's window replace each other and the
+//! latest is sent when the window ends.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use super::MarketEvent;
+use crate::config::BroadcastThrottleConfig;
+
+/// Kinds of events coalesced independently of each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CoalesceKey {
+    GridStatus,
+    OrderBookSnapshot,
+    OrderBookBuy,
+    OrderBookSell,
+    MarketDepth,
+    MarketStats,
+}
+
+impl CoalesceKey {
+    /// Kind of a coalesced event; other events are sent as they come
+    pub fn of(event: &MarketEvent) -> Option<Self> {
+        match event {
+            MarketEvent::GridStatusUpdated { .. } => Some(Self::GridStatus),
+            MarketEvent::OrderBookSnapshot { .. } => Some(Self::OrderBookSnapshot),
+            MarketEvent::OrderBookBuyUpdate { .. } => Some(Self::OrderBookBuy),
+            MarketEvent::OrderBookSellUpdate { .. } => Some(Self::OrderBookSell),
+            MarketEvent::MarketDepthUpdate { .. } => Some(Self::MarketDepth),
+            MarketEvent::MarketStats { .. } => Some(Self::MarketStats),
+            _ => None,
+        }
+    }
+
+    pub fn window(&self, throttle: &BroadcastThrottleConfig) -> Duration {
+        Duration::from_millis(match self {
+            Self::GridStatus => throttle.grid_status_ms,
+            Self::OrderBookSnapshot | Self::OrderBookBuy | Self::OrderBookSell | Self::MarketDepth => {
+                throttle.order_book_ms
+            }
+            Self::MarketStats => throttle.market_stats_ms,
+        })
+    }
+}
+
+/// What to do with an event arriving at `now`
+#[derive(Debug, PartialEq, Eq)]
+pub enum Admission {
+    /// Send now and start a window
+    Send,
+    /// Hold the event and send it at the end of the current window
+    Hold { until: Instant },
+    /// A held event is already waiting; the new one replaces it
+    Replace,
+}
+
+pub fn admit(last_sent: Option<Instant>, holding: bool, window: Duration, now: Instant) -> Admission {
+    if window.is_zero() {
+        return Admission::Send;
+    }
+    if holding {
+        return Admission::Replace;
+    }
+    match last_sent {
+        Some(sent) if now < sent + window => Admission::Hold { until: sent + window },
+        _ => Admission::Send,
+    }
+}
+
+#[derive(Debug, Default)]
+struct Slot {
+    last_sent: Option<Instant>,
+    held: Option<MarketEvent>,
+}
+
+/// Per-kind coalescing state of a WebSocket service
+#[derive(Debug, Default)]
+pub struct Coalescer {
+    slots: Mutex<HashMap<CoalesceKey, Slot>>,
+}
+
+impl Coalescer {
+    /// Offer an event. Returns the event when it should be sent now, and
+    /// whether a flush at the end of the window needs scheduling.
+    pub fn offer(&self, key: CoalesceKey, event: MarketEvent, window: Duration) -> (Option<MarketEvent>, Option<Instant>) {
+        let now = Instant::now();
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = slots.entry(key).or_default();
+        match admit(slot.last_sent, slot.held.is_some(), window, now) {
+            Admission::Send => {
+                // An event held before the window was shortened is superseded
+                slot.held = None;
+                slot.last_sent = Some(now);
+                (Some(event), None)
+            }
+            Admission::Hold { until } => {
+                slot.held = Some(event);
+                (None, Some(until))
+            }
+            Admission::Replace => {
+                slot.held = Some(event);
+                (None, None)
+            }
+        }
+    }
+
+    /// Take the held event of a kind at the end of its window
+    pub fn flush(&self, key: CoalesceKey) -> Option<MarketEvent> {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = slots.get_mut(&key)?;
+        let event = slot.held.take()?;
+        slot.last_sent = Some(Instant::now());
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admit_sends_first_and_holds_within_window() {
+        let now = Instant::now();
+        let window = Duration::from_millis(500);
+        assert_eq!(admit(None, false, window, now), Admission::Send);
+        assert_eq!(
+            admit(Some(now), false, window, now + Duration::from_millis(100)),
+            Admission::Hold { until: now + window }
+        );
+        assert_eq!(admit(Some(now), true, window, now + Duration::from_millis(200)), Admission::Replace);
+        assert_eq!(admit(Some(now), false, window, now + window), Admission::Send);
+    }
+
+    #[test]
+    fn test_zero_window_sends_everything() {
+        let now = Instant::now();
+        assert_eq!(admit(Some(now), true, Duration::ZERO, now), Admission::Send);
+    }
+}
@@ -1,13 +1,21 @@
+pub mod coalesce;
+pub mod throttle;
 pub mod types;
 
 use axum::extract::ws::{Message, WebSocket};
 use futures::{stream::SplitSink, SinkExt, StreamExt};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::config::BroadcastThrottleConfig;
+use crate::models::{EnergyKwh, TokenAmount};
+use crate::services::participant_ids::ParticipantIds;
+use crate::services::server_clock;
+use coalesce::{CoalesceKey, Coalescer};
+
 pub use types::*;
 
 /// WebSocket client connection
@@ -17,10 +25,29 @@ struct Client {
     sender: SplitSink<WebSocket, Message>,
 }
 
+/// Connected market feed client and the channels it subscribed to
+#[derive(Debug)]
+struct Subscriber {
+    tx: mpsc::UnboundedSender<MarketEvent>,
+    channels: FxHashSet<String>,
+    /// Admin clients receive user IDs and wallets; everyone else public participant IDs
+    reveal_identities: bool,
+}
+
+impl Subscriber {
+    fn is_subscribed(&self, channel: &str) -> bool {
+        self.channels.iter().any(|s| channel_matches(s, channel))
+    }
+}
+
 /// WebSocket broadcast service
 #[derive(Clone, Debug)]
 pub struct WebSocketService {
-    clients: Arc<RwLock<FxHashMap<Uuid, mpsc::UnboundedSender<MarketEvent>>>>,
+    clients: Arc<RwLock<FxHashMap<Uuid, Subscriber>>>,
+    participant_ids: ParticipantIds,
+    /// Coalescing windows of high-frequency broadcasts
+    throttle: Arc<std::sync::RwLock<BroadcastThrottleConfig>>,
+    coalescer: Arc<Coalescer>,
 }
 
 impl WebSocketService {
@@ -29,17 +56,53 @@ impl WebSocketService {
         info!("🔌 Initializing WebSocket service for real-time market updates");
         Self {
             clients: Arc::new(RwLock::new(FxHashMap::default())),
+            participant_ids: ParticipantIds::ephemeral(),
+            throttle: Arc::new(std::sync::RwLock::new(BroadcastThrottleConfig {
+                grid_status_ms: 0,
+                order_book_ms: 0,
+                market_stats_ms: 0,
+            })),
+            coalescer: Arc::new(Coalescer::default()),
         }
     }
 
-    /// Register a new WebSocket client
-    pub async fn register_client(&self, socket: WebSocket) -> Uuid {
+    /// Coalesce high-frequency broadcasts within the given windows
+    pub fn with_throttle(self, throttle: BroadcastThrottleConfig) -> Self {
+        self.set_throttle(throttle);
+        self
+    }
+
+    /// Coalescing windows in effect
+    pub fn throttle(&self) -> BroadcastThrottleConfig {
+        *self.throttle.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Change the coalescing windows; takes effect with the next broadcast
+    pub fn set_throttle(&self, throttle: BroadcastThrottleConfig) {
+        *self.throttle.write().unwrap_or_else(|e| e.into_inner()) = throttle;
+    }
+
+    /// Public participant IDs shared with the REST endpoints and other instances
+    pub fn with_participant_ids(mut self, participant_ids: ParticipantIds) -> Self {
+        self.participant_ids = participant_ids;
+        self
+    }
+
+    /// Register a new WebSocket client, optionally pre-subscribed to `channels`
+    pub async fn register_client(&self, socket: WebSocket, channels: Vec<String>, reveal_identities: bool) -> Uuid {
         let client_id = Uuid::new_v4();
         let (sender, mut receiver) = socket.split();
         let (tx, mut rx) = mpsc::unbounded_channel::<MarketEvent>();
 
         // Store the client sender
-        self.clients.write().await.insert(client_id, tx);
+        self.clients.write().await.insert(
+            client_id,
+            Subscriber {
+                tx: tx.clone(),
+                channels: channels.into_iter().collect(),
+                reveal_identities,
+            },
+        );
 
         info!("✅ WebSocket client connected: {}", client_id);
 
@@ -59,8 +122,24 @@ impl WebSocketService {
                 let _ = sender.send(Message::Text(json.into())).await;
             }
 
-            // Forward market events to this client
-            while let Some(event) = rx.recv().await {
+            // Forward market events and heartbeats to this client
+            let mut heartbeat = tokio::time::interval(server_clock::HEARTBEAT_INTERVAL);
+            loop {
+                let event = tokio::select! {
+                    _ = heartbeat.tick() => {
+                        let time = server_clock::now();
+                        MarketEvent::Heartbeat {
+                            unix_ms: time.unix_ms,
+                            monotonic_ms: time.monotonic_ms,
+                            sequence: time.sequence,
+                            timestamp: time.server_time,
+                        }
+                    }
+                    event = rx.recv() => match event {
+                        Some(event) => event,
+                        None => break,
+                    },
+                };
                 match serde_json::to_string(&event) {
                     Ok(json) => {
                         if let Err(e) = sender.send(Message::Text(json.into())).await {
@@ -80,13 +159,40 @@ impl WebSocketService {
         });
 
         // Spawn task to handle incoming messages (ping/pong, subscriptions)
+        let clients = self.clients.clone();
         tokio::spawn(async move {
             while let Some(Ok(msg)) = receiver.next().await {
                 match msg {
-                    Message::Text(text) => {
-                        // Handle subscription messages if needed
-                        info!("Received message from client: {}", text);
-                    }
+                    Message::Text(text) => match serde_json::from_str::<ClientRequest>(&text) {
+                        Ok(ClientRequest::Ping { client_time }) => {
+                            let time = server_clock::now();
+                            let _ = tx.send(MarketEvent::Pong {
+                                client_time,
+                                unix_ms: time.unix_ms,
+                                monotonic_ms: time.monotonic_ms,
+                                sequence: time.sequence,
+                                timestamp: time.server_time,
+                            });
+                        }
+                        Ok(request) => {
+                            let mut clients = clients.write().await;
+                            if let Some(client) = clients.get_mut(&client_id) {
+                                match request {
+                                    ClientRequest::Subscribe { channels } => client.channels.extend(channels),
+                                    ClientRequest::Unsubscribe { channels } => {
+                                        for channel in &channels {
+                                            client.channels.remove(channel);
+                                        }
+                                    }
+                                    ClientRequest::Ping { .. } => {}
+                                }
+                                let mut channels: Vec<String> = client.channels.iter().cloned().collect();
+                                channels.sort();
+                                let _ = tx.send(MarketEvent::Subscriptions { channels });
+                            }
+                        }
+                        Err(_) => info!("Received message from client: {}", text),
+                    },
                     Message::Close(_) => {
                         info!("Client requested close");
                         break;
@@ -103,8 +209,28 @@ impl WebSocketService {
         client_id
     }
 
-    /// Broadcast a market event to all connected clients
+    /// Broadcast a market event to all connected clients. Grid status, order
+    /// book and market statistics events are coalesced within their window.
     pub async fn broadcast(&self, event: MarketEvent) {
+        let Some(key) = CoalesceKey::of(&event) else {
+            return self.send_to_all(event).await;
+        };
+        let (send_now, flush_at) = self.coalescer.offer(key, event, key.window(&self.throttle()));
+        if let Some(until) = flush_at {
+            let service = self.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep_until(until).await;
+                if let Some(event) = service.coalescer.flush(key) {
+                    service.send_to_all(event).await;
+                }
+            });
+        }
+        if let Some(event) = send_now {
+            self.send_to_all(event).await;
+        }
+    }
+
+    async fn send_to_all(&self, event: MarketEvent) {
         let clients = self.clients.read().await;
         let client_count = clients.len();
 
@@ -118,19 +244,34 @@ impl WebSocketService {
         );
 
         // Send to all clients
-        for (client_id, tx) in clients.iter() {
-            if let Err(e) = tx.send(event.clone()) {
+        let anonymized = self.participant_ids.anonymize(&event);
+        for (client_id, client) in clients.iter() {
+            let event = if client.reveal_identities { &event } else { &anonymized };
+            if let Err(e) = client.tx.send(event.clone()) {
                 warn!("Failed to send event to client {}: {}", client_id, e);
             }
         }
     }
 
+    /// Send an event only to clients subscribed to `channel`
+    pub async fn publish(&self, channel: &str, event: MarketEvent) {
+        let clients = self.clients.read().await;
+        let anonymized = self.participant_ids.anonymize(&event);
+
+        for (client_id, client) in clients.iter().filter(|(_, c)| c.is_subscribed(channel)) {
+            let event = if client.reveal_identities { &event } else { &anonymized };
+            if let Err(e) = client.tx.send(event.clone()) {
+                warn!("Failed to send {} event to client {}: {}", channel, client_id, e);
+            }
+        }
+    }
+
     /// Broadcast offer created event
     pub async fn broadcast_offer_created(
         &self,
         offer_id: String,
-        energy_amount: f64,
-        price_per_kwh: f64,
+        energy_amount: EnergyKwh,
+        price_per_kwh: TokenAmount,
         energy_source: String,
         location: String,
         created_by: String,
@@ -151,7 +292,7 @@ impl WebSocketService {
         &self,
         offer_id: String,
         status: String,
-        energy_amount: Option<f64>,
+        energy_amount: Option<EnergyKwh>,
     ) {
         self.broadcast(MarketEvent::OfferUpdated {
             offer_id,
@@ -165,8 +306,8 @@ impl WebSocketService {
     pub async fn broadcast_order_created(
         &self,
         order_id: String,
-        energy_amount: f64,
-        max_price_per_kwh: f64,
+        energy_amount: EnergyKwh,
+        max_price_per_kwh: TokenAmount,
         energy_source: Option<String>,
         created_by: String,
     ) {
@@ -186,8 +327,8 @@ impl WebSocketService {
         order_id: String,
         offer_id: String,
         transaction_id: String,
-        matched_amount: f64,
-        price_per_kwh: f64,
+        matched_amount: EnergyKwh,
+        price_per_kwh: TokenAmount,
     ) {
         self.broadcast(MarketEvent::OrderMatched {
             order_id,
@@ -221,8 +362,8 @@ impl WebSocketService {
         &self,
         total_active_offers: i64,
         total_pending_orders: i64,
-        average_price: f64,
-        total_volume_24h: f64,
+        average_price: TokenAmount,
+        total_volume_24h: EnergyKwh,
     ) {
         self.broadcast(MarketEvent::MarketStats {
             total_active_offers,
@@ -361,7 +502,7 @@ impl WebSocketService {
         user_id: &uuid::Uuid,
         wallet_address: &str,
         meter_serial: &str,
-        kwh_amount: f64,
+        kwh_amount: EnergyKwh,
         power: Option<f64>,
         voltage: Option<f64>,
         current: Option<f64>,
@@ -385,7 +526,7 @@ impl WebSocketService {
         user_id: &uuid::Uuid,
         wallet_address: &str,
         meter_serial: &str,
-        kwh_amount: f64,
+        kwh_amount: EnergyKwh,
         tokens_minted: u64,
         transaction_signature: &str,
     ) {
@@ -407,7 +548,7 @@ impl WebSocketService {
         user_id: &uuid::Uuid,
         wallet_address: &str,
         meter_serial: &str,
-        kwh_amount: f64,
+        kwh_amount: EnergyKwh,
         error_reason: &str,
     ) {
         self.broadcast(MarketEvent::MeterReadingValidationFailed {
@@ -442,9 +583,9 @@ impl WebSocketService {
     /// Broadcast aggregate grid status updated
     pub async fn broadcast_grid_status_updated(
         &self,
-        total_generation: f64,
-        total_consumption: f64,
-        net_balance: f64,
+        total_generation: EnergyKwh,
+        total_consumption: EnergyKwh,
+        net_balance: EnergyKwh,
         active_meters: i64,
         co2_saved_kg: f64,
         zones: std::collections::HashMap<i32, ZoneStatus>,
@@ -461,6 +602,62 @@ impl WebSocketService {
         .await;
     }
 
+    /// Broadcast a market session state transition
+    pub async fn broadcast_market_session_changed(
+        &self,
+        previous_state: crate::services::market_session::SessionState,
+        session: crate::services::market_session::MarketSession,
+    ) {
+        self.broadcast(MarketEvent::MarketSessionChanged {
+            previous_state,
+            state: session.state,
+            next_state: session.next_state,
+            next_transition_at: session.next_transition_at,
+            timestamp: chrono::Utc::now(),
+        })
+        .await;
+    }
+
+    /// Broadcast a trading halt starting, being rescheduled or ending
+    pub async fn broadcast_trading_halt_changed(
+        &self,
+        halt: &crate::services::trading_halts::TradingHalt,
+        halted: bool,
+    ) {
+        self.broadcast(MarketEvent::TradingHaltChanged {
+            halt_id: halt.id,
+            market_id: halt.market_id,
+            zone_id: halt.zone_id,
+            mode: halt.mode.clone(),
+            reason: halt.reason.clone(),
+            halted,
+            resume_at: halt.resume_at,
+            timestamp: chrono::Utc::now(),
+        })
+        .await;
+    }
+
+    /// Broadcast the outcome of an epoch clearing run
+    pub async fn broadcast_epoch_cleared(
+        &self,
+        epoch: &crate::services::market_clearing::MarketEpoch,
+        settlement_ids: Vec<Uuid>,
+    ) {
+        self.broadcast(MarketEvent::EpochCleared {
+            epoch_id: epoch.id,
+            epoch_number: epoch.epoch_number,
+            start_time: epoch.start_time,
+            end_time: epoch.end_time,
+            clearing_price: epoch.clearing_price.map(|p| p.to_string()),
+            total_volume: epoch.total_volume.unwrap_or_default().to_string(),
+            total_orders: epoch.total_orders.unwrap_or(0),
+            matched_orders: epoch.matched_orders.unwrap_or(0),
+            settlement_ids,
+            timestamp: chrono::Utc::now(),
+        })
+        .await;
+    }
+
     /// Broadcast a meter alert
     pub async fn broadcast_meter_alert(
         &self,
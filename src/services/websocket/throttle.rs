@@ -0,0 +1,126 @@
+//! Broadcast Throttle Settings
+//!
+//! Coalescing windows in effect: the environment configuration, or the
+//! windows an admin stored in `broadcast_throttle_settings`. Every instance
+//! reloads the stored windows periodically and applies them to its
+//! WebSocket service.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::WebSocketService;
+use crate::config::BroadcastThrottleConfig;
+use crate::error::{ApiError, Result};
+use crate::services::audit_logger::{AuditEvent, AuditLogger};
+
+/// Coalescing windows in effect
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EffectiveBroadcastThrottle {
+    pub windows: BroadcastThrottleConfig,
+    /// Windows of the environment configuration
+    pub defaults: BroadcastThrottleConfig,
+    /// Set when an admin replaced the environment configuration
+    pub updated_by: Option<Uuid>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow)]
+struct ThrottleRow {
+    windows: sqlx::types::Json<BroadcastThrottleConfig>,
+    updated_by: Option<Uuid>,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct BroadcastThrottleSettings {
+    db: PgPool,
+    audit_logger: AuditLogger,
+    websocket: WebSocketService,
+    base: BroadcastThrottleConfig,
+}
+
+impl BroadcastThrottleSettings {
+    pub fn new(db: PgPool, audit_logger: AuditLogger, websocket: WebSocketService, base: BroadcastThrottleConfig) -> Self {
+        websocket.set_throttle(base);
+        Self {
+            db,
+            audit_logger,
+            websocket,
+            base,
+        }
+    }
+
+    /// Windows in effect and where they come from
+    pub async fn effective(&self) -> Result<EffectiveBroadcastThrottle> {
+        let row = self.stored().await?;
+        Ok(EffectiveBroadcastThrottle {
+            windows: self.websocket.throttle(),
+            defaults: self.base,
+            updated_by: row.as_ref().and_then(|row| row.updated_by),
+            updated_at: row.map(|row| row.updated_at),
+        })
+    }
+
+    /// Apply the stored windows, or the environment configuration when none
+    /// are stored. Stored windows that no longer validate are ignored.
+    pub async fn reload(&self) -> Result<()> {
+        let windows = match self.stored().await? {
+            Some(row) if row.windows.0.validate().is_ok() => row.windows.0,
+            _ => self.base,
+        };
+        if windows != self.websocket.throttle() {
+            info!("📡 WebSocket broadcast windows applied: {:?}", windows);
+            self.websocket.set_throttle(windows);
+        }
+        Ok(())
+    }
+
+    /// Store and apply new windows
+    pub async fn update(&self, admin_id: Uuid, windows: BroadcastThrottleConfig) -> Result<EffectiveBroadcastThrottle> {
+        windows.validate().map_err(ApiError::Validation)?;
+
+        let row = sqlx::query_as::<_, ThrottleRow>(
+            r#"
+            INSERT INTO broadcast_throttle_settings (id, windows, updated_by, updated_at)
+            VALUES (TRUE, $1, $2, NOW())
+            ON CONFLICT (id) DO UPDATE SET
+                windows = EXCLUDED.windows,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = EXCLUDED.updated_at
+            RETURNING windows, updated_by, updated_at
+            "#,
+        )
+        .bind(sqlx::types::Json(&windows))
+        .bind(admin_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        self.audit_logger.log_async(AuditEvent::AdminAction {
+            admin_id,
+            action: "broadcast_throttle_updated".to_string(),
+            target_user_id: None,
+            details: serde_json::to_string(&windows).unwrap_or_default(),
+        });
+        info!("📡 WebSocket broadcast windows updated by {}: {:?}", admin_id, windows);
+
+        self.websocket.set_throttle(row.windows.0);
+        Ok(EffectiveBroadcastThrottle {
+            windows: row.windows.0,
+            defaults: self.base,
+            updated_by: row.updated_by,
+            updated_at: Some(row.updated_at),
+        })
+    }
+
+    async fn stored(&self) -> Result<Option<ThrottleRow>> {
+        Ok(sqlx::query_as::<_, ThrottleRow>(
+            "SELECT windows, updated_by, updated_at FROM broadcast_throttle_settings WHERE id",
+        )
+        .fetch_optional(&self.db)
+        .await?)
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::models::{EnergyKwh, TokenAmount};
+
 /// WebSocket message types for real-time market updates
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -8,8 +10,8 @@ pub enum MarketEvent {
     /// New offer created in the market
     OfferCreated {
         offer_id: String,
-        energy_amount: f64,
-        price_per_kwh: f64,
+        energy_amount: EnergyKwh,
+        price_per_kwh: TokenAmount,
         energy_source: String,
         location: String,
         created_by: String,
@@ -18,13 +20,13 @@ pub enum MarketEvent {
     OfferUpdated {
         offer_id: String,
         status: String,
-        energy_amount: Option<f64>,
+        energy_amount: Option<EnergyKwh>,
     },
     /// New order placed
     OrderCreated {
         order_id: String,
-        energy_amount: f64,
-        max_price_per_kwh: f64,
+        energy_amount: EnergyKwh,
+        max_price_per_kwh: TokenAmount,
         energy_source: Option<String>,
         created_by: String,
     },
@@ -33,8 +35,8 @@ pub enum MarketEvent {
         order_id: String,
         offer_id: String,
         transaction_id: String,
-        matched_amount: f64,
-        price_per_kwh: f64,
+        matched_amount: EnergyKwh,
+        price_per_kwh: TokenAmount,
     },
     /// Transaction status changed
     TransactionUpdated {
@@ -47,8 +49,8 @@ pub enum MarketEvent {
     MarketStats {
         total_active_offers: i64,
         total_pending_orders: i64,
-        average_price: f64,
-        total_volume_24h: f64,
+        average_price: TokenAmount,
+        total_volume_24h: EnergyKwh,
     },
     /// Order book update (buy side)
     OrderBookBuyUpdate {
@@ -98,7 +100,7 @@ pub enum MarketEvent {
         user_id: Uuid,
         wallet_address: String,
         meter_serial: String,
-        kwh_amount: f64,
+        kwh_amount: EnergyKwh,
         #[serde(skip_serializing_if = "Option::is_none")]
         power: Option<f64>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -113,7 +115,7 @@ pub enum MarketEvent {
         user_id: Uuid,
         wallet_address: String,
         meter_serial: String,
-        kwh_amount: f64,
+        kwh_amount: EnergyKwh,
         tokens_minted: u64,
         transaction_signature: String,
         timestamp: chrono::DateTime<chrono::Utc>,
@@ -124,7 +126,7 @@ pub enum MarketEvent {
         user_id: Uuid,
         wallet_address: String,
         meter_serial: String,
-        kwh_amount: f64,
+        kwh_amount: EnergyKwh,
         error_reason: String,
         timestamp: chrono::DateTime<chrono::Utc>,
     },
@@ -140,9 +142,9 @@ pub enum MarketEvent {
 
     /// Aggregate grid status updated
     GridStatusUpdated {
-        total_generation: f64,
-        total_consumption: f64,
-        net_balance: f64,
+        total_generation: EnergyKwh,
+        total_consumption: EnergyKwh,
+        net_balance: EnergyKwh,
         active_meters: i64,
         co2_saved_kg: f64,
         #[serde(skip_serializing_if = "std::collections::HashMap::is_empty")]
@@ -150,6 +152,112 @@ pub enum MarketEvent {
         timestamp: chrono::DateTime<chrono::Utc>,
     },
 
+    /// Market session state transition (pre-open, open, closed)
+    MarketSessionChanged {
+        previous_state: crate::services::market_session::SessionState,
+        state: crate::services::market_session::SessionState,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        next_state: Option<crate::services::market_session::SessionState>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        next_transition_at: Option<chrono::DateTime<chrono::Utc>>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// Trading halt started, rescheduled or ended
+    TradingHaltChanged {
+        halt_id: Uuid,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        market_id: Option<Uuid>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        zone_id: Option<i32>,
+        mode: String,
+        reason: String,
+        /// False once the halt is lifted or has resumed on schedule
+        halted: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resume_at: Option<chrono::DateTime<chrono::Utc>>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// Epoch closed, matched and handed to settlement
+    EpochCleared {
+        epoch_id: Uuid,
+        epoch_number: i64,
+        start_time: chrono::DateTime<chrono::Utc>,
+        end_time: chrono::DateTime<chrono::Utc>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        clearing_price: Option<String>,
+        total_volume: String,
+        total_orders: i64,
+        matched_orders: i64,
+        settlement_ids: Vec<Uuid>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// Current channel subscriptions, sent after every subscribe/unsubscribe
+    Subscriptions {
+        channels: Vec<String>,
+    },
+
+    /// Server clock, sent every `server_clock::HEARTBEAT_INTERVAL`
+    Heartbeat {
+        unix_ms: i64,
+        monotonic_ms: u64,
+        sequence: u64,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// Answer to a client `ping`, echoing its `client_time`
+    Pong {
+        client_time: Option<i64>,
+        unix_ms: i64,
+        monotonic_ms: u64,
+        sequence: u64,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// Futures mark price tick (channel `futures.mark_price.{symbol}`)
+    FuturesMarkPrice {
+        product_id: Uuid,
+        symbol: String,
+        mark_price: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        index_price: Option<String>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// Futures funding rate update (channel `futures.funding.{symbol}`)
+    FuturesFundingRate {
+        product_id: Uuid,
+        symbol: String,
+        funding_rate: String,
+        mark_price: String,
+        index_price: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// Futures position liquidated (channel `futures.liquidations`)
+    FuturesLiquidation {
+        position_id: Uuid,
+        product_id: Uuid,
+        symbol: String,
+        side: String,
+        quantity: String,
+        liquidation_price: String,
+        mark_price: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// Futures trade (channel `futures.trades.{symbol}`)
+    FuturesTrade {
+        product_id: Uuid,
+        symbol: String,
+        side: String,
+        quantity: String,
+        price: String,
+        executed_at: chrono::DateTime<chrono::Utc>,
+    },
+
     /// Meter alert event
     MeterAlert {
         meter_id: String,
@@ -163,9 +271,9 @@ pub enum MarketEvent {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ZoneStatus {
     pub zone_id: i32,
-    pub generation: f64,
-    pub consumption: f64,
-    pub net_balance: f64,
+    pub generation: EnergyKwh,
+    pub consumption: EnergyKwh,
+    pub net_balance: EnergyKwh,
     pub active_meters: i32,
 }
 
@@ -175,3 +283,61 @@ pub struct PriceLevel {
     pub price: String,
     pub volume: String,
 }
+
+/// Request sent by a market feed client, e.g.
+/// `{"action": "subscribe", "channels": ["futures.mark_price.*"]}`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ClientRequest {
+    Subscribe { channels: Vec<String> },
+    Unsubscribe { channels: Vec<String> },
+    /// Latency probe; `client_time` is the client clock in Unix milliseconds
+    Ping { client_time: Option<i64> },
+}
+
+/// Channel names for subscription-only feeds
+pub mod channels {
+    pub const FUTURES_LIQUIDATIONS: &str = "futures.liquidations";
+
+    pub fn futures_mark_price(symbol: &str) -> String {
+        format!("futures.mark_price.{}", symbol)
+    }
+
+    pub fn futures_funding(symbol: &str) -> String {
+        format!("futures.funding.{}", symbol)
+    }
+
+    pub fn futures_trades(symbol: &str) -> String {
+        format!("futures.trades.{}", symbol)
+    }
+}
+
+/// Whether `subscription` covers `channel`: an exact name, or a `prefix.*` wildcard
+pub fn channel_matches(subscription: &str, channel: &str) -> bool {
+    match subscription.strip_suffix('*') {
+        Some(prefix) => channel.starts_with(prefix),
+        None => subscription == channel,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_matches() {
+        assert!(channel_matches("futures.liquidations", channels::FUTURES_LIQUIDATIONS));
+        assert!(channel_matches("futures.mark_price.*", &channels::futures_mark_price("KWH-0126")));
+        assert!(channel_matches("futures.*", &channels::futures_trades("KWH-0126")));
+        assert!(!channel_matches("futures.mark_price.*", &channels::futures_funding("KWH-0126")));
+        assert!(!channel_matches("futures.trades.KWH-0126", &channels::futures_trades("KWH-0226")));
+    }
+
+    #[test]
+    fn test_parse_client_request() {
+        let request: ClientRequest =
+            serde_json::from_str(r#"{"action":"subscribe","channels":["futures.liquidations"]}"#).unwrap();
+        assert!(matches!(request, ClientRequest::Subscribe { channels } if channels.len() == 1));
+        assert!(serde_json::from_str::<ClientRequest>(r#"{"action":"ping"}"#).is_err());
+    }
+}
@@ -2,53 +2,131 @@
 //!
 //! Only initializes essential services for Simulator → Gateway → Anchor testing.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
 
 use crate::app_state::AppState;
 use crate::auth::jwt::{ApiKeyService, JwtService};
-use crate::config::Config;
+use crate::config::{Config, StartupPolicy};
 use crate::database;
+use crate::error::ApiError;
 use crate::services;
+use crate::services::jobs::{JobKind, LogLevel};
+
+pub mod report;
+pub mod warmup;
+
+use report::{with_retry, StartupReport};
 
 /// Initialize minimal application services and create the AppState.
+///
+/// Dependencies come up in order: infrastructure (metrics, PostgreSQL,
+/// migrations, schema check, Redis), then external services (Solana RPC,
+/// email, authority wallet), then the domain services built on them. Transient failures are
+/// retried; optional dependencies follow their `StartupPolicy`.
 pub async fn initialize_app(config: &Config) -> Result<AppState> {
     info!("🚀 Starting minimal Gateway for Simulator → Anchor testing");
 
+    let mut report = StartupReport::begin();
+    let retry_attempts = config.startup.retry_attempts;
+    let retry_delay = Duration::from_millis(config.startup.retry_base_delay_ms);
+    // Runtime state of optional integrations (email, cache, metrics)
+    let degradation = services::DegradationManager::new(&config.degradation);
+
+    // ------------------------------------------------------------------
+    // Stage 1: infrastructure (always required)
+    // ------------------------------------------------------------------
+
     // Initialize Prometheus metrics exporter
-    let metrics_handle = metrics_exporter_prometheus::PrometheusBuilder::new()
-        .install_recorder()
-        .map_err(|e| anyhow::anyhow!("Failed to install Prometheus recorder: {}", e))?;
-    info!("✅ Prometheus metrics initialized");
+    let started = Instant::now();
+    let metrics_handle = match prometheus_builder().and_then(|builder| {
+        builder
+            .install_recorder()
+            .map_err(|e| anyhow::anyhow!("Failed to install Prometheus recorder: {}", e))
+    }) {
+        Ok(handle) => {
+            report.ready("metrics", false, 1, started);
+            info!("✅ Prometheus metrics initialized");
+            handle
+        }
+        Err(e) => {
+            // Served from a recorder nothing reports to, so /metrics stays up but empty
+            degradation.mark_down(services::degradation::Subsystem::Metrics, &e);
+            report.failed("metrics", StartupPolicy::Degraded, 1, started, e)?;
+            metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder().handle()
+        }
+    };
 
     // Setup database connections
-    let db_pool = database::setup_database(&config.database_url).await?;
+    let started = Instant::now();
+    let (db_pool, attempts) = with_retry("PostgreSQL", retry_attempts, retry_delay, || {
+        database::setup_database(&config.database_url)
+    })
+    .await;
+    let db_pool = db_pool.context("PostgreSQL is required at startup")?;
+    report.ready("database", true, attempts, started);
     info!("✅ PostgreSQL connection established");
 
     // Run database migrations
+    let started = Instant::now();
     database::run_migrations(&db_pool).await?;
+    report.ready("migrations", true, 1, started);
     info!("✅ Database migrations completed");
 
-    // Setup Redis connection
-    let redis_client = setup_redis(config).await?;
+    // Verify the tables and columns runtime queries rely on
+    let started = Instant::now();
+    match database::schema_check::verify_schema(&db_pool)
+        .await
+        .and_then(|schema| schema.into_result())
+    {
+        Ok(schema) => {
+            report.ready("schema", config.startup.schema_check == StartupPolicy::FailFast, 1, started);
+            info!("✅ Database schema verified ({} tables)", schema.tables_checked);
+        }
+        Err(e) => report.failed("schema", config.startup.schema_check, 1, started, e)?,
+    }
+
+    // Setup Redis connection and cache
+    let started = Instant::now();
+    let (redis, attempts) = with_retry("Redis", retry_attempts, retry_delay, || async {
+        let client = setup_redis(config).await?;
+        let cache = services::CacheService::new(&config.redis_url)
+            .await?
+            .with_degradation(degradation.clone());
+        Ok((client, cache))
+    })
+    .await;
+    let (redis_client, cache_service) = redis.context("Redis is required at startup")?;
+    report.ready("redis", true, attempts, started);
     info!("✅ Redis connection established");
+    info!("✅ Cache service initialized");
 
-    // Initialize authentication services
-    let jwt_service = JwtService::new()?;
-    let api_key_service = ApiKeyService::new()?;
-    info!("✅ JWT and API key services initialized");
+    // Load personal data keys (created and wrapped by the KMS on first start)
+    let started = Instant::now();
+    let pii = services::PiiCipher::load(db_pool.clone(), initialize_pii_kms(config)?)
+        .await
+        .context("PII data keys are required at startup")?;
+    let pii_rotation = services::PiiRotationJob::new(db_pool.clone(), pii.clone(), config.pii.clone());
+    report.ready("pii_keys", true, 1, started);
+    info!("✅ PII encryption initialized ({} KMS)", config.pii.kms_provider);
 
-    // Initialize email service (optional)
-    let email_service = initialize_email_service(config);
-
-    // Initialize auth service
-    let auth = services::AuthService::new(
-        db_pool.clone(),
-        config.clone(),
-        email_service.clone(),
-        jwt_service.clone(),
+    // Resolve the tenant profile; refuse another tenant's database or chain
+    let started = Instant::now();
+    let deployment = services::DeploymentProfileService::load(&db_pool, config)
+        .await
+        .context("Deployment profile does not match this database")?;
+    report.ready("deployment_profile", true, 1, started);
+    info!(
+        "✅ Deployment profile {} loaded ({})",
+        deployment.profile().key,
+        deployment.branding().brand_name
     );
-    info!("✅ Auth service initialized");
+
+    // ------------------------------------------------------------------
+    // Stage 2: external services (policy per dependency)
+    // ------------------------------------------------------------------
 
     // Initialize blockchain service
     let blockchain_service = services::BlockchainService::new(
@@ -56,7 +134,49 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         "localnet".to_string(),
         config.solana_programs.clone(),
     )?;
-    info!("✅ Blockchain service initialized (RPC: {})", config.solana_rpc_url);
+    let started = Instant::now();
+    let (rpc, attempts) = with_retry("Solana RPC", retry_attempts, retry_delay, || async {
+        blockchain_service
+            .client()
+            .get_version()
+            .map_err(|e| anyhow::anyhow!("RPC unreachable: {}", e))
+    })
+    .await;
+    match rpc {
+        Ok(version) => {
+            report.ready("solana_rpc", config.startup.solana_rpc == StartupPolicy::FailFast, attempts, started);
+            info!(
+                "✅ Blockchain service initialized (RPC: {}, solana-core {})",
+                config.solana_rpc_url, version.solana_core
+            );
+        }
+        Err(e) => report.failed("solana_rpc", config.startup.solana_rpc, attempts, started, e)?,
+    }
+
+    // Initialize email service (optional)
+    let started = Instant::now();
+    let email_service = match initialize_email_service(config, &deployment) {
+        Ok(service) => {
+            report.ready("email", config.startup.email == StartupPolicy::FailFast, 1, started);
+            Some(service.with_degradation(degradation.clone(), config.degradation.email_queue_capacity))
+        }
+        Err(e) => {
+            degradation.mark_down(services::degradation::Subsystem::Email, &e);
+            report.failed("email", config.startup.email, 1, started, e)?;
+            None
+        }
+    };
+
+    // Balances cached for batched lookups, invalidated on gateway mints/transfers
+    let balance_cache = services::wallet::BalanceCache::new(
+        cache_service.clone(),
+        config.balance_cache_ttl_secs,
+    );
+    let blockchain_service = blockchain_service
+        .with_balance_cache(balance_cache.clone())
+        .with_rpc_cache(services::blockchain::RpcCache::new(&config.rpc_cache))
+        .with_confirmation(config.confirmation.clone())
+        .with_submission_queue(&config.submission_queue);
 
     // Initialize wallet service
     let wallet_service = if let Ok(path) = std::env::var("AUTHORITY_WALLET_PATH") {
@@ -64,17 +184,41 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         services::WalletService::with_path(&config.solana_rpc_url, path)
     } else {
         services::WalletService::new(&config.solana_rpc_url)
-    };
-    initialize_wallet(&wallet_service).await;
+    }
+    .with_balance_cache(balance_cache);
+    let started = Instant::now();
+    match initialize_wallet(&wallet_service).await {
+        Ok(()) => report.ready(
+            "authority_wallet",
+            config.startup.authority_wallet == StartupPolicy::FailFast,
+            1,
+            started,
+        ),
+        Err(e) => report.failed("authority_wallet", config.startup.authority_wallet, 1, started, e)?,
+    }
 
+    // ------------------------------------------------------------------
+    // Stage 3: domain services
+    // ------------------------------------------------------------------
 
-    // Initialize WebSocket service
-    let websocket_service = services::WebSocketService::new();
-    info!("✅ WebSocket service initialized");
+    // Initialize authentication services
+    let jwt_service = JwtService::new()?;
+    let api_key_service = ApiKeyService::new()?;
+    info!("✅ JWT and API key services initialized");
 
-    // Initialize cache service
-    let cache_service = services::CacheService::new(&config.redis_url).await?;
-    info!("✅ Cache service initialized");
+    // Initialize auth service
+    let auth = services::AuthService::new(
+        db_pool.clone(),
+        config.clone(),
+        email_service.clone(),
+        jwt_service.clone(),
+    );
+    info!("✅ Auth service initialized");
+
+    // Initialize WebSocket service; public feeds name users by participant IDs
+    let participant_ids = services::ParticipantIds::from_config(config);
+    let websocket_service = services::WebSocketService::new().with_participant_ids(participant_ids.clone());
+    info!("✅ WebSocket service initialized");
 
     // Initialize health checker
     let health_checker = services::HealthChecker::new(
@@ -86,13 +230,107 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
     info!("✅ Health checker initialized");
 
     // Initialize audit logger
-    let audit_logger = services::AuditLogger::new(db_pool.clone());
+    // GeoIP databases for audit enrichment (optional)
+    let started = Instant::now();
+    let geoip = match services::GeoIpService::open(
+        config.geoip.city_db_path.as_deref(),
+        config.geoip.asn_db_path.as_deref(),
+    ) {
+        Ok(geoip) => {
+            if geoip.is_enabled() {
+                report.ready("geoip", false, 1, started);
+                info!("✅ GeoIP databases loaded");
+            }
+            geoip
+        }
+        Err(e) => {
+            report.failed("geoip", StartupPolicy::Degraded, 1, started, e)?;
+            services::GeoIpService::default()
+        }
+    };
+
+    let mut audit_logger = services::AuditLogger::new(db_pool.clone()).with_geoip(geoip.clone());
+    if config.audit_buffer.max_events > 0 {
+        audit_logger = audit_logger.with_buffer(services::audit_logger::AuditBuffer::new(
+            redis_client.clone(),
+            config.audit_buffer.max_events,
+        ));
+    }
     info!("✅ Audit logger initialized");
 
     // Initialize ERC service
     let erc_service = services::ErcService::new(db_pool.clone(), blockchain_service.clone());
     info!("✅ ERC service initialized");
 
+    // Initialize notification dispatcher (user inbox and WebSocket unread counters)
+    let notification_dispatcher =
+        services::NotificationDispatcher::new(db_pool.clone(), services::NotificationDispatcherConfig::default());
+    info!("✅ Notification dispatcher initialized");
+
+    // Initialize budget alerts (spend, consumption and price thresholds)
+    let budget_alerts = services::BudgetAlertService::new(db_pool.clone(), notification_dispatcher.clone());
+    info!("✅ Budget alert service initialized");
+
+    // Initialize ERC expiry monitor (owner notifications via the notification center)
+    let erc_expiry = services::erc::ErcExpiryMonitor::new(
+        db_pool.clone(),
+        notification_dispatcher.clone(),
+        config.erc_expiry_warning_days,
+    );
+    info!("✅ ERC expiry monitor initialized (warning {} days ahead)", config.erc_expiry_warning_days);
+
+    // Initialize meter submission rate limiter
+    let rate_limiter = services::EnhancedRateLimiter::new(
+        db_pool.clone(),
+        audit_logger.clone(),
+        config.meter_submission_limit,
+        config.rate_limit_window,
+    );
+    info!(
+        "✅ Rate limiter initialized ({} submissions per {}s)",
+        config.meter_submission_limit, config.rate_limit_window
+    );
+
+    // Initialize third-party app delegation (consent flow, scoped tokens)
+    let delegation = services::DelegationService::new(db_pool.clone(), audit_logger.clone(), config.delegation);
+    info!("✅ App delegation initialized");
+
+    // Initialize FX rate service (fiat reference values for settlements)
+    let fx_rates = services::FxRateService::new(db_pool.clone(), config.currency.clone());
+    info!(
+        "✅ FX rate service initialized (display: {}, settlement: {:?})",
+        config.currency.display_currency, config.currency.settlement_asset
+    );
+
+    // Initialize emission factors (CO2 savings estimates)
+    let emission_factors = services::EmissionFactorService::new(db_pool.clone(), config.emissions.clone());
+    if let Err(e) = emission_factors.refresh().await {
+        warn!(
+            "⚠️ Failed to load emission factors, using {} kg CO2/kWh: {}",
+            config.emissions.fallback_kg_per_kwh, e
+        );
+    }
+
+    // Initialize market registry (per-market orderbooks and parameters)
+    let markets = services::MarketService::new(db_pool.clone());
+    info!("✅ Market registry initialized");
+
+    // Initialize trading halts (per market, per zone or platform-wide)
+    let trading_halts = services::TradingHaltService::new(db_pool.clone(), websocket_service.clone());
+    info!("✅ Trading halt service initialized");
+
+    // Initialize order book snapshots (post-trade analysis and surveillance)
+    let book_snapshots = services::OrderBookSnapshotService::new(db_pool.clone(), markets.clone());
+    info!("✅ Order book snapshot service initialized");
+
+    // Initialize swap pool statistics (hourly rollups of AMM swaps)
+    let swap_pool_stats = services::SwapPoolStatsService::new(db_pool.clone());
+    info!("✅ Swap pool stats service initialized");
+
+    // Initialize FIX session registry (the acceptor itself starts with the background tasks)
+    let fix_sessions = services::FixSessionService::new(db_pool.clone());
+    info!("✅ FIX session registry initialized");
+
     // Initialize market clearing service
     let market_clearing = services::MarketClearingService::new(
         db_pool.clone(),
@@ -116,20 +354,40 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         blockchain_service.clone(),
         settlement_config,
         config.encryption_secret.clone(),
-    );
+    )
+    .with_fx_rates(fx_rates.clone())
+    .with_pii(pii.clone());
     info!("✅ Settlement service initialized");
 
 
+    // Initialize market analytics aggregator
+    let market_analytics = services::MarketAnalyticsAggregator::new(
+        db_pool.clone(),
+        services::MarketAnalyticsConfig::default(),
+    );
+    info!("✅ Market analytics aggregator initialized");
+
+    // Initialize market session calendar
+    let market_session = services::MarketSessionService::new(
+        config.market_session.clone(),
+        websocket_service.clone(),
+    );
+    info!("✅ Market session service initialized (calendar enabled: {})", config.market_session.enabled);
+
     // Initialize matching engine
     let market_clearing_engine = services::OrderMatchingEngine::new(db_pool.clone())
         .with_websocket(websocket_service.clone())
         .with_settlement(settlement.clone())
         .with_market_clearing(market_clearing.clone())
-        .with_blockchain(blockchain_service.clone());
+        .with_blockchain(blockchain_service.clone())
+        .with_market_analytics(market_analytics.clone())
+        .with_market_session(market_session.clone())
+        .with_trading_halts(trading_halts.clone());
     info!("✅ Order matching engine initialized");
 
     // Initialize futures service
-    let futures_service = services::FuturesService::new(db_pool.clone());
+    let futures_service = services::FuturesService::new(db_pool.clone())
+        .with_websocket(websocket_service.clone());
     info!("✅ Futures service initialized");
 
     // Initialize webhook service
@@ -152,6 +410,376 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
     );
     info!("✅ Recurring scheduler service initialized");
 
+    // Initialize leaderboard service
+    let leaderboard_service = services::LeaderboardService::new(db_pool.clone(), emission_factors.clone());
+    info!("✅ Leaderboard service initialized");
+
+    // Initialize clearing price index service
+    let price_index_service = services::PriceIndexService::new(db_pool.clone());
+    info!("✅ Price index service initialized");
+
+    // Initialize invoicing service
+    let invoice_service = services::InvoiceService::new(
+        db_pool.clone(),
+        config.invoicing.clone(),
+        config.grid_tariff.clone(),
+        config.currency.display_currency.clone(),
+        pii.clone(),
+        deployment.timezone().clone(),
+    );
+    info!("✅ Invoice service initialized");
+
+    // Initialize prepaid credit service (payment provider top-ups)
+    let prepaid_service = services::PrepaidService::new(
+        db_pool.clone(),
+        config.payments.clone(),
+        audit_logger.clone(),
+    );
+    info!("✅ Prepaid service initialized (provider: {})", config.payments.provider);
+
+    // Initialize settlement dispute service
+    let dispute_service = services::DisputeService::new(
+        db_pool.clone(),
+        settlement.clone(),
+        audit_logger.clone(),
+    );
+    info!("✅ Dispute service initialized");
+
+    // Initialize admin trade bust / manual trade service (multi-signature approval)
+    let trade_admin_service = services::TradeAdminService::new(
+        db_pool.clone(),
+        market_clearing.clone(),
+        settlement.clone(),
+        audit_logger.clone(),
+        config.trade_approval.clone(),
+    );
+    info!(
+        "✅ Trade admin service initialized ({} approvals required)",
+        config.trade_approval.required_approvals
+    );
+
+    // Initialize vesting service (incentive grant programs)
+    let vesting_service = services::VestingService::new(db_pool.clone(), audit_logger.clone());
+    info!("✅ Vesting service initialized");
+
+    // Initialize guarded admin data repairs
+    let data_fixes = services::DataFixService::new(db_pool.clone(), audit_logger.clone());
+    info!("✅ Data fix service initialized");
+
+    // Initialize referral and rewards program
+    let referral_service = services::ReferralService::new(
+        db_pool.clone(),
+        blockchain_service.clone(),
+        wallet_service.clone(),
+        config.clone(),
+        audit_logger.clone(),
+    );
+    info!(
+        "✅ Referral service initialized (rewards: {} kWh referrer, {} kWh referee)",
+        config.referral.referrer_reward_kwh, config.referral.referee_reward_kwh
+    );
+
+    // Initialize market maker incentives (quote sampling and daily rebates)
+    let maker_incentives = services::MakerIncentiveService::new(
+        db_pool.clone(),
+        blockchain_service.clone(),
+        wallet_service.clone(),
+        config.clone(),
+    );
+    info!(
+        "✅ Maker incentives initialized (pool: {} kWh/day per market)",
+        config.maker_incentives.daily_pool_kwh
+    );
+
+    // Initialize delivery verification (settlements checked against meter readings)
+    let delivery_verification = services::DeliveryVerificationService::new(db_pool.clone(), config.clone());
+    info!(
+        "✅ Delivery verification initialized (enabled: {}, remedy: {})",
+        config.delivery_verification.enabled,
+        config.delivery_verification.remedy.as_str()
+    );
+
+    // Initialize imbalance settlement (traded vs metered net export per epoch)
+    let imbalance = services::ImbalanceService::new(db_pool.clone(), config.clone());
+    info!(
+        "✅ Imbalance settlement initialized (enabled: {}, price: {})",
+        config.imbalance.enabled,
+        config.imbalance.price_source.as_str()
+    );
+
+    // Initialize trading autopilot (sell orders for forecast surplus)
+    let autopilot = services::AutopilotService::new(
+        db_pool.clone(),
+        market_clearing.clone(),
+        config.autopilot.clone(),
+    );
+    info!("✅ Trading autopilot initialized (enabled: {})", config.autopilot.enabled);
+
+    // Initialize grid meter data (official DSO intervals and reconciliation)
+    let grid_meter_data = services::GridMeterDataService::new(db_pool.clone(), audit_logger.clone(), config.clone());
+    info!(
+        "✅ Grid meter data initialized (tolerance: {}, freeze after {} of {} intervals)",
+        config.grid_reconciliation.tolerance,
+        config.grid_reconciliation.freeze_after,
+        config.grid_reconciliation.window
+    );
+
+    // Initialize minting guard (per-wallet caps from rated meter capacity)
+    let mint_guard = services::MintGuardService::new(
+        db_pool.clone(),
+        config.clone(),
+        blockchain_service.clone(),
+        wallet_service.clone(),
+        audit_logger.clone(),
+    );
+    info!(
+        "✅ Minting guard initialized (enabled: {}, mode: {:?})",
+        config.mint_guard.enabled, config.mint_guard.mode
+    );
+
+    // Initialize meter installations (capacity, orientation, inverter)
+    let meter_installations = services::MeterInstallationService::new(db_pool.clone());
+    info!("✅ Meter installations initialized");
+
+    // Initialize tokenization settings (environment config plus runtime overrides)
+    let tokenization =
+        services::TokenizationSettings::new(db_pool.clone(), audit_logger.clone(), config.tokenization.clone());
+    if let Err(e) = tokenization.reload().await {
+        warn!("⚠️ Failed to load tokenization overrides: {}", e);
+    }
+    info!("✅ Tokenization settings initialized");
+
+    // Initialize WebSocket broadcast coalescing windows (environment config or runtime setting)
+    let broadcast_throttle = services::BroadcastThrottleSettings::new(
+        db_pool.clone(),
+        audit_logger.clone(),
+        websocket_service.clone(),
+        config.broadcast_throttle,
+    );
+    if let Err(e) = broadcast_throttle.reload().await {
+        warn!("⚠️ Failed to load WebSocket broadcast windows: {}", e);
+    }
+    info!("✅ Broadcast throttle initialized: {:?}", websocket_service.throttle());
+
+    // Initialize query governor for analytics, audit search and exports
+    let query_governor = database::QueryGovernor::new(db_pool.clone(), config.query_governor);
+    info!("✅ Query governor initialized: {:?}", config.query_governor);
+
+    // Initialize market statistics archive (daily rollups)
+    let market_archive = services::MarketArchiveService::new(db_pool.clone(), deployment.timezone().clone());
+    info!("✅ Market archive initialized");
+
+    // Initialize user data exports (built by a background job)
+    let data_exports = services::DataExportService::new(
+        db_pool.clone(),
+        config.data_exports.clone(),
+        notification_dispatcher.clone(),
+        deployment.timezone().clone(),
+    );
+    info!(
+        "✅ Data exports initialized (storage: {}, {} per day)",
+        config.data_exports.storage_dir, config.data_exports.daily_quota
+    );
+
+    // Initialize AMI backfill (staged readings, minted after admin approval)
+    let ami_backfill = services::AmiBackfillService::new(
+        db_pool.clone(),
+        config.clone(),
+        blockchain_service.clone(),
+        wallet_service.clone(),
+        grid_meter_data.clone(),
+        mint_guard.clone(),
+        audit_logger.clone(),
+    );
+    info!(
+        "✅ AMI backfill initialized (windows up to {} days back)",
+        config.ami_gateway.backfill_max_age_days
+    );
+
+    // Initialize planned maintenance windows (published on /api/v1/status)
+    let maintenance = services::MaintenanceService::new(db_pool.clone(), audit_logger.clone());
+    info!("✅ Maintenance service initialized");
+
+    // Initialize as-of ledger queries for audits
+    let ledger_history = services::LedgerHistoryService::new(db_pool.clone());
+    info!("✅ Ledger history service initialized");
+
+    // Initialize step-up verification of risky logins
+    let login_step_up = services::LoginStepUpService::new(
+        db_pool.clone(),
+        geoip,
+        email_service.clone(),
+        pii.clone(),
+        audit_logger.clone(),
+        config.step_up.clone(),
+    );
+    info!("✅ Login step-up initialized (enabled: {})", config.step_up.enabled);
+
+    // Initialize wallet sessions (device-bound, sliding expiry)
+    let wallet_sessions = services::WalletSessionService::new(db_pool.clone(), config.wallet_session.clone());
+    info!(
+        "✅ Wallet sessions initialized (idle {}h, max {} days, {} per user)",
+        config.wallet_session.idle_timeout_hours,
+        config.wallet_session.max_lifetime_days,
+        config.wallet_session.max_concurrent
+    );
+
+    // Initialize fee sponsorship of user transfers
+    let fee_sponsorship = services::FeeSponsorshipService::new(
+        db_pool.clone(),
+        config.fee_sponsorship.clone(),
+        audit_logger.clone(),
+    );
+    let token_transfers = services::TokenTransferService::new(
+        db_pool.clone(),
+        blockchain_service.clone(),
+        settlement.clone(),
+        fee_sponsorship.clone(),
+        config.energy_token_mint.clone(),
+    );
+    info!(
+        "✅ Fee sponsorship initialized (enabled: {}, {} free transfers)",
+        config.fee_sponsorship.enabled, config.fee_sponsorship.free_transfers
+    );
+
+    // Initialize scheduled report subscriptions
+    let report_subscriptions = services::ReportSubscriptionService::new(
+        db_pool.clone(),
+        config.report_subscriptions.clone(),
+        email_service.clone(),
+        webhook_service.clone(),
+        emission_factors.clone(),
+        pii.clone(),
+        deployment.timezone().clone(),
+    );
+    info!(
+        "✅ Report subscriptions initialized (enabled: {}, {} per user)",
+        config.report_subscriptions.enabled, config.report_subscriptions.max_per_user
+    );
+
+    // Initialize background job tracking
+    let jobs = services::JobQueue::new(db_pool.clone(), config.jobs.clone(), audit_logger.clone());
+    info!(
+        "✅ Background job tracking initialized (retention: {} days)",
+        config.jobs.retention_days
+    );
+
+    // Initialize developer sandbox (faucet, test users, scenarios)
+    let sandbox = services::SandboxService::new(
+        db_pool.clone(),
+        blockchain_service.clone(),
+        wallet_service.clone(),
+        config.clone(),
+        pii.clone(),
+    );
+    match services::sandbox::detect_cluster(&config.solana_rpc_url) {
+        Some(cluster) if config.sandbox.enabled => info!("✅ Sandbox enabled on {}", cluster.as_str()),
+        None if config.sandbox.enabled => warn!("⚠️ Sandbox enabled but RPC is not devnet or localnet; requests will be refused"),
+        _ => info!("✅ Sandbox initialized (disabled)"),
+    }
+
+    // Initialize B2B partner onboarding (keys, webhooks, sandbox users)
+    let partners = services::PartnerService::new(
+        db_pool.clone(),
+        api_key_service.clone(),
+        sandbox.clone(),
+        audit_logger.clone(),
+        config.data_exports.signed_urls.public_base_url.clone(),
+    );
+    info!("✅ Partner onboarding initialized");
+
+    // Initialize admin overview (aggregated operational summary)
+    let admin_overview = services::AdminOverviewService::new(
+        db_pool.clone(),
+        blockchain_service.clone(),
+        wallet_service.clone(),
+        metrics_handle.clone(),
+        degradation.clone(),
+        config.tokenization.max_retry_attempts,
+    );
+    info!("✅ Admin overview service initialized");
+
+    // Initialize per-user API usage analytics
+    let api_usage = services::ApiUsageService::new(db_pool.clone()).with_geo_velocity(config.geoip.max_travel_kmh);
+    info!("✅ API usage tracker initialized");
+
+    // Initialize attachment storage (KYC documents, dispute evidence, certificate data)
+    let attachment_service = services::AttachmentService::new(db_pool.clone(), config.attachments.clone())?;
+    info!("✅ Attachment service initialized (storage: {})", config.attachments.backend);
+
+    // Initialize network access control (config rules + runtime rules from DB)
+    let network_acl = services::NetworkAclService::new(
+        db_pool.clone(),
+        config.network_acl.clone(),
+        audit_logger.clone(),
+    );
+    match network_acl.reload().await {
+        Ok(count) => info!("✅ Network ACL initialized ({} runtime rules)", count),
+        Err(e) => warn!("⚠️ Failed to load network ACL rules, using configured rules only: {}", e),
+    }
+
+    // Initialize AMI gateway registry (mTLS client certificates)
+    let meter_gateway_service =
+        services::MeterGatewayService::new(db_pool.clone(), audit_logger.clone());
+    info!(
+        "✅ Meter gateway service initialized (client cert required: {})",
+        config.ami_gateway.require_client_cert
+    );
+
+    // Initialize meter firmware tracking and compatibility policies
+    let meter_firmware = services::MeterFirmwareService::new(db_pool.clone(), audit_logger.clone());
+    info!("✅ Meter firmware service initialized");
+
+    // Initialize meter fleet health counters and cached report
+    let meter_fleet_health = services::MeterFleetHealthService::new(db_pool.clone(), config.meter_fleet.clone());
+    info!("✅ Meter fleet health service initialized");
+
+    // Initialize meter registry sync (on-chain ownership accounts for verified meters)
+    let meter_registry_sync = services::MeterRegistrySyncService::new(
+        db_pool.clone(),
+        blockchain_service.clone(),
+        wallet_service.clone(),
+    );
+    info!("✅ Meter registry sync initialized");
+
+    // Initialize wallet challenge audit (replay protection, failed signature blocks)
+    let wallet_challenges =
+        services::WalletChallengeService::new(db_pool.clone(), api_usage.clone(), audit_logger.clone());
+    info!("✅ Wallet challenge audit initialized");
+
+    // Initialize wallet linking (proof of control, link history)
+    let wallet_links = services::WalletLinkService::new(
+        db_pool.clone(),
+        audit_logger.clone(),
+        wallet_challenges.clone(),
+        blockchain_service.clone(),
+    );
+    info!("✅ Wallet link service initialized");
+
+    // Initialize address book (saved transfer beneficiaries)
+    let address_book =
+        services::AddressBookService::new(db_pool.clone(), audit_logger.clone(), wallet_challenges.clone());
+    info!("✅ Address book service initialized");
+
+    // Initialize epoch clearing scheduler (match, settle and announce closed epochs)
+    let epoch_clearing = services::EpochClearingService::new(
+        db_pool.clone(),
+        market_clearing.clone(),
+        settlement.clone(),
+        websocket_service.clone(),
+        book_snapshots.clone(),
+        config.market_session.clone(),
+    );
+    info!("✅ Epoch clearing service initialized");
+
+    // Initialize audit retention (per-class purge, legal holds)
+    let audit_retention = services::AuditRetentionService::new(
+        db_pool.clone(),
+        config.audit_retention.clone(),
+        audit_logger.clone(),
+    );
+    info!("✅ Audit retention service initialized");
+
     // Initialize event processor service
     let event_processor = services::EventProcessorService::new(
         std::sync::Arc::new(db_pool.clone()),
@@ -167,16 +795,31 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         health_checker.clone(),
         event_processor.clone(),
         websocket_service.clone(),
+        emission_factors.clone(),
+        cache_service.clone(),
+    );
+    info!("✅ Dashboard service initialized");
+
+    // Initialize maintenance rebuilds of caches and aggregates
+    let rebuilds = services::RebuildService::new(
+        db_pool.clone(),
+        cache_service.clone(),
+        market_analytics.clone(),
+        dashboard_service.clone(),
+        market_clearing_engine.clone(),
+        audit_logger.clone(),
     );
-    info!("✅ Dashboard service initialized");
+    info!("✅ Maintenance rebuild service initialized");
 
     // Initialize HTTP Client
     let http_client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
+        .timeout(Duration::from_secs(10))
         .build()
         .map_err(|e| anyhow::anyhow!("Failed to create HTTP client: {}", e))?;
     info!("✅ HTTP client initialized");
 
+    report.finish();
+
     // Create minimal application state
     let app_state = AppState {
         db: db_pool,
@@ -190,6 +833,8 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         wallet_service,
         websocket_service,
         cache_service,
+        rate_limiter,
+        delegation,
         health_checker,
         audit_logger,
         market_clearing,
@@ -200,10 +845,76 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         event_processor: event_processor.clone(),
         price_monitor,
         recurring_scheduler,
+        market_analytics,
+        leaderboard_service,
+        price_index_service,
+        market_session,
+        invoice_service,
+        prepaid_service,
+        dispute_service,
+        trade_admin_service,
+        vesting_service,
+        data_fixes,
+        referral_service,
+        admin_overview,
+        api_usage,
+        attachment_service,
+        network_acl,
+        meter_gateway_service,
+        meter_firmware,
+        meter_fleet_health,
+        meter_registry_sync,
+        address_book,
+        wallet_challenges,
+        wallet_links,
+        epoch_clearing,
+        audit_retention,
+        fx_rates,
+        emission_factors,
+        markets,
+        trading_halts,
+        book_snapshots,
+        swap_pool_stats,
+        fix_sessions,
+        maker_incentives,
+        sandbox,
+        delivery_verification,
+        imbalance,
+        autopilot,
+        grid_meter_data,
+        mint_guard,
+        meter_installations,
+        tokenization,
+        market_archive,
+        data_exports,
+        broadcast_throttle,
+        query_governor,
+        partners,
+        concurrency_limiter: services::ConcurrencyLimiter::new(config.concurrency_limits),
+        ami_backfill,
+        maintenance,
+        rebuilds,
+        ledger_history,
+        pii,
+        pii_rotation,
+        login_step_up,
+        wallet_sessions,
+        fee_sponsorship,
+        token_transfers,
+        report_subscriptions,
+        jobs,
+        deployment,
+        participant_ids,
         webhook_service,
         erc_service,
+        erc_expiry,
+        notification_dispatcher,
+        budget_alerts,
         metrics_handle,
         http_client,
+        startup_report: Arc::new(report),
+        warmup: Arc::new(warmup::Warmup::new(config.warmup.enabled)),
+        degradation,
     };
 
     info!("✅ AppState created successfully with P2P services");
@@ -212,6 +923,17 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
     Ok(app_state)
 }
 
+/// Prometheus exporter with matching latency as real histograms, so
+/// percentiles can be aggregated across instances
+fn prometheus_builder() -> Result<metrics_exporter_prometheus::PrometheusBuilder> {
+    metrics_exporter_prometheus::PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            metrics_exporter_prometheus::Matcher::Prefix("matching_".to_string()),
+            &[1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0],
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to configure matching latency buckets: {}", e))
+}
+
 /// Setup Redis connection.
 async fn setup_redis(config: &Config) -> Result<redis::Client> {
     let redis_client = redis::Client::open(config.redis_url.as_str())?;
@@ -237,47 +959,128 @@ async fn setup_redis(config: &Config) -> Result<redis::Client> {
     Ok(redis_client)
 }
 
-/// Initialize email service (optional).
-fn initialize_email_service(config: &Config) -> Option<services::EmailService> {
-    match services::EmailService::new(&config.email) {
-        Ok(service) => {
-            info!("Email service initialized");
-            Some(service)
+/// Initialize email service.
+fn initialize_email_service(
+    config: &Config,
+    deployment: &services::DeploymentProfileService,
+) -> Result<services::EmailService> {
+    let service = services::EmailService::new(&config.email)
+        .map_err(|e| anyhow::anyhow!("Email service unavailable: {}", e))?
+        .with_brand(&deployment.branding().brand_name);
+    info!("Email service initialized");
+    Ok(service)
+}
+
+/// Key provider wrapping the PII data keys.
+pub fn initialize_pii_kms(config: &Config) -> Result<Arc<dyn services::pii::KeyProvider>> {
+    match config.pii.kms_provider.as_str() {
+        "vault" => {
+            let token = config
+                .pii
+                .vault_token
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("VAULT_TOKEN is required with PII_KMS_PROVIDER=vault"))?;
+            let http = reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to create Vault client: {}", e))?;
+            Ok(Arc::new(services::pii::VaultTransitProvider::new(
+                http,
+                &config.pii.vault_addr,
+                token,
+                &config.pii.vault_key_name,
+            )))
         }
-        Err(e) => {
-            warn!("Email service disabled: {}", e);
-            None
+        "local" => {
+            let master_key = match &config.pii.master_key {
+                Some(key) => base64::Engine::decode(&base64::engine::general_purpose::STANDARD, key)
+                    .map_err(|e| anyhow::anyhow!("Invalid PII_MASTER_KEY: {}", e))?,
+                None => {
+                    if config.environment == "production" {
+                        warn!("⚠️ PII_MASTER_KEY not set; deriving the PII master key from ENCRYPTION_SECRET");
+                    }
+                    <sha2::Sha256 as sha2::Digest>::digest(format!("gridtokenx-pii:{}", config.encryption_secret)).to_vec()
+                }
+            };
+            Ok(Arc::new(services::pii::LocalKeyProvider::new(&master_key)?))
         }
+        other => Err(anyhow::anyhow!("Invalid PII_KMS_PROVIDER: {} (expected vault or local)", other)),
     }
 }
 
 /// Initialize wallet service and load authority wallet.
-async fn initialize_wallet(wallet_service: &services::WalletService) {
-    match wallet_service.initialize_authority().await {
-        Ok(()) => {
-            if let Ok(pubkey) = wallet_service.get_authority_pubkey_string().await {
-                info!("🔑 Authority wallet loaded: {}", pubkey);
-            }
-        }
-        Err(e) => {
-            warn!(
-                "⚠️ Failed to load authority wallet: {}. Token minting will not be available.",
-                e
-            );
-        }
+async fn initialize_wallet(wallet_service: &services::WalletService) -> Result<()> {
+    wallet_service.initialize_authority().await.map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to load authority wallet: {}. Token minting will not be available.",
+            e
+        )
+    })?;
+    if let Ok(pubkey) = wallet_service.get_authority_pubkey_string().await {
+        info!("🔑 Authority wallet loaded: {}", pubkey);
     }
+    Ok(())
 }
 
 /// Spawn background tasks.
-pub async fn spawn_background_tasks(app_state: &AppState, _config: &Config) {
+pub async fn spawn_background_tasks(app_state: &AppState, config: &Config) {
     info!("📌 Spawning background tasks...");
     
+    // Start Cold-Start Warmup (pools, blockhash, order book and token caches before /readyz)
+    let warmup_state = app_state.clone();
+    let warmup_config = config.warmup;
+    tokio::spawn(async move {
+        let warmup = warmup_state.warmup.clone();
+        warmup.run(warmup_state, warmup_config).await;
+    });
+
     // Start the Order Matching Engine
     app_state.market_clearing_engine.start().await;
     info!("✅ Order Matching Engine started");
 
+    // Start Market Session Watcher (broadcasts session transitions)
+    tokio::spawn(app_state.market_session.clone().watch_transitions());
+    info!("✅ Market Session Watcher started");
+
+    // Start Trading Halt Resumption Watcher (ends halts at their scheduled resumption)
+    tokio::spawn(app_state.trading_halts.clone().watch_resumptions());
+    info!("✅ Trading Halt Resumption Watcher started");
+
+    // Start Futures Mark Price Loop (mark ticks, PnL and liquidations)
+    let futures_service = app_state.futures_service.clone();
+    let mark_interval = std::env::var("FUTURES_MARK_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(5);
+    tokio::spawn(async move {
+        info!("🚀 Starting futures mark price feed (interval: {}s)", mark_interval);
+        loop {
+            match futures_service.refresh_marks().await {
+                Ok(liquidated) if liquidated > 0 => info!("💥 Liquidated {} futures positions", liquidated),
+                Ok(_) => {}
+                Err(e) => error!("❌ Error refreshing futures marks: {}", e),
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(mark_interval)).await;
+        }
+    });
+    info!("✅ Futures Mark Price Feed started");
+
+    // Start Futures Funding Rate Loop
+    let futures_service = app_state.futures_service.clone();
+    tokio::spawn(async move {
+        info!("🚀 Starting futures funding rate publisher (interval: 3600s)");
+        loop {
+            if let Err(e) = futures_service.publish_funding_rates().await {
+                error!("❌ Error publishing funding rates: {}", e);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
+        }
+    });
+    info!("✅ Futures Funding Rate Publisher started");
+
     // Start Settlement Service Loop
     let settlement = app_state.settlement.clone();
+    let imbalance = config.imbalance.enabled.then(|| app_state.imbalance.clone());
     let settlement_interval = std::env::var("SETTLEMENT_INTERVAL_SECS")
         .ok()
         .and_then(|v| v.parse::<u64>().ok())
@@ -295,11 +1098,114 @@ pub async fn spawn_background_tasks(app_state: &AppState, _config: &Config) {
                     error!("❌ Error processing settlements: {}", e);
                 }
             }
-            tokio::time::sleep(tokio::time::Duration::from_secs(settlement_interval)).await;
+            if let Some(imbalance) = &imbalance {
+                match imbalance.settle_pending().await {
+                    Ok(count) if count > 0 => info!("✅ Posted {} imbalance settlements", count),
+                    Ok(_) => {}
+                    Err(e) => error!("❌ Error posting imbalance settlements: {}", e),
+                }
+            }
+            settlement
+                .wait_for_work(tokio::time::Duration::from_secs(settlement_interval))
+                .await;
         }
     });
     info!("✅ Settlement Service started");
 
+    // Start settlement watchdog (recovers settlements stuck in processing and
+    // refreshes the settlement batch gauges)
+    let settlement_watchdog = app_state.settlement.clone();
+    let watchdog_interval = std::env::var("SETTLEMENT_WATCHDOG_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(60);
+    tokio::spawn(async move {
+        info!("🚀 Starting settlement watchdog (interval: {}s)", watchdog_interval);
+        loop {
+            match settlement_watchdog.recover_stuck_settlements().await {
+                Ok(recovery) if recovery.total() > 0 => info!(
+                    "🐕 Stuck settlements: {} resumed, {} requeued, {} failed, {} unresolved",
+                    recovery.resumed, recovery.requeued, recovery.failed, recovery.unresolved
+                ),
+                Ok(_) => {}
+                Err(e) => error!("❌ Error recovering stuck settlements: {}", e),
+            }
+            let now = chrono::Utc::now();
+            if let Err(e) = settlement_watchdog
+                .get_batch_statistics(now - chrono::Duration::hours(24), now)
+                .await
+            {
+                error!("❌ Error refreshing settlement batch metrics: {}", e);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(watchdog_interval)).await;
+        }
+    });
+    info!("✅ Settlement watchdog started");
+
+    // Start Epoch Clearing Loop (clears closed epochs, kicks off their settlements)
+    let epoch_clearing = app_state.epoch_clearing.clone();
+    let clearing_interval = std::env::var("EPOCH_CLEARING_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(10);
+    tokio::spawn(async move {
+        info!("🚀 Starting epoch clearing scheduler (interval: {}s)", clearing_interval);
+        loop {
+            match epoch_clearing.run_cycle().await {
+                Ok(count) if count > 0 => info!("🧮 Cleared {} epochs", count),
+                Ok(_) => {}
+                Err(e) => error!("❌ Error clearing epochs: {}", e),
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(clearing_interval)).await;
+        }
+    });
+    info!("✅ Epoch clearing scheduler started");
+
+    // Start order book snapshot loop (full books of open markets, pruned after the retention period)
+    let book_snapshots = app_state.book_snapshots.clone();
+    let snapshot_interval = std::env::var("BOOK_SNAPSHOT_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(60);
+    let snapshot_retention_days = std::env::var("BOOK_SNAPSHOT_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(90);
+    tokio::spawn(async move {
+        info!("🚀 Starting order book snapshot loop (interval: {}s)", snapshot_interval);
+        loop {
+            if let Err(e) = book_snapshots
+                .capture_all(services::book_snapshots::SnapshotReason::Periodic, None)
+                .await
+            {
+                error!("❌ Error capturing order book snapshots: {}", e);
+            }
+            if snapshot_retention_days > 0 {
+                if let Err(e) = book_snapshots.purge_older_than(snapshot_retention_days).await {
+                    error!("❌ Error purging order book snapshots: {}", e);
+                }
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(snapshot_interval)).await;
+        }
+    });
+    info!("✅ Order book snapshot loop started");
+
+    // Start Swap Pool Stats Loop (rolls swaps into hourly buckets, samples reserves)
+    let swap_pool_stats = app_state.swap_pool_stats.clone();
+    let pool_stats_interval = std::env::var("SWAP_POOL_STATS_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(300);
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = swap_pool_stats.aggregate().await {
+                error!("❌ Error aggregating swap pool stats: {}", e);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(pool_stats_interval)).await;
+        }
+    });
+    info!("✅ Swap pool stats aggregator started");
+
     // Start Event Processor Service
     let event_processor = app_state.event_processor.clone();
     tokio::spawn(async move {
@@ -311,6 +1217,10 @@ pub async fn spawn_background_tasks(app_state: &AppState, _config: &Config) {
     app_state.dashboard_service.start_history_recorder().await;
     info!("✅ Grid History Recorder started");
 
+    // Start Dashboard Read Model Refresher (follows event processor cycles)
+    app_state.dashboard_service.start_read_model_refresher().await;
+    info!("✅ Dashboard read model refresher started");
+
     // Start Price Monitor Loop
     let price_monitor = app_state.price_monitor.clone();
     tokio::spawn(async move {
@@ -336,6 +1246,531 @@ pub async fn spawn_background_tasks(app_state: &AppState, _config: &Config) {
         }
     });
     info!("✅ Recurring Scheduler started");
+
+    // Start Market Analytics Aggregator
+    let market_analytics = app_state.market_analytics.clone();
+    tokio::spawn(services::market_analytics::run(market_analytics));
+    info!("✅ Market Analytics Aggregator started");
+
+    // Start Leaderboard Loop (computes once per day, checks hourly)
+    let leaderboard_service = app_state.leaderboard_service.clone();
+    tokio::spawn(async move {
+        info!("🚀 Starting leaderboard scheduler (interval: 3600s)");
+        loop {
+            if let Err(e) = leaderboard_service.ensure_daily_snapshot().await {
+                error!("❌ Error computing leaderboard snapshot: {}", e);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
+        }
+    });
+    info!("✅ Leaderboard scheduler started");
+
+    // Start Reference Index Loop (publishes yesterday's index, checks hourly)
+    let price_index_service = app_state.price_index_service.clone();
+    tokio::spawn(async move {
+        info!("🚀 Starting reference index publisher (interval: 3600s)");
+        loop {
+            if let Err(e) = price_index_service.ensure_previous_day().await {
+                error!("❌ Error publishing reference index: {}", e);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
+        }
+    });
+    info!("✅ Reference index publisher started");
+
+    // Start Market Rollup Loop (archives completed days shortly after market-local midnight)
+    let market_archive = app_state.market_archive.clone();
+    let rollup_jobs = app_state.jobs.clone();
+    tokio::spawn(async move {
+        let market_archive = &market_archive;
+        loop {
+            let run = rollup_jobs
+                .run(JobKind::MarketRollups, |job| async move {
+                    let rollups = market_archive.ensure_completed_days().await?;
+                    for rollup in &rollups {
+                        job.log(
+                            LogLevel::Info,
+                            format!("{}: {} kWh in {} trades", rollup.rollup_date, rollup.volume_kwh, rollup.trade_count),
+                        )
+                        .await;
+                    }
+                    Ok::<_, ApiError>(format!("{} days archived", rollups.len()))
+                })
+                .await;
+            if let Err(e) = run {
+                error!("❌ Error archiving market rollups: {}", e);
+            }
+            let now = chrono::Utc::now();
+            let timezone = market_archive.timezone();
+            let next_run =
+                timezone.start_of_day(timezone.date_of(now) + chrono::Duration::days(1)) + chrono::Duration::minutes(5);
+            rollup_jobs
+                .wait(JobKind::MarketRollups, (next_run - now).to_std().unwrap_or(Duration::from_secs(3600)))
+                .await;
+        }
+    });
+    info!("✅ Market rollup job started");
+
+    // Start Data Export Loop (builds queued exports, deletes expired files)
+    let data_exports = app_state.data_exports.clone();
+    let export_jobs = app_state.jobs.clone();
+    let export_interval = config.data_exports.interval_secs.max(1);
+    tokio::spawn(async move {
+        info!("🚀 Starting data export job (interval: {}s)", export_interval);
+        let data_exports = &data_exports;
+        loop {
+            match data_exports.housekeep().await {
+                Ok(expired) if expired > 0 => info!("🗑️ Deleted {} expired data exports", expired),
+                Ok(_) => {}
+                Err(e) => error!("❌ Error expiring data exports: {}", e),
+            }
+            // Only record a run when there is an export to build
+            if matches!(data_exports.has_queued().await, Ok(true)) {
+                let run = export_jobs
+                    .run(JobKind::DataExports, |job| async move {
+                        let ready = data_exports.process_queued(&job).await?;
+                        Ok::<_, ApiError>(format!("{} exports ready", ready))
+                    })
+                    .await;
+                if let Err(e) = run {
+                    error!("❌ Error building data exports: {}", e);
+                }
+            }
+            export_jobs
+                .wait(JobKind::DataExports, Duration::from_secs(export_interval))
+                .await;
+        }
+    });
+    info!("✅ Data export job started");
+
+    // Start Monthly Statement Loop (issues last month's statements, idempotent)
+    let invoice_service = app_state.invoice_service.clone();
+    let statement_jobs = app_state.jobs.clone();
+    tokio::spawn(async move {
+        info!("🚀 Starting monthly statement scheduler (interval: 21600s)");
+        loop {
+            let run = statement_jobs
+                .run(JobKind::MonthlyStatements, |_| async {
+                    let count = invoice_service.ensure_previous_month().await?;
+                    if count > 0 {
+                        info!("🧾 Monthly statements ensured for {} accounts", count);
+                    }
+                    Ok::<_, anyhow::Error>(format!("Statements ensured for {} accounts", count))
+                })
+                .await;
+            if let Err(e) = run {
+                error!("❌ Error generating monthly statements: {}", e);
+            }
+            statement_jobs
+                .wait(JobKind::MonthlyStatements, Duration::from_secs(21600))
+                .await;
+        }
+    });
+    info!("✅ Monthly statement scheduler started");
+
+    // Start Report Subscription Loop (delivers reports whose period has closed)
+    if config.report_subscriptions.enabled {
+        let report_subscriptions = app_state.report_subscriptions.clone();
+        let report_jobs = app_state.jobs.clone();
+        let report_interval = config.report_subscriptions.interval_secs.max(1);
+        tokio::spawn(async move {
+            info!("🚀 Starting report subscription job (interval: {}s)", report_interval);
+            let reports = &report_subscriptions;
+            loop {
+                let run = report_jobs
+                    .run(JobKind::ReportDelivery, |job| async move {
+                        reports
+                            .run_due(&job)
+                            .await
+                            .map(|delivered| format!("{} reports delivered", delivered))
+                    })
+                    .await;
+                if let Err(e) = run {
+                    error!("❌ Error delivering scheduled reports: {}", e);
+                }
+                report_jobs
+                    .wait(JobKind::ReportDelivery, Duration::from_secs(report_interval))
+                    .await;
+            }
+        });
+        info!("✅ Report subscription job started");
+    }
+
+    // Start Referral Rewards Loop (screens qualified referrals, mints rewards)
+    let referral_service = app_state.referral_service.clone();
+    let referral_jobs = app_state.jobs.clone();
+    tokio::spawn(async move {
+        info!("🚀 Starting referral rewards job (interval: 300s)");
+        loop {
+            let run = referral_jobs
+                .run(JobKind::ReferralRewards, |_| async {
+                    let (screened, minted) = referral_service.run_cycle().await?;
+                    if screened > 0 || minted > 0 {
+                        info!("🤝 Referrals screened: {}, rewards minted: {}", screened, minted);
+                    }
+                    Ok::<_, ApiError>(format!("Referrals screened: {}, rewards minted: {}", screened, minted))
+                })
+                .await;
+            if let Err(e) = run {
+                error!("❌ Error in referral rewards job: {}", e);
+            }
+            referral_jobs
+                .wait(JobKind::ReferralRewards, Duration::from_secs(300))
+                .await;
+        }
+    });
+    info!("✅ Referral rewards job started");
+
+    // Start Maker Incentive Loop (samples best quotes, settles and mints daily rebates)
+    if config.maker_incentives.enabled {
+        let maker_incentives = app_state.maker_incentives.clone();
+        let sample_interval = config.maker_incentives.sample_interval_secs.max(1);
+        tokio::spawn(async move {
+            info!("🚀 Starting maker incentive job (interval: {}s)", sample_interval);
+            loop {
+                match maker_incentives.run_cycle().await {
+                    Ok((_, settled, minted)) if settled > 0 || minted > 0 => {
+                        info!("💧 Maker incentive days settled: {}, rebates minted: {}", settled, minted)
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("❌ Error in maker incentive job: {}", e),
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(sample_interval)).await;
+            }
+        });
+        info!("✅ Maker incentive job started");
+    }
+
+    // Start Delivery Verification Loop (checks settled trades against meter readings)
+    if config.delivery_verification.enabled {
+        let delivery_verification = app_state.delivery_verification.clone();
+        let verification_interval = config.delivery_verification.interval_secs.max(1);
+        tokio::spawn(async move {
+            info!("🚀 Starting delivery verification job (interval: {}s)", verification_interval);
+            loop {
+                match delivery_verification.verify_due().await {
+                    Ok((verified, shortfalls)) if shortfalls > 0 => {
+                        warn!("⚡ {} settlements verified, {} delivery shortfalls", verified, shortfalls)
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("❌ Error in delivery verification job: {}", e),
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(verification_interval)).await;
+            }
+        });
+        info!("✅ Delivery verification job started");
+    }
+
+    // Start Imbalance Pricing Loop (prices traded vs metered net export of finished epochs)
+    if config.imbalance.enabled {
+        let imbalance = app_state.imbalance.clone();
+        let imbalance_interval = std::env::var("IMBALANCE_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(300);
+        tokio::spawn(async move {
+            info!("🚀 Starting imbalance pricing job (interval: {}s)", imbalance_interval);
+            loop {
+                if let Err(e) = imbalance.price_due().await {
+                    error!("❌ Error in imbalance pricing job: {}", e);
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(imbalance_interval)).await;
+            }
+        });
+        info!("✅ Imbalance pricing job started");
+    }
+
+    // Start Trading Autopilot Loop (places forecast surplus orders, closes cleared epochs)
+    if config.autopilot.enabled {
+        let autopilot = app_state.autopilot.clone();
+        let autopilot_interval = config.autopilot.interval_secs.max(1);
+        tokio::spawn(async move {
+            info!("🚀 Starting trading autopilot (interval: {}s)", autopilot_interval);
+            loop {
+                if let Err(e) = autopilot.run_once().await {
+                    error!("❌ Error in trading autopilot: {}", e);
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(autopilot_interval)).await;
+            }
+        });
+        info!("✅ Trading autopilot started");
+    }
+
+    // Start Budget Alert Loop (checks household thresholds, notifies crossings)
+    let budget_alerts = app_state.budget_alerts.clone();
+    let budget_alert_interval = std::env::var("BUDGET_ALERT_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(900);
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = budget_alerts.evaluate_all().await {
+                error!("❌ Error evaluating budget alerts: {}", e);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(budget_alert_interval)).await;
+        }
+    });
+    info!("✅ Budget alert job started");
+
+    // Start Grid Reconciliation Loop (compares official DSO intervals with submitted readings)
+    let grid_meter_data = app_state.grid_meter_data.clone();
+    let reconciliation_interval = config.grid_reconciliation.interval_secs.max(1);
+    tokio::spawn(async move {
+        info!("🚀 Starting grid reconciliation job (interval: {}s)", reconciliation_interval);
+        loop {
+            match grid_meter_data.reconcile().await {
+                Ok((reconciled, divergent)) if divergent > 0 => {
+                    warn!("🔌 {} grid intervals reconciled, {} divergent", reconciled, divergent)
+                }
+                Ok(_) => {}
+                Err(e) => error!("❌ Error in grid reconciliation job: {}", e),
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(reconciliation_interval)).await;
+        }
+    });
+    info!("✅ Grid reconciliation job started");
+
+    // Start API Usage Loop (flushes counters every minute, checks anomalies hourly)
+    let api_usage = app_state.api_usage.clone();
+    tokio::spawn(async move {
+        info!("🚀 Starting API usage tracker (interval: 60s)");
+        let mut last_detection_hour = None;
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+            if let Err(e) = api_usage.flush().await {
+                error!("❌ Error flushing API usage: {}", e);
+                continue;
+            }
+            let hour = chrono::Utc::now().timestamp() / 3600;
+            if last_detection_hour != Some(hour) {
+                match api_usage.detect().await {
+                    Ok(count) if count > 0 => warn!("🚨 {} access anomalies flagged", count),
+                    Ok(_) => {}
+                    Err(e) => error!("❌ Error detecting access anomalies: {}", e),
+                }
+                if let Err(e) = api_usage.prune().await {
+                    error!("❌ Error pruning API usage: {}", e);
+                }
+                last_detection_hour = Some(hour);
+            }
+        }
+    });
+    info!("✅ API usage tracker started");
+
+    // Start Network ACL Refresh Loop (picks up rule changes made on other instances)
+    let network_acl = app_state.network_acl.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+            if let Err(e) = network_acl.reload().await {
+                error!("❌ Error reloading network ACL rules: {}", e);
+            }
+        }
+    });
+    info!("✅ Network ACL refresh started");
+
+    // Start Tokenization Overrides Refresh Loop (picks up changes made on other instances)
+    let tokenization = app_state.tokenization.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+            if let Err(e) = tokenization.reload().await {
+                error!("❌ Error reloading tokenization overrides: {}", e);
+            }
+        }
+    });
+    info!("✅ Tokenization overrides refresh started");
+
+    // Start Broadcast Throttle Refresh Loop (picks up changes made on other instances)
+    let broadcast_throttle = app_state.broadcast_throttle.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+            if let Err(e) = broadcast_throttle.reload().await {
+                error!("❌ Error reloading WebSocket broadcast windows: {}", e);
+            }
+        }
+    });
+    info!("✅ Broadcast throttle refresh started");
+
+    // Start Meter Registry Sync Loop (retries on-chain registry accounts for verified meters)
+    let meter_registry_sync = app_state.meter_registry_sync.clone();
+    let registry_sync_interval = std::env::var("METER_REGISTRY_SYNC_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(300);
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(registry_sync_interval)).await;
+            if let Err(e) = meter_registry_sync.reconcile(50).await {
+                error!("❌ Error reconciling meter registry: {}", e);
+            }
+        }
+    });
+    info!("✅ Meter registry sync job started");
+
+    // Start Meter Fleet Health Loop (rebuilds the cached fleet health report)
+    let meter_fleet_health = app_state.meter_fleet_health.clone();
+    let fleet_health_interval = std::env::var("METER_FLEET_HEALTH_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(300);
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = meter_fleet_health.refresh().await {
+                error!("❌ Error refreshing meter fleet health: {}", e);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(fleet_health_interval)).await;
+        }
+    });
+    info!("✅ Meter fleet health job started");
+
+    // Start Audit Retention Loop (purges expired audit records not under legal hold)
+    let audit_retention = app_state.audit_retention.clone();
+    let retention_interval = std::env::var("AUDIT_RETENTION_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(86400);
+    let retention_jobs = app_state.jobs.clone();
+    tokio::spawn(async move {
+        let audit_retention = &audit_retention;
+        loop {
+            let run = retention_jobs
+                .run(JobKind::AuditRetention, |job| async move {
+                    let purged = audit_retention.purge().await?;
+                    for class in &purged {
+                        job.log(LogLevel::Info, format!("{:?}: {} records deleted", class.class, class.deleted))
+                            .await;
+                    }
+                    Ok::<_, ApiError>(format!("{} audit classes purged", purged.len()))
+                })
+                .await;
+            if let Err(e) = run {
+                error!("❌ Error purging audit records: {}", e);
+            }
+            retention_jobs
+                .wait(JobKind::AuditRetention, Duration::from_secs(retention_interval))
+                .await;
+        }
+    });
+    info!("✅ Audit retention job started");
+
+    // Start Background Job Housekeeping Loop (fails interrupted runs, prunes old ones)
+    let housekeeping_jobs = app_state.jobs.clone();
+    tokio::spawn(async move {
+        info!("🚀 Starting background job housekeeping (interval: 3600s)");
+        loop {
+            match housekeeping_jobs.housekeep().await {
+                Ok((_, pruned)) if pruned > 0 => info!("🧹 Pruned {} finished background jobs", pruned),
+                Ok(_) => {}
+                Err(e) => error!("❌ Error in background job housekeeping: {}", e),
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
+        }
+    });
+    info!("✅ Background job housekeeping started");
+
+    // Start Audit Buffer Flush Loop (replays events buffered while PostgreSQL was down)
+    let audit_logger = app_state.audit_logger.clone();
+    let audit_buffer = config.audit_buffer.clone();
+    tokio::spawn(async move {
+        loop {
+            match audit_logger.flush_buffer(audit_buffer.flush_batch).await {
+                Ok(count) if count > 0 => info!("📝 Replayed {} buffered audit events", count),
+                Ok(_) => {}
+                Err(e) => warn!("⚠️ Audit buffer replay pending: {}", e),
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(audit_buffer.flush_interval_secs)).await;
+        }
+    });
+    info!("✅ Audit buffer flush job started");
+
+    // Start ERC Expiry Loop (expiry warnings, expired status transitions)
+    let erc_expiry = app_state.erc_expiry.clone();
+    let erc_expiry_interval = std::env::var("ERC_EXPIRY_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(3600);
+    tokio::spawn(async move {
+        loop {
+            match erc_expiry.run().await {
+                Ok(run) if run.warned + run.expired > 0 => info!(
+                    "📜 ERC expiry: {} owners warned, {} certificates expired",
+                    run.warned, run.expired
+                ),
+                Ok(_) => {}
+                Err(e) => error!("❌ Error monitoring ERC expiry: {}", e),
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(erc_expiry_interval)).await;
+        }
+    });
+    info!("✅ ERC expiry monitor started");
+
+    // Start PII Rotation Job (seals legacy plaintext, re-encrypts under the active data key)
+    let pii_rotation = app_state.pii_rotation.clone();
+    let pii_interval = config.pii.rotation_interval_secs;
+    tokio::spawn(async move {
+        loop {
+            match pii_rotation.run_once().await {
+                Ok(run) if run.users_sealed + run.meters_sealed + run.keys_retired > 0 => info!(
+                    "🔐 PII rotation: {} users and {} meters sealed, {} data keys retired",
+                    run.users_sealed, run.meters_sealed, run.keys_retired
+                ),
+                Ok(_) => {}
+                Err(e) => error!("❌ Error rotating PII keys: {}", e),
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(pii_interval)).await;
+        }
+    });
+    info!("✅ PII rotation job started");
+
+    // Start Degradation Probe Loop (retries down email and cache, sends queued emails)
+    let degradation = app_state.degradation.clone();
+    let probe_cache = app_state.cache_service.clone();
+    let probe_email = app_state.email_service.clone();
+    let probe_interval = config.degradation.probe_interval_secs;
+    tokio::spawn(async move {
+        use services::degradation::Subsystem;
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(probe_interval)).await;
+            if degradation.is_down(Subsystem::Cache) {
+                let _ = probe_cache.ping().await;
+            }
+            if let Some(email) = &probe_email {
+                if degradation.is_down(Subsystem::Email) {
+                    let _ = email.probe().await;
+                }
+                if !degradation.is_down(Subsystem::Email) && email.queued() > 0 {
+                    email.flush_queue().await;
+                }
+            }
+        }
+    });
+    info!("✅ Degradation probe started (interval: {}s)", probe_interval);
+
+    // Record subsystem degradations and recoveries in the audit log
+    let mut subsystem_events = app_state.degradation.subscribe();
+    let audit_logger = app_state.audit_logger.clone();
+    tokio::spawn(async move {
+        use tokio::sync::broadcast::error::RecvError;
+        loop {
+            match subsystem_events.recv().await {
+                Ok(event) => audit_logger.log_async(services::AuditEvent::SubsystemStateChanged {
+                    subsystem: event.subsystem.to_string(),
+                    transition: event.transition.as_str().to_string(),
+                    detail: event.detail,
+                }),
+                Err(RecvError::Lagged(skipped)) => warn!("⚠️ Missed {} subsystem events", skipped),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    // Start FIX Gateway (separate listener for institutional order entry and drop copy)
+    if config.fix_gateway.enabled {
+        tokio::spawn(crate::fix::serve(app_state.clone(), config.fix_gateway.clone()));
+        info!("✅ FIX gateway started");
+    }
 }
 
 /// Wait for shutdown signal.
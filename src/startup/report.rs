@@ -0,0 +1,216 @@
+//! Startup report
+//!
+//! Records how each dependency came up (attempts, time taken, whether the
+//! gateway runs without it). The report is logged once startup completes
+//! and served at `/readyz`.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::config::StartupPolicy;
+
+/// Longest wait between two attempts
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Ready,
+    /// Unavailable; the gateway started without it
+    Degraded,
+}
+
+/// Startup outcome of one dependency
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComponentReport {
+    pub name: String,
+    /// Startup aborts when a required component fails
+    pub required: bool,
+    pub status: ComponentStatus,
+    pub attempts: u32,
+    pub elapsed_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StartupReport {
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub duration_ms: u64,
+    /// Components in initialization order
+    pub components: Vec<ComponentReport>,
+    /// True when any optional component is unavailable
+    pub degraded: bool,
+}
+
+impl StartupReport {
+    pub fn begin() -> Self {
+        Self {
+            started_at: Utc::now(),
+            completed_at: None,
+            duration_ms: 0,
+            components: Vec::new(),
+            degraded: false,
+        }
+    }
+
+    /// Record a component that initialized
+    pub fn ready(&mut self, name: &str, required: bool, attempts: u32, started: Instant) {
+        self.components.push(ComponentReport {
+            name: name.to_string(),
+            required,
+            status: ComponentStatus::Ready,
+            attempts,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            detail: None,
+        });
+    }
+
+    /// Apply the component's policy to a failure: record it as degraded, or
+    /// return the error to abort startup
+    pub fn failed(
+        &mut self,
+        name: &str,
+        policy: StartupPolicy,
+        attempts: u32,
+        started: Instant,
+        error: anyhow::Error,
+    ) -> anyhow::Result<()> {
+        if policy == StartupPolicy::FailFast {
+            return Err(error.context(format!("{} is required at startup", name)));
+        }
+
+        warn!("⚠️ Starting without {}: {}", name, error);
+        self.degraded = true;
+        self.components.push(ComponentReport {
+            name: name.to_string(),
+            required: false,
+            status: ComponentStatus::Degraded,
+            attempts,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            detail: Some(error.to_string()),
+        });
+        Ok(())
+    }
+
+    /// Stamp completion and log a one-line summary per component
+    pub fn finish(&mut self) {
+        let now = Utc::now();
+        self.completed_at = Some(now);
+        self.duration_ms = (now - self.started_at).num_milliseconds().max(0) as u64;
+
+        info!(
+            "📋 Startup report: {} components in {}ms{}",
+            self.components.len(),
+            self.duration_ms,
+            if self.degraded { " (DEGRADED)" } else { "" }
+        );
+        for component in &self.components {
+            match component.status {
+                ComponentStatus::Ready => info!(
+                    "   ✅ {} ({} attempt(s), {}ms)",
+                    component.name, component.attempts, component.elapsed_ms
+                ),
+                ComponentStatus::Degraded => warn!(
+                    "   ⚠️ {} unavailable: {}",
+                    component.name,
+                    component.detail.as_deref().unwrap_or("unknown error")
+                ),
+            }
+        }
+    }
+}
+
+/// Delay before retry number `attempt` (1-based): the base delay doubled
+/// per attempt, capped at 30 seconds
+pub fn retry_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Run `op` up to `attempts` times with exponential backoff.
+/// Returns the result of the last attempt and the number of attempts made.
+pub async fn with_retry<T, F, Fut>(
+    name: &str,
+    attempts: u32,
+    base_delay: Duration,
+    mut op: F,
+) -> (anyhow::Result<T>, u32)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let attempts = attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(value) => return (Ok(value), attempt),
+            Err(e) if attempt < attempts => {
+                let delay = retry_delay(base_delay, attempt);
+                warn!(
+                    "⏳ {} unavailable (attempt {}/{}): {}. Retrying in {:?}",
+                    name, attempt, attempts, e, delay
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return (Err(e), attempt),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_doubles_and_caps() {
+        let base = Duration::from_millis(500);
+        assert_eq!(retry_delay(base, 1), Duration::from_millis(500));
+        assert_eq!(retry_delay(base, 2), Duration::from_secs(1));
+        assert_eq!(retry_delay(base, 4), Duration::from_secs(4));
+        assert_eq!(retry_delay(base, 20), MAX_RETRY_DELAY);
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_stops_on_success() {
+        let mut calls = 0;
+        let (result, attempts) = with_retry("test", 5, Duration::ZERO, || {
+            calls += 1;
+            let ok = calls >= 3;
+            async move {
+                if ok {
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!("down"))
+                }
+            }
+        })
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_failed_policy() {
+        let mut report = StartupReport::begin();
+        let started = Instant::now();
+
+        assert!(report
+            .failed("email", StartupPolicy::Degraded, 1, started, anyhow::anyhow!("no smtp"))
+            .is_ok());
+        assert!(report.degraded);
+        assert_eq!(report.components[0].status, ComponentStatus::Degraded);
+
+        assert!(report
+            .failed("database", StartupPolicy::FailFast, 5, started, anyhow::anyhow!("refused"))
+            .is_err());
+        assert_eq!(report.components.len(), 1);
+    }
+}
@@ -0,0 +1,188 @@
+//! Cold-start warmup
+//!
+//! Runs once in the background after startup so the first requests after a
+//! deploy don't pay for cold pools and empty caches: it opens the minimum
+//! database connections, checks the Redis connection, prefetches the recent
+//! blockhash, captures the order book depth and primes the energy token
+//! accounts. `/readyz` reports not ready until warmup has finished or timed
+//! out. A failed step is logged and recorded but does not hold readiness back.
+
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use futures::future::try_join_all;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::app_state::AppState;
+use crate::config::WarmupConfig;
+
+/// Outcome of one warmup step
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WarmupStep {
+    pub name: String,
+    pub ok: bool,
+    pub elapsed_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct WarmupReport {
+    /// Finished, timed out or disabled; readiness waits for this
+    pub complete: bool,
+    /// Steps still running were abandoned at `WARMUP_TIMEOUT_SECS`
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    /// Steps in completion order
+    pub steps: Vec<WarmupStep>,
+}
+
+/// Warmup progress shared between the warmup task and `/readyz`
+#[derive(Debug, Default)]
+pub struct Warmup {
+    report: RwLock<WarmupReport>,
+}
+
+impl Warmup {
+    /// A disabled warmup is complete from the start
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            report: RwLock::new(WarmupReport {
+                complete: !enabled,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.report.read().map(|r| r.complete).unwrap_or(true)
+    }
+
+    pub fn report(&self) -> WarmupReport {
+        self.report.read().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    fn record(&self, step: WarmupStep) {
+        if let Ok(mut report) = self.report.write() {
+            report.steps.push(step);
+        }
+    }
+
+    fn finish(&self, timed_out: bool, started: Instant) {
+        if let Ok(mut report) = self.report.write() {
+            report.complete = true;
+            report.timed_out = timed_out;
+            report.duration_ms = started.elapsed().as_millis() as u64;
+        }
+    }
+
+    /// Run the warmup steps concurrently, bounded by the configured timeout
+    pub async fn run(&self, state: AppState, config: WarmupConfig) {
+        if self.is_complete() {
+            return;
+        }
+
+        info!("🔥 Warming up caches and connection pools...");
+        let started = Instant::now();
+        let steps = async {
+            tokio::join!(
+                self.step("database_pool", warm_database(&state, config.db_connections)),
+                self.step("redis", state.cache_service.ping()),
+                self.step("recent_blockhash", async {
+                    state.blockchain_service.get_latest_blockhash().await.map(|_| ())
+                }),
+                self.step("order_book", async { state.market_analytics.refresh_depth().await.map(|_| ()) }),
+                self.step("token_info", warm_token_accounts(&state)),
+            )
+        };
+        let timed_out = tokio::time::timeout(Duration::from_secs(config.timeout_secs), steps)
+            .await
+            .is_err();
+        self.finish(timed_out, started);
+
+        let report = self.report();
+        if timed_out {
+            warn!(
+                "⚠️ Warmup timed out after {}s; {} of 5 steps finished",
+                config.timeout_secs,
+                report.steps.len()
+            );
+        } else {
+            info!(
+                "✅ Warmup finished in {}ms ({} of {} steps ok)",
+                report.duration_ms,
+                report.steps.iter().filter(|s| s.ok).count(),
+                report.steps.len()
+            );
+        }
+    }
+
+    async fn step(&self, name: &str, fut: impl Future<Output = anyhow::Result<()>>) {
+        let started = Instant::now();
+        let result = fut.await;
+        if let Err(e) = &result {
+            warn!("⚠️ Warmup step {} failed: {}", name, e);
+        }
+        self.record(WarmupStep {
+            name: name.to_string(),
+            ok: result.is_ok(),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            detail: result.err().map(|e| e.to_string()),
+        });
+    }
+}
+
+/// Hold `count` pool connections at once and run a query on each, so the
+/// pool has that many established connections before traffic arrives
+async fn warm_database(state: &AppState, count: u32) -> anyhow::Result<()> {
+    let mut connections = try_join_all((0..count.max(1)).map(|_| state.db.acquire())).await?;
+    for conn in connections.iter_mut() {
+        sqlx::query("SELECT 1").execute(&mut **conn).await?;
+    }
+    Ok(())
+}
+
+/// Load the energy mint and token info accounts into the RPC account cache
+async fn warm_token_accounts(state: &AppState) -> anyhow::Result<()> {
+    let mint = Pubkey::from_str(&state.config.energy_token_mint)
+        .map_err(|e| anyhow::anyhow!("Invalid ENERGY_TOKEN_MINT: {}", e))?;
+    let (token_info, _) = Pubkey::find_program_address(
+        &[b"token_info_2022"],
+        &state.blockchain_service.energy_token_program_id()?,
+    );
+    state.blockchain_service.get_account(&mint).await?;
+    state.blockchain_service.get_account(&token_info).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_warmup_is_complete() {
+        assert!(Warmup::new(false).is_complete());
+        assert!(!Warmup::new(true).is_complete());
+    }
+
+    #[tokio::test]
+    async fn test_failed_step_recorded_and_finish_completes() {
+        let warmup = Warmup::new(true);
+        warmup.step("redis", async { Err(anyhow::anyhow!("refused")) }).await;
+        warmup.step("order_book", async { Ok(()) }).await;
+        assert!(!warmup.is_complete());
+
+        warmup.finish(false, Instant::now());
+        let report = warmup.report();
+        assert!(report.complete);
+        assert_eq!(report.steps.len(), 2);
+        assert!(!report.steps[0].ok);
+        assert_eq!(report.steps[0].detail.as_deref(), Some("refused"));
+        assert!(report.steps[1].ok);
+    }
+}
@@ -0,0 +1,142 @@
+//! Decimal conversions
+//!
+//! Amounts are `rust_decimal::Decimal` in services and `NUMERIC` in the
+//! database; on chain amounts are integer base units (lamports for the
+//! 9-decimal energy token). Conversions here are exact: anything that would
+//! overflow or drop digits returns an error instead of truncating or falling
+//! back to zero. Where rounding is intended, callers pick the strategy
+//! explicitly.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::constants::energy::TOKEN_DECIMALS;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DecimalConversionError {
+    #[error("Amount {0} is negative")]
+    Negative(String),
+    #[error("Amount {0} is out of range")]
+    Overflow(String),
+    #[error("Amount {value} has more than {max_scale} decimal places")]
+    PrecisionLoss { value: String, max_scale: u32 },
+}
+
+/// Exact conversion to integer base units with `decimals` fractional digits
+pub fn to_base_units(value: Decimal, decimals: u32) -> Result<u64, DecimalConversionError> {
+    if value.is_sign_negative() && !value.is_zero() {
+        return Err(DecimalConversionError::Negative(value.to_string()));
+    }
+    let normalized = value.normalize();
+    if normalized.scale() > decimals {
+        return Err(DecimalConversionError::PrecisionLoss {
+            value: value.to_string(),
+            max_scale: decimals,
+        });
+    }
+
+    // Integer mantissa at exactly `decimals` places, computed in i128 so it
+    // cannot be rounded by Decimal's 96-bit multiplication
+    let shift = decimals - normalized.scale();
+    let units = 10i128
+        .checked_pow(shift)
+        .and_then(|factor| normalized.mantissa().checked_mul(factor))
+        .ok_or_else(|| DecimalConversionError::Overflow(value.to_string()))?;
+
+    u64::try_from(units).map_err(|_| DecimalConversionError::Overflow(value.to_string()))
+}
+
+/// Conversion to base units after rounding to `decimals` places with `strategy`
+pub fn to_base_units_rounded(
+    value: Decimal,
+    decimals: u32,
+    strategy: RoundingStrategy,
+) -> Result<u64, DecimalConversionError> {
+    to_base_units(value.round_dp_with_strategy(decimals, strategy), decimals)
+}
+
+/// Exact conversion from integer base units
+pub fn from_base_units(units: u64, decimals: u32) -> Result<Decimal, DecimalConversionError> {
+    Decimal::try_from_i128_with_scale(units as i128, decimals)
+        .map(|d| d.normalize())
+        .map_err(|_| DecimalConversionError::Overflow(units.to_string()))
+}
+
+/// Exact conversion of an energy-token amount to lamports (9 decimals)
+pub fn to_lamports(value: Decimal) -> Result<u64, DecimalConversionError> {
+    to_base_units(value, TOKEN_DECIMALS as u32)
+}
+
+/// An energy amount derived by arithmetic (grid losses), rounded down to
+/// what the token can carry so it converts to lamports exactly
+pub fn round_to_token(value: Decimal) -> Decimal {
+    value.round_dp_with_strategy(TOKEN_DECIMALS as u32, RoundingStrategy::ToZero)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_to_lamports_exact() {
+        assert_eq!(to_lamports(Decimal::new(15, 1)).unwrap(), 1_500_000_000);
+        assert_eq!(to_lamports(Decimal::new(1, 9)).unwrap(), 1);
+        assert_eq!(to_lamports(Decimal::ZERO).unwrap(), 0);
+        // Trailing zeros beyond 9 places are not precision loss
+        assert_eq!(to_lamports(Decimal::new(1_000, 12)).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_to_lamports_rejects_lossy_values() {
+        assert!(matches!(
+            to_lamports(Decimal::new(1, 10)),
+            Err(DecimalConversionError::PrecisionLoss { .. })
+        ));
+        assert!(matches!(
+            to_lamports(Decimal::new(-1, 0)),
+            Err(DecimalConversionError::Negative(_))
+        ));
+        // u64::MAX lamports is ~18.4 billion tokens
+        assert!(matches!(
+            to_lamports(Decimal::from(20_000_000_000u64)),
+            Err(DecimalConversionError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_rounded_conversion() {
+        let value = Decimal::from_str("0.1234567899").unwrap();
+        assert_eq!(to_base_units_rounded(value, 9, RoundingStrategy::ToZero).unwrap(), 123_456_789);
+        assert_eq!(to_base_units_rounded(value, 6, RoundingStrategy::AwayFromZero).unwrap(), 123_457);
+    }
+
+    #[test]
+    fn test_round_to_token_converts_exactly() {
+        // 5 kWh less a 3% loss factor computed with excess digits
+        let effective = round_to_token(Decimal::from_str("4.8499999999999").unwrap());
+        assert_eq!(effective, Decimal::from_str("4.849999999").unwrap());
+        assert_eq!(to_lamports(effective).unwrap(), 4_849_999_999);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_lamports_round_trip(lamports in any::<u64>()) {
+            let amount = from_base_units(lamports, TOKEN_DECIMALS as u32).unwrap();
+            prop_assert_eq!(to_lamports(amount).unwrap(), lamports);
+        }
+
+        #[test]
+        fn prop_base_units_round_trip(units in any::<u64>(), decimals in 0u32..=18) {
+            let amount = from_base_units(units, decimals).unwrap();
+            prop_assert_eq!(to_base_units(amount, decimals).unwrap(), units);
+        }
+
+        #[test]
+        fn prop_excess_precision_is_rejected(lamports in 1u64..u64::MAX / 10, extra in 1u64..10) {
+            // One more decimal digit than the token supports
+            let value = Decimal::from_i128_with_scale(lamports as i128 * 10 + extra as i128, 10);
+            prop_assert!(to_lamports(value).is_err());
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Market-local periods
+//!
+//! Timestamps are stored in UTC, but days, weeks and months are the market's:
+//! a Bangkok deployment's statement for January runs from 1 January 00:00
+//! +07:00, not UTC midnight. The timezone is a fixed offset from the
+//! deployment profile; the markets served do not observe daylight saving.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, Timelike, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Timezone days, epochs and billing periods are counted in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct MarketTimezone {
+    /// IANA name, e.g. Asia/Bangkok
+    pub name: String,
+    pub utc_offset_minutes: i32,
+}
+
+impl MarketTimezone {
+    pub fn new(name: impl Into<String>, utc_offset_minutes: i32) -> Self {
+        Self {
+            name: name.into(),
+            utc_offset_minutes,
+        }
+    }
+
+    pub fn utc() -> Self {
+        Self::new("UTC", 0)
+    }
+
+    pub fn offset(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.utc_offset_minutes * 60)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset"))
+    }
+
+    /// `at` on the market's wall clock
+    pub fn local(&self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        at.with_timezone(&self.offset())
+    }
+
+    /// Market-local date at `at`
+    pub fn date_of(&self, at: DateTime<Utc>) -> NaiveDate {
+        self.local(at).date_naive()
+    }
+
+    /// Instant the market-local `date` begins
+    pub fn start_of_day(&self, date: NaiveDate) -> DateTime<Utc> {
+        (date.and_time(NaiveTime::MIN) - Duration::minutes(self.utc_offset_minutes as i64)).and_utc()
+    }
+
+    /// Start (inclusive) and end (exclusive) of the market-local `date`
+    pub fn day_bounds(&self, date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        (self.start_of_day(date), self.start_of_day(date + Duration::days(1)))
+    }
+
+    /// Start of the market-local ISO week containing `at`
+    pub fn start_of_week(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let today = self.date_of(at);
+        self.start_of_day(today - Duration::days(today.weekday().num_days_from_monday() as i64))
+    }
+
+    /// Start of the market-local calendar month containing `at`
+    pub fn start_of_month(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let today = self.date_of(at);
+        self.start_of_day(today.with_day(1).unwrap_or(today))
+    }
+
+    /// Start and end of a market-local calendar month
+    pub fn month_bounds(&self, year: i32, month: u32) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let next = if month == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)?
+        };
+        Some((self.start_of_day(start), self.start_of_day(next)))
+    }
+
+    /// Year and month of the market-local month before the one containing `at`
+    pub fn previous_month(&self, at: DateTime<Utc>) -> (i32, u32) {
+        let today = self.date_of(at);
+        let last_month = today.with_day(1).unwrap_or(today) - Duration::days(1);
+        (last_month.year(), last_month.month())
+    }
+
+    /// Start of the `minutes`-long epoch containing `at`, aligned to the
+    /// market-local hour
+    pub fn epoch_start(&self, at: DateTime<Utc>, minutes: u32) -> DateTime<Utc> {
+        let local = self.local(at);
+        let minutes = minutes.max(1);
+        local
+            .with_minute((local.minute() / minutes) * minutes)
+            .and_then(|dt| dt.with_second(0))
+            .and_then(|dt| dt.with_nanosecond(0))
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or(at)
+    }
+}
+
+impl Default for MarketTimezone {
+    fn default() -> Self {
+        Self::new("Asia/Bangkok", 420)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_bangkok_day_starts_at_utc_evening() {
+        let bangkok = MarketTimezone::default();
+        // 18:30 UTC on 31 January is already 1 February in Bangkok
+        assert_eq!(bangkok.date_of(utc(2026, 1, 31, 18, 30)), NaiveDate::from_ymd_opt(2026, 2, 1).unwrap());
+        let (start, end) = bangkok.day_bounds(NaiveDate::from_ymd_opt(2026, 2, 1).unwrap());
+        assert_eq!(start, utc(2026, 1, 31, 17, 0));
+        assert_eq!(end, utc(2026, 2, 1, 17, 0));
+    }
+
+    #[test]
+    fn test_months_and_weeks_follow_local_calendar() {
+        let bangkok = MarketTimezone::default();
+        assert_eq!(
+            bangkok.month_bounds(2025, 12),
+            Some((utc(2025, 11, 30, 17, 0), utc(2025, 12, 31, 17, 0)))
+        );
+        assert_eq!(bangkok.previous_month(utc(2026, 1, 31, 18, 0)), (2026, 1));
+        assert_eq!(MarketTimezone::utc().previous_month(utc(2026, 1, 31, 18, 0)), (2025, 12));
+        // Monday 19 October 2026 begins at 17:00 UTC on Sunday
+        assert_eq!(bangkok.start_of_week(utc(2026, 10, 18, 18, 0)), utc(2026, 10, 18, 17, 0));
+    }
+
+    #[test]
+    fn test_epochs_align_to_local_hour() {
+        let india = MarketTimezone::new("Asia/Kolkata", 330);
+        // 10:20 UTC is 15:50 local; an hourly epoch began at 15:00 local
+        assert_eq!(india.epoch_start(utc(2026, 1, 18, 10, 20), 60), utc(2026, 1, 18, 9, 30));
+        assert_eq!(india.epoch_start(utc(2026, 1, 18, 10, 20), 15), utc(2026, 1, 18, 10, 15));
+    }
+}
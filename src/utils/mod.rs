@@ -2,14 +2,19 @@
 // Validation, encryption, formatting, etc.
 
 pub mod crypto;
+pub mod decimal;
 pub mod error_tracker;
+pub mod market_time;
 pub mod pagination;
+pub mod pdf;
 pub mod request_info;
 pub mod secrets;
 pub mod signature;
+pub mod signed_url;
 pub mod validation;
 
+pub use market_time::MarketTimezone;
 pub use pagination::{PaginationMeta, PaginationParams, SortOrder};
 pub use request_info::{extract_ip_address, extract_user_agent};
 pub use secrets::validate_secrets;
-pub use signature::{verify_signature, MeterReadingMessage};
+pub use signature::{verify_message_signature, verify_signature, MeterReadingMessage, METER_MESSAGE_VERSION};
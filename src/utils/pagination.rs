@@ -145,7 +145,7 @@ impl<T> PaginatedResponse<T> {
 }
 
 /// Filter parameters for list endpoints
-#[derive(Debug, Deserialize, IntoParams)]
+#[derive(Debug, Clone, Deserialize, IntoParams)]
 pub struct FilterParams {
     /// Filter by status
     pub status: Option<String>,
@@ -264,6 +264,55 @@ impl ListQueryParams {
     }
 }
 
+/// `ILIKE` pattern matching `search` anywhere, with LIKE wildcards escaped
+pub fn contains_pattern(search: &str) -> String {
+    let escaped = search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Keyset position after the last row of a page ordered newest first.
+///
+/// Encoded as an opaque URL-safe token for `cursor` / `next_cursor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageCursor {
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub id: uuid::Uuid,
+}
+
+impl PageCursor {
+    pub fn encode(&self) -> String {
+        use base64::{engine::general_purpose, Engine as _};
+        general_purpose::URL_SAFE_NO_PAD.encode(format!("{}:{}", self.at.timestamp_micros(), self.id))
+    }
+
+    pub fn decode(token: &str) -> Result<Self, String> {
+        use base64::{engine::general_purpose, Engine as _};
+        let invalid = || "Invalid cursor".to_string();
+        let bytes = general_purpose::URL_SAFE_NO_PAD.decode(token).map_err(|_| invalid())?;
+        let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (micros, id) = text.split_once(':').ok_or_else(invalid)?;
+        Ok(Self {
+            at: micros
+                .parse()
+                .ok()
+                .and_then(chrono::DateTime::from_timestamp_micros)
+                .ok_or_else(invalid)?,
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+
+    /// Cursor for the page after `rows` when the page came back full
+    pub fn after<T>(rows: &[T], limit: i64, key: impl Fn(&T) -> Self) -> Option<Self> {
+        if (rows.len() as i64) < limit {
+            return None;
+        }
+        rows.last().map(key)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -346,4 +395,20 @@ mod tests {
         assert_eq!(response.pagination.total_items, 50);
         assert_eq!(response.pagination.total_pages, 5);
     }
+
+    #[test]
+    fn test_page_cursor_round_trip() {
+        let cursor = PageCursor {
+            at: chrono::DateTime::from_timestamp_micros(1_768_700_000_123_456).unwrap(),
+            id: uuid::Uuid::new_v4(),
+        };
+        assert_eq!(PageCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(PageCursor::decode("not-a-cursor").is_err());
+    }
+
+    #[test]
+    fn test_contains_pattern_escapes_wildcards() {
+        assert_eq!(contains_pattern("5Kx"), "%5Kx%");
+        assert_eq!(contains_pattern("a%b_c"), "%a\\%b\\_c%");
+    }
 }
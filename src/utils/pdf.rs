@@ -0,0 +1,108 @@
+//! Minimal PDF writer
+//!
+//! Produces plain-text, monospaced PDF documents (Courier, A4) for report
+//! exports without pulling in a rendering dependency.
+
+const PAGE_WIDTH: u32 = 595;
+const PAGE_HEIGHT: u32 = 842;
+const MARGIN: u32 = 50;
+const FONT_SIZE: u32 = 9;
+const LINE_HEIGHT: u32 = 12;
+const LINES_PER_PAGE: usize = ((PAGE_HEIGHT - 2 * MARGIN) / LINE_HEIGHT) as usize;
+
+/// Render a title and lines of text into a PDF document
+pub fn text_document(title: &str, lines: &[String]) -> Vec<u8> {
+    let mut all_lines = Vec::with_capacity(lines.len() + 2);
+    all_lines.push(title.to_string());
+    all_lines.push(String::new());
+    all_lines.extend(lines.iter().cloned());
+
+    let pages: Vec<&[String]> = all_lines.chunks(LINES_PER_PAGE).collect();
+
+    // Object layout: 1 catalog, 2 pages, 3 font, then (page, content) pairs
+    let mut objects: Vec<String> = Vec::new();
+    objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", 4 + i * 2))
+        .collect();
+    objects.push(format!(
+        "<< /Type /Pages /Kids [{}] /Count {} >>",
+        kids.join(" "),
+        pages.len()
+    ));
+    objects.push("<< /Type /Font /Subtype /Type1 /BaseFont /Courier >>".to_string());
+
+    for (i, page_lines) in pages.iter().enumerate() {
+        let content_id = 5 + i * 2;
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH, PAGE_HEIGHT, content_id
+        ));
+
+        let mut stream = format!(
+            "BT /F1 {} Tf {} TL {} {} Td\n",
+            FONT_SIZE,
+            LINE_HEIGHT,
+            MARGIN,
+            PAGE_HEIGHT - MARGIN
+        );
+        for line in page_lines.iter() {
+            stream.push_str(&format!("({}) Tj T*\n", escape(line)));
+        }
+        stream.push_str("ET");
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}\nendstream",
+            stream.len(),
+            stream
+        ));
+    }
+
+    let mut out = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+    }
+
+    let xref_offset = out.len();
+    out.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+    for offset in offsets {
+        out.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    out.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    ));
+
+    out.into_bytes()
+}
+
+/// Escape PDF string delimiters and replace characters the base font cannot encode
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            c if c.is_ascii() && !c.is_ascii_control() => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_document_structure() {
+        let lines: Vec<String> = (0..100).map(|i| format!("line (#{})", i)).collect();
+        let pdf = String::from_utf8(text_document("Report", &lines)).unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.trim_end().ends_with("%%EOF"));
+        assert!(pdf.contains("/Count 2"));
+        assert!(pdf.contains("(line \\(#0\\)) Tj"));
+    }
+}
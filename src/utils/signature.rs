@@ -2,14 +2,29 @@ use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error};
 
+/// Current canonical message version.
+///
+/// - v1: serial, timestamp, amount and wallet only (legacy, replayable)
+/// - v2: adds a per-meter monotonic sequence number
+pub const METER_MESSAGE_VERSION: u8 = 2;
+
 /// Canonical message format for meter reading signatures
 /// This ensures both simulator and API gateway create identical messages
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MeterReadingMessage {
+    #[serde(default = "default_message_version")]
+    pub version: u8,
     pub meter_serial: String,
     pub timestamp: String,  // ISO 8601 format
     pub kwh_amount: String, // Fixed precision string
     pub wallet: String,     // Base58 wallet address
+    /// Strictly increasing per meter; ignored for v1 messages
+    #[serde(default)]
+    pub sequence: u64,
+}
+
+fn default_message_version() -> u8 {
+    1
 }
 
 impl MeterReadingMessage {
@@ -19,21 +34,36 @@ impl MeterReadingMessage {
         timestamp: chrono::DateTime<chrono::Utc>,
         kwh_amount: rust_decimal::Decimal,
         wallet: String,
+        sequence: u64,
     ) -> Self {
         Self {
+            version: METER_MESSAGE_VERSION,
             meter_serial,
             timestamp: timestamp.to_rfc3339(),
             kwh_amount: format!("{:.6}", kwh_amount), // 6 decimal places
             wallet,
+            sequence,
         }
     }
 
+    /// Whether this message carries replay protection
+    pub fn has_sequence(&self) -> bool {
+        self.version >= 2
+    }
+
     /// Convert to canonical string format for signing/verification
     pub fn to_canonical_string(&self) -> String {
-        format!(
-            "GRIDTOKENX_METER_READING\nmeter_serial: {}\ntimestamp: {}\nkwh_amount: {}\nwallet: {}",
-            self.meter_serial, self.timestamp, self.kwh_amount, self.wallet
-        )
+        if self.has_sequence() {
+            format!(
+                "GRIDTOKENX_METER_READING\nversion: {}\nmeter_serial: {}\ntimestamp: {}\nkwh_amount: {}\nwallet: {}\nsequence: {}",
+                self.version, self.meter_serial, self.timestamp, self.kwh_amount, self.wallet, self.sequence
+            )
+        } else {
+            format!(
+                "GRIDTOKENX_METER_READING\nmeter_serial: {}\ntimestamp: {}\nkwh_amount: {}\nwallet: {}",
+                self.meter_serial, self.timestamp, self.kwh_amount, self.wallet
+            )
+        }
     }
 
     /// Get bytes for signing/verification
@@ -49,7 +79,15 @@ pub fn verify_signature(
     message: &MeterReadingMessage,
 ) -> Result<bool, String> {
     debug!("Verifying signature for meter: {}", message.meter_serial);
+    verify_message_signature(public_key_base58, signature_base58, &message.to_bytes())
+}
 
+/// Verify an Ed25519 signature over arbitrary message bytes
+pub fn verify_message_signature(
+    public_key_base58: &str,
+    signature_base58: &str,
+    message_bytes: &[u8],
+) -> Result<bool, String> {
     // Decode public key from base58
     let public_key_bytes = bs58::decode(public_key_base58)
         .into_vec()
@@ -87,11 +125,8 @@ pub fn verify_signature(
 
     let signature = Signature::from_bytes(&signature_array);
 
-    // Get message bytes
-    let message_bytes = message.to_bytes();
-
     // Verify signature
-    match public_key.verify(&message_bytes, &signature) {
+    match public_key.verify(message_bytes, &signature) {
         Ok(_) => {
             debug!("Signature verification successful");
             Ok(true)
@@ -120,16 +155,44 @@ mod tests {
     #[test]
     fn test_canonical_message_format() {
         let message = MeterReadingMessage {
+            version: 1,
             meter_serial: "METER-123".to_string(),
             timestamp: "2025-12-03T04:00:00Z".to_string(),
             kwh_amount: "5.123456".to_string(),
             wallet: "5KQwr...".to_string(),
+            sequence: 0,
         };
 
         let canonical = message.to_canonical_string();
         assert!(canonical.contains("GRIDTOKENX_METER_READING"));
         assert!(canonical.contains("meter_serial: METER-123"));
         assert!(canonical.contains("kwh_amount: 5.123456"));
+        assert!(!canonical.contains("sequence"));
+    }
+
+    #[test]
+    fn test_v2_message_binds_sequence() {
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2025-12-03T04:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let first = MeterReadingMessage::new(
+            "METER-123".to_string(),
+            timestamp,
+            rust_decimal::Decimal::new(5123456, 6),
+            "5KQwr...".to_string(),
+            41,
+        );
+        let replay = MeterReadingMessage::new(
+            "METER-123".to_string(),
+            timestamp,
+            rust_decimal::Decimal::new(5123456, 6),
+            "5KQwr...".to_string(),
+            42,
+        );
+
+        assert!(first.to_canonical_string().contains("version: 2"));
+        assert!(first.to_canonical_string().contains("sequence: 41"));
+        assert_ne!(first.to_bytes(), replay.to_bytes());
     }
 
     #[test]
@@ -139,10 +202,12 @@ mod tests {
 
         // Create message
         let message = MeterReadingMessage {
+            version: 1,
             meter_serial: "METER-123".to_string(),
             timestamp: "2025-12-03T04:00:00Z".to_string(),
             kwh_amount: "5.123456".to_string(),
             wallet: "5KQwr...".to_string(),
+            sequence: 0,
         };
 
         // Sign message
@@ -166,10 +231,12 @@ mod tests {
 
         // Create message
         let message = MeterReadingMessage {
+            version: 1,
             meter_serial: "METER-123".to_string(),
             timestamp: "2025-12-03T04:00:00Z".to_string(),
             kwh_amount: "5.123456".to_string(),
             wallet: "5KQwr...".to_string(),
+            sequence: 0,
         };
 
         // Sign with signing_key1
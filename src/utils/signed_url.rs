@@ -0,0 +1,93 @@
+//! Signed download URLs
+//!
+//! Files kept in the local object store are downloaded through links signed
+//! by this API. A link carries its expiry and an HMAC-SHA256 over the kind of
+//! resource, its id and the expiry, so it opens only that resource and only
+//! until it expires.
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::config::SignedUrlConfig;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Expiry of a link issued now
+pub fn expires_at(config: &SignedUrlConfig) -> DateTime<Utc> {
+    Utc::now() + Duration::seconds(config.url_ttl_secs)
+}
+
+/// Absolute URL of `path` (e.g. `/api/v1/invoices/{id}/download`) signed for
+/// the `kind` resource `id` until `expires_at`
+pub fn sign_url(config: &SignedUrlConfig, kind: &str, path: &str, id: Uuid, expires_at: DateTime<Utc>) -> String {
+    let expires = expires_at.timestamp();
+    format!(
+        "{}{}?expires={}&signature={}",
+        config.public_base_url.trim_end_matches('/'),
+        path,
+        expires,
+        hex::encode(mac(config, kind, id, expires).finalize().into_bytes())
+    )
+}
+
+/// Check a link's signature and expiry
+pub fn verify(config: &SignedUrlConfig, kind: &str, id: Uuid, expires: i64, signature: &str) -> bool {
+    if expires < Utc::now().timestamp() {
+        return false;
+    }
+    let Ok(provided) = hex::decode(signature) else {
+        return false;
+    };
+    mac(config, kind, id, expires).verify_slice(&provided).is_ok()
+}
+
+fn mac(config: &SignedUrlConfig, kind: &str, id: Uuid, expires: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(config.url_signing_secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("{}:{}:{}", kind, id, expires).as_bytes());
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SignedUrlConfig {
+        SignedUrlConfig {
+            url_signing_secret: "test-secret".to_string(),
+            url_ttl_secs: 900,
+            public_base_url: "https://api.example.com/".to_string(),
+        }
+    }
+
+    fn signature(url: &str) -> &str {
+        url.split("signature=").nth(1).unwrap()
+    }
+
+    #[test]
+    fn test_signed_url_verifies_for_its_resource_only() {
+        let config = config();
+        let id = Uuid::new_v4();
+        let expires_at = expires_at(&config);
+        let url = sign_url(&config, "invoice-download", &format!("/api/v1/invoices/{}/download", id), id, expires_at);
+        assert!(url.starts_with(&format!("https://api.example.com/api/v1/invoices/{}/download?expires=", id)));
+
+        let expires = expires_at.timestamp();
+        assert!(verify(&config, "invoice-download", id, expires, signature(&url)));
+        assert!(!verify(&config, "data-export", id, expires, signature(&url)));
+        assert!(!verify(&config, "invoice-download", Uuid::new_v4(), expires, signature(&url)));
+        assert!(!verify(&config, "invoice-download", id, expires + 1, signature(&url)));
+        assert!(!verify(&config, "invoice-download", id, expires, "not-hex"));
+    }
+
+    #[test]
+    fn test_expired_url_is_rejected() {
+        let config = config();
+        let id = Uuid::new_v4();
+        let expired = Utc::now() - Duration::seconds(1);
+        let url = sign_url(&config, "attachment", "/download", id, expired);
+        assert!(!verify(&config, "attachment", id, expired.timestamp(), signature(&url)));
+    }
+}
@@ -359,20 +359,6 @@ impl Validator {
         Ok(())
     }
 
-    /// Validate BigDecimal amount (for database storage)
-    pub fn validate_bigdecimal_amount(amount: &bigdecimal::BigDecimal, field_name: &str) -> Result<(), ApiError> {
-        use bigdecimal::Zero;
-        
-        if amount.is_zero() || amount < &bigdecimal::BigDecimal::zero() {
-            return Err(ApiError::validation_field(
-                field_name,
-                format!("{} must be greater than zero", field_name)
-            ));
-        }
-
-        Ok(())
-    }
-
     /// Validate username format
     pub fn validate_username(username: &str) -> Result<(), ApiError> {
         if username.is_empty() {
@@ -433,6 +419,111 @@ impl Validator {
     }
 }
 
+/// Declarative rules for `#[validate(custom(function = ...))]` on request DTOs.
+///
+/// Failures are collected per field and returned as a single 422 response
+/// (see `ApiError::FieldErrors`).
+pub mod rules {
+    use std::borrow::Cow;
+
+    use chrono::{NaiveDate, Utc};
+    use rust_decimal::prelude::ToPrimitive;
+    use rust_decimal::Decimal;
+    use validator::ValidationError;
+
+    use super::WALLET_REGEX;
+    use crate::constants::energy::{
+        AMOUNT_DECIMAL_PLACES, MAX_ENERGY_KWH, MAX_PRICE_PER_KWH, MIN_ENERGY_KWH, MIN_PRICE_PER_KWH,
+    };
+
+    fn error(code: &'static str, message: String) -> ValidationError {
+        ValidationError::new(code).with_message(Cow::Owned(message))
+    }
+
+    fn decimal_in_range(value: &Decimal, min: f64, max: f64, unit: &str) -> Result<(), ValidationError> {
+        let as_f64 = value.to_f64().unwrap_or(f64::MAX);
+        if as_f64 < min || as_f64 > max {
+            return Err(error("range", format!("Must be between {} and {} {}", min, max, unit)));
+        }
+        if value.normalize().scale() > AMOUNT_DECIMAL_PLACES {
+            return Err(error(
+                "precision",
+                format!("At most {} decimal places are allowed", AMOUNT_DECIMAL_PLACES),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Order and reading sizes in kWh
+    pub fn energy_amount(value: &Decimal) -> Result<(), ValidationError> {
+        decimal_in_range(value, MIN_ENERGY_KWH, MAX_ENERGY_KWH, "kWh")
+    }
+
+    /// Prices per kWh
+    pub fn price_per_kwh(value: &Decimal) -> Result<(), ValidationError> {
+        decimal_in_range(value, MIN_PRICE_PER_KWH, MAX_PRICE_PER_KWH, "per kWh")
+    }
+
+    /// Strictly positive amounts with storage precision (fiat, offsets)
+    pub fn positive_amount(value: &Decimal) -> Result<(), ValidationError> {
+        if *value <= Decimal::ZERO {
+            return Err(error("range", "Must be greater than zero".to_string()));
+        }
+        if value.normalize().scale() > AMOUNT_DECIMAL_PLACES {
+            return Err(error(
+                "precision",
+                format!("At most {} decimal places are allowed", AMOUNT_DECIMAL_PLACES),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Solana wallet address (base58, 32-44 characters)
+    pub fn wallet_address(value: &str) -> Result<(), ValidationError> {
+        if WALLET_REGEX.is_match(value) {
+            Ok(())
+        } else {
+            Err(error("wallet_format", "Invalid Solana wallet address format".to_string()))
+        }
+    }
+
+    /// Non-blank text
+    pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+        if value.trim().is_empty() {
+            Err(error("required", "Must not be blank".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Rated output of an installation
+    pub fn capacity_kw(value: &Decimal) -> Result<(), ValidationError> {
+        if *value <= Decimal::ZERO {
+            return Err(error("range", "Must be greater than zero".to_string()));
+        }
+        decimal_in_range(value, 0.0, 100_000.0, "kW")
+    }
+
+    /// Compass bearing, clockwise from north
+    pub fn azimuth_degrees(value: &Decimal) -> Result<(), ValidationError> {
+        decimal_in_range(value, 0.0, 360.0, "degrees")
+    }
+
+    /// Angle from horizontal
+    pub fn tilt_degrees(value: &Decimal) -> Result<(), ValidationError> {
+        decimal_in_range(value, 0.0, 90.0, "degrees")
+    }
+
+    /// Dates up to today (UTC)
+    pub fn not_future_date(value: &NaiveDate) -> Result<(), ValidationError> {
+        if *value > Utc::now().date_naive() {
+            Err(error("range", "Must not be in the future".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -507,4 +598,22 @@ mod tests {
         assert!(Validator::validate_price(-5.0).is_err());
         assert!(Validator::validate_price(1500.0).is_err()); // Too high
     }
+
+    #[test]
+    fn test_rule_energy_amount() {
+        use rust_decimal::Decimal;
+
+        assert!(rules::energy_amount(&Decimal::new(105, 1)).is_ok());
+        assert_eq!(rules::energy_amount(&Decimal::ZERO).unwrap_err().code, "range");
+        // 9 decimal places exceeds NUMERIC(20, 8)
+        assert_eq!(rules::energy_amount(&Decimal::new(1_000_000_001, 9)).unwrap_err().code, "precision");
+        // Trailing zeros do not count towards precision
+        assert!(rules::energy_amount(&Decimal::new(1_500_000_000, 9)).is_ok());
+    }
+
+    #[test]
+    fn test_rule_wallet_address() {
+        assert!(rules::wallet_address("GvPhiX9W1v3fj8WbN5D2TzzPwf1Kp1TfMg1e8KW1Pump").is_ok());
+        assert!(rules::wallet_address("0x1234567890").is_err());
+    }
 }
@@ -252,6 +252,7 @@ async fn test_complete_trading_cycle() -> Result<()> {
             .update_settlement_confirmed(
                 settlement.id,
                 &mock_tx,
+                "confirmed",
                 api_gateway::services::settlement_service::SettlementStatus::Completed,
             )
             .await?;
@@ -366,6 +367,7 @@ async fn test_trading_cycle_error_handling() -> Result<()> {
         .update_settlement_confirmed(
             settlement.id,
             "ERROR-TEST-TX",
+            "confirmed",
             api_gateway::services::settlement_service::SettlementStatus::Completed,
         )
         .await?;